@@ -1,6 +1,7 @@
 //! Audit entry types for Jin operations
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Audit context information
 ///
@@ -65,6 +66,75 @@ pub struct AuditEntry {
     /// Additional context
     #[serde(skip_serializing_if = "Option::is_none")]
     pub context: Option<AuditContext>,
+    /// How a conflict was settled automatically (apply conflict policies);
+    /// absent for commits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<String>,
+    /// What `jin repair --fix` changed; absent for commits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repair: Option<String>,
+    /// ID of the machine the operation ran on (see [`crate::core::machine`]);
+    /// absent in entries recorded before machine IDs existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine: Option<String>,
+    /// What a `jin apply` wrote and how it ended; absent for other operations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apply: Option<ApplyRecord>,
+}
+
+/// How an apply ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApplyOutcome {
+    /// Every file was written
+    Applied,
+    /// Conflicts were left for `jin resolve`
+    Paused,
+    /// Stopped with an error
+    Failed,
+}
+
+impl std::fmt::Display for ApplyOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApplyOutcome::Applied => write!(f, "applied"),
+            ApplyOutcome::Paused => write!(f, "paused"),
+            ApplyOutcome::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// Details of one `jin apply`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApplyRecord {
+    /// How it ended
+    pub outcome: ApplyOutcome,
+    /// Commit of every merged layer, keyed by ref path
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub layers: BTreeMap<String, String>,
+    /// Files left conflicted, when paused
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<String>,
+    /// Wall-clock time, in milliseconds
+    pub duration_ms: u64,
+    /// The error, when failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ApplyRecord {
+    /// One-line summary, e.g. `paused with 2 conflicts in 120 ms`
+    pub fn summary(&self) -> String {
+        let mut summary = self.outcome.to_string();
+        if !self.conflicts.is_empty() {
+            summary.push_str(&format!(" with {} conflicts", self.conflicts.len()));
+        }
+        summary.push_str(&format!(" in {} ms", self.duration_ms));
+        if let Some(error) = &self.error {
+            summary.push_str(&format!(": {}", error));
+        }
+        summary
+    }
 }
 
 impl AuditEntry {
@@ -100,6 +170,115 @@ impl AuditEntry {
             base_commit,
             merge_commit: Some(merge_commit),
             context,
+            resolution: None,
+            repair: None,
+            machine: None,
+            apply: None,
+        }
+    }
+
+    /// Create an audit entry for a conflict settled by an apply policy
+    ///
+    /// `layer` is the precedence of the layer whose version was kept, or
+    /// `None` when the workspace file was kept.
+    pub fn from_resolution(
+        user: String,
+        project: Option<String>,
+        mode: Option<String>,
+        scope: Option<String>,
+        layer: Option<u8>,
+        file: String,
+        resolution: String,
+    ) -> Self {
+        let context = if mode.is_some() || scope.is_some() {
+            Some(AuditContext {
+                active_mode: mode.clone(),
+                active_scope: scope.clone(),
+            })
+        } else {
+            None
+        };
+
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            user,
+            project,
+            mode,
+            scope,
+            layer,
+            files: vec![file],
+            base_commit: None,
+            merge_commit: None,
+            context,
+            resolution: Some(resolution),
+            repair: None,
+            machine: None,
+            apply: None,
+        }
+    }
+
+    /// Create an audit entry for a `jin apply`
+    ///
+    /// `files` are the files it wrote to the workspace.
+    pub fn from_apply(
+        user: String,
+        project: Option<String>,
+        mode: Option<String>,
+        scope: Option<String>,
+        files: Vec<String>,
+        apply: ApplyRecord,
+    ) -> Self {
+        let context = if mode.is_some() || scope.is_some() {
+            Some(AuditContext {
+                active_mode: mode.clone(),
+                active_scope: scope.clone(),
+            })
+        } else {
+            None
+        };
+
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            user,
+            project,
+            mode,
+            scope,
+            layer: None,
+            files,
+            base_commit: None,
+            merge_commit: None,
+            context,
+            resolution: None,
+            repair: None,
+            machine: None,
+            apply: Some(apply),
+        }
+    }
+
+    /// Create an audit entry for a fix applied by `jin repair --fix`
+    ///
+    /// `files` are the Jin state files the fix rewrote, if any.
+    pub fn from_repair(
+        user: String,
+        project: Option<String>,
+        files: Vec<String>,
+        repair: String,
+    ) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            user,
+            project,
+            mode: None,
+            scope: None,
+            layer: None,
+            files,
+            base_commit: None,
+            merge_commit: None,
+            context: None,
+            resolution: None,
+            repair: Some(repair),
+            machine: None,
+            apply: None,
         }
     }
 }
@@ -124,6 +303,10 @@ mod tests {
                 active_mode: Some("claude".to_string()),
                 active_scope: Some("language:javascript".to_string()),
             }),
+            resolution: None,
+            repair: None,
+            machine: None,
+            apply: None,
         };
 
         let json = serde_json::to_string(&entry).unwrap();
@@ -150,6 +333,10 @@ mod tests {
             base_commit: None,
             merge_commit: Some("abc123".to_string()),
             context: None,
+            resolution: None,
+            repair: None,
+            machine: None,
+            apply: None,
         };
 
         let json = serde_json::to_string(&entry).unwrap();
@@ -194,6 +381,77 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_audit_entry_from_resolution() {
+        let entry = AuditEntry::from_resolution(
+            "user@example.com".to_string(),
+            None,
+            Some("claude".to_string()),
+            None,
+            Some(2),
+            "config.json".to_string(),
+            "--theirs kept mode-base".to_string(),
+        );
+
+        assert_eq!(entry.files, vec!["config.json".to_string()]);
+        assert_eq!(entry.merge_commit, None);
+        assert_eq!(entry.resolution.as_deref(), Some("--theirs kept mode-base"));
+
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(json.contains("\"resolution\":\"--theirs kept mode-base\""));
+        assert!(!json.contains("merge_commit"));
+        let parsed: AuditEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.resolution, entry.resolution);
+    }
+
+    #[test]
+    fn test_audit_entry_from_repair() {
+        let entry = AuditEntry::from_repair(
+            "test@example.com".to_string(),
+            Some("myproject".to_string()),
+            vec![".jin/staging/index.json".to_string()],
+            "Staging index rebuilt".to_string(),
+        );
+
+        assert_eq!(entry.repair.as_deref(), Some("Staging index rebuilt"));
+        assert!(entry.merge_commit.is_none());
+        assert!(entry.resolution.is_none());
+
+        let json = serde_json::to_string(&entry).unwrap();
+        let parsed: AuditEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.repair, entry.repair);
+    }
+
+    #[test]
+    fn test_audit_entry_from_apply() {
+        let mut layers = BTreeMap::new();
+        layers.insert("refs/jin/layers/global".to_string(), "abc1234".to_string());
+        let entry = AuditEntry::from_apply(
+            "test@example.com".to_string(),
+            None,
+            Some("claude".to_string()),
+            None,
+            vec!["app.json".to_string()],
+            ApplyRecord {
+                outcome: ApplyOutcome::Paused,
+                layers,
+                conflicts: vec!["settings.json".to_string()],
+                duration_ms: 42,
+                error: None,
+            },
+        );
+
+        let apply = entry.apply.as_ref().unwrap();
+        assert_eq!(apply.summary(), "paused with 1 conflicts in 42 ms");
+        assert!(entry.layer.is_none());
+
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(json.contains("\"outcome\":\"paused\""));
+        assert!(!json.contains("\"error\""));
+        let parsed: AuditEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.apply, entry.apply);
+    }
+
     #[test]
     fn test_audit_entry_from_commit_no_context() {
         let entry = AuditEntry::from_commit(
@@ -0,0 +1,469 @@
+//! Normalized audit export for security tooling
+//!
+//! Converts stored [`AuditEntry`] records into a flat [`AuditRecord`] shape
+//! and renders them as JSON Lines, CSV, or ArcSight Common Event Format (CEF).
+
+use crate::audit::AuditEntry;
+use crate::core::schema::JsonSurface;
+use crate::core::{JinError, Layer, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use std::path::Path;
+
+/// CSV column order, shared by the header and each row
+const CSV_COLUMNS: [&str; 15] = [
+    "timestamp",
+    "user",
+    "project",
+    "mode",
+    "scope",
+    "layer",
+    "layer_name",
+    "layer_ref",
+    "commit",
+    "base_commit",
+    "files",
+    "resolution",
+    "repair",
+    "machine",
+    "apply",
+];
+
+/// A single audit record in normalized form
+///
+/// Unlike [`AuditEntry`], every field is always present (empty values are
+/// serialized as `null`) and the layer is resolved to its Git ref.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditRecord {
+    /// Version of the record layout
+    pub schema_version: u32,
+    /// RFC 3339 timestamp in UTC
+    pub timestamp: String,
+    /// Author identity (Git user.email at commit time)
+    pub user: String,
+    /// Project name
+    pub project: Option<String>,
+    /// Active mode
+    pub mode: Option<String>,
+    /// Active scope
+    pub scope: Option<String>,
+    /// Layer precedence number (1-9)
+    pub layer: Option<u8>,
+    /// Layer name (e.g., `project-base`)
+    pub layer_name: Option<String>,
+    /// Git ref the commit was written to
+    pub layer_ref: Option<String>,
+    /// Commit OID created by the operation
+    pub commit: Option<String>,
+    /// Parent commit OID, if the layer had history
+    pub base_commit: Option<String>,
+    /// Files affected by the operation
+    pub files: Vec<String>,
+    /// How a conflict was settled automatically, for apply resolutions
+    pub resolution: Option<String>,
+    /// What was fixed, for `jin repair --fix` records
+    pub repair: Option<String>,
+    /// ID of the machine the operation ran on
+    pub machine: Option<String>,
+    /// How an apply ended, for `jin apply` records
+    pub apply: Option<String>,
+}
+
+impl AuditRecord {
+    /// Normalize a stored audit entry
+    pub fn from_entry(entry: &AuditEntry) -> Self {
+        let layer = entry.layer.and_then(Layer::from_precedence);
+        let timestamp = parse_timestamp(&entry.timestamp)
+            .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+            .unwrap_or_else(|| entry.timestamp.clone());
+
+        Self {
+            schema_version: JsonSurface::Audit.version(),
+            timestamp,
+            user: entry.user.clone(),
+            project: entry.project.clone(),
+            mode: entry.mode.clone(),
+            scope: entry.scope.clone(),
+            layer: entry.layer,
+            layer_name: layer.map(|l| l.to_string()),
+            layer_ref: layer.map(|l| {
+                l.ref_path(
+                    entry.mode.as_deref(),
+                    entry.scope.as_deref(),
+                    entry.project.as_deref(),
+                )
+            }),
+            commit: entry.merge_commit.clone(),
+            base_commit: entry.base_commit.clone(),
+            files: entry.files.clone(),
+            resolution: entry.resolution.clone(),
+            repair: entry.repair.clone(),
+            machine: entry.machine.clone(),
+            apply: entry.apply.as_ref().map(|apply| apply.summary()),
+        }
+    }
+}
+
+/// Parse a `--since` value
+///
+/// Accepts a date (`2025-10-19`, interpreted as midnight UTC) or a full
+/// RFC 3339 timestamp.
+pub fn parse_since(value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        if let Some(midnight) = date.and_hms_opt(0, 0, 0) {
+            return Ok(midnight.and_utc());
+        }
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| {
+            JinError::Other(format!(
+                "Invalid date '{}'. Use YYYY-MM-DD or an RFC 3339 timestamp.",
+                value
+            ))
+        })
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Read audit entries from every log file in `audit_dir`
+///
+/// Entries are returned oldest first. When `since` is given, entries older
+/// than it (or with an unreadable timestamp) are skipped. A missing audit
+/// directory yields no entries.
+///
+/// # Errors
+///
+/// Returns an error if a log file cannot be read or contains a line that is
+/// not a valid audit entry.
+pub fn read_entries(audit_dir: &Path, since: Option<DateTime<Utc>>) -> Result<Vec<AuditEntry>> {
+    if !audit_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files: Vec<_> = std::fs::read_dir(audit_dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("audit-") && n.ends_with(".jsonl"))
+        })
+        .collect();
+    // Daily file names sort chronologically
+    files.sort();
+
+    let mut entries = Vec::new();
+    for path in files {
+        let content = std::fs::read_to_string(&path)?;
+        for (n, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: AuditEntry = serde_json::from_str(line).map_err(|e| JinError::Parse {
+                format: "JSON".to_string(),
+                message: format!("{}:{}: {}", path.display(), n + 1, e),
+            })?;
+            if let Some(since) = since {
+                match parse_timestamp(&entry.timestamp) {
+                    Some(t) if t >= since => {}
+                    _ => continue,
+                }
+            }
+            entries.push(entry);
+        }
+    }
+
+    entries.sort_by_key(|e| parse_timestamp(&e.timestamp));
+    Ok(entries)
+}
+
+/// Render records as JSON Lines (one object per line)
+pub fn to_jsonl(records: &[AuditRecord]) -> Result<String> {
+    let mut out = String::new();
+    for record in records {
+        let line = serde_json::to_string(record).map_err(|e| JinError::Parse {
+            format: "JSON".to_string(),
+            message: e.to_string(),
+        })?;
+        out.push_str(&line);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Render records as RFC 4180 CSV with a header row
+///
+/// Multiple files are joined with `;` in the `files` column.
+pub fn to_csv(records: &[AuditRecord]) -> String {
+    let mut out = CSV_COLUMNS.join(",");
+    out.push_str("\r\n");
+
+    for r in records {
+        let fields = [
+            r.timestamp.clone(),
+            r.user.clone(),
+            r.project.clone().unwrap_or_default(),
+            r.mode.clone().unwrap_or_default(),
+            r.scope.clone().unwrap_or_default(),
+            r.layer.map(|l| l.to_string()).unwrap_or_default(),
+            r.layer_name.clone().unwrap_or_default(),
+            r.layer_ref.clone().unwrap_or_default(),
+            r.commit.clone().unwrap_or_default(),
+            r.base_commit.clone().unwrap_or_default(),
+            r.files.join(";"),
+            r.resolution.clone().unwrap_or_default(),
+            r.repair.clone().unwrap_or_default(),
+            r.machine.clone().unwrap_or_default(),
+            r.apply.clone().unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render records as CEF (Common Event Format), one event per line
+pub fn to_cef(records: &[AuditRecord]) -> String {
+    let mut out = String::new();
+    for r in records {
+        let mut ext: Vec<(&str, String)> = Vec::new();
+        if let Some(t) = parse_timestamp(&r.timestamp) {
+            ext.push(("rt", t.timestamp_millis().to_string()));
+        }
+        ext.push(("suser", r.user.clone()));
+        if let Some(machine) = &r.machine {
+            ext.push(("deviceExternalId", machine.clone()));
+        }
+        let custom = [
+            ("cs1", "cs1Label", "project", &r.project),
+            ("cs2", "cs2Label", "mode", &r.mode),
+            ("cs3", "cs3Label", "scope", &r.scope),
+            ("cs4", "cs4Label", "layerRef", &r.layer_ref),
+            ("cs5", "cs5Label", "commit", &r.commit),
+            ("cs6", "cs6Label", "baseCommit", &r.base_commit),
+        ];
+        for (key, label_key, label, value) in custom {
+            if let Some(value) = value {
+                ext.push((key, value.clone()));
+                ext.push((label_key, label.to_string()));
+            }
+        }
+        if let Some(layer) = r.layer {
+            ext.push(("cn1", layer.to_string()));
+            ext.push(("cn1Label", "layer".to_string()));
+        }
+        if !r.files.is_empty() {
+            ext.push(("fname", r.files.join(",")));
+        }
+        if let Some(message) = r
+            .resolution
+            .as_ref()
+            .or(r.repair.as_ref())
+            .or(r.apply.as_ref())
+        {
+            ext.push(("msg", message.clone()));
+        }
+        let (event, name) = if r.resolution.is_some() {
+            ("resolve", "Conflict auto-resolved")
+        } else if r.repair.is_some() {
+            ("repair", "Jin state repaired")
+        } else if r.apply.is_some() {
+            ("apply", "Layers applied to workspace")
+        } else {
+            ("commit", "Layer commit")
+        };
+
+        let extension: Vec<String> = ext
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, cef_extension_value(v)))
+            .collect();
+
+        out.push_str(&format!(
+            "CEF:0|{}|{}|{}|{}|{}|{}|{}\n",
+            cef_header_value("Jin"),
+            cef_header_value("jin"),
+            cef_header_value(env!("CARGO_PKG_VERSION")),
+            cef_header_value(event),
+            cef_header_value(name),
+            3,
+            extension.join(" ")
+        ));
+    }
+    out
+}
+
+fn cef_header_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+fn cef_extension_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('=', "\\=")
+        .replace('\r', "\\r")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditLogger;
+    use tempfile::TempDir;
+
+    fn sample_entry(timestamp: &str) -> AuditEntry {
+        AuditEntry {
+            timestamp: timestamp.to_string(),
+            user: "dev@example.com".to_string(),
+            project: Some("ui".to_string()),
+            mode: Some("claude".to_string()),
+            scope: Some("lang:rust".to_string()),
+            layer: Some(4),
+            files: vec!["a.json".to_string(), "b,c.json".to_string()],
+            base_commit: Some("parent1".to_string()),
+            merge_commit: Some("commit1".to_string()),
+            context: None,
+            resolution: None,
+            repair: None,
+            machine: None,
+            apply: None,
+        }
+    }
+
+    #[test]
+    fn test_record_resolves_layer_ref() {
+        let record = AuditRecord::from_entry(&sample_entry("2025-10-19T15:04:02+00:00"));
+        assert_eq!(record.timestamp, "2025-10-19T15:04:02Z");
+        assert_eq!(record.layer_name.as_deref(), Some("mode-scope-project"));
+        assert_eq!(
+            record.layer_ref.as_deref(),
+            Some("refs/jin/layers/mode/claude/scope/lang/rust/project/ui")
+        );
+        assert_eq!(record.commit.as_deref(), Some("commit1"));
+    }
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(
+            parse_since("2025-10-19").unwrap().to_rfc3339(),
+            "2025-10-19T00:00:00+00:00"
+        );
+        assert!(parse_since("2025-10-19T12:00:00Z").is_ok());
+        assert!(parse_since("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_read_entries_filters_since() {
+        let temp = TempDir::new().unwrap();
+        let logger = AuditLogger::new(temp.path().join("audit")).unwrap();
+        logger
+            .log_entry(&sample_entry("2025-10-18T10:00:00Z"))
+            .unwrap();
+        logger
+            .log_entry(&sample_entry("2025-10-20T10:00:00Z"))
+            .unwrap();
+
+        let all = read_entries(logger.audit_dir(), None).unwrap();
+        assert_eq!(all.len(), 2);
+
+        let since = parse_since("2025-10-19").unwrap();
+        let recent = read_entries(logger.audit_dir(), Some(since)).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].timestamp, "2025-10-20T10:00:00Z");
+    }
+
+    #[test]
+    fn test_read_entries_missing_dir_is_empty() {
+        let temp = TempDir::new().unwrap();
+        assert!(read_entries(&temp.path().join("none"), None)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_to_jsonl_includes_null_fields() {
+        let mut entry = sample_entry("2025-10-19T15:04:02Z");
+        entry.base_commit = None;
+        let out = to_jsonl(&[AuditRecord::from_entry(&entry)]).unwrap();
+        let value: serde_json::Value = serde_json::from_str(out.trim_end()).unwrap();
+        assert!(value["base_commit"].is_null());
+        assert_eq!(value["files"][1], "b,c.json");
+    }
+
+    #[test]
+    fn test_jsonl_matches_schema() {
+        let mut entry = sample_entry("2025-10-19T15:04:02Z");
+        entry.repair = Some("Staging index rebuilt".to_string());
+        let out = to_jsonl(&[AuditRecord::from_entry(&entry)]).unwrap();
+        let value: serde_json::Value = serde_json::from_str(out.trim_end()).unwrap();
+        assert_eq!(value["schema_version"], 1);
+        crate::core::schema::assert_matches(&JsonSurface::Audit.schema(), &value, "audit");
+    }
+
+    #[test]
+    fn test_to_csv_quotes_fields() {
+        let out = to_csv(&[AuditRecord::from_entry(&sample_entry(
+            "2025-10-19T15:04:02Z",
+        ))]);
+        let lines: Vec<&str> = out.split("\r\n").collect();
+        assert_eq!(lines[0], CSV_COLUMNS.join(","));
+        assert!(lines[1].ends_with(",commit1,parent1,\"a.json;b,c.json\",,,,"));
+    }
+
+    #[test]
+    fn test_to_cef_escapes_extension_values() {
+        let mut entry = sample_entry("2025-10-19T15:04:02Z");
+        entry.user = "a=b".to_string();
+        let out = to_cef(&[AuditRecord::from_entry(&entry)]);
+        assert!(out.starts_with("CEF:0|Jin|jin|"));
+        assert!(out.contains("|commit|Layer commit|3|rt=1760886242000 "));
+        assert!(out.contains("suser=a\\=b"));
+        assert!(out.contains("cs5=commit1 cs5Label=commit"));
+        assert!(out.contains("cn1=4 cn1Label=layer"));
+    }
+
+    #[test]
+    fn test_resolution_records_export_as_resolve_events() {
+        let mut entry = sample_entry("2025-10-19T15:04:02Z");
+        entry.merge_commit = None;
+        entry.resolution = Some("--prefer mode-base kept mode-base".to_string());
+        let record = AuditRecord::from_entry(&entry);
+
+        let cef = to_cef(std::slice::from_ref(&record));
+        assert!(cef.contains("|resolve|Conflict auto-resolved|3|"));
+        assert!(cef.contains("msg=--prefer mode-base kept mode-base"));
+
+        let csv = to_csv(&[record]);
+        assert!(csv.ends_with(",\"a.json;b,c.json\",--prefer mode-base kept mode-base,,,\r\n"));
+    }
+
+    #[test]
+    fn test_repair_records_export_as_repair_events() {
+        let mut entry = sample_entry("2025-10-19T15:04:02Z");
+        entry.merge_commit = None;
+        entry.repair = Some("Staging index rebuilt".to_string());
+        entry.machine = Some("3f2a9c0d".to_string());
+        let record = AuditRecord::from_entry(&entry);
+
+        let cef = to_cef(std::slice::from_ref(&record));
+        assert!(cef.contains("|repair|Jin state repaired|3|"));
+        assert!(cef.contains("msg=Staging index rebuilt"));
+        assert!(cef.contains("deviceExternalId=3f2a9c0d"));
+
+        let csv = to_csv(&[record]);
+        assert!(csv.ends_with(",,Staging index rebuilt,3f2a9c0d,\r\n"));
+    }
+}
@@ -13,6 +13,8 @@ use std::path::{Path, PathBuf};
 pub struct AuditLogger {
     /// Base directory for audit files
     audit_dir: PathBuf,
+    /// Machine ID stamped on entries that don't carry one
+    machine: Option<String>,
 }
 
 impl AuditLogger {
@@ -24,7 +26,17 @@ impl AuditLogger {
     pub fn new(audit_dir: PathBuf) -> Result<Self> {
         // Ensure audit directory exists
         std::fs::create_dir_all(&audit_dir).map_err(JinError::Io)?;
-        Ok(Self { audit_dir })
+        Ok(Self {
+            audit_dir,
+            machine: None,
+        })
+    }
+
+    /// Stamp entries that don't name a machine with `machine` (usually
+    /// [`crate::core::machine::current`])
+    pub fn with_machine(mut self, machine: Option<String>) -> Self {
+        self.machine = machine;
+        self
     }
 
     /// Get the audit file path for today
@@ -57,6 +69,18 @@ impl AuditLogger {
 
         let mut writer = BufWriter::new(file);
 
+        let stamped;
+        let entry = match (&entry.machine, &self.machine) {
+            (None, Some(machine)) => {
+                stamped = AuditEntry {
+                    machine: Some(machine.clone()),
+                    ..entry.clone()
+                };
+                &stamped
+            }
+            _ => entry,
+        };
+
         // Serialize as single-line JSON (JSON Lines format)
         let json_line = serde_json::to_string(entry).map_err(|e| JinError::Parse {
             format: "JSON".to_string(),
@@ -72,15 +96,15 @@ impl AuditLogger {
         Ok(())
     }
 
-    /// Create audit logger from project context
+    /// Create the audit logger of the workspace at `root`
     ///
     /// Uses `.jin/audit/` as the audit directory.
     ///
     /// # Errors
     ///
     /// Returns an error if the audit directory cannot be created.
-    pub fn from_project() -> Result<Self> {
-        let audit_dir = PathBuf::from(".jin").join("audit");
+    pub fn from_project(root: &Path) -> Result<Self> {
+        let audit_dir = root.join(".jin").join("audit");
         Self::new(audit_dir)
     }
 
@@ -144,6 +168,10 @@ mod tests {
             base_commit: None,
             merge_commit: Some("abc123".to_string()),
             context: None,
+            resolution: None,
+            repair: None,
+            machine: None,
+            apply: None,
         };
 
         logger.log_entry(&entry).unwrap();
@@ -170,6 +198,10 @@ mod tests {
                 active_mode: Some("claude".to_string()),
                 active_scope: Some("language:rust".to_string()),
             }),
+            resolution: None,
+            repair: None,
+            machine: None,
+            apply: None,
         };
 
         logger.log_entry(&entry).unwrap();
@@ -201,6 +233,10 @@ mod tests {
             base_commit: None,
             merge_commit: Some("commit1".to_string()),
             context: None,
+            resolution: None,
+            repair: None,
+            machine: None,
+            apply: None,
         };
 
         let entry2 = AuditEntry {
@@ -214,6 +250,10 @@ mod tests {
             base_commit: None,
             merge_commit: Some("commit2".to_string()),
             context: None,
+            resolution: None,
+            repair: None,
+            machine: None,
+            apply: None,
         };
 
         logger.log_entry(&entry1).unwrap();
@@ -235,10 +275,9 @@ mod tests {
     #[test]
     fn test_audit_logger_from_project() {
         let temp = TempDir::new().unwrap();
-        std::env::set_current_dir(temp.path()).unwrap();
 
-        let logger = AuditLogger::from_project().unwrap();
-        assert_eq!(logger.audit_dir(), PathBuf::from(".jin").join("audit"));
+        let logger = AuditLogger::from_project(temp.path()).unwrap();
+        assert_eq!(logger.audit_dir(), temp.path().join(".jin").join("audit"));
     }
 
     #[test]
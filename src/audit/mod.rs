@@ -1,10 +1,14 @@
 //! Audit logging for Jin operations
 //!
-//! Tracks all Jin commit operations in PRD-compliant JSON format,
-//! stored in `.jin/audit/` directory for compliance and debugging.
+//! Tracks all Jin commit operations, every `jin apply` (shown by `jin log
+//! --applies`) and conflicts settled automatically by it, in PRD-compliant
+//! JSON format, stored in `.jin/audit/` directory for compliance and
+//! debugging.
 
 pub mod entry;
+pub mod export;
 pub mod logger;
 
-pub use entry::{AuditContext, AuditEntry};
+pub use entry::{ApplyOutcome, ApplyRecord, AuditContext, AuditEntry};
+pub use export::AuditRecord;
 pub use logger::AuditLogger;
@@ -1,6 +1,12 @@
 //! Shared argument types for CLI commands
 
+use super::{
+    ArchiveTarget, AssertCheck, BadgeFormat, ColorWhen, CompletionShell, LayersFormat, LayersGraph,
+    LinkAction, ListKind, OnExists, SchemaCommand, StatusSection, TakeSide, VerifyFormat,
+};
+use crate::git::auth::AuthMethod;
 use clap::Args;
+use std::path::PathBuf;
 
 /// Arguments for the `add` command
 #[derive(Args, Debug)]
@@ -16,11 +22,28 @@ use clap::Args;
                          → Layer 4 (ModeScopeProject) jin/mode/<mode>/scope/<scope>/project/<project>/
   --global               → Layer 1 (GlobalBase)      jin/global/
   --local                → Layer 8 (UserLocal)       ~/.jin/local/
+
+MANIFEST:
+  jin add --manifest add.yaml stages every listed path with its own flags.
+  All entries are validated before anything is staged, and --create-missing
+  creates the modes and scopes they route to.
+
+    files:
+      - path: .claude/settings.json
+        mode: true
+      - path: config/lint.toml
+        scope: language:rust
+      - path: .env.local
+        local: true
 "#)]
 pub struct AddArgs {
     /// Files to stage
     pub files: Vec<String>,
 
+    /// Stage the paths listed in a YAML manifest, each with its own layer flags
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["files", "mode", "scope", "project", "global", "local"])]
+    pub manifest: Option<PathBuf>,
+
     /// Target mode layer
     #[arg(long)]
     pub mode: bool,
@@ -40,6 +63,57 @@ pub struct AddArgs {
     /// Target user-local layer (Layer 8, machine-specific)
     #[arg(long)]
     pub local: bool,
+
+    /// Stop applying the user-local override after this long (e.g., 7d, 12h, 2w)
+    #[arg(long, value_name = "AGE", requires = "local")]
+    pub expires: Option<String>,
+
+    /// Create the active mode or the --scope scope (or the manifest's
+    /// scopes) if it doesn't exist yet (default: add.create-missing)
+    #[arg(long)]
+    pub create_missing: bool,
+}
+
+/// Arguments for the `adopt` command
+#[derive(Args, Debug)]
+#[command(after_help = r#"LAYER INFERENCE:
+  Without layer flags, the target layer is inferred from the file:
+    AI tool files (CLAUDE.md, .claude/, .cursor/, ...)  → mode layer (needs an active mode)
+    Machine-specific files (.env.local, *.local.*)      → user-local layer
+    Anything else                                       → project layer
+  Layer flags work as for 'jin add' and override the inference.
+"#)]
+pub struct AdoptArgs {
+    /// Workspace file to bring under Jin management
+    pub path: String,
+
+    /// Target mode layer
+    #[arg(long)]
+    pub mode: bool,
+
+    /// Target scope layer
+    #[arg(long)]
+    pub scope: Option<String>,
+
+    /// Target mode-project layer (requires --mode)
+    #[arg(long)]
+    pub project: bool,
+
+    /// Target global layer
+    #[arg(long)]
+    pub global: bool,
+
+    /// Target user-local layer
+    #[arg(long)]
+    pub local: bool,
+
+    /// Commit message (default: "Adopt <path>")
+    #[arg(short, long)]
+    pub message: Option<String>,
+
+    /// Show the inferred layer without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 /// Arguments for the `commit` command
@@ -52,6 +126,11 @@ pub struct CommitArgs {
     /// Dry run - show what would be committed
     #[arg(long)]
     pub dry_run: bool,
+
+    /// Commit only this staged entry (`<layer>:<path>`, a path, or its
+    /// number in `jin status`), leaving the rest staged; repeatable
+    #[arg(long, value_name = "ENTRY")]
+    pub only: Vec<String>,
 }
 
 /// Arguments for the `apply` command
@@ -73,6 +152,12 @@ pub struct CommitArgs {
 
   Remove conflict markers and keep desired content,
   then run 'jin resolve' to apply the resolution.
+
+  To settle conflicts without pausing, pass a policy:
+    --ours            keep the workspace's current file
+    --theirs          take the highest-precedence layer
+    --prefer <LAYER>  take the named layer (e.g. mode-base)
+  Each automatic resolution is recorded in the audit log.
 "#)]
 pub struct ApplyArgs {
     /// Force apply even if workspace is dirty
@@ -82,12 +167,75 @@ pub struct ApplyArgs {
     /// Show what would be applied
     #[arg(long)]
     pub dry_run: bool,
+
+    /// Write files under this directory instead of the workspace
+    /// (overrides .jintargets; may start with ~)
+    #[arg(long, value_name = "DIR")]
+    pub target: Option<String>,
+
+    /// Settle conflicts by keeping the file currently in the workspace
+    #[arg(long, conflicts_with_all = ["theirs", "prefer"])]
+    pub ours: bool,
+
+    /// Settle conflicts with the highest-precedence layer's version
+    #[arg(long, conflicts_with = "prefer")]
+    pub theirs: bool,
+
+    /// Settle conflicts with this layer's version (e.g. mode-base), or the
+    /// highest layer's when it lacks the file
+    #[arg(long, value_name = "LAYER")]
+    pub prefer: Option<String>,
+
+    /// Write .jin/provenance.json recording the source layer and commit of
+    /// every applied file and top-level key (default: apply.provenance)
+    #[arg(long)]
+    pub provenance: bool,
+
+    /// Adjust the merge for this run (repeatable): keyed_arrays=false,
+    /// array_keys=<fields>, nulls=keep|delete, ours_label=, theirs_label=,
+    /// base_label=, diff3=true
+    #[arg(long = "merge-opt", value_name = "KEY=VALUE")]
+    pub merge_opt: Vec<String>,
+
+    /// Wait until no matching apply has been requested for this long
+    /// before applying, folding bursts from hooks and watchers into one run
+    #[arg(long, value_name = "MS")]
+    pub debounce: Option<u64>,
+
+    /// Don't run the reload hooks (`[[reload]]` in the global config) of
+    /// changed files
+    #[arg(long)]
+    pub no_reload: bool,
+}
+
+/// Arguments for the `run` command
+#[derive(Args, Debug)]
+pub struct RunArgs {
+    /// Merge this mode's layers instead of the active mode's
+    #[arg(long)]
+    pub mode: Option<String>,
+
+    /// Merge this scope's layers instead of the active scope's
+    #[arg(long)]
+    pub scope: Option<String>,
+
+    /// Only materialize files matching this pattern (repeatable)
+    #[arg(long, value_name = "PATTERN")]
+    pub only: Vec<String>,
+
+    /// Run the command inside the directory holding the merged files
+    #[arg(long)]
+    pub in_dir: bool,
+
+    /// Command to run and its arguments (after `--`)
+    #[arg(last = true, required = true, value_name = "COMMAND")]
+    pub command: Vec<String>,
 }
 
 /// Arguments for the `reset` command
 #[derive(Args, Debug)]
 pub struct ResetArgs {
-    /// Keep changes in staging
+    /// Leave staging and workspace untouched
     #[arg(long)]
     pub soft: bool,
 
@@ -95,7 +243,7 @@ pub struct ResetArgs {
     #[arg(long)]
     pub mixed: bool,
 
-    /// Discard all changes
+    /// Unstage and restore the workspace to the last applied content
     #[arg(long)]
     pub hard: bool,
 
@@ -115,6 +263,15 @@ pub struct ResetArgs {
     #[arg(long)]
     pub global: bool,
 
+    /// Unstage only this entry (`<layer>:<path>`, a path, or its number in
+    /// `jin status`), keeping the file; repeatable
+    #[arg(
+        long,
+        value_name = "ENTRY",
+        conflicts_with_all = ["soft", "hard", "mode", "scope", "project", "global"]
+    )]
+    pub staged: Vec<String>,
+
     /// Skip confirmation prompt and bypass detached state validation (use for recovery)
     #[arg(long, short = 'f')]
     pub force: bool,
@@ -150,6 +307,10 @@ pub struct RmArgs {
     #[arg(long, short = 'f')]
     pub force: bool,
 
+    /// Keep the workspace copy, only remove the file from the layer
+    #[arg(long)]
+    pub cached: bool,
+
     /// Show what would be removed without doing it
     #[arg(long)]
     pub dry_run: bool,
@@ -202,6 +363,30 @@ pub struct DiffArgs {
     /// Show staged changes
     #[arg(long)]
     pub staged: bool,
+
+    /// Show only the paths of changed files
+    #[arg(long)]
+    pub name_only: bool,
+
+    /// Exit with status 1 if there are differences, 2 on errors, 0 otherwise
+    #[arg(long)]
+    pub exit_code: bool,
+
+    /// Lines of context around each change
+    #[arg(short = 'U', long, value_name = "N", default_value_t = 3)]
+    pub unified: u32,
+
+    /// Ignore whitespace when comparing lines
+    #[arg(short = 'w', long)]
+    pub ignore_all_space: bool,
+
+    /// Ignore changes whose lines are all blank
+    #[arg(long)]
+    pub ignore_blank_lines: bool,
+
+    /// When to color the diff
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t)]
+    pub color: ColorWhen,
 }
 
 /// Arguments for the `log` command
@@ -214,6 +399,24 @@ pub struct LogArgs {
     /// Number of entries to show
     #[arg(long, default_value = "10")]
     pub count: usize,
+
+    /// Show what each commit changed: added, changed and removed keys of
+    /// structured files, then the unified diff
+    #[arg(short = 'p', long)]
+    pub patch: bool,
+
+    /// Show past applies instead of layer commits: context, layer commits,
+    /// files written, duration and outcome
+    #[arg(long, conflicts_with_all = ["layer", "patch"])]
+    pub applies: bool,
+
+    /// With --applies, only those since a date (YYYY-MM-DD) or RFC 3339 time
+    #[arg(long, requires = "applies")]
+    pub since: Option<String>,
+
+    /// When to color the log
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t)]
+    pub color: ColorWhen,
 }
 
 /// Arguments for the `import` command
@@ -258,6 +461,10 @@ pub struct ImportArgs {
     /// Target user-local layer (Layer 8, machine-specific)
     #[arg(long)]
     pub local: bool,
+
+    /// What to do when the target layer already has a file
+    #[arg(long, value_enum, value_name = "POLICY", default_value = "overwrite")]
+    pub on_exists: OnExists,
 }
 
 /// Arguments for the `export` command
@@ -268,46 +475,175 @@ pub struct ExportArgs {
 }
 
 /// Arguments for the `repair` command
-#[derive(Args, Debug)]
+#[derive(Args, Debug, Default)]
+#[command(after_help = r#"TARGETS:
+  Without target flags every check runs. Targets narrow the run:
+    --refs       Repository structure, layer refs and interrupted transactions
+    --staging    Staging index
+    --context    Project context, .jinmap and global configuration
+    --gitignore  .gitignore managed block
+    --workspace  Workspace metadata and attachment
+
+Repair only reports by default. With --fix each fix runs on its own: the
+files it touches are backed up first and restored if it fails, and every
+fix that succeeds is recorded in the audit log.
+"#)]
 pub struct RepairArgs {
-    /// Show what would be repaired
+    /// Apply fixes (default: report only)
+    #[arg(long, conflicts_with = "dry_run")]
+    pub fix: bool,
+
+    /// Show what would be repaired (the default without --fix)
     #[arg(long)]
     pub dry_run: bool,
 
     /// Check workspace state without making repairs
     #[arg(long)]
     pub check: bool,
+
+    /// Check repository structure, layer refs and transactions
+    #[arg(long)]
+    pub refs: bool,
+
+    /// Check the staging index
+    #[arg(long)]
+    pub staging: bool,
+
+    /// Check project context, .jinmap and global configuration
+    #[arg(long)]
+    pub context: bool,
+
+    /// Check the .gitignore managed block
+    #[arg(long)]
+    pub gitignore: bool,
+
+    /// Check workspace metadata and attachment
+    #[arg(long)]
+    pub workspace: bool,
+
+    /// Finish the interrupted commit with this transaction ID (or a unique
+    /// prefix of it), as reported by `jin repair`
+    #[arg(long, value_name = "ID")]
+    pub resume_txn: Option<String>,
+
+    /// With --resume-txn: roll the transaction back instead of finishing it
+    #[arg(long, requires = "resume_txn")]
+    pub rollback: bool,
+}
+
+/// Arguments for the `repro-pack` command
+#[derive(Args, Debug)]
+pub struct ReproPackArgs {
+    /// Files or directories involved in the merge, as layer paths
+    #[arg(required = true)]
+    pub files: Vec<String>,
+
+    /// Directory to write the package to (must not exist or be empty)
+    #[arg(short, long, value_name = "DIR", default_value = "jin-repro")]
+    pub output: PathBuf,
+
+    /// Replace string values and text lines with hashes
+    #[arg(long)]
+    pub redact: bool,
+}
+
+/// Arguments for the `layers` command
+#[derive(Args, Debug)]
+pub struct LayersArgs {
+    /// Preview the layers for a hypothetical context instead of the active
+    /// one; `-` leaves a part unset, and an omitted project defaults to the
+    /// current project
+    #[arg(long = "for", num_args = 1..=3, value_names = ["MODE", "SCOPE", "PROJECT"])]
+    pub for_context: Option<Vec<String>>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "table")]
+    pub format: LayersFormat,
+
+    /// Print the stack as a diagram (DOT or Mermaid) instead, for
+    /// documentation
+    #[arg(long, value_enum, value_name = "LANG", conflicts_with = "format")]
+    pub graph: Option<LayersGraph>,
+}
+
+/// Arguments for the `status` command
+#[derive(Args, Debug, Default)]
+pub struct StatusArgs {
+    /// Only show these sections (comma-separated; all by default)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub sections: Vec<StatusSection>,
 }
 
 /// Arguments for the `link` command
 #[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct LinkArgs {
-    /// Remote repository URL
-    pub url: String,
+    /// Manage named remotes
+    #[command(subcommand)]
+    pub action: Option<LinkAction>,
+
+    /// Remote repository URL (linked as `origin`)
+    pub url: Option<String>,
 
     /// Force update existing remote
     #[arg(long)]
     pub force: bool,
+
+    /// Where credentials come from: auto (default), ssh-agent, ssh-key (key
+    /// files), helper (git credential helpers) or token (from an environment
+    /// variable or --token-file)
+    #[arg(long, value_name = "METHOD")]
+    pub auth: Option<AuthMethod>,
+
+    /// Environment variable holding an access token (default JIN_TOKEN;
+    /// implies --auth token). Only the variable name is saved
+    #[arg(long, value_name = "VAR")]
+    pub token_env: Option<String>,
+
+    /// SSH private key to use for this remote, tried before ssh-agent
+    #[arg(long, value_name = "PATH")]
+    pub ssh_key: Option<String>,
+
+    /// File holding an access token, read when the token variable is unset
+    /// (only the path is saved)
+    #[arg(long, value_name = "PATH")]
+    pub token_file: Option<String>,
+
+    /// Layer `jin push` pushes when none are named (repeatable; all layers
+    /// if omitted)
+    #[arg(long = "push-layer", value_name = "LAYER")]
+    pub push_layers: Vec<String>,
 }
 
 /// Arguments for the `push` command
 #[derive(Args, Debug)]
 #[command(after_help = r#"PUSH SAFETY:
-  • Fetches automatically before pushing
-  • Requires clean merge state
-  • Rejects push if local is behind remote
-  • Use --force to bypass (caution: may overwrite remote changes)
+  • Compares each layer with the remote before pushing; local refs are never
+    overwritten by the remote
+  • Rejects push if a layer is behind or has diverged from the remote
+  • --force overwrites such layers, but only if the remote still has them
+    where the comparison saw them (like git push --force-with-lease)
 "#)]
 pub struct PushArgs {
-    /// Force push (overwrite remote)
+    /// Layers to push (e.g. `global`, `mode/claude`); all layers if omitted
+    #[arg(value_name = "LAYER")]
+    pub layers: Vec<String>,
+
+    /// Overwrite remote layers that are ahead or have diverged, unless they
+    /// changed since the comparison
     #[arg(long)]
     pub force: bool,
+
+    /// Remote to push to, as listed by `jin link list` (default origin)
+    #[arg(long, value_name = "NAME")]
+    pub remote: Option<String>,
 }
 
 /// Arguments for the `resolve` command
 #[derive(Args, Debug)]
 pub struct ResolveArgs {
-    /// File(s) to resolve (optional, resolves all if not specified)
+    /// File(s) to resolve: paths, directories or globs such as
+    /// 'configs/*.json' (optional, resolves all if not specified)
     pub files: Vec<String>,
 
     /// Resolve all remaining conflicts
@@ -321,4 +657,342 @@ pub struct ResolveArgs {
     /// Show what would be resolved without doing it
     #[arg(long)]
     pub dry_run: bool,
+
+    /// Pick a side for each conflict region interactively instead of
+    /// editing .jinmerge files by hand
+    #[arg(long, short = 'i', conflicts_with = "dry_run")]
+    pub interactive: bool,
+
+    /// Resolve every conflict region of the selected files by taking one
+    /// side, without editing the .jinmerge files
+    #[arg(long, value_enum, value_name = "SIDE", conflicts_with = "interactive")]
+    pub take: Option<TakeSide>,
+}
+
+/// Arguments for the `suggest` command
+#[derive(Args, Debug)]
+pub struct SuggestArgs {
+    /// Create (if missing) and activate the primary suggested mode and scope
+    #[arg(long)]
+    pub apply: bool,
+}
+
+/// Arguments for the `verify` command
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    /// Report format
+    #[arg(long, value_enum, default_value = "text")]
+    pub format: VerifyFormat,
+}
+
+/// Arguments for the `badge` command
+#[derive(Args, Debug)]
+pub struct BadgeArgs {
+    /// Workspace paths to describe
+    #[arg(required = true)]
+    pub paths: Vec<PathBuf>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    pub format: BadgeFormat,
+}
+
+/// Arguments for the `check` command
+#[derive(Args, Debug)]
+pub struct CheckArgs {
+    /// Check that applying twice produces no changes
+    #[arg(long)]
+    pub idempotent: bool,
+}
+
+/// Arguments for the `help` command
+#[derive(Args, Debug)]
+pub struct HelpArgs {
+    /// Topic (layers, conflicts, syncing) or command, e.g. `mode use`
+    pub topic: Vec<String>,
+}
+
+/// Arguments for the `explain` command
+#[derive(Args, Debug)]
+pub struct ExplainArgs {
+    /// Error code, e.g. JIN-E006 (lists all codes when omitted)
+    pub code: Option<String>,
+}
+
+/// Arguments for the `completion` command
+#[derive(Args, Debug)]
+pub struct CompletionArgs {
+    /// Shell to generate completions for (detected from $SHELL when
+    /// installing)
+    #[arg(value_enum, required_unless_present_any = ["install", "uninstall"])]
+    pub shell: Option<CompletionShell>,
+
+    /// Write the script to the directory the shell loads completions from
+    #[arg(long, conflicts_with = "uninstall")]
+    pub install: bool,
+
+    /// Remove a script written by --install
+    #[arg(long)]
+    pub uninstall: bool,
+
+    /// Skip the confirmation prompt
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+}
+
+/// Arguments for the `schema` command
+#[derive(Args, Debug)]
+pub struct SchemaArgs {
+    /// Command whose JSON output to describe (lists all when omitted)
+    #[arg(value_enum)]
+    pub command: Option<SchemaCommand>,
+}
+
+/// Arguments for the `version` command
+#[derive(Args, Debug)]
+pub struct VersionArgs {
+    /// Also list supported file formats, JSON schema versions and features
+    #[arg(long)]
+    pub capabilities: bool,
+}
+
+/// Arguments for the `dedupe` command
+#[derive(Args, Debug)]
+pub struct DedupeArgs {
+    /// Report duplicated files and suggested consolidations (the default)
+    #[arg(long)]
+    pub report: bool,
+
+    /// Stage the suggested consolidations for the active context
+    #[arg(long, conflicts_with = "report")]
+    pub fix: bool,
+}
+
+/// Arguments for the `sed` command
+#[derive(Args, Debug)]
+pub struct SedArgs {
+    /// Regular expression to search for
+    pub pattern: String,
+
+    /// Replacement text (`$1` or `${name}` refer to capture groups)
+    pub replacement: String,
+
+    /// Only replace in the value at this dotted key path of structured files
+    /// (e.g., registry.url)
+    #[arg(long, value_name = "KEY.PATH")]
+    pub key: Option<String>,
+
+    /// Only edit files matching this pattern (repeatable)
+    #[arg(long, value_name = "PATTERN")]
+    pub only: Vec<String>,
+
+    /// Only edit this layer (repeatable, e.g., global-base, mode-base;
+    /// default: every layer of the active context)
+    #[arg(long, value_name = "LAYER")]
+    pub layer: Vec<String>,
+
+    /// Show the changes as a diff without staging them
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Arguments for the `assert` command
+#[derive(Args, Debug)]
+#[command(after_help = r#"EXIT STATUS:
+  0  the assertion holds
+  1  the assertion fails
+  2  it could not be evaluated (not initialized, unreadable or unparsable file)
+
+EXAMPLES:
+  jin assert key-equals settings.json editor.formatOnSave true --context mode=claude
+  jin assert managed .claude/settings.json
+  jin assert no-conflicts --context mode=claude,scope=language:rust
+"#)]
+pub struct AssertArgs {
+    /// What to assert
+    #[command(subcommand)]
+    pub check: AssertCheck,
+
+    /// Evaluate this context instead of the active one (mode=, scope=,
+    /// project=; `-` leaves a part unset)
+    #[arg(long, global = true, value_name = "KEY=VALUE", value_delimiter = ',')]
+    pub context: Vec<String>,
+}
+
+/// Arguments for the `list` command
+#[derive(Args, Debug, Default)]
+pub struct ListArgs {
+    /// List only mode, scope or project names (one per line), or the files
+    /// tracked in each layer
+    #[arg(value_enum, conflicts_with = "stale")]
+    pub kind: Option<ListKind>,
+
+    /// With `files`, list only this layer (e.g. global, mode/claude,
+    /// mode/claude/project/ui)
+    #[arg(long, value_name = "LAYER")]
+    pub layer: Option<String>,
+
+    /// Show only modes and scopes that were never used or not used recently
+    #[arg(long)]
+    pub stale: bool,
+
+    /// Age after which an unused mode or scope is stale (e.g., 90d, 12w)
+    #[arg(long, value_name = "AGE", default_value = "90d")]
+    pub than: String,
+}
+
+/// Arguments for the `prune` command
+#[derive(Args, Debug)]
+pub struct PruneArgs {
+    /// Age after which an unused mode or scope is pruned (e.g., 90d, 12w)
+    #[arg(long, value_name = "AGE", default_value = "90d")]
+    pub than: String,
+
+    /// Show what would be deleted without deleting anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Skip the confirmation prompt
+    #[arg(long, short = 'f')]
+    pub force: bool,
+}
+
+/// Arguments for the `stats` command
+#[derive(Args, Debug)]
+pub struct StatsArgs {
+    /// Also list the N largest files across all layers (default 10)
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "10")]
+    pub top: Option<usize>,
+}
+
+/// Arguments for the `gc` command
+#[derive(Args, Debug)]
+pub struct GcArgs {
+    /// Spend much more time to find better deltas (git gc --aggressive)
+    #[arg(long)]
+    pub aggressive: bool,
+
+    /// Delta window; wider windows pack text-heavy history smaller
+    /// (overrides gc.window)
+    #[arg(long, value_name = "N")]
+    pub window: Option<u32>,
+
+    /// Maximum delta chain length (overrides gc.depth)
+    #[arg(long, value_name = "N")]
+    pub depth: Option<u32>,
+
+    /// Only report storage; do not repack
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Arguments for the `projects` command
+#[derive(Args, Debug)]
+pub struct ProjectsArgs {
+    /// Forget workspaces whose directory no longer exists
+    #[arg(long)]
+    pub forget_missing: bool,
+}
+
+/// Arguments for the `context` command
+#[derive(Args, Debug, Default)]
+pub struct ContextArgs {
+    /// Check that the active mode and scope still exist
+    #[arg(long, conflicts_with = "heal")]
+    pub validate: bool,
+
+    /// Unset an active mode or scope that no longer exists
+    #[arg(long)]
+    pub heal: bool,
+}
+
+/// Arguments for the `doctor` command
+#[derive(Args, Debug)]
+pub struct DoctorArgs {
+    /// Replace this machine's ID (recorded in audit entries and user-local
+    /// commits) with a new one
+    #[arg(long)]
+    pub regenerate_machine_id: bool,
+}
+
+/// Arguments for the `init` command
+#[derive(Args, Debug)]
+pub struct InitArgs {
+    /// Set up a user-level workspace in the home directory for dotfiles
+    #[arg(long)]
+    pub home: bool,
+
+    /// Add a path pattern to the home allowlist (repeatable)
+    #[arg(long, value_name = "PATTERN", requires = "home")]
+    pub allow: Vec<String>,
+}
+
+/// Arguments for the `lock` command
+#[derive(Args, Debug)]
+pub struct LockArgs {
+    /// Path to lock (lists current locks if omitted)
+    pub path: Option<String>,
+
+    /// Layer holding the path (e.g., global, mode/claude, project/ui)
+    #[arg(long, default_value = "global")]
+    pub layer: String,
+
+    /// Why the path is locked, shown to teammates
+    #[arg(long, short = 'm')]
+    pub message: Option<String>,
+}
+
+/// Arguments for the `unlock` command
+#[derive(Args, Debug)]
+pub struct UnlockArgs {
+    /// Path to unlock
+    pub path: String,
+
+    /// Layer holding the path
+    #[arg(long, default_value = "global")]
+    pub layer: String,
+
+    /// Release a lock held by someone else
+    #[arg(long, short = 'f')]
+    pub force: bool,
+}
+
+/// Arguments for the `annotate` command
+#[derive(Args, Debug)]
+pub struct AnnotateArgs {
+    /// Layer commit to annotate (hash as shown by `jin log`)
+    pub commit: String,
+
+    /// Annotation, e.g. "rolled back in prod"
+    pub message: String,
+}
+
+/// Arguments for the `archive` command
+#[derive(Args, Debug)]
+pub struct ArchiveArgs {
+    /// Archive a mode or a scope (lists archived ones if omitted)
+    #[arg(value_enum, requires = "name")]
+    pub target: Option<ArchiveTarget>,
+
+    /// Mode or scope name
+    pub name: Option<String>,
+
+    /// Leave its layers out of merges as well
+    #[arg(long)]
+    pub no_merge: bool,
+
+    /// Why it is archived, shown in list output
+    #[arg(long, short = 'm')]
+    pub message: Option<String>,
+}
+
+/// Arguments for the `unarchive` command
+#[derive(Args, Debug)]
+pub struct UnarchiveArgs {
+    /// Unarchive a mode or a scope
+    #[arg(value_enum)]
+    pub target: ArchiveTarget,
+
+    /// Mode or scope name
+    pub name: String,
 }
@@ -4,8 +4,11 @@
 
 pub mod args;
 
-use clap::{Parser, Subcommand};
-use clap_complete::Shell;
+use crate::core::pager::Palette;
+use crate::core::schema::JsonSurface;
+use crate::git::auth::AuthMethod;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
 
 pub use args::*;
 
@@ -18,26 +21,300 @@ pub use args::*;
     about = "Phantom Git layer system for developer configuration"
 )]
 #[command(propagate_version = true)]
+#[command(disable_help_subcommand = true)]
+#[command(after_help = "Run 'jin help <topic>' for a guide: layers, conflicts, syncing")]
 pub struct Cli {
+    /// Run as if jin was started in <PATH> instead of the current directory
+    #[arg(short = 'C', long = "chdir", global = true, value_name = "PATH")]
+    pub chdir: Option<PathBuf>,
+
+    /// Report time spent per phase after the command (table or json)
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "table"
+    )]
+    pub profile: Option<ProfileFormat>,
+
+    /// Progress reporting: a progress line on a terminal (human), or
+    /// NDJSON events on stderr for tools driving jin (json)
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "FORMAT",
+        default_value = "human"
+    )]
+    pub progress: ProgressFormat,
+
+    /// Do not pipe long output (log, diff) through a pager
+    #[arg(long, global = true)]
+    pub no_pager: bool,
+
+    /// Print versioned JSON instead of text (status, log, diff, layers,
+    /// list, version; see `jin schema`)
+    #[arg(long, global = true)]
+    pub json: bool,
+
     /// The command to execute
     #[command(subcommand)]
     pub command: Commands,
 }
 
+/// Output format for `--profile` reports
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfileFormat {
+    /// Human-readable table
+    Table,
+    /// Single-line JSON object
+    Json,
+}
+
+/// Format of progress reports (`--progress`)
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProgressFormat {
+    /// Progress line, drawn only when stdout is a terminal
+    #[default]
+    Human,
+    /// One JSON event per line on stderr
+    Json,
+}
+
+/// When to color output (`--color`)
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorWhen {
+    /// Color when writing to a terminal and `NO_COLOR` is unset
+    #[default]
+    Auto,
+    /// Always color
+    Always,
+    /// Never color
+    Never,
+}
+
+impl ColorWhen {
+    /// The palette for this choice
+    pub fn palette(self) -> Palette {
+        match self {
+            ColorWhen::Auto => Palette::auto(),
+            ColorWhen::Always => Palette::new(true),
+            ColorWhen::Never => Palette::new(false),
+        }
+    }
+}
+
+/// Output format for `jin audit export`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditFormat {
+    /// One JSON object per line
+    Jsonl,
+    /// Comma-separated values with a header row
+    Csv,
+    /// ArcSight Common Event Format
+    Cef,
+}
+
+/// Output format for `jin env export`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnvFormat {
+    /// `NAME=value` lines, for `.env` loaders
+    Dotenv,
+    /// `export NAME='value'` lines, for `eval` in a shell
+    Shell,
+}
+
+/// Output format for `jin layers`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayersFormat {
+    /// Human-readable table
+    Table,
+    /// JSON document
+    Json,
+}
+
+/// Diagram language for `jin layers --graph`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayersGraph {
+    /// Graphviz DOT
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+/// What `jin archive` freezes
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveTarget {
+    /// A mode and all its layers
+    Mode,
+    /// A scope and all its layers
+    Scope,
+}
+
+/// Side taken for every conflict region by `jin resolve --take`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TakeSide {
+    /// The first, lower-precedence side
+    Ours,
+    /// The second side
+    Theirs,
+    /// Both sides, first side first
+    Both,
+}
+
+/// What `jin list` enumerates
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListKind {
+    /// Mode names
+    Modes,
+    /// Scope names
+    Scopes,
+    /// Project names
+    Projects,
+    /// Files tracked in each layer
+    Files,
+}
+
+/// Policy for `jin import --on-exists`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnExists {
+    /// Keep the layer's version and leave the file in Git
+    Skip,
+    /// Stage the workspace version
+    Overwrite,
+    /// Deep-merge the workspace version over the layer's
+    Merge,
+    /// Ask for each file
+    Prompt,
+}
+
+/// Shell for `jin completion`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompletionShell {
+    /// Bourne Again SHell
+    Bash,
+    /// Z SHell
+    Zsh,
+    /// Friendly Interactive SHell
+    Fish,
+    /// PowerShell
+    Powershell,
+    /// Elvish
+    Elvish,
+    /// Nushell
+    Nushell,
+}
+
+/// A command with JSON output, for `jin schema`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchemaCommand {
+    /// `jin status --json`
+    Status,
+    /// `jin log --json`
+    Log,
+    /// `jin diff --json`
+    Diff,
+    /// `jin layers --format json`
+    Layers,
+    /// `jin list --json`
+    List,
+    /// `jin verify --format json`
+    Verify,
+    /// `jin audit export --format jsonl`
+    Audit,
+    /// `jin --profile=json`
+    Profile,
+    /// `jin --progress=json` events
+    Progress,
+    /// `jin badge --format json`
+    Badge,
+    /// `jin version --json`
+    Version,
+}
+
+impl SchemaCommand {
+    /// The output surface this command's schema describes
+    pub fn surface(self) -> JsonSurface {
+        match self {
+            SchemaCommand::Status => JsonSurface::Status,
+            SchemaCommand::Log => JsonSurface::Log,
+            SchemaCommand::Diff => JsonSurface::Diff,
+            SchemaCommand::Layers => JsonSurface::Layers,
+            SchemaCommand::List => JsonSurface::List,
+            SchemaCommand::Verify => JsonSurface::Verify,
+            SchemaCommand::Audit => JsonSurface::Audit,
+            SchemaCommand::Profile => JsonSurface::Profile,
+            SchemaCommand::Progress => JsonSurface::Progress,
+            SchemaCommand::Badge => JsonSurface::Badge,
+            SchemaCommand::Version => JsonSurface::Version,
+        }
+    }
+}
+
+/// A section of `jin status` output, in display order
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StatusSection {
+    /// Active mode, scope and project
+    Context,
+    /// Newer commits on the remote (needs remote.check-interval)
+    Remote,
+    /// Workspace files changed since the last apply
+    Drift,
+    /// Conflicts from a paused apply
+    Conflicts,
+    /// Applies running or waiting to write the workspace
+    Queue,
+    /// Staged changes
+    Staging,
+    /// File counts per layer
+    Layers,
+}
+
+impl StatusSection {
+    /// Every section, in display order
+    pub fn all() -> Vec<Self> {
+        Self::value_variants().to_vec()
+    }
+}
+
+/// Output format for `jin badge`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BadgeFormat {
+    /// One line per path
+    Text,
+    /// JSON document
+    Json,
+}
+
+/// Output format for `jin verify`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifyFormat {
+    /// Human-readable summary
+    Text,
+    /// JSON report
+    Json,
+}
+
 /// Available Jin commands
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Initialize Jin in current project
-    Init,
+    Init(InitArgs),
 
     /// Stage files to appropriate layer
     Add(AddArgs),
 
+    /// Move an existing workspace file into its layer, commit and apply it
+    Adopt(AdoptArgs),
+
     /// Commit staged files atomically
     Commit(CommitArgs),
 
     /// Show workspace state and active contexts
-    Status,
+    Status(StatusArgs),
 
     /// Mode lifecycle management
     #[command(subcommand)]
@@ -56,13 +333,20 @@ pub enum Commands {
     /// Apply merged layers to workspace
     Apply(ApplyArgs),
 
+    /// Run a command with the merged configuration, without touching the workspace
+    Run(RunArgs),
+
+    /// Show or export environment variables defined by the active layers
+    #[command(subcommand)]
+    Env(EnvAction),
+
     /// Resolve merge conflicts
     Resolve(ResolveArgs),
 
     /// Reset staged or committed changes
     Reset(ResetArgs),
 
-    /// Remove files from staging and optionally workspace
+    /// Remove files from a layer and the workspace
     Rm(RmArgs),
 
     /// Move/rename files in staging and optionally workspace
@@ -73,9 +357,18 @@ pub enum Commands {
 
     /// Show commit history
     Log(LogArgs),
+    /// Attach an annotation to a layer commit, shown in `jin log`
+    Annotate(AnnotateArgs),
 
     /// Show/set active context
-    Context,
+    Context(ContextArgs),
+
+    /// Print a short context and status summary for shell prompts
+    Prompt,
+
+    /// Show whether paths are managed, staged, drifted or conflicted, and
+    /// their source layers (for editor plugins)
+    Badge(BadgeArgs),
 
     /// Import Git-tracked files into Jin
     Import(ImportArgs),
@@ -86,13 +379,17 @@ pub enum Commands {
     /// Repair Jin state
     Repair(RepairArgs),
 
+    /// Package the files of a merge for a bug report, without the rest of
+    /// your configuration
+    ReproPack(ReproPackArgs),
+
     /// Show current layer composition
-    Layers,
+    Layers(LayersArgs),
 
     /// List available modes/scopes/projects
-    List,
+    List(ListArgs),
 
-    /// Link to shared Jin config repo
+    /// Link to shared Jin config repo, or manage named remotes
     Link(LinkArgs),
 
     /// Fetch updates from remote
@@ -107,25 +404,105 @@ pub enum Commands {
     /// Fetch + merge + apply
     Sync,
 
+    /// Inspect layers on the linked remote without pulling
+    #[command(subcommand)]
+    Remote(RemoteAction),
+
+    /// Sync the user-local layer between your own machines
+    #[command(subcommand)]
+    Local(LocalAction),
+
+    /// Lock a path in a shared layer, or list locks
+    Lock(LockArgs),
+
+    /// Release a path lock
+    Unlock(UnlockArgs),
+
+    /// Archive a mode or scope read-only, or list archived ones
+    ///
+    /// Commits to an archived mode or scope are refused and list output
+    /// shows it apart from the others. It still takes part in merges
+    /// unless archived with --no-merge.
+    Archive(ArchiveArgs),
+
+    /// Make an archived mode or scope writable again
+    Unarchive(UnarchiveArgs),
+
     /// Generate shell completion scripts
     ///
     /// Outputs completion script to stdout. Redirect to a file and source it
-    /// to enable tab completion in your shell.
+    /// to enable tab completion in your shell, or let --install put it where
+    /// your shell looks for completions.
     ///
     /// Installation:
-    ///   Bash:       jin completion bash > /usr/local/share/bash-completion/completions/jin
-    ///   Zsh:        jin completion zsh > ~/.zsh/completions/_jin
+    ///   Any shell:  jin completion --install   (shell detected from $SHELL)
+    ///   Bash:       jin completion bash > ~/.local/share/bash-completion/completions/jin
+    ///   Zsh:        jin completion zsh > ~/.zfunc/_jin
     ///   Fish:       jin completion fish > ~/.config/fish/completions/jin.fish
     ///   PowerShell: jin completion powershell > $PROFILE\..\Completions\jin_completion.ps1
-    Completion {
-        /// Shell type to generate completions for
-        #[arg(value_enum)]
-        shell: Shell,
-    },
+    ///   Elvish:     jin completion elvish > ~/.config/elvish/lib/jin-completions.elv
+    ///   Nushell:    jin completion nushell > ~/.config/nushell/completions/jin.nu
+    Completion(CompletionArgs),
+
+    /// Show help for a command, or a guide on a topic (layers, conflicts, syncing)
+    Help(HelpArgs),
+
+    /// Print the JSON Schema of a command's JSON output
+    Schema(SchemaArgs),
+    /// Show the installed version and, for tools, what it supports
+    Version(VersionArgs),
+
+    /// Explain an error code (e.g. JIN-E006): causes and how to fix it
+    Explain(ExplainArgs),
 
     /// View/edit Jin configuration
     #[command(subcommand)]
     Config(ConfigAction),
+
+    /// Manage named Jin repositories
+    #[command(subcommand)]
+    Repo(RepoAction),
+
+    /// Inspect and export the audit log
+    #[command(subcommand)]
+    Audit(AuditAction),
+
+    /// Recommend modes and scopes based on the workspace
+    Suggest(SuggestArgs),
+
+    /// Verify layer objects and applied files against recorded hashes
+    Verify(VerifyArgs),
+
+    /// Check that the merge engine output is stable (e.g., for CI)
+    Check(CheckArgs),
+
+    /// Find files duplicated across layers and consolidate them
+    Dedupe(DedupeArgs),
+
+    /// Search and replace across layers, staging the edits for review
+    Sed(SedArgs),
+
+    /// Assert config invariants of the merged layers (exit 1 on failure)
+    Assert(AssertArgs),
+
+    /// Delete modes and scopes that were never used or are abandoned
+    Prune(PruneArgs),
+
+    /// Repack the Jin repository and report storage before and after
+    Gc(GcArgs),
+
+    /// Show storage per mode and scope against their quotas
+    Stats(StatsArgs),
+
+    /// List workspaces initialized or applied against this repository
+    Projects(ProjectsArgs),
+
+    /// Show this installation's setup: Jin directory, machine ID, remotes
+    Doctor(DoctorArgs),
+
+    /// Fetch remote layer refs for the status freshness check (internal)
+    #[command(hide = true)]
+    RemoteCheck,
 }
 
 /// Mode subcommands
@@ -140,6 +517,9 @@ pub enum ModeAction {
     Use {
         /// Name of the mode to activate
         name: String,
+        /// Apply the new context to the workspace right away
+        #[arg(long)]
+        apply: bool,
     },
     /// List available modes
     List,
@@ -152,6 +532,22 @@ pub enum ModeAction {
     Show,
     /// Deactivate current mode
     Unset,
+    /// Write a mode and its scope/project layers to a shareable YAML file
+    Export {
+        /// Name of the mode to export
+        name: String,
+        /// File to write (default: stdout)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Create a mode from a file written by 'jin mode export'
+    Import {
+        /// Exported mode file ('-' for stdin)
+        file: PathBuf,
+        /// Name for the imported mode (default: the exported name)
+        #[arg(long = "as", value_name = "NAME")]
+        rename: Option<String>,
+    },
 }
 
 /// Scope subcommands
@@ -169,6 +565,9 @@ pub enum ScopeAction {
     Use {
         /// Name of the scope to activate
         name: String,
+        /// Apply the new context to the workspace right away
+        #[arg(long)]
+        apply: bool,
     },
     /// List available scopes
     List,
@@ -201,3 +600,178 @@ pub enum ConfigAction {
         value: String,
     },
 }
+
+/// Repo subcommands
+#[derive(Subcommand, Debug)]
+pub enum RepoAction {
+    /// Register a named repository (created if it doesn't exist)
+    Add {
+        /// Name of the repository
+        name: String,
+        /// Path to the bare repository (may start with ~)
+        path: String,
+    },
+    /// Unregister a named repository (files on disk are kept)
+    Remove {
+        /// Name of the repository
+        name: String,
+    },
+    /// List registered repositories
+    List,
+    /// Select the repository used by this project
+    Use {
+        /// Name of the repository ("default" for the default location)
+        name: String,
+    },
+    /// Show the repository used by this project
+    Show,
+    /// Move a named repository and update its registration
+    Move {
+        /// New location of the bare repository (may start with ~)
+        path: String,
+        /// Repository to move (default: the one this project uses)
+        #[arg(long)]
+        name: Option<String>,
+    },
+}
+
+/// Link subcommands
+#[derive(Subcommand, Debug)]
+pub enum LinkAction {
+    /// Link a named remote (`origin` is the team remote used by fetch and pull)
+    Add {
+        /// Remote name (e.g., origin, backup)
+        name: String,
+        /// Remote repository URL
+        url: String,
+        /// Replace the remote if it is already linked
+        #[arg(long)]
+        force: bool,
+        /// Where credentials come from: auto (default), ssh-agent, ssh-key
+        /// (key files), helper (git credential helpers) or token (from an
+        /// environment variable or --token-file)
+        #[arg(long, value_name = "METHOD")]
+        auth: Option<AuthMethod>,
+        /// Environment variable holding an access token (default JIN_TOKEN;
+        /// implies --auth token). Only the variable name is saved
+        #[arg(long, value_name = "VAR")]
+        token_env: Option<String>,
+        /// SSH private key to use for this remote, tried before ssh-agent
+        #[arg(long, value_name = "PATH")]
+        ssh_key: Option<String>,
+        /// File holding an access token, read when the token variable is
+        /// unset (only the path is saved)
+        #[arg(long, value_name = "PATH")]
+        token_file: Option<String>,
+        /// Layer `jin push` pushes to this remote when none are named
+        /// (repeatable; all layers if omitted)
+        #[arg(long = "push-layer", value_name = "LAYER")]
+        push_layers: Vec<String>,
+    },
+    /// List linked remotes and their settings
+    List,
+    /// Unlink a remote and drop its remote-tracking refs
+    Remove {
+        /// Remote name
+        name: String,
+    },
+}
+
+/// Remote subcommands
+#[derive(Subcommand, Debug)]
+pub enum RemoteAction {
+    /// List layers on the remote and compare them with local layers
+    Ls {
+        /// Only show layers starting with this prefix (e.g., mode/claude)
+        prefix: Option<String>,
+    },
+    /// List the files of a remote layer, or print one of them
+    Show {
+        /// Layer as shown by `jin remote ls` (e.g., mode/claude)
+        layer: String,
+        /// File to print (lists all files when omitted)
+        path: Option<String>,
+    },
+}
+
+/// Local subcommands
+#[derive(Subcommand, Debug)]
+pub enum LocalAction {
+    /// Link a private remote for syncing the user-local layer
+    Link {
+        /// Private repository URL (must not be the team remote)
+        url: String,
+        /// Name of this machine (defaults to the host name)
+        #[arg(long)]
+        machine: Option<String>,
+    },
+    /// Merge user-local layers from other machines and publish this one
+    Sync,
+}
+
+/// Env subcommands
+#[derive(Subcommand, Debug)]
+pub enum EnvAction {
+    /// List the composed variables and the layer that set each one
+    Show,
+    /// Print the composed variables for a shell or an .env loader
+    Export {
+        /// Output format
+        #[arg(long, value_enum, default_value = "dotenv")]
+        format: EnvFormat,
+    },
+}
+
+/// Audit subcommands
+#[derive(Subcommand, Debug)]
+pub enum AuditAction {
+    /// Export audit records for security tooling
+    Export {
+        /// Output format
+        #[arg(long, value_enum, default_value = "jsonl")]
+        format: AuditFormat,
+        /// Only include records at or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        since: Option<String>,
+    },
+}
+
+/// Assertions checked by `jin assert`
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum AssertCheck {
+    /// The merged value at a key path equals a value
+    KeyEquals {
+        /// Managed file (e.g., settings.json)
+        file: String,
+        /// Dotted key path (e.g., editor.formatOnSave)
+        key: String,
+        /// Expected value (JSON scalar or plain text, e.g., true, 8080, dark)
+        value: String,
+    },
+    /// The merged file sets a key path
+    KeyExists {
+        /// Managed file
+        file: String,
+        /// Dotted key path
+        key: String,
+    },
+    /// The merged file doesn't set a key path
+    KeyAbsent {
+        /// Managed file
+        file: String,
+        /// Dotted key path
+        key: String,
+    },
+    /// A layer of the context provides the file
+    Managed {
+        /// Workspace path
+        file: String,
+    },
+    /// No layer of the context provides the file
+    Unmanaged {
+        /// Workspace path
+        file: String,
+    },
+    /// The layers of the context merge without conflicts
+    NoConflicts,
+}
@@ -3,17 +3,87 @@
 //! This command stages files to the appropriate layer based on flags.
 //! Files are validated, their content is hashed into Git blobs, and
 //! they are added to the staging index for later commit.
+//!
+//! The active mode (for mode layers) and the `--scope` scope must exist.
+//! With `--create-missing` (or `add.create-missing`), the missing ones are
+//! created in one ref transaction instead, after confirmation when run
+//! interactively, and removed again if no file could be staged.
 
 use crate::cli::AddArgs;
-use crate::core::{JinError, Layer, ProjectContext, Result};
+use crate::core::dangling::{self, ContextEntry};
+use crate::core::fsnames;
+use crate::core::limits::{format_size, LimitsConfig};
+use crate::core::usage::{self, parse_age, UsageKind};
+use crate::core::{HomeConfig, JinConfig, JinError, Layer, LocalExpiry, ProjectContext, Result};
+use crate::git::transaction::JinTransaction;
 use crate::git::{JinRepo, ObjectOps};
+use crate::merge::layer::read_layer_contents;
+use crate::merge::{
+    find_layers_containing_file, get_applicable_layers, merge_layers, LayerMergeConfig,
+};
 use crate::staging::{
     ensure_in_managed_block, get_file_mode, is_git_tracked, is_symlink, read_file, route_to_layer,
-    validate_routing_options, walk_directory, RoutingOptions, StagedEntry, StagedOperation,
-    StagingIndex,
+    stale_merge_layers, validate_routing_options, walk_directory, RoutingOptions, StagedEntry,
+    StagedOperation, StagingIndex, WorkspaceMetadata,
 };
+use chrono::Utc;
+use serde::Deserialize;
+use std::io::{IsTerminal, Write};
 use std::path::{Path, PathBuf};
 
+/// A bulk staging manifest for `jin add --manifest`
+///
+/// Paths are relative to the workspace root, just like paths given on the
+/// command line. Each entry carries the same layer flags as `jin add`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AddManifest {
+    /// Paths to stage with their target layer flags
+    pub files: Vec<ManifestEntry>,
+}
+
+/// A single path in an add manifest
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ManifestEntry {
+    /// File or directory to stage
+    pub path: String,
+    /// Target mode layer
+    pub mode: bool,
+    /// Target scope layer
+    pub scope: Option<String>,
+    /// Target mode-project layer
+    pub project: bool,
+    /// Target global layer
+    pub global: bool,
+    /// Target user-local layer
+    pub local: bool,
+}
+
+impl ManifestEntry {
+    fn routing_options(&self) -> RoutingOptions {
+        RoutingOptions {
+            mode: self.mode,
+            scope: self.scope.clone(),
+            project: self.project,
+            global: self.global,
+            local: self.local,
+        }
+    }
+}
+
+impl AddManifest {
+    /// Load a manifest from a YAML file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| JinError::NotFound(format!("Manifest {}: {}", path.display(), e)))?;
+        serde_yaml::from_str(&content).map_err(|e| JinError::Parse {
+            format: "YAML".to_string(),
+            message: format!("{}: {}", path.display(), e),
+        })
+    }
+}
+
 /// Execute the add command
 ///
 /// Stages files to the appropriate layer based on flags.
@@ -31,14 +101,19 @@ use std::path::{Path, PathBuf};
 /// - A file is tracked by Git
 /// - Routing options are invalid
 /// - No active mode when --mode flag is used
-pub fn execute(args: AddArgs) -> Result<()> {
+/// - The target mode or scope doesn't exist, without --create-missing
+pub fn execute(args: AddArgs, root: &Path) -> Result<()> {
+    if let Some(manifest) = &args.manifest {
+        return execute_manifest(root, manifest, args.create_missing);
+    }
+
     // 1. Validate we have files to stage
     if args.files.is_empty() {
         return Err(JinError::Other("No files specified".to_string()));
     }
 
     // 2. Load project context for active mode/scope
-    let context = match ProjectContext::load() {
+    let context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -58,23 +133,39 @@ pub fn execute(args: AddArgs) -> Result<()> {
 
     // 4. Determine target layer
     let target_layer = route_to_layer(&options, &context)?;
+    let expires_at = args
+        .expires
+        .as_deref()
+        .map(|age| parse_age(age).map(|ttl| Utc::now() + ttl))
+        .transpose()?;
 
     // 5. Open Jin repository
-    let repo = JinRepo::open_or_create()?;
+    let repo = JinRepo::open_or_create(root)?;
+
+    // 5.5. The mode and scope routed to must exist, or be created now
+    let missing = missing_targets(&options, target_layer, &context, &repo);
+    if !missing.is_empty() {
+        if !create_missing_enabled(args.create_missing) || !confirm_creation(&missing)? {
+            return Err(missing_error(&missing));
+        }
+        create_targets(&missing, &repo)?;
+    }
 
-    // 6. Load staging index
-    let mut staging = StagingIndex::load().unwrap_or_else(|_| StagingIndex::new());
+    // 6. Load staging index and last-applied metadata (for stale merge checks)
+    let mut staging = StagingIndex::load(root).unwrap_or_else(|_| StagingIndex::new());
+    let metadata = WorkspaceMetadata::load(root).ok();
 
     // 7. Process each file
     let mut staged_count = 0;
     let mut errors = Vec::new();
+    let mut staged_paths = Vec::new();
 
     for path_str in &args.files {
         let path = PathBuf::from(path_str);
 
         // Expand directories
-        let files_to_stage = if path.is_dir() {
-            match walk_directory(&path) {
+        let files_to_stage = if root.join(&path).is_dir() {
+            match walk_directory(root, &path) {
                 Ok(files) => files,
                 Err(e) => {
                     errors.push(format!("{}: {}", path.display(), e));
@@ -86,13 +177,16 @@ pub fn execute(args: AddArgs) -> Result<()> {
         };
 
         for file_path in files_to_stage {
-            match stage_file(&file_path, target_layer, &repo, &mut staging) {
+            match stage_file(root, &file_path, target_layer, &repo, &mut staging) {
                 Ok(_) => {
+                    warn_if_stale(&file_path, metadata.as_ref(), &repo);
+
                     // Add to .gitignore managed block
-                    if let Err(e) = ensure_in_managed_block(&file_path) {
+                    if let Err(e) = ensure_in_managed_block(root, &file_path) {
                         eprintln!("Warning: Could not update .gitignore: {}", e);
                     }
                     staged_count += 1;
+                    staged_paths.push(file_path);
                 }
                 Err(e) => {
                     errors.push(format!("{}: {}", file_path.display(), e));
@@ -101,8 +195,34 @@ pub fn execute(args: AddArgs) -> Result<()> {
         }
     }
 
-    // 8. Save staging index
-    staging.save()?;
+    // Nothing was staged for the new mode or scope, so it goes again
+    if staged_count == 0 && !missing.is_empty() {
+        if let Err(e) = remove_targets(&missing, &repo) {
+            eprintln!("Warning: Could not remove the created refs: {}", e);
+        }
+    } else {
+        for target in &missing {
+            println!("Created {} '{}'", target.entry, target.name);
+        }
+    }
+
+    // 8. Save staging index, and when the staged overrides expire
+    staging.save(root)?;
+    if let Some(at) = expires_at {
+        if !staged_paths.is_empty() {
+            let mut expiry = LocalExpiry::load_or_default(repo.path());
+            for path in &staged_paths {
+                expiry.set(path, at);
+            }
+            expiry.save(repo.path())?;
+        }
+    }
+
+    let staged: Vec<(PathBuf, Layer)> = staged_paths
+        .into_iter()
+        .map(|path| (path, target_layer))
+        .collect();
+    warn_if_merge_artifacts(root, &staged, &context, &repo);
 
     // 9. Print summary
     if staged_count > 0 {
@@ -111,6 +231,12 @@ pub fn execute(args: AddArgs) -> Result<()> {
             staged_count,
             format_layer_name_with_context(target_layer, &context)
         );
+        if let Some(at) = expires_at {
+            println!(
+                "The override expires {} and is then no longer applied",
+                at.format("%Y-%m-%d %H:%M UTC")
+            );
+        }
     }
 
     if !errors.is_empty() {
@@ -128,19 +254,367 @@ pub fn execute(args: AddArgs) -> Result<()> {
     Ok(())
 }
 
+/// A mode or scope that `jin add` routes to but that doesn't exist
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MissingTarget {
+    entry: ContextEntry,
+    name: String,
+    /// Ref marking it as existing
+    ref_path: String,
+    /// Command creating it by hand
+    command: String,
+}
+
+/// The active mode (for mode layers) and the `--scope` scope, when they
+/// don't exist
+///
+/// A missing scope routed to with `--mode` is bound to the active mode.
+fn missing_targets(
+    options: &RoutingOptions,
+    layer: Layer,
+    context: &ProjectContext,
+    repo: &JinRepo,
+) -> Vec<MissingTarget> {
+    let mut missing = Vec::new();
+    let mode = context.mode.as_deref().filter(|_| layer.requires_mode());
+    if let Some(mode) = mode.filter(|m| !dangling::mode_exists(repo, m)) {
+        missing.push(MissingTarget {
+            entry: ContextEntry::Mode,
+            name: mode.to_string(),
+            ref_path: format!("refs/jin/modes/{}/_mode", mode),
+            command: format!("jin mode create {}", mode),
+        });
+    }
+    let scope = options.scope.as_deref().filter(|_| layer.requires_scope());
+    if let Some(scope) = scope.filter(|s| !dangling::scope_exists(repo, s)) {
+        let ref_safe_name = scope.replace(':', "/");
+        missing.push(MissingTarget {
+            entry: ContextEntry::Scope,
+            name: scope.to_string(),
+            ref_path: match mode {
+                Some(mode) => format!("refs/jin/modes/{}/scopes/{}", mode, ref_safe_name),
+                None => format!("refs/jin/scopes/{}", ref_safe_name),
+            },
+            command: match mode {
+                Some(mode) => format!("jin scope create {} --mode {}", scope, mode),
+                None => format!("jin scope create {}", scope),
+            },
+        });
+    }
+    missing
+}
+
+/// Whether `--create-missing` was given or `add.create-missing` is set
+fn create_missing_enabled(flag: bool) -> bool {
+    flag || JinConfig::load()
+        .ok()
+        .and_then(|config| config.add)
+        .is_some_and(|add| add.create_missing)
+}
+
+fn missing_error(missing: &[MissingTarget]) -> JinError {
+    let lines: Vec<String> = missing
+        .iter()
+        .map(|t| {
+            let mut entry = t.entry.to_string();
+            entry[..1].make_ascii_uppercase();
+            format!(
+                "{} '{}' does not exist. Create it with: {}",
+                entry, t.name, t.command
+            )
+        })
+        .collect();
+    JinError::Config(format!(
+        "{}\nOr pass --create-missing to create it while adding.",
+        lines.join("\n")
+    ))
+}
+
+/// Ask before creating, when someone is there to answer
+fn confirm_creation(missing: &[MissingTarget]) -> Result<bool> {
+    if !std::io::stdin().is_terminal() {
+        return Ok(true);
+    }
+    let names: Vec<String> = missing
+        .iter()
+        .map(|t| format!("{} '{}'", t.entry, t.name))
+        .collect();
+    print!("Create {}? [y/N] ", names.join(" and "));
+    std::io::stdout().flush()?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Create every missing mode and scope in one ref transaction, as
+/// `jin mode create` and `jin scope create` do
+fn create_targets(missing: &[MissingTarget], repo: &JinRepo) -> Result<()> {
+    for target in missing {
+        match target.entry {
+            ContextEntry::Mode => super::mode::validate_mode_name(&target.name)?,
+            ContextEntry::Scope => super::scope::validate_scope_name(&target.name)?,
+        }
+    }
+
+    let empty_tree = repo.create_tree(&[])?;
+    let mut tx = JinTransaction::new(repo)?;
+    for target in missing {
+        let commit = repo.create_commit(
+            None,
+            &format!("Initialize {}: {}", target.entry, target.name),
+            empty_tree,
+            &[],
+        )?;
+        tx.lock_ref(&target.ref_path)?;
+        tx.set_target(
+            &target.ref_path,
+            commit,
+            &format!("create {} {} (jin add)", target.entry, target.name),
+        )?;
+    }
+    tx.commit()?;
+
+    usage::record(repo.path(), |log| {
+        for target in missing {
+            let kind = match target.entry {
+                ContextEntry::Mode => UsageKind::Mode,
+                ContextEntry::Scope => UsageKind::Scope,
+            };
+            log.mark_created(kind, &target.name, Utc::now());
+        }
+    });
+    Ok(())
+}
+
+/// Undo [`create_targets`]
+fn remove_targets(missing: &[MissingTarget], repo: &JinRepo) -> Result<()> {
+    let mut tx = JinTransaction::new(repo)?;
+    for target in missing {
+        tx.lock_ref(&target.ref_path)?;
+        tx.remove(&target.ref_path)?;
+    }
+    tx.commit()
+}
+
+/// Stage every path listed in a manifest
+///
+/// The whole manifest is validated first (routing flags, file existence,
+/// symlinks, Git tracking); if any entry is invalid, all problems are
+/// reported and nothing is staged. Modes and scopes the entries route to
+/// that don't exist are created as for a single `jin add`, and removed
+/// again if staging fails.
+fn execute_manifest(root: &Path, manifest_path: &Path, create_missing: bool) -> Result<()> {
+    let manifest = AddManifest::load(&root.join(manifest_path))?;
+    if manifest.files.is_empty() {
+        return Err(JinError::Other(format!(
+            "Manifest {} lists no files",
+            manifest_path.display()
+        )));
+    }
+
+    let context = match ProjectContext::load(root) {
+        Ok(ctx) => ctx,
+        Err(JinError::NotInitialized) => {
+            return Err(JinError::NotInitialized);
+        }
+        Err(_) => ProjectContext::default(),
+    };
+
+    let plan = match plan_manifest(root, &manifest, &context) {
+        Ok(plan) => plan,
+        Err(errors) => {
+            for error in &errors {
+                eprintln!("Error: {}", error);
+            }
+            return Err(JinError::Other(format!(
+                "Manifest {} has {} invalid entr{}; nothing was staged",
+                manifest_path.display(),
+                errors.len(),
+                if errors.len() == 1 { "y" } else { "ies" }
+            )));
+        }
+    };
+
+    let repo = JinRepo::open_or_create(root)?;
+
+    let mut missing: Vec<MissingTarget> = Vec::new();
+    for planned in &plan {
+        let options = RoutingOptions {
+            scope: planned.scope.clone(),
+            ..Default::default()
+        };
+        for target in missing_targets(&options, planned.layer, &context, &repo) {
+            if !missing.iter().any(|t| t.ref_path == target.ref_path) {
+                missing.push(target);
+            }
+        }
+    }
+    if !missing.is_empty() {
+        if !create_missing_enabled(create_missing) || !confirm_creation(&missing)? {
+            return Err(missing_error(&missing));
+        }
+        create_targets(&missing, &repo)?;
+    }
+
+    let staged = stage_manifest(root, &plan, &context, &repo);
+    if staged.is_err() && !missing.is_empty() {
+        if let Err(e) = remove_targets(&missing, &repo) {
+            eprintln!("Warning: Could not remove the created refs: {}", e);
+        }
+    }
+    let counts = staged?;
+    for target in &missing {
+        println!("Created {} '{}'", target.entry, target.name);
+    }
+    for (layer_name, count) in counts {
+        println!("Staged {} file(s) to {} layer", count, layer_name);
+    }
+
+    Ok(())
+}
+
+/// Stage every planned file and save the index, returning how many files
+/// went to each layer
+fn stage_manifest(
+    root: &Path,
+    plan: &[PlannedFile],
+    context: &ProjectContext,
+    repo: &JinRepo,
+) -> Result<Vec<(String, usize)>> {
+    let mut staging = StagingIndex::load(root).unwrap_or_else(|_| StagingIndex::new());
+    let metadata = WorkspaceMetadata::load(root).ok();
+
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for planned in plan {
+        stage_file(root, &planned.path, planned.layer, repo, &mut staging)?;
+        warn_if_stale(&planned.path, metadata.as_ref(), repo);
+        if let Err(e) = ensure_in_managed_block(root, &planned.path) {
+            eprintln!("Warning: Could not update .gitignore: {}", e);
+        }
+
+        let display_context = ProjectContext {
+            scope: planned.scope.clone().or_else(|| context.scope.clone()),
+            ..context.clone()
+        };
+        let layer_name = format_layer_name_with_context(planned.layer, &display_context);
+        match counts.iter_mut().find(|(name, _)| *name == layer_name) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((layer_name, 1)),
+        }
+    }
+
+    staging.save(root)?;
+
+    let staged: Vec<(PathBuf, Layer)> = plan
+        .iter()
+        .map(|planned| (planned.path.clone(), planned.layer))
+        .collect();
+    warn_if_merge_artifacts(root, &staged, context, repo);
+
+    Ok(counts)
+}
+
+/// A file resolved from a manifest entry, ready to stage
+#[derive(Debug)]
+struct PlannedFile {
+    path: PathBuf,
+    layer: Layer,
+    scope: Option<String>,
+}
+
+/// Resolve and validate every manifest entry without staging anything
+///
+/// Returns all validation errors at once so a manifest can be fixed in a
+/// single pass.
+fn plan_manifest(
+    root: &Path,
+    manifest: &AddManifest,
+    context: &ProjectContext,
+) -> std::result::Result<Vec<PlannedFile>, Vec<String>> {
+    let mut plan: Vec<PlannedFile> = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, entry) in manifest.files.iter().enumerate() {
+        let label = format!("entry {} ({})", index + 1, entry.path);
+        if entry.path.is_empty() {
+            errors.push(format!("entry {}: missing 'path'", index + 1));
+            continue;
+        }
+
+        let options = entry.routing_options();
+        let layer = match validate_routing_options(&options)
+            .and_then(|_| route_to_layer(&options, context))
+        {
+            Ok(layer) => layer,
+            Err(e) => {
+                errors.push(format!("{}: {}", label, e));
+                continue;
+            }
+        };
+
+        let path = PathBuf::from(&entry.path);
+        let files = if root.join(&path).is_dir() {
+            match walk_directory(root, &path) {
+                Ok(files) => files,
+                Err(e) => {
+                    errors.push(format!("{}: {}", label, e));
+                    continue;
+                }
+            }
+        } else {
+            vec![path]
+        };
+
+        for file in files {
+            if let Err(e) = validate_file(root, &file) {
+                errors.push(format!("{}: {}", label, e));
+                continue;
+            }
+            if let Some(existing) = plan.iter().find(|p| p.path == file) {
+                if existing.layer != layer || existing.scope != entry.scope {
+                    errors.push(format!(
+                        "{}: {} is already listed for a different layer",
+                        label,
+                        file.display()
+                    ));
+                }
+                continue;
+            }
+            plan.push(PlannedFile {
+                path: file,
+                layer,
+                scope: entry.scope.clone(),
+            });
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(plan)
+    } else {
+        Err(errors)
+    }
+}
+
 /// Stage a single file to the staging index
-fn stage_file(path: &Path, layer: Layer, repo: &JinRepo, staging: &mut StagingIndex) -> Result<()> {
+fn stage_file(
+    root: &Path,
+    path: &Path,
+    layer: Layer,
+    repo: &JinRepo,
+    staging: &mut StagingIndex,
+) -> Result<()> {
     // Validate file
-    validate_file(path)?;
+    validate_file(root, path)?;
 
     // Read content from workspace
-    let content = read_file(path)?;
+    let location = root.join(path);
+    let content = read_file(&location)?;
 
     // Create blob in Jin's bare repository
     let oid = repo.create_blob(&content)?;
 
     // Get file mode (executable or regular)
-    let mode = get_file_mode(path);
+    let mode = get_file_mode(&location);
 
     // Create staged entry
     let entry = StagedEntry {
@@ -157,40 +631,188 @@ fn stage_file(path: &Path, layer: Layer, repo: &JinRepo, staging: &mut StagingIn
     Ok(())
 }
 
+/// Warn when a file was generated from an older merge than the current layers
+///
+/// Staging such a file would commit stale merged content back into a layer
+/// and revert whatever changed upstream since the last `jin apply`. This is
+/// a warning only; the user may intend to overwrite.
+pub(crate) fn warn_if_stale(path: &Path, metadata: Option<&WorkspaceMetadata>, repo: &JinRepo) {
+    let Some(metadata) = metadata else {
+        return;
+    };
+    let path = path.strip_prefix(".").unwrap_or(path);
+
+    match stale_merge_layers(path, metadata, repo) {
+        Ok(layers) if !layers.is_empty() => {
+            eprintln!(
+                "Warning: {} was generated from an older merge; {} changed since the last 'jin apply'.",
+                path.display(),
+                layers.join(", ")
+            );
+            eprintln!(
+                "         Committing it may revert those changes. Run 'jin apply' to refresh it first."
+            );
+        }
+        _ => {}
+    }
+}
+
+/// Warn when staged files are merge output rather than one layer's version
+///
+/// A file that is byte-identical to what `jin apply` would write now, yet
+/// differs from every layer holding it, combines several layers. Staging it
+/// copies everything those layers contribute into the target layer, where
+/// it would shadow later changes to them. This is a warning only.
+///
+/// The merge preview is only computed when a staged file exists in more
+/// than one layer of the active context.
+pub(crate) fn warn_if_merge_artifacts(
+    root: &Path,
+    staged: &[(PathBuf, Layer)],
+    context: &ProjectContext,
+    repo: &JinRepo,
+) {
+    let config = LayerMergeConfig {
+        layers: get_applicable_layers(
+            context.mode.as_deref(),
+            context.scope.as_deref(),
+            context.project.as_deref(),
+        ),
+        mode: context.mode.clone(),
+        scope: context.scope.clone(),
+        project: context.project.clone(),
+        root: root.to_path_buf(),
+    };
+
+    let candidates: Vec<(&Path, Layer, Vec<Layer>)> = staged
+        .iter()
+        .filter_map(|(path, target)| {
+            let path = path.strip_prefix(".").unwrap_or(path);
+            let layers = find_layers_containing_file(path, &config.layers, &config, repo).ok()?;
+            (layers.len() > 1).then_some((path, *target, layers))
+        })
+        .collect();
+    if candidates.is_empty() {
+        return;
+    }
+    let Ok(merged) = merge_layers(&config, repo) else {
+        return;
+    };
+    let Ok(attributes) = super::apply::load_attributes(root, &merged) else {
+        return;
+    };
+
+    for (path, target, layers) in candidates {
+        let Some(merged_file) = merged.merged_files.get(path) else {
+            continue;
+        };
+        let (Ok(preview), Ok(workspace)) = (
+            super::apply::applied_content(merged_file, &attributes.resolve(path)),
+            std::fs::read(root.join(path)),
+        ) else {
+            continue;
+        };
+        if preview.as_bytes() != workspace.as_slice() {
+            continue;
+        }
+        let Ok(contents) = read_layer_contents(path, &layers, &config, repo) else {
+            continue;
+        };
+        if contents
+            .iter()
+            .any(|(_, content)| content.as_bytes() == workspace.as_slice())
+        {
+            continue;
+        }
+
+        let names: Vec<String> = layers
+            .iter()
+            .map(|layer| format_layer_name_with_context(*layer, context))
+            .collect();
+        eprintln!(
+            "Warning: {} is the merged output of {}, not any single layer's version.",
+            path.display(),
+            names.join(", ")
+        );
+        eprintln!(
+            "         Staging it copies all of them into the {} layer. To change one setting, edit only that layer's copy (e.g., 'jin sed --layer {} --key <key> ...').",
+            format_layer_name_with_context(target, context),
+            target
+        );
+    }
+}
+
 /// Validate a file for staging
-fn validate_file(path: &Path) -> Result<()> {
+fn validate_file(root: &Path, path: &Path) -> Result<()> {
+    let location = root.join(path);
+
     // Check file exists
-    if !path.exists() {
+    if !location.exists() {
         return Err(JinError::NotFound(path.display().to_string()));
     }
 
     // Check not a directory (should have been expanded)
-    if path.is_dir() {
+    if location.is_dir() {
         return Err(JinError::Other(format!(
             "{} is a directory, not a file",
             path.display()
         )));
     }
 
+    // Layers store names as UTF-8 text, one per line in some files
+    if let Some(reason) = fsnames::unstorable(path) {
+        return Err(JinError::StagingFailed {
+            path: path.display().to_string(),
+            reason,
+        });
+    }
+
     // Check not a symlink
-    if is_symlink(path)? {
+    if is_symlink(&location)? {
         return Err(JinError::Symlink {
             path: path.display().to_string(),
         });
     }
 
     // Check not tracked by project's Git
-    if is_git_tracked(path)? {
+    if is_git_tracked(root, path)? {
         return Err(JinError::GitTracked {
             path: path.display().to_string(),
         });
     }
 
+    // Check against the configured size limit
+    check_file_size(&location, LimitsConfig::current().max_file_size())?;
+
+    // A home workspace only manages allowlisted paths
+    if let Some(home) = HomeConfig::current(root) {
+        home.check(path)?;
+    }
+
+    Ok(())
+}
+
+/// Refuse files larger than `limits.max-file-size` (`None` disables the check)
+fn check_file_size(path: &Path, limit: Option<u64>) -> Result<()> {
+    let Some(limit) = limit else {
+        return Ok(());
+    };
+    let size = std::fs::metadata(path)?.len();
+    if size > limit {
+        return Err(JinError::StagingFailed {
+            path: path.display().to_string(),
+            reason: format!(
+                "file is {}, above the {} limit (raise or disable with 'jin config set limits.max-file-size <size>')",
+                format_size(size),
+                format_size(limit)
+            ),
+        });
+    }
     Ok(())
 }
 
 /// Format layer name for display, including context (mode/scope names)
-fn format_layer_name_with_context(layer: Layer, context: &ProjectContext) -> String {
+pub(crate) fn format_layer_name_with_context(layer: Layer, context: &ProjectContext) -> String {
     match layer {
         Layer::GlobalBase => "global".to_string(),
         Layer::ModeBase => {
@@ -234,14 +856,14 @@ mod tests {
     #[test]
     fn test_validate_file_not_found() {
         let path = PathBuf::from("/nonexistent/file.txt");
-        let result = validate_file(&path);
+        let result = validate_file(Path::new("."), &path);
         assert!(matches!(result, Err(JinError::NotFound(_))));
     }
 
     #[test]
     fn test_validate_file_is_directory() {
         let temp = TempDir::new().unwrap();
-        let result = validate_file(temp.path());
+        let result = validate_file(Path::new("."), temp.path());
         assert!(result.is_err());
     }
 
@@ -252,7 +874,7 @@ mod tests {
         let file = ctx.project_path.join("test.json");
         std::fs::write(&file, b"{}").unwrap();
 
-        let result = validate_file(&file);
+        let result = validate_file(&ctx.project_path, &file);
         assert!(result.is_ok());
     }
 
@@ -266,21 +888,43 @@ mod tests {
         let link = temp.path().join("link.txt");
         symlink(&file, &link).unwrap();
 
-        let result = validate_file(&link);
+        let result = validate_file(Path::new("."), &link);
         assert!(matches!(result, Err(JinError::Symlink { .. })));
     }
 
+    #[test]
+    fn test_check_file_size() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("big.bin");
+        std::fs::write(&file, vec![0u8; 2048]).unwrap();
+
+        assert!(check_file_size(&file, None).is_ok());
+        assert!(check_file_size(&file, Some(4096)).is_ok());
+        match check_file_size(&file, Some(1024)) {
+            Err(JinError::StagingFailed { reason, .. }) => {
+                assert!(reason.contains("limits.max-file-size"))
+            }
+            other => panic!("expected StagingFailed, got {:?}", other),
+        }
+    }
+
     #[test]
     #[serial]
     fn test_stage_file_creates_blob() {
         let ctx = crate::test_utils::setup_unit_test();
-        let repo = JinRepo::open_or_create().unwrap();
+        let repo = JinRepo::open_or_create(&ctx.project_path).unwrap();
 
         let file = ctx.project_path.join("test.json");
         std::fs::write(&file, b"{\"key\": \"value\"}").unwrap();
 
         let mut staging = StagingIndex::new();
-        let result = stage_file(&file, Layer::ProjectBase, &repo, &mut staging);
+        let result = stage_file(
+            &ctx.project_path,
+            &file,
+            Layer::ProjectBase,
+            &repo,
+            &mut staging,
+        );
 
         assert!(result.is_ok());
         assert_eq!(staging.len(), 1);
@@ -311,6 +955,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_manifest_parse() {
+        let manifest: AddManifest = serde_yaml::from_str(
+            "files:\n  - path: a.json\n    mode: true\n  - path: b.toml\n    scope: language:rust\n",
+        )
+        .unwrap();
+        assert_eq!(manifest.files.len(), 2);
+        assert!(manifest.files[0].mode);
+        assert_eq!(manifest.files[1].scope.as_deref(), Some("language:rust"));
+
+        let unknown: std::result::Result<AddManifest, _> =
+            serde_yaml::from_str("files:\n  - path: a.json\n    mod: true\n");
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_manifest_stages_to_each_layer() {
+        let ctx = crate::test_utils::setup_unit_test();
+        std::fs::write(ctx.project_path.join("a.json"), b"{}").unwrap();
+        std::fs::write(ctx.project_path.join("b.json"), b"{}").unwrap();
+        let manifest = ctx.project_path.join("add.yaml");
+        std::fs::write(
+            &manifest,
+            "files:\n  - path: a.json\n    global: true\n  - path: b.json\n",
+        )
+        .unwrap();
+
+        execute_manifest(&ctx.project_path, &manifest, false).unwrap();
+
+        let staging = StagingIndex::load(&ctx.project_path).unwrap();
+        assert_eq!(
+            staging.get(Path::new("a.json")).unwrap().target_layer,
+            Layer::GlobalBase
+        );
+        assert_eq!(
+            staging.get(Path::new("b.json")).unwrap().target_layer,
+            Layer::ProjectBase
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_manifest_invalid_entry_stages_nothing() {
+        let ctx = crate::test_utils::setup_unit_test();
+        std::fs::write(ctx.project_path.join("a.json"), b"{}").unwrap();
+        let manifest = ctx.project_path.join("add.yaml");
+        std::fs::write(
+            &manifest,
+            "files:\n  - path: a.json\n  - path: missing.json\n  - path: a.json\n    mode: true\n",
+        )
+        .unwrap();
+
+        let context = ProjectContext::load(&ctx.project_path).unwrap();
+        let errors = plan_manifest(
+            &ctx.project_path,
+            &AddManifest::load(&manifest).unwrap(),
+            &context,
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("entry 2 (missing.json)"));
+        assert!(errors[1].contains("--mode flag requires an active mode"));
+
+        let index_path = ctx.jin_dir.join("staging").join("index.json");
+        let before = std::fs::read_to_string(&index_path).unwrap();
+        assert!(execute_manifest(&ctx.project_path, &manifest, false).is_err());
+        assert_eq!(std::fs::read_to_string(&index_path).unwrap(), before);
+    }
+
     #[test]
     fn test_execute_no_files() {
         let args = AddArgs {
@@ -320,8 +1034,11 @@ mod tests {
             project: false,
             global: false,
             local: false,
+            expires: None,
+            manifest: None,
+            create_missing: false,
         };
-        let result = execute(args);
+        let result = execute(args, Path::new("."));
         assert!(result.is_err());
     }
 
@@ -334,8 +1051,11 @@ mod tests {
             project: true,
             global: false,
             local: false,
+            expires: None,
+            manifest: None,
+            create_missing: false,
         };
-        let result = execute(args);
+        let result = execute(args, Path::new("."));
         assert!(result.is_err());
     }
 
@@ -348,8 +1068,11 @@ mod tests {
             project: false,
             global: true,
             local: false,
+            expires: None,
+            manifest: None,
+            create_missing: false,
         };
-        let result = execute(args);
+        let result = execute(args, Path::new("."));
         assert!(result.is_err());
     }
 }
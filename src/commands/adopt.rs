@@ -0,0 +1,280 @@
+//! Implementation of `jin adopt`
+//!
+//! Brings a plain workspace file under Jin management in one step: the
+//! target layer is inferred from the file (or taken from layer flags), the
+//! file is staged and committed to that layer, and the layers are applied
+//! so the workspace metadata records the file as Jin-managed.
+
+use crate::cli::{AddArgs, AdoptArgs, ApplyArgs, CommitArgs};
+use crate::core::{JinError, ProjectContext, Result};
+use crate::staging::{
+    is_git_tracked, route_to_layer, validate_routing_options, RoutingOptions, StagingIndex,
+    WorkspaceMetadata,
+};
+use std::path::{Path, PathBuf};
+
+/// Execute the adopt command
+///
+/// # Errors
+///
+/// Returns an error if Jin is not initialized, the path is not a regular
+/// file, it is already managed or tracked by Git, other changes are staged,
+/// or the file is not Jin-managed once the apply finishes (for example when
+/// the apply paused on a conflict).
+pub fn execute(args: AdoptArgs, root: &Path) -> Result<()> {
+    let context = ProjectContext::load(root)?;
+
+    let path = workspace_path(&args.path, root)?;
+    if !root.join(&path).is_file() {
+        return Err(JinError::NotFound(format!(
+            "{} is not a file in the workspace",
+            path.display()
+        )));
+    }
+    if is_git_tracked(root, &path)? {
+        return Err(JinError::GitTracked {
+            path: path.display().to_string(),
+        });
+    }
+    if let Ok(metadata) = WorkspaceMetadata::load(root) {
+        if metadata.files.contains_key(&path) {
+            return Err(JinError::AlreadyExists(format!(
+                "{} is already managed by Jin",
+                path.display()
+            )));
+        }
+    }
+
+    // The adopt commit must contain only this file
+    let staging = StagingIndex::load(root).unwrap_or_else(|_| StagingIndex::new());
+    if !staging.is_empty() {
+        return Err(JinError::Other(format!(
+            "{} file(s) already staged; commit or reset them before adopting",
+            staging.len()
+        )));
+    }
+
+    let (options, reason) = infer_options(&args, &path, &context);
+    validate_routing_options(&options)?;
+    let layer = route_to_layer(&options, &context)?;
+    let layer_name = super::add::format_layer_name_with_context(layer, &context);
+
+    println!(
+        "Adopting {} into {} layer ({})",
+        path.display(),
+        layer_name,
+        reason
+    );
+    if args.dry_run {
+        println!("\nDry run - nothing changed");
+        return Ok(());
+    }
+
+    let original = std::fs::read(root.join(&path))?;
+    let path_str = path.display().to_string();
+
+    super::add::execute(
+        AddArgs {
+            files: vec![path_str.clone()],
+            manifest: None,
+            mode: options.mode,
+            scope: options.scope.clone(),
+            project: options.project,
+            global: options.global,
+            local: options.local,
+            expires: None,
+            create_missing: false,
+        },
+        root,
+    )?;
+    super::commit_cmd::execute(
+        CommitArgs {
+            message: args
+                .message
+                .clone()
+                .unwrap_or_else(|| format!("Adopt {}", path_str)),
+            dry_run: false,
+            only: Vec::new(),
+        },
+        root,
+    )?;
+    super::apply::execute(
+        ApplyArgs {
+            force: false,
+            dry_run: false,
+            target: None,
+            ours: false,
+            theirs: false,
+            prefer: None,
+            provenance: false,
+            merge_opt: Vec::new(),
+            debounce: None,
+            no_reload: false,
+        },
+        root,
+    )?;
+
+    // Verify the apply recorded the file with its current content
+    let metadata = WorkspaceMetadata::load(root)?;
+    let current = std::fs::read(root.join(&path))?;
+    let hash = git2::Oid::hash_object(git2::ObjectType::Blob, &current)?.to_string();
+    match metadata.files.get(&path) {
+        Some(recorded) if *recorded == hash => {}
+        _ => {
+            return Err(JinError::Other(format!(
+                "{} was committed to the {} layer but is not applied yet; \
+                 resolve any conflicts and run 'jin apply'",
+                path_str, layer_name
+            )));
+        }
+    }
+
+    println!();
+    println!("✓ {} is now managed by Jin ({})", path_str, layer_name);
+    if current != original {
+        println!("  Note: merged content differs from the original file (see 'jin blame')");
+    }
+    Ok(())
+}
+
+/// Workspace-relative form of a path given on the command line
+fn workspace_path(arg: &str, root: &Path) -> Result<PathBuf> {
+    let path = Path::new(arg);
+    let path = if path.is_absolute() {
+        path.strip_prefix(root)
+            .map_err(|_| JinError::Other(format!("{} is outside the workspace", arg)))?
+            .to_path_buf()
+    } else {
+        path.to_path_buf()
+    };
+    Ok(PathBuf::from(
+        path.to_string_lossy().trim_start_matches("./"),
+    ))
+}
+
+/// Routing options for the adopted file, with the reason they were chosen
+///
+/// Explicit layer flags always win. Otherwise AI tool files go to the active
+/// mode, machine-specific `*.local` files to the user-local layer, and
+/// everything else to the project layer.
+fn infer_options(
+    args: &AdoptArgs,
+    path: &Path,
+    context: &ProjectContext,
+) -> (RoutingOptions, String) {
+    let explicit = RoutingOptions {
+        mode: args.mode,
+        scope: args.scope.clone(),
+        project: args.project,
+        global: args.global,
+        local: args.local,
+    };
+    if explicit.mode
+        || explicit.scope.is_some()
+        || explicit.project
+        || explicit.global
+        || explicit.local
+    {
+        return (explicit, "from flags".to_string());
+    }
+
+    if let Some(tool) = super::suggest::tool_mode(path) {
+        if let Some(mode) = &context.mode {
+            return (
+                RoutingOptions {
+                    mode: true,
+                    ..Default::default()
+                },
+                format!("{} file, active mode '{}'", tool, mode),
+            );
+        }
+    }
+
+    if is_machine_local(path) {
+        return (
+            RoutingOptions {
+                local: true,
+                ..Default::default()
+            },
+            "machine-specific file".to_string(),
+        );
+    }
+
+    (RoutingOptions::default(), "default routing".to_string())
+}
+
+/// Whether a file name marks machine-specific content (`.env.local`,
+/// `settings.local.json`)
+fn is_machine_local(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(".local") || name.contains(".local."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args() -> AdoptArgs {
+        AdoptArgs {
+            path: String::new(),
+            mode: false,
+            scope: None,
+            project: false,
+            global: false,
+            local: false,
+            message: None,
+            dry_run: false,
+        }
+    }
+
+    #[test]
+    fn test_infer_options() {
+        let no_mode = ProjectContext::default();
+        let with_mode = ProjectContext {
+            mode: Some("claude".to_string()),
+            ..Default::default()
+        };
+
+        let (options, _) = infer_options(&args(), Path::new("CLAUDE.md"), &with_mode);
+        assert!(options.mode);
+
+        // Without an active mode, tool files fall back to default routing
+        let (options, reason) = infer_options(&args(), Path::new("CLAUDE.md"), &no_mode);
+        assert!(!options.mode);
+        assert_eq!(reason, "default routing");
+
+        let (options, _) = infer_options(&args(), Path::new("config/.env.local"), &no_mode);
+        assert!(options.local);
+        let (options, _) = infer_options(&args(), Path::new("settings.local.json"), &no_mode);
+        assert!(options.local);
+        let (options, _) = infer_options(&args(), Path::new("locales.json"), &no_mode);
+        assert!(!options.local);
+
+        let explicit = AdoptArgs {
+            global: true,
+            ..args()
+        };
+        let (options, reason) = infer_options(&explicit, Path::new("CLAUDE.md"), &with_mode);
+        assert!(options.global && !options.mode);
+        assert_eq!(reason, "from flags");
+    }
+
+    #[test]
+    fn test_workspace_path() {
+        let root = Path::new("/work/project");
+        assert_eq!(
+            workspace_path("./config/app.json", root).unwrap(),
+            PathBuf::from("config/app.json")
+        );
+        assert_eq!(
+            workspace_path("app.json", root).unwrap(),
+            PathBuf::from("app.json")
+        );
+        assert_eq!(
+            workspace_path("/work/project/config/app.json", root).unwrap(),
+            PathBuf::from("config/app.json")
+        );
+        assert!(workspace_path("/work/other/app.json", root).is_err());
+    }
+}
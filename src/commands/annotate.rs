@@ -0,0 +1,30 @@
+//! Implementation of `jin annotate`
+//!
+//! Attaches an annotation to a layer commit (see [`crate::git::notes`]). As
+//! with locks, with a linked remote the annotations are fetched first and
+//! pushed right after the change, so teammates see it on their next fetch.
+
+use crate::cli::AnnotateArgs;
+use crate::core::{JinError, Result};
+use crate::git::{locks, notes, JinRepo};
+use std::path::Path;
+
+/// Execute the annotate command
+pub fn execute(args: AnnotateArgs, root: &Path) -> Result<()> {
+    let repo = JinRepo::open(root).map_err(|_| JinError::NotInitialized)?;
+    let commit = notes::resolve_commit(&repo, &args.commit)?;
+
+    let mut remote = locks::team_remote(&repo)?;
+    if let Some(remote) = remote.as_mut() {
+        notes::fetch(remote)?;
+    }
+
+    notes::annotate(&repo, commit, &args.message, &locks::current_owner(&repo))?;
+    match remote.as_mut() {
+        Some(remote) => notes::push(remote)?,
+        None => println!("No remote linked: the annotation is only visible on this machine"),
+    }
+
+    println!("Annotated commit {}", &commit.to_string()[..7]);
+    Ok(())
+}
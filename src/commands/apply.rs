@@ -1,17 +1,57 @@
 //! Implementation of `jin apply`
 //!
 //! Applies merged layers to workspace with dry-run and force modes.
+//!
+//! Files are written to their storage path in the workspace unless
+//! `.jintargets` maps them elsewhere or `--target` redirects every file
+//! under another directory (see [`crate::staging::targets`]). Paths matching
+//! the project's `apply.exclude` patterns are never written.
+//!
+//! Conflicts normally pause the apply for manual resolution. `--ours`,
+//! `--theirs` and `--prefer <layer>` settle them by policy instead, and each
+//! automatic resolution is written to the audit log.
+//!
+//! Each destination is checked again just before it is replaced: a file
+//! edited on disk after the apply was planned keeps the edit and pauses the
+//! apply as a drift conflict between the workspace and the merged layers.
+//!
+//! Every apply that writes (or tries to) is recorded in the audit log with
+//! the layer commits it merged, the files it wrote, how long it took and how
+//! it ended; `jin log --applies` lists them.
 
+use crate::audit::{ApplyOutcome, ApplyRecord, AuditEntry, AuditLogger};
 use crate::cli::ApplyArgs;
-use crate::core::{JinError, ProjectContext, Result};
+use crate::commit::CommitPipeline;
+use crate::core::config::expand_home;
+use crate::core::dangling;
+use crate::core::profile::{self, Phase};
+use crate::core::progress::{self, Event, Operation};
+use crate::core::queue::{self, Admission, WorkspaceQueue};
+use crate::core::usage::{self, UsageKind};
+use crate::core::{machine, workspaces};
+use crate::core::{
+    BranchScope, HomeConfig, JinError, Layer, LocalExpiry, NameRules, OfflineCache, ProjectConfig,
+    ProjectContext, Result,
+};
+use crate::git::refs::RefSnapshot;
 use crate::git::{JinRepo, ObjectOps, RefOps, TreeOps};
+use crate::merge::cache::merge_cached;
+use crate::merge::conflicts::ConflictFiles;
+use crate::merge::env::ENV_PATH;
 use crate::merge::jinmerge::JinMergeConflict;
-use crate::merge::{get_applicable_layers, merge_layers, FileFormat, LayerMergeConfig};
+use crate::merge::{
+    get_applicable_layers, merge_layers, FileFormat, LayerMergeConfig, MergeOptions, MergeValue,
+    MergedFile, Provenance, StrictMerges,
+};
+use crate::notify::{self, reload, EventKind, NotifyEvent};
+use crate::staging::attributes::{self, FileAttributes, JinAttributes, ATTRIBUTES_PATH};
+use crate::staging::targets::{TargetMap, TARGETS_PATH};
 use crate::staging::{ensure_in_managed_block, validate_workspace_attached, WorkspaceMetadata};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 /// State for a paused apply operation due to conflicts
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +66,9 @@ pub struct PausedApplyState {
     pub applied_files: Vec<PathBuf>,
     /// Number of conflicts total
     pub conflict_count: usize,
+    /// Commit OIDs of the merged layer refs, keyed by ref path
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub layer_commits: HashMap<String, String>,
 }
 
 /// Simplified layer config for serialization
@@ -42,9 +85,14 @@ pub struct PausedLayerConfig {
 }
 
 impl PausedApplyState {
+    /// Path of the saved state (`.jin/.paused_apply.yaml`)
+    pub fn default_path(root: &Path) -> PathBuf {
+        root.join(".jin").join(".paused_apply.yaml")
+    }
+
     /// Save state to `.jin/.paused_apply.yaml`
-    pub fn save(&self) -> Result<()> {
-        let path = PathBuf::from(".jin/.paused_apply.yaml");
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = Self::default_path(root);
         let content = serde_yaml::to_string(self)
             .map_err(|e| JinError::Other(format!("Failed to serialize paused state: {}", e)))?;
 
@@ -57,13 +105,13 @@ impl PausedApplyState {
     }
 
     /// Check if a paused operation exists
-    pub fn exists() -> bool {
-        PathBuf::from(".jin/.paused_apply.yaml").exists()
+    pub fn exists(root: &Path) -> bool {
+        Self::default_path(root).exists()
     }
 
     /// Load state from `.jin/.paused_apply.yaml`
-    pub fn load() -> Result<Self> {
-        let path = PathBuf::from(".jin/.paused_apply.yaml");
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = Self::default_path(root);
 
         if !path.exists() {
             return Err(JinError::Other(
@@ -78,6 +126,28 @@ impl PausedApplyState {
     }
 }
 
+/// Run a plain `jin apply`, for commands that switch context and apply
+/// (`jin mode use --apply`, `jin scope use --apply`)
+///
+/// Contexts used recently are usually served from the merge cache.
+pub(crate) fn apply_workspace(root: &Path) -> Result<()> {
+    execute(
+        ApplyArgs {
+            force: false,
+            dry_run: false,
+            target: None,
+            ours: false,
+            theirs: false,
+            prefer: None,
+            provenance: false,
+            merge_opt: Vec::new(),
+            debounce: None,
+            no_reload: false,
+        },
+        root,
+    )
+}
+
 /// Execute the apply command
 ///
 /// Applies merged layers to workspace.
@@ -85,6 +155,7 @@ impl PausedApplyState {
 /// # Arguments
 ///
 /// * `args` - Command line arguments including force and dry_run flags
+/// * `root` - Workspace root
 ///
 /// # Errors
 ///
@@ -93,9 +164,89 @@ impl PausedApplyState {
 /// - Workspace is dirty without --force
 /// - Merge conflicts are detected
 /// - Files cannot be written
-pub fn execute(args: ApplyArgs) -> Result<()> {
+pub fn execute(args: ApplyArgs, root: &Path) -> Result<()> {
+    // Previews write nothing, and nothing is queued before `jin init`
+    if args.dry_run || !ProjectContext::is_initialized(root) {
+        return apply(args, root).map(|_| ());
+    }
+
+    // One apply writes the workspace at a time (see `crate::core::queue`)
+    let key = queue_key(&args);
+    let command = match key.as_str() {
+        "" => "apply".to_string(),
+        key => format!("apply {}", key),
+    };
+    let debounce = Duration::from_millis(args.debounce.unwrap_or(0));
+    let admission =
+        WorkspaceQueue::open(root).enter(&command, &key, debounce, queue::announce_wait)?;
+    match admission {
+        Admission::Run(guard) => {
+            let started = Instant::now();
+            let result = apply(args, root);
+            log_apply(root, &result, started.elapsed());
+            result?;
+            guard.finish()
+        }
+        Admission::Coalesced(finished) => {
+            println!(
+                "Workspace already applied by a later jin apply (finished {})",
+                finished.with_timezone(&chrono::Local).format("%H:%M:%S")
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Arguments deciding what an apply writes, in a canonical order; applies
+/// with equal keys are interchangeable
+fn queue_key(args: &ApplyArgs) -> String {
+    let mut parts = Vec::new();
+    if args.force {
+        parts.push("--force".to_string());
+    }
+    if let Some(target) = &args.target {
+        parts.push(format!("--target {}", target));
+    }
+    if args.ours {
+        parts.push("--ours".to_string());
+    }
+    if args.theirs {
+        parts.push("--theirs".to_string());
+    }
+    if let Some(layer) = &args.prefer {
+        parts.push(format!("--prefer {}", layer));
+    }
+    if args.provenance {
+        parts.push("--provenance".to_string());
+    }
+    if args.no_reload {
+        parts.push("--no-reload".to_string());
+    }
+    let mut merge_opts = args.merge_opt.clone();
+    merge_opts.sort();
+    parts.extend(merge_opts.iter().map(|opt| format!("--merge-opt {}", opt)));
+    parts.join(" ")
+}
+
+/// What an apply did, for the audit log
+struct AppliedRun {
+    context: ProjectContext,
+    layer_commits: HashMap<String, String>,
+    /// Files written, sorted
+    files: Vec<String>,
+    /// Files left for `jin resolve`, sorted
+    conflicts: Vec<String>,
+}
+
+/// Apply the active context; `None` for a preview
+fn apply(args: ApplyArgs, root: &Path) -> Result<Option<AppliedRun>> {
+    let policy = ConflictPolicy::from_args(&args)?;
+    if !args.merge_opt.is_empty() {
+        MergeOptions::parse(&args.merge_opt)?.install()?;
+    }
+
     // 1. Load context
-    let context = match ProjectContext::load() {
+    let mut context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -104,7 +255,7 @@ pub fn execute(args: ApplyArgs) -> Result<()> {
     };
 
     // 2. Check workspace dirty (unless --force)
-    if !args.force && check_workspace_dirty()? {
+    if !args.force && check_workspace_dirty(root)? {
         return Err(JinError::Other(
             "Workspace has uncommitted changes. Use --force to override.".to_string(),
         ));
@@ -112,12 +263,22 @@ pub fn execute(args: ApplyArgs) -> Result<()> {
 
     // 2.5. Validate workspace state before destructive apply (only with --force)
     let repo = if args.force {
-        let r = JinRepo::open()?;
-        validate_workspace_attached(&context, &r)?;
+        let r = JinRepo::open(root)?;
+        validate_workspace_attached(root, &context, &r)?;
         r
     } else {
-        JinRepo::open()?
+        JinRepo::open(root)?
     };
+    // A deleted mode or scope would silently drop its layers from the merge
+    dangling::ensure_valid(&context, &repo)?;
+
+    // 2.6. The host branch's scope stands in for the active scope
+    if let Some(branch) = BranchScope::activate(root, &mut context, &repo) {
+        println!(
+            "Using scope '{}' for branch '{}'",
+            branch.scope, branch.branch
+        );
+    }
 
     // 3. Determine applicable layers
     let layers = get_applicable_layers(
@@ -132,11 +293,95 @@ pub fn execute(args: ApplyArgs) -> Result<()> {
         mode: context.mode.clone(),
         scope: context.scope.clone(),
         project: context.project.clone(),
+        root: root.to_path_buf(),
+    };
+    // Layer refs read by the merge, checked again before writing
+    let snapshot = RefSnapshot::take(&repo, layer_refs(&config));
+    Event::new(Operation::Apply, progress::Phase::Merge).emit();
+    // Merge options apply to this invocation only, so bypass the cache
+    let mut merged = if args.merge_opt.is_empty() {
+        merge_cached(&config, &repo)?.0
+    } else {
+        merge_layers(&config, &repo)?
     };
-    let merged = merge_layers(&config, &repo)?;
 
-    // 6. Check for conflicts and prepare paused state if needed
-    let has_conflicts = !merged.conflict_files.is_empty();
+    // Expired user-local overrides were left out of the merge
+    if config.layers.contains(&Layer::UserLocal) {
+        for (path, at) in LocalExpiry::load_or_default(repo.path()).expired(Utc::now()) {
+            progress::warn(format_args!(
+                "Local override {} expired {}; not applied",
+                path.display(),
+                at.format("%Y-%m-%d")
+            ));
+        }
+    }
+
+    // Environment layers are read by `jin env` and `jin run`, never written out
+    merged.merged_files.remove(Path::new(ENV_PATH));
+
+    // A home workspace never writes paths outside its allowlist, even if a
+    // layer carries them
+    if let Some(home) = HomeConfig::current(root) {
+        merged.merged_files.retain(|path, _| {
+            let allowed = path.as_path() == Path::new(TARGETS_PATH)
+                || path.as_path() == Path::new(ATTRIBUTES_PATH)
+                || home.allows(path);
+            if !allowed {
+                progress::warn(format_args!(
+                    "Skipping {} (not allowed in the home workspace)",
+                    path.display()
+                ));
+            }
+            allowed
+        });
+    }
+    // Paths this project opted out of are dropped before anything else, so
+    // they are neither written, tracked nor reported as conflicts
+    let excluded = exclude_paths(root, &mut merged)?;
+
+    let destinations = plan_destinations(&merged, args.target.as_deref(), root)?;
+    // Paths the workspace filesystem can't keep apart stop the apply before
+    // anything is written
+    check_path_names(&merged, &destinations, root)?;
+    // What each destination holds now, checked again before it is replaced
+    let observed = observe_destinations(root, &merged, &destinations)?;
+
+    // 5.5. Settle conflicts by policy, if one was given
+    let mut resolutions = Vec::new();
+    if let Some(policy) = policy {
+        for path in std::mem::take(&mut merged.conflict_files) {
+            let destination = destination(root, &destinations, &path);
+            let resolution = auto_resolve(&path, &destination, policy, &config, &repo)?;
+            merged.merged_files.insert(
+                path,
+                MergedFile {
+                    content: MergeValue::String(resolution.content.clone()),
+                    source_layers: resolution.layer.into_iter().collect(),
+                    format: FileFormat::Text,
+                    bom: false,
+                },
+            );
+            resolutions.push(resolution);
+        }
+    }
+    if !resolutions.is_empty() {
+        println!(
+            "Auto-resolved {} conflicts with {}:",
+            resolutions.len(),
+            policy.map(|p| p.flag()).unwrap_or_default()
+        );
+        for resolution in &resolutions {
+            println!(
+                "  - {} (kept {})",
+                resolution.path.display(),
+                resolution.source()
+            );
+        }
+    }
+
+    // 6. Check for conflicts and explain what each pair of layers disagrees on
+    let mut has_conflicts = !merged.conflict_files.is_empty();
+    let mut conflicts = Vec::new();
 
     if has_conflicts {
         println!(
@@ -144,7 +389,12 @@ pub fn execute(args: ApplyArgs) -> Result<()> {
             merged.conflict_files.len()
         );
         for path in &merged.conflict_files {
+            let conflict = build_conflict(root, path, &config)?;
             println!("  - {}", path.display());
+            for line in &conflict.summary {
+                println!("      {}", line);
+            }
+            conflicts.push(conflict);
         }
     }
 
@@ -154,27 +404,76 @@ pub fn execute(args: ApplyArgs) -> Result<()> {
             println!();
             println!("Use --force to apply non-conflicting files, or resolve conflicts first.");
         }
-        preview_changes(&merged)?;
-        return Ok(());
+        preview_changes(root, &merged, &destinations)?;
+        if !excluded.is_empty() {
+            println!("\nExcluded files (apply.exclude):");
+            for (path, pattern) in &excluded {
+                println!("  x {} ({})", path.display(), pattern);
+            }
+        }
+        return Ok(None);
     }
 
-    // 8. Apply to workspace (non-conflicting files only)
-    apply_to_workspace(&merged, &repo)?;
+    // 8. Apply to workspace (non-conflicting files only), unless a layer
+    // moved while the merge was planned
+    snapshot.ensure_unchanged(&repo, "jin apply")?;
+    let drifted = apply_to_workspace(root, &merged, &repo, &destinations, &observed)?;
+    if !drifted.is_empty() {
+        println!(
+            "{} files changed on disk during apply and were left as they are:",
+            drifted.len()
+        );
+        for conflict in drifted {
+            println!("  - {}", conflict.file_path.display());
+            merged.merged_files.remove(&conflict.file_path);
+            merged.conflict_files.push(conflict.file_path.clone());
+            conflicts.push(conflict);
+        }
+        has_conflicts = true;
+    }
+    // Read-only commands fall back on this when the repository is offline
+    if let Err(e) = OfflineCache::capture(
+        &repo,
+        &config.layers,
+        config.mode.as_deref(),
+        config.scope.as_deref(),
+        config.project.as_deref(),
+        merged.merged_files.keys().cloned(),
+    )
+    .save(root)
+    {
+        progress::warn(format_args!("Could not update the offline cache: {}", e));
+    }
+    if let Some(policy) = policy {
+        log_resolutions(root, &resolutions, policy, &context);
+    }
 
     // 9. Handle conflicts if any
     if has_conflicts {
         // Handle conflicts: generate .jinmerge files and save state
-        let paused_state = handle_conflicts(&merged.conflict_files, &config, &merged.merged_files)?;
+        let mut paused_state = handle_conflicts(root, &conflicts, &config, &merged.merged_files)?;
+        paused_state.layer_commits = layer_commits(&config, &repo);
+        let run = AppliedRun {
+            context,
+            layer_commits: paused_state.layer_commits.clone(),
+            files: sorted_paths(merged.merged_files.keys()),
+            conflicts: sorted_paths(merged.conflict_files.iter()),
+        };
 
         println!();
         println!("Created .jinmerge files for manual resolution:");
+        let conflict_files = ConflictFiles::load(root)?;
         for conflict_path in &merged.conflict_files {
-            let merge_path = JinMergeConflict::merge_path_for_file(conflict_path);
-            println!("  - {}", merge_path.display());
+            let merge_path = conflict_files.path_for(conflict_path);
+            if conflict_files.is_indexed(conflict_path) {
+                println!("  - {} ({})", merge_path.display(), conflict_path.display());
+            } else {
+                println!("  - {}", merge_path.display());
+            }
         }
 
         // Save paused state
-        paused_state.save()?;
+        paused_state.save(root)?;
 
         println!();
         println!("Operation paused. Resolve conflicts with:");
@@ -182,44 +481,418 @@ pub fn execute(args: ApplyArgs) -> Result<()> {
         println!();
         println!("For more information, run: jin status");
 
-        return Ok(());
+        return Ok(Some(run));
     }
 
     // 10. Update workspace metadata (only if no conflicts)
+    let previous = WorkspaceMetadata::load(root).ok();
     let mut metadata = WorkspaceMetadata::new();
     metadata.applied_layers = config.layers.iter().map(|l| l.to_string()).collect();
+    metadata.layer_commits = layer_commits(&config, &repo);
+    metadata.targets = destinations.clone();
+    let attributes = load_attributes(root, &merged)?;
     for (path, merged_file) in &merged.merged_files {
         // Get content hash by creating a blob
-        let content = serialize_merged_content(&merged_file.content, merged_file.format)?;
+        let content = applied_content(merged_file, &attributes.resolve(path))?;
         let oid = repo.create_blob(content.as_bytes())?;
         metadata.add_file(path.clone(), oid.to_string());
+        metadata.sources.insert(
+            path.clone(),
+            merged_file
+                .source_layers
+                .iter()
+                .map(|l| l.to_string())
+                .collect(),
+        );
+    }
+    metadata.save(root)?;
+    let mut changed: Vec<PathBuf> = metadata
+        .files
+        .iter()
+        .filter(|(path, hash)| {
+            previous
+                .as_ref()
+                .and_then(|previous| previous.files.get(*path))
+                != Some(*hash)
+        })
+        .map(|(path, _)| path.clone())
+        .chain(merged.removed_files.iter().cloned())
+        .collect();
+    changed.sort();
+
+    // Provenance for editor plugins; removed when not requested so it never
+    // describes an older apply
+    let provenance_path = Provenance::default_path(root);
+    let provenance = args.provenance
+        || ProjectConfig::load(root)
+            .ok()
+            .and_then(|project| project.apply)
+            .is_some_and(|apply| apply.provenance);
+    if provenance {
+        Provenance::build(&merged, &config, &metadata.layer_commits, &repo)?
+            .save(&provenance_path)?;
+    } else if provenance_path.exists() {
+        std::fs::remove_file(&provenance_path)?;
     }
-    metadata.save()?;
 
-    // 11. Update .gitignore managed block
+    // Applying counts as using the active mode and scope
+    usage::record(repo.path(), |log| {
+        let now = Utc::now();
+        if let Some(mode) = &context.mode {
+            log.mark_used(UsageKind::Mode, mode, now);
+        }
+        if let Some(scope) = &context.scope {
+            log.mark_used(UsageKind::Scope, scope, now);
+        }
+    });
+
+    workspaces::record(repo.path(), root, |registry, root| {
+        registry.mark_applied(root, context.project.as_deref(), Utc::now())
+    });
+
+    // 11. Update .gitignore managed block (files applied elsewhere aren't
+    // in the workspace)
     for path in merged.merged_files.keys() {
-        if let Err(e) = ensure_in_managed_block(path) {
-            eprintln!("Warning: Could not update .gitignore: {}", e);
+        if destinations.contains_key(path) {
+            continue;
+        }
+        if let Err(e) = ensure_in_managed_block(root, path) {
+            progress::warn(format_args!("Could not update .gitignore: {}", e));
         }
     }
 
-    // 12. Report results
+    // 12. Notify external automation
+    let files = sorted_paths(merged.merged_files.keys());
+    notify::notify(&NotifyEvent::new(
+        EventKind::Apply,
+        context.project.clone(),
+        context.mode.clone(),
+        context.scope.clone(),
+        files.clone(),
+    ));
+
+    // 13. Let applications reload changed files
+    if !args.no_reload {
+        reload::run(root, &changed);
+    }
+
+    // 14. Report results
+    Event::new(Operation::Apply, progress::Phase::Done).emit();
     println!("Applied {} files to workspace", merged.merged_files.len());
+    if !destinations.is_empty() {
+        println!("  Outside workspace: {}", destinations.len());
+    }
     if !merged.added_files.is_empty() {
         println!("  Added: {}", merged.added_files.len());
     }
     if !merged.removed_files.is_empty() {
         println!("  Removed: {}", merged.removed_files.len());
     }
+    if !excluded.is_empty() {
+        println!("  Excluded: {} (apply.exclude)", excluded.len());
+    }
 
-    Ok(())
+    Ok(Some(AppliedRun {
+        context,
+        layer_commits: metadata.layer_commits,
+        files,
+        conflicts: Vec::new(),
+    }))
+}
+
+fn sorted_paths<'a>(paths: impl Iterator<Item = &'a PathBuf>) -> Vec<String> {
+    let mut paths: Vec<String> = paths.map(|p| p.display().to_string()).collect();
+    paths.sort();
+    paths
+}
+
+/// Record an apply in the audit log
+///
+/// Previews aren't recorded. Like [`log_resolutions`], a failure to log is
+/// only a warning.
+fn log_apply(root: &Path, result: &Result<Option<AppliedRun>>, elapsed: Duration) {
+    let duration_ms = elapsed.as_millis() as u64;
+    let (context, files, record) = match result {
+        Ok(None) => return,
+        Ok(Some(run)) => (
+            run.context.clone(),
+            run.files.clone(),
+            ApplyRecord {
+                outcome: if run.conflicts.is_empty() {
+                    ApplyOutcome::Applied
+                } else {
+                    ApplyOutcome::Paused
+                },
+                layers: run
+                    .layer_commits
+                    .iter()
+                    .map(|(r, c)| (r.clone(), c.clone()))
+                    .collect::<BTreeMap<_, _>>(),
+                conflicts: run.conflicts.clone(),
+                duration_ms,
+                error: None,
+            },
+        ),
+        Err(e) => (
+            ProjectContext::load(root).unwrap_or_default(),
+            Vec::new(),
+            ApplyRecord {
+                outcome: ApplyOutcome::Failed,
+                layers: BTreeMap::new(),
+                conflicts: Vec::new(),
+                duration_ms,
+                error: Some(e.to_string()),
+            },
+        ),
+    };
+    let result = AuditLogger::from_project(root).and_then(|logger| {
+        logger
+            .with_machine(machine::current())
+            .log_entry(&AuditEntry::from_apply(
+                CommitPipeline::get_git_user(root),
+                context.project,
+                context.mode,
+                context.scope,
+                files,
+                record,
+            ))
+    });
+    if let Err(e) = result {
+        progress::warn(format_args!(
+            "Could not record the apply in the audit log: {}",
+            e
+        ));
+    }
 }
 
-/// Handle merge conflicts by generating .jinmerge files and creating paused state
+/// Drop merged and conflicting paths matching the project's
+/// `apply.exclude` patterns
+///
+/// Returns each excluded path with the pattern that excluded it, sorted.
+fn exclude_paths(
+    root: &Path,
+    merged: &mut crate::merge::LayerMergeResult,
+) -> Result<Vec<(PathBuf, String)>> {
+    let Some(apply) = ProjectConfig::load(root)?.apply else {
+        return Ok(Vec::new());
+    };
+
+    let mut excluded: Vec<(PathBuf, String)> = merged
+        .merged_files
+        .keys()
+        .chain(&merged.conflict_files)
+        .filter_map(|path| Some((path.clone(), apply.excluded_by(path)?.to_string())))
+        .collect();
+    excluded.sort();
+
+    for (path, _) in &excluded {
+        merged.merged_files.remove(path);
+    }
+    merged
+        .conflict_files
+        .retain(|path| apply.excluded_by(path).is_none());
+    Ok(excluded)
+}
+
+/// How `--ours`, `--theirs` and `--prefer` settle conflicts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConflictPolicy {
+    /// Keep the file currently in the workspace
+    Ours,
+    /// Take the highest-precedence layer's version
+    Theirs,
+    /// Take this layer's version, or the highest layer's if it lacks the file
+    Prefer(Layer),
+}
+
+impl ConflictPolicy {
+    fn from_args(args: &ApplyArgs) -> Result<Option<Self>> {
+        if let Some(name) = &args.prefer {
+            return Ok(Some(Self::Prefer(parse_layer_name(name)?)));
+        }
+        Ok(if args.ours {
+            Some(Self::Ours)
+        } else if args.theirs {
+            Some(Self::Theirs)
+        } else {
+            None
+        })
+    }
+
+    /// The flag that selected this policy
+    fn flag(&self) -> String {
+        match self {
+            Self::Ours => "--ours".to_string(),
+            Self::Theirs => "--theirs".to_string(),
+            Self::Prefer(layer) => format!("--prefer {}", layer),
+        }
+    }
+}
+
+/// A conflict settled by policy
+#[derive(Debug)]
+struct AutoResolution {
+    path: PathBuf,
+    /// Layer whose version was kept (`None` for the workspace file)
+    layer: Option<Layer>,
+    content: String,
+}
+
+impl AutoResolution {
+    fn source(&self) -> String {
+        self.layer
+            .map(|l| l.to_string())
+            .unwrap_or_else(|| "workspace".to_string())
+    }
+}
+
+/// Settle one conflicted file by policy
+///
+/// `--ours` keeps the file at `destination`; when there is none, it falls
+/// back to the highest layer, as does `--prefer` for a layer that lacks the
+/// file.
+fn auto_resolve(
+    path: &Path,
+    destination: &Path,
+    policy: ConflictPolicy,
+    config: &LayerMergeConfig,
+    repo: &JinRepo,
+) -> Result<AutoResolution> {
+    if policy == ConflictPolicy::Ours {
+        match std::fs::read_to_string(destination) {
+            Ok(content) => {
+                return Ok(AutoResolution {
+                    path: path.to_path_buf(),
+                    layer: None,
+                    content,
+                })
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let versions = layer_versions(path, config, repo)?;
+    let preferred = match policy {
+        ConflictPolicy::Prefer(layer) => versions.iter().find(|(l, _)| *l == layer),
+        _ => None,
+    };
+    let (layer, content) = preferred
+        .or(versions.first())
+        .ok_or_else(|| JinError::Other(format!("No layer contains {}", path.display())))?;
+    Ok(AutoResolution {
+        path: path.to_path_buf(),
+        layer: Some(*layer),
+        content: content.clone(),
+    })
+}
+
+/// Every applicable layer's version of a file, highest precedence first
+fn layer_versions(
+    path: &Path,
+    config: &LayerMergeConfig,
+    repo: &JinRepo,
+) -> Result<Vec<(Layer, String)>> {
+    let mut versions = Vec::new();
+    for layer in config.layers.iter().rev() {
+        let ref_path = layer.ref_path(
+            config.mode.as_deref(),
+            config.scope.as_deref(),
+            config.project.as_deref(),
+        );
+        let Ok(commit_oid) = repo.resolve_ref(&ref_path) else {
+            continue;
+        };
+        let tree_oid = repo.inner().find_commit(commit_oid)?.tree_id();
+        if let Ok(content) = repo.read_file_from_tree(tree_oid, path) {
+            versions.push((*layer, String::from_utf8_lossy(&content).to_string()));
+        }
+    }
+    Ok(versions)
+}
+
+/// Record each automatic resolution in the audit log
+///
+/// The files are already written, so a failure to log is only a warning.
+fn log_resolutions(
+    root: &Path,
+    resolutions: &[AutoResolution],
+    policy: ConflictPolicy,
+    context: &ProjectContext,
+) {
+    if resolutions.is_empty() {
+        return;
+    }
+    let result = AuditLogger::from_project(root).and_then(|logger| {
+        let logger = logger.with_machine(machine::current());
+        let user = CommitPipeline::get_git_user(root);
+        for resolution in resolutions {
+            logger.log_entry(&AuditEntry::from_resolution(
+                user.clone(),
+                context.project.clone(),
+                context.mode.clone(),
+                context.scope.clone(),
+                resolution.layer.map(|l| l.precedence()),
+                resolution.path.display().to_string(),
+                format!("{} kept {}", policy.flag(), resolution.source()),
+            ))?;
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        progress::warn(format_args!(
+            "Could not record resolutions in the audit log: {}",
+            e
+        ));
+    }
+}
+
+/// Parse layer name from string
+fn parse_layer_name(name: &str) -> Result<Layer> {
+    match name {
+        "global-base" => Ok(Layer::GlobalBase),
+        "mode-base" => Ok(Layer::ModeBase),
+        "mode-scope" => Ok(Layer::ModeScope),
+        "mode-scope-project" => Ok(Layer::ModeScopeProject),
+        "mode-project" => Ok(Layer::ModeProject),
+        "scope-base" => Ok(Layer::ScopeBase),
+        "project-base" => Ok(Layer::ProjectBase),
+        "user-local" => Ok(Layer::UserLocal),
+        "workspace-active" => Ok(Layer::WorkspaceActive),
+        _ => Err(JinError::Other(format!(
+            "Unknown layer: {}. Valid layers: global-base, mode-base, mode-scope, \
+             mode-scope-project, mode-project, scope-base, project-base, user-local, workspace-active",
+            name
+        ))),
+    }
+}
+
+/// Build the `.jinmerge` representation of a conflicted file
+///
+/// Reads the two conflicting layer versions; the resulting conflict carries
+/// a summary of the keys or lines the layers disagree on.
+fn build_conflict(
+    root: &Path,
+    conflict_path: &Path,
+    config: &LayerMergeConfig,
+) -> Result<JinMergeConflict> {
+    let (layer1_ref, layer1_content, layer2_ref, layer2_content) =
+        get_conflicting_layer_contents(root, conflict_path, config)?;
+
+    Ok(JinMergeConflict::from_text_merge(
+        conflict_path.to_path_buf(),
+        layer1_ref,
+        layer1_content,
+        layer2_ref,
+        layer2_content,
+    ))
+}
+
+/// Handle merge conflicts by writing .jinmerge files and creating paused state
 ///
 /// # Arguments
 ///
-/// * `conflict_files` - List of files that have conflicts
+/// * `conflicts` - Conflicts to write, one per conflicted file
 /// * `config` - Layer merge configuration
 /// * `merged_files` - Successfully merged files (for tracking in state)
 ///
@@ -227,30 +900,22 @@ pub fn execute(args: ApplyArgs) -> Result<()> {
 ///
 /// PausedApplyState with conflict information
 fn handle_conflicts(
-    conflict_files: &[PathBuf],
+    root: &Path,
+    conflicts: &[JinMergeConflict],
     config: &LayerMergeConfig,
     merged_files: &HashMap<PathBuf, crate::merge::MergedFile>,
 ) -> Result<PausedApplyState> {
     // Collect successfully applied files
     let applied_files: Vec<PathBuf> = merged_files.keys().cloned().collect();
 
-    for conflict_path in conflict_files {
-        // Get the two conflicting layer contents
-        let (layer1_ref, layer1_content, layer2_ref, layer2_content) =
-            get_conflicting_layer_contents(conflict_path, config)?;
-
-        // Create .jinmerge file
-        let merge_conflict = JinMergeConflict::from_text_merge(
-            conflict_path.clone(),
-            layer1_ref,
-            layer1_content,
-            layer2_ref,
-            layer2_content,
-        );
-
-        let merge_path = JinMergeConflict::merge_path_for_file(conflict_path);
-        merge_conflict.write_to_file(&merge_path)?;
+    let mut conflict_files = ConflictFiles::load(root)?;
+    for merge_conflict in conflicts {
+        let merge_path = conflict_files.place(&merge_conflict.file_path);
+        merge_conflict.write_to_file(&root.join(merge_path))?;
     }
+    conflict_files.save()?;
+
+    let conflict_files: Vec<PathBuf> = conflicts.iter().map(|c| c.file_path.clone()).collect();
 
     // Create paused state
     Ok(PausedApplyState {
@@ -261,12 +926,48 @@ fn handle_conflicts(
             scope: config.scope.clone(),
             project: config.project.clone(),
         },
-        conflict_files: conflict_files.to_vec(),
-        applied_files,
         conflict_count: conflict_files.len(),
+        conflict_files,
+        applied_files,
+        layer_commits: HashMap::new(),
     })
 }
 
+/// Ref path of every layer in the merge
+fn layer_refs(config: &LayerMergeConfig) -> Vec<String> {
+    config
+        .layers
+        .iter()
+        .map(|layer| {
+            layer.ref_path(
+                config.mode.as_deref(),
+                config.scope.as_deref(),
+                config.project.as_deref(),
+            )
+        })
+        .collect()
+}
+
+/// Record the commit each merged layer ref points at
+///
+/// Stored in workspace metadata so `jin add`/`jin commit` can tell when a
+/// layer has moved since the workspace was generated.
+fn layer_commits(config: &LayerMergeConfig, repo: &JinRepo) -> HashMap<String, String> {
+    config
+        .layers
+        .iter()
+        .filter_map(|layer| {
+            let ref_path = layer.ref_path(
+                config.mode.as_deref(),
+                config.scope.as_deref(),
+                config.project.as_deref(),
+            );
+            let oid = repo.inner().refname_to_id(&ref_path).ok()?;
+            Some((ref_path, oid.to_string()))
+        })
+        .collect()
+}
+
 /// Get content from the two conflicting layers for a file
 ///
 /// Iterates layers in REVERSE (highest precedence first) to find the first
@@ -277,14 +978,22 @@ fn handle_conflicts(
 /// (layer1_ref, layer1_content, layer2_ref, layer2_content)
 /// where layer1 is lower precedence (ours) and layer2 is higher (theirs)
 fn get_conflicting_layer_contents(
+    root: &Path,
     file_path: &Path,
     config: &LayerMergeConfig,
 ) -> Result<(String, String, String, String)> {
-    let repo = JinRepo::open()?;
+    let repo = JinRepo::open(root)?;
     let mut layer_refs = Vec::new();
+    // A strict merge conflicts between the layers its rule covers
+    let strict = StrictMerges::current().find(file_path);
 
     // Iterate layers in REVERSE (highest precedence first)
-    for layer in config.layers.iter().rev() {
+    for layer in config
+        .layers
+        .iter()
+        .rev()
+        .filter(|layer| strict.is_none_or(|rule| rule.covers(**layer)))
+    {
         let ref_path = layer.ref_path(
             config.mode.as_deref(),
             config.scope.as_deref(),
@@ -332,15 +1041,126 @@ fn get_conflicting_layer_contents(
     ))
 }
 
+/// Fail when merged paths would collide or be rejected on the workspace
+/// filesystem (see [`crate::core::fsnames`])
+fn check_path_names(
+    merged: &crate::merge::LayerMergeResult,
+    destinations: &HashMap<PathBuf, PathBuf>,
+    root: &Path,
+) -> Result<()> {
+    let paths = merged
+        .merged_files
+        .keys()
+        .chain(&merged.conflict_files)
+        .map(|path| destinations.get(path).unwrap_or(path).as_path());
+    let problems = NameRules::detect(root).check(paths);
+    if problems.is_empty() {
+        return Ok(());
+    }
+    let mut report = format!(
+        "Cannot apply: {} path problem(s) on this filesystem would make files overwrite each other or fail to write:",
+        problems.len()
+    );
+    for problem in &problems {
+        report.push_str(&format!("\n  - {}", problem));
+    }
+    report.push_str("\nRename the files in their layers (see 'jin mv') so every path is valid and unique ignoring case");
+    Err(JinError::Other(report))
+}
+
+/// Decide where each merged file is written
+///
+/// Returns the files (merged or conflicting) that go somewhere other than
+/// their storage path in the workspace. `--target` redirects every file; otherwise `.jintargets` (the
+/// merged version if a layer provides one) maps individual files.
+fn plan_destinations(
+    merged: &crate::merge::LayerMergeResult,
+    target: Option<&str>,
+    root: &Path,
+) -> Result<HashMap<PathBuf, PathBuf>> {
+    if let Some(target) = target {
+        let dir = expand_home(target);
+        // Relative targets are relative to the workspace root
+        let dir = root.join(dir);
+        return Ok(merged
+            .merged_files
+            .keys()
+            .chain(&merged.conflict_files)
+            .map(|path| (path.clone(), dir.join(path)))
+            .collect());
+    }
+
+    let targets =
+        TargetMap::load_with_overrides(root, merged_rules(merged, TARGETS_PATH)?.as_deref())?;
+    if targets.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    Ok(merged
+        .merged_files
+        .keys()
+        .chain(&merged.conflict_files)
+        .filter_map(|path| Some((path.clone(), targets.destination(path)?)))
+        .collect())
+}
+
+/// Where a merged file is written: its planned destination, or its storage
+/// path in the workspace
+fn destination(root: &Path, destinations: &HashMap<PathBuf, PathBuf>, path: &Path) -> PathBuf {
+    root.join(destinations.get(path).map_or(path, PathBuf::as_path))
+}
+
+/// Content of a rules file (`.jintargets`, `.jinattributes`) when a layer
+/// carries one
+fn merged_rules(merged: &crate::merge::LayerMergeResult, path: &str) -> Result<Option<String>> {
+    merged
+        .merged_files
+        .get(Path::new(path))
+        .map(|file| serialize_merged_content(&file.content, file.format))
+        .transpose()
+}
+
 /// Apply merged files to workspace
-fn apply_to_workspace(merged: &crate::merge::LayerMergeResult, _repo: &JinRepo) -> Result<()> {
+fn apply_to_workspace(
+    root: &Path,
+    merged: &crate::merge::LayerMergeResult,
+    _repo: &JinRepo,
+    destinations: &HashMap<PathBuf, PathBuf>,
+    observed: &HashMap<PathBuf, Option<DiskState>>,
+) -> Result<Vec<JinMergeConflict>> {
     let mut applied_count = 0;
     let mut errors = Vec::new();
+    let mut drifted = Vec::new();
+
+    let attributes = load_attributes(root, merged)?;
+    if !attributes::platform_supports(&attributes) {
+        progress::warn(
+            ".jinattributes mode and ownership rules cannot be fully enforced on this platform",
+        );
+    }
 
     // Process each merged file
-    for (path, merged_file) in &merged.merged_files {
-        match apply_file(path, merged_file) {
-            Ok(_) => applied_count += 1,
+    let total = merged.merged_files.len();
+    for (i, (path, merged_file)) in merged.merged_files.iter().enumerate() {
+        Event::new(Operation::Apply, progress::Phase::Write)
+            .count(i, total)
+            .path(path)
+            .emit();
+        let destination = destination(root, destinations, path);
+        let file_attributes = attributes.resolve(path);
+        match apply_file(
+            &destination,
+            merged_file,
+            &file_attributes,
+            observed.get(&destination),
+        ) {
+            Ok(true) => applied_count += 1,
+            Ok(false) => drifted.push(drift_conflict(
+                path,
+                &destination,
+                merged_file,
+                &file_attributes,
+            )?),
             Err(e) => errors.push(format!("{}: {}", path.display(), e)),
         }
     }
@@ -348,20 +1168,31 @@ fn apply_to_workspace(merged: &crate::merge::LayerMergeResult, _repo: &JinRepo)
     // Report errors
     if !errors.is_empty() {
         for error in &errors {
-            eprintln!("Error: {}", error);
+            progress::error(error);
         }
-        if applied_count == 0 {
+        if applied_count == 0 && drifted.is_empty() {
             return Err(JinError::Other("Failed to apply any files".to_string()));
         }
     }
 
-    Ok(())
+    Ok(drifted)
 }
 
 /// Apply a single file to workspace with atomic write
-fn apply_file(path: &Path, merged_file: &crate::merge::MergedFile) -> Result<()> {
-    // Serialize content based on format
-    let content = serialize_merged_content(&merged_file.content, merged_file.format)?;
+///
+/// Mode bits from `.jinattributes` are set on the temp file before the
+/// rename, so restricted files are never visible with default permissions.
+/// Returns `false`, leaving the file alone, if it no longer matches the
+/// state `observed` when the apply was planned.
+fn apply_file(
+    path: &Path,
+    merged_file: &crate::merge::MergedFile,
+    file_attributes: &FileAttributes,
+    observed: Option<&Option<DiskState>>,
+) -> Result<bool> {
+    let content = applied_content(merged_file, file_attributes)?;
+
+    let _span = profile::span(Phase::Io);
 
     // Ensure parent directory exists
     if let Some(parent) = path.parent() {
@@ -371,21 +1202,133 @@ fn apply_file(path: &Path, merged_file: &crate::merge::MergedFile) -> Result<()>
     // Atomic write pattern: write to temp file, then rename
     let temp_path = path.with_extension("jin-tmp");
     std::fs::write(&temp_path, &content)?;
+    if let Err(e) = attributes::apply_mode(&temp_path, file_attributes) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    // Last look before replacing the file, so edits made since planning
+    // are never clobbered
+    if let Some(observed) = observed {
+        if !DiskState::unchanged(observed, path)? {
+            let _ = std::fs::remove_file(&temp_path);
+            return Ok(false);
+        }
+    }
 
     // Atomic rename
     std::fs::rename(&temp_path, path)?;
 
-    // Set file mode (Unix only)
-    #[cfg(unix)]
-    {
-        // File mode is determined by content, not stored in merge
-        // Default to regular file mode
-        use std::os::unix::fs::PermissionsExt;
-        let perms = std::fs::Permissions::from_mode(0o100644);
-        std::fs::set_permissions(path, perms)?;
+    if let Err(e) = attributes::apply_ownership(path, file_attributes) {
+        progress::warn(e);
     }
 
-    Ok(())
+    Ok(true)
+}
+
+/// Size, modification time and blob hash of a workspace file
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DiskState {
+    len: u64,
+    modified: Option<std::time::SystemTime>,
+    hash: String,
+}
+
+impl DiskState {
+    /// State of `path`, `None` if it doesn't exist
+    fn read(path: &Path) -> Result<Option<Self>> {
+        let metadata = match std::fs::symlink_metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let content = std::fs::read(path)?;
+        Ok(Some(Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+            hash: git2::Oid::hash_object(git2::ObjectType::Blob, &content)?.to_string(),
+        }))
+    }
+
+    /// Whether `path` still holds what was observed
+    ///
+    /// Equal size and modification time are trusted; otherwise the content
+    /// is hashed, so a file touched but not edited still counts as unchanged.
+    fn unchanged(observed: &Option<Self>, path: &Path) -> Result<bool> {
+        let metadata = match std::fs::symlink_metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(observed.is_none()),
+            Err(e) => return Err(e.into()),
+        };
+        let Some(observed) = observed else {
+            return Ok(false);
+        };
+        if metadata.len() == observed.len && metadata.modified().ok() == observed.modified {
+            return Ok(true);
+        }
+        Ok(Self::read(path)?.is_some_and(|current| current.hash == observed.hash))
+    }
+}
+
+/// State of every destination the apply will write, keyed by destination
+fn observe_destinations(
+    root: &Path,
+    merged: &crate::merge::LayerMergeResult,
+    destinations: &HashMap<PathBuf, PathBuf>,
+) -> Result<HashMap<PathBuf, Option<DiskState>>> {
+    merged
+        .merged_files
+        .keys()
+        .map(|path| {
+            let destination = destination(root, destinations, path);
+            let state = DiskState::read(&destination)?;
+            Ok((destination, state))
+        })
+        .collect()
+}
+
+/// Conflict between a file edited during the apply and what the apply
+/// would have written, for resolution like any other conflict
+fn drift_conflict(
+    path: &Path,
+    destination: &Path,
+    merged_file: &crate::merge::MergedFile,
+    file_attributes: &FileAttributes,
+) -> Result<JinMergeConflict> {
+    let workspace = match std::fs::read(destination) {
+        Ok(content) => String::from_utf8_lossy(&content).into_owned(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(JinMergeConflict::from_text_merge(
+        path.to_path_buf(),
+        "workspace".to_string(),
+        workspace,
+        "layers".to_string(),
+        applied_content(merged_file, file_attributes)?,
+    ))
+}
+
+/// `.jinattributes` rules for this apply (see [`merged_rules`])
+pub(crate) fn load_attributes(
+    root: &Path,
+    merged: &crate::merge::LayerMergeResult,
+) -> Result<JinAttributes> {
+    JinAttributes::load_with_overrides(root, merged_rules(merged, ATTRIBUTES_PATH)?.as_deref())
+}
+
+/// File content as written to the workspace: serialized (with its byte
+/// order mark, if any), with the line endings of the path's `eol` attribute
+/// or the global policy
+pub(crate) fn applied_content(
+    merged_file: &crate::merge::MergedFile,
+    file_attributes: &FileAttributes,
+) -> Result<String> {
+    let content = merged_file.serialize()?;
+    let eol = file_attributes
+        .eol
+        .unwrap_or(crate::merge::EolConfig::current().policy);
+    Ok(eol.apply(&content).into_owned())
 }
 
 /// Serialize merged content based on file format
@@ -393,50 +1336,33 @@ fn serialize_merged_content(
     content: &crate::merge::MergeValue,
     format: FileFormat,
 ) -> Result<String> {
-    match format {
-        FileFormat::Json => content.to_json_string(),
-        FileFormat::Yaml => content.to_yaml_string(),
-        FileFormat::Toml => content.to_toml_string(),
-        FileFormat::Ini => content.to_ini_string(),
-        FileFormat::Text => {
-            // For text files, MergeValue should be a String variant
-            if let Some(text) = content.as_str() {
-                Ok(text.to_string())
-            } else {
-                Err(JinError::Other(
-                    "Text file has non-string content".to_string(),
-                ))
-            }
-        }
-    }
+    crate::merge::serialize_content(content, format)
 }
 
 /// Preview changes that would be applied
-fn preview_changes(merged: &crate::merge::LayerMergeResult) -> Result<()> {
-    eprintln!(
-        "[DEBUG] preview_changes: merged_files.len() = {}",
-        merged.merged_files.len()
-    );
+fn preview_changes(
+    root: &Path,
+    merged: &crate::merge::LayerMergeResult,
+    destinations: &HashMap<PathBuf, PathBuf>,
+) -> Result<()> {
     println!("Would apply {} files:", merged.merged_files.len());
 
     // Show added files (files in merged result but not in workspace)
     let mut added = Vec::new();
     let mut modified = Vec::new();
+    let attributes = load_attributes(root, merged)?;
 
     for (path, merged_file) in &merged.merged_files {
-        eprintln!("[DEBUG] preview_changes: Checking path: {}", path.display());
-        eprintln!("[DEBUG] preview_changes: path.exists() = {}", path.exists());
-        if path.exists() {
+        let file_attributes = attributes.resolve(path);
+        // Files applied outside the workspace are shown at their destination
+        let path = destinations.get(path).unwrap_or(path);
+        let location = root.join(path);
+        if location.exists() {
             // File exists, check if it would be modified
-            let workspace_content = std::fs::read_to_string(path)?;
-            let merged_content =
-                serialize_merged_content(&merged_file.content, merged_file.format)?;
+            let workspace_content = std::fs::read_to_string(&location)?;
+            let merged_content = applied_content(merged_file, &file_attributes)?;
 
             let content_differs = workspace_content != merged_content;
-            eprintln!(
-                "[DEBUG] preview_changes: File content differs: {}",
-                content_differs
-            );
 
             if content_differs {
                 modified.push(path);
@@ -469,20 +1395,15 @@ fn preview_changes(merged: &crate::merge::LayerMergeResult) -> Result<()> {
         }
     }
 
-    eprintln!(
-        "[DEBUG] preview_changes: Added: {}, Modified: {}",
-        added.len(),
-        modified.len()
-    );
     Ok(())
 }
 
 /// Check if workspace has uncommitted changes
-fn check_workspace_dirty() -> Result<bool> {
+fn check_workspace_dirty(root: &Path) -> Result<bool> {
     // Check if workspace has uncommitted changes by comparing
     // current workspace files to last applied configuration
 
-    let metadata = match WorkspaceMetadata::load() {
+    let metadata = match WorkspaceMetadata::load(root) {
         Ok(m) => m,
         Err(_) => return Ok(false), // No metadata = clean
     };
@@ -490,13 +1411,14 @@ fn check_workspace_dirty() -> Result<bool> {
     // Check if any tracked files have changed
     for (path, expected_hash) in &metadata.files {
         // File deleted
-        if !path.exists() {
+        let location = root.join(metadata.location(path));
+        if !location.exists() {
             return Ok(true);
         }
 
         // File modified - compare hash
-        let content = std::fs::read(path)?;
-        let repo = JinRepo::open()?;
+        let content = std::fs::read(&location)?;
+        let repo = JinRepo::open(root)?;
         let current_hash = repo.create_blob(&content)?;
         if current_hash.to_string() != *expected_hash {
             return Ok(true);
@@ -519,8 +1441,16 @@ mod tests {
         let args = ApplyArgs {
             force: false,
             dry_run: false,
+            target: None,
+            ours: false,
+            theirs: false,
+            prefer: None,
+            provenance: false,
+            merge_opt: Vec::new(),
+            debounce: None,
+            no_reload: false,
         };
-        let result = execute(args);
+        let result = execute(args, temp.path());
         assert!(matches!(result, Err(JinError::NotInitialized)));
     }
 
@@ -529,7 +1459,7 @@ mod tests {
         let temp = TempDir::new().unwrap();
         std::env::set_current_dir(temp.path()).unwrap();
 
-        let result = check_workspace_dirty().unwrap();
+        let result = check_workspace_dirty(temp.path()).unwrap();
         assert!(!result); // No metadata = clean
     }
 
@@ -554,4 +1484,46 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "Hello, World!");
     }
+
+    #[test]
+    fn test_apply_file_keeps_edits_made_after_planning() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("config.txt");
+        std::fs::write(&path, "old\n").unwrap();
+        let merged_file = MergedFile {
+            content: MergeValue::String("merged\n".to_string()),
+            source_layers: vec![Layer::GlobalBase],
+            format: FileFormat::Text,
+            bom: false,
+        };
+        let attributes = FileAttributes::default();
+
+        // Touched but not edited: still replaced
+        let observed = DiskState::read(&path).unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(std::time::SystemTime::UNIX_EPOCH)
+            .unwrap();
+        assert!(apply_file(&path, &merged_file, &attributes, Some(&observed)).unwrap());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "merged\n");
+
+        let observed = DiskState::read(&path).unwrap();
+        std::fs::write(&path, "edited meanwhile\n").unwrap();
+        assert!(!apply_file(&path, &merged_file, &attributes, Some(&observed)).unwrap());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "edited meanwhile\n"
+        );
+        assert!(!path.with_extension("jin-tmp").exists());
+
+        // A file created after planning is kept too
+        let created = temp.path().join("new.txt");
+        let observed = DiskState::read(&created).unwrap();
+        std::fs::write(&created, "mine\n").unwrap();
+        assert!(!apply_file(&created, &merged_file, &attributes, Some(&observed)).unwrap());
+
+        let conflict =
+            drift_conflict(Path::new("new.txt"), &created, &merged_file, &attributes).unwrap();
+        assert_eq!(conflict.conflicts[0].layer1_content, "mine\n");
+        assert_eq!(conflict.conflicts[0].layer2_content, "merged\n");
+    }
 }
@@ -0,0 +1,203 @@
+//! Implementation of `jin archive` and `jin unarchive`
+//!
+//! Freezes a mode or scope read-only (see [`crate::git::archive`]). As with
+//! locks, with a linked remote the archive list is fetched first and pushed
+//! right after the change, so teammates see it on their next fetch.
+
+use crate::cli::{ArchiveArgs, ArchiveTarget, UnarchiveArgs};
+use crate::commands::mode::validate_mode_name;
+use crate::commands::scope::validate_scope_name;
+use crate::core::{JinError, ProjectContext, Result};
+use crate::git::archive::{self, ArchiveKind, ArchiveSet, ArchivedContext};
+use crate::git::locks;
+use crate::git::refs::layer_name;
+use crate::git::{JinRepo, RefOps};
+use crate::staging::StagingIndex;
+use chrono::Utc;
+use git2::Remote;
+use std::path::Path;
+
+/// Execute `jin archive`: archive a mode or scope, or list archived ones
+pub fn archive(args: ArchiveArgs, root: &Path) -> Result<()> {
+    let repo = JinRepo::open(root).map_err(|_| JinError::NotInitialized)?;
+    let mut remote = locks::team_remote(&repo)?;
+
+    let (Some(target), Some(name)) = (args.target, args.name) else {
+        if let Some(remote) = remote.as_mut() {
+            if let Err(e) = archive::fetch(remote) {
+                eprintln!("Warning: Could not refresh the archive list: {}", e);
+            }
+        }
+        return list(&ArchiveSet::load(&repo)?);
+    };
+
+    let kind = kind(target);
+    validate(&repo, kind, &name)?;
+    if let Some(remote) = remote.as_mut() {
+        archive::fetch(remote)?;
+    }
+
+    let mut archive_set = ArchiveSet::load(&repo)?;
+    if archive_set.contains(kind, &name) {
+        println!("{} '{}' is already archived", capitalize(kind), name);
+        return Ok(());
+    }
+
+    archive_set.archived.push(ArchivedContext {
+        kind,
+        name: name.clone(),
+        archived_by: locks::current_owner(&repo),
+        archived_at: Utc::now(),
+        merge: !args.no_merge,
+        reason: args.message,
+    });
+    archive_set.save(&repo, &format!("Archive {} {}", kind, name))?;
+    publish(remote.as_mut())?;
+
+    println!("Archived {} '{}'", kind, name);
+    if args.no_merge {
+        println!("Its layers are left out of merges; run 'jin apply' to update the workspace");
+    }
+    Ok(())
+}
+
+/// Execute `jin unarchive`
+pub fn unarchive(args: UnarchiveArgs, root: &Path) -> Result<()> {
+    let repo = JinRepo::open(root).map_err(|_| JinError::NotInitialized)?;
+    let mut remote = locks::team_remote(&repo)?;
+    let kind = kind(args.target);
+    if let Some(remote) = remote.as_mut() {
+        archive::fetch(remote)?;
+    }
+
+    let mut archive_set = ArchiveSet::load(&repo)?;
+    let index = archive_set
+        .position(kind, &args.name)
+        .ok_or_else(|| JinError::NotFound(format!("Archived {} '{}'", kind, args.name)))?;
+    let was_excluded = !archive_set.archived[index].merge;
+
+    archive_set.archived.remove(index);
+    archive_set.save(&repo, &format!("Unarchive {} {}", kind, args.name))?;
+    publish(remote.as_mut())?;
+
+    println!("Unarchived {} '{}'", kind, args.name);
+    if was_excluded {
+        println!("Its layers take part in merges again; run 'jin apply' to update the workspace");
+    }
+    Ok(())
+}
+
+fn kind(target: ArchiveTarget) -> ArchiveKind {
+    match target {
+        ArchiveTarget::Mode => ArchiveKind::Mode,
+        ArchiveTarget::Scope => ArchiveKind::Scope,
+    }
+}
+
+fn capitalize(kind: ArchiveKind) -> &'static str {
+    match kind {
+        ArchiveKind::Mode => "Mode",
+        ArchiveKind::Scope => "Scope",
+    }
+}
+
+/// Check that the mode or scope to archive exists
+fn validate(repo: &JinRepo, kind: ArchiveKind, name: &str) -> Result<()> {
+    let exists = match kind {
+        ArchiveKind::Mode => {
+            validate_mode_name(name)?;
+            repo.ref_exists(&format!("refs/jin/modes/{}/_mode", name))
+        }
+        ArchiveKind::Scope => {
+            validate_scope_name(name)?;
+            let ref_safe_name = name.replace(':', "/");
+            repo.ref_exists(&format!("refs/jin/scopes/{}", ref_safe_name))
+                || repo
+                    .list_refs("refs/jin/modes/*/scopes/*")
+                    .unwrap_or_default()
+                    .iter()
+                    .any(|r| r.ends_with(&format!("/scopes/{}", ref_safe_name)))
+        }
+    };
+    if exists {
+        Ok(())
+    } else {
+        Err(JinError::NotFound(format!(
+            "{} '{}'",
+            capitalize(kind),
+            name
+        )))
+    }
+}
+
+/// Push the archive list, or note that it stays local
+fn publish(remote: Option<&mut Remote<'_>>) -> Result<()> {
+    match remote {
+        Some(remote) => archive::push(remote),
+        None => {
+            println!("No remote linked: the change is only visible on this machine");
+            Ok(())
+        }
+    }
+}
+
+/// Print the archive list
+fn list(archive_set: &ArchiveSet) -> Result<()> {
+    if archive_set.archived.is_empty() {
+        println!("No archived modes or scopes");
+        return Ok(());
+    }
+
+    for entry in &archive_set.archived {
+        print!(
+            "{} {}  {}  {}",
+            entry.kind,
+            entry.name,
+            entry.archived_by,
+            entry.archived_at.format("%Y-%m-%d %H:%M")
+        );
+        if !entry.merge {
+            print!("  [not merged]");
+        }
+        match &entry.reason {
+            Some(reason) => println!("  ({})", reason),
+            None => println!(),
+        }
+    }
+    Ok(())
+}
+
+/// Refuse to commit staged entries targeting an archived mode or scope
+///
+/// Uses the archive list from the last fetch, so it works offline.
+pub fn check_staged(root: &Path, staging: &StagingIndex, context: &ProjectContext) -> Result<()> {
+    let Ok(repo) = JinRepo::open(root) else {
+        return Ok(());
+    };
+    let archive_set = ArchiveSet::load(&repo)?;
+    if archive_set.archived.is_empty() {
+        return Ok(());
+    }
+
+    let mode = context.mode.as_deref();
+    let scope = context.scope.as_deref();
+    for entry in staging.entries() {
+        if let Some(archived) = archive_set.covering(entry.target_layer, mode, scope) {
+            let ref_path = entry
+                .target_layer
+                .ref_path(mode, scope, context.project.as_deref());
+            return Err(JinError::Other(format!(
+                "Layer '{}' belongs to {} '{}', archived by {} on {}. \
+                 Run 'jin unarchive {} {}' to edit it.",
+                layer_name(&ref_path),
+                archived.kind,
+                archived.name,
+                archived.archived_by,
+                archived.archived_at.format("%Y-%m-%d"),
+                archived.kind,
+                archived.name
+            )));
+        }
+    }
+    Ok(())
+}
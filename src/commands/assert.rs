@@ -0,0 +1,309 @@
+//! Implementation of `jin assert`
+//!
+//! Checks one invariant of the merged configuration, for CI jobs that run
+//! after a sync: the value of a key, whether a key or file is provided, or
+//! that the layers merge cleanly. The layers of the active context (or of
+//! the one given with `--context mode=claude,scope=...`) are merged in
+//! memory; nothing is written to the workspace.
+//!
+//! Key paths are dotted (`editor.tabSize`); numeric segments index arrays,
+//! and keys that themselves contain dots (`editor.formatOnSave` in VS Code
+//! settings) are matched before nested objects.
+//!
+//! The exit status is 0 when the assertion holds, 1 when it fails and 2
+//! when it can't be evaluated, so CI can tell a policy violation from a
+//! broken setup.
+
+use crate::cli::{AssertArgs, AssertCheck};
+use crate::core::{JinError, ProjectContext, Result};
+use crate::git::JinRepo;
+use crate::merge::{get_applicable_layers, merge_layers, LayerMergeConfig, MergeValue};
+use std::path::Path;
+
+/// Result of an assertion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// Whether the assertion holds
+    pub passed: bool,
+    /// What was found
+    pub message: String,
+}
+
+impl Outcome {
+    fn new(passed: bool, message: String) -> Self {
+        Self { passed, message }
+    }
+}
+
+/// Execute the assert command
+///
+/// Exits with status 1 when the assertion fails and 2 when it can't be
+/// evaluated.
+pub fn execute(args: AssertArgs, root: &Path) -> Result<()> {
+    let outcome = context_for(root, &args.context).and_then(|context| {
+        let repo = JinRepo::open(root)?;
+        evaluate(root, &args.check, &context, &repo)
+    });
+    match outcome {
+        Ok(outcome) if outcome.passed => {
+            println!("ok: {}", outcome.message);
+            Ok(())
+        }
+        Ok(outcome) => {
+            println!("FAILED: {}", outcome.message);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(2);
+        }
+    }
+}
+
+/// The active context with the `--context` overrides applied
+fn context_for(root: &Path, overrides: &[String]) -> Result<ProjectContext> {
+    let mut context = match ProjectContext::load(root) {
+        Ok(ctx) => ctx,
+        Err(JinError::NotInitialized) if overrides.is_empty() => {
+            return Err(JinError::NotInitialized);
+        }
+        Err(_) => ProjectContext::default(),
+    };
+    for part in overrides {
+        let invalid = || {
+            JinError::Config(format!(
+                "Invalid --context '{}'. Use mode=<name>, scope=<name> or project=<name>",
+                part
+            ))
+        };
+        let (key, value) = part.split_once('=').ok_or_else(invalid)?;
+        let value = (!value.is_empty() && value != "-").then(|| value.to_string());
+        match key.trim() {
+            "mode" => context.mode = value,
+            "scope" => context.scope = value,
+            "project" => context.project = value,
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(context)
+}
+
+/// Evaluate an assertion against the merged layers of `context`
+pub fn evaluate(
+    root: &Path,
+    check: &AssertCheck,
+    context: &ProjectContext,
+    repo: &JinRepo,
+) -> Result<Outcome> {
+    let config = LayerMergeConfig {
+        layers: get_applicable_layers(
+            context.mode.as_deref(),
+            context.scope.as_deref(),
+            context.project.as_deref(),
+        ),
+        mode: context.mode.clone(),
+        scope: context.scope.clone(),
+        project: context.project.clone(),
+        root: root.to_path_buf(),
+    };
+    let merged = merge_layers(&config, repo)?;
+    let provided = |file: &str| {
+        let path = normalize(file);
+        merged.merged_files.contains_key(path) || merged.conflict_files.iter().any(|p| p == path)
+    };
+
+    let (file, key) = match check {
+        AssertCheck::Managed { file } => {
+            return Ok(if provided(file) {
+                Outcome::new(true, format!("{} is managed", file))
+            } else {
+                Outcome::new(false, format!("{} is not provided by any layer", file))
+            });
+        }
+        AssertCheck::Unmanaged { file } => {
+            return Ok(if provided(file) {
+                Outcome::new(false, format!("{} is provided by a layer", file))
+            } else {
+                Outcome::new(true, format!("{} is unmanaged", file))
+            });
+        }
+        AssertCheck::NoConflicts => {
+            let conflicts = &merged.conflict_files;
+            return Ok(if conflicts.is_empty() {
+                Outcome::new(
+                    true,
+                    format!("{} file(s) merge cleanly", merged.merged_files.len()),
+                )
+            } else {
+                let names: Vec<String> =
+                    conflicts.iter().map(|p| p.display().to_string()).collect();
+                Outcome::new(
+                    false,
+                    format!("{} file(s) conflict: {}", conflicts.len(), names.join(", ")),
+                )
+            });
+        }
+        AssertCheck::KeyEquals { file, key, .. }
+        | AssertCheck::KeyExists { file, key }
+        | AssertCheck::KeyAbsent { file, key } => (file, key),
+    };
+
+    let path = normalize(file);
+    if merged.conflict_files.iter().any(|p| p == path) {
+        return Err(JinError::Other(format!(
+            "{} has a merge conflict in this context",
+            file
+        )));
+    }
+    let found = match merged.merged_files.get(path) {
+        Some(merged_file) if merged_file.format.is_structured() => {
+            lookup(&merged_file.content, key)
+        }
+        Some(_) => {
+            return Err(JinError::Other(format!(
+                "{} is not a structured file (JSON, YAML, TOML or INI)",
+                file
+            )));
+        }
+        None if matches!(check, AssertCheck::KeyAbsent { .. }) => None,
+        None => {
+            return Ok(Outcome::new(
+                false,
+                format!("{} is not provided by any layer", file),
+            ));
+        }
+    };
+
+    Ok(match (check, found) {
+        (AssertCheck::KeyEquals { value, .. }, Some(actual)) => {
+            let shown = display(actual);
+            if equals(actual, value) {
+                Outcome::new(true, format!("{} {} = {}", file, key, shown))
+            } else {
+                Outcome::new(
+                    false,
+                    format!("{} {} is {}, expected {}", file, key, shown, value),
+                )
+            }
+        }
+        (AssertCheck::KeyAbsent { .. }, None) => {
+            Outcome::new(true, format!("{} does not set {}", file, key))
+        }
+        (AssertCheck::KeyAbsent { .. }, Some(actual)) => Outcome::new(
+            false,
+            format!("{} sets {} = {}", file, key, display(actual)),
+        ),
+        (_, Some(actual)) => Outcome::new(true, format!("{} {} = {}", file, key, display(actual))),
+        (_, None) => Outcome::new(false, format!("{} does not set {}", file, key)),
+    })
+}
+
+/// Workspace-relative path as the merge keys it
+fn normalize(file: &str) -> &Path {
+    let path = Path::new(file);
+    path.strip_prefix(".").unwrap_or(path)
+}
+
+/// The value at a dotted key path
+///
+/// At each object, the longest run of segments naming a key wins, so keys
+/// containing dots are found too.
+fn lookup<'a>(value: &'a MergeValue, key: &str) -> Option<&'a MergeValue> {
+    fn find<'a>(value: &'a MergeValue, segments: &[&str]) -> Option<&'a MergeValue> {
+        if segments.is_empty() {
+            return Some(value);
+        }
+        match value {
+            MergeValue::Object(map) => (1..=segments.len()).rev().find_map(|n| {
+                let child = map.get(&segments[..n].join("."))?;
+                find(child, &segments[n..])
+            }),
+            MergeValue::Array(items) => {
+                let item = items.get(segments[0].parse::<usize>().ok()?)?;
+                find(item, &segments[1..])
+            }
+            _ => None,
+        }
+    }
+    find(value, &key.split('.').collect::<Vec<_>>())
+}
+
+/// Whether `actual` equals the expected value as written on the command line
+///
+/// Strings compare as text; other values compare with `expected` read as
+/// JSON, so `true` matches a boolean and `8080` a number.
+fn equals(actual: &MergeValue, expected: &str) -> bool {
+    match actual {
+        MergeValue::String(s) => {
+            s == expected || MergeValue::from_json(expected).is_ok_and(|v| v == *actual)
+        }
+        _ => MergeValue::from_json(expected).is_ok_and(|v| v == *actual),
+    }
+}
+
+/// A value for messages: strings as-is, anything else as compact JSON
+fn display(value: &MergeValue) -> String {
+    match value {
+        MergeValue::String(s) => s.clone(),
+        other => other
+            .to_json_string_compact()
+            .unwrap_or_else(|_| format!("{:?}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> MergeValue {
+        MergeValue::from_json(
+            r#"{"editor.formatOnSave": true, "editor": {"tabSize": 4}, "ports": [8080], "theme": "dark"}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_lookup_key_paths() {
+        let value = settings();
+        assert_eq!(
+            lookup(&value, "editor.formatOnSave"),
+            Some(&MergeValue::Bool(true))
+        );
+        assert_eq!(
+            lookup(&value, "editor.tabSize"),
+            Some(&MergeValue::Integer(4))
+        );
+        assert_eq!(lookup(&value, "ports.0"), Some(&MergeValue::Integer(8080)));
+        assert_eq!(lookup(&value, "ports.1"), None);
+        assert_eq!(lookup(&value, "editor.missing"), None);
+    }
+
+    #[test]
+    fn test_equals_reads_scalars() {
+        let value = settings();
+        assert!(equals(
+            lookup(&value, "editor.formatOnSave").unwrap(),
+            "true"
+        ));
+        assert!(!equals(
+            lookup(&value, "editor.formatOnSave").unwrap(),
+            "false"
+        ));
+        assert!(equals(lookup(&value, "editor.tabSize").unwrap(), "4"));
+        assert!(equals(lookup(&value, "theme").unwrap(), "dark"));
+        assert!(equals(lookup(&value, "theme").unwrap(), "\"dark\""));
+        assert!(equals(lookup(&value, "ports").unwrap(), "[8080]"));
+    }
+
+    #[test]
+    fn test_context_overrides_are_validated() {
+        assert!(matches!(
+            context_for(Path::new("."), &["flavor=x".to_string()]),
+            Err(JinError::Config(_))
+        ));
+        assert!(matches!(
+            context_for(Path::new("."), &["mode".to_string()]),
+            Err(JinError::Config(_))
+        ));
+    }
+}
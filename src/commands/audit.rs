@@ -0,0 +1,43 @@
+//! Implementation of `jin audit` subcommands
+//!
+//! Exports the project's audit log (`.jin/audit/`) in normalized formats
+//! for ingestion by SIEM and other security tooling.
+
+use crate::audit::export::{self, AuditRecord};
+use crate::cli::{AuditAction, AuditFormat};
+use crate::core::{JinError, ProjectContext, Result};
+use std::io::Write;
+use std::path::Path;
+
+/// Execute an audit subcommand
+pub fn execute(action: AuditAction, root: &Path) -> Result<()> {
+    match action {
+        AuditAction::Export { format, since } => export_records(root, format, since.as_deref()),
+    }
+}
+
+/// Write audit records to stdout in the requested format
+fn export_records(root: &Path, format: AuditFormat, since: Option<&str>) -> Result<()> {
+    if !ProjectContext::is_initialized(root) {
+        return Err(JinError::NotInitialized);
+    }
+
+    let since = since.map(export::parse_since).transpose()?;
+    let audit_dir = root.join(".jin").join("audit");
+    let records: Vec<AuditRecord> = export::read_entries(&audit_dir, since)?
+        .iter()
+        .map(AuditRecord::from_entry)
+        .collect();
+
+    let output = match format {
+        AuditFormat::Jsonl => export::to_jsonl(&records)?,
+        AuditFormat::Csv => export::to_csv(&records),
+        AuditFormat::Cef => export::to_cef(&records),
+    };
+
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(output.as_bytes())?;
+    stdout.flush()?;
+
+    Ok(())
+}
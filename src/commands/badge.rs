@@ -0,0 +1,319 @@
+//! Implementation of `jin badge`
+//!
+//! Answers, for a workspace path, what an editor decorates it with: whether
+//! Jin manages the file, whether it is staged (and to which layer), whether
+//! it drifted from the last apply, whether a paused apply left it
+//! conflicted, and which layers its content was merged from.
+//!
+//! Like `jin prompt`, it never opens the Jin repository: it reads the
+//! last-apply metadata, the staging index and the paused apply state, and
+//! hashes a file only when Jin manages it. [`BadgeProvider`] is the library
+//! entry point for long-running editor plugins: it keeps that state loaded,
+//! reloading a file only when its modification time or size changes, and
+//! remembers each file's drift until the file itself changes, so a repeated
+//! call costs a few `stat`s.
+
+use crate::cli::{BadgeArgs, BadgeFormat};
+use crate::commands::apply::PausedApplyState;
+use crate::core::schema::JsonSurface;
+use crate::core::{JinError, Layer, Result};
+use crate::staging::{StagingIndex, WorkspaceMetadata};
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+/// Jin state of one workspace path
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Badge {
+    /// Path relative to the workspace root
+    pub path: PathBuf,
+    /// Written by the last apply
+    pub managed: bool,
+    /// Layer the path is staged to, if staged
+    #[serde(serialize_with = "layer_name")]
+    pub staged: Option<Layer>,
+    /// Modified or deleted since the last apply
+    pub drifted: bool,
+    /// Waiting for `jin resolve` after a paused apply
+    pub conflicted: bool,
+    /// Layers the applied content was merged from, in precedence order
+    pub layers: Vec<String>,
+}
+
+impl Badge {
+    /// One-line summary, e.g. `managed, drifted (global-base, mode-base)`
+    pub fn summary(&self) -> String {
+        let mut flags = Vec::new();
+        if self.managed {
+            flags.push("managed".to_string());
+        }
+        if let Some(layer) = self.staged {
+            flags.push(format!("staged to {}", layer));
+        }
+        if self.drifted {
+            flags.push("drifted".to_string());
+        }
+        if self.conflicted {
+            flags.push("conflicted".to_string());
+        }
+        if flags.is_empty() {
+            return "not managed".to_string();
+        }
+        let mut summary = flags.join(", ");
+        if !self.layers.is_empty() {
+            summary.push_str(&format!(" ({})", self.layers.join(", ")));
+        }
+        summary
+    }
+}
+
+/// Layers appear under their display names (`mode-base`), as elsewhere
+fn layer_name<S: Serializer>(
+    layer: &Option<Layer>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match layer {
+        Some(layer) => serializer.serialize_some(&layer.to_string()),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// `jin badge --format json` output
+#[derive(Debug, Serialize)]
+struct BadgeReport<'a> {
+    schema_version: u32,
+    badges: &'a [Badge],
+}
+
+/// Modification time and size of a file, `None` if it doesn't exist
+type Stamp = Option<(Option<SystemTime>, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    std::fs::metadata(path)
+        .ok()
+        .map(|m| (m.modified().ok(), m.len()))
+}
+
+/// A state file and its parsed content, reloaded when the file changes
+#[derive(Debug)]
+struct Watched<T> {
+    path: PathBuf,
+    stamp: Option<Stamp>,
+    value: T,
+}
+
+impl<T: Default> Watched<T> {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            stamp: None,
+            value: T::default(),
+        }
+    }
+
+    fn refresh(&mut self, load: impl FnOnce(&Path) -> Option<T>) -> &T {
+        let current = stamp(&self.path);
+        if self.stamp != Some(current) {
+            self.value = load(&self.path).unwrap_or_default();
+            self.stamp = Some(current);
+        }
+        &self.value
+    }
+}
+
+/// Answers [`Badge`] queries for one workspace, caching what it reads
+/// between calls
+#[derive(Debug)]
+pub struct BadgeProvider {
+    root: PathBuf,
+    metadata: Watched<WorkspaceMetadata>,
+    staging: Watched<StagingIndex>,
+    conflicts: Watched<BTreeSet<PathBuf>>,
+    /// Drift per path, valid while the file's stamp and recorded hash hold
+    drift: HashMap<PathBuf, (Stamp, String, bool)>,
+}
+
+impl BadgeProvider {
+    /// Provider for the workspace at `root`, whatever the process's
+    /// working directory
+    pub fn new(root: &Path) -> Self {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        Self {
+            metadata: Watched::new(WorkspaceMetadata::default_path(&root)),
+            staging: Watched::new(StagingIndex::default_path(&root)),
+            conflicts: Watched::new(PausedApplyState::default_path(&root)),
+            drift: HashMap::new(),
+            root,
+        }
+    }
+
+    /// Jin state of `path` (absolute, or relative to the workspace root)
+    ///
+    /// Unreadable state files count as empty, so this never fails.
+    pub fn badge(&mut self, path: &Path) -> Badge {
+        self.metadata
+            .refresh(|path| serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok());
+        self.staging
+            .refresh(|path| serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok());
+        self.conflicts.refresh(|path| {
+            serde_yaml::from_str::<PausedApplyState>(&std::fs::read_to_string(path).ok()?)
+                .ok()
+                .map(|state| state.conflict_files.into_iter().collect())
+        });
+
+        let path = self.workspace_path(path);
+        let expected = self.metadata.value.files.get(&path).cloned();
+        let drifted = match &expected {
+            Some(hash) => self.drifted(&path, hash),
+            None => false,
+        };
+        let metadata = &self.metadata.value;
+        Badge {
+            managed: expected.is_some(),
+            staged: self.staging.value.get(&path).map(|e| e.target_layer),
+            drifted,
+            conflicted: self.conflicts.value.contains(&path),
+            layers: metadata.sources.get(&path).cloned().unwrap_or_default(),
+            path,
+        }
+    }
+
+    /// Workspace-relative form of `path`, mapping files applied outside
+    /// the workspace back to their layer path
+    fn workspace_path(&self, path: &Path) -> PathBuf {
+        let metadata = &self.metadata.value;
+        if let Some((layer_path, _)) = metadata
+            .targets
+            .iter()
+            .find(|(_, location)| location.as_path() == path)
+        {
+            return layer_path.clone();
+        }
+
+        let relative = if path.is_absolute() {
+            let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+            match path.strip_prefix(&self.root) {
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => path,
+            }
+        } else {
+            path.to_path_buf()
+        };
+        relative
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .collect()
+    }
+
+    /// Whether a managed file differs from the hash recorded at apply time
+    fn drifted(&mut self, path: &Path, expected: &str) -> bool {
+        let location = self.root.join(self.metadata.value.location(path));
+        let current = stamp(&location);
+        if let Some((cached, hash, drifted)) = self.drift.get(path) {
+            if *cached == current && hash == expected {
+                return *drifted;
+            }
+        }
+
+        // Hashed like a blob, as `jin status` does
+        let drifted = match std::fs::read(&location) {
+            Ok(content) => git2::Oid::hash_object(git2::ObjectType::Blob, &content)
+                .map_or(true, |oid| oid.to_string() != expected),
+            Err(_) => true,
+        };
+        self.drift
+            .insert(path.to_path_buf(), (current, expected.to_string(), drifted));
+        drifted
+    }
+}
+
+/// Execute the badge command
+pub fn execute(args: BadgeArgs, root: &Path) -> Result<()> {
+    let mut provider = BadgeProvider::new(root);
+    let badges: Vec<Badge> = args.paths.iter().map(|p| provider.badge(p)).collect();
+
+    match args.format {
+        BadgeFormat::Text => {
+            for badge in &badges {
+                println!("{}: {}", badge.path.display(), badge.summary());
+            }
+        }
+        BadgeFormat::Json => {
+            let report = BadgeReport {
+                schema_version: JsonSurface::Badge.version(),
+                badges: &badges,
+            };
+            let json = serde_json::to_string_pretty(&report)
+                .map_err(|e| JinError::Other(format!("Failed to serialize badges: {}", e)))?;
+            println!("{}", json);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::staging::StagedEntry;
+    use crate::test_utils::setup_unit_test;
+    use serial_test::serial;
+
+    fn hash(content: &[u8]) -> String {
+        git2::Oid::hash_object(git2::ObjectType::Blob, content)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    #[serial]
+    fn test_badge_follows_workspace_changes() {
+        let ctx = setup_unit_test();
+        std::fs::write("app.json", "{}").unwrap();
+        let mut metadata = WorkspaceMetadata::new();
+        metadata.add_file(PathBuf::from("app.json"), hash(b"{}"));
+        metadata.sources.insert(
+            PathBuf::from("app.json"),
+            vec!["global-base".to_string(), "mode-base".to_string()],
+        );
+        metadata.save(&ctx.project_path).unwrap();
+
+        let mut provider = BadgeProvider::new(&ctx.project_path);
+        let badge = provider.badge(&ctx.project_path.join("app.json"));
+        assert_eq!(badge.path, PathBuf::from("app.json"));
+        assert_eq!(badge.summary(), "managed (global-base, mode-base)");
+        assert_eq!(
+            provider.badge(Path::new("other.json")).summary(),
+            "not managed"
+        );
+
+        std::fs::write("app.json", r#"{"a": 1}"#).unwrap();
+        let mut staging = StagingIndex::new();
+        staging.add(StagedEntry::new(
+            PathBuf::from("app.json"),
+            Layer::ModeBase,
+            hash(br#"{"a": 1}"#),
+        ));
+        staging.save(&ctx.project_path).unwrap();
+
+        let badge = provider.badge(Path::new("./app.json"));
+        assert!(badge.drifted);
+        assert_eq!(badge.staged, Some(Layer::ModeBase));
+        assert!(!badge.conflicted);
+
+        let json = serde_json::to_value(BadgeReport {
+            schema_version: JsonSurface::Badge.version(),
+            badges: &[badge],
+        })
+        .unwrap();
+        assert_eq!(json["badges"][0]["staged"], "mode-base");
+        crate::core::schema::assert_matches(&JsonSurface::Badge.schema(), &json, "badge");
+
+        // Library callers may run from anywhere
+        std::env::set_current_dir(std::env::temp_dir()).unwrap();
+        let mut provider = BadgeProvider::new(&ctx.project_path);
+        let badge = provider.badge(Path::new("app.json"));
+        assert!(badge.managed && badge.drifted);
+        assert_eq!(badge.staged, Some(Layer::ModeBase));
+    }
+}
@@ -0,0 +1,189 @@
+//! Implementation of `jin check`
+//!
+//! Consistency checks for the merge engine, meant to run in CI. Nothing is
+//! written to the workspace.
+//!
+//! `--idempotent` verifies that applying twice changes nothing. For every
+//! merged file it checks that:
+//! - merging the same layers again produces the same bytes (no ordering
+//!   nondeterminism)
+//! - re-reading the applied output and writing it again produces the same
+//!   bytes, so a file that is applied, re-added and applied again doesn't
+//!   churn (e.g., TOML key reordering or float reformatting)
+
+use crate::cli::CheckArgs;
+use crate::core::{JinError, ProjectContext, Result};
+use crate::git::JinRepo;
+use crate::merge::{
+    get_applicable_layers, merge_layers, parse_content, serialize_content, FileFormat,
+    LayerMergeConfig, LayerMergeResult,
+};
+use std::path::{Path, PathBuf};
+
+/// A file whose applied content isn't stable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Churn {
+    /// Workspace path of the file
+    pub path: PathBuf,
+    /// What changed, including the first differing line
+    pub reason: String,
+}
+
+/// Execute the check command
+///
+/// With no flags, every check runs.
+pub fn execute(args: CheckArgs, root: &Path) -> Result<()> {
+    let run_all = !args.idempotent;
+    let context = ProjectContext::load(root)?;
+    let repo = JinRepo::open(root)?;
+
+    if args.idempotent || run_all {
+        let (checked, churn) = check_idempotent(root, &context, &repo)?;
+        if churn.is_empty() {
+            println!(
+                "Idempotent: {} merged file(s) are stable across applies",
+                checked
+            );
+        } else {
+            println!(
+                "Not idempotent: {} of {} file(s) churn",
+                churn.len(),
+                checked
+            );
+            for item in &churn {
+                println!("  {}: {}", item.path.display(), item.reason);
+            }
+            return Err(JinError::Other(format!(
+                "{} file(s) change when applied twice",
+                churn.len()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Merge the active layers twice and round-trip every merged file
+///
+/// Returns the number of files checked and the ones that churn. Conflicted
+/// files are skipped: apply never writes them.
+pub fn check_idempotent(
+    root: &Path,
+    context: &ProjectContext,
+    repo: &JinRepo,
+) -> Result<(usize, Vec<Churn>)> {
+    let config = LayerMergeConfig {
+        layers: get_applicable_layers(
+            context.mode.as_deref(),
+            context.scope.as_deref(),
+            context.project.as_deref(),
+        ),
+        mode: context.mode.clone(),
+        scope: context.scope.clone(),
+        project: context.project.clone(),
+        root: root.to_path_buf(),
+    };
+    let first = merge_layers(&config, repo)?;
+    let second = merge_layers(&config, repo)?;
+
+    let mut paths: Vec<&PathBuf> = first.merged_files.keys().collect();
+    paths.sort();
+
+    let mut churn = Vec::new();
+    for path in &paths {
+        if let Some(reason) = check_file(path, &first, &second)? {
+            churn.push(Churn {
+                path: (*path).clone(),
+                reason,
+            });
+        }
+    }
+    Ok((paths.len(), churn))
+}
+
+/// Why a merged file churns, if it does
+fn check_file(
+    path: &Path,
+    first: &LayerMergeResult,
+    second: &LayerMergeResult,
+) -> Result<Option<String>> {
+    let file = &first.merged_files[path];
+    let applied = serialize_content(&file.content, file.format)?;
+
+    let Some(again) = second.merged_files.get(path) else {
+        return Ok(Some("missing from a second merge".to_string()));
+    };
+    let reapplied = serialize_content(&again.content, again.format)?;
+    if reapplied != applied {
+        return Ok(Some(format!(
+            "merge output differs between runs ({})",
+            first_difference(&applied, &reapplied)
+        )));
+    }
+
+    Ok(round_trip(&applied, file.format)?.map(|rewritten| {
+        format!(
+            "changes when re-read ({})",
+            first_difference(&applied, &rewritten)
+        )
+    }))
+}
+
+/// Re-serialized content, if parsing and writing it again changes it
+fn round_trip(content: &str, format: FileFormat) -> Result<Option<String>> {
+    if !format.is_structured() {
+        return Ok(None);
+    }
+    let rewritten = serialize_content(&parse_content(content, format)?, format)?;
+    Ok((rewritten != content).then_some(rewritten))
+}
+
+/// Describe the first line where two texts differ
+fn first_difference(before: &str, after: &str) -> String {
+    let mut before_lines = before.lines();
+    let mut after_lines = after.lines();
+    let mut line = 1;
+    loop {
+        match (before_lines.next(), after_lines.next()) {
+            (Some(a), Some(b)) if a == b => line += 1,
+            (None, None) => return "line endings differ".to_string(),
+            (a, b) => {
+                return format!(
+                    "line {}: {:?} -> {:?}",
+                    line,
+                    a.unwrap_or("<end of file>"),
+                    b.unwrap_or("<end of file>")
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_difference() {
+        assert_eq!(
+            first_difference("a = 1\nb = 2\n", "a = 1\nb = 2.0\n"),
+            "line 2: \"b = 2\" -> \"b = 2.0\""
+        );
+        assert_eq!(
+            first_difference("a\n", "a\nb\n"),
+            "line 2: \"<end of file>\" -> \"b\""
+        );
+        assert_eq!(first_difference("a", "a\n"), "line endings differ");
+    }
+
+    #[test]
+    fn test_round_trip_stable_output() {
+        let json = serialize_content(
+            &parse_content(r#"{"b": 1, "a": [1.5, "x"]}"#, FileFormat::Json).unwrap(),
+            FileFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(round_trip(&json, FileFormat::Json).unwrap(), None);
+        assert_eq!(round_trip("any text", FileFormat::Text).unwrap(), None);
+    }
+}
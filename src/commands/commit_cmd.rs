@@ -2,11 +2,21 @@
 //!
 //! Commits staged files atomically across all affected layers.
 //! Uses the CommitPipeline to handle multi-layer atomic commits.
+//! `--only` commits the addressed entries and leaves the rest staged.
 
 use crate::cli::CommitArgs;
+use crate::commands::add::warn_if_stale;
+use crate::commands::archive;
+use crate::commands::lock::check_staged;
 use crate::commit::{CommitConfig, CommitPipeline, CommitResult};
+use crate::core::limits::{format_size, LimitsConfig};
+use crate::core::quota;
 use crate::core::{JinError, ProjectContext, Result};
-use crate::staging::StagingIndex;
+use crate::git::JinRepo;
+use crate::notify::{self, EventKind, NotifyEvent};
+use crate::staging::{StagedOperation, StagingIndex, WorkspaceMetadata};
+use std::collections::BTreeSet;
+use std::path::Path;
 
 /// Execute the commit command
 ///
@@ -22,14 +32,45 @@ use crate::staging::StagingIndex;
 /// - Jin is not initialized in the current project
 /// - No files are staged (empty staging index)
 /// - Commit operation fails (Git errors, transaction errors, etc.)
-pub fn execute(args: CommitArgs) -> Result<()> {
+pub fn execute(args: CommitArgs, root: &Path) -> Result<()> {
     // PATTERN: Check initialization first (follow add.rs pattern)
     // ProjectContext::load() returns Err(JinError::NotInitialized) if not initialized
-    let _context = ProjectContext::load()?;
+    let context = ProjectContext::load(root)?;
 
     // PATTERN: Load staging index
     // This will fail if .jin doesn't exist (redundant with context check but safe)
-    let staging = StagingIndex::load()?;
+    let staging = StagingIndex::load(root)?;
+    let (staging, remaining) = if args.only.is_empty() {
+        (staging, None)
+    } else {
+        let (selected, remaining) = split_staging(staging, &args.only)?;
+        (selected, Some(remaining))
+    };
+
+    // Capture staged paths for the notification event before the pipeline
+    // takes ownership of the staging index
+    let files: Vec<String> = staging
+        .entries()
+        .map(|e| e.path.display().to_string())
+        .collect();
+
+    // Warn about files merged from layers that have since moved; committing
+    // them would revert those newer layer changes
+    if let (Ok(metadata), Ok(repo)) = (WorkspaceMetadata::load(root), JinRepo::open(root)) {
+        for entry in staging.entries() {
+            if entry.operation == StagedOperation::AddOrModify {
+                warn_if_stale(&entry.path, Some(&metadata), &repo);
+            }
+        }
+    }
+
+    // Warn (or refuse, per locks.policy) when touching paths teammates locked
+    if !args.dry_run {
+        check_staged(root, &staging, &context)?;
+    }
+
+    // Archived modes and scopes are read-only
+    archive::check_staged(root, &staging, &context)?;
 
     // PATTERN: Build commit configuration
     // CommitConfig builder pattern - pass message as &str
@@ -38,10 +79,13 @@ pub fn execute(args: CommitArgs) -> Result<()> {
     // PATTERN: Create pipeline (staging is moved into pipeline)
     // CRITICAL: Cannot use staging after this line
     let mut pipeline = CommitPipeline::new(staging);
+    if let Some(remaining) = remaining {
+        pipeline = pipeline.keep_staged(remaining);
+    }
 
     // PATTERN: Execute commit with error handling
     // Handle "Nothing to commit" error with user-friendly message
-    match pipeline.execute(&config) {
+    match pipeline.execute(root, &config) {
         Err(JinError::Other(ref msg)) if msg == "Nothing to commit" => {
             // GOTCHA: Empty staging check happens in pipeline
             return Err(JinError::Other(
@@ -52,12 +96,52 @@ pub fn execute(args: CommitArgs) -> Result<()> {
         Ok(result) => {
             // PATTERN: Display results in user-friendly format
             display_commit_result(&result);
+            warn_layer_growth(root, &result);
+
+            // Notify external automation (dry runs produce no hashes)
+            if !result.commit_hashes.is_empty() {
+                let commits = result
+                    .commit_hashes
+                    .iter()
+                    .map(|(layer, hash)| (layer.to_string(), hash.clone()))
+                    .collect();
+                let event = NotifyEvent::new(
+                    EventKind::Commit,
+                    context.project.clone(),
+                    context.mode.clone(),
+                    context.scope.clone(),
+                    files,
+                )
+                .with_commits(commits);
+                notify::notify(&event);
+            }
         }
     }
 
     Ok(())
 }
 
+/// Split the staging index into the entries addressed by `specs` (see
+/// [`StagingIndex::select`]) and the rest
+///
+/// Every entry is resolved before any is moved, so numbers refer to the
+/// list `jin status` showed.
+fn split_staging(
+    mut staging: StagingIndex,
+    specs: &[String],
+) -> Result<(StagingIndex, StagingIndex)> {
+    let entries = specs
+        .iter()
+        .map(|spec| staging.select(spec).cloned())
+        .collect::<Result<Vec<_>>>()?;
+    let mut selected = StagingIndex::new();
+    for entry in entries {
+        staging.remove_entry(entry.target_layer, &entry.path);
+        selected.add(entry);
+    }
+    Ok((selected, staging))
+}
+
 /// Display commit results to the user
 fn display_commit_result(result: &CommitResult) {
     // PATTERN: Format output similar to Git commits
@@ -79,6 +163,141 @@ fn display_commit_result(result: &CommitResult) {
     }
 }
 
+/// Warn when a committed layer grows beyond the configured limits
+///
+/// Large layers slow down every apply and diff, so this nudges users to
+/// split or prune them: a layer past its file or size limit, a commit that
+/// added more than `limits.commit-max-growth` (someone committing
+/// `node_modules`, say), and a mode or scope whose layers together exceed
+/// their quota. It never fails the commit.
+fn warn_layer_growth(root: &Path, result: &CommitResult) {
+    let limits = LimitsConfig::load();
+    let (max_files, max_size) = (limits.layer_max_files(), limits.layer_max_size());
+    let max_growth = limits.commit_max_growth();
+    let Ok(repo) = JinRepo::open(root) else {
+        return;
+    };
+
+    let mut over = false;
+    for (layer, hash) in &result.commit_hashes {
+        let Ok(commit) = git2::Oid::from_str(hash).and_then(|oid| repo.inner().find_commit(oid))
+        else {
+            continue;
+        };
+        let Ok((files, size)) = commit
+            .tree()
+            .map_err(Into::into)
+            .and_then(|tree| quota::footprint(&repo, &tree))
+        else {
+            continue;
+        };
+        if let Some(max) = max_files.filter(|&max| files > max) {
+            eprintln!(
+                "Warning: {} layer now has {} files (limits.layer-max-files is {})",
+                layer, files, max
+            );
+            over = true;
+        }
+        if let Some(max) = max_size.filter(|&max| size > max) {
+            eprintln!(
+                "Warning: {} layer is now {} (limits.layer-max-size is {})",
+                layer,
+                format_size(size),
+                format_size(max)
+            );
+            over = true;
+        }
+
+        let before = commit
+            .parent(0)
+            .and_then(|parent| parent.tree())
+            .ok()
+            .and_then(|tree| quota::footprint(&repo, &tree).ok())
+            .map_or(0, |(_, size)| size);
+        let growth = size.saturating_sub(before);
+        if let Some(max) = max_growth.filter(|&max| growth > max) {
+            eprintln!(
+                "Warning: this commit added {} to the {} layer (limits.commit-max-growth is {})",
+                format_size(growth),
+                layer,
+                format_size(max)
+            );
+            over = true;
+        }
+    }
+
+    over |= warn_quotas(&repo, result, &limits);
+    if over {
+        eprintln!("Run 'jin stats --top' to find the largest files.");
+    }
+}
+
+/// Warn about modes and scopes of the committed layers whose layers
+/// together exceed `limits.mode-max-size` or `limits.scope-max-size`
+fn warn_quotas(repo: &JinRepo, result: &CommitResult, limits: &LimitsConfig) -> bool {
+    let (mode_max, scope_max) = (limits.mode_max_size(), limits.scope_max_size());
+    if mode_max.is_none() && scope_max.is_none() {
+        return false;
+    }
+    let Ok(layers) = quota::measure(repo) else {
+        return false;
+    };
+
+    // The refs the commit moved tell which mode and scope it touched
+    let committed: Vec<&str> = result
+        .commit_hashes
+        .iter()
+        .map(|(_, h)| h.as_str())
+        .collect();
+    let (mut modes, mut scopes) = (BTreeSet::new(), BTreeSet::new());
+    for layer in &layers {
+        let tip = repo.inner().refname_to_id(&layer.ref_path).ok();
+        if tip.is_some_and(|tip| committed.contains(&tip.to_string().as_str())) {
+            modes.extend(layer.mode.clone());
+            scopes.extend(layer.scope.clone());
+        }
+    }
+
+    let mut over = false;
+    let checks = [
+        (
+            "mode",
+            "limits.mode-max-size",
+            mode_max,
+            quota::by_mode(&layers),
+            &modes,
+        ),
+        (
+            "scope",
+            "limits.scope-max-size",
+            scope_max,
+            quota::by_scope(&layers),
+            &scopes,
+        ),
+    ];
+    for (kind, key, max, totals, touched) in checks {
+        let Some(max) = max else {
+            continue;
+        };
+        for total in totals
+            .iter()
+            .filter(|t| touched.contains(&t.name) && t.size > max)
+        {
+            eprintln!(
+                "Warning: {} '{}' now stores {} across {} layers ({} is {})",
+                kind,
+                total.name,
+                format_size(total.size),
+                total.layers,
+                key,
+                format_size(max)
+            );
+            over = true;
+        }
+    }
+    over
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,6 +309,7 @@ mod tests {
         let args = CommitArgs {
             message: "Test commit".to_string(),
             dry_run: false,
+            only: Vec::new(),
         };
         // We can't test execute without a proper Jin setup
         // This is just to verify the struct works
@@ -102,6 +322,7 @@ mod tests {
         let args = CommitArgs {
             message: "Dry run test".to_string(),
             dry_run: true,
+            only: Vec::new(),
         };
         assert!(args.dry_run);
     }
@@ -1,35 +1,259 @@
 use clap::CommandFactory;
-use clap_complete::{generate, Shell};
-use std::io;
+use clap_complete::{generate, Generator, Shell};
+use clap_complete_nushell::Nushell;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
-use crate::cli::Cli;
-use crate::core::Result;
+use crate::cli::{Cli, CompletionArgs, CompletionShell};
+use crate::core::{JinError, Result};
 
 /// Execute the completion command to generate shell completion scripts
 ///
 /// Generates shell-specific completion scripts to stdout. The generated script
 /// can be redirected to a file and sourced to enable tab completion in the shell.
-///
-/// # Arguments
-///
-/// * `shell` - The shell type to generate completions for (bash, zsh, fish, powershell)
+/// With `--install` the script is written to the directory the shell loads
+/// completions from instead (after confirmation), and `--uninstall` removes it.
+/// Either detects the shell from `$SHELL` when none is given.
 ///
 /// # Examples
 ///
 /// ```bash
-/// jin completion bash > /usr/local/share/bash-completion/completions/jin
-/// jin completion zsh > ~/.zsh/completions/_jin
+/// jin completion --install
+/// jin completion zsh > ~/.zfunc/_jin
 /// jin completion fish > ~/.config/fish/completions/jin.fish
 /// jin completion powershell > $PROFILE\..\Completions\jin_completion.ps1
 /// ```
-pub fn execute(shell: Shell) -> Result<()> {
-    // Get the clap Command from Cli's derive macros
-    // This allows clap_complete to introspect the full command structure
-    let mut cmd = Cli::command();
+pub fn execute(args: CompletionArgs) -> Result<()> {
+    let shell = match args.shell {
+        Some(shell) => shell,
+        None => detect_shell(std::env::var("SHELL").ok().as_deref()).ok_or_else(|| {
+            JinError::Other(
+                "Could not detect your shell from $SHELL; name it, e.g. 'jin completion zsh --install'"
+                    .to_string(),
+            )
+        })?,
+    };
+
+    if !args.install && !args.uninstall {
+        io::stdout().write_all(&script(shell))?;
+        return Ok(());
+    }
+
+    let home = dirs::home_dir()
+        .ok_or_else(|| JinError::Other("Could not determine home directory".to_string()))?;
+    let path = install_path(shell, |name| std::env::var(name).ok(), &home);
+    let name = shell_name(shell);
 
-    // Generate completion script to stdout
-    // Binary name "jin" must match [[bin]] name in Cargo.toml
-    generate(shell, &mut cmd, "jin", &mut io::stdout());
+    if args.uninstall {
+        if !path.exists() {
+            println!("No {} completions installed at {}", name, path.display());
+            return Ok(());
+        }
+        let message = format!("Remove {} completions from {}? [y/N]", name, path.display());
+        if !args.yes && !prompt_confirmation(&message)? {
+            println!("Uninstall cancelled");
+            return Ok(());
+        }
+        std::fs::remove_file(&path)?;
+        println!("Removed {}", path.display());
+        return Ok(());
+    }
+
+    let message = format!("Install {} completions to {}? [y/N]", name, path.display());
+    if !args.yes && !prompt_confirmation(&message)? {
+        println!("Install cancelled");
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, script(shell))?;
+    println!("Installed {} completions to {}", name, path.display());
+    if let Some(hint) = activation_hint(shell, &path) {
+        println!("{}", hint);
+    }
+    println!("Open a new shell to use them.");
 
     Ok(())
 }
+
+/// The completion script for a shell
+fn script(shell: CompletionShell) -> Vec<u8> {
+    fn render(generator: impl Generator) -> Vec<u8> {
+        // Get the clap Command from Cli's derive macros
+        // This allows clap_complete to introspect the full command structure
+        let mut cmd = Cli::command();
+        let mut out = Vec::new();
+        // Binary name "jin" must match [[bin]] name in Cargo.toml
+        generate(generator, &mut cmd, "jin", &mut out);
+        out
+    }
+
+    match shell {
+        CompletionShell::Bash => render(Shell::Bash),
+        CompletionShell::Zsh => render(Shell::Zsh),
+        CompletionShell::Fish => render(Shell::Fish),
+        CompletionShell::Powershell => render(Shell::PowerShell),
+        CompletionShell::Elvish => render(Shell::Elvish),
+        CompletionShell::Nushell => render(Nushell),
+    }
+}
+
+fn shell_name(shell: CompletionShell) -> &'static str {
+    match shell {
+        CompletionShell::Bash => "bash",
+        CompletionShell::Zsh => "zsh",
+        CompletionShell::Fish => "fish",
+        CompletionShell::Powershell => "PowerShell",
+        CompletionShell::Elvish => "elvish",
+        CompletionShell::Nushell => "nushell",
+    }
+}
+
+/// The shell named by a `$SHELL` value (`/usr/bin/zsh`, `nu`, `pwsh.exe`)
+///
+/// Without `$SHELL`, Windows is assumed to run PowerShell.
+fn detect_shell(shell_var: Option<&str>) -> Option<CompletionShell> {
+    let Some(value) = shell_var.filter(|v| !v.is_empty()) else {
+        return cfg!(windows).then_some(CompletionShell::Powershell);
+    };
+    let name = Path::new(value).file_stem()?.to_str()?;
+    match name {
+        "bash" => Some(CompletionShell::Bash),
+        "zsh" => Some(CompletionShell::Zsh),
+        "fish" => Some(CompletionShell::Fish),
+        "pwsh" | "powershell" => Some(CompletionShell::Powershell),
+        "elvish" => Some(CompletionShell::Elvish),
+        "nu" | "nushell" => Some(CompletionShell::Nushell),
+        _ => None,
+    }
+}
+
+/// Where `--install` writes the script for a shell
+///
+/// Follows `$XDG_DATA_HOME`/`$XDG_CONFIG_HOME` (and `$ZDOTDIR` for zsh),
+/// falling back to the usual directories under `home`.
+fn install_path(
+    shell: CompletionShell,
+    env: impl Fn(&str) -> Option<String>,
+    home: &Path,
+) -> PathBuf {
+    let dir = |var: &str, default: &str| {
+        env(var)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(default))
+    };
+    let data_home = dir("XDG_DATA_HOME", ".local/share");
+    let config_home = dir("XDG_CONFIG_HOME", ".config");
+
+    match shell {
+        // Loaded on demand by bash-completion
+        CompletionShell::Bash => data_home.join("bash-completion/completions/jin"),
+        CompletionShell::Zsh => dir("ZDOTDIR", "").join(".zfunc/_jin"),
+        // Loaded on demand by fish
+        CompletionShell::Fish => config_home.join("fish/completions/jin.fish"),
+        CompletionShell::Powershell if cfg!(windows) => {
+            home.join("Documents/PowerShell/Completions/jin.ps1")
+        }
+        CompletionShell::Powershell => config_home.join("powershell/Completions/jin.ps1"),
+        CompletionShell::Elvish => config_home.join("elvish/lib/jin-completions.elv"),
+        CompletionShell::Nushell => config_home.join("nushell/completions/jin.nu"),
+    }
+}
+
+/// What to add to the shell's startup file, for shells that don't load the
+/// install directory by themselves
+fn activation_hint(shell: CompletionShell, path: &Path) -> Option<String> {
+    let dir = path.parent().unwrap_or(path).display();
+    match shell {
+        CompletionShell::Bash | CompletionShell::Fish => None,
+        CompletionShell::Zsh => Some(format!(
+            "Add 'fpath=({} $fpath)' before 'compinit' in your .zshrc if it isn't there yet.",
+            dir
+        )),
+        CompletionShell::Powershell => Some(format!(
+            "Add '. \"{}\"' to your $PROFILE if it isn't there yet.",
+            path.display()
+        )),
+        CompletionShell::Elvish => {
+            Some("Add 'use jin-completions' to your rc.elv if it isn't there yet.".to_string())
+        }
+        CompletionShell::Nushell => Some(format!(
+            "Add 'source {}' to your config.nu if it isn't there yet.",
+            path.display()
+        )),
+    }
+}
+
+/// Prompt user for confirmation
+fn prompt_confirmation(message: &str) -> Result<bool> {
+    print!("{} ", message);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    let answer = input.trim();
+    Ok(answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn path(shell: CompletionShell, vars: &[(&str, &str)]) -> PathBuf {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        install_path(
+            shell,
+            |name| vars.get(name).map(|v| v.to_string()),
+            Path::new("/home/u"),
+        )
+    }
+
+    #[test]
+    fn test_detect_shell() {
+        assert_eq!(
+            detect_shell(Some("/usr/bin/zsh")),
+            Some(CompletionShell::Zsh)
+        );
+        assert_eq!(
+            detect_shell(Some("/opt/homebrew/bin/nu")),
+            Some(CompletionShell::Nushell)
+        );
+        assert_eq!(
+            detect_shell(Some("pwsh.exe")),
+            Some(CompletionShell::Powershell)
+        );
+        assert_eq!(detect_shell(Some("/bin/tcsh")), None);
+    }
+
+    #[test]
+    fn test_install_paths() {
+        assert_eq!(
+            path(CompletionShell::Bash, &[]),
+            PathBuf::from("/home/u/.local/share/bash-completion/completions/jin")
+        );
+        assert_eq!(
+            path(CompletionShell::Fish, &[("XDG_CONFIG_HOME", "/cfg")]),
+            PathBuf::from("/cfg/fish/completions/jin.fish")
+        );
+        assert_eq!(
+            path(CompletionShell::Zsh, &[("ZDOTDIR", "/home/u/.zsh")]),
+            PathBuf::from("/home/u/.zsh/.zfunc/_jin")
+        );
+        assert_eq!(
+            path(CompletionShell::Nushell, &[("XDG_CONFIG_HOME", "")]),
+            PathBuf::from("/home/u/.config/nushell/completions/jin.nu")
+        );
+    }
+
+    #[test]
+    fn test_every_shell_has_a_script() {
+        use clap::ValueEnum;
+        for shell in CompletionShell::value_variants() {
+            assert!(!script(*shell).is_empty(), "{:?}", shell);
+        }
+    }
+}
@@ -1,20 +1,55 @@
 //! Implementation of `jin config` subcommands
 
 use crate::cli::ConfigAction;
-use crate::core::config::{JinConfig, RemoteConfig, UserConfig};
+use crate::core::config::{
+    AddConfig, ApplyConfig, JinConfig, NotifyConfig, ProjectConfig, RemoteConfig, UserConfig,
+};
+use crate::core::limits::{format_size, parse_size, LimitsConfig};
 use crate::core::{JinError, Result};
+use crate::git::auth::{AuthMethod, DEFAULT_TOKEN_ENV};
+use crate::git::locks::{LockPolicy, LocksConfig};
+use crate::git::maintenance::GcConfig;
+use crate::git::retry::RetryConfig;
+use crate::merge::style::DEFAULT_YAML_INDENT;
+use crate::merge::{
+    EolConfig, FinalKeysConfig, FinalPolicy, JsonIndent, KeyOrder, LineEnding, Normalization,
+    StyleConfig, TomlTables, UnicodeConfig, YamlQuotes,
+};
+use crate::notify::notifier::MAX_RETRIES;
+use std::path::Path;
+
+/// Keys accepted by `jin config get/set`
+const VALID_KEYS: &str = "jin-dir, remote.url, remote.fetch-on-init, remote.check-interval, \
+remote.auth, remote.token-env, remote.ssh-key, remote.token-file, \
+user.name, user.email, notify.exec, notify.url, notify.retries, notify.events, \
+limits.max-file-size, limits.max-parse-size, limits.layer-max-files, limits.layer-max-size, \
+limits.commit-max-growth, limits.mode-max-size, limits.scope-max-size, \
+locks.policy, final-keys.policy, retry.attempts, retry.delay-ms, retry.max-delay-ms, \
+gc.window, gc.depth, add.create-missing, style.key-order, style.json-indent, style.yaml-indent, style.yaml-quotes, \
+style.toml-tables, style.ini-spacing, eol.policy, unicode.normalization, \
+apply.exclude, apply.provenance, apply.conflict_dir, apply.branch_scopes (per project)";
+
+/// Serializer style keys, in display order
+const STYLE_KEYS: [&str; 6] = [
+    "style.key-order",
+    "style.json-indent",
+    "style.yaml-indent",
+    "style.yaml-quotes",
+    "style.toml-tables",
+    "style.ini-spacing",
+];
 
 /// Execute a config subcommand
-pub fn execute(action: ConfigAction) -> Result<()> {
+pub fn execute(action: ConfigAction, root: &Path) -> Result<()> {
     match action {
-        ConfigAction::List => list(),
-        ConfigAction::Get { key } => get(&key),
-        ConfigAction::Set { key, value } => set(&key, &value),
+        ConfigAction::List => list(root),
+        ConfigAction::Get { key } => get(root, &key),
+        ConfigAction::Set { key, value } => set(root, &key, &value),
     }
 }
 
 /// List all configuration values
-fn list() -> Result<()> {
+fn list(root: &Path) -> Result<()> {
     let config = JinConfig::load()?;
 
     println!("Jin Configuration:");
@@ -31,6 +66,15 @@ fn list() -> Result<()> {
         println!("  remote.url: (not set)");
         println!("  remote.fetch-on-init: (not set)");
     }
+    for key in [
+        "remote.check-interval",
+        "remote.auth",
+        "remote.token-env",
+        "remote.ssh-key",
+        "remote.token-file",
+    ] {
+        println!("  {}: {}", key, get_config_value(&config, key)?);
+    }
 
     // User configuration
     if let Some(ref user) = config.user {
@@ -47,16 +91,114 @@ fn list() -> Result<()> {
         println!("  user.email: (not set)");
     }
 
+    // Notification configuration
+    for key in [
+        "notify.exec",
+        "notify.url",
+        "notify.retries",
+        "notify.events",
+    ] {
+        println!("  {}: {}", key, get_config_value(&config, key)?);
+    }
+
+    // Size guardrails
+    for key in [
+        "limits.max-file-size",
+        "limits.max-parse-size",
+        "limits.layer-max-files",
+        "limits.layer-max-size",
+        "limits.commit-max-growth",
+        "limits.mode-max-size",
+        "limits.scope-max-size",
+    ] {
+        println!("  {}: {}", key, get_config_value(&config, key)?);
+    }
+
+    // Advisory locks
+    println!(
+        "  locks.policy: {}",
+        get_config_value(&config, "locks.policy")?
+    );
+
+    // Final key overrides
+    println!(
+        "  final-keys.policy: {}",
+        get_config_value(&config, "final-keys.policy")?
+    );
+
+    // Remote retries
+    for key in ["retry.attempts", "retry.delay-ms", "retry.max-delay-ms"] {
+        println!("  {}: {}", key, get_config_value(&config, key)?);
+    }
+
+    // Repack tuning
+    for key in ["gc.window", "gc.depth"] {
+        println!("  {}: {}", key, get_config_value(&config, key)?);
+    }
+
+    // Add defaults
+    println!(
+        "  add.create-missing: {}",
+        get_config_value(&config, "add.create-missing")?
+    );
+
+    // Serializer style
+    for key in STYLE_KEYS {
+        println!("  {}: {}", key, get_config_value(&config, key)?);
+    }
+
+    // Line endings
+    println!("  eol.policy: {}", get_config_value(&config, "eol.policy")?);
+    println!(
+        "  unicode.normalization: {}",
+        get_config_value(&config, "unicode.normalization")?
+    );
+
+    // External merge drivers (edited in config.toml)
+    if config.mergers.is_empty() {
+        println!("  mergers: (none)");
+    }
+    for merger in &config.mergers {
+        println!("  mergers: {} -> {}", merger.pattern, merger.command);
+    }
+
+    // Reload hooks (edited in config.toml)
+    for hook in &config.reload {
+        println!("  reload: {} -> {}", hook.pattern, hook.command);
+    }
+
+    // Strict merge rules (edited in config.toml)
+    for rule in &config.strict_merge {
+        let layers = if rule.layers.is_empty() {
+            "all layers".to_string()
+        } else {
+            rule.layers.join(", ")
+        };
+        println!("  strict_merge: {} ({})", rule.pattern, layers);
+    }
+
+    // Per-project settings, when run inside a project
+    if in_project(root) {
+        println!("  apply.exclude: {}", get_apply_exclude(root)?);
+        println!("  apply.provenance: {}", get_apply_provenance(root)?);
+        println!("  apply.conflict_dir: {}", get_apply_conflict_dir(root)?);
+        println!("  apply.branch_scopes: {}", get_apply_branch_scopes(root)?);
+    }
+
     Ok(())
 }
 
 /// Get a specific configuration value
-fn get(key: &str) -> Result<()> {
+fn get(root: &Path, key: &str) -> Result<()> {
     match key {
         "jin-dir" => {
             let display = get_jin_dir_display()?;
             println!("{}", display);
         }
+        "apply.exclude" => println!("{}", get_apply_exclude(root)?),
+        "apply.provenance" => println!("{}", get_apply_provenance(root)?),
+        "apply.conflict_dir" => println!("{}", get_apply_conflict_dir(root)?),
+        "apply.branch_scopes" => println!("{}", get_apply_branch_scopes(root)?),
         _ => {
             let config = JinConfig::load()?;
             let value = get_config_value(&config, key)?;
@@ -67,7 +209,20 @@ fn get(key: &str) -> Result<()> {
 }
 
 /// Set a configuration value
-fn set(key: &str, value: &str) -> Result<()> {
+fn set(root: &Path, key: &str, value: &str) -> Result<()> {
+    if key == "apply.exclude" {
+        return set_apply_exclude(root, value);
+    }
+    if key == "apply.provenance" {
+        return set_apply_flag(root, key, value, |apply| &mut apply.provenance);
+    }
+    if key == "apply.conflict_dir" {
+        return set_apply_flag(root, key, value, |apply| &mut apply.conflict_dir);
+    }
+    if key == "apply.branch_scopes" {
+        return set_apply_flag(root, key, value, |apply| &mut apply.branch_scopes);
+    }
+
     let mut config = JinConfig::load()?;
 
     match key {
@@ -77,6 +232,12 @@ fn set(key: &str, value: &str) -> Result<()> {
                 .get_or_insert_with(|| RemoteConfig {
                     url: String::new(),
                     fetch_on_init: false,
+                    check_interval: None,
+                    auth: AuthMethod::Auto,
+                    token_env: None,
+                    ssh_key: None,
+                    token_file: None,
+                    push_layers: Vec::new(),
                 })
                 .url = value.to_string();
         }
@@ -92,9 +253,54 @@ fn set(key: &str, value: &str) -> Result<()> {
                 .get_or_insert_with(|| RemoteConfig {
                     url: String::new(),
                     fetch_on_init: false,
+                    check_interval: None,
+                    auth: AuthMethod::Auto,
+                    token_env: None,
+                    ssh_key: None,
+                    token_file: None,
+                    push_layers: Vec::new(),
                 })
                 .fetch_on_init = bool_val;
         }
+        "remote.check-interval" => {
+            let seconds = value.parse::<u64>().map_err(|_| {
+                JinError::Config(format!(
+                    "Invalid interval: {}. Use a number of seconds (0 disables)",
+                    value
+                ))
+            })?;
+            config
+                .remote
+                .get_or_insert_with(|| RemoteConfig {
+                    url: String::new(),
+                    fetch_on_init: false,
+                    check_interval: None,
+                    auth: AuthMethod::Auto,
+                    token_env: None,
+                    ssh_key: None,
+                    token_file: None,
+                    push_layers: Vec::new(),
+                })
+                .check_interval = (seconds > 0).then_some(seconds);
+        }
+        "remote.auth" | "remote.token-env" | "remote.ssh-key" | "remote.token-file" => {
+            let remote = config.remote.get_or_insert_with(|| RemoteConfig {
+                url: String::new(),
+                fetch_on_init: false,
+                check_interval: None,
+                auth: AuthMethod::Auto,
+                token_env: None,
+                ssh_key: None,
+                token_file: None,
+                push_layers: Vec::new(),
+            });
+            match key {
+                "remote.auth" => remote.auth = value.parse()?,
+                "remote.token-env" => remote.token_env = Some(value.to_string()),
+                "remote.ssh-key" => remote.ssh_key = Some(value.to_string()),
+                _ => remote.token_file = Some(value.to_string()),
+            }
+        }
         "user.name" => {
             config
                 .user
@@ -113,10 +319,186 @@ fn set(key: &str, value: &str) -> Result<()> {
                 })
                 .email = Some(value.to_string());
         }
+        "notify.exec" => {
+            config.notify.get_or_insert_with(NotifyConfig::default).exec = Some(value.to_string());
+        }
+        "notify.url" => {
+            config.notify.get_or_insert_with(NotifyConfig::default).url = Some(value.to_string());
+        }
+        "notify.retries" => {
+            let retries = value
+                .parse::<u32>()
+                .ok()
+                .filter(|&n| n <= MAX_RETRIES)
+                .ok_or_else(|| {
+                    JinError::Config(format!(
+                        "Invalid retry count: {}. Use an integer from 0 to {}",
+                        value, MAX_RETRIES
+                    ))
+                })?;
+            config
+                .notify
+                .get_or_insert_with(NotifyConfig::default)
+                .retries = retries;
+        }
+        "notify.events" => {
+            let events = value
+                .split(',')
+                .map(str::trim)
+                .filter(|e| !e.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            if let Some(bad) = events
+                .iter()
+                .find(|e| !matches!(e.as_str(), "commit" | "apply" | "sync"))
+            {
+                return Err(JinError::Config(format!(
+                    "Invalid event: {}. Valid events are: commit, apply, sync",
+                    bad
+                )));
+            }
+            config
+                .notify
+                .get_or_insert_with(NotifyConfig::default)
+                .events = events;
+        }
+        "limits.max-file-size"
+        | "limits.max-parse-size"
+        | "limits.layer-max-size"
+        | "limits.commit-max-growth"
+        | "limits.mode-max-size"
+        | "limits.scope-max-size" => {
+            let bytes = parse_size(value)?;
+            let limits = config.limits.get_or_insert_with(LimitsConfig::default);
+            match key {
+                "limits.max-file-size" => limits.max_file_size = Some(bytes),
+                "limits.max-parse-size" => limits.max_parse_size = Some(bytes),
+                "limits.commit-max-growth" => limits.commit_max_growth = Some(bytes),
+                "limits.mode-max-size" => limits.mode_max_size = Some(bytes),
+                "limits.scope-max-size" => limits.scope_max_size = Some(bytes),
+                _ => limits.layer_max_size = Some(bytes),
+            }
+        }
+        "limits.layer-max-files" => {
+            let count = value.parse::<u64>().map_err(|_| {
+                JinError::Config(format!(
+                    "Invalid file count: {}. Use a non-negative integer (0 disables)",
+                    value
+                ))
+            })?;
+            config
+                .limits
+                .get_or_insert_with(LimitsConfig::default)
+                .layer_max_files = Some(count);
+        }
+        "locks.policy" => {
+            config.locks.get_or_insert_with(LocksConfig::default).policy =
+                value.parse::<LockPolicy>()?;
+        }
+        "final-keys.policy" => {
+            config
+                .final_keys
+                .get_or_insert_with(FinalKeysConfig::default)
+                .policy = value.parse::<FinalPolicy>()?;
+        }
+        "retry.attempts" | "retry.delay-ms" | "retry.max-delay-ms" => {
+            let number = value.parse::<u64>().map_err(|_| {
+                JinError::Config(format!(
+                    "Invalid value for {}: {}. Use a non-negative integer",
+                    key, value
+                ))
+            })?;
+            let retry = config.retry.get_or_insert_with(RetryConfig::default);
+            match key {
+                "retry.attempts" => {
+                    retry.attempts = u32::try_from(number.max(1))
+                        .map_err(|_| JinError::Config(format!("Too many attempts: {}", value)))?
+                }
+                "retry.delay-ms" => retry.delay_ms = number,
+                _ => retry.max_delay_ms = number,
+            }
+        }
+        "gc.window" | "gc.depth" => {
+            let number = value.parse::<u32>().map_err(|_| {
+                JinError::Config(format!(
+                    "Invalid value for {}: {}. Use a non-negative integer",
+                    key, value
+                ))
+            })?;
+            let gc = config.gc.get_or_insert_with(GcConfig::default);
+            match key {
+                "gc.window" => gc.window = Some(number),
+                _ => gc.depth = Some(number),
+            }
+        }
+        "add.create-missing" => {
+            let bool_val = value.parse::<bool>().map_err(|_| {
+                JinError::Config(format!(
+                    "Invalid boolean value: {}. Use 'true' or 'false'",
+                    value
+                ))
+            })?;
+            config
+                .add
+                .get_or_insert_with(AddConfig::default)
+                .create_missing = bool_val;
+        }
+        "eol.policy" => {
+            config.eol.get_or_insert_with(EolConfig::default).policy =
+                value.parse::<LineEnding>()?;
+        }
+        "unicode.normalization" => {
+            config
+                .unicode
+                .get_or_insert_with(UnicodeConfig::default)
+                .normalization = value.parse::<Normalization>()?;
+        }
+        "style.key-order" => {
+            config
+                .style
+                .get_or_insert_with(StyleConfig::default)
+                .key_order = Some(value.parse::<KeyOrder>()?);
+        }
+        "style.json-indent" => {
+            config
+                .style
+                .get_or_insert_with(StyleConfig::default)
+                .json_indent = Some(value.parse::<JsonIndent>()?);
+        }
+        "style.yaml-indent" => {
+            config
+                .style
+                .get_or_insert_with(StyleConfig::default)
+                .yaml_indent = Some(StyleConfig::parse_yaml_indent(value)?);
+        }
+        "style.yaml-quotes" => {
+            config
+                .style
+                .get_or_insert_with(StyleConfig::default)
+                .yaml_quotes = Some(value.parse::<YamlQuotes>()?);
+        }
+        "style.toml-tables" => {
+            config
+                .style
+                .get_or_insert_with(StyleConfig::default)
+                .toml_tables = Some(value.parse::<TomlTables>()?);
+        }
+        "style.ini-spacing" => {
+            let spacing = value.parse::<bool>().map_err(|_| {
+                JinError::Config(format!(
+                    "Invalid value for style.ini-spacing: {}. Use 'true' or 'false'",
+                    value
+                ))
+            })?;
+            config
+                .style
+                .get_or_insert_with(StyleConfig::default)
+                .ini_spacing = Some(spacing);
+        }
         _ => {
             return Err(JinError::NotFound(format!(
-                "Unknown config key: '{}'. Valid keys are: jin-dir, remote.url, remote.fetch-on-init, user.name, user.email",
-                key
+                "Unknown config key: '{}'. Valid keys are: {}",
+                key, VALID_KEYS
             )));
         }
     }
@@ -126,6 +508,102 @@ fn set(key: &str, value: &str) -> Result<()> {
     Ok(())
 }
 
+/// Whether `root` is a Jin project
+fn in_project(root: &Path) -> bool {
+    root.join(".jin").is_dir()
+}
+
+/// `apply.exclude` of the current project, comma-separated
+fn get_apply_exclude(root: &Path) -> Result<String> {
+    if !in_project(root) {
+        return Err(JinError::NotInitialized);
+    }
+    let exclude = ProjectConfig::load(root)?.apply.unwrap_or_default().exclude;
+    Ok(if exclude.is_empty() {
+        "(not set)".to_string()
+    } else {
+        exclude.join(",")
+    })
+}
+
+/// Set `apply.exclude` in `.jin/config.yaml` (comma-separated patterns; an
+/// empty value clears it)
+fn set_apply_exclude(root: &Path, value: &str) -> Result<()> {
+    if !in_project(root) {
+        return Err(JinError::NotInitialized);
+    }
+    let patterns: Vec<String> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let mut project = ProjectConfig::load(root)?;
+    let mut apply = project.apply.take().unwrap_or_default();
+    apply.set_exclude(&patterns)?;
+    project.apply = (apply != ApplyConfig::default()).then_some(apply);
+    project.save(root)?;
+
+    println!("Set apply.exclude = {}", patterns.join(","));
+    Ok(())
+}
+
+/// `apply.provenance` of the current project
+fn get_apply_provenance(root: &Path) -> Result<String> {
+    get_apply_flag(root, |apply| apply.provenance)
+}
+
+/// `apply.conflict_dir` of the current project
+fn get_apply_conflict_dir(root: &Path) -> Result<String> {
+    get_apply_flag(root, |apply| apply.conflict_dir)
+}
+
+/// `apply.branch_scopes` of the current project
+fn get_apply_branch_scopes(root: &Path) -> Result<String> {
+    get_apply_flag(root, |apply| apply.branch_scopes)
+}
+
+/// A boolean apply setting of the current project
+fn get_apply_flag(root: &Path, flag: fn(&ApplyConfig) -> bool) -> Result<String> {
+    if !in_project(root) {
+        return Err(JinError::NotInitialized);
+    }
+    let apply = ProjectConfig::load(root)?.apply.unwrap_or_default();
+    Ok(if flag(&apply) {
+        "true".to_string()
+    } else {
+        "false (default)".to_string()
+    })
+}
+
+/// Set a boolean apply setting in `.jin/config.yaml`
+fn set_apply_flag(
+    root: &Path,
+    key: &str,
+    value: &str,
+    flag: fn(&mut ApplyConfig) -> &mut bool,
+) -> Result<()> {
+    if !in_project(root) {
+        return Err(JinError::NotInitialized);
+    }
+    let enabled = value.parse::<bool>().map_err(|_| {
+        JinError::Config(format!(
+            "Invalid boolean value: {}. Use 'true' or 'false'",
+            value
+        ))
+    })?;
+
+    let mut project = ProjectConfig::load(root)?;
+    let mut apply = project.apply.take().unwrap_or_default();
+    *flag(&mut apply) = enabled;
+    project.apply = (apply != ApplyConfig::default()).then_some(apply);
+    project.save(root)?;
+
+    println!("Set {} = {}", key, enabled);
+    Ok(())
+}
+
 /// Helper: Get config value by key
 fn get_config_value(config: &JinConfig, key: &str) -> Result<String> {
     match key {
@@ -139,6 +617,34 @@ fn get_config_value(config: &JinConfig, key: &str) -> Result<String> {
             .as_ref()
             .map(|r| r.fetch_on_init.to_string())
             .unwrap_or_else(|| "(not set)".to_string())),
+        "remote.check-interval" => Ok(config
+            .remote
+            .as_ref()
+            .and_then(|r| r.check_interval)
+            .map(|s| format!("{}s", s))
+            .unwrap_or_else(|| "(disabled)".to_string())),
+        "remote.auth" => Ok(config
+            .remote
+            .as_ref()
+            .map(|r| r.auth)
+            .filter(|a| !a.is_auto())
+            .map(|a| a.to_string())
+            .unwrap_or_else(|| format!("{} (default)", AuthMethod::Auto))),
+        "remote.token-env" => Ok(config
+            .remote
+            .as_ref()
+            .and_then(|r| r.token_env.clone())
+            .unwrap_or_else(|| format!("{} (default)", DEFAULT_TOKEN_ENV))),
+        "remote.ssh-key" => Ok(config
+            .remote
+            .as_ref()
+            .and_then(|r| r.ssh_key.clone())
+            .unwrap_or_else(|| "(not set)".to_string())),
+        "remote.token-file" => Ok(config
+            .remote
+            .as_ref()
+            .and_then(|r| r.token_file.clone())
+            .unwrap_or_else(|| "(not set)".to_string())),
         "user.name" => Ok(config
             .user
             .as_ref()
@@ -151,13 +657,142 @@ fn get_config_value(config: &JinConfig, key: &str) -> Result<String> {
             .and_then(|u| u.email.as_ref())
             .cloned()
             .unwrap_or_else(|| "(not set)".to_string())),
+        "notify.exec" => Ok(config
+            .notify
+            .as_ref()
+            .and_then(|n| n.exec.clone())
+            .unwrap_or_else(|| "(not set)".to_string())),
+        "notify.url" => Ok(config
+            .notify
+            .as_ref()
+            .and_then(|n| n.url.clone())
+            .unwrap_or_else(|| "(not set)".to_string())),
+        "notify.retries" => Ok(config
+            .notify
+            .as_ref()
+            .map(|n| n.retries.to_string())
+            .unwrap_or_else(|| "(not set)".to_string())),
+        "notify.events" => Ok(config
+            .notify
+            .as_ref()
+            .filter(|n| !n.events.is_empty())
+            .map(|n| n.events.join(","))
+            .unwrap_or_else(|| "(all)".to_string())),
+        "limits.max-file-size"
+        | "limits.max-parse-size"
+        | "limits.layer-max-size"
+        | "limits.commit-max-growth"
+        | "limits.mode-max-size"
+        | "limits.scope-max-size" => {
+            let limits = config.limits.clone().unwrap_or_default();
+            let (configured, effective) = match key {
+                "limits.max-file-size" => (limits.max_file_size, limits.max_file_size()),
+                "limits.max-parse-size" => (limits.max_parse_size, limits.max_parse_size()),
+                "limits.commit-max-growth" => {
+                    (limits.commit_max_growth, limits.commit_max_growth())
+                }
+                "limits.mode-max-size" => (limits.mode_max_size, limits.mode_max_size()),
+                "limits.scope-max-size" => (limits.scope_max_size, limits.scope_max_size()),
+                _ => (limits.layer_max_size, limits.layer_max_size()),
+            };
+            Ok(describe_limit(configured, effective.map(format_size)))
+        }
+        "limits.layer-max-files" => {
+            let limits = config.limits.clone().unwrap_or_default();
+            Ok(describe_limit(
+                limits.layer_max_files,
+                limits.layer_max_files().map(|n| n.to_string()),
+            ))
+        }
+        "locks.policy" => Ok(match &config.locks {
+            Some(locks) => locks.policy.to_string(),
+            None => format!("{} (default)", LockPolicy::default()),
+        }),
+        "final-keys.policy" => Ok(match &config.final_keys {
+            Some(final_keys) => final_keys.policy.to_string(),
+            None => format!("{} (default)", FinalPolicy::default()),
+        }),
+        "retry.attempts" | "retry.delay-ms" | "retry.max-delay-ms" => {
+            let retry = config.retry.clone().unwrap_or_default();
+            let value = match key {
+                "retry.attempts" => retry.attempts.to_string(),
+                "retry.delay-ms" => retry.delay_ms.to_string(),
+                _ => retry.max_delay_ms.to_string(),
+            };
+            Ok(match config.retry {
+                Some(_) => value,
+                None => format!("{} (default)", value),
+            })
+        }
+        "gc.window" | "gc.depth" => {
+            let gc = config.gc.clone().unwrap_or_default();
+            let value = match key {
+                "gc.window" => gc.window,
+                _ => gc.depth,
+            };
+            Ok(value.map_or_else(|| "(git default)".to_string(), |n| n.to_string()))
+        }
+        "add.create-missing" => Ok(match &config.add {
+            Some(add) => add.create_missing.to_string(),
+            None => "false (default)".to_string(),
+        }),
+        "eol.policy" => Ok(match &config.eol {
+            Some(eol) => eol.policy.to_string(),
+            None => format!("{} (default)", LineEnding::default()),
+        }),
+        "unicode.normalization" => Ok(match &config.unicode {
+            Some(unicode) => unicode.normalization.to_string(),
+            None => format!("{} (default)", Normalization::default()),
+        }),
+        "style.key-order" => Ok(config
+            .style
+            .as_ref()
+            .and_then(|s| s.key_order)
+            .map_or_else(|| "(format default)".to_string(), |order| order.to_string())),
+        "style.json-indent" => Ok(describe_style(
+            config.style.as_ref().and_then(|s| s.json_indent),
+            JsonIndent::default(),
+        )),
+        "style.yaml-indent" => Ok(describe_style(
+            config.style.as_ref().and_then(|s| s.yaml_indent),
+            DEFAULT_YAML_INDENT,
+        )),
+        "style.yaml-quotes" => Ok(describe_style(
+            config.style.as_ref().and_then(|s| s.yaml_quotes),
+            YamlQuotes::default(),
+        )),
+        "style.toml-tables" => Ok(describe_style(
+            config.style.as_ref().and_then(|s| s.toml_tables),
+            TomlTables::default(),
+        )),
+        "style.ini-spacing" => Ok(describe_style(
+            config.style.as_ref().and_then(|s| s.ini_spacing),
+            false,
+        )),
         _ => Err(JinError::NotFound(format!(
-            "Unknown config key: '{}'. Valid keys are: jin-dir, remote.url, remote.fetch-on-init, user.name, user.email",
-            key
+            "Unknown config key: '{}'. Valid keys are: {}",
+            key, VALID_KEYS
         ))),
     }
 }
 
+/// Helper: Describe a limit, noting defaults and disabled limits
+fn describe_limit(configured: Option<u64>, effective: Option<String>) -> String {
+    match (effective, configured) {
+        (None, _) => "(disabled)".to_string(),
+        (Some(value), None) => format!("{} (default)", value),
+        (Some(value), Some(_)) => value,
+    }
+}
+
+/// Helper: Describe a style option, noting defaults
+fn describe_style<T: std::fmt::Display>(configured: Option<T>, default: T) -> String {
+    match configured {
+        Some(value) => value.to_string(),
+        None => format!("{} (default)", default),
+    }
+}
+
 /// Helper: Get JIN_DIR display with guidance
 fn get_jin_dir_display() -> Result<String> {
     if let Ok(jin_dir) = std::env::var("JIN_DIR") {
@@ -178,21 +813,27 @@ mod tests {
     #[test]
     #[serial]
     fn test_list_empty_config() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        let result = list();
+        let ctx = crate::test_utils::setup_unit_test();
+        let result = list(&ctx.project_path);
         assert!(result.is_ok());
     }
 
     #[test]
     #[serial]
     fn test_list_with_values() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
         // Set some config values
         let mut config = JinConfig::load().unwrap();
         config.remote = Some(RemoteConfig {
             url: "https://github.com/test/jin-config".to_string(),
             fetch_on_init: true,
+            check_interval: None,
+            auth: AuthMethod::Auto,
+            token_env: None,
+            ssh_key: None,
+            token_file: None,
+            push_layers: Vec::new(),
         });
         config.user = Some(UserConfig {
             name: Some("Test User".to_string()),
@@ -200,57 +841,67 @@ mod tests {
         });
         config.save().unwrap();
 
-        let result = list();
+        let result = list(&ctx.project_path);
         assert!(result.is_ok());
     }
 
     #[test]
     #[serial]
     fn test_get_jin_dir() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        let result = get("jin-dir");
+        let ctx = crate::test_utils::setup_unit_test();
+        let result = get(&ctx.project_path, "jin-dir");
         assert!(result.is_ok());
     }
 
     #[test]
     #[serial]
     fn test_get_remote_url() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
         // Set remote.url
         let mut config = JinConfig::load().unwrap();
         config.remote = Some(RemoteConfig {
             url: "https://github.com/test/jin-config".to_string(),
             fetch_on_init: false,
+            check_interval: None,
+            auth: AuthMethod::Auto,
+            token_env: None,
+            ssh_key: None,
+            token_file: None,
+            push_layers: Vec::new(),
         });
         config.save().unwrap();
 
-        let result = get("remote.url");
+        let result = get(&ctx.project_path, "remote.url");
         assert!(result.is_ok());
     }
 
     #[test]
     #[serial]
     fn test_get_remote_url_not_set() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        let result = get("remote.url");
+        let ctx = crate::test_utils::setup_unit_test();
+        let result = get(&ctx.project_path, "remote.url");
         assert!(result.is_ok());
     }
 
     #[test]
     #[serial]
     fn test_get_unknown_key() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        let result = get("unknown.key");
+        let ctx = crate::test_utils::setup_unit_test();
+        let result = get(&ctx.project_path, "unknown.key");
         assert!(matches!(result, Err(JinError::NotFound(_))));
     }
 
     #[test]
     #[serial]
     fn test_set_remote_url() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
-        let result = set("remote.url", "https://github.com/test/jin-config");
+        let result = set(
+            &ctx.project_path,
+            "remote.url",
+            "https://github.com/test/jin-config",
+        );
         assert!(result.is_ok());
 
         // Verify the value was set
@@ -265,9 +916,9 @@ mod tests {
     #[test]
     #[serial]
     fn test_set_remote_fetch_on_init_true() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
-        let result = set("remote.fetch-on-init", "true");
+        let result = set(&ctx.project_path, "remote.fetch-on-init", "true");
         assert!(result.is_ok());
 
         // Verify the value was set
@@ -279,9 +930,9 @@ mod tests {
     #[test]
     #[serial]
     fn test_set_remote_fetch_on_init_false() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
-        let result = set("remote.fetch-on-init", "false");
+        let result = set(&ctx.project_path, "remote.fetch-on-init", "false");
         assert!(result.is_ok());
 
         // Verify the value was set
@@ -293,18 +944,169 @@ mod tests {
     #[test]
     #[serial]
     fn test_set_remote_fetch_on_init_invalid() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
+
+        let result = set(&ctx.project_path, "remote.fetch-on-init", "not-a-boolean");
+        assert!(matches!(result, Err(JinError::Config(_))));
+    }
+
+    #[test]
+    #[serial]
+    fn test_set_remote_check_interval() {
+        let ctx = crate::test_utils::setup_unit_test();
+
+        set(&ctx.project_path, "remote.check-interval", "600").unwrap();
+        let config = JinConfig::load().unwrap();
+        assert_eq!(config.remote.unwrap().check_interval, Some(600));
+
+        // Zero disables the check
+        set(&ctx.project_path, "remote.check-interval", "0").unwrap();
+        let config = JinConfig::load().unwrap();
+        assert_eq!(config.remote.unwrap().check_interval, None);
+
+        let result = set(&ctx.project_path, "remote.check-interval", "soon");
+        assert!(matches!(result, Err(JinError::Config(_))));
+    }
+
+    #[test]
+    #[serial]
+    fn test_set_retry() {
+        let ctx = crate::test_utils::setup_unit_test();
+        assert_eq!(
+            get_config_value(&JinConfig::default(), "retry.attempts").unwrap(),
+            "3 (default)"
+        );
+
+        set(&ctx.project_path, "retry.attempts", "5").unwrap();
+        set(&ctx.project_path, "retry.delay-ms", "250").unwrap();
+        let retry = JinConfig::load().unwrap().retry.unwrap();
+        assert_eq!(retry.attempts, 5);
+        assert_eq!(retry.delay_ms, 250);
+        assert_eq!(retry.max_delay_ms, 30_000);
 
-        let result = set("remote.fetch-on-init", "not-a-boolean");
+        let result = set(&ctx.project_path, "retry.max-delay-ms", "-1");
         assert!(matches!(result, Err(JinError::Config(_))));
     }
 
+    #[test]
+    #[serial]
+    fn test_set_gc() {
+        let ctx = crate::test_utils::setup_unit_test();
+        assert_eq!(
+            get_config_value(&JinConfig::default(), "gc.window").unwrap(),
+            "(git default)"
+        );
+
+        set(&ctx.project_path, "gc.window", "500").unwrap();
+        let gc = JinConfig::load().unwrap().gc.unwrap();
+        assert_eq!(gc.window, Some(500));
+        assert_eq!(gc.depth, None);
+        assert!(matches!(
+            set(&ctx.project_path, "gc.depth", "deep"),
+            Err(JinError::Config(_))
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test_set_add_create_missing() {
+        let ctx = crate::test_utils::setup_unit_test();
+        assert_eq!(
+            get_config_value(&JinConfig::default(), "add.create-missing").unwrap(),
+            "false (default)"
+        );
+
+        set(&ctx.project_path, "add.create-missing", "true").unwrap();
+        assert!(JinConfig::load().unwrap().add.unwrap().create_missing);
+        assert!(matches!(
+            set(&ctx.project_path, "add.create-missing", "sometimes"),
+            Err(JinError::Config(_))
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test_set_apply_exclude() {
+        let ctx = crate::test_utils::setup_unit_test();
+        assert_eq!(get_apply_exclude(&ctx.project_path).unwrap(), "(not set)");
+
+        set(&ctx.project_path, "apply.exclude", ".idea/, *.iml").unwrap();
+        let apply = ProjectConfig::load(&ctx.project_path)
+            .unwrap()
+            .apply
+            .unwrap();
+        assert_eq!(apply.exclude, [".idea/", "*.iml"]);
+        assert_eq!(
+            get_apply_exclude(&ctx.project_path).unwrap(),
+            ".idea/,*.iml"
+        );
+
+        set(&ctx.project_path, "apply.exclude", "").unwrap();
+        assert!(ProjectConfig::load(&ctx.project_path)
+            .unwrap()
+            .apply
+            .is_none());
+        assert!(matches!(
+            set(&ctx.project_path, "apply.exclude", "/"),
+            Err(JinError::Config(_))
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test_set_apply_provenance() {
+        let ctx = crate::test_utils::setup_unit_test();
+        assert_eq!(
+            get_apply_provenance(&ctx.project_path).unwrap(),
+            "false (default)"
+        );
+
+        set(&ctx.project_path, "apply.provenance", "true").unwrap();
+        assert!(
+            ProjectConfig::load(&ctx.project_path)
+                .unwrap()
+                .apply
+                .unwrap()
+                .provenance
+        );
+        assert_eq!(get_apply_provenance(&ctx.project_path).unwrap(), "true");
+
+        set(&ctx.project_path, "apply.provenance", "false").unwrap();
+        assert!(ProjectConfig::load(&ctx.project_path)
+            .unwrap()
+            .apply
+            .is_none());
+        assert!(matches!(
+            set(&ctx.project_path, "apply.provenance", "yes"),
+            Err(JinError::Config(_))
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test_set_apply_conflict_dir() {
+        let ctx = crate::test_utils::setup_unit_test();
+        assert_eq!(
+            get_apply_conflict_dir(&ctx.project_path).unwrap(),
+            "false (default)"
+        );
+
+        set(&ctx.project_path, "apply.conflict_dir", "true").unwrap();
+        let apply = ProjectConfig::load(&ctx.project_path)
+            .unwrap()
+            .apply
+            .unwrap();
+        assert!(apply.conflict_dir);
+        assert!(!apply.provenance);
+        assert_eq!(get_apply_conflict_dir(&ctx.project_path).unwrap(), "true");
+    }
+
     #[test]
     #[serial]
     fn test_set_user_name() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
-        let result = set("user.name", "Test User");
+        let result = set(&ctx.project_path, "user.name", "Test User");
         assert!(result.is_ok());
 
         // Verify the value was set
@@ -316,9 +1118,9 @@ mod tests {
     #[test]
     #[serial]
     fn test_set_user_email() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
-        let result = set("user.email", "test@example.com");
+        let result = set(&ctx.project_path, "user.email", "test@example.com");
         assert!(result.is_ok());
 
         // Verify the value was set
@@ -333,32 +1135,196 @@ mod tests {
     #[test]
     #[serial]
     fn test_set_unknown_key() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
-        let result = set("unknown.key", "value");
+        let result = set(&ctx.project_path, "unknown.key", "value");
         assert!(matches!(result, Err(JinError::NotFound(_))));
     }
 
     #[test]
     #[serial]
     fn test_set_jin_dir_fails() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
         // jin-dir cannot be set via config command
-        let result = set("jin-dir", "/custom/path");
+        let result = set(&ctx.project_path, "jin-dir", "/custom/path");
         assert!(matches!(result, Err(JinError::NotFound(_))));
     }
 
+    #[test]
+    #[serial]
+    fn test_set_notify_keys() {
+        let ctx = crate::test_utils::setup_unit_test();
+
+        set(
+            &ctx.project_path,
+            "notify.url",
+            "https://hooks.example.com/jin",
+        )
+        .unwrap();
+        set(&ctx.project_path, "notify.retries", "5").unwrap();
+        set(&ctx.project_path, "notify.events", "commit, sync").unwrap();
+
+        let config = JinConfig::load().unwrap();
+        let notify = config.notify.unwrap();
+        assert_eq!(notify.url.as_deref(), Some("https://hooks.example.com/jin"));
+        assert!(notify.exec.is_none());
+        assert_eq!(notify.retries, 5);
+        assert_eq!(notify.events, vec!["commit", "sync"]);
+    }
+
+    #[test]
+    #[serial]
+    fn test_set_notify_invalid_values() {
+        let ctx = crate::test_utils::setup_unit_test();
+
+        assert!(matches!(
+            set(&ctx.project_path, "notify.retries", "-1"),
+            Err(JinError::Config(_))
+        ));
+        assert!(matches!(
+            set(&ctx.project_path, "notify.retries", "100"),
+            Err(JinError::Config(_))
+        ));
+        assert!(matches!(
+            set(&ctx.project_path, "notify.events", "commit,deploy"),
+            Err(JinError::Config(_))
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test_set_limits() {
+        let ctx = crate::test_utils::setup_unit_test();
+
+        assert_eq!(
+            get_config_value(&JinConfig::default(), "limits.max-file-size").unwrap(),
+            "10.0 MB (default)"
+        );
+
+        set(&ctx.project_path, "limits.max-file-size", "512KB").unwrap();
+        set(&ctx.project_path, "limits.layer-max-files", "0").unwrap();
+
+        let config = JinConfig::load().unwrap();
+        let limits = config.limits.clone().unwrap();
+        assert_eq!(limits.max_file_size, Some(512 * 1024));
+        assert_eq!(limits.layer_max_files(), None);
+        assert_eq!(
+            get_config_value(&config, "limits.max-file-size").unwrap(),
+            "512.0 KB"
+        );
+        assert_eq!(
+            get_config_value(&config, "limits.layer-max-files").unwrap(),
+            "(disabled)"
+        );
+
+        assert!(matches!(
+            set(&ctx.project_path, "limits.max-parse-size", "huge"),
+            Err(JinError::Config(_))
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test_set_style() {
+        let ctx = crate::test_utils::setup_unit_test();
+
+        assert_eq!(
+            get_config_value(&JinConfig::default(), "style.json-indent").unwrap(),
+            "2 (default)"
+        );
+        assert_eq!(
+            get_config_value(&JinConfig::default(), "style.key-order").unwrap(),
+            "(format default)"
+        );
+
+        set(&ctx.project_path, "style.json-indent", "tab").unwrap();
+        set(&ctx.project_path, "style.yaml-quotes", "double").unwrap();
+        set(&ctx.project_path, "style.ini-spacing", "true").unwrap();
+
+        let config = JinConfig::load().unwrap();
+        let style = config.style.clone().unwrap();
+        assert_eq!(style.json_indent, Some(JsonIndent::Tab));
+        assert_eq!(style.yaml_quotes, Some(YamlQuotes::Double));
+        assert_eq!(
+            get_config_value(&config, "style.ini-spacing").unwrap(),
+            "true"
+        );
+
+        assert!(matches!(
+            set(&ctx.project_path, "style.yaml-indent", "1"),
+            Err(JinError::Config(_))
+        ));
+        assert!(matches!(
+            set(&ctx.project_path, "style.key-order", "random"),
+            Err(JinError::Config(_))
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test_set_eol_policy() {
+        let ctx = crate::test_utils::setup_unit_test();
+
+        assert_eq!(
+            get_config_value(&JinConfig::default(), "eol.policy").unwrap(),
+            "preserve (default)"
+        );
+
+        set(&ctx.project_path, "eol.policy", "crlf").unwrap();
+        let config = JinConfig::load().unwrap();
+        assert_eq!(config.eol.clone().unwrap().policy, LineEnding::Crlf);
+        assert_eq!(get_config_value(&config, "eol.policy").unwrap(), "crlf");
+
+        assert!(matches!(
+            set(&ctx.project_path, "eol.policy", "dos"),
+            Err(JinError::Config(_))
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test_set_unicode_normalization() {
+        let ctx = crate::test_utils::setup_unit_test();
+
+        assert_eq!(
+            get_config_value(&JinConfig::default(), "unicode.normalization").unwrap(),
+            "nfc (default)"
+        );
+
+        set(&ctx.project_path, "unicode.normalization", "none").unwrap();
+        let config = JinConfig::load().unwrap();
+        assert_eq!(
+            config.unicode.clone().unwrap().normalization,
+            Normalization::None
+        );
+
+        assert!(matches!(
+            set(&ctx.project_path, "unicode.normalization", "nfd"),
+            Err(JinError::Config(_))
+        ));
+    }
+
     #[test]
     #[serial]
     fn test_set_overwrites_existing_value() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
         // Set initial value
-        set("remote.url", "https://github.com/original/config").unwrap();
+        set(
+            &ctx.project_path,
+            "remote.url",
+            "https://github.com/original/config",
+        )
+        .unwrap();
 
         // Overwrite with new value
-        set("remote.url", "https://github.com/new/config").unwrap();
+        set(
+            &ctx.project_path,
+            "remote.url",
+            "https://github.com/new/config",
+        )
+        .unwrap();
 
         // Verify the new value
         let config = JinConfig::load().unwrap();
@@ -374,6 +1340,12 @@ mod tests {
         config.remote = Some(RemoteConfig {
             url: "https://example.com".to_string(),
             fetch_on_init: true,
+            check_interval: None,
+            auth: AuthMethod::Auto,
+            token_env: None,
+            ssh_key: None,
+            token_file: None,
+            push_layers: Vec::new(),
         });
         config.user = Some(UserConfig {
             name: Some("Test".to_string()),
@@ -403,35 +1375,35 @@ mod tests {
     #[test]
     #[serial]
     fn test_execute_list() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
         let action = ConfigAction::List;
-        let result = execute(action);
+        let result = execute(action, &ctx.project_path);
         assert!(result.is_ok());
     }
 
     #[test]
     #[serial]
     fn test_execute_get() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
         let action = ConfigAction::Get {
             key: "jin-dir".to_string(),
         };
-        let result = execute(action);
+        let result = execute(action, &ctx.project_path);
         assert!(result.is_ok());
     }
 
     #[test]
     #[serial]
     fn test_execute_set() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
         let action = ConfigAction::Set {
             key: "user.name".to_string(),
             value: "Test User".to_string(),
         };
-        let result = execute(action);
+        let result = execute(action, &ctx.project_path);
         assert!(result.is_ok());
 
         // Verify value was set
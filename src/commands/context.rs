@@ -1,13 +1,22 @@
 //! Implementation of `jin context`
+//!
+//! `--validate` and `--heal` check the active mode and scope against the
+//! Jin repository (see [`crate::core::dangling`]).
 
+use crate::cli::ContextArgs;
+use crate::core::dangling;
 use crate::core::{JinError, ProjectContext, Result};
+use crate::git::JinRepo;
+use std::path::Path;
 
 /// Execute the context command
 ///
 /// Shows the current active context including mode, scope, and project.
-pub fn execute() -> Result<()> {
+/// Reads only `.jin/context` and never opens the Jin repository, so it is
+/// cheap enough for scripts and prompts (see also `jin prompt`).
+pub fn execute(args: ContextArgs, root: &Path) -> Result<()> {
     // Load project context
-    let context = match ProjectContext::load() {
+    let context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -15,6 +24,10 @@ pub fn execute() -> Result<()> {
         Err(_) => ProjectContext::default(),
     };
 
+    if args.validate || args.heal {
+        return check(root, context, args.heal);
+    }
+
     // Display context information
     println!("Current Jin context:");
     println!();
@@ -38,6 +51,35 @@ pub fn execute() -> Result<()> {
     Ok(())
 }
 
+/// Report dangling entries, failing on them unless `heal` unsets them
+fn check(root: &Path, mut context: ProjectContext, heal: bool) -> Result<()> {
+    let repo = JinRepo::open(root)?;
+    if !heal {
+        dangling::ensure_valid(&context, &repo)?;
+        println!("Active context is valid.");
+        return Ok(());
+    }
+
+    let entries = dangling::check(&context, &repo);
+    if entries.is_empty() {
+        println!("Nothing to heal: the active mode and scope exist.");
+        return Ok(());
+    }
+    dangling::heal(&mut context, &entries);
+    context.save(root)?;
+    for entry in &entries {
+        println!("Unset {} '{}' (no longer exists)", entry.entry, entry.name);
+        if let Some(fix) = entry.fix() {
+            println!(
+                "  Did you mean '{}'? Activate it with: {}",
+                entry.suggestion.as_deref().unwrap_or_default(),
+                fix
+            );
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -61,7 +103,7 @@ mod tests {
         // Initialize .jin directory and context
         std::fs::create_dir_all(temp.path().join(".jin")).unwrap();
         let context = ProjectContext::default();
-        context.save().unwrap();
+        context.save(temp.path()).unwrap();
 
         temp
     }
@@ -70,7 +112,7 @@ mod tests {
     #[serial]
     fn test_execute_default_context() {
         let _temp = setup_test_env();
-        let result = execute();
+        let result = execute(ContextArgs::default(), Path::new("."));
         assert!(result.is_ok());
     }
 
@@ -80,12 +122,12 @@ mod tests {
         let _temp = setup_test_env();
 
         // Set mode and scope
-        let mut context = ProjectContext::load().unwrap();
+        let mut context = ProjectContext::load(Path::new(".")).unwrap();
         context.mode = Some("testmode".to_string());
         context.scope = Some("testscope".to_string());
-        context.save().unwrap();
+        context.save(Path::new(".")).unwrap();
 
-        let result = execute();
+        let result = execute(ContextArgs::default(), Path::new("."));
         assert!(result.is_ok());
     }
 
@@ -96,7 +138,7 @@ mod tests {
         std::env::set_current_dir(temp.path()).unwrap();
 
         // Don't initialize .jin
-        let result = execute();
+        let result = execute(ContextArgs::default(), temp.path());
         assert!(matches!(result, Err(JinError::NotInitialized)));
     }
 }
@@ -0,0 +1,522 @@
+//! Implementation of `jin dedupe`
+//!
+//! Finds files whose content is identical in several layers (the same
+//! `settings.json` committed to three modes, say) and suggests keeping a
+//! single copy in the lowest layer they all share.
+//!
+//! A copy can only go away if the result doesn't change in any context where
+//! it applies: a layer between the shared layer and the copy holding a
+//! different version would otherwise start winning. Such copies are kept.
+//!
+//! `--fix` stages the consolidation as ordinary changes for `jin commit`.
+//! The staging index holds one entry per path and commits only reach the
+//! active context's layers, so a full consolidation may take several
+//! `jin dedupe --fix` / `jin commit` rounds.
+
+use crate::cli::DedupeArgs;
+use crate::core::limits::format_size;
+use crate::core::{JinError, Layer, ProjectContext, Result};
+use crate::git::refs::layer_name;
+use crate::git::{JinRepo, ObjectOps, RefOps, TreeOps};
+use crate::merge::get_applicable_layers;
+use crate::staging::attributes::ATTRIBUTES_PATH;
+use crate::staging::targets::TARGETS_PATH;
+use crate::staging::{StagedEntry, StagingIndex};
+use git2::Oid;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Content of every file in every shared layer, by layer name then path
+type LayerFiles = BTreeMap<String, HashMap<String, Oid>>;
+
+/// One file with identical content in several layers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Duplicate {
+    /// File path
+    pub path: String,
+    /// Blob shared by the copies
+    pub blob: Oid,
+    /// Size of the content in bytes
+    pub size: u64,
+    /// Layers holding the content, lowest precedence first
+    pub layers: Vec<String>,
+    /// What to do about it
+    pub plan: Plan,
+}
+
+/// Suggested consolidation for a [`Duplicate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Plan {
+    /// Keep one copy in `target` and drop the redundant ones
+    Consolidate {
+        /// Layer that keeps the file
+        target: String,
+        /// Whether `target` already holds the content
+        in_target: bool,
+        /// Copies that can be removed
+        remove: Vec<String>,
+        /// Copies that must stay, with the layer holding another version
+        keep: Vec<(String, String)>,
+    },
+    /// The shared layer holds a different version of the file
+    Blocked {
+        /// Layer shared by all copies
+        target: String,
+    },
+    /// Every copy overrides a different version below it, so none can go
+    Shadowed,
+}
+
+/// Execute the dedupe command
+///
+/// With no flags, only the report is printed.
+pub fn execute(args: DedupeArgs, root: &Path) -> Result<()> {
+    let repo = JinRepo::open(root).map_err(|_| JinError::NotInitialized)?;
+    let duplicates = find_duplicates(&repo)?;
+
+    if duplicates.is_empty() {
+        println!("No files are duplicated across layers");
+        return Ok(());
+    }
+
+    if args.fix {
+        fix(root, &duplicates)
+    } else {
+        report(&duplicates);
+        Ok(())
+    }
+}
+
+/// Print each duplicate with its suggested consolidation
+fn report(duplicates: &[Duplicate]) {
+    let mut removable = 0;
+    let mut reclaimable = 0;
+
+    for dup in duplicates {
+        println!(
+            "{} ({}, identical in {} layers)",
+            dup.path,
+            format_size(dup.size),
+            dup.layers.len()
+        );
+        println!("  layers: {}", dup.layers.join(", "));
+        match &dup.plan {
+            Plan::Consolidate {
+                target,
+                in_target,
+                remove,
+                keep,
+            } => {
+                if *in_target {
+                    println!(
+                        "  suggestion: keep it in {} and remove it from {}",
+                        target,
+                        remove.join(", ")
+                    );
+                } else {
+                    println!(
+                        "  suggestion: move it to {} and remove it from {}",
+                        target,
+                        remove.join(", ")
+                    );
+                    println!(
+                        "  note: {} also applies to contexts that don't have this file today",
+                        target
+                    );
+                }
+                for (layer, blocker) in keep {
+                    println!(
+                        "  keep: {} ({} has a different version in between)",
+                        layer, blocker
+                    );
+                }
+                let saved = remove.len() - usize::from(!in_target);
+                removable += saved;
+                reclaimable += dup.size * saved as u64;
+            }
+            Plan::Blocked { target } => println!(
+                "  cannot consolidate: {} has a different version of this file",
+                target
+            ),
+            Plan::Shadowed => println!(
+                "  cannot consolidate: the copies override different versions in lower layers"
+            ),
+        }
+        println!();
+    }
+
+    println!(
+        "{} duplicated file(s); consolidating removes {} redundant copies ({})",
+        duplicates.len(),
+        removable,
+        format_size(reclaimable)
+    );
+    println!("Run 'jin dedupe --fix' to stage the changes for the active context");
+}
+
+/// Stage what the active context allows: one change per path
+fn fix(root: &Path, duplicates: &[Duplicate]) -> Result<()> {
+    let context = ProjectContext::load(root)?;
+    let mut staging = StagingIndex::load(root).unwrap_or_else(|_| StagingIndex::new());
+
+    let active: HashMap<String, Layer> = get_applicable_layers(
+        context.mode.as_deref(),
+        context.scope.as_deref(),
+        context.project.as_deref(),
+    )
+    .into_iter()
+    .filter(|layer| !matches!(layer, Layer::UserLocal | Layer::WorkspaceActive))
+    .map(|layer| {
+        let ref_path = layer.ref_path(
+            context.mode.as_deref(),
+            context.scope.as_deref(),
+            context.project.as_deref(),
+        );
+        (layer_name(&ref_path).to_string(), layer)
+    })
+    .collect();
+
+    let mut staged = 0;
+    let mut remaining = 0;
+    for dup in duplicates {
+        let Plan::Consolidate {
+            target,
+            in_target,
+            remove,
+            ..
+        } = &dup.plan
+        else {
+            continue;
+        };
+        let path = PathBuf::from(&dup.path);
+        if staging.get(&path).is_some() {
+            println!("Skipped {}: already staged", dup.path);
+            remaining += 1;
+            continue;
+        }
+
+        let entry = if !in_target {
+            active.get(target).map(|layer| {
+                println!("Staged {} for {}", dup.path, target);
+                StagedEntry::new(path, *layer, dup.blob.to_string())
+            })
+        } else {
+            remove
+                .iter()
+                .find_map(|name| active.get(name).map(|layer| (name, layer)))
+                .map(|(name, layer)| {
+                    println!("Staged removal of {} from {}", dup.path, name);
+                    StagedEntry::delete(path, *layer)
+                })
+        };
+        match entry {
+            Some(entry) => {
+                staging.add(entry);
+                staged += 1;
+                if !in_target || remove.len() > 1 {
+                    remaining += 1;
+                }
+            }
+            None => remaining += 1,
+        }
+    }
+    staging.save(root)?;
+
+    println!();
+    println!(
+        "Staged {} change(s). Run 'jin commit' to record them.",
+        staged
+    );
+    if remaining > 0 {
+        println!(
+            "{} file(s) need more rounds: commit, then run 'jin dedupe --fix' again, \
+             switching mode or scope for copies outside the active context",
+            remaining
+        );
+    }
+    Ok(())
+}
+
+/// Find identical files across the shared layers and plan their removal
+pub fn find_duplicates(repo: &JinRepo) -> Result<Vec<Duplicate>> {
+    let files = layer_files(repo)?;
+
+    let mut groups: BTreeMap<(&str, Oid), Vec<&str>> = BTreeMap::new();
+    for (layer, layer_files) in &files {
+        for (path, blob) in layer_files {
+            groups.entry((path, *blob)).or_default().push(layer);
+        }
+    }
+
+    let mut duplicates = Vec::new();
+    for ((path, blob), mut layers) in groups {
+        if layers.len() < 2 {
+            continue;
+        }
+        layers.sort_by_key(|name| (precedence(name), name.to_string()));
+        let size = repo.find_blob(blob)?.size() as u64;
+        duplicates.push(Duplicate {
+            path: path.to_string(),
+            blob,
+            size,
+            layers: layers.iter().map(|l| l.to_string()).collect(),
+            plan: plan(&files, path, blob, &layers),
+        });
+    }
+    Ok(duplicates)
+}
+
+/// Read the file list of every shared layer
+///
+/// User-local and workspace layers are never shared, so they're left out.
+fn layer_files(repo: &JinRepo) -> Result<LayerFiles> {
+    let mut files = LayerFiles::new();
+    for ref_path in repo.list_refs("refs/jin/layers/**")? {
+        let name = layer_name(&ref_path);
+        if name == "local" || name == "workspace" || name.starts_with("local/") {
+            continue;
+        }
+        let tree = repo.find_commit(repo.resolve_ref(&ref_path)?)?.tree_id();
+
+        let mut layer_files = HashMap::new();
+        repo.walk_tree_pre(tree, |dir, entry| {
+            if let Some(name) = entry.name() {
+                if entry.kind() == Some(git2::ObjectType::Blob) {
+                    let path = format!("{}{}", dir, name);
+                    if path != TARGETS_PATH && path != ATTRIBUTES_PATH {
+                        layer_files.insert(path, entry.id());
+                    }
+                }
+            }
+            git2::TreeWalkResult::Ok
+        })?;
+        files.insert(name.to_string(), layer_files);
+    }
+    Ok(files)
+}
+
+/// Plan the consolidation of `layers`, which all hold `blob` at `path`
+fn plan(files: &LayerFiles, path: &str, blob: Oid, layers: &[&str]) -> Plan {
+    let chains: Vec<Vec<String>> = layers.iter().map(|layer| chain(layer)).collect();
+    let shared = chains[0]
+        .iter()
+        .enumerate()
+        .take_while(|(i, name)| chains.iter().all(|c| c.get(*i) == Some(name)))
+        .count();
+    let target = chains[0][shared - 1].clone();
+
+    let version = |layer: &str| files.get(layer).and_then(|f| f.get(path)).copied();
+    if version(&target).is_some_and(|other| other != blob) {
+        return Plan::Blocked { target };
+    }
+    let in_target = layers.contains(&target.as_str());
+
+    let mut remove = Vec::new();
+    let mut keep = Vec::new();
+    for layer in layers.iter().filter(|l| **l != target) {
+        let blocker = files.keys().find(|other| {
+            precedence(other) > precedence(&target)
+                && precedence(other) < precedence(layer)
+                && compatible(other, layer)
+                && version(other).is_some_and(|v| v != blob)
+        });
+        match blocker {
+            Some(other) => keep.push((layer.to_string(), other.clone())),
+            None => remove.push(layer.to_string()),
+        }
+    }
+
+    // Moving a file into the shared layer only pays off if that frees more
+    // than one copy
+    if remove.is_empty() || (!in_target && remove.len() < 2) {
+        return Plan::Shadowed;
+    }
+    Plan::Consolidate {
+        target,
+        in_target,
+        remove,
+        keep,
+    }
+}
+
+/// Mode, scope and project a layer name belongs to
+fn parts(name: &str) -> (Option<&str>, Option<&str>, Option<&str>) {
+    let (rest, project) = match name.rsplit_once("project/") {
+        Some((rest, project)) if rest.is_empty() || rest.ends_with('/') => {
+            (rest.trim_end_matches('/'), Some(project))
+        }
+        _ => (name, None),
+    };
+    if let Some(scope) = rest.strip_prefix("scope/") {
+        return (None, Some(scope), project);
+    }
+    match rest.strip_prefix("mode/") {
+        Some(mode_rest) => match mode_rest.split_once("/scope/") {
+            Some((mode, scope)) => (Some(mode), Some(scope), project),
+            None => (Some(mode_rest), None, project),
+        },
+        None => (None, None, project),
+    }
+}
+
+/// Layer kind of a layer name
+fn layer_kind(name: &str) -> Layer {
+    match parts(name) {
+        (Some(_), Some(_), Some(_)) => Layer::ModeScopeProject,
+        (Some(_), Some(_), None) => Layer::ModeScope,
+        (Some(_), None, Some(_)) => Layer::ModeProject,
+        (Some(_), None, None) => Layer::ModeBase,
+        (None, Some(_), _) => Layer::ScopeBase,
+        (None, None, Some(_)) => Layer::ProjectBase,
+        (None, None, None) => Layer::GlobalBase,
+    }
+}
+
+/// Merge precedence of a layer name
+fn precedence(name: &str) -> u8 {
+    layer_kind(name).precedence()
+}
+
+/// Layers applied below `name` in every context where `name` applies,
+/// lowest first and ending with `name` itself
+fn chain(name: &str) -> Vec<String> {
+    let mut chain = vec!["global".to_string()];
+    if let (Some(mode), scope, project) = parts(name) {
+        chain.push(format!("mode/{}", mode));
+        if let (Some(scope), Some(_)) = (scope, project) {
+            chain.push(format!("mode/{}/scope/{}", mode, scope));
+        }
+    }
+    if name != "global" && chain.last().map(String::as_str) != Some(name) {
+        chain.push(name.to_string());
+    }
+    chain
+}
+
+/// Whether two layers can apply in the same context
+fn compatible(a: &str, b: &str) -> bool {
+    let (a, b) = (parts(a), parts(b));
+    let same = |x: Option<&str>, y: Option<&str>| x.is_none() || y.is_none() || x == y;
+    same(a.0, b.0) && same(a.1, b.1) && same(a.2, b.2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oid(n: u8) -> Oid {
+        Oid::from_bytes(&[n; 20]).unwrap()
+    }
+
+    fn files(entries: &[(&str, u8)]) -> LayerFiles {
+        entries
+            .iter()
+            .map(|(layer, n)| {
+                let mut layer_files = HashMap::new();
+                layer_files.insert("settings.json".to_string(), oid(*n));
+                (layer.to_string(), layer_files)
+            })
+            .collect()
+    }
+
+    fn files_with_global(n: u8) -> LayerFiles {
+        files(&[("global", n), ("mode/claude", 1), ("mode/cursor", 1)])
+    }
+
+    #[test]
+    fn test_chain() {
+        assert_eq!(chain("global"), vec!["global"]);
+        assert_eq!(chain("mode/claude"), vec!["global", "mode/claude"]);
+        assert_eq!(chain("scope/lang/rust"), vec!["global", "scope/lang/rust"]);
+        assert_eq!(chain("project/ui"), vec!["global", "project/ui"]);
+        assert_eq!(
+            chain("mode/claude/scope/lang/rust/project/ui"),
+            vec![
+                "global",
+                "mode/claude",
+                "mode/claude/scope/lang/rust",
+                "mode/claude/scope/lang/rust/project/ui"
+            ]
+        );
+        assert_eq!(
+            chain("mode/claude/project/ui"),
+            vec!["global", "mode/claude", "mode/claude/project/ui"]
+        );
+        assert_eq!(layer_kind("mode/claude/scope/lang/rust"), Layer::ModeScope);
+    }
+
+    #[test]
+    fn test_plan_moves_to_shared_layer() {
+        let files = files(&[("mode/claude", 1), ("mode/cursor", 1)]);
+        assert_eq!(
+            plan(
+                &files,
+                "settings.json",
+                oid(1),
+                &["mode/claude", "mode/cursor"]
+            ),
+            Plan::Consolidate {
+                target: "global".to_string(),
+                in_target: false,
+                remove: vec!["mode/claude".to_string(), "mode/cursor".to_string()],
+                keep: vec![],
+            }
+        );
+
+        let files = files_with_global(2);
+        assert_eq!(
+            plan(
+                &files,
+                "settings.json",
+                oid(1),
+                &["mode/claude", "mode/cursor"]
+            ),
+            Plan::Blocked {
+                target: "global".to_string()
+            }
+        );
+
+        let files = files_with_global(1);
+        assert_eq!(
+            plan(
+                &files,
+                "settings.json",
+                oid(1),
+                &["global", "mode/claude", "mode/cursor"]
+            ),
+            Plan::Consolidate {
+                target: "global".to_string(),
+                in_target: true,
+                remove: vec!["mode/claude".to_string(), "mode/cursor".to_string()],
+                keep: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_plan_keeps_copies_shadowing_another_version() {
+        let files = files(&[
+            ("mode/claude", 1),
+            ("mode/claude/scope/lang", 2),
+            ("mode/claude/scope/lang/project/ui", 1),
+            ("mode/claude/scope/other", 1),
+        ]);
+        let layers = [
+            "mode/claude",
+            "mode/claude/scope/other",
+            "mode/claude/scope/lang/project/ui",
+        ];
+        assert_eq!(
+            plan(&files, "settings.json", oid(1), &layers),
+            Plan::Consolidate {
+                target: "mode/claude".to_string(),
+                in_target: true,
+                remove: vec!["mode/claude/scope/other".to_string()],
+                keep: vec![(
+                    "mode/claude/scope/lang/project/ui".to_string(),
+                    "mode/claude/scope/lang".to_string()
+                )],
+            }
+        );
+    }
+}
@@ -1,22 +1,58 @@
 //! Implementation of `jin diff`
 //!
 //! Shows differences between layers, workspace, or staged changes.
+//!
+//! Like `git diff`, `--name-only` lists just the changed paths and
+//! `--exit-code` makes the command exit with status 1 when differences
+//! exist and 2 when the diff fails, so scripts and CI can gate on whether
+//! the workspace has drifted without mistaking an error for drift.
+//!
+//! Output is paged and colored like `git diff` (see [`crate::core::pager`]).
+//! When a run of removed lines is followed by added lines, the words that
+//! changed within each pair of lines are highlighted, which makes a single
+//! edited value in a long config line easy to spot. `-U <n>` sets the
+//! context lines, and `-w`/`--ignore-blank-lines` hide whitespace-only and
+//! blank-line changes (files differing only that way count as unchanged).
+//!
+//! With `--json`, the changed files and their patches are printed as a
+//! versioned JSON document instead (see `jin schema diff`); `--name-only`
+//! leaves the patches out.
 
 use crate::cli::DiffArgs;
+use crate::core::pager::{Pager, Palette};
+use crate::core::schema::{self, JsonSurface};
 use crate::core::{JinError, Layer, ProjectContext, Result};
 use crate::git::{JinRepo, TreeOps};
 use crate::merge::{get_applicable_layers, merge_layers, LayerMergeConfig};
-use crate::staging::StagingIndex;
 use crate::staging::WorkspaceMetadata;
-use git2::{DiffFormat, DiffOptions};
+use crate::staging::{StagedOperation, StagingIndex};
+use git2::DiffOptions;
+use serde::Serialize;
+use std::io::Write;
 use std::path::Path;
+use std::process::ExitCode;
 
 /// Execute the diff command
 ///
-/// Shows differences between layers.
-pub fn execute(args: DiffArgs) -> Result<()> {
+/// Shows differences between layers. With `--exit-code`, the returned
+/// status is 1 when any difference was found, and errors are reported with
+/// status 2 instead of being returned.
+pub fn execute(args: DiffArgs, root: &Path) -> Result<ExitCode> {
+    match diff(root, &args) {
+        Ok(true) if args.exit_code => Ok(ExitCode::from(1)),
+        Ok(_) => Ok(ExitCode::SUCCESS),
+        Err(e) if args.exit_code => {
+            eprintln!("Error: {}", e);
+            Ok(ExitCode::from(2))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Show the requested differences, returning whether there were any
+fn diff(root: &Path, args: &DiffArgs) -> Result<bool> {
     // Load project context
-    let context = match ProjectContext::load() {
+    let context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -25,54 +61,214 @@ pub fn execute(args: DiffArgs) -> Result<()> {
     };
 
     // Open Jin repository
-    let repo = JinRepo::open_or_create()?;
+    let repo = JinRepo::open_or_create(root)?;
     let git_repo = repo.inner();
 
+    let mut out = if schema::json_output() {
+        DiffOutput::Json {
+            files: Vec::new(),
+            patches: !args.name_only,
+        }
+    } else {
+        DiffOutput::Text {
+            name_only: args.name_only,
+        }
+    };
+    let palette = args.color.palette();
+    let style = DiffStyle {
+        context_lines: args.unified,
+        ignore_all_space: args.ignore_all_space,
+        ignore_blank_lines: args.ignore_blank_lines,
+        palette,
+    };
+    let pager = Pager::start();
+
     // Determine diff mode
-    if args.staged {
+    let (from, to, has_changes) = if args.staged {
         // Show staged changes
-        show_staged_diff(git_repo, &context)?;
+        let changed = show_staged_diff(root, git_repo, &context, &mut out)?;
+        ("layers".to_string(), "staging".to_string(), changed)
     } else if let (Some(layer1_name), Some(layer2_name)) = (&args.layer1, &args.layer2) {
         // Compare two specific layers
         let layer1 = parse_layer_name(layer1_name)?;
         let layer2 = parse_layer_name(layer2_name)?;
-        diff_layers(git_repo, layer1, layer2, &context)?;
+        let changed = diff_layers(git_repo, layer1, layer2, &context, &mut out, style)?;
+        (layer1.to_string(), layer2.to_string(), changed)
     } else if let Some(layer_name) = &args.layer1 {
         // Compare workspace vs specified layer
         let layer = parse_layer_name(layer_name)?;
-        diff_workspace_vs_layer(git_repo, layer, &context)?;
+        let changed = diff_workspace_vs_layer(root, git_repo, layer, &context, &mut out, style)?;
+        (layer.to_string(), "workspace".to_string(), changed)
     } else {
         // Default: compare workspace vs workspace-active (merged layers)
-        diff_workspace_vs_workspace_active(git_repo, &context)?;
+        let changed =
+            diff_workspace_vs_workspace_active(root, git_repo, &context, &mut out, style)?;
+        (
+            "workspace-active".to_string(),
+            "workspace".to_string(),
+            changed,
+        )
+    };
+
+    if let DiffOutput::Json { files, .. } = out {
+        schema::print_json(&DiffReport {
+            schema_version: JsonSurface::Diff.version(),
+            from,
+            to,
+            changed: has_changes,
+            files,
+        })?;
     }
 
-    Ok(())
+    drop(pager);
+    std::io::stdout().flush()?;
+    Ok(has_changes)
+}
+
+/// `jin diff --json` output
+#[derive(Debug, Serialize)]
+struct DiffReport {
+    schema_version: u32,
+    from: String,
+    to: String,
+    changed: bool,
+    files: Vec<FileChange>,
+}
+
+/// A file that differs between the two sides
+#[derive(Debug, Serialize)]
+struct FileChange {
+    path: String,
+    /// `modified`, `added` (only in the second side), `deleted` (only in
+    /// the first) or `renamed`
+    status: &'static str,
+    /// Layer a staged file will be committed to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    layer: Option<String>,
+    /// Unified diff of the file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    patch: Option<String>,
+}
+
+/// Where differences go: printed, or collected for `--json`
+enum DiffOutput {
+    Text {
+        name_only: bool,
+    },
+    Json {
+        files: Vec<FileChange>,
+        patches: bool,
+    },
+}
+
+impl DiffOutput {
+    /// Whether headers, file details and messages are printed
+    fn verbose(&self) -> bool {
+        matches!(self, DiffOutput::Text { name_only: false })
+    }
+
+    /// Report a changed file
+    ///
+    /// Returns whether the caller should print the file's details.
+    fn file(
+        &mut self,
+        path: &str,
+        status: &'static str,
+        layer: Option<Layer>,
+        patch: Option<&mut git2::Patch>,
+    ) -> Result<bool> {
+        match self {
+            DiffOutput::Text { name_only: true } => {
+                println!("{}", path);
+                Ok(false)
+            }
+            DiffOutput::Text { name_only: false } => Ok(true),
+            DiffOutput::Json { files, patches } => {
+                let patch = match patch {
+                    Some(patch) if *patches => {
+                        Some(String::from_utf8_lossy(&patch.to_buf()?).into_owned())
+                    }
+                    _ => None,
+                };
+                files.push(FileChange {
+                    path: path.to_string(),
+                    status,
+                    layer: layer.map(|layer| layer.to_string()),
+                    patch,
+                });
+                Ok(false)
+            }
+        }
+    }
+}
+
+/// How text differences are computed and shown
+#[derive(Debug, Clone, Copy)]
+struct DiffStyle {
+    /// Lines of context around each change (`-U`)
+    context_lines: u32,
+    /// Ignore whitespace when comparing lines (`-w`)
+    ignore_all_space: bool,
+    /// Ignore changes whose lines are all blank
+    ignore_blank_lines: bool,
+    palette: Palette,
+}
+
+impl DiffStyle {
+    fn options(&self) -> DiffOptions {
+        let mut opts = DiffOptions::new();
+        opts.context_lines(self.context_lines)
+            .ignore_whitespace(self.ignore_all_space)
+            .ignore_blank_lines(self.ignore_blank_lines);
+        opts
+    }
 }
 
 /// Show staged changes
-fn show_staged_diff(_repo: &git2::Repository, _context: &ProjectContext) -> Result<()> {
-    let staging = StagingIndex::load().unwrap_or_else(|_| StagingIndex::new());
+///
+/// Returns whether anything is staged.
+fn show_staged_diff(
+    root: &Path,
+    _repo: &git2::Repository,
+    _context: &ProjectContext,
+    out: &mut DiffOutput,
+) -> Result<bool> {
+    let staging = StagingIndex::load(root).unwrap_or_else(|_| StagingIndex::new());
 
     if staging.is_empty() {
-        println!("No staged changes");
-        return Ok(());
+        if out.verbose() {
+            println!("No staged changes");
+        }
+        return Ok(false);
     }
 
-    println!("Staged changes:");
-    println!();
+    if out.verbose() {
+        println!("Staged changes:");
+        println!();
+    }
 
     // Show each staged file
     for entry in staging.entries() {
         let path = &entry.path;
-        println!("  {} -> {}", path.display(), entry.target_layer);
+        let status = match entry.operation {
+            StagedOperation::AddOrModify => "modified",
+            StagedOperation::Delete => "deleted",
+            StagedOperation::Rename => "renamed",
+        };
+        let display = path.display().to_string();
+        if !out.file(&display, status, Some(entry.target_layer), None)? {
+            continue;
+        }
+        println!("  {} -> {}", display, entry.target_layer);
 
         // Try to show diff if file exists in workspace
-        if path.exists() {
+        let location = root.join(path);
+        if location.exists() {
             // Get blob from Jin repo
             if let Ok(oid) = git2::Oid::from_str(&entry.content_hash) {
                 if let Ok(blob) = _repo.find_blob(oid) {
                     // Read workspace content
-                    if let Ok(workspace_content) = std::fs::read(path) {
+                    if let Ok(workspace_content) = std::fs::read(&location) {
                         // Compare
                         if blob.content() != workspace_content.as_slice() {
                             println!("    (modified since staging)");
@@ -83,16 +279,20 @@ fn show_staged_diff(_repo: &git2::Repository, _context: &ProjectContext) -> Resu
         }
     }
 
-    Ok(())
+    Ok(true)
 }
 
 /// Diff two specific layers
+///
+/// Returns whether the layers differ.
 fn diff_layers(
     repo: &git2::Repository,
     layer1: Layer,
     layer2: Layer,
     context: &ProjectContext,
-) -> Result<()> {
+    out: &mut DiffOutput,
+    style: DiffStyle,
+) -> Result<bool> {
     let ref1 = layer1.ref_path(
         context.mode.as_deref(),
         context.scope.as_deref(),
@@ -120,32 +320,82 @@ fn diff_layers(
     };
 
     // Create diff
-    let mut opts = DiffOptions::new();
-    opts.context_lines(3);
-
-    let diff = repo.diff_tree_to_tree(Some(&tree1), Some(&tree2), Some(&mut opts))?;
+    let diff = repo.diff_tree_to_tree(Some(&tree1), Some(&tree2), Some(&mut style.options()))?;
+
+    // Files whose only changes were ignored still show up as deltas
+    let mut changed = Vec::new();
+    for idx in 0..diff.deltas().len() {
+        if let Some(patch) = git2::Patch::from_diff(&diff, idx)? {
+            if shows_changes(&patch) {
+                changed.push(idx);
+            }
+        }
+    }
 
-    if diff.deltas().count() == 0 {
-        println!("No differences between {} and {}", layer1, layer2);
-        return Ok(());
+    if changed.is_empty() {
+        if out.verbose() {
+            println!("No differences between {} and {}", layer1, layer2);
+        }
+        return Ok(false);
     }
 
     // Print diff header
-    println!("diff --jin a/{} b/{}", layer1, layer2);
-    println!();
+    if out.verbose() {
+        println!(
+            "{}",
+            style
+                .palette
+                .bold(&format!("diff --jin a/{} b/{}", layer1, layer2))
+        );
+        println!();
+    }
 
     // Print diff
-    print_diff(&diff)?;
+    let mut printer = LinePrinter::new(style.palette);
+    for idx in changed {
+        let Some(mut patch) = git2::Patch::from_diff(&diff, idx)? else {
+            continue;
+        };
+        let delta = patch.delta();
+        let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+            continue;
+        };
+        let path = path.display().to_string();
+        let status = match delta.status() {
+            git2::Delta::Added => "added",
+            git2::Delta::Deleted => "deleted",
+            git2::Delta::Renamed => "renamed",
+            _ => "modified",
+        };
+        if out.file(&path, status, None, Some(&mut patch))? {
+            patch.print(&mut |_delta, _hunk, line| {
+                printer.line(&line);
+                true
+            })?;
+        }
+    }
+    printer.flush();
 
-    Ok(())
+    Ok(true)
+}
+
+/// Whether a patch shows any change once ignored changes are left out
+fn shows_changes(patch: &git2::Patch) -> bool {
+    let delta = patch.delta();
+    patch.num_hunks() > 0 || delta.flags().is_binary() || delta.status() != git2::Delta::Modified
 }
 
 /// Diff workspace vs specific layer
+///
+/// Returns whether the workspace differs from the layer.
 fn diff_workspace_vs_layer(
+    root: &Path,
     repo: &git2::Repository,
     layer: Layer,
     context: &ProjectContext,
-) -> Result<()> {
+    out: &mut DiffOutput,
+    style: DiffStyle,
+) -> Result<bool> {
     let ref_path = layer.ref_path(
         context.mode.as_deref(),
         context.scope.as_deref(),
@@ -162,11 +412,13 @@ fn diff_workspace_vs_layer(
 
     let tree_id = tree.id();
 
-    println!("Comparing workspace vs {}", layer);
-    println!();
+    if out.verbose() {
+        println!("Comparing workspace vs {}", layer);
+        println!();
+    }
 
     // Collect all files in the layer tree
-    let jin_repo = JinRepo::open()?;
+    let jin_repo = JinRepo::open(root)?;
     let layer_files = jin_repo.list_tree_files(tree_id)?;
 
     let mut has_changes = false;
@@ -181,134 +433,310 @@ fn diff_workspace_vs_layer(
         };
 
         // Check if file exists in workspace
-        if path.exists() {
+        let location = root.join(path);
+        if location.exists() {
             // Read workspace content
-            let workspace_content = match std::fs::read(path) {
+            let workspace_content = match std::fs::read(&location) {
                 Ok(content) => content,
                 Err(_) => continue,
             };
 
             // Compare contents
             if layer_content != workspace_content {
+                let mut patch = git2::Patch::from_buffers(
+                    &layer_content,
+                    Some(path),
+                    &workspace_content,
+                    Some(path),
+                    Some(&mut style.options()),
+                )?;
+                if !shows_changes(&patch) {
+                    continue;
+                }
                 has_changes = true;
+                if !out.file(&file_path, "modified", None, Some(&mut patch))? {
+                    continue;
+                }
 
-                // Generate diff between layer and workspace
-                let layer_str = String::from_utf8_lossy(&layer_content);
-                let workspace_str = String::from_utf8_lossy(&workspace_content);
-
-                println!("--- a/{} (layer)", file_path);
-                println!("+++ b/{} (workspace)", file_path);
-
-                // Print a simple line-by-line diff
-                let layer_lines: Vec<&str> = layer_str.lines().collect();
-                let workspace_lines: Vec<&str> = workspace_str.lines().collect();
-
-                print_text_diff(&layer_lines, &workspace_lines);
+                println!(
+                    "{}",
+                    style.palette.bold(&format!("--- a/{} (layer)", file_path))
+                );
+                println!(
+                    "{}",
+                    style
+                        .palette
+                        .bold(&format!("+++ b/{} (workspace)", file_path))
+                );
+                print_hunks(&mut patch, style.palette)?;
                 println!();
             }
         } else {
             // File exists in layer but not in workspace
             has_changes = true;
-            println!("Only in {}: {}", layer, file_path);
-            println!();
+            if out.file(&file_path, "deleted", None, None)? {
+                println!("Only in {}: {}", layer, file_path);
+                println!();
+            }
         }
     }
 
-    if !has_changes {
+    if !has_changes && out.verbose() {
         println!("No differences between workspace and {}", layer);
     }
 
+    Ok(has_changes)
+}
+
+/// Print the hunks of a patch, without its file header
+fn print_hunks(patch: &mut git2::Patch, palette: Palette) -> Result<()> {
+    if patch.delta().flags().is_binary() {
+        println!("Binary files differ");
+        return Ok(());
+    }
+    let mut printer = LinePrinter::new(palette);
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, lines) = patch.hunk(hunk_idx)?;
+        printer.flush();
+        let header = String::from_utf8_lossy(hunk.header());
+        println!("{}", palette.cyan(header.trim_end()));
+        for line_idx in 0..lines {
+            printer.line(&patch.line_in_hunk(hunk_idx, line_idx)?);
+        }
+    }
+    printer.flush();
     Ok(())
 }
 
-/// Print a simple line-by-line diff for text files
-fn print_text_diff(old_lines: &[&str], new_lines: &[&str]) {
-    // Simple line-by-line comparison with unified diff output
-    let mut old_idx = 0;
-    let mut new_idx = 0;
+/// Longest line, in tokens, that gets word-level highlighting
+const MAX_HIGHLIGHT_TOKENS: usize = 200;
 
-    while old_idx < old_lines.len() || new_idx < new_lines.len() {
-        let old_line = if old_idx < old_lines.len() {
-            old_lines[old_idx]
-        } else {
-            ""
-        };
-        let new_line = if new_idx < new_lines.len() {
-            new_lines[new_idx]
-        } else {
-            ""
-        };
+/// Prints patch lines, highlighting the words that changed between a run
+/// of removed lines and the added lines right after it
+///
+/// The n-th removed line is compared with the n-th added line, like git's
+/// diff-highlight. Pairs with nothing in common are shown as plain
+/// removals and additions.
+struct LinePrinter {
+    palette: Palette,
+    removed: Vec<String>,
+    added: Vec<String>,
+    /// The last removed line had no newline at end of file
+    removed_eof: bool,
+    /// The last added line had no newline at end of file
+    added_eof: bool,
+}
 
-        if old_line == new_line {
-            // Lines are equal
-            println!(" {}", old_line);
-            old_idx += 1;
-            new_idx += 1;
-        } else {
-            // Lines differ - find the next match
-            let old_next = find_next_match(old_idx, old_lines, new_idx, new_lines);
-            let new_next = find_next_match(new_idx, new_lines, old_idx, old_lines);
-
-            // Print deletions from old
-            while old_idx < old_lines.len() && (old_idx < old_next.0 || old_next.0 == usize::MAX) {
-                println!("\x1b[31m-{}\x1b[0m", old_lines[old_idx]);
-                old_idx += 1;
+/// Marker git prints after a last line without newline
+const NO_NEWLINE: &str = "\\ No newline at end of file";
+
+impl LinePrinter {
+    fn new(palette: Palette) -> Self {
+        Self {
+            palette,
+            removed: Vec::new(),
+            added: Vec::new(),
+            removed_eof: false,
+            added_eof: false,
+        }
+    }
+
+    /// Print one line of a patch, or hold it until its run is complete
+    fn line(&mut self, line: &git2::DiffLine) {
+        let text = || {
+            let content = String::from_utf8_lossy(line.content());
+            content.strip_suffix('\n').unwrap_or(&content).to_string()
+        };
+        match line.origin() {
+            '-' => {
+                if !self.added.is_empty() {
+                    self.flush();
+                }
+                self.removed.push(text());
+            }
+            '+' => self.added.push(text()),
+            '<' => self.removed_eof = true,
+            '>' => self.added_eof = true,
+            '=' => {
+                self.flush();
+                println!("{}", NO_NEWLINE);
+            }
+            _ => {
+                self.flush();
+                print_diff_line(line, self.palette);
             }
+        }
+    }
 
-            // Print insertions from new
-            while new_idx < new_lines.len() && (new_idx < new_next.0 || new_next.0 == usize::MAX) {
-                println!("\x1b[32m+{}\x1b[0m", new_lines[new_idx]);
-                new_idx += 1;
+    /// Print the held removed and added lines
+    fn flush(&mut self) {
+        let palette = self.palette;
+        let pairs: Vec<Option<(String, String)>> = self
+            .removed
+            .iter()
+            .zip(&self.added)
+            .map(|(old, new)| highlight_words(old, new, palette))
+            .collect();
+
+        for (i, old) in self.removed.iter().enumerate() {
+            match pairs.get(i) {
+                Some(Some((old, _))) => println!("{}", old),
+                _ => println!("{}", palette.red(&format!("-{}", old))),
             }
         }
+        if std::mem::take(&mut self.removed_eof) {
+            println!("{}", NO_NEWLINE);
+        }
+        for (i, new) in self.added.iter().enumerate() {
+            match pairs.get(i) {
+                Some(Some((_, new))) => println!("{}", new),
+                _ => println!("{}", palette.green(&format!("+{}", new))),
+            }
+        }
+        if std::mem::take(&mut self.added_eof) {
+            println!("{}", NO_NEWLINE);
+        }
+        self.removed.clear();
+        self.added.clear();
     }
 }
 
-/// Find the next matching line between two sequences
-fn find_next_match(
-    current_idx: usize,
-    current_lines: &[&str],
-    other_idx: usize,
-    other_lines: &[&str],
-) -> (usize, usize) {
-    let search_radius = 5; // Look ahead up to 5 lines
-
-    for i in 0..=search_radius {
-        let curr_pos = current_idx + i;
-        if curr_pos >= current_lines.len() {
-            break;
-        }
-        let curr_line = current_lines[curr_pos];
-
-        for j in 0..=search_radius {
-            let other_pos = other_idx + j;
-            if other_pos >= other_lines.len() {
-                break;
+/// Color a removed/added line pair, highlighting the words that differ
+///
+/// Returns `None` when the lines share no words or are too long to compare.
+fn highlight_words(old: &str, new: &str, palette: Palette) -> Option<(String, String)> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    if old_tokens.len() > MAX_HIGHLIGHT_TOKENS || new_tokens.len() > MAX_HIGHLIGHT_TOKENS {
+        return None;
+    }
+    let (old_changed, new_changed) = changed_tokens(&old_tokens, &new_tokens);
+    let shares_words = old_tokens
+        .iter()
+        .zip(&old_changed)
+        .any(|(token, changed)| !changed && !token.trim().is_empty());
+    if !shares_words {
+        return None;
+    }
+
+    let paint = |prefix: &str, tokens: &[&str], changed: &[bool], removed: bool| {
+        let mut out = String::new();
+        let mut run = prefix.to_string();
+        let mut run_changed = false;
+        let emit = |run: &str, run_changed: bool, out: &mut String| {
+            if run.is_empty() {
+                return;
             }
-            if curr_line == other_lines[other_pos] {
-                return (curr_pos, other_pos);
+            out.push_str(&match (removed, run_changed) {
+                (true, false) => palette.red(run),
+                (true, true) => palette.red_highlight(run),
+                (false, false) => palette.green(run),
+                (false, true) => palette.green_highlight(run),
+            });
+        };
+        for (token, &is_changed) in tokens.iter().zip(changed) {
+            if is_changed != run_changed {
+                emit(&run, run_changed, &mut out);
+                run.clear();
+                run_changed = is_changed;
             }
+            run.push_str(token);
+        }
+        emit(&run, run_changed, &mut out);
+        out
+    };
+
+    Some((
+        paint("-", &old_tokens, &old_changed, true),
+        paint("+", &new_tokens, &new_changed, false),
+    ))
+}
+
+/// Split a line into words, runs of whitespace and single punctuation marks
+fn tokenize(line: &str) -> Vec<&str> {
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() {
+            1
+        } else {
+            2
+        }
+    };
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut previous = None;
+    for (i, c) in line.char_indices() {
+        let current = class(c);
+        if i > start && (previous != Some(current) || current == 2) {
+            tokens.push(&line[start..i]);
+            start = i;
+        }
+        previous = Some(current);
+    }
+    if start < line.len() {
+        tokens.push(&line[start..]);
+    }
+    tokens
+}
+
+/// Mark the tokens of each side that are not part of their longest common
+/// subsequence
+fn changed_tokens(old: &[&str], new: &[&str]) -> (Vec<bool>, Vec<bool>) {
+    // lengths[i][j] = LCS length of old[i..] and new[j..]
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
         }
     }
 
-    (usize::MAX, usize::MAX)
+    let mut old_changed = vec![true; old.len()];
+    let mut new_changed = vec![true; new.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            old_changed[i] = false;
+            new_changed[j] = false;
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    (old_changed, new_changed)
 }
 
 /// Diff workspace vs workspace-active (merged layers)
+///
+/// Returns whether the workspace differs from the merged layers.
 fn diff_workspace_vs_workspace_active(
+    root: &Path,
     _repo: &git2::Repository,
     context: &ProjectContext,
-) -> Result<()> {
-    println!("Comparing workspace vs workspace-active");
-    println!();
+    out: &mut DiffOutput,
+    style: DiffStyle,
+) -> Result<bool> {
+    if out.verbose() {
+        println!("Comparing workspace vs workspace-active");
+        println!();
+    }
 
     // Check if workspace metadata exists
-    let metadata = match WorkspaceMetadata::load() {
+    let metadata = match WorkspaceMetadata::load(root) {
         Ok(m) => m,
         Err(JinError::NotFound(_)) => {
-            println!("No workspace metadata found.");
-            println!("Run 'jin apply' to create an initial workspace state.");
-            return Ok(());
+            if out.verbose() {
+                println!("No workspace metadata found.");
+                println!("Run 'jin apply' to create an initial workspace state.");
+            }
+            return Ok(false);
         }
         Err(e) => return Err(e),
     };
@@ -321,19 +749,22 @@ fn diff_workspace_vs_workspace_active(
     );
 
     // Merge layers to get workspace-active content
-    let jin_repo = JinRepo::open()?;
+    let jin_repo = JinRepo::open(root)?;
     let config = LayerMergeConfig {
         layers,
         mode: context.mode.clone(),
         scope: context.scope.clone(),
         project: context.project.clone(),
+        root: root.to_path_buf(),
     };
 
     let merged = match merge_layers(&config, &jin_repo) {
         Ok(m) => m,
         Err(JinError::NotFound(_)) => {
-            println!("No layers found to merge.");
-            return Ok(());
+            if out.verbose() {
+                println!("No layers found to merge.");
+            }
+            return Ok(false);
         }
         Err(e) => return Err(e),
     };
@@ -343,34 +774,60 @@ fn diff_workspace_vs_workspace_active(
     // Compare each merged file to actual workspace file
     for (path, merged_file) in &merged.merged_files {
         // Serialize merged content to string
-        let merged_str = match serialize_merged_content(merged_file) {
+        let merged_str = match serialize_merged_content(root, path, merged_file) {
             Ok(s) => s,
             Err(_) => continue,
         };
 
-        // Read workspace file
-        let workspace_str = match std::fs::read_to_string(path) {
+        // Read workspace file (or wherever it was applied)
+        let workspace_str = match std::fs::read_to_string(root.join(metadata.location(path))) {
             Ok(s) => s,
             Err(_) => {
                 // File doesn't exist in workspace
                 has_changes = true;
-                println!("Only in workspace-active: {}", path.display());
-                println!();
+                if out.file(&path.display().to_string(), "deleted", None, None)? {
+                    println!("Only in workspace-active: {}", path.display());
+                    println!();
+                }
                 continue;
             }
         };
 
         // Compare contents
         if merged_str != workspace_str {
+            let mut patch = git2::Patch::from_buffers(
+                merged_str.as_bytes(),
+                Some(path),
+                workspace_str.as_bytes(),
+                Some(path),
+                Some(&mut style.options()),
+            )?;
+            if !shows_changes(&patch) {
+                continue;
+            }
             has_changes = true;
+            if !out.file(
+                &path.display().to_string(),
+                "modified",
+                None,
+                Some(&mut patch),
+            )? {
+                continue;
+            }
 
-            println!("--- a/{} (workspace-active)", path.display());
-            println!("+++ b/{} (workspace)", path.display());
-
-            let merged_lines: Vec<&str> = merged_str.lines().collect();
-            let workspace_lines: Vec<&str> = workspace_str.lines().collect();
-
-            print_text_diff(&merged_lines, &workspace_lines);
+            println!(
+                "{}",
+                style
+                    .palette
+                    .bold(&format!("--- a/{} (workspace-active)", path.display()))
+            );
+            println!(
+                "{}",
+                style
+                    .palette
+                    .bold(&format!("+++ b/{} (workspace)", path.display()))
+            );
+            print_hunks(&mut patch, style.palette)?;
             println!();
         }
     }
@@ -379,58 +836,56 @@ fn diff_workspace_vs_workspace_active(
     for path in metadata.files.keys() {
         if !merged.merged_files.contains_key(path) {
             has_changes = true;
-            println!("Only in workspace: {}", path.display());
-            println!();
+            if out.file(&path.display().to_string(), "added", None, None)? {
+                println!("Only in workspace: {}", path.display());
+                println!();
+            }
         }
     }
 
-    if !has_changes {
+    if !has_changes && out.verbose() {
         println!("No differences between workspace and workspace-active");
     }
 
-    Ok(())
+    Ok(has_changes)
 }
 
-/// Serialize merged content to string based on file format
-fn serialize_merged_content(merged_file: &crate::merge::MergedFile) -> Result<String> {
-    use crate::merge::FileFormat;
-
-    match merged_file.format {
-        FileFormat::Json => merged_file.content.to_json_string(),
-        FileFormat::Yaml => merged_file.content.to_yaml_string(),
-        FileFormat::Toml => merged_file.content.to_toml_string(),
-        FileFormat::Ini => merged_file.content.to_ini_string(),
-        FileFormat::Text => {
-            if let Some(text) = merged_file.content.as_str() {
-                Ok(text.to_string())
-            } else {
-                Err(JinError::Other(
-                    "Text file has non-string content".to_string(),
-                ))
-            }
-        }
-    }
+/// Serialize merged content to string based on file format, with the line
+/// endings apply would write
+fn serialize_merged_content(
+    root: &Path,
+    path: &std::path::Path,
+    merged_file: &crate::merge::MergedFile,
+) -> Result<String> {
+    let content = merged_file.serialize()?;
+    Ok(crate::merge::LineEndings::current(root)
+        .apply(path, &content)
+        .into_owned())
 }
 
-/// Print a git diff with colored output
-fn print_diff(diff: &git2::Diff) -> Result<()> {
-    diff.print(DiffFormat::Patch, |_delta, _hunk, line| {
-        let origin = line.origin();
-        let content = std::str::from_utf8(line.content()).unwrap_or("<binary>");
+/// Print one line of a patch, colored when the palette is enabled
+pub(crate) fn print_diff_line(line: &git2::DiffLine, palette: Palette) {
+    let origin = line.origin();
+    let content = std::str::from_utf8(line.content()).unwrap_or("<binary>");
+    // Color the line but not its newline, so pagers keep it intact
+    let (text, newline) = match content.strip_suffix('\n') {
+        Some(text) => (text, "\n"),
+        None => (content, ""),
+    };
 
-        match origin {
-            '+' => print!("\x1b[32m+{}\x1b[0m", content),
-            '-' => print!("\x1b[31m-{}\x1b[0m", content),
-            ' ' => print!(" {}", content),
-            'F' => print!("--- {}", content),
-            'T' => print!("+++ {}", content),
-            'H' => print!("@@ {}", content),
-            _ => print!("{}", content),
+    match origin {
+        '+' => print!("{}{}", palette.green(&format!("+{}", text)), newline),
+        '-' => print!("{}{}", palette.red(&format!("-{}", text)), newline),
+        ' ' => print!(" {}", content),
+        // File headers span several lines and already carry their markers
+        'F' => {
+            for header in text.lines() {
+                println!("{}", palette.bold(header));
+            }
         }
-        true
-    })?;
-
-    Ok(())
+        'H' => print!("{}{}", palette.cyan(text), newline),
+        _ => print!("{}", content),
+    }
 }
 
 /// Parse layer name from string
@@ -467,9 +922,15 @@ mod tests {
             layer1: None,
             layer2: None,
             staged: false,
+            name_only: false,
+            exit_code: false,
+            unified: 3,
+            ignore_all_space: false,
+            ignore_blank_lines: false,
+            color: crate::cli::ColorWhen::Never,
         };
 
-        let result = execute(args);
+        let result = execute(args, temp.path());
         assert!(matches!(result, Err(JinError::NotInitialized)));
     }
 
@@ -477,18 +938,103 @@ mod tests {
     #[serial]
     fn test_execute_staged_empty() {
         // setup_unit_test() already creates the staging index
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
         let args = DiffArgs {
             layer1: None,
             layer2: None,
             staged: true,
+            name_only: false,
+            exit_code: false,
+            unified: 3,
+            ignore_all_space: false,
+            ignore_blank_lines: false,
+            color: crate::cli::ColorWhen::Never,
         };
 
-        let result = execute(args);
+        let result = execute(args, &ctx.project_path);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_json_output_collects_files() {
+        let mut patch = git2::Patch::from_buffers(
+            b"a = 1\n",
+            Some(Path::new("app.conf")),
+            b"a = 2\n",
+            Some(Path::new("app.conf")),
+            None,
+        )
+        .unwrap();
+        let mut out = DiffOutput::Json {
+            files: Vec::new(),
+            patches: true,
+        };
+        assert!(!out.verbose());
+        assert!(!out
+            .file("app.conf", "modified", None, Some(&mut patch))
+            .unwrap());
+        out.file("new.json", "added", Some(Layer::ModeBase), None)
+            .unwrap();
+
+        let DiffOutput::Json { files, .. } = out else {
+            unreachable!()
+        };
+        let report = DiffReport {
+            schema_version: JsonSurface::Diff.version(),
+            from: "workspace-active".to_string(),
+            to: "workspace".to_string(),
+            changed: true,
+            files,
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        crate::core::schema::assert_matches(&JsonSurface::Diff.schema(), &json, "diff");
+        let patch = json["files"][0]["patch"].as_str().unwrap();
+        assert!(patch.contains("-a = 1\n+a = 2"));
+        assert_eq!(json["files"][1]["layer"], "mode-base");
+        assert!(json["files"][1].get("patch").is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn test_staged_name_only_reports_no_changes() {
+        let ctx = crate::test_utils::setup_unit_test();
+        let repo = JinRepo::open_or_create(&ctx.project_path).unwrap();
+
+        let mut out = DiffOutput::Text { name_only: true };
+        let has_changes = show_staged_diff(
+            &ctx.project_path,
+            repo.inner(),
+            &ProjectContext::default(),
+            &mut out,
+        )
+        .unwrap();
+        assert!(!has_changes);
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize(r#"  "port": 8080,"#),
+            vec!["  ", "\"", "port", "\"", ":", " ", "8080", ","]
+        );
+        assert!(tokenize("").is_empty());
+    }
+
+    #[test]
+    fn test_highlight_words_marks_changed_tokens() {
+        let (old, new) = highlight_words("port: 8080", "port: 9090", Palette::new(false)).unwrap();
+        assert_eq!(old, "-port: 8080");
+        assert_eq!(new, "+port: 9090");
+
+        let (old, new) = highlight_words("port: 8080", "port: 9090", Palette::new(true)).unwrap();
+        assert_eq!(old, "\x1b[31m-port: \x1b[0m\x1b[7;31m8080\x1b[0m");
+        assert_eq!(new, "\x1b[32m+port: \x1b[0m\x1b[7;32m9090\x1b[0m");
+
+        // Nothing in common but whitespace: no highlighting
+        assert!(highlight_words("a b", "c d", Palette::new(true)).is_none());
+    }
+
     #[test]
     fn test_parse_layer_name() {
         assert!(matches!(
@@ -0,0 +1,52 @@
+//! Implementation of `jin doctor`
+//!
+//! Prints how this installation is set up, for bug reports and for telling
+//! machines apart: the Jin directory and whether its repository opens, the
+//! machine ID (see [`crate::core::machine`]) and the linked remotes.
+//! `--regenerate-machine-id` replaces the machine ID.
+
+use crate::cli::DoctorArgs;
+use crate::core::{machine, JinConfig, Result};
+use crate::git::JinRepo;
+use std::path::Path;
+
+/// Execute the doctor command
+pub fn execute(args: DoctorArgs, root: &Path) -> Result<()> {
+    if args.regenerate_machine_id {
+        let old = machine::load().ok().flatten();
+        let new = machine::regenerate()?;
+        match old {
+            Some(old) => println!("Machine ID changed from {} to {}", old, new),
+            None => println!("Machine ID: {}", new),
+        }
+        println!("Entries recorded earlier keep the old ID.");
+        return Ok(());
+    }
+
+    let config = JinConfig::load()?;
+    let jin_dir = JinConfig::default_path()?
+        .parent()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_default();
+    let repository = match JinRepo::open(root) {
+        Ok(_) => "ok".to_string(),
+        Err(e) => format!("unavailable ({})", e),
+    };
+
+    println!("jin {}", env!("CARGO_PKG_VERSION"));
+    println!("Jin directory:   {}", jin_dir);
+    println!("Repository:      {}", repository);
+    println!("Machine ID:      {}", machine::load_or_create()?);
+    println!(
+        "Team remote:     {}",
+        config
+            .remote
+            .as_ref()
+            .map_or("not linked", |remote| remote.url.as_str())
+    );
+    match &config.local_sync {
+        Some(sync) => println!("User-local sync: {} as '{}'", sync.url, sync.machine),
+        None => println!("User-local sync: not linked"),
+    }
+    Ok(())
+}
@@ -0,0 +1,147 @@
+//! Implementation of `jin env`
+//!
+//! Shows and exports the environment variables defined by the `.jinenv`
+//! files of the active layers (see [`crate::merge::env`]).
+
+use crate::cli::{EnvAction, EnvFormat};
+use crate::core::{JinError, Layer, ProjectContext, Result};
+use crate::git::{JinRepo, ObjectOps, RefOps, TreeOps};
+use crate::merge::env::{dotenv_quote, shell_quote, ENV_FORMAT, ENV_PATH};
+use crate::merge::{get_applicable_layers, registry, MergeValue};
+use std::path::Path;
+
+/// A composed environment variable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvVar {
+    /// Variable name
+    pub name: String,
+    /// Final value
+    pub value: String,
+    /// Layer that set the final value
+    pub layer: Layer,
+}
+
+/// Execute an env subcommand
+pub fn execute(action: EnvAction, root: &Path) -> Result<()> {
+    let context = ProjectContext::load(root)?;
+    let repo = JinRepo::open(root).map_err(|_| JinError::NotInitialized)?;
+    let vars = load_vars(&context, &repo)?;
+
+    match action {
+        EnvAction::Show => {
+            if vars.is_empty() {
+                println!("No environment variables defined in the active layers");
+                println!("Define them in a {} file and add it to a layer", ENV_PATH);
+                return Ok(());
+            }
+            let width = vars.iter().map(|v| v.name.len()).max().unwrap_or(0);
+            for var in &vars {
+                println!(
+                    "{:<width$}  {}  ({})",
+                    var.name,
+                    var.value,
+                    var.layer,
+                    width = width
+                );
+            }
+        }
+        EnvAction::Export { format } => print!("{}", export(&vars, format)),
+    }
+    Ok(())
+}
+
+/// Compose the variables of the active layers, lowest precedence first
+///
+/// Each layer's `.jinenv` overrides the variables of the layers below it,
+/// and `unset NAME` drops a variable. Variables keep the position where
+/// they were first defined.
+pub fn load_vars(context: &ProjectContext, repo: &JinRepo) -> Result<Vec<EnvVar>> {
+    let mut vars: Vec<EnvVar> = Vec::new();
+
+    for layer in get_applicable_layers(
+        context.mode.as_deref(),
+        context.scope.as_deref(),
+        context.project.as_deref(),
+    ) {
+        let ref_path = layer.ref_path(
+            context.mode.as_deref(),
+            context.scope.as_deref(),
+            context.project.as_deref(),
+        );
+        if !repo.ref_exists(&ref_path) {
+            continue;
+        }
+        let tree = repo.find_commit(repo.resolve_ref(&ref_path)?)?.tree_id();
+        let Ok(content) = repo.read_file_from_tree(tree, Path::new(ENV_PATH)) else {
+            continue;
+        };
+
+        let parsed = registry().parse(&String::from_utf8_lossy(&content), ENV_FORMAT)?;
+        let Some(entries) = parsed.as_object() else {
+            continue;
+        };
+        for (name, value) in entries {
+            let position = vars.iter().position(|v| &v.name == name);
+            let value = match value {
+                MergeValue::Null => {
+                    if let Some(index) = position {
+                        vars.remove(index);
+                    }
+                    continue;
+                }
+                other => other.as_str().unwrap_or_default().to_string(),
+            };
+            match position {
+                Some(index) => {
+                    vars[index].value = value;
+                    vars[index].layer = layer;
+                }
+                None => vars.push(EnvVar {
+                    name: name.clone(),
+                    value,
+                    layer,
+                }),
+            }
+        }
+    }
+    Ok(vars)
+}
+
+/// Render variables for `jin env export`
+pub fn export(vars: &[EnvVar], format: EnvFormat) -> String {
+    vars.iter()
+        .map(|var| match format {
+            EnvFormat::Dotenv => format!("{}={}\n", var.name, dotenv_quote(&var.value)),
+            EnvFormat::Shell => format!("export {}={}\n", var.name, shell_quote(&var.value)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_formats() {
+        let vars = vec![
+            EnvVar {
+                name: "API_URL".to_string(),
+                value: "https://example.com".to_string(),
+                layer: Layer::GlobalBase,
+            },
+            EnvVar {
+                name: "GREETING".to_string(),
+                value: "it's me".to_string(),
+                layer: Layer::ModeBase,
+            },
+        ];
+        assert_eq!(
+            export(&vars, EnvFormat::Dotenv),
+            "API_URL=https://example.com\nGREETING=\"it's me\"\n"
+        );
+        assert_eq!(
+            export(&vars, EnvFormat::Shell),
+            "export API_URL='https://example.com'\nexport GREETING='it'\\''s me'\n"
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! Implementation of `jin explain`
+//!
+//! Errors that carry a code (see [`JinError::code`]) end with a pointer to
+//! `jin explain <code>`, which prints a description, common causes and
+//! remediation steps from the table below. Everything is compiled in, so it
+//! works offline and outside a Jin project.
+
+use crate::cli::ExplainArgs;
+use crate::core::{JinError, Result};
+
+/// Knowledge base entry for one error code
+struct Explanation {
+    /// Code as reported in errors, e.g. `JIN-E006`
+    code: &'static str,
+    /// One-line summary
+    title: &'static str,
+    /// What the error means
+    description: &'static str,
+    /// Common causes
+    causes: &'static [&'static str],
+    /// Remediation steps, in the order to try them
+    fixes: &'static [&'static str],
+}
+
+/// All documented codes, in code order
+const EXPLANATIONS: &[Explanation] = &[
+    Explanation {
+        code: "JIN-E001",
+        title: "I/O error",
+        description: "Jin could not read or write a file or directory.",
+        causes: &[
+            "The file or one of its parent directories is not readable or writable",
+            "The disk is full or the filesystem is read-only",
+            "Another program removed the file while jin was running",
+        ],
+        fixes: &[
+            "Check permissions on the path in the message and on ~/.jin (or $JIN_DIR)",
+            "Free disk space, then run the command again",
+            "Run 'jin doctor' to confirm which Jin directory is in use",
+        ],
+    },
+    Explanation {
+        code: "JIN-E002",
+        title: "Git error",
+        description: "An operation on the Jin repository failed inside Git.",
+        causes: &[
+            "The Jin repository is corrupt or was modified by another tool",
+            "A ref is locked by a crashed jin or git process",
+            "The remote could not be reached",
+        ],
+        fixes: &[
+            "Run 'jin verify' to check layer objects",
+            "Remove stale '*.lock' files under the Jin repository if no jin process is running",
+            "Run 'jin repair' to rebuild Jin state",
+        ],
+    },
+    Explanation {
+        code: "JIN-E003",
+        title: "Configuration error",
+        description: "A Jin configuration file or setting is invalid.",
+        causes: &[
+            "A value in ~/.jin/config.toml or .jin/context has the wrong type",
+            "An unknown option was passed to 'jin config set'",
+        ],
+        fixes: &[
+            "Run 'jin config list' to see the current settings",
+            "Fix or remove the setting named in the message",
+        ],
+    },
+    Explanation {
+        code: "JIN-E004",
+        title: "Parse error",
+        description: "A JSON, YAML, TOML or INI file could not be parsed, so it cannot be merged.",
+        causes: &[
+            "The file has a syntax error (trailing comma, bad indentation, unclosed quote)",
+            "The file extension does not match its contents",
+        ],
+        fixes: &[
+            "Fix the syntax at the position in the message",
+            "Run 'jin diff' to find the layer the broken version came from",
+            "Rename the file, or mark it as text in .jinattributes, if it is not structured data",
+        ],
+    },
+    Explanation {
+        code: "JIN-E005",
+        title: "Merge conflict",
+        description: "Two layers changed the same lines or keys and Jin could not merge them automatically.",
+        causes: &[
+            "A text file was edited in more than one layer",
+            "A key was given incompatible values in different layers",
+        ],
+        fixes: &[
+            "Edit the .jinmerge file for the path, keeping the version you want",
+            "Run 'jin resolve <file>' to finish, or 'jin resolve --interactive'",
+            "See 'jin help conflicts' for a walkthrough",
+        ],
+    },
+    Explanation {
+        code: "JIN-E006",
+        title: "Push rejected: behind remote",
+        description: "The remote has commits on a layer that you don't have locally, so pushing would discard them.",
+        causes: &[
+            "A teammate pushed to the same layer since your last fetch",
+            "You pushed the layer from another machine",
+        ],
+        fixes: &[
+            "Run 'jin pull' to merge the remote changes, then push again",
+            "Only if the remote changes should be discarded: 'jin push --force'",
+        ],
+    },
+    Explanation {
+        code: "JIN-E007",
+        title: "Layer refs changed",
+        description: "Layer refs moved while the command was running. Nothing was written.",
+        causes: &[
+            "Another jin process (apply, commit, pull) ran at the same time",
+            "A Git tool updated the Jin repository directly",
+        ],
+        fixes: &["Run the command again once the other process has finished"],
+    },
+    Explanation {
+        code: "JIN-E008",
+        title: "Detached workspace",
+        description: "The workspace no longer matches any valid layer configuration.",
+        causes: &[
+            "A mode or scope in the active context was deleted",
+            "Layer commits recorded by the last apply no longer exist",
+        ],
+        fixes: &[
+            "Follow the recovery hint in the message",
+            "Run 'jin context --validate', then 'jin context --heal'",
+            "Run 'jin apply --force' to rebuild the workspace from the current layers",
+        ],
+    },
+    Explanation {
+        code: "JIN-E009",
+        title: "Transaction failed",
+        description: "A multi-layer commit could not be completed and was rolled back.",
+        causes: &[
+            "A previous transaction was interrupted and left recovery state behind",
+            "A layer ref was updated by another process mid-commit",
+        ],
+        fixes: &[
+            "Run 'jin repair' to recover or discard the interrupted transaction",
+            "Run the command again",
+        ],
+    },
+    Explanation {
+        code: "JIN-E010",
+        title: "Invalid layer",
+        description: "The layer named or implied by the command does not exist or cannot be used here.",
+        causes: &[
+            "A --mode, --scope or --project flag was given without the matching active context",
+            "A layer name was misspelled",
+        ],
+        fixes: &[
+            "Run 'jin layers' to list the layers in the active context",
+            "See 'jin help layers' for how flags map to layers",
+        ],
+    },
+    Explanation {
+        code: "JIN-E011",
+        title: "No active mode or scope",
+        description: "The command needs an active mode or scope, and none is set.",
+        causes: &[
+            "'jin add --mode' or '--scope' was used before activating one",
+            "The context was reset with 'jin mode unset' or 'jin scope unset'",
+        ],
+        fixes: &[
+            "Activate one with 'jin mode use <name>' or 'jin scope use <name>'",
+            "Pass --create-missing to 'jin add' to create and activate it",
+        ],
+    },
+    Explanation {
+        code: "JIN-E012",
+        title: "Not found",
+        description: "A file, mode, scope or layer named in the command does not exist.",
+        causes: &["The name or path was misspelled", "It was deleted or never committed"],
+        fixes: &[
+            "Run 'jin list' or 'jin status' to see what exists",
+            "Check the path relative to the workspace root (or the -C directory)",
+        ],
+    },
+    Explanation {
+        code: "JIN-E013",
+        title: "Already exists",
+        description: "The mode, scope, file or link being created already exists.",
+        causes: &["It was created earlier, possibly on another machine and pulled"],
+        fixes: &[
+            "Use the existing one, e.g. 'jin mode use <name>'",
+            "Delete it first if it should be recreated",
+        ],
+    },
+    Explanation {
+        code: "JIN-E014",
+        title: "File is tracked by Git",
+        description: "Jin does not stage files that the host Git repository already tracks.",
+        causes: &["The file is committed to the project repository"],
+        fixes: &[
+            "Run 'jin import <file>' to move it from Git into a layer",
+            "Or keep it in Git and don't manage it with Jin",
+        ],
+    },
+    Explanation {
+        code: "JIN-E015",
+        title: "Symlinks are not supported",
+        description: "Jin stores file contents and does not follow or store symbolic links.",
+        causes: &["The path, or one of its parent directories, is a symlink"],
+        fixes: &["Add the link target instead, or replace the link with a regular file"],
+    },
+    Explanation {
+        code: "JIN-E016",
+        title: "Staging failed",
+        description: "A file could not be added to the staging index.",
+        causes: &[
+            "The file is too large or is binary where text was expected",
+            "The file matches an ignore rule",
+            "The path is locked by another user (see 'jin lock')",
+        ],
+        fixes: &[
+            "Read the reason in the message",
+            "Run 'jin status' to see what is already staged",
+        ],
+    },
+    Explanation {
+        code: "JIN-E017",
+        title: "Jin not initialized",
+        description: "The current directory is not a Jin project.",
+        causes: &[
+            "'jin init' was never run here",
+            "The command was run from the wrong directory",
+        ],
+        fixes: &[
+            "Run 'jin init' in the project root",
+            "Or run the command with -C <project-root>",
+        ],
+    },
+    Explanation {
+        code: "JIN-E018",
+        title: "Authentication failed",
+        description: "The remote rejected the credentials. Jin does not retry authentication failures.",
+        causes: &[
+            "No SSH key is loaded, or the key is not authorized on the remote",
+            "An access token expired or lacks write access",
+        ],
+        fixes: &[
+            "Test access with 'ssh -T git@github.com' (or your host)",
+            "Check the linked URL with 'jin doctor'",
+            "Refresh the token or credentials and run the command again",
+        ],
+    },
+    Explanation {
+        code: "JIN-E019",
+        title: "Operation cancelled",
+        description: "The operation was interrupted before it finished. Completed steps were kept or rolled back.",
+        causes: &[
+            "Ctrl-C was pressed",
+            "A remote operation for another layer failed and cancelled the rest",
+        ],
+        fixes: &["Run the command again", "Run 'jin status' to check the workspace"],
+    },
+];
+
+/// Execute the explain command
+///
+/// Without a code, lists all codes with their summaries.
+pub fn execute(args: ExplainArgs) -> Result<()> {
+    let Some(code) = args.code else {
+        for entry in EXPLANATIONS {
+            println!("{}  {}", entry.code, entry.title);
+        }
+        return Ok(());
+    };
+
+    let entry = lookup(&code).ok_or_else(|| {
+        JinError::NotFound(format!(
+            "No explanation for '{}'. Run 'jin explain' to list error codes",
+            code
+        ))
+    })?;
+    print!("{}", render(entry));
+    Ok(())
+}
+
+/// Find the entry for `code`, accepting `jin-e6`-style shorthand
+fn lookup(code: &str) -> Option<&'static Explanation> {
+    let code = code.trim().to_ascii_uppercase();
+    let digits = code.strip_prefix("JIN-").unwrap_or(&code);
+    let number: u32 = digits.strip_prefix('E').unwrap_or(digits).parse().ok()?;
+    let code = format!("JIN-E{:03}", number);
+    EXPLANATIONS.iter().find(|entry| entry.code == code)
+}
+
+/// Format an entry for the terminal
+fn render(entry: &Explanation) -> String {
+    let mut out = format!("{}: {}\n\n{}\n", entry.code, entry.title, entry.description);
+    out.push_str("\nCommon causes:\n");
+    for cause in entry.causes {
+        out.push_str(&format!("  - {}\n", cause));
+    }
+    out.push_str("\nHow to fix:\n");
+    for (i, fix) in entry.fixes.iter().enumerate() {
+        out.push_str(&format!("  {}. {}\n", i + 1, fix));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_accepts_shorthand() {
+        assert_eq!(lookup("JIN-E006").unwrap().code, "JIN-E006");
+        assert_eq!(lookup("jin-e6").unwrap().code, "JIN-E006");
+        assert_eq!(lookup("E017").unwrap().code, "JIN-E017");
+        assert!(lookup("JIN-E999").is_none());
+        assert!(lookup("nonsense").is_none());
+    }
+
+    #[test]
+    fn test_every_error_code_is_explained() {
+        let errors = [
+            JinError::Config(String::new()),
+            JinError::BehindRemote {
+                layer: String::new(),
+            },
+            JinError::NotInitialized,
+            JinError::Auth(String::new()),
+            JinError::Cancelled,
+        ];
+        for error in errors {
+            let code = error.code().unwrap();
+            assert!(lookup(code).is_some(), "{} has no explanation", code);
+        }
+        for (i, entry) in EXPLANATIONS.iter().enumerate() {
+            assert_eq!(entry.code, format!("JIN-E{:03}", i + 1));
+            assert!(!entry.causes.is_empty() && !entry.fixes.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_render_lists_causes_and_fixes() {
+        let text = render(lookup("JIN-E006").unwrap());
+        assert!(text.starts_with("JIN-E006: Push rejected: behind remote\n"));
+        assert!(text.contains("Common causes:\n  - A teammate pushed"));
+        assert!(text.contains("How to fix:\n  1. Run 'jin pull'"));
+    }
+}
@@ -26,17 +26,17 @@ use std::process::Command;
 /// - A file is not Jin-tracked
 /// - Git add operation fails
 /// - Rollback fails after partial completion
-pub fn execute(args: ExportArgs) -> Result<()> {
+pub fn execute(args: ExportArgs, root: &Path) -> Result<()> {
     // 1. Validate we have files to export
     if args.files.is_empty() {
         return Err(JinError::Other("No files specified".to_string()));
     }
 
     // 2. Open Jin repository (ensure it exists)
-    let repo = JinRepo::open_or_create()?;
+    let repo = JinRepo::open_or_create(root)?;
 
     // 3. Load staging index
-    let mut staging = StagingIndex::load().unwrap_or_else(|_| StagingIndex::new());
+    let mut staging = StagingIndex::load(root).unwrap_or_else(|_| StagingIndex::new());
 
     // 4. Process each file with atomic rollback capability
     let mut exported_count = 0;
@@ -46,7 +46,7 @@ pub fn execute(args: ExportArgs) -> Result<()> {
     for path_str in &args.files {
         let path = PathBuf::from(path_str);
 
-        match export_file(&path, &mut staging, &repo) {
+        match export_file(root, &path, &mut staging, &repo) {
             Ok(_) => {
                 successfully_exported.push(path.clone());
                 exported_count += 1;
@@ -58,7 +58,7 @@ pub fn execute(args: ExportArgs) -> Result<()> {
                 // Attempt to rollback previously exported files
                 if !successfully_exported.is_empty() {
                     eprintln!("Error during export, attempting rollback...");
-                    if let Err(rollback_err) = rollback_exports(&successfully_exported) {
+                    if let Err(rollback_err) = rollback_exports(root, &successfully_exported) {
                         eprintln!("Warning: Rollback failed: {}", rollback_err);
                         eprintln!("Manual intervention may be required for files:");
                         for file in &successfully_exported {
@@ -80,7 +80,7 @@ pub fn execute(args: ExportArgs) -> Result<()> {
     }
 
     // 5. Save staging index after all files processed successfully
-    staging.save()?;
+    staging.save(root)?;
 
     // 6. Print summary
     if exported_count > 0 {
@@ -108,13 +108,13 @@ pub fn execute(args: ExportArgs) -> Result<()> {
 /// 3. Remove from Jin staging
 /// 4. Extract from layer if committed and not in workspace
 /// 5. Add to Git index
-fn export_file(path: &Path, staging: &mut StagingIndex, repo: &JinRepo) -> Result<()> {
+fn export_file(root: &Path, path: &Path, staging: &mut StagingIndex, repo: &JinRepo) -> Result<()> {
     // 1. Validate file is Jin-tracked and check if it's committed
-    let is_committed = validate_jin_tracked(path, staging, repo)?;
+    let is_committed = validate_jin_tracked(root, path, staging, repo)?;
 
     // 2. Remove from .gitignore managed block FIRST (before git add)
     // If this fails, we should still continue - the user can manually fix .gitignore
-    if let Err(e) = remove_from_managed_block(path) {
+    if let Err(e) = remove_from_managed_block(root, path) {
         eprintln!(
             "Warning: Could not remove {} from .gitignore: {}",
             path.display(),
@@ -129,24 +129,24 @@ fn export_file(path: &Path, staging: &mut StagingIndex, repo: &JinRepo) -> Resul
     }
 
     // 4. For committed files not in workspace, extract from layer
-    if is_committed && !path.exists() {
-        extract_file_from_layer(path, repo)?;
+    if is_committed && !root.join(path).exists() {
+        extract_file_from_layer(root, path, repo)?;
     }
 
     // 5. Add to Git index (now that it's not in .gitignore)
-    add_to_git(path)?;
+    add_to_git(root, path)?;
 
     Ok(())
 }
 
 /// Extract a file from its committed layer to the workspace
-fn extract_file_from_layer(path: &Path, repo: &JinRepo) -> Result<()> {
+fn extract_file_from_layer(root: &Path, path: &Path, repo: &JinRepo) -> Result<()> {
     let file_name = path
         .file_name()
         .and_then(|n| n.to_str())
         .ok_or_else(|| JinError::Other("Invalid file path".to_string()))?;
 
-    let jinmap = JinMap::load()?;
+    let jinmap = JinMap::load(root)?;
 
     // Find the file in any layer that contains it
     for layer_ref in jinmap.layer_refs() {
@@ -160,12 +160,13 @@ fn extract_file_from_layer(path: &Path, repo: &JinRepo) -> Result<()> {
                 let content = repo.read_file_from_tree(tree_oid, Path::new(file_name))?;
 
                 // Create parent directory if needed
-                if let Some(parent) = path.parent() {
+                let location = root.join(path);
+                if let Some(parent) = location.parent() {
                     std::fs::create_dir_all(parent).map_err(JinError::Io)?;
                 }
 
                 // Write file to workspace
-                std::fs::write(path, content).map_err(JinError::Io)?;
+                std::fs::write(&location, content).map_err(JinError::Io)?;
 
                 return Ok(());
             }
@@ -186,11 +187,16 @@ fn extract_file_from_layer(path: &Path, repo: &JinRepo) -> Result<()> {
 ///
 /// Returns `Ok(true)` if the file is committed (not in staging),
 /// `Ok(false)` if the file is in staging, or an error if not Jin-tracked.
-fn validate_jin_tracked(path: &Path, staging: &StagingIndex, repo: &JinRepo) -> Result<bool> {
+fn validate_jin_tracked(
+    root: &Path,
+    path: &Path,
+    staging: &StagingIndex,
+    repo: &JinRepo,
+) -> Result<bool> {
     // Check if file is in staging index (fast path)
     if staging.get(path).is_some() {
         // For staged files, verify file exists in workspace
-        if !path.exists() {
+        if !root.join(path).exists() {
             return Err(JinError::NotFound(path.display().to_string()));
         }
         return Ok(false); // File is in staging (not committed)
@@ -203,7 +209,7 @@ fn validate_jin_tracked(path: &Path, staging: &StagingIndex, repo: &JinRepo) ->
         .and_then(|n| n.to_str())
         .ok_or_else(|| JinError::Other("Invalid file path".to_string()))?;
 
-    let jinmap = JinMap::load()?;
+    let jinmap = JinMap::load(root)?;
     if !jinmap.contains_file(file_name) {
         return Err(JinError::Other(format!(
             "{} is not Jin-tracked. Use `jin status` to see Jin-tracked files.",
@@ -213,7 +219,7 @@ fn validate_jin_tracked(path: &Path, staging: &StagingIndex, repo: &JinRepo) ->
 
     // For committed files, verify file exists in committed layer tree
     // The file doesn't need to exist in the workspace for committed files
-    let _context = ProjectContext::load()
+    let _context = ProjectContext::load(root)
         .map_err(|_| JinError::Other("Jin not initialized. Run 'jin init' first.".to_string()))?;
 
     // Find the file in any layer that contains it
@@ -240,8 +246,9 @@ fn validate_jin_tracked(path: &Path, staging: &StagingIndex, repo: &JinRepo) ->
 }
 
 /// Add a file to Git index using `git add`
-fn add_to_git(path: &Path) -> Result<()> {
+fn add_to_git(root: &Path, path: &Path) -> Result<()> {
     let output = Command::new("git")
+        .current_dir(root)
         .arg("add")
         .arg(path)
         .output()
@@ -260,10 +267,11 @@ fn add_to_git(path: &Path) -> Result<()> {
 }
 
 /// Rollback exported files by removing them from Git and adding back to .gitignore
-fn rollback_exports(paths: &[PathBuf]) -> Result<()> {
+fn rollback_exports(root: &Path, paths: &[PathBuf]) -> Result<()> {
     for path in paths {
         // Remove from Git index (but keep in working directory)
         let output = Command::new("git")
+            .current_dir(root)
             .arg("reset")
             .arg("HEAD")
             .arg(path)
@@ -285,7 +293,7 @@ fn rollback_exports(paths: &[PathBuf]) -> Result<()> {
         }
 
         // Add back to .gitignore managed block
-        if let Err(e) = crate::staging::ensure_in_managed_block(path) {
+        if let Err(e) = crate::staging::ensure_in_managed_block(root, path) {
             eprintln!(
                 "Warning during rollback: Could not add {} back to .gitignore: {}",
                 path.display(),
@@ -317,7 +325,7 @@ mod tests {
 
         let staging = StagingIndex::new();
         let path = PathBuf::from("/nonexistent/file.txt");
-        let result = validate_jin_tracked(&path, &staging, &repo);
+        let result = validate_jin_tracked(Path::new("."), &path, &staging, &repo);
         // File that doesn't exist and isn't in JinMap returns "not Jin-tracked"
         assert!(result.is_err());
         assert!(result
@@ -336,7 +344,7 @@ mod tests {
         let repo = JinRepo::create_at(&repo_path).unwrap();
 
         let staging = StagingIndex::new();
-        let result = validate_jin_tracked(&file, &staging, &repo);
+        let result = validate_jin_tracked(Path::new("."), &file, &staging, &repo);
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
@@ -357,7 +365,7 @@ mod tests {
         let entry = StagedEntry::new(file.clone(), Layer::ProjectBase, "hash123".to_string());
         staging.add(entry);
 
-        let result = validate_jin_tracked(&file, &staging, &repo);
+        let result = validate_jin_tracked(Path::new("."), &file, &staging, &repo);
         assert!(result.is_ok());
     }
 
@@ -374,7 +382,7 @@ mod tests {
         std::fs::create_dir_all(&jin_dir).unwrap();
 
         // Create Jin repo using JIN_DIR
-        let repo = JinRepo::open_or_create().unwrap();
+        let repo = JinRepo::open_or_create(Path::new(".")).unwrap();
 
         // Create a test file in a layer
         use crate::git::ObjectOps;
@@ -405,7 +413,7 @@ mod tests {
         let local_jin = temp.path().join(".jin");
         std::fs::create_dir_all(&local_jin).unwrap();
         let context = ProjectContext::default();
-        context.save().unwrap();
+        context.save(temp.path()).unwrap();
 
         // Create physical file
         let file = temp.path().join("config.json");
@@ -415,7 +423,7 @@ mod tests {
         let staging = StagingIndex::new();
 
         // Validation should succeed via JinMap
-        let result = validate_jin_tracked(&file, &staging, &repo);
+        let result = validate_jin_tracked(temp.path(), &file, &staging, &repo);
         if let Err(e) = &result {
             eprintln!("Validation error: {}", e);
         }
@@ -465,7 +473,7 @@ mod tests {
         let staging = StagingIndex::new();
 
         // Validation should fail - file not in JinMap
-        let result = validate_jin_tracked(&file, &staging, &repo);
+        let result = validate_jin_tracked(Path::new("."), &file, &staging, &repo);
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
@@ -479,7 +487,7 @@ mod tests {
     #[test]
     fn test_execute_no_files() {
         let args = ExportArgs { files: vec![] };
-        let result = execute(args);
+        let result = execute(args, Path::new("."));
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("No files"));
     }
@@ -511,7 +519,7 @@ mod tests {
         let args = ExportArgs {
             files: vec![file.display().to_string()],
         };
-        let result = execute(args);
+        let result = execute(args, temp.path());
 
         // Always restore directory if we have a valid one
         if let Some(ref dir) = original_dir {
@@ -547,7 +555,7 @@ mod tests {
         // Change to temp directory (no Git repo)
         std::env::set_current_dir(temp.path()).unwrap();
 
-        let result = add_to_git(&file);
+        let result = add_to_git(temp.path(), &file);
 
         // Always restore directory if we have a valid one
         if let Some(ref dir) = original_dir {
@@ -625,7 +633,7 @@ mod tests {
         }
 
         // Call add_to_git
-        let result = add_to_git(&test_file);
+        let result = add_to_git(&temp_path, &test_file);
 
         // Restore directory
         if let Some(ref dir) = original_dir {
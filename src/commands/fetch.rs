@@ -1,19 +1,31 @@
 //! Implementation of `jin fetch`
 //!
-//! Downloads remote layer refs without modifying workspace or active layers.
-//! This is a safe, read-only operation from the user's perspective.
-
-use crate::core::{JinConfig, JinError, ProjectContext, Result};
-use crate::git::remote::build_fetch_options;
-use crate::git::{JinRepo, RefOps};
+//! Downloads the remote's layer refs into the tracking namespace
+//! (`refs/jin/remote/origin/layers/*`) and reports which layers have
+//! updates. Local layers, the workspace and the active context are never
+//! modified; `jin pull` merges the tracking refs into the local layers.
+
+use crate::core::progress::{self, Event, Operation, Phase};
+use crate::core::{JinConfig, JinError, Layer, ProjectContext, Result};
+use crate::git::executor::check_interrupted;
+use crate::git::refs::touch_refs_stamp;
+use crate::git::remote::{
+    build_task_fetch_options, remote_error, tracked_layer_ref, tracking_prefix, tracking_refspec,
+    DEFAULT_REMOTE,
+};
+use crate::git::retry::with_retry;
+use crate::git::{archive, locks, notes};
+use crate::git::{JinRepo, Job, RefOps, RemoteExecutor, Task};
 use git2::ErrorCode;
 use std::collections::HashMap;
+use std::path::Path;
 
 /// Execute the fetch command
 ///
-/// Downloads all layer refs from remote repository and reports available updates.
-/// Does NOT modify workspace or active layers - read-only operation.
-pub fn execute() -> Result<()> {
+/// Downloads all layer refs from remote repository into the tracking
+/// namespace and reports available updates. Does NOT modify workspace or
+/// local layers.
+pub fn execute(root: &Path) -> Result<()> {
     // 1. Load configuration and validate remote exists
     let config = JinConfig::load()?;
     let remote_config = config.remote.ok_or(JinError::Config(
@@ -21,18 +33,18 @@ pub fn execute() -> Result<()> {
     ))?;
 
     // 1.5. Load project context with graceful fallback for uninitialized projects
-    let context = match ProjectContext::load() {
+    let context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => ProjectContext::default(),
         Err(e) => return Err(e),
     };
 
     // 2. Open Jin repository
-    let jin_repo = JinRepo::open_or_create()?;
+    let jin_repo = JinRepo::open_or_create(root)?;
     let repo = jin_repo.inner();
 
-    // 3. Find the remote
-    let mut remote = repo.find_remote("origin").map_err(|e| {
+    // 3. Verify the remote exists
+    repo.find_remote(DEFAULT_REMOTE).map_err(|e| {
         if e.code() == ErrorCode::NotFound {
             JinError::Config(
                 "Remote 'origin' not found in repository. Run 'jin link <url>'.".into(),
@@ -42,107 +54,123 @@ pub fn execute() -> Result<()> {
         }
     })?;
 
-    // 4. Capture pre-fetch local refs to compare after fetch
-    let pre_fetch_refs = capture_local_refs(&jin_repo)?;
-
-    // 5. Setup fetch options with callbacks
-    let mut fetch_opts = build_fetch_options()?;
-
-    // 6. Perform fetch
+    // 4. Fetch layers in parallel with advisory locks, the archive list and
+    //    annotations. Each job opens its own repository handle since git2
+    //    repositories can't be shared between threads.
     println!("Fetching from origin ({})...", remote_config.url);
-
-    // Fetch using configured refspec from link (no custom refspec needed)
-    let refspecs: &[&str] = &[];
-    match remote.fetch(refspecs, Some(&mut fetch_opts), None) {
-        Ok(()) => {
-            println!(); // New line after progress
-        }
-        Err(e) => {
-            println!(); // New line after progress even on error
-            return match e.code() {
-                ErrorCode::Auth => Err(JinError::Config(
-                    "Authentication failed. Check your SSH keys or credentials.\n\
-                    Try: ssh -T git@github.com (for GitHub)"
-                        .into(),
-                )),
-                _ => Err(e.into()),
+    let repo_path = jin_repo.path().clone();
+    let executor = RemoteExecutor::new();
+    let mut results = executor.run(vec![
+        Job::new(DEFAULT_REMOTE, |task| fetch_layers(&repo_path, task)),
+        Job::new("locks", |_| {
+            // Best effort: commits keep checking the last fetched copies
+            let Ok(repo) = JinRepo::open_at(&repo_path) else {
+                return Ok(());
             };
-        }
+            if let Ok(Some(mut team_remote)) = locks::team_remote(&repo) {
+                if let Err(e) = locks::fetch(&mut team_remote) {
+                    progress::warn_on_new_line(format_args!("Could not refresh locks: {}", e));
+                }
+                if let Err(e) = archive::fetch(&mut team_remote) {
+                    progress::warn_on_new_line(format_args!(
+                        "Could not refresh archived modes and scopes: {}",
+                        e
+                    ));
+                }
+                if let Err(e) = notes::fetch(&mut team_remote) {
+                    progress::warn_on_new_line(format_args!(
+                        "Could not refresh annotations: {}",
+                        e
+                    ));
+                }
+            }
+            Ok(())
+        }),
+    ]);
+    if progress::human() {
+        println!(); // New line after progress
     }
+    results.remove(0).1?;
+    check_interrupted()?;
+    Event::new(Operation::Fetch, Phase::Done)
+        .remote(DEFAULT_REMOTE)
+        .emit();
+    touch_refs_stamp(jin_repo.path());
 
-    // 7. Report available updates
-    report_updates(&jin_repo, &pre_fetch_refs, &context)?;
+    // 5. Report available updates
+    report_updates(&jin_repo, &context)?;
 
     Ok(())
 }
 
-/// Capture local refs before fetch
-fn capture_local_refs(jin_repo: &JinRepo) -> Result<HashMap<String, git2::Oid>> {
-    let mut local_refs = HashMap::new();
-    let all_refs = jin_repo.list_refs("refs/jin/layers/*")?;
-
-    for ref_name in all_refs {
-        // Skip user-local layer
-        if ref_name.contains("/local") {
-            continue;
-        }
-
-        // Store the OID of each local ref
-        if let Ok(oid) = jin_repo.resolve_ref(&ref_name) {
-            local_refs.insert(ref_name, oid);
-        }
-    }
-
-    Ok(local_refs)
+/// Fetch origin's layer refs into the tracking namespace as an executor task
+fn fetch_layers(repo_path: &Path, task: &Task) -> Result<()> {
+    let repo = JinRepo::open_at(&repo_path.to_path_buf())?;
+
+    // An anonymous remote ignores origin's configured refspec, which in
+    // repositories linked by older versions still points at local layers
+    let url = repo
+        .inner()
+        .find_remote(DEFAULT_REMOTE)?
+        .url()
+        .map(str::to_string)
+        .ok_or_else(|| JinError::Config("Remote 'origin' has no URL".into()))?;
+    let mut remote = repo.inner().remote_anonymous(&url)?;
+    let mut fetch_opts = build_task_fetch_options(task);
+    // Layers deleted on the remote stop being tracked
+    fetch_opts.prune(git2::FetchPrune::On);
+
+    let refspec = tracking_refspec(DEFAULT_REMOTE);
+    with_retry("Fetch from origin", || {
+        remote
+            .fetch(&[refspec.as_str()], Some(&mut fetch_opts), None)
+            .map_err(|e| match remote_error(e) {
+                _ if task.is_cancelled() => JinError::Cancelled,
+                e => e,
+            })
+    })
 }
 
-/// Report available updates by comparing local and remote refs
-fn report_updates(
-    jin_repo: &JinRepo,
-    pre_fetch_refs: &HashMap<String, git2::Oid>,
-    context: &ProjectContext,
-) -> Result<()> {
-    // Get all remote refs in Jin namespace (after fetch, these are in local refs)
-    let remote_refs = jin_repo.list_refs("refs/jin/layers/*")?;
+/// Report layers where the remote has commits the local layer lacks
+fn report_updates(jin_repo: &JinRepo, context: &ProjectContext) -> Result<()> {
+    let tracking_refs = jin_repo.list_refs(&format!("{}*", tracking_prefix(DEFAULT_REMOTE)))?;
 
-    if remote_refs.is_empty() {
+    if tracking_refs.is_empty() {
         println!("No remote configurations found");
         return Ok(());
     }
 
-    // Compare remote refs with pre-fetch local refs to find updates
+    let user_local = Layer::UserLocal.ref_path(None, None, None);
     let mut updates: HashMap<String, UpdateInfo> = HashMap::new();
 
-    for remote_ref in &remote_refs {
+    for tracking_ref in &tracking_refs {
+        let Some(local_ref) = tracked_layer_ref(DEFAULT_REMOTE, tracking_ref) else {
+            continue;
+        };
         // Skip user-local layer (never synced)
-        if remote_ref.contains("/local") {
+        if local_ref == user_local {
             continue;
         }
 
-        let remote_ref_obj = jin_repo.find_ref(remote_ref)?;
-        let remote_oid = remote_ref_obj
-            .target()
-            .ok_or(JinError::Git(git2::Error::from_str(
-                "Remote ref has no target",
-            )))?;
-
-        // Check if we had this ref locally before fetch
-        let is_update = match pre_fetch_refs.get(remote_ref) {
-            Some(pre_fetch_oid) => {
-                // Ref existed locally, check if it changed
-                pre_fetch_oid != &remote_oid
-            }
-            None => {
-                // Ref didn't exist locally before fetch - it's new
-                true
+        let remote_oid = jin_repo.resolve_ref(tracking_ref)?;
+
+        // Nothing to pull when the local layer already contains the remote
+        let is_update = match jin_repo.resolve_ref(&local_ref) {
+            Ok(local_oid) => {
+                local_oid != remote_oid
+                    && !jin_repo
+                        .inner()
+                        .graph_descendant_of(local_oid, remote_oid)
+                        .unwrap_or(false)
             }
+            Err(_) => true,
         };
 
         if is_update {
             // Parse layer type from ref path
-            let layer_path = remote_ref
+            let layer_path = local_ref
                 .strip_prefix("refs/jin/layers/")
-                .unwrap_or(remote_ref);
+                .unwrap_or(&local_ref);
 
             // Determine layer category for grouping
             let category = categorize_layer(layer_path);
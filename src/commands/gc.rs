@@ -0,0 +1,61 @@
+//! Implementation of `jin gc`
+//!
+//! Repacks the Jin repository (see [`crate::git::maintenance`]) and reports
+//! how much content is shared across layer history and how much space the
+//! repack saved.
+
+use crate::cli::GcArgs;
+use crate::core::limits::format_size;
+use crate::core::{JinConfig, JinError, Result};
+use crate::git::maintenance::{self, BlobReport, StorageReport};
+use crate::git::JinRepo;
+use std::path::Path;
+
+/// Execute the gc command
+pub fn execute(args: GcArgs, root: &Path) -> Result<()> {
+    let repo = JinRepo::open(root).map_err(|_| JinError::NotInitialized)?;
+    let config = JinConfig::load()?.gc.unwrap_or_default();
+    let window = args.window.or(config.window);
+    let depth = args.depth.or(config.depth);
+
+    let blobs = BlobReport::measure(repo.inner())?;
+    println!(
+        "Layer history: {} commit(s), {} file version(s), {}",
+        blobs.commits,
+        blobs.file_versions,
+        format_size(blobs.logical_bytes)
+    );
+    println!(
+        "Unique blobs:  {} ({}), {} deduplicated",
+        blobs.unique_blobs,
+        format_size(blobs.unique_bytes),
+        format_size(blobs.logical_bytes - blobs.unique_bytes)
+    );
+
+    let before = StorageReport::measure(repo.inner())?;
+    print_storage("Storage before", &before);
+
+    if args.dry_run {
+        println!("Dry run: repository was not repacked");
+        return Ok(());
+    }
+
+    maintenance::run_gc(repo.path(), args.aggressive, window, depth)?;
+
+    let after = StorageReport::measure(repo.inner())?;
+    print_storage("Storage after", &after);
+    let saved = before.total_bytes().saturating_sub(after.total_bytes());
+    println!("Saved {}", format_size(saved));
+
+    Ok(())
+}
+
+fn print_storage(label: &str, storage: &StorageReport) {
+    println!(
+        "{}: {} ({} loose object(s), {} pack(s))",
+        label,
+        format_size(storage.total_bytes()),
+        storage.loose_objects,
+        storage.packs
+    );
+}
@@ -0,0 +1,185 @@
+//! Implementation of `jin help`
+//!
+//! `jin help <command>` prints a command's help, like clap's built-in help
+//! subcommand it replaces. `jin help <topic>` prints a task-oriented guide
+//! embedded from `help/*.txt`. Guides contain `{placeholder}`s filled in from
+//! the current state (active mode, pending conflicts, linked remote) so the
+//! examples can be copied as they are.
+
+use crate::cli::{Cli, HelpArgs};
+use crate::commands::apply::PausedApplyState;
+use crate::core::{JinConfig, JinError, ProjectContext, Result};
+use clap::CommandFactory;
+use std::path::Path;
+
+/// A help topic
+struct Topic {
+    /// Name used in `jin help <name>`
+    name: &'static str,
+    /// Guide template
+    text: &'static str,
+}
+
+/// Available topics, in display order
+const TOPICS: &[Topic] = &[
+    Topic {
+        name: "layers",
+        text: include_str!("help/layers.txt"),
+    },
+    Topic {
+        name: "conflicts",
+        text: include_str!("help/conflicts.txt"),
+    },
+    Topic {
+        name: "syncing",
+        text: include_str!("help/syncing.txt"),
+    },
+];
+
+/// State the guides are tailored to
+#[derive(Debug, Default)]
+struct HelpState {
+    /// Active context, if in a Jin project
+    context: Option<ProjectContext>,
+    /// Files waiting in a paused apply
+    conflicts: Vec<String>,
+    /// URL of the linked remote
+    remote: Option<String>,
+}
+
+impl HelpState {
+    /// Gather the state, ignoring anything that can't be read
+    fn load(root: &Path) -> Self {
+        Self {
+            context: ProjectContext::load(root).ok(),
+            conflicts: PausedApplyState::load(root)
+                .map(|state| {
+                    state
+                        .conflict_files
+                        .iter()
+                        .map(|path| path.display().to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            remote: JinConfig::load()
+                .ok()
+                .and_then(|config| config.remote)
+                .map(|remote| remote.url),
+        }
+    }
+}
+
+/// Execute the help command
+pub fn execute(args: HelpArgs, root: &Path) -> Result<()> {
+    let Some(name) = args.topic.first() else {
+        Cli::command().print_long_help()?;
+        return Ok(());
+    };
+
+    if let Some(topic) = TOPICS.iter().find(|t| t.name == name) {
+        print!("{}", render(topic.text, &HelpState::load(root)));
+        return Ok(());
+    }
+
+    let mut command = Cli::command();
+    let mut current = &mut command;
+    for part in &args.topic {
+        current = current.find_subcommand_mut(part).ok_or_else(|| {
+            JinError::Other(format!(
+                "No help topic or command named '{}'. Topics: {}",
+                args.topic.join(" "),
+                TOPICS.iter().map(|t| t.name).collect::<Vec<_>>().join(", ")
+            ))
+        })?;
+    }
+    let mut current = current
+        .clone()
+        .bin_name(format!("jin {}", args.topic.join(" ")));
+    current.print_long_help()?;
+    Ok(())
+}
+
+/// Fill a guide's placeholders from `state`
+fn render(template: &str, state: &HelpState) -> String {
+    let context = state.context.as_ref();
+    let mode = context.and_then(|c| c.mode.as_deref());
+    let scope = context.and_then(|c| c.scope.as_deref());
+    let project = context.and_then(|c| c.project.as_deref());
+
+    let summary = match context {
+        None => "not in a Jin project (run 'jin init' first)".to_string(),
+        Some(_) => format!(
+            "mode {}, scope {}, project {}",
+            mode.unwrap_or("(none)"),
+            scope.unwrap_or("(none)"),
+            project.unwrap_or("(none)")
+        ),
+    };
+    let conflicts = match state.conflicts.len() {
+        0 => "No apply is paused right now.".to_string(),
+        n => format!(
+            "A paused apply is waiting on {} file(s): {}",
+            n,
+            state.conflicts.join(", ")
+        ),
+    };
+
+    template
+        .replace("{context}", &summary)
+        .replace("{mode}", mode.unwrap_or("<mode>"))
+        .replace("{scope}", scope.unwrap_or("<scope>"))
+        .replace("{project}", project.unwrap_or("<project>"))
+        .replace("{conflicts}", &conflicts)
+        .replace(
+            "{conflict_file}",
+            state.conflicts.first().map_or("<file>", String::as_str),
+        )
+        .replace("{remote}", state.remote.as_deref().unwrap_or("none linked"))
+        .replace("{remote_url}", state.remote.as_deref().unwrap_or("<url>"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_uses_active_context() {
+        let state = HelpState {
+            context: Some(ProjectContext {
+                mode: Some("claude".to_string()),
+                project: Some("ui".to_string()),
+                ..Default::default()
+            }),
+            conflicts: vec!["notes.txt".to_string()],
+            remote: None,
+        };
+
+        let layers = render(TOPICS[0].text, &state);
+        assert!(layers.contains("Your context: mode claude, scope (none), project ui"));
+        assert!(layers.contains("Store a file for everyone using mode claude:"));
+        assert!(layers.contains("--scope=<scope>"));
+
+        let conflicts = render(TOPICS[1].text, &state);
+        assert!(conflicts.contains("waiting on 1 file(s): notes.txt"));
+        assert!(conflicts.contains("jin resolve --interactive notes.txt"));
+
+        let syncing = render(TOPICS[2].text, &state);
+        assert!(syncing.contains("Remote: none linked"));
+        assert!(syncing.contains("jin link <url>"));
+        assert!(syncing.contains("--layer mode/claude"));
+    }
+
+    #[test]
+    fn test_topics_have_no_unknown_placeholders() {
+        let state = HelpState::default();
+        for topic in TOPICS {
+            let text = render(topic.text, &state);
+            assert!(
+                !text.contains('{'),
+                "{} has an unfilled placeholder",
+                topic.name
+            );
+            assert!(text.contains("not in a Jin project"));
+        }
+    }
+}
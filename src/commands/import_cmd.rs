@@ -6,15 +6,26 @@
 //! 2. Removes it from Git index (keeping in workspace)
 //! 3. Stages it to Jin using the same logic as `jin add`
 //! 4. Updates .gitignore to prevent Git from tracking it again
-
-use crate::cli::ImportArgs;
+//!
+//! When the target layer already has a version of the file (committed or
+//! staged), `--on-exists` decides what is staged: `overwrite` (the default)
+//! stages the workspace version, `skip` leaves the file in Git, `merge`
+//! deep-merges the workspace version over the layer's with the structured
+//! merge engine (and writes the result to the workspace), and `prompt` asks
+//! for each file. A line per file reports what happened.
+
+use crate::cli::{ImportArgs, OnExists};
 use crate::core::{JinError, Layer, ProjectContext, Result};
-use crate::git::{JinRepo, ObjectOps};
+use crate::git::{JinRepo, ObjectOps, Oid, TreeOps};
+use crate::merge::{
+    deep_merge_with_config, detect_format, parse_content, registry, serialize_content, MergeOptions,
+};
 use crate::staging::{
     ensure_in_managed_block, get_file_mode, is_git_tracked, is_symlink, read_file, route_to_layer,
     validate_routing_options, walk_directory, RoutingOptions, StagedEntry, StagedOperation,
     StagingIndex,
 };
+use std::io::{self, BufRead, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -39,14 +50,14 @@ use std::process::Command;
 /// - A file is NOT tracked by Git
 /// - Git rm command fails
 /// - Routing options are invalid
-pub fn execute(args: ImportArgs) -> Result<()> {
+pub fn execute(args: ImportArgs, root: &Path) -> Result<()> {
     // 1. Validate we have files to import
     if args.files.is_empty() {
         return Err(JinError::Other("No files specified".to_string()));
     }
 
     // 2. Load project context for active mode/scope
-    let context = match ProjectContext::load() {
+    let context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -68,22 +79,46 @@ pub fn execute(args: ImportArgs) -> Result<()> {
     let target_layer = route_to_layer(&options, &context)?;
 
     // 5. Open Jin repository
-    let repo = JinRepo::open_or_create()?;
-
-    // 6. Load staging index
-    let mut staging = StagingIndex::load().unwrap_or_else(|_| StagingIndex::new());
+    let repo = JinRepo::open_or_create(root)?;
+
+    // 6. Load staging index, and the target layer's committed files
+    let mut staging = StagingIndex::load(root).unwrap_or_else(|_| StagingIndex::new());
+    let ref_path = target_layer.ref_path(
+        context.mode.as_deref(),
+        args.scope.as_deref().or(context.scope.as_deref()),
+        context.project.as_deref(),
+    );
+    let layer_tree = repo
+        .inner()
+        .find_reference(&ref_path)
+        .and_then(|r| r.peel_to_commit())
+        .map(|c| c.tree_id())
+        .ok();
 
     // 7. Process each file with atomic rollback
+    let stdin = io::stdin();
+    let mut importer = Importer {
+        layer: target_layer,
+        repo: &repo,
+        layer_tree,
+        on_exists: args.on_exists,
+        force: args.force,
+        staging: &mut staging,
+        git_removed_files: Vec::new(),
+        merged_writes: Vec::new(),
+        input: stdin.lock(),
+        output: io::stdout(),
+    };
     let mut imported_count = 0;
+    let mut outcomes = Vec::new();
     let mut errors = Vec::new();
-    let mut git_removed_files = Vec::new(); // Track for rollback
 
     for path_str in &args.files {
         let path = PathBuf::from(path_str);
 
         // Expand directories
-        let files_to_import = if path.is_dir() {
-            match walk_directory(&path) {
+        let files_to_import = if root.join(&path).is_dir() {
+            match walk_directory(root, &path) {
                 Ok(files) => files,
                 Err(e) => {
                     errors.push(format!("{}: {}", path.display(), e));
@@ -95,22 +130,18 @@ pub fn execute(args: ImportArgs) -> Result<()> {
         };
 
         for file_path in files_to_import {
-            match import_file(
-                &file_path,
-                target_layer,
-                &repo,
-                &mut staging,
-                &mut git_removed_files,
-                args.force,
-            ) {
-                Ok(_) => {
-                    imported_count += 1;
+            match importer.import(root, &file_path) {
+                Ok(outcome) => {
+                    if outcome != Outcome::Skipped {
+                        imported_count += 1;
+                    }
+                    outcomes.push((file_path, outcome));
                 }
                 Err(e) => {
                     // Rollback: re-add all previously removed files back to Git
-                    if !git_removed_files.is_empty() {
+                    if !importer.git_removed_files.is_empty() {
                         eprintln!("Error occurred, rolling back changes...");
-                        rollback_git_removals(&git_removed_files);
+                        rollback_git_removals(root, &importer.git_removed_files);
                     }
                     errors.push(format!("{}: {}", file_path.display(), e));
                     break; // Stop processing on first error
@@ -123,10 +154,19 @@ pub fn execute(args: ImportArgs) -> Result<()> {
             break;
         }
     }
+    let merged_writes = std::mem::take(&mut importer.merged_writes);
+    drop(importer);
+
+    // Merged versions replace the workspace files once every import succeeded
+    if errors.is_empty() {
+        for (path, content) in &merged_writes {
+            std::fs::write(root.join(path), content)?;
+        }
+    }
 
     // 8. Save staging index only if we had successful imports
     if imported_count > 0 {
-        staging.save()?;
+        staging.save(root)?;
     }
 
     // 9. Print summary
@@ -137,6 +177,11 @@ pub fn execute(args: ImportArgs) -> Result<()> {
             format_layer_name(target_layer)
         );
     }
+    if errors.is_empty() {
+        for (path, outcome) in &outcomes {
+            println!("  {}: {}", path.display(), outcome);
+        }
+    }
 
     if !errors.is_empty() {
         for error in &errors {
@@ -153,64 +198,183 @@ pub fn execute(args: ImportArgs) -> Result<()> {
     Ok(())
 }
 
-/// Import a single file from Git to Jin
-///
-/// This performs the complete import process:
-/// 1. Validate the file is Git-tracked
-/// 2. Remove from Git index
-/// 3. Stage to Jin
-/// 4. Update .gitignore
-///
-/// # Arguments
-///
-/// * `path` - Path to the file to import
-/// * `layer` - Target layer for the file
-/// * `repo` - Jin repository
-/// * `staging` - Staging index to add the file to
-/// * `git_removed_files` - List of files removed from Git (for rollback)
-/// * `force` - Skip modification check if true
-fn import_file(
-    path: &Path,
+/// What happened to one file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    /// The layer didn't have the file
+    Added,
+    /// The layer had the same content
+    Unchanged,
+    /// The workspace version replaced the layer's
+    Overwritten,
+    /// The workspace version was merged over the layer's
+    Merged,
+    /// The layer's version was kept and the file left in Git
+    Skipped,
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Added => "added",
+            Self::Unchanged => "unchanged (the layer has the same content)",
+            Self::Overwritten => "overwrote the layer's version",
+            Self::Merged => "merged with the layer's version",
+            Self::Skipped => "skipped (the layer already has it; left in Git)",
+        })
+    }
+}
+
+/// Imports files into one layer
+struct Importer<'a, R, W> {
+    /// Target layer
     layer: Layer,
-    repo: &JinRepo,
-    staging: &mut StagingIndex,
-    git_removed_files: &mut Vec<PathBuf>,
+    /// Jin repository
+    repo: &'a JinRepo,
+    /// Tree of the target layer's commit, if it has one
+    layer_tree: Option<Oid>,
+    /// What to do when the layer has the file
+    on_exists: OnExists,
+    /// Skip the Git modification check
     force: bool,
-) -> Result<()> {
-    // Validate file for import
-    validate_import_file(path, force)?;
-
-    // Remove from Git index (keeping in workspace)
-    remove_from_git(path)?;
-    git_removed_files.push(path.to_path_buf());
-
-    // Read content from workspace
-    let content = read_file(path)?;
-
-    // Create blob in Jin's bare repository
-    let oid = repo.create_blob(&content)?;
-
-    // Get file mode (executable or regular)
-    let mode = get_file_mode(path);
-
-    // Create staged entry
-    let entry = StagedEntry {
-        path: path.to_path_buf(),
-        target_layer: layer,
-        content_hash: oid.to_string(),
-        mode,
-        operation: StagedOperation::AddOrModify,
-    };
+    /// Staging index the files are added to
+    staging: &'a mut StagingIndex,
+    /// Files removed from Git so far (for rollback)
+    git_removed_files: Vec<PathBuf>,
+    /// Merged content to write to the workspace once all files are imported
+    merged_writes: Vec<(PathBuf, Vec<u8>)>,
+    /// Answers for `--on-exists prompt`
+    input: R,
+    /// Where prompts go
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Importer<'_, R, W> {
+    /// Import a single file from Git to Jin
+    ///
+    /// This performs the complete import process:
+    /// 1. Validate the file is Git-tracked
+    /// 2. Settle what to stage when the layer already has the file
+    /// 3. Remove from Git index
+    /// 4. Stage to Jin
+    /// 5. Update .gitignore
+    fn import(&mut self, root: &Path, path: &Path) -> Result<Outcome> {
+        // Validate file for import
+        validate_import_file(root, path, self.force)?;
+
+        // Read content from workspace
+        let location = root.join(path);
+        let mut content = read_file(&location)?;
+
+        let outcome = match self.existing_version(path)? {
+            None => Outcome::Added,
+            Some(existing) if existing == content => Outcome::Unchanged,
+            Some(existing) => match self.policy_for(path)? {
+                OnExists::Skip => return Ok(Outcome::Skipped),
+                OnExists::Merge => {
+                    content = merge_versions(path, &existing, &content)?;
+                    self.merged_writes
+                        .push((path.to_path_buf(), content.clone()));
+                    Outcome::Merged
+                }
+                _ => Outcome::Overwritten,
+            },
+        };
+
+        // Remove from Git index (keeping in workspace)
+        remove_from_git(root, path)?;
+        self.git_removed_files.push(path.to_path_buf());
 
-    // Add to staging index
-    staging.add(entry);
+        // Create blob in Jin's bare repository
+        let oid = self.repo.create_blob(&content)?;
 
-    // Add to .gitignore managed block
-    if let Err(e) = ensure_in_managed_block(path) {
-        eprintln!("Warning: Could not update .gitignore: {}", e);
+        // Get file mode (executable or regular)
+        let mode = get_file_mode(&location);
+
+        // Create staged entry
+        let entry = StagedEntry {
+            path: path.to_path_buf(),
+            target_layer: self.layer,
+            content_hash: oid.to_string(),
+            mode,
+            operation: StagedOperation::AddOrModify,
+        };
+
+        // Add to staging index
+        self.staging.add(entry);
+
+        // Add to .gitignore managed block
+        if let Err(e) = ensure_in_managed_block(root, path) {
+            eprintln!("Warning: Could not update .gitignore: {}", e);
+        }
+
+        Ok(outcome)
     }
 
-    Ok(())
+    /// The layer's version of a file: staged for the layer, or committed
+    fn existing_version(&self, path: &Path) -> Result<Option<Vec<u8>>> {
+        if let Some(entry) = self.staging.get(path) {
+            if entry.target_layer == self.layer && entry.operation == StagedOperation::AddOrModify {
+                let oid = Oid::from_str(&entry.content_hash)?;
+                return Ok(Some(self.repo.read_blob_content(oid)?));
+            }
+        }
+        match self.layer_tree {
+            Some(tree) => Ok(self.repo.read_file_from_tree(tree, path).ok()),
+            None => Ok(None),
+        }
+    }
+
+    /// The policy for a file the layer already has, asking with `prompt`
+    ///
+    /// End of input counts as skipping.
+    fn policy_for(&mut self, path: &Path) -> Result<OnExists> {
+        if self.on_exists != OnExists::Prompt {
+            return Ok(self.on_exists);
+        }
+        loop {
+            write!(
+                self.output,
+                "{} already exists in the {} layer. [s]kip / [o]verwrite / [m]erge: ",
+                path.display(),
+                format_layer_name(self.layer)
+            )?;
+            self.output.flush()?;
+
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                writeln!(self.output)?;
+                return Ok(OnExists::Skip);
+            }
+            match line.trim() {
+                "s" => return Ok(OnExists::Skip),
+                "o" => return Ok(OnExists::Overwrite),
+                "m" => return Ok(OnExists::Merge),
+                other => writeln!(self.output, "Unknown choice '{}'", other)?,
+            }
+        }
+    }
+}
+
+/// Deep-merge the workspace version of a structured file over the layer's
+fn merge_versions(path: &Path, existing: &[u8], incoming: &[u8]) -> Result<Vec<u8>> {
+    let format = detect_format(path);
+    if !format.is_structured() {
+        return Err(JinError::Other(format!(
+            "{} is not a structured file and can't be merged. Use --on-exists overwrite or skip.",
+            path.display()
+        )));
+    }
+    let parse = |content: &[u8]| {
+        let text = String::from_utf8(content.to_vec()).map_err(|_| JinError::Parse {
+            format: "UTF-8".to_string(),
+            message: format!("{} is not valid UTF-8", path.display()),
+        })?;
+        parse_content(&text, format)
+    };
+    let config = MergeOptions::current().merge_config(registry().merge_hints(format));
+    let merged = deep_merge_with_config(parse(existing)?, parse(incoming)?, &config)?;
+    Ok(serialize_content(&merged, format)?.into_bytes())
 }
 
 /// Validate a file for import
@@ -220,14 +384,16 @@ fn import_file(
 /// - File is not a directory
 /// - File is not a symlink
 /// - File IS tracked by Git (opposite of `jin add`)
-fn validate_import_file(path: &Path, force: bool) -> Result<()> {
+fn validate_import_file(root: &Path, path: &Path, force: bool) -> Result<()> {
+    let location = root.join(path);
+
     // Check file exists
-    if !path.exists() {
+    if !location.exists() {
         return Err(JinError::NotFound(path.display().to_string()));
     }
 
     // Check not a directory (should have been expanded)
-    if path.is_dir() {
+    if location.is_dir() {
         return Err(JinError::Other(format!(
             "{} is a directory, not a file",
             path.display()
@@ -235,14 +401,14 @@ fn validate_import_file(path: &Path, force: bool) -> Result<()> {
     }
 
     // Check not a symlink
-    if is_symlink(path)? {
+    if is_symlink(&location)? {
         return Err(JinError::Symlink {
             path: path.display().to_string(),
         });
     }
 
     // Check IS tracked by project's Git (opposite of add.rs)
-    if !is_git_tracked(path)? {
+    if !is_git_tracked(root, path)? {
         return Err(JinError::Other(format!(
             "{} is not tracked by Git. Use `jin add` instead.",
             path.display()
@@ -250,7 +416,7 @@ fn validate_import_file(path: &Path, force: bool) -> Result<()> {
     }
 
     // If not force mode, check if file is modified
-    if !force && is_git_modified(path)? {
+    if !force && is_git_modified(root, path)? {
         return Err(JinError::Other(format!(
             "{} has uncommitted changes in Git. Use --force to import anyway.",
             path.display()
@@ -266,13 +432,15 @@ fn validate_import_file(path: &Path, force: bool) -> Result<()> {
 ///
 /// # Arguments
 ///
+/// * `root` - Workspace root the command runs in
 /// * `path` - Path to remove from Git
 ///
 /// # Errors
 ///
 /// Returns an error if the git rm command fails
-fn remove_from_git(path: &Path) -> Result<()> {
+fn remove_from_git(root: &Path, path: &Path) -> Result<()> {
     let output = Command::new("git")
+        .current_dir(root)
         .arg("rm")
         .arg("--cached")
         .arg(path)
@@ -294,8 +462,9 @@ fn remove_from_git(path: &Path) -> Result<()> {
 /// Check if a file has uncommitted changes in Git
 ///
 /// Uses `git diff-index` to check if the file differs from HEAD.
-fn is_git_modified(path: &Path) -> Result<bool> {
+fn is_git_modified(root: &Path, path: &Path) -> Result<bool> {
     let output = Command::new("git")
+        .current_dir(root)
         .arg("diff-index")
         .arg("--quiet")
         .arg("HEAD")
@@ -311,9 +480,13 @@ fn is_git_modified(path: &Path) -> Result<bool> {
 /// Rollback Git removals by re-adding files to Git index
 ///
 /// This is called when an error occurs during import to restore the Git state.
-fn rollback_git_removals(files: &[PathBuf]) {
+fn rollback_git_removals(root: &Path, files: &[PathBuf]) {
     for file in files {
-        let output = Command::new("git").arg("add").arg(file).output();
+        let output = Command::new("git")
+            .current_dir(root)
+            .arg("add")
+            .arg(file)
+            .output();
 
         match output {
             Ok(output) if output.status.success() => {
@@ -353,14 +526,14 @@ mod tests {
     #[test]
     fn test_validate_import_file_not_found() {
         let path = PathBuf::from("/nonexistent/file.txt");
-        let result = validate_import_file(&path, false);
+        let result = validate_import_file(Path::new("."), &path, false);
         assert!(matches!(result, Err(JinError::NotFound(_))));
     }
 
     #[test]
     fn test_validate_import_file_is_directory() {
         let temp = TempDir::new().unwrap();
-        let result = validate_import_file(temp.path(), false);
+        let result = validate_import_file(Path::new("."), temp.path(), false);
         assert!(result.is_err());
     }
 
@@ -374,7 +547,7 @@ mod tests {
         let link = temp.path().join("link.txt");
         symlink(&file, &link).unwrap();
 
-        let result = validate_import_file(&link, false);
+        let result = validate_import_file(Path::new("."), &link, false);
         assert!(matches!(result, Err(JinError::Symlink { .. })));
     }
 
@@ -395,8 +568,9 @@ mod tests {
             project: false,
             global: false,
             local: false,
+            on_exists: OnExists::Overwrite,
         };
-        let result = execute(args);
+        let result = execute(args, Path::new("."));
         assert!(result.is_err());
     }
 
@@ -1,22 +1,31 @@
 //! Implementation of `jin init`
 
-use crate::core::{ProjectContext, Result};
+use crate::cli::InitArgs;
+use crate::core::workspaces;
+use crate::core::{JinError, ProjectConfig, ProjectContext, Result};
 use crate::git::JinRepo;
+use chrono::Utc;
 use std::fs;
 use std::io::Write;
+use std::path::Path;
 
 /// Execute the init command
 ///
-/// Initializes Jin in the current project directory.
-pub fn execute() -> Result<()> {
+/// Initializes Jin in the current project directory, or in the home
+/// directory with `--home`.
+pub fn execute(args: InitArgs, root: &Path) -> Result<()> {
+    if args.home {
+        return init_home(&args.allow);
+    }
+
     // Check if already initialized
-    if ProjectContext::is_initialized() {
+    if ProjectContext::is_initialized(root) {
         println!("Jin is already initialized in this directory");
         return Ok(());
     }
 
     // Create .jin directory
-    let jin_dir = ProjectContext::default_path()
+    let jin_dir = ProjectContext::default_path(root)
         .parent()
         .expect("context path should have parent")
         .to_path_buf();
@@ -25,13 +34,16 @@ pub fn execute() -> Result<()> {
 
     // Create default context
     let context = ProjectContext::default();
-    context.save()?;
+    context.save(root)?;
 
-    // Ensure global Jin repository exists
-    JinRepo::open_or_create()?;
+    // Ensure global Jin repository exists and knows this workspace
+    let repo = JinRepo::open_or_create(root)?;
+    workspaces::record(repo.path(), root, |registry, root| {
+        registry.mark_initialized(root, None, Utc::now())
+    });
 
     // Add .jin/ to .gitignore if not already present
-    add_to_gitignore(".jin/")?;
+    add_to_gitignore(root, ".jin/")?;
 
     println!("Initialized Jin in {}", jin_dir.display());
     println!();
@@ -43,13 +55,59 @@ pub fn execute() -> Result<()> {
     Ok(())
 }
 
-/// Add an entry to .gitignore if not already present
-fn add_to_gitignore(entry: &str) -> Result<()> {
-    let gitignore_path = std::path::Path::new(".gitignore");
+/// Set up (or extend the allowlist of) the home workspace
+///
+/// The home directory may already contain `.jin` as the global Jin
+/// directory, so initialization is detected by the context file rather than
+/// the directory.
+fn init_home(allow: &[String]) -> Result<()> {
+    let home = dirs::home_dir()
+        .ok_or_else(|| JinError::Config("Cannot determine home directory".into()))?;
+    let root = home.as_path();
+
+    let mut config = ProjectConfig::load(root)?;
+    let existing = config.home.is_some();
+    let mut home_config = config.home.take().unwrap_or_default();
+    home_config.allow_patterns(allow)?;
+
+    if !ProjectContext::default_path(root).exists() {
+        ProjectContext::default().save(root)?;
+    }
+    config.home = Some(home_config);
+    config.save(root)?;
+
+    // Ensure global Jin repository exists and knows this workspace
+    let repo = JinRepo::open_or_create(root)?;
+    workspaces::record(repo.path(), &home, |registry, root| {
+        registry.mark_initialized(root, None, Utc::now())
+    });
+
+    if existing {
+        println!("Updated home workspace in {}", home.display());
+    } else {
+        println!("Initialized Jin home workspace in {}", home.display());
+    }
+    println!();
+    println!("Managed paths (relative to {}):", home.display());
+    for pattern in config.home.iter().flat_map(|h| &h.allow) {
+        println!("  {}", pattern);
+    }
+    println!();
+    println!("Next steps:");
+    println!("  1. Add dotfiles:      jin -C ~ add .bashrc --global");
+    println!("  2. Commit them:       jin -C ~ commit -m \"Add dotfiles\"");
+    println!("  3. Allow more paths:  jin init --home --allow '<pattern>'");
+
+    Ok(())
+}
+
+/// Add an entry to the workspace's .gitignore if not already present
+fn add_to_gitignore(root: &Path, entry: &str) -> Result<()> {
+    let gitignore_path = root.join(".gitignore");
 
     // Check if entry already exists and determine if we need a leading newline
     let needs_newline = if gitignore_path.exists() {
-        let contents = fs::read_to_string(gitignore_path)?;
+        let contents = fs::read_to_string(&gitignore_path)?;
         for line in contents.lines() {
             if line.trim() == entry || line.trim() == entry.trim_end_matches('/') {
                 return Ok(()); // Already present
@@ -64,7 +122,7 @@ fn add_to_gitignore(entry: &str) -> Result<()> {
     let mut file = fs::OpenOptions::new()
         .create(true)
         .append(true)
-        .open(gitignore_path)?;
+        .open(&gitignore_path)?;
 
     if needs_newline {
         writeln!(file)?;
@@ -1,37 +1,217 @@
 //! Implementation of `jin layers`
 //!
-//! Shows current layer composition and merge order.
+//! Shows current layer composition and merge order, or previews it for a
+//! hypothetical context with `--for <mode> <scope> <project>` without
+//! switching to it. Layers are resolved exactly as `jin apply` resolves
+//! them (see [`get_applicable_layers`]).
+//!
+//! `--graph dot` or `--graph mermaid` prints the stack as a diagram for
+//! documentation: layers in merge order, the bindings between mode, scope
+//! and project layers, and whether each layer's ref has files, is empty or
+//! doesn't exist yet.
+//!
+//! When the Jin repository can't be opened, the stack recorded by the last
+//! `jin apply` is shown instead (see [`OfflineCache`]).
 
-use crate::core::{JinError, Layer, ProjectContext, Result};
+use crate::cli::{LayersArgs, LayersFormat, LayersGraph};
+use crate::core::schema::{self, JsonSurface};
+use crate::core::{BranchScope, JinError, Layer, OfflineCache, ProjectContext, Result};
 use crate::git::JinRepo;
+use crate::merge::get_applicable_layers;
+use chrono::Utc;
+use serde::Serialize;
+use std::path::Path;
+
+/// One layer of the resolved stack
+#[derive(Debug, Serialize)]
+struct LayerRow {
+    #[serde(skip)]
+    kind: Layer,
+    precedence: u8,
+    layer: String,
+    #[serde(rename = "ref")]
+    ref_path: String,
+    storage: String,
+    exists: bool,
+    files: usize,
+}
+
+/// Resolved stack for a context (the JSON output)
+#[derive(Debug, Serialize)]
+struct Resolution {
+    schema_version: u32,
+    mode: Option<String>,
+    scope: Option<String>,
+    project: Option<String>,
+    preview: bool,
+    /// When the stack was recorded, if the repository was unavailable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cached_at: Option<String>,
+    layers: Vec<LayerRow>,
+}
 
 /// Execute the layers command
 ///
 /// Shows current layer composition and merge order.
-pub fn execute() -> Result<()> {
-    // Load project context
-    let context = match ProjectContext::load() {
+pub fn execute(args: LayersArgs, root: &Path) -> Result<()> {
+    // Load project context (a preview works outside a project too)
+    let context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
-        Err(JinError::NotInitialized) => {
+        Err(JinError::NotInitialized) if args.for_context.is_none() => {
             return Err(JinError::NotInitialized);
         }
         Err(_) => ProjectContext::default(),
     };
+    // An unreachable repository leaves the stack of the last apply
+    let preview = args.for_context.is_some();
+    let offline = if preview {
+        None
+    } else {
+        OfflineCache::fallback(root)
+    };
 
-    // Open Jin repository to check which layers have commits
-    let repo = JinRepo::open_or_create()?;
-    let git_repo = repo.inner();
+    let resolution = match &offline {
+        Some(cache) => {
+            eprintln!("{}", cache.banner(Utc::now()));
+            cached_resolution(cache)
+        }
+        None => {
+            // Open Jin repository to check which layers have commits
+            let repo = JinRepo::open_or_create(root)?;
+            let context = match &args.for_context {
+                Some(parts) => hypothetical_context(parts, &context),
+                None => {
+                    let mut context = context;
+                    BranchScope::activate(root, &mut context, &repo);
+                    context
+                }
+            };
+            resolve(&context, preview, repo.inner())
+        }
+    };
+
+    if let Some(graph) = args.graph {
+        print!(
+            "{}",
+            match graph {
+                LayersGraph::Dot => render_dot(&resolution),
+                LayersGraph::Mermaid => render_mermaid(&resolution),
+            }
+        );
+        return Ok(());
+    }
 
+    if schema::json_output() || args.format == LayersFormat::Json {
+        return schema::print_json(&resolution);
+    }
+    print_table(&resolution);
+    Ok(())
+}
+
+/// Context from `--for` values, taking the project from `current` when it
+/// is not given
+fn hypothetical_context(parts: &[String], current: &ProjectContext) -> ProjectContext {
+    let part = |index: usize| {
+        parts
+            .get(index)
+            .filter(|p| !p.is_empty() && p.as_str() != "-")
+            .cloned()
+    };
+    ProjectContext {
+        mode: part(0),
+        scope: part(1),
+        project: if parts.len() > 2 {
+            part(2)
+        } else {
+            current.project.clone()
+        },
+        ..ProjectContext::default()
+    }
+}
+
+/// Resolve the layer stack for a context and look up each layer's ref
+fn resolve(context: &ProjectContext, preview: bool, repo: &git2::Repository) -> Resolution {
+    let mode = context.mode.as_deref();
+    let scope = context.scope.as_deref();
+    let project = context.project.as_deref();
+
+    let layers = get_applicable_layers(mode, scope, project)
+        .into_iter()
+        .map(|layer| {
+            let ref_path = layer.ref_path(mode, scope, project);
+            let exists = repo.find_reference(&ref_path).is_ok();
+            let files = if exists {
+                count_files_in_layer(repo, &ref_path).unwrap_or(0)
+            } else {
+                0
+            };
+            LayerRow {
+                kind: layer,
+                precedence: layer.precedence(),
+                layer: layer.to_string(),
+                storage: layer.storage_path(mode, scope, project),
+                ref_path,
+                exists,
+                files,
+            }
+        })
+        .collect();
+
+    Resolution {
+        schema_version: JsonSurface::Layers.version(),
+        mode: context.mode.clone(),
+        scope: context.scope.clone(),
+        project: context.project.clone(),
+        preview,
+        cached_at: None,
+        layers,
+    }
+}
+
+/// The stack of the last apply
+fn cached_resolution(cache: &OfflineCache) -> Resolution {
+    Resolution {
+        schema_version: JsonSurface::Layers.version(),
+        mode: cache.mode.clone(),
+        scope: cache.scope.clone(),
+        project: cache.project.clone(),
+        preview: false,
+        cached_at: Some(cache.saved_at.to_rfc3339()),
+        layers: cache
+            .layers
+            .iter()
+            .filter_map(|layer| {
+                let kind = layer.kind()?;
+                Some(LayerRow {
+                    kind,
+                    precedence: kind.precedence(),
+                    layer: layer.layer.clone(),
+                    ref_path: layer.ref_path.clone(),
+                    storage: layer.storage.clone(),
+                    exists: layer.commit.is_some(),
+                    files: layer.files,
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Print the stack as a table
+fn print_table(resolution: &Resolution) {
     // Display header
-    println!("Layer composition for current context:");
-    if context.mode.is_some() || context.scope.is_some() || context.project.is_some() {
-        if let Some(mode) = &context.mode {
+    if resolution.preview {
+        println!("Layer composition for previewed context (not active):");
+    } else {
+        println!("Layer composition for current context:");
+    }
+    if resolution.mode.is_some() || resolution.scope.is_some() || resolution.project.is_some() {
+        if let Some(mode) = &resolution.mode {
             println!("  Mode:    {}", mode);
         }
-        if let Some(scope) = &context.scope {
+        if let Some(scope) = &resolution.scope {
             println!("  Scope:   {}", scope);
         }
-        if let Some(project) = &context.project {
+        if let Some(project) = &resolution.project {
             println!("  Project: {}", project);
         }
     } else {
@@ -42,68 +222,180 @@ pub fn execute() -> Result<()> {
     // Display layers in precedence order
     println!("Merge order (lowest to highest precedence):");
 
-    let all_layers = Layer::all_in_precedence_order();
-    let mut active_count = 0;
-    let mut total_files = 0;
-
-    for layer in &all_layers {
-        // Skip layers that don't apply to current context
-        if layer.requires_mode() && context.mode.is_none() {
-            continue;
-        }
-        if layer.requires_scope() && context.scope.is_none() {
-            continue;
-        }
-
-        // Get ref path for this layer
-        let ref_path = layer.ref_path(
-            context.mode.as_deref(),
-            context.scope.as_deref(),
-            context.project.as_deref(),
-        );
-
-        // Check if layer has commits
-        let has_commits = git_repo.find_reference(&ref_path).is_ok();
-        let file_count = if has_commits {
-            active_count += 1;
-            count_files_in_layer(git_repo, &ref_path).unwrap_or(0)
+    for row in &resolution.layers {
+        let status = if row.exists { "✓" } else { " " };
+        let detail = if row.files > 0 {
+            format!(" ({} files)", row.files)
+        } else if resolution.preview && !row.exists {
+            format!(" (missing: {})", row.ref_path)
         } else {
-            0
+            String::new()
         };
-        total_files += file_count;
-
-        // Format storage path
-        let storage_path = layer.storage_path(
-            context.mode.as_deref(),
-            context.scope.as_deref(),
-            context.project.as_deref(),
-        );
-
-        // Display layer
-        let status = if has_commits { "✓" } else { " " };
         println!(
             "  {} {:2}. {:<20} [{}]{}",
-            status,
-            layer.precedence(),
-            layer.to_string(),
-            storage_path,
-            if file_count > 0 {
-                format!(" ({} files)", file_count)
-            } else {
-                String::new()
-            }
+            status, row.precedence, row.layer, row.storage, detail
         );
     }
 
+    let active_count = resolution.layers.iter().filter(|r| r.exists).count();
+    let total_files: usize = resolution.layers.iter().map(|r| r.files).sum();
     println!();
     println!(
         "Active layers: {} of {} layers have files",
         active_count,
-        all_layers.len()
+        Layer::all_in_precedence_order().len()
     );
     println!("Total files in workspace: {}", total_files);
+}
 
-    Ok(())
+/// The layer a layer is bound to, with the kind of binding
+///
+/// Mode-bound scopes belong to their mode, and project layers to the mode
+/// or scope they override.
+fn binding(layer: Layer) -> Option<(Layer, &'static str)> {
+    match layer {
+        Layer::ModeScope => Some((Layer::ModeBase, "bound scope")),
+        Layer::ModeScopeProject => Some((Layer::ModeScope, "project binding")),
+        Layer::ModeProject => Some((Layer::ModeBase, "project binding")),
+        _ => None,
+    }
+}
+
+/// Bindings between layers of the stack, as (layer, bound to, kind)
+fn bindings(resolution: &Resolution) -> Vec<(&LayerRow, &LayerRow, &'static str)> {
+    resolution
+        .layers
+        .iter()
+        .filter_map(|row| {
+            let (parent, kind) = binding(row.kind)?;
+            let parent = resolution.layers.iter().find(|r| r.kind == parent)?;
+            Some((row, parent, kind))
+        })
+        .collect()
+}
+
+/// Short state of a layer's ref for diagrams
+fn ref_state(row: &LayerRow) -> String {
+    match (row.exists, row.files) {
+        (false, _) => "no ref".to_string(),
+        (true, 0) => "empty".to_string(),
+        (true, 1) => "1 file".to_string(),
+        (true, n) => format!("{} files", n),
+    }
+}
+
+/// Title of a diagram: the context it shows
+fn graph_title(resolution: &Resolution) -> String {
+    let parts: Vec<String> = [
+        ("mode", &resolution.mode),
+        ("scope", &resolution.scope),
+        ("project", &resolution.project),
+    ]
+    .iter()
+    .filter_map(|(name, value)| value.as_ref().map(|v| format!("{}={}", name, v)))
+    .collect();
+    if parts.is_empty() {
+        "Jin layers (no active mode/scope/project)".to_string()
+    } else {
+        format!("Jin layers ({})", parts.join(" "))
+    }
+}
+
+/// The stack as a Graphviz DOT digraph, lowest layer at the bottom
+fn render_dot(resolution: &Resolution) -> String {
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut out = String::from("digraph jin_layers {\n");
+    out.push_str("  rankdir=BT;\n");
+    out.push_str(&format!("  label={};\n", quote(&graph_title(resolution))));
+    out.push_str("  node [shape=box, style=rounded];\n");
+    for row in &resolution.layers {
+        let label = format!(
+            "{}. {}\\n{}\\n{}",
+            row.precedence,
+            row.layer,
+            row.storage.replace('"', "\\\""),
+            ref_state(row)
+        );
+        let style = match (row.exists, row.files) {
+            (false, _) => ", style=\"rounded,dashed\", fontcolor=gray50, color=gray50",
+            (true, 0) => ", style=\"rounded,dotted\"",
+            _ => "",
+        };
+        out.push_str(&format!(
+            "  {} [label=\"{}\"{}];\n",
+            quote(&row.layer),
+            label,
+            style
+        ));
+    }
+    for pair in resolution.layers.windows(2) {
+        out.push_str(&format!(
+            "  {} -> {};\n",
+            quote(&pair[0].layer),
+            quote(&pair[1].layer)
+        ));
+    }
+    for (row, parent, kind) in bindings(resolution) {
+        out.push_str(&format!(
+            "  {} -> {} [style=dotted, arrowhead=none, label={}, constraint=false];\n",
+            quote(&row.layer),
+            quote(&parent.layer),
+            quote(kind)
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// The stack as a Mermaid flowchart, lowest layer at the bottom
+fn render_mermaid(resolution: &Resolution) -> String {
+    let id = |row: &LayerRow| row.layer.replace('-', "_");
+    let mut out = format!(
+        "---\ntitle: {}\n---\nflowchart BT\n",
+        graph_title(resolution)
+    );
+    for row in &resolution.layers {
+        out.push_str(&format!(
+            "  {}[\"{}. {}<br/>{}<br/>{}\"]\n",
+            id(row),
+            row.precedence,
+            row.layer,
+            row.storage.replace('"', "#quot;"),
+            ref_state(row)
+        ));
+    }
+    for pair in resolution.layers.windows(2) {
+        out.push_str(&format!("  {} --> {}\n", id(&pair[0]), id(&pair[1])));
+    }
+    for (row, parent, kind) in bindings(resolution) {
+        out.push_str(&format!("  {} -.-|{}| {}\n", id(row), kind, id(parent)));
+    }
+    out.push_str("  classDef missing stroke-dasharray: 5 5,color:#888\n");
+    out.push_str("  classDef empty stroke-dasharray: 2 2\n");
+    for (class, rows) in [
+        (
+            "missing",
+            resolution
+                .layers
+                .iter()
+                .filter(|r| !r.exists)
+                .collect::<Vec<_>>(),
+        ),
+        (
+            "empty",
+            resolution
+                .layers
+                .iter()
+                .filter(|r| r.exists && r.files == 0)
+                .collect(),
+        ),
+    ] {
+        if !rows.is_empty() {
+            let ids: Vec<String> = rows.into_iter().map(id).collect();
+            out.push_str(&format!("  class {} {}\n", ids.join(","), class));
+        }
+    }
+    out
 }
 
 /// Count files in a layer by walking its tree
@@ -129,6 +421,14 @@ mod tests {
     use serial_test::serial;
     use tempfile::TempDir;
 
+    fn table_args(for_context: Option<Vec<&str>>) -> LayersArgs {
+        LayersArgs {
+            for_context: for_context.map(|parts| parts.into_iter().map(String::from).collect()),
+            format: LayersFormat::Table,
+            graph: None,
+        }
+    }
+
     /// These tests modify global process state (environment variables and current directory).
     /// Tests are marked with #[serial] to prevent parallel execution issues.
     fn setup_test_env() -> TempDir {
@@ -147,7 +447,7 @@ mod tests {
         // Initialize .jin directory and context
         std::fs::create_dir_all(temp.path().join(".jin")).unwrap();
         let context = ProjectContext::default();
-        context.save().unwrap();
+        context.save(temp.path()).unwrap();
 
         temp
     }
@@ -156,7 +456,7 @@ mod tests {
     #[serial]
     fn test_execute_default_context() {
         let _temp = setup_test_env();
-        let result = execute();
+        let result = execute(table_args(None), Path::new("."));
         assert!(result.is_ok());
     }
 
@@ -166,12 +466,12 @@ mod tests {
         let _temp = setup_test_env();
 
         // Set mode and scope
-        let mut context = ProjectContext::load().unwrap();
+        let mut context = ProjectContext::load(Path::new(".")).unwrap();
         context.mode = Some("testmode".to_string());
         context.scope = Some("testscope".to_string());
-        context.save().unwrap();
+        context.save(Path::new(".")).unwrap();
 
-        let result = execute();
+        let result = execute(table_args(None), Path::new("."));
         assert!(result.is_ok());
     }
 
@@ -182,10 +482,94 @@ mod tests {
         std::env::set_current_dir(temp.path()).unwrap();
 
         // Don't initialize .jin
-        let result = execute();
+        let result = execute(table_args(None), temp.path());
         assert!(matches!(result, Err(JinError::NotInitialized)));
     }
 
+    #[test]
+    fn test_hypothetical_context() {
+        let current = ProjectContext {
+            mode: Some("active".to_string()),
+            project: Some("app".to_string()),
+            ..ProjectContext::default()
+        };
+        let parts = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+
+        let context = hypothetical_context(&parts(&["claude", "backend"]), &current);
+        assert_eq!(context.mode.as_deref(), Some("claude"));
+        assert_eq!(context.scope.as_deref(), Some("backend"));
+        assert_eq!(context.project.as_deref(), Some("app"));
+
+        let context = hypothetical_context(&parts(&["-", "backend", "-"]), &current);
+        assert!(context.mode.is_none());
+        assert_eq!(context.scope.as_deref(), Some("backend"));
+        assert!(context.project.is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn test_preview_works_outside_a_project() {
+        let temp = TempDir::new().unwrap();
+        std::env::set_var("JIN_DIR", temp.path().join(".jin_global"));
+        std::env::set_current_dir(temp.path()).unwrap();
+
+        let result = execute(table_args(Some(vec!["claude", "backend"])), temp.path());
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_json_matches_schema() {
+        let temp = TempDir::new().unwrap();
+        let repo = git2::Repository::init_bare(temp.path().join("repo")).unwrap();
+        let context = ProjectContext {
+            mode: Some("claude".to_string()),
+            project: Some("app".to_string()),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_value(resolve(&context, true, &repo)).unwrap();
+        crate::core::schema::assert_matches(&JsonSurface::Layers.schema(), &json, "layers");
+    }
+
+    fn graph_resolution() -> Resolution {
+        let temp = TempDir::new().unwrap();
+        let repo = git2::Repository::init_bare(temp.path().join("repo")).unwrap();
+        let context = ProjectContext {
+            mode: Some("claude".to_string()),
+            scope: Some("backend".to_string()),
+            project: Some("app".to_string()),
+            ..Default::default()
+        };
+        resolve(&context, true, &repo)
+    }
+
+    #[test]
+    fn test_render_dot() {
+        let dot = render_dot(&graph_resolution());
+        assert!(dot.starts_with("digraph jin_layers {"));
+        assert!(dot.contains("label=\"Jin layers (mode=claude scope=backend project=app)\""));
+        assert!(dot.contains("\"global-base\" -> \"mode-base\";"));
+        assert!(dot.contains("\"mode-scope\" -> \"mode-base\" [style=dotted"));
+        assert!(dot.contains("label=\"bound scope\""));
+        assert!(dot.contains("no ref\", style=\"rounded,dashed\""));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn test_render_mermaid() {
+        let mermaid = render_mermaid(&graph_resolution());
+        assert!(mermaid.contains("flowchart BT"));
+        assert!(mermaid.contains("  global_base --> mode_base\n"));
+        assert!(mermaid.contains("  mode_scope_project -.-|project binding| mode_scope\n"));
+        assert!(mermaid.contains("  class global_base,mode_base"));
+        // Without a mode there is nothing to bind to
+        let temp = TempDir::new().unwrap();
+        let repo = git2::Repository::init_bare(temp.path().join("repo")).unwrap();
+        let mermaid = render_mermaid(&resolve(&ProjectContext::default(), false, &repo));
+        assert!(!mermaid.contains("-.-"));
+        assert!(mermaid.contains("no active mode/scope/project"));
+    }
+
     #[test]
     fn test_count_files_empty_layer() {
         let temp = TempDir::new().unwrap();
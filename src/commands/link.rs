@@ -1,44 +1,138 @@
 //! Implementation of `jin link`
 //!
 //! Links a local Jin installation to a shared remote configuration repository.
-
-use crate::cli::LinkArgs;
+//!
+//! `jin link <url>` links the team remote, `origin`, which fetch and pull
+//! use. More remotes can be linked by name with `jin link add <name> <url>`
+//! (e.g., a backup or a second team's repository), listed with
+//! `jin link list` and unlinked with `jin link remove <name>`. Each remote
+//! is stored in `JinConfig` with its own credentials and default push layers
+//! (`--push-layer`), and fetches into `refs/jin/remote/<name>/layers/*`.
+
+use crate::cli::{LinkAction, LinkArgs};
+use crate::core::config::expand_home;
 use crate::core::{JinConfig, JinError, RemoteConfig, Result};
-use crate::git::JinRepo;
-use git2::{Direction, ErrorCode};
+use crate::git::auth::{setup_auth_callbacks, AuthMethod, RemoteAuth};
+use crate::git::remote::{tracking_prefix, tracking_refspec};
+use crate::git::{JinRepo, RefOps};
+use git2::{Direction, ErrorCode, RemoteCallbacks};
 use regex::Regex;
 use std::collections::HashSet;
+use std::path::Path;
+
+/// A remote to link, from `jin link <url>` or `jin link add`
+struct NewRemote {
+    name: String,
+    url: String,
+    force: bool,
+    auth: Option<AuthMethod>,
+    token_env: Option<String>,
+    ssh_key: Option<String>,
+    token_file: Option<String>,
+    push_layers: Vec<String>,
+}
 
 /// Execute the link command
+pub fn execute(args: LinkArgs, root: &Path) -> Result<()> {
+    match args.action {
+        Some(LinkAction::Add {
+            name,
+            url,
+            force,
+            auth,
+            token_env,
+            ssh_key,
+            token_file,
+            push_layers,
+        }) => add(
+            root,
+            NewRemote {
+                name,
+                url,
+                force,
+                auth,
+                token_env,
+                ssh_key,
+                token_file,
+                push_layers,
+            },
+        ),
+        Some(LinkAction::List) => list(),
+        Some(LinkAction::Remove { name }) => remove(root, &name),
+        None => {
+            let url = args.url.ok_or_else(|| {
+                JinError::Config(
+                    "No remote URL given. Usage: jin link <url>, or jin link add <name> <url>"
+                        .into(),
+                )
+            })?;
+            add(
+                root,
+                NewRemote {
+                    name: "origin".to_string(),
+                    url,
+                    force: args.force,
+                    auth: args.auth,
+                    token_env: args.token_env,
+                    ssh_key: args.ssh_key,
+                    token_file: args.token_file,
+                    push_layers: args.push_layers,
+                },
+            )
+        }
+    }
+}
+
+/// Link a remote
 ///
-/// Links to shared Jin config repo, configuring both the Git repository
-/// and JinConfig with the remote URL. Tests connectivity before persisting.
-pub fn execute(args: LinkArgs) -> Result<()> {
-    // 1. Validate URL format
-    validate_git_url(&args.url)?;
+/// Configures both the Git repository and JinConfig with the remote URL.
+/// Tests connectivity before persisting.
+fn add(root: &Path, new: NewRemote) -> Result<()> {
+    let name = new.name.as_str();
+
+    // 1. Validate name and URL format
+    if !git2::Remote::is_valid_name(name) {
+        return Err(JinError::Config(format!("Invalid remote name: {}", name)));
+    }
+    validate_git_url(&new.url)?;
+    for (flag, path) in [
+        ("--ssh-key", &new.ssh_key),
+        ("--token-file", &new.token_file),
+    ] {
+        if let Some(path) = path {
+            if !expand_home(path).is_file() {
+                return Err(JinError::NotFound(format!("{} file '{}'", flag, path)));
+            }
+        }
+    }
 
     // 2. Load global config (or create default)
     let mut config = JinConfig::load().unwrap_or_default();
 
     // 3. Open Jin repository (create if doesn't exist)
-    let jin_repo = JinRepo::open_or_create()?;
+    let jin_repo = JinRepo::open_or_create(root)?;
     let repo = jin_repo.inner();
 
     // 4. Check if remote already exists
-    match repo.find_remote("origin") {
+    match repo.find_remote(name) {
         Ok(existing_remote) => {
-            if !args.force {
+            if !new.force {
                 let existing_url = existing_remote.url().unwrap_or("(unknown)");
+                let command = if name == "origin" {
+                    format!("jin link {} --force", new.url)
+                } else {
+                    format!("jin link add {} {} --force", name, new.url)
+                };
                 return Err(JinError::AlreadyExists(format!(
-                    "Remote 'origin' already configured\n\
+                    "Remote '{}' already configured\n\
                     Current remote: {}\n\n\
                     To change remote, use:\n  \
-                    jin link {} --force",
-                    existing_url, args.url
+                    {}",
+                    name, existing_url, command
                 )));
             }
             // Delete existing remote to update it
-            repo.remote_delete("origin")?;
+            repo.remote_delete(name)?;
         }
         Err(e) if e.code() == ErrorCode::NotFound => {
             // No remote exists - OK to proceed
@@ -47,56 +141,154 @@ pub fn execute(args: LinkArgs) -> Result<()> {
     }
 
     // 5. Normalize URL for git2-rs: convert plain paths to file:// URLs
-    let normalized_url = if args.url.starts_with('/') && !args.url.starts_with("file://") {
-        format!("file://{}", args.url)
+    let normalized_url = if new.url.starts_with('/') && !new.url.starts_with("file://") {
+        format!("file://{}", new.url)
     } else {
-        args.url.clone()
+        new.url.clone()
     };
 
-    // 6. Add remote with Jin-specific refspec
-    repo.remote_with_fetch(
-        "origin",
-        &normalized_url,
-        "+refs/jin/layers/*:refs/jin/layers/*",
-    )?;
+    // 6. Add remote fetching layers into its tracking namespace
+    repo.remote_with_fetch(name, &normalized_url, &tracking_refspec(name))?;
 
     // 7. Test connectivity (skip for file:// URLs due to git2-rs bug)
-    let is_file_url = args.url.starts_with("file://") || args.url.starts_with('/');
+    let remote_config = RemoteConfig {
+        url: new.url.clone(),
+        fetch_on_init: true,
+        check_interval: None,
+        auth: match (new.auth, &new.token_env) {
+            (Some(method), _) => method,
+            (None, Some(_)) => AuthMethod::Token,
+            (None, None) => AuthMethod::Auto,
+        },
+        token_env: new.token_env,
+        ssh_key: new.ssh_key,
+        token_file: new.token_file,
+        push_layers: new.push_layers,
+    };
+    let auth = RemoteAuth::of(&remote_config);
+    let is_file_url = new.url.starts_with("file://") || new.url.starts_with('/');
     if !is_file_url {
         println!("Testing connection to remote...");
-        test_connectivity(repo, "origin")?;
+        test_connectivity(repo, name, &auth)?;
         println!("Connected successfully");
     }
 
     // 8. Update and save global config (store original URL for display purposes)
-    config.remote = Some(RemoteConfig {
-        url: args.url.clone(),
-        fetch_on_init: true,
-    });
+    if name == "origin" {
+        config.remote = Some(remote_config);
+    } else {
+        config.remotes.insert(name.to_string(), remote_config);
+    }
     config.save()?;
 
     // 9. Print confirmation
-    println!("Configured remote 'origin' for Jin repository");
+    println!("Configured remote '{}' for Jin repository", name);
     let config_path = JinConfig::default_path()?;
     println!("Stored in: {}", config_path.display());
     println!();
 
     // 10. Optionally list available configs (skip for file:// URLs due to git2-rs bug, ignore errors)
     if !is_file_url {
-        let _ = list_remote_configs(repo);
+        let _ = list_remote_configs(repo, name, &auth);
     }
 
     // 11. Print next steps
-    println!("Use 'jin fetch' to download configurations");
-    println!("Use 'jin pull' to merge and apply configurations");
+    if name == "origin" {
+        println!("Use 'jin fetch' to download configurations");
+        println!("Use 'jin pull' to merge and apply configurations");
+    } else {
+        println!("Use 'jin push --remote {}' to push layers to it", name);
+    }
 
     Ok(())
 }
 
+/// List linked remotes, `origin` first
+fn list() -> Result<()> {
+    let config = JinConfig::load()?;
+    let remotes: Vec<(&str, &RemoteConfig)> = config
+        .remote
+        .iter()
+        .map(|remote| ("origin", remote))
+        .chain(
+            config
+                .remotes
+                .iter()
+                .map(|(name, remote)| (name.as_str(), remote)),
+        )
+        .collect();
+
+    if remotes.is_empty() {
+        println!("No remotes linked. Run 'jin link <url>' to link one.");
+        return Ok(());
+    }
+
+    let width = remotes
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    for (name, remote) in remotes {
+        println!("{:<width$}  {}", name, remote.url);
+        if !remote.auth.is_auto() || remote.token_env.is_some() {
+            let auth = RemoteAuth::of(remote);
+            match auth.method {
+                AuthMethod::Token => {
+                    println!("{:<width$}  auth: token from ${}", "", auth.token_env())
+                }
+                method => println!("{:<width$}  auth: {}", "", method),
+            }
+        }
+        if let Some(key) = &remote.ssh_key {
+            println!("{:<width$}  ssh key: {}", "", key);
+        }
+        if let Some(file) = &remote.token_file {
+            println!("{:<width$}  token file: {}", "", file);
+        }
+        if !remote.push_layers.is_empty() {
+            println!(
+                "{:<width$}  push layers: {}",
+                "",
+                remote.push_layers.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Unlink a remote, dropping its remote-tracking refs
+///
+/// Local layers are never touched.
+fn remove(root: &Path, name: &str) -> Result<()> {
+    let mut config = JinConfig::load()?;
+    let configured = if name == "origin" {
+        config.remote.take().is_some()
+    } else {
+        config.remotes.remove(name).is_some()
+    };
+
+    let jin_repo = JinRepo::open_or_create(root)?;
+    for tracking_ref in jin_repo.list_refs(&format!("{}*", tracking_prefix(name)))? {
+        jin_repo.delete_ref(&tracking_ref)?;
+    }
+    let in_repository = match jin_repo.inner().remote_delete(name) {
+        Ok(()) => true,
+        Err(e) if e.code() == ErrorCode::NotFound => false,
+        Err(e) => return Err(e.into()),
+    };
+
+    if !configured && !in_repository {
+        return Err(JinError::NotFound(format!("Remote '{}'", name)));
+    }
+    config.save()?;
+    println!("Removed remote '{}'", name);
+    Ok(())
+}
+
 /// Validates Git remote URL format
 ///
 /// Supports HTTPS, SSH (both colon and scheme formats), Git protocol, and file paths.
-fn validate_git_url(url: &str) -> Result<()> {
+pub(crate) fn validate_git_url(url: &str) -> Result<()> {
     if url.is_empty() {
         return Err(JinError::Config("URL cannot be empty".into()));
     }
@@ -133,12 +325,17 @@ fn validate_git_url(url: &str) -> Result<()> {
 ///
 /// Attempts to connect in Fetch direction (read-only) and list remote refs
 /// to verify the repository is accessible.
-fn test_connectivity(repo: &git2::Repository, remote_name: &str) -> Result<()> {
+fn test_connectivity(repo: &git2::Repository, remote_name: &str, auth: &RemoteAuth) -> Result<()> {
     let mut remote = repo.find_remote(remote_name)?;
+    let mut callbacks = RemoteCallbacks::new();
+    setup_auth_callbacks(&mut callbacks, auth);
 
     // Try to connect in Fetch direction (read-only)
-    match remote.connect(Direction::Fetch) {
-        Ok(_) => {
+    let connected = remote
+        .connect_auth(Direction::Fetch, Some(callbacks), None)
+        .map(|_| ());
+    match connected {
+        Ok(()) => {
             // Connection successful, disconnect to cleanup
             remote.disconnect()?;
             Ok(())
@@ -147,11 +344,10 @@ fn test_connectivity(repo: &git2::Repository, remote_name: &str) -> Result<()> {
             // Map error codes to user-friendly messages
             let msg = match e.code() {
                 ErrorCode::Auth => {
-                    "Cannot access remote repository\n\
-                    Possible causes:\n  \
-                    - Missing SSH key or credentials\n  \
-                    - Repository requires authentication\n\n\
-                    Check your SSH keys: ssh -T git@github.com"
+                    return Err(JinError::Auth(format!(
+                        "Cannot access remote repository\n{}",
+                        e.message()
+                    )));
                 }
                 ErrorCode::NotFound => {
                     "Repository not found or not accessible\n\
@@ -178,11 +374,13 @@ fn test_connectivity(repo: &git2::Repository, remote_name: &str) -> Result<()> {
 ///
 /// Connects to the remote, lists all refs, and parses Jin layer refs
 /// to show available configurations.
-fn list_remote_configs(repo: &git2::Repository) -> Result<()> {
-    let mut remote = repo.find_remote("origin")?;
-    remote.connect(Direction::Fetch)?;
+fn list_remote_configs(repo: &git2::Repository, name: &str, auth: &RemoteAuth) -> Result<()> {
+    let mut remote = repo.find_remote(name)?;
+    let mut callbacks = RemoteCallbacks::new();
+    setup_auth_callbacks(&mut callbacks, auth);
+    let connection = remote.connect_auth(Direction::Fetch, Some(callbacks), None)?;
 
-    let refs = remote.list()?;
+    let refs = connection.list()?;
     let mut modes = HashSet::new();
     let mut scopes = HashSet::new();
     let mut projects = HashSet::new();
@@ -206,7 +404,8 @@ fn list_remote_configs(repo: &git2::Repository) -> Result<()> {
         }
     }
 
-    remote.disconnect()?;
+    // Dropping the connection disconnects
+    drop(connection);
 
     // Print available configurations if any found
     if !modes.is_empty() || !scopes.is_empty() || !projects.is_empty() {
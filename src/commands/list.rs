@@ -1,36 +1,211 @@
 //! Implementation of `jin list`
 //!
-//! Lists available modes/scopes/projects from the Jin repository.
+//! Lists available modes/scopes/projects from the Jin repository, with
+//! archived modes and scopes in a section of their own.
+//! `jin list modes|scopes|projects` prints just those names, one per line,
+//! and `jin list files` the files tracked in each layer, found by walking
+//! `refs/jin/layers/*` (`--layer mode/claude` for one layer only).
+//! With `--stale`, lists only modes and scopes with no recorded use within
+//! the given age (see [`crate::core::usage`]). With `--json`, every
+//! listing is printed as a `ListReport` holding only what was asked for.
+
+use crate::cli::{ListArgs, ListKind};
+use crate::core::schema::{self, JsonSurface};
+use crate::core::usage::{parse_age, StaleContext, UsageKind, UsageLog};
+use crate::core::{JinError, ProjectContext, Result};
+use crate::git::archive::{ArchiveKind, ArchiveSet};
+use crate::git::refs::layer_name;
+use crate::git::{JinRepo, ObjectOps, RefOps, TreeOps};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Modes, scopes and projects found in the Jin repository
+#[derive(Debug, Default)]
+pub(crate) struct Contexts {
+    pub modes: HashSet<String>,
+    pub scopes: HashSet<String>,
+    pub projects: HashSet<String>,
+}
+
+/// `jin list --json` output (see `jin schema list`)
+///
+/// Only the sections the invocation asked for are present.
+#[derive(Debug, Default, Serialize)]
+struct ListReport {
+    schema_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scopes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    projects: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archived: Option<Vec<ArchivedEntry>>,
+    /// Files by layer name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    files: Option<BTreeMap<String, Vec<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stale: Option<Vec<StaleEntry>>,
+}
+
+impl ListReport {
+    fn new() -> Self {
+        Self {
+            schema_version: JsonSurface::List.version(),
+            ..Default::default()
+        }
+    }
+}
+
+/// An archived mode or scope in [`ListReport`]
+#[derive(Debug, Serialize)]
+struct ArchivedEntry {
+    kind: ArchiveKind,
+    name: String,
+}
 
-use crate::core::{JinError, Result};
-use crate::git::JinRepo;
-use std::collections::HashSet;
+/// A stale mode or scope in [`ListReport`]
+#[derive(Debug, Serialize)]
+struct StaleEntry {
+    kind: &'static str,
+    name: String,
+    created: Option<DateTime<Utc>>,
+    last_used: Option<DateTime<Utc>>,
+}
 
 /// Execute the list command
 ///
 /// Lists available modes/scopes/projects.
-pub fn execute() -> Result<()> {
+pub fn execute(args: ListArgs, root: &Path) -> Result<()> {
     // Open Jin repository
-    let repo = match JinRepo::open() {
+    let repo = match JinRepo::open(root) {
         Ok(r) => r,
         Err(_) => {
             return Err(JinError::NotInitialized);
         }
     };
 
-    let git_repo = repo.inner();
+    if args.stale {
+        return list_stale(root, &repo, &args.than);
+    }
+
+    match (args.kind, args.layer.as_deref()) {
+        (Some(ListKind::Modes), None) => {
+            return print_names(collect(&repo)?.modes, |report, names| {
+                report.modes = Some(names)
+            })
+        }
+        (Some(ListKind::Scopes), None) => {
+            return print_names(collect(&repo)?.scopes, |report, names| {
+                report.scopes = Some(names)
+            })
+        }
+        (Some(ListKind::Projects), None) => {
+            return print_names(collect(&repo)?.projects, |report, names| {
+                report.projects = Some(names)
+            })
+        }
+        (Some(ListKind::Files), layer) | (None, layer @ Some(_)) => {
+            return list_files(&repo, layer)
+        }
+        (Some(_), Some(_)) => {
+            return Err(JinError::Other(
+                "--layer only applies to 'jin list files'".to_string(),
+            ))
+        }
+        (None, None) => {}
+    }
+
+    let Contexts {
+        mut modes,
+        mut scopes,
+        projects,
+    } = collect(&repo)?;
+
+    // Archived modes and scopes get their own section
+    let archive_set = ArchiveSet::load_or_default(&repo);
+    let mut archived: Vec<ArchivedEntry> = archive_set
+        .archived
+        .into_iter()
+        .filter(|entry| match entry.kind {
+            ArchiveKind::Mode => modes.remove(&entry.name),
+            ArchiveKind::Scope => scopes.remove(&entry.name),
+        })
+        .map(|entry| ArchivedEntry {
+            kind: entry.kind,
+            name: entry.name,
+        })
+        .collect();
+    archived.sort_by(|a, b| (a.kind.to_string(), &a.name).cmp(&(b.kind.to_string(), &b.name)));
+    let (modes, scopes, projects) = (sorted(modes), sorted(scopes), sorted(projects));
+
+    if schema::json_output() {
+        return schema::print_json(&ListReport {
+            modes: Some(modes),
+            scopes: Some(scopes),
+            projects: Some(projects),
+            archived: Some(archived),
+            ..ListReport::new()
+        });
+    }
+
+    // Display results
+    println!("Available in Jin repository:");
+    println!();
+
+    for (heading, names) in [
+        ("Modes", &modes),
+        ("Scopes", &scopes),
+        ("Projects", &projects),
+    ] {
+        if names.is_empty() {
+            continue;
+        }
+        println!("{}:", heading);
+        for name in names {
+            println!("  - {}", name);
+        }
+        println!();
+    }
+
+    if !archived.is_empty() {
+        println!("Archived (read-only):");
+        for entry in &archived {
+            println!("  - {} {}", entry.kind, entry.name);
+        }
+        println!();
+    }
+
+    if modes.is_empty() && scopes.is_empty() && projects.is_empty() && archived.is_empty() {
+        println!("  (no modes, scopes, or projects found)");
+        println!();
+    }
+
+    // Show usage hints
+    println!("Use 'jin mode use <mode>' to activate a mode");
+    println!("Use 'jin scope use <scope>' to activate a scope");
+
+    Ok(())
+}
 
-    // Parse ref paths to extract mode/scope/project names
-    let mut modes = HashSet::new();
-    let mut scopes = HashSet::new();
-    let mut projects = HashSet::new();
+/// Collect all mode, scope and project names from the repository refs
+pub(crate) fn collect(repo: &JinRepo) -> Result<Contexts> {
+    let git_repo = repo.inner();
+    let mut contexts = Contexts::default();
+    let Contexts {
+        modes,
+        scopes,
+        projects,
+    } = &mut contexts;
 
     // Enumerate all refs under refs/jin/layers/
     if let Ok(refs) = git_repo.references_glob("refs/jin/layers/**") {
         for ref_result in refs {
             let reference = ref_result?;
             if let Some(name) = reference.name() {
-                parse_ref_path(name, &mut modes, &mut scopes, &mut projects);
+                parse_ref_path(name, modes, scopes, projects);
             }
         }
     }
@@ -41,7 +216,7 @@ pub fn execute() -> Result<()> {
         for ref_result in refs {
             let reference = ref_result?;
             if let Some(name) = reference.name() {
-                parse_mode_ref(name, &mut modes, &mut scopes);
+                parse_mode_ref(name, modes, scopes);
             }
         }
     }
@@ -52,61 +227,163 @@ pub fn execute() -> Result<()> {
         for ref_result in refs {
             let reference = ref_result?;
             if let Some(name) = reference.name() {
-                parse_scope_ref(name, &mut scopes);
+                parse_scope_ref(name, scopes);
             }
         }
     }
 
-    // Display results
-    println!("Available in Jin repository:");
-    println!();
+    Ok(contexts)
+}
 
-    let has_modes = !modes.is_empty();
-    let has_scopes = !scopes.is_empty();
-    let has_projects = !projects.is_empty();
+/// Files tracked in each layer, by layer name (`global`, `mode/claude`)
+///
+/// Files are sorted; layers without a commit are left out.
+pub(crate) fn layer_files(repo: &JinRepo) -> Result<BTreeMap<String, Vec<String>>> {
+    let mut layers = BTreeMap::new();
+    for ref_path in repo.list_refs("refs/jin/layers/**")? {
+        let tree_oid = repo.find_commit(repo.resolve_ref(&ref_path)?)?.tree_id();
+        let mut files = repo.list_tree_files(tree_oid)?;
+        files.sort();
+        layers.insert(layer_name(&ref_path).to_string(), files);
+    }
+    Ok(layers)
+}
 
-    if has_modes {
-        println!("Modes:");
-        let mut mode_list: Vec<_> = modes.into_iter().collect();
-        mode_list.sort();
-        for mode in mode_list {
-            println!("  - {}", mode);
+/// Print the files of every layer, or of `layer` only
+fn list_files(repo: &JinRepo, layer: Option<&str>) -> Result<()> {
+    let mut layers = layer_files(repo)?;
+
+    if let Some(layer) = layer {
+        let name = layer_name(layer.trim_end_matches('/')).to_string();
+        let files = layers
+            .remove(&name)
+            .ok_or_else(|| JinError::NotFound(format!("Layer '{}'", name)))?;
+        if schema::json_output() {
+            return schema::print_json(&ListReport {
+                files: Some(BTreeMap::from([(name, files)])),
+                ..ListReport::new()
+            });
         }
-        println!();
+        for file in files {
+            println!("{}", file);
+        }
+        return Ok(());
     }
 
-    if has_scopes {
-        println!("Scopes:");
-        let mut scope_list: Vec<_> = scopes.into_iter().collect();
-        scope_list.sort();
-        for scope in scope_list {
-            println!("  - {}", scope);
+    if schema::json_output() {
+        return schema::print_json(&ListReport {
+            files: Some(layers),
+            ..ListReport::new()
+        });
+    }
+    if layers.is_empty() {
+        println!("No files in any layer");
+        return Ok(());
+    }
+    for (i, (name, files)) in layers.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{}:", name);
+        for file in files {
+            println!("  {}", file);
         }
-        println!();
     }
+    Ok(())
+}
 
-    if has_projects {
-        println!("Projects:");
-        let mut project_list: Vec<_> = projects.into_iter().collect();
-        project_list.sort();
-        for project in project_list {
-            println!("  - {}", project);
-        }
-        println!();
+/// Print mode, scope or project names, one per line
+///
+/// `section` puts the names in their [`ListReport`] field for `--json`.
+fn print_names(names: HashSet<String>, section: fn(&mut ListReport, Vec<String>)) -> Result<()> {
+    let names = sorted(names);
+    if schema::json_output() {
+        let mut report = ListReport::new();
+        section(&mut report, names);
+        return schema::print_json(&report);
+    }
+    for name in names {
+        println!("{}", name);
     }
+    Ok(())
+}
 
-    if !has_modes && !has_scopes && !has_projects {
-        println!("  (no modes, scopes, or projects found)");
+fn sorted(names: HashSet<String>) -> Vec<String> {
+    let mut names: Vec<String> = names.into_iter().collect();
+    names.sort();
+    names
+}
+
+/// Find modes and scopes with no recorded activity within `than` (e.g., `90d`)
+///
+/// Results are sorted, modes first.
+pub(crate) fn find_stale(repo: &JinRepo, than: &str) -> Result<Vec<StaleContext>> {
+    let cutoff = chrono::Utc::now() - parse_age(than)?;
+    let contexts = collect(repo)?;
+    let log = UsageLog::load(repo.path())?;
+
+    let mut modes: Vec<&String> = contexts.modes.iter().collect();
+    let mut scopes: Vec<&String> = contexts.scopes.iter().collect();
+    modes.sort();
+    scopes.sort();
+
+    let mut stale = log.stale(UsageKind::Mode, modes, cutoff);
+    stale.extend(log.stale(UsageKind::Scope, scopes, cutoff));
+    Ok(stale)
+}
+
+/// Print stale modes and scopes
+fn list_stale(root: &Path, repo: &JinRepo, than: &str) -> Result<()> {
+    let stale = find_stale(repo, than)?;
+    if schema::json_output() {
+        return schema::print_json(&ListReport {
+            stale: Some(stale.iter().map(stale_entry).collect()),
+            ..ListReport::new()
+        });
+    }
+    if stale.is_empty() {
+        println!("No modes or scopes unused for more than {}", than);
+        return Ok(());
+    }
+
+    let context = ProjectContext::load(root).unwrap_or_default();
+    let now = chrono::Utc::now();
+
+    for (kind, heading) in [(UsageKind::Mode, "Modes"), (UsageKind::Scope, "Scopes")] {
+        let of_kind: Vec<&StaleContext> = stale.iter().filter(|s| s.kind == kind).collect();
+        if of_kind.is_empty() {
+            continue;
+        }
+        println!("{} unused for more than {}:", heading, than);
+        for entry in of_kind {
+            let active = match kind {
+                UsageKind::Mode => context.mode.as_deref() == Some(entry.name.as_str()),
+                UsageKind::Scope => context.scope.as_deref() == Some(entry.name.as_str()),
+            };
+            println!(
+                "  - {:<24} {}{}",
+                entry.name,
+                entry.describe(now),
+                if active { " (active here)" } else { "" }
+            );
+        }
         println!();
     }
 
-    // Show usage hints
-    println!("Use 'jin mode use <mode>' to activate a mode");
-    println!("Use 'jin scope use <scope>' to activate a scope");
+    println!("Delete them with: jin prune --than {}", than);
 
     Ok(())
 }
 
+fn stale_entry(entry: &StaleContext) -> StaleEntry {
+    StaleEntry {
+        kind: entry.kind.as_str(),
+        name: entry.name.clone(),
+        created: entry.record.created,
+        last_used: entry.record.last_used,
+    }
+}
+
 /// Parse a ref path and extract mode/scope/project names
 fn parse_ref_path(
     ref_path: &str,
@@ -220,7 +497,7 @@ mod tests {
         // List command works even without project initialization
         // It reads from the global Jin repository at ~/.jin/
         // If the global repo exists (from previous tests), this will succeed
-        let result = execute();
+        let result = execute(ListArgs::default(), Path::new("."));
         // Accept either success (global repo exists) or error (doesn't exist)
         assert!(result.is_ok() || matches!(result, Err(JinError::NotInitialized)));
     }
@@ -365,4 +642,73 @@ mod tests {
         parse_scope_ref("refs/jin/scopes/myapp", &mut scopes);
         assert!(scopes.contains("myapp"));
     }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_layer_files_walks_layer_refs() {
+        let ctx = crate::test_utils::setup_unit_test();
+        let repo = JinRepo::open_or_create(&ctx.project_path).unwrap();
+        let blob = repo.create_blob(b"{}").unwrap();
+        for (ref_path, files) in [
+            ("refs/jin/layers/global", vec!["b.json", "a.json"]),
+            (
+                "refs/jin/layers/mode/claude/_",
+                vec![".claude/settings.json"],
+            ),
+        ] {
+            let entries: Vec<(String, git2::Oid)> =
+                files.iter().map(|f| (f.to_string(), blob)).collect();
+            let tree = repo.create_tree_from_paths(&entries).unwrap();
+            let commit = repo.create_commit(None, "Add", tree, &[]).unwrap();
+            repo.set_ref(ref_path, commit, "test").unwrap();
+        }
+
+        let layers = layer_files(&repo).unwrap();
+        assert_eq!(layers["global"], vec!["a.json", "b.json"]);
+        assert_eq!(layers["mode/claude"], vec![".claude/settings.json"]);
+        assert_eq!(layers.len(), 2);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_json_report_matches_schema() {
+        let ctx = crate::test_utils::setup_unit_test();
+        let repo = JinRepo::open_or_create(&ctx.project_path).unwrap();
+        let blob = repo.create_blob(b"{}").unwrap();
+        let tree = repo
+            .create_tree_from_paths(&[("a.json".to_string(), blob)])
+            .unwrap();
+        let commit = repo.create_commit(None, "Add", tree, &[]).unwrap();
+        for ref_path in [
+            "refs/jin/layers/mode/claude/_",
+            "refs/jin/modes/claude/_mode",
+        ] {
+            repo.set_ref(ref_path, commit, "test").unwrap();
+        }
+
+        let report = ListReport {
+            modes: Some(sorted(collect(&repo).unwrap().modes)),
+            archived: Some(vec![ArchivedEntry {
+                kind: ArchiveKind::Scope,
+                name: "lang:go".to_string(),
+            }]),
+            files: Some(layer_files(&repo).unwrap()),
+            stale: Some(
+                find_stale(&repo, "0d")
+                    .unwrap()
+                    .iter()
+                    .map(stale_entry)
+                    .collect(),
+            ),
+            ..ListReport::new()
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        crate::core::schema::assert_matches(&JsonSurface::List.schema(), &json, "list");
+        assert_eq!(json["modes"], serde_json::json!(["claude"]));
+        assert_eq!(json["archived"][0]["kind"], "scope");
+        assert_eq!(json["files"]["mode/claude"], serde_json::json!(["a.json"]));
+        assert_eq!(json["stale"][0]["name"], "claude");
+        assert!(json["stale"][0]["last_used"].is_null());
+        assert!(json.get("scopes").is_none());
+    }
 }
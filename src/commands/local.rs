@@ -0,0 +1,382 @@
+//! Implementation of `jin local` subcommands
+//!
+//! Syncs the user-local layer (layer 8) between a user's own machines through
+//! a private remote. The team remote only ever carries `refs/jin/layers/*`
+//! (minus the user-local layer), so user-local history lives in its own
+//! namespace and can never leak into team pushes:
+//!
+//! - each machine publishes its user-local layer as `refs/jin/local/<machine>`
+//!   and is the only writer of that ref
+//! - `jin local sync` fetches every machine's ref, merges them into the local
+//!   layer and publishes the result
+//!
+//! User-local commits carry a `Jin-Machine-Id` trailer (see
+//! [`crate::core::machine`]), so the sync output and `git log` on these refs
+//! show which machine made a change even when two share a name.
+//!
+//! Merges are three-way against the common ancestor, so edits and deletions
+//! on different files combine cleanly. When both sides changed the same file
+//! (or one side deleted it), the side with the newer commit wins; ties are
+//! broken by commit id, so every machine resolves a conflict the same way and
+//! repeated syncs converge.
+
+use crate::cli::LocalAction;
+use crate::commands::link::validate_git_url;
+use crate::core::{machine, JinConfig, JinError, Layer, LocalSyncConfig, Result};
+use crate::git::merge::{detect_merge_type, MergeType};
+use crate::git::remote::{build_push_options, remote_error, setup_callbacks};
+use crate::git::retry::with_retry;
+use crate::git::{JinRepo, LayerTransaction, ObjectOps, RefOps};
+use crate::staging::StagingIndex;
+use git2::{FetchOptions, Index, Oid, RemoteCallbacks};
+use std::path::Path;
+
+/// Namespace of per-machine user-local refs
+const LOCAL_PREFIX: &str = "refs/jin/local/";
+
+/// Refspec mirroring every machine's user-local ref
+const LOCAL_REFSPEC: &str = "+refs/jin/local/*:refs/jin/local/*";
+
+/// Stage bits of an index entry's flags
+const INDEX_STAGE_MASK: u16 = 0x3000;
+
+/// Execute a local subcommand
+pub fn execute(action: LocalAction, root: &Path) -> Result<()> {
+    match action {
+        LocalAction::Link { url, machine } => link(&url, machine),
+        LocalAction::Sync => sync(root),
+    }
+}
+
+/// Configure the private remote and this machine's name
+fn link(url: &str, machine: Option<String>) -> Result<()> {
+    validate_git_url(url)?;
+
+    let machine = match machine {
+        Some(name) => {
+            validate_machine_name(&name)?;
+            name
+        }
+        None => default_machine_name(),
+    };
+
+    let mut config = JinConfig::load()?;
+    if config.remote.as_ref().is_some_and(|r| r.url == url) {
+        return Err(JinError::Config(
+            "User-local sync needs a private remote, not the team remote".into(),
+        ));
+    }
+    config.local_sync = Some(LocalSyncConfig {
+        url: url.to_string(),
+        machine: machine.clone(),
+    });
+    config.save()?;
+
+    println!("Linked user-local sync to {} as '{}'", url, machine);
+    println!("Run 'jin local sync' to exchange the user-local layer with your other machines");
+    Ok(())
+}
+
+/// Fetch other machines' user-local layers, merge them and publish the result
+fn sync(root: &Path) -> Result<()> {
+    let config = JinConfig::load()?.local_sync.ok_or_else(|| {
+        JinError::Config("User-local sync is not configured. Run 'jin local link <url>'.".into())
+    })?;
+
+    let staging = StagingIndex::load(root)?;
+    if !staging.entries_for_layer(Layer::UserLocal).is_empty() {
+        return Err(JinError::Config(
+            "Cannot sync with uncommitted user-local changes. Commit or reset first.".into(),
+        ));
+    }
+
+    let repo = JinRepo::open_or_create(root)?;
+    let mut remote = repo.inner().remote_anonymous(&config.url)?;
+
+    println!("Fetching user-local layers from {}...", config.url);
+    let mut callbacks = RemoteCallbacks::new();
+    setup_callbacks(&mut callbacks);
+    let mut fetch_opts = FetchOptions::new();
+    fetch_opts.remote_callbacks(callbacks);
+    fetch_opts.update_fetchhead(false);
+    with_retry("Fetch user-local layers", || {
+        remote
+            .fetch(&[LOCAL_REFSPEC], Some(&mut fetch_opts), None)
+            .map_err(remote_error)
+    })?;
+
+    let layer_ref = Layer::UserLocal.ref_path(None, None, None);
+    let original = repo.resolve_ref(&layer_ref).ok();
+    let mut head = original;
+
+    // Our own ref is included: it is normally an ancestor of the local
+    // layer, but another installation may have published under this name
+    for machine_ref in repo.list_refs(&format!("{}*", LOCAL_PREFIX))? {
+        let machine = machine_ref
+            .strip_prefix(LOCAL_PREFIX)
+            .unwrap_or(&machine_ref);
+        let theirs = repo.resolve_ref(&machine_ref)?;
+
+        let merged = match head {
+            None => theirs,
+            Some(ours) => match detect_merge_type(&repo, ours, theirs)? {
+                MergeType::UpToDate | MergeType::LocalAhead => continue,
+                MergeType::FastForward => theirs,
+                MergeType::Divergent => merge_heads(&repo, ours, theirs, machine)?,
+            },
+        };
+        head = Some(merged);
+        match last_machine_id(&repo, theirs) {
+            Some(id) => println!(
+                "  ✓ Merged user-local changes from '{}' (machine {})",
+                machine,
+                machine::short(&id)
+            ),
+            None => println!("  ✓ Merged user-local changes from '{}'", machine),
+        }
+    }
+
+    let Some(head) = head else {
+        println!("Nothing to sync: no user-local layer here or on the remote");
+        return Ok(());
+    };
+
+    if original != Some(head) {
+        let mut tx = LayerTransaction::begin(&repo, root, "local sync: merge user-local layers")?;
+        tx.add_layer_update(Layer::UserLocal, None, None, None, head)?;
+        tx.commit()?;
+    }
+
+    let own_ref = format!("{}{}", LOCAL_PREFIX, config.machine);
+    if repo.resolve_ref(&own_ref).ok() != Some(head) {
+        let refspec = format!("{}:{}", layer_ref, own_ref);
+        let mut push_opts = build_push_options()?;
+        with_retry("Push user-local layer", || {
+            remote
+                .push(&[refspec.as_str()], Some(&mut push_opts))
+                .map_err(remote_error)
+        })?;
+        repo.set_ref(&own_ref, head, "local sync: publish")?;
+    }
+
+    if original != Some(head) {
+        println!("User-local layer updated. Run 'jin apply' to update your workspace.");
+    } else {
+        println!("User-local layer is up to date");
+    }
+    Ok(())
+}
+
+/// Machine ID recorded by the latest commit of a user-local history that
+/// names one
+fn last_machine_id(repo: &JinRepo, head: Oid) -> Option<String> {
+    let mut walk = repo.inner().revwalk().ok()?;
+    walk.push(head).ok()?;
+    walk.filter_map(|oid| repo.inner().find_commit(oid.ok()?).ok())
+        .find_map(|commit| machine::from_message(commit.message()?).map(str::to_string))
+}
+
+/// Three-way merge of two divergent user-local histories
+///
+/// Conflicting files are resolved in favor of the newer head (see module
+/// docs), so the merge always succeeds.
+fn merge_heads(repo: &JinRepo, ours: Oid, theirs: Oid, machine: &str) -> Result<Oid> {
+    let git_repo = repo.inner();
+    let ours_commit = git_repo.find_commit(ours)?;
+    let theirs_commit = git_repo.find_commit(theirs)?;
+
+    let ancestor_tree = match git_repo.merge_base(ours, theirs) {
+        Ok(base) => git_repo.find_commit(base)?.tree()?,
+        // Unrelated histories: everything is an addition
+        Err(_) => git_repo.find_tree(git_repo.treebuilder(None)?.write()?)?,
+    };
+
+    let mut index = git_repo.merge_trees(
+        &ancestor_tree,
+        &ours_commit.tree()?,
+        &theirs_commit.tree()?,
+        None,
+    )?;
+    if index.has_conflicts() {
+        let theirs_wins =
+            (theirs_commit.time().seconds(), theirs) > (ours_commit.time().seconds(), ours);
+        resolve_conflicts(&mut index, theirs_wins)?;
+    }
+
+    let tree = index.write_tree_to(git_repo)?;
+    let mut message = format!("Merge user-local layer from '{}'", machine);
+    if let Some(id) = machine::current() {
+        message = machine::with_trailer(&message, &id);
+    }
+    repo.create_commit(None, &message, tree, &[ours, theirs])
+}
+
+/// Replace every conflict with the winning side's entry (or drop the file
+/// if the winner deleted it)
+fn resolve_conflicts(index: &mut Index, theirs_wins: bool) -> Result<()> {
+    let conflicts = index
+        .conflicts()?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    for conflict in conflicts {
+        let Some(path) = [&conflict.our, &conflict.their, &conflict.ancestor]
+            .into_iter()
+            .flatten()
+            .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+            .next()
+        else {
+            continue;
+        };
+        let winner = if theirs_wins {
+            conflict.their
+        } else {
+            conflict.our
+        };
+
+        index.remove_path(Path::new(&path))?;
+        if let Some(mut entry) = winner {
+            entry.flags &= !INDEX_STAGE_MASK;
+            index.add(&entry)?;
+        }
+    }
+    Ok(())
+}
+
+/// Check that a machine name can be used as a ref component
+fn validate_machine_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && git2::Reference::is_valid_name(&format!("{}{}", LOCAL_PREFIX, name));
+    if valid {
+        Ok(())
+    } else {
+        Err(JinError::Config(format!(
+            "Invalid machine name: '{}'. Use letters, digits, '-', '_' and '.'",
+            name
+        )))
+    }
+}
+
+/// Machine name derived from the host name (`default` if unavailable)
+fn default_machine_name() -> String {
+    let name: String = hostname()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let name = name.trim_matches('-');
+    if name.is_empty() {
+        "default".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(unix)]
+fn hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    // SAFETY: buf is valid for writes of buf.len() bytes; gethostname
+    // NUL-terminates the name when it fits
+    let rc = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) };
+    if rc != 0 {
+        return None;
+    }
+    let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8(buf[..end].to_vec()).ok()
+}
+
+#[cfg(not(unix))]
+fn hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn commit_files(repo: &JinRepo, files: &[(&str, &str)], parents: &[Oid]) -> Oid {
+        let mut index = Index::new().unwrap();
+        for (path, content) in files {
+            let blob = repo.create_blob(content.as_bytes()).unwrap();
+            let entry = git2::IndexEntry {
+                ctime: git2::IndexTime::new(0, 0),
+                mtime: git2::IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode: 0o100644,
+                uid: 0,
+                gid: 0,
+                file_size: content.len() as u32,
+                id: blob,
+                flags: 0,
+                flags_extended: 0,
+                path: path.as_bytes().to_vec(),
+            };
+            index.add(&entry).unwrap();
+        }
+        let tree = index.write_tree_to(repo.inner()).unwrap();
+        repo.create_commit(None, "test", tree, parents).unwrap()
+    }
+
+    fn file(repo: &JinRepo, commit: Oid, path: &str) -> Option<String> {
+        let tree = repo.inner().find_commit(commit).unwrap().tree().unwrap();
+        let entry = tree.get_path(Path::new(path)).ok()?;
+        let blob = repo.inner().find_blob(entry.id()).unwrap();
+        Some(String::from_utf8_lossy(blob.content()).into_owned())
+    }
+
+    #[test]
+    fn test_merge_heads_combines_and_resolves_conflicts() {
+        let temp = TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join(".jin")).unwrap();
+
+        let base = commit_files(&repo, &[("a", "1"), ("b", "1"), ("c", "1")], &[]);
+        let ours = commit_files(&repo, &[("a", "ours"), ("b", "1"), ("c", "1")], &[base]);
+        let theirs = commit_files(&repo, &[("a", "theirs"), ("b", "2")], &[base]);
+
+        let merged = merge_heads(&repo, ours, theirs, "laptop").unwrap();
+        // Independent edits combine, deletions propagate
+        assert_eq!(file(&repo, merged, "b").as_deref(), Some("2"));
+        assert_eq!(file(&repo, merged, "c"), None);
+
+        // Same file changed on both sides: the newer head (ties by id) wins
+        let ours_time = repo.inner().find_commit(ours).unwrap().time().seconds();
+        let theirs_time = repo.inner().find_commit(theirs).unwrap().time().seconds();
+        let expected = if (theirs_time, theirs) > (ours_time, ours) {
+            "theirs"
+        } else {
+            "ours"
+        };
+        assert_eq!(file(&repo, merged, "a").as_deref(), Some(expected));
+
+        // Merging in the other direction resolves the same way
+        let reverse = merge_heads(&repo, theirs, ours, "desktop").unwrap();
+        assert_eq!(file(&repo, reverse, "a").as_deref(), Some(expected));
+    }
+
+    #[test]
+    fn test_validate_machine_name() {
+        assert!(validate_machine_name("laptop").is_ok());
+        assert!(validate_machine_name("work-mbp_2.local").is_ok());
+        assert!(validate_machine_name("").is_err());
+        assert!(validate_machine_name(".hidden").is_err());
+        assert!(validate_machine_name("a/b").is_err());
+        assert!(validate_machine_name("a..b").is_err());
+        assert!(validate_machine_name("a.lock").is_err());
+    }
+
+    #[test]
+    fn test_default_machine_name_is_valid() {
+        assert!(validate_machine_name(&default_machine_name()).is_ok());
+    }
+}
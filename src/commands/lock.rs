@@ -0,0 +1,191 @@
+//! Implementation of `jin lock` and `jin unlock`
+//!
+//! Takes and releases advisory path locks (see [`crate::git::locks`]). With
+//! a linked remote, the lock list is fetched first and pushed right after the
+//! change, so a lock is visible to teammates as soon as the command returns.
+
+use crate::cli::{LockArgs, UnlockArgs};
+use crate::core::{JinConfig, JinError, ProjectContext, Result};
+use crate::git::locks::{self, LockSet, PathLock};
+use crate::git::refs::layer_name;
+use crate::git::JinRepo;
+use crate::staging::StagingIndex;
+use chrono::Utc;
+use git2::Remote;
+use std::path::Path;
+
+/// Execute `jin lock`: take a lock, or list locks when no path is given
+pub fn lock(args: LockArgs, root: &Path) -> Result<()> {
+    let repo = JinRepo::open(root).map_err(|_| JinError::NotInitialized)?;
+    let mut remote = locks::team_remote(&repo)?;
+
+    let Some(path) = args.path else {
+        if let Some(remote) = remote.as_mut() {
+            if let Err(e) = locks::fetch(remote) {
+                eprintln!("Warning: Could not refresh locks from remote: {}", e);
+            }
+        }
+        return list(&LockSet::load(&repo)?);
+    };
+
+    let layer = locks::parse_layer(&args.layer)?;
+    let path = locks::normalize_path(&path);
+    if let Some(remote) = remote.as_mut() {
+        locks::fetch(remote)?;
+    }
+
+    let mut lock_set = LockSet::load(&repo)?;
+    let owner = locks::current_owner(&repo);
+    let new_lock = PathLock {
+        path: path.clone(),
+        layer: layer.clone(),
+        owner: owner.clone(),
+        locked_at: Utc::now(),
+        reason: args.message,
+    };
+
+    if let Some(existing) = lock_set.locks.iter().find(|l| {
+        l.owner != owner && (l.covers(&layer, &path) || new_lock.covers(&l.layer, &l.path))
+    }) {
+        return Err(JinError::Other(format!(
+            "'{}' in {} is already locked by {} (since {})",
+            existing.path,
+            existing.layer,
+            existing.owner,
+            existing.locked_at.format("%Y-%m-%d %H:%M")
+        )));
+    }
+    if lock_set.position(&layer, &path).is_some() {
+        println!("'{}' in {} is already locked by you", path, layer);
+        return Ok(());
+    }
+
+    lock_set.locks.push(new_lock);
+    lock_set.save(&repo, &format!("Lock {} in {}", path, layer))?;
+    publish(remote.as_mut())?;
+
+    println!("Locked '{}' in {}", path, layer);
+    Ok(())
+}
+
+/// Execute `jin unlock`
+pub fn unlock(args: UnlockArgs, root: &Path) -> Result<()> {
+    let repo = JinRepo::open(root).map_err(|_| JinError::NotInitialized)?;
+    let mut remote = locks::team_remote(&repo)?;
+    let layer = locks::parse_layer(&args.layer)?;
+    let path = locks::normalize_path(&args.path);
+    if let Some(remote) = remote.as_mut() {
+        locks::fetch(remote)?;
+    }
+
+    let mut lock_set = LockSet::load(&repo)?;
+    let index = lock_set
+        .position(&layer, &path)
+        .ok_or_else(|| JinError::NotFound(format!("Lock on '{}' in {}", path, layer)))?;
+    let owner = locks::current_owner(&repo);
+    let existing = &lock_set.locks[index];
+    if existing.owner != owner && !args.force {
+        return Err(JinError::Other(format!(
+            "'{}' in {} is locked by {}. Use --force to release it anyway.",
+            path, layer, existing.owner
+        )));
+    }
+
+    lock_set.locks.remove(index);
+    lock_set.save(&repo, &format!("Unlock {} in {}", path, layer))?;
+    publish(remote.as_mut())?;
+
+    println!("Unlocked '{}' in {}", path, layer);
+    Ok(())
+}
+
+/// Push the lock list, or note that it stays local
+fn publish(remote: Option<&mut Remote<'_>>) -> Result<()> {
+    match remote {
+        Some(remote) => locks::push(remote),
+        None => {
+            println!("No remote linked: the change is only visible on this machine");
+            Ok(())
+        }
+    }
+}
+
+/// Print the lock list
+fn list(lock_set: &LockSet) -> Result<()> {
+    if lock_set.locks.is_empty() {
+        println!("No locks");
+        return Ok(());
+    }
+
+    for lock in &lock_set.locks {
+        print!(
+            "{}  {}  {}  {}",
+            lock.layer,
+            lock.path,
+            lock.owner,
+            lock.locked_at.format("%Y-%m-%d %H:%M")
+        );
+        match &lock.reason {
+            Some(reason) => println!("  ({})", reason),
+            None => println!(),
+        }
+    }
+    Ok(())
+}
+
+/// Check staged entries against locks held by others before committing
+///
+/// Prints a warning per locked path; with `locks.policy = block`, refuses
+/// the commit. Uses the lock list from the last fetch, so it works offline.
+pub fn check_staged(root: &Path, staging: &StagingIndex, context: &ProjectContext) -> Result<()> {
+    let Ok(repo) = JinRepo::open(root) else {
+        return Ok(());
+    };
+    let lock_set = LockSet::load(&repo)?;
+    if lock_set.locks.is_empty() {
+        return Ok(());
+    }
+    let owner = locks::current_owner(&repo);
+
+    let mut locked = Vec::new();
+    for entry in staging.entries() {
+        let ref_path = entry.target_layer.ref_path(
+            context.mode.as_deref(),
+            context.scope.as_deref(),
+            context.project.as_deref(),
+        );
+        let path = locks::normalize_path(&entry.path.display().to_string());
+        if let Some(lock) = lock_set.find(layer_name(&ref_path), &path) {
+            if lock.owner != owner {
+                locked.push((path, lock));
+            }
+        }
+    }
+    if locked.is_empty() {
+        return Ok(());
+    }
+
+    for (path, lock) in &locked {
+        eprint!(
+            "Warning: '{}' in {} is locked by {}",
+            path, lock.layer, lock.owner
+        );
+        match &lock.reason {
+            Some(reason) => eprintln!(": {}", reason),
+            None => eprintln!(),
+        }
+    }
+
+    let policy = JinConfig::load()
+        .ok()
+        .and_then(|c| c.locks)
+        .unwrap_or_default()
+        .policy;
+    if policy == locks::LockPolicy::Block {
+        return Err(JinError::Other(format!(
+            "Commit touches {} locked path(s). Ask the owner to run 'jin unlock' first.",
+            locked.len()
+        )));
+    }
+    Ok(())
+}
@@ -1,20 +1,64 @@
 //! Implementation of `jin log`
 //!
-//! Shows commit history for layers.
+//! Shows commit history for layers, paged and colored like `git log` (see
+//! [`crate::core::pager`]).
+//!
+//! With `--patch`, each commit is followed by what it changed: for
+//! structured files the keys added, changed and removed (see
+//! [`crate::merge::key_changes`]), then the unified diff of every file.
+//!
+//! Annotations added with `jin annotate` are shown under the commit they
+//! annotate, like `git log` shows notes.
+//!
+//! With `--applies`, shows past `jin apply` runs from the audit log instead,
+//! newest first.
+//!
+//! With `--json`, prints the commits as a versioned JSON document (see
+//! `jin schema log`).
 
+use crate::audit::{export, ApplyOutcome, ApplyRecord, AuditEntry};
 use crate::cli::LogArgs;
+use crate::commands::diff::print_diff_line;
+use crate::core::pager::{Pager, Palette};
+use crate::core::schema::{self, JsonSurface};
 use crate::core::{JinError, Layer, ProjectContext, Result};
-use crate::git::{refs::RefOps, JinRepo};
+use crate::git::{notes, refs::RefOps, JinRepo};
+use crate::merge::{change_line, key_changes};
 use chrono::{DateTime, Utc};
-use git2::Sort;
+use git2::{DiffOptions, Oid, Sort};
+use serde::Serialize;
 use std::collections::HashMap;
+use std::path::Path;
+
+/// `jin log --json` output
+#[derive(Debug, Serialize)]
+struct LogReport {
+    schema_version: u32,
+    commits: Vec<LogEntry>,
+}
+
+/// A layer commit
+#[derive(Debug, Serialize)]
+struct LogEntry {
+    layer: String,
+    #[serde(rename = "ref")]
+    ref_path: String,
+    commit: String,
+    author: String,
+    email: String,
+    date: DateTime<Utc>,
+    message: String,
+    files_changed: usize,
+    /// Annotations added with `jin annotate`, oldest first
+    notes: Vec<String>,
+}
 
 /// Execute the log command
 ///
 /// Shows commit history.
-pub fn execute(args: LogArgs) -> Result<()> {
+pub fn execute(args: LogArgs, root: &Path) -> Result<()> {
     // Load project context
-    let context = match ProjectContext::load() {
+    let context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -23,142 +67,325 @@ pub fn execute(args: LogArgs) -> Result<()> {
     };
 
     // Open Jin repository
-    let repo = JinRepo::open_or_create()?;
+    let repo = JinRepo::open_or_create(root)?;
     let git_repo = repo.inner();
 
-    // Determine which layers to show history for
-    if let Some(layer_name) = &args.layer {
-        // Show history for specific layer
-        let layer = parse_layer_name(layer_name)?;
-        show_layer_history(git_repo, layer, &context, args.count)?;
-    } else {
-        // Show history for all layers with commits
-        // Discover all layer refs dynamically
-        let all_refs = repo.list_refs("refs/jin/layers/**")?;
-
-        // Group refs by layer type
-        let mut layer_refs: HashMap<Layer, Vec<String>> = HashMap::new();
-        for path in all_refs {
-            if let Some(layer) = Layer::parse_layer_from_ref_path(&path) {
-                layer_refs.entry(layer).or_default().push(path);
-            }
+    if schema::json_output() {
+        if args.applies || args.patch {
+            return Err(JinError::Other(
+                "--json does not support --applies or --patch \
+                 (use 'jin audit export' for applies)"
+                    .to_string(),
+            ));
+        }
+        let mut commits = Vec::new();
+        for (layer, ref_path) in selected_refs(&repo, args.layer.as_deref(), &context)? {
+            commits.extend(history(git_repo, &ref_path, layer, args.count)?);
         }
+        return schema::print_json(&LogReport {
+            schema_version: JsonSurface::Log.version(),
+            commits,
+        });
+    }
 
-        // Display in precedence order
-        let all_layers = Layer::all_in_precedence_order();
-        let mut shown_any = false;
+    let palette = args.color.palette();
+    let _pager = Pager::start();
 
-        for layer in &all_layers {
-            // Skip layers that don't apply to current context
-            if layer.requires_mode() && context.mode.is_none() {
-                continue;
-            }
-            if layer.requires_scope() && context.scope.is_none() {
-                continue;
-            }
+    if args.applies {
+        return show_applies(root, args.since.as_deref(), args.count, palette);
+    }
 
-            // Get all refs for this layer type
-            if let Some(refs) = layer_refs.get(layer) {
-                for path in refs {
-                    if shown_any {
-                        println!();
-                    }
-                    println!("=== {} ===", layer);
-                    println!();
-                    show_history_for_ref_path(git_repo, path, *layer, args.count)?;
-                    shown_any = true;
-                }
-            }
+    let refs = selected_refs(&repo, args.layer.as_deref(), &context)?;
+    if args.layer.is_some() {
+        // Show history for specific layer
+        for (layer, ref_path) in &refs {
+            show_history_for_ref_path(git_repo, ref_path, *layer, args.count, args.patch, palette)?;
         }
+        return Ok(());
+    }
 
-        if !shown_any {
-            println!("No commits found in any layer");
+    // Show history for all layers with commits
+    for (i, (layer, ref_path)) in refs.iter().enumerate() {
+        if i > 0 {
+            println!();
         }
+        println!("{}", palette.bold(&format!("=== {} ===", layer)));
+        println!();
+        show_history_for_ref_path(git_repo, ref_path, *layer, args.count, args.patch, palette)?;
+    }
+    if refs.is_empty() {
+        println!("No commits found in any layer");
     }
 
     Ok(())
 }
 
-/// Show commit history for a specific layer
-fn show_layer_history(
+/// Layer refs to show history for: the ref of `layer` in the active
+/// context, or every layer ref applying to it, in precedence order
+fn selected_refs(
+    repo: &JinRepo,
+    layer: Option<&str>,
+    context: &ProjectContext,
+) -> Result<Vec<(Layer, String)>> {
+    if let Some(layer_name) = layer {
+        let layer = parse_layer_name(layer_name)?;
+        let ref_path = layer.ref_path(
+            context.mode.as_deref(),
+            context.scope.as_deref(),
+            context.project.as_deref(),
+        );
+        return Ok(vec![(layer, ref_path)]);
+    }
+
+    // Discover all layer refs dynamically, grouped by layer type
+    let mut layer_refs: HashMap<Layer, Vec<String>> = HashMap::new();
+    for path in repo.list_refs("refs/jin/layers/**")? {
+        if let Some(layer) = Layer::parse_layer_from_ref_path(&path) {
+            layer_refs.entry(layer).or_default().push(path);
+        }
+    }
+
+    let mut refs = Vec::new();
+    for layer in Layer::all_in_precedence_order() {
+        // Skip layers that don't apply to current context
+        if layer.requires_mode() && context.mode.is_none() {
+            continue;
+        }
+        if layer.requires_scope() && context.scope.is_none() {
+            continue;
+        }
+        for path in layer_refs.remove(&layer).unwrap_or_default() {
+            refs.push((layer, path));
+        }
+    }
+    Ok(refs)
+}
+
+/// The latest `count` commits of a layer ref, newest first (none if the
+/// ref doesn't exist)
+fn history(
     repo: &git2::Repository,
+    ref_path: &str,
     layer: Layer,
-    context: &ProjectContext,
     count: usize,
-) -> Result<()> {
-    let ref_path = layer.ref_path(
-        context.mode.as_deref(),
-        context.scope.as_deref(),
-        context.project.as_deref(),
-    );
+) -> Result<Vec<LogEntry>> {
+    if repo.find_reference(ref_path).is_err() {
+        return Ok(Vec::new());
+    }
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_ref(ref_path)?;
+    revwalk.set_sorting(Sort::TIME)?;
 
-    show_history_for_ref_path(repo, &ref_path, layer, count)
+    let mut entries = Vec::new();
+    for oid in revwalk.take(count) {
+        let oid = oid?;
+        let commit = repo.find_commit(oid)?;
+        let author = commit.author();
+        entries.push(LogEntry {
+            layer: layer.to_string(),
+            ref_path: ref_path.to_string(),
+            commit: oid.to_string(),
+            author: author.name().unwrap_or("unknown").to_string(),
+            email: author.email().unwrap_or("unknown").to_string(),
+            date: DateTime::from_timestamp(commit.time().seconds(), 0)
+                .unwrap_or_else(|| DateTime::<Utc>::from(std::time::SystemTime::UNIX_EPOCH)),
+            message: commit
+                .message()
+                .unwrap_or("(no message)")
+                .trim()
+                .to_string(),
+            files_changed: count_files_in_commit(repo, &commit)?,
+            notes: notes::read(repo, oid)
+                .map(|notes| notes.lines().map(str::to_string).collect())
+                .unwrap_or_default(),
+        });
+    }
+    Ok(entries)
 }
 
 /// Show commit history for a specific ref path
-///
-/// This is a helper function that displays commit history for an arbitrary
-/// ref path, used internally for dynamic layer ref discovery.
 fn show_history_for_ref_path(
     repo: &git2::Repository,
     ref_path: &str,
     layer: Layer,
     count: usize,
+    patch: bool,
+    palette: Palette,
 ) -> Result<()> {
-    // Check if ref exists
-    let _reference = match repo.find_reference(ref_path) {
-        Ok(r) => r,
-        Err(_) => {
-            println!("No commits yet for layer: {}", layer);
-            return Ok(());
-        }
-    };
-
-    // Create revwalk
-    let mut revwalk = repo.revwalk()?;
-    revwalk.push_ref(ref_path)?;
-    revwalk.set_sorting(Sort::TIME)?;
+    let entries = history(repo, ref_path, layer, count)?;
+    if entries.is_empty() {
+        println!("No commits yet for layer: {}", layer);
+        return Ok(());
+    }
 
-    // Iterate through commits
-    for (i, oid_result) in revwalk.enumerate() {
-        if i >= count {
-            break;
+    for entry in entries {
+        println!(
+            "{} ({})",
+            palette.yellow(&format!("commit {}", &entry.commit[..7])),
+            layer
+        );
+        println!("Author: {} <{}>", entry.author, entry.email);
+        println!("Date:   {}", entry.date.format("%Y-%m-%d %H:%M:%S"));
+        println!();
+        println!("    {}", entry.message);
+        println!();
+        if !entry.notes.is_empty() {
+            println!("Notes:");
+            for line in &entry.notes {
+                println!("    {}", line);
+            }
+            println!();
         }
+        println!("    {} file(s) changed", entry.files_changed);
+        println!();
+        if patch {
+            let commit = repo.find_commit(Oid::from_str(&entry.commit)?)?;
+            show_patch(repo, &commit, palette)?;
+        }
+    }
 
-        let oid = oid_result?;
-        let commit = repo.find_commit(oid)?;
+    Ok(())
+}
 
-        // Format commit hash (short)
-        let hash_short = &oid.to_string()[..7];
+/// Show the latest applies recorded in the audit log, newest first
+fn show_applies(root: &Path, since: Option<&str>, count: usize, palette: Palette) -> Result<()> {
+    let since = since.map(export::parse_since).transpose()?;
+    let audit_dir = root.join(".jin").join("audit");
+    let entries = export::read_entries(&audit_dir, since)?;
+    let applies: Vec<(&AuditEntry, &ApplyRecord)> = entries
+        .iter()
+        .rev()
+        .filter_map(|entry| Some((entry, entry.apply.as_ref()?)))
+        .take(count)
+        .collect();
+
+    if applies.is_empty() {
+        println!("No applies recorded");
+        return Ok(());
+    }
 
-        // Get commit metadata
-        let author = commit.author();
-        let author_name = author.name().unwrap_or("unknown");
-        let author_email = author.email().unwrap_or("unknown");
-        let message = commit.message().unwrap_or("(no message)");
-
-        // Format timestamp
-        let time = commit.time();
-        let timestamp = DateTime::from_timestamp(time.seconds(), 0)
-            .unwrap_or_else(|| DateTime::<Utc>::from(std::time::SystemTime::UNIX_EPOCH));
-
-        // Count files changed in this commit
-        let file_count = count_files_in_commit(repo, &commit)?;
-
-        // Display commit
-        println!("commit {} ({})", hash_short, layer);
-        println!("Author: {} <{}>", author_name, author_email);
-        println!("Date:   {}", timestamp.format("%Y-%m-%d %H:%M:%S"));
+    for (entry, apply) in applies {
+        let outcome = match apply.outcome {
+            ApplyOutcome::Applied => palette.green(&apply.outcome.to_string()),
+            ApplyOutcome::Paused => palette.yellow(&apply.outcome.to_string()),
+            ApplyOutcome::Failed => palette.red(&apply.outcome.to_string()),
+        };
+        let timestamp = DateTime::parse_from_rfc3339(&entry.timestamp)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_else(|_| entry.timestamp.clone());
+        println!(
+            "{} {} in {} ms",
+            palette.yellow(&format!("apply {}", timestamp)),
+            outcome,
+            apply.duration_ms
+        );
+        match &entry.machine {
+            Some(machine) => println!("User:    {} on {}", entry.user, machine),
+            None => println!("User:    {}", entry.user),
+        }
+        println!(
+            "Context: mode={} scope={} project={}",
+            entry.mode.as_deref().unwrap_or("-"),
+            entry.scope.as_deref().unwrap_or("-"),
+            entry.project.as_deref().unwrap_or("-")
+        );
+        if let Some(error) = &apply.error {
+            println!("Error:   {}", error);
+        }
         println!();
-        println!("    {}", message.trim());
+        if !apply.layers.is_empty() {
+            println!("    Layers:");
+            for (ref_path, commit) in &apply.layers {
+                let name = Layer::parse_layer_from_ref_path(ref_path)
+                    .map(|l| l.to_string())
+                    .unwrap_or_else(|| ref_path.clone());
+                println!(
+                    "      {} {}",
+                    palette.yellow(&commit[..commit.len().min(7)]),
+                    name
+                );
+            }
+        }
+        if !entry.files.is_empty() {
+            println!("    Files written ({}):", entry.files.len());
+            for file in &entry.files {
+                println!("      {}", file);
+            }
+        }
+        if !apply.conflicts.is_empty() {
+            println!("    Conflicts ({}):", apply.conflicts.len());
+            for file in &apply.conflicts {
+                println!("      {}", palette.red(file));
+            }
+        }
         println!();
-        println!("    {} file(s) changed", file_count);
+    }
+
+    Ok(())
+}
+
+/// Show what a commit changed: key changes of structured files, then the
+/// unified diff of each file
+fn show_patch(repo: &git2::Repository, commit: &git2::Commit, palette: Palette) -> Result<()> {
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parent_count() {
+        0 => None,
+        _ => Some(commit.parent(0)?.tree()?),
+    };
+    let mut opts = DiffOptions::new();
+    opts.context_lines(3);
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut opts))?;
+
+    for (index, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+            continue;
+        };
+        let old = blob_text(repo, delta.old_file().id());
+        let new = blob_text(repo, delta.new_file().id());
+        if let Some(changes) = key_changes(path, old.as_deref(), new.as_deref()) {
+            if !changes.is_empty() {
+                println!(
+                    "{}",
+                    palette.bold(&format!("Keys changed in {}:", path.display()))
+                );
+                for change in &changes {
+                    let line = change_line(change);
+                    let colored = match line.chars().next() {
+                        Some('+') => palette.green(&line),
+                        Some('-') => palette.red(&line),
+                        _ => palette.yellow(&line),
+                    };
+                    println!("    {}", colored);
+                }
+            }
+        }
+
+        if let Some(mut file_patch) = git2::Patch::from_diff(&diff, index)? {
+            file_patch.print(&mut |_delta, _hunk, line| {
+                print_diff_line(&line, palette);
+                true
+            })?;
+        }
         println!();
     }
 
     Ok(())
 }
 
+/// Text of a blob, if it exists and is UTF-8
+fn blob_text(repo: &git2::Repository, oid: Oid) -> Option<String> {
+    if oid.is_zero() {
+        return None;
+    }
+    let blob = repo.find_blob(oid).ok()?;
+    String::from_utf8(blob.content().to_vec()).ok()
+}
+
 /// Count files in a commit by comparing with parent
 fn count_files_in_commit(repo: &git2::Repository, commit: &git2::Commit) -> Result<usize> {
     let tree = commit.tree()?;
@@ -185,23 +412,8 @@ fn count_files_in_commit(repo: &git2::Repository, commit: &git2::Commit) -> Resu
 }
 
 /// Parse layer name from string
-fn parse_layer_name(name: &str) -> Result<Layer> {
-    match name {
-        "global-base" => Ok(Layer::GlobalBase),
-        "mode-base" => Ok(Layer::ModeBase),
-        "mode-scope" => Ok(Layer::ModeScope),
-        "mode-scope-project" => Ok(Layer::ModeScopeProject),
-        "mode-project" => Ok(Layer::ModeProject),
-        "scope-base" => Ok(Layer::ScopeBase),
-        "project-base" => Ok(Layer::ProjectBase),
-        "user-local" => Ok(Layer::UserLocal),
-        "workspace-active" => Ok(Layer::WorkspaceActive),
-        _ => Err(JinError::Other(format!(
-            "Unknown layer: {}. Valid layers: global-base, mode-base, mode-scope, \
-             mode-scope-project, mode-project, scope-base, project-base, user-local, workspace-active",
-            name
-        ))),
-    }
+pub(crate) fn parse_layer_name(name: &str) -> Result<Layer> {
+    name.parse()
 }
 
 #[cfg(test)]
@@ -231,12 +443,60 @@ mod tests {
         let args = LogArgs {
             layer: None,
             count: 10,
+            patch: false,
+            applies: false,
+            since: None,
+            color: crate::cli::ColorWhen::Never,
         };
 
-        let result = execute(args);
+        let result = execute(args, temp.path());
         assert!(matches!(result, Err(JinError::NotInitialized)));
     }
 
+    #[test]
+    fn test_history_matches_schema() {
+        use tempfile::TempDir;
+        let temp = TempDir::new().unwrap();
+        let repo = git2::Repository::init_bare(temp.path().join("repo")).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let blob = repo.blob(b"{}").unwrap();
+        let mut builder = repo.treebuilder(None).unwrap();
+        builder.insert("a.json", blob, 0o100644).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let ref_path = "refs/jin/layers/global";
+        let commit = repo
+            .commit(Some(ref_path), &sig, &sig, "Add a.json\n", &tree, &[])
+            .unwrap();
+        repo.note(
+            &sig,
+            &sig,
+            Some(notes::NOTES_REF),
+            commit,
+            "deployed\n",
+            false,
+        )
+        .unwrap();
+
+        let commits = history(&repo, ref_path, Layer::GlobalBase, 10).unwrap();
+        assert!(
+            history(&repo, "refs/jin/layers/local", Layer::UserLocal, 10)
+                .unwrap()
+                .is_empty()
+        );
+        let report = LogReport {
+            schema_version: JsonSurface::Log.version(),
+            commits,
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        crate::core::schema::assert_matches(&JsonSurface::Log.schema(), &json, "log");
+        let entry = &json["commits"][0];
+        assert_eq!(entry["commit"], commit.to_string());
+        assert_eq!(entry["layer"], "global-base");
+        assert_eq!(entry["message"], "Add a.json");
+        assert_eq!(entry["files_changed"], 1);
+        assert_eq!(entry["notes"][0], "deployed");
+    }
+
     #[test]
     fn test_count_files_empty_commit() {
         use tempfile::TempDir;
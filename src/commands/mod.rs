@@ -2,66 +2,202 @@
 //!
 //! Each command module contains the implementation for a specific CLI command.
 
-use crate::cli::{Cli, Commands};
-use crate::core::Result;
+use crate::cli::{Cli, Commands, ProfileFormat, ProgressFormat};
+use crate::core::{pager, profile, progress};
+use crate::core::{JinError, Result};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Instant;
 
 pub mod add;
+pub mod adopt;
+pub mod annotate;
 pub mod apply;
+pub mod archive;
+pub mod assert;
+pub mod audit;
+pub mod badge;
+pub mod check;
 pub mod commit_cmd;
 pub mod completion;
 pub mod config;
 pub mod context;
+pub mod dedupe;
 pub mod diff;
+pub mod doctor;
+pub mod env;
+pub mod explain;
 pub mod export;
 pub mod fetch;
+pub mod gc;
+pub mod help;
 pub mod import_cmd;
 pub mod init;
 pub mod layers;
 pub mod link;
 pub mod list;
+pub mod local;
+pub mod lock;
 pub mod log;
 pub mod mode;
 pub mod mv;
+pub mod projects;
+pub mod prompt;
+pub mod prune;
 pub mod pull;
 pub mod push;
+pub mod remote;
 pub mod repair;
+pub mod repo;
+pub mod repro_pack;
 pub mod reset;
 pub mod resolve;
 pub mod rm;
+pub mod run;
+pub mod schema;
 pub mod scope;
+pub mod sed;
+pub mod stats;
 pub mod status;
+pub mod suggest;
 pub mod sync;
+pub mod verify;
+pub mod version;
 
 /// Execute the appropriate command based on CLI arguments
-pub fn execute(cli: Cli) -> Result<()> {
-    match cli.command {
-        Commands::Init => init::execute(),
-        Commands::Add(args) => add::execute(args),
-        Commands::Commit(args) => commit_cmd::execute(args),
-        Commands::Status => status::execute(),
-        Commands::Mode(action) => mode::execute(action),
-        Commands::Modes => mode::list(),
-        Commands::Scope(action) => scope::execute(action),
-        Commands::Scopes => scope::list(),
-        Commands::Apply(args) => apply::execute(args),
-        Commands::Resolve(args) => resolve::execute(args),
-        Commands::Reset(args) => reset::execute(args),
-        Commands::Rm(args) => rm::execute(args),
-        Commands::Mv(args) => mv::execute(args),
-        Commands::Diff(args) => diff::execute(args),
-        Commands::Log(args) => log::execute(args),
-        Commands::Context => context::execute(),
-        Commands::Import(args) => import_cmd::execute(args),
-        Commands::Export(args) => export::execute(args),
-        Commands::Repair(args) => repair::execute(args),
-        Commands::Layers => layers::execute(),
-        Commands::List => list::execute(),
-        Commands::Link(args) => link::execute(args),
-        Commands::Fetch => fetch::execute(),
-        Commands::Pull => pull::execute(),
-        Commands::Push(args) => push::execute(args),
-        Commands::Sync => sync::execute(),
-        Commands::Completion { shell } => completion::execute(shell),
-        Commands::Config(action) => config::execute(action),
+///
+/// The workspace root is resolved once here, from `-C <path>` or the working
+/// directory, and passed to every command. When `--profile` is set, a per-phase timing
+/// report is written to stderr after the command finishes, whether or not it
+/// succeeded. `--json` is refused by commands without JSON output rather
+/// than silently printing text.
+///
+/// Returns the exit status for commands that report one (`diff
+/// --exit-code`); everything else exits with success.
+pub fn execute(cli: Cli) -> Result<ExitCode> {
+    let root = workspace_root(cli.chdir.as_deref())?;
+    if cli.no_pager {
+        pager::disable();
     }
+    if cli.progress == ProgressFormat::Json {
+        progress::enable_json();
+    }
+    if cli.json {
+        let supported = matches!(
+            cli.command,
+            Commands::Status(_)
+                | Commands::Log(_)
+                | Commands::Diff(_)
+                | Commands::Layers(_)
+                | Commands::List(_)
+                | Commands::Version(_)
+        );
+        if !supported {
+            return Err(JinError::Other(
+                "--json is supported by status, log, diff, layers, list and version".to_string(),
+            ));
+        }
+        // JSON is for programs, never paged
+        pager::disable();
+        crate::core::schema::enable_json();
+    }
+
+    let Some(format) = cli.profile else {
+        return dispatch(cli.command, &root);
+    };
+
+    profile::enable();
+    let start = Instant::now();
+    let result = dispatch(cli.command, &root);
+    let report = profile::report(start.elapsed());
+    match format {
+        ProfileFormat::Table => eprint!("{}", report.render_table()),
+        ProfileFormat::Json => eprintln!("{}", report.to_json()),
+    }
+    result
+}
+
+/// The workspace root: `dir` when `-C` is set, otherwise the working directory
+///
+/// The process working directory is never changed. Multiple `-C` options are
+/// not supported; relative file arguments are interpreted relative to the
+/// root, as with git.
+fn workspace_root(dir: Option<&Path>) -> Result<PathBuf> {
+    let root = match dir {
+        Some(dir) if !dir.is_dir() => {
+            return Err(JinError::NotFound(format!(
+                "Cannot change to '{}': not a directory",
+                dir.display()
+            )));
+        }
+        Some(dir) => dir.to_path_buf(),
+        None => std::env::current_dir()?,
+    };
+    Ok(root.canonicalize().unwrap_or(root))
+}
+
+/// Dispatch a parsed command to its implementation
+fn dispatch(command: Commands, root: &Path) -> Result<ExitCode> {
+    let result = match command {
+        Commands::Init(args) => init::execute(args, root),
+        Commands::Add(args) => add::execute(args, root),
+        Commands::Adopt(args) => adopt::execute(args, root),
+        Commands::Commit(args) => commit_cmd::execute(args, root),
+        Commands::Status(args) => status::execute(args, root),
+        Commands::Mode(action) => mode::execute(action, root),
+        Commands::Modes => mode::list(root),
+        Commands::Scope(action) => scope::execute(action, root),
+        Commands::Scopes => scope::list(root),
+        Commands::Apply(args) => apply::execute(args, root),
+        Commands::Run(args) => run::execute(args, root),
+        Commands::Env(action) => env::execute(action, root),
+        Commands::Resolve(args) => resolve::execute(args, root),
+        Commands::Reset(args) => reset::execute(args, root),
+        Commands::Rm(args) => rm::execute(args, root),
+        Commands::Mv(args) => mv::execute(args, root),
+        Commands::Diff(args) => return diff::execute(args, root),
+        Commands::Log(args) => log::execute(args, root),
+        Commands::Annotate(args) => annotate::execute(args, root),
+        Commands::Context(args) => context::execute(args, root),
+        Commands::Prompt => prompt::execute(root),
+        Commands::Badge(args) => badge::execute(args, root),
+        Commands::Import(args) => import_cmd::execute(args, root),
+        Commands::Export(args) => export::execute(args, root),
+        Commands::Repair(args) => repair::execute(args, root),
+        Commands::ReproPack(args) => repro_pack::execute(args, root),
+        Commands::Layers(args) => layers::execute(args, root),
+        Commands::List(args) => list::execute(args, root),
+        Commands::Link(args) => link::execute(args, root),
+        Commands::Fetch => fetch::execute(root),
+        Commands::Pull => pull::execute(root),
+        Commands::Push(args) => push::execute(args, root),
+        Commands::Sync => sync::execute(root),
+        Commands::Completion(args) => completion::execute(args),
+        Commands::Help(args) => help::execute(args, root),
+        Commands::Schema(args) => schema::execute(args),
+        Commands::Version(args) => version::execute(args),
+        Commands::Explain(args) => explain::execute(args),
+        Commands::Config(action) => config::execute(action, root),
+        Commands::Repo(action) => repo::execute(action, root),
+        Commands::Remote(action) => remote::execute(action, root),
+        Commands::Local(action) => local::execute(action, root),
+        Commands::Lock(args) => lock::lock(args, root),
+        Commands::Unlock(args) => lock::unlock(args, root),
+        Commands::Archive(args) => archive::archive(args, root),
+        Commands::Unarchive(args) => archive::unarchive(args, root),
+        Commands::Audit(action) => audit::execute(action, root),
+        Commands::Suggest(args) => suggest::execute(args, root),
+        Commands::Verify(args) => verify::execute(args, root),
+        Commands::Check(args) => check::execute(args, root),
+        Commands::Dedupe(args) => dedupe::execute(args, root),
+        Commands::Sed(args) => sed::execute(args, root),
+        Commands::Assert(args) => assert::execute(args, root),
+        Commands::Prune(args) => prune::execute(args, root),
+        Commands::Gc(args) => gc::execute(args, root),
+        Commands::Stats(args) => stats::execute(args, root),
+        Commands::Projects(args) => projects::execute(args, root),
+        Commands::Doctor(args) => doctor::execute(args, root),
+        Commands::RemoteCheck => status::remote_check(root),
+    };
+    result.map(|()| ExitCode::SUCCESS)
 }
@@ -1,29 +1,306 @@
 //! Implementation of `jin mode` subcommands
 
 use crate::cli::ModeAction;
-use crate::core::{JinError, ProjectContext, Result};
-use crate::git::{JinRepo, ObjectOps, RefOps};
+use crate::core::usage::{self, UsageKind};
+use crate::core::{JinError, Layer, ProjectContext, Result};
+use crate::git::archive::{ArchiveKind, ArchiveSet};
+use crate::git::{JinRepo, JinTransaction, LayerTransaction, ObjectOps, RefOps, TreeOps};
 use crate::staging::metadata::WorkspaceMetadata;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
 
 /// Execute a mode subcommand
-pub fn execute(action: ModeAction) -> Result<()> {
+pub fn execute(action: ModeAction, root: &Path) -> Result<()> {
     match action {
-        ModeAction::Create { name } => create(&name),
-        ModeAction::Use { name } => use_mode(&name),
-        ModeAction::List => list(),
-        ModeAction::Delete { name } => delete(&name),
-        ModeAction::Show => show(),
-        ModeAction::Unset => unset(),
+        ModeAction::Create { name } => create(root, &name),
+        ModeAction::Use { name, apply } => {
+            use_mode(root, &name)?;
+            if apply {
+                super::apply::apply_workspace(root)?;
+            }
+            Ok(())
+        }
+        ModeAction::List => list(root),
+        ModeAction::Delete { name } => delete(root, &name),
+        ModeAction::Show => show(root),
+        ModeAction::Unset => unset(root),
+        ModeAction::Export { name, output } => export(root, &name, output.as_deref()),
+        ModeAction::Import { file, rename } => import(root, &file, rename.as_deref()),
     }
 }
 
+/// Version of the `jin mode export` file format
+const ARCHIVE_FORMAT: u32 = 1;
+
+/// A mode with its layers, as written by `jin mode export`
+///
+/// Plain YAML so it can be shared as a gist or pasted into a chat. Each
+/// layer holds the files of its latest commit; history is not exported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModeArchive {
+    /// File format version
+    pub format: u32,
+    /// Name of the exported mode
+    pub mode: String,
+    /// RFC3339 timestamp of the export
+    pub exported_at: String,
+    /// Scopes bound to the mode (`jin scope create --mode`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+    /// The mode's layers
+    #[serde(default)]
+    pub layers: Vec<ArchivedLayer>,
+}
+
+/// One layer of an exported mode
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedLayer {
+    /// Ref below the mode's layers (`_` for the mode base,
+    /// `scope/language/rust/_`, `project/myapp`, ...)
+    #[serde(rename = "ref")]
+    pub ref_suffix: String,
+    /// Path -> file content
+    pub files: BTreeMap<String, String>,
+}
+
+/// Export a mode with all its layers
+fn export(root: &Path, name: &str, output: Option<&Path>) -> Result<()> {
+    validate_mode_name(name)?;
+    let repo = JinRepo::open_or_create(root)?;
+    if !repo.ref_exists(&format!("refs/jin/modes/{}/_mode", name)) {
+        return Err(JinError::NotFound(format!("Mode '{}' not found", name)));
+    }
+
+    let (archive, skipped) = build_archive(&repo, name)?;
+    for path in &skipped {
+        eprintln!("Warning: Skipped binary file {}", path);
+    }
+    let content = serde_yaml::to_string(&archive).map_err(|e| JinError::Parse {
+        format: "YAML".to_string(),
+        message: e.to_string(),
+    })?;
+
+    let files: usize = archive.layers.iter().map(|l| l.files.len()).sum();
+    match output {
+        Some(path) => {
+            std::fs::write(root.join(path), content)?;
+            println!(
+                "Exported mode '{}' ({} layer(s), {} file(s)) to {}",
+                name,
+                archive.layers.len(),
+                files,
+                path.display()
+            );
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+/// Read the latest files of every layer of a mode; also returns the paths
+/// of binary files, which are left out
+fn build_archive(repo: &JinRepo, name: &str) -> Result<(ModeArchive, Vec<String>)> {
+    let layer_prefix = format!("refs/jin/layers/mode/{}/", name);
+    let scope_prefix = format!("refs/jin/modes/{}/scopes/", name);
+
+    let mut layers = Vec::new();
+    let mut skipped = Vec::new();
+    let mut refs = repo.list_refs(&format!("{}*", layer_prefix))?;
+    refs.sort();
+    for ref_path in refs {
+        let Some(suffix) = ref_path.strip_prefix(&layer_prefix) else {
+            continue;
+        };
+        let tree = repo
+            .inner()
+            .find_commit(repo.resolve_ref(&ref_path)?)?
+            .tree_id();
+        let mut files = BTreeMap::new();
+        for path in repo.list_tree_files(tree)? {
+            match String::from_utf8(repo.read_file_from_tree(tree, Path::new(&path))?) {
+                Ok(content) => {
+                    files.insert(path, content);
+                }
+                Err(_) => skipped.push(format!("{} ({})", path, suffix)),
+            }
+        }
+        layers.push(ArchivedLayer {
+            ref_suffix: suffix.to_string(),
+            files,
+        });
+    }
+
+    let mut scopes: Vec<String> = repo
+        .list_refs(&format!("{}*", scope_prefix))?
+        .iter()
+        .filter_map(|r| r.strip_prefix(&scope_prefix).map(str::to_string))
+        .collect();
+    scopes.sort();
+
+    Ok((
+        ModeArchive {
+            format: ARCHIVE_FORMAT,
+            mode: name.to_string(),
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            scopes,
+            layers,
+        },
+        skipped,
+    ))
+}
+
+/// Point each ref at its commit in one ref transaction
+fn set_refs(repo: &JinRepo, refs: &[(String, git2::Oid, String)]) -> Result<()> {
+    let mut tx = JinTransaction::new(repo)?;
+    for (ref_path, commit, message) in refs {
+        tx.lock_ref(ref_path)?;
+        tx.set_target(ref_path, *commit, message)?;
+    }
+    tx.commit()
+}
+
+/// Create a mode from an exported file
+fn import(root: &Path, file: &Path, rename: Option<&str>) -> Result<()> {
+    let content = if file == Path::new("-") {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)?;
+        content
+    } else {
+        std::fs::read_to_string(root.join(file))
+            .map_err(|e| JinError::NotFound(format!("{}: {}", file.display(), e)))?
+    };
+    let archive: ModeArchive = serde_yaml::from_str(&content).map_err(|e| JinError::Parse {
+        format: "YAML".to_string(),
+        message: format!("{}: {}", file.display(), e),
+    })?;
+    if archive.format != ARCHIVE_FORMAT {
+        return Err(JinError::Parse {
+            format: "YAML".to_string(),
+            message: format!(
+                "{}: unsupported mode export format {} (this version reads {})",
+                file.display(),
+                archive.format,
+                ARCHIVE_FORMAT
+            ),
+        });
+    }
+
+    let name = rename.unwrap_or(&archive.mode);
+    validate_mode_name(name)?;
+    let repo = JinRepo::open_or_create(root)?;
+    let mode_ref = format!("refs/jin/modes/{}/_mode", name);
+    if repo.ref_exists(&mode_ref) {
+        return Err(JinError::AlreadyExists(format!(
+            "Mode '{}' already exists; import under another name with --as <name>",
+            name
+        )));
+    }
+    // Validate every ref before writing anything
+    let layer_refs: Vec<String> = archive
+        .layers
+        .iter()
+        .map(|layer| format!("refs/jin/layers/mode/{}/{}", name, layer.ref_suffix))
+        .collect();
+    let scope_refs: Vec<String> = archive
+        .scopes
+        .iter()
+        .map(|scope| format!("refs/jin/modes/{}/scopes/{}", name, scope))
+        .collect();
+    let refs = (layer_refs.iter().map(|r| (r, true))).chain(scope_refs.iter().map(|r| (r, false)));
+    for (ref_path, is_layer) in refs {
+        if !git2::Reference::is_valid_name(ref_path)
+            || ref_path.contains("..")
+            || (is_layer && Layer::parse_layer_from_ref_path(ref_path).is_none())
+        {
+            return Err(JinError::Parse {
+                format: "YAML".to_string(),
+                message: format!("{}: invalid layer '{}'", file.display(), ref_path),
+            });
+        }
+    }
+
+    // Write every commit first, then the refs all-or-nothing: the layers
+    // in one layer transaction, then the mode and its scopes
+    let mut files = 0;
+    let mut layer_commits = Vec::new();
+    for (layer, ref_path) in archive.layers.iter().zip(&layer_refs) {
+        let mut entries = Vec::new();
+        for (path, content) in &layer.files {
+            entries.push((path.clone(), repo.create_blob(content.as_bytes())?));
+        }
+        files += entries.len();
+        let tree = repo.create_tree_from_paths(&entries)?;
+        let commit = repo.create_commit(
+            None,
+            &format!("Import mode {} from {}", name, archive.mode),
+            tree,
+            &[],
+        )?;
+        layer_commits.push((ref_path, commit));
+    }
+
+    let empty_tree = repo.create_tree(&[])?;
+    let mut markers = vec![(
+        mode_ref.clone(),
+        repo.create_commit(
+            None,
+            &format!("Initialize mode: {} (imported from {})", name, archive.mode),
+            empty_tree,
+            &[],
+        )?,
+        format!("import mode {}", name),
+    )];
+    for (scope, ref_path) in archive.scopes.iter().zip(&scope_refs) {
+        let commit = repo.create_commit(
+            None,
+            &format!("Initialize scope: {} (mode: {})", scope, name),
+            empty_tree,
+            &[],
+        )?;
+        markers.push((ref_path.clone(), commit, format!("import scope {}", scope)));
+    }
+
+    let mut tx = LayerTransaction::begin(&repo, root, format!("import mode {}", name))?;
+    let queued = layer_commits
+        .iter()
+        .try_for_each(|(ref_path, commit)| tx.add_ref_update(ref_path, *commit));
+    if let Err(e) = queued {
+        tx.abort()?;
+        return Err(e);
+    }
+    tx.commit()?;
+    if let Err(e) = set_refs(&repo, &markers) {
+        let mut undo = JinTransaction::new(&repo)?;
+        for ref_path in &layer_refs {
+            undo.lock_ref(ref_path)?;
+            undo.remove(ref_path)?;
+        }
+        undo.commit()?;
+        return Err(e);
+    }
+    usage::record(repo.path(), |log| {
+        log.mark_created(UsageKind::Mode, name, chrono::Utc::now())
+    });
+
+    println!(
+        "Imported mode '{}' ({} layer(s), {} file(s))",
+        name,
+        archive.layers.len(),
+        files
+    );
+    println!("Activate with: jin mode use {}", name);
+    Ok(())
+}
+
 /// Validate mode name
 ///
 /// Mode names must be:
 /// - Non-empty
 /// - Alphanumeric and underscores only
 /// - Not reserved names
-fn validate_mode_name(name: &str) -> Result<()> {
+pub(crate) fn validate_mode_name(name: &str) -> Result<()> {
     // Check for empty name
     if name.is_empty() {
         return Err(JinError::Other("Mode name cannot be empty".to_string()));
@@ -50,12 +327,12 @@ fn validate_mode_name(name: &str) -> Result<()> {
 }
 
 /// Create a new mode
-fn create(name: &str) -> Result<()> {
+pub(crate) fn create(root: &Path, name: &str) -> Result<()> {
     // Validate mode name
     validate_mode_name(name)?;
 
     // Open Jin repository
-    let repo = JinRepo::open_or_create()?;
+    let repo = JinRepo::open_or_create(root)?;
 
     // Use _mode suffix to make the mode name a directory (allows nested scopes)
     // The underscore prefix ensures Git ref name validity
@@ -76,6 +353,9 @@ fn create(name: &str) -> Result<()> {
 
     // Set Git ref
     repo.set_ref(&ref_path, commit_oid, &format!("create mode {}", name))?;
+    usage::record(repo.path(), |log| {
+        log.mark_created(UsageKind::Mode, name, chrono::Utc::now())
+    });
 
     println!("Created mode '{}'", name);
     println!("Activate with: jin mode use {}", name);
@@ -84,12 +364,12 @@ fn create(name: &str) -> Result<()> {
 }
 
 /// Activate a mode
-fn use_mode(name: &str) -> Result<()> {
+pub(crate) fn use_mode(root: &Path, name: &str) -> Result<()> {
     // Validate mode name
     validate_mode_name(name)?;
 
     // Open Jin repository
-    let repo = JinRepo::open_or_create()?;
+    let repo = JinRepo::open_or_create(root)?;
 
     // Check if mode exists (using _mode suffix)
     let ref_path = format!("refs/jin/modes/{}/_mode", name);
@@ -101,7 +381,7 @@ fn use_mode(name: &str) -> Result<()> {
     }
 
     // Load project context
-    let mut context = match ProjectContext::load() {
+    let mut context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -113,10 +393,13 @@ fn use_mode(name: &str) -> Result<()> {
     context.mode = Some(name.to_string());
 
     // Save context
-    context.save()?;
+    context.save(root)?;
+    usage::record(repo.path(), |log| {
+        log.mark_used(UsageKind::Mode, name, chrono::Utc::now())
+    });
 
     // Load workspace metadata (may not exist yet)
-    let metadata = match WorkspaceMetadata::load() {
+    let metadata = match WorkspaceMetadata::load(root) {
         Ok(meta) => Some(meta),
         Err(JinError::NotFound(_)) => None, // Fresh workspace - no metadata yet
         Err(e) => return Err(e),            // Other errors should propagate
@@ -136,7 +419,7 @@ fn use_mode(name: &str) -> Result<()> {
         if let Some(old_mode) = metadata_mode {
             if old_mode != name {
                 // Modes differ - clear metadata to prevent detached state
-                let metadata_path = WorkspaceMetadata::default_path();
+                let metadata_path = WorkspaceMetadata::default_path(root);
                 if metadata_path.exists() {
                     std::fs::remove_file(&metadata_path)?;
                     println!(
@@ -149,7 +432,7 @@ fn use_mode(name: &str) -> Result<()> {
         } else {
             // No mode layer in metadata (only global layers)
             // Clear metadata since we're now activating a mode
-            let metadata_path = WorkspaceMetadata::default_path();
+            let metadata_path = WorkspaceMetadata::default_path(root);
             if metadata_path.exists() {
                 std::fs::remove_file(&metadata_path)?;
                 println!("Cleared workspace metadata (activating mode '{}').", name);
@@ -165,12 +448,12 @@ fn use_mode(name: &str) -> Result<()> {
 }
 
 /// List all modes
-pub fn list() -> Result<()> {
+pub fn list(root: &Path) -> Result<()> {
     // Open Jin repository
-    let repo = JinRepo::open_or_create()?;
+    let repo = JinRepo::open_or_create(root)?;
 
     // Load project context to identify active mode
-    let context = match ProjectContext::load() {
+    let context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -189,30 +472,47 @@ pub fn list() -> Result<()> {
 
     println!("Available modes:");
 
-    // Extract names (strip both prefix and _mode suffix)
-    for ref_path in mode_refs {
+    // Extract names (strip both prefix and _mode suffix); archived modes
+    // are listed separately
+    let archive_set = ArchiveSet::load_or_default(&repo);
+    let mut archived = Vec::new();
+    for ref_path in &mode_refs {
         let name = ref_path
             .strip_prefix("refs/jin/modes/")
             .and_then(|s| s.strip_suffix("/_mode"))
-            .unwrap_or(&ref_path);
+            .unwrap_or(ref_path);
 
-        if Some(name) == context.mode.as_deref() {
+        if archive_set.contains(ArchiveKind::Mode, name) {
+            archived.push(name);
+        } else if Some(name) == context.mode.as_deref() {
             println!("  * {} [active]", name);
         } else {
             println!("    {}", name);
         }
     }
 
+    if !archived.is_empty() {
+        println!();
+        println!("Archived modes (read-only):");
+        for name in archived {
+            if Some(name) == context.mode.as_deref() {
+                println!("  * {} [active]", name);
+            } else {
+                println!("    {}", name);
+            }
+        }
+    }
+
     Ok(())
 }
 
 /// Delete a mode
-fn delete(name: &str) -> Result<()> {
+fn delete(root: &Path, name: &str) -> Result<()> {
     // Validate mode name
     validate_mode_name(name)?;
 
     // Open Jin repository
-    let repo = JinRepo::open_or_create()?;
+    let repo = JinRepo::open_or_create(root)?;
 
     // Use _mode suffix for the mode ref
     let ref_path = format!("refs/jin/modes/{}/_mode", name);
@@ -221,7 +521,7 @@ fn delete(name: &str) -> Result<()> {
     }
 
     // Load project context to check if active
-    let mut context = match ProjectContext::load() {
+    let mut context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -233,7 +533,7 @@ fn delete(name: &str) -> Result<()> {
     if Some(name) == context.mode.as_deref() {
         println!("Mode '{}' is currently active. Deactivating...", name);
         context.mode = None;
-        context.save()?;
+        context.save(root)?;
     }
 
     // Delete main mode ref
@@ -258,15 +558,17 @@ fn delete(name: &str) -> Result<()> {
         }
     }
 
+    usage::record(repo.path(), |log| log.remove(UsageKind::Mode, name));
+
     println!("Deleted mode '{}'", name);
 
     Ok(())
 }
 
 /// Show currently active mode
-fn show() -> Result<()> {
+fn show(root: &Path) -> Result<()> {
     // Load project context
-    let context = match ProjectContext::load() {
+    let context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -283,9 +585,9 @@ fn show() -> Result<()> {
 }
 
 /// Unset (deactivate) current mode
-fn unset() -> Result<()> {
+fn unset(root: &Path) -> Result<()> {
     // Load project context
-    let mut context = match ProjectContext::load() {
+    let mut context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -303,7 +605,7 @@ fn unset() -> Result<()> {
     context.mode = None;
 
     // Save context
-    context.save()?;
+    context.save(root)?;
 
     println!("Deactivated mode");
     println!("Mode layer no longer available for staging");
@@ -346,144 +648,201 @@ mod tests {
     #[test]
     #[serial]
     fn test_create_mode() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        let result = create("testmode");
+        let ctx = crate::test_utils::setup_unit_test();
+        let result = create(&ctx.project_path, "testmode");
         assert!(result.is_ok());
 
         // Verify ref was created (using _mode suffix)
-        let repo = JinRepo::open_or_create().unwrap();
+        let repo = JinRepo::open_or_create(&ctx.project_path).unwrap();
         assert!(repo.ref_exists("refs/jin/modes/testmode/_mode"));
     }
 
     #[test]
     #[serial]
     fn test_create_mode_duplicate() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        create("testmode").unwrap();
+        let ctx = crate::test_utils::setup_unit_test();
+        create(&ctx.project_path, "testmode").unwrap();
 
         // Try to create again
-        let result = create("testmode");
+        let result = create(&ctx.project_path, "testmode");
         assert!(matches!(result, Err(JinError::AlreadyExists(_))));
     }
 
     #[test]
     #[serial]
     fn test_use_mode() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        create("testmode").unwrap();
+        let ctx = crate::test_utils::setup_unit_test();
+        create(&ctx.project_path, "testmode").unwrap();
 
-        let result = use_mode("testmode");
+        let result = use_mode(&ctx.project_path, "testmode");
         assert!(result.is_ok());
 
         // Verify context was updated
-        let context = ProjectContext::load().unwrap();
+        let context = ProjectContext::load(&ctx.project_path).unwrap();
         assert_eq!(context.mode, Some("testmode".to_string()));
     }
 
     #[test]
     #[serial]
     fn test_use_mode_nonexistent() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        let result = use_mode("nonexistent");
+        let ctx = crate::test_utils::setup_unit_test();
+        let result = use_mode(&ctx.project_path, "nonexistent");
         assert!(matches!(result, Err(JinError::NotFound(_))));
     }
 
     #[test]
     #[serial]
     fn test_list_empty() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        let result = list();
+        let ctx = crate::test_utils::setup_unit_test();
+        let result = list(&ctx.project_path);
         assert!(result.is_ok());
     }
 
     #[test]
     #[serial]
     fn test_list_with_modes() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        create("mode1").unwrap();
-        create("mode2").unwrap();
-        use_mode("mode1").unwrap();
+        let ctx = crate::test_utils::setup_unit_test();
+        create(&ctx.project_path, "mode1").unwrap();
+        create(&ctx.project_path, "mode2").unwrap();
+        use_mode(&ctx.project_path, "mode1").unwrap();
 
-        let result = list();
+        let result = list(&ctx.project_path);
         assert!(result.is_ok());
     }
 
     #[test]
     #[serial]
     fn test_show_no_mode() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        let result = show();
+        let ctx = crate::test_utils::setup_unit_test();
+        let result = show(&ctx.project_path);
         assert!(result.is_ok());
     }
 
     #[test]
     #[serial]
     fn test_show_with_mode() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        create("testmode").unwrap();
-        use_mode("testmode").unwrap();
+        let ctx = crate::test_utils::setup_unit_test();
+        create(&ctx.project_path, "testmode").unwrap();
+        use_mode(&ctx.project_path, "testmode").unwrap();
 
-        let result = show();
+        let result = show(&ctx.project_path);
         assert!(result.is_ok());
     }
 
     #[test]
     #[serial]
     fn test_unset() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        create("testmode").unwrap();
-        use_mode("testmode").unwrap();
+        let ctx = crate::test_utils::setup_unit_test();
+        create(&ctx.project_path, "testmode").unwrap();
+        use_mode(&ctx.project_path, "testmode").unwrap();
 
-        let result = unset();
+        let result = unset(&ctx.project_path);
         assert!(result.is_ok());
 
         // Verify mode was unset
-        let context = ProjectContext::load().unwrap();
+        let context = ProjectContext::load(&ctx.project_path).unwrap();
         assert_eq!(context.mode, None);
     }
 
     #[test]
     #[serial]
     fn test_unset_no_mode() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        let result = unset();
+        let ctx = crate::test_utils::setup_unit_test();
+        let result = unset(&ctx.project_path);
         assert!(result.is_ok());
     }
 
     #[test]
     #[serial]
     fn test_delete_mode() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        create("testmode").unwrap();
+        let ctx = crate::test_utils::setup_unit_test();
+        create(&ctx.project_path, "testmode").unwrap();
 
-        let result = delete("testmode");
+        let result = delete(&ctx.project_path, "testmode");
         assert!(result.is_ok());
 
         // Verify ref was deleted (using _mode suffix)
-        let repo = JinRepo::open_or_create().unwrap();
+        let repo = JinRepo::open_or_create(&ctx.project_path).unwrap();
         assert!(!repo.ref_exists("refs/jin/modes/testmode/_mode"));
     }
 
     #[test]
     #[serial]
     fn test_delete_active_mode() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        create("testmode").unwrap();
-        use_mode("testmode").unwrap();
+        let ctx = crate::test_utils::setup_unit_test();
+        create(&ctx.project_path, "testmode").unwrap();
+        use_mode(&ctx.project_path, "testmode").unwrap();
 
-        let result = delete("testmode");
+        let result = delete(&ctx.project_path, "testmode");
         assert!(result.is_ok());
 
         // Verify mode was unset
-        let context = ProjectContext::load().unwrap();
+        let context = ProjectContext::load(&ctx.project_path).unwrap();
         assert_eq!(context.mode, None);
     }
 
     #[test]
     #[serial]
     fn test_delete_nonexistent() {
-        let _ctx = crate::test_utils::setup_unit_test();
-        let result = delete("nonexistent");
+        let ctx = crate::test_utils::setup_unit_test();
+        let result = delete(&ctx.project_path, "nonexistent");
         assert!(matches!(result, Err(JinError::NotFound(_))));
     }
+
+    #[test]
+    #[serial]
+    fn test_import_validates_archive() {
+        let ctx = crate::test_utils::setup_unit_test();
+        let path = ctx.project_path.join("mode.yaml");
+        let write = |archive: &ModeArchive| {
+            std::fs::write(&path, serde_yaml::to_string(archive).unwrap()).unwrap();
+        };
+        let mut archive = ModeArchive {
+            format: ARCHIVE_FORMAT + 1,
+            mode: "shared".to_string(),
+            exported_at: String::new(),
+            scopes: Vec::new(),
+            layers: vec![ArchivedLayer {
+                ref_suffix: "_".to_string(),
+                files: BTreeMap::from([("a.txt".to_string(), "a\n".to_string())]),
+            }],
+        };
+
+        write(&archive);
+        assert!(matches!(
+            import(&ctx.project_path, &path, None),
+            Err(JinError::Parse { .. })
+        ));
+
+        archive.format = ARCHIVE_FORMAT;
+        archive.layers[0].ref_suffix = "../../global".to_string();
+        write(&archive);
+        assert!(matches!(
+            import(&ctx.project_path, &path, None),
+            Err(JinError::Parse { .. })
+        ));
+        let repo = JinRepo::open_or_create(&ctx.project_path).unwrap();
+        assert!(!repo.ref_exists("refs/jin/modes/shared/_mode"));
+
+        // A valid ref name that isn't a layer writes nothing either
+        archive.layers[0].ref_suffix = "bogus".to_string();
+        write(&archive);
+        assert!(matches!(
+            import(&ctx.project_path, &path, None),
+            Err(JinError::Parse { .. })
+        ));
+        assert!(!repo.ref_exists("refs/jin/modes/shared/_mode"));
+
+        archive.layers[0].ref_suffix = "_".to_string();
+        archive.scopes = vec!["lang/rust".to_string()];
+        archive.layers.push(ArchivedLayer {
+            ref_suffix: "scope/lang/rust/_".to_string(),
+            files: BTreeMap::from([("b.txt".to_string(), "b\n".to_string())]),
+        });
+        write(&archive);
+        import(&ctx.project_path, &path, Some("renamed")).unwrap();
+        let (exported, skipped) = build_archive(&repo, "renamed").unwrap();
+        assert!(skipped.is_empty());
+        assert_eq!(exported.layers, archive.layers);
+    }
 }
@@ -32,7 +32,7 @@ use std::path::{Path, PathBuf};
 /// - Destination already exists in staging
 /// - Routing options are invalid
 /// - Jin is not initialized
-pub fn execute(args: MvArgs) -> Result<()> {
+pub fn execute(args: MvArgs, root: &Path) -> Result<()> {
     // 1. Validate we have file pairs (must be even number)
     if args.files.is_empty() {
         return Err(JinError::Other("No files specified".to_string()));
@@ -44,7 +44,7 @@ pub fn execute(args: MvArgs) -> Result<()> {
     }
 
     // 2. Load project context for active mode/scope
-    let context = match ProjectContext::load() {
+    let context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => return Err(JinError::NotInitialized),
         Err(_) => ProjectContext::default(),
@@ -64,10 +64,10 @@ pub fn execute(args: MvArgs) -> Result<()> {
     let target_layer = route_to_layer(&options, &context)?;
 
     // 5. Open Jin repository (needed for consistency with other commands)
-    let _repo = JinRepo::open_or_create()?;
+    let _repo = JinRepo::open_or_create(root)?;
 
     // 6. Load staging index
-    let mut staging = StagingIndex::load().unwrap_or_else(|_| StagingIndex::new());
+    let mut staging = StagingIndex::load(root).unwrap_or_else(|_| StagingIndex::new());
 
     // 7. Dry-run mode: print what would be moved and return
     if args.dry_run {
@@ -75,7 +75,7 @@ pub fn execute(args: MvArgs) -> Result<()> {
             let src = PathBuf::from(&chunk[0]);
             let dst = PathBuf::from(&chunk[1]);
             if staging.get(&src).is_some() {
-                let workspace_action = if args.force && src.exists() {
+                let workspace_action = if args.force && root.join(&src).exists() {
                     "and from workspace"
                 } else {
                     "from staging only"
@@ -101,7 +101,7 @@ pub fn execute(args: MvArgs) -> Result<()> {
             let src = PathBuf::from(&chunk[0]);
             let dst = PathBuf::from(&chunk[1]);
             // Check if file is in staging and exists in workspace
-            if staging.get(&src).is_some() && src.exists() {
+            if staging.get(&src).is_some() && root.join(&src).exists() {
                 Some((src, dst))
             } else {
                 None
@@ -128,14 +128,14 @@ pub fn execute(args: MvArgs) -> Result<()> {
     for chunk in args.files.chunks(2) {
         let src = PathBuf::from(&chunk[0]);
         let dst = PathBuf::from(&chunk[1]);
-        match move_file(&src, &dst, target_layer, &mut staging, &args) {
+        match move_file(root, &src, &dst, target_layer, &mut staging, &args) {
             Ok(_) => moved_count += 1,
             Err(e) => errors.push(format!("{} -> {}: {}", src.display(), dst.display(), e)),
         }
     }
 
     // 11. Save staging index
-    staging.save()?;
+    staging.save(root)?;
 
     // 12. Print summary
     if moved_count > 0 {
@@ -164,6 +164,7 @@ pub fn execute(args: MvArgs) -> Result<()> {
 
 /// Move a single file in the staging index
 fn move_file(
+    root: &Path,
     src: &Path,
     dst: &Path,
     layer: Layer,
@@ -201,14 +202,14 @@ fn move_file(
     staging.add(rename_entry);
 
     // 6. Update .gitignore: Remove old path, add new path
-    if let Err(e) = remove_from_managed_block(src) {
+    if let Err(e) = remove_from_managed_block(root, src) {
         eprintln!(
             "Warning: Could not remove {} from .gitignore: {}",
             src.display(),
             e
         );
     }
-    if let Err(e) = ensure_in_managed_block(dst) {
+    if let Err(e) = ensure_in_managed_block(root, dst) {
         eprintln!(
             "Warning: Could not add {} to .gitignore: {}",
             dst.display(),
@@ -217,6 +218,7 @@ fn move_file(
     }
 
     // 7. Workspace move: If --force is set and file exists
+    let (src, dst) = (root.join(src), root.join(dst));
     if args.force && src.exists() {
         // Create parent directory if needed
         if let Some(parent) = dst.parent() {
@@ -226,12 +228,12 @@ fn move_file(
         }
 
         // Atomic rename with cross-filesystem fallback
-        match std::fs::rename(src, dst) {
+        match std::fs::rename(&src, &dst) {
             Ok(()) => Ok(()),
             Err(e) if e.raw_os_error() == Some(18) => {
                 // Cross-device link: copy + delete fallback
-                std::fs::copy(src, dst)?;
-                std::fs::remove_file(src)
+                std::fs::copy(&src, &dst)?;
+                std::fs::remove_file(&src)
             }
             Err(e) => Err(e),
         }?;
@@ -284,7 +286,7 @@ mod tests {
             force: false,
             dry_run: false,
         };
-        let result = execute(args);
+        let result = execute(args, Path::new("."));
         assert!(result.is_err());
     }
 
@@ -304,7 +306,7 @@ mod tests {
             force: false,
             dry_run: false,
         };
-        let result = execute(args);
+        let result = execute(args, Path::new("."));
         assert!(result.is_err());
     }
 
@@ -323,7 +325,7 @@ mod tests {
             force: false,
             dry_run: false,
         };
-        let result = execute(args);
+        let result = execute(args, temp.path());
         assert!(matches!(result, Err(JinError::NotInitialized)));
     }
 
@@ -354,7 +356,14 @@ mod tests {
 
         let src = project_path.join("src.txt");
         let dst = project_path.join("dst.txt");
-        let result = move_file(&src, &dst, Layer::ProjectBase, &mut staging, &args);
+        let result = move_file(
+            &ctx.project_path,
+            &src,
+            &dst,
+            Layer::ProjectBase,
+            &mut staging,
+            &args,
+        );
         assert!(matches!(result, Err(JinError::NotFound(_))));
     }
 
@@ -386,14 +395,21 @@ mod tests {
             dry_run: false,
         };
 
-        let result = move_file(&src, &dst, Layer::ProjectBase, &mut staging, &args);
+        let result = move_file(
+            &ctx.project_path,
+            &src,
+            &dst,
+            Layer::ProjectBase,
+            &mut staging,
+            &args,
+        );
         assert!(result.is_err());
     }
 
     #[test]
     #[serial]
     fn test_execute_project_without_mode() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
         let args = MvArgs {
             files: vec!["src.txt".to_string(), "dst.txt".to_string()],
@@ -405,14 +421,14 @@ mod tests {
             force: false,
             dry_run: false,
         };
-        let result = execute(args);
+        let result = execute(args, &ctx.project_path);
         assert!(result.is_err());
     }
 
     #[test]
     #[serial]
     fn test_execute_global_with_mode() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
         let args = MvArgs {
             files: vec!["src.txt".to_string(), "dst.txt".to_string()],
@@ -424,7 +440,7 @@ mod tests {
             force: false,
             dry_run: false,
         };
-        let result = execute(args);
+        let result = execute(args, &ctx.project_path);
         assert!(result.is_err());
     }
 
@@ -455,7 +471,14 @@ mod tests {
         };
 
         // Move file (without force - only staging)
-        let result = move_file(&src, &dst, Layer::ProjectBase, &mut staging, &args);
+        let result = move_file(
+            &ctx.project_path,
+            &src,
+            &dst,
+            Layer::ProjectBase,
+            &mut staging,
+            &args,
+        );
         assert!(result.is_ok());
 
         // Source should be removed from staging
@@ -478,16 +501,16 @@ mod tests {
         std::fs::write(&test_file, r#"{"test": true}"#).unwrap();
 
         // Clear any existing staging index to avoid test pollution
-        let _ = StagingIndex::load().map(|mut s| {
+        let _ = StagingIndex::load(&ctx.project_path).map(|mut s| {
             s.clear();
-            let _ = s.save();
+            let _ = s.save(&ctx.project_path);
         });
 
         let mut staging = StagingIndex::new();
         // Use absolute path to ensure consistency
         let entry = StagedEntry::new(test_file.clone(), Layer::ProjectBase, "abc123".to_string());
         staging.add(entry);
-        staging.save().unwrap();
+        staging.save(&ctx.project_path).unwrap();
 
         // Verify file is in staging before dry run
         assert!(staging.get(&test_file).is_some());
@@ -507,7 +530,7 @@ mod tests {
             dry_run: true,
         };
 
-        let result = execute(args);
+        let result = execute(args, &ctx.project_path);
         assert!(result.is_ok());
 
         // Verify dry_run didn't modify the in-memory staging we created
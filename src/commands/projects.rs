@@ -0,0 +1,60 @@
+//! Implementation of `jin projects`
+//!
+//! Lists the workspaces recorded in the repository's workspace registry
+//! (see [`crate::core::workspaces`]) with their project name, last apply
+//! time and whether the directory still exists.
+
+use crate::cli::ProjectsArgs;
+use crate::core::workspaces::WorkspaceRegistry;
+use crate::core::{JinError, Result};
+use crate::git::JinRepo;
+use std::path::Path;
+
+/// Execute the projects command
+pub fn execute(args: ProjectsArgs, root: &Path) -> Result<()> {
+    let repo = JinRepo::open(root).map_err(|_| JinError::NotInitialized)?;
+    let mut registry = WorkspaceRegistry::load(repo.path())?;
+
+    if args.forget_missing {
+        let missing = registry.forget_missing();
+        registry.save(repo.path())?;
+        for root in &missing {
+            println!("Forgot {}", root);
+        }
+        println!("Forgot {} missing workspace(s)", missing.len());
+        return Ok(());
+    }
+
+    if registry.workspaces.is_empty() {
+        println!("No workspaces known to {}", repo.path().display());
+        return Ok(());
+    }
+
+    println!("Workspaces using {}:", repo.path().display());
+    let mut missing = 0;
+    for (root, record) in &registry.workspaces {
+        let exists = Path::new(root).exists();
+        if !exists {
+            missing += 1;
+        }
+        println!(
+            "  {} {:<40} project: {:<16} last apply: {}",
+            if exists { " " } else { "!" },
+            root,
+            record.project.as_deref().unwrap_or("(none)"),
+            record
+                .last_apply
+                .map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "never".to_string())
+        );
+    }
+
+    if missing > 0 {
+        println!();
+        println!(
+            "{} workspace(s) marked '!' no longer exist. Remove them with: jin projects --forget-missing",
+            missing
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,159 @@
+//! Implementation of `jin prompt`
+//!
+//! Prints a one-line summary of the workspace for shell prompts, such as
+//! `claude:backend +2*`. It runs on every prompt, so it reads only
+//! `.jin/context`, the staging index and `.jin/state_cache`, never the Jin
+//! repository itself. The one fact that needs git (whether the applied
+//! layers moved since the last apply) is computed on a cache miss and kept
+//! in the cache, keyed by the refs stamp every ref update rewrites (see
+//! [`crate::git::refs::touch_refs_stamp`]).
+
+use crate::commands::apply::PausedApplyState;
+use crate::core::{ProjectContext, Result};
+use crate::git::refs::refs_stamp;
+use crate::git::JinRepo;
+use crate::staging::{StagingIndex, WorkspaceMetadata};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// State derived from the repository, cached between prompts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct StateCache {
+    /// What the state was computed from; a different key means a miss
+    key: String,
+    /// Whether a layer applied to the workspace has new commits
+    layers_updated: bool,
+}
+
+impl StateCache {
+    /// Path of the cache in a workspace (`.jin/state_cache`)
+    fn default_path(root: &Path) -> PathBuf {
+        root.join(".jin").join("state_cache")
+    }
+
+    /// Load the cache if it was computed for `key`
+    fn load(root: &Path, key: &str) -> Option<Self> {
+        let content = std::fs::read_to_string(Self::default_path(root)).ok()?;
+        let cache: Self = serde_json::from_str(&content).ok()?;
+        (cache.key == key).then_some(cache)
+    }
+
+    /// Save the cache, ignoring failures (the next prompt recomputes)
+    fn save(&self, root: &Path) {
+        if let Ok(content) = serde_json::to_string(self) {
+            let _ = std::fs::write(Self::default_path(root), content);
+        }
+    }
+}
+
+/// Execute the prompt command
+///
+/// Prints nothing outside a Jin project, and never fails on unreadable
+/// state, so it is safe to call from `PS1`.
+pub fn execute(root: &Path) -> Result<()> {
+    let Ok(context) = ProjectContext::load(root) else {
+        return Ok(());
+    };
+    let staged = StagingIndex::load(root).map(|i| i.len()).unwrap_or(0);
+    let layers_updated = state(root, &context).is_some_and(|s| s.layers_updated);
+    println!(
+        "{}",
+        render(
+            &context,
+            staged,
+            layers_updated,
+            PausedApplyState::exists(root)
+        )
+    );
+    Ok(())
+}
+
+/// Cached state for `context`, recomputed when the key changed
+fn state(root: &Path, context: &ProjectContext) -> Option<StateCache> {
+    let repo_path = JinRepo::resolve_path(root).ok()?;
+    let applied = std::fs::metadata(WorkspaceMetadata::default_path(root))
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let key = format!(
+        "{}|{}|{}|{}|{}",
+        refs_stamp(&repo_path),
+        applied,
+        context.mode.as_deref().unwrap_or_default(),
+        context.scope.as_deref().unwrap_or_default(),
+        context.project.as_deref().unwrap_or_default()
+    );
+    if let Some(cache) = StateCache::load(root, &key) {
+        return Some(cache);
+    }
+
+    let cache = StateCache {
+        key,
+        layers_updated: layers_updated(root, &repo_path),
+    };
+    cache.save(root);
+    Some(cache)
+}
+
+/// Whether a layer moved since the last apply (the slow path)
+fn layers_updated(root: &Path, repo_path: &Path) -> bool {
+    let Ok(metadata) = WorkspaceMetadata::load(root) else {
+        return false;
+    };
+    let Ok(repo) = JinRepo::open_at(&repo_path.to_path_buf()) else {
+        return false;
+    };
+    metadata.layer_commits.iter().any(|(ref_path, applied)| {
+        let current = repo.inner().refname_to_id(ref_path).ok();
+        current.map(|oid| oid.to_string()).as_deref() != Some(applied.as_str())
+    })
+}
+
+/// Format the prompt: the context, then `+N` staged files, `*` when the
+/// layers moved since the last apply and `!` while an apply is paused
+fn render(context: &ProjectContext, staged: usize, layers_updated: bool, paused: bool) -> String {
+    let mut label = match (context.mode.as_deref(), context.scope.as_deref()) {
+        (Some(mode), Some(scope)) => format!("{}:{}", mode, scope),
+        (Some(mode), None) => mode.to_string(),
+        (None, Some(scope)) => format!(":{}", scope),
+        (None, None) => "-".to_string(),
+    };
+
+    let mut markers = String::new();
+    if staged > 0 {
+        markers.push_str(&format!("+{}", staged));
+    }
+    if layers_updated {
+        markers.push('*');
+    }
+    if paused {
+        markers.push('!');
+    }
+    if !markers.is_empty() {
+        label.push(' ');
+        label.push_str(&markers);
+    }
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mut context = ProjectContext::default();
+        assert_eq!(render(&context, 0, false, false), "-");
+
+        context.mode = Some("claude".to_string());
+        assert_eq!(render(&context, 0, false, false), "claude");
+
+        context.scope = Some("backend".to_string());
+        assert_eq!(render(&context, 2, true, false), "claude:backend +2*");
+
+        context.mode = None;
+        assert_eq!(render(&context, 0, false, true), ":backend !");
+    }
+}
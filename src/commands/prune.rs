@@ -0,0 +1,227 @@
+//! Implementation of `jin prune`
+//!
+//! Deletes modes and scopes that were never used or have been abandoned
+//! (no recorded use within `--than`), together with every layer ref nested
+//! under them. The candidates are shown first and deletion requires
+//! confirmation unless `--force` is given. Contexts active in the current
+//! project are never pruned.
+
+use crate::cli::PruneArgs;
+use crate::commands::list::find_stale;
+use crate::core::usage::{self, StaleContext, UsageKind};
+use crate::core::{JinError, JinMap, ProjectContext, Result};
+use crate::git::{JinRepo, RefOps};
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// A stale context and the refs that belong to it
+struct PruneCandidate {
+    stale: StaleContext,
+    refs: Vec<String>,
+}
+
+/// Execute the prune command
+pub fn execute(args: PruneArgs, root: &Path) -> Result<()> {
+    let repo = JinRepo::open(root).map_err(|_| JinError::NotInitialized)?;
+    let context = ProjectContext::load(root).unwrap_or_default();
+    let now = chrono::Utc::now();
+
+    let mut candidates = Vec::new();
+    for stale in find_stale(&repo, &args.than)? {
+        if is_active(&stale, &context) {
+            println!(
+                "Skipping {} '{}' (active in this project)",
+                stale.kind.as_str(),
+                stale.name
+            );
+            continue;
+        }
+        let refs = context_refs(&repo, stale.kind, &stale.name);
+        candidates.push(PruneCandidate { stale, refs });
+    }
+
+    if candidates.is_empty() {
+        println!(
+            "Nothing to prune (no modes or scopes unused for more than {})",
+            args.than
+        );
+        return Ok(());
+    }
+
+    println!("Unused for more than {}:", args.than);
+    for candidate in &candidates {
+        println!(
+            "  {} {:<24} {} ({} ref(s))",
+            candidate.stale.kind.as_str(),
+            candidate.stale.name,
+            candidate.stale.describe(now),
+            candidate.refs.len()
+        );
+    }
+    println!();
+
+    if args.dry_run {
+        println!("Dry run: nothing was deleted");
+        return Ok(());
+    }
+
+    if !args.force {
+        let message = format!(
+            "Delete {} mode(s)/scope(s) and all their layers? Type 'yes' to confirm:",
+            candidates.len()
+        );
+        if !prompt_confirmation(&message)? {
+            println!("Prune cancelled");
+            return Ok(());
+        }
+    }
+
+    let mut jinmap = JinMap::load(root).ok();
+    let mut deleted_refs = 0;
+    for candidate in &candidates {
+        for ref_path in &candidate.refs {
+            repo.delete_ref(ref_path)?;
+            if let Some(map) = jinmap.as_mut() {
+                map.mappings.remove(ref_path);
+            }
+            deleted_refs += 1;
+        }
+        usage::record(repo.path(), |log| {
+            log.remove(candidate.stale.kind, &candidate.stale.name)
+        });
+    }
+    if let Some(map) = jinmap {
+        if let Err(e) = map.save(root) {
+            eprintln!("Warning: Could not update .jinmap: {}", e);
+        }
+    }
+
+    println!(
+        "Pruned {} mode(s)/scope(s) ({} ref(s) deleted)",
+        candidates.len(),
+        deleted_refs
+    );
+
+    Ok(())
+}
+
+/// Whether a stale context is active in the current project
+fn is_active(stale: &StaleContext, context: &ProjectContext) -> bool {
+    let active = match stale.kind {
+        UsageKind::Mode => context.mode.as_deref(),
+        UsageKind::Scope => context.scope.as_deref(),
+    };
+    active == Some(stale.name.as_str())
+}
+
+/// Every ref belonging to a mode or scope, including nested layer refs
+///
+/// For a mode: its definition, mode-bound scopes and all mode layers
+/// (mode-base, mode-scope, mode-project, ...). For a scope: its untethered
+/// and mode-bound definitions, scope-base and mode-scope layers.
+fn context_refs(repo: &JinRepo, kind: UsageKind, name: &str) -> Vec<String> {
+    let patterns = match kind {
+        UsageKind::Mode => vec![
+            format!("refs/jin/modes/{}/*", name),
+            format!("refs/jin/layers/mode/{}/*", name),
+        ],
+        UsageKind::Scope => {
+            let ref_safe_name = name.replace(':', "/");
+            vec![
+                format!("refs/jin/scopes/{}", ref_safe_name),
+                format!("refs/jin/modes/*/scopes/{}", ref_safe_name),
+                format!("refs/jin/layers/scope/{}", ref_safe_name),
+                // Match the mode-scope layer and its projects explicitly so a
+                // parent scope never takes a nested scope's layers with it
+                format!("refs/jin/layers/mode/*/scope/{}/_", ref_safe_name),
+                format!("refs/jin/layers/mode/*/scope/{}/project/*", ref_safe_name),
+            ]
+        }
+    };
+
+    patterns
+        .iter()
+        .flat_map(|pattern| repo.list_refs(pattern).unwrap_or_default())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Prompt user for confirmation
+fn prompt_confirmation(message: &str) -> Result<bool> {
+    print!("{} ", message);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(input.trim().eq_ignore_ascii_case("yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::ObjectOps;
+    use serial_test::serial;
+
+    fn set_ref(repo: &JinRepo, name: &str) {
+        let tree = repo.create_tree(&[]).unwrap();
+        let commit = repo.create_commit(None, "test", tree, &[]).unwrap();
+        repo.set_ref(name, commit, "test").unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_context_refs_include_nested_layers() {
+        let ctx = crate::test_utils::setup_unit_test();
+        let repo = JinRepo::open_or_create(&ctx.project_path).unwrap();
+
+        set_ref(&repo, "refs/jin/modes/old/_mode");
+        set_ref(&repo, "refs/jin/modes/old/scopes/lang/rust");
+        set_ref(&repo, "refs/jin/layers/mode/old/_");
+        set_ref(&repo, "refs/jin/layers/mode/old/scope/lang/rust/_");
+        set_ref(&repo, "refs/jin/layers/mode/older/_");
+        set_ref(&repo, "refs/jin/layers/scope/lang/rust");
+        set_ref(&repo, "refs/jin/layers/mode/old/scope/lang/_");
+
+        assert_eq!(
+            context_refs(&repo, UsageKind::Mode, "old"),
+            vec![
+                "refs/jin/layers/mode/old/_",
+                "refs/jin/layers/mode/old/scope/lang/_",
+                "refs/jin/layers/mode/old/scope/lang/rust/_",
+                "refs/jin/modes/old/_mode",
+                "refs/jin/modes/old/scopes/lang/rust",
+            ]
+        );
+        assert_eq!(
+            context_refs(&repo, UsageKind::Scope, "lang:rust"),
+            vec![
+                "refs/jin/layers/mode/old/scope/lang/rust/_",
+                "refs/jin/layers/scope/lang/rust",
+                "refs/jin/modes/old/scopes/lang/rust",
+            ]
+        );
+        assert_eq!(
+            context_refs(&repo, UsageKind::Scope, "lang"),
+            vec!["refs/jin/layers/mode/old/scope/lang/_"]
+        );
+    }
+
+    #[test]
+    fn test_is_active() {
+        let context = ProjectContext {
+            mode: Some("claude".to_string()),
+            ..Default::default()
+        };
+        let stale = |kind, name: &str| StaleContext {
+            kind,
+            name: name.to_string(),
+            record: Default::default(),
+        };
+        assert!(is_active(&stale(UsageKind::Mode, "claude"), &context));
+        assert!(!is_active(&stale(UsageKind::Scope, "claude"), &context));
+        assert!(!is_active(&stale(UsageKind::Mode, "cursor"), &context));
+    }
+}
@@ -1,25 +1,39 @@
 //! Implementation of `jin pull`
 //!
-//! Fetches remote updates and merges them into local layers.
-//! Requires clean workspace (no uncommitted changes).
+//! Fetches remote updates into the tracking namespace (see `jin fetch`) and
+//! merges each tracking ref into its local layer. Layers behind the remote
+//! fast-forward; diverged layers are merged three-way with the text merge
+//! and merge drivers `jin apply` uses.
+//!
+//! A layer with conflicts is left unchanged and the pull pauses the way
+//! `apply` does: `.jinmerge` files are written, the planned merge is saved
+//! to `.jin/.paused_pull.yaml`, and `jin resolve` commits the merge once
+//! every file of the layer is resolved. Requires clean staging.
 
 use crate::core::{JinError, Layer, Result};
+use crate::git::executor::interrupted;
 use crate::git::merge::{detect_merge_type, find_merge_base, MergeType};
+use crate::git::refs::layer_name;
+use crate::git::remote::{tracked_layer_ref, tracking_prefix, DEFAULT_REMOTE};
 use crate::git::{JinRepo, LayerTransaction, ObjectOps, RefOps, TreeOps};
+use crate::merge::conflicts::ConflictFiles;
 use crate::merge::jinmerge::JinMergeConflict;
 use crate::merge::text::{text_merge, TextMergeResult};
+use crate::merge::MergeDrivers;
 use crate::staging::StagingIndex;
+use chrono::{DateTime, Utc};
 use git2::Oid;
-use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::path::{Path, PathBuf};
 
 /// Execute the pull command
 ///
 /// Fetches remote updates and merges them into local layers using LayerTransaction.
-/// Requires clean workspace to prevent data loss.
-pub fn execute() -> Result<()> {
-    // 1. Verify clean workspace
-    let staging = StagingIndex::load()?;
+/// Requires clean staging to prevent data loss.
+pub fn execute(root: &Path) -> Result<()> {
+    // 1. Verify clean workspace and no pull waiting on conflicts
+    let staging = StagingIndex::load(root)?;
     if !staging.is_empty() {
         return Err(JinError::Config(
             "Cannot pull with uncommitted changes. Commit or reset first.\n\
@@ -27,13 +41,19 @@ pub fn execute() -> Result<()> {
                 .into(),
         ));
     }
+    if PausedPullState::exists(root) {
+        return Err(JinError::Other(
+            "A previous pull is paused on conflicts. Run 'jin resolve' to finish it first."
+                .to_string(),
+        ));
+    }
 
     // 2. Implicit fetch
     println!("Fetching remote updates...");
-    super::fetch::execute()?;
+    super::fetch::execute(root)?;
 
     // 3. Open repository
-    let jin_repo = JinRepo::open_or_create()?;
+    let jin_repo = JinRepo::open_or_create(root)?;
 
     // 4. Detect which layers have updates
     let updates = detect_updates(&jin_repo)?;
@@ -45,164 +65,319 @@ pub fn execute() -> Result<()> {
 
     println!("\nMerging updates...");
 
-    // 5. Start transaction for atomic updates
-    let mut tx = LayerTransaction::begin(&jin_repo, "pull: merge remote updates")?;
-
-    // 6. Add each update to the transaction
+    // 5. Plan each layer's new commit; refs move together at the end
+    let mut ref_updates = Vec::new();
     let mut merge_count = 0;
+    let mut paused = Vec::new();
+    let mut pending_paths = HashSet::new();
+    let mut merge_files = ConflictFiles::load(root)?;
     for (ref_path, update_info) in &updates {
+        let name = layer_name(ref_path);
         match update_info.merge_type {
-            MergeType::UpToDate => {
-                // Already up to date - skip
-                continue;
-            }
+            MergeType::UpToDate => continue,
             MergeType::FastForward => {
-                // Simple fast-forward update (existing behavior)
-                tx.add_layer_update(
-                    update_info.layer,
-                    update_info.mode.as_deref(),
-                    update_info.scope.as_deref(),
-                    update_info.project.as_deref(),
-                    update_info.remote_oid,
-                )?;
-                println!("  ✓ {}: Updated (fast-forward)", format_ref_path(ref_path));
+                ref_updates.push((ref_path, update_info.remote_oid));
+                println!("  ✓ {}: Updated (fast-forward)", name);
                 merge_count += 1;
             }
             MergeType::LocalAhead => {
                 // Local is ahead of remote - no action needed for pull
-                println!(
-                    "  − {}: Local is ahead of remote",
-                    format_ref_path(ref_path)
-                );
+                println!("  − {}: Local is ahead of remote", name);
             }
             MergeType::Divergent => {
-                // Perform 3-way merge for divergent histories
+                // Divergent means the local layer exists
+                let local_oid = update_info.local_oid.unwrap();
                 match perform_three_way_merge(
+                    root,
                     &jin_repo,
-                    update_info.layer,
-                    update_info.mode.as_deref(),
-                    update_info.scope.as_deref(),
-                    update_info.project.as_deref(),
-                    update_info.local_oid.unwrap(), // Safe because divergent means local exists
+                    ref_path,
+                    local_oid,
                     update_info.remote_oid,
                 )? {
-                    MergeOutcome::Clean(merge_oid) => {
-                        tx.add_layer_update(
-                            update_info.layer,
-                            update_info.mode.as_deref(),
-                            update_info.scope.as_deref(),
-                            update_info.project.as_deref(),
-                            merge_oid,
+                    MergeOutcome::Clean(files) => {
+                        let merge_oid = commit_merge(
+                            &jin_repo,
+                            ref_path,
+                            local_oid,
+                            update_info.remote_oid,
+                            &files,
                         )?;
-                        println!("  ✓ {}: Merged (3-way)", format_ref_path(ref_path));
+                        ref_updates.push((ref_path, merge_oid));
+                        println!("  ✓ {}: Merged (3-way)", name);
                         merge_count += 1;
                     }
-                    MergeOutcome::Conflicts {
-                        merged_oid,
-                        conflict_files,
-                    } => {
-                        tx.add_layer_update(
-                            update_info.layer,
-                            update_info.mode.as_deref(),
-                            update_info.scope.as_deref(),
-                            update_info.project.as_deref(),
-                            merged_oid,
-                        )?;
+                    MergeOutcome::Conflicts { files, conflicts } => {
+                        // One .jinmerge file per path: a second layer
+                        // conflicting on the same file waits for the next pull
+                        if conflicts
+                            .iter()
+                            .any(|c| pending_paths.contains(&c.file_path))
+                        {
+                            println!(
+                                "  ! {}: Skipped, a file also conflicts in another layer (pull again after 'jin resolve')",
+                                name
+                            );
+                            continue;
+                        }
+
                         println!(
-                            "  ! {}: Merged with {} conflicts",
-                            format_ref_path(ref_path),
-                            conflict_files.len()
+                            "  ! {}: {} conflict(s), not merged yet",
+                            name,
+                            conflicts.len()
                         );
-                        for file in conflict_files {
+                        let mut conflict_paths = Vec::new();
+                        for conflict in conflicts {
+                            let merge_path = merge_files.place(&conflict.file_path);
+                            conflict.write_to_file(&root.join(merge_path))?;
                             println!(
                                 "      - {} has conflicts (.jinmerge created)",
-                                file.display()
+                                conflict.file_path.display()
                             );
+                            pending_paths.insert(conflict.file_path.clone());
+                            conflict_paths.push(conflict.file_path);
                         }
-                        merge_count += 1;
+                        paused.push(PausedLayerMerge {
+                            ref_path: ref_path.clone(),
+                            local: local_oid.to_string(),
+                            remote: update_info.remote_oid.to_string(),
+                            files: files
+                                .into_iter()
+                                .map(|(path, oid)| (path, oid.to_string()))
+                                .collect(),
+                            conflicts: conflict_paths,
+                        });
                     }
                 }
             }
         }
     }
+    merge_files.save()?;
 
-    // 7. Commit transaction (atomic)
+    // 6. Commit transaction (atomic), unless the user pressed Ctrl-C while
+    //    merging: no ref has been touched yet
+    if interrupted() {
+        return Err(JinError::Cancelled);
+    }
+    let mut tx = LayerTransaction::begin(&jin_repo, root, "pull: merge remote updates")?;
+    for (ref_path, oid) in &ref_updates {
+        tx.add_ref_update(ref_path, *oid)?;
+    }
     tx.commit()?;
 
-    // Only show success message if we actually merged something
     if merge_count > 0 {
         println!("\nSuccessfully merged {} layer(s)", merge_count);
+    }
+
+    // 7. Pause on conflicts
+    if !paused.is_empty() {
+        let state = PausedPullState {
+            timestamp: Utc::now(),
+            layers: paused,
+        };
+        state.save(root)?;
+
+        println!();
+        println!("Pull paused. Edit the .jinmerge files, then finish with:");
+        println!("  jin resolve <file>");
+        return Ok(());
+    }
+
+    if merge_count > 0 {
         println!("Run 'jin apply' to update workspace files");
-    } else if updates.is_empty() {
-        // This shouldn't happen since we return early above, but keep for safety
-        println!("Already up to date");
     } else {
-        // Had updates but none were fast-forward (e.g., all local ahead or divergent)
+        // Had updates but none could be merged (e.g., all local ahead)
         println!("\nNo layers merged");
     }
 
     Ok(())
 }
 
+/// A pull paused on conflicts
+///
+/// Saved to `.jin/.paused_pull.yaml`. Each layer keeps the commits it was
+/// planned from, so its merge is committed with both as parents once
+/// `jin resolve` has supplied every conflicted file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PausedPullState {
+    /// When the pull was paused
+    pub timestamp: DateTime<Utc>,
+    /// Layers waiting on conflict resolution
+    pub layers: Vec<PausedLayerMerge>,
+}
+
+/// A layer merge waiting on conflict resolution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PausedLayerMerge {
+    /// Local layer ref
+    pub ref_path: String,
+    /// Commit of the local layer the merge was planned from
+    pub local: String,
+    /// Commit of the remote layer being merged
+    pub remote: String,
+    /// Merged files and their blob IDs, resolved files included
+    pub files: BTreeMap<String, String>,
+    /// Files still conflicted
+    pub conflicts: Vec<PathBuf>,
+}
+
+impl PausedPullState {
+    /// Path of the saved state (`.jin/.paused_pull.yaml`)
+    pub fn default_path(root: &Path) -> PathBuf {
+        root.join(".jin").join(".paused_pull.yaml")
+    }
+
+    /// Check if a paused pull exists
+    pub fn exists(root: &Path) -> bool {
+        Self::default_path(root).exists()
+    }
+
+    /// Load state from `.jin/.paused_pull.yaml`
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = Self::default_path(root);
+        if !path.exists() {
+            return Err(JinError::Other("No paused pull found".to_string()));
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_yaml::from_str(&content)
+            .map_err(|e| JinError::Other(format!("Invalid paused pull state: {}", e)))
+    }
+
+    /// Save state, or remove it once no layer is waiting
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = Self::default_path(root);
+        if self.layers.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(&path)?;
+            }
+            return Ok(());
+        }
+
+        let content = serde_yaml::to_string(self)
+            .map_err(|e| JinError::Other(format!("Failed to serialize paused pull: {}", e)))?;
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, content)?;
+        std::fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+
+    /// Conflicted files of all layers
+    pub fn conflict_files(&self) -> Vec<PathBuf> {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.conflicts.iter().cloned())
+            .collect()
+    }
+
+    /// Record the resolved content of a conflicted file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no paused layer has `path` in conflict.
+    pub fn resolve_file(&mut self, repo: &JinRepo, path: &Path, content: &[u8]) -> Result<()> {
+        let layer = self
+            .layers
+            .iter_mut()
+            .find(|layer| layer.conflicts.iter().any(|p| p == path))
+            .ok_or_else(|| {
+                JinError::Other(format!(
+                    "File '{}' is not in conflict state",
+                    path.display()
+                ))
+            })?;
+        let oid = repo.create_blob(content)?;
+        layer
+            .files
+            .insert(path.display().to_string(), oid.to_string());
+        layer.conflicts.retain(|p| p != path);
+        Ok(())
+    }
+
+    /// Commit the merge of every layer with no conflicts left
+    ///
+    /// Returns the names of the layers updated.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`JinError::RefsChanged`] if a layer moved since the pull
+    /// was paused; that layer stays paused.
+    pub fn commit_resolved(&mut self, root: &Path, repo: &JinRepo) -> Result<Vec<String>> {
+        // Create every merge commit before touching any ref
+        let mut merges = Vec::new();
+        for layer in self.layers.iter().filter(|l| l.conflicts.is_empty()) {
+            let local = parse_oid(&layer.local)?;
+            let remote = parse_oid(&layer.remote)?;
+            if repo.resolve_ref(&layer.ref_path).ok() != Some(local) {
+                return Err(JinError::RefsChanged {
+                    operation: "the paused pull".to_string(),
+                    refs: layer_name(&layer.ref_path).to_string(),
+                });
+            }
+            let files = layer
+                .files
+                .iter()
+                .map(|(path, oid)| Ok((path.clone(), parse_oid(oid)?)))
+                .collect::<Result<Vec<_>>>()?;
+            let merge_oid = commit_merge(repo, &layer.ref_path, local, remote, &files)?;
+            merges.push((layer.ref_path.clone(), merge_oid));
+        }
+        if merges.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut tx = LayerTransaction::begin(repo, root, "pull: merge resolved remote updates")?;
+        for (ref_path, merge_oid) in &merges {
+            tx.add_ref_update(ref_path, *merge_oid)?;
+        }
+        tx.commit()?;
+
+        self.layers.retain(|layer| !layer.conflicts.is_empty());
+        Ok(merges
+            .iter()
+            .map(|(ref_path, _)| layer_name(ref_path).to_string())
+            .collect())
+    }
+}
+
+/// Parse a commit or blob ID saved in the paused state
+fn parse_oid(oid: &str) -> Result<Oid> {
+    Oid::from_str(oid).map_err(|e| JinError::Other(format!("Invalid paused pull state: {}", e)))
+}
+
 /// Information about a layer update
 #[derive(Debug)]
 struct LayerUpdateInfo {
-    layer: Layer,
-    mode: Option<String>,
-    scope: Option<String>,
-    project: Option<String>,
-    #[allow(dead_code)]
-    local_oid: Option<git2::Oid>,
-    remote_oid: git2::Oid,
+    local_oid: Option<Oid>,
+    remote_oid: Oid,
     merge_type: MergeType,
 }
 
-/// Detect which layers have remote updates
-fn detect_updates(jin_repo: &JinRepo) -> Result<HashMap<String, LayerUpdateInfo>> {
-    let mut updates = HashMap::new();
+/// Compare origin's tracking refs with the local layers, keyed by local ref
+fn detect_updates(jin_repo: &JinRepo) -> Result<BTreeMap<String, LayerUpdateInfo>> {
+    let mut updates = BTreeMap::new();
+    let user_local = Layer::UserLocal.ref_path(None, None, None);
 
-    // Get all remote refs
-    let remote_refs = jin_repo.list_refs("refs/jin/layers/*")?;
-
-    for ref_path in remote_refs {
-        // Skip user-local layer (never synced)
-        if ref_path.contains("/local") {
+    for tracking_ref in jin_repo.list_refs(&format!("{}*", tracking_prefix(DEFAULT_REMOTE)))? {
+        let Some(ref_path) = tracked_layer_ref(DEFAULT_REMOTE, &tracking_ref) else {
+            continue;
+        };
+        // Skip user-local layer (never synced) and anything not a layer
+        if ref_path == user_local || Layer::parse_layer_from_ref_path(&ref_path).is_none() {
             continue;
         }
 
-        let remote_oid = jin_repo.resolve_ref(&ref_path)?;
-
-        // Check if we have this ref locally
-        let local_oid = if jin_repo.ref_exists(&ref_path) {
-            Some(jin_repo.resolve_ref(&ref_path)?)
-        } else {
-            None
-        };
+        let remote_oid = jin_repo.resolve_ref(&tracking_ref)?;
+        let local_oid = jin_repo.resolve_ref(&ref_path).ok();
 
-        // Determine merge type
-        // For new layers (no local_oid), treat as FastForward
+        // New layers (no local ref) are a fast-forward
         let merge_type = match local_oid {
             Some(local) => detect_merge_type(jin_repo, local, remote_oid)?,
             None => MergeType::FastForward,
         };
 
-        // Determine if update is needed based on merge type
-        let needs_update = match merge_type {
-            MergeType::UpToDate => false,
-            MergeType::FastForward | MergeType::Divergent | MergeType::LocalAhead => true,
-        };
-
-        if needs_update {
-            // Parse layer information from ref path
-            let (layer, mode, scope, project) = parse_ref_path(&ref_path)?;
-
+        if merge_type != MergeType::UpToDate {
             updates.insert(
-                ref_path.clone(),
+                ref_path,
                 LayerUpdateInfo {
-                    layer,
-                    mode,
-                    scope,
-                    project,
                     local_oid,
                     remote_oid,
                     merge_type,
@@ -214,279 +389,239 @@ fn detect_updates(jin_repo: &JinRepo) -> Result<HashMap<String, LayerUpdateInfo>
     Ok(updates)
 }
 
-/// Parse layer information from ref path
-///
-/// Converts "refs/jin/layers/mode/claude" to (Layer::ModeBase, Some("claude"), None, None)
-#[allow(clippy::type_complexity)]
-fn parse_ref_path(
-    ref_path: &str,
-) -> Result<(Layer, Option<String>, Option<String>, Option<String>)> {
-    let path = ref_path
-        .strip_prefix("refs/jin/layers/")
-        .ok_or_else(|| JinError::InvalidLayer(format!("Invalid ref path: {}", ref_path)))?;
-
-    let parts: Vec<&str> = path.split('/').collect();
-
-    match parts.as_slice() {
-        ["global"] => Ok((Layer::GlobalBase, None, None, None)),
-        ["mode", mode] => Ok((Layer::ModeBase, Some(mode.to_string()), None, None)),
-        ["mode", mode, "scope", scope] => Ok((
-            Layer::ModeScope,
-            Some(mode.to_string()),
-            Some(scope.to_string()),
-            None,
-        )),
-        ["mode", mode, "scope", scope, "project", project] => Ok((
-            Layer::ModeScopeProject,
-            Some(mode.to_string()),
-            Some(scope.to_string()),
-            Some(project.to_string()),
-        )),
-        ["mode", mode, "project", project] => Ok((
-            Layer::ModeProject,
-            Some(mode.to_string()),
-            None,
-            Some(project.to_string()),
-        )),
-        ["scope", scope] => Ok((Layer::ScopeBase, None, Some(scope.to_string()), None)),
-        ["project", project] => Ok((Layer::ProjectBase, None, None, Some(project.to_string()))),
-        ["local"] => Ok((Layer::UserLocal, None, None, None)),
-        ["workspace"] => Ok((Layer::WorkspaceActive, None, None, None)),
-        _ => Err(JinError::InvalidLayer(format!(
-            "Unrecognized layer path: {}",
-            path
-        ))),
-    }
-}
-
-/// Outcome of a 3-way merge operation
-///
-/// Indicates whether the merge completed cleanly or has conflicts
-/// that require resolution.
+/// Outcome of a 3-way merge of one layer
 #[derive(Debug)]
 enum MergeOutcome {
-    /// Clean merge with no conflicts
-    Clean(Oid),
-    /// Merge completed but has conflicts requiring resolution
+    /// Every file merged: paths and blob IDs of the result
+    Clean(Vec<(String, Oid)>),
+    /// Some files need resolution
     Conflicts {
-        /// The merge commit OID (already created)
-        merged_oid: Oid,
-        /// Files that have conflicts (with .jinmerge files)
-        conflict_files: Vec<PathBuf>,
+        /// Files that merged cleanly
+        files: Vec<(String, Oid)>,
+        /// Conflicts to write as .jinmerge files
+        conflicts: Vec<JinMergeConflict>,
     },
 }
 
 /// Perform a 3-way merge for divergent layer histories
 ///
-/// This function implements the 3-way merge algorithm:
-/// 1. Find the merge base between local and remote
-/// 2. Extract file contents from base, local, and remote
-/// 3. Perform 3-way text merge on each file
-/// 4. Create .jinmerge files for conflicts
-/// 5. Create a merge commit with two parents
-///
-/// # Arguments
-///
-/// * `jin_repo` - The Jin repository
-/// * `layer` - The layer being merged
-/// * `mode` - Mode name (if applicable)
-/// * `scope` - Scope name (if applicable)
-/// * `project` - Project name (if applicable)
-/// * `local_oid` - OID of local commit
-/// * `remote_oid` - OID of remote commit
-///
-/// # Returns
-///
-/// `MergeOutcome` indicating clean merge or conflicts
+/// Each file present in the merge base, the local or the remote layer is
+/// merged on its own. A file changed on one side only takes that side's
+/// version (deleting it if that side deleted it); a file changed on both
+/// sides goes through its merge driver, or the text merge.
 ///
 /// # Errors
 ///
 /// Returns `JinError::Git` if Git operations fail
-/// Returns `JinError::Merge` if merge operations fail
 fn perform_three_way_merge(
+    root: &Path,
     jin_repo: &JinRepo,
-    layer: Layer,
-    mode: Option<&str>,
-    scope: Option<&str>,
-    project: Option<&str>,
+    ref_path: &str,
     local_oid: Oid,
     remote_oid: Oid,
 ) -> Result<MergeOutcome> {
-    // Step 1: Find merge base
+    // Step 1: Find merge base and the three trees
     let base_oid = find_merge_base(jin_repo, local_oid, remote_oid)?;
-
-    // Step 2: Get commit objects for all three
-    let base_commit = jin_repo.inner().find_commit(base_oid)?;
-    let local_commit = jin_repo.inner().find_commit(local_oid)?;
-    let remote_commit = jin_repo.inner().find_commit(remote_oid)?;
-
-    // Step 3: Collect all unique files from all three trees
-    let mut all_files = HashSet::new();
-    for tree_oid in [
-        base_commit.tree_id(),
-        local_commit.tree_id(),
-        remote_commit.tree_id(),
-    ] {
-        for file in jin_repo.list_tree_files(tree_oid)? {
-            all_files.insert(PathBuf::from(file));
-        }
+    let base_tree = jin_repo.inner().find_commit(base_oid)?.tree_id();
+    let local_tree = jin_repo.inner().find_commit(local_oid)?.tree_id();
+    let remote_tree = jin_repo.inner().find_commit(remote_oid)?.tree_id();
+
+    // Step 2: Collect all unique files from all three trees
+    let mut all_files = BTreeSet::new();
+    for tree_oid in [base_tree, local_tree, remote_tree] {
+        all_files.extend(jin_repo.list_tree_files(tree_oid)?);
     }
 
-    // Step 4: Merge each file
-    let mut merged_files = Vec::new(); // (path, blob_oid) for tree building
-    let mut conflict_files = Vec::new(); // Paths with conflicts
-
-    for file_path in all_files {
-        // Extract contents from base, local, remote
-        let base_content = extract_file_content(jin_repo, base_commit.tree_id(), &file_path)?;
-        let local_content = extract_file_content(jin_repo, local_commit.tree_id(), &file_path)?;
-        let remote_content = extract_file_content(jin_repo, remote_commit.tree_id(), &file_path)?;
-
-        // Perform 3-way merge using existing text_merge()
-        match text_merge(&base_content, &local_content, &remote_content)? {
-            TextMergeResult::Clean(merged) => {
-                // Create blob with merged content
-                let blob_oid = jin_repo.create_blob(merged.as_bytes())?;
-                merged_files.push((file_path.display().to_string(), blob_oid));
-            }
-            TextMergeResult::Conflict { .. } => {
-                // Create .jinmerge file for this conflict
-                let local_ref = layer.ref_path(mode, scope, project);
-                let remote_ref = format!(
-                    "origin/{}",
-                    local_ref.trim_start_matches("refs/jin/layers/")
-                );
-
-                let merge_conflict = JinMergeConflict::from_text_merge(
-                    file_path.clone(),
-                    local_ref,
-                    local_content.clone(),
-                    remote_ref,
-                    remote_content,
-                );
-
-                // Write .jinmerge file to workspace
-                let merge_path = JinMergeConflict::merge_path_for_file(&file_path);
-                merge_conflict.write_to_file(&merge_path)?;
-
-                // For now, use local version in the merge
-                // TODO: Could ask user to choose, or mark as conflicted
-                let blob_oid = jin_repo.create_blob(local_content.as_bytes())?;
-                merged_files.push((file_path.display().to_string(), blob_oid));
-                conflict_files.push(file_path);
+    // Step 3: Merge each file
+    let name = layer_name(ref_path);
+    let mut files = Vec::new();
+    let mut conflicts = Vec::new();
+    for file in all_files {
+        let file_path = PathBuf::from(&file);
+        let base = extract_file_content(jin_repo, base_tree, &file_path);
+        let local = extract_file_content(jin_repo, local_tree, &file_path);
+        let remote = extract_file_content(jin_repo, remote_tree, &file_path);
+
+        let merged = if local == remote || remote == base {
+            local
+        } else if local == base {
+            remote
+        } else {
+            let (base, local, remote) = (
+                base.unwrap_or_default(),
+                local.unwrap_or_default(),
+                remote.unwrap_or_default(),
+            );
+            let outcome = match MergeDrivers::current().find(&file_path) {
+                Some(driver) => driver.merge(root, &file_path, &base, &local, &remote)?,
+                None => text_merge(&base, &local, &remote)?,
+            };
+            match outcome {
+                TextMergeResult::Clean(merged) => Some(merged),
+                TextMergeResult::Conflict { .. } => {
+                    conflicts.push(JinMergeConflict::from_text_merge(
+                        file_path,
+                        name.to_string(),
+                        local,
+                        format!("origin/{}", name),
+                        remote,
+                    ));
+                    continue;
+                }
             }
+        };
+
+        if let Some(content) = merged {
+            files.push((file, jin_repo.create_blob(content.as_bytes())?));
         }
     }
 
-    // Step 5: Create merge tree
-    let merge_tree_oid = jin_repo.create_tree_from_paths(&merged_files)?;
-
-    // Step 6: Create merge commit with two parents
-    let _sig = jin_repo.inner().signature()?;
-    let message = format!(
-        "Merge remote changes into {}",
-        layer.ref_path(mode, scope, project)
-    );
-
-    // CRITICAL: Parent order matters! Local first, then remote
-    let parents: Vec<Oid> = vec![local_oid, remote_oid];
-    let merge_commit_oid = jin_repo.create_commit(None, &message, merge_tree_oid, &parents)?;
-
-    // Step 7: Return outcome
-    if conflict_files.is_empty() {
-        Ok(MergeOutcome::Clean(merge_commit_oid))
+    if conflicts.is_empty() {
+        Ok(MergeOutcome::Clean(files))
     } else {
-        Ok(MergeOutcome::Conflicts {
-            merged_oid: merge_commit_oid,
-            conflict_files,
-        })
+        Ok(MergeOutcome::Conflicts { files, conflicts })
     }
 }
 
-/// Extract file content from a tree, returning empty string if file not found
-///
-/// This helper function safely extracts file content from a tree. If the file
-/// doesn't exist in the tree (e.g., it was added in one branch but not the other),
-/// it returns an empty string, which is the correct behavior for 3-way merge.
-///
-/// # Arguments
-///
-/// * `repo` - The Jin repository
-/// * `tree_oid` - OID of the tree to read from
-/// * `path` - Path to the file within the tree
-///
-/// # Returns
-///
-/// File content as a string, or empty string if file doesn't exist
-fn extract_file_content(repo: &JinRepo, tree_oid: Oid, path: &Path) -> Result<String> {
-    match repo.read_file_from_tree(tree_oid, path) {
-        Ok(content) => Ok(String::from_utf8_lossy(&content).to_string()),
-        Err(_) => Ok(String::new()), // File doesn't exist in this tree
-    }
+/// Create the merge commit of a layer, local commit first
+fn commit_merge(
+    jin_repo: &JinRepo,
+    ref_path: &str,
+    local_oid: Oid,
+    remote_oid: Oid,
+    files: &[(String, Oid)],
+) -> Result<Oid> {
+    let tree_oid = jin_repo.create_tree_from_paths(files)?;
+    let message = format!("Merge remote changes into {}", ref_path);
+    jin_repo.create_commit(None, &message, tree_oid, &[local_oid, remote_oid])
 }
 
-/// Format ref path for display
-fn format_ref_path(ref_path: &str) -> String {
-    ref_path
-        .strip_prefix("refs/jin/layers/")
-        .unwrap_or(ref_path)
-        .to_string()
+/// Content of a file in a tree, `None` if the tree doesn't have it
+fn extract_file_content(repo: &JinRepo, tree_oid: Oid, path: &Path) -> Option<String> {
+    repo.read_file_from_tree(tree_oid, path)
+        .ok()
+        .map(|content| String::from_utf8_lossy(&content).to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    #[test]
-    fn test_parse_ref_path_global() {
-        let (layer, mode, scope, project) = parse_ref_path("refs/jin/layers/global").unwrap();
-        assert_eq!(layer, Layer::GlobalBase);
-        assert!(mode.is_none());
-        assert!(scope.is_none());
-        assert!(project.is_none());
+    use serial_test::serial;
+    use tempfile::TempDir;
+
+    fn commit(repo: &JinRepo, files: &[(&str, &str)], parents: &[Oid]) -> Oid {
+        let entries: Vec<_> = files
+            .iter()
+            .map(|(path, content)| {
+                (
+                    path.to_string(),
+                    repo.create_blob(content.as_bytes()).unwrap(),
+                )
+            })
+            .collect();
+        let tree = repo.create_tree_from_paths(&entries).unwrap();
+        repo.create_commit(None, "test", tree, parents).unwrap()
     }
 
     #[test]
-    fn test_parse_ref_path_mode() {
-        let (layer, mode, scope, project) = parse_ref_path("refs/jin/layers/mode/claude").unwrap();
-        assert_eq!(layer, Layer::ModeBase);
-        assert_eq!(mode, Some("claude".to_string()));
-        assert!(scope.is_none());
-        assert!(project.is_none());
-    }
-
-    #[test]
-    fn test_parse_ref_path_mode_scope() {
-        let (layer, mode, scope, project) =
-            parse_ref_path("refs/jin/layers/mode/claude/scope/language:rust").unwrap();
-        assert_eq!(layer, Layer::ModeScope);
-        assert_eq!(mode, Some("claude".to_string()));
-        assert_eq!(scope, Some("language:rust".to_string()));
-        assert!(project.is_none());
+    fn test_three_way_merge_takes_one_sided_changes() {
+        let temp = TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join("repo")).unwrap();
+        let base = commit(&repo, &[("a.txt", "a\n"), ("b.txt", "b\n")], &[]);
+        let local = commit(&repo, &[("a.txt", "a2\n"), ("b.txt", "b\n")], &[base]);
+        let remote = commit(&repo, &[("a.txt", "a\n")], &[base]);
+
+        let MergeOutcome::Clean(files) = perform_three_way_merge(
+            Path::new("."),
+            &repo,
+            "refs/jin/layers/global",
+            local,
+            remote,
+        )
+        .unwrap() else {
+            panic!("expected a clean merge");
+        };
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].0, "a.txt");
+        assert_eq!(repo.read_blob_content(files[0].1).unwrap(), b"a2\n");
     }
 
     #[test]
-    fn test_parse_ref_path_mode_project() {
-        let (layer, mode, scope, project) =
-            parse_ref_path("refs/jin/layers/mode/claude/project/dashboard").unwrap();
-        assert_eq!(layer, Layer::ModeProject);
-        assert_eq!(mode, Some("claude".to_string()));
-        assert!(scope.is_none());
-        assert_eq!(project, Some("dashboard".to_string()));
-    }
+    fn test_three_way_merge_reports_conflicts() {
+        let temp = TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join("repo")).unwrap();
+        let base = commit(&repo, &[("config.txt", "version=1\n"), ("x", "x\n")], &[]);
+        let local = commit(
+            &repo,
+            &[("config.txt", "version=2\n"), ("x", "x\n")],
+            &[base],
+        );
+        let remote = commit(
+            &repo,
+            &[("config.txt", "version=3\n"), ("x", "x\n")],
+            &[base],
+        );
 
-    #[test]
-    fn test_parse_ref_path_invalid() {
-        let result = parse_ref_path("invalid/path");
-        assert!(result.is_err());
+        let MergeOutcome::Conflicts { files, conflicts } = perform_three_way_merge(
+            Path::new("."),
+            &repo,
+            "refs/jin/layers/global",
+            local,
+            remote,
+        )
+        .unwrap() else {
+            panic!("expected conflicts");
+        };
+        assert_eq!(files.len(), 1);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].file_path, PathBuf::from("config.txt"));
+        assert_eq!(conflicts[0].conflicts[0].layer2_ref, "origin/global");
     }
 
     #[test]
-    fn test_format_ref_path() {
+    #[serial]
+    fn test_paused_state_commits_resolved_layers() {
+        let ctx = crate::test_utils::setup_unit_test();
+        let repo = JinRepo::open(&ctx.project_path).unwrap();
+        let base = commit(&repo, &[("config.txt", "version=1\n")], &[]);
+        let local = commit(&repo, &[("config.txt", "version=2\n")], &[base]);
+        let remote = commit(&repo, &[("config.txt", "version=3\n")], &[base]);
+        repo.set_ref("refs/jin/layers/global", local, "test")
+            .unwrap();
+
+        let mut state = PausedPullState {
+            timestamp: Utc::now(),
+            layers: vec![PausedLayerMerge {
+                ref_path: "refs/jin/layers/global".to_string(),
+                local: local.to_string(),
+                remote: remote.to_string(),
+                files: BTreeMap::new(),
+                conflicts: vec![PathBuf::from("config.txt")],
+            }],
+        };
+        assert!(state
+            .resolve_file(&repo, Path::new("other.txt"), b"")
+            .is_err());
+        assert!(state
+            .commit_resolved(&ctx.project_path, &repo)
+            .unwrap()
+            .is_empty());
+
+        state
+            .resolve_file(&repo, Path::new("config.txt"), b"version=4\n")
+            .unwrap();
         assert_eq!(
-            format_ref_path("refs/jin/layers/mode/claude"),
-            "mode/claude"
+            state.commit_resolved(&ctx.project_path, &repo).unwrap(),
+            vec!["global"]
         );
-        assert_eq!(format_ref_path("refs/jin/layers/global"), "global");
+        assert!(state.layers.is_empty());
+
+        let head = repo.resolve_ref("refs/jin/layers/global").unwrap();
+        let merge = repo.inner().find_commit(head).unwrap();
+        assert_eq!(merge.parent_ids().collect::<Vec<_>>(), vec![local, remote]);
+        let content = repo
+            .read_file_from_tree(merge.tree_id(), Path::new("config.txt"))
+            .unwrap();
+        assert_eq!(content, b"version=4\n");
     }
 }
@@ -1,268 +1,449 @@
 //! Implementation of `jin push`
 //!
-//! Uploads modified local layer refs to remote repository.
-//! Never pushes user-local layer (machine-specific).
+//! Uploads local layer refs to the linked remote (or the one named by
+//! `--remote`), negotiating each layer on its own:
+//!
+//! 1. The remote's layer refs are listed (like `git ls-remote`). Nothing is
+//!    fetched, so local layers are never touched.
+//! 2. Each selected local layer is compared with its remote ref: new layers
+//!    and fast-forwards are pushed, equal ones skipped, and layers that are
+//!    behind or have diverged are rejected unless `--force` is given.
+//! 3. Every pushed ref carries a lease: the commit the remote had in step 1.
+//!    The remote reports its refs again when the push starts, and if one
+//!    moved meanwhile (a teammate pushed), nothing is written, forced or not.
+//!
+//! Without layer arguments, the remote's `push_layers` (`jin link
+//! --push-layer`) are pushed, or every layer if it has none. Never pushes
+//! the user-local or workspace layers (machine-specific).
+//! Pushed commits touching paths a layer's `.jinowners` assigns get a
+//! required-reviewers note (see [`crate::git::reviewers`]).
 
+use super::remote::{connect, list_remote_layers};
 use crate::cli::PushArgs;
+use crate::core::progress::{self, Event, Operation, Phase};
 use crate::core::{JinConfig, JinError, Result};
-use crate::git::remote::build_push_options;
-use crate::git::{JinRepo, RefOps};
-use git2::ErrorCode;
+use crate::git::auth::RemoteAuth;
+use crate::git::refs::{compare_refs, RefComparison};
+use crate::git::remote::{build_lease_push_options, remote_error, Leases, DEFAULT_REMOTE};
+use crate::git::retry::with_retry;
+use crate::git::{locks, reviewers, JinRepo, RefOps};
+use crate::staging::LayerOwners;
+use git2::{ErrorCode, Oid};
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// How a local layer compares with its remote ref
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Negotiation {
+    /// Not on the remote yet
+    Create,
+    /// The remote commit is an ancestor of the local one
+    FastForward,
+    /// Both point at the same commit
+    UpToDate,
+    /// The remote has commits the local layer lacks
+    Behind,
+    /// Each side has commits the other lacks
+    Diverged,
+}
+
+impl Negotiation {
+    fn describe(self) -> &'static str {
+        match self {
+            Negotiation::Create => "new",
+            Negotiation::FastForward => "fast-forward",
+            Negotiation::UpToDate => "up to date",
+            Negotiation::Behind => "behind remote",
+            Negotiation::Diverged => "diverged from remote",
+        }
+    }
+}
+
+/// One local layer and where the remote has it
+#[derive(Debug, Clone)]
+struct LayerPush {
+    ref_name: String,
+    local: Oid,
+    remote: Option<Oid>,
+    negotiation: Negotiation,
+}
 
 /// Execute the push command
 ///
-/// Uploads modified layers to remote repository.
-/// Validates remote configuration exists and filters out user-local layer.
-pub fn execute(args: PushArgs) -> Result<()> {
+/// Pushes every local layer, or the ones named, after comparing each with
+/// the remote (see the module docs).
+pub fn execute(args: PushArgs, root: &Path) -> Result<()> {
     // 1. Validate remote configuration
     let config = JinConfig::load()?;
-    let remote_config = config.remote.ok_or(JinError::Config(
-        "No remote configured. Run 'jin link <url>'.".into(),
-    ))?;
-
-    // 2. Open repository
-    let jin_repo = JinRepo::open_or_create()?;
-    let repo = jin_repo.inner();
-
-    // 3. Capture pre-fetch local refs (fetch will overwrite them)
-    let pre_fetch_refs = capture_local_refs(&jin_repo)?;
-
-    // 4. Capture what refs exist on remote BEFORE fetch (to detect new refs)
-    let pre_fetch_remote_refs = capture_remote_refs(&jin_repo)?;
-
-    // 5. Fetch remote state
-    super::fetch::execute()?;
-
-    // 6. Find the remote
-    let mut remote = repo.find_remote("origin").map_err(|e| {
-        if e.code() == ErrorCode::NotFound {
-            JinError::Config(
-                "Remote 'origin' not found in repository. Run 'jin link <url>'.".into(),
-            )
+    let remote_name = args.remote.as_deref().unwrap_or(DEFAULT_REMOTE);
+    let remote_config = config.named_remote(remote_name).ok_or_else(|| {
+        JinError::Config(if remote_name == DEFAULT_REMOTE {
+            "No remote configured. Run 'jin link <url>'.".to_string()
         } else {
-            e.into()
-        }
+            format!(
+                "No remote named '{}'. Run 'jin link list' to see linked remotes.",
+                remote_name
+            )
+        })
     })?;
 
-    // 7. Detect modified layers (exclude user-local)
-    let modified_refs =
-        detect_modified_layers(&jin_repo, &pre_fetch_refs, &pre_fetch_remote_refs, &args)?;
+    // 2. Open repository and pick the layers to push
+    let jin_repo = JinRepo::open_or_create(root)?;
+    let selection = if args.layers.is_empty() {
+        &remote_config.push_layers
+    } else {
+        &args.layers
+    };
+    let local = local_layers(&jin_repo, selection)?;
 
-    if modified_refs.is_empty() {
+    // 3. See where the remote has each layer
+    let mut listing = connect(&jin_repo, remote_name)?;
+    let remote_layers: HashMap<String, Oid> = list_remote_layers(&mut listing)?
+        .into_iter()
+        .map(|layer| (layer.ref_name, layer.oid))
+        .collect();
+    listing.disconnect()?;
+
+    // 4. Negotiate each layer
+    let mut layers = Vec::new();
+    for (ref_name, local_oid) in local {
+        let remote_oid = remote_layers.get(&ref_name).copied();
+        layers.push(LayerPush {
+            negotiation: negotiate(&jin_repo, local_oid, remote_oid)?,
+            ref_name,
+            local: local_oid,
+            remote: remote_oid,
+        });
+    }
+    let rejected: Vec<&LayerPush> = layers
+        .iter()
+        .filter(|l| matches!(l.negotiation, Negotiation::Behind | Negotiation::Diverged))
+        .collect();
+    if !args.force {
+        if let Some(layer) = rejected.first() {
+            for layer in &rejected {
+                let rejection = format!(
+                    "{} ({})",
+                    display_name(&layer.ref_name),
+                    layer.negotiation.describe()
+                );
+                if progress::is_json() {
+                    progress::error(rejection);
+                } else {
+                    eprintln!("  ✗ {}", rejection);
+                }
+            }
+            return Err(JinError::BehindRemote {
+                layer: display_name(&layer.ref_name).to_string(),
+            });
+        }
+    }
+    let pushes: Vec<&LayerPush> = layers
+        .iter()
+        .filter(|l| l.negotiation != Negotiation::UpToDate)
+        .collect();
+    if pushes.is_empty() {
         println!("Nothing to push");
         return Ok(());
     }
 
-    // 8. Build refspecs for push
-    let refspecs: Vec<String> = modified_refs
+    // 5. Find who has to review the pushed changes
+    let reviews = required_reviews(&jin_repo, &pushes)?;
+
+    // 6. Refspecs, forced where the remote would refuse, each with its lease
+    let refspecs: Vec<String> = pushes
         .iter()
-        .map(|ref_name| {
-            if args.force {
-                format!("+{}:{}", ref_name, ref_name) // Force push
-            } else {
-                format!("{}:{}", ref_name, ref_name) // Normal push
+        .map(|layer| match layer.negotiation {
+            Negotiation::Behind | Negotiation::Diverged => {
+                format!("+{}:{}", layer.ref_name, layer.ref_name)
             }
+            _ => format!("{}:{}", layer.ref_name, layer.ref_name),
         })
         .collect();
-
-    // 9. Warn on force push
-    if args.force {
+    let leases: Leases = pushes
+        .iter()
+        .map(|layer| {
+            (
+                layer.ref_name.clone(),
+                layer.remote.unwrap_or_else(Oid::zero),
+            )
+        })
+        .collect();
+    if !rejected.is_empty() {
         println!("WARNING: Force push will overwrite remote changes!");
         println!("This may cause data loss for other team members.");
     }
 
-    // 10. Setup push options
-    let mut push_opts = build_push_options()?;
-
-    // 11. Perform push
-    println!("Pushing to origin ({})...", remote_config.url);
+    // 7. Perform push
+    let mut remote = jin_repo.inner().find_remote(remote_name).map_err(|e| {
+        if e.code() == ErrorCode::NotFound {
+            JinError::Config(format!(
+                "Remote '{}' not found in repository. Run 'jin link <url>'.",
+                remote_name
+            ))
+        } else {
+            e.into()
+        }
+    })?;
+    println!("Pushing to {} ({})...", remote_name, remote_config.url);
+    for layer in &pushes {
+        println!(
+            "  {} ({})",
+            display_name(&layer.ref_name),
+            layer.negotiation.describe()
+        );
+    }
 
+    let broken = Arc::new(Mutex::new(None));
+    let auth = RemoteAuth::of(remote_config);
+    let mut push_opts = build_lease_push_options(leases, broken.clone(), &auth);
     let refspec_refs: Vec<&str> = refspecs.iter().map(|s| s.as_str()).collect();
-
-    match remote.push(&refspec_refs, Some(&mut push_opts)) {
+    let result = with_retry(&format!("Push to {}", remote_name), || {
+        remote
+            .push(&refspec_refs, Some(&mut push_opts))
+            .map_err(remote_error)
+    });
+    match result {
         Ok(()) => {
-            println!("\nSuccessfully pushed {} layer(s)", modified_refs.len());
+            Event::new(Operation::Push, Phase::Done)
+                .remote(remote_name)
+                .emit();
+            println!("\nSuccessfully pushed {} layer(s)", pushes.len());
+            publish_reviews(&jin_repo, &reviews);
             Ok(())
         }
         Err(e) => {
-            println!(); // New line after push attempt
-            match e.code() {
-                ErrorCode::Auth => Err(JinError::Config(
-                    "Authentication failed. Check your SSH keys or credentials.\n\
-                    Try: ssh -T git@github.com (for GitHub)"
-                        .into(),
-                )),
-                _ if e.message().contains("non-fast-forward") => Err(JinError::Config(
-                    "Push rejected: non-fast-forward update.\n\
+            // New line after push attempt
+            println!();
+
+            // A lease broke: a teammate pushed since the comparison
+            let broken = broken.lock().unwrap_or_else(|e| e.into_inner()).take();
+            if let Some(ref_name) = broken {
+                return Err(JinError::BehindRemote {
+                    layer: display_name(&ref_name).to_string(),
+                });
+            }
+            match e {
+                JinError::Git(e) if e.message().contains("non-fast-forward") => {
+                    Err(JinError::Config(
+                        "Push rejected: non-fast-forward update.\n\
                     The remote contains commits you don't have locally.\n\
                     Run 'jin pull' to merge remote changes, or use '--force' to overwrite.\n\
                     WARNING: --force may cause data loss!"
-                        .into(),
-                )),
-                _ => Err(e.into()),
+                            .into(),
+                    ))
+                }
+                e => Err(e),
             }
         }
     }
 }
 
-/// Capture local refs before fetch (fetch will overwrite them with remote refs)
+/// Local layer refs to push, sorted by name: all of them, or those named
+/// in `selection` (`mode/claude` or a full ref name)
 ///
-/// We need to store the pre-fetch local OIDs so we can compare them against
-/// the post-fetch state (which contains remote OIDs) to detect if local is behind.
-fn capture_local_refs(jin_repo: &JinRepo) -> Result<HashMap<String, git2::Oid>> {
-    let mut local_refs = HashMap::new();
-    let all_refs = jin_repo.list_refs("refs/jin/layers/*")?;
-
-    for ref_name in all_refs {
-        // Skip user-local layer
-        if ref_name.contains("/local") {
+/// User-local and workspace layers are machine-specific and never pushed.
+fn local_layers(jin_repo: &JinRepo, selection: &[String]) -> Result<Vec<(String, Oid)>> {
+    let mut layers = Vec::new();
+    for ref_name in jin_repo.list_refs("refs/jin/layers/*")? {
+        if matches!(display_name(&ref_name), "local" | "workspace") {
             continue;
         }
-
-        // Store the OID of each local ref
         if let Ok(oid) = jin_repo.resolve_ref(&ref_name) {
-            local_refs.insert(ref_name, oid);
+            layers.push((ref_name, oid));
         }
     }
+    layers.sort();
+    if selection.is_empty() {
+        return Ok(layers);
+    }
 
-    Ok(local_refs)
+    let mut selected = Vec::new();
+    for name in selection {
+        let name = name.trim_end_matches('/');
+        let layer = layers
+            .iter()
+            .find(|(ref_name, _)| display_name(ref_name) == name || ref_name == name)
+            .ok_or_else(|| {
+                JinError::NotFound(format!(
+                    "Layer '{}' has no local commits. Run 'jin layers' to see the layers",
+                    name
+                ))
+            })?;
+        if !selected.contains(layer) {
+            selected.push(layer.clone());
+        }
+    }
+    Ok(selected)
 }
 
-/// Capture remote refs before fetch (to detect new local refs)
-///
-/// Since our refspec fetches directly into local refs (+refs/jin/layers/*:refs/jin/layers/*),
-/// we cannot rely on remote-tracking refs. Instead, we open the remote repository
-/// directly (via its URL or filesystem path) and check which refs exist there.
-fn capture_remote_refs(jin_repo: &JinRepo) -> Result<std::collections::HashSet<String>> {
-    let mut remote_refs = std::collections::HashSet::new();
-
-    // Get the remote URL from the git config
-    let repo = jin_repo.inner();
-    let remote = match repo.find_remote("origin") {
-        Ok(r) => r,
-        Err(_) => return Ok(remote_refs), // No remote configured
-    };
+/// `mode/claude` for `refs/jin/layers/mode/claude/_`
+fn display_name(ref_name: &str) -> &str {
+    let path = ref_name
+        .strip_prefix("refs/jin/layers/")
+        .unwrap_or(ref_name);
+    path.strip_suffix("/_").unwrap_or(path)
+}
 
-    let remote_url = match remote.url() {
-        Some(url) => url,
-        None => return Ok(remote_refs),
+/// Compare a local layer with the remote's copy
+///
+/// A remote commit missing from the local repository can't be an ancestor
+/// of the local layer, so the layer is behind (or diverged) and needs a pull.
+fn negotiate(jin_repo: &JinRepo, local: Oid, remote: Option<Oid>) -> Result<Negotiation> {
+    let Some(remote) = remote else {
+        return Ok(Negotiation::Create);
     };
+    if jin_repo.inner().find_commit(remote).is_err() {
+        return Ok(Negotiation::Diverged);
+    }
+    Ok(match compare_refs(jin_repo, local, remote)? {
+        RefComparison::Ahead => Negotiation::FastForward,
+        RefComparison::Equal => Negotiation::UpToDate,
+        RefComparison::Behind => Negotiation::Behind,
+        RefComparison::Diverged => Negotiation::Diverged,
+    })
+}
 
-    // For file:// URLs, we can open the bare repo directly
-    if remote_url.starts_with("file://") || remote_url.starts_with('/') {
-        // Strip the file:// prefix if present
-        let remote_path = if remote_url.starts_with("file://") {
-            remote_url.trim_start_matches("file://")
-        } else {
-            remote_url
-        };
+/// Owners of the changes in each pushed commit, per the `.jinowners` of its
+/// layer before the change
+///
+/// Commits already on the remote are skipped, as are commits that touch no
+/// owned path.
+fn required_reviews(jin_repo: &JinRepo, layers: &[&LayerPush]) -> Result<Vec<(Oid, Vec<String>)>> {
+    let repo = jin_repo.inner();
+    let mut reviews = Vec::new();
+
+    for layer in layers {
+        let mut walk = repo.revwalk()?;
+        walk.push(layer.local)?;
+        // Commits the remote has (and we know) were reviewed when pushed
+        if let Some(remote_oid) = layer.remote.filter(|oid| repo.find_commit(*oid).is_ok()) {
+            walk.hide(remote_oid)?;
+        }
 
-        // Try to open the remote repository
-        if let Ok(remote_repo) = git2::Repository::open(remote_path) {
-            // List all refs in the remote repository
-            let all_refs = match remote_repo.references_glob("refs/jin/layers/*") {
-                Ok(refs) => refs,
-                Err(_) => return Ok(remote_refs),
+        for oid in walk {
+            let commit = repo.find_commit(oid?)?;
+            let tree = commit.tree()?;
+            let parent_tree = match commit.parent(0) {
+                Ok(parent) => Some(parent.tree()?),
+                Err(_) => None,
             };
-
-            for reference in all_refs.flatten() {
-                if let Some(name) = reference.name() {
-                    // Skip user-local layer
-                    if !name.contains("/local") {
-                        remote_refs.insert(name.to_string());
-                    }
-                }
+            let owners =
+                LayerOwners::from_tree(jin_repo, parent_tree.as_ref().unwrap_or(&tree).id())?;
+            if owners.is_empty() {
+                continue;
+            }
+            let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+            let paths: Vec<&Path> = diff
+                .deltas()
+                .filter_map(|delta| delta.new_file().path().or(delta.old_file().path()))
+                .collect();
+            let reviewers = owners.reviewers(paths);
+            if !reviewers.is_empty() {
+                reviews.push((commit.id(), reviewers));
             }
         }
     }
 
-    // For non-file URLs (network remotes), we return empty set
-    // In this case, we'll rely on fetch behavior to determine if ref exists
-
-    Ok(remote_refs)
+    Ok(reviews)
 }
 
-/// Detect modified layers that need to be pushed
-///
-/// Compares pre-fetch local refs with remote refs and post-fetch local refs to determine:
-/// - New local refs (not on remote) -> push
-/// - Local refs ahead of remote -> push
-/// - Local refs behind remote -> reject (unless --force)
-/// - Local refs diverged from remote -> reject (unless --force)
-/// - Local refs equal to remote -> skip
+/// Report required reviewers and record them as notes on the team remote
 ///
-/// NOTE: Since our refspec fetches directly into local refs (+refs/jin/layers/*:refs/jin/layers/*),
-/// after fetch the local ref contains the remote OID. We use pre_fetch_remote_refs (captured
-/// by directly opening the remote repo) to determine if a ref exists on remote.
-fn detect_modified_layers(
-    jin_repo: &JinRepo,
-    pre_fetch_local_refs: &HashMap<String, git2::Oid>,
-    pre_fetch_remote_refs: &std::collections::HashSet<String>,
-    args: &PushArgs,
-) -> Result<Vec<String>> {
-    let mut modified = Vec::new();
-
-    for (ref_name, pre_fetch_local_oid) in pre_fetch_local_refs {
-        // Check if this ref exists on remote
-        let remote_has_ref = pre_fetch_remote_refs.contains(ref_name);
-
-        if !remote_has_ref {
-            // Ref doesn't exist on remote - it's new, push it
-            modified.push(ref_name.clone());
-            continue;
-        }
+/// The layers are already pushed, so failures only warn.
+fn publish_reviews(jin_repo: &JinRepo, reviews: &[(Oid, Vec<String>)]) {
+    if reviews.is_empty() {
+        return;
+    }
+    println!("Required reviewers:");
+    for (oid, reviewers) in reviews {
+        let summary = jin_repo
+            .inner()
+            .find_commit(*oid)
+            .ok()
+            .and_then(|c| c.summary().map(str::to_string))
+            .unwrap_or_default();
+        println!(
+            "  {} {}: {}",
+            &oid.to_string()[..7],
+            summary,
+            reviewers.join(" ")
+        );
+    }
 
-        // Ref exists on both local and remote - compare OIDs
-        // Note: after fetch, the local ref now points to the remote OID
-        let remote_oid = match jin_repo.resolve_ref(ref_name) {
-            Ok(oid) => oid,
-            Err(_) => {
-                // Ref was deleted by fetch - shouldn't happen but handle gracefully
-                continue;
-            }
+    let result = (|| -> Result<()> {
+        let Some(mut remote) = locks::team_remote(jin_repo)? else {
+            return Ok(());
         };
-
-        // Compare pre-fetch local OID with remote OID
-        if args.force {
-            // Force flag bypasses safety checks - push if different
-            if pre_fetch_local_oid != &remote_oid {
-                modified.push(ref_name.clone());
-            }
-        } else {
-            // Use graph comparison to determine if push is safe
-            match crate::git::refs::compare_refs(jin_repo, *pre_fetch_local_oid, remote_oid)? {
-                crate::git::refs::RefComparison::Ahead => {
-                    // Local is ahead - safe to push
-                    modified.push(ref_name.clone());
-                }
-                crate::git::refs::RefComparison::Equal => {
-                    // Refs are equal - nothing to push
-                }
-                crate::git::refs::RefComparison::Behind
-                | crate::git::refs::RefComparison::Diverged => {
-                    // REJECT: Local is behind or diverged from remote
-                    return Err(JinError::BehindRemote {
-                        layer: ref_name.clone(),
-                    });
-                }
+        reviewers::fetch(&mut remote)?;
+        for (oid, owners) in reviews {
+            if reviewers::recorded(jin_repo, *oid).as_ref() != Some(owners) {
+                reviewers::record(jin_repo, *oid, owners)?;
             }
         }
+        reviewers::push(&mut remote)
+    })();
+    if let Err(e) = result {
+        progress::warn(format_args!("Could not publish reviewer notes: {}", e));
     }
-
-    Ok(modified)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::ObjectOps;
 
     #[test]
     fn test_push_args_force() {
-        let args = PushArgs { force: true };
+        let args = PushArgs {
+            layers: Vec::new(),
+            force: true,
+            remote: None,
+        };
         assert!(args.force);
 
-        let args = PushArgs { force: false };
+        let args = PushArgs {
+            layers: vec!["mode/claude".to_string()],
+            force: false,
+            remote: None,
+        };
         assert!(!args.force);
     }
+
+    #[test]
+    fn test_display_name() {
+        assert_eq!(display_name("refs/jin/layers/mode/claude/_"), "mode/claude");
+        assert_eq!(display_name("refs/jin/layers/global"), "global");
+        assert_eq!(
+            display_name("refs/jin/layers/mode/claude/scope/api"),
+            "mode/claude/scope/api"
+        );
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join(".jin")).unwrap();
+        let tree = repo.create_tree_from_paths(&[]).unwrap();
+        let base = repo.create_commit(None, "base", tree, &[]).unwrap();
+        let ours = repo.create_commit(None, "ours", tree, &[base]).unwrap();
+        let theirs = repo.create_commit(None, "theirs", tree, &[base]).unwrap();
+
+        let cases = [
+            (ours, None, Negotiation::Create),
+            (ours, Some(base), Negotiation::FastForward),
+            (ours, Some(ours), Negotiation::UpToDate),
+            (base, Some(ours), Negotiation::Behind),
+            (ours, Some(theirs), Negotiation::Diverged),
+            (
+                ours,
+                Some(Oid::from_str(&"1".repeat(40)).unwrap()),
+                Negotiation::Diverged,
+            ),
+        ];
+        for (local, remote, expected) in cases {
+            assert_eq!(negotiate(&repo, local, remote).unwrap(), expected);
+        }
+    }
 }
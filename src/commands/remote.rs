@@ -0,0 +1,302 @@
+//! Implementation of `jin remote` subcommands
+//!
+//! Inspects the linked remote without touching local layers:
+//! - `jin remote ls` lists the remote's layer refs (like `git ls-remote`) and
+//!   compares them with the local layers
+//! - `jin remote show <layer> [path]` lists the files of a remote layer, or
+//!   prints one of them
+//!
+//! `show` needs the layer's objects, so it downloads them into the object
+//! database, but no ref (and no `FETCH_HEAD`) is updated: what a later
+//! `jin pull` would bring stays unmerged until then.
+
+use crate::cli::RemoteAction;
+use crate::core::{JinError, Result};
+use crate::git::auth::{setup_auth_callbacks, RemoteAuth};
+use crate::git::remote::{remote_error, setup_callbacks};
+use crate::git::retry::with_retry;
+use crate::git::{JinRepo, TreeOps};
+use git2::{Direction, ErrorCode, FetchOptions, Oid, Remote, RemoteCallbacks};
+use std::io::Write;
+use std::path::Path;
+
+/// Prefix of layer refs
+const LAYER_PREFIX: &str = "refs/jin/layers/";
+
+/// A layer ref advertised by the remote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteLayer {
+    /// Full ref name (e.g., `refs/jin/layers/mode/claude/_`)
+    pub ref_name: String,
+    /// Commit the remote ref points to
+    pub oid: Oid,
+}
+
+impl RemoteLayer {
+    /// Layer path relative to `refs/jin/layers/` (e.g., `mode/claude`)
+    pub fn display_name(&self) -> &str {
+        let path = self
+            .ref_name
+            .strip_prefix(LAYER_PREFIX)
+            .unwrap_or(&self.ref_name);
+        path.strip_suffix("/_").unwrap_or(path)
+    }
+}
+
+/// Execute a remote subcommand
+pub fn execute(action: RemoteAction, root: &Path) -> Result<()> {
+    match action {
+        RemoteAction::Ls { prefix } => ls(root, prefix.as_deref()),
+        RemoteAction::Show { layer, path } => show(root, &layer, path.as_deref()),
+    }
+}
+
+/// List the remote's layers and how they compare with local layers
+fn ls(root: &Path, prefix: Option<&str>) -> Result<()> {
+    let repo = JinRepo::open(root).map_err(|_| JinError::NotInitialized)?;
+    let mut remote = connect(&repo, "origin")?;
+    let layers = list_remote_layers(&mut remote)?;
+    remote.disconnect()?;
+
+    let layers: Vec<&RemoteLayer> = layers
+        .iter()
+        .filter(|l| prefix.is_none_or(|p| l.display_name().starts_with(p)))
+        .collect();
+
+    if layers.is_empty() {
+        println!("No layers found on remote");
+        return Ok(());
+    }
+
+    let width = layers
+        .iter()
+        .map(|l| l.display_name().len())
+        .max()
+        .unwrap_or(0);
+    for layer in layers {
+        println!(
+            "{}  {:<width$}  {}",
+            &layer.oid.to_string()[..8],
+            layer.display_name(),
+            local_status(&repo, layer),
+            width = width
+        );
+    }
+
+    Ok(())
+}
+
+/// List the files of a remote layer, or print one of them
+fn show(root: &Path, layer_name: &str, path: Option<&str>) -> Result<()> {
+    let repo = JinRepo::open(root).map_err(|_| JinError::NotInitialized)?;
+    let mut remote = connect(&repo, "origin")?;
+    let layers = list_remote_layers(&mut remote)?;
+    remote.disconnect()?;
+
+    let layer = find_layer(&layers, layer_name)?;
+    if repo.inner().find_commit(layer.oid).is_err() {
+        download_objects(&mut remote, &layer.ref_name)?;
+    }
+
+    let tree_oid = repo.inner().find_commit(layer.oid)?.tree_id();
+    match path {
+        Some(path) => {
+            let content = repo
+                .read_file_from_tree(tree_oid, Path::new(path))
+                .map_err(|_| {
+                    JinError::NotFound(format!(
+                        "'{}' in remote layer '{}'",
+                        path,
+                        layer.display_name()
+                    ))
+                })?;
+            std::io::stdout().write_all(&content)?;
+        }
+        None => {
+            for file in repo.list_tree_files(tree_oid)? {
+                println!("{}", file);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Connect to the remote named `name` (normally `origin`) for listing refs
+///
+/// An anonymous remote is used so the remote's configured refspec never
+/// updates local refs as a side effect.
+pub(crate) fn connect<'r>(repo: &'r JinRepo, name: &str) -> Result<Remote<'r>> {
+    let named = repo.inner().find_remote(name).map_err(|e| {
+        if e.code() == ErrorCode::NotFound {
+            if name == "origin" {
+                JinError::Config("No remote configured. Run 'jin link <url>'.".into())
+            } else {
+                JinError::Config(format!(
+                    "No remote named '{}'. Run 'jin link list' to see linked remotes.",
+                    name
+                ))
+            }
+        } else {
+            e.into()
+        }
+    })?;
+    let url = named
+        .url()
+        .map(str::to_string)
+        .ok_or_else(|| JinError::Config(format!("Remote '{}' has no URL", name)))?;
+    let mut remote = repo.inner().remote_anonymous(&url)?;
+    let auth = RemoteAuth::named(name);
+
+    with_retry(&format!("Connect to {}", name), || {
+        let mut callbacks = RemoteCallbacks::new();
+        setup_auth_callbacks(&mut callbacks, &auth);
+        remote
+            .connect_auth(Direction::Fetch, Some(callbacks), None)
+            .map(|_| ())
+            .map_err(remote_error)
+    })?;
+
+    Ok(remote)
+}
+
+/// Layer refs advertised by a connected remote, sorted by name
+///
+/// User-local and workspace layers are never synced and are skipped.
+pub fn list_remote_layers(remote: &mut Remote<'_>) -> Result<Vec<RemoteLayer>> {
+    let mut layers: Vec<RemoteLayer> = match local_repository(remote) {
+        Some(repo) => repo
+            .references_glob(&format!("{}*", LAYER_PREFIX))?
+            .flatten()
+            .filter_map(|r| {
+                let ref_name = r.name()?.to_string();
+                Some(RemoteLayer {
+                    oid: r.resolve().ok()?.target()?,
+                    ref_name,
+                })
+            })
+            .collect(),
+        None => remote
+            .list()?
+            .iter()
+            .filter(|head| head.name().starts_with(LAYER_PREFIX))
+            .map(|head| RemoteLayer {
+                ref_name: head.name().to_string(),
+                oid: head.oid(),
+            })
+            .collect(),
+    };
+    layers.retain(|layer| !matches!(layer.display_name(), "local" | "workspace"));
+    layers.sort_by(|a, b| a.ref_name.cmp(&b.ref_name));
+    Ok(layers)
+}
+
+/// The repository behind a local (`file://` or path) remote
+///
+/// Read directly: libgit2 advertises an empty local repository as a null
+/// list, which `Remote::list` can't turn into a slice.
+fn local_repository(remote: &Remote<'_>) -> Option<git2::Repository> {
+    let url = remote.url()?;
+    let path = url.strip_prefix("file://").unwrap_or(url);
+    if !path.starts_with('/') {
+        return None;
+    }
+    git2::Repository::open_bare(path)
+        .or_else(|_| git2::Repository::open(path))
+        .ok()
+}
+
+/// Find a remote layer by display name (`mode/claude`) or full ref name
+fn find_layer<'a>(layers: &'a [RemoteLayer], name: &str) -> Result<&'a RemoteLayer> {
+    let name = name.trim_end_matches('/');
+    layers
+        .iter()
+        .find(|l| l.display_name() == name || l.ref_name == name)
+        .ok_or_else(|| {
+            JinError::NotFound(format!(
+                "Layer '{}' on remote. Run 'jin remote ls' to see available layers",
+                name
+            ))
+        })
+}
+
+/// How a remote layer compares with the local one
+fn local_status(repo: &JinRepo, layer: &RemoteLayer) -> &'static str {
+    let git_repo = repo.inner();
+    let Ok(local_oid) = git_repo.refname_to_id(&layer.ref_name) else {
+        return "new";
+    };
+    if local_oid == layer.oid {
+        "up to date"
+    } else if git_repo.find_commit(layer.oid).is_err() {
+        // Unknown commit: the remote has changes we haven't fetched
+        "remote changes"
+    } else if git_repo
+        .graph_descendant_of(local_oid, layer.oid)
+        .unwrap_or(false)
+    {
+        "local ahead"
+    } else {
+        "diverged"
+    }
+}
+
+/// Download the objects of a single remote ref without updating any ref
+fn download_objects(remote: &mut Remote<'_>, ref_name: &str) -> Result<()> {
+    let mut callbacks = RemoteCallbacks::new();
+    setup_callbacks(&mut callbacks);
+    let mut opts = FetchOptions::new();
+    opts.remote_callbacks(callbacks);
+    opts.update_fetchhead(false);
+    opts.download_tags(git2::AutotagOption::None);
+
+    remote.fetch(&[ref_name], Some(&mut opts), None)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(ref_name: &str) -> RemoteLayer {
+        RemoteLayer {
+            ref_name: ref_name.to_string(),
+            oid: Oid::zero(),
+        }
+    }
+
+    #[test]
+    fn test_display_name() {
+        assert_eq!(
+            layer("refs/jin/layers/mode/claude/_").display_name(),
+            "mode/claude"
+        );
+        assert_eq!(layer("refs/jin/layers/global").display_name(), "global");
+        assert_eq!(
+            layer("refs/jin/layers/mode/claude/scope/lang/rust/_").display_name(),
+            "mode/claude/scope/lang/rust"
+        );
+    }
+
+    #[test]
+    fn test_find_layer() {
+        let layers = vec![
+            layer("refs/jin/layers/global"),
+            layer("refs/jin/layers/mode/claude/_"),
+        ];
+        assert_eq!(
+            find_layer(&layers, "mode/claude").unwrap().ref_name,
+            "refs/jin/layers/mode/claude/_"
+        );
+        assert_eq!(
+            find_layer(&layers, "refs/jin/layers/global")
+                .unwrap()
+                .display_name(),
+            "global"
+        );
+        assert!(matches!(
+            find_layer(&layers, "mode/cursor"),
+            Err(JinError::NotFound(_))
+        ));
+    }
+}
@@ -6,27 +6,85 @@
 //! 3. Staging index (.jin/staging/index.json is parseable)
 //! 4. .jinmap (.jin/.jinmap exists and is valid)
 //! 5. Workspace metadata (.jin/workspace/ tracking files)
+//! 6. Global configuration and project context
+//! 7. Interrupted transactions (finished or rolled back explicitly with
+//!    `--resume-txn <id>`)
+//! 8. The .gitignore managed block (every Jin-managed file is ignored)
+//! 9. Workspace attachment
+//!
+//! Repair only reports unless `--fix` is given. Each fix then runs on its
+//! own: the state files it rewrites are snapshotted and restored if it
+//! fails, and each successful fix is recorded in the project audit log.
 
+use crate::audit::{AuditEntry, AuditLogger};
 use crate::cli::RepairArgs;
-use crate::core::{JinConfig, JinError, ProjectContext, Result};
-use crate::git::{JinRepo, RefOps};
-use crate::staging::{validate_workspace_attached, StagingIndex, WorkspaceMetadata};
-use std::path::PathBuf;
+use crate::commit::CommitPipeline;
+use crate::core::{machine, HomeConfig, JinConfig, JinError, ProjectContext, Result};
+use crate::git::{IncompleteTransaction, JinRepo, RecoveryManager, RefOps, TransactionState};
+use crate::staging::{
+    ensure_in_managed_block, gitignore, managed_entries, validate_workspace_attached, StagingIndex,
+    WorkspaceMetadata,
+};
+use std::path::{Path, PathBuf};
+
+/// The check groups a run covers (`--refs`, `--staging`, ...)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Targets {
+    refs: bool,
+    staging: bool,
+    context: bool,
+    gitignore: bool,
+    workspace: bool,
+}
+
+impl Targets {
+    /// The targets named on the command line, or all of them when none are
+    fn from_args(args: &RepairArgs) -> Self {
+        let named = Self {
+            refs: args.refs,
+            staging: args.staging,
+            context: args.context,
+            gitignore: args.gitignore,
+            workspace: args.workspace,
+        };
+        if named.refs || named.staging || named.context || named.gitignore || named.workspace {
+            named
+        } else {
+            Self {
+                refs: true,
+                staging: true,
+                context: true,
+                gitignore: true,
+                workspace: true,
+            }
+        }
+    }
+}
+
+/// Whether this run changes anything
+fn fixing(args: &RepairArgs) -> bool {
+    args.fix && !args.dry_run
+}
 
 /// Execute the repair command
 ///
-/// Checks Jin repository integrity and repairs issues if not in dry-run mode.
+/// Checks Jin repository integrity and, with `--fix`, repairs what it finds.
 ///
 /// # Arguments
 ///
-/// * `args` - Command line arguments including dry_run flag
+/// * `args` - Command line arguments including the fix flag and targets
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - Fatal corruption is detected (manual recovery required)
 /// - Repair operations fail
-pub fn execute(args: RepairArgs) -> Result<()> {
+pub fn execute(args: RepairArgs, root: &Path) -> Result<()> {
+    // Finish or roll back one interrupted commit
+    if let Some(id) = &args.resume_txn {
+        return resume_transaction(root, id, args.rollback, args.dry_run);
+    }
+
     println!("Checking Jin repository integrity...");
     println!();
 
@@ -35,7 +93,7 @@ pub fn execute(args: RepairArgs) -> Result<()> {
 
     // Check workspace attachment if --check flag is set
     if args.check {
-        check_workspace_attachment(&args, &mut issues_found);
+        check_workspace_attachment(root, &args, &mut issues_found);
 
         // Display summary and return early
         println!();
@@ -50,59 +108,79 @@ pub fn execute(args: RepairArgs) -> Result<()> {
         return Ok(());
     }
 
-    // Check 1: Repository structure
-    let repo_result = check_repository_structure(&args, &mut issues_found, &mut issues_fixed);
+    let targets = Targets::from_args(&args);
+
+    if targets.refs {
+        // Check 1: Repository structure
+        let repo_result =
+            check_repository_structure(root, &args, &mut issues_found, &mut issues_fixed);
+
+        // If repository is fatally corrupted, we can't continue (except when
+        // only reporting)
+        if let Err(e) = &repo_result {
+            if matches!(e, JinError::Other(_))
+                && issues_found.len() > issues_fixed.len()
+                && fixing(&args)
+            {
+                println!();
+                println!("FATAL: Repository is severely corrupted.");
+                println!();
+                println!("Manual recovery steps:");
+                println!("  1. Backup current repository: cp -r ~/.jin ~/.jin.backup");
+                println!("  2. Reinitialize Jin: rm -rf ~/.jin && jin init");
+                println!("  3. Restore configurations from backup if possible");
+                return Err(JinError::Other(
+                    "Repository corruption requires manual recovery".to_string(),
+                ));
+            }
+        }
 
-    // If repository is fatally corrupted, we can't continue (except in dry-run)
-    if let Err(e) = &repo_result {
-        if matches!(e, JinError::Other(_))
-            && issues_found.len() > issues_fixed.len()
-            && !args.dry_run
-        {
-            println!();
-            println!("FATAL: Repository is severely corrupted.");
-            println!();
-            println!("Manual recovery steps:");
-            println!("  1. Backup current repository: cp -r ~/.jin ~/.jin.backup");
-            println!("  2. Reinitialize Jin: rm -rf ~/.jin && jin init");
-            println!("  3. Restore configurations from backup if possible");
-            return Err(JinError::Other(
-                "Repository corruption requires manual recovery".to_string(),
-            ));
+        // Check 2: Layer refs (only if repository is valid)
+        if let Ok(ref repo) = repo_result {
+            check_layer_refs(root, &args, repo, &mut issues_found, &mut issues_fixed);
         }
     }
 
-    // Continue with other checks if we have a repository
-    let repo = repo_result.ok();
-
-    // Check 2: Layer refs (only if repository is valid)
-    if let Some(ref repo) = repo {
-        check_layer_refs(&args, repo, &mut issues_found, &mut issues_fixed);
+    // Check 3: Staging index
+    if targets.staging {
+        check_staging_index(root, &args, &mut issues_found, &mut issues_fixed);
     }
 
-    // Check 3: Staging index
-    check_staging_index(&args, &mut issues_found, &mut issues_fixed);
+    if targets.context {
+        // Check 4: .jinmap
+        check_jinmap(root, &args, &mut issues_found, &mut issues_fixed);
 
-    // Check 4: .jinmap
-    check_jinmap(&args, &mut issues_found, &mut issues_fixed);
+        // Check 6: Global configuration
+        check_global_config(root, &args, &mut issues_found, &mut issues_fixed);
 
-    // Check 5: Workspace metadata
-    check_workspace_metadata(&args, &mut issues_found, &mut issues_fixed);
+        // Check 7: Project context
+        check_project_context(root, &args, &mut issues_found, &mut issues_fixed);
+    }
 
-    // Check 6: Global configuration
-    check_global_config(&args, &mut issues_found, &mut issues_fixed);
+    // Check 5: Workspace metadata and attachment
+    if targets.workspace {
+        check_workspace_metadata(root, &args, &mut issues_found, &mut issues_fixed);
+        check_workspace_attachment(root, &args, &mut issues_found);
+    }
 
-    // Check 7: Project context
-    check_project_context(&args, &mut issues_found, &mut issues_fixed);
+    // Check 8: .gitignore managed block
+    if targets.gitignore {
+        check_gitignore(root, &args, &mut issues_found, &mut issues_fixed);
+    }
+
+    // Check 9: Interrupted transactions (never resolved automatically)
+    if targets.refs {
+        check_transactions(root, &mut issues_found);
+    }
 
     // Display summary
     println!();
-    if args.dry_run {
+    if !fixing(&args) {
         if issues_found.is_empty() {
             println!("No issues found.");
         } else {
             println!(
-                "{} issue{} found (dry run - no changes made)",
+                "{} issue{} found (report only - no changes made)",
                 issues_found.len(),
                 if issues_found.len() == 1 { "" } else { "s" }
             );
@@ -111,6 +189,8 @@ pub fn execute(args: RepairArgs) -> Result<()> {
             for issue in &issues_found {
                 println!("  - {}", issue);
             }
+            println!();
+            println!("Run 'jin repair --fix' to repair them.");
         }
     } else if issues_found.is_empty() {
         println!("No issues found.");
@@ -143,15 +223,81 @@ pub fn execute(args: RepairArgs) -> Result<()> {
     Ok(())
 }
 
+/// A file's contents before a fix, or `None` if it did not exist
+type Snapshot = Vec<(PathBuf, Option<Vec<u8>>)>;
+
+/// Run one fix as a unit
+///
+/// `touches` lists every file the fix may write, including backups it
+/// leaves behind. They are snapshotted first and put back if the fix fails
+/// part-way, so a failed fix changes nothing. A fix that succeeds is
+/// recorded in the audit log.
+fn apply_fix(
+    root: &Path,
+    description: &str,
+    touches: &[PathBuf],
+    fix: impl FnOnce() -> Result<()>,
+) -> Result<()> {
+    let snapshot: Snapshot = touches
+        .iter()
+        .map(|path| (path.clone(), std::fs::read(path).ok()))
+        .collect();
+
+    if let Err(e) = fix() {
+        for (path, content) in snapshot {
+            let restored = match content {
+                Some(content) => std::fs::write(&path, content),
+                None if path.exists() => std::fs::remove_file(&path),
+                None => Ok(()),
+            };
+            if let Err(restore_err) = restored {
+                eprintln!(
+                    "  Warning: Could not restore {}: {}",
+                    path.display(),
+                    restore_err
+                );
+            }
+        }
+        return Err(e);
+    }
+
+    record_fix(root, description, touches);
+    Ok(())
+}
+
+/// Record a successful fix in the project audit log
+///
+/// Outside a project there is no audit log, so nothing is recorded. A
+/// failure to log is a warning: the fix itself already succeeded.
+fn record_fix(root: &Path, description: &str, touches: &[PathBuf]) {
+    if !root.join(".jin").is_dir() {
+        return;
+    }
+    let project = ProjectContext::load(root).ok().and_then(|c| c.project);
+    let files = touches.iter().map(|p| p.display().to_string()).collect();
+    let entry = AuditEntry::from_repair(
+        CommitPipeline::get_git_user(root),
+        project,
+        files,
+        description.to_string(),
+    );
+    let result = AuditLogger::from_project(root)
+        .and_then(|logger| logger.with_machine(machine::current()).log_entry(&entry));
+    if let Err(e) = result {
+        eprintln!("  Warning: Could not record fix in the audit log: {}", e);
+    }
+}
+
 /// Check 1: Repository structure
 fn check_repository_structure(
+    root: &Path,
     args: &RepairArgs,
     issues_found: &mut Vec<String>,
     issues_fixed: &mut Vec<String>,
 ) -> Result<JinRepo> {
     print!("Checking repository structure... ");
 
-    match JinRepo::open() {
+    match JinRepo::open(root) {
         Ok(repo) => {
             // Verify it's actually a bare repository
             if !repo.inner().is_bare() {
@@ -159,7 +305,7 @@ fn check_repository_structure(
                 let issue = "Repository exists but is not bare".to_string();
                 issues_found.push(issue.clone());
 
-                if !args.dry_run {
+                if fixing(args) {
                     // This is a fatal error - can't automatically fix
                     eprintln!("Error: Repository at ~/.jin exists but is not a bare repository");
                     eprintln!("Manual intervention required.");
@@ -178,11 +324,12 @@ fn check_repository_structure(
             let issue = "Repository not found or corrupted".to_string();
             issues_found.push(issue.clone());
 
-            if !args.dry_run {
+            if fixing(args) {
                 // Try to recreate repository
-                match JinRepo::create() {
+                match JinRepo::create(root) {
                     Ok(repo) => {
                         let fix = "Repository recreated".to_string();
+                        record_fix(root, &fix, &[]);
                         issues_fixed.push(fix.clone());
                         println!("  Fixed: {}", fix);
                         Ok(repo)
@@ -203,6 +350,7 @@ fn check_repository_structure(
 
 /// Check 2: Layer refs
 fn check_layer_refs(
+    root: &Path,
     args: &RepairArgs,
     repo: &JinRepo,
     issues_found: &mut Vec<String>,
@@ -242,11 +390,12 @@ fn check_layer_refs(
                     let issue = format!("Invalid ref {}: {}", ref_name, reason);
                     issues_found.push(issue.clone());
 
-                    if !args.dry_run {
+                    if fixing(args) {
                         // Try to recover from reflog
                         match recover_ref_from_reflog(repo, ref_name) {
                             Ok(true) => {
                                 let fix = format!("Recovered {} from reflog", ref_name);
+                                record_fix(root, &fix, &[]);
                                 issues_fixed.push(fix.clone());
                                 println!("  Fixed: {}", fix);
                             }
@@ -254,6 +403,7 @@ fn check_layer_refs(
                                 // No valid reflog entry, delete the ref
                                 if let Ok(()) = repo.delete_ref(ref_name) {
                                     let fix = format!("Deleted invalid ref {}", ref_name);
+                                    record_fix(root, &fix, &[]);
                                     issues_fixed.push(fix.clone());
                                     println!("  Fixed: {}", fix);
                                 } else {
@@ -307,13 +457,14 @@ fn recover_ref_from_reflog(repo: &JinRepo, ref_name: &str) -> Result<bool> {
 
 /// Check 3: Staging index
 fn check_staging_index(
+    root: &Path,
     args: &RepairArgs,
     issues_found: &mut Vec<String>,
     issues_fixed: &mut Vec<String>,
 ) {
     print!("Checking staging index... ");
 
-    let index_path = StagingIndex::default_path();
+    let index_path = StagingIndex::default_path(root);
 
     if !index_path.exists() {
         // Missing index is not an issue - just means nothing is staged
@@ -321,7 +472,7 @@ fn check_staging_index(
         return;
     }
 
-    match StagingIndex::load() {
+    match StagingIndex::load(root) {
         Ok(_) => {
             println!("✓");
         }
@@ -330,11 +481,17 @@ fn check_staging_index(
             let issue = "Staging index corrupted".to_string();
             issues_found.push(issue.clone());
 
-            if !args.dry_run {
+            if fixing(args) {
                 // Rebuild index - we lose staging data but it's better than corruption
-                match rebuild_staging_index(&index_path) {
+                let fix = "Staging index rebuilt (staged changes lost)".to_string();
+                let touches = [
+                    index_path.clone(),
+                    index_path.with_extension("json.corrupted"),
+                ];
+                match apply_fix(root, &fix, &touches, || {
+                    rebuild_staging_index(root, &index_path)
+                }) {
                     Ok(()) => {
-                        let fix = "Staging index rebuilt (staged changes lost)".to_string();
                         issues_fixed.push(fix.clone());
                         println!("  Fixed: {}", fix);
                     }
@@ -351,7 +508,7 @@ fn check_staging_index(
 }
 
 /// Rebuild a corrupted staging index
-fn rebuild_staging_index(index_path: &PathBuf) -> Result<()> {
+fn rebuild_staging_index(root: &Path, index_path: &PathBuf) -> Result<()> {
     // Create a new empty index
     let index = StagingIndex::new();
 
@@ -362,33 +519,40 @@ fn rebuild_staging_index(index_path: &PathBuf) -> Result<()> {
     }
 
     // Save new index
-    index.save()?;
+    index.save(root)?;
 
     Ok(())
 }
 
 /// Check 4: .jinmap
-fn check_jinmap(args: &RepairArgs, issues_found: &mut Vec<String>, issues_fixed: &mut Vec<String>) {
+fn check_jinmap(
+    root: &Path,
+    args: &RepairArgs,
+    issues_found: &mut Vec<String>,
+    issues_fixed: &mut Vec<String>,
+) {
     print!("Checking .jinmap... ");
 
-    let jinmap_path = crate::core::JinMap::default_path();
+    let jinmap_path = crate::core::JinMap::default_path(root);
 
     if !jinmap_path.exists() {
         // .jinmap is optional, but if .jin/ exists it should have one
         let jin_dir = jinmap_path
             .parent()
             .map(|p| p.to_path_buf())
-            .unwrap_or_else(|| PathBuf::from(".jin"));
+            .unwrap_or_else(|| root.join(".jin"));
         if jin_dir.exists() && jin_dir.is_dir() {
             println!("✗");
             let issue = ".jinmap missing".to_string();
             issues_found.push(issue.clone());
 
-            if !args.dry_run {
+            if fixing(args) {
                 // Create default .jinmap
-                match create_default_jinmap(&jinmap_path) {
+                let fix = ".jinmap created".to_string();
+                match apply_fix(root, &fix, std::slice::from_ref(&jinmap_path), || {
+                    create_default_jinmap(&jinmap_path)
+                }) {
                     Ok(()) => {
-                        let fix = ".jinmap created".to_string();
                         issues_fixed.push(fix.clone());
                         println!("  Fixed: {}", fix);
                     }
@@ -417,12 +581,15 @@ fn check_jinmap(args: &RepairArgs, issues_found: &mut Vec<String>, issues_fixed:
                 let issue = ".jinmap is not valid YAML".to_string();
                 issues_found.push(issue.clone());
 
-                if !args.dry_run {
+                if fixing(args) {
                     // Backup and recreate
-                    match repair_jinmap(&jinmap_path) {
+                    let fix = ".jinmap repaired (backed up to .jinmap.corrupted)".to_string();
+                    let touches = [
+                        jinmap_path.clone(),
+                        jinmap_path.with_extension("jinmap.corrupted"),
+                    ];
+                    match apply_fix(root, &fix, &touches, || repair_jinmap(&jinmap_path)) {
                         Ok(()) => {
-                            let fix =
-                                ".jinmap repaired (backed up to .jinmap.corrupted)".to_string();
                             issues_fixed.push(fix.clone());
                             println!("  Fixed: {}", fix);
                         }
@@ -470,13 +637,14 @@ fn repair_jinmap(path: &PathBuf) -> Result<()> {
 
 /// Check 5: Workspace metadata
 fn check_workspace_metadata(
+    root: &Path,
     args: &RepairArgs,
     issues_found: &mut Vec<String>,
     issues_fixed: &mut Vec<String>,
 ) {
     print!("Checking workspace metadata... ");
 
-    let metadata_path = WorkspaceMetadata::default_path();
+    let metadata_path = WorkspaceMetadata::default_path(root);
 
     if !metadata_path.exists() {
         // Missing metadata is not an issue - just means no apply has been done
@@ -484,7 +652,7 @@ fn check_workspace_metadata(
         return;
     }
 
-    match WorkspaceMetadata::load() {
+    match WorkspaceMetadata::load(root) {
         Ok(_) => {
             println!("✓");
         }
@@ -493,11 +661,17 @@ fn check_workspace_metadata(
             let issue = "Workspace metadata corrupted".to_string();
             issues_found.push(issue.clone());
 
-            if !args.dry_run {
+            if fixing(args) {
                 // Rebuild metadata
-                match rebuild_workspace_metadata(&metadata_path) {
+                let fix = "Workspace metadata rebuilt".to_string();
+                let touches = [
+                    metadata_path.clone(),
+                    metadata_path.with_extension("json.corrupted"),
+                ];
+                match apply_fix(root, &fix, &touches, || {
+                    rebuild_workspace_metadata(root, &metadata_path)
+                }) {
                     Ok(()) => {
-                        let fix = "Workspace metadata rebuilt".to_string();
                         issues_fixed.push(fix.clone());
                         println!("  Fixed: {}", fix);
                     }
@@ -514,7 +688,7 @@ fn check_workspace_metadata(
 }
 
 /// Rebuild corrupted workspace metadata
-fn rebuild_workspace_metadata(path: &PathBuf) -> Result<()> {
+fn rebuild_workspace_metadata(root: &Path, path: &PathBuf) -> Result<()> {
     // Backup corrupted metadata
     let backup_path = path.with_extension("json.corrupted");
     if path.exists() {
@@ -523,13 +697,14 @@ fn rebuild_workspace_metadata(path: &PathBuf) -> Result<()> {
 
     // Create new empty metadata
     let metadata = WorkspaceMetadata::new();
-    metadata.save()?;
+    metadata.save(root)?;
 
     Ok(())
 }
 
 /// Check 6: Global configuration
 fn check_global_config(
+    root: &Path,
     args: &RepairArgs,
     issues_found: &mut Vec<String>,
     issues_fixed: &mut Vec<String>,
@@ -546,11 +721,13 @@ fn check_global_config(
             let issue = "Global config is invalid".to_string();
             issues_found.push(issue.clone());
 
-            if !args.dry_run {
-                match repair_global_config() {
+            if fixing(args) {
+                let fix = "Global config repaired (backed up to config.toml.corrupted)".to_string();
+                let touches: Vec<PathBuf> = JinConfig::default_path()
+                    .map(|path| vec![path.with_extension("toml.corrupted"), path])
+                    .unwrap_or_default();
+                match apply_fix(root, &fix, &touches, repair_global_config) {
                     Ok(()) => {
-                        let fix = "Global config repaired (backed up to config.toml.corrupted)"
-                            .to_string();
                         issues_fixed.push(fix.clone());
                         println!("  Fixed: {}", fix);
                     }
@@ -589,13 +766,14 @@ fn repair_global_config() -> Result<()> {
 
 /// Check 7: Project context
 fn check_project_context(
+    root: &Path,
     args: &RepairArgs,
     issues_found: &mut Vec<String>,
     issues_fixed: &mut Vec<String>,
 ) {
     print!("Checking project context... ");
 
-    let context_path = ProjectContext::default_path();
+    let context_path = ProjectContext::default_path(root);
 
     // Only check if .jin directory exists
     if !context_path.parent().map(|p| p.exists()).unwrap_or(false) {
@@ -608,10 +786,12 @@ fn check_project_context(
         let issue = "Project context missing".to_string();
         issues_found.push(issue.clone());
 
-        if !args.dry_run {
-            match create_default_context() {
+        if fixing(args) {
+            let fix = "Project context created".to_string();
+            match apply_fix(root, &fix, std::slice::from_ref(&context_path), || {
+                create_default_context(root)
+            }) {
                 Ok(()) => {
-                    let fix = "Project context created".to_string();
                     issues_fixed.push(fix.clone());
                     println!("  Fixed: {}", fix);
                 }
@@ -626,7 +806,7 @@ fn check_project_context(
         return;
     }
 
-    match ProjectContext::load() {
+    match ProjectContext::load(root) {
         Ok(_) => {
             println!("✓");
         }
@@ -635,11 +815,14 @@ fn check_project_context(
             let issue = "Project context corrupted".to_string();
             issues_found.push(issue.clone());
 
-            if !args.dry_run {
-                match repair_project_context() {
+            if fixing(args) {
+                let fix = "Project context repaired (backed up to context.corrupted)".to_string();
+                let touches = [
+                    context_path.clone(),
+                    context_path.with_extension("corrupted"),
+                ];
+                match apply_fix(root, &fix, &touches, || repair_project_context(root)) {
                     Ok(()) => {
-                        let fix =
-                            "Project context repaired (backed up to context.corrupted)".to_string();
                         issues_fixed.push(fix.clone());
                         println!("  Fixed: {}", fix);
                     }
@@ -656,15 +839,15 @@ fn check_project_context(
 }
 
 /// Create default project context
-fn create_default_context() -> Result<()> {
+fn create_default_context(root: &Path) -> Result<()> {
     let context = ProjectContext::default();
-    context.save()?;
+    context.save(root)?;
     Ok(())
 }
 
 /// Repair project context
-fn repair_project_context() -> Result<()> {
-    let context_path = ProjectContext::default_path();
+fn repair_project_context(root: &Path) -> Result<()> {
+    let context_path = ProjectContext::default_path(root);
 
     // Backup corrupted context
     if context_path.exists() {
@@ -673,8 +856,203 @@ fn repair_project_context() -> Result<()> {
     }
 
     // Create new default context
-    create_default_context()?;
+    create_default_context(root)?;
+
+    Ok(())
+}
+
+/// Check 8: .gitignore managed block
+///
+/// Every file Jin has applied or staged in the workspace should be listed
+/// in the managed block, or Git would offer to commit it.
+fn check_gitignore(
+    root: &Path,
+    args: &RepairArgs,
+    issues_found: &mut Vec<String>,
+    issues_fixed: &mut Vec<String>,
+) {
+    print!("Checking .gitignore managed block... ");
+
+    // The home workspace isn't a Git repository: nothing to ignore
+    if !root.join(".jin").is_dir() || HomeConfig::current(root).is_some() {
+        println!("✓ (not applicable)");
+        return;
+    }
+
+    let mut managed: Vec<PathBuf> = Vec::new();
+    if let Ok(metadata) = WorkspaceMetadata::load(root) {
+        // Files applied outside the workspace are not Git's concern
+        managed.extend(
+            metadata
+                .files
+                .into_keys()
+                .filter(|path| !metadata.targets.contains_key(path)),
+        );
+    }
+    if let Ok(staging) = StagingIndex::load(root) {
+        managed.extend(staging.paths().cloned());
+    }
+    managed.sort();
+    managed.dedup();
+
+    let listed = managed_entries(root);
+    let missing: Vec<PathBuf> = managed
+        .into_iter()
+        .filter(|path| !listed.contains(&gitignore::normalize_path(path)))
+        .collect();
+
+    if missing.is_empty() {
+        println!("✓");
+        return;
+    }
+
+    println!("✗");
+    let issue = format!(
+        "{} Jin-managed file{} missing from the .gitignore managed block",
+        missing.len(),
+        if missing.len() == 1 { "" } else { "s" }
+    );
+    issues_found.push(issue.clone());
+
+    if fixing(args) {
+        let fix = format!(
+            "Added {} file{} to the .gitignore managed block",
+            missing.len(),
+            if missing.len() == 1 { "" } else { "s" }
+        );
+        let result = apply_fix(root, &fix, &[root.join(".gitignore")], || {
+            missing
+                .iter()
+                .try_for_each(|path| ensure_in_managed_block(root, path))
+        });
+        match result {
+            Ok(()) => {
+                issues_fixed.push(fix.clone());
+                println!("  Fixed: {}", fix);
+            }
+            Err(e) => {
+                println!("  Failed to update .gitignore: {}", e);
+            }
+        }
+    } else {
+        println!("  Issue: {}", issue);
+        for path in &missing {
+            println!("    {}", path.display());
+        }
+        println!("    Would add them to the managed block");
+    }
+}
+
+/// Check 9: Interrupted transactions
+///
+/// Whether an interrupted commit should be finished or undone is the
+/// user's call, so this only reports it.
+fn check_transactions(root: &Path, issues_found: &mut Vec<String>) {
+    print!("Checking transactions... ");
+
+    match RecoveryManager::detect(root) {
+        Ok(None) => println!("✓"),
+        Ok(Some(incomplete)) => {
+            println!("✗");
+            issues_found.push(format!("Interrupted transaction {}", incomplete.id()));
+            print_transaction(&incomplete);
+            if incomplete.state() == TransactionState::Prepared {
+                println!("    Finish it: jin repair --resume-txn {}", incomplete.id());
+            }
+            println!(
+                "    Undo it:   jin repair --resume-txn {} --rollback",
+                incomplete.id()
+            );
+        }
+        Err(e) => {
+            println!("✗");
+            issues_found.push(format!("Transaction log unreadable: {}", e));
+        }
+    }
+}
+
+/// Describe an interrupted transaction and its planned ref updates
+fn print_transaction(incomplete: &IncompleteTransaction) {
+    println!("  Transaction {}", incomplete.id());
+    println!("    Message: {}", incomplete.message());
+    println!("    Started: {}", incomplete.started_at());
+    println!(
+        "    State:   {}",
+        format!("{:?}", incomplete.state()).to_lowercase()
+    );
+    for update in incomplete.updates() {
+        let old = update
+            .old_oid
+            .as_deref()
+            .map_or("(new)", |oid| &oid[..8.min(oid.len())]);
+        println!(
+            "    {}: {} -> {}",
+            update.ref_path,
+            old,
+            &update.new_oid[..8.min(update.new_oid.len())]
+        );
+    }
+}
+
+/// Finish or roll back the interrupted transaction `id`
+fn resume_transaction(root: &Path, id: &str, rollback: bool, dry_run: bool) -> Result<()> {
+    let incomplete = RecoveryManager::find(root, id)?;
+    let repo = JinRepo::open(root)?;
+    let id = incomplete.id().to_string();
+    print_transaction(&incomplete);
+    println!();
+
+    if rollback {
+        if dry_run {
+            println!("Would roll back transaction {}", id);
+            return Ok(());
+        }
+        incomplete.rollback(&repo)?;
+        println!("Rolled back transaction {}", id);
+        return Ok(());
+    }
+
+    if incomplete.state() != TransactionState::Prepared {
+        return Err(JinError::Transaction(format!(
+            "Transaction {} was interrupted before its plan was complete and cannot be finished.\n\
+             Run 'jin repair --resume-txn {} --rollback' to undo it.",
+            id, id
+        )));
+    }
+    let moved = incomplete.moved_refs(&repo);
+    if !moved.is_empty() {
+        return Err(JinError::Transaction(format!(
+            "Cannot finish transaction {}: {} moved since it started.\n\
+             Run 'jin repair --resume-txn {} --rollback' to undo it.",
+            id,
+            moved.join(", "),
+            id
+        )));
+    }
+    if dry_run {
+        println!("Would finish transaction {}", id);
+        return Ok(());
+    }
+
+    let files = incomplete.files().to_vec();
+    let updates = incomplete.update_count();
+    incomplete.resume(&repo)?;
 
+    // Unstage the committed files, as the finished commit would have
+    if !files.is_empty() {
+        let mut staging = StagingIndex::load(root)?;
+        for file in &files {
+            staging.remove(Path::new(file));
+        }
+        staging.save(root)?;
+    }
+
+    println!(
+        "Finished transaction {} ({} layer{} updated)",
+        id,
+        updates,
+        if updates == 1 { "" } else { "s" }
+    );
     Ok(())
 }
 
@@ -682,11 +1060,11 @@ fn repair_project_context() -> Result<()> {
 ///
 /// Validates that the workspace is properly attached to the active context.
 /// This check is used by the --check flag to diagnose detached workspace issues.
-fn check_workspace_attachment(args: &RepairArgs, issues_found: &mut Vec<String>) {
+fn check_workspace_attachment(root: &Path, args: &RepairArgs, issues_found: &mut Vec<String>) {
     print!("Checking workspace attachment... ");
 
     // Load project context
-    let context = match ProjectContext::load() {
+    let context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             println!("✓ (not initialized)");
@@ -699,7 +1077,7 @@ fn check_workspace_attachment(args: &RepairArgs, issues_found: &mut Vec<String>)
     };
 
     // Open Jin repository
-    let repo = match JinRepo::open() {
+    let repo = match JinRepo::open(root) {
         Ok(r) => r,
         Err(_) => {
             println!("✓ (no repository)");
@@ -708,7 +1086,7 @@ fn check_workspace_attachment(args: &RepairArgs, issues_found: &mut Vec<String>)
     };
 
     // Validate workspace
-    match validate_workspace_attached(&context, &repo) {
+    match validate_workspace_attached(root, &context, &repo) {
         Ok(()) => {
             println!("✓");
         }
@@ -791,9 +1169,9 @@ mod tests {
 
         let args = RepairArgs {
             dry_run: true,
-            check: false,
+            ..Default::default()
         };
-        let result = execute(args);
+        let result = execute(args, Path::new("."));
         assert!(result.is_ok());
     }
 
@@ -803,10 +1181,10 @@ mod tests {
         let _guard = DirGuard::new(setup_isolated_test());
 
         let args = RepairArgs {
-            dry_run: false,
-            check: false,
+            fix: true,
+            ..Default::default()
         };
-        let result = execute(args);
+        let result = execute(args, Path::new("."));
         assert!(result.is_ok());
     }
 
@@ -817,12 +1195,12 @@ mod tests {
 
         let args = RepairArgs {
             dry_run: true,
-            check: false,
+            ..Default::default()
         };
         let mut issues_found = Vec::new();
         let mut issues_fixed = Vec::new();
 
-        check_staging_index(&args, &mut issues_found, &mut issues_fixed);
+        check_staging_index(Path::new("."), &args, &mut issues_found, &mut issues_fixed);
 
         // Missing index is fine
         assert_eq!(issues_found.len(), 0);
@@ -847,12 +1225,12 @@ mod tests {
 
         let args = RepairArgs {
             dry_run: true,
-            check: false,
+            ..Default::default()
         };
         let mut issues_found = Vec::new();
         let mut issues_fixed = Vec::new();
 
-        check_staging_index(&args, &mut issues_found, &mut issues_fixed);
+        check_staging_index(Path::new("."), &args, &mut issues_found, &mut issues_fixed);
 
         assert_eq!(issues_found.len(), 1);
         assert!(issues_found[0].contains("corrupted"));
@@ -860,6 +1238,73 @@ mod tests {
         std::env::remove_var("JIN_DIR");
     }
 
+    #[test]
+    fn test_targets_default_to_all() {
+        let all = Targets::from_args(&RepairArgs::default());
+        assert!(all.refs && all.staging && all.context && all.gitignore && all.workspace);
+
+        let only = Targets::from_args(&RepairArgs {
+            staging: true,
+            gitignore: true,
+            ..Default::default()
+        });
+        assert!(only.staging && only.gitignore);
+        assert!(!only.refs && !only.context && !only.workspace);
+    }
+
+    #[test]
+    fn test_apply_fix_restores_files_on_failure() {
+        let temp = TempDir::new().unwrap();
+        let state = temp.path().join("state.json");
+        let backup = temp.path().join("state.json.corrupted");
+        std::fs::write(&state, "corrupted").unwrap();
+
+        let result = apply_fix(
+            Path::new("."),
+            "Rebuild state",
+            &[state.clone(), backup.clone()],
+            || {
+                std::fs::rename(&state, &backup)?;
+                Err(JinError::Other("disk full".to_string()))
+            },
+        );
+
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_to_string(&state).unwrap(), "corrupted");
+        assert!(!backup.exists());
+    }
+
+    #[test]
+    #[serial]
+    fn test_report_only_by_default() {
+        let temp = setup_isolated_test();
+        let jin_dir = temp.path().join(".jin");
+        std::env::set_var("JIN_DIR", &jin_dir);
+        let index_path = jin_dir.join("staging").join("index.json");
+        std::fs::create_dir_all(index_path.parent().unwrap()).unwrap();
+        std::fs::write(&index_path, "invalid json").unwrap();
+        let _guard = DirGuard::new(temp);
+
+        execute(RepairArgs::default(), Path::new(".")).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&index_path).unwrap(),
+            "invalid json"
+        );
+
+        execute(
+            RepairArgs {
+                fix: true,
+                staging: true,
+                ..Default::default()
+            },
+            Path::new("."),
+        )
+        .unwrap();
+        assert!(StagingIndex::load(Path::new(".")).is_ok());
+
+        std::env::remove_var("JIN_DIR");
+    }
+
     #[test]
     fn test_create_default_jinmap() {
         let temp = TempDir::new().unwrap();
@@ -886,11 +1331,11 @@ mod tests {
         // Use DirGuard to change to temp directory and auto-restore
         let _guard = DirGuard::new(temp);
 
-        let result = rebuild_staging_index(&index_path);
+        let result = rebuild_staging_index(Path::new("."), &index_path);
         assert!(result.is_ok());
 
         // Verify new index is valid
-        let loaded = StagingIndex::load();
+        let loaded = StagingIndex::load(Path::new("."));
         assert!(loaded.is_ok());
 
         // Verify backup was created
@@ -918,12 +1363,12 @@ mod tests {
 
         let args = RepairArgs {
             dry_run: true,
-            check: false,
+            ..Default::default()
         };
         let mut issues_found = Vec::new();
         let mut issues_fixed = Vec::new();
 
-        check_jinmap(&args, &mut issues_found, &mut issues_fixed);
+        check_jinmap(Path::new("."), &args, &mut issues_found, &mut issues_fixed);
 
         assert_eq!(issues_found.len(), 0);
     }
@@ -947,12 +1392,12 @@ mod tests {
 
         let args = RepairArgs {
             dry_run: true,
-            check: false,
+            ..Default::default()
         };
         let mut issues_found = Vec::new();
         let mut issues_fixed = Vec::new();
 
-        check_jinmap(&args, &mut issues_found, &mut issues_fixed);
+        check_jinmap(Path::new("."), &args, &mut issues_found, &mut issues_fixed);
 
         assert_eq!(issues_found.len(), 1);
         assert!(issues_found[0].contains("not valid YAML"));
@@ -967,12 +1412,12 @@ mod tests {
 
         let args = RepairArgs {
             dry_run: true,
-            check: false,
+            ..Default::default()
         };
         let mut issues_found = Vec::new();
         let mut issues_fixed = Vec::new();
 
-        check_workspace_metadata(&args, &mut issues_found, &mut issues_fixed);
+        check_workspace_metadata(Path::new("."), &args, &mut issues_found, &mut issues_fixed);
 
         // Missing metadata is fine
         assert_eq!(issues_found.len(), 0);
@@ -984,18 +1429,20 @@ mod tests {
         let temp = setup_isolated_test();
 
         // Create corrupted metadata
-        let metadata_path = temp.path().join(WorkspaceMetadata::default_path());
+        let metadata_path = temp
+            .path()
+            .join(WorkspaceMetadata::default_path(Path::new(".")));
         std::fs::create_dir_all(metadata_path.parent().unwrap()).unwrap();
         std::fs::write(&metadata_path, "invalid json").unwrap();
 
         // Use DirGuard to change to temp directory and auto-restore
         let _guard = DirGuard::new(temp);
 
-        let result = rebuild_workspace_metadata(&metadata_path);
+        let result = rebuild_workspace_metadata(Path::new("."), &metadata_path);
         assert!(result.is_ok());
 
         // Verify new metadata is valid
-        let loaded = WorkspaceMetadata::load();
+        let loaded = WorkspaceMetadata::load(Path::new("."));
         assert!(loaded.is_ok());
 
         // Verify backup was created (with correct path)
@@ -1020,10 +1467,10 @@ mod tests {
         // Use DirGuard to change to temp directory and auto-restore
         let _guard = DirGuard::new(temp);
 
-        let result = create_default_context();
+        let result = create_default_context(Path::new("."));
         assert!(result.is_ok(), "Failed to create context: {:?}", result);
 
-        let context_path = ProjectContext::default_path();
+        let context_path = ProjectContext::default_path(Path::new("."));
         assert!(
             context_path.exists(),
             "Context file was not created at {}",
@@ -0,0 +1,415 @@
+//! Implementation of `jin repo` subcommands
+//!
+//! Manages named Jin repositories. Repositories are registered globally in
+//! `~/.jin/config.toml` under `[repos]`, and each project selects one in
+//! `.jin/config.yaml`. Projects without a selection use the default
+//! repository (`~/.jin` or `$JIN_DIR`).
+
+use crate::cli::RepoAction;
+use crate::core::config::expand_home;
+use crate::core::{JinConfig, JinError, ProjectConfig, ProjectContext, Result};
+use crate::git::repo::DEFAULT_REPO_NAME;
+use crate::git::JinRepo;
+use std::path::Path;
+
+/// Execute a repo subcommand
+pub fn execute(action: RepoAction, root: &Path) -> Result<()> {
+    match action {
+        RepoAction::Add { name, path } => add(&name, &path),
+        RepoAction::Remove { name } => remove(root, &name),
+        RepoAction::List => list(root),
+        RepoAction::Use { name } => use_repo(root, &name),
+        RepoAction::Show => show(root),
+        RepoAction::Move { path, name } => move_repo(root, name.as_deref(), &path),
+    }
+}
+
+/// Validate repository name
+///
+/// Repository names must be non-empty, contain only alphanumeric
+/// characters, hyphens and underscores, and must not be `default`.
+fn validate_repo_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        return Err(JinError::Other(
+            "Repository name cannot be empty".to_string(),
+        ));
+    }
+
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(JinError::Other(format!(
+            "Invalid repository name '{}'. Use alphanumeric characters, hyphens and underscores only.",
+            name
+        )));
+    }
+
+    if name == DEFAULT_REPO_NAME {
+        return Err(JinError::Other(format!(
+            "Repository name '{}' is reserved.",
+            name
+        )));
+    }
+
+    Ok(())
+}
+
+/// Name of the repository selected by the current project
+fn active_repo_name(root: &Path) -> String {
+    ProjectConfig::load(root)
+        .ok()
+        .and_then(|c| c.repo)
+        .unwrap_or_else(|| DEFAULT_REPO_NAME.to_string())
+}
+
+/// Register a named repository, creating it if needed
+fn add(name: &str, path: &str) -> Result<()> {
+    validate_repo_name(name)?;
+
+    let mut config = JinConfig::load()?;
+    if config.repos.contains_key(name) {
+        return Err(JinError::AlreadyExists(format!(
+            "Repository '{}' already exists",
+            name
+        )));
+    }
+
+    let repo = JinRepo::open_or_create_at(&expand_home(path))?;
+
+    config.repos.insert(name.to_string(), path.to_string());
+    config.save()?;
+
+    println!(
+        "Registered repository '{}' at {}",
+        name,
+        repo.path().display()
+    );
+    println!("Use it in a project with: jin repo use {}", name);
+
+    Ok(())
+}
+
+/// Unregister a named repository
+fn remove(root: &Path, name: &str) -> Result<()> {
+    let mut config = JinConfig::load()?;
+    if config.repos.remove(name).is_none() {
+        return Err(JinError::NotFound(format!(
+            "Repository '{}' not found",
+            name
+        )));
+    }
+    config.save()?;
+
+    println!(
+        "Removed repository '{}' (files on disk were not deleted)",
+        name
+    );
+    if ProjectContext::is_initialized(root) && active_repo_name(root) == name {
+        println!("Warning: this project still selects '{}'.", name);
+        println!("Select another with: jin repo use <name>");
+    }
+
+    Ok(())
+}
+
+/// List registered repositories, marking the one used by this project
+fn list(root: &Path) -> Result<()> {
+    let config = JinConfig::load()?;
+    let active = if ProjectContext::is_initialized(root) {
+        Some(active_repo_name(root))
+    } else {
+        None
+    };
+
+    let marker = |name: &str| {
+        if active.as_deref() == Some(name) {
+            "*"
+        } else {
+            " "
+        }
+    };
+
+    println!("Jin repositories:");
+    println!(
+        "{} {} -> {}",
+        marker(DEFAULT_REPO_NAME),
+        DEFAULT_REPO_NAME,
+        JinRepo::default_path()?.display()
+    );
+    for (name, path) in &config.repos {
+        println!(
+            "{} {} -> {}",
+            marker(name),
+            name,
+            expand_home(path).display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Select the repository used by this project
+fn use_repo(root: &Path, name: &str) -> Result<()> {
+    if !ProjectContext::is_initialized(root) {
+        return Err(JinError::NotInitialized);
+    }
+
+    // Resolve first so unknown names fail before anything is written
+    let path = JinRepo::path_for_name(name)?;
+    JinRepo::open_or_create_at(&path)?;
+
+    let mut project_config = ProjectConfig::load(root)?;
+    let previous = project_config.repo.clone();
+    project_config.repo = if name == DEFAULT_REPO_NAME {
+        None
+    } else {
+        Some(name.to_string())
+    };
+    project_config.save(root)?;
+
+    println!("Using repository '{}' ({})", name, path.display());
+    if previous.as_deref().unwrap_or(DEFAULT_REPO_NAME) != name {
+        println!("Run 'jin apply' to refresh the workspace from the new repository.");
+    }
+
+    Ok(())
+}
+
+/// Show the repository used by this project
+fn show(root: &Path) -> Result<()> {
+    if !ProjectContext::is_initialized(root) {
+        return Err(JinError::NotInitialized);
+    }
+
+    println!(
+        "Repository: {} ({})",
+        active_repo_name(root),
+        JinRepo::resolve_path(root)?.display()
+    );
+
+    Ok(())
+}
+
+/// Move a named repository to `path` and update its registration
+///
+/// Projects select repositories by name, so they follow the move without
+/// changes. The repository is validated at its new location; if that fails
+/// it is moved back and the registration is left untouched.
+fn move_repo(root: &Path, name: Option<&str>, path: &str) -> Result<()> {
+    let name = match name {
+        Some(name) => name.to_string(),
+        None if ProjectContext::is_initialized(root) => active_repo_name(root),
+        None => return Err(JinError::NotInitialized),
+    };
+    if name == DEFAULT_REPO_NAME {
+        return Err(JinError::Config(format!(
+            "The default repository ({}) cannot be moved; it also holds the global config. \
+             Set JIN_DIR to relocate it, or register a named repository with 'jin repo add'.",
+            JinRepo::default_path()?.display()
+        )));
+    }
+
+    let mut config = JinConfig::load()?;
+    let old_path = JinRepo::path_for_name(&name)?;
+    let new_path = expand_home(path);
+    JinRepo::open_at(&old_path).map_err(|_| {
+        JinError::NotFound(format!(
+            "Repository '{}' not found at {}",
+            name,
+            old_path.display()
+        ))
+    })?;
+    if new_path.exists() && std::fs::read_dir(&new_path)?.next().is_some() {
+        return Err(JinError::AlreadyExists(format!(
+            "{} already exists and is not empty",
+            new_path.display()
+        )));
+    }
+
+    move_dir(&old_path, &new_path)?;
+    let refs = match validate_repo(&new_path) {
+        Ok(refs) => refs,
+        Err(e) => {
+            move_dir(&new_path, &old_path)?;
+            return Err(JinError::Other(format!(
+                "Repository at {} failed validation, moved back: {}",
+                new_path.display(),
+                e
+            )));
+        }
+    };
+
+    config.repos.insert(name.clone(), path.to_string());
+    config.save()?;
+
+    println!(
+        "Moved repository '{}' from {} to {}",
+        name,
+        old_path.display(),
+        new_path.display()
+    );
+    println!(
+        "Validated {} ref(s); projects using '{}' follow the move",
+        refs, name
+    );
+    Ok(())
+}
+
+/// Check that every Jin ref of a repository points at an existing object
+fn validate_repo(path: &Path) -> Result<usize> {
+    let repo = JinRepo::open_at(&path.to_path_buf())?;
+    let mut refs = 0;
+    for reference in repo.inner().references_glob("refs/jin/*")? {
+        reference?.peel(git2::ObjectType::Any)?;
+        refs += 1;
+    }
+    Ok(refs)
+}
+
+/// Move a directory, copying it when `to` is on another filesystem
+fn move_dir(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if to.exists() {
+        // Only an empty directory gets here; rename cannot replace it everywhere
+        std::fs::remove_dir(to)?;
+    }
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_dir(from, to)?;
+    std::fs::remove_dir_all(from)?;
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    fn test_validate_repo_name() {
+        assert!(validate_repo_name("clientA").is_ok());
+        assert!(validate_repo_name("client-a_2").is_ok());
+        assert!(validate_repo_name("").is_err());
+        assert!(validate_repo_name("bad/name").is_err());
+        assert!(validate_repo_name("default").is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_add_registers_and_creates_repo() {
+        let ctx = crate::test_utils::setup_unit_test();
+        let repo_path = ctx.project_path.join("client.jinrepo");
+
+        add("client", repo_path.to_str().unwrap()).unwrap();
+
+        let config = JinConfig::load().unwrap();
+        assert!(config.repos.contains_key("client"));
+        assert!(JinRepo::open_at(&repo_path).is_ok());
+
+        assert!(matches!(
+            add("client", repo_path.to_str().unwrap()),
+            Err(JinError::AlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test_use_selects_repo_for_project() {
+        let ctx = crate::test_utils::setup_unit_test();
+        let repo_path = ctx.project_path.join("client.jinrepo");
+        add("client", repo_path.to_str().unwrap()).unwrap();
+
+        use_repo(&ctx.project_path, "client").unwrap();
+
+        assert_eq!(
+            ProjectConfig::load(&ctx.project_path)
+                .unwrap()
+                .repo
+                .as_deref(),
+            Some("client")
+        );
+        assert_eq!(JinRepo::resolve_path(&ctx.project_path).unwrap(), repo_path);
+
+        use_repo(&ctx.project_path, "default").unwrap();
+        assert!(ProjectConfig::load(&ctx.project_path)
+            .unwrap()
+            .repo
+            .is_none());
+        assert_eq!(
+            JinRepo::resolve_path(&ctx.project_path).unwrap(),
+            ctx.jin_dir
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_use_unknown_repo_fails() {
+        let ctx = crate::test_utils::setup_unit_test();
+        assert!(matches!(
+            use_repo(&ctx.project_path, "missing"),
+            Err(JinError::Config(_))
+        ));
+        assert!(ProjectConfig::load(&ctx.project_path)
+            .unwrap()
+            .repo
+            .is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn test_move_updates_registration() {
+        let ctx = crate::test_utils::setup_unit_test();
+        let old_path = ctx.project_path.join("client.jinrepo");
+        let new_path = ctx.project_path.join("moved").join("client.jinrepo");
+        add("client", old_path.to_str().unwrap()).unwrap();
+        use_repo(&ctx.project_path, "client").unwrap();
+
+        move_repo(&ctx.project_path, None, new_path.to_str().unwrap()).unwrap();
+
+        assert!(!old_path.exists());
+        assert!(JinRepo::open_at(&new_path).is_ok());
+        assert_eq!(JinRepo::resolve_path(&ctx.project_path).unwrap(), new_path);
+
+        assert!(matches!(
+            move_repo(&ctx.project_path, Some("default"), "/tmp/elsewhere"),
+            Err(JinError::Config(_))
+        ));
+        std::fs::create_dir_all(ctx.project_path.join("taken/file")).unwrap();
+        assert!(matches!(
+            move_repo(
+                &ctx.project_path,
+                Some("client"),
+                ctx.project_path.join("taken").to_str().unwrap()
+            ),
+            Err(JinError::AlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test_remove_unknown_repo_fails() {
+        let ctx = crate::test_utils::setup_unit_test();
+        assert!(matches!(
+            remove(&ctx.project_path, "missing"),
+            Err(JinError::NotFound(_))
+        ));
+    }
+}
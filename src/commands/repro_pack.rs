@@ -0,0 +1,377 @@
+//! Implementation of `jin repro-pack`
+//!
+//! Writes a reproduction package for a merge bug: only the given files from
+//! each layer of the active context, so a failing merge can be shared
+//! without the rest of the configuration. The package is a directory:
+//!
+//! ```text
+//! jin-repro/
+//!   jin/             a Jin directory: a bare repository holding one commit
+//!                    per layer, plus config.toml with the merge settings
+//!   project/.jin/    the active mode, scope and project
+//!   README.md        what was packed and how to replay the merge
+//! ```
+//!
+//! Nothing in the Jin repository or the workspace is modified. Layer history,
+//! remotes, user identity and every other setting are left out. With
+//! `--redact`, string values of structured files and lines of text files are
+//! replaced by hashes: keys, types and equal values are kept, so the merge
+//! takes the same path.
+
+use crate::cli::ReproPackArgs;
+use crate::core::{BranchScope, JinConfig, JinError, Layer, ProjectContext, Result};
+use crate::git::{JinRepo, ObjectOps, RefOps, TreeOps};
+use crate::merge::layer::get_applicable_layers;
+use crate::merge::{registry, MergeValue};
+use git2::{ObjectType, Oid, Signature, Time};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Shared merge rules, packed whenever a layer has them
+const ATTRIBUTES_FILE: &str = ".jinattributes";
+
+/// Per-machine merge rules in the workspace
+const LOCAL_ATTRIBUTES_FILE: &str = ".jinattributes.local";
+
+/// Files packed from one layer
+#[derive(Debug)]
+struct PackedLayer {
+    layer: Layer,
+    ref_path: String,
+    files: Vec<(String, Vec<u8>)>,
+}
+
+/// Execute the repro-pack command
+pub fn execute(args: ReproPackArgs, root: &Path) -> Result<()> {
+    let mut context = ProjectContext::load(root)?;
+    let repo = JinRepo::open(root).map_err(|_| JinError::NotInitialized)?;
+    BranchScope::activate(root, &mut context, &repo);
+
+    let wanted: Vec<String> = args
+        .files
+        .iter()
+        .map(|file| {
+            file.trim_start_matches("./")
+                .trim_end_matches('/')
+                .to_string()
+        })
+        .collect();
+    let layers = collect_layers(&repo, &context, &wanted)?;
+
+    // Every requested path must be in at least one layer
+    let found: BTreeSet<String> = layers
+        .iter()
+        .flat_map(|packed| packed.files.iter().map(|(path, _)| path.clone()))
+        .collect();
+    for path in &wanted {
+        if !found.iter().any(|file| matches(path, file)) {
+            return Err(JinError::NotFound(format!(
+                "'{}' in the layers of the active context",
+                path
+            )));
+        }
+    }
+
+    let output = root.join(&args.output);
+    if output.exists() && std::fs::read_dir(&output)?.next().is_some() {
+        return Err(JinError::AlreadyExists(format!(
+            "{} (choose another directory with --output)",
+            args.output.display()
+        )));
+    }
+
+    let layers: Vec<PackedLayer> = if args.redact {
+        layers
+            .into_iter()
+            .map(|packed| PackedLayer {
+                files: packed
+                    .files
+                    .into_iter()
+                    .map(|(path, content)| {
+                        let redacted = redact(Path::new(&path), &content);
+                        (path, redacted)
+                    })
+                    .collect(),
+                ..packed
+            })
+            .collect()
+    } else {
+        layers
+    };
+
+    let markers = context_markers(&repo, &context);
+    write_package(root, &output, &context, &markers, &layers, args.redact)?;
+
+    println!(
+        "Wrote {} file(s) from {} layer(s) to {}{}",
+        found.len(),
+        layers.len(),
+        args.output.display(),
+        if args.redact { " (redacted)" } else { "" }
+    );
+    println!(
+        "See {} to replay the merge",
+        args.output.join("README.md").display()
+    );
+    Ok(())
+}
+
+/// Whether `file` is the requested path or lies under it
+fn matches(wanted: &str, file: &str) -> bool {
+    file == wanted
+        || file
+            .strip_prefix(wanted)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Refs marking the active mode and scope as existing
+fn context_markers(repo: &JinRepo, context: &ProjectContext) -> Vec<String> {
+    let mut markers = Vec::new();
+    if let Some(mode) = &context.mode {
+        markers.push(format!("refs/jin/modes/{}/_mode", mode));
+    }
+    if let Some(scope) = &context.scope {
+        let ref_safe_name = scope.replace(':', "/");
+        if let Some(mode) = &context.mode {
+            markers.push(format!("refs/jin/modes/{}/scopes/{}", mode, ref_safe_name));
+        }
+        markers.push(format!("refs/jin/scopes/{}", ref_safe_name));
+    }
+    markers.retain(|marker| repo.ref_exists(marker));
+    markers
+}
+
+/// The requested files (and `.jinattributes`) of every layer in the merge
+fn collect_layers(
+    repo: &JinRepo,
+    context: &ProjectContext,
+    wanted: &[String],
+) -> Result<Vec<PackedLayer>> {
+    let mut layers = Vec::new();
+    for layer in get_applicable_layers(
+        context.mode.as_deref(),
+        context.scope.as_deref(),
+        context.project.as_deref(),
+    ) {
+        let ref_path = layer.ref_path(
+            context.mode.as_deref(),
+            context.scope.as_deref(),
+            context.project.as_deref(),
+        );
+        if !repo.ref_exists(&ref_path) {
+            continue;
+        }
+        let tree_oid = repo.find_commit(repo.resolve_ref(&ref_path)?)?.tree_id();
+        let mut files = Vec::new();
+        for file in repo.list_tree_files(tree_oid)? {
+            if file == ATTRIBUTES_FILE || wanted.iter().any(|path| matches(path, &file)) {
+                let content = repo.read_file_from_tree(tree_oid, Path::new(&file))?;
+                files.push((file, content));
+            }
+        }
+        if !files.is_empty() {
+            layers.push(PackedLayer {
+                layer,
+                ref_path,
+                files,
+            });
+        }
+    }
+    Ok(layers)
+}
+
+/// Replace the content of a file by hashes, keeping its shape
+///
+/// Structured files keep their keys, numbers, booleans and nulls; strings
+/// become `redacted-<hash>`. Other files have each line replaced. Merge
+/// rules are kept as they are.
+fn redact(path: &Path, content: &[u8]) -> Vec<u8> {
+    if path.file_name().is_some_and(|name| name == ATTRIBUTES_FILE) {
+        return content.to_vec();
+    }
+    let text = String::from_utf8_lossy(content);
+    let format = registry().detect(path);
+    if format.is_structured() {
+        if let Ok(value) = registry().parse(&text, format) {
+            if let Ok(redacted) = registry().serialize(&redact_value(value), format) {
+                return redacted.into_bytes();
+            }
+        }
+    }
+
+    let mut redacted: String = text
+        .lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                line.to_string()
+            } else {
+                hash(line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if text.ends_with('\n') {
+        redacted.push('\n');
+    }
+    redacted.into_bytes()
+}
+
+fn redact_value(value: MergeValue) -> MergeValue {
+    match value {
+        MergeValue::String(s) => MergeValue::String(hash(&s)),
+        MergeValue::Array(items) => {
+            MergeValue::Array(items.into_iter().map(redact_value).collect())
+        }
+        MergeValue::Object(map) => MergeValue::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, redact_value(value)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Stable stand-in for a redacted value: equal values stay equal
+fn hash(value: &str) -> String {
+    let oid = Oid::hash_object(ObjectType::Blob, value.as_bytes())
+        .map(|oid| oid.to_string())
+        .unwrap_or_default();
+    format!("redacted-{}", &oid[..oid.len().min(12)])
+}
+
+/// Write the package directory
+fn write_package(
+    root: &Path,
+    output: &Path,
+    context: &ProjectContext,
+    markers: &[String],
+    layers: &[PackedLayer],
+    redacted: bool,
+) -> Result<()> {
+    let jin_dir = output.join("jin");
+    let pack = JinRepo::create_at(&jin_dir)?;
+
+    // Fixed identity and time, so the package says nothing about its author
+    let signature = Signature::new("jin", "jin@local", &Time::new(0, 0))?;
+    let write_ref = |ref_path: &str, tree_oid: Oid, message: &str| -> Result<()> {
+        let tree = pack.inner().find_tree(tree_oid)?;
+        let commit = pack
+            .inner()
+            .commit(None, &signature, &signature, message, &tree, &[])?;
+        pack.set_ref(ref_path, commit, "repro-pack")
+    };
+    let empty_tree = pack.create_tree(&[])?;
+    for marker in markers {
+        write_ref(marker, empty_tree, "Marker")?;
+    }
+    for packed in layers {
+        let mut entries = Vec::new();
+        for (path, content) in &packed.files {
+            entries.push((path.clone(), pack.create_blob(content)?));
+        }
+        let tree_oid = pack.create_tree_from_paths(&entries)?;
+        write_ref(
+            &packed.ref_path,
+            tree_oid,
+            &format!("{} layer", packed.layer),
+        )?;
+    }
+
+    // Only the settings that change how files merge
+    let global = JinConfig::load()?;
+    let config = JinConfig {
+        version: global.version,
+        final_keys: global.final_keys,
+        style: global.style,
+        eol: global.eol,
+        unicode: global.unicode,
+        mergers: global.mergers,
+        strict_merge: global.strict_merge,
+        ..JinConfig::default()
+    };
+    let config = toml::to_string_pretty(&config)
+        .map_err(|e| JinError::Config(format!("Failed to serialize config: {}", e)))?;
+    std::fs::write(jin_dir.join("config.toml"), config)?;
+
+    let project_jin = output.join("project").join(".jin");
+    std::fs::create_dir_all(&project_jin)?;
+    let context = ProjectContext {
+        last_updated: None,
+        ..context.clone()
+    };
+    let context_yaml = serde_yaml::to_string(&context)
+        .map_err(|e| JinError::Config(format!("Failed to serialize context: {}", e)))?;
+    std::fs::write(project_jin.join("context"), context_yaml)?;
+    let attributes = root.join(LOCAL_ATTRIBUTES_FILE);
+    if attributes.is_file() {
+        std::fs::copy(
+            &attributes,
+            output.join("project").join(LOCAL_ATTRIBUTES_FILE),
+        )?;
+    }
+
+    std::fs::write(output.join("README.md"), readme(&context, layers, redacted))?;
+    Ok(())
+}
+
+/// Description of the package and how to replay the merge
+fn readme(context: &ProjectContext, layers: &[PackedLayer], redacted: bool) -> String {
+    let mut readme = String::from("# Jin reproduction package\n\n");
+    readme.push_str(&format!(
+        "Written by jin {}. Context: mode {}, scope {}, project {}.\n\n",
+        env!("CARGO_PKG_VERSION"),
+        context.mode.as_deref().unwrap_or("(none)"),
+        context.scope.as_deref().unwrap_or("(none)"),
+        context.project.as_deref().unwrap_or("(none)"),
+    ));
+    if redacted {
+        readme.push_str(
+            "Content is redacted: strings and text lines are replaced by hashes, \
+             equal values by equal hashes.\n\n",
+        );
+    }
+    readme.push_str("Layers, lowest precedence first:\n\n");
+    for packed in layers {
+        readme.push_str(&format!("- {} (`{}`)\n", packed.layer, packed.ref_path));
+        for (path, _) in &packed.files {
+            readme.push_str(&format!("  - {}\n", path));
+        }
+    }
+    readme.push_str(
+        "\nReplay the merge:\n\n```bash\ncd project\nJIN_DIR=\"$PWD/../jin\" jin apply\n```\n",
+    );
+    readme
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_files_and_directories() {
+        assert!(matches("config.json", "config.json"));
+        assert!(matches(".vscode", ".vscode/settings.json"));
+        assert!(!matches(".vscode", ".vscode-old/settings.json"));
+        assert!(!matches("config.json", "other.json"));
+    }
+
+    #[test]
+    fn test_redact_keeps_shape() {
+        let json = br#"{"token": "s3cret", "port": 8080, "hosts": ["a", "s3cret"]}"#;
+        let redacted = String::from_utf8(redact(Path::new("app.json"), json)).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&redacted).unwrap();
+        assert!(!redacted.contains("s3cret"));
+        assert_eq!(value["port"], 8080);
+        assert_eq!(value["token"], value["hosts"][1]);
+        assert_ne!(value["token"], value["hosts"][0]);
+
+        let text = redact(Path::new("notes.txt"), b"password=hunter2\n\nhunter2\n");
+        let text = String::from_utf8(text).unwrap();
+        assert!(!text.contains("hunter2"));
+        assert_eq!(text.lines().count(), 3);
+        assert!(text.ends_with('\n'));
+
+        let rules = b"*.json merge=deep\n";
+        assert_eq!(redact(Path::new(".jinattributes"), rules), rules);
+    }
+}
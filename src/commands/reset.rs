@@ -1,14 +1,30 @@
 //! Implementation of `jin reset`
 //!
 //! Resets staged or committed changes with --soft, --mixed, and --hard modes.
+//!
+//! - `--soft` leaves staging and the workspace untouched
+//! - `--mixed` (default) unstages files but keeps them in the workspace
+//! - `--hard` unstages files and restores the workspace to the content of the
+//!   last `jin apply`: applied files are rewritten from the blobs recorded in
+//!   the workspace metadata, and staged files that were never applied are
+//!   deleted
+//!
+//! `--staged <entry>` unstages single entries instead of a whole layer,
+//! addressed as `<layer>:<path>`, by path, or by their number in
+//! `jin status` (see [`StagingIndex::select`]).
 
 use crate::cli::ResetArgs;
+use crate::core::queue::{self, WorkspaceQueue};
 use crate::core::{JinError, Layer, ProjectContext, Result};
 use crate::git::JinRepo;
 use crate::staging::{
-    remove_from_managed_block, validate_workspace_attached, StagedEntry, StagingIndex,
+    remove_from_managed_block, validate_layers_attached, StagedEntry, StagingIndex,
+    WorkspaceMetadata,
 };
+use git2::{ObjectType, Oid};
+use std::collections::BTreeMap;
 use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 /// Reset mode enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -17,10 +33,25 @@ enum ResetMode {
     Soft,
     /// Unstage but keep in workspace (default)
     Mixed,
-    /// Discard all changes (DESTRUCTIVE)
+    /// Unstage and restore the workspace to the last applied state (DESTRUCTIVE)
     Hard,
 }
 
+/// Workspace changes performed by `reset --hard`
+#[derive(Debug, Default, PartialEq, Eq)]
+struct HardResetPlan {
+    /// Files rewritten from the last applied content (path -> blob OID)
+    restore: BTreeMap<PathBuf, String>,
+    /// Staged files that were not part of the last apply
+    remove: Vec<PathBuf>,
+}
+
+impl HardResetPlan {
+    fn is_empty(&self) -> bool {
+        self.restore.is_empty() && self.remove.is_empty()
+    }
+}
+
 /// Execute the reset command
 ///
 /// Resets staged or committed changes.
@@ -35,7 +66,7 @@ enum ResetMode {
 /// - Jin is not initialized
 /// - Invalid layer combination
 /// - No active mode/scope when flags require them
-pub fn execute(args: ResetArgs) -> Result<()> {
+pub fn execute(args: ResetArgs, root: &Path) -> Result<()> {
     // 1. Determine reset mode
     let mode = if args.soft {
         ResetMode::Soft
@@ -46,31 +77,52 @@ pub fn execute(args: ResetArgs) -> Result<()> {
     };
 
     // 2. Load context
-    let context = match ProjectContext::load() {
+    let context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => return Err(JinError::NotInitialized),
         Err(_) => ProjectContext::default(),
     };
 
+    // 2.5. Single entries leave the rest of the layer staged
+    if !args.staged.is_empty() {
+        return unstage_entries(root, &args.staged);
+    }
+
     // 3. Determine target layer
     let layer = determine_target_layer(&args, &context)?;
 
+    // 3.25. --hard rewrites the workspace, so it waits for running applies
+    // (see `crate::core::queue`)
+    let _guard = if mode == ResetMode::Hard && ProjectContext::is_initialized(root) {
+        Some(WorkspaceQueue::open(root).lock("reset --hard", queue::announce_wait)?)
+    } else {
+        None
+    };
+
     // 3.5. Validate workspace is attached before destructive operation (unless --force)
     // CRITICAL: Only validate for Hard mode (destructive) AND when --force is not set
     // CRITICAL: Validation happens BEFORE confirmation prompt - don't prompt if operation will be rejected
     // CRITICAL: When --force is set, skip both validation AND confirmation
+    // Edited applied files are what --hard restores, so they don't count as detachment
     if mode == ResetMode::Hard && !args.force {
-        let repo = JinRepo::open()?;
-        validate_workspace_attached(&context, &repo)?;
+        let repo = JinRepo::open(root)?;
+        validate_layers_attached(root, &context, &repo)?;
         // If --force, skip validation and proceed to load staging
     }
 
     // 4. Load staging
-    let mut staging = StagingIndex::load().unwrap_or_else(|_| StagingIndex::new());
+    let mut staging = StagingIndex::load(root).unwrap_or_else(|_| StagingIndex::new());
 
-    // 5. Get affected entries
+    // 5. Get affected entries (for --hard, also workspace files that drifted
+    // from the last apply)
     let entries: Vec<&StagedEntry> = staging.entries_for_layer(layer);
-    if entries.is_empty() {
+    let plan = if mode == ResetMode::Hard {
+        let metadata = WorkspaceMetadata::load(root).ok();
+        plan_hard_reset(root, &entries, metadata.as_ref())
+    } else {
+        HardResetPlan::default()
+    };
+    if entries.is_empty() && plan.is_empty() {
         println!("Nothing to reset for layer: {}", layer_name(layer));
         return Ok(());
     }
@@ -79,10 +131,12 @@ pub fn execute(args: ResetArgs) -> Result<()> {
     if mode == ResetMode::Hard {
         // Skip confirmation if --force is present
         if !args.force {
-            let count = entries.len();
             let message = format!(
-                "This will discard {} file(s) from staging AND workspace. Type 'yes' to confirm:",
-                count
+                "This will unstage {} file(s), restore {} file(s) to the last applied content \
+                 and delete {} file(s) that were never applied. Type 'yes' to confirm:",
+                entries.len(),
+                plan.restore.len(),
+                plan.remove.len()
             );
             if !prompt_confirmation(&message)? {
                 println!("Reset cancelled");
@@ -101,23 +155,58 @@ pub fn execute(args: ResetArgs) -> Result<()> {
             // Remove from staging, keep in workspace
             let count = entries.len();
             reset_staging(&mut staging, layer)?;
-            staging.save()?;
+            staging.save(root)?;
             println!("Unstaged {} file(s) (kept in workspace)", count);
         }
         ResetMode::Hard => {
-            // Remove from staging AND workspace
+            // Remove from staging, then restore the workspace
             let count = entries.len();
+            reset_staging(&mut staging, layer)?;
+            staging.save(root)?;
+
+            let restored = if plan.restore.is_empty() {
+                0
+            } else {
+                restore_workspace(root, &plan.restore, &JinRepo::open(root)?)?
+            };
+            reset_workspace(root, &plan.remove)?;
+
+            println!("Discarded {} file(s) from staging", count);
+            if restored > 0 {
+                println!("Restored {} file(s) to the last applied content", restored);
+            }
+            if !plan.remove.is_empty() {
+                println!(
+                    "Deleted {} file(s) that were never applied",
+                    plan.remove.len()
+                );
+            }
+        }
+    }
 
-            // Clone entries before modifying staging to avoid borrow issues
-            let entries_to_reset: Vec<StagedEntry> = entries.iter().map(|e| (*e).clone()).collect();
+    Ok(())
+}
 
-            reset_staging(&mut staging, layer)?;
-            reset_workspace(&entries_to_reset)?;
-            staging.save()?;
-            println!("Discarded {} file(s) from staging and workspace", count);
+/// Unstage the addressed entries, keeping the files in the workspace
+///
+/// Every entry is resolved before any is removed, so numbers refer to the
+/// list `jin status` showed.
+fn unstage_entries(root: &Path, specs: &[String]) -> Result<()> {
+    let mut staging = StagingIndex::load(root)?;
+    let mut selected = Vec::new();
+    for spec in specs {
+        let entry = staging.select(spec)?;
+        let key = (entry.target_layer, entry.path.clone());
+        if !selected.contains(&key) {
+            selected.push(key);
         }
     }
 
+    for (layer, path) in &selected {
+        staging.remove_entry(*layer, path);
+        println!("Unstaged {}:{}", layer, path.display());
+    }
+    staging.save(root)?;
     Ok(())
 }
 
@@ -183,23 +272,119 @@ fn reset_staging(staging: &mut StagingIndex, layer: Layer) -> Result<()> {
     Ok(())
 }
 
+/// Decide how `reset --hard` brings the workspace back to the last apply
+///
+/// Staged files that were applied are restored from their recorded blob;
+/// staged files that were never applied are removed. Applied files that
+/// were modified or deleted in the workspace are restored even if they are
+/// not staged. Without workspace metadata, staged files are simply removed.
+fn plan_hard_reset(
+    root: &Path,
+    entries: &[&StagedEntry],
+    metadata: Option<&WorkspaceMetadata>,
+) -> HardResetPlan {
+    let mut plan = HardResetPlan::default();
+
+    for entry in entries {
+        let path = normalize(&entry.path);
+        match metadata.and_then(|m| Some((m.location(path), m.files.get(path)?))) {
+            Some((location, hash)) => {
+                plan.restore.insert(location, hash.clone());
+            }
+            None => plan.remove.push(entry.path.clone()),
+        }
+    }
+
+    if let Some(metadata) = metadata {
+        for (path, hash) in &metadata.files {
+            let location = metadata.location(path);
+            if !plan.restore.contains_key(&location)
+                && workspace_differs(&root.join(&location), hash)
+            {
+                plan.restore.insert(location, hash.clone());
+            }
+        }
+    }
+
+    plan
+}
+
+/// Strip a leading `./` so staged paths match workspace metadata keys
+fn normalize(path: &Path) -> &Path {
+    path.strip_prefix(".").unwrap_or(path)
+}
+
+/// Whether a workspace file is missing or no longer matches its applied hash
+fn workspace_differs(path: &Path, expected: &str) -> bool {
+    match std::fs::read(path) {
+        Ok(content) => Oid::hash_object(ObjectType::Blob, &content)
+            .map(|oid| oid.to_string() != expected)
+            .unwrap_or(true),
+        Err(_) => true,
+    }
+}
+
+/// Rewrite workspace files from their last applied blobs
+///
+/// Returns the number of files restored.
+fn restore_workspace(
+    root: &Path,
+    files: &BTreeMap<PathBuf, String>,
+    repo: &JinRepo,
+) -> Result<usize> {
+    let mut restored = 0;
+    let mut errors = Vec::new();
+
+    for (path, hash) in files {
+        let result = Oid::from_str(hash)
+            .and_then(|oid| repo.inner().find_blob(oid))
+            .map_err(JinError::from)
+            .and_then(|blob| write_file(&root.join(path), blob.content()));
+        match result {
+            Ok(()) => restored += 1,
+            Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    if !errors.is_empty() {
+        eprintln!("Errors during workspace restore:");
+        for error in &errors {
+            eprintln!("  {}", error);
+        }
+    }
+
+    Ok(restored)
+}
+
+/// Write a file atomically, creating parent directories
+fn write_file(path: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temp_path = path.with_extension("jin-tmp");
+    std::fs::write(&temp_path, content)?;
+    std::fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
 /// Reset workspace files (delete them)
-fn reset_workspace(entries: &[StagedEntry]) -> Result<()> {
+fn reset_workspace(root: &Path, paths: &[PathBuf]) -> Result<()> {
     let mut errors = Vec::new();
 
-    for entry in entries {
+    for path in paths {
         // Remove from workspace
-        if entry.path.exists() {
-            if let Err(e) = std::fs::remove_file(&entry.path) {
-                errors.push(format!("{}: {}", entry.path.display(), e));
+        let location = root.join(path);
+        if location.exists() {
+            if let Err(e) = std::fs::remove_file(&location) {
+                errors.push(format!("{}: {}", path.display(), e));
             }
         }
 
         // Remove from .gitignore managed block
-        if let Err(e) = remove_from_managed_block(&entry.path) {
+        if let Err(e) = remove_from_managed_block(root, path) {
             errors.push(format!(
                 "{}: Failed to update .gitignore: {}",
-                entry.path.display(),
+                path.display(),
                 e
             ));
         }
@@ -260,9 +445,10 @@ mod tests {
             scope: None,
             project: false,
             global: false,
+            staged: Vec::new(),
             force: false,
         };
-        let result = execute(args);
+        let result = execute(args, temp.path());
         assert!(matches!(result, Err(JinError::NotInitialized)));
     }
 
@@ -277,6 +463,7 @@ mod tests {
             scope: None,
             project: false,
             global: false,
+            staged: Vec::new(),
             force: false,
         };
         let result = determine_target_layer(&args, &context).unwrap();
@@ -294,6 +481,7 @@ mod tests {
             scope: None,
             project: false,
             global: true,
+            staged: Vec::new(),
             force: false,
         };
         let result = determine_target_layer(&args, &context).unwrap();
@@ -313,6 +501,7 @@ mod tests {
             scope: None,
             project: false,
             global: false,
+            staged: Vec::new(),
             force: false,
         };
         let result = determine_target_layer(&args, &context).unwrap();
@@ -332,6 +521,7 @@ mod tests {
             scope: Some("lang:rust".to_string()),
             project: false,
             global: false,
+            staged: Vec::new(),
             force: false,
         };
         let result = determine_target_layer(&args, &context).unwrap();
@@ -351,6 +541,7 @@ mod tests {
             scope: None,
             project: true,
             global: false,
+            staged: Vec::new(),
             force: false,
         };
         let result = determine_target_layer(&args, &context).unwrap();
@@ -368,6 +559,7 @@ mod tests {
             scope: None,
             project: true,
             global: false,
+            staged: Vec::new(),
             force: false,
         };
         let result = determine_target_layer(&args, &context);
@@ -389,6 +581,41 @@ mod tests {
         assert_eq!(layer_name(Layer::ProjectBase), "project-base");
     }
 
+    #[test]
+    fn test_plan_hard_reset() {
+        let temp = TempDir::new().unwrap();
+        let applied = temp.path().join("applied.json");
+        let drifted = temp.path().join("drifted.json");
+        let fresh = temp.path().join("fresh.json");
+        std::fs::write(&applied, "{}").unwrap();
+        std::fs::write(&drifted, "{\"changed\": true}").unwrap();
+
+        let hash = Oid::hash_object(ObjectType::Blob, b"{}")
+            .unwrap()
+            .to_string();
+        let mut metadata = WorkspaceMetadata::new();
+        metadata.add_file(applied.clone(), hash.clone());
+        metadata.add_file(drifted.clone(), hash.clone());
+
+        let staged = StagedEntry {
+            path: fresh.clone(),
+            target_layer: Layer::ProjectBase,
+            content_hash: hash.clone(),
+            mode: 0o644,
+            operation: crate::staging::StagedOperation::AddOrModify,
+        };
+        let plan = plan_hard_reset(temp.path(), &[&staged], Some(&metadata));
+
+        // Unmodified applied files are left alone
+        assert_eq!(plan.restore.keys().collect::<Vec<_>>(), vec![&drifted]);
+        assert_eq!(plan.remove, vec![fresh.clone()]);
+
+        // Without metadata, staged files are removed
+        let plan = plan_hard_reset(temp.path(), &[&staged], None);
+        assert!(plan.restore.is_empty());
+        assert_eq!(plan.remove, vec![fresh]);
+    }
+
     #[test]
     #[serial]
     fn test_reset_hard_with_force() {
@@ -409,7 +636,7 @@ mod tests {
             operation: crate::staging::StagedOperation::AddOrModify,
         };
         staging.add(entry);
-        staging.save().unwrap();
+        staging.save(&ctx.project_path).unwrap();
 
         // Reset hard with force flag (should not prompt)
         let args = ResetArgs {
@@ -420,9 +647,10 @@ mod tests {
             scope: None,
             project: false,
             global: false,
+            staged: Vec::new(),
             force: true,
         };
-        let result = execute(args);
+        let result = execute(args, &ctx.project_path);
         assert!(result.is_ok());
 
         // Verify file was deleted
@@ -1,23 +1,27 @@
 //! Implementation of `jin rm`
 //!
-//! This command removes files from the staging index and optionally from the workspace.
-//! Files are marked for deletion with StagedOperation::Delete entries.
-//! Similar to git rm, the default behavior removes from staging only (like git rm --cached),
-//! while --force removes from both staging and workspace.
+//! This command removes files from a layer, routed with the same flags as
+//! `jin add`. Files committed to the layer or staged for it are marked for
+//! deletion with StagedOperation::Delete entries; the next commit drops them
+//! from the layer and from `.jinmap`. Like git rm, the workspace copy is
+//! deleted too (after confirmation, or right away with --force), while
+//! --cached keeps it.
 
 use crate::cli::RmArgs;
-use crate::core::{JinError, Layer, ProjectContext, Result};
-use crate::git::JinRepo;
+use crate::core::{BranchScope, JinError, Layer, ProjectContext, Result};
+use crate::git::{JinRepo, ObjectOps, RefOps, TreeOps};
 use crate::staging::{
     remove_from_managed_block, route_to_layer, validate_routing_options, RoutingOptions,
     StagedEntry, StagingIndex,
 };
+use std::collections::HashSet;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 
 /// Execute the rm command
 ///
-/// Removes files from staging and optionally from workspace.
+/// Stages deletions from the target layer and removes the workspace copies
+/// unless `--cached` is given.
 ///
 /// # Arguments
 ///
@@ -27,17 +31,17 @@ use std::path::{Path, PathBuf};
 ///
 /// Returns an error if:
 /// - No files are specified
-/// - A file is not in staging
+/// - A file is neither in the target layer nor in staging
 /// - Routing options are invalid
 /// - Jin is not initialized
-pub fn execute(args: RmArgs) -> Result<()> {
+pub fn execute(args: RmArgs, root: &Path) -> Result<()> {
     // 1. Validate we have files to remove
     if args.files.is_empty() {
         return Err(JinError::Other("No files specified".to_string()));
     }
 
     // 2. Load project context for active mode/scope
-    let context = match ProjectContext::load() {
+    let mut context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -58,25 +62,36 @@ pub fn execute(args: RmArgs) -> Result<()> {
     // 4. Determine target layer
     let target_layer = route_to_layer(&options, &context)?;
 
-    // 5. Open Jin repository (needed for consistency with other commands)
-    let _repo = JinRepo::open_or_create()?;
+    // 5. Open Jin repository and read the files committed to the layer
+    let repo = JinRepo::open_or_create(root)?;
+    if args.scope.is_none() {
+        // Scope layers of a branch with its own scope are committed there
+        BranchScope::activate(root, &mut context, &repo);
+    }
+    let committed = committed_files(&repo, target_layer, &context);
 
     // 6. Load staging index
-    let mut staging = StagingIndex::load().unwrap_or_else(|_| StagingIndex::new());
+    let mut staging = StagingIndex::load(root).unwrap_or_else(|_| StagingIndex::new());
+    let tracked =
+        |path: &Path| staging.get(path).is_some() || committed.contains(&layer_path(path));
 
     // 7. Dry-run mode: print what would be removed and return
     if args.dry_run {
         for path_str in &args.files {
             let path = PathBuf::from(path_str);
-            if staging.get(&path).is_some() {
-                let workspace_action = if args.force && path.exists() {
+            if tracked(&path) {
+                let workspace_action = if !args.cached && root.join(&path).exists() {
                     "and from workspace"
                 } else {
-                    "from staging only"
+                    "from the layer only"
                 };
                 println!("Would remove: {} ({})", path.display(), workspace_action);
             } else {
-                eprintln!("Warning: {} not in staging", path.display());
+                eprintln!(
+                    "Warning: {} not in {} layer or staging",
+                    path.display(),
+                    format_layer_name(target_layer)
+                );
             }
         }
         return Ok(());
@@ -86,18 +101,14 @@ pub fn execute(args: RmArgs) -> Result<()> {
     let files_to_remove_from_workspace: Vec<PathBuf> = args
         .files
         .iter()
-        .filter(|path_str| {
-            let path = PathBuf::from(path_str);
-            // Check if file is in staging and exists in workspace
-            staging.get(&path).is_some() && path.exists() && args.force
-        })
-        .map(|s| PathBuf::from(s.as_str()))
+        .map(PathBuf::from)
+        .filter(|path| !args.cached && tracked(path) && root.join(path).exists())
         .collect();
 
     // 9. Confirmation prompt for workspace deletion (without --force)
     if !files_to_remove_from_workspace.is_empty() && !args.force {
         let message = format!(
-            "This will remove {} file(s) from workspace. Type 'yes' to confirm:",
+            "This will remove {} file(s) from workspace (use --cached to keep them). Type 'yes' to confirm:",
             files_to_remove_from_workspace.len()
         );
         if !prompt_confirmation(&message)? {
@@ -112,14 +123,15 @@ pub fn execute(args: RmArgs) -> Result<()> {
 
     for path_str in &args.files {
         let path = PathBuf::from(path_str);
-        match unstage_file(&path, target_layer, &mut staging, &args) {
+        let in_layer = committed.contains(&layer_path(&path));
+        match unstage_file(root, &path, target_layer, in_layer, &mut staging, &args) {
             Ok(_) => removed_count += 1,
             Err(e) => errors.push(format!("{}: {}", path.display(), e)),
         }
     }
 
     // 11. Save staging index
-    staging.save()?;
+    staging.save(root)?;
 
     // 12. Print summary
     if removed_count > 0 {
@@ -146,17 +158,48 @@ pub fn execute(args: RmArgs) -> Result<()> {
     Ok(())
 }
 
-/// Unstage a single file from the staging index
+/// Path of a workspace file inside a layer tree
+fn layer_path(path: &Path) -> String {
+    path.strip_prefix(".").unwrap_or(path).display().to_string()
+}
+
+/// Files committed to `layer` in the given context (empty if it has none)
+fn committed_files(repo: &JinRepo, layer: Layer, context: &ProjectContext) -> HashSet<String> {
+    let ref_path = layer.ref_path(
+        context.mode.as_deref(),
+        context.scope.as_deref(),
+        context.project.as_deref(),
+    );
+    if !repo.ref_exists(&ref_path) {
+        return HashSet::new();
+    }
+    repo.resolve_ref(&ref_path)
+        .and_then(|oid| Ok(repo.find_commit(oid)?.tree_id()))
+        .and_then(|tree_oid| repo.list_tree_files(tree_oid))
+        .map(|files| files.into_iter().collect())
+        .unwrap_or_default()
+}
+
+/// Stage the deletion of a single file from the target layer
+///
+/// `in_layer` tells whether the file is committed to the layer; otherwise
+/// it must be staged.
 fn unstage_file(
+    root: &Path,
     path: &Path,
     layer: Layer,
+    in_layer: bool,
     staging: &mut StagingIndex,
     args: &RmArgs,
 ) -> Result<()> {
-    // Check if file is in staging
-    let _existing_entry = staging
-        .get(path)
-        .ok_or_else(|| JinError::NotFound(format!("File not in staging: {}", path.display())))?;
+    // Check if file is in the layer or in staging
+    if !in_layer && staging.get(path).is_none() {
+        return Err(JinError::NotFound(format!(
+            "File not in {} layer or staging: {}",
+            format_layer_name(layer),
+            path.display()
+        )));
+    }
 
     // Remove from staging index
     staging.remove(path);
@@ -166,13 +209,14 @@ fn unstage_file(
     staging.add(delete_entry);
 
     // Remove from .gitignore managed block
-    if let Err(e) = remove_from_managed_block(path) {
+    if let Err(e) = remove_from_managed_block(root, path) {
         eprintln!("Warning: Could not update .gitignore: {}", e);
     }
 
-    // Remove from workspace if --force or confirmed
-    if args.force && path.exists() {
-        std::fs::remove_file(path)?;
+    // Remove from workspace unless --cached (confirmed by the caller)
+    let location = root.join(path);
+    if !args.cached && location.exists() {
+        std::fs::remove_file(&location)?;
     }
 
     Ok(())
@@ -220,9 +264,10 @@ mod tests {
             global: false,
             local: false,
             force: false,
+            cached: false,
             dry_run: false,
         };
-        let result = execute(args);
+        let result = execute(args, Path::new("."));
         assert!(result.is_err());
     }
 
@@ -239,9 +284,10 @@ mod tests {
             global: false,
             local: false,
             force: false,
+            cached: false,
             dry_run: false,
         };
-        let result = execute(args);
+        let result = execute(args, temp.path());
         assert!(matches!(result, Err(JinError::NotInitialized)));
     }
 
@@ -267,11 +313,19 @@ mod tests {
             global: false,
             local: false,
             force: false,
+            cached: false,
             dry_run: false,
         };
 
         let file_path = project_path.join("test.json");
-        let result = unstage_file(&file_path, Layer::ProjectBase, &mut staging, &args);
+        let result = unstage_file(
+            &ctx.project_path,
+            &file_path,
+            Layer::ProjectBase,
+            false,
+            &mut staging,
+            &args,
+        );
         assert!(matches!(result, Err(JinError::NotFound(_))));
     }
 
@@ -304,11 +358,19 @@ mod tests {
             global: false,
             local: false,
             force: false,
+            cached: true,
             dry_run: false,
         };
 
-        // Unstage without force (should not delete from workspace)
-        let result = unstage_file(&test_file, Layer::ProjectBase, &mut staging, &args);
+        // Unstage with --cached (should not delete from workspace)
+        let result = unstage_file(
+            &ctx.project_path,
+            &test_file,
+            Layer::ProjectBase,
+            false,
+            &mut staging,
+            &args,
+        );
         assert!(result.is_ok());
 
         // File should still exist in workspace
@@ -349,11 +411,19 @@ mod tests {
             global: false,
             local: false,
             force: true,
+            cached: false,
             dry_run: false,
         };
 
         // Unstage with force (should delete from workspace)
-        let result = unstage_file(&test_file, Layer::ProjectBase, &mut staging, &args);
+        let result = unstage_file(
+            &ctx.project_path,
+            &test_file,
+            Layer::ProjectBase,
+            false,
+            &mut staging,
+            &args,
+        );
         assert!(result.is_ok());
 
         // File should be deleted from workspace
@@ -384,7 +454,7 @@ mod tests {
             operation: crate::staging::StagedOperation::AddOrModify,
         };
         staging.add(entry);
-        staging.save().unwrap();
+        staging.save(&ctx.project_path).unwrap();
 
         // Pass the full path string to match the staged path
         let args = RmArgs {
@@ -395,21 +465,22 @@ mod tests {
             global: false,
             local: false,
             force: false,
+            cached: false,
             dry_run: true,
         };
 
-        let result = execute(args);
+        let result = execute(args, &ctx.project_path);
         assert!(result.is_ok());
 
         // File should still be in staging after dry run
-        let staging_after = StagingIndex::load().unwrap();
+        let staging_after = StagingIndex::load(&ctx.project_path).unwrap();
         assert!(staging_after.get(&test_file).is_some());
     }
 
     #[test]
     #[serial]
     fn test_execute_project_without_mode() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
         let args = RmArgs {
             files: vec!["file.txt".to_string()],
@@ -419,16 +490,17 @@ mod tests {
             global: false,
             local: false,
             force: false,
+            cached: false,
             dry_run: false,
         };
-        let result = execute(args);
+        let result = execute(args, &ctx.project_path);
         assert!(result.is_err());
     }
 
     #[test]
     #[serial]
     fn test_execute_global_with_mode() {
-        let _ctx = crate::test_utils::setup_unit_test();
+        let ctx = crate::test_utils::setup_unit_test();
 
         let args = RmArgs {
             files: vec!["file.txt".to_string()],
@@ -438,9 +510,10 @@ mod tests {
             global: true,
             local: false,
             force: false,
+            cached: false,
             dry_run: false,
         };
-        let result = execute(args);
+        let result = execute(args, &ctx.project_path);
         assert!(result.is_err());
     }
 }
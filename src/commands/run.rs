@@ -0,0 +1,184 @@
+//! Implementation of `jin run`
+//!
+//! Runs a command with the merged configuration without touching the
+//! workspace. The merged files are written to a temporary directory, passed
+//! to the child as `JIN_RUN_DIR`, and removed once it exits. The child also
+//! gets the variables of the environment layers (see [`crate::merge::env`])
+//! and of merged env-format files (`.env`, `*.env`), so
+//! `jin run --mode prod -- ./deploy.sh` sees the prod variables.
+
+use crate::cli::RunArgs;
+use crate::commands::env::load_vars;
+use crate::core::{BranchScope, JinError, ProjectContext, Result};
+use crate::git::{JinRepo, RefOps};
+use crate::merge::env::{env_vars, EnvBackend, ENV_PATH};
+use crate::merge::{
+    get_applicable_layers, merge_layers, serialize_content, FormatBackend, LayerMergeConfig,
+    MergedFile,
+};
+use crate::staging::attributes::ATTRIBUTES_PATH;
+use crate::staging::attributes::{glob_to_regex, normalize};
+use crate::staging::targets::TARGETS_PATH;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Variable pointing the child at the materialized files
+pub const RUN_DIR_VAR: &str = "JIN_RUN_DIR";
+
+/// Execute the run command
+///
+/// Exits with the child's exit code once the temporary directory is gone.
+pub fn execute(args: RunArgs, root: &Path) -> Result<()> {
+    let mut context = ProjectContext::load(root)?;
+    let repo = JinRepo::open(root)?;
+    BranchScope::activate(root, &mut context, &repo);
+    if let Some(mode) = args.mode {
+        if !repo.ref_exists(&format!("refs/jin/modes/{}/_mode", mode)) {
+            return Err(JinError::NotFound(format!("Mode '{}' not found", mode)));
+        }
+        context.mode = Some(mode);
+    }
+    if let Some(scope) = args.scope {
+        context.scope = Some(scope);
+    }
+
+    let patterns = args
+        .only
+        .iter()
+        .map(|p| glob_to_regex(p).map_err(|e| JinError::Config(format!("--only {}: {}", p, e))))
+        .collect::<Result<Vec<_>>>()?;
+
+    let merged = merge_layers(
+        &LayerMergeConfig {
+            layers: get_applicable_layers(
+                context.mode.as_deref(),
+                context.scope.as_deref(),
+                context.project.as_deref(),
+            ),
+            mode: context.mode.clone(),
+            scope: context.scope.clone(),
+            project: context.project.clone(),
+            root: root.to_path_buf(),
+        },
+        &repo,
+    )?;
+    for path in &merged.conflict_files {
+        eprintln!(
+            "Warning: {} has unresolved layer conflicts and was left out",
+            path.display()
+        );
+    }
+
+    let mut files: Vec<(&PathBuf, &MergedFile)> = merged
+        .merged_files
+        .iter()
+        .filter(|(path, _)| {
+            ![TARGETS_PATH, ATTRIBUTES_PATH, ENV_PATH]
+                .iter()
+                .any(|rules| path.as_os_str() == *rules)
+        })
+        .filter(|(path, _)| {
+            patterns.is_empty() || patterns.iter().any(|re| re.is_match(&normalize(path)))
+        })
+        .collect();
+    files.sort_by_key(|(path, _)| *path);
+
+    let run_dir = RunDir::create()?;
+    let mut env: Vec<(String, String)> = load_vars(&context, &repo)?
+        .into_iter()
+        .map(|var| (var.name, var.value))
+        .collect();
+    for (path, file) in &files {
+        let content = serialize_content(&file.content, file.format)?;
+        let dest = run_dir.path.join(path);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&dest, &content)?;
+        if is_env_file(path) {
+            match EnvBackend.parse(&content) {
+                Ok(vars) => env.extend(env_vars(&vars)),
+                Err(e) => eprintln!(
+                    "Warning: Could not read variables from {}: {}",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+    }
+
+    let (program, program_args) = args
+        .command
+        .split_first()
+        .ok_or_else(|| JinError::Other("No command given".to_string()))?;
+    let mut command = Command::new(program);
+    command
+        .args(program_args)
+        .env(RUN_DIR_VAR, &run_dir.path)
+        .envs(env);
+    command.current_dir(if args.in_dir { &run_dir.path } else { root });
+    let status = command
+        .status()
+        .map_err(|e| JinError::Other(format!("Failed to run '{}': {}", program, e)))?;
+
+    drop(run_dir);
+    match status.code() {
+        Some(0) => Ok(()),
+        Some(code) => std::process::exit(code),
+        None => Err(JinError::Other(format!(
+            "'{}' was terminated by a signal",
+            program
+        ))),
+    }
+}
+
+/// Temporary directory holding the merged files, removed on drop
+struct RunDir {
+    path: PathBuf,
+}
+
+impl RunDir {
+    fn create() -> Result<Self> {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or_default();
+        let path = std::env::temp_dir().join(format!("jin-run-{}-{}", std::process::id(), nanos));
+        std::fs::create_dir_all(&path)?;
+        Ok(Self { path })
+    }
+}
+
+impl Drop for RunDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Whether a file holds environment variables (`.env`, `prod.env`)
+fn is_env_file(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == ".env")
+        || path.extension().is_some_and(|ext| ext == "env")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_env_file() {
+        assert!(is_env_file(Path::new(".env")));
+        assert!(is_env_file(Path::new("config/prod.env")));
+        assert!(!is_env_file(Path::new(".envrc")));
+        assert!(!is_env_file(Path::new("settings.json")));
+    }
+
+    #[test]
+    fn test_run_dir_removed_on_drop() {
+        let run_dir = RunDir::create().unwrap();
+        let path = run_dir.path.clone();
+        assert!(path.is_dir());
+        drop(run_dir);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,33 @@
+//! Implementation of `jin schema`
+//!
+//! Prints the JSON Schema of a command's JSON output, or lists the commands
+//! with JSON output and their current `schema_version`. See
+//! [`crate::core::schema`] for the compatibility rules.
+
+use crate::cli::SchemaArgs;
+use crate::core::schema::JsonSurface;
+use crate::core::{JinError, Result};
+
+/// Execute the schema command
+pub fn execute(args: SchemaArgs) -> Result<()> {
+    let Some(command) = args.command else {
+        println!("JSON outputs (run 'jin schema <command>' for the schema):");
+        for surface in JsonSurface::all() {
+            println!(
+                "  {:<8} v{}  {}",
+                surface.name(),
+                surface.version(),
+                surface.command()
+            );
+        }
+        return Ok(());
+    };
+
+    let schema = command.surface().schema();
+    let json = serde_json::to_string_pretty(&schema).map_err(|e| JinError::Parse {
+        format: "JSON".to_string(),
+        message: e.to_string(),
+    })?;
+    println!("{}", json);
+    Ok(())
+}
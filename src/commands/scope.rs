@@ -1,19 +1,28 @@
 //! Implementation of `jin scope` subcommands
 
 use crate::cli::ScopeAction;
+use crate::core::usage::{self, UsageKind};
 use crate::core::{JinError, ProjectContext, Result};
+use crate::git::archive::{ArchiveKind, ArchiveSet};
 use crate::git::{JinRepo, ObjectOps, RefOps};
 use crate::staging::metadata::WorkspaceMetadata;
+use std::path::Path;
 
 /// Execute a scope subcommand
-pub fn execute(action: ScopeAction) -> Result<()> {
+pub fn execute(action: ScopeAction, root: &Path) -> Result<()> {
     match action {
-        ScopeAction::Create { name, mode } => create(&name, mode.as_deref()),
-        ScopeAction::Use { name } => use_scope(&name),
-        ScopeAction::List => list(),
-        ScopeAction::Delete { name } => delete(&name),
-        ScopeAction::Show => show(),
-        ScopeAction::Unset => unset(),
+        ScopeAction::Create { name, mode } => create(root, &name, mode.as_deref()),
+        ScopeAction::Use { name, apply } => {
+            use_scope(root, &name)?;
+            if apply {
+                super::apply::apply_workspace(root)?;
+            }
+            Ok(())
+        }
+        ScopeAction::List => list(root),
+        ScopeAction::Delete { name } => delete(root, &name),
+        ScopeAction::Show => show(root),
+        ScopeAction::Unset => unset(root),
     }
 }
 
@@ -23,7 +32,7 @@ pub fn execute(action: ScopeAction) -> Result<()> {
 /// - Non-empty
 /// - Alphanumeric, underscores, and colons only
 /// - Not reserved names
-fn validate_scope_name(name: &str) -> Result<()> {
+pub(crate) fn validate_scope_name(name: &str) -> Result<()> {
     // Check for empty name
     if name.is_empty() {
         return Err(JinError::Other("Scope name cannot be empty".to_string()));
@@ -69,12 +78,12 @@ fn validate_mode_name(name: &str) -> Result<()> {
 }
 
 /// Create a new scope
-fn create(name: &str, mode: Option<&str>) -> Result<()> {
+pub(crate) fn create(root: &Path, name: &str, mode: Option<&str>) -> Result<()> {
     // Validate scope name
     validate_scope_name(name)?;
 
     // Open Jin repository
-    let repo = JinRepo::open_or_create()?;
+    let repo = JinRepo::open_or_create(root)?;
 
     // Convert scope name to ref-safe format (replace colons with slashes)
     let ref_safe_name = name.replace(':', "/");
@@ -127,6 +136,9 @@ fn create(name: &str, mode: Option<&str>) -> Result<()> {
     };
 
     repo.set_ref(&ref_path, commit_oid, &reflog_message)?;
+    usage::record(repo.path(), |log| {
+        log.mark_created(UsageKind::Scope, name, chrono::Utc::now())
+    });
 
     // Print success message
     if let Some(mode_name) = mode {
@@ -140,12 +152,12 @@ fn create(name: &str, mode: Option<&str>) -> Result<()> {
 }
 
 /// Activate a scope
-fn use_scope(name: &str) -> Result<()> {
+pub(crate) fn use_scope(root: &Path, name: &str) -> Result<()> {
     // Validate scope name
     validate_scope_name(name)?;
 
     // Open Jin repository
-    let repo = JinRepo::open_or_create()?;
+    let repo = JinRepo::open_or_create(root)?;
 
     // Convert scope name to ref-safe format (replace colons with slashes)
     let ref_safe_name = name.replace(':', "/");
@@ -168,7 +180,7 @@ fn use_scope(name: &str) -> Result<()> {
     }
 
     // Load project context
-    let mut context = match ProjectContext::load() {
+    let mut context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -180,10 +192,13 @@ fn use_scope(name: &str) -> Result<()> {
     context.scope = Some(name.to_string());
 
     // Save context
-    context.save()?;
+    context.save(root)?;
+    usage::record(repo.path(), |log| {
+        log.mark_used(UsageKind::Scope, name, chrono::Utc::now())
+    });
 
     // Load workspace metadata (may not exist yet)
-    let metadata = match WorkspaceMetadata::load() {
+    let metadata = match WorkspaceMetadata::load(root) {
         Ok(meta) => Some(meta),
         Err(JinError::NotFound(_)) => None, // Fresh workspace - no metadata yet
         Err(e) => return Err(e),            // Other errors should propagate
@@ -204,7 +219,7 @@ fn use_scope(name: &str) -> Result<()> {
         if let Some(old_scope) = metadata_scope {
             if old_scope != name {
                 // Scopes differ - clear metadata to prevent detached state
-                let metadata_path = WorkspaceMetadata::default_path();
+                let metadata_path = WorkspaceMetadata::default_path(root);
                 if metadata_path.exists() {
                     std::fs::remove_file(&metadata_path)?;
                     println!(
@@ -217,7 +232,7 @@ fn use_scope(name: &str) -> Result<()> {
         } else {
             // No scope layer in metadata (only global or mode layers)
             // Clear metadata since we're now activating a scope
-            let metadata_path = WorkspaceMetadata::default_path();
+            let metadata_path = WorkspaceMetadata::default_path(root);
             if metadata_path.exists() {
                 std::fs::remove_file(&metadata_path)?;
                 println!("Cleared workspace metadata (activating scope '{}').", name);
@@ -233,12 +248,12 @@ fn use_scope(name: &str) -> Result<()> {
 }
 
 /// List all scopes
-pub fn list() -> Result<()> {
+pub fn list(root: &Path) -> Result<()> {
     // Open Jin repository
-    let repo = JinRepo::open_or_create()?;
+    let repo = JinRepo::open_or_create(root)?;
 
     // Load project context to identify active scope
-    let context = match ProjectContext::load() {
+    let context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -262,19 +277,29 @@ pub fn list() -> Result<()> {
 
     println!("Available scopes:");
 
+    // Archived scopes are listed separately
+    let archive_set = ArchiveSet::load_or_default(&repo);
+    let mut archived = Vec::new();
+    let mut show = |display_name: String, origin: String| {
+        let line = if Some(display_name.as_str()) == context.scope.as_deref() {
+            format!("  * {} ({}) [active]", display_name, origin)
+        } else {
+            format!("    {} ({})", display_name, origin)
+        };
+        if archive_set.contains(ArchiveKind::Scope, &display_name) {
+            archived.push(line);
+        } else {
+            println!("{}", line);
+        }
+    };
+
     // Display untethered scopes
     for ref_path in untethered_refs {
         let ref_safe_name = ref_path
             .strip_prefix("refs/jin/scopes/")
             .unwrap_or(&ref_path);
         // Convert back from ref-safe format (slashes to colons)
-        let display_name = ref_safe_name.replace('/', ":");
-
-        if Some(display_name.as_str()) == context.scope.as_deref() {
-            println!("  * {} (untethered) [active]", display_name);
-        } else {
-            println!("    {} (untethered)", display_name);
-        }
+        show(ref_safe_name.replace('/', ":"), "untethered".to_string());
     }
 
     // Display mode-bound scopes
@@ -284,28 +309,32 @@ pub fn list() -> Result<()> {
             if let Some(mode_end) = rest.find("/scopes/") {
                 let mode_name = &rest[..mode_end];
                 let ref_safe_scope = &rest[mode_end + 8..]; // Skip "/scopes/"
-                                                            // Convert back from ref-safe format (slashes to colons)
-                let display_name = ref_safe_scope.replace('/', ":");
-
-                if Some(display_name.as_str()) == context.scope.as_deref() {
-                    println!("  * {} (mode: {}) [active]", display_name, mode_name);
-                } else {
-                    println!("    {} (mode: {})", display_name, mode_name);
-                }
+                show(
+                    ref_safe_scope.replace('/', ":"),
+                    format!("mode: {}", mode_name),
+                );
             }
         }
     }
 
+    if !archived.is_empty() {
+        println!();
+        println!("Archived scopes (read-only):");
+        for line in archived {
+            println!("{}", line);
+        }
+    }
+
     Ok(())
 }
 
 /// Delete a scope
-fn delete(name: &str) -> Result<()> {
+fn delete(root: &Path, name: &str) -> Result<()> {
     // Validate scope name
     validate_scope_name(name)?;
 
     // Open Jin repository
-    let repo = JinRepo::open_or_create()?;
+    let repo = JinRepo::open_or_create(root)?;
 
     // Convert scope name to ref-safe format (replace colons with slashes)
     let ref_safe_name = name.replace(':', "/");
@@ -331,7 +360,7 @@ fn delete(name: &str) -> Result<()> {
     }
 
     // Load project context to check if active
-    let mut context = match ProjectContext::load() {
+    let mut context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -343,7 +372,7 @@ fn delete(name: &str) -> Result<()> {
     if Some(name) == context.scope.as_deref() {
         println!("Scope '{}' is currently active. Deactivating...", name);
         context.scope = None;
-        context.save()?;
+        context.save(root)?;
     }
 
     // Delete all refs
@@ -375,15 +404,17 @@ fn delete(name: &str) -> Result<()> {
         }
     }
 
+    usage::record(repo.path(), |log| log.remove(UsageKind::Scope, name));
+
     println!("Deleted scope '{}'", name);
 
     Ok(())
 }
 
 /// Show currently active scope
-fn show() -> Result<()> {
+fn show(root: &Path) -> Result<()> {
     // Load project context
-    let context = match ProjectContext::load() {
+    let context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -400,9 +431,9 @@ fn show() -> Result<()> {
 }
 
 /// Unset (deactivate) current scope
-fn unset() -> Result<()> {
+fn unset(root: &Path) -> Result<()> {
     // Load project context
-    let mut context = match ProjectContext::load() {
+    let mut context = match ProjectContext::load(root) {
         Ok(ctx) => ctx,
         Err(JinError::NotInitialized) => {
             return Err(JinError::NotInitialized);
@@ -420,7 +451,7 @@ fn unset() -> Result<()> {
     context.scope = None;
 
     // Save context
-    context.save()?;
+    context.save(root)?;
 
     println!("Deactivated scope");
     println!("Scope layers no longer available for staging");
@@ -444,7 +475,7 @@ mod tests {
 
         // CRITICAL: Create JIN_DIR and initialize the Jin repository
         std::fs::create_dir_all(&jin_dir).unwrap();
-        let _ = JinRepo::open_or_create();
+        let _ = JinRepo::open_or_create(Path::new("."));
 
         // Change to temp directory for project context
         // CRITICAL: Verify current directory is valid after change
@@ -455,13 +486,13 @@ mod tests {
         // Initialize .jin directory and context
         std::fs::create_dir_all(temp.path().join(".jin")).unwrap();
         let context = ProjectContext::default();
-        context.save().unwrap();
+        context.save(temp.path()).unwrap();
 
         temp
     }
 
     fn create_test_mode(name: &str) {
-        let repo = JinRepo::open_or_create().unwrap();
+        let repo = JinRepo::open_or_create(Path::new(".")).unwrap();
         let empty_tree = repo.create_tree(&[]).unwrap();
         let commit_oid = repo
             .create_commit(None, &format!("Initialize mode: {}", name), empty_tree, &[])
@@ -623,11 +654,11 @@ mod tests {
     #[serial]
     fn test_create_untethered_scope() {
         let _temp = setup_test_env();
-        let result = create("testscope", None);
+        let result = create(Path::new("."), "testscope", None);
         assert!(result.is_ok());
 
         // Verify ref was created
-        let repo = JinRepo::open_or_create().unwrap();
+        let repo = JinRepo::open_or_create(Path::new(".")).unwrap();
         assert!(repo.ref_exists("refs/jin/scopes/testscope"));
     }
 
@@ -650,7 +681,7 @@ mod tests {
         std::env::set_var("JIN_DIR", &ctx.jin_dir);
 
         // EXECUTE: The function under test
-        let result = create("testscope", Some("testmode"));
+        let result = create(&ctx.project_path, "testscope", Some("testmode"));
         assert!(result.is_ok());
 
         // VERIFY: Use explicit path for repository access
@@ -667,11 +698,11 @@ mod tests {
     #[serial]
     fn test_create_scope_with_colon() {
         let _temp = setup_test_env();
-        let result = create("language:javascript", None);
+        let result = create(Path::new("."), "language:javascript", None);
         assert!(result.is_ok());
 
         // Colons are replaced with slashes in ref names
-        let repo = JinRepo::open_or_create().unwrap();
+        let repo = JinRepo::open_or_create(Path::new(".")).unwrap();
         assert!(repo.ref_exists("refs/jin/scopes/language/javascript"));
     }
 
@@ -679,7 +710,7 @@ mod tests {
     #[serial]
     fn test_create_scope_nonexistent_mode() {
         let _temp = setup_test_env();
-        let result = create("testscope", Some("nonexistent"));
+        let result = create(Path::new("."), "testscope", Some("nonexistent"));
         assert!(matches!(result, Err(JinError::NotFound(_))));
     }
 
@@ -687,10 +718,10 @@ mod tests {
     #[serial]
     fn test_create_scope_duplicate() {
         let _temp = setup_test_env();
-        create("testscope", None).unwrap();
+        create(Path::new("."), "testscope", None).unwrap();
 
         // Try to create again
-        let result = create("testscope", None);
+        let result = create(Path::new("."), "testscope", None);
         assert!(matches!(result, Err(JinError::AlreadyExists(_))));
     }
 
@@ -698,13 +729,13 @@ mod tests {
     #[serial]
     fn test_use_scope() {
         let _temp = setup_test_env();
-        create("testscope", None).unwrap();
+        create(Path::new("."), "testscope", None).unwrap();
 
-        let result = use_scope("testscope");
+        let result = use_scope(Path::new("."), "testscope");
         assert!(result.is_ok());
 
         // Verify context was updated
-        let context = ProjectContext::load().unwrap();
+        let context = ProjectContext::load(Path::new(".")).unwrap();
         assert_eq!(context.scope, Some("testscope".to_string()));
     }
 
@@ -712,7 +743,7 @@ mod tests {
     #[serial]
     fn test_use_scope_nonexistent() {
         let _temp = setup_test_env();
-        let result = use_scope("nonexistent");
+        let result = use_scope(Path::new("."), "nonexistent");
         assert!(matches!(result, Err(JinError::NotFound(_))));
     }
 
@@ -720,7 +751,7 @@ mod tests {
     #[serial]
     fn test_list_empty() {
         let _temp = setup_test_env();
-        let result = list();
+        let result = list(Path::new("."));
         assert!(result.is_ok());
     }
 
@@ -728,13 +759,13 @@ mod tests {
     #[serial]
     fn test_list_with_scopes() {
         let _temp = setup_test_env();
-        create("scope1", None).unwrap();
-        create("scope2", None).unwrap();
+        create(Path::new("."), "scope1", None).unwrap();
+        create(Path::new("."), "scope2", None).unwrap();
         create_test_mode("testmode");
-        create("scope3", Some("testmode")).unwrap();
-        use_scope("scope1").unwrap();
+        create(Path::new("."), "scope3", Some("testmode")).unwrap();
+        use_scope(Path::new("."), "scope1").unwrap();
 
-        let result = list();
+        let result = list(Path::new("."));
         assert!(result.is_ok());
     }
 
@@ -742,7 +773,7 @@ mod tests {
     #[serial]
     fn test_show_no_scope() {
         let _temp = setup_test_env();
-        let result = show();
+        let result = show(Path::new("."));
         assert!(result.is_ok());
     }
 
@@ -750,10 +781,10 @@ mod tests {
     #[serial]
     fn test_show_with_scope() {
         let _temp = setup_test_env();
-        create("testscope", None).unwrap();
-        use_scope("testscope").unwrap();
+        create(Path::new("."), "testscope", None).unwrap();
+        use_scope(Path::new("."), "testscope").unwrap();
 
-        let result = show();
+        let result = show(Path::new("."));
         assert!(result.is_ok());
     }
 
@@ -761,14 +792,14 @@ mod tests {
     #[serial]
     fn test_unset() {
         let _temp = setup_test_env();
-        create("testscope", None).unwrap();
-        use_scope("testscope").unwrap();
+        create(Path::new("."), "testscope", None).unwrap();
+        use_scope(Path::new("."), "testscope").unwrap();
 
-        let result = unset();
+        let result = unset(Path::new("."));
         assert!(result.is_ok());
 
         // Verify scope was unset
-        let context = ProjectContext::load().unwrap();
+        let context = ProjectContext::load(Path::new(".")).unwrap();
         assert_eq!(context.scope, None);
     }
 
@@ -776,7 +807,7 @@ mod tests {
     #[serial]
     fn test_unset_no_scope() {
         let _temp = setup_test_env();
-        let result = unset();
+        let result = unset(Path::new("."));
         assert!(result.is_ok());
     }
 
@@ -784,13 +815,13 @@ mod tests {
     #[serial]
     fn test_delete_untethered_scope() {
         let _temp = setup_test_env();
-        create("testscope", None).unwrap();
+        create(Path::new("."), "testscope", None).unwrap();
 
-        let result = delete("testscope");
+        let result = delete(Path::new("."), "testscope");
         assert!(result.is_ok());
 
         // Verify ref was deleted
-        let repo = JinRepo::open_or_create().unwrap();
+        let repo = JinRepo::open_or_create(Path::new(".")).unwrap();
         assert!(!repo.ref_exists("refs/jin/scopes/testscope"));
     }
 
@@ -799,13 +830,13 @@ mod tests {
     fn test_delete_mode_bound_scope() {
         let _temp = setup_test_env();
         create_test_mode("testmode");
-        create("testscope", Some("testmode")).unwrap();
+        create(Path::new("."), "testscope", Some("testmode")).unwrap();
 
-        let result = delete("testscope");
+        let result = delete(Path::new("."), "testscope");
         assert!(result.is_ok());
 
         // Verify ref was deleted
-        let repo = JinRepo::open_or_create().unwrap();
+        let repo = JinRepo::open_or_create(Path::new(".")).unwrap();
         assert!(!repo.ref_exists("refs/jin/modes/testmode/scopes/testscope"));
     }
 
@@ -813,14 +844,14 @@ mod tests {
     #[serial]
     fn test_delete_active_scope() {
         let _temp = setup_test_env();
-        create("testscope", None).unwrap();
-        use_scope("testscope").unwrap();
+        create(Path::new("."), "testscope", None).unwrap();
+        use_scope(Path::new("."), "testscope").unwrap();
 
-        let result = delete("testscope");
+        let result = delete(Path::new("."), "testscope");
         assert!(result.is_ok());
 
         // Verify scope was unset
-        let context = ProjectContext::load().unwrap();
+        let context = ProjectContext::load(Path::new(".")).unwrap();
         assert_eq!(context.scope, None);
     }
 
@@ -828,7 +859,7 @@ mod tests {
     #[serial]
     fn test_delete_nonexistent() {
         let _temp = setup_test_env();
-        let result = delete("nonexistent");
+        let result = delete(Path::new("."), "nonexistent");
         assert!(matches!(result, Err(JinError::NotFound(_))));
     }
 }
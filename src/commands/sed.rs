@@ -0,0 +1,379 @@
+//! Implementation of `jin sed`
+//!
+//! Applies one regex replacement to the committed files of several layers
+//! at once, such as rotating a registry URL wherever it appears. Without
+//! `--key` the whole text of every file is searched; with `--key` only the
+//! scalar at that dotted path of structured files is, and edited files are
+//! rewritten in the configured output style.
+//!
+//! Edits are staged like `jin add` would stage them, so they can be
+//! reviewed with `jin status` and recorded with `jin commit`; `--dry-run`
+//! prints them as a diff instead. The staging index holds one entry per
+//! path, so when the same path changes in several layers (or is already
+//! staged) the remaining edits wait for another `jin sed` after the commit.
+
+use crate::cli::SedArgs;
+use crate::core::pager::Palette;
+use crate::core::{JinError, Layer, ProjectContext, Result};
+use crate::git::refs::layer_name;
+use crate::git::{JinRepo, ObjectOps, RefOps, TreeOps};
+use crate::merge::MergeValue;
+use crate::merge::{detect_format, get_applicable_layers, parse_content, serialize_content};
+use crate::staging::attributes::glob_to_regex;
+use crate::staging::{StagedEntry, StagingIndex};
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// One file changed in one layer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    /// Layer holding the file
+    pub layer: Layer,
+    /// Display name of the layer (e.g., `global`, `mode/claude`)
+    pub layer_name: String,
+    /// File path
+    pub path: String,
+    /// Committed content
+    pub before: String,
+    /// Content after the replacement
+    pub after: String,
+}
+
+/// Execute the sed command
+///
+/// # Errors
+///
+/// Returns an error if Jin is not initialized, the pattern or a file
+/// pattern is invalid, or a `--layer` is not part of the active context.
+pub fn execute(args: SedArgs, root: &Path) -> Result<()> {
+    let context = ProjectContext::load(root)?;
+    let regex = Regex::new(&args.pattern)
+        .map_err(|e| JinError::Config(format!("Invalid pattern '{}': {}", args.pattern, e)))?;
+    let only = args
+        .only
+        .iter()
+        .map(|p| glob_to_regex(p).map_err(|e| JinError::Config(format!("--only {}: {}", p, e))))
+        .collect::<Result<Vec<_>>>()?;
+    let layers = select_layers(&args.layer, &context)?;
+    let key: Option<Vec<&str>> = args.key.as_deref().map(|k| k.split('.').collect());
+
+    let repo = JinRepo::open(root)?;
+    let replacer = Replacer {
+        regex: &regex,
+        replacement: &args.replacement,
+        key: key.as_deref(),
+    };
+
+    let mut edits = Vec::new();
+    for layer in layers {
+        let ref_path = layer.ref_path(
+            context.mode.as_deref(),
+            context.scope.as_deref(),
+            context.project.as_deref(),
+        );
+        if !repo.ref_exists(&ref_path) {
+            continue;
+        }
+        let tree = repo
+            .inner()
+            .find_commit(repo.resolve_ref(&ref_path)?)?
+            .tree_id();
+        for path in repo.list_tree_files(tree)? {
+            if !only.is_empty() && !only.iter().any(|re| re.is_match(&path)) {
+                continue;
+            }
+            // Binary files are never edited
+            let Ok(before) = String::from_utf8(repo.read_file_from_tree(tree, Path::new(&path))?)
+            else {
+                continue;
+            };
+            let after = match replacer.apply(Path::new(&path), &before) {
+                Ok(Some(after)) => after,
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!(
+                        "Warning: Skipped {} in {}: {}",
+                        path,
+                        layer_name(&ref_path),
+                        e
+                    );
+                    continue;
+                }
+            };
+            edits.push(Edit {
+                layer,
+                layer_name: layer_name(&ref_path).to_string(),
+                path,
+                before,
+                after,
+            });
+        }
+    }
+
+    if edits.is_empty() {
+        println!("No matches in the selected layers");
+        return Ok(());
+    }
+
+    if args.dry_run {
+        let palette = Palette::auto();
+        for edit in &edits {
+            print_diff(edit, palette);
+        }
+        let layers: HashSet<Layer> = edits.iter().map(|e| e.layer).collect();
+        println!(
+            "\nDry run - {} file(s) in {} layer(s) would change",
+            edits.len(),
+            layers.len()
+        );
+        return Ok(());
+    }
+
+    stage(root, &edits, &repo)
+}
+
+/// Stage the edits, one per path
+fn stage(root: &Path, edits: &[Edit], repo: &JinRepo) -> Result<()> {
+    let mut staging = StagingIndex::load(root).unwrap_or_else(|_| StagingIndex::new());
+    let mut staged = 0;
+    let mut pending = 0;
+
+    for edit in edits {
+        let path = PathBuf::from(&edit.path);
+        if staging.get(&path).is_some() {
+            println!(
+                "Skipped {} in {}: already staged",
+                edit.path, edit.layer_name
+            );
+            pending += 1;
+            continue;
+        }
+        let blob = repo.create_blob(edit.after.as_bytes())?;
+        staging.add(StagedEntry::new(path, edit.layer, blob.to_string()));
+        println!("Staged {} in {}", edit.path, edit.layer_name);
+        staged += 1;
+    }
+    staging.save(root)?;
+
+    println!();
+    println!(
+        "Staged {} edit(s). Review with 'jin status', then run 'jin commit' and 'jin apply'.",
+        staged
+    );
+    if pending > 0 {
+        println!(
+            "{} edit(s) wait for another round: commit, then run the same 'jin sed' again",
+            pending
+        );
+    }
+    Ok(())
+}
+
+/// Layers to edit: the named ones, or every committable layer of the
+/// active context
+fn select_layers(names: &[String], context: &ProjectContext) -> Result<Vec<Layer>> {
+    let active: Vec<Layer> = get_applicable_layers(
+        context.mode.as_deref(),
+        context.scope.as_deref(),
+        context.project.as_deref(),
+    )
+    .into_iter()
+    .filter(|layer| *layer != Layer::WorkspaceActive)
+    .collect();
+    if names.is_empty() {
+        return Ok(active);
+    }
+
+    let mut selected = Vec::new();
+    for name in names {
+        let layer = super::log::parse_layer_name(name)?;
+        if !active.contains(&layer) {
+            return Err(JinError::Other(format!(
+                "Layer {} is not part of the active context; switch mode or scope first",
+                name
+            )));
+        }
+        if !selected.contains(&layer) {
+            selected.push(layer);
+        }
+    }
+    selected.sort_by_key(|layer| layer.precedence());
+    Ok(selected)
+}
+
+/// A replacement, over whole files or at one key path
+struct Replacer<'a> {
+    regex: &'a Regex,
+    replacement: &'a str,
+    key: Option<&'a [&'a str]>,
+}
+
+impl Replacer<'_> {
+    /// `content` after the replacement, or `None` when nothing changes
+    ///
+    /// Key-path replacements skip plain-text files and files without the
+    /// key; they fail on files that don't parse.
+    fn apply(&self, path: &Path, content: &str) -> Result<Option<String>> {
+        let Some(key) = self.key else {
+            let after = self.regex.replace_all(content, self.replacement);
+            return Ok((after != content).then(|| after.into_owned()));
+        };
+
+        let format = detect_format(path);
+        if !format.is_structured() {
+            return Ok(None);
+        }
+        let mut value = parse_content(content, format)?;
+        if !replace_at(&mut value, key, self.regex, self.replacement) {
+            return Ok(None);
+        }
+        Ok(Some(serialize_content(&value, format)?))
+    }
+}
+
+/// Replace in the scalar at `key` under `value`; whether it changed
+///
+/// Numeric segments index arrays. A number or boolean keeps its type when
+/// the replaced text still parses as one.
+fn replace_at(value: &mut MergeValue, key: &[&str], regex: &Regex, replacement: &str) -> bool {
+    if let Some((first, rest)) = key.split_first() {
+        let child = match value {
+            MergeValue::Object(map) => map.get_mut(*first),
+            MergeValue::Array(items) => first.parse::<usize>().ok().and_then(|i| items.get_mut(i)),
+            _ => None,
+        };
+        return child.is_some_and(|child| replace_at(child, rest, regex, replacement));
+    }
+
+    let text = match value {
+        MergeValue::String(s) => s.clone(),
+        MergeValue::Integer(i) => i.to_string(),
+        MergeValue::Float(f) => f.to_string(),
+        MergeValue::Bool(b) => b.to_string(),
+        _ => return false,
+    };
+    let replaced = regex.replace_all(&text, replacement);
+    if replaced == text {
+        return false;
+    }
+    let replaced = replaced.into_owned();
+    *value = match value {
+        MergeValue::Integer(_) => replaced
+            .parse()
+            .map(MergeValue::Integer)
+            .unwrap_or(MergeValue::String(replaced)),
+        MergeValue::Float(_) => replaced
+            .parse()
+            .map(MergeValue::Float)
+            .unwrap_or(MergeValue::String(replaced)),
+        MergeValue::Bool(_) => replaced
+            .parse()
+            .map(MergeValue::Bool)
+            .unwrap_or(MergeValue::String(replaced)),
+        _ => MergeValue::String(replaced),
+    };
+    true
+}
+
+/// Print an edit as a unified diff
+fn print_diff(edit: &Edit, palette: Palette) {
+    let name = format!("{}/{}", edit.layer_name, edit.path);
+    println!("{}", palette.bold(&format!("--- {}", name)));
+    println!("{}", palette.bold(&format!("+++ {}", name)));
+    let patch = diffy::create_patch(&edit.before, &edit.after).to_string();
+    // Skip diffy's own `--- original`/`+++ modified` header
+    for line in patch.lines().skip(2) {
+        match line.chars().next() {
+            Some('@') => println!("{}", palette.cyan(line)),
+            Some('+') => println!("{}", palette.green(line)),
+            Some('-') => println!("{}", palette.red(line)),
+            _ => println!("{}", line),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replacer<'a>(regex: &'a Regex, key: Option<&'a [&'a str]>) -> Replacer<'a> {
+        Replacer {
+            regex,
+            replacement: "https://new.example.com",
+            key,
+        }
+    }
+
+    #[test]
+    fn test_replace_whole_file() {
+        let regex = Regex::new(r"https://old\.example\.com").unwrap();
+        let content = "registry=https://old.example.com\nmirror=https://old.example.com/x\n";
+        assert_eq!(
+            replacer(&regex, None)
+                .apply(Path::new(".npmrc"), content)
+                .unwrap()
+                .as_deref(),
+            Some("registry=https://new.example.com\nmirror=https://new.example.com/x\n")
+        );
+        assert_eq!(
+            replacer(&regex, None)
+                .apply(Path::new(".npmrc"), "nothing here")
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_replace_at_key_path() {
+        let regex = Regex::new(r"https://old\.example\.com").unwrap();
+        let key = ["registry", "url"];
+        let content = r#"{"registry": {"url": "https://old.example.com"}, "docs": "https://old.example.com"}"#;
+
+        let after = replacer(&regex, Some(&key))
+            .apply(Path::new("app.json"), content)
+            .unwrap()
+            .unwrap();
+        let value = MergeValue::from_json(&after).unwrap();
+        let obj = value.as_object().unwrap();
+        assert_eq!(
+            obj["registry"].as_object().unwrap()["url"].as_str(),
+            Some("https://new.example.com")
+        );
+        // Other keys are untouched
+        assert_eq!(obj["docs"].as_str(), Some("https://old.example.com"));
+
+        // Files without the key, and text files, are skipped
+        assert_eq!(
+            replacer(&regex, Some(&key))
+                .apply(Path::new("other.json"), r#"{"registry": 1}"#)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            replacer(&regex, Some(&key))
+                .apply(Path::new("notes.txt"), content)
+                .unwrap(),
+            None
+        );
+        assert!(replacer(&regex, Some(&key))
+            .apply(Path::new("bad.json"), "{")
+            .is_err());
+    }
+
+    #[test]
+    fn test_replace_keeps_scalar_types() {
+        let mut value = MergeValue::from_json(r#"{"ports": [8080, 9000], "tls": false}"#).unwrap();
+        let regex = Regex::new("8080").unwrap();
+        assert!(replace_at(&mut value, &["ports", "0"], &regex, "8443"));
+        assert_eq!(
+            value.as_object().unwrap()["ports"].as_array().unwrap()[0],
+            MergeValue::Integer(8443)
+        );
+        assert!(!replace_at(&mut value, &["ports", "5"], &regex, "1"));
+
+        let regex = Regex::new("false").unwrap();
+        assert!(replace_at(&mut value, &["tls"], &regex, "true"));
+        assert_eq!(value.as_object().unwrap()["tls"], MergeValue::Bool(true));
+    }
+}
@@ -0,0 +1,82 @@
+//! Implementation of `jin stats`
+//!
+//! Shows how much each mode and scope stores across its layers, marking
+//! those over `limits.mode-max-size` or `limits.scope-max-size` (see
+//! [`crate::core::quota`]). `--top` also lists the largest files, to find
+//! what made a layer grow.
+
+use crate::cli::StatsArgs;
+use crate::core::limits::{format_size, LimitsConfig};
+use crate::core::quota::{self, ContextStorage};
+use crate::core::{JinError, Result};
+use crate::git::JinRepo;
+use std::path::Path;
+
+/// Execute the stats command
+pub fn execute(args: StatsArgs, root: &Path) -> Result<()> {
+    let repo = JinRepo::open(root).map_err(|_| JinError::NotInitialized)?;
+    let limits = LimitsConfig::load();
+    let layers = quota::measure(&repo)?;
+
+    let (files, size) = layers
+        .iter()
+        .fold((0, 0), |(files, size), l| (files + l.files, size + l.size));
+    println!(
+        "Total: {} in {} file(s) across {} layer(s)",
+        format_size(size),
+        files,
+        layers.len()
+    );
+
+    print_totals(
+        "Storage by mode",
+        &quota::by_mode(&layers),
+        limits.mode_max_size(),
+        "limits.mode-max-size",
+    );
+    print_totals(
+        "Storage by scope",
+        &quota::by_scope(&layers),
+        limits.scope_max_size(),
+        "limits.scope-max-size",
+    );
+
+    if let Some(count) = args.top {
+        println!();
+        println!("Largest files:");
+        for file in quota::largest_files(&repo, count)? {
+            println!(
+                "  {:>10}  {} ({})",
+                format_size(file.size),
+                file.path.display(),
+                file.layer
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn print_totals(label: &str, totals: &[ContextStorage], max: Option<u64>, key: &str) {
+    println!();
+    if totals.is_empty() {
+        println!("{}: none", label);
+        return;
+    }
+    println!("{}:", label);
+    let width = totals.iter().map(|t| t.name.len()).max().unwrap_or(0);
+    for total in totals {
+        let mut line = format!(
+            "  {:<width$}  {:>10}  {} file(s) in {} layer(s)",
+            total.name,
+            format_size(total.size),
+            total.files,
+            total.layers,
+            width = width
+        );
+        if let Some(max) = max.filter(|&max| total.size > max) {
+            line.push_str(&format!("  over {} ({})", key, format_size(max)));
+        }
+        println!("{}", line);
+    }
+}
@@ -2,13 +2,33 @@
 //!
 //! Shows workspace state, active contexts, staged changes, and layer composition.
 
+use crate::cli::{StatusArgs, StatusSection};
 use crate::commands::apply::PausedApplyState;
-use crate::core::{JinError, Layer, ProjectContext, Result};
-use crate::git::{JinRepo, ObjectOps};
-use crate::merge::jinmerge::JinMergeConflict;
-use crate::staging::StagingIndex;
+use crate::core::dangling;
+use crate::core::profile::{self, Phase};
+use crate::core::queue::{QueueState, WorkspaceQueue};
+use crate::core::schema::{self, JsonSurface};
+use crate::core::{
+    BranchScope, JinConfig, JinError, Layer, LocalExpiry, NameRules, OfflineCache, ProjectContext,
+    Result,
+};
+use crate::git::freshness::{self, LayerFreshness};
+use crate::git::remote::DEFAULT_REMOTE;
+use crate::git::{JinRepo, RefOps, TreeOps};
+use crate::merge::conflicts::ConflictFiles;
+use crate::merge::get_applicable_layers;
 use crate::staging::WorkspaceMetadata;
-use std::path::PathBuf;
+use crate::staging::{LayerOwners, StagingIndex};
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// How long sections are printed strictly in display order before output
+/// switches to completion order
+const SECTION_BUDGET: Duration = Duration::from_millis(200);
 
 /// Workspace state representation
 enum WorkspaceState {
@@ -19,171 +39,680 @@ enum WorkspaceState {
     },
 }
 
+/// `jin status --json` output; sections not shown are left out
+#[derive(Debug, Serialize)]
+struct StatusReport {
+    schema_version: u32,
+    mode: Option<String>,
+    scope: Option<String>,
+    project: Option<String>,
+    /// Scope of the checked-out branch, used instead of `scope`
+    branch_scope: Option<String>,
+    /// When the state was recorded, if the repository was unavailable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cached_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remote: Option<Vec<LayerFreshness>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    workspace: Option<WorkspaceReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conflicts: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    queue: Option<Vec<QueuedApply>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    staged: Option<Vec<StagedFile>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    layers: Option<Vec<LayerCount>>,
+}
+
+/// Files changed since the last apply
+#[derive(Debug, Serialize)]
+struct WorkspaceReport {
+    clean: bool,
+    modified: Vec<String>,
+    deleted: Vec<String>,
+}
+
+/// An apply holding or waiting for the workspace
+#[derive(Debug, Serialize)]
+struct QueuedApply {
+    state: &'static str,
+    pid: u32,
+    command: String,
+}
+
+/// A staged file and the layer it will be committed to
+#[derive(Debug, Serialize)]
+struct StagedFile {
+    path: String,
+    layer: String,
+    owners: Vec<String>,
+}
+
+/// Committed and staged files of a layer
+#[derive(Debug, Serialize)]
+struct LayerCount {
+    /// Layer name (`global-base`, ...)
+    layer: String,
+    /// Git ref of the layer
+    ref_path: String,
+    /// Storage path shown to users
+    storage: String,
+    files: usize,
+    staged: usize,
+}
+
 /// Check for in-progress apply operation with conflicts
-fn check_for_conflicts() -> Option<PausedApplyState> {
+fn check_for_conflicts(root: &Path) -> Option<PausedApplyState> {
     // Follow pattern from resolve.rs:33-37
-    if !PausedApplyState::exists() {
+    if !PausedApplyState::exists(root) {
         return None;
     }
 
     // Graceful degradation: if load fails, return None
-    PausedApplyState::load().ok()
+    PausedApplyState::load(root).ok()
 }
 
 /// Display conflict state from paused apply operation
-fn show_conflict_state(state: &PausedApplyState) -> Result<()> {
+fn show_conflict_state(root: &Path, state: &PausedApplyState) -> Vec<String> {
     // Follow pluralization pattern from line 71-73 in status.rs
     let count = state.conflict_count;
-    println!(
+    let mut lines = vec![format!(
         "Merge conflicts ({} file{}):",
         count,
         if count == 1 { "" } else { "s" }
-    );
+    )];
 
     // List each .jinmerge file
     // CRITICAL: conflict_files contains original paths, convert to .jinmerge paths
+    let conflict_files = ConflictFiles::load(root).unwrap_or_default();
     for original_path in &state.conflict_files {
-        let merge_path = JinMergeConflict::merge_path_for_file(original_path);
-        println!("  {}", merge_path.display());
+        let merge_path = conflict_files.path_for(original_path);
+        if conflict_files.is_indexed(original_path) {
+            lines.push(format!(
+                "  {} ({})",
+                merge_path.display(),
+                original_path.display()
+            ));
+        } else {
+            lines.push(format!("  {}", merge_path.display()));
+        }
     }
 
     // Show resolve instruction
-    println!("  Resolve with: jin resolve <files>");
+    lines.push("  Resolve with: jin resolve <files>".to_string());
 
     // Show timestamp - use RFC3339 format for ISO 8601
-    println!("  Detected: {}", state.timestamp.to_rfc3339());
+    lines.push(format!("  Detected: {}", state.timestamp.to_rfc3339()));
 
     // Blank line for spacing (follow status section pattern)
-    println!();
+    lines.push(String::new());
 
-    Ok(())
+    lines
+}
+
+/// Display applies holding or waiting for the workspace (nothing when idle)
+fn show_queue(state: &QueueState) -> Vec<String> {
+    if state.is_idle() {
+        return Vec::new();
+    }
+    let time = |at: &DateTime<Utc>| at.with_timezone(&Local).format("%H:%M:%S").to_string();
+    let mut lines = vec!["Apply queue:".to_string()];
+    if let Some(running) = &state.running {
+        lines.push(match &running.started_at {
+            Some(started) => format!(
+                "  running: jin {} (pid {}, since {})",
+                running.command,
+                running.pid,
+                time(started)
+            ),
+            None => format!(
+                "  running: jin {} (pid {}, debouncing)",
+                running.command, running.pid
+            ),
+        });
+    }
+    for waiting in &state.waiting {
+        lines.push(format!(
+            "  waiting: jin {} (pid {}, requested {})",
+            waiting.command,
+            waiting.pid,
+            time(&waiting.requested_at)
+        ));
+    }
+    lines.push(String::new());
+    lines
 }
 
 /// Execute the status command
 ///
-/// Shows workspace state and active contexts.
-pub fn execute() -> Result<()> {
+/// Shows workspace state and active contexts. Each section is computed on
+/// its own thread and printed as soon as it is ready, in display order; once
+/// [`SECTION_BUDGET`] has passed, a slow section (drift on a huge tree, say)
+/// no longer holds up the ones after it.
+pub fn execute(args: StatusArgs, root: &Path) -> Result<()> {
     // Check if Jin is initialized
-    if !ProjectContext::is_initialized() {
+    if !ProjectContext::is_initialized(root) {
         return Err(JinError::NotInitialized);
     }
 
     // Load context
-    let context = ProjectContext::load()?;
+    let context = ProjectContext::load(root)?;
 
-    // Open repo for layer operations
-    let repo = JinRepo::open_or_create()?;
+    let mut sections = if args.sections.is_empty() {
+        StatusSection::all()
+    } else {
+        args.sections
+    };
+    sections.sort();
+    sections.dedup();
 
-    // Load staging
-    let staging = StagingIndex::load().unwrap_or_else(|_| StagingIndex::new());
+    // An unreachable repository leaves the state of the last apply
+    let offline = OfflineCache::fallback(root);
+
+    if schema::json_output() {
+        return schema::print_json(&report(root, &sections, &context, offline.as_ref())?);
+    }
 
     println!("Jin status:");
     println!();
+    if let Some(cache) = &offline {
+        println!("{}", cache.banner(Utc::now()));
+        println!();
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    std::thread::scope(|scope| {
+        for &section in &sections {
+            let sender = sender.clone();
+            let context = &context;
+            let offline = offline.as_ref();
+            scope.spawn(move || {
+                let _ = sender.send((section, render(section, context, root, offline)));
+            });
+        }
+        drop(sender);
+        print_sections(&sections, &receiver, SECTION_BUDGET, |line| {
+            println!("{}", line)
+        })
+    })
+}
 
-    // Show active mode
-    match &context.mode {
-        Some(mode) => println!("  Mode:  {} (active)", mode),
-        None => println!("  Mode:  (none)"),
+/// Compute one section's output lines
+///
+/// With `offline` set, sections that need the repository are rendered from
+/// the last apply instead.
+fn render(
+    section: StatusSection,
+    context: &ProjectContext,
+    root: &Path,
+    offline: Option<&OfflineCache>,
+) -> Result<Vec<String>> {
+    if let Some(cache) = offline {
+        match section {
+            // The banner already says the repository is unreachable
+            StatusSection::Remote => return Ok(Vec::new()),
+            StatusSection::Layers => {
+                return Ok(show_cached_layer_summary(cache, &load_staging(root)))
+            }
+            _ => {}
+        }
+    }
+    match section {
+        StatusSection::Context => {
+            let repo = JinRepo::open(root).ok();
+            let branch = repo
+                .as_ref()
+                .and_then(|repo| BranchScope::active(root, repo));
+            let mut lines = show_context(context, branch.as_ref());
+            if let Some(repo) = &repo {
+                for entry in dangling::check(context, repo) {
+                    lines.insert(
+                        lines.len() - 1,
+                        format!("  Warning: {}; run 'jin context --heal'", entry.message()),
+                    );
+                }
+            }
+            Ok(lines)
+        }
+        StatusSection::Remote => Ok(show_remote_freshness(
+            root,
+            context,
+            &JinRepo::open_or_create(root)?,
+        )),
+        StatusSection::Drift => Ok(show_workspace_state(check_workspace_state(root)?)),
+        StatusSection::Conflicts => Ok(check_for_conflicts(root)
+            .map(|state| show_conflict_state(root, &state))
+            .unwrap_or_default()),
+        StatusSection::Queue => Ok(show_queue(&WorkspaceQueue::open(root).state())),
+        StatusSection::Staging => {
+            let staging = load_staging(root);
+            let owners = match offline {
+                Some(_) => BTreeMap::new(),
+                None => staged_owners(root, context, &staging),
+            };
+            Ok(show_staging(context, &staging, &owners))
+        }
+        StatusSection::Layers => show_layer_summary(
+            context,
+            root,
+            &JinRepo::open_or_create(root)?,
+            &load_staging(root),
+        ),
     }
+}
 
-    // Show active scope
-    match &context.scope {
-        Some(scope) => println!("  Scope: {} (active)", scope),
-        None => println!("  Scope: (none)"),
+/// Compute the given sections for `--json`
+fn report(
+    root: &Path,
+    sections: &[StatusSection],
+    context: &ProjectContext,
+    offline: Option<&OfflineCache>,
+) -> Result<StatusReport> {
+    let branch = JinRepo::open(root)
+        .ok()
+        .as_ref()
+        .and_then(|repo| BranchScope::active(root, repo));
+    let mut report = StatusReport {
+        schema_version: JsonSurface::Status.version(),
+        mode: context.mode.clone(),
+        scope: context.scope.clone(),
+        project: context.project.clone(),
+        branch_scope: branch.map(|branch| branch.scope),
+        cached_at: offline.map(|cache| cache.saved_at.to_rfc3339()),
+        remote: None,
+        workspace: None,
+        conflicts: None,
+        queue: None,
+        staged: None,
+        layers: None,
+    };
+    let staging = load_staging(root);
+
+    for section in sections {
+        match section {
+            StatusSection::Context => {}
+            // Unknown while the repository is unreachable
+            StatusSection::Remote if offline.is_some() => {}
+            StatusSection::Remote => {
+                report.remote = Some(remote_updates(
+                    root,
+                    context,
+                    &JinRepo::open_or_create(root)?,
+                ));
+            }
+            StatusSection::Drift => {
+                let paths = |paths: Vec<PathBuf>| -> Vec<String> {
+                    paths.iter().map(|p| p.display().to_string()).collect()
+                };
+                report.workspace = Some(match check_workspace_state(root)? {
+                    WorkspaceState::Clean => WorkspaceReport {
+                        clean: true,
+                        modified: Vec::new(),
+                        deleted: Vec::new(),
+                    },
+                    WorkspaceState::Dirty { modified, deleted } => WorkspaceReport {
+                        clean: false,
+                        modified: paths(modified),
+                        deleted: paths(deleted),
+                    },
+                });
+            }
+            StatusSection::Conflicts => {
+                report.conflicts = Some(
+                    check_for_conflicts(root)
+                        .map(|state| {
+                            state
+                                .conflict_files
+                                .iter()
+                                .map(|path| path.display().to_string())
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                );
+            }
+            StatusSection::Queue => {
+                let state = WorkspaceQueue::open(root).state();
+                let running = state.running.iter().map(|request| ("running", request));
+                let waiting = state.waiting.iter().map(|request| ("waiting", request));
+                report.queue = Some(
+                    running
+                        .chain(waiting)
+                        .map(|(state, request)| QueuedApply {
+                            state,
+                            pid: request.pid,
+                            command: request.command.clone(),
+                        })
+                        .collect(),
+                );
+            }
+            StatusSection::Staging => {
+                let owners = match offline {
+                    Some(_) => BTreeMap::new(),
+                    None => staged_owners(root, context, &staging),
+                };
+                report.staged = Some(
+                    staging
+                        .numbered()
+                        .iter()
+                        .map(|entry| StagedFile {
+                            path: entry.path.display().to_string(),
+                            layer: entry.target_layer.to_string(),
+                            owners: owners.get(&entry.path).cloned().unwrap_or_default(),
+                        })
+                        .collect(),
+                );
+            }
+            StatusSection::Layers => {
+                report.layers = Some(match offline {
+                    Some(cache) => cached_layer_counts(cache, &staging),
+                    None => layer_counts(context, &JinRepo::open_or_create(root)?, &staging),
+                });
+            }
+        }
     }
+    Ok(report)
+}
+
+/// Print sections with `print` as their results arrive
+///
+/// Sections are printed in `order` until `budget` has elapsed, then in the
+/// order they complete. A failed section does not stop the others; the
+/// first error is returned once all have been printed.
+fn print_sections(
+    order: &[StatusSection],
+    receiver: &Receiver<(StatusSection, Result<Vec<String>>)>,
+    budget: Duration,
+    mut print: impl FnMut(&str),
+) -> Result<()> {
+    let started = Instant::now();
+    let mut pending = order.to_vec();
+    let mut done: BTreeMap<StatusSection, Result<Vec<String>>> = BTreeMap::new();
+    let mut first_error = None;
 
-    // Show project
-    match &context.project {
-        Some(project) => println!("  Project: {}", project),
-        None => println!("  Project: (none)"),
+    while !pending.is_empty() {
+        let in_order = started.elapsed() < budget;
+        let ready: Vec<StatusSection> = if in_order {
+            pending
+                .iter()
+                .take_while(|s| done.contains_key(*s))
+                .copied()
+                .collect()
+        } else {
+            pending
+                .iter()
+                .filter(|s| done.contains_key(*s))
+                .copied()
+                .collect()
+        };
+        for section in ready {
+            pending.retain(|s| *s != section);
+            match done.remove(&section) {
+                Some(Ok(lines)) => {
+                    for line in lines {
+                        print(&line);
+                    }
+                }
+                Some(Err(e)) => {
+                    first_error.get_or_insert(e);
+                }
+                None => {}
+            }
+        }
+        if pending.is_empty() {
+            break;
+        }
+
+        let received = if in_order {
+            match receiver.recv_timeout(budget.saturating_sub(started.elapsed())) {
+                Err(RecvTimeoutError::Timeout) => continue,
+                other => other.ok(),
+            }
+        } else {
+            receiver.recv().ok()
+        };
+        match received {
+            Some((section, result)) => {
+                done.insert(section, result);
+            }
+            None => break,
+        }
     }
 
-    println!();
+    first_error.map_or(Ok(()), Err)
+}
 
-    // Check and display workspace state
-    let workspace_state = check_workspace_state()?;
+/// Load staging, treating an unreadable index as empty
+fn load_staging(root: &Path) -> StagingIndex {
+    StagingIndex::load(root).unwrap_or_else(|_| StagingIndex::new())
+}
+
+/// Show the active mode, scope and project
+fn show_context(context: &ProjectContext, branch: Option<&BranchScope>) -> Vec<String> {
+    vec![
+        // Show active mode
+        match &context.mode {
+            Some(mode) => format!("  Mode:  {} (active)", mode),
+            None => "  Mode:  (none)".to_string(),
+        },
+        // Show active scope, or the branch scope standing in for it
+        match (branch, &context.scope) {
+            (Some(branch), Some(scope)) => format!(
+                "  Scope: {} (branch {}, instead of {})",
+                branch.scope, branch.branch, scope
+            ),
+            (Some(branch), None) => {
+                format!("  Scope: {} (branch {})", branch.scope, branch.branch)
+            }
+            (None, Some(scope)) => format!("  Scope: {} (active)", scope),
+            (None, None) => "  Scope: (none)".to_string(),
+        },
+        // Show project
+        match &context.project {
+            Some(project) => format!("  Project: {}", project),
+            None => "  Project: (none)".to_string(),
+        },
+        String::new(),
+    ]
+}
+
+/// Show files changed since the last apply
+fn show_workspace_state(workspace_state: WorkspaceState) -> Vec<String> {
     match workspace_state {
-        WorkspaceState::Clean => {
-            println!("Workspace state: Clean");
-            println!();
-        }
+        WorkspaceState::Clean => vec!["Workspace state: Clean".to_string(), String::new()],
         WorkspaceState::Dirty { modified, deleted } => {
             let total = modified.len() + deleted.len();
-            println!(
+            let mut lines = vec![format!(
                 "Workspace state: Dirty ({} file{} modified)",
                 total,
                 if total == 1 { "" } else { "s" }
-            );
+            )];
             // List modified files
             for path in &modified {
-                println!("  {} (modified)", path.display());
+                lines.push(format!("  {} (modified)", path.display()));
             }
             for path in &deleted {
-                println!("  {} (deleted)", path.display());
+                lines.push(format!("  {} (deleted)", path.display()));
             }
-            println!();
-            println!("Use 'jin diff' to see changes or 'jin add <file>' to stage them.");
-            println!();
+            lines.push(String::new());
+            lines.push(
+                "Use 'jin diff' to see changes or 'jin add <file>' to stage them.".to_string(),
+            );
+            lines.push(String::new());
+            lines
         }
     }
+}
 
-    // Check and display conflict state
-    if let Some(conflict_state) = check_for_conflicts() {
-        show_conflict_state(&conflict_state)?;
+/// Show staged files
+/// Owners of each staged file, per the `.jinowners` of the layer it will be
+/// committed to (see [`crate::staging::owners`])
+fn staged_owners(
+    root: &Path,
+    context: &ProjectContext,
+    staging: &StagingIndex,
+) -> BTreeMap<PathBuf, Vec<String>> {
+    let mut owned = BTreeMap::new();
+    let Ok(repo) = JinRepo::open(root) else {
+        return owned;
+    };
+    let mut context = context.clone();
+    BranchScope::activate(root, &mut context, &repo);
+
+    let mut by_layer: BTreeMap<String, LayerOwners> = BTreeMap::new();
+    for entry in staging.entries() {
+        let ref_path = entry.target_layer.ref_path(
+            context.mode.as_deref(),
+            context.scope.as_deref(),
+            context.project.as_deref(),
+        );
+        let owners = by_layer
+            .entry(ref_path)
+            .or_insert_with_key(|ref_path| {
+                LayerOwners::load(&repo, ref_path).unwrap_or_else(|e| {
+                    eprintln!("Warning: Ignoring owners of {}: {}", ref_path, e);
+                    LayerOwners::default()
+                })
+            })
+            .owners_of(&entry.path);
+        if !owners.is_empty() {
+            owned.insert(entry.path.clone(), owners.to_vec());
+        }
     }
+    owned
+}
 
-    // Show staged files
+fn show_staging(
+    context: &ProjectContext,
+    staging: &StagingIndex,
+    owners: &BTreeMap<PathBuf, Vec<String>>,
+) -> Vec<String> {
     let staged_count = staging.len();
+    let mut lines = Vec::new();
 
     if staged_count == 0 {
-        println!("No staged changes.");
+        lines.push("No staged changes.".to_string());
+        lines.push(String::new());
         // Context-sensitive help
         if context.mode.is_none() && context.scope.is_none() && context.project.is_none() {
-            println!();
-            println!("Use 'jin add <file> --mode' to stage files to a mode layer.");
+            lines.push("Use 'jin add <file> --mode' to stage files to a mode layer.".to_string());
         } else {
-            println!();
-            println!("Use 'jin add <file>' to stage files for commit.");
+            lines.push("Use 'jin add <file>' to stage files for commit.".to_string());
         }
     } else {
-        println!(
+        lines.push(format!(
             "Staged changes ({} file{}):",
             staged_count,
             if staged_count == 1 { "" } else { "s" }
-        );
-        for entry in staging.entries() {
-            println!("  {} -> {}", entry.path.display(), entry.target_layer);
+        ));
+        // Numbered so single entries can be unstaged or committed
+        let entries = staging.numbered();
+        let multiple = entries.windows(2).any(|pair| pair[0].path == pair[1].path);
+        for (i, entry) in entries.iter().enumerate() {
+            match owners.get(&entry.path) {
+                Some(owners) => lines.push(format!(
+                    "  [{}] {} -> {} (owners: {})",
+                    i + 1,
+                    entry.path.display(),
+                    entry.target_layer,
+                    owners.join(", ")
+                )),
+                None => lines.push(format!(
+                    "  [{}] {} -> {}",
+                    i + 1,
+                    entry.path.display(),
+                    entry.target_layer
+                )),
+            }
+        }
+        lines.push(String::new());
+        lines.push("Use 'jin commit -m <message>' to commit staged changes.".to_string());
+        if multiple {
+            lines.push(
+                "Some files are staged to several layers; use 'jin reset --staged <n|layer:path>' \
+                 or 'jin commit --only <n|layer:path>' for a single entry."
+                    .to_string(),
+            );
         }
-        println!();
-        println!("Use 'jin commit -m <message>' to commit staged changes.");
     }
+    lines
+}
 
-    // Show layer summary
-    show_layer_summary(&context, &repo, &staging)?;
+/// Show how far the remote is ahead on the active context's layers
+///
+/// Uses the result of the last background check and starts a new one when
+/// the configured interval has elapsed. Never fails the status command.
+fn show_remote_freshness(root: &Path, context: &ProjectContext, repo: &JinRepo) -> Vec<String> {
+    let updates = remote_updates(root, context, repo);
+    if updates.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines: Vec<String> = updates
+        .iter()
+        .map(|update| {
+            format!(
+                "Remote has {} newer commit{} on {}",
+                update.newer_commits,
+                if update.newer_commits == 1 { "" } else { "s" },
+                update.layer
+            )
+        })
+        .collect();
+    lines.push("Use 'jin pull' to merge remote changes.".to_string());
+    lines.push(String::new());
+    lines
+}
+
+/// Layers of the active context with newer commits on the remote, per the
+/// last background check (none when checks are not configured)
+fn remote_updates(root: &Path, context: &ProjectContext, repo: &JinRepo) -> Vec<LayerFreshness> {
+    let interval = match JinConfig::load()
+        .ok()
+        .and_then(|c| c.remote)
+        .and_then(|r| r.check_interval)
+    {
+        Some(seconds) => Duration::from_secs(seconds),
+        None => return Vec::new(),
+    };
+
+    if let Err(e) = freshness::refresh_in_background(root, repo, interval) {
+        eprintln!("Warning: Failed to start remote check: {}", e);
+    }
+
+    freshness::check_context(repo, context, DEFAULT_REMOTE).unwrap_or_default()
+}
 
-    Ok(())
+/// Fetch remote layer refs for the freshness check
+///
+/// Runs as a detached background process started by `jin status`.
+pub fn remote_check(root: &Path) -> Result<()> {
+    let repo = JinRepo::open_or_create(root)?;
+    freshness::fetch_tracking_refs(&repo, DEFAULT_REMOTE)
 }
 
 /// Check workspace state by comparing current files to metadata
-fn check_workspace_state() -> Result<WorkspaceState> {
-    let metadata = match WorkspaceMetadata::load() {
+fn check_workspace_state(root: &Path) -> Result<WorkspaceState> {
+    let metadata = match WorkspaceMetadata::load(root) {
         Ok(m) => m,
         Err(JinError::NotFound(_)) => return Ok(WorkspaceState::Clean),
         Err(e) => return Err(e),
     };
 
-    let repo = JinRepo::open()?;
-
     let mut modified = Vec::new();
     let mut deleted = Vec::new();
 
     // Compare current file hashes to stored hashes
     for (path, expected_hash) in &metadata.files {
-        if !path.exists() {
+        let location = root.join(metadata.location(path));
+        if !location.exists() {
             deleted.push(path.clone());
         } else {
-            let content = std::fs::read(path)?;
-            let current_hash = repo.create_blob(&content)?.to_string();
+            let content = profile::time(Phase::Io, || std::fs::read(&location))?;
+            // Hashed like a blob, without the repository (which may be
+            // offline) or writing to it
+            let current_hash =
+                git2::Oid::hash_object(git2::ObjectType::Blob, &content)?.to_string();
             if current_hash != *expected_hash {
                 modified.push(path.clone());
             }
@@ -200,14 +729,61 @@ fn check_workspace_state() -> Result<WorkspaceState> {
 /// Show layer summary with file counts
 fn show_layer_summary(
     context: &ProjectContext,
+    root: &Path,
     repo: &JinRepo,
     staging: &StagingIndex,
-) -> Result<()> {
-    let git_repo = repo.inner();
-    println!();
-    println!("Layer summary:");
+) -> Result<Vec<String>> {
+    let mut lines = vec![String::new(), "Layer summary:".to_string()];
+    lines.extend(show_layer_counts(&layer_counts(context, repo, staging)));
+    lines.extend(show_expired_overrides(repo));
+    lines.extend(show_name_problems(context, root, repo));
+    Ok(lines)
+}
+
+/// Layer summary of the last apply, for when the repository is unavailable
+fn show_cached_layer_summary(cache: &OfflineCache, staging: &StagingIndex) -> Vec<String> {
+    let mut lines = vec![
+        String::new(),
+        "Layer summary (as of the last apply):".to_string(),
+    ];
+    lines.extend(show_layer_counts(&cached_layer_counts(cache, staging)));
+    lines
+}
+
+/// One line per layer with files, committed or staged
+fn show_layer_counts(counts: &[LayerCount]) -> Vec<String> {
+    let lines: Vec<String> = counts
+        .iter()
+        .filter(|count| count.files + count.staged > 0)
+        .map(|count| {
+            let total = count.files + count.staged;
+            format!(
+                "  {}: {} file{}{}",
+                count.storage,
+                total,
+                if total == 1 { "" } else { "s" },
+                if count.staged > 0 {
+                    format!(" ({} staged)", count.staged)
+                } else {
+                    String::new()
+                }
+            )
+        })
+        .collect();
+    if lines.is_empty() {
+        return vec!["  (no layers with files)".to_string()];
+    }
+    lines
+}
 
-    let mut has_layers = false;
+/// Committed and staged file counts of the layers of the active context
+fn layer_counts(
+    context: &ProjectContext,
+    repo: &JinRepo,
+    staging: &StagingIndex,
+) -> Vec<LayerCount> {
+    let git_repo = repo.inner();
+    let mut counts = Vec::new();
 
     // Iterate through applicable layers
     for layer in Layer::all_in_precedence_order() {
@@ -226,48 +802,108 @@ fn show_layer_summary(
         );
 
         // Count files in layer using tree walk
-        let committed_files = if git_repo.find_reference(&ref_path).is_ok() {
+        let files = if git_repo.find_reference(&ref_path).is_ok() {
             count_files_in_layer(git_repo, &ref_path).unwrap_or(0)
         } else {
             0
         };
 
-        // Count staged files for this layer
-        let staged_files = staging.entries_for_layer(layer).len();
+        counts.push(LayerCount {
+            layer: layer.to_string(),
+            storage: layer.storage_path(
+                context.mode.as_deref(),
+                context.scope.as_deref(),
+                context.project.as_deref(),
+            ),
+            ref_path,
+            files,
+            staged: staging.entries_for_layer(layer).len(),
+        });
+    }
+    counts
+}
+
+/// File counts of the layers of the last apply
+fn cached_layer_counts(cache: &OfflineCache, staging: &StagingIndex) -> Vec<LayerCount> {
+    cache
+        .layers
+        .iter()
+        .map(|layer| LayerCount {
+            layer: layer.layer.clone(),
+            ref_path: layer.ref_path.clone(),
+            storage: layer.storage.clone(),
+            files: layer.files,
+            staged: layer
+                .kind()
+                .map_or(0, |kind| staging.entries_for_layer(kind).len()),
+        })
+        .collect()
+}
 
-        let storage_path = layer.storage_path(
+/// Warn about layer paths this workspace's filesystem can't keep apart,
+/// which make `jin apply` fail
+fn show_name_problems(context: &ProjectContext, root: &Path, repo: &JinRepo) -> Vec<String> {
+    let mut paths = BTreeSet::new();
+    for layer in get_applicable_layers(
+        context.mode.as_deref(),
+        context.scope.as_deref(),
+        context.project.as_deref(),
+    ) {
+        let ref_path = layer.ref_path(
             context.mode.as_deref(),
             context.scope.as_deref(),
             context.project.as_deref(),
         );
-
-        // Display with staged count if any
-        if committed_files > 0 || staged_files > 0 {
-            has_layers = true;
-            let staged_note = if staged_files > 0 {
-                format!(" ({} staged)", staged_files)
-            } else {
-                String::new()
-            };
-            println!(
-                "  {}: {} file{}{}",
-                storage_path,
-                committed_files + staged_files,
-                if (committed_files + staged_files) == 1 {
-                    ""
-                } else {
-                    "s"
-                },
-                staged_note
-            );
-        }
+        let files = repo
+            .resolve_ref(&ref_path)
+            .and_then(|oid| Ok(repo.inner().find_commit(oid)?.tree_id()))
+            .and_then(|tree| repo.list_tree_files(tree));
+        paths.extend(files.unwrap_or_default().into_iter().map(PathBuf::from));
     }
 
-    if !has_layers {
-        println!("  (no layers with files)");
+    let problems = NameRules::detect(root).check(paths.iter().map(PathBuf::as_path));
+    if problems.is_empty() {
+        return Vec::new();
     }
+    let mut lines = vec![
+        String::new(),
+        format!(
+            "Warning: {} path problem{} on this filesystem (jin apply will refuse to run):",
+            problems.len(),
+            if problems.len() == 1 { "" } else { "s" }
+        ),
+    ];
+    lines.extend(problems.iter().map(|problem| format!("  {}", problem)));
+    lines.push("  Rename the files in their layers with 'jin mv'".to_string());
+    lines
+}
 
-    Ok(())
+/// Warn about user-local overrides past their `--expires` date
+fn show_expired_overrides(repo: &JinRepo) -> Vec<String> {
+    let expired = LocalExpiry::load_or_default(repo.path()).expired(Utc::now());
+    if expired.is_empty() {
+        return Vec::new();
+    }
+    let mut lines = vec![
+        String::new(),
+        format!(
+            "Warning: {} expired local override{} (no longer applied):",
+            expired.len(),
+            if expired.len() == 1 { "" } else { "s" }
+        ),
+    ];
+    for (path, at) in expired {
+        lines.push(format!(
+            "  {} (expired {})",
+            path.display(),
+            at.format("%Y-%m-%d")
+        ));
+    }
+    lines.push(
+        "  Renew with 'jin add --local --expires <age>' or remove with 'jin rm --local'"
+            .to_string(),
+    );
+    lines
 }
 
 /// Count files in a layer by walking its tree
@@ -298,16 +934,104 @@ mod tests {
         let temp = TempDir::new().unwrap();
         std::env::set_current_dir(temp.path()).unwrap();
 
-        let result = check_workspace_state();
+        let result = check_workspace_state(temp.path());
         assert!(matches!(result, Ok(WorkspaceState::Clean)));
     }
 
+    #[test]
+    fn test_slow_section_does_not_block_others() {
+        let (sender, receiver) = mpsc::channel();
+        let order = [
+            StatusSection::Context,
+            StatusSection::Drift,
+            StatusSection::Staging,
+        ];
+        let mut printed = Vec::new();
+        std::thread::scope(|scope| {
+            let drift = sender.clone();
+            scope.spawn(move || {
+                std::thread::sleep(Duration::from_millis(300));
+                let _ = drift.send((StatusSection::Drift, Ok(vec!["drift".to_string()])));
+            });
+            sender
+                .send((StatusSection::Staging, Ok(vec!["staging".to_string()])))
+                .unwrap();
+            sender
+                .send((StatusSection::Context, Ok(vec!["context".to_string()])))
+                .unwrap();
+            drop(sender);
+
+            let result = print_sections(&order, &receiver, Duration::from_millis(50), |line| {
+                printed.push(line.to_string())
+            });
+            assert!(result.is_ok());
+        });
+        // Ready sections go first in display order; the slow one follows
+        assert_eq!(printed, ["context", "staging", "drift"]);
+
+        // Within the budget the display order is kept
+        let (sender, receiver) = mpsc::channel();
+        sender
+            .send((StatusSection::Staging, Ok(vec!["staging".to_string()])))
+            .unwrap();
+        sender
+            .send((StatusSection::Drift, Err(JinError::Other("boom".into()))))
+            .unwrap();
+        sender
+            .send((StatusSection::Context, Ok(vec!["context".to_string()])))
+            .unwrap();
+        let mut printed = Vec::new();
+        let result = print_sections(&order, &receiver, Duration::from_secs(5), |line| {
+            printed.push(line.to_string())
+        });
+        assert!(matches!(result, Err(JinError::Other(_))));
+        assert_eq!(printed, ["context", "staging"]);
+    }
+
+    #[test]
+    #[serial]
+    fn test_json_report_matches_schema() {
+        let ctx = crate::test_utils::setup_unit_test();
+        let context = ProjectContext {
+            mode: Some("claude".to_string()),
+            ..ProjectContext::default()
+        };
+        let mut staging = StagingIndex::new();
+        staging.add(crate::staging::StagedEntry::new(
+            PathBuf::from("config.json"),
+            Layer::ModeBase,
+            "abc123".to_string(),
+        ));
+        staging.save(&ctx.project_path).unwrap();
+
+        let json = serde_json::to_value(
+            report(&ctx.project_path, &StatusSection::all(), &context, None).unwrap(),
+        )
+        .unwrap();
+        crate::core::schema::assert_matches(&JsonSurface::Status.schema(), &json, "status");
+        assert_eq!(json["mode"], "claude");
+        assert_eq!(json["staged"][0]["path"], "config.json");
+        assert_eq!(json["staged"][0]["layer"], "mode-base");
+        assert_eq!(json["layers"][1]["layer"], "mode-base");
+        assert_eq!(
+            json["layers"][1]["ref_path"],
+            "refs/jin/layers/mode/claude/_"
+        );
+        assert_eq!(json["layers"][1]["storage"], "jin/mode/claude/");
+        assert_eq!(json["workspace"]["clean"], true);
+
+        // Sections not asked for are left out
+        let report = report(&ctx.project_path, &[StatusSection::Staging], &context, None).unwrap();
+        assert!(report.workspace.is_none());
+        assert_eq!(report.staged.map(|staged| staged.len()), Some(1));
+    }
+
     #[test]
     fn test_execute_not_initialized() {
         let temp = TempDir::new().unwrap();
         std::env::set_current_dir(temp.path()).unwrap();
 
-        let result = execute();
+        let result = execute(StatusArgs::default(), temp.path());
         assert!(matches!(result, Err(JinError::NotInitialized)));
     }
 
@@ -321,7 +1045,7 @@ mod tests {
         let _ = std::env::set_current_dir(temp.path());
 
         // No paused state file exists
-        let result = check_for_conflicts();
+        let result = check_for_conflicts(temp.path());
         assert!(result.is_none());
     }
 
@@ -356,6 +1080,7 @@ mod tests {
             conflict_files: vec![PathBuf::from("config.json")],
             applied_files: vec![],
             conflict_count: 1,
+            layer_commits: Default::default(),
         };
 
         // Manually write the state file to .jin/.paused_apply.yaml
@@ -364,7 +1089,7 @@ mod tests {
         fs::write(&state_path, content).unwrap();
 
         // Verify check_for_conflicts returns Some
-        let result = check_for_conflicts();
+        let result = check_for_conflicts(temp_path);
         assert!(result.is_some());
 
         // Verify the state has expected content
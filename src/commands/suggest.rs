@@ -0,0 +1,384 @@
+//! Implementation of `jin suggest`
+//!
+//! Inspects the workspace for languages, frameworks and AI tool files and
+//! recommends modes and scopes to activate (or create). With `--apply`, the
+//! primary suggestion of each kind is created if missing and activated.
+
+use crate::cli::SuggestArgs;
+use crate::commands::{mode, scope};
+use crate::core::dangling::{mode_exists, scope_exists};
+use crate::core::{ProjectContext, Result};
+use crate::git::JinRepo;
+use std::path::Path;
+
+/// Kind of context a suggestion refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionKind {
+    /// A mode (AI tool or workflow)
+    Mode,
+    /// A scope (language or framework)
+    Scope,
+}
+
+/// A recommended mode or scope
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// Whether this is a mode or a scope
+    pub kind: SuggestionKind,
+    /// Mode or scope name (e.g., `claude`, `language:rust`)
+    pub name: String,
+    /// Workspace evidence for the suggestion
+    pub reason: String,
+}
+
+/// Files or directories whose presence suggests a mode or scope
+///
+/// Order matters: the first match of each kind is the primary suggestion
+/// used by `--apply`.
+const MARKERS: &[(&str, SuggestionKind, &str)] = &[
+    ("CLAUDE.md", SuggestionKind::Mode, "claude"),
+    (".claude", SuggestionKind::Mode, "claude"),
+    (".cursorrules", SuggestionKind::Mode, "cursor"),
+    (".cursor", SuggestionKind::Mode, "cursor"),
+    (
+        ".github/copilot-instructions.md",
+        SuggestionKind::Mode,
+        "copilot",
+    ),
+    (".windsurfrules", SuggestionKind::Mode, "windsurf"),
+    ("Cargo.toml", SuggestionKind::Scope, "language:rust"),
+    (
+        "tsconfig.json",
+        SuggestionKind::Scope,
+        "language:typescript",
+    ),
+    ("package.json", SuggestionKind::Scope, "language:javascript"),
+    ("pyproject.toml", SuggestionKind::Scope, "language:python"),
+    ("requirements.txt", SuggestionKind::Scope, "language:python"),
+    ("setup.py", SuggestionKind::Scope, "language:python"),
+    ("go.mod", SuggestionKind::Scope, "language:go"),
+    ("Gemfile", SuggestionKind::Scope, "language:ruby"),
+    ("pom.xml", SuggestionKind::Scope, "language:java"),
+    ("build.gradle", SuggestionKind::Scope, "language:java"),
+];
+
+/// `package.json` dependencies that map to framework scopes
+const JS_FRAMEWORKS: &[(&str, &str)] = &[
+    ("next", "framework:nextjs"),
+    ("react", "framework:react"),
+    ("vue", "framework:vue"),
+    ("svelte", "framework:svelte"),
+    ("@angular/core", "framework:angular"),
+    ("express", "framework:express"),
+];
+
+/// Python requirements that map to framework scopes
+const PY_FRAMEWORKS: &[(&str, &str)] = &[
+    ("django", "framework:django"),
+    ("flask", "framework:flask"),
+    ("fastapi", "framework:fastapi"),
+];
+
+/// Execute the suggest command
+pub fn execute(args: SuggestArgs, root: &Path) -> Result<()> {
+    let context = ProjectContext::load(root)?;
+    let repo = JinRepo::open_or_create(root)?;
+
+    let suggestions = detect(root);
+    if suggestions.is_empty() {
+        println!("No suggestions: no known languages, frameworks or tool files found.");
+        println!("Create contexts manually with: jin mode create <name> / jin scope create <name>");
+        return Ok(());
+    }
+
+    for (kind, heading) in [
+        (SuggestionKind::Mode, "Suggested modes:"),
+        (SuggestionKind::Scope, "Suggested scopes:"),
+    ] {
+        let of_kind: Vec<&Suggestion> = suggestions.iter().filter(|s| s.kind == kind).collect();
+        if of_kind.is_empty() {
+            continue;
+        }
+        println!("{}", heading);
+        for suggestion in of_kind {
+            println!(
+                "  {:<24} {:<36} ({})",
+                suggestion.name,
+                suggestion.reason,
+                status_label(&repo, &context, suggestion)
+            );
+        }
+    }
+
+    let primary_mode = primary(&suggestions, SuggestionKind::Mode);
+    let primary_scope = primary(&suggestions, SuggestionKind::Scope);
+
+    if !args.apply {
+        let mut targets = Vec::new();
+        if let Some(s) = primary_mode {
+            targets.push(format!("mode {}", s.name));
+        }
+        if let Some(s) = primary_scope {
+            targets.push(format!("scope {}", s.name));
+        }
+        println!();
+        println!(
+            "Run 'jin suggest --apply' to create and activate: {}",
+            targets.join(", ")
+        );
+        return Ok(());
+    }
+
+    println!();
+    if let Some(s) = primary_mode {
+        if context.mode.as_deref() != Some(s.name.as_str()) {
+            if !mode_exists(&repo, &s.name) {
+                mode::create(root, &s.name)?;
+            }
+            mode::use_mode(root, &s.name)?;
+        }
+    }
+    if let Some(s) = primary_scope {
+        if context.scope.as_deref() != Some(s.name.as_str()) {
+            if !scope_exists(&repo, &s.name) {
+                scope::create(root, &s.name, None)?;
+            }
+            scope::use_scope(root, &s.name)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Detect suggestions for the workspace rooted at `root`
+///
+/// Each name is suggested at most once, with the reason of its first match.
+pub fn detect(root: &Path) -> Vec<Suggestion> {
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+    let mut push = |kind: SuggestionKind, name: &str, reason: String| {
+        if !suggestions.iter().any(|s| s.name == name && s.kind == kind) {
+            suggestions.push(Suggestion {
+                kind,
+                name: name.to_string(),
+                reason,
+            });
+        }
+    };
+
+    for (marker, kind, name) in MARKERS {
+        if root.join(marker).exists() {
+            push(*kind, name, format!("{} found", marker));
+        }
+    }
+
+    for dependency in package_json_dependencies(root) {
+        if let Some((_, name)) = JS_FRAMEWORKS.iter().find(|(dep, _)| *dep == dependency) {
+            push(
+                SuggestionKind::Scope,
+                name,
+                format!("package.json depends on {}", dependency),
+            );
+        }
+    }
+
+    for requirement in python_requirements(root) {
+        if let Some((_, name)) = PY_FRAMEWORKS.iter().find(|(dep, _)| *dep == requirement) {
+            push(
+                SuggestionKind::Scope,
+                name,
+                format!("Python project requires {}", requirement),
+            );
+        }
+    }
+
+    suggestions
+}
+
+/// The AI tool mode a workspace path belongs to, if it is (or is inside) a
+/// tool marker such as `CLAUDE.md` or `.cursor/`
+pub fn tool_mode(path: &Path) -> Option<&'static str> {
+    MARKERS
+        .iter()
+        .filter(|(_, kind, _)| *kind == SuggestionKind::Mode)
+        .find(|(marker, _, _)| path.starts_with(marker))
+        .map(|(_, _, name)| *name)
+}
+
+/// First suggestion of a kind
+fn primary(suggestions: &[Suggestion], kind: SuggestionKind) -> Option<&Suggestion> {
+    suggestions.iter().find(|s| s.kind == kind)
+}
+
+/// Names of `dependencies` and `devDependencies` in `package.json`
+fn package_json_dependencies(root: &Path) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(root.join("package.json")) else {
+        return Vec::new();
+    };
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return Vec::new();
+    };
+
+    ["dependencies", "devDependencies"]
+        .iter()
+        .filter_map(|section| json.get(section).and_then(|v| v.as_object()))
+        .flat_map(|deps| deps.keys().cloned())
+        .collect()
+}
+
+/// Lowercased package names from `requirements.txt` and `pyproject.toml`
+///
+/// This is a lightweight scan, not a full parser: any line in
+/// `requirements.txt` and any quoted requirement string in `pyproject.toml`
+/// contributes its leading package name.
+fn python_requirements(root: &Path) -> Vec<String> {
+    let mut names = Vec::new();
+
+    if let Ok(content) = std::fs::read_to_string(root.join("requirements.txt")) {
+        names.extend(
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with('-'))
+                .map(package_name),
+        );
+    }
+
+    if let Ok(content) = std::fs::read_to_string(root.join("pyproject.toml")) {
+        names.extend(
+            content
+                .split('"')
+                .skip(1)
+                .step_by(2)
+                .map(package_name)
+                .filter(|n| !n.is_empty()),
+        );
+    }
+
+    names
+}
+
+/// Leading package name of a requirement specifier (e.g., `Django>=4` → `django`)
+fn package_name(spec: &str) -> String {
+    spec.chars()
+        .take_while(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Whether a suggestion is already active, exists, or would be created
+fn status_label(repo: &JinRepo, context: &ProjectContext, suggestion: &Suggestion) -> &'static str {
+    let (active, exists) = match suggestion.kind {
+        SuggestionKind::Mode => (
+            context.mode.as_deref() == Some(suggestion.name.as_str()),
+            mode_exists(repo, &suggestion.name),
+        ),
+        SuggestionKind::Scope => (
+            context.scope.as_deref() == Some(suggestion.name.as_str()),
+            scope_exists(repo, &suggestion.name),
+        ),
+    };
+
+    if active {
+        "active"
+    } else if exists {
+        "exists"
+    } else {
+        "new"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_markers() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("Cargo.toml"), "[package]\n").unwrap();
+        std::fs::create_dir(temp.path().join(".claude")).unwrap();
+        std::fs::write(temp.path().join("CLAUDE.md"), "# notes\n").unwrap();
+
+        let suggestions = detect(temp.path());
+        assert_eq!(
+            suggestions,
+            vec![
+                Suggestion {
+                    kind: SuggestionKind::Mode,
+                    name: "claude".to_string(),
+                    reason: "CLAUDE.md found".to_string(),
+                },
+                Suggestion {
+                    kind: SuggestionKind::Scope,
+                    name: "language:rust".to_string(),
+                    reason: "Cargo.toml found".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_frameworks() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("package.json"),
+            r#"{"dependencies": {"react": "^18"}, "devDependencies": {"vite": "^5"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join("requirements.txt"),
+            "# web\nDjango>=4.2\nrequests\n",
+        )
+        .unwrap();
+
+        let names: Vec<String> = detect(temp.path()).into_iter().map(|s| s.name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "language:javascript",
+                "language:python",
+                "framework:react",
+                "framework:django"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_empty_workspace() {
+        let temp = TempDir::new().unwrap();
+        assert!(detect(temp.path()).is_empty());
+    }
+
+    #[test]
+    fn test_tool_mode() {
+        assert_eq!(tool_mode(Path::new("CLAUDE.md")), Some("claude"));
+        assert_eq!(tool_mode(Path::new(".cursor/rules/a.mdc")), Some("cursor"));
+        assert_eq!(tool_mode(Path::new(".claudeignore")), None);
+        assert_eq!(tool_mode(Path::new("Cargo.toml")), None);
+    }
+
+    #[test]
+    fn test_package_name() {
+        assert_eq!(package_name("Django>=4.2"), "django");
+        assert_eq!(package_name("fastapi[all]==0.110"), "fastapi");
+        assert_eq!(package_name(">=3.9"), "");
+    }
+
+    #[test]
+    #[serial]
+    fn test_apply_creates_and_activates() {
+        let ctx = crate::test_utils::setup_unit_test();
+        std::fs::write(ctx.project_path.join("go.mod"), "module example\n").unwrap();
+        std::fs::write(ctx.project_path.join(".cursorrules"), "rules\n").unwrap();
+
+        execute(SuggestArgs { apply: true }, &ctx.project_path).unwrap();
+
+        let context = ProjectContext::load(&ctx.project_path).unwrap();
+        assert_eq!(context.mode.as_deref(), Some("cursor"));
+        assert_eq!(context.scope.as_deref(), Some("language:go"));
+
+        // Re-running is a no-op once everything is active
+        execute(SuggestArgs { apply: true }, &ctx.project_path).unwrap();
+    }
+}
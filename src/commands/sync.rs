@@ -3,7 +3,12 @@
 //! Orchestrates fetch + pull + apply for complete synchronization workflow.
 
 use crate::cli::ApplyArgs;
-use crate::core::Result;
+use crate::commands::pull::PausedPullState;
+use crate::core::{progress, ProjectContext, Result};
+use crate::git::executor::{check_interrupted, install_interrupt_handler};
+use crate::notify::{self, EventKind, NotifyEvent};
+use crate::staging::WorkspaceMetadata;
+use std::path::Path;
 
 /// Execute the sync command
 ///
@@ -13,47 +18,89 @@ use crate::core::Result;
 /// 3. Apply: Regenerate workspace files
 ///
 /// This is equivalent to running `jin fetch && jin pull && jin apply` in sequence.
-pub fn execute() -> Result<()> {
+/// Ctrl-C stops the sync at the next step boundary, so refs are never left
+/// half-updated.
+pub fn execute(root: &Path) -> Result<()> {
+    install_interrupt_handler();
     println!("=== Jin Sync: Fetch + Pull + Apply ===\n");
 
     // Step 1: Fetch remote updates
     println!("Step 1/3: Fetching remote updates...");
-    match super::fetch::execute() {
+    match super::fetch::execute(root) {
         Ok(()) => println!("✓ Fetch completed\n"),
         Err(e) => {
-            eprintln!("✗ Fetch failed: {}", e);
+            if !progress::is_json() {
+                eprintln!("✗ Fetch failed: {}", e);
+            }
             return Err(e);
         }
     }
 
     // Step 2: Pull (merge) remote changes
+    check_interrupted()?;
     println!("Step 2/3: Merging remote changes...");
-    match super::pull::execute() {
+    match super::pull::execute(root) {
         Ok(()) => println!("✓ Pull completed\n"),
         Err(e) => {
-            eprintln!("✗ Pull failed: {}", e);
-            eprintln!("\nSync stopped at merge phase.");
-            eprintln!("Resolve conflicts and run 'jin apply' to complete workspace update.");
+            if !progress::is_json() {
+                eprintln!("✗ Pull failed: {}", e);
+                eprintln!("\nSync stopped at merge phase.");
+                eprintln!("Resolve conflicts and run 'jin apply' to complete workspace update.");
+            }
             return Err(e);
         }
     }
 
+    // A pull paused on conflicts leaves some layers unmerged
+    if PausedPullState::exists(root) {
+        println!("Sync paused at merge phase.");
+        println!(
+            "Resolve conflicts with 'jin resolve', then run 'jin apply' to update the workspace."
+        );
+        return Ok(());
+    }
+
     // Step 3: Apply to workspace
+    check_interrupted()?;
     println!("Step 3/3: Applying to workspace...");
     let apply_args = ApplyArgs {
         force: false,
         dry_run: false,
+        target: None,
+        ours: false,
+        theirs: false,
+        prefer: None,
+        provenance: false,
+        merge_opt: Vec::new(),
+        debounce: None,
+        no_reload: false,
     };
-    match super::apply::execute(apply_args) {
+    match super::apply::execute(apply_args, root) {
         Ok(()) => println!("✓ Apply completed\n"),
         Err(e) => {
-            eprintln!("✗ Apply failed: {}", e);
-            eprintln!("\nRemote changes merged successfully, but workspace update failed.");
-            eprintln!("Run 'jin apply' manually to update workspace files.");
+            if !progress::is_json() {
+                eprintln!("✗ Apply failed: {}", e);
+                eprintln!("\nRemote changes merged successfully, but workspace update failed.");
+                eprintln!("Run 'jin apply' manually to update workspace files.");
+            }
             return Err(e);
         }
     }
 
+    // Notify external automation with the resulting workspace state
+    let context = ProjectContext::load(root).unwrap_or_default();
+    let mut files: Vec<String> = WorkspaceMetadata::load(root)
+        .map(|m| m.files.keys().map(|p| p.display().to_string()).collect())
+        .unwrap_or_default();
+    files.sort();
+    notify::notify(&NotifyEvent::new(
+        EventKind::Sync,
+        context.project,
+        context.mode,
+        context.scope,
+        files,
+    ));
+
     println!("=== Sync completed successfully ===");
     println!("Your workspace is now synchronized with the remote repository.");
 
@@ -69,7 +116,7 @@ mod tests {
         // Verify the execute function signature is correct
         // Actual execution would require a full Jin environment
         fn _type_check() {
-            let _: fn() -> Result<()> = execute;
+            let _: fn(&Path) -> Result<()> = execute;
         }
     }
 }
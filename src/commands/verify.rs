@@ -0,0 +1,510 @@
+//! Implementation of `jin verify`
+//!
+//! Recomputes object hashes to detect bit-rot, manual tampering in the bare
+//! repository, or partial writes. Three things are checked:
+//! - every blob reachable from a layer ref hashes to its object ID
+//! - layer trees match the file lists recorded in `.jinmap`
+//! - files written by the last `jin apply` still match their recorded hashes
+//!
+//! Unlike `jin repair`, this command never modifies anything.
+
+use crate::cli::{VerifyArgs, VerifyFormat};
+use crate::core::schema::JsonSurface;
+use crate::core::{JinError, JinMap, Result};
+use crate::git::{JinRepo, RefOps};
+use crate::staging::WorkspaceMetadata;
+use git2::{ObjectType, Oid};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Kind of problem found during verification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IssueKind {
+    /// Object content does not hash to its ID
+    HashMismatch,
+    /// Object is missing or cannot be read
+    UnreadableObject,
+    /// Layer recorded in `.jinmap` has no ref
+    MissingLayer,
+    /// File recorded in `.jinmap` is absent from the layer tree
+    MissingFile,
+    /// File in the layer tree is not recorded in `.jinmap`
+    UnrecordedFile,
+    /// Applied workspace file no longer matches its recorded hash
+    WorkspaceModified,
+    /// Applied workspace file no longer exists
+    WorkspaceMissing,
+}
+
+impl IssueKind {
+    /// Kebab-case name, as used in the JSON report
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IssueKind::HashMismatch => "hash-mismatch",
+            IssueKind::UnreadableObject => "unreadable-object",
+            IssueKind::MissingLayer => "missing-layer",
+            IssueKind::MissingFile => "missing-file",
+            IssueKind::UnrecordedFile => "unrecorded-file",
+            IssueKind::WorkspaceModified => "workspace-modified",
+            IssueKind::WorkspaceMissing => "workspace-missing",
+        }
+    }
+}
+
+/// A single verification problem
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VerifyIssue {
+    /// What went wrong
+    pub kind: IssueKind,
+    /// Layer ref the problem was found in, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layer: Option<String>,
+    /// File path the problem concerns, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Recorded hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// Recomputed hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<String>,
+}
+
+/// Result of a verification run
+#[derive(Debug, Serialize)]
+pub struct VerifyReport {
+    /// Version of the JSON report layout
+    pub schema_version: u32,
+    /// Number of layer refs checked
+    pub layers_checked: usize,
+    /// Number of blobs rehashed
+    pub blobs_checked: usize,
+    /// Number of last-applied workspace files rehashed
+    pub workspace_files_checked: usize,
+    /// Problems found
+    pub issues: Vec<VerifyIssue>,
+}
+
+impl Default for VerifyReport {
+    fn default() -> Self {
+        Self {
+            schema_version: JsonSurface::Verify.version(),
+            layers_checked: 0,
+            blobs_checked: 0,
+            workspace_files_checked: 0,
+            issues: Vec::new(),
+        }
+    }
+}
+
+impl VerifyReport {
+    /// Whether no problems were found
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn push(
+        &mut self,
+        kind: IssueKind,
+        layer: Option<&str>,
+        path: Option<&str>,
+        expected: Option<String>,
+        actual: Option<String>,
+    ) {
+        self.issues.push(VerifyIssue {
+            kind,
+            layer: layer.map(str::to_string),
+            path: path.map(str::to_string),
+            expected,
+            actual,
+        });
+    }
+}
+
+/// Execute the verify command
+pub fn execute(args: VerifyArgs, root: &Path) -> Result<()> {
+    let repo = JinRepo::open(root)?;
+    let jinmap = JinMap::load(root)?;
+    let metadata = match WorkspaceMetadata::load(root) {
+        Ok(m) => Some(m),
+        Err(JinError::NotFound(_)) => None,
+        Err(e) => return Err(e),
+    };
+
+    let mut report = VerifyReport::default();
+    verify_layers(&repo, &jinmap, &mut report)?;
+    if let Some(metadata) = &metadata {
+        verify_workspace(root, metadata, &mut report);
+    }
+
+    match args.format {
+        VerifyFormat::Text => print_text(&report),
+        VerifyFormat::Json => {
+            let json = serde_json::to_string_pretty(&report).map_err(|e| JinError::Parse {
+                format: "JSON".to_string(),
+                message: e.to_string(),
+            })?;
+            println!("{}", json);
+        }
+    }
+
+    if report.is_ok() {
+        Ok(())
+    } else {
+        Err(JinError::Other(format!(
+            "Verification failed: {} issue(s) found",
+            report.issues.len()
+        )))
+    }
+}
+
+/// Rehash every blob reachable from layer refs and compare trees with `.jinmap`
+pub fn verify_layers(repo: &JinRepo, jinmap: &JinMap, report: &mut VerifyReport) -> Result<()> {
+    let mut refs = repo.list_refs("refs/jin/layers/*")?;
+    refs.sort();
+
+    for ref_name in &refs {
+        report.layers_checked += 1;
+
+        let commit = match repo
+            .resolve_ref(ref_name)
+            .and_then(|oid| Ok(repo.inner().find_commit(oid)?))
+        {
+            Ok(commit) => commit,
+            Err(e) => {
+                report.push(
+                    IssueKind::UnreadableObject,
+                    Some(ref_name),
+                    None,
+                    None,
+                    Some(e.to_string()),
+                );
+                continue;
+            }
+        };
+
+        let mut files = BTreeSet::new();
+        check_tree(repo, ref_name, commit.tree_id(), "", &mut files, report);
+
+        if let Some(recorded) = jinmap.get_layer_files(ref_name) {
+            for path in recorded {
+                if !files.contains(path) {
+                    report.push(
+                        IssueKind::MissingFile,
+                        Some(ref_name),
+                        Some(path),
+                        None,
+                        None,
+                    );
+                }
+            }
+            for path in &files {
+                if !recorded.contains(path) {
+                    report.push(
+                        IssueKind::UnrecordedFile,
+                        Some(ref_name),
+                        Some(path),
+                        None,
+                        None,
+                    );
+                }
+            }
+        }
+    }
+
+    let mut recorded_refs = jinmap.layer_refs();
+    recorded_refs.sort();
+    for ref_name in recorded_refs {
+        if !refs.contains(ref_name) {
+            report.push(IssueKind::MissingLayer, Some(ref_name), None, None, None);
+        }
+    }
+
+    Ok(())
+}
+
+/// Recursively rehash the blobs of a tree, collecting file paths
+fn check_tree(
+    repo: &JinRepo,
+    layer: &str,
+    tree_id: Oid,
+    prefix: &str,
+    files: &mut BTreeSet<String>,
+    report: &mut VerifyReport,
+) {
+    let tree = match repo.inner().find_tree(tree_id) {
+        Ok(tree) => tree,
+        Err(e) => {
+            let path = (!prefix.is_empty()).then_some(prefix);
+            report.push(
+                IssueKind::UnreadableObject,
+                Some(layer),
+                path,
+                Some(tree_id.to_string()),
+                Some(e.message().to_string()),
+            );
+            return;
+        }
+    };
+
+    for entry in tree.iter() {
+        let name = entry.name().unwrap_or_default();
+        let path = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+
+        match entry.kind() {
+            Some(ObjectType::Tree) => check_tree(repo, layer, entry.id(), &path, files, report),
+            Some(ObjectType::Blob) => {
+                report.blobs_checked += 1;
+                check_blob(repo, layer, entry.id(), &path, report);
+                files.insert(path);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Read a blob from the object database and compare its hash with its ID
+fn check_blob(repo: &JinRepo, layer: &str, id: Oid, path: &str, report: &mut VerifyReport) {
+    let odb = match repo.inner().odb() {
+        Ok(odb) => odb,
+        Err(e) => {
+            report.push(
+                IssueKind::UnreadableObject,
+                Some(layer),
+                Some(path),
+                Some(id.to_string()),
+                Some(e.message().to_string()),
+            );
+            return;
+        }
+    };
+
+    let rehashed = odb
+        .read(id)
+        .and_then(|object| Oid::hash_object(ObjectType::Blob, object.data()));
+    match rehashed {
+        Ok(actual) if actual == id => {}
+        Ok(actual) => report.push(
+            IssueKind::HashMismatch,
+            Some(layer),
+            Some(path),
+            Some(id.to_string()),
+            Some(actual.to_string()),
+        ),
+        Err(e) => report.push(
+            IssueKind::UnreadableObject,
+            Some(layer),
+            Some(path),
+            Some(id.to_string()),
+            Some(e.message().to_string()),
+        ),
+    }
+}
+
+/// Rehash files written by the last apply and compare with recorded hashes
+pub fn verify_workspace(root: &Path, metadata: &WorkspaceMetadata, report: &mut VerifyReport) {
+    let mut paths: Vec<&Path> = metadata.files.keys().map(|p| p.as_path()).collect();
+    paths.sort();
+
+    for path in paths {
+        report.workspace_files_checked += 1;
+        let expected = &metadata.files[path];
+        let display = path.display().to_string();
+
+        let content = match std::fs::read(root.join(metadata.location(path))) {
+            Ok(content) => content,
+            Err(_) => {
+                report.push(
+                    IssueKind::WorkspaceMissing,
+                    None,
+                    Some(&display),
+                    Some(expected.clone()),
+                    None,
+                );
+                continue;
+            }
+        };
+
+        // Hash without writing, so verification leaves the repository untouched
+        let actual = match Oid::hash_object(ObjectType::Blob, &content) {
+            Ok(oid) => oid.to_string(),
+            Err(_) => continue,
+        };
+        if actual != *expected {
+            report.push(
+                IssueKind::WorkspaceModified,
+                None,
+                Some(&display),
+                Some(expected.clone()),
+                Some(actual),
+            );
+        }
+    }
+}
+
+/// Print a human-readable report
+fn print_text(report: &VerifyReport) {
+    println!(
+        "Checked {} layer(s), {} blob(s), {} workspace file(s)",
+        report.layers_checked, report.blobs_checked, report.workspace_files_checked
+    );
+
+    if report.is_ok() {
+        println!("All hashes verified.");
+        return;
+    }
+
+    println!();
+    println!("Issues ({}):", report.issues.len());
+    for issue in &report.issues {
+        let mut line = format!("  {}", issue.kind.as_str());
+        if let Some(layer) = &issue.layer {
+            line.push_str(&format!(" {}", layer));
+        }
+        if let Some(path) = &issue.path {
+            line.push_str(&format!(" {}", path));
+        }
+        if let Some(expected) = &issue.expected {
+            line.push_str(&format!(" (expected {}", expected));
+            match &issue.actual {
+                Some(actual) => line.push_str(&format!(", got {})", actual)),
+                None => line.push(')'),
+            }
+        } else if let Some(actual) = &issue.actual {
+            line.push_str(&format!(" ({})", actual));
+        }
+        println!("{}", line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::ObjectOps;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn commit_files(repo: &JinRepo, ref_name: &str, files: &[(&str, &[u8])]) -> Oid {
+        let entries: Vec<(String, Oid)> = files
+            .iter()
+            .map(|(path, content)| (path.to_string(), repo.create_blob(content).unwrap()))
+            .collect();
+        let tree = repo.create_tree_from_paths(&entries).unwrap();
+        let commit = repo.create_commit(None, "test", tree, &[]).unwrap();
+        repo.set_ref(ref_name, commit, "test").unwrap();
+        commit
+    }
+
+    /// Path of a loose object inside a bare repository
+    fn loose_object_path(repo: &JinRepo, oid: Oid) -> PathBuf {
+        let hex = oid.to_string();
+        repo.path().join("objects").join(&hex[..2]).join(&hex[2..])
+    }
+
+    #[test]
+    fn test_verify_layers_clean() {
+        let temp = TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join("repo")).unwrap();
+        commit_files(
+            &repo,
+            "refs/jin/layers/global",
+            &[("a.json", b"{}"), ("dir/b.txt", b"b")],
+        );
+
+        let mut jinmap = JinMap::default();
+        jinmap.add_layer_mapping(
+            "refs/jin/layers/global",
+            vec!["a.json".to_string(), "dir/b.txt".to_string()],
+        );
+
+        let mut report = VerifyReport::default();
+        verify_layers(&repo, &jinmap, &mut report).unwrap();
+        assert!(report.is_ok(), "{:?}", report.issues);
+        assert_eq!(report.layers_checked, 1);
+        assert_eq!(report.blobs_checked, 2);
+    }
+
+    #[test]
+    fn test_verify_layers_reports_jinmap_drift() {
+        let temp = TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join("repo")).unwrap();
+        commit_files(&repo, "refs/jin/layers/global", &[("a.json", b"{}")]);
+
+        let mut jinmap = JinMap::default();
+        jinmap.add_layer_mapping("refs/jin/layers/global", vec!["gone.json".to_string()]);
+        jinmap.add_layer_mapping("refs/jin/layers/mode/old/_", vec!["x".to_string()]);
+
+        let mut report = VerifyReport::default();
+        verify_layers(&repo, &jinmap, &mut report).unwrap();
+
+        let kinds: Vec<IssueKind> = report.issues.iter().map(|i| i.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                IssueKind::MissingFile,
+                IssueKind::UnrecordedFile,
+                IssueKind::MissingLayer
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verify_layers_reports_missing_blob() {
+        let temp = TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join("repo")).unwrap();
+        commit_files(
+            &repo,
+            "refs/jin/layers/global",
+            &[("a.json", b"{\"k\": 1}")],
+        );
+
+        let blob = Oid::hash_object(ObjectType::Blob, b"{\"k\": 1}").unwrap();
+        std::fs::remove_file(loose_object_path(&repo, blob)).unwrap();
+
+        let mut report = VerifyReport::default();
+        verify_layers(&repo, &JinMap::default(), &mut report).unwrap();
+
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].kind, IssueKind::UnreadableObject);
+        assert_eq!(report.issues[0].path.as_deref(), Some("a.json"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        crate::core::schema::assert_matches(&JsonSurface::Verify.schema(), &json, "verify");
+    }
+
+    #[test]
+    fn test_verify_workspace_detects_changes() {
+        let temp = TempDir::new().unwrap();
+
+        let same = temp.path().join("same.txt");
+        let changed = temp.path().join("changed.txt");
+        std::fs::write(&same, "same").unwrap();
+        std::fs::write(&changed, "new").unwrap();
+
+        let hash = |content: &[u8]| Oid::hash_object(ObjectType::Blob, content).unwrap();
+        let mut metadata = WorkspaceMetadata::new();
+        metadata.add_file(same.clone(), hash(b"same").to_string());
+        metadata.add_file(changed.clone(), hash(b"old").to_string());
+        metadata.add_file(temp.path().join("missing.txt"), hash(b"x").to_string());
+
+        let mut report = VerifyReport::default();
+        verify_workspace(temp.path(), &metadata, &mut report);
+
+        assert_eq!(report.workspace_files_checked, 3);
+        let kinds: Vec<IssueKind> = report.issues.iter().map(|i| i.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![IssueKind::WorkspaceModified, IssueKind::WorkspaceMissing]
+        );
+        assert_eq!(
+            report.issues[0].actual.as_deref(),
+            Some(hash(b"new").to_string().as_str())
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Implementation of `jin version`
+//!
+//! Prints the installed version. Wrapper tools and editor plugins use
+//! `jin version --json --capabilities` to adapt to the installed Jin: it
+//! lists the file formats the merge engine understands, the
+//! `schema_version` of every JSON output and which optional features this
+//! build supports.
+
+use crate::cli::VersionArgs;
+use crate::core::schema::{self, JsonSurface};
+use crate::core::Result;
+use crate::git::repo::REPO_VERSION;
+use crate::merge::registry;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Features tools ask about, including ones this build lacks, so that
+/// "unsupported" can be told apart from "unknown to this version"
+const FEATURES: &[(&str, bool)] = &[
+    ("remotes", true),
+    ("named-remotes", true),
+    ("locks", true),
+    ("archive", true),
+    ("local-sync", true),
+    ("branch-scopes", true),
+    ("reload-hooks", true),
+    ("notifications", true),
+    ("audit-log", true),
+    ("encryption", false),
+    ("watch", false),
+];
+
+/// `jin version --json` output
+#[derive(Debug, Serialize)]
+struct VersionReport {
+    schema_version: u32,
+    version: &'static str,
+    repo_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    capabilities: Option<Capabilities>,
+}
+
+/// What this build supports
+#[derive(Debug, Serialize)]
+struct Capabilities {
+    formats: Vec<FormatInfo>,
+    /// `schema_version` of each JSON output, by `jin schema` name
+    schemas: BTreeMap<&'static str, u32>,
+    features: BTreeMap<&'static str, bool>,
+}
+
+/// A file format the merge engine understands
+#[derive(Debug, Serialize)]
+struct FormatInfo {
+    name: &'static str,
+    /// Extensions detected as this format (none when detected by file name)
+    extensions: Vec<&'static str>,
+}
+
+impl Capabilities {
+    fn current() -> Self {
+        let mut formats: Vec<FormatInfo> = registry()
+            .backends()
+            .map(|backend| FormatInfo {
+                name: backend.format().name(),
+                extensions: backend.extensions().to_vec(),
+            })
+            .collect();
+        formats.push(FormatInfo {
+            name: "text",
+            extensions: Vec::new(),
+        });
+
+        Self {
+            formats,
+            schemas: JsonSurface::all()
+                .iter()
+                .map(|surface| (surface.name(), surface.version()))
+                .collect(),
+            features: FEATURES.iter().copied().collect(),
+        }
+    }
+}
+
+fn report(capabilities: bool) -> VersionReport {
+    VersionReport {
+        schema_version: JsonSurface::Version.version(),
+        version: env!("CARGO_PKG_VERSION"),
+        repo_version: REPO_VERSION,
+        capabilities: capabilities.then(Capabilities::current),
+    }
+}
+
+/// Execute the version command
+pub fn execute(args: VersionArgs) -> Result<()> {
+    let report = report(args.capabilities);
+    if schema::json_output() {
+        return schema::print_json(&report);
+    }
+
+    println!(
+        "jin {} (repository v{})",
+        report.version, report.repo_version
+    );
+    let Some(capabilities) = report.capabilities else {
+        return Ok(());
+    };
+
+    let formats: Vec<String> = capabilities
+        .formats
+        .iter()
+        .map(|format| match format.extensions.as_slice() {
+            [] => format.name.to_string(),
+            extensions => format!("{} (.{})", format.name, extensions.join(", .")),
+        })
+        .collect();
+    println!("Formats:      {}", formats.join(", "));
+
+    let schemas: Vec<String> = capabilities
+        .schemas
+        .iter()
+        .map(|(name, version)| format!("{} v{}", name, version))
+        .collect();
+    println!("JSON outputs: {}", schemas.join(", "));
+
+    let names = |supported: bool| -> Vec<&str> {
+        FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled == supported)
+            .map(|(name, _)| *name)
+            .collect()
+    };
+    println!("Features:     {}", names(true).join(", "));
+    println!("Unsupported:  {}", names(false).join(", "));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_matches_schema() {
+        let schema = JsonSurface::Version.schema();
+        let plain = serde_json::to_value(report(false)).unwrap();
+        assert!(plain.get("capabilities").is_none());
+        crate::core::schema::assert_matches(&schema, &plain, "version");
+
+        let json = serde_json::to_value(report(true)).unwrap();
+        crate::core::schema::assert_matches(&schema, &json, "version");
+        let capabilities = &json["capabilities"];
+        assert_eq!(capabilities["schemas"]["version"], 1);
+        assert_eq!(capabilities["features"]["remotes"], true);
+        assert_eq!(capabilities["features"]["encryption"], false);
+        let formats: Vec<&str> = capabilities["formats"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|format| format["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(formats, ["json", "yaml", "toml", "ini", "env", "text"]);
+    }
+}
@@ -1,10 +1,12 @@
 //! Commit pipeline implementation
 
 use crate::audit::{AuditEntry, AuditLogger};
-use crate::core::{JinError, JinMap, Layer, ProjectContext, Result};
+use crate::core::{machine, BranchScope, JinError, JinMap, Layer, ProjectContext, Result};
+use crate::git::refs::RefSnapshot;
 use crate::git::{JinRepo, LayerTransaction, ObjectOps, RefOps};
 use crate::staging::{StagedEntry, StagingIndex};
 use git2::Oid;
+use std::path::Path;
 
 /// Configuration for a commit operation
 #[derive(Debug)]
@@ -53,12 +55,24 @@ pub struct CommitResult {
 pub struct CommitPipeline {
     /// The staging index to commit from
     staging: StagingIndex,
+    /// Entries left staged after the commit (`jin commit --only`)
+    remaining: Option<StagingIndex>,
 }
 
 impl CommitPipeline {
     /// Create a new commit pipeline
     pub fn new(staging: StagingIndex) -> Self {
-        Self { staging }
+        Self {
+            staging,
+            remaining: None,
+        }
+    }
+
+    /// Commit only the pipeline's entries, saving `remaining` as the staging
+    /// index afterwards instead of clearing it
+    pub fn keep_staged(mut self, remaining: StagingIndex) -> Self {
+        self.remaining = Some(remaining);
+        self
     }
 
     /// Execute the commit
@@ -69,7 +83,9 @@ impl CommitPipeline {
     /// 3. For each layer, build a tree and create a commit
     /// 4. Execute all ref updates atomically via LayerTransaction
     /// 5. Clear staging on success
-    pub fn execute(&mut self, config: &CommitConfig) -> Result<CommitResult> {
+    ///
+    /// `root` is the workspace whose context, staging index and logs are used.
+    pub fn execute(&mut self, root: &Path, config: &CommitConfig) -> Result<CommitResult> {
         // Validate staging not empty
         if self.staging.is_empty() {
             return Err(JinError::Other("Nothing to commit".to_string()));
@@ -84,10 +100,25 @@ impl CommitPipeline {
         }
 
         // Load context for ref path generation (use default if not initialized)
-        let context = ProjectContext::load().unwrap_or_default();
+        let mut context = ProjectContext::load(root).unwrap_or_default();
 
         // Open Jin repository
-        let repo = JinRepo::open_or_create()?;
+        let repo = JinRepo::open_or_create(root)?;
+
+        // Scope layers of a branch with its own scope go to that scope
+        BranchScope::activate(root, &mut context, &repo);
+
+        // Parents are read now; the refs must not move before the update
+        let snapshot = RefSnapshot::take(
+            &repo,
+            affected_layers.iter().map(|layer| {
+                layer.ref_path(
+                    context.mode.as_deref(),
+                    context.scope.as_deref(),
+                    context.project.as_deref(),
+                )
+            }),
+        );
 
         // Create commits for each layer, capturing parent commits
         let mut layer_commits: Vec<(Layer, Oid, Option<String>)> = Vec::new();
@@ -99,8 +130,18 @@ impl CommitPipeline {
             layer_commits.push((*layer, commit_oid, parent_oid));
         }
 
+        // Collect files for the transaction plan and audit
+        let files: Vec<String> = self
+            .staging
+            .entries()
+            .map(|e| e.path.display().to_string())
+            .collect();
+
+        // Committing on top of stale parents would drop the other changes
+        snapshot.ensure_unchanged(&repo, "jin commit")?;
+
         // Apply all updates atomically via transaction
-        let mut tx = LayerTransaction::begin(&repo, &config.message)?;
+        let mut tx = LayerTransaction::begin(&repo, root, &config.message)?;
         for (layer, commit_oid, _) in &layer_commits {
             tx.add_layer_update(
                 *layer,
@@ -110,18 +151,15 @@ impl CommitPipeline {
                 *commit_oid,
             )?;
         }
+        tx.record_files(files.clone())?;
         tx.commit()?;
 
-        // Collect files for audit before clearing staging
-        let files: Vec<String> = self
-            .staging
-            .entries()
-            .map(|e| e.path.display().to_string())
-            .collect();
-
         // Clear staging on success
         self.staging.clear();
-        self.staging.save()?;
+        match &self.remaining {
+            Some(remaining) => remaining.save(root)?,
+            None => self.staging.save(root)?,
+        }
 
         // Build result
         let commit_hashes: Vec<(Layer, String)> = layer_commits
@@ -130,12 +168,12 @@ impl CommitPipeline {
             .collect();
 
         // Update JinMap with new layer mappings (non-blocking)
-        if let Err(e) = self.update_jinmap(&layer_commits, &context, &repo) {
+        if let Err(e) = self.update_jinmap(root, &layer_commits, &context, &repo) {
             eprintln!("Warning: Failed to update .jinmap: {}", e);
         }
 
         // Write audit log (non-blocking - log warning on failure)
-        if let Err(e) = self.log_audit(&layer_commits, &context, &files) {
+        if let Err(e) = self.log_audit(root, &layer_commits, &context, &files) {
             eprintln!("Warning: Failed to write audit log: {}", e);
         }
 
@@ -174,8 +212,15 @@ impl CommitPipeline {
         // Capture parent OID for audit
         let parent_oid = parent_oids.first().map(|oid| oid.to_string());
 
+        // User-local history travels between the user's machines, so it
+        // records which one made each change
+        let message = match (layer, machine::current()) {
+            (Layer::UserLocal, Some(id)) => machine::with_trailer(message, &id),
+            _ => message.to_string(),
+        };
+
         // Create commit (don't update ref directly - transaction handles that)
-        let commit_oid = repo.create_commit(None, message, tree_oid, &parent_oids)?;
+        let commit_oid = repo.create_commit(None, &message, tree_oid, &parent_oids)?;
 
         Ok((commit_oid, parent_oid))
     }
@@ -307,15 +352,16 @@ impl CommitPipeline {
     /// This is a non-blocking operation - failures will log warnings but not fail the commit.
     fn log_audit(
         &self,
+        root: &Path,
         layer_commits: &[(Layer, Oid, Option<String>)],
         context: &ProjectContext,
         files: &[String],
     ) -> Result<()> {
         // Get user from Git config
-        let user = Self::get_git_user();
+        let user = Self::get_git_user(root);
 
         // Create audit logger
-        let logger = AuditLogger::from_project()?;
+        let logger = AuditLogger::from_project(root)?.with_machine(machine::current());
 
         // For each layer commit, create audit entry
         for (layer, commit_oid, base_commit) in layer_commits {
@@ -344,17 +390,19 @@ impl CommitPipeline {
     ///
     /// # Arguments
     ///
+    /// * `root` - Workspace root holding the `.jinmap`
     /// * `layer_commits` - Slice of (Layer, commit Oid, parent Oid) tuples from the commit
     /// * `context` - Project context for mode/scope/project values
     /// * `repo` - Jin repository for reading tree objects
     fn update_jinmap(
         &self,
+        root: &Path,
         layer_commits: &[(Layer, Oid, Option<String>)],
         context: &ProjectContext,
         repo: &JinRepo,
     ) -> Result<()> {
         // Load or create JinMap
-        let mut jinmap = JinMap::load()?;
+        let mut jinmap = JinMap::load(root)?;
 
         // Extract just the (Layer, Oid) pairs for update_from_commits
         let layer_oids: Vec<(Layer, Oid)> =
@@ -364,14 +412,15 @@ impl CommitPipeline {
         jinmap.update_from_commits(&layer_oids, context, repo)?;
 
         // Save updated JinMap
-        jinmap.save()?;
+        jinmap.save(root)?;
 
         Ok(())
     }
 
-    /// Get the current Git user from Git config
-    fn get_git_user() -> String {
+    /// Get the current Git user from the Git config seen from `root`
+    pub(crate) fn get_git_user(root: &Path) -> String {
         std::process::Command::new("git")
+            .current_dir(root)
             .args(["config", "user.email"])
             .output()
             .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
@@ -420,7 +469,7 @@ mod tests {
         let mut pipeline = CommitPipeline::new(staging);
         let config = CommitConfig::new("Empty commit");
 
-        let result = pipeline.execute(&config);
+        let result = pipeline.execute(Path::new("."), &config);
         assert!(result.is_err());
         if let Err(JinError::Other(msg)) = result {
             assert_eq!(msg, "Nothing to commit");
@@ -439,7 +488,7 @@ mod tests {
         let mut pipeline = CommitPipeline::new(staging);
         let config = CommitConfig::new("Test commit").dry_run(true);
 
-        let result = pipeline.execute(&config).unwrap();
+        let result = pipeline.execute(Path::new("."), &config).unwrap();
         assert_eq!(result.file_count, 1);
         assert!(result.committed_layers.contains(&Layer::ProjectBase));
         // Dry run should not produce commit hashes
@@ -703,7 +752,7 @@ mod tests {
         let mut pipeline = CommitPipeline::new(staging);
         let config = CommitConfig::new("Dry run test").dry_run(true);
 
-        let result = pipeline.execute(&config).unwrap();
+        let result = pipeline.execute(Path::new("."), &config).unwrap();
 
         assert_eq!(result.file_count, 2);
         assert_eq!(result.committed_layers.len(), 2);
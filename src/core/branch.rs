@@ -0,0 +1,130 @@
+//! Branch scopes
+//!
+//! With `jin config set apply.branch_scopes true`, the branch checked out in
+//! the host repository picks the scope: on `feature/login`, a scope named
+//! `branch:feature:login` (created with `jin scope create`) takes the place
+//! of the active scope for `jin apply`, `jin commit`, `jin status`,
+//! `jin layers` and `jin run`. Switching to a branch without such a scope (like `main`)
+//! brings the active scope back, so overrides committed to a branch scope
+//! only last as long as the branch is checked out.
+//!
+//! Branch names map to scope names by turning `/` into `:` (so each part is
+//! a ref component, like other scope names) and any other character a scope
+//! name can't hold into `_`.
+
+use crate::core::{ProjectConfig, ProjectContext};
+use crate::git::{JinRepo, RefOps};
+use std::path::Path;
+
+/// Prefix of scope names selected by branch
+pub const BRANCH_SCOPE_PREFIX: &str = "branch:";
+
+/// A scope activated by the host repository's branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchScope {
+    /// Branch checked out in the host repository
+    pub branch: String,
+    /// Scope name for the branch
+    pub scope: String,
+}
+
+impl BranchScope {
+    /// The branch scope to use in the current project, when
+    /// `apply.branch_scopes` is on and the scope for the checked-out branch
+    /// exists
+    pub fn active(root: &Path, repo: &JinRepo) -> Option<Self> {
+        let enabled = ProjectConfig::load(root)
+            .ok()?
+            .apply
+            .is_some_and(|apply| apply.branch_scopes);
+        if !enabled {
+            return None;
+        }
+        let found = Self::for_branch(&host_branch(root)?);
+        found.exists(repo).then_some(found)
+    }
+
+    /// Swap the branch scope into `context`, returning it when one applies
+    pub fn activate(root: &Path, context: &mut ProjectContext, repo: &JinRepo) -> Option<Self> {
+        let found = Self::active(root, repo)?;
+        context.scope = Some(found.scope.clone());
+        Some(found)
+    }
+
+    /// The scope a branch maps to
+    pub fn for_branch(branch: &str) -> Self {
+        let name: String = branch
+            .chars()
+            .map(|c| match c {
+                '/' => ':',
+                c if c.is_alphanumeric() || c == '_' => c,
+                _ => '_',
+            })
+            .collect();
+        Self {
+            branch: branch.to_string(),
+            scope: format!("{}{}", BRANCH_SCOPE_PREFIX, name),
+        }
+    }
+
+    /// Whether the scope was created, untethered or bound to any mode
+    pub fn exists(&self, repo: &JinRepo) -> bool {
+        let ref_safe_name = self.scope.replace(':', "/");
+        repo.ref_exists(&format!("refs/jin/scopes/{}", ref_safe_name))
+            || !repo
+                .list_refs(&format!("refs/jin/modes/*/scopes/{}", ref_safe_name))
+                .unwrap_or_default()
+                .is_empty()
+    }
+}
+
+/// The branch checked out in the Git repository holding `dir`, if any
+/// (`None` on a detached HEAD or outside a repository)
+pub fn host_branch(dir: &Path) -> Option<String> {
+    let repo = git2::Repository::discover(dir).ok()?;
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+    head.shorthand().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branch_scope_names() {
+        assert_eq!(
+            BranchScope::for_branch("feature/login-form").scope,
+            "branch:feature:login_form"
+        );
+        assert_eq!(BranchScope::for_branch("main").scope, "branch:main");
+        assert_eq!(
+            BranchScope::for_branch("release/v1.2").scope,
+            "branch:release:v1_2"
+        );
+    }
+
+    #[test]
+    fn test_host_branch() {
+        let temp = tempfile::TempDir::new().unwrap();
+        assert_eq!(host_branch(temp.path()), None);
+
+        let repo = git2::Repository::init(temp.path()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let commit = repo
+            .commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+        let commit = repo.find_commit(commit).unwrap();
+        repo.branch("feature/x", &commit, false).unwrap();
+        repo.set_head("refs/heads/feature/x").unwrap();
+        assert_eq!(host_branch(temp.path()).as_deref(), Some("feature/x"));
+
+        repo.set_head_detached(commit.id()).unwrap();
+        assert_eq!(host_branch(temp.path()), None);
+    }
+}
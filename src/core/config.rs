@@ -1,9 +1,21 @@
 //! Configuration types for Jin
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 use crate::core::error::{JinError, Result};
+use crate::core::home::HomeConfig;
+use crate::core::limits::LimitsConfig;
+use crate::git::auth::AuthMethod;
+use crate::git::locks::LocksConfig;
+use crate::git::maintenance::GcConfig;
+use crate::git::retry::RetryConfig;
+use crate::merge::{
+    EolConfig, FinalKeysConfig, MergerConfig, StrictMergeConfig, StyleConfig, UnicodeConfig,
+};
+use crate::notify::reload::ReloadConfig;
+use crate::staging::attributes::{glob_to_regex, normalize};
 
 fn default_version() -> u32 {
     1
@@ -21,6 +33,61 @@ pub struct JinConfig {
 
     /// User information
     pub user: Option<UserConfig>,
+
+    /// Event notifications for external automation
+    pub notify: Option<NotifyConfig>,
+
+    /// Size guardrails for staging, merging and layer growth
+    pub limits: Option<LimitsConfig>,
+
+    /// Private remote syncing the user-local layer between own machines
+    pub local_sync: Option<LocalSyncConfig>,
+
+    /// Advisory path lock settings
+    pub locks: Option<LocksConfig>,
+
+    /// Handling of overrides of final (pinned) keys during merge
+    pub final_keys: Option<FinalKeysConfig>,
+
+    /// Retry and backoff for transient remote failures
+    pub retry: Option<RetryConfig>,
+
+    /// Repack tuning for `jin gc`
+    pub gc: Option<GcConfig>,
+
+    /// Defaults of `jin add`
+    pub add: Option<AddConfig>,
+
+    /// Layout of serialized (applied) structured files
+    pub style: Option<StyleConfig>,
+
+    /// Default line endings of applied files
+    pub eol: Option<EolConfig>,
+
+    /// Unicode normalization of parsed structured files
+    pub unicode: Option<UnicodeConfig>,
+
+    /// External merge drivers by path pattern (`[[mergers]]`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mergers: Vec<MergerConfig>,
+
+    /// Paths where scalar overwrites between layers conflict
+    /// (`[[strict_merge]]`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strict_merge: Vec<StrictMergeConfig>,
+
+    /// Commands run when `jin apply` changes matching files (`[[reload]]`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reload: Vec<ReloadConfig>,
+
+    /// Named Jin repositories (name -> path), selectable per project
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub repos: BTreeMap<String, String>,
+
+    /// Named remotes other than `origin` (`jin link add <name> <url>`);
+    /// `origin` itself is [`JinConfig::remote`]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub remotes: BTreeMap<String, RemoteConfig>,
 }
 
 /// Remote repository configuration
@@ -31,6 +98,42 @@ pub struct RemoteConfig {
     /// Whether to fetch on init
     #[serde(default)]
     pub fetch_on_init: bool,
+    /// Seconds between background freshness checks run by `jin status`
+    /// (unset disables the check)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check_interval: Option<u64>,
+    /// Where credentials come from (`jin link --auth`)
+    #[serde(default, skip_serializing_if = "AuthMethod::is_auto")]
+    pub auth: AuthMethod,
+    /// Name of the environment variable holding an access token (the
+    /// token itself is never stored)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+    /// SSH private key for this remote (`~` allowed), tried before
+    /// ssh-agent and the default keys
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_key: Option<String>,
+    /// File holding an access token (`~` allowed), read when the token
+    /// variable is unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_file: Option<String>,
+    /// Layers `jin push` pushes to this remote when none are named (all
+    /// layers if empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub push_layers: Vec<String>,
+}
+
+/// User-local sync configuration
+///
+/// The user-local layer is never pushed to the team remote. When this is
+/// set, `jin local sync` exchanges it with the user's other machines through
+/// a separate, private remote.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalSyncConfig {
+    /// URL of the private remote
+    pub url: String,
+    /// Name of this machine (its ref is `refs/jin/local/<machine>`)
+    pub machine: String,
 }
 
 /// User configuration
@@ -42,6 +145,48 @@ pub struct UserConfig {
     pub email: Option<String>,
 }
 
+/// Defaults of `jin add` (stored under `[add]` in the global config)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddConfig {
+    /// Create the mode or scope routed to when it doesn't exist, as with
+    /// `--create-missing`
+    #[serde(default)]
+    pub create_missing: bool,
+}
+
+fn default_notify_retries() -> u32 {
+    3
+}
+
+/// Notification configuration
+///
+/// After commits, applies and syncs, Jin can run a program and/or POST a
+/// JSON event payload to a URL so external automation can react.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifyConfig {
+    /// Program to run for each event (payload is written to its stdin)
+    pub exec: Option<String>,
+    /// URL to POST each event payload to
+    pub url: Option<String>,
+    /// Number of retries after a failed delivery
+    #[serde(default = "default_notify_retries")]
+    pub retries: u32,
+    /// Event kinds to deliver (empty means all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<String>,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            exec: None,
+            url: None,
+            retries: default_notify_retries(),
+            events: Vec::new(),
+        }
+    }
+}
+
 impl JinConfig {
     /// Load config from default location (~/.jin/config.toml)
     pub fn load() -> Result<Self> {
@@ -67,6 +212,15 @@ impl JinConfig {
         Ok(())
     }
 
+    /// Settings of the remote named `name` (`origin` is [`JinConfig::remote`])
+    pub fn named_remote(&self, name: &str) -> Option<&RemoteConfig> {
+        if name == crate::git::remote::DEFAULT_REMOTE {
+            self.remote.as_ref()
+        } else {
+            self.remotes.get(name)
+        }
+    }
+
     /// Returns default config path (~/.jin/config.toml or $JIN_DIR/config.toml)
     ///
     /// Respects JIN_DIR environment variable for test isolation.
@@ -85,6 +239,132 @@ impl JinConfig {
     }
 }
 
+/// Expand a leading `~` in a configured path to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    if path == "~" {
+        if let Some(home) = dirs::home_dir() {
+            return home;
+        }
+    } else if let Some(rest) = path.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    PathBuf::from(path)
+}
+
+/// Per-project settings (stored at .jin/config.yaml)
+///
+/// Unlike [`ProjectContext`], which tracks what is *active* and changes
+/// frequently, this holds stable per-project choices such as which named
+/// Jin repository backs the project.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProjectConfig {
+    /// Version of the project config schema
+    #[serde(default = "default_version")]
+    pub version: u32,
+
+    /// Named repository from `repos` in the global config (None = default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+
+    /// Home workspace settings (set by `jin init --home`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home: Option<HomeConfig>,
+
+    /// Apply settings (`jin config set apply.exclude ...`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apply: Option<ApplyConfig>,
+}
+
+impl ProjectConfig {
+    /// Load project config from .jin/config.yaml under the workspace root,
+    /// or defaults if absent
+    pub fn load(root: &Path) -> Result<Self> {
+        Self::load_from(&Self::default_path(root))
+    }
+
+    /// Load project config from a specific path, or defaults if absent
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        serde_yaml::from_str(&content)
+            .map_err(|e| JinError::Config(format!("Failed to parse project config: {}", e)))
+    }
+
+    /// Save project config to .jin/config.yaml under the workspace root
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = Self::default_path(root);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_yaml::to_string(self)
+            .map_err(|e| JinError::Config(format!("Failed to serialize project config: {}", e)))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Returns the project config path (.jin/config.yaml) of a workspace
+    pub fn default_path(root: &Path) -> PathBuf {
+        root.join(".jin").join("config.yaml")
+    }
+}
+
+/// Apply settings of a project (stored under `apply` in `.jin/config.yaml`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApplyConfig {
+    /// Patterns of merged paths never written to this workspace
+    ///
+    /// Patterns follow the `.jinattributes` conventions; a trailing `/`
+    /// matches everything under a directory (`.idea/`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Write `.jin/provenance.json` on every apply
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub provenance: bool,
+    /// Write `.jinmerge` files under `.jin/conflicts` instead of next to
+    /// the conflicted files
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub conflict_dir: bool,
+    /// Use the scope of the host repository's branch (`branch:<name>`)
+    /// instead of the active scope, when it exists
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub branch_scopes: bool,
+}
+
+impl ApplyConfig {
+    /// Replace the exclusion patterns, validating each
+    pub fn set_exclude(&mut self, patterns: &[String]) -> Result<()> {
+        for pattern in patterns {
+            glob_to_regex(&expand_dir_pattern(pattern)).map_err(|message| {
+                JinError::Config(format!("Invalid apply.exclude pattern: {}", message))
+            })?;
+        }
+        self.exclude = patterns.to_vec();
+        Ok(())
+    }
+
+    /// The pattern excluding a path, if any
+    pub fn excluded_by(&self, path: &Path) -> Option<&str> {
+        let path = normalize(path);
+        self.exclude
+            .iter()
+            .find(|p| glob_to_regex(&expand_dir_pattern(p)).is_ok_and(|re| re.is_match(&path)))
+            .map(String::as_str)
+    }
+}
+
+/// `dir/` stands for everything under `dir`
+fn expand_dir_pattern(pattern: &str) -> String {
+    if pattern.len() > 1 && pattern.ends_with('/') {
+        format!("{}**", pattern)
+    } else {
+        pattern.to_string()
+    }
+}
+
 /// Per-project context (stored at .jin/context)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProjectContext {
@@ -107,9 +387,9 @@ pub struct ProjectContext {
 }
 
 impl ProjectContext {
-    /// Load context from .jin/context in current directory
-    pub fn load() -> Result<Self> {
-        let path = Self::default_path();
+    /// Load context from .jin/context under the workspace root
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = Self::default_path(root);
         if path.exists() {
             let content = std::fs::read_to_string(&path)?;
             serde_yaml::from_str(&content)
@@ -119,9 +399,9 @@ impl ProjectContext {
         }
     }
 
-    /// Save context to .jin/context
-    pub fn save(&self) -> Result<()> {
-        let path = Self::default_path();
+    /// Save context to .jin/context under the workspace root
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = Self::default_path(root);
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
@@ -131,14 +411,14 @@ impl ProjectContext {
         Ok(())
     }
 
-    /// Returns default context path (.jin/context)
-    pub fn default_path() -> PathBuf {
-        PathBuf::from(".jin").join("context")
+    /// Returns the context path (.jin/context) of a workspace
+    pub fn default_path(root: &Path) -> PathBuf {
+        root.join(".jin").join("context")
     }
 
-    /// Check if Jin is initialized in current directory
-    pub fn is_initialized() -> bool {
-        Self::default_path()
+    /// Check if Jin is initialized in a workspace
+    pub fn is_initialized(root: &Path) -> bool {
+        Self::default_path(root)
             .parent()
             .map(|p| p.exists())
             .unwrap_or(false)
@@ -173,6 +453,24 @@ mod tests {
         assert_eq!(config.version, 0); // Default is 0, loaded defaults to 1
         assert!(config.remote.is_none());
         assert!(config.user.is_none());
+        assert!(config.notify.is_none());
+    }
+
+    #[test]
+    fn test_notify_config_defaults_retries() {
+        let config: JinConfig = toml::from_str(
+            r#"
+version = 1
+
+[notify]
+url = "https://hooks.example.com/jin"
+"#,
+        )
+        .unwrap();
+        let notify = config.notify.unwrap();
+        assert_eq!(notify.url.as_deref(), Some("https://hooks.example.com/jin"));
+        assert_eq!(notify.retries, 3);
+        assert!(notify.events.is_empty());
     }
 
     #[test]
@@ -182,11 +480,33 @@ mod tests {
             remote: Some(RemoteConfig {
                 url: "git@github.com:org/jin-config".to_string(),
                 fetch_on_init: true,
+                check_interval: None,
+                auth: AuthMethod::Auto,
+                token_env: None,
+                ssh_key: None,
+                token_file: None,
+                push_layers: Vec::new(),
             }),
             user: Some(UserConfig {
                 name: Some("Test User".to_string()),
                 email: Some("test@example.com".to_string()),
             }),
+            notify: None,
+            limits: None,
+            local_sync: None,
+            locks: None,
+            final_keys: None,
+            retry: None,
+            gc: None,
+            add: None,
+            style: None,
+            eol: None,
+            unicode: None,
+            mergers: Vec::new(),
+            strict_merge: Vec::new(),
+            reload: Vec::new(),
+            repos: BTreeMap::new(),
+            remotes: BTreeMap::new(),
         };
 
         let toml_str = toml::to_string_pretty(&config).unwrap();
@@ -195,6 +515,97 @@ mod tests {
         assert!(toml_str.contains("git@github.com:org/jin-config"));
     }
 
+    #[test]
+    fn test_repos_round_trip() {
+        let mut config = JinConfig::default();
+        config
+            .repos
+            .insert("clientA".to_string(), "~/clientA/.jinrepo".to_string());
+
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        assert!(toml_str.contains("[repos]"));
+
+        let parsed: JinConfig = toml::from_str(&toml_str).unwrap();
+        assert_eq!(parsed.repos["clientA"], "~/clientA/.jinrepo");
+    }
+
+    #[test]
+    fn test_named_remotes_round_trip() {
+        let mut config = JinConfig::default();
+        config.remotes.insert(
+            "backup".to_string(),
+            RemoteConfig {
+                url: "/srv/jin-backup".to_string(),
+                fetch_on_init: true,
+                check_interval: None,
+                auth: AuthMethod::Auto,
+                token_env: None,
+                ssh_key: None,
+                token_file: None,
+                push_layers: vec!["global".to_string()],
+            },
+        );
+
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        assert!(toml_str.contains("[remotes.backup]"));
+
+        let parsed: JinConfig = toml::from_str(&toml_str).unwrap();
+        assert!(parsed.named_remote("origin").is_none());
+        assert_eq!(
+            parsed.named_remote("backup").unwrap().push_layers,
+            vec!["global"]
+        );
+    }
+
+    #[test]
+    fn test_mergers_round_trip() {
+        let mut config = JinConfig::default();
+        config.mergers.push(MergerConfig {
+            pattern: "*.xml".to_string(),
+            command: "xmlmerge %O %A %B".to_string(),
+        });
+        config
+            .repos
+            .insert("clientA".to_string(), "~/clientA/.jinrepo".to_string());
+
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        assert!(toml_str.contains("[[mergers]]"));
+
+        let parsed: JinConfig = toml::from_str(&toml_str).unwrap();
+        assert_eq!(parsed.mergers, config.mergers);
+        assert_eq!(parsed.repos.len(), 1);
+    }
+
+    #[test]
+    fn test_expand_home() {
+        assert_eq!(expand_home("/abs/path"), PathBuf::from("/abs/path"));
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(expand_home("~/x/.jinrepo"), home.join("x/.jinrepo"));
+        }
+    }
+
+    #[test]
+    fn test_project_config_missing_is_default() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config = ProjectConfig::load_from(&temp.path().join("config.yaml")).unwrap();
+        assert!(config.repo.is_none());
+    }
+
+    #[test]
+    fn test_apply_exclude_patterns() {
+        let mut apply = ApplyConfig::default();
+        apply
+            .set_exclude(&[".idea/".to_string(), "*.iml".to_string()])
+            .unwrap();
+        assert_eq!(
+            apply.excluded_by(Path::new(".idea/workspace.xml")),
+            Some(".idea/")
+        );
+        assert_eq!(apply.excluded_by(Path::new("./app/app.iml")), Some("*.iml"));
+        assert_eq!(apply.excluded_by(Path::new(".ideas.txt")), None);
+        assert!(apply.set_exclude(&["/".to_string()]).is_err());
+    }
+
     #[test]
     fn test_default_context() {
         let ctx = ProjectContext::default();
@@ -0,0 +1,226 @@
+//! Dangling context entries
+//!
+//! `.jin/context` names the active mode and scope, but nothing stops them
+//! from being deleted afterwards, by `jin mode delete` in another workspace
+//! or on another machine followed by a pull. A context naming a deleted mode
+//! or scope then makes `jin apply` merge fewer layers than expected, with no
+//! hint why.
+//!
+//! [`check`] finds such entries and suggests the closest existing name for
+//! each; commands that merge layers refuse to run with them
+//! ([`ensure_valid`]), and `jin context --heal` unsets them.
+
+use crate::core::{JinError, ProjectContext, Result};
+use crate::git::{JinRepo, RefOps};
+use std::fmt;
+
+/// Which context entry is dangling
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextEntry {
+    /// The active mode
+    Mode,
+    /// The active scope
+    Scope,
+}
+
+impl fmt::Display for ContextEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContextEntry::Mode => write!(f, "mode"),
+            ContextEntry::Scope => write!(f, "scope"),
+        }
+    }
+}
+
+/// An active mode or scope that no longer exists
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dangling {
+    /// Mode or scope
+    pub entry: ContextEntry,
+    /// Name recorded in the context
+    pub name: String,
+    /// Closest existing name, if any is close
+    pub suggestion: Option<String>,
+}
+
+impl Dangling {
+    /// e.g. `Active mode 'claud' no longer exists (did you mean 'claude'?)`
+    pub fn message(&self) -> String {
+        let mut message = format!("Active {} '{}' no longer exists", self.entry, self.name);
+        if let Some(suggestion) = &self.suggestion {
+            message.push_str(&format!(" (did you mean '{}'?)", suggestion));
+        }
+        message
+    }
+
+    /// Command activating the suggestion, if there is one
+    pub fn fix(&self) -> Option<String> {
+        self.suggestion
+            .as_ref()
+            .map(|name| format!("jin {} use {}", self.entry, name))
+    }
+}
+
+/// Whether a mode exists
+pub fn mode_exists(repo: &JinRepo, name: &str) -> bool {
+    repo.ref_exists(&format!("refs/jin/modes/{}/_mode", name))
+}
+
+/// Whether a scope exists, untethered or under any mode
+pub fn scope_exists(repo: &JinRepo, name: &str) -> bool {
+    let ref_safe_name = name.replace(':', "/");
+    repo.ref_exists(&format!("refs/jin/scopes/{}", ref_safe_name))
+        || !repo
+            .list_refs(&format!("refs/jin/modes/*/scopes/{}", ref_safe_name))
+            .unwrap_or_default()
+            .is_empty()
+}
+
+/// Names of all modes
+pub fn mode_names(repo: &JinRepo) -> Vec<String> {
+    repo.list_refs("refs/jin/modes/*/_mode")
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|r| r.strip_prefix("refs/jin/modes/")?.strip_suffix("/_mode"))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Names of all scopes, untethered or under any mode
+pub fn scope_names(repo: &JinRepo) -> Vec<String> {
+    let untethered = repo.list_refs("refs/jin/scopes/*").unwrap_or_default();
+    let bound = repo
+        .list_refs("refs/jin/modes/*/scopes/*")
+        .unwrap_or_default();
+    let mut names: Vec<String> = untethered
+        .iter()
+        .filter_map(|r| r.strip_prefix("refs/jin/scopes/"))
+        .chain(
+            bound
+                .iter()
+                .filter_map(|r| Some(r.split_once("/scopes/")?.1)),
+        )
+        .map(|name| name.replace('/', ":"))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Active mode and scope entries that no longer exist
+pub fn check(context: &ProjectContext, repo: &JinRepo) -> Vec<Dangling> {
+    let mut dangling = Vec::new();
+    if let Some(mode) = context.mode.as_deref().filter(|m| !mode_exists(repo, m)) {
+        dangling.push(Dangling {
+            entry: ContextEntry::Mode,
+            name: mode.to_string(),
+            suggestion: nearest(mode, &mode_names(repo)),
+        });
+    }
+    if let Some(scope) = context.scope.as_deref().filter(|s| !scope_exists(repo, s)) {
+        dangling.push(Dangling {
+            entry: ContextEntry::Scope,
+            name: scope.to_string(),
+            suggestion: nearest(scope, &scope_names(repo)),
+        });
+    }
+    dangling
+}
+
+/// Fail with every dangling entry and how to fix it
+pub fn ensure_valid(context: &ProjectContext, repo: &JinRepo) -> Result<()> {
+    let dangling = check(context, repo);
+    if dangling.is_empty() {
+        return Ok(());
+    }
+    let mut message = String::from("The active context is invalid:");
+    for entry in &dangling {
+        message.push_str(&format!("\n  {}", entry.message()));
+    }
+    message.push_str(
+        "\nIt may have been deleted from another workspace or machine. \
+Run 'jin context --heal' to unset it.",
+    );
+    Err(JinError::Config(message))
+}
+
+/// Unset the dangling entries in `context`
+pub fn heal(context: &mut ProjectContext, dangling: &[Dangling]) {
+    for entry in dangling {
+        match entry.entry {
+            ContextEntry::Mode => context.mode = None,
+            ContextEntry::Scope => context.scope = None,
+        }
+    }
+}
+
+/// Closest of `candidates` to `name`, if within a third of its length
+/// (at least two edits)
+pub fn nearest(name: &str, candidates: &[String]) -> Option<String> {
+    let limit = (name.chars().count() / 3).max(2);
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min()
+        .map(|(_, candidate)| candidate.clone())
+}
+
+/// Levenshtein distance, counting characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_name() {
+        let names = vec![
+            "claude".to_string(),
+            "cursor".to_string(),
+            "language:javascript".to_string(),
+        ];
+        assert_eq!(nearest("claud", &names), Some("claude".to_string()));
+        assert_eq!(
+            nearest("language:javscript", &names),
+            Some("language:javascript".to_string())
+        );
+        assert_eq!(nearest("zed", &names), None);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_heal_unsets_dangling_entries() {
+        let mut context = ProjectContext {
+            mode: Some("claud".to_string()),
+            scope: Some("backend".to_string()),
+            ..Default::default()
+        };
+        let dangling = Dangling {
+            entry: ContextEntry::Mode,
+            name: "claud".to_string(),
+            suggestion: Some("claude".to_string()),
+        };
+        assert_eq!(
+            dangling.message(),
+            "Active mode 'claud' no longer exists (did you mean 'claude'?)"
+        );
+        assert_eq!(dangling.fix().as_deref(), Some("jin mode use claude"));
+
+        heal(&mut context, &[dangling]);
+        assert_eq!(context.mode, None);
+        assert_eq!(context.scope.as_deref(), Some("backend"));
+    }
+}
@@ -35,6 +35,13 @@ WARNING: --force may cause data loss!"
     )]
     BehindRemote { layer: String },
 
+    /// Layer refs moved while a command was running
+    #[error(
+        "Layer refs changed while {operation} was running: {refs}\n\
+Another jin process or Git tool updated the Jin repository. Nothing was written; run the command again."
+    )]
+    RefsChanged { operation: String, refs: String },
+
     /// Detached workspace state - workspace doesn't match any valid layer configuration
     #[error(
         "Workspace is in a detached state.\n\
@@ -89,11 +96,100 @@ Recovery: {recovery_hint}"
     #[error("Jin not initialized in this project")]
     NotInitialized,
 
+    /// Authentication with a remote failed (permanent, never retried)
+    #[error(
+        "Authentication failed: {0}\n\
+Check your SSH keys or credentials. Try: ssh -T git@github.com (for GitHub)"
+    )]
+    Auth(String),
+
+    /// Operation cancelled (Ctrl-C, or a failing sibling remote operation)
+    #[error("Operation cancelled")]
+    Cancelled,
+
     /// General errors
     #[error("{0}")]
     Other(String),
 }
 
+impl JinError {
+    /// Stable code for this kind of error (`JIN-E006`), explained by
+    /// `jin explain <code>`; `None` for general errors
+    pub fn code(&self) -> Option<&'static str> {
+        Some(match self {
+            JinError::Io(_) => "JIN-E001",
+            JinError::Git(_) => "JIN-E002",
+            JinError::Config(_) => "JIN-E003",
+            JinError::Parse { .. } => "JIN-E004",
+            JinError::MergeConflict { .. } => "JIN-E005",
+            JinError::BehindRemote { .. } => "JIN-E006",
+            JinError::RefsChanged { .. } => "JIN-E007",
+            JinError::DetachedWorkspace { .. } => "JIN-E008",
+            JinError::Transaction(_) => "JIN-E009",
+            JinError::InvalidLayer(_) => "JIN-E010",
+            JinError::NoActiveContext { .. } => "JIN-E011",
+            JinError::NotFound(_) => "JIN-E012",
+            JinError::AlreadyExists(_) => "JIN-E013",
+            JinError::GitTracked { .. } => "JIN-E014",
+            JinError::Symlink { .. } => "JIN-E015",
+            JinError::StagingFailed { .. } => "JIN-E016",
+            JinError::NotInitialized => "JIN-E017",
+            JinError::Auth(_) => "JIN-E018",
+            JinError::Cancelled => "JIN-E019",
+            JinError::Other(_) => return None,
+        })
+    }
+
+    /// Whether retrying the operation may succeed
+    ///
+    /// True for network failures, HTTP 5xx responses, dropped SSH
+    /// connections and timeouts. Authentication failures, rejected pushes
+    /// and local errors are permanent.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            JinError::Git(e) => is_transient_git_error(e),
+            JinError::Io(e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::Interrupted
+            ),
+            _ => false,
+        }
+    }
+}
+
+/// Classify a git2 error as transient (see [`JinError::is_transient`])
+fn is_transient_git_error(e: &git2::Error) -> bool {
+    use git2::{ErrorClass, ErrorCode};
+
+    if matches!(
+        e.code(),
+        ErrorCode::Auth | ErrorCode::Certificate | ErrorCode::User | ErrorCode::NotFastForward
+    ) {
+        return false;
+    }
+    let message = e.message().to_ascii_lowercase();
+    if let Some(status) = message
+        .split("status code: ")
+        .nth(1)
+        .and_then(|rest| rest.get(..3))
+        .and_then(|code| code.parse::<u16>().ok())
+    {
+        return status >= 500 || status == 429;
+    }
+    match e.class() {
+        ErrorClass::Net | ErrorClass::Ssh | ErrorClass::Http => true,
+        ErrorClass::Os => {
+            message.contains("timed out")
+                || message.contains("connection reset")
+                || message.contains("broken pipe")
+        }
+        _ => false,
+    }
+}
+
 /// Result type alias using JinError
 pub type Result<T> = std::result::Result<T, JinError>;
 
@@ -107,6 +203,53 @@ mod tests {
         assert_eq!(err.to_string(), "Jin not initialized in this project");
     }
 
+    #[test]
+    fn test_transient_errors() {
+        let git = |code, class, message| JinError::from(git2::Error::new(code, class, message));
+        use git2::{ErrorClass, ErrorCode};
+
+        assert!(git(
+            ErrorCode::GenericError,
+            ErrorClass::Net,
+            "failed to resolve address"
+        )
+        .is_transient());
+        assert!(git(
+            ErrorCode::GenericError,
+            ErrorClass::Http,
+            "unexpected HTTP status code: 503"
+        )
+        .is_transient());
+        assert!(!git(
+            ErrorCode::GenericError,
+            ErrorClass::Http,
+            "unexpected HTTP status code: 404"
+        )
+        .is_transient());
+        assert!(!git(ErrorCode::Auth, ErrorClass::Ssh, "authentication required").is_transient());
+        assert!(!git(
+            ErrorCode::GenericError,
+            ErrorClass::Reference,
+            "cannot push non-fastforwardable reference"
+        )
+        .is_transient());
+        assert!(!JinError::Auth("denied".into()).is_transient());
+        assert!(!JinError::Config("bad".into()).is_transient());
+    }
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(JinError::NotInitialized.code(), Some("JIN-E017"));
+        assert_eq!(
+            JinError::BehindRemote {
+                layer: "global".into()
+            }
+            .code(),
+            Some("JIN-E006")
+        );
+        assert_eq!(JinError::Other("oops".into()).code(), None);
+    }
+
     #[test]
     fn test_config_error() {
         let err = JinError::Config("invalid value".to_string());
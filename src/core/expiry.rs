@@ -0,0 +1,150 @@
+//! Expiry dates for user-local overrides
+//!
+//! Files staged with `jin add --local --expires 7d` record when they expire
+//! in `jin/local-expiry.yaml` inside the Jin repository. Once a path has
+//! expired, merges leave its user-local version out, so `jin apply` falls
+//! back to the lower layers and `jin status` warns about it. The override
+//! itself stays committed; adding it again with a new `--expires` renews
+//! it, and `jin rm --local` removes it for good.
+//!
+//! Like usage tracking, expiry is advisory: an unreadable file means no
+//! override expires.
+
+use crate::core::progress;
+use crate::core::{JinError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Expiry dates of user-local overrides, by path
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalExpiry {
+    /// Path -> moment the override stops being applied
+    #[serde(default)]
+    pub overrides: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl LocalExpiry {
+    /// Path of the expiry file inside a Jin repository
+    pub fn path(repo_path: &Path) -> PathBuf {
+        repo_path.join("jin").join("local-expiry.yaml")
+    }
+
+    /// Load the expiry dates (empty if none were recorded)
+    pub fn load(repo_path: &Path) -> Result<Self> {
+        let path = Self::path(repo_path);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_yaml::from_str(&content).map_err(|e| JinError::Parse {
+            format: "YAML".to_string(),
+            message: format!("{}: {}", path.display(), e),
+        })
+    }
+
+    /// Load the expiry dates, warning and treating them as empty on error
+    pub fn load_or_default(repo_path: &Path) -> Self {
+        Self::load(repo_path).unwrap_or_else(|e| {
+            progress::warn(format_args!("Could not read local override expiry: {}", e));
+            Self::default()
+        })
+    }
+
+    /// Save the expiry dates (atomic write)
+    pub fn save(&self, repo_path: &Path) -> Result<()> {
+        let path = Self::path(repo_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_yaml::to_string(self).map_err(|e| JinError::Parse {
+            format: "YAML".to_string(),
+            message: e.to_string(),
+        })?;
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, content)?;
+        std::fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+
+    /// Set when the override at `path` expires
+    pub fn set(&mut self, path: &Path, at: DateTime<Utc>) {
+        self.overrides.insert(key(path), at);
+    }
+
+    /// Forget the expiry of `path` (it no longer expires)
+    pub fn remove(&mut self, path: &Path) -> bool {
+        self.overrides.remove(&key(path)).is_some()
+    }
+
+    /// When the override at `path` expires, if it does
+    pub fn get(&self, path: &Path) -> Option<DateTime<Utc>> {
+        self.overrides.get(&key(path)).copied()
+    }
+
+    /// Whether the override at `path` has expired at `now`
+    pub fn is_expired(&self, path: &Path, now: DateTime<Utc>) -> bool {
+        self.get(path).is_some_and(|at| at <= now)
+    }
+
+    /// Expired overrides at `now`, oldest first
+    pub fn expired(&self, now: DateTime<Utc>) -> Vec<(PathBuf, DateTime<Utc>)> {
+        let mut expired: Vec<_> = self
+            .overrides
+            .iter()
+            .filter(|(_, at)| **at <= now)
+            .map(|(path, at)| (PathBuf::from(path), *at))
+            .collect();
+        expired.sort_by_key(|(_, at)| *at);
+        expired
+    }
+}
+
+/// Key of a path in the expiry file (forward slashes on every platform)
+fn key(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_expired_overrides() {
+        let now = Utc::now();
+        let mut expiry = LocalExpiry::default();
+        expiry.set(Path::new(".env.local"), now - Duration::days(1));
+        expiry.set(Path::new("debug.json"), now + Duration::days(6));
+        expiry.set(Path::new("old.toml"), now - Duration::days(30));
+
+        assert!(expiry.is_expired(Path::new(".env.local"), now));
+        assert!(!expiry.is_expired(Path::new("debug.json"), now));
+        assert!(!expiry.is_expired(Path::new("untracked"), now));
+
+        let paths: Vec<PathBuf> = expiry.expired(now).into_iter().map(|(p, _)| p).collect();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("old.toml"), PathBuf::from(".env.local")]
+        );
+
+        assert!(expiry.remove(Path::new("old.toml")));
+        assert!(!expiry.remove(Path::new("old.toml")));
+    }
+
+    #[test]
+    fn test_load_save_round_trip() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(
+            LocalExpiry::load(temp.path()).unwrap(),
+            LocalExpiry::default()
+        );
+
+        let mut expiry = LocalExpiry::default();
+        expiry.set(Path::new("config/debug.json"), Utc::now());
+        expiry.save(temp.path()).unwrap();
+        assert_eq!(LocalExpiry::load(temp.path()).unwrap(), expiry);
+    }
+}
@@ -0,0 +1,332 @@
+//! Filesystem name checks for applied paths
+//!
+//! Layers are Git trees, so they can hold paths a workspace filesystem
+//! can't: on a case-insensitive filesystem (the macOS and Windows default)
+//! `Config.json` from one layer and `config.json` from another are the same
+//! file and the second write silently replaces the first, and Windows
+//! refuses names like `aux.json` or `notes?.md`. `jin apply` checks the
+//! merged paths against the rules of the workspace filesystem and refuses
+//! to write anything when some would clash; `jin status` reports the same
+//! problems.
+//!
+//! Case sensitivity is detected from the workspace directory itself, so a
+//! case-sensitive APFS volume or a case-insensitive mount on Linux is
+//! handled correctly. macOS also treats the composed and decomposed Unicode
+//! forms of a name (`café` typed on Linux and on a Mac) as one file.
+//!
+//! [`unstorable`] covers the other direction: names a workspace can hold
+//! but a layer can't store faithfully, which `jin add` refuses.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use unicode_normalization::UnicodeNormalization;
+
+/// Device names Windows reserves in every directory, with any extension
+const WINDOWS_RESERVED: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Characters Windows does not allow in file names
+const WINDOWS_INVALID_CHARS: &[char] = &['<', '>', ':', '"', '|', '?', '*', '\\'];
+
+/// Naming rules of a workspace filesystem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NameRules {
+    /// Names differing only in case are the same file
+    pub case_insensitive: bool,
+    /// Windows naming restrictions apply
+    pub windows: bool,
+    /// Names differing only in Unicode normalization are the same file
+    pub unicode_insensitive: bool,
+}
+
+/// A path (or set of paths) the workspace filesystem can't hold as-is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameProblem {
+    /// Paths that differ only in case, and would overwrite each other
+    CaseCollision(Vec<PathBuf>),
+    /// Paths that differ only in Unicode normalization (composed `é` versus
+    /// `e` and a combining accent), and would overwrite each other
+    UnicodeCollision(Vec<PathBuf>),
+    /// A path with a name the filesystem rejects
+    Invalid {
+        /// Offending path
+        path: PathBuf,
+        /// Why it is rejected
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for NameProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CaseCollision(paths) => {
+                let names: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+                write!(f, "{} differ only in case", names.join(", "))
+            }
+            Self::UnicodeCollision(paths) => {
+                let names: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+                write!(
+                    f,
+                    "{} differ only in Unicode normalization",
+                    names.join(", ")
+                )
+            }
+            Self::Invalid { path, reason } => write!(f, "{}: {}", path.display(), reason),
+        }
+    }
+}
+
+impl NameRules {
+    /// Rules of the filesystem holding `dir`
+    pub fn detect(dir: &Path) -> Self {
+        Self {
+            case_insensitive: is_case_insensitive(dir)
+                .unwrap_or(cfg!(any(target_os = "macos", windows))),
+            windows: cfg!(windows),
+            unicode_insensitive: cfg!(target_os = "macos"),
+        }
+    }
+
+    /// Problems with a set of paths, collisions first, each sorted by path
+    pub fn check<'a>(&self, paths: impl IntoIterator<Item = &'a Path>) -> Vec<NameProblem> {
+        let mut by_folded: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+        let mut invalid = Vec::new();
+        for path in paths {
+            if self.windows {
+                if let Some(reason) = windows_name_problem(path) {
+                    invalid.push(NameProblem::Invalid {
+                        path: path.to_path_buf(),
+                        reason,
+                    });
+                }
+            }
+            let key = path.to_string_lossy().replace('\\', "/");
+            let key = if self.unicode_insensitive {
+                key.nfc().collect()
+            } else {
+                key
+            };
+            let key = if self.case_insensitive {
+                key.to_lowercase()
+            } else {
+                key
+            };
+            let group = by_folded.entry(key).or_default();
+            if !group.iter().any(|p| p == path) {
+                group.push(path.to_path_buf());
+            }
+        }
+
+        let mut problems: Vec<NameProblem> = by_folded
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .map(|mut group| {
+                group.sort();
+                let composed: Vec<String> = group
+                    .iter()
+                    .map(|p| p.to_string_lossy().nfc().collect())
+                    .collect();
+                if composed.iter().all(|name| *name == composed[0]) {
+                    NameProblem::UnicodeCollision(group)
+                } else {
+                    NameProblem::CaseCollision(group)
+                }
+            })
+            .collect();
+        invalid.sort_by_key(|problem| problem.to_string());
+        problems.extend(invalid);
+        problems
+    }
+}
+
+/// Why a path can't be stored in a layer as it is, if it can't
+///
+/// Layers record paths as UTF-8 text: in Git trees, the staging index and
+/// `.jinmap`, and one per line in `.gitignore`. A name that isn't valid
+/// UTF-8 or contains a control character (such as a newline) would be
+/// stored altered, or break those files.
+pub fn unstorable(path: &Path) -> Option<String> {
+    let Some(name) = path.to_str() else {
+        return Some("the name is not valid UTF-8".to_string());
+    };
+    name.chars()
+        .find(|c| c.is_control())
+        .map(|c| format!("the name contains the control character {:?}", c))
+}
+
+/// Why Windows would reject a path, if it would
+fn windows_name_problem(path: &Path) -> Option<String> {
+    for component in path.iter() {
+        let name = component.to_string_lossy();
+        if let Some(c) = name
+            .chars()
+            .find(|c| WINDOWS_INVALID_CHARS.contains(c) || c.is_control())
+        {
+            return Some(format!("'{}' contains the invalid character {:?}", name, c));
+        }
+        if name.ends_with('.') || name.ends_with(' ') {
+            return Some(format!("'{}' ends with a dot or space", name));
+        }
+        let stem = name.split('.').next().unwrap_or_default().trim_end();
+        if WINDOWS_RESERVED
+            .iter()
+            .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+        {
+            return Some(format!("'{}' is a reserved device name", name));
+        }
+    }
+    None
+}
+
+/// Whether the filesystem holding `dir` ignores case, found by looking up
+/// `dir` (or its nearest ancestor with letters in its name) with the case
+/// of its name swapped
+///
+/// Nothing is written. `None` when no ancestor has a name with letters.
+fn is_case_insensitive(dir: &Path) -> Option<bool> {
+    let dir = dir.canonicalize().ok()?;
+    let mut current = dir.as_path();
+    loop {
+        let name = current.file_name()?.to_string_lossy();
+        let swapped: String = name
+            .chars()
+            .map(|c| {
+                if c.is_lowercase() {
+                    c.to_uppercase().next().unwrap_or(c)
+                } else {
+                    c.to_lowercase().next().unwrap_or(c)
+                }
+            })
+            .collect();
+        if swapped != name {
+            let twin = current.with_file_name(&swapped);
+            return Some(same_file(current, &twin));
+        }
+        current = current.parent()?;
+    }
+}
+
+#[cfg(unix)]
+fn same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (std::fs::metadata(a), std::fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_file(_a: &Path, b: &Path) -> bool {
+    b.exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INSENSITIVE: NameRules = NameRules {
+        case_insensitive: true,
+        windows: false,
+        unicode_insensitive: false,
+    };
+
+    #[test]
+    fn test_case_collisions() {
+        let paths = [
+            Path::new("Config.json"),
+            Path::new("config.json"),
+            Path::new("docs/README.md"),
+            Path::new("Docs/readme.md"),
+            Path::new("other.json"),
+        ];
+        assert_eq!(
+            INSENSITIVE.check(paths),
+            vec![
+                NameProblem::CaseCollision(vec![
+                    PathBuf::from("Config.json"),
+                    PathBuf::from("config.json")
+                ]),
+                NameProblem::CaseCollision(vec![
+                    PathBuf::from("Docs/readme.md"),
+                    PathBuf::from("docs/README.md")
+                ]),
+            ]
+        );
+
+        let sensitive = NameRules {
+            case_insensitive: false,
+            windows: false,
+            unicode_insensitive: false,
+        };
+        assert!(sensitive.check(paths).is_empty());
+    }
+
+    #[test]
+    fn test_unicode_collisions() {
+        let composed = "caf\u{e9}/r\u{e9}sum\u{e9}.md";
+        let decomposed = "cafe\u{301}/re\u{301}sume\u{301}.md";
+        let paths = [Path::new(composed), Path::new(decomposed)];
+        let mac = NameRules {
+            case_insensitive: true,
+            windows: false,
+            unicode_insensitive: true,
+        };
+        let problems = mac.check(paths);
+        assert!(matches!(
+            problems.as_slice(),
+            [NameProblem::UnicodeCollision(group)] if group.len() == 2
+        ));
+        assert!(problems[0]
+            .to_string()
+            .ends_with("differ only in Unicode normalization"));
+        assert!(INSENSITIVE.check(paths).is_empty());
+    }
+
+    #[test]
+    fn test_unstorable_names() {
+        assert_eq!(unstorable(Path::new("my notes/日本語 (1).md")), None);
+        assert_eq!(unstorable(Path::new("quote\"d'.json")), None);
+        assert!(unstorable(Path::new("line\nbreak.txt"))
+            .unwrap()
+            .contains("control character"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            let latin1 = std::ffi::OsStr::from_bytes(b"caf\xe9.txt");
+            assert!(unstorable(Path::new(latin1)).unwrap().contains("UTF-8"));
+        }
+    }
+
+    #[test]
+    fn test_windows_names() {
+        let windows = NameRules {
+            case_insensitive: true,
+            windows: true,
+            unicode_insensitive: false,
+        };
+        let problems = windows.check([
+            Path::new("aux.json"),
+            Path::new("notes?.md"),
+            Path::new("dir./file"),
+            Path::new("com10.txt"),
+            Path::new("console.json"),
+        ]);
+        let paths: Vec<String> = problems.iter().map(|p| p.to_string()).collect();
+        assert_eq!(problems.len(), 3, "{:?}", paths);
+        assert!(paths[0].starts_with("aux.json: 'aux.json' is a reserved device name"));
+        assert!(paths[1].contains("ends with a dot or space"));
+        assert!(paths[2].contains("invalid character '?'"));
+    }
+
+    #[test]
+    fn test_detects_case_sensitivity_without_writing() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path().join("Workspace");
+        std::fs::create_dir(&dir).unwrap();
+        let detected = is_case_insensitive(&dir).unwrap();
+        assert_eq!(detected, temp.path().join("WORKSPACE").exists());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,171 @@
+//! Home-directory workspace ("home mode")
+//!
+//! `jin init --home` turns the home directory into a user-level workspace so
+//! Jin can manage dotfiles that aren't tied to any project. Other commands
+//! reach it from anywhere with `jin -C ~ <command>`.
+//!
+//! Because the home directory holds credentials and other sensitive files,
+//! a home workspace only manages paths on an allowlist (stored in
+//! `~/.jin/config.yaml` and extended with `jin init --home --allow`). A few
+//! paths, such as SSH private keys, are refused even if allowlisted. The
+//! home directory isn't a Git repository, so no `.gitignore` is maintained.
+
+use crate::core::{JinError, ProjectConfig, Result};
+use crate::staging::attributes::{glob_to_regex, normalize};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Patterns a new home workspace may manage
+pub const DEFAULT_ALLOW: &[&str] = &[
+    ".config/**",
+    ".local/bin/**",
+    ".bashrc",
+    ".bash_profile",
+    ".profile",
+    ".zshrc",
+    ".zprofile",
+    ".zshenv",
+    ".gitconfig",
+    ".vimrc",
+    ".tmux.conf",
+    ".inputrc",
+    ".editorconfig",
+    ".ssh/config",
+];
+
+/// Patterns never managed, whatever the allowlist says
+pub const ALWAYS_DENY: &[&str] = &[
+    ".jin/**",
+    ".gnupg/**",
+    ".ssh/id_*",
+    ".ssh/authorized_keys",
+    ".aws/credentials",
+    ".netrc",
+];
+
+fn default_allow() -> Vec<String> {
+    DEFAULT_ALLOW.iter().map(|p| p.to_string()).collect()
+}
+
+/// Home workspace settings (stored under `home` in `.jin/config.yaml`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HomeConfig {
+    /// Path patterns (relative to the home directory) Jin may manage
+    #[serde(default = "default_allow")]
+    pub allow: Vec<String>,
+}
+
+impl Default for HomeConfig {
+    fn default() -> Self {
+        Self {
+            allow: default_allow(),
+        }
+    }
+}
+
+impl HomeConfig {
+    /// Home settings of the workspace at `root`
+    ///
+    /// `None` unless the workspace was created with `jin init --home`.
+    pub fn current(root: &Path) -> Option<Self> {
+        ProjectConfig::load(root).ok().and_then(|c| c.home)
+    }
+
+    /// Add allowlist patterns, validating each (duplicates are skipped)
+    pub fn allow_patterns(&mut self, patterns: &[String]) -> Result<()> {
+        for pattern in patterns {
+            glob_to_regex(pattern).map_err(|message| {
+                JinError::Config(format!("Invalid allow pattern: {}", message))
+            })?;
+            if !self.allow.contains(pattern) {
+                self.allow.push(pattern.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that a workspace path may be managed
+    pub fn check(&self, path: &Path) -> Result<()> {
+        let relative = relative_to_home(path).ok_or_else(|| JinError::StagingFailed {
+            path: path.display().to_string(),
+            reason: "outside the home directory".to_string(),
+        })?;
+
+        if matches_any(ALWAYS_DENY.iter().copied(), &relative) {
+            return Err(JinError::StagingFailed {
+                path: path.display().to_string(),
+                reason: "sensitive file, never managed in a home workspace".to_string(),
+            });
+        }
+        if !matches_any(self.allow.iter().map(String::as_str), &relative) {
+            return Err(JinError::StagingFailed {
+                path: path.display().to_string(),
+                reason:
+                    "not on the home allowlist (add it with 'jin init --home --allow <pattern>')"
+                        .to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether a workspace path may be managed
+    pub fn allows(&self, path: &Path) -> bool {
+        self.check(path).is_ok()
+    }
+}
+
+/// Path relative to the home directory (relative paths are taken as-is,
+/// since a home workspace runs from the home directory)
+fn relative_to_home(path: &Path) -> Option<PathBuf> {
+    if path.is_relative() {
+        return Some(path.to_path_buf());
+    }
+    let home = dirs::home_dir()?;
+    path.strip_prefix(&home).ok().map(Path::to_path_buf)
+}
+
+fn matches_any<'a>(patterns: impl IntoIterator<Item = &'a str>, path: &Path) -> bool {
+    let path = normalize(path);
+    patterns
+        .into_iter()
+        .filter_map(|p| glob_to_regex(p).ok())
+        .any(|re| re.is_match(&path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_allowlist() {
+        let home = HomeConfig::default();
+        assert!(home.allows(Path::new(".bashrc")));
+        assert!(home.allows(Path::new("./.config/nvim/init.lua")));
+        assert!(home.allows(Path::new(".ssh/config")));
+        assert!(!home.allows(Path::new("Documents/notes.txt")));
+        assert!(!home.allows(Path::new(".ssh/known_hosts")));
+    }
+
+    #[test]
+    fn test_deny_overrides_allowlist() {
+        let mut home = HomeConfig::default();
+        home.allow_patterns(&["**".to_string()]).unwrap();
+        assert!(home.allows(Path::new("Documents/notes.txt")));
+        assert!(!home.allows(Path::new(".ssh/id_ed25519")));
+        assert!(!home.allows(Path::new(".gnupg/private-keys-v1.d/key")));
+        assert!(!home.allows(Path::new(".jin/context")));
+
+        let err = home.check(Path::new(".ssh/id_rsa")).unwrap_err();
+        assert!(err.to_string().contains("never managed"));
+    }
+
+    #[test]
+    fn test_allow_patterns_skips_duplicates() {
+        let mut home = HomeConfig::default();
+        let before = home.allow.len();
+        home.allow_patterns(&[".bashrc".to_string(), ".hgrc".to_string()])
+            .unwrap();
+        assert_eq!(home.allow.len(), before + 1);
+        assert!(home.allows(Path::new(".hgrc")));
+    }
+}
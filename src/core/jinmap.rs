@@ -14,7 +14,7 @@ use crate::git::{JinRepo, ObjectOps, TreeOps};
 use git2::Oid;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// JinMap: Layer-to-file mapping metadata
 ///
@@ -94,8 +94,8 @@ impl JinMap {
     /// Load the JinMap from disk
     ///
     /// Returns a default JinMap if the file doesn't exist (first-run pattern).
-    pub fn load() -> Result<Self> {
-        let path = Self::default_path();
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = Self::default_path(root);
         if path.exists() {
             let content = std::fs::read_to_string(&path)?;
             serde_yaml::from_str(&content).map_err(|e| JinError::Parse {
@@ -110,8 +110,8 @@ impl JinMap {
     /// Save the JinMap to disk
     ///
     /// Uses atomic write pattern: write to temp file, then rename.
-    pub fn save(&self) -> Result<()> {
-        let path = Self::default_path();
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = Self::default_path(root);
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
@@ -133,13 +133,13 @@ impl JinMap {
         Ok(())
     }
 
-    /// Get the default path for the JinMap file
-    pub fn default_path() -> PathBuf {
+    /// Get the path of the JinMap file of a workspace
+    pub fn default_path(root: &Path) -> PathBuf {
         // Check JIN_DIR environment variable first for test isolation
         if let Ok(jin_dir) = std::env::var("JIN_DIR") {
             return PathBuf::from(jin_dir).join(".jinmap");
         }
-        PathBuf::from(".jin").join(".jinmap")
+        root.join(".jin").join(".jinmap")
     }
 
     /// Update JinMap with layer mappings from recent commits
@@ -277,6 +277,32 @@ mod tests {
         assert!(files.contains(&".claude/config.json".to_string()));
     }
 
+    #[test]
+    fn test_jinmap_round_trips_unusual_names() {
+        let names: Vec<String> = [
+            "my notes.md",
+            "#todo.md",
+            "- dash.json",
+            "key: value.yaml",
+            "'single' \"double\".txt",
+            "yes",
+            "日本語/設定.json",
+            "trailing ",
+        ]
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+        let mut jinmap = JinMap::default();
+        jinmap.add_layer_mapping("refs/jin/layers/global", names.clone());
+
+        let yaml = serde_yaml::to_string(&jinmap).unwrap();
+        let parsed: JinMap = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(
+            parsed.get_layer_files("refs/jin/layers/global"),
+            Some(names.as_slice())
+        );
+    }
+
     #[test]
     fn test_jinmap_yaml_format() {
         let mut jinmap = JinMap::default();
@@ -332,7 +358,7 @@ mod tests {
         std::env::set_current_dir(temp.path()).unwrap();
 
         // Don't create a file, load should return default
-        let jinmap = JinMap::load().unwrap();
+        let jinmap = JinMap::load(temp.path()).unwrap();
         assert_eq!(jinmap.version, 1);
         assert!(jinmap.mappings.is_empty());
     }
@@ -133,6 +133,13 @@ impl Layer {
         }
     }
 
+    /// Returns the layer with the given precedence level (1-9)
+    pub fn from_precedence(precedence: u8) -> Option<Layer> {
+        Self::all_in_precedence_order()
+            .into_iter()
+            .find(|l| l.precedence() == precedence)
+    }
+
     /// Returns all layers in precedence order (lowest to highest)
     pub fn all_in_precedence_order() -> Vec<Layer> {
         vec![
@@ -206,16 +213,20 @@ impl Layer {
         // CRITICAL: More specific patterns must come before less specific ones
         // CRITICAL: "_" in patterns is a literal string (the suffix), not a wildcard
         match parts {
-            // Most specific: 6 segments for ModeScopeProject
-            ["mode", _, "scope", _, "project", _] => Some(Layer::ModeScopeProject),
-            // 5 segments for ModeScope with /_ suffix
-            ["mode", _, "scope", _, "_"] => Some(Layer::ModeScope),
+            // Scopes like `lang:rust` are stored as `lang/rust`, so the scope
+            // name may span several segments
+            // Most specific: ModeScopeProject
+            ["mode", _, "scope", scope @ .., "project", _] if !scope.is_empty() => {
+                Some(Layer::ModeScopeProject)
+            }
+            // ModeScope with /_ suffix
+            ["mode", _, "scope", scope @ .., "_"] if !scope.is_empty() => Some(Layer::ModeScope),
             // 4 segments for ModeProject
             ["mode", _, "project", _] => Some(Layer::ModeProject),
             // 3 segments for ModeBase with /_ suffix
             ["mode", _, "_"] => Some(Layer::ModeBase),
             // 2 segments with wildcard
-            ["scope", _] => Some(Layer::ScopeBase),
+            ["scope", _, ..] => Some(Layer::ScopeBase),
             ["project", _] => Some(Layer::ProjectBase),
             // Single segments
             ["global"] => Some(Layer::GlobalBase),
@@ -244,6 +255,24 @@ impl std::fmt::Display for Layer {
     }
 }
 
+impl std::str::FromStr for Layer {
+    type Err = crate::core::JinError;
+
+    /// Parse a layer from its display name (e.g., `mode-scope`)
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::all_in_precedence_order()
+            .into_iter()
+            .find(|layer| layer.to_string() == name)
+            .ok_or_else(|| {
+                crate::core::JinError::Other(format!(
+                    "Unknown layer: {}. Valid layers: global-base, mode-base, mode-scope, \
+                     mode-scope-project, mode-project, scope-base, project-base, user-local, workspace-active",
+                    name
+                ))
+            })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -274,6 +303,15 @@ mod tests {
         assert_eq!(Layer::WorkspaceActive.precedence(), 9);
     }
 
+    #[test]
+    fn test_from_precedence() {
+        for layer in Layer::all_in_precedence_order() {
+            assert_eq!(Layer::from_precedence(layer.precedence()), Some(layer));
+        }
+        assert_eq!(Layer::from_precedence(0), None);
+        assert_eq!(Layer::from_precedence(10), None);
+    }
+
     #[test]
     fn test_ref_paths() {
         assert_eq!(
@@ -555,6 +593,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_layer_from_ref_path_multi_segment_scope() {
+        // As written by `ref_path` for scope `lang:rust`
+        for (layer, mode) in [
+            (Layer::ScopeBase, None),
+            (Layer::ModeScope, Some("dev")),
+            (Layer::ModeScopeProject, Some("dev")),
+        ] {
+            let ref_path = layer.ref_path(mode, Some("lang:rust"), Some("ui"));
+            assert_eq!(Layer::parse_layer_from_ref_path(&ref_path), Some(layer));
+        }
+    }
+
     #[test]
     fn test_parse_layer_from_ref_path_pattern_order() {
         // Verify that more specific patterns match before less specific ones
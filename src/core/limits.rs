@@ -0,0 +1,208 @@
+//! Size guardrails
+//!
+//! Very large files make Jin slow in surprising places: staging hashes them,
+//! every apply re-reads them, and structured formats are parsed in full
+//! before merging. These limits keep that in check:
+//! - files above `max-file-size` are refused by `jin add`
+//! - structured files above `max-parse-size` are merged as opaque text
+//! - `jin commit` warns when a layer grows beyond `layer-max-files` files or
+//!   `layer-max-size` bytes, when one commit adds more than
+//!   `commit-max-growth` bytes to a layer, and when all layers of a mode or
+//!   scope together exceed `mode-max-size` or `scope-max-size` (see
+//!   [`crate::core::storage`])
+//!
+//! Every limit has a default and can be changed (or disabled with `0`)
+//! through the `[limits]` table in the global config.
+
+use crate::core::error::{JinError, Result};
+use crate::core::JinConfig;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+const KB: u64 = 1024;
+const MB: u64 = 1024 * KB;
+const GB: u64 = 1024 * MB;
+
+/// Default largest file `jin add` will stage
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 10 * MB;
+/// Default largest structured file parsed for deep merge
+pub const DEFAULT_MAX_PARSE_SIZE: u64 = 2 * MB;
+/// Default number of files in a layer before `jin commit` warns
+pub const DEFAULT_LAYER_MAX_FILES: u64 = 1000;
+/// Default total layer size before `jin commit` warns
+pub const DEFAULT_LAYER_MAX_SIZE: u64 = 50 * MB;
+/// Default bytes one commit may add to a layer before `jin commit` warns
+pub const DEFAULT_COMMIT_MAX_GROWTH: u64 = 5 * MB;
+/// Default total size of a mode's layers before `jin commit` warns
+pub const DEFAULT_MODE_MAX_SIZE: u64 = 100 * MB;
+/// Default total size of a scope's layers before `jin commit` warns
+pub const DEFAULT_SCOPE_MAX_SIZE: u64 = 100 * MB;
+
+/// Size and count limits (stored under `[limits]` in the global config)
+///
+/// Unset fields use the defaults above; `0` disables a limit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitsConfig {
+    /// Largest file, in bytes, that may be staged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_size: Option<u64>,
+    /// Largest structured file, in bytes, parsed for deep merge
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_parse_size: Option<u64>,
+    /// Number of files in a layer before warning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer_max_files: Option<u64>,
+    /// Total size of a layer, in bytes, before warning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer_max_size: Option<u64>,
+    /// Bytes one commit may add to a layer before warning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_max_growth: Option<u64>,
+    /// Total size of a mode's layers, in bytes, before warning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode_max_size: Option<u64>,
+    /// Total size of a scope's layers, in bytes, before warning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope_max_size: Option<u64>,
+}
+
+impl LimitsConfig {
+    /// Load limits from the global config (defaults if unreadable)
+    pub fn load() -> Self {
+        JinConfig::load()
+            .ok()
+            .and_then(|c| c.limits)
+            .unwrap_or_default()
+    }
+
+    /// Limits loaded once per process, for hot paths like the merge engine
+    pub fn current() -> &'static Self {
+        static LIMITS: OnceLock<LimitsConfig> = OnceLock::new();
+        LIMITS.get_or_init(Self::load)
+    }
+
+    /// Effective staging limit (`None` when disabled)
+    pub fn max_file_size(&self) -> Option<u64> {
+        effective(self.max_file_size, DEFAULT_MAX_FILE_SIZE)
+    }
+
+    /// Effective parse limit (`None` when disabled)
+    pub fn max_parse_size(&self) -> Option<u64> {
+        effective(self.max_parse_size, DEFAULT_MAX_PARSE_SIZE)
+    }
+
+    /// Effective layer file count limit (`None` when disabled)
+    pub fn layer_max_files(&self) -> Option<u64> {
+        effective(self.layer_max_files, DEFAULT_LAYER_MAX_FILES)
+    }
+
+    /// Effective layer size limit (`None` when disabled)
+    pub fn layer_max_size(&self) -> Option<u64> {
+        effective(self.layer_max_size, DEFAULT_LAYER_MAX_SIZE)
+    }
+
+    /// Effective per-commit growth limit (`None` when disabled)
+    pub fn commit_max_growth(&self) -> Option<u64> {
+        effective(self.commit_max_growth, DEFAULT_COMMIT_MAX_GROWTH)
+    }
+
+    /// Effective mode quota (`None` when disabled)
+    pub fn mode_max_size(&self) -> Option<u64> {
+        effective(self.mode_max_size, DEFAULT_MODE_MAX_SIZE)
+    }
+
+    /// Effective scope quota (`None` when disabled)
+    pub fn scope_max_size(&self) -> Option<u64> {
+        effective(self.scope_max_size, DEFAULT_SCOPE_MAX_SIZE)
+    }
+}
+
+fn effective(configured: Option<u64>, default: u64) -> Option<u64> {
+    match configured.unwrap_or(default) {
+        0 => None,
+        limit => Some(limit),
+    }
+}
+
+/// Parse a human-readable size such as `512KB`, `10MB`, `1GB` or `2048`
+///
+/// Units are binary (1KB = 1024 bytes) and case-insensitive; a trailing
+/// `B` or `iB` is optional.
+pub fn parse_size(value: &str) -> Result<u64> {
+    let trimmed = value.trim();
+    let upper = trimmed.to_uppercase();
+    let digits_end = upper
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(upper.len());
+    let (number, unit) = upper.split_at(digits_end);
+
+    let multiplier = match unit.trim() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => KB,
+        "M" | "MB" | "MIB" => MB,
+        "G" | "GB" | "GIB" => GB,
+        _ => 0,
+    };
+
+    match number.parse::<u64>() {
+        Ok(n) if multiplier > 0 => n
+            .checked_mul(multiplier)
+            .ok_or_else(|| JinError::Config(format!("Size too large: {}", trimmed))),
+        _ => Err(JinError::Config(format!(
+            "Invalid size: {}. Use bytes or a unit such as 512KB, 10MB, 1GB",
+            trimmed
+        ))),
+    }
+}
+
+/// Format a byte count for display (e.g., `1.5 MB`)
+pub fn format_size(bytes: u64) -> String {
+    if bytes >= GB {
+        format!("{:.1} GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.1} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("2048").unwrap(), 2048);
+        assert_eq!(parse_size("512KB").unwrap(), 512 * KB);
+        assert_eq!(parse_size("10mb").unwrap(), 10 * MB);
+        assert_eq!(parse_size("1 GiB").unwrap(), GB);
+        assert_eq!(parse_size("0").unwrap(), 0);
+        assert!(parse_size("ten").is_err());
+        assert!(parse_size("10XB").is_err());
+        assert!(parse_size("").is_err());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(10 * MB), "10.0 MB");
+    }
+
+    #[test]
+    fn test_effective_limits() {
+        let defaults = LimitsConfig::default();
+        assert_eq!(defaults.max_file_size(), Some(DEFAULT_MAX_FILE_SIZE));
+        assert_eq!(defaults.layer_max_files(), Some(DEFAULT_LAYER_MAX_FILES));
+
+        let custom = LimitsConfig {
+            max_file_size: Some(0),
+            max_parse_size: Some(KB),
+            ..Default::default()
+        };
+        assert_eq!(custom.max_file_size(), None);
+        assert_eq!(custom.max_parse_size(), Some(KB));
+    }
+}
@@ -0,0 +1,161 @@
+//! Stable machine identity
+//!
+//! Someone working from several machines shares one Jin identity (the
+//! configured user), so the audit log alone can't tell which laptop made a
+//! change. Each Jin directory gets a random ID the first time one is needed,
+//! stored in `~/.jin/machine-id` (or `$JIN_DIR/machine-id`). It is recorded
+//! in audit entries and in the user-local commits `jin local sync` exchanges,
+//! and shown by `jin doctor`.
+//!
+//! The ID carries nothing about the machine itself. `jin doctor
+//! --regenerate-machine-id` replaces it, for example after cloning a home
+//! directory onto a new machine; entries recorded earlier keep the old one.
+
+use crate::core::{JinConfig, JinError, Result};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File holding the ID, in the Jin directory
+pub const MACHINE_ID_FILE: &str = "machine-id";
+
+/// Trailer naming the machine in user-local commit messages
+pub const MACHINE_ID_TRAILER: &str = "Jin-Machine-Id";
+
+/// Hex digits shown in short form
+const SHORT_LEN: usize = 8;
+
+/// Path of the ID file
+pub fn path() -> Result<PathBuf> {
+    let config = JinConfig::default_path()?;
+    let dir = config
+        .parent()
+        .ok_or_else(|| JinError::Config("Cannot determine the Jin directory".into()))?;
+    Ok(dir.join(MACHINE_ID_FILE))
+}
+
+/// The recorded ID, if one was generated
+pub fn load() -> Result<Option<String>> {
+    match std::fs::read_to_string(path()?) {
+        Ok(content) => {
+            let id = content.trim();
+            if is_valid(id) {
+                Ok(Some(id.to_string()))
+            } else {
+                Err(JinError::Parse {
+                    format: MACHINE_ID_FILE.to_string(),
+                    message: format!(
+                        "'{}' is not a machine ID. Run 'jin doctor --regenerate-machine-id'.",
+                        id
+                    ),
+                })
+            }
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The recorded ID, generating one on first use
+pub fn load_or_create() -> Result<String> {
+    match load()? {
+        Some(id) => Ok(id),
+        None => regenerate(),
+    }
+}
+
+/// Replace the ID with a new one
+pub fn regenerate() -> Result<String> {
+    let path = path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let id = generate();
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, format!("{}\n", id))?;
+    std::fs::rename(&temp_path, &path)?;
+    Ok(id)
+}
+
+/// This machine's ID for recording alongside a change, or `None` when it
+/// can't be read or created (recording never fails because of it)
+pub fn current() -> Option<String> {
+    load_or_create().ok()
+}
+
+/// First digits of an ID, enough to tell machines apart in output
+pub fn short(id: &str) -> &str {
+    &id[..SHORT_LEN.min(id.len())]
+}
+
+/// The ID recorded in a commit message trailer, if any
+pub fn from_message(message: &str) -> Option<&str> {
+    message.lines().rev().find_map(|line| {
+        line.strip_prefix(MACHINE_ID_TRAILER)?
+            .strip_prefix(':')
+            .map(str::trim)
+    })
+}
+
+/// Append this machine's trailer to a commit message
+pub fn with_trailer(message: &str, id: &str) -> String {
+    format!("{}\n\n{}: {}", message.trim_end(), MACHINE_ID_TRAILER, id)
+}
+
+/// 32 random hex digits
+///
+/// `RandomState` is seeded from the operating system's random source, so
+/// two hashers give 128 unpredictable bits without another dependency.
+fn generate() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    let mut halves = [0u64; 2];
+    for (index, half) in halves.iter_mut().enumerate() {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(nanos);
+        hasher.write_u32(std::process::id());
+        hasher.write_usize(index);
+        *half = hasher.finish();
+    }
+    format!("{:016x}{:016x}", halves[0], halves[1])
+}
+
+fn is_valid(id: &str) -> bool {
+    id.len() == 32 && id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_id_is_stable_until_regenerated() {
+        let ctx = crate::test_utils::setup_unit_test();
+        assert_eq!(load().unwrap(), None);
+
+        let id = load_or_create().unwrap();
+        assert!(is_valid(&id), "{}", id);
+        assert_eq!(load_or_create().unwrap(), id);
+        assert!(ctx.jin_dir.join(MACHINE_ID_FILE).exists());
+
+        let new_id = regenerate().unwrap();
+        assert_ne!(new_id, id);
+        assert_eq!(load().unwrap(), Some(new_id));
+
+        std::fs::write(ctx.jin_dir.join(MACHINE_ID_FILE), "laptop\n").unwrap();
+        assert!(matches!(load(), Err(JinError::Parse { .. })));
+    }
+
+    #[test]
+    fn test_trailer_round_trip() {
+        let id = generate();
+        let message = with_trailer("Merge user-local layer from 'laptop'\n", &id);
+        assert_eq!(from_message(&message), Some(id.as_str()));
+        assert_eq!(short(&id).len(), SHORT_LEN);
+        assert_eq!(from_message("Plain message"), None);
+    }
+}
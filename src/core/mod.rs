@@ -1,11 +1,36 @@
 //! Core types and infrastructure for Jin
 
+pub mod branch;
 pub mod config;
+pub mod dangling;
 pub mod error;
+pub mod expiry;
+pub mod fsnames;
+pub mod home;
 pub mod jinmap;
 pub mod layer;
+pub mod limits;
+pub mod machine;
+pub mod offline;
+pub mod pager;
+pub mod profile;
+pub mod progress;
+pub mod queue;
+pub mod quota;
+pub mod schema;
+pub mod usage;
+pub mod workspaces;
 
-pub use config::{JinConfig, ProjectContext, RemoteConfig, UserConfig};
+pub use branch::BranchScope;
+pub use config::{
+    ApplyConfig, JinConfig, LocalSyncConfig, NotifyConfig, ProjectConfig, ProjectContext,
+    RemoteConfig, UserConfig,
+};
 pub use error::{JinError, Result};
+pub use expiry::LocalExpiry;
+pub use fsnames::{NameProblem, NameRules};
+pub use home::HomeConfig;
 pub use jinmap::JinMap;
 pub use layer::Layer;
+pub use limits::LimitsConfig;
+pub use offline::OfflineCache;
@@ -0,0 +1,215 @@
+//! Offline fallback for read-only commands
+//!
+//! The Jin repository may live on a network mount that is not always
+//! reachable. Every successful `jin apply` records the layer stack it merged
+//! (each layer's ref, commit and file count) and the files it wrote in
+//! `.jin/cache/offline.json`. When the repository can't be opened, `jin
+//! status` and `jin layers` fall back to that record instead of failing,
+//! behind a banner saying how old it is. Commands that change layers still
+//! need the repository.
+
+use crate::core::{JinError, Layer, Result};
+use crate::git::{JinRepo, TreeOps};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// One layer of the stack as last merged
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedLayer {
+    /// Layer name (`global-base`, ...)
+    pub layer: String,
+    /// Git ref of the layer
+    pub ref_path: String,
+    /// Storage path shown to users
+    pub storage: String,
+    /// Commit the ref pointed at, if it existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Files in the layer
+    pub files: usize,
+}
+
+impl CachedLayer {
+    /// The layer this entry describes
+    pub fn kind(&self) -> Option<Layer> {
+        self.layer.parse().ok()
+    }
+}
+
+/// The last successful merge of this workspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfflineCache {
+    /// When the merge was applied
+    pub saved_at: DateTime<Utc>,
+    /// Repository the layers were read from
+    pub repo_path: PathBuf,
+    /// Mode of the merge
+    pub mode: Option<String>,
+    /// Scope of the merge
+    pub scope: Option<String>,
+    /// Project of the merge
+    pub project: Option<String>,
+    /// Merged layers, in merge order
+    pub layers: Vec<CachedLayer>,
+    /// Files written to the workspace
+    pub files: Vec<PathBuf>,
+}
+
+impl OfflineCache {
+    /// Record a merge of `layers` for the given context
+    pub fn capture(
+        repo: &JinRepo,
+        layers: &[Layer],
+        mode: Option<&str>,
+        scope: Option<&str>,
+        project: Option<&str>,
+        files: impl IntoIterator<Item = PathBuf>,
+    ) -> Self {
+        let layers = layers
+            .iter()
+            .map(|layer| {
+                let ref_path = layer.ref_path(mode, scope, project);
+                let commit = repo
+                    .inner()
+                    .find_reference(&ref_path)
+                    .and_then(|r| r.peel_to_commit())
+                    .ok();
+                let files = commit
+                    .as_ref()
+                    .and_then(|c| repo.list_tree_files(c.tree_id()).ok())
+                    .map_or(0, |files| files.len());
+                CachedLayer {
+                    layer: layer.to_string(),
+                    storage: layer.storage_path(mode, scope, project),
+                    ref_path,
+                    commit: commit.map(|c| c.id().to_string()),
+                    files,
+                }
+            })
+            .collect();
+        let mut files: Vec<PathBuf> = files.into_iter().collect();
+        files.sort();
+        Self {
+            saved_at: Utc::now(),
+            repo_path: repo.path().to_path_buf(),
+            mode: mode.map(str::to_string),
+            scope: scope.map(str::to_string),
+            project: project.map(str::to_string),
+            layers,
+            files,
+        }
+    }
+
+    /// Location in a workspace (`.jin/cache/offline.json`)
+    pub fn default_path(root: &Path) -> PathBuf {
+        root.join(".jin").join("cache").join("offline.json")
+    }
+
+    /// The recorded merge, if there is one
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        let path = Self::default_path(root);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| JinError::Parse {
+                format: "JSON".to_string(),
+                message: format!("{}: {}", path.display(), e),
+            })
+    }
+
+    /// Replace the recorded merge
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = Self::default_path(root);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| JinError::Other(format!("Failed to serialize offline cache: {}", e)))?;
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, content)?;
+        std::fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+
+    /// The recorded merge to fall back on when the repository can't be
+    /// opened, or `None` when the repository is fine or nothing is recorded
+    pub fn fallback(root: &Path) -> Option<Self> {
+        if JinRepo::open(root).is_ok() {
+            return None;
+        }
+        Self::load(root).ok().flatten()
+    }
+
+    /// Warning shown above degraded output
+    pub fn banner(&self, now: DateTime<Utc>) -> String {
+        format!(
+            "Warning: Jin repository {} is unavailable; showing the state of the last apply ({}, {} ago).",
+            self.repo_path.display(),
+            self.saved_at.to_rfc3339(),
+            format_age(now - self.saved_at)
+        )
+    }
+}
+
+/// Rough age for the staleness banner
+fn format_age(age: chrono::Duration) -> String {
+    let (count, unit) = if age.num_days() > 0 {
+        (age.num_days(), "day")
+    } else if age.num_hours() > 0 {
+        (age.num_hours(), "hour")
+    } else if age.num_minutes() > 0 {
+        (age.num_minutes(), "minute")
+    } else {
+        (age.num_seconds().max(0), "second")
+    };
+    format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::ObjectOps;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_capture_and_reload() {
+        let ctx = crate::test_utils::setup_unit_test();
+        let repo = JinRepo::open_or_create_at(&ctx.jin_dir).unwrap();
+        let blob = repo.create_blob(b"{}").unwrap();
+        let tree = repo
+            .create_tree_from_paths(&[("app.json".to_string(), blob)])
+            .unwrap();
+        let commit = repo.create_commit(None, "init", tree, &[]).unwrap();
+        repo.inner()
+            .reference("refs/jin/layers/global", commit, true, "test")
+            .unwrap();
+
+        let cache = OfflineCache::capture(
+            &repo,
+            &[Layer::GlobalBase, Layer::ProjectBase],
+            None,
+            None,
+            Some("demo"),
+            [PathBuf::from("app.json")],
+        );
+        assert_eq!(cache.layers[0].files, 1);
+        assert_eq!(cache.layers[0].commit, Some(commit.to_string()));
+        assert_eq!(cache.layers[1].commit, None);
+        assert_eq!(cache.layers[1].kind(), Some(Layer::ProjectBase));
+
+        cache.save(&ctx.project_path).unwrap();
+        assert_eq!(OfflineCache::load(&ctx.project_path).unwrap(), Some(cache));
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(chrono::Duration::seconds(1)), "1 second");
+        assert_eq!(format_age(chrono::Duration::minutes(90)), "1 hour");
+        assert_eq!(format_age(chrono::Duration::days(3)), "3 days");
+    }
+}
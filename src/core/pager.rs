@@ -0,0 +1,291 @@
+//! Paged, colored output for long listings
+//!
+//! `jin log` and `jin diff` pipe their output through a pager when stdout is
+//! a terminal, choosing it like git does: `$GIT_PAGER`, then `core.pager`
+//! from the git config, then `$PAGER`, then `less`. An empty value or `cat`
+//! disables paging, as does the global `--no-pager` flag. `less` is started
+//! with `LESS=FRX` (unless set) so short output is printed directly and
+//! colors pass through.
+//!
+//! Color follows `--color <auto|always|never>`; `auto` colors only when
+//! stdout is a terminal and `NO_COLOR` is unset. Pick the [`Palette`]
+//! *before* starting the pager, since stdout is a pipe afterwards.
+
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Never start a pager for the rest of the process (`--no-pager`)
+pub fn disable() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// ANSI styling, or plain text when color is off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    enabled: bool,
+}
+
+impl Palette {
+    /// A palette that colors only when `enabled`
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    /// Color when stdout is a terminal and `NO_COLOR` is unset (`--color
+    /// auto`)
+    pub fn auto() -> Self {
+        Self::new(
+            std::io::stdout().is_terminal()
+                && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                && std::env::var("TERM").map_or(true, |t| t != "dumb"),
+        )
+    }
+
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.enabled {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Bold text (headers)
+    pub fn bold(&self, text: &str) -> String {
+        self.paint("1", text)
+    }
+
+    /// Red text (removed lines)
+    pub fn red(&self, text: &str) -> String {
+        self.paint("31", text)
+    }
+
+    /// Green text (added lines)
+    pub fn green(&self, text: &str) -> String {
+        self.paint("32", text)
+    }
+
+    /// Reverse-video red text (changed words of removed lines)
+    pub fn red_highlight(&self, text: &str) -> String {
+        self.paint("7;31", text)
+    }
+
+    /// Reverse-video green text (changed words of added lines)
+    pub fn green_highlight(&self, text: &str) -> String {
+        self.paint("7;32", text)
+    }
+
+    /// Yellow text (commit ids)
+    pub fn yellow(&self, text: &str) -> String {
+        self.paint("33", text)
+    }
+
+    /// Cyan text (hunk headers)
+    pub fn cyan(&self, text: &str) -> String {
+        self.paint("36", text)
+    }
+}
+
+/// The pager command to use, or `None` when paging is off
+///
+/// `core_pager` is the git config value, looked up only when `$GIT_PAGER`
+/// is unset.
+fn pager_command(
+    env: impl Fn(&str) -> Option<String>,
+    core_pager: impl FnOnce() -> Option<String>,
+) -> Option<String> {
+    let command = env("GIT_PAGER")
+        .or_else(core_pager)
+        .or_else(|| env("PAGER"))
+        .unwrap_or_else(|| "less".to_string());
+    let command = command.trim();
+    if command.is_empty() || command == "cat" {
+        None
+    } else {
+        Some(command.to_string())
+    }
+}
+
+fn git_core_pager() -> Option<String> {
+    git2::Config::open_default()
+        .ok()?
+        .get_string("core.pager")
+        .ok()
+}
+
+/// A running pager that receives this process's stdout
+///
+/// Dropping it hands stdout back and waits for the user to quit the pager.
+/// Drop it explicitly before `std::process::exit`, which skips destructors.
+#[derive(Debug)]
+pub struct Pager {
+    /// Held for its `Drop`, which restores stdout
+    #[cfg(unix)]
+    _active: Option<unix::Active>,
+}
+
+impl Pager {
+    /// Start the configured pager if stdout is a terminal
+    ///
+    /// Paging is best effort: when the pager cannot be started, output
+    /// goes to the terminal as usual.
+    pub fn start() -> Self {
+        if DISABLED.load(Ordering::Relaxed) || !std::io::stdout().is_terminal() {
+            return Self::inactive();
+        }
+        let Some(command) = pager_command(|name| std::env::var(name).ok(), git_core_pager) else {
+            return Self::inactive();
+        };
+        Self::spawn(&command)
+    }
+
+    fn inactive() -> Self {
+        Self {
+            #[cfg(unix)]
+            _active: None,
+        }
+    }
+
+    #[cfg(unix)]
+    fn spawn(command: &str) -> Self {
+        Self {
+            _active: unix::Active::spawn(command),
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn spawn(_command: &str) -> Self {
+        Self::inactive()
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::io::{IsTerminal, Write};
+    use std::os::fd::AsRawFd;
+    use std::process::{Child, Command, Stdio};
+
+    /// Pager process and the terminal descriptors it replaced
+    #[derive(Debug)]
+    pub struct Active {
+        child: Child,
+        stdout: libc::c_int,
+        stderr: Option<libc::c_int>,
+    }
+
+    impl Active {
+        pub fn spawn(command: &str) -> Option<Self> {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c").arg(command).stdin(Stdio::piped());
+            if std::env::var_os("LESS").is_none() {
+                cmd.env("LESS", "FRX");
+            }
+            if std::env::var_os("LV").is_none() {
+                cmd.env("LV", "-c");
+            }
+            let mut child = cmd.spawn().ok()?;
+            let pipe = child.stdin.take()?;
+
+            let _ = std::io::stdout().flush();
+            // Like git, send terminal stderr to the pager too, so warnings
+            // don't scribble over the paged screen
+            let stderr = std::io::stderr()
+                .is_terminal()
+                .then(|| redirect(libc::STDERR_FILENO, pipe.as_raw_fd()))
+                .flatten();
+            let Some(stdout) = redirect(libc::STDOUT_FILENO, pipe.as_raw_fd()) else {
+                if let Some(saved) = stderr {
+                    restore(libc::STDERR_FILENO, saved);
+                }
+                drop(pipe);
+                let _ = child.wait();
+                return None;
+            };
+            // Quitting the pager early closes the pipe; end quietly like git
+            // instead of panicking on the next write
+            unsafe { libc::signal(libc::SIGPIPE, libc::SIG_DFL) };
+
+            Some(Self {
+                child,
+                stdout,
+                stderr,
+            })
+        }
+    }
+
+    /// Point `fd` at `target`, returning a duplicate of the original
+    fn redirect(fd: libc::c_int, target: libc::c_int) -> Option<libc::c_int> {
+        let saved = unsafe { libc::dup(fd) };
+        if saved < 0 {
+            return None;
+        }
+        if unsafe { libc::dup2(target, fd) } < 0 {
+            unsafe { libc::close(saved) };
+            return None;
+        }
+        Some(saved)
+    }
+
+    fn restore(fd: libc::c_int, saved: libc::c_int) {
+        unsafe {
+            libc::dup2(saved, fd);
+            libc::close(saved);
+        }
+    }
+
+    impl Drop for Active {
+        fn drop(&mut self) {
+            let _ = std::io::stdout().flush();
+            let _ = std::io::stderr().flush();
+            // Restoring the terminal closes our last handles on the pipe, so
+            // the pager sees end of input
+            restore(libc::STDOUT_FILENO, self.stdout);
+            if let Some(stderr) = self.stderr {
+                restore(libc::STDERR_FILENO, stderr);
+            }
+            let _ = self.child.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn command(vars: &[(&str, &str)], core_pager: Option<&str>) -> Option<String> {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        pager_command(
+            |name| vars.get(name).map(|v| v.to_string()),
+            || core_pager.map(str::to_string),
+        )
+    }
+
+    #[test]
+    fn test_pager_command_precedence() {
+        assert_eq!(command(&[], None).as_deref(), Some("less"));
+        assert_eq!(command(&[("PAGER", "more")], None).as_deref(), Some("more"));
+        assert_eq!(
+            command(&[("PAGER", "more")], Some("delta")).as_deref(),
+            Some("delta")
+        );
+        assert_eq!(
+            command(&[("GIT_PAGER", "most"), ("PAGER", "more")], Some("delta")).as_deref(),
+            Some("most")
+        );
+    }
+
+    #[test]
+    fn test_empty_or_cat_disables_paging() {
+        assert_eq!(command(&[("GIT_PAGER", "")], Some("less")), None);
+        assert_eq!(command(&[("PAGER", "cat")], None), None);
+        assert_eq!(command(&[], Some(" ")), None);
+    }
+
+    #[test]
+    fn test_palette() {
+        assert_eq!(Palette::new(false).red("-x"), "-x");
+        assert_eq!(Palette::new(true).green("+x"), "\x1b[32m+x\x1b[0m");
+    }
+}
@@ -0,0 +1,264 @@
+//! Opt-in command profiling
+//!
+//! When `--profile` is passed, Jin records wall-clock time spent in a small
+//! set of coarse phases (repository open, tree walks, parsing, merging,
+//! serialization and workspace IO) and prints a summary to stderr after the
+//! command finishes.
+//!
+//! Recording is a no-op unless [`enable`] has been called, so instrumented
+//! code paths cost a single atomic load in normal operation.
+//!
+//! Phases are measured inclusively: time spent parsing inside a merge is
+//! counted under both `parse` and `merge`.
+
+use super::schema::JsonSurface;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static TIMINGS: Mutex<BTreeMap<Phase, PhaseStats>> = Mutex::new(BTreeMap::new());
+
+/// A coarse phase of command execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Opening the Jin bare repository
+    RepoOpen,
+    /// Walking layer trees to enumerate files
+    TreeWalk,
+    /// Parsing layer content into merge values
+    Parse,
+    /// Merging files across layers
+    Merge,
+    /// Serializing merged values back to text
+    Serialize,
+    /// Reading and writing workspace files
+    Io,
+}
+
+impl Phase {
+    /// Returns the display name used in reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Phase::RepoOpen => "repo_open",
+            Phase::TreeWalk => "tree_walk",
+            Phase::Parse => "parse",
+            Phase::Merge => "merge",
+            Phase::Serialize => "serialize",
+            Phase::Io => "io",
+        }
+    }
+}
+
+/// Accumulated timing for a single phase
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseStats {
+    /// Number of times the phase was entered
+    pub calls: u64,
+    /// Total time spent in the phase
+    pub total: Duration,
+}
+
+/// Guard that records the elapsed time for a phase when dropped
+#[must_use = "the span records its timing when dropped"]
+pub struct Span {
+    phase: Phase,
+    start: Option<Instant>,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            record(self.phase, start.elapsed());
+        }
+    }
+}
+
+/// Enable profiling for the remainder of the process
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Check whether profiling is enabled
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Start timing a phase. Timing stops when the returned guard is dropped.
+pub fn span(phase: Phase) -> Span {
+    Span {
+        phase,
+        start: is_enabled().then(Instant::now),
+    }
+}
+
+/// Time a closure under the given phase
+pub fn time<T>(phase: Phase, f: impl FnOnce() -> T) -> T {
+    let _span = span(phase);
+    f()
+}
+
+/// Record a measured duration for a phase
+pub fn record(phase: Phase, elapsed: Duration) {
+    if !is_enabled() {
+        return;
+    }
+    if let Ok(mut timings) = TIMINGS.lock() {
+        let stats = timings.entry(phase).or_default();
+        stats.calls += 1;
+        stats.total += elapsed;
+    }
+}
+
+/// Build a report from everything recorded so far
+///
+/// `total` is the wall-clock duration of the whole command.
+pub fn report(total: Duration) -> ProfileReport {
+    let phases = TIMINGS
+        .lock()
+        .map(|timings| timings.clone())
+        .unwrap_or_default();
+    ProfileReport { total, phases }
+}
+
+/// Summary of time spent per phase
+#[derive(Debug, Clone, Default)]
+pub struct ProfileReport {
+    /// Wall-clock duration of the whole command
+    pub total: Duration,
+    /// Per-phase accumulated timings
+    pub phases: BTreeMap<Phase, PhaseStats>,
+}
+
+#[derive(Serialize)]
+struct JsonPhase {
+    phase: Phase,
+    calls: u64,
+    total_ms: f64,
+}
+
+#[derive(Serialize)]
+struct JsonReport {
+    schema_version: u32,
+    total_ms: f64,
+    phases: Vec<JsonPhase>,
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+impl ProfileReport {
+    /// Render the report as a human-readable table
+    pub fn render_table(&self) -> String {
+        let mut out = String::new();
+        out.push_str("Profile:\n");
+        out.push_str(&format!(
+            "  {:<12} {:>8} {:>12}\n",
+            "PHASE", "CALLS", "TIME (ms)"
+        ));
+        for (phase, stats) in &self.phases {
+            out.push_str(&format!(
+                "  {:<12} {:>8} {:>12.3}\n",
+                phase.as_str(),
+                stats.calls,
+                millis(stats.total)
+            ));
+        }
+        out.push_str(&format!(
+            "  {:<12} {:>8} {:>12.3}\n",
+            "total",
+            "",
+            millis(self.total)
+        ));
+        out
+    }
+
+    /// Render the report as a single-line JSON object
+    pub fn to_json(&self) -> String {
+        let report = JsonReport {
+            schema_version: JsonSurface::Profile.version(),
+            total_ms: millis(self.total),
+            phases: self
+                .phases
+                .iter()
+                .map(|(phase, stats)| JsonPhase {
+                    phase: *phase,
+                    calls: stats.calls,
+                    total_ms: millis(stats.total),
+                })
+                .collect(),
+        };
+        serde_json::to_string(&report).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_report() -> ProfileReport {
+        let mut phases = BTreeMap::new();
+        phases.insert(
+            Phase::Merge,
+            PhaseStats {
+                calls: 2,
+                total: Duration::from_millis(5),
+            },
+        );
+        phases.insert(
+            Phase::RepoOpen,
+            PhaseStats {
+                calls: 1,
+                total: Duration::from_millis(1),
+            },
+        );
+        ProfileReport {
+            total: Duration::from_millis(10),
+            phases,
+        }
+    }
+
+    #[test]
+    fn test_render_table_lists_phases_in_order() {
+        let table = sample_report().render_table();
+        let repo_open = table.find("repo_open").unwrap();
+        let merge = table.find("merge").unwrap();
+        assert!(repo_open < merge);
+        assert!(table.contains("total"));
+        assert!(table.contains("10.000"));
+    }
+
+    #[test]
+    fn test_to_json_structure() {
+        let json: serde_json::Value = serde_json::from_str(&sample_report().to_json()).unwrap();
+        assert_eq!(json["total_ms"], 10.0);
+        assert_eq!(json["phases"][0]["phase"], "repo_open");
+        assert_eq!(json["phases"][1]["phase"], "merge");
+        assert_eq!(json["phases"][1]["calls"], 2);
+    }
+
+    #[test]
+    fn test_to_json_matches_schema() {
+        let json: serde_json::Value = serde_json::from_str(&sample_report().to_json()).unwrap();
+        super::super::schema::assert_matches(&JsonSurface::Profile.schema(), &json, "profile");
+    }
+
+    #[test]
+    fn test_span_records_when_enabled() {
+        enable();
+        let before = report(Duration::ZERO)
+            .phases
+            .get(&Phase::Serialize)
+            .map(|s| s.calls)
+            .unwrap_or(0);
+
+        time(Phase::Serialize, || ());
+
+        let after = report(Duration::ZERO).phases[&Phase::Serialize].calls;
+        assert!(after > before);
+    }
+}
@@ -0,0 +1,218 @@
+//! Progress reporting for long-running operations
+//!
+//! Fetches, pushes and applies report how far along they are. By default
+//! (`--progress=human`) fetches redraw a progress line, but only when stdout
+//! is a terminal, so piped output stays free of `\r` noise.
+//!
+//! Tools driving Jin as a subprocess pass `--progress=json` instead: every
+//! [`Event`] is written to stderr as one JSON object per line (NDJSON) and
+//! no progress line is drawn. Stdout keeps the command's normal output
+//! either way. See `jin schema progress` for the event format.
+//!
+//! Warnings and other messages for stderr go through [`warn`], [`error`]
+//! and [`wait`], which become `warning`, `error` and `wait` events with
+//! `--progress=json`, so the stream stays one JSON object per line.
+
+use super::schema::JsonSurface;
+use serde::Serialize;
+use std::fmt::Display;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// Report progress as JSON events for the rest of the process
+pub fn enable_json() {
+    JSON.store(true, Ordering::Relaxed);
+}
+
+/// Whether `--progress=json` was given
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Whether to draw human-readable progress lines
+pub fn human() -> bool {
+    !is_json() && io::stdout().is_terminal()
+}
+
+/// Operation an event belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    /// Downloading layer refs from a remote
+    Fetch,
+    /// Uploading layer refs to a remote
+    Push,
+    /// Writing merged layers to the workspace
+    Apply,
+}
+
+/// Step of an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Receiving objects (counts objects)
+    Receive,
+    /// Resolving received deltas (counts deltas)
+    Index,
+    /// Sending objects (counts objects)
+    Send,
+    /// Merging layers
+    Merge,
+    /// Writing a workspace file (`current` files of `total` written
+    /// before `path`)
+    Write,
+    /// The operation finished
+    Done,
+    /// Something went wrong without stopping the command (`message`)
+    Warning,
+    /// A step failed; the command reports the overall result (`message`)
+    Error,
+    /// Waiting for another Jin process (`message`)
+    Wait,
+}
+
+/// A single progress event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Event {
+    schema_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    operation: Option<Operation>,
+    phase: Phase,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remote: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+impl Event {
+    /// Create an event without counts
+    pub fn new(operation: Operation, phase: Phase) -> Self {
+        Self {
+            schema_version: JsonSurface::Progress.version(),
+            operation: Some(operation),
+            phase,
+            current: None,
+            total: None,
+            path: None,
+            remote: None,
+            message: None,
+        }
+    }
+
+    /// Create a message event, which belongs to no operation
+    fn message(phase: Phase, message: String) -> Self {
+        Self {
+            schema_version: JsonSurface::Progress.version(),
+            operation: None,
+            phase,
+            current: None,
+            total: None,
+            path: None,
+            remote: None,
+            message: Some(message),
+        }
+    }
+
+    /// Set how many of `total` items are done
+    pub fn count(mut self, current: usize, total: usize) -> Self {
+        self.current = Some(current);
+        self.total = Some(total);
+        self
+    }
+
+    /// Set the file the event is about
+    pub fn path(mut self, path: &Path) -> Self {
+        self.path = Some(path.display().to_string());
+        self
+    }
+
+    /// Set the remote the event is about
+    pub fn remote(mut self, remote: &str) -> Self {
+        self.remote = Some(remote.to_string());
+        self
+    }
+
+    /// Write the event to stderr if `--progress=json` was given
+    pub fn emit(&self) {
+        if !is_json() {
+            return;
+        }
+        if let Ok(line) = serde_json::to_string(self) {
+            let _ = writeln!(io::stderr().lock(), "{}", line);
+        }
+    }
+}
+
+/// Report a warning: `Warning: <message>` on stderr, or a `warning` event
+pub fn warn(message: impl Display) {
+    report(Phase::Warning, "Warning: ", message);
+}
+
+/// Report a failed step: `Error: <message>` on stderr, or an `error` event
+pub fn error(message: impl Display) {
+    report(Phase::Error, "Error: ", message);
+}
+
+/// Report waiting for another process: the message on stderr, or a `wait`
+/// event
+pub fn wait(message: impl Display) {
+    report(Phase::Wait, "", message);
+}
+
+/// [`warn`] while a progress line may be drawn, starting the warning on a
+/// line of its own
+pub fn warn_on_new_line(message: impl Display) {
+    if human() {
+        eprintln!();
+    }
+    warn(message);
+}
+
+fn report(phase: Phase, prefix: &str, message: impl Display) {
+    if is_json() {
+        Event::message(phase, message.to_string()).emit();
+    } else {
+        eprintln!("{}{}", prefix, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_matches_schema() {
+        let schema = JsonSurface::Progress.schema();
+        let event = Event::new(Operation::Apply, Phase::Write)
+            .count(2, 5)
+            .path(Path::new("config/app.json"));
+        let json = serde_json::to_value(&event).unwrap();
+        crate::core::schema::assert_matches(&schema, &json, "progress");
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"schema_version":1,"operation":"apply","phase":"write","current":2,"total":5,"path":"config/app.json"}"#
+        );
+
+        let done = Event::new(Operation::Fetch, Phase::Done).remote("origin");
+        let json = serde_json::to_value(&done).unwrap();
+        crate::core::schema::assert_matches(&schema, &json, "progress");
+        assert!(json.get("current").is_none());
+
+        let warning = Event::message(Phase::Warning, "Retrying".to_string());
+        let json = serde_json::to_value(&warning).unwrap();
+        crate::core::schema::assert_matches(&schema, &json, "progress");
+        assert_eq!(
+            serde_json::to_string(&warning).unwrap(),
+            r#"{"schema_version":1,"phase":"warning","message":"Retrying"}"#
+        );
+    }
+}
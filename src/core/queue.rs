@@ -0,0 +1,436 @@
+//! Single-writer queue for workspace mutations
+//!
+//! Editor plugins, Git hooks and file watchers tend to call `jin apply` in
+//! bursts, often while someone runs it by hand. Two applies writing the
+//! workspace at once can interleave their files, so every apply goes
+//! through a queue in `.jin/queue/`:
+//!
+//! - one process at a time holds `writer.lock` and mutates the workspace;
+//!   `running.json` says which
+//! - the others record themselves in `waiting/` and block on the lock
+//! - a waiter whose request is covered by an apply that *started* after it
+//!   and finished successfully with the same arguments (recorded in
+//!   `last.json`) has nothing left to do and exits instead of applying
+//!   again, so a burst of N requests costs at most two applies
+//! - `jin apply --debounce <ms>` waits, once it holds the lock, until no
+//!   matching request has arrived for that long, so a burst is folded into
+//!   the apply that runs after it settles
+//!
+//! Commands that write the workspace without applying (`jin reset --hard`,
+//! `jin resolve`) take the same lock through [`WorkspaceQueue::lock`]. They
+//! are never coalesced, and since they change the workspace behind the last
+//! apply's back, waiting applies run again instead of counting as covered.
+//!
+//! The lock is an OS file lock, released even if the holder crashes.
+//! `jin status` shows the running and waiting requests.
+
+use crate::core::progress;
+use crate::core::{JinError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Directory holding the queue
+pub const QUEUE_DIR: &str = ".jin/queue";
+
+/// Lock held by the process mutating the workspace
+const WRITER_LOCK: &str = "writer.lock";
+
+/// Request of the lock holder
+const RUNNING_FILE: &str = "running.json";
+
+/// Last successful run, for coalescing
+const LAST_FILE: &str = "last.json";
+
+/// One file per waiting request
+const WAITING_DIR: &str = "waiting";
+
+/// A request to mutate the workspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueRequest {
+    /// Requesting process
+    pub pid: u32,
+    /// Command line shown in `jin status` (e.g., `apply --force`)
+    pub command: String,
+    /// Requests with equal keys do the same thing and can be coalesced
+    pub key: String,
+    /// When the request was made
+    pub requested_at: DateTime<Utc>,
+    /// When it started writing, after any debounce (set while running)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+}
+
+/// Who is running and who is waiting
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueueState {
+    /// The request holding the lock
+    pub running: Option<QueueRequest>,
+    /// Requests waiting for the lock, oldest first
+    pub waiting: Vec<QueueRequest>,
+}
+
+impl QueueState {
+    /// Whether nothing is running or waiting
+    pub fn is_idle(&self) -> bool {
+        self.running.is_none() && self.waiting.is_empty()
+    }
+}
+
+/// Outcome of entering the queue
+#[derive(Debug)]
+pub enum Admission {
+    /// Go ahead; the lock is held until the guard is dropped
+    Run(WriterGuard),
+    /// An equal request started after this one and finished at the given
+    /// time, so there is nothing left to do
+    Coalesced(DateTime<Utc>),
+}
+
+/// The workspace's queue
+#[derive(Debug, Clone)]
+pub struct WorkspaceQueue {
+    dir: PathBuf,
+}
+
+impl WorkspaceQueue {
+    /// Queue of the workspace at `root`
+    pub fn open(root: &Path) -> Self {
+        Self::at(&root.join(QUEUE_DIR))
+    }
+
+    /// Queue kept in `dir`
+    pub fn at(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    /// Wait for the lock (calling `on_wait` with the holder's request if
+    /// it is taken), then coalesce or debounce as described in the module
+    /// docs
+    pub fn enter(
+        &self,
+        command: &str,
+        key: &str,
+        debounce: Duration,
+        on_wait: impl FnOnce(Option<&QueueRequest>),
+    ) -> Result<Admission> {
+        let (lock, request) = self.acquire(command, key, on_wait)?;
+        if let Some(last) = read_json::<QueueRequest>(&self.dir.join(LAST_FILE)) {
+            if last.key == request.key
+                && last
+                    .started_at
+                    .is_some_and(|started| started > request.requested_at)
+            {
+                return Ok(Admission::Coalesced(last.requested_at));
+            }
+        }
+
+        write_json(&self.dir.join(RUNNING_FILE), &request)?;
+        if !debounce.is_zero() {
+            loop {
+                let newest = self
+                    .waiting()
+                    .into_iter()
+                    .filter(|r| r.key == request.key)
+                    .map(|r| r.requested_at)
+                    .max()
+                    .map_or(request.requested_at, |t| t.max(request.requested_at));
+                let quiet = (Utc::now() - newest).to_std().unwrap_or_default();
+                if quiet >= debounce {
+                    break;
+                }
+                std::thread::sleep(debounce - quiet);
+            }
+        }
+
+        self.start(lock, request).map(Admission::Run)
+    }
+
+    /// Wait for the lock for a command that writes the workspace without
+    /// applying (calling `on_wait` as [`enter`](Self::enter) does)
+    pub fn lock(
+        &self,
+        command: &str,
+        on_wait: impl FnOnce(Option<&QueueRequest>),
+    ) -> Result<WriterGuard> {
+        let (lock, request) = self.acquire(command, "", on_wait)?;
+        // The last apply no longer describes the workspace
+        match std::fs::remove_file(self.dir.join(LAST_FILE)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        self.start(lock, request)
+    }
+
+    /// Record a request as waiting and block until it holds the lock
+    fn acquire(
+        &self,
+        command: &str,
+        key: &str,
+        on_wait: impl FnOnce(Option<&QueueRequest>),
+    ) -> Result<(File, QueueRequest)> {
+        std::fs::create_dir_all(self.dir.join(WAITING_DIR))?;
+        let request = QueueRequest {
+            pid: std::process::id(),
+            command: command.to_string(),
+            key: key.to_string(),
+            requested_at: Utc::now(),
+            started_at: None,
+        };
+        let waiting_path = self.dir.join(WAITING_DIR).join(format!(
+            "{}-{}.json",
+            request.pid,
+            request
+                .requested_at
+                .timestamp_nanos_opt()
+                .unwrap_or_default()
+        ));
+        write_json(&waiting_path, &request)?;
+
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.dir.join(WRITER_LOCK))?;
+        let locked = match lock.try_lock() {
+            Ok(()) => Ok(()),
+            Err(TryLockError::WouldBlock) => {
+                on_wait(read_json::<QueueRequest>(&self.dir.join(RUNNING_FILE)).as_ref());
+                lock.lock()
+            }
+            Err(TryLockError::Error(e)) => Err(e),
+        };
+        let _ = std::fs::remove_file(&waiting_path);
+        locked?;
+        Ok((lock, request))
+    }
+
+    /// Mark the lock holder's request as running
+    fn start(&self, lock: File, mut request: QueueRequest) -> Result<WriterGuard> {
+        request.started_at = Some(Utc::now());
+        write_json(&self.dir.join(RUNNING_FILE), &request)?;
+        Ok(WriterGuard {
+            _lock: lock,
+            dir: self.dir.clone(),
+            request,
+        })
+    }
+
+    /// Current requests; a stale `running.json` left by a crash is ignored
+    pub fn state(&self) -> QueueState {
+        let running = read_json::<QueueRequest>(&self.dir.join(RUNNING_FILE)).filter(|_| {
+            matches!(
+                File::open(self.dir.join(WRITER_LOCK)).map(|f| f.try_lock()),
+                Ok(Err(TryLockError::WouldBlock))
+            )
+        });
+        QueueState {
+            running,
+            waiting: self.waiting(),
+        }
+    }
+
+    /// Requests waiting for the lock, oldest first, dropping those whose
+    /// process is gone
+    fn waiting(&self) -> Vec<QueueRequest> {
+        let Ok(entries) = std::fs::read_dir(self.dir.join(WAITING_DIR)) else {
+            return Vec::new();
+        };
+        let mut waiting: Vec<QueueRequest> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let request = read_json::<QueueRequest>(&entry.path())?;
+                if process_alive(request.pid) {
+                    Some(request)
+                } else {
+                    let _ = std::fs::remove_file(entry.path());
+                    None
+                }
+            })
+            .collect();
+        waiting.sort_by_key(|r| r.requested_at);
+        waiting
+    }
+}
+
+/// Holds the writer lock until dropped
+#[derive(Debug)]
+pub struct WriterGuard {
+    _lock: File,
+    dir: PathBuf,
+    request: QueueRequest,
+}
+
+impl WriterGuard {
+    /// Record a successful run, so equal requests made before it started
+    /// are coalesced
+    pub fn finish(self) -> Result<()> {
+        let finished = QueueRequest {
+            requested_at: Utc::now(),
+            ..self.request.clone()
+        };
+        write_json(&self.dir.join(LAST_FILE), &finished)
+    }
+}
+
+impl Drop for WriterGuard {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(self.dir.join(RUNNING_FILE));
+    }
+}
+
+/// Tell the user a command is waiting for the queue (the `on_wait`
+/// callback commands pass to [`WorkspaceQueue::enter`] and
+/// [`WorkspaceQueue::lock`])
+pub fn announce_wait(running: Option<&QueueRequest>) {
+    match running {
+        Some(r) => progress::wait(format_args!(
+            "Waiting for another jin {} (pid {}) to finish...",
+            r.command, r.pid
+        )),
+        None => progress::wait("Waiting for another jin command to finish..."),
+    }
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let content = serde_json::to_string(value)
+        .map_err(|e| JinError::Other(format!("Failed to serialize queue state: {}", e)))?;
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, content)?;
+    std::fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Option<T> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    // SAFETY: signal 0 only checks that the process exists
+    let rc = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    rc == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn run(queue: &WorkspaceQueue, key: &str) -> WriterGuard {
+        match queue.enter("apply", key, Duration::ZERO, |_| {}).unwrap() {
+            Admission::Run(guard) => guard,
+            Admission::Coalesced(_) => panic!("expected to run"),
+        }
+    }
+
+    #[test]
+    fn test_state_shows_running_request() {
+        let temp = TempDir::new().unwrap();
+        let queue = WorkspaceQueue::at(temp.path());
+        assert!(queue.state().is_idle());
+
+        let guard = run(&queue, "apply");
+        let state = queue.state();
+        assert_eq!(
+            state.running.as_ref().map(|r| r.pid),
+            Some(std::process::id())
+        );
+        assert!(state.waiting.is_empty());
+
+        drop(guard);
+        assert!(queue.state().is_idle());
+    }
+
+    #[test]
+    fn test_burst_of_waiters_runs_once() {
+        let temp = TempDir::new().unwrap();
+        let queue = WorkspaceQueue::at(temp.path());
+        let holder = run(&queue, "apply");
+
+        let waiters: Vec<_> = (0..2)
+            .map(|_| {
+                let dir = temp.path().to_path_buf();
+                std::thread::spawn(move || {
+                    let admission = WorkspaceQueue::at(&dir)
+                        .enter("apply", "apply", Duration::ZERO, |running| {
+                            assert_eq!(running.map(|r| r.pid), Some(std::process::id()));
+                        })
+                        .unwrap();
+                    match admission {
+                        Admission::Run(guard) => {
+                            guard.finish().unwrap();
+                            false
+                        }
+                        Admission::Coalesced(_) => true,
+                    }
+                })
+            })
+            .collect();
+        while queue.state().waiting.len() < 2 {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        // The holder started before both requests, so one of them runs and
+        // covers the other
+        holder.finish().unwrap();
+        let coalesced: Vec<bool> = waiters.into_iter().map(|w| w.join().unwrap()).collect();
+        assert_eq!(
+            coalesced.iter().filter(|c| **c).count(),
+            1,
+            "{:?}",
+            coalesced
+        );
+        assert!(queue.state().is_idle());
+    }
+
+    #[test]
+    fn test_finished_run_covers_earlier_requests_only() {
+        let temp = TempDir::new().unwrap();
+        let queue = WorkspaceQueue::at(temp.path());
+        // Requests made after the last run started always run
+        run(&queue, "apply").finish().unwrap();
+        run(&queue, "apply").finish().unwrap();
+        run(&queue, "apply --force");
+    }
+
+    #[test]
+    fn test_lock_forgets_last_apply() {
+        let temp = TempDir::new().unwrap();
+        let queue = WorkspaceQueue::at(temp.path());
+        run(&queue, "apply").finish().unwrap();
+        assert!(temp.path().join(LAST_FILE).exists());
+
+        let guard = queue.lock("reset --hard", |_| {}).unwrap();
+        assert_eq!(
+            queue.state().running.map(|r| r.command),
+            Some("reset --hard".to_string())
+        );
+        // Applies must run again after the workspace was rewritten
+        assert!(!temp.path().join(LAST_FILE).exists());
+
+        drop(guard);
+        assert!(queue.state().is_idle());
+    }
+
+    #[test]
+    fn test_debounce_waits_for_quiet_period() {
+        let temp = TempDir::new().unwrap();
+        let queue = WorkspaceQueue::at(temp.path());
+        let started = std::time::Instant::now();
+        let admission = queue
+            .enter("apply", "apply", Duration::from_millis(50), |_| {})
+            .unwrap();
+        assert!(matches!(admission, Admission::Run(_)));
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    }
+}
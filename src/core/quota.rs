@@ -0,0 +1,247 @@
+//! Storage quotas per mode and scope
+//!
+//! A mode or scope owns several layer refs (`mode/claude/_`,
+//! `mode/claude/scope/backend/_`, ...), so a single runaway layer is easy to
+//! miss while the mode as a whole keeps growing. This module measures every
+//! layer ref (file count and blob bytes at its tip) and adds them up per mode
+//! and per scope, for `jin stats` and for the quota warnings `jin commit`
+//! prints (`limits.mode-max-size`, `limits.scope-max-size`, see
+//! [`crate::core::limits`]).
+//!
+//! Sizes are logical: a blob shared by two layers counts in both.
+
+use crate::core::Result;
+use crate::git::refs::layer_name;
+use crate::git::{JinRepo, RefOps};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Prefix of layer refs
+const LAYERS_PREFIX: &str = "refs/jin/layers/";
+
+/// Size of one layer ref
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerStorage {
+    /// Layer ref
+    pub ref_path: String,
+    /// Mode the layer belongs to, if any
+    pub mode: Option<String>,
+    /// Scope the layer belongs to, if any
+    pub scope: Option<String>,
+    /// Files at the tip
+    pub files: u64,
+    /// Blob bytes at the tip
+    pub size: u64,
+}
+
+/// Storage of every layer of one mode or scope
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextStorage {
+    /// Mode or scope name
+    pub name: String,
+    /// Layer refs counted
+    pub layers: usize,
+    /// Files across those layers
+    pub files: u64,
+    /// Blob bytes across those layers
+    pub size: u64,
+}
+
+/// One file of a layer, for finding the largest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStorage {
+    /// Layer display name (e.g., `mode/claude/scope/backend`)
+    pub layer: String,
+    /// Path in the layer
+    pub path: PathBuf,
+    /// Blob bytes
+    pub size: u64,
+}
+
+/// Mode and scope a layer ref belongs to
+///
+/// Scope names are stored with `:` replaced by `/` (see
+/// [`crate::core::Layer::ref_path`]) and are returned with `:` again.
+pub fn context_of(ref_path: &str) -> (Option<String>, Option<String>) {
+    let Some(path) = ref_path.strip_prefix(LAYERS_PREFIX) else {
+        return (None, None);
+    };
+    let path = path.strip_suffix("/_").unwrap_or(path);
+    let scope_of = |rest: &str| {
+        let scope = rest.split("/project/").next().unwrap_or(rest);
+        (!scope.is_empty()).then(|| scope.replace('/', ":"))
+    };
+
+    if let Some(rest) = path.strip_prefix("mode/") {
+        let (mode, rest) = rest.split_once('/').unwrap_or((rest, ""));
+        let scope = rest.strip_prefix("scope/").and_then(scope_of);
+        (Some(mode.to_string()), scope)
+    } else if let Some(rest) = path.strip_prefix("scope/") {
+        (None, scope_of(rest))
+    } else {
+        (None, None)
+    }
+}
+
+/// Number of files and total blob bytes in a tree
+pub fn footprint(repo: &JinRepo, tree: &git2::Tree) -> Result<(u64, u64)> {
+    let odb = repo.inner().odb()?;
+    let (mut files, mut size) = (0u64, 0u64);
+    tree.walk(git2::TreeWalkMode::PreOrder, |_, entry| {
+        if entry.kind() == Some(git2::ObjectType::Blob) {
+            files += 1;
+            if let Ok((len, _)) = odb.read_header(entry.id()) {
+                size += len as u64;
+            }
+        }
+        git2::TreeWalkResult::Ok
+    })?;
+    Ok((files, size))
+}
+
+/// Measure every layer ref
+pub fn measure(repo: &JinRepo) -> Result<Vec<LayerStorage>> {
+    let mut layers = Vec::new();
+    for ref_path in repo.list_refs(&format!("{}**", LAYERS_PREFIX))? {
+        let reference = repo.inner().find_reference(&ref_path)?;
+        let Ok(tree) = reference.peel_to_tree() else {
+            continue;
+        };
+        let (files, size) = footprint(repo, &tree)?;
+        let (mode, scope) = context_of(&ref_path);
+        layers.push(LayerStorage {
+            ref_path,
+            mode,
+            scope,
+            files,
+            size,
+        });
+    }
+    Ok(layers)
+}
+
+/// Totals per mode, largest first
+pub fn by_mode(layers: &[LayerStorage]) -> Vec<ContextStorage> {
+    totals(layers, |layer| layer.mode.as_deref())
+}
+
+/// Totals per scope, largest first
+pub fn by_scope(layers: &[LayerStorage]) -> Vec<ContextStorage> {
+    totals(layers, |layer| layer.scope.as_deref())
+}
+
+fn totals(
+    layers: &[LayerStorage],
+    key: impl Fn(&LayerStorage) -> Option<&str>,
+) -> Vec<ContextStorage> {
+    let mut totals: BTreeMap<&str, ContextStorage> = BTreeMap::new();
+    for layer in layers {
+        let Some(name) = key(layer) else {
+            continue;
+        };
+        let total = totals.entry(name).or_insert_with(|| ContextStorage {
+            name: name.to_string(),
+            layers: 0,
+            files: 0,
+            size: 0,
+        });
+        total.layers += 1;
+        total.files += layer.files;
+        total.size += layer.size;
+    }
+    let mut totals: Vec<ContextStorage> = totals.into_values().collect();
+    totals.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    totals
+}
+
+/// The `count` largest files across all layers, largest first
+pub fn largest_files(repo: &JinRepo, count: usize) -> Result<Vec<FileStorage>> {
+    let odb = repo.inner().odb()?;
+    let mut files = Vec::new();
+    for ref_path in repo.list_refs(&format!("{}**", LAYERS_PREFIX))? {
+        let Ok(tree) = repo.inner().find_reference(&ref_path)?.peel_to_tree() else {
+            continue;
+        };
+        tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() == Some(git2::ObjectType::Blob) {
+                if let Ok((len, _)) = odb.read_header(entry.id()) {
+                    files.push(FileStorage {
+                        layer: layer_name(&ref_path).to_string(),
+                        path: PathBuf::from(dir).join(entry.name().unwrap_or_default()),
+                        size: len as u64,
+                    });
+                }
+            }
+            git2::TreeWalkResult::Ok
+        })?;
+    }
+    files.sort_by(|a, b| {
+        b.size
+            .cmp(&a.size)
+            .then_with(|| a.layer.cmp(&b.layer))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    files.truncate(count);
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(ref_path: &str, size: u64) -> LayerStorage {
+        let (mode, scope) = context_of(ref_path);
+        LayerStorage {
+            ref_path: ref_path.to_string(),
+            mode,
+            scope,
+            files: 1,
+            size,
+        }
+    }
+
+    #[test]
+    fn test_context_of() {
+        let of = |path: &str| context_of(path);
+        assert_eq!(of("refs/jin/layers/global"), (None, None));
+        assert_eq!(
+            of("refs/jin/layers/mode/claude/_"),
+            (Some("claude".into()), None)
+        );
+        assert_eq!(
+            of("refs/jin/layers/mode/claude/scope/lang/rust/_"),
+            (Some("claude".into()), Some("lang:rust".into()))
+        );
+        assert_eq!(
+            of("refs/jin/layers/mode/claude/scope/backend/project/api"),
+            (Some("claude".into()), Some("backend".into()))
+        );
+        assert_eq!(
+            of("refs/jin/layers/mode/claude/project/api"),
+            (Some("claude".into()), None)
+        );
+        assert_eq!(
+            of("refs/jin/layers/scope/backend"),
+            (None, Some("backend".into()))
+        );
+        assert_eq!(of("refs/jin/layers/project/api"), (None, None));
+    }
+
+    #[test]
+    fn test_totals_largest_first() {
+        let layers = vec![
+            layer("refs/jin/layers/mode/claude/_", 10),
+            layer("refs/jin/layers/mode/claude/scope/backend/_", 30),
+            layer("refs/jin/layers/mode/cursor/_", 25),
+            layer("refs/jin/layers/scope/backend", 5),
+        ];
+        let modes = by_mode(&layers);
+        assert_eq!(modes[0].name, "claude");
+        assert_eq!((modes[0].layers, modes[0].size), (2, 40));
+        assert_eq!(modes[1].name, "cursor");
+
+        let scopes = by_scope(&layers);
+        assert_eq!(scopes.len(), 1);
+        assert_eq!((scopes[0].layers, scopes[0].size), (2, 35));
+    }
+}
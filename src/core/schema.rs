@@ -0,0 +1,660 @@
+//! Versioned schemas for machine-readable output
+//!
+//! Every JSON surface (`jin status`, `jin log`, `jin diff`, `jin layers`,
+//! `jin list` and `jin version` with the global `--json` flag, `jin verify
+//! --format json`, `jin audit export --format jsonl`, `jin badge --format
+//! json`, `--profile=json` and the `--progress=json` events) carries a
+//! top-level `schema_version`. Within a version, output only ever gains
+//! fields: existing fields keep their name, type and meaning, so consumers
+//! should ignore fields they don't know. Removing, renaming or retyping a
+//! field bumps the version of that surface.
+//!
+//! `jin schema <command>` prints the JSON Schema of a surface for tooling
+//! authors.
+
+use crate::core::{JinError, Result};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Print JSON instead of text for the rest of the process (`--json`)
+pub fn enable_json() {
+    JSON_OUTPUT.store(true, Ordering::Relaxed);
+}
+
+/// Whether `--json` was given
+pub fn json_output() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Print a report as pretty-printed JSON
+pub fn print_json<T: Serialize>(report: &T) -> Result<()> {
+    let json = serde_json::to_string_pretty(report)
+        .map_err(|e| JinError::Other(format!("Failed to serialize output: {}", e)))?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// A command output with a versioned JSON schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonSurface {
+    /// `jin status --json`
+    Status,
+    /// `jin log --json`
+    Log,
+    /// `jin diff --json`
+    Diff,
+    /// `jin layers --format json` (or `--json`)
+    Layers,
+    /// `jin list --json`
+    List,
+    /// `jin verify --format json`
+    Verify,
+    /// `jin audit export --format jsonl` (one object per line)
+    Audit,
+    /// `--profile=json` (printed to stderr)
+    Profile,
+    /// `--progress=json` (one event per line on stderr)
+    Progress,
+    /// `jin badge --format json`
+    Badge,
+    /// `jin version --json`
+    Version,
+}
+
+impl JsonSurface {
+    /// Every surface, in display order
+    pub fn all() -> [Self; 11] {
+        [
+            Self::Status,
+            Self::Log,
+            Self::Diff,
+            Self::Layers,
+            Self::List,
+            Self::Verify,
+            Self::Audit,
+            Self::Profile,
+            Self::Progress,
+            Self::Badge,
+            Self::Version,
+        ]
+    }
+
+    /// Name used in `jin schema <name>`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Status => "status",
+            Self::Log => "log",
+            Self::Diff => "diff",
+            Self::Layers => "layers",
+            Self::List => "list",
+            Self::Verify => "verify",
+            Self::Audit => "audit",
+            Self::Profile => "profile",
+            Self::Progress => "progress",
+            Self::Badge => "badge",
+            Self::Version => "version",
+        }
+    }
+
+    /// The command line that produces this output
+    pub fn command(&self) -> &'static str {
+        match self {
+            Self::Status => "jin status --json",
+            Self::Log => "jin log --json",
+            Self::Diff => "jin diff --json",
+            Self::Layers => "jin layers --format json",
+            Self::List => "jin list --json",
+            Self::Verify => "jin verify --format json",
+            Self::Audit => "jin audit export --format jsonl",
+            Self::Profile => "jin --profile=json <command>",
+            Self::Progress => "jin --progress=json <command>",
+            Self::Badge => "jin badge --format json",
+            Self::Version => "jin version --json --capabilities",
+        }
+    }
+
+    /// Current `schema_version` of this surface
+    pub fn version(&self) -> u32 {
+        match self {
+            Self::Status
+            | Self::Log
+            | Self::Diff
+            | Self::Layers
+            | Self::List
+            | Self::Verify
+            | Self::Audit
+            | Self::Profile
+            | Self::Progress
+            | Self::Badge
+            | Self::Version => 1,
+        }
+    }
+
+    /// JSON Schema (draft 2020-12) of the current version
+    pub fn schema(&self) -> Value {
+        let (description, properties, required) = match self {
+            Self::Status => status_schema(),
+            Self::Log => log_schema(),
+            Self::Diff => diff_schema(),
+            Self::Layers => layers_schema(),
+            Self::List => list_schema(),
+            Self::Verify => verify_schema(),
+            Self::Audit => audit_schema(),
+            Self::Profile => profile_schema(),
+            Self::Progress => progress_schema(),
+            Self::Badge => badge_schema(),
+            Self::Version => version_schema(),
+        };
+        let mut properties = properties;
+        properties
+            .as_object_mut()
+            .expect("schema properties are an object")
+            .insert(
+                "schema_version".to_string(),
+                json!({ "const": self.version() }),
+            );
+        let mut required = required;
+        required.insert(0, "schema_version");
+
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": self.command(),
+            "description": description,
+            "type": "object",
+            "required": required,
+            "properties": properties,
+        })
+    }
+}
+
+fn nullable(kind: &str) -> Value {
+    json!({ "type": [kind, "null"] })
+}
+
+fn string_array() -> Value {
+    json!({ "type": "array", "items": { "type": "string" } })
+}
+
+fn status_schema() -> (&'static str, Value, Vec<&'static str>) {
+    (
+        "Workspace state: active context and, per section shown, remote updates, drift, \
+         conflicts, queued applies, staged files and layer file counts",
+        json!({
+            "mode": nullable("string"),
+            "scope": nullable("string"),
+            "project": nullable("string"),
+            "branch_scope": nullable("string"),
+            "cached_at": { "type": "string", "format": "date-time" },
+            "remote": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["layer", "newer_commits"],
+                    "properties": {
+                        "layer": { "type": "string" },
+                        "newer_commits": { "type": "integer", "minimum": 1 }
+                    }
+                }
+            },
+            "workspace": {
+                "type": "object",
+                "required": ["clean", "modified", "deleted"],
+                "properties": {
+                    "clean": { "type": "boolean" },
+                    "modified": string_array(),
+                    "deleted": string_array()
+                }
+            },
+            "conflicts": string_array(),
+            "queue": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["state", "pid", "command"],
+                    "properties": {
+                        "state": { "enum": ["running", "waiting"] },
+                        "pid": { "type": "integer" },
+                        "command": { "type": "string" }
+                    }
+                }
+            },
+            "staged": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["path", "layer", "owners"],
+                    "properties": {
+                        "path": { "type": "string" },
+                        "layer": { "type": "string" },
+                        "owners": string_array()
+                    }
+                }
+            },
+            "layers": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["layer", "ref_path", "storage", "files", "staged"],
+                    "properties": {
+                        "layer": { "type": "string" },
+                        "ref_path": { "type": "string" },
+                        "storage": { "type": "string" },
+                        "files": { "type": "integer", "minimum": 0 },
+                        "staged": { "type": "integer", "minimum": 0 }
+                    }
+                }
+            }
+        }),
+        vec!["mode", "scope", "project", "branch_scope"],
+    )
+}
+
+fn log_schema() -> (&'static str, Value, Vec<&'static str>) {
+    (
+        "Layer commits, newest first within each layer, layers in precedence order",
+        json!({
+            "commits": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": [
+                        "layer", "ref", "commit", "author", "email", "date", "message",
+                        "files_changed", "notes"
+                    ],
+                    "properties": {
+                        "layer": { "type": "string" },
+                        "ref": { "type": "string" },
+                        "commit": { "type": "string" },
+                        "author": { "type": "string" },
+                        "email": { "type": "string" },
+                        "date": { "type": "string", "format": "date-time" },
+                        "message": { "type": "string" },
+                        "files_changed": { "type": "integer", "minimum": 0 },
+                        "notes": string_array()
+                    }
+                }
+            }
+        }),
+        vec!["commits"],
+    )
+}
+
+fn diff_schema() -> (&'static str, Value, Vec<&'static str>) {
+    (
+        "Files that differ between two sides (layers, workspace-active, workspace or staging)",
+        json!({
+            "from": { "type": "string" },
+            "to": { "type": "string" },
+            "changed": { "type": "boolean" },
+            "files": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["path", "status"],
+                    "properties": {
+                        "path": { "type": "string" },
+                        "status": { "enum": ["modified", "added", "deleted", "renamed"] },
+                        "layer": { "type": "string" },
+                        "patch": { "type": "string" }
+                    }
+                }
+            }
+        }),
+        vec!["from", "to", "changed", "files"],
+    )
+}
+
+fn list_schema() -> (&'static str, Value, Vec<&'static str>) {
+    (
+        "Modes, scopes and projects in the Jin repository, or what was asked for: \
+         names of one kind, files per layer or stale modes and scopes",
+        json!({
+            "modes": string_array(),
+            "scopes": string_array(),
+            "projects": string_array(),
+            "archived": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["kind", "name"],
+                    "properties": {
+                        "kind": { "enum": ["mode", "scope"] },
+                        "name": { "type": "string" }
+                    }
+                }
+            },
+            "files": {
+                "type": "object",
+                "additionalProperties": string_array()
+            },
+            "stale": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["kind", "name", "created", "last_used"],
+                    "properties": {
+                        "kind": { "enum": ["mode", "scope"] },
+                        "name": { "type": "string" },
+                        "created": { "type": ["string", "null"], "format": "date-time" },
+                        "last_used": { "type": ["string", "null"], "format": "date-time" }
+                    }
+                }
+            }
+        }),
+        Vec::new(),
+    )
+}
+
+fn layers_schema() -> (&'static str, Value, Vec<&'static str>) {
+    (
+        "Resolved layer stack for the active (or previewed) context, in merge order",
+        json!({
+            "mode": nullable("string"),
+            "scope": nullable("string"),
+            "project": nullable("string"),
+            "preview": { "type": "boolean" },
+            "cached_at": { "type": "string", "format": "date-time" },
+            "layers": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["precedence", "layer", "ref", "storage", "exists", "files"],
+                    "properties": {
+                        "precedence": { "type": "integer", "minimum": 1, "maximum": 9 },
+                        "layer": { "type": "string" },
+                        "ref": { "type": "string" },
+                        "storage": { "type": "string" },
+                        "exists": { "type": "boolean" },
+                        "files": { "type": "integer", "minimum": 0 }
+                    }
+                }
+            }
+        }),
+        vec!["mode", "scope", "project", "preview", "layers"],
+    )
+}
+
+fn verify_schema() -> (&'static str, Value, Vec<&'static str>) {
+    (
+        "Integrity report for layer objects and applied workspace files",
+        json!({
+            "layers_checked": { "type": "integer", "minimum": 0 },
+            "blobs_checked": { "type": "integer", "minimum": 0 },
+            "workspace_files_checked": { "type": "integer", "minimum": 0 },
+            "issues": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["kind"],
+                    "properties": {
+                        "kind": {
+                            "enum": [
+                                "hash-mismatch",
+                                "unreadable-object",
+                                "missing-layer",
+                                "missing-file",
+                                "unrecorded-file",
+                                "workspace-modified",
+                                "workspace-missing"
+                            ]
+                        },
+                        "layer": { "type": "string" },
+                        "path": { "type": "string" },
+                        "expected": { "type": "string" },
+                        "actual": { "type": "string" }
+                    }
+                }
+            }
+        }),
+        vec![
+            "layers_checked",
+            "blobs_checked",
+            "workspace_files_checked",
+            "issues",
+        ],
+    )
+}
+
+fn audit_schema() -> (&'static str, Value, Vec<&'static str>) {
+    (
+        "One audit record: a layer commit, an apply, an automatic conflict resolution or a repair",
+        json!({
+            "timestamp": { "type": "string", "format": "date-time" },
+            "user": { "type": "string" },
+            "project": nullable("string"),
+            "mode": nullable("string"),
+            "scope": nullable("string"),
+            "layer": { "type": ["integer", "null"], "minimum": 1, "maximum": 9 },
+            "layer_name": nullable("string"),
+            "layer_ref": nullable("string"),
+            "commit": nullable("string"),
+            "base_commit": nullable("string"),
+            "files": { "type": "array", "items": { "type": "string" } },
+            "resolution": nullable("string"),
+            "repair": nullable("string"),
+            "machine": nullable("string"),
+            "apply": nullable("string")
+        }),
+        vec![
+            "timestamp",
+            "user",
+            "project",
+            "mode",
+            "scope",
+            "layer",
+            "layer_name",
+            "layer_ref",
+            "commit",
+            "base_commit",
+            "files",
+            "resolution",
+            "repair",
+            "machine",
+            "apply",
+        ],
+    )
+}
+
+fn profile_schema() -> (&'static str, Value, Vec<&'static str>) {
+    (
+        "Time spent per phase of a command",
+        json!({
+            "total_ms": { "type": "number", "minimum": 0 },
+            "phases": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["phase", "calls", "total_ms"],
+                    "properties": {
+                        "phase": {
+                            "enum": ["repo_open", "tree_walk", "parse", "merge", "serialize", "io"]
+                        },
+                        "calls": { "type": "integer", "minimum": 0 },
+                        "total_ms": { "type": "number", "minimum": 0 }
+                    }
+                }
+            }
+        }),
+        vec!["total_ms", "phases"],
+    )
+}
+
+fn progress_schema() -> (&'static str, Value, Vec<&'static str>) {
+    (
+        "A progress event of a long-running operation, or a warning, error or \
+         wait message (no operation), one per line",
+        json!({
+            "operation": { "enum": ["fetch", "push", "apply"] },
+            "phase": {
+                "enum": [
+                    "receive", "index", "send", "merge", "write", "done", "warning", "error", "wait"
+                ]
+            },
+            "current": { "type": "integer", "minimum": 0 },
+            "total": { "type": "integer", "minimum": 0 },
+            "path": { "type": "string" },
+            "remote": { "type": "string" },
+            "message": { "type": "string" }
+        }),
+        vec!["phase"],
+    )
+}
+
+fn badge_schema() -> (&'static str, Value, Vec<&'static str>) {
+    (
+        "Jin state of workspace paths, for editor plugins",
+        json!({
+            "badges": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["path", "managed", "staged", "drifted", "conflicted", "layers"],
+                    "properties": {
+                        "path": { "type": "string" },
+                        "managed": { "type": "boolean" },
+                        "staged": nullable("string"),
+                        "drifted": { "type": "boolean" },
+                        "conflicted": { "type": "boolean" },
+                        "layers": { "type": "array", "items": { "type": "string" } }
+                    }
+                }
+            }
+        }),
+        vec!["badges"],
+    )
+}
+
+fn version_schema() -> (&'static str, Value, Vec<&'static str>) {
+    (
+        "Installed Jin version and, with --capabilities, what it supports",
+        json!({
+            "version": { "type": "string" },
+            "repo_version": { "type": "integer", "minimum": 1 },
+            "capabilities": {
+                "type": "object",
+                "required": ["formats", "schemas", "features"],
+                "properties": {
+                    "formats": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "required": ["name", "extensions"],
+                            "properties": {
+                                "name": { "type": "string" },
+                                "extensions": { "type": "array", "items": { "type": "string" } }
+                            }
+                        }
+                    },
+                    "schemas": {
+                        "type": "object",
+                        "additionalProperties": { "type": "integer", "minimum": 1 }
+                    },
+                    "features": {
+                        "type": "object",
+                        "additionalProperties": { "type": "boolean" }
+                    }
+                }
+            }
+        }),
+        vec!["version", "repo_version"],
+    )
+}
+
+/// Check that `value` has exactly the shape `schema` describes
+///
+/// A small subset of JSON Schema (`type`, `const`, `enum`, `required`,
+/// `properties`, `items`), enough to keep the hand-written schemas in sync
+/// with the structs they describe. Unlike consumers, it rejects properties
+/// the schema doesn't list.
+#[cfg(test)]
+pub fn assert_matches(schema: &Value, value: &Value, path: &str) {
+    if let Some(expected) = schema.get("const") {
+        assert_eq!(value, expected, "{}: const mismatch", path);
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        assert!(options.contains(value), "{}: {} not in enum", path, value);
+    }
+    if let Some(kinds) = schema.get("type") {
+        let kinds: Vec<&str> = match kinds {
+            Value::String(kind) => vec![kind.as_str()],
+            Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        let actual = match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(n) if n.is_u64() || n.is_i64() => "integer",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        };
+        let ok = kinds.contains(&actual) || (actual == "integer" && kinds.contains(&"number"));
+        assert!(ok, "{}: expected {:?}, got {}", path, kinds, actual);
+    }
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        let object = value.as_object().expect("schema object");
+        for key in object.keys() {
+            assert!(
+                properties.contains_key(key),
+                "{}.{}: not in schema",
+                path,
+                key
+            );
+        }
+        for key in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            assert!(object.contains_key(key), "{}.{}: missing", path, key);
+        }
+        for (key, property) in properties {
+            if let Some(field) = object.get(key) {
+                assert_matches(property, field, &format!("{}.{}", path, key));
+            }
+        }
+    }
+    if let Some(items) = schema.get("items") {
+        for (i, item) in value.as_array().expect("schema array").iter().enumerate() {
+            assert_matches(items, item, &format!("{}[{}]", path, i));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_schema_requires_its_version() {
+        for surface in JsonSurface::all() {
+            let schema = surface.schema();
+            assert_eq!(schema["required"][0], "schema_version");
+            assert_eq!(
+                schema["properties"]["schema_version"]["const"],
+                surface.version()
+            );
+            // Every required field is described
+            for key in schema["required"].as_array().unwrap() {
+                assert!(schema["properties"].get(key.as_str().unwrap()).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn test_assert_matches_rejects_unknown_fields() {
+        let schema = JsonSurface::Profile.schema();
+        let ok = json!({ "schema_version": 1, "total_ms": 2.5, "phases": [] });
+        assert_matches(&schema, &ok, "profile");
+
+        let extra = json!({ "schema_version": 1, "total_ms": 2, "phases": [], "x": 1 });
+        let result = std::panic::catch_unwind(|| assert_matches(&schema, &extra, "profile"));
+        assert!(result.is_err());
+    }
+}
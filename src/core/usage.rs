@@ -0,0 +1,283 @@
+//! Mode and scope usage tracking
+//!
+//! Jin records when each mode and scope was created and last used (activated
+//! or applied) in `jin/usage.yaml` inside the Jin repository. `jin list
+//! --stale` and `jin prune` use these timestamps to find contexts that were
+//! never used or have been abandoned.
+//!
+//! Usage tracking is advisory: a missing or unreadable log simply means no
+//! timestamps are known.
+
+use crate::core::progress;
+use crate::core::{JinError, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Kind of context tracked in the usage log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageKind {
+    /// A mode
+    Mode,
+    /// A scope
+    Scope,
+}
+
+impl UsageKind {
+    /// Lowercase name for display
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UsageKind::Mode => "mode",
+            UsageKind::Scope => "scope",
+        }
+    }
+}
+
+/// Timestamps for a single mode or scope
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    /// When the context was created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<DateTime<Utc>>,
+    /// When the context was last activated or applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+impl UsageRecord {
+    /// Most recent activity (last use, or creation if never used)
+    pub fn last_activity(&self) -> Option<DateTime<Utc>> {
+        self.last_used.or(self.created)
+    }
+}
+
+/// A mode or scope with no recent activity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleContext {
+    /// Whether this is a mode or a scope
+    pub kind: UsageKind,
+    /// Mode or scope name
+    pub name: String,
+    /// Recorded timestamps (default if none were recorded)
+    pub record: UsageRecord,
+}
+
+impl StaleContext {
+    /// Short human-readable description (e.g., `last used 120 days ago`)
+    pub fn describe(&self, now: DateTime<Utc>) -> String {
+        match (self.record.last_used, self.record.created) {
+            (Some(used), _) => format!("last used {} days ago", (now - used).num_days()),
+            (None, Some(created)) => {
+                format!(
+                    "never used (created {} days ago)",
+                    (now - created).num_days()
+                )
+            }
+            (None, None) => "never used".to_string(),
+        }
+    }
+}
+
+/// Usage timestamps for all modes and scopes in a Jin repository
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageLog {
+    /// Mode name -> timestamps
+    #[serde(default)]
+    pub modes: BTreeMap<String, UsageRecord>,
+    /// Scope name -> timestamps
+    #[serde(default)]
+    pub scopes: BTreeMap<String, UsageRecord>,
+}
+
+impl UsageLog {
+    /// Path of the usage log inside a Jin repository
+    pub fn path(repo_path: &Path) -> PathBuf {
+        repo_path.join("jin").join("usage.yaml")
+    }
+
+    /// Load the usage log (empty if it doesn't exist yet)
+    pub fn load(repo_path: &Path) -> Result<Self> {
+        let path = Self::path(repo_path);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_yaml::from_str(&content).map_err(|e| JinError::Parse {
+            format: "YAML".to_string(),
+            message: format!("{}: {}", path.display(), e),
+        })
+    }
+
+    /// Save the usage log (atomic write)
+    pub fn save(&self, repo_path: &Path) -> Result<()> {
+        let path = Self::path(repo_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_yaml::to_string(self).map_err(|e| JinError::Parse {
+            format: "YAML".to_string(),
+            message: e.to_string(),
+        })?;
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, content)?;
+        std::fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+
+    fn records_mut(&mut self, kind: UsageKind) -> &mut BTreeMap<String, UsageRecord> {
+        match kind {
+            UsageKind::Mode => &mut self.modes,
+            UsageKind::Scope => &mut self.scopes,
+        }
+    }
+
+    /// Timestamps recorded for a context, if any
+    pub fn get(&self, kind: UsageKind, name: &str) -> Option<&UsageRecord> {
+        match kind {
+            UsageKind::Mode => self.modes.get(name),
+            UsageKind::Scope => self.scopes.get(name),
+        }
+    }
+
+    /// Record that a context was created
+    pub fn mark_created(&mut self, kind: UsageKind, name: &str, at: DateTime<Utc>) {
+        self.records_mut(kind)
+            .entry(name.to_string())
+            .or_default()
+            .created = Some(at);
+    }
+
+    /// Record that a context was activated or applied
+    pub fn mark_used(&mut self, kind: UsageKind, name: &str, at: DateTime<Utc>) {
+        self.records_mut(kind)
+            .entry(name.to_string())
+            .or_default()
+            .last_used = Some(at);
+    }
+
+    /// Forget a deleted context
+    pub fn remove(&mut self, kind: UsageKind, name: &str) {
+        self.records_mut(kind).remove(name);
+    }
+
+    /// Contexts among `names` with no activity since `cutoff`
+    ///
+    /// Contexts without any recorded timestamp are considered stale.
+    pub fn stale<'a>(
+        &self,
+        kind: UsageKind,
+        names: impl IntoIterator<Item = &'a String>,
+        cutoff: DateTime<Utc>,
+    ) -> Vec<StaleContext> {
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let record = self.get(kind, name).cloned().unwrap_or_default();
+                match record.last_activity() {
+                    Some(at) if at >= cutoff => None,
+                    _ => Some(StaleContext {
+                        kind,
+                        name: name.clone(),
+                        record,
+                    }),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Update the usage log of a repository, warning instead of failing
+///
+/// Usage tracking must never break the command that triggered it.
+pub fn record(repo_path: &Path, update: impl FnOnce(&mut UsageLog)) {
+    let result = UsageLog::load(repo_path).and_then(|mut log| {
+        update(&mut log);
+        log.save(repo_path)
+    });
+    if let Err(e) = result {
+        progress::warn(format_args!("Could not update usage log: {}", e));
+    }
+}
+
+/// Parse an age such as `90d`, `12w` or `36h`
+pub fn parse_age(value: &str) -> Result<Duration> {
+    let trimmed = value.trim();
+    let invalid = || {
+        JinError::Other(format!(
+            "Invalid age: '{}'. Use a number followed by h, d or w (e.g., 90d)",
+            trimmed
+        ))
+    };
+
+    let unit = trimmed.chars().last().ok_or_else(invalid)?;
+    let number = &trimmed[..trimmed.len() - unit.len_utf8()];
+    let amount: i64 = number.parse().map_err(|_| invalid())?;
+    if amount < 0 {
+        return Err(invalid());
+    }
+    match unit {
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        'w' => Duration::try_weeks(amount),
+        _ => None,
+    }
+    .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("90d").unwrap(), Duration::days(90));
+        assert_eq!(parse_age("2w").unwrap(), Duration::weeks(2));
+        assert_eq!(parse_age("36h").unwrap(), Duration::hours(36));
+        assert!(parse_age("90").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("-1d").is_err());
+        assert!(parse_age("3m").is_err());
+    }
+
+    #[test]
+    fn test_stale_detection() {
+        let now = Utc::now();
+        let mut log = UsageLog::default();
+        log.mark_created(UsageKind::Mode, "fresh", now - Duration::days(3));
+        log.mark_created(UsageKind::Mode, "old", now - Duration::days(200));
+        log.mark_used(UsageKind::Mode, "active", now - Duration::days(1));
+        log.mark_created(UsageKind::Mode, "abandoned", now - Duration::days(300));
+        log.mark_used(UsageKind::Mode, "abandoned", now - Duration::days(120));
+
+        let names: Vec<String> = ["abandoned", "active", "fresh", "old", "untracked"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let stale = log.stale(UsageKind::Mode, &names, now - Duration::days(90));
+        let stale_names: Vec<&str> = stale.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(stale_names, vec!["abandoned", "old", "untracked"]);
+
+        assert_eq!(stale[0].describe(now), "last used 120 days ago");
+        assert_eq!(stale[1].describe(now), "never used (created 200 days ago)");
+        assert_eq!(stale[2].describe(now), "never used");
+    }
+
+    #[test]
+    fn test_load_save_round_trip() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(UsageLog::load(temp.path()).unwrap(), UsageLog::default());
+
+        record(temp.path(), |log| {
+            log.mark_used(UsageKind::Scope, "language:rust", Utc::now())
+        });
+        let log = UsageLog::load(temp.path()).unwrap();
+        assert!(log.get(UsageKind::Scope, "language:rust").is_some());
+
+        record(temp.path(), |log| {
+            log.remove(UsageKind::Scope, "language:rust")
+        });
+        assert!(UsageLog::load(temp.path()).unwrap().scopes.is_empty());
+    }
+}
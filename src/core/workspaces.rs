@@ -0,0 +1,164 @@
+//! Workspaces known to a Jin repository
+//!
+//! Jin records every workspace root initialized or applied against a
+//! repository in `jin/workspaces.yaml` inside that repository, together with
+//! the project name and the time of the last apply. `jin projects` lists
+//! them, which helps find stale entries after moving to another machine.
+//!
+//! Like usage tracking, the registry is advisory: a missing or unreadable
+//! file simply means no workspaces are known.
+
+use crate::core::progress;
+use crate::core::{JinError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// What is known about one workspace
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceRecord {
+    /// Project name active at the last init or apply
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// When the workspace was initialized
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initialized: Option<DateTime<Utc>>,
+    /// When layers were last applied to the workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_apply: Option<DateTime<Utc>>,
+}
+
+/// Workspace roots (absolute paths) known to a Jin repository
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceRegistry {
+    /// Workspace root -> record
+    #[serde(default)]
+    pub workspaces: BTreeMap<String, WorkspaceRecord>,
+}
+
+impl WorkspaceRegistry {
+    /// Path of the registry inside a Jin repository
+    pub fn path(repo_path: &Path) -> PathBuf {
+        repo_path.join("jin").join("workspaces.yaml")
+    }
+
+    /// Load the registry (empty if it doesn't exist yet)
+    pub fn load(repo_path: &Path) -> Result<Self> {
+        let path = Self::path(repo_path);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_yaml::from_str(&content).map_err(|e| JinError::Parse {
+            format: "YAML".to_string(),
+            message: format!("{}: {}", path.display(), e),
+        })
+    }
+
+    /// Save the registry (atomic write)
+    pub fn save(&self, repo_path: &Path) -> Result<()> {
+        let path = Self::path(repo_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_yaml::to_string(self).map_err(|e| JinError::Parse {
+            format: "YAML".to_string(),
+            message: e.to_string(),
+        })?;
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, content)?;
+        std::fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+
+    fn entry(&mut self, root: &Path, project: Option<&str>) -> &mut WorkspaceRecord {
+        let record = self
+            .workspaces
+            .entry(root.display().to_string())
+            .or_default();
+        if project.is_some() {
+            record.project = project.map(str::to_string);
+        }
+        record
+    }
+
+    /// Record that a workspace was initialized
+    pub fn mark_initialized(&mut self, root: &Path, project: Option<&str>, at: DateTime<Utc>) {
+        self.entry(root, project).initialized.get_or_insert(at);
+    }
+
+    /// Record that layers were applied to a workspace
+    pub fn mark_applied(&mut self, root: &Path, project: Option<&str>, at: DateTime<Utc>) {
+        self.entry(root, project).last_apply = Some(at);
+    }
+
+    /// Forget workspaces whose root no longer exists, returning them
+    pub fn forget_missing(&mut self) -> Vec<String> {
+        let missing: Vec<String> = self
+            .workspaces
+            .keys()
+            .filter(|root| !Path::new(root).exists())
+            .cloned()
+            .collect();
+        for root in &missing {
+            self.workspaces.remove(root);
+        }
+        missing
+    }
+}
+
+/// Update the registry for the workspace at `root`, warning instead of
+/// failing
+///
+/// Workspace tracking must never break the command that triggered it.
+pub fn record(repo_path: &Path, root: &Path, update: impl FnOnce(&mut WorkspaceRegistry, &Path)) {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let result = WorkspaceRegistry::load(repo_path).and_then(|mut registry| {
+        update(&mut registry, &root);
+        registry.save(repo_path)
+    });
+    if let Err(e) = result {
+        progress::warn(format_args!("Could not update workspace registry: {}", e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_registry_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let now = Utc::now();
+        let root = temp.path().join("app");
+        std::fs::create_dir_all(&root).unwrap();
+
+        let mut registry = WorkspaceRegistry::default();
+        registry.mark_initialized(&root, None, now);
+        registry.mark_applied(&root, Some("app"), now);
+        registry.mark_initialized(&root, None, now + chrono::Duration::days(1));
+        registry.mark_applied(Path::new("/nonexistent/old-machine"), Some("old"), now);
+        registry.save(temp.path()).unwrap();
+
+        let mut loaded = WorkspaceRegistry::load(temp.path()).unwrap();
+        assert_eq!(loaded, registry);
+        let record = &loaded.workspaces[&root.display().to_string()];
+        assert_eq!(record.project.as_deref(), Some("app"));
+        assert_eq!(record.initialized, Some(now));
+        assert_eq!(record.last_apply, Some(now));
+
+        assert_eq!(loaded.forget_missing(), ["/nonexistent/old-machine"]);
+        assert_eq!(loaded.workspaces.len(), 1);
+    }
+
+    #[test]
+    fn test_missing_registry_is_empty() {
+        let temp = TempDir::new().unwrap();
+        assert!(WorkspaceRegistry::load(temp.path())
+            .unwrap()
+            .workspaces
+            .is_empty());
+    }
+}
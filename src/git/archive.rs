@@ -0,0 +1,255 @@
+//! Archived (read-only) modes and scopes
+//!
+//! `jin archive mode <name>` freezes a mode, say an ex-teammate's setup, so
+//! it is preserved without accidental edits: commits to any of its layers
+//! are refused, and list output shows it apart from live modes. An archived
+//! mode or scope still takes part in merges unless it was archived with
+//! `--no-merge`.
+//!
+//! Like locks, the archive list is a single JSON file committed to
+//! `refs/jin/archive`, outside `refs/jin/layers/*`. `jin archive` and
+//! `jin unarchive` push it straight to the team remote (fast-forward only)
+//! and `jin fetch` refreshes it; commits check the last fetched copy.
+
+use crate::core::progress;
+use crate::core::{JinError, Layer, Result};
+use crate::git::executor::interrupted;
+use crate::git::remote::{build_push_options, remote_error, setup_callbacks};
+use crate::git::retry::with_retry;
+use crate::git::{JinRepo, ObjectOps, RefOps, TreeOps};
+use chrono::{DateTime, Utc};
+use git2::{FetchOptions, Oid, Remote, RemoteCallbacks};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Ref holding the archive list
+pub const ARCHIVE_REF: &str = "refs/jin/archive";
+
+/// Refspec mirroring the remote archive list
+const ARCHIVE_REFSPEC: &str = "+refs/jin/archive:refs/jin/archive";
+
+/// File in the archive commit's tree holding the entries
+const ARCHIVE_FILE: &str = "archive.json";
+
+/// What an archive entry freezes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveKind {
+    /// All layers of a mode
+    Mode,
+    /// All layers of a scope
+    Scope,
+}
+
+impl std::fmt::Display for ArchiveKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mode => write!(f, "mode"),
+            Self::Scope => write!(f, "scope"),
+        }
+    }
+}
+
+/// An archived mode or scope
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedContext {
+    /// Mode or scope
+    pub kind: ArchiveKind,
+    /// Mode or scope name (scopes as written, e.g. `lang:rust`)
+    pub name: String,
+    /// Who archived it
+    pub archived_by: String,
+    /// When it was archived
+    pub archived_at: DateTime<Utc>,
+    /// Whether its layers still take part in merges
+    pub merge: bool,
+    /// Why it was archived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// The archive list at `refs/jin/archive`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveSet {
+    /// Entries, oldest first
+    pub archived: Vec<ArchivedContext>,
+}
+
+impl ArchiveSet {
+    /// Load the local copy of the archive list (empty if there is none)
+    pub fn load(repo: &JinRepo) -> Result<Self> {
+        if !repo.ref_exists(ARCHIVE_REF) {
+            return Ok(Self::default());
+        }
+        let tree_oid = repo.find_commit(repo.resolve_ref(ARCHIVE_REF)?)?.tree_id();
+        let content = repo.read_file_from_tree(tree_oid, Path::new(ARCHIVE_FILE))?;
+        serde_json::from_slice(&content).map_err(|e| JinError::Parse {
+            format: "archive".to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Load the archive list, warning and treating it as empty on error
+    pub fn load_or_default(repo: &JinRepo) -> Self {
+        Self::load(repo).unwrap_or_else(|e| {
+            progress::warn(format_args!(
+                "Could not read archived modes and scopes: {}",
+                e
+            ));
+            Self::default()
+        })
+    }
+
+    /// Commit the archive list on top of the current `refs/jin/archive`
+    pub fn save(&self, repo: &JinRepo, message: &str) -> Result<Oid> {
+        let content = serde_json::to_vec_pretty(self).map_err(|e| JinError::Parse {
+            format: "archive".to_string(),
+            message: e.to_string(),
+        })?;
+        let blob = repo.create_blob(&content)?;
+        let tree = repo.create_tree_from_paths(&[(ARCHIVE_FILE.to_string(), blob)])?;
+        let parents: Vec<Oid> = repo.resolve_ref(ARCHIVE_REF).into_iter().collect();
+        let commit = repo.create_commit(None, message, tree, &parents)?;
+        repo.set_ref(ARCHIVE_REF, commit, message)?;
+        Ok(commit)
+    }
+
+    /// Index of the entry for `name`
+    pub fn position(&self, kind: ArchiveKind, name: &str) -> Option<usize> {
+        self.archived
+            .iter()
+            .position(|entry| entry.kind == kind && entry.name == name)
+    }
+
+    /// Whether `name` is archived
+    pub fn contains(&self, kind: ArchiveKind, name: &str) -> bool {
+        self.position(kind, name).is_some()
+    }
+
+    /// Entry freezing `layer` in the given context, if any
+    pub fn covering(
+        &self,
+        layer: Layer,
+        mode: Option<&str>,
+        scope: Option<&str>,
+    ) -> Option<&ArchivedContext> {
+        let mode = mode.filter(|_| layer.requires_mode());
+        let scope = scope.filter(|_| layer.requires_scope());
+        self.archived.iter().find(|entry| match entry.kind {
+            ArchiveKind::Mode => mode == Some(entry.name.as_str()),
+            ArchiveKind::Scope => scope == Some(entry.name.as_str()),
+        })
+    }
+
+    /// Whether `layer` is left out of merges in the given context
+    pub fn excludes(&self, layer: Layer, mode: Option<&str>, scope: Option<&str>) -> bool {
+        let mode = mode.filter(|_| layer.requires_mode());
+        let scope = scope.filter(|_| layer.requires_scope());
+        self.archived.iter().any(|entry| {
+            !entry.merge
+                && match entry.kind {
+                    ArchiveKind::Mode => mode == Some(entry.name.as_str()),
+                    ArchiveKind::Scope => scope == Some(entry.name.as_str()),
+                }
+        })
+    }
+}
+
+/// Update the local archive list from the remote
+pub fn fetch(remote: &mut Remote<'_>) -> Result<()> {
+    let mut callbacks = RemoteCallbacks::new();
+    setup_callbacks(&mut callbacks);
+    callbacks.transfer_progress(|_| !interrupted());
+    let mut opts = FetchOptions::new();
+    opts.remote_callbacks(callbacks);
+    opts.update_fetchhead(false);
+    opts.download_tags(git2::AutotagOption::None);
+
+    with_retry("Fetch archived modes and scopes", || {
+        remote
+            .fetch(&[ARCHIVE_REFSPEC], Some(&mut opts), None)
+            .map_err(remote_error)
+    })
+}
+
+/// Publish the local archive list (fast-forward only)
+pub fn push(remote: &mut Remote<'_>) -> Result<()> {
+    let mut opts = build_push_options()?;
+    let refspec = format!("{}:{}", ARCHIVE_REF, ARCHIVE_REF);
+    remote
+        .push(&[refspec.as_str()], Some(&mut opts))
+        .map_err(|e| {
+            if e.message().contains("fast-forward") || e.message().contains("fetch first") {
+                JinError::Other(
+                    "The archive list changed on the remote while updating it. \
+                     Run the command again."
+                        .into(),
+                )
+            } else {
+                e.into()
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_unit_test;
+    use serial_test::serial;
+
+    fn entry(kind: ArchiveKind, name: &str, merge: bool) -> ArchivedContext {
+        ArchivedContext {
+            kind,
+            name: name.to_string(),
+            archived_by: "alice".to_string(),
+            archived_at: Utc::now(),
+            merge,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_covering_matches_layers_of_archived_context() {
+        let set = ArchiveSet {
+            archived: vec![
+                entry(ArchiveKind::Mode, "bob", true),
+                entry(ArchiveKind::Scope, "lang:go", false),
+            ],
+        };
+        let bob = Some("bob");
+        let go = Some("lang:go");
+
+        assert!(set.covering(Layer::ModeBase, bob, None).is_some());
+        assert!(set.covering(Layer::ModeProject, bob, None).is_some());
+        assert!(set.covering(Layer::GlobalBase, bob, None).is_none());
+        assert!(set.covering(Layer::ProjectBase, bob, go).is_none());
+        assert!(set.covering(Layer::ScopeBase, None, go).is_some());
+        assert!(set.covering(Layer::ModeBase, Some("claude"), go).is_none());
+
+        assert!(!set.excludes(Layer::ModeBase, bob, None));
+        assert!(set.excludes(Layer::ModeScope, Some("claude"), go));
+    }
+
+    #[test]
+    #[serial]
+    fn test_archive_set_round_trip() {
+        let ctx = setup_unit_test();
+        let repo = JinRepo::open_or_create(&ctx.project_path).unwrap();
+        assert!(ArchiveSet::load(&repo).unwrap().archived.is_empty());
+
+        let mut set = ArchiveSet::default();
+        set.archived.push(entry(ArchiveKind::Mode, "bob", true));
+        let first = set.save(&repo, "Archive mode bob").unwrap();
+        assert!(ArchiveSet::load(&repo)
+            .unwrap()
+            .contains(ArchiveKind::Mode, "bob"));
+
+        set.archived.clear();
+        let second = set.save(&repo, "Unarchive mode bob").unwrap();
+        assert!(ArchiveSet::load(&repo).unwrap().archived.is_empty());
+        assert_eq!(
+            repo.find_commit(second).unwrap().parent_id(0).unwrap(),
+            first
+        );
+    }
+}
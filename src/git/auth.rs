@@ -0,0 +1,420 @@
+//! Credentials for remote operations
+//!
+//! Jin never stores secrets. Credentials come from the places git already
+//! uses:
+//! - `ssh-agent`, a configured key file (`jin link --ssh-key`) and the
+//!   default keys in `~/.ssh` for SSH remotes; a passphrase-protected key
+//!   is unlocked with `$JIN_SSH_PASSPHRASE`
+//! - git credential helpers (`credential.helper` in the git config) for
+//!   HTTPS remotes
+//! - a token read from an environment variable (`JIN_TOKEN` unless
+//!   configured otherwise), or from a file (`--token-file`, e.g. one kept
+//!   by a secret manager) when the variable is unset, for CI and private
+//!   GitHub/GitLab repositories
+//!
+//! `jin link <url> --auth <method>` restricts authentication to one source;
+//! the default, `auto`, tries each source the remote accepts in turn. Only
+//! the method, the *name* of the token variable and the key and token file
+//! *paths* are saved in the config.
+
+use super::remote::AuthCounter;
+use crate::core::config::expand_home;
+use crate::core::{JinConfig, JinError, RemoteConfig, Result};
+use git2::{Cred, CredentialType, ErrorClass, ErrorCode, RemoteCallbacks};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Environment variable holding the access token unless configured
+/// otherwise
+pub const DEFAULT_TOKEN_ENV: &str = "JIN_TOKEN";
+
+/// Environment variable holding the passphrase of SSH key files
+pub const SSH_PASSPHRASE_ENV: &str = "JIN_SSH_PASSPHRASE";
+
+/// Where credentials for the remote come from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuthMethod {
+    /// Try every source the remote accepts
+    #[default]
+    Auto,
+    /// Keys loaded in `ssh-agent` only
+    SshAgent,
+    /// SSH key files only: the configured key, or the default ones
+    SshKey,
+    /// git credential helpers only
+    Helper,
+    /// Token from the environment only
+    Token,
+}
+
+impl AuthMethod {
+    /// Whether this is the default method (used to keep it out of the config)
+    pub fn is_auto(&self) -> bool {
+        *self == Self::Auto
+    }
+}
+
+impl std::str::FromStr for AuthMethod {
+    type Err = JinError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "auto" => Ok(Self::Auto),
+            "ssh-agent" => Ok(Self::SshAgent),
+            "ssh-key" => Ok(Self::SshKey),
+            "helper" => Ok(Self::Helper),
+            "token" => Ok(Self::Token),
+            _ => Err(JinError::Config(format!(
+                "Invalid auth method: {}. Use 'auto', 'ssh-agent', 'ssh-key', 'helper' or 'token'",
+                value
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for AuthMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::SshAgent => write!(f, "ssh-agent"),
+            Self::SshKey => write!(f, "ssh-key"),
+            Self::Helper => write!(f, "helper"),
+            Self::Token => write!(f, "token"),
+        }
+    }
+}
+
+/// Authentication settings for a remote
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemoteAuth {
+    /// Credential sources to use
+    pub method: AuthMethod,
+    /// Variable holding the token (default [`DEFAULT_TOKEN_ENV`])
+    pub token_env: Option<String>,
+    /// SSH private key to try before the default keys
+    pub ssh_key: Option<PathBuf>,
+    /// File holding the token, read when the variable is unset
+    pub token_file: Option<PathBuf>,
+}
+
+impl RemoteAuth {
+    /// Settings of the linked remote, loaded once per process
+    pub fn current() -> &'static Self {
+        static AUTH: OnceLock<RemoteAuth> = OnceLock::new();
+        AUTH.get_or_init(|| {
+            JinConfig::load()
+                .ok()
+                .and_then(|c| c.remote)
+                .map(|r| Self::of(&r))
+                .unwrap_or_default()
+        })
+    }
+
+    /// Settings of a configured remote
+    pub fn of(remote: &RemoteConfig) -> Self {
+        RemoteAuth {
+            method: remote.auth,
+            token_env: remote.token_env.clone(),
+            ssh_key: remote.ssh_key.as_deref().map(expand_home),
+            token_file: remote.token_file.as_deref().map(expand_home),
+        }
+    }
+
+    /// Settings of the remote named `name`
+    pub fn named(name: &str) -> Self {
+        if name == "origin" {
+            return Self::current().clone();
+        }
+        JinConfig::load()
+            .ok()
+            .and_then(|c| c.remotes.get(name).map(Self::of))
+            .unwrap_or_default()
+    }
+
+    /// Name of the token variable
+    pub fn token_env(&self) -> &str {
+        self.token_env.as_deref().unwrap_or(DEFAULT_TOKEN_ENV)
+    }
+
+    /// The token: the variable's value, else the token file's first line
+    pub fn token(&self) -> Option<String> {
+        let token = match std::env::var(self.token_env()) {
+            Ok(token) if !token.is_empty() => token,
+            _ => {
+                let content = std::fs::read_to_string(self.token_file.as_ref()?).ok()?;
+                content.lines().next()?.trim().to_string()
+            }
+        };
+        (!token.is_empty()).then_some(token)
+    }
+}
+
+/// A credential source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    SshAgent,
+    /// The key file from the config
+    ConfiguredKey,
+    SshKey(&'static str),
+    Token,
+    Helper,
+}
+
+impl Source {
+    fn describe(&self, auth: &RemoteAuth) -> String {
+        match self {
+            Source::SshAgent => "ssh-agent".to_string(),
+            Source::ConfiguredKey => auth
+                .ssh_key
+                .as_ref()
+                .map(|key| key.display().to_string())
+                .unwrap_or_default(),
+            Source::SshKey(name) => format!("~/.ssh/{}", name),
+            Source::Token => match &auth.token_file {
+                Some(file) => format!("token from ${} or {}", auth.token_env(), file.display()),
+                None => format!("token from ${}", auth.token_env()),
+            },
+            Source::Helper => "git credential helper".to_string(),
+        }
+    }
+}
+
+/// Sources to try for a request, in order
+///
+/// A configured key file comes first: it was set for this remote, while
+/// the agent may hold keys for other accounts.
+fn candidates(auth: &RemoteAuth, allowed: CredentialType) -> Vec<Source> {
+    let method = auth.method;
+    let mut sources = Vec::new();
+    if allowed.contains(CredentialType::SSH_KEY) {
+        let key_files = matches!(method, AuthMethod::Auto | AuthMethod::SshKey);
+        if key_files && auth.ssh_key.is_some() {
+            sources.push(Source::ConfiguredKey);
+        }
+        if matches!(method, AuthMethod::Auto | AuthMethod::SshAgent) {
+            sources.push(Source::SshAgent);
+        }
+        if method == AuthMethod::Auto || (key_files && auth.ssh_key.is_none()) {
+            sources.push(Source::SshKey("id_ed25519"));
+            sources.push(Source::SshKey("id_rsa"));
+        }
+    }
+    if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+        if matches!(method, AuthMethod::Auto | AuthMethod::Token) {
+            sources.push(Source::Token);
+        }
+        if matches!(method, AuthMethod::Auto | AuthMethod::Helper) {
+            sources.push(Source::Helper);
+        }
+    }
+    sources
+}
+
+/// Build a credential from a source, if the source has one
+fn credential(
+    source: Source,
+    auth: &RemoteAuth,
+    url: &str,
+    username: Option<&str>,
+) -> Option<Cred> {
+    match source {
+        Source::SshAgent => Cred::ssh_key_from_agent(username.unwrap_or("git")).ok(),
+        Source::ConfiguredKey => key_file(auth.ssh_key.as_deref()?, username),
+        Source::SshKey(name) => {
+            let key = PathBuf::from(std::env::var("HOME").ok()?)
+                .join(".ssh")
+                .join(name);
+            key_file(&key, username)
+        }
+        Source::Token => {
+            let token = auth.token()?;
+            Cred::userpass_plaintext(username.unwrap_or("x-access-token"), &token).ok()
+        }
+        Source::Helper => {
+            let config = git2::Config::open_default().ok()?;
+            Cred::credential_helper(&config, url, username).ok()
+        }
+    }
+}
+
+/// Credential from an SSH private key file, unlocked with
+/// `$JIN_SSH_PASSPHRASE` if set
+fn key_file(key: &Path, username: Option<&str>) -> Option<Cred> {
+    if !key.exists() {
+        return None;
+    }
+    let passphrase = std::env::var(SSH_PASSPHRASE_ENV).ok();
+    Cred::ssh_key(
+        username.unwrap_or("git"),
+        None,
+        key,
+        passphrase.as_deref().filter(|p| !p.is_empty()),
+    )
+    .ok()
+}
+
+/// Setup authentication callbacks using `auth`
+///
+/// Each time the remote rejects a credential, the next source is tried.
+/// When none is left the operation fails with an authentication error
+/// listing what was tried and how to configure it.
+pub fn setup_auth_callbacks(callbacks: &mut RemoteCallbacks, auth: &RemoteAuth) {
+    let auth = auth.clone();
+    let tried: Arc<Mutex<Vec<Source>>> = Arc::default();
+    let counter = AuthCounter::new();
+
+    callbacks.credentials(move |url, username, allowed| {
+        // For file:// URLs or absolute paths, no authentication is needed
+        if url.starts_with("file://") || url.starts_with('/') {
+            return Cred::default();
+        }
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(username.unwrap_or("git"));
+        }
+
+        let mut tried = tried.lock().unwrap();
+        if counter.increment_and_check(8) {
+            for source in candidates(&auth, allowed) {
+                if tried.contains(&source) {
+                    continue;
+                }
+                tried.push(source);
+                if let Some(cred) = credential(source, &auth, url, username) {
+                    return Ok(cred);
+                }
+            }
+        }
+
+        Err(git2::Error::new(
+            ErrorCode::Auth,
+            ErrorClass::Callback,
+            auth_failure(url, &auth, &tried),
+        ))
+    });
+}
+
+/// Message for a remote that accepted none of the credentials
+fn auth_failure(url: &str, auth: &RemoteAuth, tried: &[Source]) -> String {
+    let tried = if tried.is_empty() {
+        format!("no {} credentials available", auth.method)
+    } else {
+        format!(
+            "tried {}",
+            tried
+                .iter()
+                .map(|s| s.describe(auth))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
+    format!(
+        "{} rejected the credentials ({}).\n\
+         For SSH, load a key with 'ssh-add' or set one with --ssh-key. For HTTPS, \
+         configure a git credential helper, export ${} or set --token-file.\n\
+         To pick a method: jin link {} --force --auth <auto|ssh-agent|ssh-key|helper|token>",
+        url,
+        tried,
+        auth.token_env(),
+        url
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth(method: AuthMethod, ssh_key: Option<&str>) -> RemoteAuth {
+        RemoteAuth {
+            method,
+            ssh_key: ssh_key.map(PathBuf::from),
+            ..RemoteAuth::default()
+        }
+    }
+
+    #[test]
+    fn test_auth_method_round_trips() {
+        for method in ["auto", "ssh-agent", "ssh-key", "helper", "token"] {
+            assert_eq!(method.parse::<AuthMethod>().unwrap().to_string(), method);
+        }
+        assert!("password".parse::<AuthMethod>().is_err());
+    }
+
+    #[test]
+    fn test_candidates_follow_method() {
+        let ssh = CredentialType::SSH_KEY;
+        let https = CredentialType::USER_PASS_PLAINTEXT;
+        let defaults = [Source::SshKey("id_ed25519"), Source::SshKey("id_rsa")];
+        assert_eq!(
+            candidates(&auth(AuthMethod::Auto, None), ssh),
+            [&[Source::SshAgent][..], &defaults].concat()
+        );
+        assert_eq!(
+            candidates(&auth(AuthMethod::Auto, None), https),
+            vec![Source::Token, Source::Helper]
+        );
+        assert_eq!(
+            candidates(&auth(AuthMethod::SshAgent, Some("/k")), ssh),
+            vec![Source::SshAgent]
+        );
+        assert_eq!(
+            candidates(&auth(AuthMethod::Token, None), https),
+            vec![Source::Token]
+        );
+        assert!(candidates(&auth(AuthMethod::Helper, None), ssh).is_empty());
+    }
+
+    #[test]
+    fn test_configured_key_is_tried_first() {
+        let ssh = CredentialType::SSH_KEY;
+        assert_eq!(
+            candidates(&auth(AuthMethod::Auto, Some("/keys/deploy")), ssh),
+            vec![
+                Source::ConfiguredKey,
+                Source::SshAgent,
+                Source::SshKey("id_ed25519"),
+                Source::SshKey("id_rsa")
+            ]
+        );
+        assert_eq!(
+            candidates(&auth(AuthMethod::SshKey, Some("/keys/deploy")), ssh),
+            vec![Source::ConfiguredKey]
+        );
+        assert_eq!(
+            candidates(&auth(AuthMethod::SshKey, None), ssh),
+            vec![Source::SshKey("id_ed25519"), Source::SshKey("id_rsa")]
+        );
+    }
+
+    #[test]
+    fn test_token_falls_back_to_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = temp.path().join("token");
+        std::fs::write(&file, "file-token\n").unwrap();
+        let auth = RemoteAuth {
+            token_env: Some("JIN_TEST_UNSET_TOKEN_VAR".to_string()),
+            token_file: Some(file),
+            ..RemoteAuth::default()
+        };
+        assert_eq!(auth.token().as_deref(), Some("file-token"));
+        assert_eq!(RemoteAuth::default().token_file, None);
+    }
+
+    #[test]
+    fn test_auth_failure_names_sources() {
+        let auth = RemoteAuth {
+            method: AuthMethod::Auto,
+            token_env: Some("CI_TOKEN".to_string()),
+            ..RemoteAuth::default()
+        };
+        let message = auth_failure(
+            "https://git.example.com/config.git",
+            &auth,
+            &[Source::Token, Source::Helper],
+        );
+        assert!(message.contains("tried token from $CI_TOKEN, git credential helper"));
+        assert!(message.contains("export $CI_TOKEN"));
+        assert!(message.contains("--auth <auto|ssh-agent|ssh-key|helper|token>"));
+    }
+}
@@ -0,0 +1,337 @@
+//! Structured concurrency for remote operations
+//!
+//! [`RemoteExecutor`] runs one [`Job`] per remote on scoped threads, so every
+//! job has finished (or given up) by the time [`RemoteExecutor::run`]
+//! returns. Jobs share a [`CancelToken`]: Ctrl-C, or the first job that
+//! fails, cancels the others.
+//!
+//! Cancellation is cooperative. The git2 callbacks built by
+//! [`crate::git::remote`] check the token and abort the transfer, and git2
+//! only updates refs once a fetch has downloaded everything, so a cancelled
+//! fetch leaves refs untouched. Commands check [`check_interrupted`] before
+//! committing ref updates of their own.
+
+use crate::core::progress;
+use crate::core::{JinError, Result};
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Once};
+
+/// Set by the SIGINT handler
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Install the Ctrl-C handler (once per process)
+///
+/// The first Ctrl-C only sets a flag that running transfers notice at their
+/// next progress callback; a second one exits immediately.
+pub fn install_interrupt_handler() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        #[cfg(unix)]
+        {
+            extern "C" fn on_sigint(_: libc::c_int) {
+                if INTERRUPTED.swap(true, Ordering::SeqCst) {
+                    // SAFETY: _exit is async-signal-safe
+                    unsafe { libc::_exit(130) };
+                }
+            }
+            // SAFETY: the handler only touches an atomic and calls _exit,
+            // both async-signal-safe
+            unsafe {
+                libc::signal(
+                    libc::SIGINT,
+                    on_sigint as extern "C" fn(libc::c_int) as libc::sighandler_t,
+                );
+            }
+        }
+    });
+}
+
+/// Whether the user pressed Ctrl-C
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Fail with [`JinError::Cancelled`] if the user pressed Ctrl-C
+pub fn check_interrupted() -> Result<()> {
+    if interrupted() {
+        Err(JinError::Cancelled)
+    } else {
+        Ok(())
+    }
+}
+
+/// Shared cancellation flag for a group of jobs
+///
+/// A token is also cancelled by Ctrl-C once the handler is installed.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    flag: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Create a token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel every job holding this token
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether the token was cancelled or the user pressed Ctrl-C
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::SeqCst) || interrupted()
+    }
+
+    /// Fail with [`JinError::Cancelled`] if the token was cancelled
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(JinError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// One progress line shared by all jobs of a run
+#[derive(Debug, Default)]
+struct ProgressBoard {
+    /// Latest status per job, in job order
+    slots: Mutex<Vec<(String, String)>>,
+}
+
+impl ProgressBoard {
+    /// Update a job's status and redraw the line
+    fn update(&self, index: usize, status: &str) {
+        let Ok(mut slots) = self.slots.lock() else {
+            return;
+        };
+        slots[index].1 = status.to_string();
+        let line = if slots.len() == 1 {
+            slots[0].1.clone()
+        } else {
+            slots
+                .iter()
+                .filter(|(_, status)| !status.is_empty())
+                .map(|(name, status)| format!("[{}] {}", name, status))
+                .collect::<Vec<_>>()
+                .join("  ")
+        };
+        print!("{}\r", line);
+        let _ = io::stdout().flush();
+    }
+}
+
+/// Handle a running job uses to report progress and notice cancellation
+#[derive(Debug, Clone)]
+pub struct Task {
+    name: String,
+    index: usize,
+    token: CancelToken,
+    board: Arc<ProgressBoard>,
+}
+
+impl Task {
+    /// Name of the remote this task works on
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The token shared with the other jobs
+    pub fn token(&self) -> &CancelToken {
+        &self.token
+    }
+
+    /// Whether the task should stop
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Report progress (e.g., `Received 3/10 objects (30%)`)
+    ///
+    /// Drawn only when human progress is shown (see [`crate::core::progress`]).
+    pub fn progress(&self, status: &str) {
+        if progress::human() {
+            self.board.update(self.index, status);
+        }
+    }
+}
+
+/// Body of a job
+type JobFn<'a, T> = Box<dyn FnOnce(&Task) -> Result<T> + Send + 'a>;
+
+/// A remote operation to run on the executor
+pub struct Job<'a, T> {
+    name: String,
+    run: JobFn<'a, T>,
+}
+
+impl<'a, T> Job<'a, T> {
+    /// Create a job named after the remote it works on
+    pub fn new(name: impl Into<String>, run: impl FnOnce(&Task) -> Result<T> + Send + 'a) -> Self {
+        Self {
+            name: name.into(),
+            run: Box::new(run),
+        }
+    }
+}
+
+/// Runs remote jobs in parallel with shared cancellation
+#[derive(Debug, Clone)]
+pub struct RemoteExecutor {
+    max_parallel: usize,
+    token: CancelToken,
+}
+
+impl Default for RemoteExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RemoteExecutor {
+    /// Create an executor and install the Ctrl-C handler
+    ///
+    /// At most four jobs run at once; remotes are network-bound, so more
+    /// threads rarely help.
+    pub fn new() -> Self {
+        install_interrupt_handler();
+        Self {
+            max_parallel: 4,
+            token: CancelToken::new(),
+        }
+    }
+
+    /// Limit how many jobs run at once (at least one)
+    pub fn with_max_parallel(mut self, max_parallel: usize) -> Self {
+        self.max_parallel = max_parallel.max(1);
+        self
+    }
+
+    /// Token cancelling every job of this executor
+    pub fn token(&self) -> &CancelToken {
+        &self.token
+    }
+
+    /// Run `jobs` and wait for all of them
+    ///
+    /// Returns each job's name and result in the order the jobs were given.
+    /// When a job fails, jobs that have not started yet are skipped with
+    /// [`JinError::Cancelled`] and running ones are asked to stop.
+    pub fn run<'a, T: Send>(&self, jobs: Vec<Job<'a, T>>) -> Vec<(String, Result<T>)> {
+        let names: Vec<String> = jobs.iter().map(|job| job.name.clone()).collect();
+        let board = Arc::new(ProgressBoard {
+            slots: Mutex::new(
+                names
+                    .iter()
+                    .map(|name| (name.clone(), String::new()))
+                    .collect(),
+            ),
+        });
+        let queue: Mutex<VecDeque<(usize, Job<'a, T>)>> =
+            Mutex::new(jobs.into_iter().enumerate().collect());
+        let results: Mutex<Vec<Option<Result<T>>>> =
+            Mutex::new(names.iter().map(|_| None).collect());
+
+        std::thread::scope(|scope| {
+            for _ in 0..self.max_parallel.min(names.len()) {
+                scope.spawn(|| loop {
+                    let Some((index, job)) = queue.lock().ok().and_then(|mut q| q.pop_front())
+                    else {
+                        break;
+                    };
+                    let result = match self.token.check() {
+                        Err(e) => Err(e),
+                        Ok(()) => {
+                            let task = Task {
+                                name: job.name,
+                                index,
+                                token: self.token.clone(),
+                                board: Arc::clone(&board),
+                            };
+                            (job.run)(&task)
+                        }
+                    };
+                    if matches!(result, Err(ref e) if !matches!(e, JinError::Cancelled)) {
+                        self.token.cancel();
+                    }
+                    if let Ok(mut results) = results.lock() {
+                        results[index] = Some(result);
+                    }
+                });
+            }
+        });
+
+        let results = results.into_inner().unwrap_or_default();
+        names
+            .into_iter()
+            .zip(results)
+            .map(|(name, result)| (name, result.unwrap_or(Err(JinError::Cancelled))))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Barrier;
+
+    #[test]
+    fn test_results_keep_job_order() {
+        let executor = RemoteExecutor::new();
+        let results = executor.run(vec![
+            Job::new("origin", |_| Ok(1)),
+            Job::new("backup", |_| Ok(2)),
+        ]);
+        let results: Vec<(String, i32)> = results
+            .into_iter()
+            .map(|(name, result)| (name, result.unwrap()))
+            .collect();
+        assert_eq!(
+            results,
+            vec![("origin".to_string(), 1), ("backup".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_jobs_run_in_parallel() {
+        // Both jobs must be running at the same time to pass the barrier
+        let barrier = Barrier::new(2);
+        let executor = RemoteExecutor::new().with_max_parallel(2);
+        let results = executor.run(vec![
+            Job::new("a", |_| {
+                barrier.wait();
+                Ok(())
+            }),
+            Job::new("b", |_| {
+                barrier.wait();
+                Ok(())
+            }),
+        ]);
+        assert!(results.iter().all(|(_, r)| r.is_ok()));
+    }
+
+    #[test]
+    fn test_failure_cancels_remaining_jobs() {
+        let executor = RemoteExecutor::new().with_max_parallel(1);
+        let results = executor.run(vec![
+            Job::new("origin", |_| Err(JinError::Other("unreachable".into()))),
+            Job::new("backup", |_| Ok(())),
+        ]);
+        assert!(matches!(results[0].1, Err(JinError::Other(_))));
+        assert!(matches!(results[1].1, Err(JinError::Cancelled)));
+        assert!(executor.token().is_cancelled());
+    }
+
+    #[test]
+    fn test_cancel_token() {
+        let token = CancelToken::new();
+        let task_token = token.clone();
+        assert!(task_token.check().is_ok());
+        token.cancel();
+        assert!(matches!(task_token.check(), Err(JinError::Cancelled)));
+    }
+}
@@ -0,0 +1,305 @@
+//! Remote freshness checks for `jin status`
+//!
+//! When `remote.check-interval` is configured, `jin status` reports how many
+//! commits the remote has on the active context's layers that are not yet
+//! present locally. The remote's layer refs are fetched into the same
+//! tracking namespace `jin fetch` uses (`refs/jin/remote/<remote>/layers/*`),
+//! so the check never touches local layers; pulling stays an explicit step.
+//!
+//! Fetches are rate-limited by a small state file in the Jin repository and
+//! run in a detached background process, so `status` itself never waits on
+//! the network and always reports the result of the most recent check.
+
+use crate::core::{JinError, Layer, ProjectContext, Result};
+use crate::git::remote::{setup_callbacks, tracking_prefix, tracking_refspec};
+use crate::git::JinRepo;
+use chrono::{DateTime, Utc};
+use git2::{FetchOptions, RemoteCallbacks};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Prefix of local layer refs
+const LAYER_PREFIX: &str = "refs/jin/layers/";
+
+/// State file name inside the Jin repository
+const STATE_FILE: &str = "freshness.json";
+
+/// Hidden subcommand used to run the fetch in the background
+pub const BACKGROUND_COMMAND: &str = "remote-check";
+
+/// Persisted timestamps of freshness checks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FreshnessState {
+    /// When a check was last started
+    pub last_attempt: Option<DateTime<Utc>>,
+    /// When a check last completed successfully
+    pub last_success: Option<DateTime<Utc>>,
+}
+
+impl FreshnessState {
+    fn path(repo: &JinRepo) -> PathBuf {
+        repo.path().join(STATE_FILE)
+    }
+
+    /// Load state for a repository (missing or unreadable state is empty)
+    pub fn load(repo: &JinRepo) -> Self {
+        std::fs::read_to_string(Self::path(repo))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save state for a repository
+    pub fn save(&self, repo: &JinRepo) -> Result<()> {
+        let content = serde_json::to_string(self).map_err(|e| JinError::Parse {
+            format: "JSON".to_string(),
+            message: e.to_string(),
+        })?;
+        std::fs::write(Self::path(repo), content)?;
+        Ok(())
+    }
+
+    /// Check whether a new fetch should be started
+    ///
+    /// The last *attempt* is used so a failing remote is not retried on
+    /// every `status` invocation.
+    pub fn is_due(&self, interval: Duration, now: DateTime<Utc>) -> bool {
+        match self.last_attempt {
+            Some(last) => now
+                .signed_duration_since(last)
+                .to_std()
+                .map(|elapsed| elapsed >= interval)
+                .unwrap_or(false),
+            None => true,
+        }
+    }
+}
+
+/// Number of remote commits not yet present on a local layer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LayerFreshness {
+    /// Layer path relative to `refs/jin/layers/` (e.g., `mode/claude`)
+    pub layer: String,
+    /// Commits on the remote that are not reachable locally
+    pub newer_commits: usize,
+}
+
+/// Fetch `remote_name`'s layer refs into its tracking namespace and record
+/// the result
+///
+/// # Errors
+///
+/// Returns an error if the remote is missing or the fetch fails.
+pub fn fetch_tracking_refs(repo: &JinRepo, remote_name: &str) -> Result<()> {
+    let mut state = FreshnessState::load(repo);
+    state.last_attempt = Some(Utc::now());
+    state.save(repo)?;
+
+    // An anonymous remote ignores the remote's configured refspec, which
+    // would otherwise opportunistically update local layer refs during the
+    // fetch
+    let url = repo
+        .inner()
+        .find_remote(remote_name)?
+        .url()
+        .map(str::to_string)
+        .ok_or_else(|| JinError::Config(format!("Remote '{}' has no URL", remote_name)))?;
+    let mut remote = repo.inner().remote_anonymous(&url)?;
+
+    // Quiet fetch: authentication only, no progress output
+    let mut callbacks = RemoteCallbacks::new();
+    setup_callbacks(&mut callbacks);
+    let mut opts = FetchOptions::new();
+    opts.remote_callbacks(callbacks);
+    opts.prune(git2::FetchPrune::On);
+
+    remote.fetch(&[tracking_refspec(remote_name)], Some(&mut opts), None)?;
+
+    state.last_success = Some(Utc::now());
+    state.save(repo)
+}
+
+/// Start a background fetch if the last one is older than `interval`
+///
+/// The attempt time is recorded before spawning so concurrent `status`
+/// invocations don't start duplicate fetches.
+pub fn refresh_in_background(root: &Path, repo: &JinRepo, interval: Duration) -> Result<()> {
+    let mut state = FreshnessState::load(repo);
+    if !state.is_due(interval, Utc::now()) {
+        return Ok(());
+    }
+    state.last_attempt = Some(Utc::now());
+    state.save(repo)?;
+
+    let exe = std::env::current_exe()?;
+    Command::new(exe)
+        .arg(BACKGROUND_COMMAND)
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    Ok(())
+}
+
+/// Compare `remote_name`'s tracking refs with local refs for the active
+/// context
+///
+/// Only layers that apply to the context are considered (the same set
+/// `jin status` summarizes). Layers where the remote has nothing new are
+/// omitted.
+pub fn check_context(
+    repo: &JinRepo,
+    context: &ProjectContext,
+    remote_name: &str,
+) -> Result<Vec<LayerFreshness>> {
+    let git_repo = repo.inner();
+    let mut results = Vec::new();
+
+    for layer in Layer::all_in_precedence_order() {
+        if matches!(layer, Layer::UserLocal | Layer::WorkspaceActive) {
+            continue;
+        }
+        if layer.requires_mode() && context.mode.is_none() {
+            continue;
+        }
+        if layer.requires_scope() && context.scope.is_none() {
+            continue;
+        }
+
+        let local_ref = layer.ref_path(
+            context.mode.as_deref(),
+            context.scope.as_deref(),
+            context.project.as_deref(),
+        );
+        let layer_path = local_ref.strip_prefix(LAYER_PREFIX).unwrap_or(&local_ref);
+        let tracking_ref = format!("{}{}", tracking_prefix(remote_name), layer_path);
+
+        let remote_oid = match git_repo.refname_to_id(&tracking_ref) {
+            Ok(oid) => oid,
+            Err(_) => continue,
+        };
+
+        let mut walk = git_repo.revwalk()?;
+        walk.push(remote_oid)?;
+        if let Ok(local_oid) = git_repo.refname_to_id(&local_ref) {
+            walk.hide(local_oid)?;
+        }
+        let newer_commits = walk.filter(|r| r.is_ok()).count();
+
+        if newer_commits > 0 {
+            results.push(LayerFreshness {
+                layer: layer_path.trim_end_matches("/_").to_string(),
+                newer_commits,
+            });
+        }
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{ObjectOps, RefOps};
+    use tempfile::TempDir;
+
+    fn commit_on(repo: &JinRepo, refname: &str, parent: Option<git2::Oid>, msg: &str) -> git2::Oid {
+        let tree = repo.create_tree(&[]).unwrap();
+        let parents: Vec<git2::Oid> = parent.into_iter().collect();
+        let oid = repo.create_commit(None, msg, tree, &parents).unwrap();
+        repo.set_ref(refname, oid, msg).unwrap();
+        oid
+    }
+
+    #[test]
+    fn test_is_due() {
+        let now = Utc::now();
+        let interval = Duration::from_secs(60);
+        assert!(FreshnessState::default().is_due(interval, now));
+
+        let recent = FreshnessState {
+            last_attempt: Some(now - chrono::Duration::seconds(10)),
+            last_success: None,
+        };
+        assert!(!recent.is_due(interval, now));
+
+        let stale = FreshnessState {
+            last_attempt: Some(now - chrono::Duration::seconds(120)),
+            last_success: None,
+        };
+        assert!(stale.is_due(interval, now));
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join("repo")).unwrap();
+        assert!(FreshnessState::load(&repo).last_attempt.is_none());
+
+        let state = FreshnessState {
+            last_attempt: Some(Utc::now()),
+            last_success: None,
+        };
+        state.save(&repo).unwrap();
+        assert!(FreshnessState::load(&repo).last_attempt.is_some());
+    }
+
+    #[test]
+    fn test_check_context_counts_newer_commits() {
+        let temp = TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join("repo")).unwrap();
+
+        let base = commit_on(&repo, "refs/jin/layers/mode/claude/_", None, "base");
+        let c1 = commit_on(
+            &repo,
+            "refs/jin/remote/origin/layers/mode/claude/_",
+            Some(base),
+            "r1",
+        );
+        commit_on(
+            &repo,
+            "refs/jin/remote/origin/layers/mode/claude/_",
+            Some(c1),
+            "r2",
+        );
+        // Not part of the active context
+        commit_on(
+            &repo,
+            "refs/jin/remote/origin/layers/mode/other/_",
+            None,
+            "other",
+        );
+
+        let context = ProjectContext {
+            mode: Some("claude".to_string()),
+            ..Default::default()
+        };
+        let results = check_context(&repo, &context, "origin").unwrap();
+        assert_eq!(
+            results,
+            vec![LayerFreshness {
+                layer: "mode/claude".to_string(),
+                newer_commits: 2,
+            }]
+        );
+        // Other remotes track their own namespace
+        assert!(check_context(&repo, &context, "backup").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_check_context_up_to_date() {
+        let temp = TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join("repo")).unwrap();
+
+        let oid = commit_on(&repo, "refs/jin/layers/global", None, "base");
+        repo.set_ref("refs/jin/remote/origin/layers/global", oid, "track")
+            .unwrap();
+
+        let results = check_context(&repo, &ProjectContext::default(), "origin").unwrap();
+        assert!(results.is_empty());
+    }
+}
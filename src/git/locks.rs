@@ -0,0 +1,315 @@
+//! Advisory path locks for team-shared layers
+//!
+//! `jin lock <path> --layer <layer>` records that someone is working on a
+//! path (say, during a risky config migration) so teammates' commits to it
+//! warn, or fail when `locks.policy` is `block`. Locks are advisory: nothing
+//! stops a determined teammate, much like Git LFS file locking.
+//!
+//! All locks live in a single JSON file committed to `refs/jin/locks`. The
+//! ref sits outside `refs/jin/layers/*`, so it never takes part in layer
+//! merges, and `jin lock`/`jin unlock` push it straight to the team remote
+//! (a fast-forward only, so two people can't take the same lock at once).
+//! `jin fetch` refreshes it; commits check the last fetched copy.
+
+use crate::core::{JinConfig, JinError, Layer, Result};
+use crate::git::executor::interrupted;
+use crate::git::refs::layer_name;
+use crate::git::remote::{build_push_options, remote_error, setup_callbacks};
+use crate::git::retry::with_retry;
+use crate::git::{JinRepo, ObjectOps, RefOps, TreeOps};
+use chrono::{DateTime, Utc};
+use git2::{ErrorCode, FetchOptions, Oid, Remote, RemoteCallbacks};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Ref holding the lock list
+pub const LOCKS_REF: &str = "refs/jin/locks";
+
+/// Refspec mirroring the remote lock list
+const LOCKS_REFSPEC: &str = "+refs/jin/locks:refs/jin/locks";
+
+/// File in the lock commit's tree holding the locks
+const LOCKS_FILE: &str = "locks.json";
+
+/// Prefix of layer refs
+const LAYER_PREFIX: &str = "refs/jin/layers/";
+
+/// A lock on a path in one layer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathLock {
+    /// Locked path (a directory locks everything below it)
+    pub path: String,
+    /// Layer name, as shown by `jin remote ls` (e.g., `global`, `mode/claude`)
+    pub layer: String,
+    /// Who holds the lock
+    pub owner: String,
+    /// When the lock was taken
+    pub locked_at: DateTime<Utc>,
+    /// Why the path is locked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl PathLock {
+    /// Whether this lock covers `path` in `layer`
+    pub fn covers(&self, layer: &str, path: &str) -> bool {
+        self.layer == layer
+            && (self.path == path
+                || path
+                    .strip_prefix(self.path.trim_end_matches('/'))
+                    .is_some_and(|rest| rest.starts_with('/')))
+    }
+}
+
+/// Policy for commits touching paths locked by someone else
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LockPolicy {
+    /// Print a warning and commit anyway
+    #[default]
+    Warn,
+    /// Refuse the commit
+    Block,
+}
+
+impl std::str::FromStr for LockPolicy {
+    type Err = JinError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "warn" => Ok(Self::Warn),
+            "block" => Ok(Self::Block),
+            _ => Err(JinError::Config(format!(
+                "Invalid lock policy: {}. Use 'warn' or 'block'",
+                value
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for LockPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warn => write!(f, "warn"),
+            Self::Block => write!(f, "block"),
+        }
+    }
+}
+
+/// Lock settings (stored under `[locks]` in the global config)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocksConfig {
+    /// What a commit to a path locked by someone else does
+    #[serde(default)]
+    pub policy: LockPolicy,
+}
+
+/// The lock list at `refs/jin/locks`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockSet {
+    /// Locks, oldest first
+    pub locks: Vec<PathLock>,
+}
+
+impl LockSet {
+    /// Load the local copy of the lock list (empty if there is none)
+    pub fn load(repo: &JinRepo) -> Result<Self> {
+        if !repo.ref_exists(LOCKS_REF) {
+            return Ok(Self::default());
+        }
+        let tree_oid = repo.find_commit(repo.resolve_ref(LOCKS_REF)?)?.tree_id();
+        let content = repo.read_file_from_tree(tree_oid, Path::new(LOCKS_FILE))?;
+        serde_json::from_slice(&content).map_err(|e| JinError::Parse {
+            format: "locks".to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Commit the lock list on top of the current `refs/jin/locks`
+    pub fn save(&self, repo: &JinRepo, message: &str) -> Result<Oid> {
+        let content = serde_json::to_vec_pretty(self).map_err(|e| JinError::Parse {
+            format: "locks".to_string(),
+            message: e.to_string(),
+        })?;
+        let blob = repo.create_blob(&content)?;
+        let tree = repo.create_tree_from_paths(&[(LOCKS_FILE.to_string(), blob)])?;
+        let parents: Vec<Oid> = repo.resolve_ref(LOCKS_REF).into_iter().collect();
+        let commit = repo.create_commit(None, message, tree, &parents)?;
+        repo.set_ref(LOCKS_REF, commit, message)?;
+        Ok(commit)
+    }
+
+    /// Lock covering `path` in `layer`, if any
+    pub fn find(&self, layer: &str, path: &str) -> Option<&PathLock> {
+        self.locks.iter().find(|lock| lock.covers(layer, path))
+    }
+
+    /// Index of the lock taken on exactly `path` in `layer`
+    pub fn position(&self, layer: &str, path: &str) -> Option<usize> {
+        self.locks
+            .iter()
+            .position(|lock| lock.layer == layer && lock.path == path)
+    }
+}
+
+/// Identity recorded as lock owner: the configured user, else Git's
+pub fn current_owner(repo: &JinRepo) -> String {
+    let user = JinConfig::load().ok().and_then(|c| c.user);
+    let name = user.as_ref().and_then(|u| u.name.clone());
+    let email = user.as_ref().and_then(|u| u.email.clone());
+    let signature = repo.inner().signature().ok();
+
+    let name = name
+        .or_else(|| {
+            signature
+                .as_ref()
+                .and_then(|s| s.name().map(str::to_string))
+        })
+        .or_else(|| std::env::var("USER").ok())
+        .unwrap_or_else(|| "unknown".to_string());
+    match email.or_else(|| {
+        signature
+            .as_ref()
+            .and_then(|s| s.email().map(str::to_string))
+    }) {
+        Some(email) => format!("{} <{}>", name, email),
+        None => name,
+    }
+}
+
+/// Validate and normalize a `--layer` argument
+///
+/// Accepts layer names (`global`, `mode/claude`, `project/ui`) and full ref
+/// names. User-local and workspace layers are never shared, so locking them
+/// makes no sense.
+pub fn parse_layer(name: &str) -> Result<String> {
+    let name = layer_name(name.trim_end_matches('/')).to_string();
+    let layer = Layer::parse_layer_from_ref_path(&format!("{}{}", LAYER_PREFIX, name))
+        .or_else(|| Layer::parse_layer_from_ref_path(&format!("{}{}/_", LAYER_PREFIX, name)));
+    match layer {
+        Some(Layer::UserLocal | Layer::WorkspaceActive) => Err(JinError::Config(format!(
+            "Layer '{}' is never shared and can't be locked",
+            name
+        ))),
+        Some(_) => Ok(name),
+        None => Err(JinError::Config(format!(
+            "Unknown layer: {}. Use a layer name such as 'global', 'mode/<mode>' or \
+             'project/<project>' (see 'jin remote ls')",
+            name
+        ))),
+    }
+}
+
+/// Normalize a path argument the way staged paths are stored
+pub fn normalize_path(path: &str) -> String {
+    path.trim_start_matches("./").replace('\\', "/")
+}
+
+/// Anonymous remote for the team remote, if one is linked
+///
+/// An anonymous remote keeps origin's layer refspec from running.
+pub fn team_remote(repo: &JinRepo) -> Result<Option<Remote<'_>>> {
+    let origin = match repo.inner().find_remote("origin") {
+        Ok(origin) => origin,
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    match origin.url() {
+        Some(url) => Ok(Some(repo.inner().remote_anonymous(url)?)),
+        None => Ok(None),
+    }
+}
+
+/// Update the local lock list from the remote
+pub fn fetch(remote: &mut Remote<'_>) -> Result<()> {
+    let mut callbacks = RemoteCallbacks::new();
+    setup_callbacks(&mut callbacks);
+    callbacks.transfer_progress(|_| !interrupted());
+    let mut opts = FetchOptions::new();
+    opts.remote_callbacks(callbacks);
+    opts.update_fetchhead(false);
+    opts.download_tags(git2::AutotagOption::None);
+
+    with_retry("Fetch locks", || {
+        remote
+            .fetch(&[LOCKS_REFSPEC], Some(&mut opts), None)
+            .map_err(remote_error)
+    })
+}
+
+/// Publish the local lock list (fast-forward only)
+pub fn push(remote: &mut Remote<'_>) -> Result<()> {
+    let mut opts = build_push_options()?;
+    let refspec = format!("{}:{}", LOCKS_REF, LOCKS_REF);
+    remote
+        .push(&[refspec.as_str()], Some(&mut opts))
+        .map_err(|e| {
+            if e.message().contains("fast-forward") || e.message().contains("fetch first") {
+                JinError::Other(
+                    "Locks changed on the remote while updating them. Run the command again."
+                        .into(),
+                )
+            } else {
+                e.into()
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_unit_test;
+    use serial_test::serial;
+
+    fn lock(layer: &str, path: &str) -> PathLock {
+        PathLock {
+            path: path.to_string(),
+            layer: layer.to_string(),
+            owner: "alice".to_string(),
+            locked_at: Utc::now(),
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_lock_covers_path_and_directory() {
+        assert!(lock("global", "config.yaml").covers("global", "config.yaml"));
+        assert!(!lock("global", "config.yaml").covers("mode/claude", "config.yaml"));
+        assert!(lock("global", "deploy").covers("global", "deploy/prod.yaml"));
+        assert!(lock("global", "deploy/").covers("global", "deploy/prod.yaml"));
+        assert!(!lock("global", "deploy").covers("global", "deployment.yaml"));
+    }
+
+    #[test]
+    fn test_parse_layer() {
+        assert_eq!(parse_layer("global").unwrap(), "global");
+        assert_eq!(parse_layer("mode/claude/").unwrap(), "mode/claude");
+        assert_eq!(
+            parse_layer("refs/jin/layers/mode/claude/scope/lang/_").unwrap(),
+            "mode/claude/scope/lang"
+        );
+        assert_eq!(parse_layer("project/ui").unwrap(), "project/ui");
+        assert!(parse_layer("local").is_err());
+        assert!(parse_layer("nonsense/a/b").is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_lock_set_round_trip() {
+        let ctx = setup_unit_test();
+        let repo = JinRepo::open_or_create(&ctx.project_path).unwrap();
+        assert!(LockSet::load(&repo).unwrap().locks.is_empty());
+
+        let mut locks = LockSet::default();
+        locks.locks.push(lock("global", "config.yaml"));
+        let first = locks.save(&repo, "Lock config.yaml").unwrap();
+        locks.locks.clear();
+        let second = locks.save(&repo, "Unlock config.yaml").unwrap();
+
+        assert!(LockSet::load(&repo).unwrap().locks.is_empty());
+        assert_eq!(
+            repo.find_commit(second).unwrap().parent_id(0).unwrap(),
+            first
+        );
+    }
+}
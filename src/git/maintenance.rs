@@ -0,0 +1,277 @@
+//! Repository maintenance for `jin gc`
+//!
+//! Layer history accumulates loose objects and small packs from every
+//! commit, sync and repair. `jin gc` repacks the repository with the system
+//! `git` (libgit2 cannot repack) and reports storage before and after.
+//!
+//! Configs are small, text-heavy files that change a few lines at a time, so
+//! they delta-compress well against many earlier versions. A wider delta
+//! window (`gc.window` in the global config, or `--window`) finds better
+//! bases at the cost of a slower repack; `gc.depth` bounds delta chains.
+//! Unset values fall back to git's defaults (window 10 and depth 50, or
+//! window 250 with `--aggressive`).
+
+use crate::core::{JinError, Result};
+use git2::{ObjectType, Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Repack settings (stored under `[gc]` in the global config)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GcConfig {
+    /// Objects considered as delta bases for each object
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<u32>,
+    /// Maximum length of a delta chain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<u32>,
+}
+
+/// On-disk size of the object database
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageReport {
+    /// Loose object files
+    pub loose_objects: u64,
+    /// Bytes in loose object files
+    pub loose_bytes: u64,
+    /// Pack files
+    pub packs: u64,
+    /// Bytes in pack files and their indexes
+    pub pack_bytes: u64,
+}
+
+impl StorageReport {
+    /// Measure the object database of a repository
+    pub fn measure(repo: &Repository) -> Result<Self> {
+        let objects = repo.path().join("objects");
+        let mut report = Self::default();
+
+        for dir in std::fs::read_dir(&objects)? {
+            let dir = dir?;
+            let name = dir.file_name().to_string_lossy().to_string();
+            if name.len() == 2 && name.chars().all(|c| c.is_ascii_hexdigit()) {
+                for object in std::fs::read_dir(dir.path())? {
+                    report.loose_objects += 1;
+                    report.loose_bytes += object?.metadata()?.len();
+                }
+            }
+        }
+
+        let pack_dir = objects.join("pack");
+        if pack_dir.is_dir() {
+            for file in std::fs::read_dir(pack_dir)? {
+                let file = file?;
+                let path = file.path();
+                match path.extension().and_then(|e| e.to_str()) {
+                    Some("pack") => {
+                        report.packs += 1;
+                        report.pack_bytes += file.metadata()?.len();
+                    }
+                    Some("idx") => report.pack_bytes += file.metadata()?.len(),
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Total bytes on disk
+    pub fn total_bytes(&self) -> u64 {
+        self.loose_bytes + self.pack_bytes
+    }
+}
+
+/// How much identical content is shared across layers and history
+///
+/// Every commit of every layer is a full snapshot; git stores each distinct
+/// blob once, however many snapshots contain it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlobReport {
+    /// Commits reachable from Jin refs
+    pub commits: u64,
+    /// Files across all snapshots
+    pub file_versions: u64,
+    /// Bytes across all snapshots, before deduplication
+    pub logical_bytes: u64,
+    /// Distinct blobs
+    pub unique_blobs: u64,
+    /// Bytes in distinct blobs (uncompressed)
+    pub unique_bytes: u64,
+}
+
+impl BlobReport {
+    /// Walk every commit reachable from `refs/jin/*`
+    pub fn measure(repo: &Repository) -> Result<Self> {
+        let mut walk = repo.revwalk()?;
+        for reference in repo.references_glob("refs/jin/*")? {
+            if let Ok(commit) = reference?.peel_to_commit() {
+                walk.push(commit.id())?;
+            }
+        }
+
+        let mut report = Self::default();
+        let mut trees = HashMap::new();
+        let mut blobs = HashSet::new();
+        for commit in walk {
+            let tree = repo.find_commit(commit?)?.tree_id();
+            let (files, bytes) = tree_stats(repo, tree, &mut trees, &mut blobs, &mut report)?;
+            report.commits += 1;
+            report.file_versions += files;
+            report.logical_bytes += bytes;
+        }
+        Ok(report)
+    }
+}
+
+/// File count and size of a tree, memoized so shared subtrees are read once
+///
+/// Blobs seen for the first time are added to the unique totals.
+fn tree_stats(
+    repo: &Repository,
+    tree: Oid,
+    trees: &mut HashMap<Oid, (u64, u64)>,
+    blobs: &mut HashSet<Oid>,
+    report: &mut BlobReport,
+) -> Result<(u64, u64)> {
+    if let Some(stats) = trees.get(&tree) {
+        return Ok(*stats);
+    }
+
+    let mut stats = (0, 0);
+    for entry in repo.find_tree(tree)?.iter() {
+        match entry.kind() {
+            Some(ObjectType::Tree) => {
+                let (files, bytes) = tree_stats(repo, entry.id(), trees, blobs, report)?;
+                stats.0 += files;
+                stats.1 += bytes;
+            }
+            Some(ObjectType::Blob) => {
+                let size = repo.find_blob(entry.id())?.size() as u64;
+                if blobs.insert(entry.id()) {
+                    report.unique_blobs += 1;
+                    report.unique_bytes += size;
+                }
+                stats.0 += 1;
+                stats.1 += size;
+            }
+            _ => {}
+        }
+    }
+    trees.insert(tree, stats);
+    Ok(stats)
+}
+
+/// Arguments for `git gc` with the given tuning
+pub fn gc_args(aggressive: bool, window: Option<u32>, depth: Option<u32>) -> Vec<String> {
+    // --aggressive reads its own window and depth settings
+    let (window_key, depth_key) = if aggressive {
+        ("gc.aggressiveWindow", "gc.aggressiveDepth")
+    } else {
+        ("pack.window", "pack.depth")
+    };
+
+    let mut args = Vec::new();
+    if let Some(window) = window {
+        args.push("-c".to_string());
+        args.push(format!("{}={}", window_key, window));
+    }
+    if let Some(depth) = depth {
+        args.push("-c".to_string());
+        args.push(format!("{}={}", depth_key, depth));
+    }
+    args.push("gc".to_string());
+    args.push("--quiet".to_string());
+    if aggressive {
+        args.push("--aggressive".to_string());
+    }
+    args
+}
+
+/// Repack the repository at `path` with the system `git`
+pub fn run_gc(
+    path: &Path,
+    aggressive: bool,
+    window: Option<u32>,
+    depth: Option<u32>,
+) -> Result<()> {
+    let status = Command::new("git")
+        .arg("--git-dir")
+        .arg(path)
+        .args(gc_args(aggressive, window, depth))
+        .stdin(Stdio::null())
+        .status()
+        .map_err(|e| {
+            JinError::Other(format!(
+                "Failed to run git gc: {}. jin gc needs git on PATH",
+                e
+            ))
+        })?;
+    if !status.success() {
+        return Err(JinError::Other(format!("git gc failed ({})", status)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{JinRepo, ObjectOps, RefOps};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_gc_args() {
+        assert_eq!(gc_args(false, None, None), ["gc", "--quiet"]);
+        assert_eq!(
+            gc_args(true, Some(500), Some(20)),
+            [
+                "-c",
+                "gc.aggressiveWindow=500",
+                "-c",
+                "gc.aggressiveDepth=20",
+                "gc",
+                "--quiet",
+                "--aggressive"
+            ]
+        );
+        assert_eq!(gc_args(false, Some(50), None)[1], "pack.window=50");
+    }
+
+    #[test]
+    fn test_blob_report_counts_shared_content_once() {
+        let temp = TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join("repo")).unwrap();
+
+        let shared = repo.create_blob(b"shared").unwrap();
+        let changed = repo.create_blob(b"v2").unwrap();
+        let tree1 = repo
+            .create_tree_from_paths(&[("a.txt".to_string(), shared)])
+            .unwrap();
+        let tree2 = repo
+            .create_tree_from_paths(&[
+                ("a.txt".to_string(), shared),
+                ("b.txt".to_string(), changed),
+            ])
+            .unwrap();
+        let first = repo.create_commit(None, "one", tree1, &[]).unwrap();
+        let second = repo.create_commit(None, "two", tree2, &[first]).unwrap();
+        repo.set_ref("refs/jin/layers/global", second, "test")
+            .unwrap();
+
+        let report = BlobReport::measure(repo.inner()).unwrap();
+        assert_eq!(report.commits, 2);
+        assert_eq!(report.file_versions, 3);
+        assert_eq!(report.logical_bytes, 6 + 6 + 2);
+        assert_eq!(report.unique_blobs, 2);
+        assert_eq!(report.unique_bytes, 8);
+
+        let storage = StorageReport::measure(repo.inner()).unwrap();
+        assert!(storage.loose_objects >= 6);
+        assert_eq!(
+            storage.total_bytes(),
+            storage.loose_bytes + storage.pack_bytes
+        );
+    }
+}
@@ -7,15 +7,33 @@
 //! - [`TreeOps`]: Tree walking utilities
 //! - [`JinTransaction`]: Transaction wrapper for atomic reference updates
 //! - [`remote`]: Remote operation utilities for fetch, pull, push
+//! - [`archive`]: Archived (read-only) modes and scopes in `refs/jin/archive`
+//! - [`auth`]: Credential sources for remotes (ssh-agent, helpers, tokens)
+//! - [`executor`]: Parallel, cancellable execution of remote operations
+//! - [`retry`]: Retry with backoff for transient remote failures
+//! - [`freshness`]: Remote freshness checks reported by `jin status`
+//! - [`locks`]: Advisory path locks shared through `refs/jin/locks`
+//! - [`maintenance`]: Repacking and storage reports for `jin gc`
+//! - [`reviewers`]: Required-reviewer notes recorded by `jin push`
 
+pub mod archive;
+pub mod auth;
+pub mod executor;
+pub mod freshness;
+pub mod locks;
+pub mod maintenance;
 pub mod merge;
+pub mod notes;
 pub mod objects;
 pub mod refs;
 pub mod remote;
 pub mod repo;
+pub mod retry;
+pub mod reviewers;
 pub mod transaction;
 pub mod tree;
 
+pub use executor::{CancelToken, Job, RemoteExecutor, Task};
 pub use merge::{detect_merge_type, find_merge_base, MergeType};
 pub use objects::{EntryMode, ObjectOps, TreeEntry};
 pub use refs::RefOps;
@@ -0,0 +1,153 @@
+//! Annotations on layer commits
+//!
+//! `jin annotate <commit> "rolled back in prod"` attaches a note to a layer
+//! commit after the fact, building an operational history next to the
+//! config history. Annotations are git notes kept under `refs/jin/notes`,
+//! outside `refs/jin/layers/*`, and `jin log` shows them under the commit
+//! they annotate. A commit can carry several annotations, one per line,
+//! each signed with its author and date.
+//!
+//! Like locks and the archive list, `jin annotate` fetches the notes from
+//! the team remote and pushes them right after adding one (fast-forward
+//! only), and `jin fetch` refreshes them.
+
+use crate::core::{JinError, Result};
+use crate::git::executor::interrupted;
+use crate::git::remote::{build_push_options, remote_error, setup_callbacks};
+use crate::git::retry::with_retry;
+use crate::git::JinRepo;
+use chrono::Utc;
+use git2::{ErrorCode, FetchOptions, Oid, Remote, RemoteCallbacks, Signature};
+
+/// Notes ref holding the annotations
+pub const NOTES_REF: &str = "refs/jin/notes";
+
+/// Refspec mirroring the remote annotations
+const NOTES_REFSPEC: &str = "+refs/jin/notes:refs/jin/notes";
+
+/// Annotations of a commit, one per line, oldest first
+pub fn read(repo: &git2::Repository, commit: Oid) -> Option<String> {
+    let note = repo.find_note(Some(NOTES_REF), commit).ok()?;
+    note.message().map(|message| message.trim_end().to_string())
+}
+
+/// Add an annotation by `author` to a commit's notes
+///
+/// Returns the new notes commit.
+pub fn annotate(repo: &JinRepo, commit: Oid, message: &str, author: &str) -> Result<Oid> {
+    let message = message.trim();
+    if message.is_empty() || message.contains('\n') {
+        return Err(JinError::Other(
+            "An annotation must be a single, non-empty line".to_string(),
+        ));
+    }
+    let line = format!(
+        "{} ({}, {})",
+        message,
+        author,
+        Utc::now().format("%Y-%m-%d")
+    );
+
+    let inner = repo.inner();
+    let note = match read(inner, commit) {
+        Some(existing) => format!("{}\n{}\n", existing, line),
+        None => format!("{}\n", line),
+    };
+    let signature = inner
+        .signature()
+        .or_else(|_| Signature::now("jin", "jin@local"))?;
+    Ok(inner.note(&signature, &signature, Some(NOTES_REF), commit, &note, true)?)
+}
+
+/// Resolve a commit given by (abbreviated) hash
+pub fn resolve_commit(repo: &JinRepo, spec: &str) -> Result<Oid> {
+    let not_found = || JinError::NotFound(format!("Commit '{}'", spec));
+    let object = repo.inner().revparse_single(spec).map_err(|e| {
+        if matches!(e.code(), ErrorCode::NotFound | ErrorCode::Ambiguous) {
+            not_found()
+        } else {
+            e.into()
+        }
+    })?;
+    object
+        .peel_to_commit()
+        .map(|commit| commit.id())
+        .map_err(|_| not_found())
+}
+
+/// Update the local annotations from the remote
+pub fn fetch(remote: &mut Remote<'_>) -> Result<()> {
+    let mut callbacks = RemoteCallbacks::new();
+    setup_callbacks(&mut callbacks);
+    callbacks.transfer_progress(|_| !interrupted());
+    let mut opts = FetchOptions::new();
+    opts.remote_callbacks(callbacks);
+    opts.update_fetchhead(false);
+    opts.download_tags(git2::AutotagOption::None);
+
+    with_retry("Fetch annotations", || {
+        remote
+            .fetch(&[NOTES_REFSPEC], Some(&mut opts), None)
+            .map_err(remote_error)
+    })
+}
+
+/// Publish the local annotations (fast-forward only)
+pub fn push(remote: &mut Remote<'_>) -> Result<()> {
+    let mut opts = build_push_options()?;
+    let refspec = format!("{}:{}", NOTES_REF, NOTES_REF);
+    remote
+        .push(&[refspec.as_str()], Some(&mut opts))
+        .map_err(|e| {
+            if e.message().contains("fast-forward") || e.message().contains("fetch first") {
+                JinError::Other(
+                    "Annotations changed on the remote while adding one. \
+                     Run the command again."
+                        .into(),
+                )
+            } else {
+                e.into()
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::ObjectOps;
+    use crate::test_utils::setup_unit_test;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_annotations_accumulate() {
+        let ctx = setup_unit_test();
+        let repo = JinRepo::open_or_create(&ctx.project_path).unwrap();
+        let blob = repo.create_blob(b"{}").unwrap();
+        let tree = repo
+            .create_tree_from_paths(&[("a.json".to_string(), blob)])
+            .unwrap();
+        let commit = repo.create_commit(None, "Add a.json", tree, &[]).unwrap();
+        assert!(read(repo.inner(), commit).is_none());
+
+        annotate(&repo, commit, "deployed to staging", "alice").unwrap();
+        annotate(&repo, commit, "rolled back in prod ", "bob").unwrap();
+        let today = Utc::now().format("%Y-%m-%d");
+        assert_eq!(
+            read(repo.inner(), commit).unwrap(),
+            format!(
+                "deployed to staging (alice, {})\nrolled back in prod (bob, {})",
+                today, today
+            )
+        );
+        assert!(annotate(&repo, commit, "  ", "alice").is_err());
+        assert!(annotate(&repo, commit, "two\nlines", "alice").is_err());
+
+        let short = &commit.to_string()[..7];
+        assert_eq!(resolve_commit(&repo, short).unwrap(), commit);
+        assert!(matches!(
+            resolve_commit(&repo, "0000000"),
+            Err(JinError::NotFound(_))
+        ));
+    }
+}
@@ -94,8 +94,9 @@ pub trait ObjectOps {
     ///
     /// ```no_run
     /// use jin::git::{JinRepo, ObjectOps};
+    /// use std::path::Path;
     ///
-    /// let repo = JinRepo::open()?;
+    /// let repo = JinRepo::open(Path::new("."))?;
     /// let oid = repo.create_blob(b"Hello, Jin!")?;
     /// # Ok::<(), jin::JinError>(())
     /// ```
@@ -126,8 +127,9 @@ pub trait ObjectOps {
     ///
     /// ```no_run
     /// use jin::git::{JinRepo, ObjectOps};
+    /// use std::path::Path;
     ///
-    /// let repo = JinRepo::open()?;
+    /// let repo = JinRepo::open(Path::new("."))?;
     /// let blob1 = repo.create_blob(b"content1")?;
     /// let blob2 = repo.create_blob(b"content2")?;
     ///
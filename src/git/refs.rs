@@ -5,6 +5,8 @@
 
 use crate::core::{JinError, Result};
 use git2::{Oid, Reference};
+use std::collections::BTreeMap;
+use std::path::Path;
 
 use super::JinRepo;
 
@@ -97,12 +99,14 @@ impl RefOps for JinRepo {
 
         // Create or update the reference
         self.inner().reference(name, oid, true, message)?;
+        touch_refs_stamp(self.path());
         Ok(())
     }
 
     fn delete_ref(&self, name: &str) -> Result<()> {
         let mut reference = self.find_ref(name)?;
         reference.delete()?;
+        touch_refs_stamp(self.path());
         Ok(())
     }
 
@@ -176,6 +180,103 @@ pub fn compare_refs(repo: &JinRepo, local_oid: Oid, remote_oid: Oid) -> Result<R
     }
 }
 
+/// Short layer name for a layer ref, as shown to users
+///
+/// Strips `refs/jin/layers/` and the `/_` suffix of layers with children
+/// (`refs/jin/layers/mode/claude/_` -> `mode/claude`).
+pub fn layer_name(ref_path: &str) -> &str {
+    let path = ref_path
+        .strip_prefix("refs/jin/layers/")
+        .unwrap_or(ref_path);
+    path.strip_suffix("/_").unwrap_or(path)
+}
+
+/// File in the repository directory rewritten on every ref update
+pub const REFS_STAMP: &str = "jin-refs-stamp";
+
+/// Record that refs in the repository at `repo_path` changed
+///
+/// Caches of state derived from refs (see `jin prompt`) compare the stamp
+/// instead of opening the repository. Best effort: a failed write only costs
+/// a stale cache until the next update.
+pub fn touch_refs_stamp(repo_path: &Path) {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let _ = std::fs::write(
+        repo_path.join(REFS_STAMP),
+        format!("{}-{}", nanos, std::process::id()),
+    );
+}
+
+/// Current refs stamp of the repository at `repo_path`, empty if refs were
+/// never updated through Jin
+pub fn refs_stamp(repo_path: &Path) -> String {
+    std::fs::read_to_string(repo_path.join(REFS_STAMP)).unwrap_or_default()
+}
+
+/// Commits a set of refs pointed at when a command started planning
+///
+/// Another Jin process (or any Git tool) can move layer refs while a long
+/// command runs. Commands take a snapshot of the refs they read, and check
+/// it right before writing, so they never write a result planned from refs
+/// that have since moved.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RefSnapshot {
+    /// Ref path -> commit it pointed at (`None` if it didn't exist)
+    refs: BTreeMap<String, Option<Oid>>,
+}
+
+impl RefSnapshot {
+    /// Record where each of `ref_paths` points now
+    pub fn take<I, S>(repo: &JinRepo, ref_paths: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let refs = ref_paths
+            .into_iter()
+            .map(|ref_path| {
+                let ref_path = ref_path.into();
+                let oid = current_oid(repo, &ref_path);
+                (ref_path, oid)
+            })
+            .collect();
+        Self { refs }
+    }
+
+    /// Refs that point elsewhere now (created, moved or deleted)
+    pub fn changed(&self, repo: &JinRepo) -> Vec<&str> {
+        self.refs
+            .iter()
+            .filter(|(ref_path, oid)| current_oid(repo, ref_path) != **oid)
+            .map(|(ref_path, _)| ref_path.as_str())
+            .collect()
+    }
+
+    /// Fail with [`JinError::RefsChanged`] if any ref moved since the
+    /// snapshot was taken
+    pub fn ensure_unchanged(&self, repo: &JinRepo, operation: &str) -> Result<()> {
+        let changed = self.changed(repo);
+        if changed.is_empty() {
+            return Ok(());
+        }
+        Err(JinError::RefsChanged {
+            operation: operation.to_string(),
+            refs: changed
+                .iter()
+                .map(|ref_path| layer_name(ref_path))
+                .collect::<Vec<_>>()
+                .join(", "),
+        })
+    }
+}
+
+fn current_oid(repo: &JinRepo, ref_path: &str) -> Option<Oid> {
+    repo.inner().refname_to_id(ref_path).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -403,4 +504,44 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), RefComparison::Diverged);
     }
+
+    #[test]
+    fn test_ref_snapshot_detects_moved_refs() {
+        let (_temp, repo) = create_test_repo();
+        let first = create_test_commit(&repo);
+        repo.set_ref("refs/jin/layers/global", first, "init")
+            .unwrap();
+
+        let snapshot = RefSnapshot::take(
+            &repo,
+            ["refs/jin/layers/global", "refs/jin/layers/mode/claude/_"],
+        );
+        assert!(snapshot.changed(&repo).is_empty());
+        assert!(snapshot.ensure_unchanged(&repo, "jin apply").is_ok());
+
+        // Another process moves one ref and creates the other
+        let sig = git2::Signature::now("Other", "other@example.com").unwrap();
+        let tree = repo.inner().find_commit(first).unwrap().tree().unwrap();
+        let parent = repo.inner().find_commit(first).unwrap();
+        let second = repo
+            .inner()
+            .commit(None, &sig, &sig, "Other change", &tree, &[&parent])
+            .unwrap();
+        repo.set_ref("refs/jin/layers/global", second, "other")
+            .unwrap();
+        repo.set_ref("refs/jin/layers/mode/claude/_", second, "other")
+            .unwrap();
+
+        assert_eq!(
+            snapshot.changed(&repo),
+            vec!["refs/jin/layers/global", "refs/jin/layers/mode/claude/_"]
+        );
+        match snapshot.ensure_unchanged(&repo, "jin apply") {
+            Err(JinError::RefsChanged { operation, refs }) => {
+                assert_eq!(operation, "jin apply");
+                assert_eq!(refs, "global, mode/claude");
+            }
+            other => panic!("expected RefsChanged, got {:?}", other),
+        }
+    }
 }
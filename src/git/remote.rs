@@ -3,11 +3,43 @@
 //! This module provides shared utilities for remote operations (fetch, pull, push)
 //! including authentication callbacks, progress reporting, and option builders.
 
-use crate::core::Result;
-use git2::{Cred, FetchOptions, PushOptions, RemoteCallbacks};
+use super::auth::{setup_auth_callbacks, RemoteAuth};
+use super::executor::{interrupted, Task};
+use crate::core::progress::{self, Event, Operation, Phase};
+use crate::core::{JinError, Result};
+use git2::{FetchOptions, Oid, PushOptions, RemoteCallbacks};
 use std::io::{self, Write};
 use std::sync::{Arc, Mutex};
 
+/// Remote `jin link <url>` configures, used when no remote is named
+pub const DEFAULT_REMOTE: &str = "origin";
+
+/// Prefix of local layer refs
+const LAYER_PREFIX: &str = "refs/jin/layers/";
+
+/// Prefix of the refs tracking `remote`'s layers
+///
+/// `jin fetch` (and the `jin status` freshness check) download the remote's
+/// layer refs to `refs/jin/remote/<remote>/layers/*`; local layers only move
+/// when `jin pull` merges them.
+pub fn tracking_prefix(remote: &str) -> String {
+    format!("refs/jin/remote/{}/layers/", remote)
+}
+
+/// Refspec fetching `remote`'s layers into its tracking namespace
+pub fn tracking_refspec(remote: &str) -> String {
+    format!("+{}*:{}*", LAYER_PREFIX, tracking_prefix(remote))
+}
+
+/// Local layer ref followed by a tracking ref of `remote`
+///
+/// `None` if `tracking_ref` is not one of `remote`'s tracking refs.
+pub fn tracked_layer_ref(remote: &str, tracking_ref: &str) -> Option<String> {
+    tracking_ref
+        .strip_prefix(&tracking_prefix(remote))
+        .map(|layer| format!("{}{}", LAYER_PREFIX, layer))
+}
+
 /// Authentication attempt counter to prevent infinite loops
 #[derive(Debug, Clone)]
 pub struct AuthCounter {
@@ -43,10 +75,10 @@ impl AuthCounter {
 
 /// Setup authentication callbacks for remote operations
 ///
-/// Tries multiple authentication methods in order:
-/// 1. SSH key from SSH agent (most secure, no keys on disk)
-/// 2. Default SSH keys (~/.ssh/id_rsa, ~/.ssh/id_ed25519)
-/// 3. Fails after 3 attempts to prevent infinite loops
+/// Uses the authentication settings of the linked remote (see
+/// [`crate::git::auth`]): by default ssh-agent and the default SSH keys for
+/// SSH remotes, and a token from `$JIN_TOKEN` or a git credential helper for
+/// HTTPS remotes.
 ///
 /// # Example
 ///
@@ -57,84 +89,111 @@ impl AuthCounter {
 /// setup_callbacks(&mut callbacks);
 /// ```
 pub fn setup_callbacks(callbacks: &mut RemoteCallbacks) {
-    let auth_counter = AuthCounter::new();
+    setup_auth_callbacks(callbacks, RemoteAuth::current());
+}
 
-    callbacks.credentials(move |url, username, _allowed| {
-        // For file:// URLs or absolute paths, no authentication is needed
-        if url.starts_with("file://") || url.starts_with('/') {
-            return Cred::default();
-        }
+/// Convert a failed remote operation into a [`JinError`]
+///
+/// Authentication failures become [`JinError::Auth`] so they are never
+/// retried, and transfers aborted by Ctrl-C become [`JinError::Cancelled`].
+pub fn remote_error(e: git2::Error) -> JinError {
+    if interrupted() {
+        JinError::Cancelled
+    } else if e.code() == git2::ErrorCode::Auth {
+        JinError::Auth(e.message().to_string())
+    } else {
+        e.into()
+    }
+}
 
-        if !auth_counter.increment_and_check(3) {
-            return Err(git2::Error::from_str(
-                "Authentication failed after 3 attempts",
-            ));
-        }
+/// Progress event for a fetch, unless it repeats the last one emitted
+///
+/// Objects are counted while they are received, then deltas while they are
+/// resolved.
+fn fetch_event(stats: &git2::Progress<'_>, last: &mut Option<(Phase, usize)>) -> Option<Event> {
+    let (phase, current, total) = if stats.received_objects() < stats.total_objects() {
+        (
+            Phase::Receive,
+            stats.received_objects(),
+            stats.total_objects(),
+        )
+    } else {
+        (Phase::Index, stats.indexed_deltas(), stats.total_deltas())
+    };
+    if *last == Some((phase, current)) {
+        return None;
+    }
+    *last = Some((phase, current));
+    Some(Event::new(Operation::Fetch, phase).count(current, total))
+}
 
-        let username = username.unwrap_or("git");
-
-        // Try SSH agent first (most secure)
-        match Cred::ssh_key_from_agent(username) {
-            Ok(cred) => Ok(cred),
-            Err(_) => {
-                // SSH agent failed, try default keys
-                // Try id_ed25519 first (modern), then id_rsa (legacy)
-                if let Ok(home) = std::env::var("HOME") {
-                    let ssh_dir = std::path::Path::new(&home).join(".ssh");
-
-                    // Try ed25519 key
-                    let ed25519_key = ssh_dir.join("id_ed25519");
-                    if ed25519_key.exists() {
-                        if let Ok(cred) = Cred::ssh_key(username, None, &ed25519_key, None) {
-                            return Ok(cred);
-                        }
-                    }
-
-                    // Try RSA key
-                    let rsa_key = ssh_dir.join("id_rsa");
-                    if rsa_key.exists() {
-                        if let Ok(cred) = Cred::ssh_key(username, None, &rsa_key, None) {
-                            return Ok(cred);
-                        }
-                    }
-                }
-
-                // All authentication methods failed
-                Err(git2::Error::from_str(
-                    "No valid SSH credentials found. Ensure SSH agent is running or SSH keys exist.",
-                ))
+/// Setup transfer progress callback for fetch operations
+///
+/// Displays download progress in the format: "Received X/Y objects (Z%)"
+/// with carriage return for line overwriting, or emits progress events with
+/// `--progress=json`. Aborts the transfer on Ctrl-C.
+pub fn setup_transfer_progress(callbacks: &mut RemoteCallbacks) {
+    let mut last = None;
+    callbacks.transfer_progress(move |stats| {
+        if interrupted() {
+            return false;
+        }
+        if stats.total_objects() > 0 {
+            if let Some(event) = fetch_event(&stats, &mut last) {
+                event.emit();
+            }
+            if progress::human() {
+                let percent = (stats.received_objects() * 100) / stats.total_objects();
+                print!(
+                    "Received {}/{} objects ({}%)\r",
+                    stats.received_objects(),
+                    stats.total_objects(),
+                    percent
+                );
+                io::stdout().flush().unwrap();
             }
         }
+        true // Continue
     });
 }
 
-/// Setup transfer progress callback for fetch operations
+/// Setup progress and cancellation callbacks reporting to an executor task
 ///
-/// Displays download progress in the format: "Received X/Y objects (Z%)"
-/// with carriage return for line overwriting.
-pub fn setup_transfer_progress(callbacks: &mut RemoteCallbacks) {
-    callbacks.transfer_progress(|stats| {
+/// Transfer progress goes to the task's slot of the shared progress line
+/// (and to events naming the task's remote with `--progress=json`), and
+/// returning `false` once the task is cancelled makes git2 abort the
+/// transfer before any ref is updated.
+pub fn setup_task_progress(callbacks: &mut RemoteCallbacks, task: &Task) {
+    let progress = task.clone();
+    let mut last = None;
+    callbacks.transfer_progress(move |stats| {
         if stats.total_objects() > 0 {
-            let percent = (stats.received_objects() * 100) / stats.total_objects();
-            print!(
-                "Received {}/{} objects ({}%)\r",
+            if let Some(event) = fetch_event(&stats, &mut last) {
+                event.remote(progress.name()).emit();
+            }
+            progress.progress(&format!(
+                "Received {}/{} objects ({}%)",
                 stats.received_objects(),
                 stats.total_objects(),
-                percent
-            );
-            io::stdout().flush().unwrap();
+                (stats.received_objects() * 100) / stats.total_objects()
+            ));
         }
-        true // Continue
+        !progress.is_cancelled()
     });
+    let sideband = task.clone();
+    callbacks.sideband_progress(move |_| !sideband.is_cancelled());
 }
 
 /// Setup sideband progress callback for remote messages
 ///
 /// Displays messages from the remote server (e.g., "Compressing objects: 100%")
+/// when human progress is drawn.
 pub fn setup_sideband_progress(callbacks: &mut RemoteCallbacks) {
     callbacks.sideband_progress(|data| {
-        print!("remote: {}", String::from_utf8_lossy(data));
-        io::stdout().flush().unwrap();
+        if progress::human() {
+            print!("remote: {}", String::from_utf8_lossy(data));
+            io::stdout().flush().unwrap();
+        }
         true
     });
 }
@@ -145,7 +204,7 @@ pub fn setup_sideband_progress(callbacks: &mut RemoteCallbacks) {
 pub fn setup_push_update_callback(callbacks: &mut RemoteCallbacks) {
     callbacks.push_update_reference(|refname, status| match status {
         Some(msg) => {
-            eprintln!("Failed to push {}: {}", refname, msg);
+            progress::error(format_args!("Failed to push {}: {}", refname, msg));
             Err(git2::Error::from_str(msg))
         }
         None => {
@@ -179,6 +238,17 @@ pub fn build_fetch_options() -> Result<FetchOptions<'static>> {
     Ok(opts)
 }
 
+/// Build FetchOptions for a fetch running as an executor task
+pub fn build_task_fetch_options(task: &Task) -> FetchOptions<'static> {
+    let mut callbacks = RemoteCallbacks::new();
+    setup_callbacks(&mut callbacks);
+    setup_task_progress(&mut callbacks, task);
+
+    let mut opts = FetchOptions::new();
+    opts.remote_callbacks(callbacks);
+    opts
+}
+
 /// Build PushOptions with all standard callbacks
 ///
 /// Configures authentication and push validation.
@@ -195,6 +265,13 @@ pub fn build_push_options() -> Result<PushOptions<'static>> {
     let mut callbacks = RemoteCallbacks::new();
     setup_callbacks(&mut callbacks);
     setup_push_update_callback(&mut callbacks);
+    callbacks.push_negotiation(|_| {
+        if interrupted() {
+            Err(git2::Error::from_str("cancelled"))
+        } else {
+            Ok(())
+        }
+    });
 
     let mut opts = PushOptions::new();
     opts.remote_callbacks(callbacks);
@@ -202,6 +279,56 @@ pub fn build_push_options() -> Result<PushOptions<'static>> {
     Ok(opts)
 }
 
+/// Leases of a push: remote ref -> commit it must still point at (zero: the
+/// ref must not exist yet)
+pub type Leases = std::collections::HashMap<String, Oid>;
+
+/// Build PushOptions that only update refs the remote still has where the
+/// push expected them (like `git push --force-with-lease`)
+///
+/// The remote reports each ref's current commit during push negotiation; if
+/// one differs from its lease, the push is aborted before anything is
+/// written and the ref is stored in `broken`.
+pub fn build_lease_push_options(
+    leases: Leases,
+    broken: Arc<Mutex<Option<String>>>,
+    auth: &RemoteAuth,
+) -> PushOptions<'static> {
+    let mut callbacks = RemoteCallbacks::new();
+    setup_auth_callbacks(&mut callbacks, auth);
+    setup_push_update_callback(&mut callbacks);
+    let mut last = None;
+    callbacks.push_transfer_progress(move |current, total, _| {
+        if total > 0 && last.replace(current) != Some(current) {
+            Event::new(Operation::Push, Phase::Send)
+                .count(current, total)
+                .emit();
+        }
+    });
+    callbacks.push_negotiation(move |updates| {
+        if interrupted() {
+            return Err(git2::Error::from_str("cancelled"));
+        }
+        for update in updates {
+            let Some(name) = update.dst_refname() else {
+                continue;
+            };
+            if leases.get(name).is_some_and(|lease| *lease != update.src()) {
+                *broken.lock().unwrap_or_else(|e| e.into_inner()) = Some(name.to_string());
+                return Err(git2::Error::from_str(&format!(
+                    "{} changed on the remote",
+                    name
+                )));
+            }
+        }
+        Ok(())
+    });
+
+    let mut opts = PushOptions::new();
+    opts.remote_callbacks(callbacks);
+    opts
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,6 +351,39 @@ mod tests {
         assert_eq!(counter.get(), 4);
     }
 
+    #[test]
+    fn test_remote_error_marks_auth_failures_permanent() {
+        let auth = git2::Error::new(
+            git2::ErrorCode::Auth,
+            git2::ErrorClass::Ssh,
+            "authentication required",
+        );
+        assert!(matches!(remote_error(auth), JinError::Auth(_)));
+
+        let net = git2::Error::new(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Net,
+            "connection refused",
+        );
+        assert!(remote_error(net).is_transient());
+    }
+
+    #[test]
+    fn test_tracking_refs() {
+        assert_eq!(
+            tracking_refspec("origin"),
+            "+refs/jin/layers/*:refs/jin/remote/origin/layers/*"
+        );
+        assert_eq!(
+            tracked_layer_ref("origin", "refs/jin/remote/origin/layers/mode/claude/_"),
+            Some("refs/jin/layers/mode/claude/_".to_string())
+        );
+        assert_eq!(
+            tracked_layer_ref("origin", "refs/jin/remote/backup/layers/global"),
+            None
+        );
+    }
+
     #[test]
     fn test_build_fetch_options() {
         let opts = build_fetch_options();
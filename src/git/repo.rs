@@ -4,9 +4,18 @@
 //! for Jin's phantom Git layer. Jin maintains a bare repository at `~/.jin/`
 //! that stores all layer configurations.
 
-use crate::core::{JinError, Result};
+use crate::core::config::expand_home;
+use crate::core::profile::{self, Phase};
+use crate::core::{JinConfig, JinError, ProjectConfig, Result};
 use git2::{Repository, RepositoryInitOptions};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Name that always refers to the default repository location
+pub const DEFAULT_REPO_NAME: &str = "default";
+
+/// Version of the repository layout (refs under `refs/jin/` and the state
+/// files next to them), bumped when an older Jin could no longer read it
+pub const REPO_VERSION: u32 = 1;
 
 /// Wrapper around `git2::Repository` for Jin's phantom Git layer.
 ///
@@ -18,9 +27,10 @@ use std::path::PathBuf;
 ///
 /// ```no_run
 /// use jin::git::JinRepo;
+/// use std::path::Path;
 ///
 /// // Open or create the Jin repository
-/// let repo = JinRepo::open_or_create()?;
+/// let repo = JinRepo::open_or_create(Path::new("."))?;
 ///
 /// // Access the underlying repository
 /// let inner = repo.inner();
@@ -51,12 +61,13 @@ impl JinRepo {
     ///
     /// ```no_run
     /// use jin::git::JinRepo;
+    /// use std::path::Path;
     ///
-    /// let repo = JinRepo::open()?;
+    /// let repo = JinRepo::open(Path::new("."))?;
     /// # Ok::<(), jin::JinError>(())
     /// ```
-    pub fn open() -> Result<Self> {
-        let path = Self::default_path()?;
+    pub fn open(root: &Path) -> Result<Self> {
+        let path = Self::resolve_path(root)?;
         Self::open_at(&path)
     }
 
@@ -66,6 +77,7 @@ impl JinRepo {
     ///
     /// Returns `JinError::Git` if the repository doesn't exist or is corrupted.
     pub fn open_at(path: &PathBuf) -> Result<Self> {
+        let _span = profile::span(Phase::RepoOpen);
         let repo = Repository::open_bare(path)?;
         Ok(Self {
             repo,
@@ -83,12 +95,13 @@ impl JinRepo {
     ///
     /// ```no_run
     /// use jin::git::JinRepo;
+    /// use std::path::Path;
     ///
-    /// let repo = JinRepo::create()?;
+    /// let repo = JinRepo::create(Path::new("."))?;
     /// # Ok::<(), jin::JinError>(())
     /// ```
-    pub fn create() -> Result<Self> {
-        let path = Self::default_path()?;
+    pub fn create(root: &Path) -> Result<Self> {
+        let path = Self::resolve_path(root)?;
         Self::create_at(&path)
     }
 
@@ -125,12 +138,13 @@ impl JinRepo {
     ///
     /// ```no_run
     /// use jin::git::JinRepo;
+    /// use std::path::Path;
     ///
-    /// let repo = JinRepo::open_or_create()?;
+    /// let repo = JinRepo::open_or_create(Path::new("."))?;
     /// # Ok::<(), jin::JinError>(())
     /// ```
-    pub fn open_or_create() -> Result<Self> {
-        let path = Self::default_path()?;
+    pub fn open_or_create(root: &Path) -> Result<Self> {
+        let path = Self::resolve_path(root)?;
         Self::open_or_create_at(&path)
     }
 
@@ -160,6 +174,46 @@ impl JinRepo {
             .ok_or_else(|| JinError::Config("Cannot determine home directory".into()))
     }
 
+    /// Returns the repository path for the current project.
+    ///
+    /// If the project selects a named repository in `.jin/config.yaml`, the
+    /// path registered for that name in the global config is used. Otherwise
+    /// this is [`JinRepo::default_path`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `JinError::Config` if the selected repository name is not
+    /// registered.
+    pub fn resolve_path(root: &Path) -> Result<PathBuf> {
+        match ProjectConfig::load(root)?.repo {
+            Some(name) => Self::path_for_name(&name),
+            None => Self::default_path(),
+        }
+    }
+
+    /// Returns the path registered for a named repository.
+    ///
+    /// The name `default` always refers to [`JinRepo::default_path`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `JinError::Config` if the name is not registered.
+    pub fn path_for_name(name: &str) -> Result<PathBuf> {
+        if name == DEFAULT_REPO_NAME {
+            return Self::default_path();
+        }
+        JinConfig::load()?
+            .repos
+            .get(name)
+            .map(|p| expand_home(p))
+            .ok_or_else(|| {
+                JinError::Config(format!(
+                    "Unknown Jin repository '{}'. Register it with: jin repo add {} <path>",
+                    name, name
+                ))
+            })
+    }
+
     /// Returns the path to the Jin repository.
     pub fn path(&self) -> &PathBuf {
         &self.path
@@ -0,0 +1,190 @@
+//! Retry with exponential backoff for transient remote failures
+//!
+//! Network hiccups, HTTP 5xx responses and dropped SSH connections are
+//! retried; authentication failures, rejected pushes and everything else
+//! fail at once (see [`crate::core::JinError::is_transient`]). Settings live under
+//! `[retry]` in the global config.
+
+use super::executor::{check_interrupted, interrupted};
+use crate::core::progress;
+use crate::core::{JinConfig, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Retry settings (stored under `[retry]` in the global config)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Attempts per operation, including the first (1 disables retries)
+    #[serde(default = "default_attempts")]
+    pub attempts: u32,
+    /// Delay before the first retry, in milliseconds; doubled for each
+    /// further retry
+    #[serde(default = "default_delay_ms")]
+    pub delay_ms: u64,
+    /// Upper bound for the delay between retries, in milliseconds
+    #[serde(default = "default_max_delay_ms")]
+    pub max_delay_ms: u64,
+}
+
+fn default_attempts() -> u32 {
+    3
+}
+
+fn default_delay_ms() -> u64 {
+    1000
+}
+
+fn default_max_delay_ms() -> u64 {
+    30_000
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            attempts: default_attempts(),
+            delay_ms: default_delay_ms(),
+            max_delay_ms: default_max_delay_ms(),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Settings loaded once per process
+    pub fn current() -> &'static Self {
+        static RETRY: OnceLock<RetryConfig> = OnceLock::new();
+        RETRY.get_or_init(|| {
+            JinConfig::load()
+                .ok()
+                .and_then(|c| c.retry)
+                .unwrap_or_default()
+        })
+    }
+
+    /// Delay before retry number `retry` (starting at 1)
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = 2u64.saturating_pow(retry.saturating_sub(1));
+        Duration::from_millis(self.delay_ms.saturating_mul(factor).min(self.max_delay_ms))
+    }
+}
+
+/// Run a remote operation, retrying transient failures with the configured
+/// backoff
+///
+/// `what` names the operation in the retry warnings ("Fetch from origin").
+pub fn with_retry<T>(what: &str, op: impl FnMut() -> Result<T>) -> Result<T> {
+    retry_with(RetryConfig::current(), what, op)
+}
+
+/// [`with_retry`] with explicit settings
+pub fn retry_with<T>(
+    config: &RetryConfig,
+    what: &str,
+    mut op: impl FnMut() -> Result<T>,
+) -> Result<T> {
+    let attempts = config.attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < attempts && e.is_transient() && !interrupted() => {
+                let delay = config.delay(attempt);
+                progress::warn_on_new_line(format_args!(
+                    "{} failed: {}. Retrying in {:.1}s (attempt {}/{})",
+                    what,
+                    e,
+                    delay.as_secs_f64(),
+                    attempt + 1,
+                    attempts
+                ));
+                sleep(delay)?;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Sleep, waking up early to honor Ctrl-C
+fn sleep(delay: Duration) -> Result<()> {
+    let step = Duration::from_millis(50);
+    let mut left = delay;
+    while !left.is_zero() {
+        check_interrupted()?;
+        let nap = left.min(step);
+        std::thread::sleep(nap);
+        left -= nap;
+    }
+    check_interrupted()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::JinError;
+    use std::cell::Cell;
+
+    fn fast(attempts: u32) -> RetryConfig {
+        RetryConfig {
+            attempts,
+            delay_ms: 1,
+            max_delay_ms: 2,
+        }
+    }
+
+    fn network_error() -> JinError {
+        git2::Error::new(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Net,
+            "failed to connect: Connection timed out",
+        )
+        .into()
+    }
+
+    #[test]
+    fn test_delay_doubles_up_to_max() {
+        let config = RetryConfig {
+            attempts: 5,
+            delay_ms: 500,
+            max_delay_ms: 1500,
+        };
+        assert_eq!(config.delay(1), Duration::from_millis(500));
+        assert_eq!(config.delay(2), Duration::from_millis(1000));
+        assert_eq!(config.delay(3), Duration::from_millis(1500));
+        assert_eq!(config.delay(40), Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn test_transient_failures_are_retried() {
+        let calls = Cell::new(0);
+        let result = retry_with(&fast(3), "fetch", || {
+            calls.set(calls.get() + 1);
+            if calls.get() < 3 {
+                Err(network_error())
+            } else {
+                Ok("done")
+            }
+        });
+        assert_eq!(result.unwrap(), "done");
+        assert_eq!(calls.get(), 3);
+
+        calls.set(0);
+        let result: Result<()> = retry_with(&fast(2), "fetch", || {
+            calls.set(calls.get() + 1);
+            Err(network_error())
+        });
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_permanent_failures_are_not_retried() {
+        let calls = Cell::new(0);
+        let result: Result<()> = retry_with(&fast(3), "push", || {
+            calls.set(calls.get() + 1);
+            Err(JinError::Auth("bad credentials".into()))
+        });
+        assert!(matches!(result, Err(JinError::Auth(_))));
+        assert_eq!(calls.get(), 1);
+    }
+}
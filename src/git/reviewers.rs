@@ -0,0 +1,115 @@
+//! Required-reviewer notes on pushed layer commits
+//!
+//! When `jin push` publishes layer commits touching files a layer's
+//! `.jinowners` assigns (see [`crate::staging::owners`]), it records the
+//! owners as a Git note on each commit:
+//!
+//! ```text
+//! Required-Reviewers: @ops @platform
+//! ```
+//!
+//! Notes live under `refs/notes/jin/reviewers`, outside `refs/jin/layers/*`,
+//! and are pushed to the team remote next to the layers (fast-forward only),
+//! so review tooling on the shared repository can read them with
+//! `git notes --ref=jin/reviewers show <commit>`.
+
+use crate::core::{JinError, Result};
+use crate::git::executor::interrupted;
+use crate::git::remote::{build_push_options, remote_error, setup_callbacks};
+use crate::git::retry::with_retry;
+use crate::git::JinRepo;
+use git2::{FetchOptions, Oid, Remote, RemoteCallbacks, Signature};
+
+/// Notes ref holding required reviewers
+pub const REVIEWERS_REF: &str = "refs/notes/jin/reviewers";
+
+/// Refspec mirroring the remote notes
+const REVIEWERS_REFSPEC: &str = "+refs/notes/jin/reviewers:refs/notes/jin/reviewers";
+
+/// Key of the note line listing reviewers
+const TRAILER: &str = "Required-Reviewers";
+
+/// Reviewers recorded for a commit, if it has a note
+pub fn recorded(repo: &JinRepo, commit: Oid) -> Option<Vec<String>> {
+    let note = repo.inner().find_note(Some(REVIEWERS_REF), commit).ok()?;
+    note.message()?.lines().find_map(|line| {
+        let value = line.strip_prefix(TRAILER)?.strip_prefix(':')?;
+        Some(value.split_whitespace().map(str::to_string).collect())
+    })
+}
+
+/// Record the required reviewers of a commit, replacing any earlier note
+pub fn record(repo: &JinRepo, commit: Oid, reviewers: &[String]) -> Result<()> {
+    let signature = repo
+        .inner()
+        .signature()
+        .or_else(|_| Signature::now("jin", "jin@local"))?;
+    let message = format!("{}: {}\n", TRAILER, reviewers.join(" "));
+    repo.inner().note(
+        &signature,
+        &signature,
+        Some(REVIEWERS_REF),
+        commit,
+        &message,
+        true,
+    )?;
+    Ok(())
+}
+
+/// Update the local notes from the remote
+pub fn fetch(remote: &mut Remote<'_>) -> Result<()> {
+    let mut callbacks = RemoteCallbacks::new();
+    setup_callbacks(&mut callbacks);
+    callbacks.transfer_progress(|_| !interrupted());
+    let mut opts = FetchOptions::new();
+    opts.remote_callbacks(callbacks);
+    opts.update_fetchhead(false);
+    opts.download_tags(git2::AutotagOption::None);
+
+    with_retry("Fetch reviewer notes", || {
+        remote
+            .fetch(&[REVIEWERS_REFSPEC], Some(&mut opts), None)
+            .map_err(remote_error)
+    })
+}
+
+/// Publish the local notes (fast-forward only)
+pub fn push(remote: &mut Remote<'_>) -> Result<()> {
+    let mut opts = build_push_options()?;
+    let refspec = format!("{}:{}", REVIEWERS_REF, REVIEWERS_REF);
+    remote
+        .push(&[refspec.as_str()], Some(&mut opts))
+        .map_err(|e| {
+            if e.message().contains("fast-forward") || e.message().contains("fetch first") {
+                JinError::Other(
+                    "Reviewer notes changed on the remote while pushing. Run 'jin push' again."
+                        .into(),
+                )
+            } else {
+                e.into()
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::ObjectOps;
+    use crate::test_utils::setup_unit_test;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_record_and_read_back() {
+        let ctx = setup_unit_test();
+        let repo = JinRepo::open_or_create(&ctx.project_path).unwrap();
+        let tree = repo.create_tree_from_paths(&[]).unwrap();
+        let commit = repo.create_commit(None, "change", tree, &[]).unwrap();
+        assert_eq!(recorded(&repo, commit), None);
+
+        let reviewers = vec!["@ops".to_string(), "@platform".to_string()];
+        record(&repo, commit, &reviewers).unwrap();
+        assert_eq!(recorded(&repo, commit), Some(reviewers));
+        assert!(repo.inner().find_reference(REVIEWERS_REF).is_ok());
+    }
+}
@@ -22,12 +22,22 @@
 //!
 //! If a crash occurs during the transaction, [`RecoveryManager`] can detect and
 //! recover the incomplete transaction on the next jin command.
+//!
+//! # Transaction IDs
+//!
+//! Transactions are identified by a [ULID](https://github.com/ulid/spec):
+//! 26 Crockford base32 characters whose first 10 encode the start time, so
+//! IDs sort chronologically and stay unique across processes. The log keeps
+//! the full plan (every ref update with its old and new commit, and the
+//! committed files), which is all `jin repair --resume-txn <id>` needs to
+//! finish or roll back an interrupted commit.
 
 use crate::core::{JinError, Layer, Result};
 use git2::{Oid, Signature};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
+use super::refs::touch_refs_stamp;
 use super::JinRepo;
 
 /// Transaction wrapper for atomic reference updates.
@@ -45,8 +55,9 @@ use super::JinRepo;
 ///
 /// ```no_run
 /// use jin::git::{JinRepo, JinTransaction, ObjectOps, TreeEntry};
+/// use std::path::Path;
 ///
-/// let repo = JinRepo::open()?;
+/// let repo = JinRepo::open(Path::new("."))?;
 /// let blob = repo.create_blob(b"content")?;
 /// let tree = repo.create_tree(&[TreeEntry::blob("file.txt", blob)])?;
 /// let commit = repo.create_commit(None, "test", tree, &[])?;
@@ -61,6 +72,7 @@ use super::JinRepo;
 /// ```
 pub struct JinTransaction<'repo> {
     inner: git2::Transaction<'repo>,
+    repo_path: &'repo Path,
 }
 
 impl<'repo> JinTransaction<'repo> {
@@ -71,7 +83,10 @@ impl<'repo> JinTransaction<'repo> {
     /// Returns `JinError::Git` if the transaction cannot be created.
     pub fn new(repo: &'repo JinRepo) -> Result<Self> {
         let inner = repo.inner().transaction()?;
-        Ok(Self { inner })
+        Ok(Self {
+            inner,
+            repo_path: repo.path(),
+        })
     }
 
     /// Locks a reference for update within this transaction.
@@ -138,6 +153,7 @@ impl<'repo> JinTransaction<'repo> {
     /// updates may have already been applied.
     pub fn commit(self) -> Result<()> {
         self.inner.commit()?;
+        touch_refs_stamp(self.repo_path);
         Ok(())
     }
 }
@@ -225,7 +241,7 @@ impl LayerUpdate {
 pub struct TransactionLog {
     /// Version of the log format (for future compatibility)
     pub version: u32,
-    /// Transaction ID (timestamp-based for uniqueness)
+    /// Transaction ID (a ULID, see [`new_transaction_id`])
     pub id: String,
     /// Current state of the transaction
     pub state: TransactionState,
@@ -235,23 +251,27 @@ pub struct TransactionLog {
     pub message: String,
     /// All queued layer updates
     pub updates: Vec<LayerUpdate>,
+    /// Staged files the transaction commits (removed from staging when an
+    /// interrupted transaction is resumed)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
     /// Base path for the transaction log (not serialized)
     #[serde(skip)]
-    base_path: Option<PathBuf>,
+    base_path: PathBuf,
 }
 
 impl TransactionLog {
     /// Create a new transaction log with the given commit message.
-    pub fn new(message: impl Into<String>) -> Self {
-        let id = chrono::Utc::now().format("%Y%m%d%H%M%S%f").to_string();
+    fn new(message: impl Into<String>) -> Self {
         Self {
             version: 1,
-            id,
+            id: new_transaction_id(),
             state: TransactionState::Pending,
             started_at: chrono::Utc::now().to_rfc3339(),
             message: message.into(),
             updates: Vec::new(),
-            base_path: None,
+            files: Vec::new(),
+            base_path: PathBuf::new(),
         }
     }
 
@@ -260,33 +280,30 @@ impl TransactionLog {
     /// The transaction log will be stored at `{base_path}/.jin/.transaction_in_progress`.
     pub fn with_base_path(message: impl Into<String>, base_path: PathBuf) -> Self {
         let mut log = Self::new(message);
-        log.base_path = Some(base_path);
+        log.base_path = base_path;
         log
     }
 
-    /// Returns the default path for the transaction log.
+    /// Returns the path for the transaction log of a workspace.
     ///
-    /// The log is stored at `.jin/.transaction_in_progress` relative to
-    /// the current working directory.
-    pub fn default_path() -> PathBuf {
-        PathBuf::from(".jin").join(".transaction_in_progress")
+    /// The log is stored at `.jin/.transaction_in_progress` under the
+    /// workspace root.
+    pub fn default_path(root: &Path) -> PathBuf {
+        root.join(".jin").join(".transaction_in_progress")
     }
 
-    /// Returns the path for this transaction log, using base_path if set.
+    /// Returns the path for this transaction log.
     fn path(&self) -> PathBuf {
-        match &self.base_path {
-            Some(base) => base.join(".jin").join(".transaction_in_progress"),
-            None => Self::default_path(),
-        }
+        Self::default_path(&self.base_path)
     }
 
-    /// Load transaction log from disk if it exists.
+    /// Load the transaction log of a workspace if it exists.
     ///
     /// # Errors
     ///
     /// Returns error if the file exists but cannot be parsed.
-    pub fn load() -> Result<Option<Self>> {
-        Self::load_from(Self::default_path())
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        Self::load_from(Self::default_path(root))
     }
 
     /// Load transaction log from a specific path.
@@ -301,7 +318,7 @@ impl TransactionLog {
         // Derive base_path from the loaded path
         if let Some(jin_dir) = path.parent() {
             if let Some(base) = jin_dir.parent() {
-                log.base_path = Some(base.to_path_buf());
+                log.base_path = base.to_path_buf();
             }
         }
         Ok(Some(log))
@@ -326,9 +343,9 @@ impl TransactionLog {
         Ok(())
     }
 
-    /// Delete transaction log (called after successful commit or abort).
-    pub fn delete() -> Result<()> {
-        Self::delete_at(Self::default_path())
+    /// Delete the transaction log of a workspace.
+    pub fn delete(root: &Path) -> Result<()> {
+        Self::delete_at(Self::default_path(root))
     }
 
     /// Delete transaction log at a specific path.
@@ -350,6 +367,49 @@ impl TransactionLog {
     }
 }
 
+/// Generate a transaction ID (ULID: 48-bit millisecond timestamp followed by
+/// 80 random bits, in Crockford base32)
+pub fn new_transaction_id() -> String {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let millis = chrono::Utc::now().timestamp_millis().max(0) as u128;
+
+    // RandomState is seeded randomly per process; mixing in a counter keeps
+    // IDs created in the same millisecond distinct
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    hasher.write_u32(std::process::id());
+    let high = hasher.finish();
+    hasher.write_u64(high);
+    let low = hasher.finish();
+    let random = ((high as u128) << 64 | low as u128) & ((1u128 << 80) - 1);
+
+    encode_ulid((millis & ((1u128 << 48) - 1)) << 80 | random)
+}
+
+/// Encode 128 bits as 26 Crockford base32 characters
+fn encode_ulid(mut value: u128) -> String {
+    const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+    let mut out = [0u8; 26];
+    for slot in out.iter_mut().rev() {
+        *slot = ALPHABET[(value & 0x1f) as usize];
+        value >>= 5;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Error for a transaction started while another one is incomplete
+fn incomplete_transaction_error(log: &TransactionLog) -> JinError {
+    JinError::Transaction(format!(
+        "Incomplete transaction exists: {} ('{}').\n\
+         Run 'jin repair --resume-txn {}' to finish it, or add --rollback to undo it.",
+        log.id, log.message, log.id
+    ))
+}
+
 /// Multi-layer atomic transaction with crash recovery.
 ///
 /// Provides true atomic multi-ref updates using a two-phase commit pattern
@@ -374,11 +434,13 @@ impl TransactionLog {
 /// ```no_run
 /// use jin::git::{JinRepo, LayerTransaction};
 /// use jin::core::Layer;
+/// use std::path::Path;
 ///
-/// let repo = JinRepo::open()?;
+/// let root = Path::new(".");
+/// let repo = JinRepo::open(root)?;
 ///
 /// // Begin transaction
-/// let mut tx = LayerTransaction::begin(&repo, "Update mode and project layers")?;
+/// let mut tx = LayerTransaction::begin(&repo, root, "Update mode and project layers")?;
 ///
 /// // Queue updates (commit OIDs would come from actual commits)
 /// // tx.add_layer_update(Layer::ModeBase, Some("claude"), None, None, commit1)?;
@@ -394,7 +456,7 @@ pub struct LayerTransaction<'repo> {
 }
 
 impl<'repo> LayerTransaction<'repo> {
-    /// Begin a new atomic transaction.
+    /// Begin a new atomic transaction in the workspace at `root`.
     ///
     /// Creates a transaction log file to track state for crash recovery.
     ///
@@ -402,41 +464,29 @@ impl<'repo> LayerTransaction<'repo> {
     ///
     /// Returns error if an incomplete transaction already exists.
     /// Use [`RecoveryManager::auto_recover`] to handle incomplete transactions first.
-    pub fn begin(repo: &'repo super::JinRepo, message: impl Into<String>) -> Result<Self> {
+    pub fn begin(
+        repo: &'repo super::JinRepo,
+        root: &Path,
+        message: impl Into<String>,
+    ) -> Result<Self> {
         // Check for existing incomplete transaction
-        if TransactionLog::load()?.is_some() {
-            return Err(JinError::Transaction(
-                "Incomplete transaction exists. Run recovery first.".to_string(),
-            ));
+        if let Some(existing) = TransactionLog::load(root)? {
+            return Err(incomplete_transaction_error(&existing));
         }
 
-        let log = TransactionLog::new(message);
+        let log = TransactionLog::with_base_path(message, root.to_path_buf());
         log.save()?;
 
         Ok(Self { repo, log })
     }
 
-    /// Begin a new atomic transaction with a custom base path for the transaction log.
-    ///
-    /// This is useful for testing or when the project directory differs from
-    /// the current working directory.
+    /// Begin a new atomic transaction with the transaction log under `base_path`.
     pub fn begin_with_path(
         repo: &'repo super::JinRepo,
         message: impl Into<String>,
         base_path: PathBuf,
     ) -> Result<Self> {
-        let log_path = base_path.join(".jin").join(".transaction_in_progress");
-        // Check for existing incomplete transaction
-        if TransactionLog::load_from(log_path)?.is_some() {
-            return Err(JinError::Transaction(
-                "Incomplete transaction exists. Run recovery first.".to_string(),
-            ));
-        }
-
-        let log = TransactionLog::with_base_path(message, base_path);
-        log.save()?;
-
-        Ok(Self { repo, log })
+        Self::begin(repo, &base_path, message)
     }
 
     /// Add a layer update to the transaction.
@@ -485,6 +535,49 @@ impl<'repo> LayerTransaction<'repo> {
         Ok(())
     }
 
+    /// Add an update of a layer ref given by its path.
+    ///
+    /// For refs discovered by listing (e.g. the remote's layers in
+    /// `jin pull`) rather than built from a context. Mode, scope and
+    /// project are not recorded; rollback and recovery only use the path.
+    ///
+    /// # Errors
+    ///
+    /// Returns `JinError::InvalidLayer` if `ref_path` is not a layer ref.
+    pub fn add_ref_update(&mut self, ref_path: &str, new_commit: Oid) -> Result<()> {
+        use super::refs::RefOps;
+
+        let layer = Layer::parse_layer_from_ref_path(ref_path)
+            .ok_or_else(|| JinError::InvalidLayer(format!("Not a layer ref: {}", ref_path)))?;
+        let old_oid = if self.repo.ref_exists(ref_path) {
+            Some(self.repo.resolve_ref(ref_path)?)
+        } else {
+            None
+        };
+
+        self.log.updates.push(LayerUpdate {
+            layer,
+            mode: None,
+            scope: None,
+            project: None,
+            ref_path: ref_path.to_string(),
+            old_oid: old_oid.map(|o| o.to_string()),
+            new_oid: new_commit.to_string(),
+        });
+        self.log.save()?;
+
+        Ok(())
+    }
+
+    /// Record the staged files this transaction commits.
+    ///
+    /// Resuming an interrupted transaction removes them from staging, as
+    /// the finished commit would have.
+    pub fn record_files(&mut self, files: Vec<String>) -> Result<()> {
+        self.log.files = files;
+        self.log.save()
+    }
+
     /// Commit the transaction atomically.
     ///
     /// Uses git2::Transaction for the actual ref updates, with our
@@ -594,32 +687,42 @@ impl<'repo> LayerTransaction<'repo> {
 pub struct RecoveryManager;
 
 impl RecoveryManager {
-    /// Detect if there's an incomplete transaction.
+    /// Detect if there's an incomplete transaction in the workspace at `root`.
     ///
     /// Returns [`IncompleteTransaction`] if a transaction log exists.
-    pub fn detect() -> Result<Option<IncompleteTransaction>> {
-        match TransactionLog::load()? {
+    pub fn detect(root: &Path) -> Result<Option<IncompleteTransaction>> {
+        match TransactionLog::load(root)? {
             Some(log) => Ok(Some(IncompleteTransaction { log })),
             None => Ok(None),
         }
     }
 
-    /// Detect if there's an incomplete transaction at a specific path.
-    pub fn detect_at(base_path: &Path) -> Result<Option<IncompleteTransaction>> {
-        let path = base_path.join(".jin").join(".transaction_in_progress");
-        match TransactionLog::load_from(path)? {
-            Some(log) => Ok(Some(IncompleteTransaction { log })),
-            None => Ok(None),
+    /// Find the incomplete transaction with the given ID.
+    ///
+    /// A unique prefix of the ID is accepted.
+    pub fn find(root: &Path, id: &str) -> Result<IncompleteTransaction> {
+        match Self::detect(root)? {
+            Some(incomplete) if !id.is_empty() && incomplete.id().starts_with(id) => Ok(incomplete),
+            Some(incomplete) => Err(JinError::NotFound(format!(
+                "Transaction {} (the incomplete transaction is {})",
+                id,
+                incomplete.id()
+            ))),
+            None => Err(JinError::NotFound(format!(
+                "Transaction {} (no incomplete transaction)",
+                id
+            ))),
         }
     }
 
-    /// Check for and automatically handle incomplete transactions.
+    /// Check for and automatically handle incomplete transactions in the
+    /// workspace at `root`.
     ///
     /// Default strategy is to rollback incomplete transactions.
     ///
     /// Returns `true` if a recovery was performed.
-    pub fn auto_recover(repo: &super::JinRepo) -> Result<bool> {
-        match Self::detect()? {
+    pub fn auto_recover(repo: &super::JinRepo, root: &Path) -> Result<bool> {
+        match Self::detect(root)? {
             Some(incomplete) => {
                 // Strategy: rollback incomplete transactions
                 // Alternative: could attempt resume for Prepared state
@@ -629,17 +732,6 @@ impl RecoveryManager {
             None => Ok(false),
         }
     }
-
-    /// Check for and automatically handle incomplete transactions at a specific path.
-    pub fn auto_recover_at(repo: &super::JinRepo, base_path: &Path) -> Result<bool> {
-        match Self::detect_at(base_path)? {
-            Some(incomplete) => {
-                incomplete.rollback(repo)?;
-                Ok(true)
-            }
-            None => Ok(false),
-        }
-    }
 }
 
 /// An incomplete transaction detected during recovery.
@@ -681,6 +773,31 @@ impl IncompleteTransaction {
         &self.log.updates
     }
 
+    /// Get the staged files the transaction commits.
+    pub fn files(&self) -> &[String] {
+        &self.log.files
+    }
+
+    /// Refs that moved to a commit outside the transaction since it started.
+    ///
+    /// Resuming would overwrite those commits, so [`Self::resume`] refuses.
+    pub fn moved_refs(&self, repo: &super::JinRepo) -> Vec<String> {
+        use super::refs::RefOps;
+
+        self.log
+            .updates
+            .iter()
+            .filter(|update| {
+                let current = repo
+                    .resolve_ref(&update.ref_path)
+                    .ok()
+                    .map(|oid| oid.to_string());
+                current != update.old_oid && current.as_ref() != Some(&update.new_oid)
+            })
+            .map(|update| update.ref_path.clone())
+            .collect()
+    }
+
     /// Rollback the incomplete transaction.
     ///
     /// Restores all refs to their previous values (best effort).
@@ -705,17 +822,27 @@ impl IncompleteTransaction {
 
     /// Resume the incomplete transaction (attempt to complete it).
     ///
-    /// Only valid if state is Prepared (updates were in progress).
+    /// Only valid if state is Prepared (updates were in progress). Updates
+    /// already applied before the interruption are simply applied again.
     ///
     /// # Errors
     ///
-    /// Returns error if state is not Prepared, or if resume fails.
+    /// Returns error if state is not Prepared, if a ref moved to a commit
+    /// outside the transaction, or if resume fails.
     pub fn resume(self, repo: &super::JinRepo) -> Result<()> {
         if self.log.state != TransactionState::Prepared {
             return Err(JinError::Transaction(
                 "Can only resume transactions in Prepared state".to_string(),
             ));
         }
+        let moved = self.moved_refs(repo);
+        if !moved.is_empty() {
+            return Err(JinError::Transaction(format!(
+                "Cannot resume transaction {}: {} moved since it started",
+                self.log.id,
+                moved.join(", ")
+            )));
+        }
 
         let mut tx = JinTransaction::new(repo)?;
 
@@ -995,6 +1122,26 @@ mod tests {
             .is_none());
     }
 
+    #[test]
+    fn test_layer_transaction_ref_update() {
+        let (_temp, repo, base_path) = setup_layer_transaction_test();
+        cleanup_transaction_log_at(&base_path);
+
+        let commit_oid = create_test_commit(&repo);
+
+        let mut tx =
+            LayerTransaction::begin_with_path(&repo, "ref update test", base_path.clone()).unwrap();
+        tx.add_ref_update("refs/jin/layers/mode/claude/scope/api/_", commit_oid)
+            .unwrap();
+        assert!(tx.add_ref_update("refs/heads/main", commit_oid).is_err());
+        tx.commit().unwrap();
+
+        let resolved = repo
+            .resolve_ref("refs/jin/layers/mode/claude/scope/api/_")
+            .unwrap();
+        assert_eq!(resolved, commit_oid);
+    }
+
     #[test]
     fn test_layer_transaction_multi_layer() {
         let (_temp, repo, base_path) = setup_layer_transaction_test();
@@ -1118,14 +1265,14 @@ mod tests {
         cleanup_transaction_log_at(&base_path);
 
         // No transaction - nothing to detect
-        assert!(RecoveryManager::detect_at(&base_path).unwrap().is_none());
+        assert!(RecoveryManager::detect(&base_path).unwrap().is_none());
 
         // Create a transaction log manually
         let log = TransactionLog::with_base_path("incomplete test", base_path.clone());
         log.save().unwrap();
 
         // Now should detect
-        let incomplete = RecoveryManager::detect_at(&base_path).unwrap();
+        let incomplete = RecoveryManager::detect(&base_path).unwrap();
         assert!(incomplete.is_some());
         let incomplete = incomplete.unwrap();
         assert_eq!(incomplete.message(), "incomplete test");
@@ -1168,7 +1315,7 @@ mod tests {
         );
 
         // Recover - should rollback to old_commit
-        let incomplete = RecoveryManager::detect_at(&base_path).unwrap().unwrap();
+        let incomplete = RecoveryManager::detect(&base_path).unwrap().unwrap();
         incomplete.rollback(&repo).unwrap();
 
         // Ref should be restored to old value
@@ -1207,7 +1354,7 @@ mod tests {
         assert!(!repo.ref_exists("refs/jin/layers/global"));
 
         // Resume should complete the transaction
-        let incomplete = RecoveryManager::detect_at(&base_path).unwrap().unwrap();
+        let incomplete = RecoveryManager::detect(&base_path).unwrap().unwrap();
         incomplete.resume(&repo).unwrap();
 
         // Ref should now exist
@@ -1223,6 +1370,61 @@ mod tests {
             .is_none());
     }
 
+    #[test]
+    fn test_recovery_resume_refuses_moved_refs() {
+        let (_temp, repo, base_path) = setup_layer_transaction_test();
+        cleanup_transaction_log_at(&base_path);
+
+        let planned = create_test_commit(&repo);
+        let other = repo
+            .create_commit(
+                None,
+                "someone else",
+                repo.find_commit(planned).unwrap().tree_id(),
+                &[planned],
+            )
+            .unwrap();
+        repo.set_ref("refs/jin/layers/global", other, "moved")
+            .unwrap();
+
+        let mut log = TransactionLog::with_base_path("moved test", base_path.clone());
+        log.updates.push(LayerUpdate::new(
+            Layer::GlobalBase,
+            None,
+            None,
+            None,
+            None,
+            planned,
+        ));
+        log.state = TransactionState::Prepared;
+        log.save().unwrap();
+
+        let incomplete = RecoveryManager::detect(&base_path).unwrap().unwrap();
+        assert_eq!(incomplete.moved_refs(&repo), vec!["refs/jin/layers/global"]);
+        let err = incomplete.resume(&repo).unwrap_err();
+        assert!(err.to_string().contains("moved since it started"));
+        assert_eq!(repo.resolve_ref("refs/jin/layers/global").unwrap(), other);
+
+        cleanup_transaction_log_at(&base_path);
+    }
+
+    #[test]
+    fn test_transaction_ids_are_ulids() {
+        let first = new_transaction_id();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let second = new_transaction_id();
+
+        assert_eq!(first.len(), 26);
+        assert!(first
+            .chars()
+            .all(|c| c.is_ascii_digit() || (c.is_ascii_uppercase() && !"ILOU".contains(c))));
+        assert_ne!(first, second);
+        // The timestamp prefix makes IDs sort chronologically
+        assert!(first < second);
+        assert_eq!(encode_ulid(0), "0".repeat(26));
+        assert_eq!(encode_ulid(u128::MAX), format!("7{}", "Z".repeat(25)));
+    }
+
     #[test]
     fn test_recovery_resume_requires_prepared_state() {
         let (_temp, repo, base_path) = setup_layer_transaction_test();
@@ -1233,7 +1435,7 @@ mod tests {
         log.save().unwrap();
 
         // Try to resume - should fail
-        let incomplete = RecoveryManager::detect_at(&base_path).unwrap().unwrap();
+        let incomplete = RecoveryManager::detect(&base_path).unwrap().unwrap();
         let result = incomplete.resume(&repo);
         assert!(result.is_err());
         if let Err(JinError::Transaction(msg)) = result {
@@ -1249,14 +1451,14 @@ mod tests {
         cleanup_transaction_log_at(&base_path);
 
         // No transaction - no recovery needed
-        assert!(!RecoveryManager::auto_recover_at(&repo, &base_path).unwrap());
+        assert!(!RecoveryManager::auto_recover(&repo, &base_path).unwrap());
 
         // Create incomplete transaction
         let log = TransactionLog::with_base_path("auto recover test", base_path.clone());
         log.save().unwrap();
 
         // Auto recover should clean it up
-        assert!(RecoveryManager::auto_recover_at(&repo, &base_path).unwrap());
+        assert!(RecoveryManager::auto_recover(&repo, &base_path).unwrap());
 
         // Log should be deleted
         assert!(TransactionLog::load_from(log_path_for(&base_path))
@@ -1290,7 +1492,7 @@ mod tests {
         ));
         log.save().unwrap();
 
-        let incomplete = RecoveryManager::detect_at(&base_path).unwrap().unwrap();
+        let incomplete = RecoveryManager::detect(&base_path).unwrap().unwrap();
         assert_eq!(incomplete.state(), TransactionState::Pending);
         assert_eq!(incomplete.message(), "inspection test");
         assert_eq!(incomplete.update_count(), 2);
@@ -3,6 +3,7 @@
 //! This module provides [`TreeOps`], a trait for traversing and reading
 //! Git tree contents in Jin's phantom repository.
 
+use crate::core::profile::{self, Phase};
 use crate::core::Result;
 use git2::{ObjectType, Oid, TreeEntry as Git2TreeEntry, TreeWalkMode, TreeWalkResult};
 use std::path::Path;
@@ -24,8 +25,9 @@ pub trait TreeOps {
     ///
     /// ```no_run
     /// use jin::git::{JinRepo, ObjectOps, TreeOps, TreeWalkResult};
+    /// use std::path::Path;
     ///
-    /// let repo = JinRepo::open()?;
+    /// let repo = JinRepo::open(Path::new("."))?;
     /// // ... create some commits ...
     /// # let tree_oid = git2::Oid::zero();
     ///
@@ -59,8 +61,9 @@ pub trait TreeOps {
     ///
     /// ```no_run
     /// use jin::git::{JinRepo, ObjectOps, TreeOps};
+    /// use std::path::Path;
     ///
-    /// let repo = JinRepo::open()?;
+    /// let repo = JinRepo::open(Path::new("."))?;
     /// let blob_oid = repo.create_blob(b"Hello!")?;
     /// let content = repo.read_blob_content(blob_oid)?;
     /// assert_eq!(content, b"Hello!");
@@ -83,8 +86,9 @@ pub trait TreeOps {
     ///
     /// ```no_run
     /// use jin::git::{JinRepo, ObjectOps, TreeOps, TreeEntry};
+    /// use std::path::Path;
     ///
-    /// let repo = JinRepo::open()?;
+    /// let repo = JinRepo::open(Path::new("."))?;
     /// let blob1 = repo.create_blob(b"content1")?;
     /// let blob2 = repo.create_blob(b"content2")?;
     /// let tree = repo.create_tree_from_paths(&[
@@ -136,6 +140,7 @@ impl TreeOps for JinRepo {
     }
 
     fn list_tree_files(&self, tree_oid: Oid) -> Result<Vec<String>> {
+        let _span = profile::span(Phase::TreeWalk);
         let mut files = Vec::new();
 
         self.walk_tree_pre(tree_oid, |parent_path, entry| {
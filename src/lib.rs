@@ -19,17 +19,38 @@ pub mod commit;
 pub mod core;
 pub mod git;
 pub mod merge;
+pub mod notify;
 pub mod staging;
+pub mod store;
 
 // Test utilities (only available when building tests)
 #[cfg(test)]
 pub mod test_utils;
 
+// Isolated Jin environments for downstream test suites
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+
 // Re-export commonly used types
 pub use core::error::{JinError, Result};
 pub use core::layer::Layer;
 
 /// Execute the Jin CLI with the parsed arguments
-pub fn run(cli: cli::Cli) -> anyhow::Result<()> {
-    commands::execute(cli).map_err(|e| anyhow::anyhow!("{}", e))
+///
+/// Errors with a code end with a pointer to `jin explain`; on success the
+/// command's exit status is returned. With `--progress=json` the error is
+/// reported as an `error` event, so stderr stays one JSON object per line.
+pub fn run(cli: cli::Cli) -> anyhow::Result<std::process::ExitCode> {
+    match commands::execute(cli) {
+        Err(e) if core::progress::is_json() => {
+            core::progress::error(&e);
+            Ok(std::process::ExitCode::FAILURE)
+        }
+        result => result.map_err(|e| match e.code() {
+            Some(code) => {
+                anyhow::anyhow!("{}\n\nFor causes and fixes, run 'jin explain {}'", e, code)
+            }
+            None => anyhow::anyhow!("{}", e),
+        }),
+    }
 }
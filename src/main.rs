@@ -22,7 +22,7 @@ fn reset_sigpipe() {
     // Windows handles broken pipes differently via error codes
 }
 
-fn main() -> anyhow::Result<()> {
+fn main() -> anyhow::Result<std::process::ExitCode> {
     // Reset SIGPIPE BEFORE any other initialization
     // This must be called before CLI parsing to catch all stdout writes
     reset_sigpipe();
@@ -0,0 +1,403 @@
+//! Cached merge results for recently used contexts
+//!
+//! Switching between a handful of modes and scopes re-merges the same layers
+//! over and over. Every merge `jin apply` runs is kept in
+//! `.jin/cache/merge/`, one file per context (mode, scope and project),
+//! for the [`MAX_ENTRIES`] most recently used contexts. The next merge of
+//! a context reuses its entry if nothing the merge read has changed since:
+//!
+//! - the commit every merged layer ref points at, so commits, pulls and
+//!   resets invalidate it
+//! - the global and project configuration, which hold merge settings
+//! - the workspace `.jinattributes` and `.jinattributes.local`, whose `eol`
+//!   rules decide how text merges treat line endings
+//! - which user-local overrides have expired
+//! - which modes and scopes are archived
+//! - the Jin version that wrote the entry
+//!
+//! Warnings the merge printed are stored with the entry and printed again
+//! when it is reused. The cache is best effort: unreadable or stale entries are merged again,
+//! and failures to write one are ignored.
+
+use super::format::registry;
+use super::layer::{merge_layers, FileFormat, LayerMergeConfig, LayerMergeResult, MergedFile};
+use super::{MergeDrivers, MergeValue};
+use crate::core::progress;
+use crate::core::{JinConfig, Layer, LocalExpiry, ProjectConfig, Result};
+use crate::git::archive::ARCHIVE_REF;
+use crate::git::JinRepo;
+use crate::staging::attributes::{ATTRIBUTES_PATH, LOCAL_ATTRIBUTES_PATH};
+use crate::store::LayerStore;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Contexts kept in the cache; the least recently used is dropped first
+pub const MAX_ENTRIES: usize = 5;
+
+/// One merged file as stored
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedFile {
+    content: MergeValue,
+    source_layers: Vec<Layer>,
+    format: String,
+    bom: bool,
+}
+
+/// The merge of one context
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Context the merge was for
+    context: String,
+    /// Everything else the merge read (see the module docs)
+    fingerprint: String,
+    files: HashMap<PathBuf, CachedFile>,
+    conflict_files: Vec<PathBuf>,
+    added_files: Vec<PathBuf>,
+    removed_files: Vec<PathBuf>,
+    #[serde(default)]
+    warnings: Vec<String>,
+}
+
+/// Merge layers for `config`, reusing the cached result when it is still
+/// valid
+///
+/// Returns the result and whether it came from the cache.
+pub fn merge_cached(config: &LayerMergeConfig, repo: &JinRepo) -> Result<(LayerMergeResult, bool)> {
+    // External drivers may give a different answer for the same input
+    if !MergeDrivers::current().is_empty() {
+        return Ok((merge_layers(config, repo)?, false));
+    }
+    let context = context_key(config);
+    let fingerprint = fingerprint(config, repo)?;
+    let path = entry_path(&config.root, &context);
+    if let Some(result) = load(&path, &context, &fingerprint) {
+        touch(&path);
+        for warning in &result.warnings {
+            progress::warn(warning);
+        }
+        return Ok((result, true));
+    }
+
+    let result = merge_layers(config, repo)?;
+    save(&path, &context, &fingerprint, &result);
+    Ok((result, false))
+}
+
+/// Directory of the cache in a workspace (`.jin/cache/merge`)
+pub fn cache_dir(root: &Path) -> PathBuf {
+    root.join(".jin").join("cache").join("merge")
+}
+
+fn context_key(config: &LayerMergeConfig) -> String {
+    format!(
+        "{}|{}|{}",
+        config.mode.as_deref().unwrap_or_default(),
+        config.scope.as_deref().unwrap_or_default(),
+        config.project.as_deref().unwrap_or_default()
+    )
+}
+
+fn entry_path(root: &Path, context: &str) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    context.hash(&mut hasher);
+    cache_dir(root).join(format!("{:016x}.json", hasher.finish()))
+}
+
+/// Everything besides the context a merge depends on
+fn fingerprint(config: &LayerMergeConfig, repo: &JinRepo) -> Result<String> {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    repo.path().hash(&mut hasher);
+    for layer in &config.layers {
+        let layer_ref = layer.ref_path(
+            config.mode.as_deref(),
+            config.scope.as_deref(),
+            config.project.as_deref(),
+        );
+        let head = repo.head(&layer_ref)?;
+        (layer_ref, head.as_ref().map(|r| r.as_str())).hash(&mut hasher);
+    }
+    let global = JinConfig::default_path().ok();
+    for config_path in global
+        .iter()
+        .chain([&ProjectConfig::default_path(&config.root)])
+    {
+        std::fs::read(config_path).ok().hash(&mut hasher);
+    }
+    for attributes_path in [ATTRIBUTES_PATH, LOCAL_ATTRIBUTES_PATH] {
+        std::fs::read(config.root.join(attributes_path))
+            .ok()
+            .hash(&mut hasher);
+    }
+    repo.head(ARCHIVE_REF)?
+        .as_ref()
+        .map(|r| r.as_str())
+        .hash(&mut hasher);
+    if config.layers.contains(&Layer::UserLocal) {
+        for (path, _) in LocalExpiry::load_or_default(repo.path()).expired(Utc::now()) {
+            path.hash(&mut hasher);
+        }
+    }
+    Ok(format!("{:016x}", hasher.finish()))
+}
+
+fn load(path: &Path, context: &str, fingerprint: &str) -> Option<LayerMergeResult> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+    if entry.context != context || entry.fingerprint != fingerprint {
+        return None;
+    }
+    let merged_files = entry
+        .files
+        .into_iter()
+        .map(|(path, file)| {
+            let format = parse_format(&file.format, &path)?;
+            let merged = MergedFile {
+                content: file.content,
+                source_layers: file.source_layers,
+                format,
+                bom: file.bom,
+            };
+            Some((path, merged))
+        })
+        .collect::<Option<_>>()?;
+    Some(LayerMergeResult {
+        merged_files,
+        conflict_files: entry.conflict_files,
+        added_files: entry.added_files,
+        removed_files: entry.removed_files,
+        warnings: entry.warnings,
+    })
+}
+
+fn save(path: &Path, context: &str, fingerprint: &str, result: &LayerMergeResult) {
+    // Non-finite floats don't survive JSON; such merges are never cached
+    if result.merged_files.values().any(|f| !is_finite(&f.content)) {
+        return;
+    }
+    let entry = CacheEntry {
+        context: context.to_string(),
+        fingerprint: fingerprint.to_string(),
+        files: result
+            .merged_files
+            .iter()
+            .map(|(path, file)| {
+                let cached = CachedFile {
+                    content: file.content.clone(),
+                    source_layers: file.source_layers.clone(),
+                    format: format_name(file.format).to_string(),
+                    bom: file.bom,
+                };
+                (path.clone(), cached)
+            })
+            .collect(),
+        conflict_files: result.conflict_files.clone(),
+        added_files: result.added_files.clone(),
+        removed_files: result.removed_files.clone(),
+        warnings: result.warnings.clone(),
+    };
+    let Ok(content) = serde_json::to_string(&entry) else {
+        return;
+    };
+    let Some(dir) = path.parent() else {
+        return;
+    };
+    if std::fs::create_dir_all(dir).is_err() {
+        return;
+    }
+    let temp_path = path.with_extension("json.tmp");
+    if std::fs::write(&temp_path, content).is_ok() && std::fs::rename(&temp_path, path).is_ok() {
+        prune(dir);
+    }
+}
+
+/// Mark an entry as just used
+fn touch(path: &Path) {
+    if let Ok(file) = std::fs::File::options().append(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+/// Drop the least recently used entries in `dir` beyond [`MAX_ENTRIES`]
+fn prune(dir: &Path) {
+    let Ok(dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<(SystemTime, PathBuf)> = dir
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|p| Some((std::fs::metadata(&p).ok()?.modified().ok()?, p)))
+        .collect();
+    entries.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    for (_, path) in entries.into_iter().skip(MAX_ENTRIES) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+fn format_name(format: FileFormat) -> &'static str {
+    match format {
+        FileFormat::Json => "json",
+        FileFormat::Yaml => "yaml",
+        FileFormat::Toml => "toml",
+        FileFormat::Ini => "ini",
+        FileFormat::Text => "text",
+        FileFormat::Custom(_) => "custom",
+    }
+}
+
+/// The format a stored name stands for; custom formats are detected from
+/// the path again, as they were by the merge
+fn parse_format(name: &str, path: &Path) -> Option<FileFormat> {
+    match name {
+        "json" => Some(FileFormat::Json),
+        "yaml" => Some(FileFormat::Yaml),
+        "toml" => Some(FileFormat::Toml),
+        "ini" => Some(FileFormat::Ini),
+        "text" => Some(FileFormat::Text),
+        "custom" => Some(registry().detect(path)).filter(|f| matches!(f, FileFormat::Custom(_))),
+        _ => None,
+    }
+}
+
+fn is_finite(value: &MergeValue) -> bool {
+    match value {
+        MergeValue::Float(f) => f.is_finite(),
+        MergeValue::Array(items) => items.iter().all(is_finite),
+        MergeValue::Object(map) => map.values().all(is_finite),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::ObjectOps;
+    use serial_test::serial;
+
+    fn commit_global(repo: &JinRepo, content: &[u8]) {
+        commit_layer(repo, "refs/jin/layers/global", content);
+    }
+
+    fn commit_layer(repo: &JinRepo, layer_ref: &str, content: &[u8]) {
+        let blob = repo.create_blob(content).unwrap();
+        let tree = repo
+            .create_tree_from_paths(&[("app.json".to_string(), blob)])
+            .unwrap();
+        let commit = repo.create_commit(None, "update", tree, &[]).unwrap();
+        repo.inner()
+            .reference(layer_ref, commit, true, "test")
+            .unwrap();
+    }
+
+    fn merged_app(result: &LayerMergeResult) -> String {
+        result.merged_files[Path::new("app.json")]
+            .serialize()
+            .unwrap()
+    }
+
+    #[test]
+    #[serial]
+    fn test_cache_hit_until_a_layer_moves() {
+        let ctx = crate::test_utils::setup_unit_test();
+        let repo = JinRepo::open_or_create_at(&ctx.jin_dir).unwrap();
+        commit_global(&repo, br#"{"a": 1}"#);
+        let config = LayerMergeConfig {
+            layers: vec![Layer::GlobalBase],
+            mode: None,
+            scope: None,
+            project: None,
+            root: ctx.project_path.clone(),
+        };
+
+        let (first, hit) = merge_cached(&config, &repo).unwrap();
+        assert!(!hit);
+        let (second, hit) = merge_cached(&config, &repo).unwrap();
+        assert!(hit);
+        assert_eq!(merged_app(&first), merged_app(&second));
+        assert_eq!(
+            second.merged_files[Path::new("app.json")].source_layers,
+            [Layer::GlobalBase]
+        );
+
+        commit_global(&repo, br#"{"a": 2}"#);
+        let (third, hit) = merge_cached(&config, &repo).unwrap();
+        assert!(!hit);
+        assert!(merged_app(&third).contains('2'));
+    }
+
+    #[test]
+    #[serial]
+    fn test_cache_miss_when_local_attributes_change() {
+        let ctx = crate::test_utils::setup_unit_test();
+        let repo = JinRepo::open_or_create_at(&ctx.jin_dir).unwrap();
+        commit_global(&repo, br#"{"a": 1}"#);
+        let config = LayerMergeConfig {
+            layers: vec![Layer::GlobalBase],
+            mode: None,
+            scope: None,
+            project: None,
+            root: ctx.project_path.clone(),
+        };
+
+        merge_cached(&config, &repo).unwrap();
+        assert!(merge_cached(&config, &repo).unwrap().1);
+
+        // A new eol rule changes how text merges treat line endings
+        std::fs::write(LOCAL_ATTRIBUTES_PATH, "*.txt eol=crlf\n").unwrap();
+        assert!(!merge_cached(&config, &repo).unwrap().1);
+        assert!(merge_cached(&config, &repo).unwrap().1);
+    }
+
+    #[test]
+    #[serial]
+    fn test_cache_hit_keeps_final_key_warnings() {
+        let ctx = crate::test_utils::setup_unit_test();
+        let repo = JinRepo::open_or_create_at(&ctx.jin_dir).unwrap();
+        commit_global(&repo, br#"{"tls": true, "$final": ["tls"]}"#);
+        commit_layer(&repo, "refs/jin/layers/project/app", br#"{"tls": false}"#);
+        let config = LayerMergeConfig {
+            layers: vec![Layer::GlobalBase, Layer::ProjectBase],
+            mode: None,
+            scope: None,
+            project: Some("app".to_string()),
+            root: ctx.project_path.clone(),
+        };
+
+        let (first, hit) = merge_cached(&config, &repo).unwrap();
+        assert!(!hit);
+        assert_eq!(first.warnings.len(), 1, "{:?}", first.warnings);
+        let (second, hit) = merge_cached(&config, &repo).unwrap();
+        assert!(hit);
+        assert_eq!(second.warnings, first.warnings);
+    }
+
+    #[test]
+    #[serial]
+    fn test_prune_keeps_recent_contexts() {
+        let ctx = crate::test_utils::setup_unit_test();
+        let repo = JinRepo::open_or_create_at(&ctx.jin_dir).unwrap();
+        commit_global(&repo, br#"{"a": 1}"#);
+        for i in 0..MAX_ENTRIES + 2 {
+            let config = LayerMergeConfig {
+                layers: vec![Layer::GlobalBase],
+                mode: Some(format!("mode{}", i)),
+                scope: None,
+                project: None,
+                root: ctx.project_path.clone(),
+            };
+            merge_cached(&config, &repo).unwrap();
+        }
+        assert_eq!(
+            std::fs::read_dir(cache_dir(&ctx.project_path))
+                .unwrap()
+                .count(),
+            MAX_ENTRIES
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Where `.jinmerge` files live
+//!
+//! By default a conflicted file gets a `<file>.jinmerge` sibling in the
+//! workspace. Those siblings can break builds and watchers that pick up
+//! every file in a directory, so a project can keep them out of the
+//! workspace instead:
+//!
+//! ```text
+//! jin config set apply.conflict_dir true
+//! ```
+//!
+//! Conflict files are then written to `.jin/conflicts/<hash>-<name>.jinmerge`,
+//! where `<hash>` is derived from the file's workspace path, and
+//! `.jin/conflicts/index.json` maps each one back to the file it resolves.
+//! `jin status` and `jin resolve` consult the index first, so conflicts
+//! created before the setting changed are still found.
+
+use crate::core::{JinError, ProjectConfig, Result};
+use crate::merge::jinmerge::JinMergeConflict;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Directory holding conflict files when `apply.conflict_dir` is set
+pub const CONFLICTS_DIR: &str = ".jin/conflicts";
+
+/// Name of the index inside [`CONFLICTS_DIR`]
+const INDEX_FILE: &str = "index.json";
+
+/// Hex digits of the path hash in conflict file names
+const HASH_LEN: usize = 12;
+
+/// Longest file name most filesystems accept, in bytes
+const MAX_NAME_LEN: usize = 255;
+
+/// The conflict files of a workspace
+#[derive(Debug, Clone, Default)]
+pub struct ConflictFiles {
+    /// New conflict files go to the conflicts directory
+    in_directory: bool,
+    /// Workspace root that `dir` is relative to
+    root: PathBuf,
+    /// Conflicts directory
+    dir: PathBuf,
+    /// Workspace path -> conflict file name in `dir`
+    index: BTreeMap<PathBuf, String>,
+}
+
+impl ConflictFiles {
+    /// Conflict files of the workspace at `root`
+    ///
+    /// The paths handed out are relative to `root`, like the files they
+    /// resolve.
+    pub fn load(root: &Path) -> Result<Self> {
+        let in_directory = ProjectConfig::load(root)?
+            .apply
+            .is_some_and(|apply| apply.conflict_dir);
+        let mut files = Self::load_from(&root.join(CONFLICTS_DIR), in_directory)?;
+        files.root = root.to_path_buf();
+        files.dir = PathBuf::from(CONFLICTS_DIR);
+        Ok(files)
+    }
+
+    /// Conflict files indexed in `dir`
+    pub fn load_from(dir: &Path, in_directory: bool) -> Result<Self> {
+        let index_path = dir.join(INDEX_FILE);
+        let index = if index_path.exists() {
+            let content = std::fs::read_to_string(&index_path)?;
+            serde_json::from_str(&content).map_err(|e| JinError::Parse {
+                format: "JSON".to_string(),
+                message: format!("{}: {}", index_path.display(), e),
+            })?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            in_directory,
+            root: PathBuf::new(),
+            dir: dir.to_path_buf(),
+            index,
+        })
+    }
+
+    /// Where the conflict file for `original` is (or would be, if it was
+    /// created before the index existed)
+    pub fn path_for(&self, original: &Path) -> PathBuf {
+        match self.index.get(original) {
+            Some(name) => self.dir.join(name),
+            None => JinMergeConflict::merge_path_for_file(original),
+        }
+    }
+
+    /// Choose where a new conflict file for `original` goes, recording it
+    /// in the index when it goes to the conflicts directory
+    ///
+    /// Call [`ConflictFiles::save`] once every conflict is placed.
+    pub fn place(&mut self, original: &Path) -> PathBuf {
+        if !self.in_directory {
+            self.index.remove(original);
+            return JinMergeConflict::merge_path_for_file(original);
+        }
+        let name = hashed_name(original);
+        self.index.insert(original.to_path_buf(), name.clone());
+        self.dir.join(name)
+    }
+
+    /// Drop the index entry of a resolved file
+    pub fn forget(&mut self, original: &Path) {
+        self.index.remove(original);
+    }
+
+    /// Whether conflict files of `original` live in the conflicts directory
+    pub fn is_indexed(&self, original: &Path) -> bool {
+        self.index.contains_key(original)
+    }
+
+    /// Write the index, removing it (and the directory, once empty) when
+    /// no conflicts are left
+    pub fn save(&self) -> Result<()> {
+        let dir = self.root.join(&self.dir);
+        let index_path = dir.join(INDEX_FILE);
+        if self.index.is_empty() {
+            if index_path.exists() {
+                std::fs::remove_file(&index_path)?;
+            }
+            // Only succeeds when nothing else is left in the directory
+            let _ = std::fs::remove_dir(&dir);
+            return Ok(());
+        }
+        std::fs::create_dir_all(&dir)?;
+        let content = serde_json::to_string_pretty(&self.index)
+            .map_err(|e| JinError::Other(format!("Failed to serialize conflict index: {}", e)))?;
+        let temp_path = index_path.with_extension("json.tmp");
+        std::fs::write(&temp_path, content)?;
+        std::fs::rename(&temp_path, &index_path)?;
+        Ok(())
+    }
+}
+
+/// `<hash>-<name>.jinmerge`, unique per workspace path and still showing
+/// which file it belongs to
+fn hashed_name(original: &Path) -> String {
+    let path = original.to_string_lossy().replace('\\', "/");
+    let hash = git2::Oid::hash_object(git2::ObjectType::Blob, path.as_bytes())
+        .map(|oid| oid.to_string())
+        .unwrap_or_default();
+    let mut file_name = original
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    // Most filesystems cap names at 255 bytes; the hash keeps a shortened
+    // name unique
+    let limit = MAX_NAME_LEN - HASH_LEN - "-.jinmerge".len();
+    if file_name.len() > limit {
+        let end = (0..=limit)
+            .rev()
+            .find(|&i| file_name.is_char_boundary(i))
+            .unwrap_or_default();
+        file_name.truncate(end);
+    }
+    format!(
+        "{}-{}.jinmerge",
+        &hash[..HASH_LEN.min(hash.len())],
+        file_name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sibling_layout() {
+        let temp = TempDir::new().unwrap();
+        let mut files = ConflictFiles::load_from(temp.path(), false).unwrap();
+        let original = Path::new("config/app.json");
+        assert_eq!(
+            files.place(original),
+            PathBuf::from("config/app.json.jinmerge")
+        );
+        assert_eq!(files.path_for(original), files.place(original));
+        files.save().unwrap();
+        assert!(!temp.path().join(INDEX_FILE).exists());
+    }
+
+    #[test]
+    fn test_directory_layout_names_fit_the_filesystem() {
+        let temp = TempDir::new().unwrap();
+        let mut files = ConflictFiles::load_from(temp.path(), true).unwrap();
+        let long = format!("{}.json", "é".repeat(120));
+        let placed = files.place(Path::new(&long));
+        let name = placed.file_name().unwrap().to_str().unwrap();
+        assert!(name.len() <= MAX_NAME_LEN);
+        assert!(name.ends_with(".jinmerge"));
+        std::fs::write(&placed, "conflict").unwrap();
+
+        let spaced = files.place(Path::new("my dir/naïve \"copy\".json"));
+        assert!(spaced
+            .to_string_lossy()
+            .ends_with("-naïve \"copy\".json.jinmerge"));
+        files.save().unwrap();
+        let reloaded = ConflictFiles::load_from(temp.path(), false).unwrap();
+        assert!(reloaded.is_indexed(Path::new("my dir/naïve \"copy\".json")));
+    }
+
+    #[test]
+    fn test_directory_layout_round_trips_through_index() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("conflicts");
+        let mut files = ConflictFiles::load_from(&dir, true).unwrap();
+        let app = files.place(Path::new("config/app.json"));
+        let other = files.place(Path::new("other/app.json"));
+        assert_ne!(app, other);
+        assert!(app.starts_with(&dir));
+        assert!(app.to_string_lossy().ends_with("-app.json.jinmerge"));
+        files.save().unwrap();
+
+        // Found again even with the setting turned off
+        let mut reloaded = ConflictFiles::load_from(&dir, false).unwrap();
+        assert_eq!(reloaded.path_for(Path::new("config/app.json")), app);
+        assert!(reloaded.is_indexed(Path::new("other/app.json")));
+
+        reloaded.forget(Path::new("config/app.json"));
+        reloaded.forget(Path::new("other/app.json"));
+        reloaded.save().unwrap();
+        assert!(!dir.exists());
+    }
+}
@@ -2,20 +2,96 @@
 //!
 //! Implements RFC 7396 (JSON Merge Patch) semantics with extensions for
 //! keyed array merging. Key behaviors:
-//! - Null values delete keys (RFC 7396)
+//! - Null values delete keys (RFC 7396), unless `keep_nulls` is set
 //! - Objects merge recursively
 //! - Arrays with keyed items (by "id" or "name") merge by key
 //! - Other arrays are replaced by the higher-precedence value
+//! - Keys listed in an object's `$final` array can't be overridden by
+//!   higher layers
+//!
+//! # Final keys
+//!
+//! A layer pins keys by listing them in a `$final` entry of the object
+//! holding them, e.g. `{"tls": true, "$final": ["tls"]}` in global keeps
+//! every mode and project from turning TLS off. Pins accumulate: a higher
+//! layer can add its own `$final` keys but never remove inherited ones.
+//! An override of a pinned key (or the removal of an object containing one)
+//! is dropped and reported as a violation; [`FinalPolicy`] decides whether
+//! that's a warning or an error. `$final` entries are removed from the merged
+//! output with [`strip_final_markers`].
 
 use super::MergeValue;
-use crate::core::Result;
+use crate::core::{JinConfig, JinError, Result};
 use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Object entry listing the keys higher layers can't override
+pub const FINAL_KEY: &str = "$final";
+
+/// What happens when a layer overrides a final key
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FinalPolicy {
+    /// Keep the pinned value and print a warning
+    #[default]
+    Warn,
+    /// Fail the merge
+    Error,
+}
+
+impl std::str::FromStr for FinalPolicy {
+    type Err = JinError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "warn" => Ok(Self::Warn),
+            "error" => Ok(Self::Error),
+            _ => Err(JinError::Config(format!(
+                "Invalid final key policy: {}. Use 'warn' or 'error'",
+                value
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for FinalPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warn => write!(f, "warn"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Final key settings (stored under `[final_keys]` in the global config)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinalKeysConfig {
+    /// What an override of a final key does
+    #[serde(default)]
+    pub policy: FinalPolicy,
+}
+
+impl FinalKeysConfig {
+    /// Settings loaded once per process, for the merge engine
+    pub fn current() -> &'static Self {
+        static FINAL_KEYS: OnceLock<FinalKeysConfig> = OnceLock::new();
+        FINAL_KEYS.get_or_init(|| {
+            JinConfig::load()
+                .ok()
+                .and_then(|c| c.final_keys)
+                .unwrap_or_default()
+        })
+    }
+}
 
 /// Configuration for merge operations
 #[derive(Debug, Clone)]
 pub struct MergeConfig {
     /// Key fields to use for keyed array merge (default: ["id", "name"])
     pub array_key_fields: Vec<String>,
+    /// Keep nulls from higher layers as values instead of deleting keys
+    pub keep_nulls: bool,
 }
 
 impl Default for MergeConfig {
@@ -29,6 +105,7 @@ impl MergeConfig {
     pub fn new() -> Self {
         Self {
             array_key_fields: vec!["id".to_string(), "name".to_string()],
+            keep_nulls: false,
         }
     }
 
@@ -36,6 +113,7 @@ impl MergeConfig {
     pub fn with_key_fields(fields: Vec<String>) -> Self {
         Self {
             array_key_fields: fields,
+            keep_nulls: false,
         }
     }
 }
@@ -71,10 +149,48 @@ pub fn deep_merge(base: MergeValue, overlay: MergeValue) -> Result<MergeValue> {
 /// - Other arrays are replaced by the higher-precedence value
 /// - Null values delete keys (RFC 7396 semantics)
 /// - Type conflicts: overlay wins completely
+/// - Final keys keep their base value (see [`deep_merge_checked`])
 pub fn deep_merge_with_config(
     base: MergeValue,
     overlay: MergeValue,
     config: &MergeConfig,
+) -> Result<MergeValue> {
+    Ok(deep_merge_checked(base, overlay, config)?.0)
+}
+
+/// Deep merge that also reports attempts to override final keys.
+///
+/// Returns the merged value and the dotted paths of the final keys the
+/// overlay tried to change (e.g. `security.tls`). Those overrides are not
+/// applied.
+pub fn deep_merge_checked(
+    base: MergeValue,
+    overlay: MergeValue,
+    config: &MergeConfig,
+) -> Result<(MergeValue, Vec<String>)> {
+    let mut violations = Vec::new();
+    let merged = merge_value(base, overlay, config, "", &mut violations)?;
+    Ok((merged, violations))
+}
+
+/// Remove `$final` entries from a merged value before it is written out
+pub fn strip_final_markers(value: &mut MergeValue) {
+    match value {
+        MergeValue::Object(obj) => {
+            obj.shift_remove(FINAL_KEY);
+            obj.values_mut().for_each(strip_final_markers);
+        }
+        MergeValue::Array(arr) => arr.iter_mut().for_each(strip_final_markers),
+        _ => {}
+    }
+}
+
+fn merge_value(
+    base: MergeValue,
+    overlay: MergeValue,
+    config: &MergeConfig,
+    path: &str,
+    violations: &mut Vec<String>,
 ) -> Result<MergeValue> {
     match (base, overlay) {
         // Null in overlay = delete the key (RFC 7396)
@@ -82,13 +198,40 @@ pub fn deep_merge_with_config(
 
         // Both objects: recursive merge
         (MergeValue::Object(mut base_obj), MergeValue::Object(overlay_obj)) => {
+            let finals = final_keys(&base_obj);
             for (key, overlay_val) in overlay_obj {
-                if overlay_val.is_null() {
+                let key_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+
+                if key == FINAL_KEY {
+                    // Pins accumulate across layers
+                    let pins = merge_final_keys(&finals, &overlay_val, &key_path, violations);
+                    base_obj.insert(key, pins);
+                    continue;
+                }
+                if finals.contains(&key) {
+                    if base_obj.get(&key) != Some(&overlay_val) {
+                        violations.push(key_path);
+                    }
+                    continue;
+                }
+                if base_obj.get(&key).is_some_and(has_final_keys) && !overlay_val.is_object() {
+                    // Removing or replacing the object would drop its pins
+                    violations.push(key_path);
+                    continue;
+                }
+
+                if overlay_val.is_null() && config.keep_nulls {
+                    base_obj.insert(key, MergeValue::Null);
+                } else if overlay_val.is_null() {
                     // Null removes the key entirely
                     base_obj.shift_remove(&key);
                 } else if let Some(base_val) = base_obj.shift_remove(&key) {
                     // Recursively merge existing keys
-                    let merged = deep_merge_with_config(base_val, overlay_val, config)?;
+                    let merged = merge_value(base_val, overlay_val, config, &key_path, violations)?;
                     if !merged.is_null() {
                         base_obj.insert(key, merged);
                     }
@@ -107,7 +250,7 @@ pub fn deep_merge_with_config(
                 return Ok(MergeValue::Array(overlay_arr));
             }
 
-            let result = merge_arrays_with_config(base_arr, overlay_arr, config)?;
+            let result = merge_arrays_with_config(base_arr, overlay_arr, config, path, violations)?;
             Ok(MergeValue::Array(result))
         }
 
@@ -135,6 +278,8 @@ fn merge_arrays_with_config(
     base: Vec<MergeValue>,
     overlay: Vec<MergeValue>,
     config: &MergeConfig,
+    path: &str,
+    violations: &mut Vec<String>,
 ) -> Result<Vec<MergeValue>> {
     // Check if arrays have keyed objects
     let base_keyed = extract_array_keys(&base, &config.array_key_fields);
@@ -148,7 +293,8 @@ fn merge_arrays_with_config(
         for (key, base_val) in base_map {
             if let Some(overlay_val) = overlay_map.shift_remove(&key) {
                 // Merge overlay into base item
-                let merged = deep_merge_with_config(base_val, overlay_val, config)?;
+                let item_path = format!("{}[{}]", path, key);
+                let merged = merge_value(base_val, overlay_val, config, &item_path, violations)?;
                 result.push(merged);
             } else {
                 // Keep base item as-is
@@ -202,6 +348,56 @@ fn extract_array_keys(
     Some(result)
 }
 
+/// Keys pinned by an object's `$final` entry
+fn final_keys(obj: &IndexMap<String, MergeValue>) -> Vec<String> {
+    match obj.get(FINAL_KEY) {
+        Some(MergeValue::Array(keys)) => keys
+            .iter()
+            .filter_map(|k| k.as_str().map(str::to_string))
+            .collect(),
+        Some(MergeValue::String(key)) => vec![key.clone()],
+        _ => Vec::new(),
+    }
+}
+
+/// Combine inherited pins with the ones an overlay adds
+///
+/// An overlay can't drop inherited pins, so a `$final` that isn't a list of
+/// keys only counts as a violation when there were pins to lose.
+fn merge_final_keys(
+    inherited: &[String],
+    overlay: &MergeValue,
+    path: &str,
+    violations: &mut Vec<String>,
+) -> MergeValue {
+    let mut keys = inherited.to_vec();
+    let added: Vec<&str> = match overlay {
+        MergeValue::Array(added) => added.iter().filter_map(|k| k.as_str()).collect(),
+        MergeValue::String(key) => vec![key.as_str()],
+        _ => {
+            if !inherited.is_empty() {
+                violations.push(path.to_string());
+            }
+            Vec::new()
+        }
+    };
+    for key in added {
+        if !keys.iter().any(|k| k == key) {
+            keys.push(key.to_string());
+        }
+    }
+    MergeValue::Array(keys.into_iter().map(MergeValue::String).collect())
+}
+
+/// Whether a value contains pinned keys at any depth
+fn has_final_keys(value: &MergeValue) -> bool {
+    match value {
+        MergeValue::Object(obj) => !final_keys(obj).is_empty() || obj.values().any(has_final_keys),
+        MergeValue::Array(arr) => arr.iter().any(has_final_keys),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -736,4 +932,84 @@ mod tests {
 
         assert_eq!(result1, result2);
     }
+
+    #[test]
+    fn test_keep_nulls() {
+        let base = json_to_merge(serde_json::json!({"a": 1, "b": {"c": 2}}));
+        let overlay = json_to_merge(serde_json::json!({"a": null, "b": {"c": null}, "d": null}));
+        let config = MergeConfig {
+            keep_nulls: true,
+            ..MergeConfig::new()
+        };
+
+        let result = deep_merge_with_config(base.clone(), overlay.clone(), &config).unwrap();
+        assert_eq!(
+            result,
+            json_to_merge(serde_json::json!({"a": null, "b": {"c": null}, "d": null}))
+        );
+
+        let result = deep_merge(base, overlay).unwrap();
+        assert_eq!(result, json_to_merge(serde_json::json!({"b": {}})));
+    }
+
+    // ========== Final Key Tests ==========
+
+    #[test]
+    fn test_final_key_keeps_pinned_value() {
+        let base = json_to_merge(serde_json::json!({
+            "security": {"tls": true, "level": 1, "$final": ["tls"]}
+        }));
+        let overlay = json_to_merge(serde_json::json!({
+            "security": {"tls": false, "level": 2}
+        }));
+
+        let (result, violations) = deep_merge_checked(base, overlay, &MergeConfig::new()).unwrap();
+        let security = result.as_object().unwrap()["security"].as_object().unwrap();
+        assert_eq!(security["tls"], MergeValue::Bool(true));
+        assert_eq!(security["level"], MergeValue::Integer(2));
+        assert_eq!(violations, vec!["security.tls"]);
+    }
+
+    #[test]
+    fn test_final_key_same_value_is_not_a_violation() {
+        let base = json_to_merge(serde_json::json!({"tls": true, "$final": "tls"}));
+        let overlay = json_to_merge(serde_json::json!({"tls": true}));
+
+        let (_, violations) = deep_merge_checked(base, overlay, &MergeConfig::new()).unwrap();
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn test_final_keys_accumulate_and_protect_parents() {
+        let base = json_to_merge(serde_json::json!({
+            "security": {"tls": true, "$final": ["tls"]},
+            "port": 80
+        }));
+        let overlay = json_to_merge(serde_json::json!({
+            "security": {"$final": [], "hsts": true, "$final_note": null},
+            "port": 443,
+            "$final": ["port"]
+        }));
+        let (merged, violations) = deep_merge_checked(base, overlay, &MergeConfig::new()).unwrap();
+        assert!(violations.is_empty());
+        let security = merged.as_object().unwrap()["security"].as_object().unwrap();
+        assert_eq!(
+            security[FINAL_KEY],
+            MergeValue::Array(vec![MergeValue::String("tls".into())])
+        );
+
+        let top = json_to_merge(serde_json::json!({"security": null, "port": 8080}));
+        let (mut merged, violations) =
+            deep_merge_checked(merged, top, &MergeConfig::new()).unwrap();
+        assert_eq!(violations, vec!["port", "security"]);
+
+        strip_final_markers(&mut merged);
+        assert_eq!(
+            merged,
+            json_to_merge(serde_json::json!({
+                "security": {"tls": true, "hsts": true},
+                "port": 443
+            }))
+        );
+    }
 }
@@ -0,0 +1,302 @@
+//! External merge drivers
+//!
+//! Formats Jin does not understand can be merged by an external program
+//! registered for a path pattern in the global config:
+//!
+//! ```toml
+//! [[mergers]]
+//! pattern = "*.xml"
+//! command = "xmlmerge --base %O --ours %A --theirs %B"
+//! ```
+//!
+//! Patterns follow the `.jinattributes` conventions; when several drivers
+//! match, the last one wins. The three versions are written to temporary
+//! files: `%O` is replaced by the base, `%A` by ours (the lower layer, or
+//! the local side on pull), `%B` by theirs and `%P` by the path being
+//! merged. The merged content is read from the driver's stdout.
+//!
+//! A driver exits with 0 for a clean merge and 1 when the result has
+//! conflicts; the file is then reported as conflicting like any text
+//! conflict. Any other exit status aborts the merge.
+
+use super::TextMergeResult;
+use crate::core::progress;
+use crate::core::{JinConfig, JinError, Result};
+use crate::staging::attributes::{glob_to_regex, normalize};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+/// A merge driver registration (one `[[mergers]]` entry)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergerConfig {
+    /// Path pattern the driver handles
+    pub pattern: String,
+    /// Shell command to run, with `%O`, `%A`, `%B` and `%P` placeholders
+    pub command: String,
+}
+
+/// A registered driver ready to run
+#[derive(Debug, Clone)]
+pub struct MergeDriver {
+    /// Pattern as configured
+    pub pattern: String,
+    /// Command as configured
+    pub command: String,
+    matcher: Regex,
+}
+
+impl MergeDriver {
+    /// Validate a registration
+    pub fn new(config: &MergerConfig) -> Result<Self> {
+        let matcher = glob_to_regex(&config.pattern)
+            .map_err(|message| JinError::Config(format!("mergers: {}", message)))?;
+        if config.command.trim().is_empty() {
+            return Err(JinError::Config(format!(
+                "mergers: no command for pattern '{}'",
+                config.pattern
+            )));
+        }
+        Ok(Self {
+            pattern: config.pattern.clone(),
+            command: config.command.clone(),
+            matcher,
+        })
+    }
+
+    /// Whether this driver handles a path
+    pub fn matches(&self, path: &Path) -> bool {
+        self.matcher.is_match(&normalize(path))
+    }
+
+    /// Merge `ours` and `theirs` against `base` with the external command,
+    /// run from the workspace `root`
+    pub fn merge(
+        &self,
+        root: &Path,
+        path: &Path,
+        base: &str,
+        ours: &str,
+        theirs: &str,
+    ) -> Result<TextMergeResult> {
+        let dir = TempDir::create()?;
+        let extension = path
+            .extension()
+            .map(|e| format!(".{}", e.to_string_lossy()))
+            .unwrap_or_default();
+        let mut files = Vec::new();
+        for (name, content) in [("base", base), ("ours", ours), ("theirs", theirs)] {
+            let file = dir.0.join(format!("{}{}", name, extension));
+            std::fs::write(&file, content)?;
+            files.push(file);
+        }
+
+        let output = shell_command(&expand_placeholders(&self.command))
+            .args(&files)
+            .arg(normalize(path))
+            .current_dir(root)
+            .stdin(Stdio::null())
+            .stderr(Stdio::inherit())
+            .output()
+            .map_err(|e| self.failure(path, &e.to_string()))?;
+
+        let content = String::from_utf8(output.stdout)
+            .map_err(|_| self.failure(path, "output is not valid UTF-8"))?;
+        match output.status.code() {
+            Some(0) => Ok(TextMergeResult::Clean(content)),
+            Some(1) => Ok(TextMergeResult::Conflict {
+                content,
+                conflict_count: 1,
+            }),
+            _ => Err(self.failure(path, &format!("exited with {}", output.status))),
+        }
+    }
+
+    fn failure(&self, path: &Path, reason: &str) -> JinError {
+        JinError::Other(format!(
+            "Merge driver '{}' for {} failed: {}",
+            self.command,
+            path.display(),
+            reason
+        ))
+    }
+}
+
+/// Drivers registered in the global config
+#[derive(Debug, Clone, Default)]
+pub struct MergeDrivers {
+    drivers: Vec<MergeDriver>,
+}
+
+impl MergeDrivers {
+    /// Drivers from the global config, loaded once per process
+    ///
+    /// Invalid registrations are reported once and ignored.
+    pub fn current() -> &'static Self {
+        static DRIVERS: OnceLock<MergeDrivers> = OnceLock::new();
+        DRIVERS.get_or_init(|| {
+            let mergers = JinConfig::load().map(|c| c.mergers).unwrap_or_default();
+            Self::from_config(&mergers).unwrap_or_else(|e| {
+                progress::warn(format_args!("{}; external merge drivers are disabled", e));
+                Self::default()
+            })
+        })
+    }
+
+    /// Whether no driver is registered
+    pub fn is_empty(&self) -> bool {
+        self.drivers.is_empty()
+    }
+
+    /// Build drivers from registrations
+    pub fn from_config(mergers: &[MergerConfig]) -> Result<Self> {
+        let drivers = mergers
+            .iter()
+            .map(MergeDriver::new)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { drivers })
+    }
+
+    /// The driver for a path (the last matching registration wins)
+    pub fn find(&self, path: &Path) -> Option<&MergeDriver> {
+        self.drivers.iter().rev().find(|d| d.matches(path))
+    }
+}
+
+/// Replace the placeholders with positional shell parameters, so paths are
+/// passed as arguments and never need quoting
+fn expand_placeholders(command: &str) -> String {
+    let mut out = String::new();
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('O') => out.push_str("\"$1\""),
+            Some('A') => out.push_str("\"$2\""),
+            Some('B') => out.push_str("\"$3\""),
+            Some('P') => out.push_str("\"$4\""),
+            Some('%') => out.push('%'),
+            Some(other) => {
+                out.push('%');
+                out.push(other);
+            }
+            None => out.push('%'),
+        }
+    }
+    out
+}
+
+#[cfg(unix)]
+fn shell_command(program: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(program).arg("sh");
+    cmd
+}
+
+#[cfg(windows)]
+fn shell_command(program: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(program);
+    cmd
+}
+
+/// Scratch directory for one driver run, removed on drop
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn create() -> Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "jin-merge-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self(dir))
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn driver(pattern: &str, command: &str) -> MergeDriver {
+        MergeDriver::new(&MergerConfig {
+            pattern: pattern.to_string(),
+            command: command.to_string(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_expand_placeholders() {
+        assert_eq!(
+            expand_placeholders("tool %O %A %B --path=%P 100%%"),
+            "tool \"$1\" \"$2\" \"$3\" --path=\"$4\" 100%"
+        );
+        assert_eq!(expand_placeholders("keep %x %"), "keep %x %");
+    }
+
+    #[test]
+    fn test_last_matching_driver_wins() {
+        let drivers = MergeDrivers::from_config(&[
+            MergerConfig {
+                pattern: "*.xml".into(),
+                command: "generic".into(),
+            },
+            MergerConfig {
+                pattern: "config/*.xml".into(),
+                command: "specific".into(),
+            },
+        ])
+        .unwrap();
+        let find = |p: &str| drivers.find(Path::new(p)).map(|d| d.command.as_str());
+        assert_eq!(find("a/b.xml"), Some("generic"));
+        assert_eq!(find("config/app.xml"), Some("specific"));
+        assert_eq!(find("config/app.json"), None);
+
+        assert!(MergeDrivers::from_config(&[MergerConfig {
+            pattern: "*.xml".into(),
+            command: " ".into(),
+        }])
+        .is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_driver_exit_status() {
+        let path = Path::new("app.cfg");
+        let concat = driver("*.cfg", "cat %A %B");
+        match concat
+            .merge(Path::new("."), path, "base\n", "ours\n", "theirs\n")
+            .unwrap()
+        {
+            TextMergeResult::Clean(content) => assert_eq!(content, "ours\ntheirs\n"),
+            other => panic!("expected clean merge, got {:?}", other),
+        }
+
+        let conflict = driver("*.cfg", "cat %O; exit 1");
+        assert!(matches!(
+            conflict.merge(Path::new("."), path, "base\n", "a", "b").unwrap(),
+            TextMergeResult::Conflict { content, .. } if content == "base\n"
+        ));
+
+        let broken = driver("*.cfg", "exit 3");
+        let err = broken.merge(Path::new("."), path, "", "", "").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Merge driver 'exit 3' for app.cfg failed"));
+    }
+}
@@ -0,0 +1,200 @@
+//! Environment variable layers (`.jinenv`)
+//!
+//! A layer can define environment variables instead of (or next to) files by
+//! committing a `.jinenv` file in dotenv syntax:
+//!
+//! ```text
+//! # set or override a variable
+//! API_URL=https://staging.example.com
+//! export LOG_LEVEL="debug"
+//! # drop a variable set by a lower layer
+//! unset DEBUG_TOKEN
+//! ```
+//!
+//! `.jinenv` is parsed into an object of variables, so it composes across
+//! layers key by key like any structured file: higher layers override lower
+//! ones and `unset` deletes. `jin apply` never writes it to the workspace;
+//! the variables are read with `jin env` and passed to `jin run` children.
+
+use super::format::{FileFormat, FormatBackend};
+use super::MergeValue;
+use crate::core::{JinError, Result};
+use indexmap::IndexMap;
+use std::path::Path;
+
+/// Path of the environment file in a layer
+pub const ENV_PATH: &str = ".jinenv";
+
+/// Format identifier of environment files
+pub const ENV_FORMAT: FileFormat = FileFormat::Custom("env");
+
+/// Backend for `.jinenv` files
+pub struct EnvBackend;
+
+impl FormatBackend for EnvBackend {
+    fn format(&self) -> FileFormat {
+        ENV_FORMAT
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        path.file_name().is_some_and(|name| name == ENV_PATH)
+    }
+
+    fn parse(&self, content: &str) -> Result<MergeValue> {
+        let mut vars = IndexMap::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix("unset ") {
+                vars.insert(valid_name(name.trim(), number)?, MergeValue::Null);
+                continue;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (name, value) = line.split_once('=').ok_or_else(|| JinError::Parse {
+                format: ENV_PATH.to_string(),
+                message: format!("line {}: expected NAME=value or unset NAME", number + 1),
+            })?;
+            vars.insert(
+                valid_name(name.trim(), number)?,
+                MergeValue::String(unquote(value.trim())),
+            );
+        }
+        Ok(MergeValue::Object(vars))
+    }
+
+    fn serialize(&self, value: &MergeValue) -> Result<String> {
+        let mut out = String::new();
+        for (name, value) in env_vars(value) {
+            out.push_str(&format!("{}={}\n", name, dotenv_quote(&value)));
+        }
+        Ok(out)
+    }
+}
+
+/// Variables in a parsed environment value, in definition order
+///
+/// Scalars are converted to strings; `unset` entries are left out.
+pub fn env_vars(value: &MergeValue) -> Vec<(String, String)> {
+    let Some(vars) = value.as_object() else {
+        return Vec::new();
+    };
+    vars.iter()
+        .filter_map(|(name, value)| {
+            let value = match value {
+                MergeValue::String(s) => s.clone(),
+                MergeValue::Bool(b) => b.to_string(),
+                MergeValue::Integer(i) => i.to_string(),
+                MergeValue::Float(f) => f.to_string(),
+                _ => return None,
+            };
+            Some((name.clone(), value))
+        })
+        .collect()
+}
+
+/// Quote a value for a dotenv file, if it needs it
+pub fn dotenv_quote(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:@%+,".contains(c));
+    if plain {
+        return value.to_string();
+    }
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+/// Quote a value for a POSIX shell
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn valid_name(name: &str, number: usize) -> Result<String> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(name.to_string())
+    } else {
+        Err(JinError::Parse {
+            format: ENV_PATH.to_string(),
+            message: format!("line {}: invalid variable name '{}'", number + 1, name),
+        })
+    }
+}
+
+/// Strip quotes from a value: single quotes are literal, double quotes
+/// understand `\"`, `\\` and `\n`
+fn unquote(value: &str) -> String {
+    if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return inner.to_string();
+    }
+    let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+        return value.to_string();
+    };
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                out.push('\n');
+                chars.next();
+            }
+            ('\\', Some(next @ ('"' | '\\'))) => {
+                out.push(next);
+                chars.next();
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_file() {
+        let value = EnvBackend
+            .parse("# comment\nA=1\nexport B=\"two words\"\nC='it''s'\nunset D\n")
+            .unwrap();
+        let vars = value.as_object().unwrap();
+        assert_eq!(vars["A"], MergeValue::String("1".into()));
+        assert_eq!(vars["B"], MergeValue::String("two words".into()));
+        assert_eq!(vars["C"], MergeValue::String("it''s".into()));
+        assert_eq!(vars["D"], MergeValue::Null);
+
+        assert!(EnvBackend.parse("not a variable").is_err());
+        assert!(EnvBackend.parse("1BAD=x").is_err());
+    }
+
+    #[test]
+    fn test_serialize_round_trips() {
+        let content = "A=1\nB=\"two words\"\nC=\"say \\\"hi\\\"\\nbye\"\nEMPTY=\"\"\n";
+        let value = EnvBackend.parse(content).unwrap();
+        assert_eq!(
+            value.as_object().unwrap()["C"],
+            MergeValue::String("say \"hi\"\nbye".into())
+        );
+        assert_eq!(EnvBackend.serialize(&value).unwrap(), content);
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("plain"), "'plain'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+}
@@ -0,0 +1,217 @@
+//! Line-ending policy for merged and applied files
+//!
+//! Layers committed from Windows and Unix machines can hold the same file
+//! with different line endings, which would otherwise show up as
+//! whole-file differences and spurious text conflicts. The policy decides
+//! what line endings applied files get:
+//!
+//! - `preserve` (default): whatever the layers contain, unchanged
+//! - `lf` / `crlf`: always `\n` / `\r\n`
+//! - `native`: `crlf` on Windows, `lf` elsewhere
+//!
+//! The global default is `eol.policy` in the config; `.jinattributes`
+//! rules override it per path (`*.bat eol=crlf`). With any policy other
+//! than `preserve`, text layers are compared and merged with their line
+//! endings normalized, so a CRLF copy of an LF file is not a conflict.
+
+use crate::core::progress;
+use crate::core::{JinConfig, JinError, Result};
+use crate::staging::attributes::JinAttributes;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+/// Line endings of applied files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// Keep the line endings stored in the layers
+    #[default]
+    Preserve,
+    /// `\n`
+    Lf,
+    /// `\r\n`
+    Crlf,
+    /// The platform's convention
+    Native,
+}
+
+impl LineEnding {
+    /// Whether files are normalized at all
+    pub fn normalizes(&self) -> bool {
+        *self != Self::Preserve
+    }
+
+    /// Convert `text` to this line ending
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self.resolve() {
+            Self::Lf => to_lf(text),
+            Self::Crlf => {
+                let lf = to_lf(text);
+                if lf.contains('\n') {
+                    Cow::Owned(lf.replace('\n', "\r\n"))
+                } else {
+                    lf
+                }
+            }
+            _ => Cow::Borrowed(text),
+        }
+    }
+
+    /// `native` as the concrete ending for this platform
+    fn resolve(&self) -> Self {
+        match self {
+            Self::Native if cfg!(windows) => Self::Crlf,
+            Self::Native => Self::Lf,
+            other => *other,
+        }
+    }
+}
+
+impl std::str::FromStr for LineEnding {
+    type Err = JinError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "preserve" => Ok(Self::Preserve),
+            "lf" => Ok(Self::Lf),
+            "crlf" => Ok(Self::Crlf),
+            "native" => Ok(Self::Native),
+            _ => Err(JinError::Config(format!(
+                "Invalid line-ending policy: {}. Use 'native', 'lf', 'crlf' or 'preserve'",
+                value
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Preserve => write!(f, "preserve"),
+            Self::Lf => write!(f, "lf"),
+            Self::Crlf => write!(f, "crlf"),
+            Self::Native => write!(f, "native"),
+        }
+    }
+}
+
+/// `text` with every `\r\n` replaced by `\n`
+pub fn to_lf(text: &str) -> Cow<'_, str> {
+    if text.contains("\r\n") {
+        Cow::Owned(text.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Line-ending settings (stored under `[eol]` in the global config)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EolConfig {
+    /// Policy for paths no `.jinattributes` rule covers
+    #[serde(default)]
+    pub policy: LineEnding,
+}
+
+impl EolConfig {
+    /// Settings loaded once per process
+    pub fn current() -> &'static Self {
+        static EOL: OnceLock<EolConfig> = OnceLock::new();
+        EOL.get_or_init(|| {
+            JinConfig::load()
+                .ok()
+                .and_then(|c| c.eol)
+                .unwrap_or_default()
+        })
+    }
+}
+
+/// Line-ending policy for each path
+#[derive(Debug, Clone, Default)]
+pub struct LineEndings {
+    default: LineEnding,
+    attributes: JinAttributes,
+}
+
+impl LineEndings {
+    /// `default` for every path, overridden by `eol=` rules in `attributes`
+    pub fn new(default: LineEnding, attributes: JinAttributes) -> Self {
+        Self {
+            default,
+            attributes,
+        }
+    }
+
+    /// Global policy with the `.jinattributes` and `.jinattributes.local`
+    /// rules of the workspace at `root`, loaded once per workspace for
+    /// merging
+    ///
+    /// Unreadable rules are reported once and ignored.
+    pub fn current(root: &Path) -> &'static Self {
+        static LINE_ENDINGS: OnceLock<Mutex<HashMap<PathBuf, &'static LineEndings>>> =
+            OnceLock::new();
+        let mut loaded = LINE_ENDINGS
+            .get_or_init(Mutex::default)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        loaded.entry(root.to_path_buf()).or_insert_with(|| {
+            let attributes = JinAttributes::load_with_overrides(root, None).unwrap_or_else(|e| {
+                progress::warn(format_args!("{}; eol rules are ignored", e));
+                JinAttributes::default()
+            });
+            Box::leak(Box::new(Self::new(EolConfig::current().policy, attributes)))
+        })
+    }
+
+    /// Policy for a workspace-relative path
+    pub fn policy(&self, path: &Path) -> LineEnding {
+        self.attributes.resolve(path).eol.unwrap_or(self.default)
+    }
+
+    /// `content` converted for `path`
+    pub fn apply<'a>(&self, path: &Path, content: &'a str) -> Cow<'a, str> {
+        self.policy(path).apply(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_line_endings() {
+        let mixed = "a\r\nb\nc";
+        assert_eq!(LineEnding::Lf.apply(mixed), "a\nb\nc");
+        assert_eq!(LineEnding::Crlf.apply(mixed), "a\r\nb\r\nc");
+        assert_eq!(LineEnding::Preserve.apply(mixed), mixed);
+        let native = if cfg!(windows) {
+            "a\r\nb\r\nc"
+        } else {
+            "a\nb\nc"
+        };
+        assert_eq!(LineEnding::Native.apply(mixed), native);
+        assert!(matches!(LineEnding::Lf.apply("plain\n"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_parse_policy() {
+        assert_eq!("crlf".parse::<LineEnding>().unwrap(), LineEnding::Crlf);
+        assert_eq!(LineEnding::Native.to_string(), "native");
+        assert!(matches!(
+            "dos".parse::<LineEnding>(),
+            Err(JinError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_policy_per_path() {
+        let attributes = JinAttributes::parse("*.bat eol=crlf\n*.sh eol=preserve\n").unwrap();
+        let endings = LineEndings::new(LineEnding::Lf, attributes);
+        assert_eq!(endings.policy(Path::new("run.bat")), LineEnding::Crlf);
+        assert_eq!(endings.policy(Path::new("bin/x.sh")), LineEnding::Preserve);
+        assert_eq!(endings.policy(Path::new("README.md")), LineEnding::Lf);
+        assert_eq!(endings.apply(Path::new("run.bat"), "a\nb\n"), "a\r\nb\r\n");
+    }
+}
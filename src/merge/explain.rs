@@ -0,0 +1,409 @@
+//! Human-readable explanations of layer conflicts
+//!
+//! When `jin apply` pauses on a conflict, the user needs more than a file
+//! name: which two layers disagree, where, and what each one wants. This
+//! module compares the two conflicting versions of a file and produces a
+//! short summary, key-by-key for structured formats and line-by-line for
+//! everything else.
+//!
+//! The same key-by-key comparison describes what a layer commit changed
+//! (`jin log --patch`, see [`key_changes`]).
+
+use crate::merge::layer::{detect_format, parse_content, FileFormat};
+use crate::merge::MergeValue;
+use indexmap::IndexMap;
+use std::path::Path;
+
+/// Maximum number of differences listed in a summary
+pub const MAX_DETAILS: usize = 5;
+
+/// Maximum displayed length of a single value
+const MAX_VALUE_LEN: usize = 60;
+
+/// One point of disagreement between two layers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictDetail {
+    /// Where the layers disagree (e.g., `key server.port` or `line 3`)
+    pub location: String,
+    /// Value in the lower-precedence layer (`None` if absent)
+    pub ours: Option<String>,
+    /// Value in the higher-precedence layer (`None` if absent)
+    pub theirs: Option<String>,
+}
+
+/// Explanation of a conflict between two layers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictSummary {
+    /// Lower-precedence layer label
+    pub layer1: String,
+    /// Higher-precedence layer label
+    pub layer2: String,
+    /// Differences between the two versions
+    pub details: Vec<ConflictDetail>,
+}
+
+impl ConflictSummary {
+    /// Compare two versions of a file from different layers
+    ///
+    /// Structured files (JSON, YAML, TOML, INI) that parse on both sides are
+    /// compared by key path; anything else is compared line by line.
+    pub fn explain(
+        path: &Path,
+        layer1: &str,
+        layer1_content: &str,
+        layer2: &str,
+        layer2_content: &str,
+    ) -> Self {
+        let format = detect_format(path);
+        let details = if format == FileFormat::Text {
+            None
+        } else {
+            match (
+                parse_content(layer1_content, format),
+                parse_content(layer2_content, format),
+            ) {
+                (Ok(ours), Ok(theirs)) => Some(diff_keys(&ours, &theirs)),
+                _ => None,
+            }
+        }
+        .unwrap_or_else(|| diff_lines(layer1_content, layer2_content));
+
+        Self {
+            layer1: display_label(layer1),
+            layer2: display_label(layer2),
+            details,
+        }
+    }
+
+    /// Render the summary as short lines suitable for terminal output or the
+    /// `.jinmerge` header
+    pub fn to_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "Conflict between {} (lower precedence) and {} (higher precedence)",
+            self.layer1, self.layer2
+        )];
+
+        for detail in self.details.iter().take(MAX_DETAILS) {
+            lines.push(format!(
+                "  {}: {} wants {}, {} wants {}",
+                detail.location,
+                self.layer1,
+                display_value(detail.ours.as_deref()),
+                self.layer2,
+                display_value(detail.theirs.as_deref())
+            ));
+        }
+
+        if self.details.len() > MAX_DETAILS {
+            lines.push(format!(
+                "  ... and {} more difference{}",
+                self.details.len() - MAX_DETAILS,
+                if self.details.len() - MAX_DETAILS == 1 {
+                    ""
+                } else {
+                    "s"
+                }
+            ));
+        }
+
+        lines
+    }
+}
+
+/// Key-by-key changes between two versions of a structured file, with
+/// `ours` holding the old value and `theirs` the new one
+///
+/// A missing version (file added or deleted) counts as an empty document.
+/// Returns `None` for text files and content that doesn't parse.
+pub fn key_changes(
+    path: &Path,
+    old: Option<&str>,
+    new: Option<&str>,
+) -> Option<Vec<ConflictDetail>> {
+    let format = detect_format(path);
+    if format == FileFormat::Text {
+        return None;
+    }
+    let parse = |content: Option<&str>| match content {
+        Some(content) => parse_content(content, format).ok(),
+        None => Some(MergeValue::Object(IndexMap::new())),
+    };
+    let changes = diff_keys(&parse(old)?, &parse(new)?);
+    // Nothing can be removed from a missing version or added to one; this
+    // drops the stand-in's own root (`- value: "{}"`)
+    Some(
+        changes
+            .into_iter()
+            .filter(|c| {
+                !((old.is_none() && c.theirs.is_none()) || (new.is_none() && c.ours.is_none()))
+            })
+            .collect(),
+    )
+}
+
+/// Render a change from [`key_changes`] as `+ key: value`, `- key: value`
+/// or `~ key: old -> new`
+pub fn change_line(change: &ConflictDetail) -> String {
+    let key = change
+        .location
+        .strip_prefix("key ")
+        .unwrap_or(&change.location);
+    match (&change.ours, &change.theirs) {
+        (None, new) => format!("+ {}: {}", key, display_value(new.as_deref())),
+        (old, None) => format!("- {}: {}", key, display_value(old.as_deref())),
+        (old, new) => format!(
+            "~ {}: {} -> {}",
+            key,
+            display_value(old.as_deref()),
+            display_value(new.as_deref())
+        ),
+    }
+}
+
+/// Strip the ref prefix and `/_` suffix from a layer label
+fn display_label(label: &str) -> String {
+    let label = label.strip_prefix("refs/jin/layers/").unwrap_or(label);
+    let label = label.strip_suffix("/_").unwrap_or(label);
+    label.trim_end_matches('/').to_string()
+}
+
+fn display_value(value: Option<&str>) -> String {
+    match value {
+        None => "(absent)".to_string(),
+        Some(v) if v.chars().count() > MAX_VALUE_LEN => {
+            let truncated: String = v.chars().take(MAX_VALUE_LEN).collect();
+            format!("{:?}...", truncated)
+        }
+        Some(v) => format!("{:?}", v),
+    }
+}
+
+/// Compare two structured values by flattened key path
+fn diff_keys(ours: &MergeValue, theirs: &MergeValue) -> Vec<ConflictDetail> {
+    let mut ours_flat = IndexMap::new();
+    let mut theirs_flat = IndexMap::new();
+    flatten("", ours, &mut ours_flat);
+    flatten("", theirs, &mut theirs_flat);
+
+    let mut keys: Vec<&String> = ours_flat.keys().collect();
+    keys.extend(theirs_flat.keys().filter(|k| !ours_flat.contains_key(*k)));
+
+    keys.into_iter()
+        .filter(|k| ours_flat.get(*k) != theirs_flat.get(*k))
+        .map(|k| ConflictDetail {
+            location: if k.is_empty() {
+                "value".to_string()
+            } else {
+                format!("key {}", k)
+            },
+            ours: ours_flat.get(k).cloned(),
+            theirs: theirs_flat.get(k).cloned(),
+        })
+        .collect()
+}
+
+/// Flatten objects into `a.b.c` paths; arrays and scalars are leaves
+fn flatten(prefix: &str, value: &MergeValue, out: &mut IndexMap<String, String>) {
+    match value {
+        MergeValue::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&path, child, out);
+            }
+        }
+        MergeValue::String(s) => {
+            out.insert(prefix.to_string(), s.clone());
+        }
+        other => {
+            let rendered = other
+                .to_json_string_compact()
+                .unwrap_or_else(|_| format!("{:?}", other));
+            out.insert(prefix.to_string(), rendered);
+        }
+    }
+}
+
+/// Compare two texts line by line, grouping adjacent changed lines
+fn diff_lines(ours: &str, theirs: &str) -> Vec<ConflictDetail> {
+    let patch = diffy::create_patch(ours, theirs);
+    let mut details = Vec::new();
+
+    for hunk in patch.hunks() {
+        let mut line_no = hunk.old_range().start();
+        let mut group: Option<(usize, Vec<&str>, Vec<&str>)> = None;
+
+        for line in hunk.lines() {
+            match line {
+                diffy::Line::Context(_) => {
+                    if let Some(g) = group.take() {
+                        details.push(line_detail(g));
+                    }
+                    line_no += 1;
+                }
+                diffy::Line::Delete(text) => {
+                    group
+                        .get_or_insert_with(|| (line_no, Vec::new(), Vec::new()))
+                        .1
+                        .push(text.trim_end_matches('\n'));
+                    line_no += 1;
+                }
+                diffy::Line::Insert(text) => {
+                    group
+                        .get_or_insert_with(|| (line_no, Vec::new(), Vec::new()))
+                        .2
+                        .push(text.trim_end_matches('\n'));
+                }
+            }
+        }
+        if let Some(g) = group.take() {
+            details.push(line_detail(g));
+        }
+    }
+
+    details
+}
+
+fn line_detail((start, ours, theirs): (usize, Vec<&str>, Vec<&str>)) -> ConflictDetail {
+    let span = ours.len().max(1);
+    let location = if span == 1 {
+        format!("line {}", start)
+    } else {
+        format!("lines {}-{}", start, start + span - 1)
+    };
+    let join = |lines: Vec<&str>| (!lines.is_empty()).then(|| lines.join("\n"));
+
+    ConflictDetail {
+        location,
+        ours: join(ours),
+        theirs: join(theirs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_changes_between_commits() {
+        let changes = key_changes(
+            Path::new("app.json"),
+            Some(r#"{"server": {"port": 80}, "debug": true}"#),
+            Some(r#"{"server": {"port": 8080, "tls": true}}"#),
+        )
+        .unwrap();
+        let lines: Vec<String> = changes.iter().map(change_line).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "~ server.port: \"80\" -> \"8080\"",
+                "- debug: \"true\"",
+                "+ server.tls: \"true\"",
+            ]
+        );
+
+        let added = key_changes(Path::new("app.yaml"), None, Some("a: 1\n")).unwrap();
+        assert_eq!(
+            added.iter().map(change_line).collect::<Vec<_>>(),
+            ["+ a: \"1\""]
+        );
+        let removed = key_changes(Path::new("app.yaml"), Some("a: 1\n"), None).unwrap();
+        assert_eq!(
+            removed.iter().map(change_line).collect::<Vec<_>>(),
+            ["- a: \"1\""]
+        );
+        assert!(key_changes(Path::new("notes.txt"), None, Some("x")).is_none());
+        assert!(key_changes(Path::new("app.json"), Some("{"), Some("{}")).is_none());
+    }
+
+    #[test]
+    fn test_explain_text_reports_changed_line() {
+        let summary = ConflictSummary::explain(
+            Path::new("settings.txt"),
+            "global",
+            "host=localhost\nport=80\ndebug=false\n",
+            "mode/claude/_",
+            "host=localhost\nport=8080\ndebug=false\n",
+        );
+
+        assert_eq!(summary.layer2, "mode/claude");
+        assert_eq!(
+            summary.details,
+            vec![ConflictDetail {
+                location: "line 2".to_string(),
+                ours: Some("port=80".to_string()),
+                theirs: Some("port=8080".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_explain_text_added_line() {
+        let summary = ConflictSummary::explain(
+            Path::new("notes.txt"),
+            "global",
+            "a\n",
+            "project/x",
+            "a\nb\n",
+        );
+        assert_eq!(summary.details.len(), 1);
+        assert_eq!(summary.details[0].ours, None);
+        assert_eq!(summary.details[0].theirs.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_explain_structured_reports_keys() {
+        let summary = ConflictSummary::explain(
+            Path::new("config.json"),
+            "global",
+            r#"{"server": {"port": 80, "host": "a"}, "old": true}"#,
+            "mode/claude/_",
+            r#"{"server": {"port": 8080, "host": "a"}, "new": [1]}"#,
+        );
+
+        let locations: Vec<&str> = summary
+            .details
+            .iter()
+            .map(|d| d.location.as_str())
+            .collect();
+        assert_eq!(locations.len(), 3);
+        assert!(locations.contains(&"key old"));
+        assert!(locations.contains(&"key new"));
+
+        let port = summary
+            .details
+            .iter()
+            .find(|d| d.location == "key server.port")
+            .unwrap();
+        assert_eq!(port.ours.as_deref(), Some("80"));
+        assert_eq!(port.theirs.as_deref(), Some("8080"));
+    }
+
+    #[test]
+    fn test_explain_unparseable_structured_falls_back_to_lines() {
+        let summary = ConflictSummary::explain(
+            Path::new("broken.json"),
+            "global",
+            "{\n",
+            "project/x",
+            "}\n",
+        );
+        assert_eq!(summary.details[0].location, "line 1");
+    }
+
+    #[test]
+    fn test_to_lines_limits_details() {
+        let ours: String = (0..8).map(|i| format!("line{}\n", i)).collect();
+        let theirs: String = (0..8).map(|i| format!("line{}\n\n", i)).collect();
+        let summary =
+            ConflictSummary::explain(Path::new("many.txt"), "global", &ours, "project/x", &theirs);
+
+        let lines = summary.to_lines();
+        assert!(lines[0].starts_with("Conflict between global"));
+        assert_eq!(lines.len(), MAX_DETAILS + 2);
+        assert!(lines.last().unwrap().contains("more difference"));
+    }
+}
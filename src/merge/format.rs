@@ -0,0 +1,464 @@
+//! Pluggable file format backends
+//!
+//! Every structured format Jin understands is described by a
+//! [`FormatBackend`]: how to recognize a file, parse it into a
+//! [`MergeValue`], serialize it back, and any hints the merge engine should
+//! follow. Backends are collected in a [`FormatRegistry`]; the merge engine,
+//! `apply` and `diff` only ever go through the registry, so adding a format
+//! (XML, HCL, Java properties, ...) means writing one backend and
+//! registering it in [`FormatRegistry::with_builtins`], optionally behind a
+//! cargo feature.
+//!
+//! Files no backend claims are plain text and are merged line by line.
+
+use super::unicode::{strip_bom, UnicodeConfig};
+use super::{MergeConfig, MergeValue, StyleConfig};
+use crate::core::profile::{self, Phase};
+use crate::core::{JinError, Result};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// File format for parsing and serialization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileFormat {
+    /// JSON format (.json)
+    Json,
+    /// YAML format (.yaml, .yml)
+    Yaml,
+    /// TOML format (.toml)
+    Toml,
+    /// INI format (.ini, .cfg, .conf)
+    Ini,
+    /// Plain text (any other extension)
+    Text,
+    /// Format provided by an additional backend, identified by name
+    Custom(&'static str),
+}
+
+impl FileFormat {
+    /// Whether files of this format are deep-merged rather than text-merged
+    pub fn is_structured(&self) -> bool {
+        *self != FileFormat::Text
+    }
+
+    /// Lowercase format name (`json`, `yaml`, ... or the custom name)
+    pub fn name(&self) -> &'static str {
+        match self {
+            FileFormat::Json => "json",
+            FileFormat::Yaml => "yaml",
+            FileFormat::Toml => "toml",
+            FileFormat::Ini => "ini",
+            FileFormat::Text => "text",
+            FileFormat::Custom(name) => name,
+        }
+    }
+}
+
+/// Hints a backend gives the merge engine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeHints {
+    /// Fields used to match array elements during deep merge
+    pub array_key_fields: &'static [&'static str],
+}
+
+impl Default for MergeHints {
+    fn default() -> Self {
+        Self {
+            array_key_fields: &["id", "name"],
+        }
+    }
+}
+
+impl MergeHints {
+    /// Deep merge configuration corresponding to these hints
+    pub fn merge_config(&self) -> MergeConfig {
+        MergeConfig::with_key_fields(
+            self.array_key_fields
+                .iter()
+                .map(|f| f.to_string())
+                .collect(),
+        )
+    }
+}
+
+/// A structured file format Jin can parse, merge and serialize
+pub trait FormatBackend: Send + Sync {
+    /// Format identifier stored in merge results
+    fn format(&self) -> FileFormat;
+
+    /// File extensions (lowercase, without the dot) handled by this backend
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Whether this backend handles the given path
+    ///
+    /// The default implementation matches the extension case-insensitively.
+    fn detect(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .map(|ext| {
+                let ext = ext.to_lowercase();
+                self.extensions().contains(&ext.as_str())
+            })
+            .unwrap_or(false)
+    }
+
+    /// Parse file content into a merge value
+    fn parse(&self, content: &str) -> Result<MergeValue>;
+
+    /// Serialize a merge value back into file content
+    fn serialize(&self, value: &MergeValue) -> Result<String>;
+
+    /// Merge behavior hints for this format
+    fn merge_hints(&self) -> MergeHints {
+        MergeHints::default()
+    }
+}
+
+/// JSON backend
+pub struct JsonBackend;
+
+impl FormatBackend for JsonBackend {
+    fn format(&self) -> FileFormat {
+        FileFormat::Json
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn parse(&self, content: &str) -> Result<MergeValue> {
+        MergeValue::from_json(content)
+    }
+
+    fn serialize(&self, value: &MergeValue) -> Result<String> {
+        value.to_json_string_with(StyleConfig::current())
+    }
+}
+
+/// YAML backend
+pub struct YamlBackend;
+
+impl FormatBackend for YamlBackend {
+    fn format(&self) -> FileFormat {
+        FileFormat::Yaml
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["yaml", "yml"]
+    }
+
+    fn parse(&self, content: &str) -> Result<MergeValue> {
+        MergeValue::from_yaml(content)
+    }
+
+    fn serialize(&self, value: &MergeValue) -> Result<String> {
+        value.to_yaml_string_with(StyleConfig::current())
+    }
+}
+
+/// TOML backend
+pub struct TomlBackend;
+
+impl FormatBackend for TomlBackend {
+    fn format(&self) -> FileFormat {
+        FileFormat::Toml
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["toml"]
+    }
+
+    fn parse(&self, content: &str) -> Result<MergeValue> {
+        MergeValue::from_toml(content)
+    }
+
+    fn serialize(&self, value: &MergeValue) -> Result<String> {
+        value.to_toml_string_with(StyleConfig::current())
+    }
+}
+
+/// INI backend
+pub struct IniBackend;
+
+impl FormatBackend for IniBackend {
+    fn format(&self) -> FileFormat {
+        FileFormat::Ini
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ini", "cfg", "conf"]
+    }
+
+    fn parse(&self, content: &str) -> Result<MergeValue> {
+        MergeValue::from_ini(content)
+    }
+
+    fn serialize(&self, value: &MergeValue) -> Result<String> {
+        value.to_ini_string_with(StyleConfig::current())
+    }
+}
+
+/// Ordered collection of format backends
+///
+/// Detection tries backends in registration order, most recently
+/// registered first, so a registered backend can override a built-in one.
+#[derive(Default)]
+pub struct FormatRegistry {
+    backends: Vec<Box<dyn FormatBackend>>,
+}
+
+impl FormatRegistry {
+    /// Create an empty registry (every file is plain text)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry with the built-in JSON, YAML, TOML, INI and
+    /// `.jinenv` backends
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(JsonBackend));
+        registry.register(Box::new(YamlBackend));
+        registry.register(Box::new(TomlBackend));
+        registry.register(Box::new(IniBackend));
+        registry.register(Box::new(super::env::EnvBackend));
+        registry
+    }
+
+    /// Add a backend, taking priority over previously registered ones
+    pub fn register(&mut self, backend: Box<dyn FormatBackend>) {
+        self.backends.insert(0, backend);
+    }
+
+    /// Find the backend for a format
+    pub fn backend(&self, format: FileFormat) -> Option<&dyn FormatBackend> {
+        self.backends
+            .iter()
+            .find(|b| b.format() == format)
+            .map(|b| b.as_ref())
+    }
+
+    /// Registered backends, in registration order
+    pub fn backends(&self) -> impl Iterator<Item = &dyn FormatBackend> {
+        self.backends.iter().rev().map(|b| b.as_ref())
+    }
+
+    /// Detect the format of a path; unclaimed paths are plain text
+    pub fn detect(&self, path: &Path) -> FileFormat {
+        self.backends
+            .iter()
+            .find(|b| b.detect(path))
+            .map(|b| b.format())
+            .unwrap_or(FileFormat::Text)
+    }
+
+    /// Parse content; plain text is wrapped as a string value
+    ///
+    /// Structured content is parsed without its byte order mark, and object
+    /// keys are normalized to the configured Unicode form (see
+    /// [`super::unicode`]).
+    pub fn parse(&self, content: &str, format: FileFormat) -> Result<MergeValue> {
+        match self.backend(format) {
+            Some(backend) => {
+                let (content, _) = strip_bom(content);
+                let value = backend.parse(content)?;
+                Ok(UnicodeConfig::current().normalization.normalize_keys(value))
+            }
+            None if format == FileFormat::Text => Ok(MergeValue::String(content.to_string())),
+            None => Err(unknown_format(format)),
+        }
+    }
+
+    /// Serialize a value; plain text must be a string value
+    pub fn serialize(&self, value: &MergeValue, format: FileFormat) -> Result<String> {
+        match self.backend(format) {
+            Some(backend) => backend.serialize(value),
+            None if format == FileFormat::Text => value
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| JinError::Other("Text file has non-string content".to_string())),
+            None => Err(unknown_format(format)),
+        }
+    }
+
+    /// Merge hints for a format (defaults for plain text or unknown formats)
+    pub fn merge_hints(&self, format: FileFormat) -> MergeHints {
+        self.backend(format)
+            .map(|b| b.merge_hints())
+            .unwrap_or_default()
+    }
+}
+
+fn unknown_format(format: FileFormat) -> JinError {
+    JinError::Parse {
+        format: format!("{:?}", format),
+        message: "No backend registered for this format".to_string(),
+    }
+}
+
+/// The process-wide registry used by the merge engine
+pub fn registry() -> &'static FormatRegistry {
+    static REGISTRY: OnceLock<FormatRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(FormatRegistry::with_builtins)
+}
+
+/// Serialize merged content using the global registry
+pub fn serialize_content(value: &MergeValue, format: FileFormat) -> Result<String> {
+    let _span = profile::span(Phase::Serialize);
+    registry().serialize(value, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Minimal `key=value` backend used to exercise registration
+    struct PropertiesBackend;
+
+    impl FormatBackend for PropertiesBackend {
+        fn format(&self) -> FileFormat {
+            FileFormat::Custom("properties")
+        }
+
+        fn extensions(&self) -> &'static [&'static str] {
+            &["properties"]
+        }
+
+        fn parse(&self, content: &str) -> Result<MergeValue> {
+            let map = content
+                .lines()
+                .filter_map(|l| l.split_once('='))
+                .map(|(k, v)| {
+                    (
+                        k.trim().to_string(),
+                        MergeValue::String(v.trim().to_string()),
+                    )
+                })
+                .collect();
+            Ok(MergeValue::Object(map))
+        }
+
+        fn serialize(&self, value: &MergeValue) -> Result<String> {
+            let map = value
+                .as_object()
+                .ok_or_else(|| JinError::Other("expected object".to_string()))?;
+            Ok(map
+                .iter()
+                .map(|(k, v)| format!("{}={}\n", k, v.as_str().unwrap_or_default()))
+                .collect())
+        }
+
+        fn merge_hints(&self) -> MergeHints {
+            MergeHints {
+                array_key_fields: &["key"],
+            }
+        }
+    }
+
+    #[test]
+    fn test_builtin_detection() {
+        let registry = FormatRegistry::with_builtins();
+        assert_eq!(
+            registry.detect(&PathBuf::from("a/config.JSON")),
+            FileFormat::Json
+        );
+        assert_eq!(registry.detect(&PathBuf::from("x.yml")), FileFormat::Yaml);
+        assert_eq!(registry.detect(&PathBuf::from("app.conf")), FileFormat::Ini);
+        assert_eq!(
+            registry.detect(&PathBuf::from("README.md")),
+            FileFormat::Text
+        );
+        assert_eq!(
+            FormatRegistry::new().detect(&PathBuf::from("x.json")),
+            FileFormat::Text
+        );
+    }
+
+    #[test]
+    fn test_text_round_trip_without_backend() {
+        let registry = FormatRegistry::new();
+        let value = registry.parse("hello\n", FileFormat::Text).unwrap();
+        assert_eq!(
+            registry.serialize(&value, FileFormat::Text).unwrap(),
+            "hello\n"
+        );
+        assert!(registry.parse("{}", FileFormat::Json).is_err());
+    }
+
+    #[test]
+    fn test_register_custom_backend() {
+        let mut registry = FormatRegistry::with_builtins();
+        registry.register(Box::new(PropertiesBackend));
+
+        let path = PathBuf::from("app.properties");
+        let format = registry.detect(&path);
+        assert_eq!(format, FileFormat::Custom("properties"));
+        assert!(format.is_structured());
+
+        let value = registry.parse("a = 1\nb=2\n", format).unwrap();
+        assert_eq!(registry.serialize(&value, format).unwrap(), "a=1\nb=2\n");
+        assert_eq!(
+            registry.merge_hints(format).merge_config().array_key_fields,
+            vec!["key".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_non_ascii_keys_with_bom() {
+        let registry = FormatRegistry::with_builtins();
+        let nfd = "cafe\u{301}";
+        for (format, content) in [
+            (
+                FileFormat::Json,
+                format!("{{\"{}\": {{\"名前\": \"ü\"}}}}", nfd),
+            ),
+            (FileFormat::Yaml, format!("{}:\n  名前: ü\n", nfd)),
+            (
+                FileFormat::Toml,
+                format!("[\"{}\"]\n\"名前\" = \"ü\"\n", nfd),
+            ),
+            (FileFormat::Ini, format!("[{}]\n名前 = ü\n", nfd)),
+        ] {
+            let value = registry
+                .parse(&format!("\u{feff}{}", content), format)
+                .unwrap_or_else(|e| panic!("{:?}: {}", format, e));
+            let section = &value.as_object().unwrap()["café"];
+            assert_eq!(section.as_object().unwrap()["名前"].as_str(), Some("ü"));
+
+            let output = registry.serialize(&value, format).unwrap();
+            assert!(
+                output.contains("café") && output.contains("名前"),
+                "{}",
+                output
+            );
+            assert!(!output.starts_with('\u{feff}'));
+        }
+    }
+
+    #[test]
+    fn test_registered_backend_overrides_builtin() {
+        struct RawJson;
+        impl FormatBackend for RawJson {
+            fn format(&self) -> FileFormat {
+                FileFormat::Custom("raw-json")
+            }
+            fn extensions(&self) -> &'static [&'static str] {
+                &["json"]
+            }
+            fn parse(&self, content: &str) -> Result<MergeValue> {
+                Ok(MergeValue::String(content.to_string()))
+            }
+            fn serialize(&self, value: &MergeValue) -> Result<String> {
+                Ok(value.as_str().unwrap_or_default().to_string())
+            }
+        }
+
+        let mut registry = FormatRegistry::with_builtins();
+        registry.register(Box::new(RawJson));
+        assert_eq!(
+            registry.detect(&PathBuf::from("x.json")),
+            FileFormat::Custom("raw-json")
+        );
+    }
+}
@@ -3,9 +3,12 @@
 //! This module provides the file format and parsing for `.jinmerge` files,
 //! which are generated when `jin apply` detects conflicts between layers.
 //!
-//! The format uses Git-compatible conflict markers with layer ref paths as labels:
+//! The format uses Git-compatible conflict markers with layer ref paths as labels.
+//! The header is followed by `#: ` lines explaining what the layers disagree on:
 //! ```text
 //! # Jin merge conflict. Resolve and run 'jin resolve <file>'
+//! #: Conflict between mode/claude/scope:javascript (lower precedence) and ...
+//! #:   key target: mode/claude/scope:javascript wants "es6", ... wants "es2020"
 //! <<<<<<< mode/claude/scope:javascript/
 //! {"target": "es6", "modules": true}
 //! =======
@@ -13,6 +16,9 @@
 //! >>>>>>> mode/claude/project/ui-dashboard/
 //! ```
 //!
+//! When a layer's content has marker-like lines of its own, the markers are
+//! lengthened past them (see [`crate::merge::text`]).
+//!
 //! # Example
 //!
 //! ```ignore
@@ -36,13 +42,24 @@
 //! ```
 
 use crate::core::{JinError, Result};
+use crate::merge::explain::ConflictSummary;
+use crate::merge::text::{
+    conflict_marker_size, marker_size_for, parse_marker, Marker, MIN_MARKER_SIZE,
+};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
 /// Header comment added to all .jinmerge files
 pub const JINMERGE_HEADER: &str = "# Jin merge conflict. Resolve and run 'jin resolve <file>'";
 
-/// Marker constants (Git-compatible - exactly 7 characters)
+/// Prefix of conflict summary lines following the header
+pub const SUMMARY_PREFIX: &str = "#: ";
+
+/// Start of the summary line recording a non-default marker size
+const MARKER_NOTE: &str = "conflict markers are ";
+
+/// Marker constants (Git-compatible - 7 characters, the size used unless
+/// the content has marker-like lines)
 pub const MARKER_START: &str = "<<<<<<< ";
 pub const MARKER_SEP: &str = "=======";
 pub const MARKER_END: &str = ">>>>>>> ";
@@ -77,13 +94,17 @@ pub struct JinMergeConflict {
     pub file_path: PathBuf,
     /// All conflict regions in the file
     pub conflicts: Vec<JinMergeRegion>,
+    /// Explanation of the conflict, written below the header
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub summary: Vec<String>,
 }
 
 impl JinMergeConflict {
     /// Create from text merge result and layer ref paths
     ///
     /// Creates a single conflict region from the provided content.
-    /// Line numbers are calculated based on the content.
+    /// Line numbers are calculated based on the content, and a summary of
+    /// the differing keys or lines is generated for the header.
     ///
     /// # Arguments
     ///
@@ -121,8 +142,18 @@ impl JinMergeConflict {
             .max(layer2_content.lines().count());
         let end_line = line_count + 2; // Account for marker lines
 
+        let summary = ConflictSummary::explain(
+            &file_path,
+            &layer1_ref,
+            &layer1_content,
+            &layer2_ref,
+            &layer2_content,
+        )
+        .to_lines();
+
         Self {
             file_path,
+            summary,
             conflicts: vec![JinMergeRegion {
                 layer1_ref,
                 layer1_content,
@@ -291,31 +322,84 @@ impl JinMergeConflict {
         // Add header
         output.push_str(JINMERGE_HEADER);
         output.push('\n');
+        let size = self.marker_size();
+        for line in &self.summary {
+            output.push_str(SUMMARY_PREFIX);
+            output.push_str(line);
+            output.push('\n');
+        }
+        if size > MIN_MARKER_SIZE {
+            output.push_str(&format!(
+                "{}{}{} characters long; shorter marker-like lines are file content\n",
+                SUMMARY_PREFIX, MARKER_NOTE, size
+            ));
+        }
 
         // Add each conflict region
         for conflict in &self.conflicts {
-            output.push_str(MARKER_START);
-            output.push_str(&conflict.layer1_ref);
-            output.push('\n');
-            output.push_str(&conflict.layer1_content);
-            // Ensure newline after content if not present
-            if !conflict.layer1_content.ends_with('\n') {
-                output.push('\n');
-            }
-            output.push_str(MARKER_SEP);
-            output.push('\n');
-            output.push_str(&conflict.layer2_content);
-            // Ensure newline after content if not present
-            if !conflict.layer2_content.ends_with('\n') {
-                output.push('\n');
-            }
-            output.push_str(MARKER_END);
-            output.push_str(&conflict.layer2_ref);
-            output.push('\n');
+            output.push_str(&format_region(conflict, size));
         }
 
         Ok(output)
     }
+
+    /// Marker size for this file: longer than any marker-like line in the
+    /// layer contents
+    fn marker_size(&self) -> usize {
+        let contents: Vec<&str> = self
+            .conflicts
+            .iter()
+            .flat_map(|c| [c.layer1_content.as_str(), c.layer2_content.as_str()])
+            .collect();
+        marker_size_for(&contents)
+    }
+}
+
+/// Render one conflict region with markers of `size`
+pub fn format_region(region: &JinMergeRegion, size: usize) -> String {
+    let mut output = format!("{} {}\n", "<".repeat(size), region.layer1_ref);
+    output.push_str(&region.layer1_content);
+    // Ensure newline after content if not present
+    if !region.layer1_content.ends_with('\n') {
+        output.push('\n');
+    }
+    output.push_str(&"=".repeat(size));
+    output.push('\n');
+    output.push_str(&region.layer2_content);
+    // Ensure newline after content if not present
+    if !region.layer2_content.ends_with('\n') {
+        output.push('\n');
+    }
+    output.push_str(&format!("{} {}\n", ">".repeat(size), region.layer2_ref));
+    output
+}
+
+/// Size of the conflict markers in `.jinmerge` content
+///
+/// Taken from the summary note written with lengthened markers, so it is
+/// still known once the regions are resolved; otherwise the size of the
+/// markers present, or the Git default.
+pub fn marker_size(content: &str) -> usize {
+    content
+        .lines()
+        .skip(1)
+        .take_while(|line| line.starts_with(SUMMARY_PREFIX))
+        .find_map(|line| {
+            line[SUMMARY_PREFIX.len()..]
+                .strip_prefix(MARKER_NOTE)?
+                .split(' ')
+                .next()?
+                .parse()
+                .ok()
+        })
+        .or_else(|| conflict_marker_size(content))
+        .unwrap_or(MIN_MARKER_SIZE)
+}
+
+/// Label after a marker of `size`, if it has one
+fn marker_label(line: &str, size: usize) -> Option<String> {
+    let label = line.get(size..)?.trim();
+    (!label.is_empty()).then(|| label.to_string())
 }
 
 /// Parse .jinmerge content into a JinMergeConflict
@@ -331,24 +415,34 @@ fn parse_jinmerge_content(content: &str, merge_path: &Path) -> Result<JinMergeCo
         i += 1;
     }
 
+    // Collect summary lines
+    let mut summary = Vec::new();
+    while i < lines.len() && lines[i].starts_with(SUMMARY_PREFIX) {
+        let line = &lines[i][SUMMARY_PREFIX.len()..];
+        if !line.starts_with(MARKER_NOTE) {
+            summary.push(line.to_string());
+        }
+        i += 1;
+    }
+
+    // Marker-like lines of other sizes are layer content
+    let size = marker_size(content);
+    let is = |line: &str, kind: Marker| parse_marker(line) == Some((kind, size));
+
     while i < lines.len() {
-        if lines[i].starts_with("<<<<<<<") {
+        if is(lines[i], Marker::Start) {
             let start_line = i + 1; // 1-indexed
 
             // Extract layer1_ref from start marker
-            let layer1_ref = if lines[i].len() > MARKER_START.len() {
-                lines[i][MARKER_START.len()..].trim().to_string()
-            } else {
-                return Err(JinError::Parse {
-                    format: "jinmerge".to_string(),
-                    message: "Missing layer ref in start marker".to_string(),
-                });
-            };
+            let layer1_ref = marker_label(lines[i], size).ok_or_else(|| JinError::Parse {
+                format: "jinmerge".to_string(),
+                message: "Missing layer ref in start marker".to_string(),
+            })?;
 
             // Find separator (=======)
             let sep_idx = lines[i..]
                 .iter()
-                .position(|l| l.starts_with(MARKER_SEP))
+                .position(|l| is(l, Marker::Separator))
                 .ok_or_else(|| JinError::Parse {
                     format: "jinmerge".to_string(),
                     message: "Missing separator marker".to_string(),
@@ -361,7 +455,7 @@ fn parse_jinmerge_content(content: &str, merge_path: &Path) -> Result<JinMergeCo
             // Find end marker (>>>>>>>)
             let end_idx = lines[sep_idx..]
                 .iter()
-                .position(|l| l.starts_with(">>>>>>>"))
+                .position(|l| is(l, Marker::End))
                 .ok_or_else(|| JinError::Parse {
                     format: "jinmerge".to_string(),
                     message: "Missing end marker".to_string(),
@@ -369,14 +463,10 @@ fn parse_jinmerge_content(content: &str, merge_path: &Path) -> Result<JinMergeCo
             let end_idx = sep_idx + end_idx;
 
             // Extract layer2_ref from end marker
-            let layer2_ref = if lines[end_idx].len() > MARKER_END.len() {
-                lines[end_idx][MARKER_END.len()..].trim().to_string()
-            } else {
-                return Err(JinError::Parse {
-                    format: "jinmerge".to_string(),
-                    message: "Missing layer ref in end marker".to_string(),
-                });
-            };
+            let layer2_ref = marker_label(lines[end_idx], size).ok_or_else(|| JinError::Parse {
+                format: "jinmerge".to_string(),
+                message: "Missing layer ref in end marker".to_string(),
+            })?;
 
             // Extract layer2 content
             let layer2_content = lines[sep_idx + 1..end_idx].join("\n");
@@ -400,18 +490,107 @@ fn parse_jinmerge_content(content: &str, merge_path: &Path) -> Result<JinMergeCo
     // Use just the file name (without directory prefix) and remove .jinmerge extension
     let file_name = merge_path
         .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown");
+        .map(|n| n.to_string_lossy())
+        .unwrap_or(std::borrow::Cow::Borrowed("unknown"));
     // Remove .jinmerge extension if present
-    let file_name = file_name.strip_suffix(".jinmerge").unwrap_or(file_name);
+    let file_name = file_name.strip_suffix(".jinmerge").unwrap_or(&file_name);
     let file_path = PathBuf::from(file_name);
 
     Ok(JinMergeConflict {
         file_path,
         conflicts,
+        summary,
     })
 }
 
+/// Remove the conflict summary lines that follow the `.jinmerge` header
+///
+/// The summary only explains the conflict; it must not end up in the
+/// resolved file. Other content, including the header line itself and any
+/// ordinary `#` comments, is left untouched.
+///
+/// # Example
+///
+/// ```
+/// use jin::merge::jinmerge::strip_summary;
+///
+/// let content = "# Jin merge conflict. Resolve and run 'jin resolve <file>'\n#: note\nport=80\n";
+/// assert_eq!(
+///     strip_summary(content),
+///     "# Jin merge conflict. Resolve and run 'jin resolve <file>'\nport=80\n"
+/// );
+/// ```
+pub fn strip_summary(content: &str) -> String {
+    let Some((header, mut rest)) = content.split_once('\n') else {
+        return content.to_string();
+    };
+    if !header.starts_with("# Jin merge conflict") {
+        return content.to_string();
+    }
+    while rest.starts_with(SUMMARY_PREFIX) {
+        rest = rest.split_once('\n').map(|(_, r)| r).unwrap_or("");
+    }
+    format!("{}\n{}", header, rest)
+}
+
+/// Replace each conflict region of `.jinmerge` content with its resolution
+///
+/// `resolutions` holds the chosen text for every region, in file order. The
+/// header and summary lines are dropped; content outside the regions is kept.
+///
+/// # Errors
+///
+/// Returns `JinError::Parse` if a region is unterminated or the number of
+/// resolutions doesn't match the number of regions.
+pub fn apply_resolutions(content: &str, resolutions: &[String]) -> Result<String> {
+    let size = marker_size(content);
+    let content = strip_summary(content);
+    let mut lines = content.lines().peekable();
+    if lines
+        .peek()
+        .is_some_and(|l| l.starts_with("# Jin merge conflict"))
+    {
+        lines.next();
+    }
+
+    let is = |line: &str, kind: Marker| parse_marker(line) == Some((kind, size));
+
+    let mut output = String::new();
+    let mut remaining = resolutions.iter();
+    while let Some(line) = lines.next() {
+        if !is(line, Marker::Start) {
+            output.push_str(line);
+            output.push('\n');
+            continue;
+        }
+
+        let resolution = remaining.next().ok_or_else(|| JinError::Parse {
+            format: "jinmerge".to_string(),
+            message: "More conflict regions than resolutions".to_string(),
+        })?;
+        if !lines.any(|l| is(l, Marker::End)) {
+            return Err(JinError::Parse {
+                format: "jinmerge".to_string(),
+                message: "Missing end marker".to_string(),
+            });
+        }
+        if !resolution.is_empty() {
+            output.push_str(resolution);
+            if !resolution.ends_with('\n') {
+                output.push('\n');
+            }
+        }
+    }
+
+    if remaining.next().is_some() {
+        return Err(JinError::Parse {
+            format: "jinmerge".to_string(),
+            message: "Fewer conflict regions than resolutions".to_string(),
+        });
+    }
+    Ok(output)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -672,6 +851,42 @@ mod tests {
         assert_eq!(parsed.conflicts[1].layer1_content, "content3");
     }
 
+    #[test]
+    fn test_marker_like_content_gets_longer_markers() {
+        let temp = TempDir::new().unwrap();
+        let merge_path = temp.path().join("GIT.md.jinmerge");
+
+        let docs = "Conflicts\n=========\n<<<<<<< HEAD\nmine\n=======\ntheirs\n>>>>>>> topic";
+        let original = JinMergeConflict::from_text_merge(
+            PathBuf::from("GIT.md"),
+            "global/".to_string(),
+            docs.to_string(),
+            "mode/claude/".to_string(),
+            "Short docs".to_string(),
+        );
+        original.write_to_file(&merge_path).unwrap();
+
+        let content = fs::read_to_string(&merge_path).unwrap();
+        assert!(content.contains("\n<<<<<<<<<< global/\n"));
+        assert!(content.contains("#: conflict markers are 10 characters long"));
+        assert_eq!(marker_size(&content), 10);
+
+        let parsed = JinMergeConflict::parse_from_file(&merge_path).unwrap();
+        assert_eq!(parsed.conflicts.len(), 1);
+        assert_eq!(parsed.conflicts[0].layer1_content, docs);
+        assert_eq!(parsed.summary, original.summary);
+
+        let resolved = apply_resolutions(&content, &[docs.to_string()]).unwrap();
+        assert_eq!(resolved, format!("{}\n", docs));
+        // The size is still known after the regions are resolved
+        let header_and_note: String = content
+            .lines()
+            .take_while(|l| l.starts_with('#'))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(marker_size(&format!("{}\n{}", header_and_note, docs)), 10);
+    }
+
     // ========== Round-trip Tests ==========
 
     #[test]
@@ -730,11 +945,76 @@ mod tests {
         let conflict = JinMergeConflict {
             file_path: PathBuf::from("test.txt"),
             conflicts: vec![],
+            summary: vec![],
         };
 
         assert_eq!(conflict.conflict_count(), 0);
     }
 
+    #[test]
+    fn test_summary_written_and_parsed() {
+        let temp = TempDir::new().unwrap();
+        let merge_path = temp.path().join("app.txt.jinmerge");
+
+        let original = JinMergeConflict::from_text_merge(
+            PathBuf::from("app.txt"),
+            "global/".to_string(),
+            "port=80\n".to_string(),
+            "mode/claude/_".to_string(),
+            "port=8080\n".to_string(),
+        );
+        original.write_to_file(&merge_path).unwrap();
+
+        let content = fs::read_to_string(&merge_path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines[0], JINMERGE_HEADER);
+        assert_eq!(
+            lines[1],
+            "#: Conflict between global (lower precedence) and mode/claude (higher precedence)"
+        );
+        assert_eq!(
+            lines[2],
+            "#:   line 1: global wants \"port=80\", mode/claude wants \"port=8080\""
+        );
+        assert!(lines[3].starts_with(MARKER_START));
+
+        let parsed = JinMergeConflict::parse_from_file(&merge_path).unwrap();
+        assert_eq!(parsed.summary, original.summary);
+        assert_eq!(parsed.conflicts[0].layer1_content, "port=80");
+    }
+
+    #[test]
+    fn test_strip_summary() {
+        let content = format!("{}\n#: summary\n#: more\nkept\n", JINMERGE_HEADER);
+        assert_eq!(
+            strip_summary(&content),
+            format!("{}\nkept\n", JINMERGE_HEADER)
+        );
+
+        // Ordinary comments in resolved content are preserved
+        let content = format!("{}\n# user comment\nkept\n", JINMERGE_HEADER);
+        assert_eq!(strip_summary(&content), content);
+
+        assert_eq!(
+            strip_summary("no header\n#: kept\n"),
+            "no header\n#: kept\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_resolutions() {
+        let content = format!(
+            "{}\n#: summary\nbefore\n<<<<<<< global/\na\n=======\nb\n>>>>>>> mode/x/\nmiddle\n<<<<<<< global/\nc\n=======\nd\n>>>>>>> mode/x/\n",
+            JINMERGE_HEADER
+        );
+
+        let resolved = apply_resolutions(&content, &["b".to_string(), "c\nd".to_string()]).unwrap();
+        assert_eq!(resolved, "before\nb\nmiddle\nc\nd\n");
+
+        assert!(apply_resolutions(&content, &["b".to_string()]).is_err());
+        assert!(apply_resolutions("<<<<<<< a/\nx\n=======\n", &["x".to_string()]).is_err());
+    }
+
     #[test]
     fn test_is_jinmerge_file_by_extension() {
         let temp = TempDir::new().unwrap();
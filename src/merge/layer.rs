@@ -2,30 +2,31 @@
 //!
 //! This module handles merging configuration files across Jin's 9-layer
 //! hierarchy. Files at higher precedence layers override lower layers,
-//! with structured files (JSON, YAML, TOML, INI) being deep-merged
-//! according to RFC 7396 semantics.
-
-use crate::core::{JinError, Layer, Result};
-use crate::git::{JinRepo, RefOps, TreeOps};
+//! with structured files (any format with a registered backend, see
+//! [`super::format`]) being deep-merged according to RFC 7396 semantics.
+
+use crate::core::limits::{format_size, LimitsConfig};
+use crate::core::profile::{self, Phase};
+use crate::core::progress;
+use crate::core::{JinError, Layer, LocalExpiry, Result};
+use crate::git::archive::ArchiveSet;
+use crate::git::JinRepo;
+use crate::staging::owners::OWNERS_PATH;
+use crate::store::{LayerStore, Revision};
+use chrono::Utc;
 use std::collections::HashSet;
-use std::path::PathBuf;
-
-use super::{deep_merge, text_merge, MergeValue, TextMergeResult};
-
-/// File format for parsing and serialization
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum FileFormat {
-    /// JSON format (.json)
-    Json,
-    /// YAML format (.yaml, .yml)
-    Yaml,
-    /// TOML format (.toml)
-    Toml,
-    /// INI format (.ini, .cfg, .conf)
-    Ini,
-    /// Plain text (any other extension)
-    Text,
-}
+use std::path::{Path, PathBuf};
+
+use super::driver::{MergeDriver, MergeDrivers};
+use super::eol::{to_lf, LineEndings};
+use super::format::registry;
+pub use super::format::FileFormat;
+use super::strict::{overwritten_scalars, StrictMerges, StrictRule};
+use super::unicode::{strip_bom, BOM};
+use super::{
+    deep_merge_checked, strip_final_markers, text_merge_with_config, FinalKeysConfig, FinalPolicy,
+    MergeOptions, MergeValue, TextMergeResult,
+};
 
 /// Represents a merged file across multiple layers
 #[derive(Debug)]
@@ -36,6 +37,21 @@ pub struct MergedFile {
     pub source_layers: Vec<Layer>,
     /// Original format (for serialization)
     pub format: FileFormat,
+    /// Whether the winning layer's copy started with a byte order mark,
+    /// which is restored on output
+    pub bom: bool,
+}
+
+impl MergedFile {
+    /// Serialized content, with its byte order mark when it had one
+    pub fn serialize(&self) -> Result<String> {
+        let content = super::serialize_content(&self.content, self.format)?;
+        Ok(if self.bom {
+            format!("{}{}", BOM, content)
+        } else {
+            content
+        })
+    }
 }
 
 /// Configuration for a layer merge operation
@@ -49,6 +65,8 @@ pub struct LayerMergeConfig {
     pub scope: Option<String>,
     /// Project name
     pub project: Option<String>,
+    /// Workspace root, for its `.jinattributes` and merge cache
+    pub root: PathBuf,
 }
 
 /// Result of a layer merge operation
@@ -62,6 +80,9 @@ pub struct LayerMergeResult {
     pub added_files: Vec<PathBuf>,
     /// Files that were removed (deleted in higher layer)
     pub removed_files: Vec<PathBuf>,
+    /// Warnings printed while merging (final keys a layer could not
+    /// override), kept so a cached result reports them again
+    pub warnings: Vec<String>,
 }
 
 impl Default for LayerMergeResult {
@@ -78,6 +99,7 @@ impl LayerMergeResult {
             conflict_files: Vec::new(),
             added_files: Vec::new(),
             removed_files: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 
@@ -107,45 +129,78 @@ impl LayerMergeResult {
 ///
 /// * `LayerMergeResult` with merged files and their content, plus conflict/added/removed files
 pub fn merge_layers(config: &LayerMergeConfig, repo: &JinRepo) -> Result<LayerMergeResult> {
-    eprintln!(
-        "[DEBUG] merge_layers: Starting with {} layers",
-        config.layers.len()
-    );
+    let _span = profile::span(Phase::Merge);
     let mut result = LayerMergeResult::new();
 
+    // Modes and scopes archived with --no-merge are left out entirely
+    let archived = ArchiveSet::load_or_default(repo);
+    let config = &LayerMergeConfig {
+        layers: config
+            .layers
+            .iter()
+            .copied()
+            .filter(|layer| {
+                !archived.excludes(*layer, config.mode.as_deref(), config.scope.as_deref())
+            })
+            .collect(),
+        mode: config.mode.clone(),
+        scope: config.scope.clone(),
+        project: config.project.clone(),
+        root: config.root.clone(),
+    };
+
     // Collect all unique file paths across all layers
     let all_paths = collect_all_file_paths(&config.layers, config, repo)?;
-    eprintln!(
-        "[DEBUG] merge_layers: Collected {} unique file paths",
-        all_paths.len()
-    );
-    eprintln!("[DEBUG] merge_layers: File paths: {:?}", all_paths);
+
+    // Expired user-local overrides are left out, as if never committed
+    let expiry = if config.layers.contains(&Layer::UserLocal) {
+        LocalExpiry::load_or_default(repo.path())
+    } else {
+        LocalExpiry::default()
+    };
+    let now = Utc::now();
 
     // Merge each file path
     for path in &all_paths {
-        eprintln!("[DEBUG] merge_layers: Processing path: {}", path.display());
         // ============================================================
         // NEW: Collision detection BEFORE merge_file_across_layers()
         // ============================================================
-        let layers_with_file = find_layers_containing_file(path, &config.layers, config, repo)?;
-        eprintln!(
-            "[DEBUG] merge_layers: Layers with file: {:?}",
-            layers_with_file
-        );
+        let expired = expiry.is_expired(path, now);
+        let layers: Vec<Layer> = config
+            .layers
+            .iter()
+            .copied()
+            .filter(|layer| !(expired && *layer == Layer::UserLocal))
+            .collect();
+        let layers_with_file = find_layers_containing_file(path, &layers, config, repo)?;
+        if layers_with_file.is_empty() {
+            continue;
+        }
 
-        if layers_with_file.len() > 1 {
-            // Detect file format to determine conflict check strategy
-            let format = detect_format(path);
-            eprintln!("[DEBUG] merge_layers: File format: {:?}", format);
+        // Paths with an external merge driver bypass the built-in merges
+        if let Some(driver) = MergeDrivers::current().find(path) {
+            if layers_with_file.len() > 1 {
+                match merge_with_driver(path, driver, &layers_with_file, config, repo) {
+                    Ok(merged) => {
+                        result.merged_files.insert(path.clone(), merged);
+                    }
+                    Err(JinError::MergeConflict { .. }) => {
+                        result.conflict_files.push(path.clone());
+                    }
+                    Err(e) => return Err(e),
+                }
+                continue;
+            }
+        }
+
+        // Detect file format (oversized structured files are handled as text)
+        let format = merge_format(path, &layers_with_file, config, repo)?;
 
+        if layers_with_file.len() > 1 {
             // Only check for conflicts in text files (line-based 3-way merge)
             if format == FileFormat::Text {
                 let has_conflict =
                     has_different_text_content(path, &layers_with_file, config, repo)?;
-                eprintln!(
-                    "[DEBUG] merge_layers: Text file has conflict: {}",
-                    has_conflict
-                );
 
                 if has_conflict {
                     // Different text content detected - add to conflicts and skip merge
@@ -160,7 +215,7 @@ pub fn merge_layers(config: &LayerMergeConfig, repo: &JinRepo) -> Result<LayerMe
                 true
             } else {
                 // For structured files: check if semantic content is identical
-                !has_different_content_across_layers(path, &layers_with_file, config, repo)?
+                !has_different_structured_content(path, &layers_with_file, config, repo, format)?
             };
 
             if same_content {
@@ -169,11 +224,8 @@ pub fn merge_layers(config: &LayerMergeConfig, repo: &JinRepo) -> Result<LayerMe
                 // ============================================================
                 // All layers have identical content - use first layer directly
                 let first_layer = &layers_with_file[0];
-                let merged = create_merged_file_from_first_layer(path, first_layer, config, repo);
-                eprintln!(
-                    "[DEBUG] merge_layers: Merged result (same content): {:?}",
-                    merged.is_ok()
-                );
+                let merged =
+                    create_merged_file_from_first_layer(path, first_layer, format, config, repo);
 
                 let mut merged = merged?;
 
@@ -185,38 +237,32 @@ pub fn merge_layers(config: &LayerMergeConfig, repo: &JinRepo) -> Result<LayerMe
                 result.merged_files.insert(path.clone(), merged);
                 continue; // Skip merge_file_across_layers() - optimization complete
             }
+            // Strict paths conflict instead of letting the higher layer win
+            if let Some(rule) = StrictMerges::current().find(path) {
+                if !strict_overwrites(path, rule, &layers_with_file, format, config, repo)?
+                    .is_empty()
+                {
+                    result.conflict_files.push(path.clone());
+                    continue;
+                }
+            }
             // For structured files with different content: proceed to deep merge below
         }
 
         // ============================================================
         // EXISTING: Merge logic (for non-conflicting files)
         // ============================================================
-        match merge_file_across_layers(path, &config.layers, config, repo) {
+        match merge_file_across_layers(path, &layers, format, config, repo, &mut result.warnings) {
             Ok(merged) => {
-                eprintln!("[DEBUG] merge_layers: Merged result (merge_file_across_layers): Ok");
                 result.merged_files.insert(path.clone(), merged);
             }
             Err(JinError::MergeConflict { .. }) => {
-                eprintln!(
-                    "[DEBUG] merge_layers: Merged result (merge_file_across_layers): MergeConflict"
-                );
                 result.conflict_files.push(path.clone());
             }
-            Err(e) => {
-                eprintln!(
-                    "[DEBUG] merge_layers: Merged result (merge_file_across_layers): Error {:?}",
-                    e
-                );
-                return Err(e);
-            }
+            Err(e) => return Err(e),
         }
     }
 
-    eprintln!(
-        "[DEBUG] merge_layers: Returning with {} merged files, {} conflicts",
-        result.merged_files.len(),
-        result.conflict_files.len()
-    );
     Ok(result)
 }
 
@@ -227,112 +273,114 @@ pub fn merge_layers(config: &LayerMergeConfig, repo: &JinRepo) -> Result<LayerMe
 fn collect_all_file_paths(
     layers: &[Layer],
     config: &LayerMergeConfig,
-    repo: &JinRepo,
+    store: &dyn LayerStore,
 ) -> Result<HashSet<PathBuf>> {
-    eprintln!(
-        "[DEBUG] collect_all_file_paths: Checking {} layers",
-        layers.len()
-    );
     let mut paths = HashSet::new();
 
     for layer in layers {
-        let ref_path = layer.ref_path(
-            config.mode.as_deref(),
-            config.scope.as_deref(),
-            config.project.as_deref(),
-        );
-        eprintln!(
-            "[DEBUG] collect_all_file_paths: Layer {:?}, ref_path: {}",
-            layer, ref_path
-        );
+        // Layer never written = no files in this layer (skip gracefully)
+        let Some(revision) = layer_revision(layer, config, store)? else {
+            continue;
+        };
 
-        // CRITICAL: Check ref_exists() before resolve_ref()
-        eprintln!(
-            "[DEBUG] collect_all_file_paths: ref_exists: {}",
-            repo.ref_exists(&ref_path)
-        );
-        if repo.ref_exists(&ref_path) {
-            if let Ok(commit_oid) = repo.resolve_ref(&ref_path) {
-                eprintln!(
-                    "[DEBUG] collect_all_file_paths: Resolved commit_oid: {:?}",
-                    commit_oid
-                );
-                let commit = repo.inner().find_commit(commit_oid)?;
-                let tree_oid = commit.tree_id();
-
-                for file_path in repo.list_tree_files(tree_oid)? {
-                    eprintln!("[DEBUG] collect_all_file_paths: Tree file: {:?}", file_path);
-                    paths.insert(PathBuf::from(file_path));
-                }
+        for file_path in store.files(&revision)? {
+            // Ownership belongs to each layer and is never merged
+            if file_path == Path::new(OWNERS_PATH) {
+                continue;
             }
+            paths.insert(file_path);
         }
-        // Layer ref doesn't exist = no files in this layer (skip gracefully)
     }
 
-    eprintln!(
-        "[DEBUG] collect_all_file_paths: Total paths collected: {}",
-        paths.len()
-    );
     Ok(paths)
 }
 
+/// Current revision of a layer in the merge context, `None` if the layer
+/// has never been written
+fn layer_revision(
+    layer: &Layer,
+    config: &LayerMergeConfig,
+    store: &dyn LayerStore,
+) -> Result<Option<Revision>> {
+    store.head(&layer.ref_path(
+        config.mode.as_deref(),
+        config.scope.as_deref(),
+        config.project.as_deref(),
+    ))
+}
+
+/// Content of a file in a layer known to contain it
+fn read_layer_file(
+    path: &Path,
+    layer: &Layer,
+    config: &LayerMergeConfig,
+    store: &dyn LayerStore,
+) -> Result<Vec<u8>> {
+    let missing = || {
+        JinError::NotFound(format!(
+            "{} in {}",
+            path.display(),
+            layer.ref_path(
+                config.mode.as_deref(),
+                config.scope.as_deref(),
+                config.project.as_deref(),
+            )
+        ))
+    };
+    let revision = layer_revision(layer, config, store)?.ok_or_else(missing)?;
+    store.read(&revision, path)?.ok_or_else(missing)
+}
+
 /// Merge a single file across multiple layers.
 ///
 /// Reads the file content from each layer that contains it,
-/// and merges according to file format:
+/// and merges according to file format (paths with an external merge driver
+/// are handled by [`merge_with_driver`] instead):
 /// - Text files: 3-way line-level merge using text_merge()
 /// - Structured files: deep merge using deep_merge()
 fn merge_file_across_layers(
     path: &std::path::Path,
     layers: &[Layer],
+    format: FileFormat,
     config: &LayerMergeConfig,
-    repo: &JinRepo,
+    store: &dyn LayerStore,
+    warnings: &mut Vec<String>,
 ) -> Result<MergedFile> {
     // First, collect all layers with this file's content
-    let mut text_contents: Vec<(Layer, String)> = Vec::new();
-    let mut source_layers = Vec::new();
-    let mut format = FileFormat::Text;
-
-    for layer in layers {
-        let ref_path = layer.ref_path(
-            config.mode.as_deref(),
-            config.scope.as_deref(),
-            config.project.as_deref(),
-        );
-
-        // CRITICAL: Check ref_exists() before resolve_ref()
-        if !repo.ref_exists(&ref_path) {
-            continue;
-        }
-
-        if let Ok(commit_oid) = repo.resolve_ref(&ref_path) {
-            let commit = repo.inner().find_commit(commit_oid)?;
-            let tree_oid = commit.tree_id();
-
-            if let Ok(content) = repo.read_file_from_tree(tree_oid, path) {
-                let content_str = String::from_utf8_lossy(&content);
-                format = detect_format(path);
-                source_layers.push(*layer);
-                text_contents.push((*layer, content_str.to_string()));
-            }
-        }
-    }
-
-    // Handle empty result (no layers had this file)
-    if text_contents.is_empty() {
-        return Err(JinError::NotFound(path.display().to_string()));
-    }
+    let text_contents = read_layer_contents(path, layers, config, store)?;
+    let source_layers: Vec<Layer> = text_contents.iter().map(|(layer, _)| *layer).collect();
+
+    // Merge without byte order marks, so a BOM alone is never a difference
+    let bom = text_contents
+        .last()
+        .is_some_and(|(_, content)| strip_bom(content).1);
+    let text_contents: Vec<(Layer, String)> = text_contents
+        .into_iter()
+        .map(|(layer, content)| (layer, strip_bom(&content).0.to_string()))
+        .collect();
 
     // ============================================================
     // TEXT FILE ROUTING: Use 3-way text_merge() for line-level merge
     // ============================================================
     if format == FileFormat::Text {
+        // Merge on normalized line endings; apply writes the policy's endings
+        let text_contents: Vec<(Layer, String)> =
+            if LineEndings::current(&config.root).policy(path).normalizes() {
+                text_contents
+                    .into_iter()
+                    .map(|(layer, content)| (layer, to_lf(&content).into_owned()))
+                    .collect()
+            } else {
+                text_contents
+            };
+
         // Single layer: return content directly
         if text_contents.len() == 1 {
             return Ok(MergedFile {
                 content: MergeValue::String(text_contents[0].1.clone()),
                 source_layers,
                 format,
+                bom,
             });
         }
 
@@ -342,8 +390,9 @@ fn merge_file_across_layers(
         let mut merged = base.clone();
 
         // Iterate through remaining layers, merging each into the accumulated result
+        let text_config = MergeOptions::current().text_config();
         for (_, theirs) in text_contents.iter().skip(1) {
-            match text_merge(base, &merged, theirs)? {
+            match text_merge_with_config(base, &merged, theirs, &text_config)? {
                 TextMergeResult::Clean(clean_content) => {
                     merged = clean_content;
                 }
@@ -353,6 +402,7 @@ fn merge_file_across_layers(
                         content: MergeValue::String(content),
                         source_layers,
                         format,
+                        bom,
                     });
                 }
             }
@@ -363,31 +413,173 @@ fn merge_file_across_layers(
             content: MergeValue::String(merged),
             source_layers,
             format,
+            bom,
         });
     }
 
     // ============================================================
     // STRUCTURED FILE ROUTING: Use deep_merge() for JSON/YAML/TOML/INI
     // ============================================================
+    let merge_config = MergeOptions::current().merge_config(registry().merge_hints(format));
     let mut accumulated: Option<MergeValue> = None;
-    for (_layer, content_str) in text_contents {
+    for (layer, content_str) in text_contents {
         let layer_value = parse_content(&content_str, format)?;
         accumulated = Some(match accumulated {
-            Some(base) => deep_merge(base, layer_value)?,
+            Some(base) => {
+                let (merged, violations) = deep_merge_checked(base, layer_value, &merge_config)?;
+                check_final_violations(path, layer, &violations, warnings)?;
+                merged
+            }
             None => layer_value,
         });
     }
 
     match accumulated {
-        Some(content) => Ok(MergedFile {
-            content,
-            source_layers,
-            format,
-        }),
+        Some(mut content) => {
+            strip_final_markers(&mut content);
+            Ok(MergedFile {
+                content,
+                source_layers,
+                format,
+                bom,
+            })
+        }
         None => Err(JinError::NotFound(path.display().to_string())),
     }
 }
 
+/// Read a file's content from every layer that contains it, lowest first
+///
+/// Fails with `NotFound` when no layer has the file.
+pub(crate) fn read_layer_contents(
+    path: &Path,
+    layers: &[Layer],
+    config: &LayerMergeConfig,
+    store: &dyn LayerStore,
+) -> Result<Vec<(Layer, String)>> {
+    let mut contents = Vec::new();
+
+    for layer in layers {
+        let Some(revision) = layer_revision(layer, config, store)? else {
+            continue;
+        };
+        if let Some(content) = store.read(&revision, path)? {
+            contents.push((*layer, String::from_utf8_lossy(&content).to_string()));
+        }
+    }
+
+    if contents.is_empty() {
+        return Err(JinError::NotFound(path.display().to_string()));
+    }
+    Ok(contents)
+}
+
+/// Merge a file across layers with an external merge driver.
+///
+/// Layers are folded like text files: the lowest layer is the base, and
+/// each higher layer is merged into the accumulated result. The result is
+/// kept verbatim as text.
+fn merge_with_driver(
+    path: &std::path::Path,
+    driver: &MergeDriver,
+    layers: &[Layer],
+    config: &LayerMergeConfig,
+    store: &dyn LayerStore,
+) -> Result<MergedFile> {
+    let contents = read_layer_contents(path, layers, config, store)?;
+    let base = &contents[0].1;
+    let mut merged = base.clone();
+
+    for (_, theirs) in contents.iter().skip(1) {
+        match driver.merge(&config.root, path, base, &merged, theirs)? {
+            TextMergeResult::Clean(content) => merged = content,
+            TextMergeResult::Conflict { .. } => {
+                return Err(JinError::MergeConflict {
+                    path: path.display().to_string(),
+                })
+            }
+        }
+    }
+
+    Ok(MergedFile {
+        content: MergeValue::String(merged),
+        source_layers: contents.iter().map(|(layer, _)| *layer).collect(),
+        format: FileFormat::Text,
+        bom: false,
+    })
+}
+
+/// Scalar keys two layers covered by a strict rule set to different values
+///
+/// Every pair of covered layers holding the file is compared, so a
+/// disagreement is found even when a layer in between sets the same key.
+fn strict_overwrites(
+    path: &std::path::Path,
+    rule: &StrictRule,
+    layers: &[Layer],
+    format: FileFormat,
+    config: &LayerMergeConfig,
+    store: &dyn LayerStore,
+) -> Result<Vec<String>> {
+    let covered: Vec<Layer> = layers
+        .iter()
+        .copied()
+        .filter(|layer| rule.covers(*layer))
+        .collect();
+    if covered.len() < 2 {
+        return Ok(Vec::new());
+    }
+
+    let values = read_layer_contents(path, &covered, config, store)?
+        .into_iter()
+        .map(|(_, content)| parse_content(strip_bom(&content).0, format))
+        .collect::<Result<Vec<_>>>()?;
+    let mut keys = Vec::new();
+    for (i, lower) in values.iter().enumerate() {
+        for higher in &values[i + 1..] {
+            for key in overwritten_scalars(lower, higher) {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+    }
+    Ok(keys)
+}
+
+/// Report overrides of final keys by `layer` according to the configured
+/// policy, adding printed warnings to `warnings`
+fn check_final_violations(
+    path: &std::path::Path,
+    layer: Layer,
+    violations: &[String],
+    warnings: &mut Vec<String>,
+) -> Result<()> {
+    if violations.is_empty() {
+        return Ok(());
+    }
+    let keys = violations.join(", ");
+    match FinalKeysConfig::current().policy {
+        FinalPolicy::Warn => {
+            let warning = format!(
+                "{}: {} cannot override final key(s) {}; keeping the pinned value",
+                path.display(),
+                layer,
+                keys
+            );
+            progress::warn(&warning);
+            warnings.push(warning);
+            Ok(())
+        }
+        FinalPolicy::Error => Err(JinError::Config(format!(
+            "{}: {} overrides final key(s) {} pinned by a lower layer",
+            path.display(),
+            layer,
+            keys
+        ))),
+    }
+}
+
 /// Create a MergedFile directly from a single layer's content.
 ///
 /// Used as an optimization when all layers containing a file have identical
@@ -398,68 +590,99 @@ fn merge_file_across_layers(
 ///
 /// * `path` - Path to the file (relative to repo root)
 /// * `layer` - The layer to read content from
+/// * `format` - Format to parse the content as
 /// * `config` - Merge configuration with mode/scope/project context
-/// * `repo` - Jin repository for Git operations
+/// * `store` - Storage to read layers from
 ///
 /// # Returns
 ///
 /// * `Ok(MergedFile)` - File content with metadata
 /// * `Err(JinError)` - Git operation or parse failure
 fn create_merged_file_from_first_layer(
-    path: &std::path::Path,
+    path: &Path,
     layer: &Layer,
+    format: FileFormat,
     config: &LayerMergeConfig,
-    repo: &JinRepo,
+    store: &dyn LayerStore,
 ) -> Result<MergedFile> {
-    // Get the Git ref for this layer
-    let ref_path = layer.ref_path(
-        config.mode.as_deref(),
-        config.scope.as_deref(),
-        config.project.as_deref(),
-    );
-
-    // CRITICAL: Verify layer ref exists (it should since we found it earlier)
-    if !repo.ref_exists(&ref_path) {
-        return Err(JinError::NotFound(format!(
-            "Layer ref not found: {}",
-            ref_path
-        )));
-    }
-
-    // Resolve to commit and get tree
-    let commit_oid = repo.resolve_ref(&ref_path)?;
-    let commit = repo.inner().find_commit(commit_oid)?;
-    let tree_oid = commit.tree_id();
-
-    // Read file content from tree
-    let content_bytes = repo.read_file_from_tree(tree_oid, path)?;
+    let content_bytes = read_layer_file(path, layer, config, store)?;
     let content_str = String::from_utf8_lossy(&content_bytes);
+    let (content_str, bom) = strip_bom(&content_str);
 
-    // Detect format and parse content
-    let format = detect_format(path);
-    let layer_value = parse_content(&content_str, format)?;
+    // Parse content
+    let mut layer_value = parse_content(content_str, format)?;
+    strip_final_markers(&mut layer_value);
 
     // Create MergedFile - source_layers will be extended in merge_layers()
     Ok(MergedFile {
         content: layer_value,
         source_layers: Vec::new(),
         format,
+        bom,
     })
 }
 
-/// Detect file format from path extension.
+/// Detect file format from path.
 ///
-/// Returns the appropriate FileFormat based on the file extension.
-/// Unknown extensions default to Text.
+/// Asks the registered format backends; paths no backend claims are Text.
 pub fn detect_format(path: &std::path::Path) -> FileFormat {
-    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-    match ext.to_lowercase().as_str() {
-        "json" => FileFormat::Json,
-        "yaml" | "yml" => FileFormat::Yaml,
-        "toml" => FileFormat::Toml,
-        "ini" | "cfg" | "conf" => FileFormat::Ini,
-        _ => FileFormat::Text,
+    registry().detect(path)
+}
+
+/// Format to merge a path with, honoring `limits.max-parse-size`
+///
+/// Structured files whose largest version across `layers` exceeds the parse
+/// limit are handled as opaque text (raw comparison, line-level merge)
+/// instead of being parsed in full.
+fn merge_format(
+    path: &std::path::Path,
+    layers: &[Layer],
+    config: &LayerMergeConfig,
+    store: &dyn LayerStore,
+) -> Result<FileFormat> {
+    let format = detect_format(path);
+    let limit = LimitsConfig::current().max_parse_size();
+    if !format.is_structured() || limit.is_none() {
+        return Ok(format);
+    }
+
+    let size = largest_blob_size(path, layers, config, store)?;
+    let effective = opaque_if_oversized(format, size, limit);
+    if effective != format {
+        progress::warn(format_args!(
+            "{} is {}, above limits.max-parse-size ({}); merging it as plain text",
+            path.display(),
+            format_size(size),
+            format_size(limit.unwrap_or_default())
+        ));
+    }
+    Ok(effective)
+}
+
+/// Fall back to text for structured content above the parse limit
+fn opaque_if_oversized(format: FileFormat, size: u64, limit: Option<u64>) -> FileFormat {
+    match limit {
+        Some(limit) if format.is_structured() && size > limit => FileFormat::Text,
+        _ => format,
+    }
+}
+
+/// Size of the largest version of a file across layers (from object headers)
+fn largest_blob_size(
+    path: &Path,
+    layers: &[Layer],
+    config: &LayerMergeConfig,
+    store: &dyn LayerStore,
+) -> Result<u64> {
+    let mut largest = 0;
+
+    for layer in layers {
+        if let Some(revision) = layer_revision(layer, config, store)? {
+            largest = largest.max(store.size(&revision, path)?.unwrap_or_default());
+        }
     }
+
+    Ok(largest)
 }
 
 /// Parse content string according to file format.
@@ -467,13 +690,8 @@ pub fn detect_format(path: &std::path::Path) -> FileFormat {
 /// Returns a MergeValue representation of the content.
 /// Text files are wrapped as MergeValue::String.
 pub fn parse_content(content: &str, format: FileFormat) -> Result<MergeValue> {
-    match format {
-        FileFormat::Json => MergeValue::from_json(content),
-        FileFormat::Yaml => MergeValue::from_yaml(content),
-        FileFormat::Toml => MergeValue::from_toml(content),
-        FileFormat::Ini => MergeValue::from_ini(content),
-        FileFormat::Text => Ok(MergeValue::String(content.to_string())),
-    }
+    let _span = profile::span(Phase::Parse);
+    registry().parse(content, format)
 }
 
 /// Get the list of layers that apply given the current context
@@ -517,7 +735,7 @@ pub fn get_applicable_layers(
 /// * `file_path` - Path to the file to search for (relative to repo root)
 /// * `layers` - Layers to search, in precedence order
 /// * `config` - Merge configuration with mode/scope/project context
-/// * `repo` - Jin repository for Git operations
+/// * `store` - Storage to read layers from
 ///
 /// # Returns
 ///
@@ -541,39 +759,21 @@ pub fn get_applicable_layers(
 /// )?;
 /// ```
 pub fn find_layers_containing_file(
-    file_path: &std::path::Path,
+    file_path: &Path,
     layers: &[Layer],
     config: &LayerMergeConfig,
-    repo: &JinRepo,
+    store: &dyn LayerStore,
 ) -> Result<Vec<Layer>> {
     let mut containing_layers = Vec::new();
 
     for layer in layers {
-        let ref_path = layer.ref_path(
-            config.mode.as_deref(),
-            config.scope.as_deref(),
-            config.project.as_deref(),
-        );
-
-        // CRITICAL: Check ref_exists() before resolve_ref()
-        // Layer refs may not exist yet - skip gracefully
-        if !repo.ref_exists(&ref_path) {
+        // Layers may not exist yet - skip gracefully
+        let Some(revision) = layer_revision(layer, config, store)? else {
             continue;
+        };
+        if store.size(&revision, file_path)?.is_some() {
+            containing_layers.push(*layer);
         }
-
-        // Resolve the commit for this layer
-        let commit_oid = repo.resolve_ref(&ref_path);
-        if let Ok(commit_oid) = commit_oid {
-            let commit = repo.inner().find_commit(commit_oid)?;
-            let tree_oid = commit.tree_id();
-
-            // Check if file exists in this layer's tree
-            // get_tree_entry() returns Err if file not found
-            if repo.get_tree_entry(tree_oid, file_path).is_ok() {
-                containing_layers.push(*layer);
-            }
-        }
-        // If resolve_ref fails, skip this layer (may not be initialized)
     }
 
     Ok(containing_layers)
@@ -591,7 +791,7 @@ pub fn find_layers_containing_file(
 /// * `file_path` - Path to the file to check (relative to repo root)
 /// * `layers_with_file` - Layers containing this file (from find_layers_containing_file)
 /// * `config` - Merge configuration with mode/scope/project context
-/// * `repo` - Jin repository for Git operations
+/// * `store` - Storage to read layers from
 ///
 /// # Returns
 ///
@@ -608,7 +808,7 @@ pub fn find_layers_containing_file(
 ///
 /// let layers = vec![Layer::GlobalBase, Layer::ModeBase];
 /// let config = LayerMergeConfig { /* ... */ };
-/// let containing = find_layers_containing_file(Path::new("config.json"), &layers, &config, &repo)?;
+/// let containing = find_layers_containing_file(Path::new("config.json"), &layers, &config, &store)?;
 ///
 /// if containing.len() > 1 {
 ///     let has_conflict = has_different_content_across_layers(
@@ -626,61 +826,55 @@ pub fn has_different_content_across_layers(
     file_path: &std::path::Path,
     layers_with_file: &[Layer],
     config: &LayerMergeConfig,
-    repo: &JinRepo,
+    store: &dyn LayerStore,
 ) -> Result<bool> {
     // Early exit: no conflict possible with fewer than 2 layers
     if layers_with_file.len() <= 1 {
         return Ok(false);
     }
 
-    let format = detect_format(file_path);
+    let format = merge_format(file_path, layers_with_file, config, store)?;
 
     // For text files, compare raw strings (not MergeValue)
     if format == FileFormat::Text {
-        return has_different_text_content(file_path, layers_with_file, config, repo);
+        return has_different_text_content(file_path, layers_with_file, config, store);
     }
 
     // For structured files, parse and compare MergeValue
-    has_different_structured_content(file_path, layers_with_file, config, repo, format)
+    has_different_structured_content(file_path, layers_with_file, config, store, format)
 }
 
 /// Helper: Compare text file content across layers (raw string comparison)
 fn has_different_text_content(
-    file_path: &std::path::Path,
+    file_path: &Path,
     layers_with_file: &[Layer],
     config: &LayerMergeConfig,
-    repo: &JinRepo,
+    store: &dyn LayerStore,
 ) -> Result<bool> {
-    // Read content from first layer
-    let first_layer = &layers_with_file[0];
-    let first_ref_path = first_layer.ref_path(
-        config.mode.as_deref(),
-        config.scope.as_deref(),
-        config.project.as_deref(),
-    );
-
-    let first_commit_oid = repo.resolve_ref(&first_ref_path)?;
-    let first_commit = repo.inner().find_commit(first_commit_oid)?;
-    let first_tree_oid = first_commit.tree_id();
+    // Line endings don't count as a difference when the policy normalizes them
+    let normalize = LineEndings::current(&config.root)
+        .policy(file_path)
+        .normalizes();
+    let comparable = |content: &[u8]| -> String {
+        let content = String::from_utf8_lossy(content);
+        if normalize {
+            to_lf(&content).into_owned()
+        } else {
+            content.into_owned()
+        }
+    };
 
-    let first_content_bytes = repo.read_file_from_tree(first_tree_oid, file_path)?;
-    let first_content = String::from_utf8_lossy(&first_content_bytes);
+    // Read content from first layer
+    let first_content = comparable(&read_layer_file(
+        file_path,
+        &layers_with_file[0],
+        config,
+        store,
+    )?);
 
     // Compare with each subsequent layer
     for layer in &layers_with_file[1..] {
-        let ref_path = layer.ref_path(
-            config.mode.as_deref(),
-            config.scope.as_deref(),
-            config.project.as_deref(),
-        );
-
-        let commit_oid = repo.resolve_ref(&ref_path)?;
-        let commit = repo.inner().find_commit(commit_oid)?;
-        let tree_oid = commit.tree_id();
-
-        let content_bytes = repo.read_file_from_tree(tree_oid, file_path)?;
-        let content = String::from_utf8_lossy(&content_bytes);
-
+        let content = comparable(&read_layer_file(file_path, layer, config, store)?);
         if content != first_content {
             return Ok(true); // Different content detected
         }
@@ -691,45 +885,23 @@ fn has_different_text_content(
 
 /// Helper: Compare structured file content across layers (MergeValue comparison)
 fn has_different_structured_content(
-    file_path: &std::path::Path,
+    file_path: &Path,
     layers_with_file: &[Layer],
     config: &LayerMergeConfig,
-    repo: &JinRepo,
+    store: &dyn LayerStore,
     format: FileFormat,
 ) -> Result<bool> {
-    // Read and parse content from first layer
-    let first_layer = &layers_with_file[0];
-    let first_ref_path = first_layer.ref_path(
-        config.mode.as_deref(),
-        config.scope.as_deref(),
-        config.project.as_deref(),
-    );
-
-    let first_commit_oid = repo.resolve_ref(&first_ref_path)?;
-    let first_commit = repo.inner().find_commit(first_commit_oid)?;
-    let first_tree_oid = first_commit.tree_id();
+    let parse = |layer: &Layer| -> Result<MergeValue> {
+        let content = read_layer_file(file_path, layer, config, store)?;
+        parse_content(&String::from_utf8_lossy(&content), format)
+    };
 
-    let first_content_bytes = repo.read_file_from_tree(first_tree_oid, file_path)?;
-    let first_content_str = String::from_utf8_lossy(&first_content_bytes);
-    let first_value = parse_content(&first_content_str, format)?;
+    // Read and parse content from first layer
+    let first_value = parse(&layers_with_file[0])?;
 
     // Compare with each subsequent layer
     for layer in &layers_with_file[1..] {
-        let ref_path = layer.ref_path(
-            config.mode.as_deref(),
-            config.scope.as_deref(),
-            config.project.as_deref(),
-        );
-
-        let commit_oid = repo.resolve_ref(&ref_path)?;
-        let commit = repo.inner().find_commit(commit_oid)?;
-        let tree_oid = commit.tree_id();
-
-        let content_bytes = repo.read_file_from_tree(tree_oid, file_path)?;
-        let content_str = String::from_utf8_lossy(&content_bytes);
-        let value = parse_content(&content_str, format)?;
-
-        if value != first_value {
+        if parse(layer)? != first_value {
             return Ok(true); // Different content detected
         }
     }
@@ -741,7 +913,7 @@ fn has_different_structured_content(
 mod tests {
     use super::*;
     use crate::git::objects::ObjectOps;
-    use std::path::Path;
+    use crate::git::RefOps;
     use tempfile;
 
     // ========== FileFormat & MergedFile Tests ==========
@@ -784,6 +956,26 @@ mod tests {
         assert!(!result.is_clean());
     }
 
+    #[test]
+    fn test_opaque_if_oversized() {
+        assert_eq!(
+            opaque_if_oversized(FileFormat::Json, 4096, Some(1024)),
+            FileFormat::Text
+        );
+        assert_eq!(
+            opaque_if_oversized(FileFormat::Json, 512, Some(1024)),
+            FileFormat::Json
+        );
+        assert_eq!(
+            opaque_if_oversized(FileFormat::Yaml, 4096, None),
+            FileFormat::Yaml
+        );
+        assert_eq!(
+            opaque_if_oversized(FileFormat::Text, 4096, Some(1024)),
+            FileFormat::Text
+        );
+    }
+
     // ========== detect_format Tests ==========
 
     #[test]
@@ -1008,6 +1200,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_layer_reads_work_on_any_store() {
+        let store = crate::store::MemoryStore::new();
+        let write = |layer_ref: &str, content: &[u8]| {
+            let files = [(PathBuf::from("config.json"), content.to_vec())].into();
+            store.write(layer_ref, None, &files, "test").unwrap();
+        };
+        write("refs/jin/layers/global", br#"{"a": 1}"#);
+        write("refs/jin/layers/mode/claude/_", br#"{"a":1}"#);
+        let config = LayerMergeConfig {
+            layers: vec![Layer::GlobalBase, Layer::ModeBase, Layer::ProjectBase],
+            mode: Some("claude".to_string()),
+            scope: None,
+            project: Some("app".to_string()),
+            root: PathBuf::new(),
+        };
+        let path = Path::new("config.json");
+
+        let containing =
+            find_layers_containing_file(path, &config.layers, &config, &store).unwrap();
+        assert_eq!(containing, [Layer::GlobalBase, Layer::ModeBase]);
+        // Equal once parsed, despite different bytes
+        assert!(!has_different_content_across_layers(path, &containing, &config, &store).unwrap());
+        assert_eq!(
+            read_layer_contents(path, &containing, &config, &store).unwrap()[1].1,
+            r#"{"a":1}"#
+        );
+    }
+
     #[test]
     fn test_find_layers_single_layer_containing_file() {
         let (_temp, repo) = create_layer_test_repo();
@@ -1027,6 +1248,7 @@ mod tests {
             mode: None,
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let result =
@@ -1063,6 +1285,7 @@ mod tests {
             mode: Some("dev".to_string()),
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let result =
@@ -1092,6 +1315,7 @@ mod tests {
             mode: None,
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let result =
@@ -1110,6 +1334,7 @@ mod tests {
             mode: None,
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         // Non-existent file should return empty vec, not error
@@ -1133,6 +1358,7 @@ mod tests {
             mode: None,
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let result =
@@ -1161,6 +1387,7 @@ mod tests {
             mode: Some("dev".to_string()),
             scope: None,
             project: Some("myproject".to_string()),
+            root: PathBuf::new(),
         };
 
         let result =
@@ -1199,6 +1426,7 @@ mod tests {
             mode: None,
             scope: None,
             project: Some("myproject".to_string()),
+            root: PathBuf::new(),
         };
 
         let result =
@@ -1229,6 +1457,7 @@ mod tests {
             mode: None,
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let result = find_layers_containing_file(
@@ -1261,6 +1490,7 @@ mod tests {
             mode: Some("dev".to_string()),
             scope: Some("frontend".to_string()),
             project: None,
+            root: PathBuf::new(),
         };
 
         let result =
@@ -1280,6 +1510,7 @@ mod tests {
             mode: None,
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         // Single layer - should return false
@@ -1299,6 +1530,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let content = br#"{"port": 8080, "debug": true}"#;
@@ -1324,6 +1556,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let global_content = br#"{"port": 8080}"#;
@@ -1361,6 +1594,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let content = b"hello world\nline two\n";
@@ -1386,6 +1620,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let global_content = b"hello world\n";
@@ -1423,6 +1658,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: Some("web".to_string()),
             project: None,
+            root: PathBuf::new(),
         };
 
         let content = br#"{"value": 42}"#;
@@ -1455,6 +1691,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: Some("web".to_string()),
             project: None,
+            root: PathBuf::new(),
         };
 
         let global_content = br#"{"value": 1}"#;
@@ -1500,6 +1737,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let global_content = b"port: 8080\n";
@@ -1536,6 +1774,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let global_content = br#"port = 8080"#;
@@ -1572,6 +1811,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let global_content = b"[section]\nport=8080\n";
@@ -1608,6 +1848,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         // Semantically identical JSON, different formatting
@@ -1648,6 +1889,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         // Text files: different whitespace = different content
@@ -1685,6 +1927,7 @@ mod tests {
             mode: None,
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let layers: Vec<Layer> = vec![];
@@ -1710,6 +1953,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: Some("web".to_string()),
             project: Some("myproject".to_string()),
+            root: PathBuf::new(),
         };
 
         let content = br#"{"value": 42}"#;
@@ -1759,6 +2003,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: Some("web".to_string()),
             project: Some("myproject".to_string()),
+            root: PathBuf::new(),
         };
 
         // Global differs, others are same
@@ -1821,6 +2066,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: Some("web".to_string()),
             project: Some("myproject".to_string()),
+            root: PathBuf::new(),
         };
 
         let base_content = br#"{"value": 1}"#;
@@ -1887,6 +2133,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: Some("web".to_string()),
             project: Some("myproject".to_string()),
+            root: PathBuf::new(),
         };
 
         let content1 = br#"{"value": 1}"#;
@@ -1956,6 +2203,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: Some("web".to_string()),
             project: Some("myproject".to_string()),
+            root: PathBuf::new(),
         };
 
         let base_content = br#"{"value": 1}"#;
@@ -2040,6 +2288,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: Some("web".to_string()),
             project: Some("myproject".to_string()),
+            root: PathBuf::new(),
         };
 
         // Each layer has unique content: [1, 2, 3, 4, 5, 6, 7, 8]
@@ -2135,6 +2384,7 @@ mod tests {
             mode: Some("dev".to_string()),
             scope: Some("backend".to_string()),
             project: Some("api-service".to_string()),
+            root: PathBuf::new(),
         };
 
         // Realistic pattern: base configs match, some overrides differ
@@ -2220,6 +2470,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: Some("web".to_string()),
             project: Some("myproject".to_string()),
+            root: PathBuf::new(),
         };
 
         let base_content = b"Hello World\n";
@@ -2292,6 +2543,7 @@ mod tests {
             mode: None,
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let result = merge_layers(&config, &repo).unwrap();
@@ -2320,6 +2572,7 @@ mod tests {
             mode: Some("dev".to_string()),
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let result = merge_layers(&config, &repo).unwrap();
@@ -2356,6 +2609,7 @@ mod tests {
             mode: Some("dev".to_string()),
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let result = merge_layers(&config, &repo).unwrap();
@@ -2421,6 +2675,7 @@ mod tests {
             mode: Some("dev".to_string()),
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let result = merge_layers(&config, &repo).unwrap();
@@ -2476,6 +2731,7 @@ mod tests {
             mode: Some("dev".to_string()),
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let result = merge_layers(&config, &repo).unwrap();
@@ -2516,6 +2772,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: Some("dev".to_string()),
             project: None,
+            root: PathBuf::new(),
         };
 
         let result = merge_layers(&config, &repo).unwrap();
@@ -2554,6 +2811,7 @@ mod tests {
             mode: Some("test".to_string()),
             scope: None,
             project: None,
+            root: PathBuf::new(),
         };
 
         let result = merge_layers(&config, &repo).unwrap();
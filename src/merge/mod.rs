@@ -8,6 +8,7 @@
 //! - [`MergeValue`]: Universal representation for structured data
 //! - [`deep_merge`]: RFC 7396 compliant deep merge with keyed array support
 //! - [`merge_layers`]: Multi-layer merge orchestration for Jin's 9-layer system
+//! - [`cache`]: Cached merge results for recently used contexts
 //! - [`text_merge`]: 3-way text merge for plain text files
 //!
 //! # Example
@@ -20,14 +21,49 @@
 //! let merged = deep_merge(base, overlay)?;
 //! ```
 
+pub mod cache;
+pub mod conflicts;
 pub mod deep;
+pub mod driver;
+pub mod env;
+pub mod eol;
+pub mod explain;
+pub mod format;
 pub mod jinmerge;
 pub mod layer;
+pub mod options;
+pub mod provenance;
+pub mod strict;
+pub mod style;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
 pub mod text;
+pub mod unicode;
 pub mod value;
 
 // Core deep merge
-pub use deep::{deep_merge, deep_merge_with_config, MergeConfig};
+pub use deep::{
+    deep_merge, deep_merge_checked, deep_merge_with_config, strip_final_markers, FinalKeysConfig,
+    FinalPolicy, MergeConfig, FINAL_KEY,
+};
+
+// Line endings
+pub use eol::{EolConfig, LineEnding, LineEndings};
+
+// External merge drivers
+pub use driver::{MergeDriver, MergeDrivers, MergerConfig};
+
+// Per-invocation merge options
+pub use options::{MergeOptions, MERGE_OPTIONS};
+
+// Strict merges
+pub use strict::{StrictMergeConfig, StrictMerges, StrictRule};
+
+// Format backends
+pub use format::{registry, serialize_content, FormatBackend, FormatRegistry, MergeHints};
+
+// Serializer style
+pub use style::{JsonIndent, KeyOrder, StyleConfig, TomlTables, YamlQuotes};
 
 // Layer merge orchestration
 pub use layer::{
@@ -38,12 +74,22 @@ pub use layer::{
 
 // Text merge
 pub use text::{
-    has_conflict_markers, parse_conflicts, text_merge, text_merge_with_config, ConflictRegion,
-    TextMergeConfig, TextMergeResult,
+    conflict_marker_size, has_conflict_markers, marker_size_for, parse_conflicts, parse_marker,
+    text_merge, text_merge_with_config, ConflictRegion, Marker, TextMergeConfig, TextMergeResult,
+    MIN_MARKER_SIZE,
 };
 
+// Conflict explanations
+pub use explain::{change_line, key_changes, ConflictDetail, ConflictSummary};
+
 // JinMerge conflict files
 pub use jinmerge::{JinMergeConflict, JinMergeRegion, JINMERGE_HEADER};
 
+// Provenance of applied files
+pub use provenance::Provenance;
+
+// Byte order marks and key normalization
+pub use unicode::{Normalization, UnicodeConfig, BOM};
+
 // Value type
 pub use value::MergeValue;
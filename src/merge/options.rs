@@ -0,0 +1,249 @@
+//! Per-invocation merge options
+//!
+//! `jin apply --merge-opt KEY=VALUE` (repeatable) adjusts the merge engine
+//! for one run without touching any config file:
+//! - `keyed_arrays=false` replaces arrays of objects instead of merging
+//!   them by key
+//! - `array_keys=key,uuid` sets the fields matching array items, in place
+//!   of the format's own (`id`, `name`)
+//! - `nulls=keep` keeps a null from a higher layer in the output instead of
+//!   deleting the key (`nulls=delete`, the RFC 7396 default)
+//! - `ours_label=`, `theirs_label=` and `base_label=` set the labels of
+//!   text conflict markers, and `diff3=true` includes the base
+//!
+//! The options are installed once per process before merging, and the
+//! merge engine reads them through [`MergeOptions::current`].
+
+use super::{MergeConfig, MergeHints, TextMergeConfig};
+use crate::core::{JinError, Result};
+use std::sync::OnceLock;
+
+/// Option names accepted by `--merge-opt`
+pub const MERGE_OPTIONS: &[&str] = &[
+    "keyed_arrays",
+    "array_keys",
+    "nulls",
+    "ours_label",
+    "theirs_label",
+    "base_label",
+    "diff3",
+];
+
+static INSTALLED: OnceLock<MergeOptions> = OnceLock::new();
+
+/// Merge engine adjustments for one invocation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeOptions {
+    /// Merge arrays of objects by key (otherwise arrays are replaced)
+    pub keyed_arrays: bool,
+    /// Fields matching array items, replacing the format's own
+    pub array_keys: Option<Vec<String>>,
+    /// Keep nulls from higher layers instead of deleting keys
+    pub keep_nulls: bool,
+    /// Label of the lower side in text conflict markers
+    pub ours_label: Option<String>,
+    /// Label of the higher side in text conflict markers
+    pub theirs_label: Option<String>,
+    /// Label of the base in diff3 conflict markers
+    pub base_label: Option<String>,
+    /// Include the base in text conflict markers
+    pub diff3: bool,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self {
+            keyed_arrays: true,
+            array_keys: None,
+            keep_nulls: false,
+            ours_label: None,
+            theirs_label: None,
+            base_label: None,
+            diff3: false,
+        }
+    }
+}
+
+impl MergeOptions {
+    /// Parse `KEY=VALUE` options, later ones overriding earlier ones
+    pub fn parse(options: &[String]) -> Result<Self> {
+        let mut parsed = Self::default();
+        for option in options {
+            let (key, value) = option.split_once('=').ok_or_else(|| {
+                JinError::Config(format!(
+                    "Invalid merge option '{}'. Use KEY=VALUE with one of: {}",
+                    option,
+                    MERGE_OPTIONS.join(", ")
+                ))
+            })?;
+            let (key, value) = (key.trim(), value.trim());
+            let label = || {
+                if value.is_empty() || value.contains(char::is_whitespace) {
+                    Err(JinError::Config(format!(
+                        "Invalid merge option {}: a label is one word",
+                        key
+                    )))
+                } else {
+                    Ok(Some(value.to_string()))
+                }
+            };
+            match key {
+                "keyed_arrays" => parsed.keyed_arrays = parse_bool(key, value)?,
+                "array_keys" => {
+                    let fields: Vec<String> = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|f| !f.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                    if fields.is_empty() {
+                        return Err(JinError::Config(
+                            "Invalid merge option array_keys: no fields (use keyed_arrays=false to replace arrays)"
+                                .to_string(),
+                        ));
+                    }
+                    parsed.array_keys = Some(fields);
+                }
+                "nulls" => {
+                    parsed.keep_nulls = match value {
+                        "delete" => false,
+                        "keep" => true,
+                        _ => {
+                            return Err(JinError::Config(format!(
+                                "Invalid merge option nulls: {}. Use 'delete' or 'keep'",
+                                value
+                            )))
+                        }
+                    }
+                }
+                "ours_label" => parsed.ours_label = label()?,
+                "theirs_label" => parsed.theirs_label = label()?,
+                "base_label" => parsed.base_label = label()?,
+                "diff3" => parsed.diff3 = parse_bool(key, value)?,
+                _ => {
+                    return Err(JinError::Config(format!(
+                        "Unknown merge option: {}. Valid options: {}",
+                        key,
+                        MERGE_OPTIONS.join(", ")
+                    )))
+                }
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Use these options for every merge in this process
+    ///
+    /// Fails if different options were installed already.
+    pub fn install(self) -> Result<()> {
+        let installed = INSTALLED.get_or_init(|| self.clone());
+        if *installed != self {
+            return Err(JinError::Other(
+                "Merge options were already set for this run".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The installed options, or the defaults
+    pub fn current() -> &'static Self {
+        static DEFAULT: OnceLock<MergeOptions> = OnceLock::new();
+        INSTALLED
+            .get()
+            .unwrap_or_else(|| DEFAULT.get_or_init(Self::default))
+    }
+
+    /// Deep merge configuration for a format with these options applied
+    pub fn merge_config(&self, hints: MergeHints) -> MergeConfig {
+        let mut config = match &self.array_keys {
+            Some(fields) => MergeConfig::with_key_fields(fields.clone()),
+            None => hints.merge_config(),
+        };
+        if !self.keyed_arrays {
+            // No field matches, so every array is replaced
+            config.array_key_fields.clear();
+        }
+        config.keep_nulls = self.keep_nulls;
+        config
+    }
+
+    /// Text merge configuration with these options applied
+    pub fn text_config(&self) -> TextMergeConfig {
+        let defaults = TextMergeConfig::default();
+        TextMergeConfig {
+            ours_label: self.ours_label.clone().unwrap_or(defaults.ours_label),
+            theirs_label: self.theirs_label.clone().unwrap_or(defaults.theirs_label),
+            show_base: self.diff3,
+            base_label: self.base_label.clone().unwrap_or(defaults.base_label),
+        }
+    }
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(JinError::Config(format!(
+            "Invalid merge option {}: {}. Use 'true' or 'false'",
+            key, value
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(options: &[&str]) -> Result<MergeOptions> {
+        MergeOptions::parse(&options.iter().map(|o| o.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(parse(&[]).unwrap(), MergeOptions::default());
+
+        let options = parse(&[
+            "keyed_arrays=false",
+            "nulls=keep",
+            "ours_label=lower",
+            "diff3=true",
+        ])
+        .unwrap();
+        assert!(!options.keyed_arrays);
+        assert!(options.keep_nulls);
+        assert!(options.diff3);
+
+        let text = options.text_config();
+        assert_eq!(text.ours_label, "lower");
+        assert_eq!(text.theirs_label, "theirs");
+        assert!(text.show_base);
+
+        let config = options.merge_config(MergeHints::default());
+        assert!(config.array_key_fields.is_empty());
+        assert!(config.keep_nulls);
+
+        let options = parse(&["array_keys=key, uuid"]).unwrap();
+        assert_eq!(
+            options.merge_config(MergeHints::default()).array_key_fields,
+            vec!["key".to_string(), "uuid".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_options() {
+        for bad in [
+            "keyed_arrays",
+            "keyed_arrays=no",
+            "nulls=drop",
+            "array_keys=",
+            "ours_label=two words",
+            "colour=blue",
+        ] {
+            assert!(
+                matches!(parse(&[bad]), Err(JinError::Config(_))),
+                "{} should be rejected",
+                bad
+            );
+        }
+    }
+}
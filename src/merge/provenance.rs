@@ -0,0 +1,190 @@
+//! Provenance of applied files
+//!
+//! `jin apply --provenance` (or `apply.provenance: true` in the project
+//! config) writes `.jin/provenance.json` next to the applied files. It
+//! records, for every applied file, the layer and commit its content comes
+//! from, and for structured files the layer of every top-level key, so
+//! editor plugins can show "this setting comes from mode/claude" inline.
+//!
+//! ```json
+//! {
+//!   "applied_at": "2026-01-05T10:00:00+00:00",
+//!   "files": {
+//!     ".claude/settings.json": {
+//!       "layer": "mode/claude",
+//!       "commit": "3f2a…",
+//!       "keys": {
+//!         "model": { "layer": "mode/claude", "commit": "3f2a…" },
+//!         "theme": { "layer": "global", "commit": "91bc…" }
+//!       }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! A key is attributed to the highest-precedence layer that sets it. Nested
+//! objects can combine several layers below the top level; they are
+//! attributed as a whole.
+
+use super::layer::{parse_content, read_layer_contents, LayerMergeConfig, LayerMergeResult};
+use crate::core::{JinError, Layer, Result};
+use crate::git::refs::layer_name;
+use crate::git::JinRepo;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Where a file or key comes from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Source {
+    /// Layer display name (e.g., `global`, `mode/claude`)
+    pub layer: String,
+    /// Commit the layer pointed at when applied
+    pub commit: String,
+}
+
+/// Provenance of one applied file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileProvenance {
+    /// Highest-precedence layer holding the file
+    #[serde(flatten)]
+    pub source: Source,
+    /// Source of each top-level key (structured files only)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<String, Source>,
+}
+
+/// Provenance of every file written by an apply
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// RFC3339 timestamp of the apply
+    pub applied_at: String,
+    /// Applied path -> where its content comes from
+    pub files: BTreeMap<String, FileProvenance>,
+}
+
+impl Provenance {
+    /// Path of the provenance map in a workspace
+    pub fn default_path(root: &Path) -> PathBuf {
+        root.join(".jin").join("provenance.json")
+    }
+
+    /// Provenance of the merged files, with the commit of each layer ref
+    /// (`layer_commits`, as recorded in the workspace metadata)
+    pub fn build(
+        merged: &LayerMergeResult,
+        config: &LayerMergeConfig,
+        layer_commits: &HashMap<String, String>,
+        repo: &JinRepo,
+    ) -> Result<Self> {
+        let source = |layer: Layer| {
+            let ref_path = layer.ref_path(
+                config.mode.as_deref(),
+                config.scope.as_deref(),
+                config.project.as_deref(),
+            );
+            Source {
+                layer: layer_name(&ref_path).to_string(),
+                commit: layer_commits.get(&ref_path).cloned().unwrap_or_default(),
+            }
+        };
+
+        let mut files = BTreeMap::new();
+        for (path, merged_file) in &merged.merged_files {
+            let Some(top) = merged_file
+                .source_layers
+                .iter()
+                .max_by_key(|layer| layer.precedence())
+            else {
+                continue;
+            };
+
+            let mut keys = BTreeMap::new();
+            if let Some(object) = merged_file
+                .format
+                .is_structured()
+                .then(|| merged_file.content.as_object())
+                .flatten()
+            {
+                let mut layers = merged_file.source_layers.clone();
+                layers.sort_by_key(|layer| layer.precedence());
+                layers.dedup();
+                let defined = keys_by_layer(path, &layers, merged_file.format, config, repo)?;
+                for key in object.keys() {
+                    if let Some(layer) = defined
+                        .iter()
+                        .rev()
+                        .find(|(_, keys)| keys.contains(key))
+                        .map(|(layer, _)| *layer)
+                    {
+                        keys.insert(key.clone(), source(layer));
+                    }
+                }
+            }
+
+            files.insert(
+                path.to_string_lossy().replace('\\', "/"),
+                FileProvenance {
+                    source: source(*top),
+                    keys,
+                },
+            );
+        }
+
+        Ok(Self {
+            applied_at: chrono::Utc::now().to_rfc3339(),
+            files,
+        })
+    }
+
+    /// Write the map to `path` (atomic write)
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(|e| JinError::Parse {
+            format: "JSON".to_string(),
+            message: e.to_string(),
+        })?;
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, content)?;
+        std::fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+}
+
+/// Top-level keys each layer sets in a structured file, lowest layer first
+///
+/// Layers whose copy doesn't parse as an object set no keys.
+fn keys_by_layer(
+    path: &Path,
+    layers: &[Layer],
+    format: super::FileFormat,
+    config: &LayerMergeConfig,
+    repo: &JinRepo,
+) -> Result<Vec<(Layer, Vec<String>)>> {
+    let contents = match read_layer_contents(path, layers, config, repo) {
+        Ok(contents) => contents,
+        Err(JinError::NotFound(_)) => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(contents
+        .into_iter()
+        .map(|(layer, content)| {
+            let keys = parse_content(&content, format)
+                .ok()
+                .and_then(|value| {
+                    value.as_object().map(|object| {
+                        object
+                            .iter()
+                            // A null removes the key instead of setting it
+                            .filter(|(_, value)| !value.is_null())
+                            .map(|(key, _)| key.clone())
+                            .collect()
+                    })
+                })
+                .unwrap_or_default();
+            (layer, keys)
+        })
+        .collect())
+}
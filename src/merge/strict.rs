@@ -0,0 +1,215 @@
+//! Strict merges
+//!
+//! Deep merges let a higher layer overwrite any value silently. For paths
+//! where two layers are expected to agree, such as a file shared by two
+//! sibling scopes, a strict rule in the global config turns overwrites into
+//! conflicts:
+//!
+//! ```toml
+//! [[strict_merge]]
+//! pattern = "config/*.json"
+//! layers = ["mode-scope", "scope-base"]
+//! ```
+//!
+//! When two of the listed layers (every layer if `layers` is omitted) set
+//! the same scalar key of a matching structured file to different values,
+//! the file is reported as a conflict and resolved with `jin resolve`
+//! instead of the higher layer winning. Keys set by only one of them, and
+//! objects and arrays, merge as usual.
+//!
+//! Patterns follow the `.jinattributes` conventions; when several rules
+//! match, the last one wins.
+
+use super::MergeValue;
+use crate::core::progress;
+use crate::core::{JinConfig, JinError, Layer, Result};
+use crate::staging::attributes::{glob_to_regex, normalize};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// A strict merge registration (one `[[strict_merge]]` entry)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StrictMergeConfig {
+    /// Path pattern the rule covers
+    pub pattern: String,
+    /// Layers whose scalar overwrites conflict (every layer when empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub layers: Vec<String>,
+}
+
+/// A validated strict merge rule
+#[derive(Debug, Clone)]
+pub struct StrictRule {
+    /// Pattern as configured
+    pub pattern: String,
+    /// Layers the rule applies between (every layer when empty)
+    pub layers: Vec<Layer>,
+    matcher: Regex,
+}
+
+impl StrictRule {
+    /// Validate a registration
+    pub fn new(config: &StrictMergeConfig) -> Result<Self> {
+        let matcher = glob_to_regex(&config.pattern)
+            .map_err(|message| JinError::Config(format!("strict_merge: {}", message)))?;
+        let layers = config
+            .layers
+            .iter()
+            .map(|name| {
+                name.parse::<Layer>()
+                    .map_err(|e| JinError::Config(format!("strict_merge: {}", e)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            pattern: config.pattern.clone(),
+            layers,
+            matcher,
+        })
+    }
+
+    /// Whether this rule covers a path
+    pub fn matches(&self, path: &Path) -> bool {
+        self.matcher.is_match(&normalize(path))
+    }
+
+    /// Whether overwrites by or of `layer` are checked
+    pub fn covers(&self, layer: Layer) -> bool {
+        self.layers.is_empty() || self.layers.contains(&layer)
+    }
+}
+
+/// Strict merge rules registered in the global config
+#[derive(Debug, Clone, Default)]
+pub struct StrictMerges {
+    rules: Vec<StrictRule>,
+}
+
+impl StrictMerges {
+    /// Rules from the global config, loaded once per process
+    ///
+    /// Invalid registrations are reported once and ignored.
+    pub fn current() -> &'static Self {
+        static RULES: OnceLock<StrictMerges> = OnceLock::new();
+        RULES.get_or_init(|| {
+            let rules = JinConfig::load()
+                .map(|c| c.strict_merge)
+                .unwrap_or_default();
+            Self::from_config(&rules).unwrap_or_else(|e| {
+                progress::warn(format_args!("{}; strict merges are disabled", e));
+                Self::default()
+            })
+        })
+    }
+
+    /// Build rules from registrations
+    pub fn from_config(rules: &[StrictMergeConfig]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(StrictRule::new)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { rules })
+    }
+
+    /// The rule for a path (the last matching registration wins)
+    pub fn find(&self, path: &Path) -> Option<&StrictRule> {
+        self.rules.iter().rev().find(|rule| rule.matches(path))
+    }
+}
+
+/// Dotted paths of the scalars `lower` and `higher` both set, to different
+/// values
+pub fn overwritten_scalars(lower: &MergeValue, higher: &MergeValue) -> Vec<String> {
+    fn walk(lower: &MergeValue, higher: &MergeValue, path: &str, found: &mut Vec<String>) {
+        match (lower, higher) {
+            (MergeValue::Object(lower), MergeValue::Object(higher)) => {
+                for (key, higher_value) in higher {
+                    let Some(lower_value) = lower.get(key) else {
+                        continue;
+                    };
+                    let key_path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    walk(lower_value, higher_value, &key_path, found);
+                }
+            }
+            (lower, higher) if is_scalar(lower) && is_scalar(higher) && lower != higher => {
+                found.push(path.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    let mut found = Vec::new();
+    walk(lower, higher, "", &mut found);
+    found
+}
+
+fn is_scalar(value: &MergeValue) -> bool {
+    matches!(
+        value,
+        MergeValue::String(_) | MergeValue::Integer(_) | MergeValue::Float(_) | MergeValue::Bool(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, layers: &[&str]) -> Result<StrictRule> {
+        StrictRule::new(&StrictMergeConfig {
+            pattern: pattern.to_string(),
+            layers: layers.iter().map(|l| l.to_string()).collect(),
+        })
+    }
+
+    #[test]
+    fn test_rules_match_paths_and_layers() {
+        let scoped = rule("config/*.json", &["mode-scope", "scope-base"]).unwrap();
+        assert!(scoped.matches(Path::new("config/app.json")));
+        assert!(!scoped.matches(Path::new("app.json")));
+        assert!(scoped.covers(Layer::ScopeBase));
+        assert!(!scoped.covers(Layer::GlobalBase));
+        assert!(rule("*.json", &[]).unwrap().covers(Layer::GlobalBase));
+
+        assert!(matches!(
+            rule("*.json", &["sibling-scope"]),
+            Err(JinError::Config(_))
+        ));
+
+        let rules = StrictMerges::from_config(&[
+            StrictMergeConfig {
+                pattern: "*.json".to_string(),
+                layers: Vec::new(),
+            },
+            StrictMergeConfig {
+                pattern: "app.json".to_string(),
+                layers: vec!["project-base".to_string()],
+            },
+        ])
+        .unwrap();
+        let found = rules.find(Path::new("app.json")).unwrap();
+        assert_eq!(found.pattern, "app.json");
+        assert!(rules.find(Path::new("app.yaml")).is_none());
+    }
+
+    #[test]
+    fn test_overwritten_scalars() {
+        let lower = MergeValue::from_json(
+            r#"{"port": 8080, "host": "a", "tls": {"enabled": true, "cert": "x"}, "tags": [1]}"#,
+        )
+        .unwrap();
+        let higher = MergeValue::from_json(
+            r#"{"port": 9090, "host": "a", "tls": {"enabled": false}, "tags": [2], "new": 1}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            overwritten_scalars(&lower, &higher),
+            vec!["port".to_string(), "tls.enabled".to_string()]
+        );
+        assert!(overwritten_scalars(&higher, &higher).is_empty());
+    }
+}
@@ -0,0 +1,643 @@
+//! Serializer style for merged files
+//!
+//! Applied files are written by the [`MergeValue`] serializers, so their
+//! layout follows the `[style]` section of the global config rather than
+//! whatever the layer files looked like:
+//!
+//! - `key-order`: `preserve` keeps merge order (lowest layer first),
+//!   `sorted` sorts keys alphabetically. Unset keeps each format's default:
+//!   JSON and TOML sorted, YAML and INI in merge order.
+//! - `json-indent`: spaces per level, or `tab` (default 2)
+//! - `yaml-indent`: spaces per level (default 2)
+//! - `yaml-quotes`: `auto` quotes strings only when needed, `single` and
+//!   `double` quote every string value
+//! - `toml-tables`: `block` writes arrays of tables as `[[name]]` sections,
+//!   `inline` writes them (and other arrays) inline
+//! - `ini-spacing`: `key = value` instead of `key=value`
+//!
+//! Every option defaults to the output Jin wrote before it existed.
+
+use super::MergeValue;
+use crate::core::{JinConfig, JinError, Result};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Order of object keys in serialized output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyOrder {
+    /// Merge order: keys from lower layers first, new keys appended
+    Preserve,
+    /// Alphabetical order at every level
+    Sorted,
+}
+
+impl std::str::FromStr for KeyOrder {
+    type Err = JinError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "preserve" => Ok(Self::Preserve),
+            "sorted" => Ok(Self::Sorted),
+            _ => Err(JinError::Config(format!(
+                "Invalid key order: {}. Use 'preserve' or 'sorted'",
+                value
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for KeyOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Preserve => write!(f, "preserve"),
+            Self::Sorted => write!(f, "sorted"),
+        }
+    }
+}
+
+/// Indentation of one JSON nesting level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum JsonIndent {
+    /// This many spaces (1-16)
+    Spaces(u8),
+    /// One tab character
+    Tab,
+}
+
+impl Default for JsonIndent {
+    fn default() -> Self {
+        Self::Spaces(2)
+    }
+}
+
+impl JsonIndent {
+    fn as_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Spaces(n) => vec![b' '; *n as usize],
+            Self::Tab => vec![b'\t'],
+        }
+    }
+}
+
+impl std::str::FromStr for JsonIndent {
+    type Err = JinError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        if value == "tab" {
+            return Ok(Self::Tab);
+        }
+        match value.parse::<u8>() {
+            Ok(n @ 1..=16) => Ok(Self::Spaces(n)),
+            _ => Err(JinError::Config(format!(
+                "Invalid JSON indent: {}. Use a number of spaces (1-16) or 'tab'",
+                value
+            ))),
+        }
+    }
+}
+
+impl TryFrom<String> for JsonIndent {
+    type Error = JinError;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<JsonIndent> for String {
+    fn from(indent: JsonIndent) -> Self {
+        indent.to_string()
+    }
+}
+
+impl std::fmt::Display for JsonIndent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Spaces(n) => write!(f, "{}", n),
+            Self::Tab => write!(f, "tab"),
+        }
+    }
+}
+
+/// Quoting of YAML string values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum YamlQuotes {
+    /// Quote only strings that would otherwise parse as something else
+    #[default]
+    Auto,
+    /// `'value'`
+    Single,
+    /// `"value"`
+    Double,
+}
+
+impl std::str::FromStr for YamlQuotes {
+    type Err = JinError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "auto" => Ok(Self::Auto),
+            "single" => Ok(Self::Single),
+            "double" => Ok(Self::Double),
+            _ => Err(JinError::Config(format!(
+                "Invalid YAML quote style: {}. Use 'auto', 'single' or 'double'",
+                value
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for YamlQuotes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Single => write!(f, "single"),
+            Self::Double => write!(f, "double"),
+        }
+    }
+}
+
+/// Layout of TOML arrays of tables
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TomlTables {
+    /// `[[servers]]` sections
+    #[default]
+    Block,
+    /// `servers = [{ name = "a" }]`
+    Inline,
+}
+
+impl std::str::FromStr for TomlTables {
+    type Err = JinError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "block" => Ok(Self::Block),
+            "inline" => Ok(Self::Inline),
+            _ => Err(JinError::Config(format!(
+                "Invalid TOML table style: {}. Use 'block' or 'inline'",
+                value
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for TomlTables {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Block => write!(f, "block"),
+            Self::Inline => write!(f, "inline"),
+        }
+    }
+}
+
+/// Serializer style (stored under `[style]` in the global config)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StyleConfig {
+    /// Key order for every format (unset: per-format default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_order: Option<KeyOrder>,
+    /// JSON indentation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_indent: Option<JsonIndent>,
+    /// Spaces per YAML nesting level
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yaml_indent: Option<u8>,
+    /// Quoting of YAML string values
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yaml_quotes: Option<YamlQuotes>,
+    /// Layout of TOML arrays of tables
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toml_tables: Option<TomlTables>,
+    /// Spaces around `=` in INI files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ini_spacing: Option<bool>,
+}
+
+/// Default YAML indentation
+pub const DEFAULT_YAML_INDENT: u8 = 2;
+
+impl StyleConfig {
+    /// Settings loaded once per process, for the serializers
+    pub fn current() -> &'static Self {
+        static STYLE: OnceLock<StyleConfig> = OnceLock::new();
+        STYLE.get_or_init(|| {
+            JinConfig::load()
+                .ok()
+                .and_then(|c| c.style)
+                .unwrap_or_default()
+        })
+    }
+
+    /// Parse a `style.yaml-indent` value
+    pub fn parse_yaml_indent(value: &str) -> Result<u8> {
+        match value.parse::<u8>() {
+            Ok(n @ 2..=8) => Ok(n),
+            _ => Err(JinError::Config(format!(
+                "Invalid YAML indent: {}. Use a number of spaces (2-8)",
+                value
+            ))),
+        }
+    }
+
+    /// `value` with its keys in the configured order, or `None` when the
+    /// format's `default` order applies unchanged
+    fn reorder(&self, value: &MergeValue, default: KeyOrder) -> Option<MergeValue> {
+        match (self.key_order.unwrap_or(default), default) {
+            (KeyOrder::Sorted, KeyOrder::Preserve) => Some(sort_keys(value)),
+            _ => None,
+        }
+    }
+
+    fn yaml_is_default(&self) -> bool {
+        self.yaml_indent.unwrap_or(DEFAULT_YAML_INDENT) == DEFAULT_YAML_INDENT
+            && self.yaml_quotes.unwrap_or_default() == YamlQuotes::Auto
+    }
+}
+
+/// `value` with object keys sorted at every level
+fn sort_keys(value: &MergeValue) -> MergeValue {
+    match value {
+        MergeValue::Object(obj) => {
+            let mut entries: Vec<_> = obj.iter().map(|(k, v)| (k.clone(), sort_keys(v))).collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            MergeValue::Object(entries.into_iter().collect())
+        }
+        MergeValue::Array(items) => MergeValue::Array(items.iter().map(sort_keys).collect()),
+        other => other.clone(),
+    }
+}
+
+fn parse_error(format: &str, message: impl std::fmt::Display) -> JinError {
+    JinError::Parse {
+        format: format.to_string(),
+        message: message.to_string(),
+    }
+}
+
+// ================== JSON ==================
+
+pub(super) fn to_json(value: &MergeValue, style: &StyleConfig) -> Result<String> {
+    let indent = style.json_indent.unwrap_or_default().as_bytes();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(&indent);
+    let mut out = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+    let result = match style.key_order.unwrap_or(KeyOrder::Sorted) {
+        KeyOrder::Preserve => value.serialize(&mut serializer),
+        KeyOrder::Sorted => sort_keys(value).serialize(&mut serializer),
+    };
+    result.map_err(|e| parse_error("JSON", e))?;
+    String::from_utf8(out).map_err(|e| parse_error("JSON", e))
+}
+
+// ================== YAML ==================
+
+pub(super) fn to_yaml(value: &MergeValue, style: &StyleConfig) -> Result<String> {
+    let reordered = style.reorder(value, KeyOrder::Preserve);
+    let value = reordered.as_ref().unwrap_or(value);
+    if style.yaml_is_default() {
+        let yaml_value: serde_yaml::Value = value.clone().into();
+        return serde_yaml::to_string(&yaml_value).map_err(|e| parse_error("YAML", e));
+    }
+
+    let emitter = YamlEmitter {
+        indent: style.yaml_indent.unwrap_or(DEFAULT_YAML_INDENT) as usize,
+        quotes: style.yaml_quotes.unwrap_or_default(),
+    };
+    let lines = match value {
+        MergeValue::Object(obj) if !obj.is_empty() => emitter.block(value)?,
+        MergeValue::Array(items) if !items.is_empty() => emitter.block(value)?,
+        scalar => vec![emitter.scalar(scalar, emitter.quotes)?],
+    };
+    let mut out = lines.join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+/// Block-style YAML writer for non-default indentation and quoting
+struct YamlEmitter {
+    indent: usize,
+    quotes: YamlQuotes,
+}
+
+impl YamlEmitter {
+    /// Lines of a non-empty collection, starting at column 0
+    fn block(&self, value: &MergeValue) -> Result<Vec<String>> {
+        let pad = " ".repeat(self.indent);
+        let mut lines = Vec::new();
+        match value {
+            MergeValue::Object(obj) => {
+                for (key, child) in obj {
+                    let key = self.scalar(&MergeValue::String(key.clone()), YamlQuotes::Auto)?;
+                    if is_block(child) {
+                        lines.push(format!("{}:", key));
+                        lines.extend(self.block(child)?.into_iter().map(|l| pad.clone() + &l));
+                    } else {
+                        lines.push(format!("{}: {}", key, self.scalar(child, self.quotes)?));
+                    }
+                }
+            }
+            MergeValue::Array(items) => {
+                // The item's content starts one indent past the dash
+                let dash = format!("-{}", " ".repeat(self.indent - 1));
+                for item in items {
+                    if is_block(item) {
+                        let mut child = self.block(item)?.into_iter();
+                        if let Some(first) = child.next() {
+                            lines.push(dash.clone() + &first);
+                        }
+                        lines.extend(child.map(|l| pad.clone() + &l));
+                    } else {
+                        lines.push(dash.clone() + &self.scalar(item, self.quotes)?);
+                    }
+                }
+            }
+            _ => unreachable!("block() is only called on collections"),
+        }
+        Ok(lines)
+    }
+
+    /// A value written on the same line as its key or dash
+    fn scalar(&self, value: &MergeValue, quotes: YamlQuotes) -> Result<String> {
+        match value {
+            MergeValue::Object(_) => Ok("{}".to_string()),
+            MergeValue::Array(_) => Ok("[]".to_string()),
+            MergeValue::String(s) if s.chars().any(char::is_control) => double_quoted(s),
+            MergeValue::String(s) => match quotes {
+                YamlQuotes::Auto => plain_or_quoted(value),
+                YamlQuotes::Single => Ok(format!("'{}'", s.replace('\'', "''"))),
+                YamlQuotes::Double => double_quoted(s),
+            },
+            _ => plain_or_quoted(value),
+        }
+    }
+}
+
+fn is_block(value: &MergeValue) -> bool {
+    match value {
+        MergeValue::Object(obj) => !obj.is_empty(),
+        MergeValue::Array(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+/// serde_yaml's own rendering of a single-line scalar
+fn plain_or_quoted(value: &MergeValue) -> Result<String> {
+    let yaml_value: serde_yaml::Value = value.clone().into();
+    let text = serde_yaml::to_string(&yaml_value).map_err(|e| parse_error("YAML", e))?;
+    Ok(text.trim_end_matches('\n').to_string())
+}
+
+/// A double-quoted YAML string (JSON string syntax is valid YAML)
+fn double_quoted(s: &str) -> Result<String> {
+    serde_json::to_string(s).map_err(|e| parse_error("YAML", e))
+}
+
+// ================== TOML ==================
+
+pub(super) fn to_toml(value: &MergeValue, style: &StyleConfig) -> Result<String> {
+    // Conversion rejects nulls with the usual error
+    let toml_value: toml::Value = value.clone().try_into()?;
+    let preserve = style.key_order == Some(KeyOrder::Preserve);
+
+    if style.toml_tables.unwrap_or_default() == TomlTables::Block {
+        let result = if preserve {
+            toml::to_string_pretty(value)
+        } else {
+            toml::to_string_pretty(&toml_value)
+        };
+        return result.map_err(|e| parse_error("TOML", e));
+    }
+
+    let sorted;
+    let value = if preserve {
+        value
+    } else {
+        sorted = sort_keys(value);
+        &sorted
+    };
+    let MergeValue::Object(root) = value else {
+        return Err(parse_error("TOML", "TOML root must be a table"));
+    };
+    let mut out = String::new();
+    write_toml_table(&mut out, &mut Vec::new(), root)?;
+    Ok(out)
+}
+
+/// Write a table's values, then its sub-tables as `[a.b]` sections; arrays
+/// are always inline
+fn write_toml_table(
+    out: &mut String,
+    path: &mut Vec<String>,
+    table: &IndexMap<String, MergeValue>,
+) -> Result<()> {
+    let (tables, values): (Vec<_>, Vec<_>) = table
+        .iter()
+        .partition(|(_, v)| matches!(v, MergeValue::Object(_)));
+
+    if !path.is_empty() && (!values.is_empty() || tables.is_empty()) {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("[{}]\n", path.join(".")));
+    }
+    for (key, value) in values {
+        let inline: toml::Value = value.clone().try_into()?;
+        out.push_str(&format!("{} = {}\n", toml_key(key), inline));
+    }
+    for (key, value) in tables {
+        let MergeValue::Object(child) = value else {
+            unreachable!("partitioned on objects");
+        };
+        path.push(toml_key(key));
+        write_toml_table(out, path, child)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if bare {
+        key.to_string()
+    } else {
+        toml::Value::String(key.to_string()).to_string()
+    }
+}
+
+// ================== INI ==================
+
+pub(super) fn ini_options(style: &StyleConfig) -> ini::WriteOption {
+    ini::WriteOption {
+        kv_separator: if style.ini_spacing.unwrap_or(false) {
+            " = "
+        } else {
+            "="
+        },
+        ..Default::default()
+    }
+}
+
+pub(super) fn ini_order(value: &MergeValue, style: &StyleConfig) -> Option<MergeValue> {
+    style.reorder(value, KeyOrder::Preserve)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(edit: impl FnOnce(&mut StyleConfig)) -> StyleConfig {
+        let mut style = StyleConfig::default();
+        edit(&mut style);
+        style
+    }
+
+    fn sample() -> MergeValue {
+        MergeValue::from_json(
+            r#"{"name": "app", "version": "1.0", "servers": [{"host": "a", "port": 80}], "db": {"url": "x"}}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_defaults_match_plain_serializers() {
+        let value = sample();
+        let default = StyleConfig::default();
+        assert_eq!(
+            to_json(&value, &default).unwrap(),
+            serde_json::to_string_pretty(&serde_json::Value::from(value.clone())).unwrap()
+        );
+        assert_eq!(
+            to_yaml(&value, &default).unwrap(),
+            serde_yaml::to_string(&serde_yaml::Value::from(value.clone())).unwrap()
+        );
+        let toml_value: toml::Value = value.clone().try_into().unwrap();
+        assert_eq!(
+            to_toml(&value, &default).unwrap(),
+            toml::to_string_pretty(&toml_value).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_json_indent_and_order() {
+        let value = MergeValue::from_yaml("b:\n  c: 1\na: 2\n").unwrap();
+        let tabs = style(|s| s.json_indent = Some(JsonIndent::Tab));
+        assert_eq!(
+            to_json(&value, &tabs).unwrap(),
+            "{\n\t\"a\": 2,\n\t\"b\": {\n\t\t\"c\": 1\n\t}\n}"
+        );
+        let preserve = style(|s| {
+            s.json_indent = Some(JsonIndent::Spaces(4));
+            s.key_order = Some(KeyOrder::Preserve);
+        });
+        assert_eq!(
+            to_json(&value, &preserve).unwrap(),
+            "{\n    \"b\": {\n        \"c\": 1\n    },\n    \"a\": 2\n}"
+        );
+    }
+
+    #[test]
+    fn test_yaml_indent_and_quotes() {
+        let value = MergeValue::from_yaml(
+            "name: it's\n\"on\": 'true'\nlist:\n- a: 1\n  b: [true]\n- x\nempty: {}\n",
+        )
+        .unwrap();
+        let four = style(|s| s.yaml_indent = Some(4));
+        let out = to_yaml(&value, &four).unwrap();
+        assert_eq!(
+            out,
+            "name: it's\non: 'true'\nlist:\n    -   a: 1\n        b:\n            -   true\n    -   x\nempty: {}\n"
+        );
+
+        let double = style(|s| s.yaml_quotes = Some(YamlQuotes::Double));
+        let out = to_yaml(&value, &double).unwrap();
+        assert!(out.starts_with("name: \"it's\"\non: \"true\"\n"));
+        assert!(out.contains("  - \"x\""));
+
+        let single = style(|s| s.yaml_quotes = Some(YamlQuotes::Single));
+        assert!(to_yaml(&value, &single)
+            .unwrap()
+            .starts_with("name: 'it''s'\n"));
+
+        // Every variant parses back to the same value
+        for style in [four, double, single] {
+            let text = to_yaml(&value, &style).unwrap();
+            assert_eq!(MergeValue::from_yaml(&text).unwrap(), value, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_yaml_multiline_strings_are_double_quoted() {
+        let value = MergeValue::from_json(r#"{"script": "a\nb"}"#).unwrap();
+        let single = style(|s| s.yaml_quotes = Some(YamlQuotes::Single));
+        let out = to_yaml(&value, &single).unwrap();
+        assert_eq!(out, "script: \"a\\nb\"\n");
+        assert_eq!(MergeValue::from_yaml(&out).unwrap(), value);
+    }
+
+    #[test]
+    fn test_toml_inline_tables() {
+        let inline = style(|s| s.toml_tables = Some(TomlTables::Inline));
+        let out = to_toml(&sample(), &inline).unwrap();
+        assert_eq!(
+            out,
+            "name = \"app\"\nservers = [{ host = \"a\", port = 80 }]\nversion = \"1.0\"\n\n[db]\nurl = \"x\"\n"
+        );
+        assert_eq!(MergeValue::from_toml(&out).unwrap(), sort_keys(&sample()));
+
+        let nulls = MergeValue::from_json(r#"{"a": null}"#).unwrap();
+        assert!(to_toml(&nulls, &inline).is_err());
+    }
+
+    #[test]
+    fn test_toml_preserve_order() {
+        let preserve = style(|s| s.key_order = Some(KeyOrder::Preserve));
+        let out = to_toml(&sample(), &preserve).unwrap();
+        assert!(out.starts_with("name = \"app\"\nversion = \"1.0\"\n"));
+        assert_eq!(MergeValue::from_toml(&out).unwrap(), sample());
+    }
+
+    #[test]
+    fn test_sorted_yaml_and_ini_order() {
+        let value = MergeValue::from_json(r#"{"b": 1, "a": {"d": 1, "c": 2}}"#).unwrap();
+        let sorted = style(|s| s.key_order = Some(KeyOrder::Sorted));
+        assert_eq!(
+            to_yaml(&value, &sorted).unwrap(),
+            "a:\n  c: 2\n  d: 1\nb: 1\n"
+        );
+        assert!(ini_order(&value, &StyleConfig::default()).is_none());
+        assert_eq!(
+            ini_order(&value, &sorted)
+                .unwrap()
+                .to_json_string_compact()
+                .unwrap(),
+            r#"{"a":{"c":2,"d":1},"b":1}"#
+        );
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!("tab".parse::<JsonIndent>().unwrap(), JsonIndent::Tab);
+        assert_eq!("4".parse::<JsonIndent>().unwrap(), JsonIndent::Spaces(4));
+        assert!("0".parse::<JsonIndent>().is_err());
+        assert!("wide".parse::<JsonIndent>().is_err());
+        assert!(StyleConfig::parse_yaml_indent("1").is_err());
+        assert_eq!(StyleConfig::parse_yaml_indent("4").unwrap(), 4);
+        assert!("fancy".parse::<YamlQuotes>().is_err());
+        assert!("nested".parse::<TomlTables>().is_err());
+        assert!("random".parse::<KeyOrder>().is_err());
+
+        // JSON indent round-trips through the config file as a string
+        let config: StyleConfig = toml::from_str("json_indent = \"tab\"").unwrap();
+        assert_eq!(config.json_indent, Some(JsonIndent::Tab));
+        assert_eq!(toml::to_string(&config).unwrap(), "json_indent = \"tab\"\n");
+    }
+}
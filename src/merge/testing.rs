@@ -0,0 +1,536 @@
+//! Property-based testing harness for the merge engine
+//!
+//! Available to Jin's own tests and, with the `test-utils` feature, to
+//! downstream crates (e.g., format plugins):
+//!
+//! ```toml
+//! [dev-dependencies]
+//! jin = { version = "*", features = ["test-utils"] }
+//! ```
+//!
+//! [`Generator`] produces arbitrary [`MergeValue`]s and layer stacks from a
+//! seed, and the `check_*` functions verify the invariants the merge engine
+//! guarantees, returning a description of the counterexample on failure.
+//! [`check_property`] runs a property over many seeded cases and reports
+//! the seed of a failing case so it can be replayed:
+//!
+//! ```ignore
+//! use jin::merge::testing::{check_property, check_round_trip};
+//!
+//! check_property("xml round trip", 200, |gen| {
+//!     check_round_trip(&XmlBackend, &gen.document())
+//! });
+//! ```
+//!
+//! `JIN_FUZZ_CASES` raises the number of cases for long fuzzing runs and
+//! `JIN_FUZZ_SEED` replays a reported seed.
+
+use super::format::FormatBackend;
+use super::{deep_merge_with_config, MergeConfig, MergeValue};
+use crate::core::Result;
+use indexmap::IndexMap;
+
+/// Outcome of a property check: `Err` describes the counterexample
+pub type CheckResult = std::result::Result<(), String>;
+
+/// Object keys drawn by the generator; a small pool makes layers overlap
+const KEYS: &[&str] = &["alpha", "beta", "gamma", "delta", "port", "debug"];
+
+/// Identifiers of keyed array items
+const ITEM_IDS: &[&str] = &["api", "db", "cache", "queue"];
+
+/// Strings drawn by the generator
+const STRINGS: &[&str] = &["", "on", "localhost", "two words", "ünïcode", "a:b"];
+
+/// Small deterministic PRNG (SplitMix64), so failures replay from a seed
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Create a generator from a seed
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Next 64 random bits
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform number in `0..n` (`n` must not be zero)
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// True with probability `percent`/100
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.next_u64() % 100 < percent
+    }
+
+    /// A random element of a non-empty slice
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+/// Shape of generated values
+#[derive(Debug, Clone)]
+pub struct GenConfig {
+    /// Maximum nesting of objects and arrays
+    pub max_depth: usize,
+    /// Maximum number of members per object or array
+    pub max_width: usize,
+    /// Generate nulls (deletions) in objects
+    pub nulls: bool,
+    /// Generate floats (formats such as INI cannot round-trip them)
+    pub floats: bool,
+    /// Generate arrays of objects keyed by `id`
+    pub keyed_arrays: bool,
+}
+
+impl Default for GenConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            max_width: 4,
+            nulls: true,
+            floats: true,
+            keyed_arrays: true,
+        }
+    }
+}
+
+impl GenConfig {
+    /// Values any structured format can represent: no nulls (TOML has
+    /// none) and no keyed arrays
+    pub fn portable() -> Self {
+        Self {
+            nulls: false,
+            keyed_arrays: false,
+            ..Self::default()
+        }
+    }
+}
+
+/// Generator of arbitrary merge inputs
+#[derive(Debug, Clone)]
+pub struct Generator {
+    rng: Rng,
+    config: GenConfig,
+}
+
+impl Generator {
+    /// Generator with the default shape
+    pub fn new(seed: u64) -> Self {
+        Self::with_config(seed, GenConfig::default())
+    }
+
+    /// Generator with a custom shape
+    pub fn with_config(seed: u64, config: GenConfig) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            config,
+        }
+    }
+
+    /// The underlying random source
+    pub fn rng(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    /// The shape settings
+    pub fn config(&self) -> &GenConfig {
+        &self.config
+    }
+
+    /// An arbitrary scalar (never null)
+    pub fn scalar(&mut self) -> MergeValue {
+        match self.rng.below(if self.config.floats { 4 } else { 3 }) {
+            0 => MergeValue::Bool(self.rng.chance(50)),
+            1 => MergeValue::Integer(self.rng.below(2001) as i64 - 1000),
+            2 => MergeValue::String(self.rng.pick(STRINGS).to_string()),
+            // Halves are exact in binary and never look like integers
+            _ => MergeValue::Float((self.rng.below(200) as f64 - 100.0) + 0.5),
+        }
+    }
+
+    /// An arbitrary value
+    pub fn value(&mut self) -> MergeValue {
+        self.value_at(self.config.max_depth)
+    }
+
+    /// An arbitrary object (the shape of a structured file)
+    pub fn document(&mut self) -> MergeValue {
+        self.object(self.config.max_depth)
+    }
+
+    /// `len` documents drawn from the same keys, lowest precedence first
+    pub fn layer_stack(&mut self, len: usize) -> Vec<MergeValue> {
+        (0..len).map(|_| self.document()).collect()
+    }
+
+    /// `len` null-free documents that agree on the type at every path
+    ///
+    /// Each layer sets a random subset of one skeleton's paths to new
+    /// values of the same type. Stacks like this merge associatively.
+    pub fn compatible_stack(&mut self, len: usize) -> Vec<MergeValue> {
+        let nulls = std::mem::replace(&mut self.config.nulls, false);
+        let skeleton = self.document();
+        self.config.nulls = nulls;
+        (0..len).map(|_| self.vary(&skeleton)).collect()
+    }
+
+    fn value_at(&mut self, depth: usize) -> MergeValue {
+        if depth == 0 || self.rng.chance(50) {
+            return self.scalar();
+        }
+        match self.rng.below(if self.config.keyed_arrays { 3 } else { 2 }) {
+            0 => self.object(depth),
+            1 => self.scalar_array(),
+            _ => self.keyed_array(depth),
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> MergeValue {
+        let mut obj = IndexMap::new();
+        for _ in 0..self.rng.below(self.config.max_width + 1) {
+            let key = self.rng.pick(KEYS).to_string();
+            let value = if self.config.nulls && self.rng.chance(15) {
+                MergeValue::Null
+            } else {
+                self.value_at(depth.saturating_sub(1))
+            };
+            obj.insert(key, value);
+        }
+        MergeValue::Object(obj)
+    }
+
+    fn scalar_array(&mut self) -> MergeValue {
+        let len = 1 + self.rng.below(self.config.max_width);
+        MergeValue::Array((0..len).map(|_| self.scalar()).collect())
+    }
+
+    /// Objects with unique `id`s (arrays Jin merges item by item)
+    ///
+    /// Items never contain nulls: an array is a value, so a null inside a
+    /// new array is kept, while the same item merged into an existing array
+    /// deletes the key.
+    fn keyed_array(&mut self, depth: usize) -> MergeValue {
+        let nulls = std::mem::replace(&mut self.config.nulls, false);
+        let array = self.keyed_items(depth);
+        self.config.nulls = nulls;
+        array
+    }
+
+    fn keyed_items(&mut self, depth: usize) -> MergeValue {
+        let mut ids: Vec<&str> = ITEM_IDS.to_vec();
+        let len = 1 + self.rng.below(self.config.max_width.min(ids.len()));
+        let items = (0..len)
+            .map(|_| {
+                let id = ids.remove(self.rng.below(ids.len()));
+                let mut item = match self.object(depth.saturating_sub(1)) {
+                    MergeValue::Object(obj) => obj,
+                    _ => IndexMap::new(),
+                };
+                item.insert("id".to_string(), MergeValue::String(id.to_string()));
+                MergeValue::Object(item)
+            })
+            .collect();
+        MergeValue::Array(items)
+    }
+
+    /// A copy of `skeleton` with random members dropped and scalars
+    /// replaced by values of the same type
+    fn vary(&mut self, skeleton: &MergeValue) -> MergeValue {
+        match skeleton {
+            MergeValue::Object(obj) => {
+                let mut varied = IndexMap::new();
+                for (key, value) in obj {
+                    if key == "id" {
+                        varied.insert(key.clone(), value.clone());
+                    } else if self.rng.chance(70) {
+                        varied.insert(key.clone(), self.vary(value));
+                    }
+                }
+                MergeValue::Object(varied)
+            }
+            MergeValue::Array(items) if items.iter().all(MergeValue::is_object) => {
+                let mut varied = Vec::new();
+                for item in items {
+                    if self.rng.chance(70) {
+                        varied.push(self.vary(item));
+                    }
+                }
+                // An empty overlay array would replace instead of merge
+                if varied.is_empty() {
+                    varied.push(self.vary(&items[0]));
+                }
+                MergeValue::Array(varied)
+            }
+            MergeValue::Array(items) => {
+                MergeValue::Array(items.iter().map(|item| self.vary(item)).collect())
+            }
+            scalar => {
+                if self.rng.chance(50) {
+                    return scalar.clone();
+                }
+                loop {
+                    let candidate = self.scalar();
+                    if std::mem::discriminant(&candidate) == std::mem::discriminant(scalar) {
+                        return candidate;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Merge a stack of layers the way `jin apply` does (lowest first)
+pub fn merge_stack(stack: &[MergeValue], config: &MergeConfig) -> Result<MergeValue> {
+    let mut layers = stack.iter().cloned();
+    let Some(mut merged) = layers.next() else {
+        return Ok(MergeValue::Object(IndexMap::new()));
+    };
+    for layer in layers {
+        merged = deep_merge_with_config(merged, layer, config)?;
+    }
+    Ok(merged)
+}
+
+fn merge(
+    base: MergeValue,
+    overlay: MergeValue,
+    config: &MergeConfig,
+) -> std::result::Result<MergeValue, String> {
+    deep_merge_with_config(base, overlay, config).map_err(|e| format!("merge failed: {}", e))
+}
+
+/// Compare two values, describing the inputs on mismatch
+fn expect_eq(
+    property: &str,
+    actual: &MergeValue,
+    expected: &MergeValue,
+    inputs: &[&MergeValue],
+) -> CheckResult {
+    if actual == expected {
+        return Ok(());
+    }
+    let mut message = format!(
+        "{} violated\n  expected: {:?}\n  actual:   {:?}",
+        property, expected, actual
+    );
+    for (index, input) in inputs.iter().enumerate() {
+        message.push_str(&format!("\n  input {}: {:?}", index, input));
+    }
+    Err(message)
+}
+
+/// Merging an empty object changes nothing
+pub fn check_identity(value: &MergeValue, config: &MergeConfig) -> CheckResult {
+    let merged = merge(value.clone(), MergeValue::Object(IndexMap::new()), config)?;
+    expect_eq("identity", &merged, value, &[value])
+}
+
+/// Merging a null-free value into itself changes nothing
+pub fn check_idempotent(value: &MergeValue, config: &MergeConfig) -> CheckResult {
+    let merged = merge(value.clone(), value.clone(), config)?;
+    expect_eq("idempotence", &merged, value, &[value])
+}
+
+/// Applying the same overlay twice is the same as applying it once
+///
+/// Only holds for overlays without deletions: a null inside an object the
+/// overlay adds is kept by the first merge and deletes the key in the second.
+pub fn check_overlay_idempotent(
+    base: &MergeValue,
+    overlay: &MergeValue,
+    config: &MergeConfig,
+) -> CheckResult {
+    let once = merge(base.clone(), overlay.clone(), config)?;
+    let twice = merge(once.clone(), overlay.clone(), config)?;
+    expect_eq("overlay idempotence", &twice, &once, &[base, overlay])
+}
+
+/// `(a + b) + c == a + (b + c)`
+///
+/// Only holds for stacks without deletions whose layers agree on the type
+/// at every path, such as [`Generator::compatible_stack`]: otherwise a
+/// middle layer replacing an object changes what the top layer merges into.
+pub fn check_associative(
+    a: &MergeValue,
+    b: &MergeValue,
+    c: &MergeValue,
+    config: &MergeConfig,
+) -> CheckResult {
+    let left = merge(merge(a.clone(), b.clone(), config)?, c.clone(), config)?;
+    let right = merge(a.clone(), merge(b.clone(), c.clone(), config)?, config)?;
+    expect_eq("associativity", &left, &right, &[a, b, c])
+}
+
+/// The top layer wins: every scalar it sets through nested objects is in
+/// the merged result, and every key it sets to null is gone (or kept as
+/// null, inside an object no lower layer had)
+pub fn check_precedence(stack: &[MergeValue], config: &MergeConfig) -> CheckResult {
+    let Some(top) = stack.last() else {
+        return Ok(());
+    };
+    let merged = merge_stack(stack, config).map_err(|e| format!("merge failed: {}", e))?;
+    let mut path = Vec::new();
+    precedence_at(top, &merged, &mut path).map_err(|message| {
+        let mut message = format!("precedence violated at {}", message);
+        for (index, layer) in stack.iter().enumerate() {
+            message.push_str(&format!("\n  layer {}: {:?}", index, layer));
+        }
+        message
+    })
+}
+
+fn precedence_at(top: &MergeValue, merged: &MergeValue, path: &mut Vec<String>) -> CheckResult {
+    let (MergeValue::Object(top), MergeValue::Object(merged)) = (top, merged) else {
+        return Ok(());
+    };
+    for (key, value) in top {
+        path.push(key.clone());
+        let actual = merged.get(key);
+        match value {
+            MergeValue::Null if actual.is_some_and(|v| !v.is_null()) => {
+                return Err(format!("{}: deleted key is present", path.join(".")))
+            }
+            MergeValue::Object(_) => match actual {
+                Some(actual) => precedence_at(value, actual, path)?,
+                None => return Err(format!("{}: object is missing", path.join("."))),
+            },
+            MergeValue::Null | MergeValue::Array(_) => {}
+            scalar if actual != Some(scalar) => {
+                return Err(format!(
+                    "{}: expected {:?}, found {:?}",
+                    path.join("."),
+                    scalar,
+                    actual
+                ))
+            }
+            _ => {}
+        }
+        path.pop();
+    }
+    Ok(())
+}
+
+/// A format backend parses back what it serialized
+///
+/// Generate values the format can represent (e.g., [`GenConfig::portable`]).
+pub fn check_round_trip(backend: &dyn FormatBackend, value: &MergeValue) -> CheckResult {
+    let text = backend
+        .serialize(value)
+        .map_err(|e| format!("serialize failed: {}\n  input: {:?}", e, value))?;
+    let parsed = backend
+        .parse(&text)
+        .map_err(|e| format!("parse failed: {}\n  text: {:?}", e, text))?;
+    expect_eq("round trip", &parsed, value, &[value])
+}
+
+/// Run a property over `cases` generated inputs, panicking with the seed of
+/// the first failing case
+///
+/// `JIN_FUZZ_CASES` overrides the number of cases and `JIN_FUZZ_SEED` the
+/// starting seed. Case `i` uses seed `start + i`.
+pub fn check_property(name: &str, cases: u64, property: impl FnMut(&mut Generator) -> CheckResult) {
+    check_property_with(name, cases, GenConfig::default(), property)
+}
+
+/// [`check_property`] with a custom generator shape
+pub fn check_property_with(
+    name: &str,
+    cases: u64,
+    config: GenConfig,
+    mut property: impl FnMut(&mut Generator) -> CheckResult,
+) {
+    let env = |var: &str| std::env::var(var).ok().and_then(|v| v.parse::<u64>().ok());
+    let cases = env("JIN_FUZZ_CASES").unwrap_or(cases);
+    let start = env("JIN_FUZZ_SEED").unwrap_or(0);
+
+    for seed in start..start.saturating_add(cases) {
+        let mut generator = Generator::with_config(seed, config.clone());
+        if let Err(message) = property(&mut generator) {
+            panic!(
+                "property '{}' failed (replay with JIN_FUZZ_SEED={} JIN_FUZZ_CASES=1)\n{}",
+                name, seed, message
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merge::format::{registry, FileFormat};
+
+    #[test]
+    fn test_generator_is_deterministic() {
+        let a = Generator::new(7).layer_stack(3);
+        let b = Generator::new(7).layer_stack(3);
+        assert_eq!(a, b);
+        assert_ne!(a, Generator::new(8).layer_stack(3));
+    }
+
+    #[test]
+    fn test_merge_invariants() {
+        let config = MergeConfig::new();
+        check_property("identity", 300, |gen| {
+            check_identity(&gen.document(), &config)
+        });
+        check_property("overlay idempotence", 300, |gen| {
+            let base = gen.document();
+            let nulls = std::mem::replace(&mut gen.config.nulls, false);
+            let overlay = gen.document();
+            gen.config.nulls = nulls;
+            check_overlay_idempotent(&base, &overlay, &config)
+        });
+        check_property("precedence", 300, |gen| {
+            let len = 2 + gen.rng().below(3);
+            check_precedence(&gen.layer_stack(len), &config)
+        });
+        check_property("associativity", 300, |gen| {
+            let stack = gen.compatible_stack(3);
+            check_associative(&stack[0], &stack[1], &stack[2], &config)
+        });
+    }
+
+    #[test]
+    fn test_self_merge_without_nulls() {
+        let shape = GenConfig {
+            nulls: false,
+            ..GenConfig::default()
+        };
+        let config = MergeConfig::new();
+        check_property_with("idempotence", 300, shape, |gen| {
+            check_idempotent(&gen.document(), &config)
+        });
+    }
+
+    #[test]
+    fn test_builtin_formats_round_trip() {
+        let json = registry().backend(FileFormat::Json).unwrap();
+        let yaml = registry().backend(FileFormat::Yaml).unwrap();
+        check_property_with("json/yaml round trip", 200, GenConfig::default(), |gen| {
+            let doc = gen.document();
+            check_round_trip(json, &doc)?;
+            check_round_trip(yaml, &doc)
+        });
+
+        let toml = registry().backend(FileFormat::Toml).unwrap();
+        check_property_with("toml round trip", 200, GenConfig::portable(), |gen| {
+            check_round_trip(toml, &gen.document())
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "JIN_FUZZ_SEED=")]
+    fn test_failing_property_reports_seed() {
+        check_property("always fails", 1, |_| Err("counterexample".to_string()));
+    }
+}
@@ -3,6 +3,15 @@
 //! Implements line-level 3-way merging using the `diffy` crate with proper
 //! conflict detection, configurable conflict markers, and conflict parsing.
 //!
+//! # Marker size
+//!
+//! Files can legitimately contain marker-like lines (docs about Git, a
+//! Markdown `=======` underline). Like Git's `conflict-marker-size`, Jin
+//! writes conflict markers one character longer than the longest
+//! marker-like line in the merged inputs, and readers treat only the
+//! longest complete set of markers in a file as real; shorter ones are
+//! content.
+//!
 //! # Example
 //!
 //! ```
@@ -22,6 +31,22 @@
 
 use crate::core::{JinError, Result};
 
+/// Length of Git's conflict markers, and the shortest Jin writes
+pub const MIN_MARKER_SIZE: usize = 7;
+
+/// Kind of conflict marker line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Marker {
+    /// `<<<<<<< label`
+    Start,
+    /// `||||||| label` (diff3 base)
+    Base,
+    /// `=======`
+    Separator,
+    /// `>>>>>>> label`
+    End,
+}
+
 /// Result of a text merge operation
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TextMergeResult {
@@ -139,17 +164,21 @@ pub fn text_merge_with_config(
     // CRITICAL: diffy::merge() returns:
     // Ok(String) = clean merge result
     // Err(String) = content WITH conflict markers (NOT an error condition!)
-    match diffy::merge(base, ours, theirs) {
+    let size = marker_size_for(&[base, ours, theirs]);
+    match diffy::MergeOptions::new()
+        .set_conflict_marker_length(size)
+        .merge(base, ours, theirs)
+    {
         Ok(merged) => Ok(TextMergeResult::Clean(merged)),
         Err(conflict_content) => {
             // diffy inserts its own markers - optionally rewrite with custom labels
             let content = if needs_label_rewrite(config) {
-                rewrite_conflict_labels(&conflict_content, config)
+                rewrite_conflict_labels(&conflict_content, config, size)
             } else {
                 conflict_content
             };
 
-            let conflict_count = count_conflict_regions(&content);
+            let conflict_count = count_conflict_regions(&content, size);
 
             Ok(TextMergeResult::Conflict {
                 content,
@@ -159,10 +188,96 @@ pub fn text_merge_with_config(
     }
 }
 
+/// Recognize a conflict marker line, returning its kind and size
+///
+/// A marker is a run of at least [`MIN_MARKER_SIZE`] `<`, `|`, `=` or `>`
+/// characters starting the line, followed by the end of the line or (except
+/// for the separator) a space and a label.
+///
+/// # Example
+///
+/// ```
+/// use jin::merge::text::{parse_marker, Marker};
+///
+/// assert_eq!(parse_marker("<<<<<<< ours"), Some((Marker::Start, 7)));
+/// assert_eq!(parse_marker("========"), Some((Marker::Separator, 8)));
+/// assert_eq!(parse_marker("<<<<<<<<<x"), None);
+/// assert_eq!(parse_marker("<<< short"), None);
+/// ```
+pub fn parse_marker(line: &str) -> Option<(Marker, usize)> {
+    let first = line.chars().next()?;
+    let kind = match first {
+        '<' => Marker::Start,
+        '|' => Marker::Base,
+        '=' => Marker::Separator,
+        '>' => Marker::End,
+        _ => return None,
+    };
+    let rest = line.trim_start_matches(first);
+    let size = line.len() - rest.len();
+    if size < MIN_MARKER_SIZE {
+        return None;
+    }
+    let rest = rest.strip_suffix('\r').unwrap_or(rest);
+    let valid = match kind {
+        Marker::Separator => rest.trim().is_empty(),
+        _ => rest.is_empty() || rest.starts_with(' '),
+    };
+    valid.then_some((kind, size))
+}
+
+/// Marker size to use when merging `inputs`
+///
+/// One longer than the longest marker-like line in any input, and never
+/// shorter than [`MIN_MARKER_SIZE`], so the markers Jin writes can't be
+/// confused with file content.
+pub fn marker_size_for(inputs: &[&str]) -> usize {
+    inputs
+        .iter()
+        .flat_map(|input| input.lines())
+        .filter_map(parse_marker)
+        .map(|(_, size)| size + 1)
+        .fold(MIN_MARKER_SIZE, usize::max)
+}
+
+/// Size of the conflict markers in `content`, if it has conflicts
+///
+/// The largest size with a start, separator and end marker line; marker
+/// lines of other sizes are file content.
+pub fn conflict_marker_size(content: &str) -> Option<usize> {
+    let mut starts = Vec::new();
+    let mut separators = Vec::new();
+    let mut ends = Vec::new();
+    for (kind, size) in content.lines().filter_map(parse_marker) {
+        match kind {
+            Marker::Start => starts.push(size),
+            Marker::Separator => separators.push(size),
+            Marker::End => ends.push(size),
+            Marker::Base => {}
+        }
+    }
+    starts
+        .into_iter()
+        .filter(|size| separators.contains(size) && ends.contains(size))
+        .max()
+}
+
+/// Whether any line of `content` is a marker of at least `size`
+///
+/// Stricter than [`has_conflict_markers`]: one stray marker line counts.
+/// Used to check a resolution is free of leftover markers.
+pub fn has_marker_lines(content: &str, size: usize) -> bool {
+    content
+        .lines()
+        .filter_map(parse_marker)
+        .any(|(_, n)| n >= size)
+}
+
 /// Check if content contains conflict markers
 ///
-/// Returns true if the content contains all three standard Git conflict markers:
-/// `<<<<<<<`, `=======`, and `>>>>>>>`.
+/// Returns true if the content has start (`<<<<<<<`), separator
+/// (`=======`) and end (`>>>>>>>`) marker lines of the same size. Marker
+/// text in the middle of a line doesn't count.
 ///
 /// # Example
 ///
@@ -171,15 +286,18 @@ pub fn text_merge_with_config(
 ///
 /// assert!(!has_conflict_markers("normal content"));
 /// assert!(has_conflict_markers("<<<<<<< ours\nfoo\n=======\nbar\n>>>>>>> theirs"));
+/// assert!(!has_conflict_markers("Title\n========\nsee <<<<<<< and >>>>>>> in git docs"));
 /// ```
 pub fn has_conflict_markers(content: &str) -> bool {
-    content.contains("<<<<<<<") && content.contains("=======") && content.contains(">>>>>>>")
+    conflict_marker_size(content).is_some()
 }
 
 /// Parse conflict markers from content to extract conflict regions
 ///
-/// Extracts all conflict regions from content with standard Git conflict markers.
-/// Returns an empty vector if no conflicts are found.
+/// Extracts all conflict regions from content with Git-style conflict
+/// markers of the size found by [`conflict_marker_size`]; marker-like lines
+/// of other sizes are kept as region content. Returns an empty vector if no
+/// conflicts are found.
 ///
 /// # Arguments
 /// * `content` - Text content potentially containing conflict markers
@@ -201,16 +319,17 @@ pub fn has_conflict_markers(content: &str) -> bool {
 /// assert_eq!(regions[0].theirs, "their content");
 /// ```
 pub fn parse_conflicts(content: &str) -> Result<Vec<ConflictRegion>> {
-    if !has_conflict_markers(content) {
+    let Some(size) = conflict_marker_size(content) else {
         return Ok(Vec::new());
-    }
+    };
+    let is = |line: &str, kind: Marker| parse_marker(line) == Some((kind, size));
 
     let mut regions = Vec::new();
     let lines: Vec<&str> = content.lines().collect();
     let mut i = 0;
 
     while i < lines.len() {
-        if lines[i].starts_with("<<<<<<<") {
+        if is(lines[i], Marker::Start) {
             let start_line = i + 1; // 1-indexed for user display
 
             // Check for diff3 format (has ||||||| base marker)
@@ -219,9 +338,9 @@ pub fn parse_conflicts(content: &str) -> Result<Vec<ConflictRegion>> {
 
             // Look for either ||||||| (diff3) or ======= (standard)
             while sep_idx < lines.len() {
-                if lines[sep_idx].starts_with("|||||||") {
+                if is(lines[sep_idx], Marker::Base) {
                     base_idx = Some(sep_idx);
-                } else if lines[sep_idx].starts_with("=======") {
+                } else if is(lines[sep_idx], Marker::Separator) {
                     break;
                 }
                 sep_idx += 1;
@@ -229,7 +348,7 @@ pub fn parse_conflicts(content: &str) -> Result<Vec<ConflictRegion>> {
 
             // Find >>>>>>> end marker
             let mut end_idx = sep_idx + 1;
-            while end_idx < lines.len() && !lines[end_idx].starts_with(">>>>>>>") {
+            while end_idx < lines.len() && !is(lines[end_idx], Marker::End) {
                 end_idx += 1;
             }
 
@@ -293,9 +412,12 @@ pub struct ConflictRegion {
 // Helper Functions
 // ============================================================================
 
-/// Count the number of conflict regions in content
-fn count_conflict_regions(content: &str) -> usize {
-    content.matches("<<<<<<<").count()
+/// Count the conflict regions (start markers of `size`) in content
+fn count_conflict_regions(content: &str, size: usize) -> usize {
+    content
+        .lines()
+        .filter(|line| parse_marker(line) == Some((Marker::Start, size)))
+        .count()
 }
 
 /// Check if labels need to be rewritten
@@ -303,17 +425,19 @@ fn needs_label_rewrite(config: &TextMergeConfig) -> bool {
     config.ours_label != "ours" || config.theirs_label != "theirs"
 }
 
-/// Rewrite conflict labels in content with custom labels from config
-fn rewrite_conflict_labels(content: &str, config: &TextMergeConfig) -> String {
+/// Rewrite the labels of `size` markers with custom labels from config
+fn rewrite_conflict_labels(content: &str, config: &TextMergeConfig, size: usize) -> String {
     let mut result = String::with_capacity(content.len());
 
     for line in content.lines() {
-        if line.starts_with("<<<<<<<") {
-            result.push_str(&format!("<<<<<<< {}", config.ours_label));
-        } else if line.starts_with(">>>>>>>") {
-            result.push_str(&format!(">>>>>>> {}", config.theirs_label));
-        } else {
-            result.push_str(line);
+        match parse_marker(line) {
+            Some((Marker::Start, n)) if n == size => {
+                result.push_str(&format!("{} {}", "<".repeat(size), config.ours_label));
+            }
+            Some((Marker::End, n)) if n == size => {
+                result.push_str(&format!("{} {}", ">".repeat(size), config.theirs_label));
+            }
+            _ => result.push_str(line),
         }
         result.push('\n');
     }
@@ -764,23 +888,91 @@ mod tests {
         assert!(result.is_err() || !result.unwrap().is_empty());
     }
 
+    // ========== Marker Collision Tests ==========
+
+    #[test]
+    fn test_parse_marker() {
+        assert_eq!(parse_marker("<<<<<<< ours"), Some((Marker::Start, 7)));
+        assert_eq!(parse_marker("||||||| base"), Some((Marker::Base, 7)));
+        assert_eq!(parse_marker("========="), Some((Marker::Separator, 9)));
+        assert_eq!(parse_marker(">>>>>>>>"), Some((Marker::End, 8)));
+        assert_eq!(parse_marker("======= x"), None);
+        assert_eq!(parse_marker("<<<<<<<x"), None);
+        assert_eq!(parse_marker(" <<<<<<< indented"), None);
+        assert_eq!(parse_marker("<<<<<< six"), None);
+    }
+
+    #[test]
+    fn test_marker_size_for() {
+        assert_eq!(marker_size_for(&["plain\n", "text\n"]), 7);
+        assert_eq!(marker_size_for(&["Title\n=======\n", ""]), 8);
+        assert_eq!(marker_size_for(&["a\n", "<<<<<<<<<< deep\n"]), 11);
+    }
+
+    #[test]
+    fn test_merge_of_marker_like_content_uses_longer_markers() {
+        let base = "Resolving\n=========\nline\n<<<<<<< HEAD\n";
+        let ours = "Resolving\n=========\nours\n<<<<<<< HEAD\n";
+        let theirs = "Resolving\n=========\ntheirs\n<<<<<<< HEAD\n";
+
+        let TextMergeResult::Conflict {
+            content,
+            conflict_count,
+        } = text_merge(base, ours, theirs).unwrap()
+        else {
+            panic!("Expected conflict");
+        };
+        assert_eq!(conflict_count, 1);
+        assert_eq!(conflict_marker_size(&content), Some(10));
+        assert!(content.contains("<<<<<<<<<< ours\n"));
+
+        let regions = parse_conflicts(&content).unwrap();
+        assert_eq!(regions.len(), 1);
+        assert!(regions[0].ours.contains("ours"));
+        assert!(regions[0].theirs.contains("theirs"));
+        // The file's own marker-like lines are untouched
+        assert!(content.starts_with("Resolving\n=========\n"));
+        assert!(content.contains("\n<<<<<<< HEAD\n"));
+    }
+
+    #[test]
+    fn test_marker_like_content_is_not_a_conflict() {
+        let docs = "# Git\n\nA conflict looks like:\n\n<<<<<<< HEAD\nmine\n\nTitle\n=======\n";
+        assert!(!has_conflict_markers(docs));
+        assert!(parse_conflicts(docs).unwrap().is_empty());
+
+        // Shorter marker lines inside a real region are content
+        let content = "<<<<<<<< ours\n<<<<<<< HEAD\n=======\n========\ntheirs\n>>>>>>>> theirs\n";
+        let regions = parse_conflicts(content).unwrap();
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].ours, "<<<<<<< HEAD\n=======");
+        assert_eq!(regions[0].theirs, "theirs");
+    }
+
     // ========== Helper Function Tests ==========
 
     #[test]
     fn test_count_conflict_regions() {
-        assert_eq!(count_conflict_regions("no conflicts"), 0);
-        assert_eq!(count_conflict_regions("<<<<<<< one\n=======\n>>>>>>>"), 1);
+        assert_eq!(count_conflict_regions("no conflicts", 7), 0);
+        assert_eq!(
+            count_conflict_regions("<<<<<<< one\n=======\n>>>>>>>", 7),
+            1
+        );
         assert_eq!(
-            count_conflict_regions("<<<<<<< one\n>>>>>>>\n<<<<<<< two\n>>>>>>>"),
+            count_conflict_regions("<<<<<<< one\n>>>>>>>\n<<<<<<< two\n>>>>>>>", 7),
             2
         );
+        assert_eq!(
+            count_conflict_regions("<<<<<<< doc\n<<<<<<<< ours\n========\n>>>>>>>> theirs", 8),
+            1
+        );
     }
 
     #[test]
     fn test_rewrite_conflict_labels() {
         let content = "<<<<<<< ours\ncontent\n=======\nother\n>>>>>>> theirs\n";
         let config = TextMergeConfig::with_labels("HEAD", "feature");
-        let rewritten = rewrite_conflict_labels(content, &config);
+        let rewritten = rewrite_conflict_labels(content, &config, 7);
 
         assert!(rewritten.contains("<<<<<<< HEAD"));
         assert!(rewritten.contains(">>>>>>> feature"));
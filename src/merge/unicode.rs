@@ -0,0 +1,192 @@
+//! Byte order marks and Unicode normalization of parsed files
+//!
+//! Editors on Windows often save files with a UTF-8 byte order mark, which
+//! JSON and TOML parsers reject, and macOS tools can write keys in
+//! decomposed (NFD) form, so `café` typed on two machines becomes two
+//! different keys. Structured files are therefore parsed with any leading
+//! BOM removed and their object keys normalized:
+//!
+//! - `nfc` (default): canonical composition, what most editors produce
+//! - `nfkc`: compatibility composition, which also folds look-alikes such as
+//!   full-width letters and ligatures
+//! - `none`: keys are kept byte for byte
+//!
+//! The form is `unicode.normalization` in the global config. Only keys are
+//! normalized; string values are content and stay as written. A BOM is
+//! restored on output when the winning layer had one (see
+//! [`MergedFile::bom`](super::MergedFile::bom)).
+
+use super::MergeValue;
+use crate::core::{JinConfig, JinError, Result};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use unicode_normalization::UnicodeNormalization;
+
+/// UTF-8 byte order mark, as decoded
+pub const BOM: char = '\u{feff}';
+
+/// `content` without a leading byte order mark, and whether it had one
+pub fn strip_bom(content: &str) -> (&str, bool) {
+    match content.strip_prefix(BOM) {
+        Some(rest) => (rest, true),
+        None => (content, false),
+    }
+}
+
+/// Normalization form applied to parsed keys
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Normalization {
+    /// Canonical composition
+    #[default]
+    Nfc,
+    /// Compatibility composition
+    Nfkc,
+    /// Keep keys as written
+    None,
+}
+
+impl Normalization {
+    /// `text` in this form
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            Self::Nfc => text.nfc().collect(),
+            Self::Nfkc => text.nfkc().collect(),
+            Self::None => text.to_string(),
+        }
+    }
+
+    /// Normalize every object key in `value`, recursively
+    ///
+    /// Keys that only differed in form collapse into one; as with duplicate
+    /// keys in a file, the later one wins.
+    pub fn normalize_keys(&self, value: MergeValue) -> MergeValue {
+        if *self == Self::None {
+            return value;
+        }
+        match value {
+            MergeValue::Object(map) => {
+                let mut normalized = IndexMap::with_capacity(map.len());
+                for (key, value) in map {
+                    normalized.insert(self.apply(&key), self.normalize_keys(value));
+                }
+                MergeValue::Object(normalized)
+            }
+            MergeValue::Array(items) => {
+                MergeValue::Array(items.into_iter().map(|v| self.normalize_keys(v)).collect())
+            }
+            other => other,
+        }
+    }
+}
+
+impl std::str::FromStr for Normalization {
+    type Err = JinError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "nfc" => Ok(Self::Nfc),
+            "nfkc" => Ok(Self::Nfkc),
+            "none" => Ok(Self::None),
+            _ => Err(JinError::Config(format!(
+                "Invalid Unicode normalization: {}. Use 'nfc', 'nfkc' or 'none'",
+                value
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for Normalization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Nfc => write!(f, "nfc"),
+            Self::Nfkc => write!(f, "nfkc"),
+            Self::None => write!(f, "none"),
+        }
+    }
+}
+
+/// Unicode settings (stored under `[unicode]` in the global config)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnicodeConfig {
+    /// Form of object keys in parsed structured files
+    #[serde(default)]
+    pub normalization: Normalization,
+}
+
+impl UnicodeConfig {
+    /// Settings loaded once per process
+    pub fn current() -> &'static Self {
+        static UNICODE: OnceLock<UnicodeConfig> = OnceLock::new();
+        UNICODE.get_or_init(|| {
+            JinConfig::load()
+                .ok()
+                .and_then(|c| c.unicode)
+                .unwrap_or_default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_bom() {
+        assert_eq!(strip_bom("\u{feff}{}"), ("{}", true));
+        assert_eq!(strip_bom("{}"), ("{}", false));
+        // Only a leading mark counts
+        assert_eq!(strip_bom("a\u{feff}"), ("a\u{feff}", false));
+    }
+
+    #[test]
+    fn test_normalize_keys() {
+        let decomposed = "cafe\u{301}";
+        let value = MergeValue::from_json(&format!(
+            r#"{{"{}": {{"{}": 1}}, "list": [{{"{}": 2}}], "v": "{}"}}"#,
+            decomposed, decomposed, decomposed, decomposed
+        ))
+        .unwrap();
+
+        let nfc = Normalization::Nfc.normalize_keys(value.clone());
+        let obj = nfc.as_object().unwrap();
+        assert!(obj.contains_key("café"));
+        assert!(obj["café"].as_object().unwrap().contains_key("café"));
+        assert!(obj["list"].as_array().unwrap()[0]
+            .as_object()
+            .unwrap()
+            .contains_key("café"));
+        // Values are left alone
+        assert_eq!(obj["v"].as_str(), Some(decomposed));
+
+        assert_eq!(Normalization::None.normalize_keys(value.clone()), value);
+    }
+
+    #[test]
+    fn test_equivalent_keys_collapse() {
+        let value =
+            MergeValue::from_json("{\"cafe\u{301}\": 1, \"caf\u{e9}\": 2, \"\u{ff21}\": 3}")
+                .unwrap();
+        let nfc = Normalization::Nfc.normalize_keys(value.clone());
+        assert_eq!(nfc.as_object().unwrap().len(), 2);
+        assert_eq!(nfc.as_object().unwrap()["café"], MergeValue::Integer(2));
+
+        // NFKC also folds the full-width letter
+        let nfkc = Normalization::Nfkc.normalize_keys(value);
+        assert!(nfkc.as_object().unwrap().contains_key("A"));
+    }
+
+    #[test]
+    fn test_parse_normalization() {
+        assert_eq!(
+            "nfkc".parse::<Normalization>().unwrap(),
+            Normalization::Nfkc
+        );
+        assert_eq!(Normalization::None.to_string(), "none");
+        assert!(matches!(
+            "nfd".parse::<Normalization>(),
+            Err(JinError::Config(_))
+        ));
+    }
+}
@@ -1,5 +1,7 @@
 //! Merge value types for structured configuration
 
+use super::style::{self, StyleConfig};
+use super::FileFormat;
 use crate::core::{JinError, Result};
 use indexmap::IndexMap;
 use ini::Ini;
@@ -77,12 +79,14 @@ impl MergeValue {
     // ================== Parsing Helpers ==================
 
     /// Parse a JSON string into a MergeValue
+    ///
+    /// Deserializes directly (not through `serde_json::Value`) so object keys
+    /// keep their order in the file.
     pub fn from_json(s: &str) -> Result<Self> {
-        let value: serde_json::Value = serde_json::from_str(s).map_err(|e| JinError::Parse {
+        serde_json::from_str(s).map_err(|e| JinError::Parse {
             format: "JSON".to_string(),
             message: e.to_string(),
-        })?;
-        Ok(Self::from(value))
+        })
     }
 
     /// Parse a YAML string into a MergeValue
@@ -120,12 +124,9 @@ impl MergeValue {
 
     /// Parse a file, auto-detecting format from extension
     ///
-    /// Supported extensions:
-    /// - `.json` - JSON format
-    /// - `.yaml`, `.yml` - YAML format
-    /// - `.toml` - TOML format
-    /// - `.ini`, `.cfg`, `.conf` - INI format
-    /// - `.editorconfig` - INI format (special case)
+    /// Any extension claimed by a registered format backend is supported
+    /// (built in: `.json`, `.yaml`/`.yml`, `.toml`, `.ini`/`.cfg`/`.conf`).
+    /// `.editorconfig` is parsed as INI as a special case.
     pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
         let path = path.as_ref();
         let content = std::fs::read_to_string(path)?;
@@ -137,23 +138,24 @@ impl MergeValue {
 
         // Special case: .editorconfig is INI format
         let file_name = path.file_name().and_then(|n| n.to_str());
+        let registry = crate::merge::format::registry();
         if file_name == Some(".editorconfig") {
-            return Self::from_ini(&content);
+            return registry.parse(&content, FileFormat::Ini);
         }
 
-        match extension.as_deref() {
-            Some("json") => Self::from_json(&content),
-            Some("yaml") | Some("yml") => Self::from_yaml(&content),
-            Some("toml") => Self::from_toml(&content),
-            Some("ini") | Some("cfg") | Some("conf") => Self::from_ini(&content),
-            Some(ext) => Err(JinError::Parse {
-                format: ext.to_string(),
-                message: format!("Unsupported file extension: .{}", ext),
-            }),
-            None => Err(JinError::Parse {
-                format: "unknown".to_string(),
-                message: "File has no extension".to_string(),
-            }),
+        let format = registry.detect(path);
+        match registry.backend(format) {
+            Some(_) => registry.parse(&content, format),
+            None => match extension {
+                Some(ext) => Err(JinError::Parse {
+                    format: ext.to_string(),
+                    message: format!("Unsupported file extension: .{}", ext),
+                }),
+                None => Err(JinError::Parse {
+                    format: "unknown".to_string(),
+                    message: "File has no extension".to_string(),
+                }),
+            },
         }
     }
 
@@ -161,11 +163,12 @@ impl MergeValue {
 
     /// Serialize to a pretty-printed JSON string
     pub fn to_json_string(&self) -> Result<String> {
-        let json_value: serde_json::Value = self.clone().into();
-        serde_json::to_string_pretty(&json_value).map_err(|e| JinError::Parse {
-            format: "JSON".to_string(),
-            message: e.to_string(),
-        })
+        self.to_json_string_with(&StyleConfig::default())
+    }
+
+    /// Serialize to a pretty JSON string in the given style
+    pub fn to_json_string_with(&self, style: &StyleConfig) -> Result<String> {
+        style::to_json(self, style)
     }
 
     /// Serialize to a compact JSON string (no formatting)
@@ -179,11 +182,12 @@ impl MergeValue {
 
     /// Serialize to a YAML string
     pub fn to_yaml_string(&self) -> Result<String> {
-        let yaml_value: serde_yaml::Value = self.clone().into();
-        serde_yaml::to_string(&yaml_value).map_err(|e| JinError::Parse {
-            format: "YAML".to_string(),
-            message: e.to_string(),
-        })
+        self.to_yaml_string_with(&StyleConfig::default())
+    }
+
+    /// Serialize to a YAML string in the given style
+    pub fn to_yaml_string_with(&self, style: &StyleConfig) -> Result<String> {
+        style::to_yaml(self, style)
     }
 
     /// Serialize to a TOML string
@@ -193,11 +197,16 @@ impl MergeValue {
     /// Returns `JinError::Parse` if the value contains null, as TOML
     /// does not support null values.
     pub fn to_toml_string(&self) -> Result<String> {
-        let toml_value: toml::Value = self.clone().try_into()?;
-        toml::to_string_pretty(&toml_value).map_err(|e| JinError::Parse {
-            format: "TOML".to_string(),
-            message: e.to_string(),
-        })
+        self.to_toml_string_with(&StyleConfig::default())
+    }
+
+    /// Serialize to a TOML string in the given style
+    ///
+    /// # Errors
+    ///
+    /// Same as [`MergeValue::to_toml_string`].
+    pub fn to_toml_string_with(&self, style: &StyleConfig) -> Result<String> {
+        style::to_toml(self, style)
     }
 
     /// Serialize to an INI string
@@ -209,12 +218,23 @@ impl MergeValue {
     /// - Arrays (INI doesn't support arrays)
     /// - Objects nested more than 2 levels deep
     pub fn to_ini_string(&self) -> Result<String> {
-        let ini = to_ini_value(self)?;
+        self.to_ini_string_with(&StyleConfig::default())
+    }
+
+    /// Serialize to an INI string in the given style
+    ///
+    /// # Errors
+    ///
+    /// Same as [`MergeValue::to_ini_string`].
+    pub fn to_ini_string_with(&self, style: &StyleConfig) -> Result<String> {
+        let reordered = style::ini_order(self, style);
+        let ini = to_ini_value(reordered.as_ref().unwrap_or(self))?;
         let mut output = Vec::new();
-        ini.write_to(&mut output).map_err(|e| JinError::Parse {
-            format: "INI".to_string(),
-            message: e.to_string(),
-        })?;
+        ini.write_to_opt(&mut output, style::ini_options(style))
+            .map_err(|e| JinError::Parse {
+                format: "INI".to_string(),
+                message: e.to_string(),
+            })?;
         String::from_utf8(output).map_err(|e| JinError::Parse {
             format: "INI".to_string(),
             message: e.to_string(),
@@ -995,6 +1015,16 @@ count: 42
         );
     }
 
+    #[test]
+    fn test_from_json_keeps_key_order() {
+        let value = MergeValue::from_json(r#"{"zeta": 1, "alpha": {"b": 1.5, "a": 2}}"#).unwrap();
+        let obj = value.as_object().unwrap();
+        assert_eq!(obj.keys().collect::<Vec<_>>(), ["zeta", "alpha"]);
+        let alpha = obj["alpha"].as_object().unwrap();
+        assert_eq!(alpha["b"], MergeValue::Float(1.5));
+        assert_eq!(alpha["a"], MergeValue::Integer(2));
+    }
+
     #[test]
     fn test_from_json_invalid() {
         let result = MergeValue::from_json("{invalid json}");
@@ -0,0 +1,140 @@
+//! Event payload types for notifications
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Version of the event payload schema
+///
+/// Bumped whenever a field is removed or changes meaning. Adding optional
+/// fields does not change the version.
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// Kind of operation that produced an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// Staged files were committed to one or more layers
+    Commit,
+    /// Merged layers were applied to the workspace
+    Apply,
+    /// Remote changes were fetched, merged and applied
+    Sync,
+}
+
+impl EventKind {
+    /// Returns the name used in payloads and the `notify.events` filter
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Commit => "commit",
+            EventKind::Apply => "apply",
+            EventKind::Sync => "sync",
+        }
+    }
+}
+
+impl std::fmt::Display for EventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Event payload delivered to notification targets
+///
+/// # Example
+///
+/// ```json
+/// {
+///   "schema_version": 1,
+///   "event": "commit",
+///   "timestamp": "2025-10-19T15:04:02Z",
+///   "project": "ui-dashboard",
+///   "mode": "claude",
+///   "files": [".claude/config.json"],
+///   "commits": { "mode-base": "def456" }
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifyEvent {
+    /// Payload schema version (see [`EVENT_SCHEMA_VERSION`])
+    pub schema_version: u32,
+    /// Operation that produced the event
+    pub event: EventKind,
+    /// ISO 8601 timestamp
+    pub timestamp: String,
+    /// Project name, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Active mode, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    /// Active scope, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// Files affected by the operation
+    pub files: Vec<String>,
+    /// New commit hash per layer (commit events only)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub commits: BTreeMap<String, String>,
+}
+
+impl NotifyEvent {
+    /// Create an event stamped with the current time
+    pub fn new(
+        event: EventKind,
+        project: Option<String>,
+        mode: Option<String>,
+        scope: Option<String>,
+        files: Vec<String>,
+    ) -> Self {
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            event,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            project,
+            mode,
+            scope,
+            files,
+            commits: BTreeMap::new(),
+        }
+    }
+
+    /// Attach per-layer commit hashes
+    pub fn with_commits(mut self, commits: BTreeMap<String, String>) -> Self {
+        self.commits = commits;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_serialization() {
+        let mut commits = BTreeMap::new();
+        commits.insert("mode-base".to_string(), "abc123".to_string());
+        let event = NotifyEvent::new(
+            EventKind::Commit,
+            Some("ui-dashboard".to_string()),
+            Some("claude".to_string()),
+            None,
+            vec![".claude/config.json".to_string()],
+        )
+        .with_commits(commits);
+
+        let json: serde_json::Value = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["schema_version"], EVENT_SCHEMA_VERSION);
+        assert_eq!(json["event"], "commit");
+        assert_eq!(json["mode"], "claude");
+        assert!(json.get("scope").is_none());
+        assert_eq!(json["commits"]["mode-base"], "abc123");
+    }
+
+    #[test]
+    fn test_event_omits_empty_commits() {
+        let event = NotifyEvent::new(EventKind::Apply, None, None, None, vec![]);
+        let json: serde_json::Value = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "apply");
+        assert!(json.get("commits").is_none());
+    }
+}
@@ -0,0 +1,25 @@
+//! Event notifications for external automation
+//!
+//! After commits, applies and syncs, Jin can hand a JSON event payload to a
+//! configured program and/or POST it to a webhook URL, so that chat-ops bots
+//! and CI can announce or react to shared configuration changes.
+//!
+//! Notifications are configured in `~/.jin/config.toml`:
+//!
+//! ```toml
+//! [notify]
+//! exec = "/usr/local/bin/jin-announce"
+//! url = "https://hooks.example.com/jin"
+//! retries = 3        # at most 5
+//! events = ["commit", "sync"]
+//! ```
+//!
+//! Reload hooks ([`reload`]) run commands when `jin apply` changes matching
+//! files, so applications pick up new configuration.
+
+pub mod event;
+pub mod notifier;
+pub mod reload;
+
+pub use event::{EventKind, NotifyEvent, EVENT_SCHEMA_VERSION};
+pub use notifier::{notify, Notifier};
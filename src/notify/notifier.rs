@@ -0,0 +1,361 @@
+//! Delivery of notification events to configured targets
+
+use crate::core::progress;
+use crate::core::{JinConfig, JinError, NotifyConfig, Result};
+use crate::notify::{EventKind, NotifyEvent};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Base delay between delivery attempts; doubled after each failure
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Upper bound on `notify.retries`, whatever the config file says
+pub const MAX_RETRIES: u32 = 5;
+
+/// Longest a single target may spend waiting between attempts
+const MAX_TOTAL_WAIT: Duration = Duration::from_secs(10);
+
+/// Delivers events to the program and/or URL configured under `[notify]`
+///
+/// - `exec`: run through the shell with the JSON payload on stdin and the
+///   event kind in the `JIN_EVENT` environment variable
+/// - `url`: the JSON payload is POSTed with `Content-Type: application/json`
+///   (via `curl`, so no TLS stack is linked into Jin itself)
+///
+/// Each target is retried independently with exponential backoff, at most
+/// [`MAX_RETRIES`] times and for no longer than ten seconds of waiting.
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    config: NotifyConfig,
+    retry_delay: Duration,
+}
+
+impl Notifier {
+    /// Create a notifier for the given configuration
+    ///
+    /// The retry count is capped at [`MAX_RETRIES`].
+    pub fn new(mut config: NotifyConfig) -> Self {
+        config.retries = config.retries.min(MAX_RETRIES);
+        Self {
+            config,
+            retry_delay: DEFAULT_RETRY_DELAY,
+        }
+    }
+
+    /// Load the notifier from the global Jin config
+    ///
+    /// Returns `None` when no notification target is configured.
+    pub fn from_config() -> Result<Option<Self>> {
+        let config = JinConfig::load()?;
+        Ok(config
+            .notify
+            .filter(|n| n.exec.is_some() || n.url.is_some())
+            .map(Self::new))
+    }
+
+    /// Override the base delay between retries
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Check whether events of this kind should be delivered
+    pub fn wants(&self, kind: EventKind) -> bool {
+        self.config.events.is_empty() || self.config.events.iter().any(|e| e == kind.as_str())
+    }
+
+    /// Deliver an event to every configured target
+    ///
+    /// # Errors
+    ///
+    /// Returns `JinError::Other` describing every target that still failed
+    /// after all retries.
+    pub fn send(&self, event: &NotifyEvent) -> Result<()> {
+        if !self.wants(event.event) {
+            return Ok(());
+        }
+
+        let payload = serde_json::to_string(event).map_err(|e| JinError::Parse {
+            format: "JSON".to_string(),
+            message: e.to_string(),
+        })?;
+
+        let mut failures = Vec::new();
+
+        if let Some(program) = &self.config.exec {
+            if let Err(e) = self.with_retries(|| run_exec(program, &payload, event.event)) {
+                failures.push(format!("exec '{}': {}", program, e));
+            }
+        }
+
+        if let Some(url) = &self.config.url {
+            if let Err(e) = self.with_retries(|| post_json(url, &payload)) {
+                failures.push(format!("POST {}: {}", url, e));
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(JinError::Other(format!(
+                "Notification delivery failed: {}",
+                failures.join("; ")
+            )))
+        }
+    }
+
+    /// Run a delivery attempt, retrying with exponential backoff on failure
+    fn with_retries<F>(&self, mut attempt: F) -> Result<()>
+    where
+        F: FnMut() -> Result<()>,
+    {
+        let mut delay = self.retry_delay;
+        let mut waited = Duration::ZERO;
+        let mut last_err = None;
+
+        for n in 0..=self.config.retries {
+            if n > 0 {
+                if waited + delay > MAX_TOTAL_WAIT {
+                    break;
+                }
+                std::thread::sleep(delay);
+                waited += delay;
+                delay *= 2;
+            }
+            match attempt() {
+                Ok(()) => return Ok(()),
+                Err(e) => last_err = Some(e),
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| JinError::Other("no delivery attempted".to_string())))
+    }
+}
+
+/// Deliver an event using the global configuration
+///
+/// This is non-fatal for the calling command: configuration and delivery
+/// failures are reported as warnings and never fail the operation that
+/// produced the event.
+pub fn notify(event: &NotifyEvent) {
+    let notifier = match Notifier::from_config() {
+        Ok(Some(n)) => n,
+        Ok(None) => return,
+        Err(e) => {
+            progress::warn(format_args!("Failed to load notification config: {}", e));
+            return;
+        }
+    };
+
+    if let Err(e) = notifier.send(event) {
+        progress::warn(e);
+    }
+}
+
+/// Run the configured program with the payload on stdin
+fn run_exec(program: &str, payload: &str, kind: EventKind) -> Result<()> {
+    let mut cmd = shell_command(program);
+    cmd.env("JIN_EVENT", kind.as_str())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::inherit());
+
+    let mut child = cmd.spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(payload.as_bytes())?;
+    }
+
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(JinError::Other(format!("exited with {}", status)))
+    }
+}
+
+/// POST the payload to a URL using curl
+fn post_json(url: &str, payload: &str) -> Result<()> {
+    let mut child = Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--fail",
+            "--max-time",
+            "10",
+            "-X",
+            "POST",
+            "-H",
+            "Content-Type: application/json",
+            "--data-binary",
+            "@-",
+            url,
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| JinError::Other(format!("failed to run curl: {}", e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(payload.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(JinError::Other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+#[cfg(unix)]
+pub(super) fn shell_command(program: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(program);
+    cmd
+}
+
+#[cfg(windows)]
+pub(super) fn shell_command(program: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(program);
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample_event(kind: EventKind) -> NotifyEvent {
+        NotifyEvent::new(
+            kind,
+            Some("demo".to_string()),
+            None,
+            None,
+            vec!["config.json".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_wants_all_events_by_default() {
+        let notifier = Notifier::new(NotifyConfig::default());
+        assert!(notifier.wants(EventKind::Commit));
+        assert!(notifier.wants(EventKind::Apply));
+        assert!(notifier.wants(EventKind::Sync));
+    }
+
+    #[test]
+    fn test_wants_filters_events() {
+        let notifier = Notifier::new(NotifyConfig {
+            events: vec!["sync".to_string()],
+            ..Default::default()
+        });
+        assert!(!notifier.wants(EventKind::Commit));
+        assert!(notifier.wants(EventKind::Sync));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_receives_payload_on_stdin() {
+        let temp = TempDir::new().unwrap();
+        let out = temp.path().join("payload.json");
+        let notifier = Notifier::new(NotifyConfig {
+            exec: Some(format!("cat > '{}'", out.display())),
+            ..Default::default()
+        });
+
+        notifier.send(&sample_event(EventKind::Commit)).unwrap();
+
+        let payload: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(payload["event"], "commit");
+        assert_eq!(payload["project"], "demo");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_retries_until_success() {
+        let temp = TempDir::new().unwrap();
+        let counter = temp.path().join("attempts");
+        // Fails on the first attempt, succeeds on the second
+        let script = format!(
+            "echo x >> '{0}'; [ $(wc -l < '{0}') -ge 2 ]",
+            counter.display()
+        );
+        let notifier = Notifier::new(NotifyConfig {
+            exec: Some(script),
+            retries: 2,
+            ..Default::default()
+        })
+        .retry_delay(Duration::from_millis(1));
+
+        notifier.send(&sample_event(EventKind::Apply)).unwrap();
+
+        let attempts = std::fs::read_to_string(&counter).unwrap();
+        assert_eq!(attempts.lines().count(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_failure_reported_after_retries() {
+        let notifier = Notifier::new(NotifyConfig {
+            exec: Some("exit 1".to_string()),
+            retries: 1,
+            ..Default::default()
+        })
+        .retry_delay(Duration::from_millis(1));
+
+        let result = notifier.send(&sample_event(EventKind::Sync));
+        assert!(matches!(result, Err(JinError::Other(msg)) if msg.contains("exec 'exit 1'")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_retries_are_capped() {
+        let temp = TempDir::new().unwrap();
+        let counter = temp.path().join("attempts");
+        let notifier = Notifier::new(NotifyConfig {
+            exec: Some(format!("echo x >> '{}'; exit 1", counter.display())),
+            retries: 100,
+            ..Default::default()
+        })
+        .retry_delay(Duration::from_millis(1));
+
+        assert!(notifier.send(&sample_event(EventKind::Commit)).is_err());
+
+        let attempts = std::fs::read_to_string(&counter).unwrap();
+        assert_eq!(attempts.lines().count(), MAX_RETRIES as usize + 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_retries_stop_at_total_wait() {
+        let temp = TempDir::new().unwrap();
+        let counter = temp.path().join("attempts");
+        let notifier = Notifier::new(NotifyConfig {
+            exec: Some(format!("echo x >> '{}'; exit 1", counter.display())),
+            retries: 3,
+            ..Default::default()
+        })
+        .retry_delay(MAX_TOTAL_WAIT + Duration::from_millis(1));
+
+        assert!(notifier.send(&sample_event(EventKind::Commit)).is_err());
+
+        let attempts = std::fs::read_to_string(&counter).unwrap();
+        assert_eq!(attempts.lines().count(), 1);
+    }
+
+    #[test]
+    fn test_filtered_event_is_not_delivered() {
+        let notifier = Notifier::new(NotifyConfig {
+            exec: Some("exit 1".to_string()),
+            events: vec!["commit".to_string()],
+            ..Default::default()
+        });
+        assert!(notifier.send(&sample_event(EventKind::Apply)).is_ok());
+    }
+}
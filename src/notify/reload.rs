@@ -0,0 +1,195 @@
+//! Reload hooks run after `jin apply`
+//!
+//! Applications that only read their configuration at startup can be told
+//! to reload it when `jin apply` changes one of their files. Hooks map a
+//! path pattern to a shell command in `~/.jin/config.toml`:
+//!
+//! ```toml
+//! [[reload]]
+//! pattern = ".config/kitty/kitty.conf"
+//! command = "pkill -USR1 kitty"
+//! ```
+//!
+//! Patterns follow the `.jinattributes` conventions and are matched against
+//! layer paths. Each command runs at most once per apply, after all files
+//! are written, with the changed paths it matched in `$JIN_FILES` (one per
+//! line). Files whose content did not change trigger nothing. Hooks live in
+//! the global config only, never in layers, so pulling shared configuration
+//! can't run commands; `jin apply --no-reload` skips them.
+
+use super::notifier::shell_command;
+use crate::core::progress;
+use crate::core::{JinConfig, JinError, Result};
+use crate::staging::attributes::{glob_to_regex, normalize};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+/// A reload hook registration (one `[[reload]]` entry)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReloadConfig {
+    /// Path pattern of the files the command reloads
+    pub pattern: String,
+    /// Shell command to run when a matching file changes
+    pub command: String,
+}
+
+/// A registered hook ready to run
+#[derive(Debug, Clone)]
+struct ReloadHook {
+    command: String,
+    matcher: Regex,
+}
+
+/// Hooks registered in the global config
+#[derive(Debug, Clone, Default)]
+pub struct ReloadHooks {
+    hooks: Vec<ReloadHook>,
+}
+
+impl ReloadHooks {
+    /// Hooks from the global config
+    pub fn load() -> Result<Self> {
+        Self::from_config(&JinConfig::load()?.reload)
+    }
+
+    /// Build hooks from registrations
+    pub fn from_config(entries: &[ReloadConfig]) -> Result<Self> {
+        let hooks = entries
+            .iter()
+            .map(|entry| {
+                let matcher = glob_to_regex(&entry.pattern)
+                    .map_err(|message| JinError::Config(format!("reload: {}", message)))?;
+                if entry.command.trim().is_empty() {
+                    return Err(JinError::Config(format!(
+                        "reload: no command for pattern '{}'",
+                        entry.pattern
+                    )));
+                }
+                Ok(ReloadHook {
+                    command: entry.command.clone(),
+                    matcher,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { hooks })
+    }
+
+    /// Commands triggered by `changed`, each once in registration order,
+    /// with the paths that triggered it
+    pub fn triggered(&self, changed: &[PathBuf]) -> Vec<(&str, Vec<String>)> {
+        let mut triggered: Vec<(&str, Vec<String>)> = Vec::new();
+        for hook in &self.hooks {
+            let paths: Vec<String> = changed
+                .iter()
+                .map(|path| normalize(path))
+                .filter(|path| hook.matcher.is_match(path))
+                .collect();
+            if paths.is_empty() {
+                continue;
+            }
+            match triggered
+                .iter_mut()
+                .find(|(command, _)| *command == hook.command)
+            {
+                Some((_, existing)) => {
+                    for path in paths {
+                        if !existing.contains(&path) {
+                            existing.push(path);
+                        }
+                    }
+                }
+                None => triggered.push((&hook.command, paths)),
+            }
+        }
+        triggered
+    }
+}
+
+/// Run the reload hooks matching `changed`
+///
+/// Commands run in the workspace `root`. A failing hook is reported and
+/// never fails the apply. Returns the number of commands run.
+pub fn run(root: &Path, changed: &[PathBuf]) -> usize {
+    if changed.is_empty() {
+        return 0;
+    }
+    let hooks = match ReloadHooks::load() {
+        Ok(hooks) => hooks,
+        Err(e) => {
+            progress::warn(format_args!("{}; reload hooks are disabled", e));
+            return 0;
+        }
+    };
+
+    let triggered = hooks.triggered(changed);
+    for (command, paths) in &triggered {
+        println!("Reloading: {}", command);
+        let status = shell_command(command)
+            .current_dir(root)
+            .env("JIN_FILES", paths.join("\n"))
+            .stdin(Stdio::null())
+            .status();
+        match status {
+            Ok(status) if status.success() => {}
+            Ok(status) => progress::warn(format_args!(
+                "Reload hook '{}' exited with {}",
+                command, status
+            )),
+            Err(e) => progress::warn(format_args!(
+                "Could not run reload hook '{}': {}",
+                command, e
+            )),
+        }
+    }
+    triggered.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hooks(entries: &[(&str, &str)]) -> ReloadHooks {
+        let entries: Vec<ReloadConfig> = entries
+            .iter()
+            .map(|(pattern, command)| ReloadConfig {
+                pattern: pattern.to_string(),
+                command: command.to_string(),
+            })
+            .collect();
+        ReloadHooks::from_config(&entries).unwrap()
+    }
+
+    #[test]
+    fn test_triggered_runs_each_command_once() {
+        let hooks = hooks(&[
+            ("kitty.conf", "pkill -USR1 kitty"),
+            ("*.tmux.conf", "tmux source-file ~/.tmux.conf"),
+            (".config/kitty/*.conf", "pkill -USR1 kitty"),
+        ]);
+        let changed = vec![
+            PathBuf::from(".config/kitty/kitty.conf"),
+            PathBuf::from(".config/kitty/theme.conf"),
+            PathBuf::from("settings.json"),
+        ];
+
+        let triggered = hooks.triggered(&changed);
+        assert_eq!(triggered.len(), 1);
+        assert_eq!(triggered[0].0, "pkill -USR1 kitty");
+        assert_eq!(
+            triggered[0].1,
+            vec![".config/kitty/kitty.conf", ".config/kitty/theme.conf"]
+        );
+        assert!(hooks.triggered(&[PathBuf::from("other.conf")]).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_hook_is_rejected() {
+        let entry = ReloadConfig {
+            pattern: "*.conf".to_string(),
+            command: " ".to_string(),
+        };
+        assert!(ReloadHooks::from_config(&[entry]).is_err());
+    }
+}
@@ -0,0 +1,617 @@
+//! Apply-time file attributes (`.jinattributes`)
+//!
+//! Applied files are written with default permissions (0644), which is wrong
+//! for credentials and other secrets. A `.jinattributes` file at the
+//! workspace root maps path patterns to the mode bits, and optionally the
+//! owner and group, that `jin apply` sets on matching files:
+//!
+//! ```text
+//! # pattern           attributes
+//! *.pem               mode=0600
+//! .env*               mode=0600
+//! secrets/**          mode=0600 owner=deploy group=deploy
+//! bin/*.sh            mode=0755
+//! *.bat               eol=crlf
+//! ```
+//!
+//! `eol` sets the line endings of matching files (`native`, `lf`, `crlf` or
+//! `preserve`; see [`crate::merge::eol`]), overriding the global
+//! `eol.policy`.
+//!
+//! Patterns follow `.gitattributes` conventions: a pattern without a `/`
+//! matches the file name in any directory, otherwise it is matched against
+//! the path from the workspace root; `*` and `?` never match `/`, while `**`
+//! matches across directories, and a backslash makes the next character
+//! literal. Patterns containing whitespace are written in double quotes
+//! (`"my notes.md" mode=0600`). When several rules match, later rules win
+//! for each attribute they set.
+//!
+//! Rules are validated on every platform; on platforms without POSIX
+//! permissions only read-only modes can be honored and `jin apply` warns
+//! that the rest are ignored.
+//!
+//! `.jinattributes` can be committed to a layer (usually the global one,
+//! with `jin add .jinattributes --global`) so a team shares the same rules
+//! on every machine after a sync. When the merged result contains it, that
+//! version is used; otherwise the workspace copy is read. Rules in
+//! `.jinattributes.local` are read last and so override the shared ones on
+//! this machine; keep that file out of the layers.
+
+use crate::core::{JinError, Result};
+use crate::merge::eol::LineEnding;
+use regex::Regex;
+use std::path::Path;
+
+/// Path of the attributes file, relative to the workspace root
+pub const ATTRIBUTES_PATH: &str = ".jinattributes";
+
+/// Machine-local rules read after the shared ones
+pub const LOCAL_ATTRIBUTES_PATH: &str = ".jinattributes.local";
+
+/// Mode applied to files no rule sets a mode for
+pub const DEFAULT_FILE_MODE: u32 = 0o644;
+
+/// A single `.jinattributes` rule
+#[derive(Debug, Clone)]
+pub struct AttributeRule {
+    /// Pattern as written in the file
+    pub pattern: String,
+    /// Permission bits to set (e.g., 0o600)
+    pub mode: Option<u32>,
+    /// Owning user (name or numeric uid)
+    pub owner: Option<String>,
+    /// Owning group (name or numeric gid)
+    pub group: Option<String>,
+    /// Line endings of matching files
+    pub eol: Option<LineEnding>,
+    matcher: Regex,
+}
+
+impl AttributeRule {
+    /// Whether this rule applies to a workspace-relative path
+    pub fn matches(&self, path: &Path) -> bool {
+        self.matcher.is_match(&normalize(path))
+    }
+}
+
+/// Effective attributes for one file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileAttributes {
+    /// Permission bits to set
+    pub mode: Option<u32>,
+    /// Owning user (name or numeric uid)
+    pub owner: Option<String>,
+    /// Owning group (name or numeric gid)
+    pub group: Option<String>,
+    /// Line endings
+    pub eol: Option<LineEnding>,
+}
+
+impl FileAttributes {
+    /// Whether any attribute is set
+    pub fn is_empty(&self) -> bool {
+        self.mode.is_none() && self.owner.is_none() && self.group.is_none() && self.eol.is_none()
+    }
+}
+
+/// Parsed `.jinattributes` rules
+#[derive(Debug, Clone, Default)]
+pub struct JinAttributes {
+    /// Rules in file order
+    pub rules: Vec<AttributeRule>,
+}
+
+impl JinAttributes {
+    /// Load `.jinattributes` from the workspace root (no rules if absent)
+    pub fn load(root: &Path) -> Result<Self> {
+        Self::load_from(&root.join(ATTRIBUTES_PATH))
+    }
+
+    /// Load attribute rules from a specific file (no rules if absent)
+    pub fn load_from(path: &Path) -> Result<Self> {
+        match read_optional(path)? {
+            Some(content) => Self::parse_named(&content, &path.to_string_lossy()),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Rules for `jin apply`: `shared` (the merged `.jinattributes` when a
+    /// layer carries one) or else the workspace copy, followed by the local
+    /// overrides
+    pub fn load_with_overrides(root: &Path, shared: Option<&str>) -> Result<Self> {
+        let mut attributes = match shared {
+            Some(content) => Self::parse(content)?,
+            None => Self::load(root)?,
+        };
+        let local = Self::load_from(&root.join(LOCAL_ATTRIBUTES_PATH))?;
+        attributes.rules.extend(local.rules);
+        Ok(attributes)
+    }
+
+    /// Parse and validate attribute rules
+    pub fn parse(content: &str) -> Result<Self> {
+        Self::parse_named(content, ATTRIBUTES_PATH)
+    }
+
+    fn parse_named(content: &str, name: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let rule = parse_rule(line).map_err(|message| JinError::Parse {
+                format: name.to_string(),
+                message: format!("line {}: {}", index + 1, message),
+            })?;
+            rules.push(rule);
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Whether there are no rules
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Effective attributes for a path (later matching rules win)
+    pub fn resolve(&self, path: &Path) -> FileAttributes {
+        let mut attributes = FileAttributes::default();
+        for rule in self.rules.iter().filter(|r| r.matches(path)) {
+            if rule.mode.is_some() {
+                attributes.mode = rule.mode;
+            }
+            if rule.owner.is_some() {
+                attributes.owner = rule.owner.clone();
+            }
+            if rule.group.is_some() {
+                attributes.group = rule.group.clone();
+            }
+            if rule.eol.is_some() {
+                attributes.eol = rule.eol;
+            }
+        }
+        attributes
+    }
+}
+
+fn parse_rule(line: &str) -> std::result::Result<AttributeRule, String> {
+    let mut fields = split_fields(line)?.into_iter();
+    let pattern = fields.next().unwrap_or_default();
+
+    let mut rule = AttributeRule {
+        matcher: glob_to_regex(&pattern)?,
+        pattern,
+        mode: None,
+        owner: None,
+        group: None,
+        eol: None,
+    };
+
+    for field in fields {
+        let (key, value) = field
+            .split_once('=')
+            .ok_or_else(|| format!("expected key=value, found '{}'", field))?;
+        if value.is_empty() {
+            return Err(format!("empty value for '{}'", key));
+        }
+        match key {
+            "mode" => rule.mode = Some(parse_mode(value)?),
+            "owner" => rule.owner = Some(value.to_string()),
+            "group" => rule.group = Some(value.to_string()),
+            "eol" => rule.eol = Some(value.parse().map_err(|e: JinError| e.to_string())?),
+            _ => {
+                return Err(format!(
+                    "unknown attribute '{}' (expected mode, owner, group or eol)",
+                    key
+                ))
+            }
+        }
+    }
+
+    if rule.mode.is_none() && rule.owner.is_none() && rule.group.is_none() && rule.eol.is_none() {
+        return Err(format!("pattern '{}' sets no attributes", rule.pattern));
+    }
+    Ok(rule)
+}
+
+/// Read a rules file, `None` if it does not exist
+pub(crate) fn read_optional(path: &Path) -> Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Split the first field off a rules line
+///
+/// A field is a run of non-whitespace characters, or a double-quoted string
+/// as in `.gitattributes` (`"my notes.md"`), which may contain whitespace
+/// and the escapes `\"`, `\\`, `\t`, `\n`, `\r` and `\ooo` (octal bytes,
+/// as `git ls-files` prints non-ASCII names). Returns the field and the
+/// rest of the line, or `None` at the end of the line.
+pub(crate) fn next_field(line: &str) -> std::result::Result<Option<(String, &str)>, String> {
+    let line = line.trim_start();
+    let Some(quoted) = line.strip_prefix('"') else {
+        return Ok(line
+            .split_once(char::is_whitespace)
+            .map(|(field, rest)| (field.to_string(), rest))
+            .or_else(|| (!line.is_empty()).then(|| (line.to_string(), ""))));
+    };
+
+    let mut bytes = Vec::new();
+    let mut chars = quoted.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => {
+                let rest = &quoted[i + 1..];
+                if rest.starts_with(|c: char| !c.is_whitespace()) {
+                    return Err(format!("expected whitespace after {}", &line[..i + 2]));
+                }
+                let field = String::from_utf8(bytes)
+                    .map_err(|_| format!("{} is not valid UTF-8", &line[..i + 2]))?;
+                return Ok(Some((field, rest)));
+            }
+            '\\' => {
+                let escaped = match chars.next().map(|(_, c)| c) {
+                    Some('"') => b'"',
+                    Some('\\') => b'\\',
+                    Some('t') => b'\t',
+                    Some('n') => b'\n',
+                    Some('r') => b'\r',
+                    Some(d @ '0'..='3') => {
+                        let digits: String = std::iter::once(d)
+                            .chain(chars.by_ref().take(2).map(|(_, c)| c))
+                            .collect();
+                        u8::from_str_radix(&digits, 8)
+                            .map_err(|_| format!("invalid escape '\\{}'", digits))?
+                    }
+                    Some(other) => return Err(format!("invalid escape '\\{}'", other)),
+                    None => break,
+                };
+                bytes.push(escaped);
+            }
+            c => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    Err(format!("unterminated quote in {}", line))
+}
+
+/// All fields of a rules line (see [`next_field`])
+pub(crate) fn split_fields(line: &str) -> std::result::Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut rest = line;
+    while let Some((field, remainder)) = next_field(rest)? {
+        fields.push(field);
+        rest = remainder;
+    }
+    Ok(fields)
+}
+
+/// Parse octal permission bits such as `0600` or `755`
+fn parse_mode(value: &str) -> std::result::Result<u32, String> {
+    let digits = value.strip_prefix("0o").unwrap_or(value);
+    match u32::from_str_radix(digits, 8) {
+        Ok(mode) if mode <= 0o7777 => Ok(mode),
+        _ => Err(format!(
+            "invalid mode '{}' (expected octal permissions such as 0600)",
+            value
+        )),
+    }
+}
+
+/// Translate a `.gitattributes`-style pattern into an anchored regex
+pub(crate) fn glob_to_regex(pattern: &str) -> std::result::Result<Regex, String> {
+    let (anchored, body) = match pattern.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (pattern.contains('/'), pattern),
+    };
+    if body.is_empty() {
+        return Err("empty pattern".to_string());
+    }
+
+    let mut regex = String::from("^");
+    if !anchored {
+        regex.push_str("(?:.*/)?");
+    }
+
+    let chars: Vec<char> = body.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                if chars.get(i + 2) == Some(&'/') {
+                    regex.push_str("(?:.*/)?");
+                    i += 3;
+                } else {
+                    regex.push_str(".*");
+                    i += 2;
+                }
+                continue;
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            // A backslash makes the next character literal (`\*`)
+            '\\' if i + 1 < chars.len() => {
+                regex.push_str(&regex::escape(&chars[i + 1].to_string()));
+                i += 2;
+                continue;
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    regex.push('$');
+
+    Regex::new(&regex).map_err(|e| format!("invalid pattern '{}': {}", pattern, e))
+}
+
+/// Workspace-relative path with `/` separators and no leading `./`
+pub(crate) fn normalize(path: &Path) -> String {
+    let path = path.strip_prefix(".").unwrap_or(path);
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Set the mode bits of an applied file
+///
+/// Files without a mode rule get [`DEFAULT_FILE_MODE`].
+#[cfg(unix)]
+pub fn apply_mode(path: &Path, attributes: &FileAttributes) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = attributes.mode.unwrap_or(DEFAULT_FILE_MODE);
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    Ok(())
+}
+
+/// Set the mode bits of an applied file
+///
+/// Without POSIX permissions only the read-only bit can be honored: a mode
+/// without any write bit marks the file read-only.
+#[cfg(not(unix))]
+pub fn apply_mode(path: &Path, attributes: &FileAttributes) -> Result<()> {
+    if let Some(mode) = attributes.mode {
+        let mut permissions = std::fs::metadata(path)?.permissions();
+        permissions.set_readonly(mode & 0o222 == 0);
+        std::fs::set_permissions(path, permissions)?;
+    }
+    Ok(())
+}
+
+/// Change the owner and group of an applied file
+///
+/// This usually requires elevated privileges, so callers should report
+/// failures rather than abort.
+#[cfg(unix)]
+pub fn apply_ownership(path: &Path, attributes: &FileAttributes) -> Result<()> {
+    if attributes.owner.is_none() && attributes.group.is_none() {
+        return Ok(());
+    }
+    let uid = attributes.owner.as_deref().map(lookup_user).transpose()?;
+    let gid = attributes.group.as_deref().map(lookup_group).transpose()?;
+    std::os::unix::fs::chown(path, uid, gid).map_err(|e| {
+        JinError::Other(format!(
+            "Could not change ownership of {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// Ownership cannot be changed without POSIX permissions
+#[cfg(not(unix))]
+pub fn apply_ownership(path: &Path, attributes: &FileAttributes) -> Result<()> {
+    if attributes.owner.is_none() && attributes.group.is_none() {
+        return Ok(());
+    }
+    Err(JinError::Other(format!(
+        "Cannot change ownership of {} on this platform",
+        path.display()
+    )))
+}
+
+/// Whether this platform can enforce every attribute in the rules
+pub fn platform_supports(attributes: &JinAttributes) -> bool {
+    cfg!(unix)
+        || attributes
+            .rules
+            .iter()
+            .all(|r| r.mode.is_none() && r.owner.is_none() && r.group.is_none())
+}
+
+#[cfg(unix)]
+fn lookup_user(name: &str) -> Result<u32> {
+    if let Ok(uid) = name.parse() {
+        return Ok(uid);
+    }
+    let c_name = std::ffi::CString::new(name)
+        .map_err(|_| JinError::Other(format!("Invalid user name: {}", name)))?;
+    // SAFETY: c_name is a valid NUL-terminated string; getpwnam returns
+    // either null or a pointer to a static passwd record that is read
+    // immediately, before any other passwd lookup can overwrite it.
+    let passwd = unsafe { libc::getpwnam(c_name.as_ptr()) };
+    if passwd.is_null() {
+        return Err(JinError::NotFound(format!("User '{}'", name)));
+    }
+    // SAFETY: passwd was checked to be non-null above
+    Ok(unsafe { (*passwd).pw_uid })
+}
+
+#[cfg(unix)]
+fn lookup_group(name: &str) -> Result<u32> {
+    if let Ok(gid) = name.parse() {
+        return Ok(gid);
+    }
+    let c_name = std::ffi::CString::new(name)
+        .map_err(|_| JinError::Other(format!("Invalid group name: {}", name)))?;
+    // SAFETY: c_name is a valid NUL-terminated string; getgrnam returns
+    // either null or a pointer to a static group record that is read
+    // immediately, before any other group lookup can overwrite it.
+    let group = unsafe { libc::getgrnam(c_name.as_ptr()) };
+    if group.is_null() {
+        return Err(JinError::NotFound(format!("Group '{}'", name)));
+    }
+    // SAFETY: group was checked to be non-null above
+    Ok(unsafe { (*group).gr_gid })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::path::PathBuf;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        glob_to_regex(pattern)
+            .unwrap()
+            .is_match(&normalize(&PathBuf::from(path)))
+    }
+
+    #[test]
+    fn test_pattern_matching() {
+        assert!(matches("*.pem", "key.pem"));
+        assert!(matches("*.pem", "certs/deep/key.pem"));
+        assert!(!matches("*.pem", "key.pem.bak"));
+        assert!(matches(".env*", "./.env.local"));
+        assert!(matches("/config.json", "config.json"));
+        assert!(!matches("/config.json", "sub/config.json"));
+        assert!(matches("secrets/*", "secrets/token"));
+        assert!(!matches("secrets/*", "secrets/nested/token"));
+        assert!(matches("secrets/**", "secrets/nested/token"));
+        assert!(matches("**/id_?sa", "home/.ssh/id_rsa"));
+        assert!(matches("**/id_?sa", "id_dsa"));
+    }
+
+    #[test]
+    fn test_quoted_patterns() {
+        assert_eq!(
+            split_fields(r#""my notes.md"  mode=0600 "a\"b\\c" "\303\251t\303\251""#).unwrap(),
+            ["my notes.md", "mode=0600", "a\"b\\c", "\u{e9}t\u{e9}"]
+        );
+        assert!(split_fields(r#""open mode=0600"#).is_err());
+        assert!(split_fields(r#""a"b"#).is_err());
+        assert!(split_fields(r#""\q""#).is_err());
+
+        let attributes =
+            JinAttributes::parse("\"secrets/my key*.pem\" mode=0600\nliteral\\*.txt mode=0640\n")
+                .unwrap();
+        assert_eq!(
+            attributes
+                .resolve(Path::new("secrets/my key (old).pem"))
+                .mode,
+            Some(0o600)
+        );
+        assert_eq!(
+            attributes.resolve(Path::new("literal*.txt")).mode,
+            Some(0o640)
+        );
+        assert!(attributes.resolve(Path::new("literally.txt")).is_empty());
+    }
+
+    #[test]
+    fn test_parse_and_resolve() {
+        let attributes = JinAttributes::parse(
+            "# secrets\n*.env mode=0600\n\nprod/*.env mode=0640 group=deploy\n",
+        )
+        .unwrap();
+        assert_eq!(attributes.rules.len(), 2);
+
+        assert_eq!(
+            attributes.resolve(Path::new("dev.env")),
+            FileAttributes {
+                mode: Some(0o600),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            attributes.resolve(Path::new("prod/app.env")),
+            FileAttributes {
+                mode: Some(0o640),
+                owner: None,
+                group: Some("deploy".to_string()),
+                eol: None,
+            }
+        );
+        assert!(attributes.resolve(Path::new("README.md")).is_empty());
+
+        let attributes = JinAttributes::parse("*.bat eol=crlf\n").unwrap();
+        assert_eq!(
+            attributes.resolve(Path::new("scripts/run.bat")).eol,
+            Some(LineEnding::Crlf)
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        for (content, expected) in [
+            ("*.pem mode=999", "line 1: invalid mode"),
+            (
+                "ok mode=0600\n*.pem perms=0600",
+                "line 2: unknown attribute",
+            ),
+            ("*.pem", "sets no attributes"),
+            ("*.pem mode", "expected key=value"),
+            ("*.bat eol=dos", "Invalid line-ending policy"),
+        ] {
+            let err = JinAttributes::parse(content).unwrap_err().to_string();
+            assert!(err.contains(expected), "{} -> {}", content, err);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_local_rules_override_shared() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::env::set_current_dir(temp.path()).unwrap();
+
+        std::fs::write(".jinattributes", "*.key mode=0644\n").unwrap();
+        std::fs::write(LOCAL_ATTRIBUTES_PATH, "*.key owner=me\n*.pem mode=0400\n").unwrap();
+        let shared =
+            JinAttributes::load_with_overrides(temp.path(), Some("*.key mode=0600\n")).unwrap();
+        let workspace = JinAttributes::load_with_overrides(temp.path(), None).unwrap();
+        std::fs::write(LOCAL_ATTRIBUTES_PATH, "*.pem mode=9").unwrap();
+        let err = JinAttributes::load_with_overrides(temp.path(), None).unwrap_err();
+
+        // The merged copy wins over the workspace copy; local rules win last
+        assert_eq!(
+            shared.resolve(Path::new("a.key")),
+            FileAttributes {
+                mode: Some(0o600),
+                owner: Some("me".to_string()),
+                group: None,
+                eol: None,
+            }
+        );
+        assert_eq!(workspace.resolve(Path::new("a.key")).mode, Some(0o644));
+        assert_eq!(shared.resolve(Path::new("b.pem")).mode, Some(0o400));
+        assert!(err.to_string().contains(LOCAL_ATTRIBUTES_PATH));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_apply_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = temp.path().join("token");
+        std::fs::write(&file, "secret").unwrap();
+
+        let attributes = FileAttributes {
+            mode: Some(0o600),
+            ..Default::default()
+        };
+        apply_mode(&file, &attributes).unwrap();
+        let mode = std::fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        apply_mode(&file, &FileAttributes::default()).unwrap();
+        let mode = std::fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, DEFAULT_FILE_MODE);
+    }
+}
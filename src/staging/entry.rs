@@ -53,6 +53,12 @@ impl StagedEntry {
         }
     }
 
+    /// `<layer>:<path>`, addressing this entry even when its path is staged
+    /// to several layers
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.target_layer, self.path.display())
+    }
+
     /// Check if this entry is a deletion
     pub fn is_delete(&self) -> bool {
         self.operation == StagedOperation::Delete
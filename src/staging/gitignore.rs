@@ -4,7 +4,7 @@
 //! The managed block is delimited by special markers to prevent conflicts
 //! with user-managed entries.
 
-use crate::core::Result;
+use crate::core::{HomeConfig, Result};
 use std::path::Path;
 
 /// Start marker for Jin managed block
@@ -24,13 +24,23 @@ const GITIGNORE_PATH: &str = ".gitignore";
 ///
 /// # Arguments
 ///
+/// * `root` - Workspace root holding the .gitignore
 /// * `path` - Path to add to the managed block
 ///
 /// # Errors
 ///
 /// Returns `JinError::Io` if the file cannot be read or written
-pub fn ensure_in_managed_block(path: &Path) -> Result<()> {
-    ensure_in_managed_block_at(path, Path::new(GITIGNORE_PATH))
+pub fn ensure_in_managed_block(root: &Path, path: &Path) -> Result<()> {
+    // The home workspace isn't a Git repository: nothing to ignore
+    if HomeConfig::current(root).is_some() {
+        return Ok(());
+    }
+    ensure_in_managed_block_at(path, &root.join(GITIGNORE_PATH))
+}
+
+/// Entries currently in the .gitignore managed block of a workspace
+pub fn managed_entries(root: &Path) -> Vec<String> {
+    parse_managed_block(&read_gitignore_at(&root.join(GITIGNORE_PATH))).1
 }
 
 /// Ensure a path is in the .gitignore managed block at a specific gitignore path
@@ -65,13 +75,17 @@ fn ensure_in_managed_block_at(path: &Path, gitignore_path: &Path) -> Result<()>
 ///
 /// # Arguments
 ///
+/// * `root` - Workspace root holding the .gitignore
 /// * `path` - Path to remove from the managed block
 ///
 /// # Errors
 ///
 /// Returns `JinError::Io` if the file cannot be read or written
-pub fn remove_from_managed_block(path: &Path) -> Result<()> {
-    remove_from_managed_block_at(path, Path::new(GITIGNORE_PATH))
+pub fn remove_from_managed_block(root: &Path, path: &Path) -> Result<()> {
+    if HomeConfig::current(root).is_some() {
+        return Ok(());
+    }
+    remove_from_managed_block_at(path, &root.join(GITIGNORE_PATH))
 }
 
 /// Remove a path from the .gitignore managed block at a specific gitignore path
@@ -80,12 +94,16 @@ pub fn remove_from_managed_block(path: &Path) -> Result<()> {
 fn remove_from_managed_block_at(path: &Path, gitignore_path: &Path) -> Result<()> {
     let content = read_gitignore_at(gitignore_path);
     let path_str = normalize_path(path);
+    let legacy = unescaped_path(path);
 
     // Parse existing content
     let (before, managed, after) = parse_managed_block(&content);
 
-    // Remove the path
-    let new_managed: Vec<String> = managed.into_iter().filter(|p| p != &path_str).collect();
+    // Remove the path, also as written before entries were escaped
+    let new_managed: Vec<String> = managed
+        .into_iter()
+        .filter(|p| p != &path_str && p != &legacy)
+        .collect();
 
     // Rebuild content
     let new_content = build_gitignore(&before, &new_managed, &after);
@@ -98,10 +116,42 @@ fn remove_from_managed_block_at(path: &Path, gitignore_path: &Path) -> Result<()
 ///
 /// Converts path to a string suitable for .gitignore,
 /// using forward slashes and adding trailing slash for directories.
-fn normalize_path(path: &Path) -> String {
-    let path_str = path.display().to_string();
+/// Characters gitignore reads as syntax are escaped so the entry matches
+/// exactly this path: a leading `#` or `!`, the glob characters `*`, `?`
+/// and `[`, and trailing spaces.
+pub(crate) fn normalize_path(path: &Path) -> String {
+    let path_str = unescaped_path(path);
+    let mut entry = String::with_capacity(path_str.len());
+    if path_str.starts_with(['#', '!']) {
+        entry.push('\\');
+    }
+    let kept = path_str.trim_end_matches(' ');
+    for c in kept.chars() {
+        if matches!(c, '*' | '?' | '[') {
+            entry.push('\\');
+        }
+        entry.push(c);
+    }
+    for _ in kept.len()..path_str.len() {
+        entry.push_str("\\ ");
+    }
+    entry
+}
+
+/// The path with forward slashes, as entries were written before escaping
+fn unescaped_path(path: &Path) -> String {
     // Convert backslashes to forward slashes for cross-platform
-    path_str.replace('\\', "/")
+    path.display().to_string().replace('\\', "/")
+}
+
+/// A managed block line without surrounding whitespace, keeping trailing
+/// spaces escaped with a backslash
+fn trim_entry(line: &str) -> &str {
+    let mut entry = line.trim_start().trim_end_matches(['\r', '\t']);
+    while entry.ends_with(' ') && !entry[..entry.len() - 1].ends_with('\\') {
+        entry = &entry[..entry.len() - 1];
+    }
+    entry
 }
 
 /// Read the .gitignore file at a specific path, returning empty string if it doesn't exist
@@ -140,7 +190,7 @@ fn parse_managed_block(content: &str) -> (String, Vec<String>, String) {
 
         if in_block {
             // Only add non-empty, non-comment lines as managed entries
-            let trimmed = line.trim();
+            let trimmed = trim_entry(line);
             if !trimmed.is_empty() && !trimmed.starts_with('#') {
                 managed.push(trimmed.to_string());
             }
@@ -302,6 +352,39 @@ mod tests {
         assert!(result.contains("# end comment"));
     }
 
+    #[test]
+    fn test_entries_escape_gitignore_syntax() {
+        assert_eq!(normalize_path(Path::new("my notes.md")), "my notes.md");
+        assert_eq!(normalize_path(Path::new("#todo.md")), "\\#todo.md");
+        assert_eq!(normalize_path(Path::new("!keep")), "\\!keep");
+        assert_eq!(
+            normalize_path(Path::new("a[1]*?.json")),
+            "a\\[1]\\*\\?.json"
+        );
+        assert_eq!(normalize_path(Path::new("trailing  ")), "trailing\\ \\ ");
+        assert_eq!(
+            normalize_path(Path::new("café/日本.json")),
+            "café/日本.json"
+        );
+
+        let temp = TempDir::new().unwrap();
+        let gitignore = temp.path().join(".gitignore");
+        for name in ["#todo.md", "trailing ", "a*.json"] {
+            ensure_in_managed_block_at(Path::new(name), &gitignore).unwrap();
+            ensure_in_managed_block_at(Path::new(name), &gitignore).unwrap();
+        }
+        let content = std::fs::read_to_string(&gitignore).unwrap();
+        assert_eq!(parse_managed_block(&content).1.len(), 3);
+        assert!(content.contains("trailing\\ \n"));
+
+        remove_from_managed_block_at(Path::new("trailing "), &gitignore).unwrap();
+        let content = std::fs::read_to_string(&gitignore).unwrap();
+        assert_eq!(
+            parse_managed_block(&content).1,
+            vec!["\\#todo.md".to_string(), "a\\*.json".to_string()]
+        );
+    }
+
     #[test]
     fn test_normalize_path() {
         assert_eq!(normalize_path(Path::new(".claude/")), ".claude/");
@@ -1,4 +1,10 @@
 //! Staging index for Jin
+//!
+//! A path may be staged to several layers at once (by adding it with
+//! different flags before committing); each is a separate entry. Commands
+//! taking a single entry accept any of the forms [`StagingIndex::select`]
+//! understands: `<layer>:<path>`, a path staged to one layer only, or the
+//! number `jin status` lists it under.
 
 use super::StagedEntry;
 use crate::core::{JinError, Layer, Result};
@@ -9,8 +15,9 @@ use std::path::{Path, PathBuf};
 /// The staging index, tracking all staged files
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct StagingIndex {
-    /// Staged entries, keyed by path
-    entries: HashMap<PathBuf, StagedEntry>,
+    /// Staged entries, keyed by target layer and path
+    #[serde(with = "keyed_entries")]
+    entries: HashMap<(Layer, PathBuf), StagedEntry>,
     /// Version of the staging format
     #[serde(default = "default_version")]
     version: u32,
@@ -29,9 +36,9 @@ impl StagingIndex {
         }
     }
 
-    /// Load the staging index from disk
-    pub fn load() -> Result<Self> {
-        let path = Self::default_path();
+    /// Load the staging index of a workspace from disk
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = Self::default_path(root);
         if path.exists() {
             let content = std::fs::read_to_string(&path).map_err(JinError::Io)?;
             serde_json::from_str(&content).map_err(|e| JinError::Parse {
@@ -46,8 +53,8 @@ impl StagingIndex {
     /// Save the staging index to disk
     ///
     /// Uses atomic write pattern: write to temp file, then rename.
-    pub fn save(&self) -> Result<()> {
-        let path = Self::default_path();
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = Self::default_path(root);
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent).map_err(JinError::Io)?;
         }
@@ -64,28 +71,52 @@ impl StagingIndex {
         Ok(())
     }
 
-    /// Get the default path for the staging index
-    pub fn default_path() -> PathBuf {
+    /// Get the path of the staging index of a workspace
+    pub fn default_path(root: &Path) -> PathBuf {
         // Check JIN_DIR environment variable first for test isolation
         if let Ok(jin_dir) = std::env::var("JIN_DIR") {
             return PathBuf::from(jin_dir).join("staging").join("index.json");
         }
-        PathBuf::from(".jin").join("staging").join("index.json")
+        root.join(".jin").join("staging").join("index.json")
     }
 
-    /// Add an entry to the staging index
+    /// Add an entry to the staging index, replacing the entry for the same
+    /// path and layer
     pub fn add(&mut self, entry: StagedEntry) {
-        self.entries.insert(entry.path.clone(), entry);
+        self.entries
+            .insert((entry.target_layer, entry.path.clone()), entry);
     }
 
-    /// Remove an entry from the staging index
-    pub fn remove(&mut self, path: &Path) -> Option<StagedEntry> {
-        self.entries.remove(path)
+    /// Remove every entry for a path, whatever its layer
+    pub fn remove(&mut self, path: &Path) -> Vec<StagedEntry> {
+        let keys: Vec<(Layer, PathBuf)> = self
+            .entries
+            .keys()
+            .filter(|(_, p)| p == path)
+            .cloned()
+            .collect();
+        keys.iter()
+            .filter_map(|key| self.entries.remove(key))
+            .collect()
     }
 
-    /// Get an entry by path
+    /// Remove the entry for a path in one layer
+    pub fn remove_entry(&mut self, layer: Layer, path: &Path) -> Option<StagedEntry> {
+        self.entries.remove(&(layer, path.to_path_buf()))
+    }
+
+    /// Get the entry for a path; when it is staged to several layers, the
+    /// one with the highest precedence
     pub fn get(&self, path: &Path) -> Option<&StagedEntry> {
-        self.entries.get(path)
+        self.entries
+            .values()
+            .filter(|e| e.path == path)
+            .max_by_key(|e| e.target_layer.precedence())
+    }
+
+    /// Get the entry for a path in one layer
+    pub fn get_in(&self, layer: Layer, path: &Path) -> Option<&StagedEntry> {
+        self.entries.get(&(layer, path.to_path_buf()))
     }
 
     /// Get all entries
@@ -93,9 +124,74 @@ impl StagingIndex {
         self.entries.values()
     }
 
-    /// Get all staged paths
+    /// Get all staged paths (once per layer they are staged to)
     pub fn paths(&self) -> impl Iterator<Item = &PathBuf> {
-        self.entries.keys()
+        self.entries.keys().map(|(_, path)| path)
+    }
+
+    /// All entries in the order `jin status` numbers them (from 1): by path,
+    /// then by layer precedence
+    pub fn numbered(&self) -> Vec<&StagedEntry> {
+        let mut entries: Vec<&StagedEntry> = self.entries.values().collect();
+        entries.sort_by(|a, b| {
+            a.path.cmp(&b.path).then(
+                a.target_layer
+                    .precedence()
+                    .cmp(&b.target_layer.precedence()),
+            )
+        });
+        entries
+    }
+
+    /// Find the entry addressed by `spec`: `<layer>:<path>`, a path staged
+    /// to a single layer, or the number `jin status` lists an entry under
+    pub fn select(&self, spec: &str) -> Result<&StagedEntry> {
+        if let Some((layer, path)) = spec.split_once(':') {
+            if let Ok(layer) = layer.parse::<Layer>() {
+                // A staged file may itself be named like an address
+                let literal = self
+                    .numbered()
+                    .into_iter()
+                    .any(|e| e.path == Path::new(spec));
+                if !literal {
+                    return self.get_in(layer, Path::new(path)).ok_or_else(|| {
+                        JinError::NotFound(format!("{} is not staged to {}", path, layer))
+                    });
+                }
+            }
+        }
+
+        let matching: Vec<&StagedEntry> = self
+            .numbered()
+            .into_iter()
+            .filter(|e| e.path == Path::new(spec))
+            .collect();
+        match matching.as_slice() {
+            [entry] => Ok(entry),
+            [] => match spec.parse::<usize>() {
+                Ok(n) => n
+                    .checked_sub(1)
+                    .and_then(|i| self.numbered().get(i).copied())
+                    .ok_or_else(|| {
+                        JinError::NotFound(format!(
+                            "No staged entry #{} ({} staged)",
+                            n,
+                            self.len()
+                        ))
+                    }),
+                Err(_) => Err(JinError::NotFound(format!("{} is not staged", spec))),
+            },
+            entries => Err(JinError::Other(format!(
+                "{} is staged to {} layers; pick one of: {}",
+                spec,
+                entries.len(),
+                entries
+                    .iter()
+                    .map(|e| e.address())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+        }
     }
 
     /// Get entries for a specific layer
@@ -135,6 +231,43 @@ impl StagingIndex {
     }
 }
 
+/// On disk, entries are keyed by path, or by `<layer>:<path>` when the path
+/// is staged to several layers; keys are rebuilt from the entries on load,
+/// so indexes written before entries were keyed by layer still load
+mod keyed_entries {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+    use std::collections::BTreeMap;
+
+    pub fn serialize<S: Serializer>(
+        entries: &HashMap<(Layer, PathBuf), StagedEntry>,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let mut layers: HashMap<&PathBuf, usize> = HashMap::new();
+        for (_, path) in entries.keys() {
+            *layers.entry(path).or_default() += 1;
+        }
+        let keyed: BTreeMap<String, &StagedEntry> = entries
+            .values()
+            .map(|entry| match layers[&entry.path] {
+                1 => (entry.path.display().to_string(), entry),
+                _ => (entry.address(), entry),
+            })
+            .collect();
+        keyed.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<HashMap<(Layer, PathBuf), StagedEntry>, D::Error> {
+        let keyed: HashMap<String, StagedEntry> = HashMap::deserialize(deserializer)?;
+        Ok(keyed
+            .into_values()
+            .map(|entry| ((entry.target_layer, entry.path.clone()), entry))
+            .collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -212,4 +345,67 @@ mod tests {
         assert_eq!(layers[0], Layer::ModeBase);
         assert_eq!(layers[1], Layer::ProjectBase);
     }
+
+    #[test]
+    fn test_same_path_in_several_layers() {
+        let mut index = StagingIndex::new();
+        index.add(StagedEntry::new(
+            PathBuf::from("config.json"),
+            Layer::ModeBase,
+            "h1".to_string(),
+        ));
+        index.add(StagedEntry::new(
+            PathBuf::from("config.json"),
+            Layer::ProjectBase,
+            "h2".to_string(),
+        ));
+        index.add(StagedEntry::new(
+            PathBuf::from("a.json"),
+            Layer::ProjectBase,
+            "h3".to_string(),
+        ));
+        assert_eq!(index.len(), 3);
+        assert_eq!(
+            index.get(Path::new("config.json")).unwrap().target_layer,
+            Layer::ProjectBase
+        );
+
+        let numbered: Vec<String> = index.numbered().iter().map(|e| e.address()).collect();
+        assert_eq!(
+            numbered,
+            [
+                "project-base:a.json",
+                "mode-base:config.json",
+                "project-base:config.json"
+            ]
+        );
+        assert_eq!(index.select("2").unwrap().content_hash, "h1");
+        assert_eq!(index.select("a.json").unwrap().content_hash, "h3");
+        assert_eq!(
+            index
+                .select("project-base:config.json")
+                .unwrap()
+                .content_hash,
+            "h2"
+        );
+        assert!(matches!(
+            index.select("config.json"),
+            Err(JinError::Other(_))
+        ));
+        assert!(matches!(index.select("4"), Err(JinError::NotFound(_))));
+        assert!(matches!(
+            index.select("global-base:a.json"),
+            Err(JinError::NotFound(_))
+        ));
+
+        // Both entries survive a round trip
+        let json = serde_json::to_string(&index).unwrap();
+        assert!(json.contains("\"mode-base:config.json\""));
+        assert!(json.contains("\"a.json\""));
+        let loaded: StagingIndex = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.len(), 3);
+
+        assert_eq!(index.remove(Path::new("config.json")).len(), 2);
+        assert_eq!(index.len(), 1);
+    }
 }
@@ -22,6 +22,17 @@ pub struct WorkspaceMetadata {
     pub applied_layers: Vec<String>,
     /// Map of file paths to their content hashes (Git blob OID)
     pub files: HashMap<PathBuf, String>,
+    /// Map of layer ref paths to the commit OIDs they pointed at when merged
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub layer_commits: HashMap<String, String>,
+    /// Map of file paths to where they were written, for files applied
+    /// outside the workspace (`.jintargets` or `apply --target`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub targets: HashMap<PathBuf, PathBuf>,
+    /// Map of file paths to the layers their content was merged from, in
+    /// precedence order
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub sources: HashMap<PathBuf, Vec<String>>,
 }
 
 impl WorkspaceMetadata {
@@ -31,16 +42,19 @@ impl WorkspaceMetadata {
             timestamp: chrono::Utc::now().to_rfc3339(),
             applied_layers: Vec::new(),
             files: HashMap::new(),
+            layer_commits: HashMap::new(),
+            targets: HashMap::new(),
+            sources: HashMap::new(),
         }
     }
 
-    /// Load workspace metadata from disk
+    /// Load the workspace metadata of a workspace from disk
     ///
     /// # Returns
     ///
     /// The loaded metadata, or an error if the file doesn't exist or is invalid
-    pub fn load() -> Result<Self> {
-        let path = Self::default_path();
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = Self::default_path(root);
         if path.exists() {
             let content = std::fs::read_to_string(&path)?;
             serde_json::from_str(&content).map_err(|e| JinError::Parse {
@@ -55,8 +69,8 @@ impl WorkspaceMetadata {
     /// Save workspace metadata to disk
     ///
     /// Creates the parent directory if it doesn't exist.
-    pub fn save(&self) -> Result<()> {
-        let path = Self::default_path();
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = Self::default_path(root);
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
@@ -86,17 +100,28 @@ impl WorkspaceMetadata {
     /// Remove a file from the metadata
     pub fn remove_file(&mut self, path: &Path) {
         self.files.remove(path);
+        self.targets.remove(path);
+        self.sources.remove(path);
     }
 
-    /// Get the default path for workspace metadata
-    pub fn default_path() -> PathBuf {
+    /// Where a tracked file was written (its own path unless it was applied
+    /// outside the workspace)
+    pub fn location(&self, path: &Path) -> PathBuf {
+        self.targets
+            .get(path)
+            .cloned()
+            .unwrap_or_else(|| path.to_path_buf())
+    }
+
+    /// Get the path of the workspace metadata of a workspace
+    pub fn default_path(root: &Path) -> PathBuf {
         // Check JIN_DIR environment variable first for test isolation
         if let Ok(jin_dir) = std::env::var("JIN_DIR") {
             return PathBuf::from(jin_dir)
                 .join("workspace")
                 .join("last_applied.json");
         }
-        PathBuf::from(".jin")
+        root.join(".jin")
             .join("workspace")
             .join("last_applied.json")
     }
@@ -166,10 +191,10 @@ mod tests {
         meta.add_file(PathBuf::from(".claude/config.json"), "abc123".to_string());
 
         // Save
-        meta.save().unwrap();
+        meta.save(temp.path()).unwrap();
 
         // Load
-        let loaded = WorkspaceMetadata::load().unwrap();
+        let loaded = WorkspaceMetadata::load(temp.path()).unwrap();
         assert_eq!(loaded.applied_layers, meta.applied_layers);
         assert_eq!(loaded.files.len(), 1);
         assert_eq!(
@@ -186,7 +211,7 @@ mod tests {
         let temp = TempDir::new().unwrap();
         std::env::set_current_dir(temp.path()).unwrap();
 
-        let result = WorkspaceMetadata::load();
+        let result = WorkspaceMetadata::load(temp.path());
         assert!(matches!(result, Err(JinError::NotFound(_))));
     }
 
@@ -3,19 +3,25 @@
 //! Manages the staging area where files are prepared before committing
 //! to their target layers.
 
+pub mod attributes;
 pub mod entry;
 pub mod gitignore;
 pub mod index;
 pub mod metadata;
+pub mod owners;
 pub mod router;
+pub mod targets;
 pub mod workspace;
 
+pub use attributes::{FileAttributes, JinAttributes};
 pub use entry::{StagedEntry, StagedOperation};
-pub use gitignore::{ensure_in_managed_block, remove_from_managed_block};
+pub use gitignore::{ensure_in_managed_block, managed_entries, remove_from_managed_block};
 pub use index::StagingIndex;
 pub use metadata::WorkspaceMetadata;
+pub use owners::LayerOwners;
 pub use router::{route_to_layer, validate_routing_options, RoutingOptions};
+pub use targets::TargetMap;
 pub use workspace::{
-    get_file_mode, is_git_tracked, is_symlink, read_file, validate_workspace_attached,
-    walk_directory,
+    get_file_mode, is_git_tracked, is_symlink, read_file, stale_merge_layers,
+    validate_layers_attached, validate_workspace_attached, walk_directory,
 };
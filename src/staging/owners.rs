@@ -0,0 +1,196 @@
+//! Layer ownership (`.jinowners`)
+//!
+//! A layer can record who maintains its files in a `.jinowners` file at the
+//! root of its tree, using `CODEOWNERS` syntax:
+//!
+//! ```text
+//! # pattern           owners
+//! *                   @platform
+//! deploy/             @ops @alice
+//! *.pem               security@example.com
+//! ```
+//!
+//! Owners are `@user`, `@org/team` or email addresses. Patterns follow
+//! [`crate::staging::attributes`] conventions (including double quotes
+//! around patterns with whitespace), and a pattern ending in `/`
+//! covers everything below that directory. The last matching rule wins, so
+//! a rule without owners leaves its paths unowned.
+//!
+//! Each layer has its own owners: the file is committed like any other
+//! (`jin add .jinowners --mode`), but it never takes part in merges and is
+//! never applied to the workspace. `jin status` shows the owners of staged
+//! files, and `jin push` records the owners of pushed changes as required
+//! reviewers (see [`crate::git::reviewers`]).
+
+use super::attributes::{glob_to_regex, normalize, split_fields};
+use crate::core::{JinError, Result};
+use crate::git::{JinRepo, RefOps, TreeOps};
+use git2::Oid;
+use regex::Regex;
+use std::path::Path;
+
+/// Path of the owners file in a layer's tree
+pub const OWNERS_PATH: &str = ".jinowners";
+
+/// A single `.jinowners` rule
+#[derive(Debug, Clone)]
+pub struct OwnerRule {
+    /// Pattern as written in the file
+    pub pattern: String,
+    /// Owners of matching paths (none unassigns them)
+    pub owners: Vec<String>,
+    matcher: Regex,
+}
+
+impl OwnerRule {
+    /// Whether this rule applies to a layer path
+    pub fn matches(&self, path: &Path) -> bool {
+        self.matcher.is_match(&normalize(path))
+    }
+}
+
+/// Parsed `.jinowners` rules of one layer
+#[derive(Debug, Clone, Default)]
+pub struct LayerOwners {
+    /// Rules in file order
+    pub rules: Vec<OwnerRule>,
+}
+
+impl LayerOwners {
+    /// Owners recorded at the tip of a layer ref (none if the ref or the
+    /// file does not exist)
+    pub fn load(repo: &JinRepo, ref_path: &str) -> Result<Self> {
+        if !repo.ref_exists(ref_path) {
+            return Ok(Self::default());
+        }
+        let commit = repo.inner().find_commit(repo.resolve_ref(ref_path)?)?;
+        Self::from_tree(repo, commit.tree_id())
+    }
+
+    /// Owners recorded in a layer tree (none if the file does not exist)
+    pub fn from_tree(repo: &JinRepo, tree: Oid) -> Result<Self> {
+        match repo.read_file_from_tree(tree, Path::new(OWNERS_PATH)) {
+            Ok(content) => Self::parse(&String::from_utf8_lossy(&content)),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Parse and validate owner rules
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let rule = parse_rule(line).map_err(|message| JinError::Parse {
+                format: OWNERS_PATH.to_string(),
+                message: format!("line {}: {}", index + 1, message),
+            })?;
+            rules.push(rule);
+        }
+        Ok(Self { rules })
+    }
+
+    /// Whether there are no rules
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Owners of a path (the last matching rule wins)
+    pub fn owners_of(&self, path: &Path) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(path))
+            .map_or(&[], |rule| rule.owners.as_slice())
+    }
+
+    /// Everyone owning at least one of `paths`, sorted
+    pub fn reviewers<'a>(&self, paths: impl IntoIterator<Item = &'a Path>) -> Vec<String> {
+        let mut reviewers: Vec<String> = paths
+            .into_iter()
+            .flat_map(|path| self.owners_of(path).iter().cloned())
+            .collect();
+        reviewers.sort();
+        reviewers.dedup();
+        reviewers
+    }
+}
+
+fn parse_rule(line: &str) -> std::result::Result<OwnerRule, String> {
+    let mut fields = split_fields(line)?.into_iter();
+    let pattern = fields.next().unwrap_or_default();
+    let glob = match pattern.strip_suffix('/') {
+        Some(dir) => format!("{}/**", dir),
+        None => pattern.clone(),
+    };
+
+    let owners: Vec<String> = fields.collect();
+    if let Some(owner) = owners.iter().find(|owner| !is_owner(owner)) {
+        return Err(format!(
+            "invalid owner '{}' (expected @user, @org/team or an email address)",
+            owner
+        ));
+    }
+
+    Ok(OwnerRule {
+        matcher: glob_to_regex(&glob)?,
+        pattern,
+        owners,
+    })
+}
+
+fn is_owner(owner: &str) -> bool {
+    match owner.strip_prefix('@') {
+        Some(name) => !name.is_empty() && !name.starts_with('/') && !name.ends_with('/'),
+        None => owner
+            .split_once('@')
+            .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.')),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_matching_rule_wins() {
+        let owners = LayerOwners::parse(
+            "# comment\n\
+             *            @platform\n\
+             deploy/      @ops @alice\n\
+             *.pem        security@example.com\n\
+             deploy/tmp/\n",
+        )
+        .unwrap();
+
+        assert_eq!(owners.owners_of(Path::new("app.json")), ["@platform"]);
+        assert_eq!(
+            owners.owners_of(Path::new("deploy/prod.yaml")),
+            ["@ops", "@alice"]
+        );
+        assert_eq!(
+            owners.owners_of(Path::new("deploy/certs/key.pem")),
+            ["security@example.com"]
+        );
+        assert!(owners.owners_of(Path::new("deploy/tmp/x")).is_empty());
+
+        assert_eq!(
+            owners.reviewers([Path::new("app.json"), Path::new("deploy/prod.yaml")]),
+            vec!["@alice", "@ops", "@platform"]
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_owners() {
+        for bad in ["* alice", "* @", "* @org/", "* alice@localhost"] {
+            assert!(
+                matches!(LayerOwners::parse(bad), Err(JinError::Parse { .. })),
+                "{} should be rejected",
+                bad
+            );
+        }
+        assert!(LayerOwners::parse("* @org/team alice@example.com").is_ok());
+    }
+}
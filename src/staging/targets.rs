@@ -0,0 +1,260 @@
+//! Apply destinations outside the workspace (`.jintargets`)
+//!
+//! Some managed files live outside the project, such as dotfiles under
+//! `~/.config`. A `.jintargets` file maps storage paths (as staged, relative
+//! to the workspace root) to the destinations `jin apply` writes them to:
+//!
+//! ```text
+//! # storage path      destination
+//! nvim/init.lua       ~/.config/nvim/init.lua
+//! git/                ~/.config/git/
+//! ```
+//!
+//! Storage paths containing whitespace are written in double quotes
+//! (`"My Notes/" ~/notes/`). A storage path ending in `/` maps every file
+//! below it; the longest matching storage path wins. Destinations may start
+//! with `~` and must be absolute after expansion.
+//!
+//! `.jintargets` can be committed to a layer like any other file. When the
+//! merged result contains it, that version is used, so the mappings travel
+//! with the layer; otherwise the workspace copy (if any) is read. Mappings
+//! in `.jintargets.local` are read last and override the shared ones on
+//! this machine.
+
+use super::attributes::{next_field, read_optional, split_fields};
+use crate::core::config::expand_home;
+use crate::core::{JinError, Result};
+use std::path::{Component, Path, PathBuf};
+
+/// Path of the targets file, relative to the workspace root
+pub const TARGETS_PATH: &str = ".jintargets";
+
+/// Machine-local mappings read after the shared ones
+pub const LOCAL_TARGETS_PATH: &str = ".jintargets.local";
+
+/// A single storage path -> destination mapping
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetRule {
+    /// Storage path (a directory prefix if it ends with `/`)
+    pub storage: String,
+    /// Absolute destination (a directory if `storage` is a prefix)
+    pub destination: PathBuf,
+}
+
+impl TargetRule {
+    fn is_prefix(&self) -> bool {
+        self.storage.ends_with('/')
+    }
+}
+
+/// Parsed `.jintargets` mappings
+#[derive(Debug, Clone, Default)]
+pub struct TargetMap {
+    /// Rules in file order
+    pub rules: Vec<TargetRule>,
+}
+
+impl TargetMap {
+    /// Load `.jintargets` from the workspace root (no mappings if absent)
+    pub fn load(root: &Path) -> Result<Self> {
+        match read_optional(&root.join(TARGETS_PATH))? {
+            Some(content) => Self::parse(&content),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Mappings for `jin apply`: `shared` (the merged `.jintargets` when a
+    /// layer carries one) or else the workspace copy, followed by the local
+    /// overrides
+    ///
+    /// A local mapping for the same storage path replaces the shared one.
+    pub fn load_with_overrides(root: &Path, shared: Option<&str>) -> Result<Self> {
+        let mut targets = match shared {
+            Some(content) => Self::parse(content)?,
+            None => Self::load(root)?,
+        };
+        if let Some(content) = read_optional(&root.join(LOCAL_TARGETS_PATH))? {
+            targets
+                .rules
+                .extend(Self::parse_named(&content, LOCAL_TARGETS_PATH)?.rules);
+        }
+        Ok(targets)
+    }
+
+    /// Parse and validate mappings
+    pub fn parse(content: &str) -> Result<Self> {
+        Self::parse_named(content, TARGETS_PATH)
+    }
+
+    fn parse_named(content: &str, name: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let rule = parse_rule(line).map_err(|message| JinError::Parse {
+                format: name.to_string(),
+                message: format!("line {}: {}", index + 1, message),
+            })?;
+            rules.push(rule);
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Whether there are no mappings
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Destination for a storage path, if one is mapped (the longest
+    /// storage path wins, then the last mapping)
+    pub fn destination(&self, path: &Path) -> Option<PathBuf> {
+        let path = path.strip_prefix(".").unwrap_or(path);
+        let path_str = path.to_string_lossy().replace('\\', "/");
+
+        self.rules
+            .iter()
+            .filter_map(|rule| {
+                if rule.is_prefix() {
+                    let rest = path_str.strip_prefix(&rule.storage)?;
+                    Some((rule.storage.len(), rule.destination.join(rest)))
+                } else if rule.storage == path_str {
+                    Some((rule.storage.len(), rule.destination.clone()))
+                } else {
+                    None
+                }
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, destination)| destination)
+    }
+}
+
+fn parse_rule(line: &str) -> std::result::Result<TargetRule, String> {
+    let (storage, rest) =
+        next_field(line)?.ok_or_else(|| "expected '<storage path> <destination>'".to_string())?;
+    let storage = storage.as_str();
+    // The destination is the rest of the line, or one quoted field
+    let destination = match rest.trim() {
+        quoted if quoted.starts_with('"') => match split_fields(quoted)?.as_slice() {
+            [destination] => destination.clone(),
+            _ => {
+                return Err(format!(
+                    "unexpected text after the destination of '{}'",
+                    storage
+                ))
+            }
+        },
+        destination => destination.to_string(),
+    };
+    let destination = destination.as_str();
+    if destination.is_empty() {
+        return Err("expected '<storage path> <destination>'".to_string());
+    }
+
+    let storage = storage.strip_prefix("./").unwrap_or(storage);
+    let storage_path = Path::new(storage);
+    if storage_path.is_absolute()
+        || storage_path
+            .components()
+            .any(|c| matches!(c, Component::ParentDir))
+    {
+        return Err(format!(
+            "storage path '{}' must be relative to the workspace root",
+            storage
+        ));
+    }
+
+    let expanded = expand_home(destination);
+    if !expanded.is_absolute() {
+        return Err(format!(
+            "destination '{}' must be absolute or start with ~",
+            destination
+        ));
+    }
+
+    Ok(TargetRule {
+        storage: storage.to_string(),
+        destination: expanded,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_destination_prefers_longest_match() {
+        let targets = TargetMap::parse(
+            "# dotfiles\nnvim/ /home/me/.config/nvim/\nnvim/init.lua /etc/nvim/sysinit.lua\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            targets.destination(Path::new("nvim/lua/plugins.lua")),
+            Some(PathBuf::from("/home/me/.config/nvim/lua/plugins.lua"))
+        );
+        assert_eq!(
+            targets.destination(Path::new("./nvim/init.lua")),
+            Some(PathBuf::from("/etc/nvim/sysinit.lua"))
+        );
+        assert_eq!(targets.destination(Path::new("nvimrc")), None);
+        assert_eq!(
+            targets.destination(Path::new(".vscode/settings.json")),
+            None
+        );
+
+        // Later mappings (such as local overrides) win ties
+        let targets =
+            TargetMap::parse("gitconfig /shared/gitconfig\ngitconfig /local/gitconfig").unwrap();
+        assert_eq!(
+            targets.destination(Path::new("gitconfig")),
+            Some(PathBuf::from("/local/gitconfig"))
+        );
+    }
+
+    #[test]
+    fn test_quoted_storage_paths() {
+        let targets = TargetMap::parse(
+            "\"My Notes/\" /home/me/notes/\n\"caf\\303\\251.txt\" \"/home/me/my caf\u{e9}.txt\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            targets.destination(Path::new("My Notes/todo list.md")),
+            Some(PathBuf::from("/home/me/notes/todo list.md"))
+        );
+        assert_eq!(
+            targets.destination(Path::new("caf\u{e9}.txt")),
+            Some(PathBuf::from("/home/me/my caf\u{e9}.txt"))
+        );
+        let err = TargetMap::parse("\"open /tmp/x").unwrap_err().to_string();
+        assert!(err.contains("unterminated quote"), "{}", err);
+    }
+
+    #[test]
+    fn test_destination_expands_home() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+        let targets = TargetMap::parse("gitconfig ~/.gitconfig").unwrap();
+        assert_eq!(
+            targets.destination(Path::new("gitconfig")),
+            Some(home.join(".gitconfig"))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        for (content, expected) in [
+            ("gitconfig", "line 1: expected"),
+            ("ok /tmp/ok\n../escape /tmp/x", "line 2: storage path"),
+            ("/abs /tmp/x", "must be relative"),
+            ("gitconfig relative/path", "must be absolute"),
+        ] {
+            let err = TargetMap::parse(content).unwrap_err().to_string();
+            assert!(err.contains(expected), "{} -> {}", content, err);
+        }
+    }
+}
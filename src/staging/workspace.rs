@@ -59,17 +59,18 @@ pub fn is_symlink(path: &Path) -> Result<bool> {
 ///
 /// # Arguments
 ///
+/// * `root` - Workspace root that relative paths are resolved against
 /// * `path` - Path to check (can be relative or absolute)
 ///
 /// # Returns
 ///
 /// `true` if the file is tracked by Git, `false` otherwise
-pub fn is_git_tracked(path: &Path) -> Result<bool> {
+pub fn is_git_tracked(root: &Path, path: &Path) -> Result<bool> {
     // Determine the directory to search from
     let search_from = if path.is_absolute() {
         path.parent().unwrap_or(path)
     } else {
-        Path::new(".")
+        root
     };
 
     // Try to discover project's Git repository
@@ -82,12 +83,9 @@ pub fn is_git_tracked(path: &Path) -> Result<bool> {
     let index = repo.index().map_err(JinError::Git)?;
 
     // Normalize path relative to repo workdir
-    let workdir = repo.workdir().unwrap_or_else(|| Path::new("."));
-    let rel_path = if path.is_absolute() {
-        path.strip_prefix(workdir).unwrap_or(path)
-    } else {
-        path
-    };
+    let workdir = repo.workdir().unwrap_or(root);
+    let absolute = root.join(path);
+    let rel_path = absolute.strip_prefix(workdir).unwrap_or(path);
 
     // Check if file is in the index
     Ok(index.get_path(rel_path, 0).is_some())
@@ -120,8 +118,12 @@ pub fn get_file_mode(_path: &Path) -> u32 {
 
 /// Walk a directory recursively and return all file paths
 ///
+/// The returned paths start with `path`, as given; only the file system
+/// access is resolved against `root`.
+///
 /// # Arguments
 ///
+/// * `root` - Workspace root that a relative `path` is resolved against
 /// * `path` - Path to the directory to walk
 ///
 /// # Returns
@@ -131,18 +133,18 @@ pub fn get_file_mode(_path: &Path) -> u32 {
 /// # Errors
 ///
 /// Returns `JinError::Io` if the directory cannot be read
-pub fn walk_directory(path: &Path) -> Result<Vec<PathBuf>> {
+pub fn walk_directory(root: &Path, path: &Path) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
-    walk_directory_recursive(path, &mut files)?;
+    walk_directory_recursive(root, path, &mut files)?;
     Ok(files)
 }
 
-fn walk_directory_recursive(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
-    for entry in std::fs::read_dir(path)? {
+fn walk_directory_recursive(root: &Path, path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(root.join(path))? {
         let entry = entry?;
-        let entry_path = entry.path();
-        if entry_path.is_dir() {
-            walk_directory_recursive(&entry_path, files)?;
+        let entry_path = path.join(entry.file_name());
+        if entry.path().is_dir() {
+            walk_directory_recursive(root, &entry_path, files)?;
         } else {
             files.push(entry_path);
         }
@@ -159,6 +161,7 @@ fn walk_directory_recursive(path: &Path, files: &mut Vec<PathBuf>) -> Result<()>
 ///
 /// # Arguments
 ///
+/// * `root` - Workspace root the tracked files are relative to
 /// * `metadata` - The workspace metadata containing expected file hashes
 /// * `repo` - The Jin repository (used to compute Git blob hashes)
 ///
@@ -168,6 +171,7 @@ fn walk_directory_recursive(path: &Path, files: &mut Vec<PathBuf>) -> Result<()>
 /// - `Ok(None)` - All files match (workspace is attached)
 /// - `Err(JinError)` - Error reading files or computing hashes
 fn detect_file_mismatch(
+    root: &Path,
     metadata: &WorkspaceMetadata,
     repo: &JinRepo,
 ) -> Result<Option<Vec<PathBuf>>> {
@@ -175,14 +179,15 @@ fn detect_file_mismatch(
 
     // Iterate through tracked files in metadata
     for (path, stored_hash) in metadata.files.iter() {
-        // Check if file exists in workspace
-        if !path.exists() {
+        // Check if file exists where it was applied
+        let location = root.join(metadata.location(path));
+        if !location.exists() {
             modified_files.push(path.clone());
             continue;
         }
 
         // Compute current hash using Git blob hash
-        let content = std::fs::read(path)?;
+        let content = std::fs::read(&location)?;
         let oid = repo.inner().blob(&content)?;
         let current_hash = oid.to_string();
 
@@ -202,8 +207,13 @@ fn detect_file_mismatch(
 /// Detect if workspace metadata references non-existent layer commits
 ///
 /// This function checks if the layer refs stored in WorkspaceMetadata
-/// (applied_layers) still exist in the Jin repository. If any referenced
-/// layer has been deleted, this function returns the list of missing refs.
+/// still exist in the Jin repository. If any referenced layer has been
+/// deleted, this function returns the list of missing refs.
+///
+/// The refs recorded with their commit (`layer_commits`) are checked: only
+/// layers that had a commit when the workspace was applied can go missing,
+/// while applied layers that were never written simply have no ref.
+/// Metadata without recorded commits falls back to `applied_layers`.
 ///
 /// # Arguments
 ///
@@ -219,19 +229,23 @@ fn detect_missing_commits(
     metadata: &WorkspaceMetadata,
     repo: &JinRepo,
 ) -> Result<Option<Vec<String>>> {
-    let mut missing_refs = Vec::new();
-
-    // Check if each applied layer ref exists
-    for layer_name in &metadata.applied_layers {
-        // Convert layer name to ref path
+    let ref_paths: Vec<String> = if metadata.layer_commits.is_empty() {
         // Layer names are stored like "mode/claude" or "scope/default"
         // We need to convert to "refs/jin/layers/mode/claude"
-        let ref_path = format!("refs/jin/layers/{}", layer_name);
+        metadata
+            .applied_layers
+            .iter()
+            .map(|layer_name| format!("refs/jin/layers/{}", layer_name))
+            .collect()
+    } else {
+        metadata.layer_commits.keys().cloned().collect()
+    };
 
-        if !repo.ref_exists(&ref_path) {
-            missing_refs.push(ref_path);
-        }
-    }
+    let mut missing_refs: Vec<String> = ref_paths
+        .into_iter()
+        .filter(|ref_path| !repo.ref_exists(ref_path))
+        .collect();
+    missing_refs.sort();
 
     Ok(if missing_refs.is_empty() {
         None
@@ -240,6 +254,59 @@ fn detect_missing_commits(
     })
 }
 
+/// Find layers that changed a workspace file since it was last applied
+///
+/// A file written by `jin apply` is a merge of the layers that were current
+/// at the time. If one of those layers has since moved and now holds a
+/// different version of the file, staging the workspace copy would commit
+/// the older merged content and silently revert that change.
+///
+/// # Arguments
+///
+/// * `path` - Workspace-relative path of the file being staged
+/// * `metadata` - The workspace metadata from the last apply
+/// * `repo` - The Jin repository
+///
+/// # Returns
+///
+/// Layer paths (e.g., `mode/claude`) whose version of the file differs from
+/// the one that was merged. Empty if the file was not generated by `jin
+/// apply`, or if the metadata predates layer commit tracking.
+pub fn stale_merge_layers(
+    path: &Path,
+    metadata: &WorkspaceMetadata,
+    repo: &JinRepo,
+) -> Result<Vec<String>> {
+    if !metadata.files.contains_key(path) {
+        return Ok(Vec::new());
+    }
+
+    let git_repo = repo.inner();
+    let blob_at = |commit: Option<git2::Oid>| -> Option<git2::Oid> {
+        let tree = git_repo.find_commit(commit?).ok()?.tree().ok()?;
+        tree.get_path(path).ok().map(|entry| entry.id())
+    };
+
+    let mut layers = Vec::new();
+    for (ref_path, applied_commit) in &metadata.layer_commits {
+        let applied = git2::Oid::from_str(applied_commit).ok();
+        let current = git_repo.refname_to_id(ref_path).ok();
+        if current == applied {
+            continue;
+        }
+
+        if blob_at(applied) != blob_at(current) {
+            let layer = ref_path
+                .strip_prefix("refs/jin/layers/")
+                .unwrap_or(ref_path);
+            layers.push(layer.trim_end_matches("/_").to_string());
+        }
+    }
+
+    layers.sort();
+    Ok(layers)
+}
+
 /// Detect if active context references deleted modes or scopes
 ///
 /// This function checks if the active mode and scope stored in ProjectContext
@@ -314,6 +381,7 @@ fn describe_context(context: &ProjectContext) -> String {
 ///
 /// # Arguments
 ///
+/// * `root` - Workspace root
 /// * `context` - The project context containing active mode/scope
 /// * `repo` - The Jin repository
 ///
@@ -322,16 +390,20 @@ fn describe_context(context: &ProjectContext) -> String {
 /// - `Ok(())` - Workspace is properly attached
 /// - `Err(JinError::DetachedWorkspace)` - Workspace is detached with details
 /// - `Err(JinError)` - Other error (e.g., loading metadata)
-pub fn validate_workspace_attached(context: &ProjectContext, repo: &JinRepo) -> Result<()> {
+pub fn validate_workspace_attached(
+    root: &Path,
+    context: &ProjectContext,
+    repo: &JinRepo,
+) -> Result<()> {
     // Fresh workspace - no metadata means no attachment to validate
-    let metadata = match WorkspaceMetadata::load() {
+    let metadata = match WorkspaceMetadata::load(root) {
         Ok(m) => m,
         Err(JinError::NotFound(_)) => return Ok(()),
         Err(e) => return Err(e),
     };
 
     // Condition 1: File mismatch (most specific - check first)
-    if let Some(modified_files) = detect_file_mismatch(&metadata, repo)? {
+    if let Some(modified_files) = detect_file_mismatch(root, &metadata, repo)? {
         let workspace_commit = repo
             .inner()
             .head()
@@ -357,8 +429,35 @@ pub fn validate_workspace_attached(context: &ProjectContext, repo: &JinRepo) ->
         });
     }
 
+    check_layers(context, repo, &metadata)
+}
+
+/// Validate that the workspace's layers and context still exist, ignoring
+/// modified workspace files
+///
+/// For operations whose purpose is to replace modified workspace files
+/// (`jin reset --hard`), where edited files are expected rather than a sign
+/// of detachment. A fresh workspace is always attached.
+pub fn validate_layers_attached(
+    root: &Path,
+    context: &ProjectContext,
+    repo: &JinRepo,
+) -> Result<()> {
+    match WorkspaceMetadata::load(root) {
+        Ok(metadata) => check_layers(context, repo, &metadata),
+        Err(JinError::NotFound(_)) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Conditions 2 and 3 of [`validate_workspace_attached`]
+fn check_layers(
+    context: &ProjectContext,
+    repo: &JinRepo,
+    metadata: &WorkspaceMetadata,
+) -> Result<()> {
     // Condition 2: Missing commits/refs
-    if let Some(missing_refs) = detect_missing_commits(&metadata, repo)? {
+    if let Some(missing_refs) = detect_missing_commits(metadata, repo)? {
         return Err(JinError::DetachedWorkspace {
             workspace_commit: None,
             expected_layer_ref: "<unknown>".to_string(),
@@ -454,14 +553,14 @@ mod tests {
         std::fs::write(temp.path().join("file2.txt"), b"2").unwrap();
         std::fs::write(subdir.join("nested.txt"), b"3").unwrap();
 
-        let files = walk_directory(temp.path()).unwrap();
+        let files = walk_directory(temp.path(), Path::new("")).unwrap();
         assert_eq!(files.len(), 3);
     }
 
     #[test]
     fn test_walk_empty_directory() {
         let temp = TempDir::new().unwrap();
-        let files = walk_directory(temp.path()).unwrap();
+        let files = walk_directory(temp.path(), Path::new("")).unwrap();
         assert!(files.is_empty());
     }
 
@@ -495,7 +594,7 @@ mod tests {
 
         // Use absolute path - git2 will search for repo from file's parent
         // Since there's no Git repo in the temp directory, it should return false
-        let result = is_git_tracked(&file);
+        let result = is_git_tracked(temp.path(), &file);
 
         assert!(!result.unwrap());
     }
@@ -558,7 +657,7 @@ mod tests {
 
         // No metadata exists - should return Ok(())
         let context = ProjectContext::default();
-        let result = validate_workspace_attached(&context, &repo);
+        let result = validate_workspace_attached(temp.path(), &context, &repo);
         assert!(result.is_ok());
 
         std::env::remove_var("JIN_DIR");
@@ -571,7 +670,7 @@ mod tests {
         let repo = JinRepo::create_at(&repo_path).unwrap();
 
         let metadata = WorkspaceMetadata::new();
-        let result = detect_file_mismatch(&metadata, &repo).unwrap();
+        let result = detect_file_mismatch(temp.path(), &metadata, &repo).unwrap();
         assert!(result.is_none()); // No files = no mismatch
     }
 
@@ -587,7 +686,7 @@ mod tests {
         let mut metadata = WorkspaceMetadata::new();
         metadata.add_file(PathBuf::from("nonexistent.txt"), "abc123".to_string());
 
-        let result = detect_file_mismatch(&metadata, &repo).unwrap();
+        let result = detect_file_mismatch(temp.path(), &metadata, &repo).unwrap();
         assert!(result.is_some());
         assert_eq!(result.unwrap().len(), 1);
     }
@@ -616,7 +715,7 @@ mod tests {
         std::fs::write(&file_path, b"modified content").unwrap();
 
         // Detect mismatch
-        let result = detect_file_mismatch(&metadata, &repo).unwrap();
+        let result = detect_file_mismatch(temp.path(), &metadata, &repo).unwrap();
         assert!(result.is_some());
         assert_eq!(result.unwrap().len(), 1);
     }
@@ -642,7 +741,7 @@ mod tests {
         metadata.add_file(file_path.clone(), hash);
 
         // No mismatch should be detected
-        let result = detect_file_mismatch(&metadata, &repo).unwrap();
+        let result = detect_file_mismatch(temp.path(), &metadata, &repo).unwrap();
         assert!(result.is_none());
     }
 
@@ -705,6 +804,137 @@ mod tests {
         assert_eq!(result.unwrap(), vec!["refs/jin/layers/mode/nonexistent"]);
     }
 
+    #[test]
+    fn test_detect_missing_commits_uses_recorded_commits() {
+        let temp = TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join(".jin")).unwrap();
+        let commit = commit_layer_file(&repo, "refs/jin/layers/global", "a.json", b"{}", None);
+
+        // Applied layers without a commit (never written) are not missing
+        let mut metadata = WorkspaceMetadata::new();
+        metadata.applied_layers = vec!["global-base".to_string(), "user-local".to_string()];
+        metadata
+            .layer_commits
+            .insert("refs/jin/layers/global".to_string(), commit.to_string());
+        assert!(detect_missing_commits(&metadata, &repo).unwrap().is_none());
+
+        metadata.layer_commits.insert(
+            "refs/jin/layers/mode/gone/_".to_string(),
+            commit.to_string(),
+        );
+        assert_eq!(
+            detect_missing_commits(&metadata, &repo).unwrap(),
+            Some(vec!["refs/jin/layers/mode/gone/_".to_string()])
+        );
+    }
+
+    /// Commit a single-file tree to a layer ref, returning the commit OID
+    fn commit_layer_file(
+        repo: &JinRepo,
+        ref_path: &str,
+        file: &str,
+        content: &[u8],
+        parent: Option<git2::Oid>,
+    ) -> git2::Oid {
+        let sig = git2::Signature::now("test", "test@test.com").unwrap();
+        let blob = repo.inner().blob(content).unwrap();
+        let tree_oid = {
+            let mut builder = repo.inner().treebuilder(None).unwrap();
+            builder.insert(file, blob, 0o100644).unwrap();
+            builder.write().unwrap()
+        };
+        let tree = repo.inner().find_tree(tree_oid).unwrap();
+        let parents: Vec<git2::Commit> = parent
+            .map(|p| repo.inner().find_commit(p).unwrap())
+            .into_iter()
+            .collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        repo.inner()
+            .commit(Some(ref_path), &sig, &sig, "test", &tree, &parent_refs)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_stale_merge_layers_detects_moved_layer() {
+        let temp = TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join(".jin")).unwrap();
+
+        let applied = commit_layer_file(
+            &repo,
+            "refs/jin/layers/mode/claude/_",
+            "config.json",
+            b"{\"a\": 1}",
+            None,
+        );
+        let mut metadata = WorkspaceMetadata::new();
+        metadata.add_file(PathBuf::from("config.json"), "deadbeef".to_string());
+        metadata.layer_commits.insert(
+            "refs/jin/layers/mode/claude/_".to_string(),
+            applied.to_string(),
+        );
+
+        // Layer unchanged since apply
+        let path = Path::new("config.json");
+        assert!(stale_merge_layers(path, &metadata, &repo)
+            .unwrap()
+            .is_empty());
+
+        // Layer moved and changed the file
+        commit_layer_file(
+            &repo,
+            "refs/jin/layers/mode/claude/_",
+            "config.json",
+            b"{\"a\": 2}",
+            Some(applied),
+        );
+        assert_eq!(
+            stale_merge_layers(path, &metadata, &repo).unwrap(),
+            vec!["mode/claude".to_string()]
+        );
+
+        // Files not written by apply are never stale
+        assert!(
+            stale_merge_layers(Path::new("other.json"), &metadata, &repo)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_stale_merge_layers_ignores_unrelated_changes() {
+        let temp = TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join(".jin")).unwrap();
+
+        let applied =
+            commit_layer_file(&repo, "refs/jin/layers/global", "config.json", b"{}", None);
+        let mut metadata = WorkspaceMetadata::new();
+        metadata.add_file(PathBuf::from("config.json"), "deadbeef".to_string());
+        metadata
+            .layer_commits
+            .insert("refs/jin/layers/global".to_string(), applied.to_string());
+
+        // The layer moved, but this file's blob is the same
+        let sig = git2::Signature::now("test", "test@test.com").unwrap();
+        let tree = repo.inner().find_commit(applied).unwrap().tree().unwrap();
+        let parent = repo.inner().find_commit(applied).unwrap();
+        repo.inner()
+            .commit(
+                Some("refs/jin/layers/global"),
+                &sig,
+                &sig,
+                "touch",
+                &tree,
+                &[&parent],
+            )
+            .unwrap();
+
+        assert!(
+            stale_merge_layers(Path::new("config.json"), &metadata, &repo)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_detect_invalid_context_no_mode_or_scope() {
         let temp = TempDir::new().unwrap();
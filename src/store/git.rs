@@ -0,0 +1,200 @@
+//! The Git backend: layers are commits under `refs/jin/`, in the Jin
+//! repository
+
+use super::{changed, LayerStore, Revision, RevisionInfo};
+use crate::core::{JinError, Result};
+use crate::git::refs::touch_refs_stamp;
+use crate::git::{JinRepo, ObjectOps, RefOps, TreeOps};
+use chrono::{TimeZone, Utc};
+use git2::{ErrorCode, ObjectType, Oid, Tree};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+impl JinRepo {
+    fn revision_tree(&self, revision: &Revision) -> Result<Tree<'_>> {
+        let oid = Oid::from_str(revision.as_str())?;
+        Ok(self.inner().find_commit(oid)?.tree()?)
+    }
+
+    /// Blob ID of a file in a revision, `None` if it isn't a file there
+    fn file_oid(&self, revision: &Revision, path: &Path) -> Result<Option<Oid>> {
+        match self.revision_tree(revision)?.get_path(path) {
+            Ok(entry) if entry.kind() == Some(ObjectType::Blob) => Ok(Some(entry.id())),
+            Ok(_) => Ok(None),
+            Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl LayerStore for JinRepo {
+    fn head(&self, layer_ref: &str) -> Result<Option<Revision>> {
+        match self.inner().find_reference(layer_ref) {
+            Ok(reference) => Ok(reference
+                .resolve()
+                .ok()
+                .and_then(|r| r.target())
+                .map(|oid| Revision::new(oid.to_string()))),
+            Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn layers(&self, pattern: &str) -> Result<Vec<String>> {
+        self.list_refs(pattern)
+    }
+
+    fn files(&self, revision: &Revision) -> Result<Vec<PathBuf>> {
+        let tree = self.revision_tree(revision)?;
+        let mut files: Vec<PathBuf> = self
+            .list_tree_files(tree.id())?
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    fn read(&self, revision: &Revision, path: &Path) -> Result<Option<Vec<u8>>> {
+        match self.file_oid(revision, path)? {
+            Some(oid) => Ok(Some(self.read_blob_content(oid)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn size(&self, revision: &Revision, path: &Path) -> Result<Option<u64>> {
+        match self.file_oid(revision, path)? {
+            Some(oid) => Ok(Some(self.inner().odb()?.read_header(oid)?.0 as u64)),
+            None => Ok(None),
+        }
+    }
+
+    fn write(
+        &self,
+        layer_ref: &str,
+        expected: Option<&Revision>,
+        files: &BTreeMap<PathBuf, Vec<u8>>,
+        message: &str,
+    ) -> Result<Revision> {
+        let entries = files
+            .iter()
+            .map(|(path, content)| {
+                let path = path.to_str().ok_or_else(|| {
+                    JinError::Other(format!("Path is not valid UTF-8: {}", path.display()))
+                })?;
+                Ok((path.replace('\\', "/"), self.create_blob(content)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let tree = self.create_tree_from_paths(&entries)?;
+        let parents = match expected {
+            Some(revision) => vec![Oid::from_str(revision.as_str())?],
+            None => Vec::new(),
+        };
+        let commit = Revision::new(
+            self.create_commit(None, message, tree, &parents)?
+                .to_string(),
+        );
+        self.compare_and_swap(layer_ref, expected, Some(&commit))?;
+        Ok(commit)
+    }
+
+    fn compare_and_swap(
+        &self,
+        layer_ref: &str,
+        expected: Option<&Revision>,
+        new: Option<&Revision>,
+    ) -> Result<()> {
+        let message = "jin: layer update";
+        let result = match (expected, new) {
+            // Creating fails if the ref appeared meanwhile
+            (None, Some(new)) => self
+                .inner()
+                .reference(layer_ref, Oid::from_str(new.as_str())?, false, message)
+                .map(|_| ()),
+            // Updating checks the old value under the ref lock
+            (Some(expected), Some(new)) => self
+                .inner()
+                .reference_matching(
+                    layer_ref,
+                    Oid::from_str(new.as_str())?,
+                    true,
+                    Oid::from_str(expected.as_str())?,
+                    message,
+                )
+                .map(|_| ()),
+            (expected, None) => {
+                if self.head(layer_ref)?.as_ref() != expected {
+                    return Err(changed(layer_ref));
+                }
+                match self.inner().find_reference(layer_ref) {
+                    Ok(mut reference) => reference.delete(),
+                    Err(e) if e.code() == ErrorCode::NotFound => Ok(()),
+                    Err(e) => Err(e),
+                }
+            }
+        };
+        match result {
+            Ok(()) => {
+                touch_refs_stamp(self.path());
+                Ok(())
+            }
+            Err(e)
+                if matches!(
+                    e.code(),
+                    ErrorCode::Exists | ErrorCode::Modified | ErrorCode::NotFound
+                ) =>
+            {
+                Err(changed(layer_ref))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn history(&self, layer_ref: &str, limit: usize) -> Result<Vec<RevisionInfo>> {
+        let Some(head) = self.head(layer_ref)? else {
+            return Ok(Vec::new());
+        };
+        let mut history = Vec::new();
+        let mut next = Some(self.inner().find_commit(Oid::from_str(head.as_str())?)?);
+        while let Some(commit) = next.filter(|_| history.len() < limit) {
+            let author = commit.author();
+            history.push(RevisionInfo {
+                revision: Revision::new(commit.id().to_string()),
+                message: commit.message().unwrap_or_default().to_string(),
+                author: author.name().unwrap_or_default().to_string(),
+                time: Utc
+                    .timestamp_opt(commit.time().seconds(), 0)
+                    .single()
+                    .unwrap_or_default(),
+            });
+            next = commit.parent(0).ok();
+        }
+        Ok(history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_git_store_contract() {
+        let temp = TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join(".jin")).unwrap();
+        crate::store::contract::check(&repo);
+    }
+
+    #[test]
+    fn test_missing_expected_ref_is_a_conflict() {
+        let temp = TempDir::new().unwrap();
+        let repo = JinRepo::create_at(&temp.path().join(".jin")).unwrap();
+        let written = repo
+            .write("refs/jin/layers/global", None, &BTreeMap::new(), "Empty")
+            .unwrap();
+        assert!(matches!(
+            repo.compare_and_swap("refs/jin/layers/other", Some(&written), Some(&written)),
+            Err(JinError::RefsChanged { .. })
+        ));
+    }
+}
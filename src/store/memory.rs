@@ -0,0 +1,205 @@
+//! An in-memory backend, for tests and as a template for new backends
+
+use super::{changed, LayerStore, Revision, RevisionInfo};
+use crate::core::{JinError, Result};
+use chrono::Utc;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// One stored revision
+#[derive(Debug, Clone)]
+struct Snapshot {
+    files: BTreeMap<PathBuf, Vec<u8>>,
+    parent: Option<Revision>,
+    info: RevisionInfo,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    refs: BTreeMap<String, Revision>,
+    revisions: HashMap<Revision, Snapshot>,
+}
+
+/// Layers kept in memory; revisions are numbered in the order written
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    state: Mutex<State>,
+}
+
+impl MemoryStore {
+    /// An empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl State {
+    fn snapshot(&self, revision: &Revision) -> Result<&Snapshot> {
+        self.revisions
+            .get(revision)
+            .ok_or_else(|| JinError::NotFound(format!("Revision {}", revision)))
+    }
+
+    fn swap(
+        &mut self,
+        layer_ref: &str,
+        expected: Option<&Revision>,
+        new: Option<&Revision>,
+    ) -> Result<()> {
+        if self.refs.get(layer_ref) != expected {
+            return Err(changed(layer_ref));
+        }
+        match new {
+            Some(new) => {
+                self.snapshot(new)?;
+                self.refs.insert(layer_ref.to_string(), new.clone());
+            }
+            None => {
+                self.refs.remove(layer_ref);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl LayerStore for MemoryStore {
+    fn head(&self, layer_ref: &str) -> Result<Option<Revision>> {
+        Ok(self.state().refs.get(layer_ref).cloned())
+    }
+
+    fn layers(&self, pattern: &str) -> Result<Vec<String>> {
+        Ok(self
+            .state()
+            .refs
+            .keys()
+            .filter(|name| glob_match(pattern, name))
+            .cloned()
+            .collect())
+    }
+
+    fn files(&self, revision: &Revision) -> Result<Vec<PathBuf>> {
+        Ok(self
+            .state()
+            .snapshot(revision)?
+            .files
+            .keys()
+            .cloned()
+            .collect())
+    }
+
+    fn read(&self, revision: &Revision, path: &Path) -> Result<Option<Vec<u8>>> {
+        Ok(self.state().snapshot(revision)?.files.get(path).cloned())
+    }
+
+    fn size(&self, revision: &Revision, path: &Path) -> Result<Option<u64>> {
+        Ok(self
+            .state()
+            .snapshot(revision)?
+            .files
+            .get(path)
+            .map(|content| content.len() as u64))
+    }
+
+    fn write(
+        &self,
+        layer_ref: &str,
+        expected: Option<&Revision>,
+        files: &BTreeMap<PathBuf, Vec<u8>>,
+        message: &str,
+    ) -> Result<Revision> {
+        let mut state = self.state();
+        let revision = Revision::new((state.revisions.len() + 1).to_string());
+        state.revisions.insert(
+            revision.clone(),
+            Snapshot {
+                files: files.clone(),
+                parent: expected.cloned(),
+                info: RevisionInfo {
+                    revision: revision.clone(),
+                    message: message.to_string(),
+                    author: "jin".to_string(),
+                    time: Utc::now(),
+                },
+            },
+        );
+        state.swap(layer_ref, expected, Some(&revision))?;
+        Ok(revision)
+    }
+
+    fn compare_and_swap(
+        &self,
+        layer_ref: &str,
+        expected: Option<&Revision>,
+        new: Option<&Revision>,
+    ) -> Result<()> {
+        self.state().swap(layer_ref, expected, new)
+    }
+
+    fn history(&self, layer_ref: &str, limit: usize) -> Result<Vec<RevisionInfo>> {
+        let state = self.state();
+        let mut history = Vec::new();
+        let mut next = state.refs.get(layer_ref).cloned();
+        while let Some(revision) = next.filter(|_| history.len() < limit) {
+            let snapshot = state.snapshot(&revision)?;
+            history.push(snapshot.info.clone());
+            next = snapshot.parent.clone();
+        }
+        Ok(history)
+    }
+}
+
+/// Ref globbing as Git does it: `*` matches any run of characters
+/// (including `/`), `?` any one character
+fn glob_match(pattern: &str, name: &str) -> bool {
+    match pattern.chars().next() {
+        None => name.is_empty(),
+        Some('*') => {
+            let rest = &pattern[1..];
+            name.char_indices()
+                .map(|(i, _)| i)
+                .chain(std::iter::once(name.len()))
+                .any(|i| glob_match(rest, &name[i..]))
+        }
+        Some(c) => {
+            let mut chars = name.chars();
+            match chars.next() {
+                Some(n) if c == '?' || c == n => {
+                    glob_match(&pattern[c.len_utf8()..], chars.as_str())
+                }
+                _ => false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_store_contract() {
+        crate::store::contract::check(&MemoryStore::new());
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match(
+            "refs/jin/modes/*/_mode",
+            "refs/jin/modes/claude/_mode"
+        ));
+        assert!(glob_match(
+            "refs/jin/layers/*",
+            "refs/jin/layers/mode/claude/_"
+        ));
+        assert!(!glob_match(
+            "refs/jin/modes/*/_mode",
+            "refs/jin/modes/claude"
+        ));
+        assert!(glob_match("refs/?", "refs/a"));
+    }
+}
@@ -0,0 +1,195 @@
+//! Storage backends for layer content
+//!
+//! Commands historically talk to [`JinRepo`](crate::git::JinRepo) and git2
+//! directly. [`LayerStore`] names the handful of operations layers actually
+//! need, so that a different backend (an object store caching layers for CI,
+//! a jj or libgit-free repository) can be tried without rewriting commands:
+//!
+//! - reading a layer: its current [`Revision`], the files in a revision and
+//!   their content
+//! - writing a layer: a new revision holding a full snapshot of its files,
+//!   published only if the layer still points where the writer expects
+//! - moving layer refs with compare-and-swap
+//! - a layer's history
+//!
+//! Revisions are opaque strings to callers; only the backend that produced
+//! one can interpret it. Git ([`git`]) is the default backend and the only
+//! one commands open. [`MemoryStore`] keeps everything in memory, for tests
+//! and as a template for new backends.
+//!
+//! Layer merging reads through this trait; writes and history still go to
+//! git2 directly in most commands and are moved over as backends need them.
+
+pub mod git;
+pub mod memory;
+
+pub use memory::MemoryStore;
+
+use crate::core::Result;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// A backend-specific identifier of one state of a layer (a commit ID in Git)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Revision(String);
+
+impl Revision {
+    /// Wrap a backend identifier
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    /// The backend identifier
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Revision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// One entry of a layer's history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevisionInfo {
+    /// The revision
+    pub revision: Revision,
+    /// Its message
+    pub message: String,
+    /// Who wrote it
+    pub author: String,
+    /// When it was written
+    pub time: DateTime<Utc>,
+}
+
+/// Storage for layer content
+///
+/// Layers are addressed by their ref path (see
+/// [`Layer::ref_path`](crate::core::Layer::ref_path)). Missing layers and
+/// files are `None`, never errors.
+pub trait LayerStore {
+    /// Current revision of a layer, `None` if it has never been written
+    fn head(&self, layer_ref: &str) -> Result<Option<Revision>>;
+
+    /// Ref paths of layers matching a glob (e.g. `refs/jin/layers/mode/*`)
+    fn layers(&self, pattern: &str) -> Result<Vec<String>>;
+
+    /// Files in a revision, relative to the layer root
+    fn files(&self, revision: &Revision) -> Result<Vec<PathBuf>>;
+
+    /// Content of a file in a revision
+    fn read(&self, revision: &Revision, path: &Path) -> Result<Option<Vec<u8>>>;
+
+    /// Size of a file in a revision, without reading its content
+    fn size(&self, revision: &Revision, path: &Path) -> Result<Option<u64>>;
+
+    /// Write `files` as the full content of a new revision of the layer
+    /// and publish it, unless the layer no longer points at `expected`
+    /// (`None`: the layer must not exist yet)
+    ///
+    /// Fails with [`JinError::RefsChanged`](crate::core::JinError::RefsChanged)
+    /// when another writer got there first; nothing is published then.
+    fn write(
+        &self,
+        layer_ref: &str,
+        expected: Option<&Revision>,
+        files: &BTreeMap<PathBuf, Vec<u8>>,
+        message: &str,
+    ) -> Result<Revision>;
+
+    /// Point a layer at `new` (`None` deletes it) if it still points at
+    /// `expected`, failing like [`LayerStore::write`] otherwise
+    fn compare_and_swap(
+        &self,
+        layer_ref: &str,
+        expected: Option<&Revision>,
+        new: Option<&Revision>,
+    ) -> Result<()>;
+
+    /// The layer's revisions, newest first, following first parents
+    fn history(&self, layer_ref: &str, limit: usize) -> Result<Vec<RevisionInfo>>;
+}
+
+/// The error a failed compare-and-swap reports
+pub(crate) fn changed(layer_ref: &str) -> crate::core::JinError {
+    crate::core::JinError::RefsChanged {
+        operation: "a layer update".to_string(),
+        refs: layer_ref.to_string(),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod contract {
+    //! Behavior every backend must share
+
+    use super::*;
+    use crate::core::JinError;
+
+    pub fn check(store: &dyn LayerStore) {
+        let layer = "refs/jin/layers/global";
+        assert_eq!(store.head(layer).unwrap(), None);
+        assert!(store.history(layer, 10).unwrap().is_empty());
+
+        let mut files = BTreeMap::new();
+        files.insert(PathBuf::from("config.json"), b"{}".to_vec());
+        files.insert(
+            PathBuf::from(".claude/settings.json"),
+            b"{\"a\": 1}".to_vec(),
+        );
+        let first = store.write(layer, None, &files, "First").unwrap();
+        assert_eq!(store.head(layer).unwrap(), Some(first.clone()));
+        assert_eq!(
+            store.files(&first).unwrap(),
+            vec![
+                PathBuf::from(".claude/settings.json"),
+                PathBuf::from("config.json")
+            ]
+        );
+        assert_eq!(
+            store
+                .read(&first, Path::new(".claude/settings.json"))
+                .unwrap(),
+            Some(b"{\"a\": 1}".to_vec())
+        );
+        assert_eq!(store.read(&first, Path::new("missing")).unwrap(), None);
+        assert_eq!(
+            store.size(&first, Path::new("config.json")).unwrap(),
+            Some(2)
+        );
+
+        // A writer holding an outdated revision loses
+        assert!(matches!(
+            store.write(layer, None, &files, "Stale"),
+            Err(JinError::RefsChanged { .. })
+        ));
+        files.remove(Path::new("config.json"));
+        let second = store.write(layer, Some(&first), &files, "Second").unwrap();
+        assert_eq!(store.read(&second, Path::new("config.json")).unwrap(), None);
+        // Old revisions stay readable
+        assert!(store
+            .read(&first, Path::new("config.json"))
+            .unwrap()
+            .is_some());
+
+        let history = store.history(layer, 10).unwrap();
+        let messages: Vec<&str> = history.iter().map(|r| r.message.trim()).collect();
+        assert_eq!(messages, ["Second", "First"]);
+        assert_eq!(history[0].revision, second);
+        assert_eq!(store.history(layer, 1).unwrap().len(), 1);
+
+        let other = "refs/jin/layers/mode/claude/_";
+        store.compare_and_swap(other, None, Some(&first)).unwrap();
+        assert!(matches!(
+            store.compare_and_swap(other, Some(&second), None),
+            Err(JinError::RefsChanged { .. })
+        ));
+        assert_eq!(store.layers("refs/jin/layers/*").unwrap().len(), 2);
+        store.compare_and_swap(other, Some(&first), None).unwrap();
+        assert_eq!(store.head(other).unwrap(), None);
+        assert_eq!(store.layers("refs/jin/layers/*").unwrap(), [layer]);
+    }
+}
@@ -148,7 +148,7 @@ pub fn setup_unit_test() -> UnitTestContext {
 
     // Initialize Jin repository (before setting current directory)
     // CRITICAL: Don't ignore the result - the repo MUST be initialized
-    JinRepo::open_or_create().expect("Failed to initialize Jin repository");
+    JinRepo::open_or_create(Path::new(".")).expect("Failed to initialize Jin repository");
 
     // CRITICAL: Create .jin directory structure using absolute path
     // Do this BEFORE setting current directory to avoid relative path issues
@@ -0,0 +1,203 @@
+//! Isolated Jin environments for tests
+//!
+//! Available with the `test-utils` feature, for tools and plugins that drive
+//! the `jin` binary in their own test suites:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! jin = { version = "*", features = ["test-utils"] }
+//! ```
+//!
+//! A [`JinEnv`] owns a temporary directory holding a workspace and its own
+//! Jin repository. Every command it builds runs in that workspace with
+//! `JIN_DIR` pointing at that repository, passed per command rather than
+//! through the process environment, so tests using separate environments
+//! can run in parallel. Everything is deleted when the environment is
+//! dropped.
+//!
+//! ```ignore
+//! use jin::testing::JinEnv;
+//!
+//! let env = JinEnv::initialized()?;
+//! let mode = JinEnv::unique_name("mode");
+//! env.run_ok(&["mode", "create", &mode])?;
+//! env.write("settings.json", r#"{"theme": "dark"}"#)?;
+//! env.run_ok(&["add", "settings.json", "--global"])?;
+//! env.run_ok(&["commit", "-m", "Add settings"])?;
+//! ```
+//!
+//! The binary is `$JIN_BIN` when set, `jin` from `PATH` otherwise, or the
+//! one given to [`JinEnv::with_binary`] (Jin's own integration tests pass
+//! `env!("CARGO_BIN_EXE_jin")`).
+
+use crate::core::{JinError, Result};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tempfile::TempDir;
+
+/// A temporary workspace with its own Jin repository
+pub struct JinEnv {
+    /// Deleted on drop, with everything below it
+    tempdir: TempDir,
+    /// Project directory commands run in
+    workspace: PathBuf,
+    /// Isolated Jin repository (`JIN_DIR`)
+    jin_dir: PathBuf,
+    /// `jin` binary to run
+    binary: PathBuf,
+}
+
+impl JinEnv {
+    /// A fresh environment running `$JIN_BIN` (or `jin` from `PATH`)
+    pub fn new() -> Result<Self> {
+        let binary = std::env::var_os("JIN_BIN")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("jin"));
+        Self::with_binary(binary)
+    }
+
+    /// A fresh environment running `binary`
+    pub fn with_binary(binary: impl Into<PathBuf>) -> Result<Self> {
+        let tempdir = TempDir::new()?;
+        let workspace = tempdir.path().join("workspace");
+        let jin_dir = tempdir.path().join("jin");
+        std::fs::create_dir(&workspace)?;
+        Ok(Self {
+            tempdir,
+            workspace,
+            jin_dir,
+            binary: binary.into(),
+        })
+    }
+
+    /// A fresh environment whose workspace is a Git repository with Jin
+    /// initialized
+    pub fn initialized() -> Result<Self> {
+        let env = Self::new()?;
+        env.init()?;
+        Ok(env)
+    }
+
+    /// Make the workspace a Git repository and run `jin init` in it
+    pub fn init(&self) -> Result<()> {
+        git2::Repository::init(&self.workspace)?;
+        self.run_ok(&["init"])?;
+        Ok(())
+    }
+
+    /// Root of the temporary directory (holds the workspace and the Jin
+    /// repository; free for other files such as a bare remote)
+    pub fn root(&self) -> &Path {
+        self.tempdir.path()
+    }
+
+    /// The project directory
+    pub fn workspace(&self) -> &Path {
+        &self.workspace
+    }
+
+    /// The isolated Jin repository
+    pub fn jin_dir(&self) -> &Path {
+        &self.jin_dir
+    }
+
+    /// A `jin` command set up to run in this environment
+    pub fn command(&self) -> Command {
+        let mut command = Command::new(&self.binary);
+        command
+            .current_dir(&self.workspace)
+            .env("JIN_DIR", &self.jin_dir)
+            .env("NO_COLOR", "1");
+        command
+    }
+
+    /// Run `jin` with `args`, whatever its exit status
+    pub fn run(&self, args: &[&str]) -> Result<Output> {
+        self.command()
+            .args(args)
+            .output()
+            .map_err(|e| JinError::Other(format!("Could not run {}: {}", self.binary.display(), e)))
+    }
+
+    /// Run `jin` with `args` and return its stdout, failing unless it
+    /// succeeds
+    pub fn run_ok(&self, args: &[&str]) -> Result<String> {
+        let output = self.run(args)?;
+        if !output.status.success() {
+            return Err(JinError::Other(format!(
+                "'jin {}' failed ({}): {}",
+                args.join(" "),
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Write a workspace file, creating its directories
+    pub fn write(&self, path: impl AsRef<Path>, content: impl AsRef<[u8]>) -> Result<()> {
+        let path = self.workspace.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Read a workspace file
+    pub fn read(&self, path: impl AsRef<Path>) -> Result<String> {
+        Ok(std::fs::read_to_string(self.workspace.join(path))?)
+    }
+
+    /// A name no other test in this process uses, for modes and scopes
+    /// created in shared fixtures (e.g., `mode_4242_7`)
+    pub fn unique_name(prefix: &str) -> String {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        format!(
+            "{}_{}_{}",
+            prefix,
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_environments_are_isolated() {
+        let a = JinEnv::with_binary("jin").unwrap();
+        let b = JinEnv::with_binary("jin").unwrap();
+        assert_ne!(a.jin_dir(), b.jin_dir());
+        assert!(a.workspace().starts_with(a.root()));
+
+        a.write("config/app.json", "{}").unwrap();
+        assert_eq!(a.read("config/app.json").unwrap(), "{}");
+        assert!(b.read("config/app.json").is_err());
+
+        let command = a.command();
+        assert_eq!(command.get_current_dir(), Some(a.workspace()));
+        assert!(command
+            .get_envs()
+            .any(|(key, value)| key == "JIN_DIR" && value == Some(a.jin_dir().as_os_str())));
+
+        let root = a.root().to_path_buf();
+        drop(a);
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn test_unique_names() {
+        assert_ne!(JinEnv::unique_name("mode"), JinEnv::unique_name("mode"));
+        assert!(JinEnv::unique_name("scope").starts_with("scope_"));
+    }
+
+    #[test]
+    fn test_missing_binary_is_an_error() {
+        let env = JinEnv::with_binary("/nonexistent/jin").unwrap();
+        assert!(env.run(&["--version"]).is_err());
+    }
+}
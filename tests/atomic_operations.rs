@@ -224,7 +224,7 @@ fn test_state_consistent_after_failure() -> Result<(), Box<dyn std::error::Error
 
     // Repair state
     jin()
-        .arg("repair")
+        .args(["repair", "--fix"])
         .current_dir(project_path)
         .env("JIN_DIR", jin_dir)
         .assert()
@@ -548,3 +548,113 @@ fn test_operations_isolated() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Test that an interrupted commit is reported by ID and can be finished
+#[test]
+fn test_resume_interrupted_transaction() -> Result<(), Box<dyn std::error::Error>> {
+    use jin::git::{
+        JinRepo, LayerUpdate, ObjectOps, RefOps, TransactionLog, TransactionState, TreeEntry,
+    };
+    use jin::Layer;
+
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    let mode_name = format!("resume_{}", unique_test_id());
+    create_mode(&mode_name, Some(jin_dir))?;
+    jin()
+        .args(["mode", "use", &mode_name])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    fs::write(project_path.join("test.txt"), "v1")?;
+    jin()
+        .args(["add", "test.txt", "--mode"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "v1"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    // Stage v2, then simulate a commit interrupted after its commit object
+    // was written but before the layer ref moved
+    fs::write(project_path.join("test.txt"), "v2")?;
+    jin()
+        .args(["add", "test.txt", "--mode"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    let ref_path = format!("refs/jin/layers/mode/{}/_", mode_name);
+    let repo = JinRepo::open_at(jin_dir)?;
+    let v1 = repo.resolve_ref(&ref_path)?;
+    let blob = repo.create_blob(b"v2")?;
+    let tree = repo.create_tree(&[TreeEntry::blob("test.txt", blob)])?;
+    let v2 = repo.create_commit(None, "v2", tree, &[v1])?;
+
+    let mut log = TransactionLog::with_base_path("v2", project_path.to_path_buf());
+    log.updates.push(LayerUpdate::new(
+        Layer::ModeBase,
+        Some(mode_name.clone()),
+        None,
+        None,
+        Some(v1),
+        v2,
+    ));
+    log.files = vec!["test.txt".to_string()];
+    log.state = TransactionState::Prepared;
+    log.save()?;
+    let id = log.id.clone();
+
+    // New commits are blocked with a pointer to the transaction
+    jin()
+        .args(["commit", "-m", "blocked"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(format!(
+            "jin repair --resume-txn {}",
+            id
+        )));
+
+    jin()
+        .arg("repair")
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(format!(
+            "Interrupted transaction {}",
+            id
+        )));
+
+    // A unique prefix of the ID is enough
+    jin()
+        .args(["repair", "--resume-txn", &id[..12]])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(format!(
+            "Finished transaction {}",
+            id
+        )));
+
+    assert_eq!(JinRepo::open_at(jin_dir)?.resolve_ref(&ref_path)?, v2);
+    assert_staging_not_contains(project_path, "test.txt", Some(jin_dir));
+    assert!(!project_path.join(".jin/.transaction_in_progress").exists());
+
+    Ok(())
+}
@@ -5,13 +5,16 @@
 //! - `.jin/.paused_apply.yaml` is written
 //! - Non-conflicting files are still applied
 //! - User is instructed to run `jin resolve`
+//!
+//! and that `--ours`, `--theirs` and `--prefer` settle conflicts instead.
 
 mod common;
-use common::fixtures::{setup_test_repo, unique_test_id};
+use common::fixtures::{setup_test_repo, unique_test_id, TestFixture};
 
 use assert_cmd::Command;
 use predicates::prelude::*;
 use std::fs;
+use std::path::PathBuf;
 
 /// Get a Command for the jin binary
 fn jin_cmd() -> Command {
@@ -813,3 +816,372 @@ fn test_text_file_conflicts_create_jinmerge() {
         ".jinmerge should contain at least one of the conflicting versions"
     );
 }
+
+#[test]
+fn test_conflict_explanation_printed_and_in_header() {
+    let fixture = setup_test_repo().unwrap();
+    let jin_dir = fixture.jin_dir.clone().unwrap();
+
+    let mode_name = format!("test_mode_{}", unique_test_id());
+    jin_cmd()
+        .args(["mode", "create", &mode_name])
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+    jin_cmd()
+        .args(["mode", "use", &mode_name])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    let config_path = fixture.path().join("settings.txt");
+    for (content, flag) in [
+        ("host=local\nport=80\n", "--global"),
+        ("host=local\nport=8080\n", "--mode"),
+    ] {
+        fs::write(&config_path, content).unwrap();
+        jin_cmd()
+            .args(["add", "settings.txt", flag])
+            .current_dir(fixture.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success();
+        jin_cmd()
+            .args(["commit", "-m", "Add settings"])
+            .current_dir(fixture.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success();
+    }
+    fs::remove_file(&config_path).unwrap();
+
+    let expected_layers = format!(
+        "Conflict between global (lower precedence) and mode/{} (higher precedence)",
+        mode_name
+    );
+    let expected_line = format!(
+        "line 2: global wants \"port=80\", mode/{} wants \"port=8080\"",
+        mode_name
+    );
+
+    jin_cmd()
+        .arg("apply")
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(expected_layers.clone()))
+        .stdout(predicate::str::contains(expected_line.clone()));
+
+    let jinmerge_path = fixture.path().join("settings.txt.jinmerge");
+    let jinmerge_content = fs::read_to_string(&jinmerge_path).unwrap();
+    assert!(jinmerge_content.contains(&format!("#: {}", expected_layers)));
+    assert!(jinmerge_content.contains(&expected_line));
+
+    // Resolve keeping the header; the summary must not leak into the file
+    let header: String = jinmerge_content
+        .lines()
+        .take_while(|l| l.starts_with('#'))
+        .map(|l| format!("{}\n", l))
+        .collect();
+    fs::write(&jinmerge_path, format!("{}host=local\nport=8080\n", header)).unwrap();
+
+    jin_cmd()
+        .args(["resolve", "settings.txt"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    let resolved = fs::read_to_string(&config_path).unwrap();
+    assert!(resolved.ends_with("host=local\nport=8080\n"));
+    assert!(!resolved.contains("#:"));
+}
+
+#[test]
+fn test_merge_driver_merges_layers() {
+    let fixture = setup_test_repo().unwrap();
+    let jin_dir = fixture.jin_dir.clone().unwrap();
+
+    // Register a driver that concatenates both sides
+    let config_toml = jin_dir.join("config.toml");
+    let mut config = fs::read_to_string(&config_toml).unwrap_or_default();
+    config.push_str("\n[[mergers]]\npattern = \"*.list\"\ncommand = \"cat %A %B\"\n");
+    fs::write(&config_toml, config).unwrap();
+
+    let mode_name = format!("test_mode_{}", unique_test_id());
+    jin_cmd()
+        .args(["mode", "create", &mode_name])
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+    jin_cmd()
+        .args(["mode", "use", &mode_name])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    // Conflicting content in two layers
+    let list_path = fixture.path().join("hosts.list");
+    for (content, flag) in [("alpha\n", "--global"), ("beta\n", "--mode")] {
+        fs::write(&list_path, content).unwrap();
+        jin_cmd()
+            .args(["add", "hosts.list", flag])
+            .current_dir(fixture.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success();
+        jin_cmd()
+            .args(["commit", "-m", "Add hosts"])
+            .current_dir(fixture.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success();
+    }
+    fs::remove_file(&list_path).unwrap();
+
+    jin_cmd()
+        .arg("apply")
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Operation paused").not());
+
+    assert_eq!(fs::read_to_string(&list_path).unwrap(), "alpha\nbeta\n");
+    assert!(!fixture.path().join("hosts.list.jinmerge").exists());
+}
+
+/// Commit conflicting `settings.txt` versions to the global and mode layers
+fn setup_text_conflict() -> (TestFixture, PathBuf) {
+    let fixture = setup_test_repo().unwrap();
+    let jin_dir = fixture.jin_dir.clone().unwrap();
+
+    let mode_name = format!("test_mode_{}", unique_test_id());
+    jin_cmd()
+        .args(["mode", "create", &mode_name])
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+    jin_cmd()
+        .args(["mode", "use", &mode_name])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    let settings_path = fixture.path().join("settings.txt");
+    for (content, flag) in [
+        ("line1\nline2\nline3\n", "--global"),
+        ("line1\nMODIFIED\nline3\n", "--mode"),
+    ] {
+        fs::write(&settings_path, content).unwrap();
+        jin_cmd()
+            .args(["add", "settings.txt", flag])
+            .current_dir(fixture.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success();
+        jin_cmd()
+            .args(["commit", "-m", "Add settings"])
+            .current_dir(fixture.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success();
+    }
+    fs::remove_file(&settings_path).unwrap();
+
+    (fixture, jin_dir)
+}
+
+#[test]
+fn test_apply_theirs_resolves_without_pausing() {
+    let (fixture, jin_dir) = setup_text_conflict();
+
+    jin_cmd()
+        .args(["apply", "--theirs"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Auto-resolved 1 conflicts with --theirs",
+        ))
+        .stdout(predicate::str::contains("settings.txt (kept mode-base)"))
+        .stdout(predicate::str::contains("Operation paused").not());
+
+    assert_eq!(
+        fs::read_to_string(fixture.path().join("settings.txt")).unwrap(),
+        "line1\nMODIFIED\nline3\n"
+    );
+    assert!(!fixture.path().join("settings.txt.jinmerge").exists());
+    assert!(!fixture.path().join(".jin/.paused_apply.yaml").exists());
+
+    // The resolution is in the audit log and its export
+    let audit_dir = fixture.path().join(".jin/audit");
+    let log: String = fs::read_dir(&audit_dir)
+        .unwrap()
+        .map(|e| fs::read_to_string(e.unwrap().path()).unwrap())
+        .collect();
+    assert!(log.contains(r#""resolution":"--theirs kept mode-base""#));
+    assert!(log.contains(r#""files":["settings.txt"]"#));
+}
+
+#[test]
+fn test_apply_prefer_named_layer() {
+    let (fixture, jin_dir) = setup_text_conflict();
+
+    jin_cmd()
+        .args(["apply", "--prefer", "global-base"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("settings.txt (kept global-base)"));
+
+    assert_eq!(
+        fs::read_to_string(fixture.path().join("settings.txt")).unwrap(),
+        "line1\nline2\nline3\n"
+    );
+}
+
+#[test]
+fn test_apply_ours_keeps_workspace_file() {
+    let (fixture, jin_dir) = setup_text_conflict();
+    let settings_path = fixture.path().join("settings.txt");
+    fs::write(&settings_path, "local\n").unwrap();
+
+    jin_cmd()
+        .args(["apply", "--ours"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("settings.txt (kept workspace)"));
+
+    assert_eq!(fs::read_to_string(&settings_path).unwrap(), "local\n");
+    assert!(!fixture.path().join("settings.txt.jinmerge").exists());
+}
+
+#[test]
+fn test_apply_conflict_policies_are_exclusive() {
+    let (fixture, jin_dir) = setup_text_conflict();
+
+    jin_cmd()
+        .args(["apply", "--ours", "--theirs"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .failure();
+
+    jin_cmd()
+        .args(["apply", "--prefer", "nonsense"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown layer: nonsense"));
+}
+
+#[test]
+fn test_strict_merge_reports_scalar_overwrites() {
+    let fixture = setup_test_repo().unwrap();
+    let jin_dir = fixture.jin_dir.clone().unwrap();
+
+    let config_toml = jin_dir.join("config.toml");
+    let mut config = fs::read_to_string(&config_toml).unwrap_or_default();
+    config.push_str(
+        "\n[[strict_merge]]\npattern = \"strict.json\"\nlayers = [\"global-base\", \"mode-base\"]\n",
+    );
+    fs::write(&config_toml, config).unwrap();
+
+    let mode_name = format!("test_mode_{}", unique_test_id());
+    jin_cmd()
+        .args(["mode", "create", &mode_name])
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+    jin_cmd()
+        .args(["mode", "use", &mode_name])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    // Same scalar, different values: strict.json conflicts, loose.json merges
+    for (file, flag, content) in [
+        ("strict.json", "--global", r#"{"port": 8080, "host": "a"}"#),
+        ("strict.json", "--mode", r#"{"port": 9090, "debug": true}"#),
+        ("loose.json", "--global", r#"{"port": 8080}"#),
+        ("loose.json", "--mode", r#"{"port": 9090}"#),
+    ] {
+        fs::write(fixture.path().join(file), content).unwrap();
+        jin_cmd()
+            .args(["add", file, flag])
+            .current_dir(fixture.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success();
+        jin_cmd()
+            .args(["commit", "-m", "Add config"])
+            .current_dir(fixture.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success();
+    }
+    fs::remove_file(fixture.path().join("strict.json")).unwrap();
+    fs::remove_file(fixture.path().join("loose.json")).unwrap();
+
+    jin_cmd()
+        .arg("apply")
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Operation paused"));
+
+    assert!(fixture.path().join("strict.json.jinmerge").exists());
+    let loose = fs::read_to_string(fixture.path().join("loose.json")).unwrap();
+    assert!(loose.contains("9090"));
+}
+
+#[test]
+fn test_conflict_dir_keeps_jinmerge_files_out_of_workspace() {
+    let (fixture, jin_dir) = setup_text_conflict();
+    let run = |args: &[&str]| {
+        jin_cmd()
+            .args(args)
+            .current_dir(fixture.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success()
+    };
+
+    run(&["config", "set", "apply.conflict_dir", "true"]);
+    run(&["apply"]).stdout(predicate::str::contains(".jin/conflicts/"));
+    assert!(!fixture.path().join("settings.txt.jinmerge").exists());
+
+    let conflicts_dir = fixture.path().join(".jin/conflicts");
+    let index = fs::read_to_string(conflicts_dir.join("index.json")).unwrap();
+    assert!(index.contains("settings.txt"));
+    let merge_file = fs::read_dir(&conflicts_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "jinmerge"))
+        .unwrap();
+
+    run(&["status"]).stdout(predicate::str::contains("(settings.txt)"));
+
+    fs::write(
+        &merge_file,
+        "# Jin merge conflict. Resolve and run 'jin resolve <file>'\nline1\nRESOLVED\nline3\n",
+    )
+    .unwrap();
+    run(&["resolve", "settings.txt"]);
+    assert!(fs::read_to_string(fixture.path().join("settings.txt"))
+        .unwrap()
+        .contains("line1\nRESOLVED\nline3\n"));
+    assert!(!conflicts_dir.exists());
+}
@@ -27,6 +27,22 @@ fn test_version() {
         .stdout(predicate::str::contains("jin"));
 }
 
+#[test]
+fn test_version_capabilities_json() {
+    let output = jin()
+        .args(["version", "--json", "--capabilities"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json["schema_version"], 1);
+    assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(json["capabilities"]["schemas"]["layers"], 1);
+    assert_eq!(json["capabilities"]["features"]["remotes"], true);
+}
+
 #[test]
 fn test_add_help() {
     jin()
@@ -253,6 +269,27 @@ fn test_status_with_staged_files() {
         .stdout(predicate::str::contains("settings.yaml"))
         .stdout(predicate::str::contains("mode-base"))
         .stdout(predicate::str::contains("jin commit"));
+
+    // --sections shows only the selected sections, in display order
+    let output = jin()
+        .args(["status", "--sections", "staging,context"])
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Staged changes"));
+    assert!(!stdout.contains("Workspace state"));
+    assert!(!stdout.contains("Layer summary"));
+    assert!(stdout.find("Mode:").unwrap() < stdout.find("Staged changes").unwrap());
+
+    jin()
+        .args(["status", "--sections", "nope"])
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .failure();
 }
 
 #[test]
@@ -586,6 +623,287 @@ fn test_layers_subcommand() {
         .stderr(predicate::str::contains("Jin not initialized"));
 }
 
+#[test]
+fn test_layers_preview_json() {
+    use tempfile::TempDir;
+    let temp = TempDir::new().unwrap();
+
+    // A preview needs no project and does not switch context
+    let output = jin()
+        .args([
+            "layers", "--for", "claude", "backend", "app", "--format", "json",
+        ])
+        .current_dir(temp.path())
+        .env("JIN_DIR", temp.path().join(".jin_global"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["mode"], "claude");
+    assert_eq!(json["scope"], "backend");
+    assert_eq!(json["project"], "app");
+    let layers: Vec<&str> = json["layers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| l["layer"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        layers,
+        [
+            "global-base",
+            "mode-base",
+            "mode-scope",
+            "mode-scope-project",
+            "mode-project",
+            "scope-base",
+            "project-base",
+            "user-local",
+            "workspace-active"
+        ]
+    );
+    assert_eq!(json["layers"][1]["ref"], "refs/jin/layers/mode/claude/_");
+    assert_eq!(json["layers"][1]["exists"], false);
+
+    jin()
+        .args(["layers", "--for", "-", "backend"])
+        .current_dir(temp.path())
+        .env("JIN_DIR", temp.path().join(".jin_global"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("previewed context"))
+        .stdout(predicate::str::contains("scope-base"))
+        .stdout(predicate::str::contains("mode-base").not());
+}
+
+#[test]
+fn test_layers_graph() {
+    use tempfile::TempDir;
+    let temp = TempDir::new().unwrap();
+
+    jin()
+        .args(["layers", "--for", "claude", "backend", "--graph", "dot"])
+        .current_dir(temp.path())
+        .env("JIN_DIR", temp.path().join(".jin_global"))
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("digraph jin_layers {"))
+        .stdout(predicate::str::contains(
+            "\"global-base\" -> \"mode-base\";",
+        ))
+        .stdout(predicate::str::contains("label=\"bound scope\""));
+
+    jin()
+        .args(["layers", "--for", "claude", "--graph", "mermaid"])
+        .current_dir(temp.path())
+        .env("JIN_DIR", temp.path().join(".jin_global"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("flowchart BT"))
+        .stdout(predicate::str::contains(
+            "mode_project -.-|project binding| mode_base",
+        ));
+}
+
+#[test]
+fn test_schema_subcommand() {
+    jin()
+        .arg("schema")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "layers   v1  jin layers --format json",
+        ))
+        .stdout(predicate::str::contains("audit"));
+
+    let output = jin().args(["schema", "layers"]).output().unwrap();
+    assert!(output.status.success());
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        schema["$schema"],
+        "https://json-schema.org/draft/2020-12/schema"
+    );
+    assert_eq!(schema["properties"]["schema_version"]["const"], 1);
+
+    // The layers output carries the version its schema describes
+    let temp = tempfile::TempDir::new().unwrap();
+    let output = jin()
+        .args(["layers", "--for", "claude", "--format", "json"])
+        .current_dir(temp.path())
+        .env("JIN_DIR", temp.path().join(".jin_global"))
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["schema_version"], 1);
+
+    jin()
+        .args(["schema", "nonsense"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value"));
+}
+
+#[test]
+fn test_global_json_flag() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let jin_dir = temp.path().join(".jin_global");
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(temp.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+    };
+    run(&["init"]).success();
+    std::fs::write(temp.path().join("app.json"), r#"{"port": 8080}"#).unwrap();
+    run(&["add", "app.json", "--global"]).success();
+    run(&["commit", "-m", "Add app"]).success();
+
+    // Accepted before or after the subcommand
+    for args in [
+        &["--json", "status"][..],
+        &["log", "--json"],
+        &["diff", "--json"],
+        &["layers", "--json"],
+        &["list", "files", "--json"],
+        &["--json", "version"],
+    ] {
+        let output = run(args).success().get_output().stdout.clone();
+        let json: serde_json::Value = serde_json::from_slice(&output)
+            .unwrap_or_else(|e| panic!("{:?} did not print JSON: {}", args, e));
+        assert_eq!(json["schema_version"], 1, "{:?}", args);
+    }
+
+    let output = run(&["list", "files", "--json"])
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json["files"]["global"], serde_json::json!(["app.json"]));
+
+    run(&["--json", "gc"])
+        .failure()
+        .stderr(predicate::str::contains("--json is supported by"));
+}
+
+#[test]
+fn test_gc_subcommand() {
+    use std::fs;
+    use tempfile::TempDir;
+    let temp = TempDir::new().unwrap();
+    let jin_dir = temp.path().join(".jin_global");
+
+    jin()
+        .arg("init")
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+    for version in 1..=2 {
+        fs::write(
+            temp.path().join("config.json"),
+            format!("{{\"v\": {}}}", version),
+        )
+        .unwrap();
+        fs::write(temp.path().join("shared.txt"), "same").unwrap();
+        jin()
+            .args(["add", "config.json", "shared.txt", "--global"])
+            .current_dir(temp.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success();
+        jin()
+            .args(["commit", "-m", &format!("v{}", version)])
+            .current_dir(temp.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success();
+    }
+
+    jin()
+        .args(["gc", "--dry-run"])
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("4 file version(s)"))
+        .stdout(predicate::str::contains("Unique blobs:  3"))
+        .stdout(predicate::str::contains("Dry run"));
+
+    jin()
+        .args(["gc", "--aggressive", "--window", "50"])
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Storage after"))
+        .stdout(predicate::str::contains("0 loose object(s), 1 pack(s)"));
+}
+
+#[test]
+fn test_projects_subcommand() {
+    use tempfile::TempDir;
+    let temp = TempDir::new().unwrap();
+    let jin_dir = temp.path().join(".jin_global");
+    let app = temp.path().join("app");
+    let old = temp.path().join("old");
+
+    for workspace in [&app, &old] {
+        std::fs::create_dir_all(workspace).unwrap();
+        jin()
+            .arg("init")
+            .current_dir(workspace)
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success();
+    }
+    jin()
+        .arg("apply")
+        .current_dir(&app)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+    std::fs::remove_dir_all(&old).unwrap();
+
+    let app_root = app.canonicalize().unwrap().display().to_string();
+    let old_root = temp
+        .path()
+        .canonicalize()
+        .unwrap()
+        .join("old")
+        .display()
+        .to_string();
+    let output = jin()
+        .arg("projects")
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let app_line = stdout.lines().find(|l| l.contains(&app_root)).unwrap();
+    assert!(!app_line.contains("last apply: never"));
+    let old_line = stdout.lines().find(|l| l.contains(&old_root)).unwrap();
+    assert!(old_line.trim_start().starts_with('!'));
+    assert!(old_line.contains("last apply: never"));
+
+    jin()
+        .args(["projects", "--forget-missing"])
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Forgot 1 missing workspace(s)"));
+    jin()
+        .arg("projects")
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(old_root).not());
+}
+
 #[test]
 fn test_list_subcommand() {
     // List requires Jin initialization - use isolated JIN_DIR
@@ -937,6 +1255,75 @@ fn test_completion_help() {
         .stdout(predicate::str::contains("powershell"));
 }
 
+#[test]
+#[serial]
+fn test_completion_nushell_and_elvish() {
+    jin()
+        .args(["completion", "nushell"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("export extern jin"));
+    jin()
+        .args(["completion", "elvish"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "edit:completion:arg-completer[jin]",
+        ));
+}
+
+#[test]
+#[serial]
+fn test_completion_install_and_uninstall() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let script = temp.path().join(".config/fish/completions/jin.fish");
+
+    // The shell is detected from $SHELL
+    jin()
+        .args(["completion", "--install", "--yes"])
+        .env("HOME", temp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env("SHELL", "/usr/bin/fish")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Installed fish completions"));
+    assert!(std::fs::read_to_string(&script)
+        .unwrap()
+        .contains("complete -c jin"));
+
+    // Declining the prompt leaves the script alone
+    jin()
+        .args(["completion", "fish", "--uninstall"])
+        .env("HOME", temp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .write_stdin("n\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Uninstall cancelled"));
+    assert!(script.exists());
+
+    jin()
+        .args(["completion", "fish", "--uninstall"])
+        .env("HOME", temp.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .write_stdin("y\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed"));
+    assert!(!script.exists());
+}
+
+#[test]
+#[serial]
+fn test_completion_install_unknown_shell() {
+    jin()
+        .args(["completion", "--install", "--yes"])
+        .env("SHELL", "/bin/tcsh")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Could not detect your shell"));
+}
+
 // ============================================================
 // Status Command - Conflict State Integration Tests
 // ============================================================
@@ -1118,3 +1505,641 @@ conflict_count: 1
         .stdout(predicate::str::contains("Resolve with: jin resolve"))
         .stdout(predicate::str::contains("Detected:"));
 }
+
+// ============================================================
+// Profiling Integration Tests
+// ============================================================
+
+#[test]
+fn test_profile_table_reported_on_stderr() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let jin_dir = temp.path().join(".jin_global");
+
+    jin()
+        .arg("init")
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    jin()
+        .args(["status", "--profile"])
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Profile:").not())
+        .stderr(predicate::str::contains("Profile:"))
+        .stderr(predicate::str::contains("repo_open"))
+        .stderr(predicate::str::contains("total"));
+}
+
+#[test]
+fn test_profile_json_format() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let jin_dir = temp.path().join(".jin_global");
+
+    jin()
+        .arg("init")
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    let output = jin()
+        .args(["--profile=json", "status"])
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let line = stderr
+        .lines()
+        .find(|l| l.starts_with('{'))
+        .expect("profile JSON line");
+    let json: serde_json::Value = serde_json::from_str(line).unwrap();
+    assert!(json["total_ms"].is_number());
+    assert!(json["phases"].is_array());
+}
+
+#[test]
+fn test_progress_json_events_on_stderr() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let jin_dir = temp.path().join(".jin_global");
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(temp.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+    };
+    run(&["init"]).success();
+    for file in ["a.json", "b.json"] {
+        std::fs::write(temp.path().join(file), "{}").unwrap();
+    }
+    run(&["add", "a.json", "b.json", "--global"]).success();
+    run(&["commit", "-m", "Add files"]).success();
+    for file in ["a.json", "b.json"] {
+        std::fs::remove_file(temp.path().join(file)).unwrap();
+    }
+
+    let output = run(&["--progress=json", "apply"])
+        .success()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Applied 2 files"));
+    assert!(!stdout.contains("schema_version"));
+
+    // stderr carries nothing but the events, one per line
+    let events: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap_or_else(|e| panic!("not NDJSON: {:?} ({})", l, e)))
+        .collect();
+    let phases: Vec<&str> = events
+        .iter()
+        .map(|e| e["phase"].as_str().unwrap())
+        .collect();
+    assert_eq!(phases, ["merge", "write", "write", "done"]);
+    assert!(events.iter().all(|e| e["operation"] == "apply"));
+    assert_eq!(events[2]["current"], 1);
+    assert_eq!(events[2]["total"], 2);
+    assert!(events[1]["path"].is_string());
+
+    // Human progress stays the default and never prints events
+    run(&["apply"])
+        .success()
+        .stderr(predicate::str::contains("schema_version").not());
+}
+
+#[test]
+fn test_progress_json_reports_warnings_as_events() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let jin_dir = temp.path().join(".jin_global");
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(temp.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+    };
+    run(&["init"]).success();
+    let path = temp.path().join("debug.json");
+    std::fs::write(&path, r#"{"debug": false}"#).unwrap();
+    run(&["add", "debug.json", "--global"]).success();
+    run(&["commit", "-m", "Add debug config"]).success();
+    // An override that expires right away warns on every apply
+    std::fs::write(&path, r#"{"debug": true}"#).unwrap();
+    run(&["add", "debug.json", "--local", "--expires", "0h"]).success();
+    run(&["commit", "-m", "Debug locally"]).success();
+
+    let output = run(&["--progress=json", "apply", "--force"])
+        .success()
+        .get_output()
+        .clone();
+    let events: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap_or_else(|e| panic!("not NDJSON: {:?} ({})", l, e)))
+        .collect();
+    let warning = events
+        .iter()
+        .find(|e| e["phase"] == "warning")
+        .expect("warning event");
+    assert!(warning["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Local override debug.json expired"));
+    assert!(warning.get("operation").is_none());
+
+    // Errors that end the command are events too (no remote is linked)
+    let output = run(&["--progress=json", "fetch"])
+        .failure()
+        .get_output()
+        .clone();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let last: serde_json::Value = serde_json::from_str(stderr.lines().last().unwrap()).unwrap();
+    assert_eq!(last["phase"], "error");
+}
+
+// ============================================================
+// -C / --chdir Integration Tests
+// ============================================================
+
+#[test]
+fn test_chdir_runs_command_in_target_directory() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let project = temp.path().join("project");
+    std::fs::create_dir(&project).unwrap();
+    let jin_dir = temp.path().join(".jin_global");
+
+    jin()
+        .arg("-C")
+        .arg(&project)
+        .arg("init")
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    assert!(project.join(".jin").join("context").exists());
+    assert!(!temp.path().join(".jin").exists());
+
+    jin()
+        .arg("status")
+        .arg("--chdir")
+        .arg(&project)
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Jin status:"));
+}
+
+#[test]
+fn test_chdir_nonexistent_directory() {
+    let temp = tempfile::TempDir::new().unwrap();
+
+    jin()
+        .arg("-C")
+        .arg(temp.path().join("missing"))
+        .arg("status")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not a directory"));
+}
+
+#[test]
+fn test_suggest_lists_and_applies_contexts() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let project = temp.path().join("project");
+    let jin_dir = temp.path().join(".jin_global");
+    std::fs::create_dir(&project).unwrap();
+    std::fs::write(project.join("Cargo.toml"), "[package]\nname = \"demo\"\n").unwrap();
+    std::fs::write(project.join("CLAUDE.md"), "# Notes\n").unwrap();
+
+    jin()
+        .arg("init")
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    jin()
+        .arg("suggest")
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("claude"))
+        .stdout(predicate::str::contains("language:rust"))
+        .stdout(predicate::str::contains("(new)"));
+
+    jin()
+        .args(["suggest", "--apply"])
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Activated mode 'claude'"))
+        .stdout(predicate::str::contains("Activated scope 'language:rust'"));
+
+    let context = std::fs::read_to_string(project.join(".jin").join("context")).unwrap();
+    assert!(context.contains("claude"));
+    assert!(context.contains("language:rust"));
+}
+
+#[test]
+fn test_add_manifest_validates_before_staging() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let project = temp.path().join("project");
+    let jin_dir = temp.path().join(".jin_global");
+    std::fs::create_dir(&project).unwrap();
+    std::fs::write(project.join("settings.json"), "{}").unwrap();
+    std::fs::write(project.join("notes.txt"), "notes").unwrap();
+
+    jin()
+        .arg("init")
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    std::fs::write(
+        project.join("add.yaml"),
+        "files:\n  - path: settings.json\n    global: true\n  - path: missing.txt\n",
+    )
+    .unwrap();
+    jin()
+        .args(["add", "--manifest", "add.yaml"])
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("entry 2 (missing.txt)"))
+        .stderr(predicate::str::contains("nothing was staged"));
+
+    std::fs::write(
+        project.join("add.yaml"),
+        "files:\n  - path: settings.json\n    global: true\n  - path: notes.txt\n",
+    )
+    .unwrap();
+    jin()
+        .args(["add", "--manifest", "add.yaml"])
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Staged 1 file(s) to global layer"))
+        .stdout(predicate::str::contains(
+            "Staged 1 file(s) to project layer",
+        ));
+
+    jin()
+        .args(["add", "--manifest", "add.yaml", "notes.txt"])
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_add_refuses_files_above_size_limit() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let project = temp.path().join("project");
+    let jin_dir = temp.path().join(".jin_global");
+    std::fs::create_dir(&project).unwrap();
+    std::fs::write(project.join("big.txt"), "x".repeat(4096)).unwrap();
+    std::fs::write(project.join("small.txt"), "x").unwrap();
+
+    jin()
+        .arg("init")
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["config", "set", "limits.max-file-size", "1KB"])
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    jin()
+        .args(["add", "big.txt", "small.txt", "--global"])
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Staged 1 file(s)"))
+        .stderr(predicate::str::contains("limits.max-file-size"));
+}
+
+#[test]
+fn test_commit_warns_about_mode_quota_and_stats_finds_offenders() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let project = temp.path().join("project");
+    let jin_dir = temp.path().join(".jin_global");
+    std::fs::create_dir_all(project.join("node_modules/dep")).unwrap();
+    std::fs::write(project.join("node_modules/dep/index.js"), "x".repeat(4096)).unwrap();
+    std::fs::write(project.join("settings.json"), "{}").unwrap();
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(&project)
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success()
+    };
+
+    run(&["init"]);
+    run(&["mode", "create", "claude"]);
+    run(&["mode", "use", "claude"]);
+    run(&["config", "set", "limits.mode-max-size", "2KB"]);
+    run(&["config", "set", "limits.commit-max-growth", "1KB"]);
+    run(&["config", "get", "limits.scope-max-size"])
+        .stdout(predicate::str::contains("100.0 MB (default)"));
+
+    run(&["add", "settings.json", "--mode"]);
+    run(&["commit", "-m", "Settings"]).stderr(predicate::str::contains("Warning").not());
+
+    run(&["add", "node_modules/dep/index.js", "--mode"]);
+    run(&["commit", "-m", "Oops"])
+        .stderr(predicate::str::contains(
+            "this commit added 4.0 KB to the mode-base layer (limits.commit-max-growth is 1.0 KB)",
+        ))
+        .stderr(predicate::str::contains(
+            "mode 'claude' now stores 4.0 KB across 1 layers (limits.mode-max-size is 2.0 KB)",
+        ))
+        .stderr(predicate::str::contains("jin stats --top"));
+
+    run(&["stats", "--top", "1"])
+        .stdout(predicate::str::contains("Storage by mode:"))
+        .stdout(predicate::str::contains(
+            "over limits.mode-max-size (2.0 KB)",
+        ))
+        .stdout(predicate::str::contains("Storage by scope: none"))
+        .stdout(predicate::str::contains(
+            "4.0 KB  node_modules/dep/index.js (mode/claude)",
+        ))
+        .stdout(predicate::str::contains("settings.json").not());
+}
+
+#[test]
+fn test_init_home_allowlist() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let home = temp.path().join("home");
+    let jin_dir = temp.path().join(".jin_global");
+    std::fs::create_dir_all(home.join(".ssh")).unwrap();
+    std::fs::create_dir_all(home.join("Documents")).unwrap();
+    std::fs::write(home.join(".bashrc"), "export EDITOR=vim\n").unwrap();
+    std::fs::write(home.join(".ssh/id_rsa"), "secret").unwrap();
+    std::fs::write(home.join("Documents/notes.txt"), "notes").unwrap();
+
+    let home_jin = |args: &[&str]| {
+        let mut cmd = jin();
+        cmd.args(args)
+            .current_dir(temp.path())
+            .env("HOME", &home)
+            .env("JIN_DIR", &jin_dir);
+        cmd
+    };
+
+    home_jin(&["init", "--home"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("home workspace"));
+    assert!(home.join(".jin/context").exists());
+
+    let home_arg = home.to_str().unwrap();
+    home_jin(&["-C", home_arg, "add", ".bashrc", "--global"])
+        .assert()
+        .success();
+    home_jin(&["-C", home_arg, "add", "Documents/notes.txt", "--global"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not on the home allowlist"));
+    home_jin(&["-C", home_arg, "add", ".ssh/id_rsa", "--global"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("never managed"));
+    assert!(!home.join(".gitignore").exists());
+
+    home_jin(&["init", "--home", "--allow", "Documents/**"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Documents/**"));
+    home_jin(&["-C", home_arg, "add", "Documents/notes.txt", "--global"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_help_topics_and_commands() {
+    use tempfile::TempDir;
+    let temp = TempDir::new().unwrap();
+
+    jin()
+        .args(["help", "layers"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("LAYERS"))
+        .stdout(predicate::str::contains("not in a Jin project"))
+        .stdout(predicate::str::contains("jin add settings.json --mode"));
+
+    jin()
+        .args(["help", "mode", "use"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Usage: jin mode use [OPTIONS] <NAME>",
+        ));
+
+    jin()
+        .arg("help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("jin help <topic>"));
+
+    jin()
+        .args(["help", "nonsense"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "No help topic or command named 'nonsense'",
+        ));
+}
+
+#[test]
+fn test_context_validate_and_heal_dangling_mode() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let project = temp.path().join("project");
+    let jin_dir = temp.path().join(".jin_global");
+    std::fs::create_dir_all(&project).unwrap();
+    let jin_at = |args: &[&str]| {
+        let mut cmd = jin();
+        cmd.args(args)
+            .current_dir(&project)
+            .env("JIN_DIR", &jin_dir);
+        cmd
+    };
+
+    jin_at(&["init"]).assert().success();
+    jin_at(&["mode", "create", "claude"]).assert().success();
+    jin_at(&["mode", "use", "claude"]).assert().success();
+    jin_at(&["context", "--validate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Active context is valid"));
+
+    // As if the mode had been renamed on another machine
+    let context_path = project.join(".jin/context");
+    let context = std::fs::read_to_string(&context_path).unwrap();
+    std::fs::write(&context_path, context.replace("claude", "claud")).unwrap();
+
+    jin_at(&["apply"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Active mode 'claud' no longer exists (did you mean 'claude'?)",
+        ))
+        .stderr(predicate::str::contains("jin context --heal"));
+    jin_at(&["context", "--validate"]).assert().failure();
+    jin_at(&["status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Warning: Active mode 'claud' no longer exists",
+        ));
+
+    jin_at(&["context", "--heal"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Unset mode 'claud'"))
+        .stdout(predicate::str::contains("jin mode use claude"));
+    jin_at(&["context"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Active mode:   (none)"));
+    jin_at(&["context", "--heal"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing to heal"));
+}
+
+#[test]
+fn test_add_create_missing_scope() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let project = temp.path().join("project");
+    let jin_dir = temp.path().join(".jin_global");
+    std::fs::create_dir_all(&project).unwrap();
+    let jin_at = |args: &[&str]| {
+        let mut cmd = jin();
+        cmd.args(args)
+            .current_dir(&project)
+            .env("JIN_DIR", &jin_dir);
+        cmd
+    };
+
+    jin_at(&["init"]).assert().success();
+    std::fs::write(project.join("lint.toml"), "strict = true\n").unwrap();
+
+    jin_at(&["add", "lint.toml", "--scope=lang:rust"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Scope 'lang:rust' does not exist. Create it with: jin scope create lang:rust",
+        ))
+        .stderr(predicate::str::contains("--create-missing"));
+
+    // Nothing staged: the scope is created and removed again
+    jin_at(&[
+        "add",
+        "missing.toml",
+        "--scope=lang:rust",
+        "--create-missing",
+    ])
+    .assert()
+    .failure();
+    jin_at(&["scope", "use", "lang:rust"]).assert().failure();
+
+    jin_at(&["add", "lint.toml", "--scope=lang:rust", "--create-missing"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created scope 'lang:rust'"));
+    jin_at(&["commit", "-m", "Rust lints"]).assert().success();
+    jin_at(&["scope", "use", "lang:rust"]).assert().success();
+}
+
+#[test]
+fn test_add_manifest_create_missing_scope() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let project = temp.path().join("project");
+    let jin_dir = temp.path().join(".jin_global");
+    std::fs::create_dir_all(&project).unwrap();
+    let jin_at = |args: &[&str]| {
+        let mut cmd = jin();
+        cmd.args(args)
+            .current_dir(&project)
+            .env("JIN_DIR", &jin_dir);
+        cmd
+    };
+
+    jin_at(&["init"]).assert().success();
+    std::fs::write(project.join("lint.toml"), "strict = true\n").unwrap();
+    std::fs::write(
+        project.join("add.yaml"),
+        "files:\n  - path: lint.toml\n    scope: lang:rust\n",
+    )
+    .unwrap();
+
+    jin_at(&["add", "--manifest", "add.yaml"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Scope 'lang:rust' does not exist. Create it with: jin scope create lang:rust",
+        ));
+
+    jin_at(&["add", "--manifest", "add.yaml", "--create-missing"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created scope 'lang:rust'"))
+        .stdout(predicate::str::contains("Staged 1 file(s)"));
+    jin_at(&["commit", "-m", "Rust lints"]).assert().success();
+    jin_at(&["scope", "use", "lang:rust"]).assert().success();
+}
+
+#[test]
+fn test_explain_error_code() {
+    let temp = tempfile::TempDir::new().unwrap();
+
+    jin()
+        .arg("status")
+        .current_dir(temp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("run 'jin explain JIN-E017'"));
+
+    jin()
+        .args(["explain", "jin-e17"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("JIN-E017: Jin not initialized"))
+        .stdout(predicate::str::contains(
+            "Run 'jin init' in the project root",
+        ));
+
+    jin()
+        .arg("explain")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("JIN-E006  Push rejected"));
+
+    jin()
+        .args(["explain", "JIN-E999"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No explanation for 'JIN-E999'"));
+}
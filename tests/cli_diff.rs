@@ -7,6 +7,7 @@
 //! 4. `jin diff` - Compare workspace vs workspace-active
 
 use assert_cmd::Command;
+use predicates::prelude::*;
 use predicates::str::contains;
 use std::fs;
 use tempfile::TempDir;
@@ -203,7 +204,40 @@ fn test_diff_workspace_vs_layer() {
         .env("JIN_DIR", &jin_dir)
         .assert()
         .success()
-        .stdout(contains("Comparing workspace vs mode-base"));
+        .stdout(contains("Comparing workspace vs mode-base"))
+        .stdout(contains("\n-{\"key\": \"original\"}\n"))
+        .stdout(contains("\x1b[").not());
+
+    // Colors are only added on request when stdout is not a terminal
+    jin()
+        .args(["diff", "mode-base", "--color", "always"])
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(contains("\x1b[36m@@ -1 +1 @@\x1b[0m"))
+        .stdout(contains(
+            "\x1b[31m-{\"key\": \"\x1b[0m\x1b[7;31moriginal\x1b[0m\x1b[31m\"}\x1b[0m",
+        ))
+        .stdout(contains(
+            "\x1b[32m+{\"key\": \"\x1b[0m\x1b[7;32mmodified\x1b[0m\x1b[32m\"}\x1b[0m",
+        ));
+
+    // Whitespace-only edits are ignored with -w
+    fs::write(&config_path, r#"{"key":   "original"}"#).unwrap();
+    jin()
+        .args(["diff", "mode-base", "--exit-code"])
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .code(1);
+    jin()
+        .args(["diff", "mode-base", "-w", "--exit-code"])
+        .current_dir(temp.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(contains("No differences between workspace and mode-base"));
 }
 
 /// Test: `jin diff` - Default workspace vs workspace-active
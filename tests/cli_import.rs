@@ -1219,3 +1219,63 @@ fn test_import_with_invalid_routing_combination() {
         .failure()
         .stderr(contains("requires --mode"));
 }
+
+/// Test: `--on-exists` when the target layer already has the file
+#[test]
+fn test_import_on_exists_policies() {
+    let temp = TempDir::new().unwrap();
+    let jin_dir = temp.path().join(".jin_global");
+    git_init_with_config(&temp);
+    let git = |args: &[&str]| {
+        StdCommand::new("git")
+            .args(args)
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+    };
+    let run = |args: &[&str], stdin: &str| {
+        jin()
+            .args(args)
+            .current_dir(temp.path())
+            .env("JIN_DIR", &jin_dir)
+            .write_stdin(stdin)
+            .assert()
+            .success()
+    };
+    run(&["init"], "");
+
+    // The layer has config.json; Git tracks a different version of it
+    let config_path = temp.path().join("config.json");
+    fs::write(&config_path, r#"{"port": 8080, "host": "layer"}"#).unwrap();
+    run(&["add", "config.json"], "");
+    run(&["commit", "-m", "Layer config"], "");
+    fs::write(&config_path, r#"{"port": 9090}"#).unwrap();
+    git(&["add", "-f", "config.json"]);
+    git(&["commit", "-m", "Tracked config"]);
+
+    run(&["import", "config.json", "--on-exists", "skip"], "")
+        .stdout(contains("config.json: skipped"));
+    let tracked = StdCommand::new("git")
+        .args(["ls-files"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&tracked.stdout).contains("config.json"));
+
+    // Unanswered prompts skip too
+    run(&["import", "config.json", "--on-exists", "prompt"], "")
+        .stdout(contains("already exists in the project-base layer"))
+        .stdout(contains("config.json: skipped"));
+
+    run(
+        &["import", "config.json", "--on-exists", "prompt"],
+        "x\nm\n",
+    )
+    .stdout(contains("Unknown choice 'x'"))
+    .stdout(contains("Imported 1 file(s)"))
+    .stdout(contains("config.json: merged with the layer's version"));
+    let merged: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(merged["port"], 9090);
+    assert_eq!(merged["host"], "layer");
+}
@@ -204,6 +204,45 @@ fn test_list_with_all_categories() {
         .stdout(predicate::str::contains("Projects:"));
 }
 
+/// Test `jin list modes` and `jin list files` with a layer filter
+#[test]
+fn test_list_names_and_files() {
+    let fixture = TestFixture::new().unwrap();
+    let jin_dir = fixture.path().join(".jin");
+    jin_init(fixture.path(), Some(&jin_dir)).unwrap();
+
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(fixture.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+    };
+    run(&["mode", "create", "editor"]).success();
+    run(&["mode", "use", "editor"]).success();
+    std::fs::write(fixture.path().join("editor.conf"), "font = mono").unwrap();
+    std::fs::write(fixture.path().join("keys.conf"), "save = ctrl-s").unwrap();
+    run(&["add", "keys.conf", "editor.conf", "--mode"]).success();
+    run(&["commit", "-m", "Add editor config"]).success();
+
+    run(&["list", "modes"]).success().stdout("editor\n");
+    run(&["list", "files", "--layer", "mode/editor"])
+        .success()
+        .stdout("editor.conf\nkeys.conf\n");
+    run(&["list", "files"])
+        .success()
+        .stdout(predicate::str::contains(
+            "mode/editor:\n  editor.conf\n  keys.conf",
+        ));
+
+    run(&["list", "files", "--layer", "mode/other"])
+        .failure()
+        .stderr(predicate::str::contains("Layer 'mode/other'"));
+    run(&["list", "modes", "--layer", "global"])
+        .failure()
+        .stderr(predicate::str::contains("--layer only applies"));
+}
+
 /// Test list not initialized error
 #[test]
 fn test_list_not_initialized() {
@@ -417,3 +456,83 @@ fn test_list_empty_categories_hidden() {
         .stdout(predicate::str::contains("Scopes:").not())
         .stdout(predicate::str::contains("Projects:").not());
 }
+
+/// Test stale listing and pruning based on recorded usage
+#[test]
+fn test_list_stale_and_prune() {
+    let fixture = TestFixture::new().unwrap();
+    let jin_dir = fixture.path().join(".jin");
+    jin_init(fixture.path(), Some(&jin_dir)).unwrap();
+
+    for mode in ["abandoned", "current"] {
+        jin()
+            .args(["mode", "create", mode])
+            .current_dir(fixture.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success();
+    }
+    jin()
+        .args(["mode", "use", "current"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    // Freshly created modes are not stale
+    jin()
+        .args(["list", "--stale", "--than", "90d"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No modes or scopes unused"));
+
+    // With a zero age everything is stale
+    jin()
+        .args(["list", "--stale", "--than", "0h"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("abandoned"))
+        .stdout(predicate::str::contains("never used"))
+        .stdout(predicate::str::contains("(active here)"));
+
+    jin()
+        .args(["prune", "--than", "0h", "--dry-run"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Dry run: nothing was deleted"));
+
+    // The active mode is kept; the abandoned one and its refs are deleted
+    jin()
+        .args(["prune", "--than", "0h", "--force"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Skipping mode 'current' (active in this project)",
+        ))
+        .stdout(predicate::str::contains("Pruned 1 mode(s)/scope(s)"));
+
+    jin()
+        .arg("list")
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- current"))
+        .stdout(predicate::str::contains("abandoned").not());
+
+    jin()
+        .args(["list", "--stale", "--than", "soon"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid age"));
+}
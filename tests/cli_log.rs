@@ -104,3 +104,130 @@ fn test_log_shows_all_layer_commits() {
         .stdout(predicate::str::contains("Mode base commit"))
         .stdout(predicate::str::contains("Mode scope commit"));
 }
+
+/// Color is off when piped unless forced, and piped output is never paged
+#[test]
+fn test_log_color_follows_color_flag() {
+    let fixture = TestFixture::new().unwrap();
+    let jin_dir = fixture.path().join(".jin");
+    jin_init(fixture.path(), Some(&jin_dir)).unwrap();
+
+    std::fs::write(fixture.path().join("global.json"), "{\"a\": 1}").unwrap();
+    jin()
+        .args(["add", "global.json", "--global"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Global commit"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    // A pager that would mark every line is ignored when stdout is a pipe
+    jin()
+        .arg("log")
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .env("GIT_PAGER", "sed s/^/PAGED:/")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Global commit"))
+        .stdout(predicate::str::contains("PAGED:").not())
+        .stdout(predicate::str::contains("\x1b[").not());
+
+    jin()
+        .args(["log", "--color", "always"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[33mcommit "))
+        .stdout(predicate::str::contains(
+            "\x1b[1m=== global-base ===\x1b[0m",
+        ));
+}
+
+/// `--patch` shows key changes of structured files and the unified diff
+#[test]
+fn test_log_patch_shows_key_changes() {
+    let fixture = TestFixture::new().unwrap();
+    let jin_dir = fixture.path().join(".jin");
+    jin_init(fixture.path(), Some(&jin_dir)).unwrap();
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(fixture.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success()
+    };
+
+    let path = fixture.path().join("app.json");
+    std::fs::write(&path, "{\"port\": 80, \"debug\": true}\n").unwrap();
+    run(&["add", "app.json", "--global"]);
+    run(&["commit", "-m", "Add app config"]);
+    std::fs::write(&path, "{\"port\": 8080, \"tls\": true}\n").unwrap();
+    run(&["add", "app.json", "--global"]);
+    run(&["commit", "-m", "Serve TLS"]);
+
+    let output = run(&["log", "--layer", "global-base", "--patch"])
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    let latest = output.split("Add app config").next().unwrap();
+    assert!(latest.contains("Keys changed in app.json:"), "{}", output);
+    assert!(latest.contains("~ port: \"80\" -> \"8080\""), "{}", output);
+    assert!(latest.contains("- debug: \"true\""), "{}", output);
+    assert!(latest.contains("+ tls: \"true\""), "{}", output);
+    assert!(
+        latest.contains("+{\"port\": 8080, \"tls\": true}"),
+        "{}",
+        output
+    );
+
+    // The first commit adds every key
+    let first = output.split("Add app config").nth(1).unwrap();
+    assert!(first.contains("+ port: \"80\""), "{}", output);
+
+    run(&["log", "--layer", "global-base"]).stdout(predicate::str::contains("Keys changed").not());
+}
+
+/// `--applies` lists recorded applies with their layers and files
+#[test]
+fn test_log_applies_shows_recorded_applies() {
+    let fixture = TestFixture::new().unwrap();
+    let jin_dir = fixture.path().join(".jin");
+    jin_init(fixture.path(), Some(&jin_dir)).unwrap();
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(fixture.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success()
+    };
+
+    run(&["log", "--applies"]).stdout(predicate::str::contains("No applies recorded"));
+
+    std::fs::write(fixture.path().join("app.json"), "{\"port\": 80}\n").unwrap();
+    run(&["add", "app.json", "--global"]);
+    run(&["commit", "-m", "Add app config"]);
+    run(&["apply", "--force", "--dry-run"]);
+    run(&["apply", "--force"]);
+
+    let output = run(&["log", "--applies"]).get_output().stdout.clone();
+    let output = String::from_utf8(output).unwrap();
+    // The preview isn't recorded
+    assert_eq!(output.matches("applied in").count(), 1, "{}", output);
+    assert!(output.contains("Layers:"), "{}", output);
+    assert!(output.contains("global-base"), "{}", output);
+    assert!(output.contains("Files written (1):"), "{}", output);
+    assert!(output.contains("app.json"), "{}", output);
+
+    run(&["log", "--applies", "--since", "2999-01-01"])
+        .stdout(predicate::str::contains("No applies recorded"));
+}
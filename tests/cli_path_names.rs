@@ -0,0 +1,176 @@
+//! Integration tests for file names with spaces, quotes, Unicode and
+//! characters that mean something to `.gitignore` or a shell
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Get a Command for the jin binary
+fn jin() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_jin"))
+}
+
+/// Names that have broken path handling somewhere
+const HOSTILE_NAMES: &[&str] = &[
+    "my notes.md",
+    "quote\"d 'name'.json",
+    "naïve café.yaml",
+    "日本語/設定.json",
+    "dir with space/nested file.toml",
+    "#hash.txt",
+    "!bang.txt",
+    "star*[1]?.txt",
+    "-dash.txt",
+    "trail ",
+    "emoji 🎉.ini",
+];
+
+fn content_for(name: &str) -> String {
+    if name.ends_with(".json") {
+        format!("{{\"name\": {:?}}}", name)
+    } else if name.ends_with(".yaml") {
+        format!("name: {:?}\n", name)
+    } else if name.ends_with(".toml") || name.ends_with(".ini") {
+        format!("name = {:?}\n", name)
+    } else {
+        format!("{}\n", name)
+    }
+}
+
+fn git(project: &Path, args: &[&str]) -> Option<std::process::Output> {
+    std::process::Command::new("git")
+        .args(args)
+        .current_dir(project)
+        .output()
+        .ok()
+}
+
+#[test]
+fn test_hostile_names_round_trip() {
+    let temp = TempDir::new().unwrap();
+    let project = temp.path().join("project");
+    let jin_dir = temp.path().join(".jin_global");
+    fs::create_dir_all(&project).unwrap();
+    let has_git = git(&project, &["init", "-q"]).is_some_and(|o| o.status.success());
+
+    jin()
+        .arg("init")
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    for name in HOSTILE_NAMES {
+        let path = project.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content_for(name)).unwrap();
+    }
+
+    jin()
+        .args(["add", "--global", "--"])
+        .args(HOSTILE_NAMES)
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["reset", "--staged", "日本語/設定.json"])
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["add", "--global", "日本語/設定.json"])
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Hostile names"])
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    // Every name is ignored by Git as exactly itself
+    if has_git {
+        for name in HOSTILE_NAMES {
+            let ignored = git(&project, &["check-ignore", "-q", "--no-index", "--", name])
+                .unwrap()
+                .status
+                .success();
+            assert!(ignored, "{:?} is not ignored", name);
+        }
+        let bystander = project.join("star1x.txt");
+        fs::write(&bystander, "not managed").unwrap();
+        let ignored = git(
+            &project,
+            &["check-ignore", "-q", "--no-index", "star1x.txt"],
+        )
+        .unwrap()
+        .status
+        .success();
+        assert!(!ignored, "an escaped glob matched another file");
+    }
+
+    for name in HOSTILE_NAMES {
+        fs::remove_file(project.join(name)).unwrap();
+    }
+    jin()
+        .arg("apply")
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+    for name in HOSTILE_NAMES {
+        let content = fs::read_to_string(project.join(name))
+            .unwrap_or_else(|e| panic!("{:?} was not applied: {}", name, e));
+        if name.ends_with(".json") {
+            let value: serde_json::Value = serde_json::from_str(&content).unwrap();
+            assert_eq!(value["name"], *name);
+        } else if name.contains(".yaml") || name.contains(".toml") || name.contains(".ini") {
+            assert!(content.contains("name"), "{:?}: {}", name, content);
+        } else {
+            assert_eq!(content, content_for(name), "{:?}", name);
+        }
+    }
+
+    jin()
+        .arg("status")
+        .current_dir(&project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Workspace state: Clean"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_unstorable_names_are_refused() {
+    let temp = TempDir::new().unwrap();
+    let project = temp.path();
+    let jin_dir = temp.path().join(".jin_global");
+    jin()
+        .arg("init")
+        .current_dir(project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    fs::write(project.join("line\nbreak.txt"), "x").unwrap();
+    jin()
+        .args(["add", "line\nbreak.txt"])
+        .current_dir(project)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("control character"));
+    assert!(
+        !project.join(".gitignore").exists()
+            || !fs::read_to_string(project.join(".gitignore"))
+                .unwrap()
+                .contains("break.txt")
+    );
+}
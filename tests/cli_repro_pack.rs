@@ -0,0 +1,76 @@
+//! Integration tests for `jin repro-pack` command
+//!
+//! Tests that a package holds only the requested files of each layer and
+//! replays the same merge on its own.
+
+use predicates::prelude::*;
+
+mod common;
+use common::fixtures::*;
+
+/// Test packing a merge and replaying it from the package
+#[test]
+fn test_repro_pack_replays_merge() {
+    let fixture = TestFixture::new().unwrap();
+    let jin_dir = fixture.path().join(".jin");
+    jin_init(fixture.path(), Some(&jin_dir)).unwrap();
+
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(fixture.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+    };
+    run(&["mode", "create", "editor"]).success();
+    run(&["mode", "use", "editor"]).success();
+    let write = |file: &str, content: &str| {
+        std::fs::write(fixture.path().join(file), content).unwrap();
+    };
+    write("app.json", r#"{"token": "s3cret", "port": 8080}"#);
+    write("unrelated.json", r#"{"private": true}"#);
+    run(&["add", "app.json", "unrelated.json", "--global"]).success();
+    run(&["commit", "-m", "Global config"]).success();
+    write("app.json", r#"{"host": "example.com"}"#);
+    run(&["add", "app.json", "--mode"]).success();
+    run(&["commit", "-m", "Editor config"]).success();
+
+    run(&["repro-pack", "app.json", "--redact", "-o", "repro"])
+        .success()
+        .stdout(predicate::str::contains(
+            "Wrote 1 file(s) from 2 layer(s) to repro (redacted)",
+        ));
+
+    let repro = fixture.path().join("repro");
+    let pack = git2::Repository::open_bare(repro.join("jin")).unwrap();
+    let global = pack
+        .revparse_single("refs/jin/layers/global")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(global.author().email(), Some("jin@local"));
+    let tree = global.tree().unwrap();
+    assert!(tree.get_name("app.json").is_some());
+    assert!(tree.get_name("unrelated.json").is_none());
+    assert!(pack.find_reference("refs/jin/layers/mode/editor/_").is_ok());
+
+    // The package replays the merge with the secret hashed away
+    jin()
+        .arg("apply")
+        .current_dir(repro.join("project"))
+        .env("JIN_DIR", repro.join("jin"))
+        .assert()
+        .success();
+    let merged = std::fs::read_to_string(repro.join("project/app.json")).unwrap();
+    let merged: serde_json::Value = serde_json::from_str(&merged).unwrap();
+    assert_eq!(merged["port"], 8080);
+    assert!(merged["host"].as_str().unwrap().starts_with("redacted-"));
+    assert!(merged["token"].as_str().unwrap().starts_with("redacted-"));
+
+    run(&["repro-pack", "app.json", "-o", "repro"])
+        .failure()
+        .stderr(predicate::str::contains("--output"));
+    run(&["repro-pack", "missing.json", "-o", "other"])
+        .failure()
+        .stderr(predicate::str::contains("'missing.json'"));
+}
@@ -365,22 +365,20 @@ fn test_reset_hard_force_in_detached_state() {
 
     // ================== STEP 5: CREATE DETACHED STATE ==================
     // Modify config.json (which is in WorkspaceMetadata) externally.
-    // This creates a detached state because WorkspaceMetadata has the original hash.
+    // Apply treats this as detached because WorkspaceMetadata has the original hash.
     fs::write(project_path.join("config.json"), r#"{"modified": true}"#).unwrap();
 
-    // ================== STEP 6: VERIFY reset --hard FAILS ==================
-    // Without --force, should fail with detached state error
+    // ================== STEP 6: VERIFY reset --hard IS NOT REJECTED ==================
+    // Restoring edited applied files is what --hard is for, so it prompts
+    // instead of failing (and cancels without input)
     jin()
         .args(["reset", "--hard"])
         .current_dir(project_path)
         .env("JIN_DIR", &jin_dir)
         .assert()
-        .failure()
-        .stderr(
-            predicate::str::contains("detached")
-                .or(predicate::str::contains("modified"))
-                .or(predicate::str::contains("Workspace files")),
-        );
+        .success()
+        .stdout(predicate::str::contains("Type 'yes' to confirm"));
+    assert!(project_path.join("settings.json").exists());
 
     // ================== STEP 7: VERIFY reset --hard --force SUCCEEDS ==================
     // With --force, should skip validation and succeed
@@ -398,3 +396,158 @@ fn test_reset_hard_force_in_detached_state() {
     // config.json should still exist (it was modified, not reset)
     assert!(project_path.join("config.json").exists());
 }
+
+#[test]
+fn test_reset_hard_restores_last_applied_content() {
+    let temp = TempDir::new().unwrap();
+    let project_path = temp.path();
+    let jin_dir = temp.path().join(".jin_global");
+
+    jin()
+        .arg("init")
+        .current_dir(project_path)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    // Commit and apply a global config file
+    fs::write(project_path.join("config.json"), r#"{"port": 80}"#).unwrap();
+    jin()
+        .args(["add", "config.json", "--global"])
+        .current_dir(project_path)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Add config"])
+        .current_dir(project_path)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["apply", "--force"])
+        .current_dir(project_path)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+    let applied = fs::read_to_string(project_path.join("config.json")).unwrap();
+
+    // Modify and stage the applied file, and stage a brand new file
+    fs::write(project_path.join("config.json"), r#"{"port": 8080}"#).unwrap();
+    fs::write(project_path.join("extra.json"), "{}").unwrap();
+    jin()
+        .args(["add", "config.json", "extra.json", "--global"])
+        .current_dir(project_path)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    jin()
+        .args(["reset", "--hard", "--global", "--force"])
+        .current_dir(project_path)
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Discarded 2 file(s) from staging"))
+        .stdout(predicate::str::contains(
+            "Restored 1 file(s) to the last applied content",
+        ));
+
+    assert_eq!(
+        fs::read_to_string(project_path.join("config.json")).unwrap(),
+        applied
+    );
+    assert!(!project_path.join("extra.json").exists());
+}
+
+#[test]
+fn test_reset_hard_restores_drifted_file_without_force() {
+    let temp = TempDir::new().unwrap();
+    let project_path = temp.path();
+    let jin_dir = temp.path().join(".jin_global");
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(project_path)
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+    };
+
+    run(&["init"]).success();
+    fs::write(project_path.join("config.json"), r#"{"port": 80}"#).unwrap();
+    run(&["add", "config.json", "--global"]).success();
+    run(&["commit", "-m", "Add config"]).success();
+    run(&["apply", "--force"]).success();
+    let applied = fs::read_to_string(project_path.join("config.json")).unwrap();
+
+    // Edit and stage the applied file
+    fs::write(project_path.join("config.json"), r#"{"port": 8080}"#).unwrap();
+    run(&["add", "config.json", "--global"]).success();
+
+    jin()
+        .args(["reset", "--hard", "--global"])
+        .current_dir(project_path)
+        .env("JIN_DIR", &jin_dir)
+        .write_stdin("yes\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Restored 1 file(s) to the last applied content",
+        ));
+    assert_eq!(
+        fs::read_to_string(project_path.join("config.json")).unwrap(),
+        applied
+    );
+}
+
+#[test]
+fn test_reset_and_commit_single_staged_entries() {
+    let temp = TempDir::new().unwrap();
+    let project_path = temp.path();
+    let jin_dir = temp.path().join(".jin_global");
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(project_path)
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+    };
+
+    run(&["init"]).success();
+    fs::write(project_path.join("config.json"), r#"{"test": true}"#).unwrap();
+    fs::write(project_path.join("notes.txt"), "notes\n").unwrap();
+    run(&["add", "config.json"]).success();
+    run(&["add", "config.json", "--global"]).success();
+    run(&["add", "notes.txt"]).success();
+
+    // Entries are numbered by path, then layer precedence
+    run(&["status"])
+        .success()
+        .stdout(predicate::str::contains("[1] config.json -> global-base"))
+        .stdout(predicate::str::contains("[2] config.json -> project-base"))
+        .stdout(predicate::str::contains("[3] notes.txt -> project-base"))
+        .stdout(predicate::str::contains("staged to several layers"));
+
+    // A bare path staged to two layers is ambiguous
+    run(&["reset", "--staged", "config.json"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "global-base:config.json, project-base:config.json",
+        ));
+    run(&["reset", "--staged", "global-base:config.json"])
+        .success()
+        .stdout(predicate::str::contains("Unstaged global-base:config.json"));
+    run(&["status"])
+        .success()
+        .stdout(predicate::str::contains("[1] config.json -> project-base"))
+        .stdout(predicate::str::contains("global-base").not());
+
+    // Only the numbered entry is committed; the other stays staged
+    run(&["commit", "-m", "Notes", "--only", "2"])
+        .success()
+        .stdout(predicate::str::contains("Committed 1 file(s)"));
+    run(&["status"])
+        .success()
+        .stdout(predicate::str::contains("[1] config.json -> project-base"))
+        .stdout(predicate::str::contains("notes.txt").not());
+}
@@ -393,3 +393,64 @@ conflict_count: 2
     // Verify paused state still exists
     assert!(paused_state_path.exists());
 }
+
+#[test]
+fn test_resolve_glob_take_theirs() {
+    let fixture = setup_test_repo().unwrap();
+    let jin_dir = fixture.jin_dir.clone().unwrap();
+
+    let paused_state_path = fixture.path().join(".jin/.paused_apply.yaml");
+    fs::create_dir_all(fixture.path().join(".jin")).ok();
+    fs::write(
+        &paused_state_path,
+        r#"timestamp: "2099-01-01T00:00:00Z"
+layer_config:
+  layers: ["global"]
+  mode: None
+  scope: None
+  project: None
+conflict_files:
+  - configs/a.json
+  - configs/b.json
+  - notes.txt
+applied_files: []
+conflict_count: 3
+"#,
+    )
+    .unwrap();
+
+    fs::create_dir_all(fixture.path().join("configs")).unwrap();
+    for name in ["configs/a.json", "configs/b.json", "notes.txt"] {
+        fs::write(
+            fixture.path().join(format!("{}.jinmerge", name)),
+            "# Jin merge conflict. Resolve and run 'jin resolve <file>'\n\
+             <<<<<<< global/\n{\"v\":1}\n=======\n{\"v\":2}\n>>>>>>> mode/dev/\n",
+        )
+        .unwrap();
+    }
+
+    jin_cmd()
+        .args(["resolve", "configs/*.json", "--take", "theirs"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Resolved 2 file"));
+
+    for name in ["configs/a.json", "configs/b.json"] {
+        let content = fs::read_to_string(fixture.path().join(name)).unwrap();
+        assert_eq!(content, "{\"v\":2}\n");
+        assert!(!fixture.path().join(format!("{}.jinmerge", name)).exists());
+    }
+    assert!(fixture.path().join("notes.txt.jinmerge").exists());
+    assert!(paused_state_path.exists());
+
+    // A glob matching nothing is an error
+    jin_cmd()
+        .args(["resolve", "configs/*.yaml", "--take", "ours"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No conflicted files match"));
+}
@@ -0,0 +1,90 @@
+//! Integration tests for `jin rm` command
+//!
+//! Tests removing committed and staged files from a layer and the workspace.
+
+mod common;
+use common::assertions::*;
+use common::fixtures::*;
+use std::fs;
+use std::path::Path;
+
+/// Run a jin command in the fixture project and expect success
+fn run(fixture: &TestFixture, args: &[&str]) {
+    jin()
+        .args(args)
+        .current_dir(fixture.path())
+        .env("JIN_DIR", fixture.jin_dir.as_ref().unwrap())
+        .assert()
+        .success();
+}
+
+/// Commit `file` to a fresh mode's layer
+fn commit_to_mode(fixture: &TestFixture, file: &str) {
+    let mode_name = format!("test_mode_{}", unique_test_id());
+    create_mode(&mode_name, fixture.jin_dir.as_ref()).unwrap();
+    run(fixture, &["mode", "use", &mode_name]);
+
+    fs::write(fixture.path.join(file), r#"{"key": "value"}"#).unwrap();
+    run(fixture, &["add", file, "--mode"]);
+    run(fixture, &["commit", "-m", "Add file"]);
+}
+
+fn jinmap(jin_dir: &Path) -> String {
+    fs::read_to_string(jin_dir.join(".jinmap")).unwrap_or_default()
+}
+
+/// Test removing a committed file with --cached keeps the workspace copy
+#[test]
+fn test_rm_cached_removes_committed_file_from_layer() {
+    let fixture = setup_test_repo().unwrap();
+    commit_to_mode(&fixture, "config.json");
+    assert!(jinmap(fixture.jin_dir.as_ref().unwrap()).contains("config.json"));
+
+    jin()
+        .args(["rm", "--mode", "--cached", "config.json"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", fixture.jin_dir.as_ref().unwrap())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Removed 1 file(s) from mode-base layer",
+        ));
+
+    assert_staging_contains(fixture.path(), "config.json", fixture.jin_dir.as_deref());
+    assert_workspace_file_exists(fixture.path(), "config.json");
+
+    // The commit drops the file from the layer and from .jinmap
+    run(&fixture, &["commit", "-m", "Remove file"]);
+    assert!(!jinmap(fixture.jin_dir.as_ref().unwrap()).contains("config.json"));
+}
+
+/// Test removing a committed file with --force deletes the workspace copy
+#[test]
+fn test_rm_force_deletes_workspace_copy() {
+    let fixture = setup_test_repo().unwrap();
+    commit_to_mode(&fixture, "settings.json");
+
+    run(&fixture, &["rm", "--mode", "--force", "settings.json"]);
+
+    assert_staging_contains(fixture.path(), "settings.json", fixture.jin_dir.as_deref());
+    assert_workspace_file_not_exists(fixture.path(), "settings.json");
+}
+
+/// Test that a file outside the target layer is rejected
+#[test]
+fn test_rm_file_not_in_layer() {
+    let fixture = setup_test_repo().unwrap();
+    commit_to_mode(&fixture, "config.json");
+
+    // Committed to the mode layer, not the project-base layer
+    jin()
+        .args(["rm", "--cached", "config.json"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", fixture.jin_dir.as_ref().unwrap())
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "File not in project-base layer or staging",
+        ));
+    assert_workspace_file_exists(fixture.path(), "config.json");
+}
@@ -161,6 +161,61 @@ fn test_commit_creates_layer_commit() -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
+/// Test that commit delivers a notification event to the configured program
+#[cfg(unix)]
+#[test]
+fn test_commit_notifies_configured_exec() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    let payload_path = project_path.join("event.json");
+    jin()
+        .args([
+            "config",
+            "set",
+            "notify.exec",
+            &format!("cat > '{}'", payload_path.display()),
+        ])
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    let mode_name = format!("test_mode_{}", unique_test_id());
+    create_mode(&mode_name, Some(jin_dir))?;
+    jin()
+        .args(["mode", "use", &mode_name])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    fs::write(project_path.join("test.txt"), "content")?;
+    jin()
+        .args(["add", "test.txt", "--mode"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Notify commit"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    let payload: serde_json::Value = serde_json::from_str(&fs::read_to_string(&payload_path)?)?;
+    assert_eq!(payload["schema_version"], 1);
+    assert_eq!(payload["event"], "commit");
+    assert_eq!(payload["mode"], mode_name.as_str());
+    assert_eq!(payload["files"][0], "test.txt");
+    assert!(payload["commits"]["mode-base"].is_string());
+
+    Ok(())
+}
+
 /// Test that apply merges to workspace
 #[test]
 fn test_apply_merges_to_workspace() -> Result<(), Box<dyn std::error::Error>> {
@@ -231,6 +286,84 @@ fn test_apply_merges_to_workspace() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Test that apply runs reload hooks for changed files only
+#[cfg(unix)]
+#[test]
+fn test_apply_runs_reload_hooks() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+    let log_path = project_path.join("reload.log");
+    fs::write(
+        jin_dir.join("config.toml"),
+        format!(
+            "[[reload]]\npattern = \"kitty.conf\"\ncommand = \"echo \\\"$JIN_FILES\\\" >> '{}'\"\n",
+            log_path.display()
+        ),
+    )?;
+
+    // A second project sharing the mode makes the changes
+    let other = tempfile::TempDir::new()?;
+    jin_init(other.path(), Some(jin_dir))?;
+    let mode_name = format!("test_mode_{}", unique_test_id());
+    create_mode(&mode_name, Some(jin_dir))?;
+    for project in [project_path, other.path()] {
+        jin()
+            .args(["mode", "use", &mode_name])
+            .current_dir(project)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+    }
+    let commit = |content: &str| {
+        for file in ["kitty.conf", "other.conf"] {
+            fs::write(other.path().join(file), content).unwrap();
+        }
+        jin()
+            .args(["add", "kitty.conf", "other.conf", "--mode"])
+            .current_dir(other.path())
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+        jin()
+            .args(["commit", "-m", content])
+            .current_dir(other.path())
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+    };
+    let apply = |extra: &[&str]| {
+        jin()
+            .arg("apply")
+            .args(extra)
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success()
+    };
+    let runs = || fs::read_to_string(&log_path).unwrap_or_default();
+
+    commit("font_size = 12");
+    apply(&[]).stdout(predicate::str::contains("Reloading: echo"));
+    assert_eq!(runs(), "kitty.conf\n");
+
+    // Unchanged files trigger nothing
+    apply(&[]).stdout(predicate::str::contains("Reloading").not());
+    assert_eq!(runs(), "kitty.conf\n");
+
+    commit("font_size = 14");
+    apply(&["--no-reload"]).stdout(predicate::str::contains("Reloading").not());
+    assert_eq!(runs(), "kitty.conf\n");
+
+    commit("font_size = 16");
+    apply(&[]).stdout(predicate::str::contains("Reloading: echo"));
+    assert_eq!(runs(), "kitty.conf\nkitty.conf\n");
+
+    Ok(())
+}
+
 /// Test complete workflow from init to apply
 #[test]
 fn test_complete_workflow_init_to_apply() -> Result<(), Box<dyn std::error::Error>> {
@@ -461,3 +594,1847 @@ fn test_init_already_initialized() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Test that `jin audit export` emits normalized records for commits
+#[test]
+fn test_audit_export_after_commit() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    fs::write(project_path.join("audit.json"), r#"{"a": 1}"#)?;
+    jin()
+        .args(["add", "audit.json"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Audited commit"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    let output = jin()
+        .args([
+            "audit",
+            "export",
+            "--format",
+            "jsonl",
+            "--since",
+            "2000-01-01",
+        ])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let record: serde_json::Value = serde_json::from_str(stdout.lines().next().unwrap())?;
+    assert_eq!(record["layer"], 7);
+    assert!(record["layer_ref"]
+        .as_str()
+        .unwrap()
+        .starts_with("refs/jin/layers/project/"));
+    assert!(record["commit"].is_string());
+    assert_eq!(record["files"][0], "audit.json");
+
+    jin()
+        .args([
+            "audit",
+            "export",
+            "--format",
+            "cef",
+            "--since",
+            "2999-01-01",
+        ])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout("");
+
+    Ok(())
+}
+
+/// Test that staging a file merged from an outdated layer state warns
+#[test]
+fn test_add_warns_when_layer_moved_since_apply() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+    let other = tempfile::TempDir::new()?;
+
+    let mode_name = format!("stale_{}", unique_test_id());
+    create_mode(&mode_name, Some(jin_dir))?;
+
+    for dir in [project_path, other.path()] {
+        jin_init(dir, Some(jin_dir))?;
+        jin()
+            .args(["mode", "use", &mode_name])
+            .current_dir(dir)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+    }
+
+    // First project commits and applies the mode config
+    fs::write(project_path.join("settings.json"), r#"{"theme": "dark"}"#)?;
+    jin()
+        .args(["add", "settings.json", "--mode"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Add settings"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["apply", "--force"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    // Another project moves the mode layer forward
+    fs::write(other.path().join("settings.json"), r#"{"theme": "light"}"#)?;
+    jin()
+        .args(["add", "settings.json", "--mode"])
+        .current_dir(other.path())
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Switch theme"])
+        .current_dir(other.path())
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    // Re-staging the stale merged copy warns on add and commit
+    fs::write(
+        project_path.join("settings.json"),
+        r#"{"theme": "dark", "x": 1}"#,
+    )?;
+    jin()
+        .args(["add", "settings.json", "--mode"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("generated from an older merge"))
+        .stderr(predicate::str::contains(format!("mode/{}", mode_name)));
+    jin()
+        .args(["commit", "-m", "Stale commit"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("generated from an older merge"));
+
+    Ok(())
+}
+
+/// Test that verify passes after a clean apply and reports workspace edits
+#[test]
+fn test_verify_reports_modified_applied_file() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    fs::write(project_path.join("settings.json"), r#"{"a": 1}"#)?;
+    jin()
+        .args(["add", "settings.json", "--global"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Add settings"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["apply", "--force"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    jin()
+        .arg("verify")
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("All hashes verified."));
+
+    fs::write(project_path.join("settings.json"), r#"{"a": 2}"#)?;
+    let output = jin()
+        .args(["verify", "--format", "json"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let report: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(report["issues"][0]["kind"], "workspace-modified");
+    assert_eq!(report["issues"][0]["path"], "settings.json");
+    assert!(report["blobs_checked"].as_u64().unwrap() >= 1);
+
+    Ok(())
+}
+
+/// Test that diff --name-only lists drifted paths and --exit-code gates on them
+#[test]
+fn test_diff_name_only_and_exit_code() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    fs::write(project_path.join("settings.json"), r#"{"a": 1}"#)?;
+    jin()
+        .args(["add", "settings.json", "--global"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Add settings"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["apply", "--force"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    jin()
+        .args(["diff", "--name-only", "--exit-code"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .code(0)
+        .stdout("");
+
+    fs::write(project_path.join("settings.json"), r#"{"a": 2}"#)?;
+    jin()
+        .args(["diff", "--name-only", "--exit-code"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .code(1)
+        .stdout("settings.json\n");
+
+    // The profile report is still written when differences set the status
+    jin()
+        .args(["--profile=json", "diff", "--name-only", "--exit-code"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("\"total_ms\""));
+
+    // Errors are told apart from differences
+    jin()
+        .args(["diff", "no-such-layer", "--exit-code"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Error:"));
+
+    // Without --exit-code, differences still succeed
+    jin()
+        .args(["diff", "--name-only"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+/// Test that .jinattributes sets mode bits on applied files
+#[cfg(unix)]
+#[test]
+fn test_apply_sets_mode_from_jinattributes() -> Result<(), Box<dyn std::error::Error>> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    fs::write(project_path.join("credentials.json"), r#"{"token": "x"}"#)?;
+    fs::write(project_path.join("settings.json"), r#"{"a": 1}"#)?;
+    jin()
+        .args(["add", "credentials.json", "settings.json", "--global"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Add files"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    fs::write(
+        project_path.join(".jinattributes"),
+        "# secrets stay private\ncredentials.json mode=0600\n",
+    )?;
+    jin()
+        .args(["apply", "--force"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    let mode = |name: &str| -> std::io::Result<u32> {
+        Ok(fs::metadata(project_path.join(name))?.permissions().mode() & 0o777)
+    };
+    assert_eq!(mode("credentials.json")?, 0o600);
+    assert_eq!(mode("settings.json")?, 0o644);
+
+    Ok(())
+}
+
+/// Test that .jinattributes committed to the global layer is honored on a
+/// fresh checkout, and that .jinattributes.local overrides it
+#[cfg(unix)]
+#[test]
+fn test_shared_jinattributes_with_local_override() -> Result<(), Box<dyn std::error::Error>> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    fs::write(project_path.join("credentials.json"), r#"{"token": "x"}"#)?;
+    fs::write(
+        project_path.join(".jinattributes"),
+        "credentials.json mode=0600\n",
+    )?;
+    jin()
+        .args(["add", "credentials.json", ".jinattributes", "--global"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Share attributes"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    // Another machine has no workspace copy yet; the layer's rules apply
+    fs::remove_file(project_path.join("credentials.json"))?;
+    fs::remove_file(project_path.join(".jinattributes"))?;
+    jin()
+        .args(["apply", "--force"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    let mode = |name: &str| -> std::io::Result<u32> {
+        Ok(fs::metadata(project_path.join(name))?.permissions().mode() & 0o777)
+    };
+    assert_eq!(mode("credentials.json")?, 0o600);
+    assert!(project_path.join(".jinattributes").exists());
+
+    fs::write(
+        project_path.join(".jinattributes.local"),
+        "credentials.json mode=0640\n",
+    )?;
+    jin()
+        .arg("apply")
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    assert_eq!(mode("credentials.json")?, 0o640);
+
+    Ok(())
+}
+
+/// Test that apply.exclude keeps matching merged paths out of the workspace
+#[test]
+fn test_apply_exclude_skips_matching_paths() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    fs::create_dir_all(project_path.join(".idea"))?;
+    fs::write(project_path.join(".idea/workspace.xml"), "<project/>\n")?;
+    fs::write(project_path.join("settings.json"), r#"{"a": 1}"#)?;
+    jin()
+        .args(["add", ".idea/workspace.xml", "settings.json", "--global"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Add editor config"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    fs::remove_dir_all(project_path.join(".idea"))?;
+    fs::remove_file(project_path.join("settings.json"))?;
+
+    jin()
+        .args(["config", "set", "apply.exclude", ".idea/"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    jin()
+        .args(["apply", "--dry-run"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Excluded files (apply.exclude):"))
+        .stdout(predicate::str::contains("x .idea/workspace.xml (.idea/)"))
+        .stdout(predicate::str::contains("+ .idea/workspace.xml").not());
+
+    jin()
+        .arg("apply")
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Excluded: 1 (apply.exclude)"));
+
+    assert!(project_path.join("settings.json").exists());
+    assert!(!project_path.join(".idea").exists());
+
+    Ok(())
+}
+
+/// Test adopting existing workspace files into inferred layers
+#[test]
+fn test_adopt_existing_files() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    let mode_name = format!("adopt_mode_{}", unique_test_id());
+    jin()
+        .args(["mode", "create", &mode_name])
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["mode", "use", &mode_name])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    fs::write(project_path.join("CLAUDE.md"), "# Instructions\n")?;
+    fs::write(project_path.join(".env.local"), "TOKEN=abc\n")?;
+
+    jin()
+        .args(["adopt", "CLAUDE.md", "--dry-run"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "Adopting CLAUDE.md into '{}' (mode) layer",
+            mode_name
+        )))
+        .stdout(predicate::str::contains("Dry run"));
+
+    jin()
+        .args(["adopt", "./CLAUDE.md"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("CLAUDE.md is now managed by Jin"));
+
+    jin()
+        .args(["adopt", ".env.local"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Adopting .env.local into user-local layer (machine-specific file)",
+        ))
+        .stdout(predicate::str::contains(".env.local is now managed by Jin"));
+
+    assert_eq!(
+        fs::read_to_string(project_path.join("CLAUDE.md"))?,
+        "# Instructions\n"
+    );
+
+    jin()
+        .args(["adopt", "CLAUDE.md"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already managed"));
+
+    Ok(())
+}
+
+/// Test applying files outside the workspace via .jintargets and --target
+#[test]
+fn test_apply_to_targets_outside_workspace() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+    let home = tempfile::TempDir::new()?;
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    fs::create_dir_all(project_path.join("nvim"))?;
+    fs::write(project_path.join("nvim/init.lua"), "vim.o.number = true\n")?;
+    fs::write(project_path.join("settings.json"), r#"{"a": 1}"#)?;
+    jin()
+        .args(["add", "nvim/init.lua", "settings.json", "--global"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Add dotfiles"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    fs::remove_dir_all(project_path.join("nvim"))?;
+
+    // Per-file mapping: only mapped files leave the workspace
+    fs::write(
+        project_path.join(".jintargets"),
+        format!("nvim/ {}/.config/nvim/\n", home.path().display()),
+    )?;
+    jin()
+        .arg("apply")
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Outside workspace: 1"));
+    assert_eq!(
+        fs::read_to_string(home.path().join(".config/nvim/init.lua"))?,
+        "vim.o.number = true\n"
+    );
+    assert!(!project_path.join("nvim/init.lua").exists());
+    assert!(project_path.join("settings.json").exists());
+
+    // Drift is detected at the destination, not the storage path
+    jin()
+        .arg("apply")
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    fs::write(home.path().join(".config/nvim/init.lua"), "edited\n")?;
+    jin()
+        .arg("apply")
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("uncommitted changes"));
+
+    // --target redirects every file under one directory
+    fs::write(
+        home.path().join(".config/nvim/init.lua"),
+        "vim.o.number = true\n",
+    )?;
+    let target = tempfile::TempDir::new()?;
+    jin()
+        .arg("apply")
+        .arg("--target")
+        .arg(target.path())
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Outside workspace: 2"));
+    assert!(target.path().join("nvim/init.lua").exists());
+    assert!(target.path().join("settings.json").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_check_idempotent_merged_files() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+    let mode_name = format!("check_{}", unique_test_id());
+
+    jin_init(project_path, Some(jin_dir))?;
+    create_mode(&mode_name, Some(jin_dir))?;
+    jin()
+        .args(["mode", "use", &mode_name])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    fs::write(
+        project_path.join("settings.json"),
+        r#"{"z": 1, "ratio": 0.5, "nested": {"b": true, "a": [1, 2]}}"#,
+    )?;
+    fs::write(
+        project_path.join("config.toml"),
+        "title = \"app\"\n\n[server]\nport = 8080\nscale = 1.0\n",
+    )?;
+    fs::write(
+        project_path.join("app.yaml"),
+        "name: app\nitems:\n  - a\n  - b\n",
+    )?;
+    jin()
+        .args([
+            "add",
+            "settings.json",
+            "config.toml",
+            "app.yaml",
+            "--global",
+        ])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Add configs"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    fs::write(project_path.join("settings.json"), r#"{"ratio": 0.75}"#)?;
+    jin()
+        .args(["add", "settings.json", "--mode"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Override ratio"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    jin()
+        .args(["check", "--idempotent"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Idempotent: 3 merged file(s) are stable",
+        ));
+
+    Ok(())
+}
+
+/// Test that jin dedupe reports a file duplicated across modes and stages
+/// its consolidation into global
+#[test]
+fn test_dedupe_consolidates_duplicated_file() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+    let id = unique_test_id();
+    let modes = [format!("dedupe_a_{}", id), format!("dedupe_b_{}", id)];
+
+    jin_init(project_path, Some(jin_dir))?;
+    fs::write(project_path.join("settings.json"), r#"{"theme": "dark"}"#)?;
+    for mode in &modes {
+        create_mode(mode, Some(jin_dir))?;
+        for args in [
+            vec!["mode", "use", mode],
+            vec!["add", "settings.json", "--mode"],
+            vec!["commit", "-m", "Add settings"],
+        ] {
+            jin()
+                .args(&args)
+                .current_dir(project_path)
+                .env("JIN_DIR", jin_dir)
+                .assert()
+                .success();
+        }
+    }
+
+    jin()
+        .args(["dedupe", "--report"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("identical in 2 layers"))
+        .stdout(predicate::str::contains(format!(
+            "move it to global and remove it from mode/{}, mode/{}",
+            modes[0], modes[1]
+        )));
+
+    jin()
+        .args(["dedupe", "--fix"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Staged settings.json for global"));
+    jin()
+        .args(["commit", "-m", "Consolidate settings"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    jin()
+        .arg("dedupe")
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("identical in 3 layers"))
+        .stdout(predicate::str::contains(format!(
+            "keep it in global and remove it from mode/{}, mode/{}",
+            modes[0], modes[1]
+        )));
+
+    Ok(())
+}
+
+/// Test that jin run hands merged files and env vars to a child process
+/// without writing them to the workspace
+#[test]
+fn test_run_with_merged_environment() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+    let mode_name = format!("run_{}", unique_test_id());
+
+    jin_init(project_path, Some(jin_dir))?;
+    create_mode(&mode_name, Some(jin_dir))?;
+    jin()
+        .args(["mode", "use", &mode_name])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    fs::write(project_path.join(".env"), "GREETING=hello\nTARGET=dev\n")?;
+    fs::write(project_path.join("notes.txt"), "shared notes\n")?;
+    jin()
+        .args(["add", ".env", "notes.txt", "--mode"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Add env"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    fs::remove_file(project_path.join(".env"))?;
+    fs::remove_file(project_path.join("notes.txt"))?;
+    jin()
+        .args(["mode", "unset"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    jin()
+        .args([
+            "run", "--mode", &mode_name, "--only", "*.txt", "--only", ".env",
+        ])
+        .args([
+            "--",
+            "sh",
+            "-c",
+            r#"echo "$GREETING $TARGET"; cat "$JIN_RUN_DIR/notes.txt""#,
+        ])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hello dev"))
+        .stdout(predicate::str::contains("shared notes"));
+    assert!(!project_path.join(".env").exists());
+    assert!(!project_path.join("notes.txt").exists());
+
+    jin()
+        .args(["run", "--mode", &mode_name, "--", "sh", "-c", "exit 3"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .code(3);
+
+    Ok(())
+}
+
+/// Test that .jinenv variables compose across layers and are never applied
+/// as a file
+#[test]
+fn test_env_layers_compose() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+    let mode_name = format!("env_{}", unique_test_id());
+
+    jin_init(project_path, Some(jin_dir))?;
+    create_mode(&mode_name, Some(jin_dir))?;
+    jin()
+        .args(["mode", "use", &mode_name])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    for (content, flag) in [
+        ("API_URL=https://prod.example.com\nDEBUG=1\n", "--global"),
+        (
+            "API_URL=https://staging.example.com\nunset DEBUG\nLEVEL=\"debug all\"\n",
+            "--mode",
+        ),
+    ] {
+        fs::write(project_path.join(".jinenv"), content)?;
+        jin()
+            .args(["add", ".jinenv", flag])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+        jin()
+            .args(["commit", "-m", "Env"])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+    }
+    fs::remove_file(project_path.join(".jinenv"))?;
+
+    jin()
+        .args(["env", "show"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "API_URL  https://staging.example.com  (mode-base)",
+        ))
+        .stdout(predicate::str::contains("DEBUG").not());
+
+    jin()
+        .args(["env", "export", "--format", "shell"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout("export API_URL='https://staging.example.com'\nexport LEVEL='debug all'\n");
+
+    jin()
+        .args(["run", "--", "sh", "-c", "echo \"$LEVEL at $API_URL\""])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "debug all at https://staging.example.com",
+        ));
+
+    jin()
+        .args(["apply", "--force"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    assert!(!project_path.join(".jinenv").exists());
+
+    Ok(())
+}
+
+/// Test that `jin prompt` reports staged files and notices layer updates
+/// made after the last apply
+#[test]
+fn test_prompt_tracks_staging_and_layer_updates() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+    let mode_name = format!("prompt_{}", unique_test_id());
+    let prompt = || {
+        let output = jin()
+            .arg("prompt")
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    };
+
+    jin()
+        .arg("prompt")
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout("");
+
+    jin_init(project_path, Some(jin_dir))?;
+    create_mode(&mode_name, Some(jin_dir))?;
+    jin()
+        .args(["mode", "use", &mode_name])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    assert_eq!(prompt(), mode_name);
+
+    fs::write(project_path.join("settings.json"), r#"{"a": 1}"#)?;
+    jin()
+        .args(["add", "settings.json", "--mode"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    assert_eq!(prompt(), format!("{} +1", mode_name));
+
+    jin()
+        .args(["commit", "-m", "Add settings"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["apply", "--force"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    assert_eq!(prompt(), mode_name);
+    assert!(project_path.join(".jin/state_cache").exists());
+
+    fs::write(project_path.join("settings.json"), r#"{"a": 2}"#)?;
+    jin()
+        .args(["add", "settings.json", "--mode"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Update settings"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    assert_eq!(prompt(), format!("{} *", mode_name));
+
+    Ok(())
+}
+
+/// Test that applied files follow the `[style]` serializer settings
+#[test]
+fn test_applied_files_follow_style_config() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    fs::write(
+        project_path.join("app.json"),
+        r#"{"zeta": 1, "alpha": [1]}"#,
+    )?;
+    fs::write(project_path.join("app.yaml"), "name: app\nports:\n- 80\n")?;
+    for file in ["app.json", "app.yaml"] {
+        jin()
+            .args(["add", file, "--global"])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+    }
+    jin()
+        .args(["commit", "-m", "Add styled files"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    for (key, value) in [
+        ("style.json-indent", "tab"),
+        ("style.key-order", "preserve"),
+        ("style.yaml-indent", "4"),
+        ("style.yaml-quotes", "double"),
+    ] {
+        jin()
+            .args(["config", "set", key, value])
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+    }
+    jin()
+        .args(["config", "get", "style.yaml-quotes"])
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("double"));
+
+    jin()
+        .args(["apply", "--force"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(project_path.join("app.yaml"))?,
+        "name: \"app\"\nports:\n    -   80\n"
+    );
+    assert_eq!(
+        fs::read_to_string(project_path.join("app.json"))?,
+        "{\n\t\"zeta\": 1,\n\t\"alpha\": [\n\t\t1\n\t]\n}"
+    );
+
+    Ok(())
+}
+
+/// Test that a line-ending policy hides CRLF/LF differences between layers
+///
+/// Under the default `preserve` policy the two layers would conflict.
+#[test]
+fn test_eol_policy_normalizes_layers() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    // The same notes committed from Windows (global) and Unix (local)
+    for (content, flag) in [("one\r\ntwo\r\n", "--global"), ("one\ntwo\n", "--local")] {
+        fs::write(project_path.join("notes.txt"), content)?;
+        jin()
+            .args(["add", "notes.txt", flag])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+        jin()
+            .args(["commit", "-m", "Add notes"])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+    }
+    jin()
+        .args(["config", "set", "eol.policy", "crlf"])
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["apply", "--force"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(project_path.join("notes.txt"))?,
+        "one\r\ntwo\r\n"
+    );
+    jin()
+        .args(["diff", "--name-only"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("notes.txt").not());
+
+    Ok(())
+}
+
+/// Test that keys differing only in Unicode form merge, and a BOM survives
+#[test]
+fn test_unicode_keys_merge_and_bom_is_kept() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    // Decomposed key from one machine, composed key with a BOM from another
+    for (content, flag) in [
+        ("{\"cafe\u{301}\": 1, \"name\": \"global\"}", "--global"),
+        ("\u{feff}{\"caf\u{e9}\": 2}", "--local"),
+    ] {
+        fs::write(project_path.join("menu.json"), content)?;
+        jin()
+            .args(["add", "menu.json", flag])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+        jin()
+            .args(["commit", "-m", "Add menu"])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+    }
+
+    jin()
+        .args(["apply", "--force"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    let applied = fs::read_to_string(project_path.join("menu.json"))?;
+    let json = applied
+        .strip_prefix('\u{feff}')
+        .expect("BOM should be preserved");
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    assert_eq!(
+        value,
+        serde_json::json!({ "caf\u{e9}": 2, "name": "global" })
+    );
+
+    Ok(())
+}
+
+/// Test that `jin sed` stages a replacement across layers for commit
+#[test]
+fn test_sed_rotates_value_across_layers() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    for (file, content, flag) in [
+        (
+            "registry.json",
+            r#"{"registry": {"url": "https://old.example.com"}}"#,
+            "--global",
+        ),
+        (".npmrc", "registry=https://old.example.com/\n", "--local"),
+    ] {
+        fs::write(project_path.join(file), content)?;
+        jin()
+            .args(["add", file, flag])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+        jin()
+            .args(["commit", "-m", "Add registry"])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+    }
+
+    let sed = ["sed", r"old\.example\.com", "new.example.com"];
+    jin()
+        .args(sed)
+        .arg("--dry-run")
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--- local/.npmrc"))
+        .stdout(predicate::str::contains(
+            "+registry=https://new.example.com/",
+        ))
+        .stdout(predicate::str::contains(
+            "2 file(s) in 2 layer(s) would change",
+        ));
+    jin()
+        .args(["status"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("registry.json").not());
+
+    jin()
+        .args(sed)
+        .args(["--layer", "global-base"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Staged registry.json in global"))
+        .stdout(predicate::str::contains(".npmrc").not());
+    jin()
+        .args(sed)
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Staged .npmrc in local"))
+        .stdout(predicate::str::contains("already staged"));
+    jin()
+        .args(["commit", "-m", "Rotate registry"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["apply", "--force"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    assert!(
+        fs::read_to_string(project_path.join("registry.json"))?.contains("https://new.example.com")
+    );
+    assert_eq!(
+        fs::read_to_string(project_path.join(".npmrc"))?,
+        "registry=https://new.example.com/\n"
+    );
+
+    // Nothing is left to replace
+    jin()
+        .args(sed)
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No matches"));
+
+    Ok(())
+}
+
+/// Test that an expired user-local override stops being applied
+#[test]
+fn test_expired_local_override_is_not_applied() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success()
+    };
+
+    fs::write(project_path.join("debug.json"), r#"{"debug": false}"#)?;
+    run(&["add", "debug.json", "--global"]);
+    run(&["commit", "-m", "Add debug config"]);
+
+    // An override that expires right away
+    fs::write(project_path.join("debug.json"), r#"{"debug": true}"#)?;
+    run(&["add", "debug.json", "--local", "--expires", "0h"])
+        .stdout(predicate::str::contains("The override expires"));
+    run(&["commit", "-m", "Debug locally"]);
+
+    run(&["apply", "--force"]).stderr(predicate::str::contains(
+        "Warning: Local override debug.json expired",
+    ));
+    let applied = fs::read_to_string(project_path.join("debug.json"))?;
+    assert!(applied.contains("false"), "{}", applied);
+    run(&["status"]).stdout(predicate::str::contains("1 expired local override"));
+
+    // Renewing brings the override back
+    fs::write(
+        project_path.join("debug.json"),
+        r#"{"debug": true, "verbose": 1}"#,
+    )?;
+    run(&["add", "debug.json", "--local", "--expires", "7d"]);
+    run(&["commit", "-m", "Renew debug override"]);
+    fs::write(project_path.join("debug.json"), &applied)?;
+    run(&["apply"]).stderr(predicate::str::contains("expired").not());
+    let applied = fs::read_to_string(project_path.join("debug.json"))?;
+    assert!(applied.contains("true"), "{}", applied);
+    run(&["status"]).stdout(predicate::str::contains("expired local override").not());
+
+    // --expires only applies to the user-local layer
+    jin()
+        .args(["add", "debug.json", "--global", "--expires", "7d"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .failure();
+
+    Ok(())
+}
+
+/// Test that apply --provenance records the source layer of files and keys
+#[test]
+fn test_apply_writes_provenance_map() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success()
+    };
+
+    let mode_name = format!("test_mode_{}", unique_test_id());
+    run(&["mode", "create", &mode_name]);
+    run(&["mode", "use", &mode_name]);
+
+    fs::write(
+        project_path.join("settings.json"),
+        r#"{"theme": "dark", "model": "small"}"#,
+    )?;
+    run(&["add", "settings.json", "--global"]);
+    run(&["commit", "-m", "Global settings"]);
+    fs::write(project_path.join("settings.json"), r#"{"model": "large"}"#)?;
+    run(&["add", "settings.json", "--mode"]);
+    run(&["commit", "-m", "Mode settings"]);
+    fs::write(project_path.join("notes.txt"), "hello\n")?;
+    run(&["add", "notes.txt", "--global"]);
+    run(&["commit", "-m", "Notes"]);
+    fs::remove_file(project_path.join("settings.json"))?;
+    fs::remove_file(project_path.join("notes.txt"))?;
+
+    run(&["apply", "--provenance"]);
+    let path = project_path.join(".jin/provenance.json");
+    let provenance: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    let settings = &provenance["files"]["settings.json"];
+    let mode_layer = format!("mode/{}", mode_name);
+    assert_eq!(settings["layer"], mode_layer.as_str());
+    assert_eq!(settings["keys"]["model"]["layer"], mode_layer.as_str());
+    assert_eq!(settings["keys"]["theme"]["layer"], "global");
+    assert_eq!(
+        settings["keys"]["theme"]["commit"].as_str().unwrap().len(),
+        40
+    );
+    let notes = &provenance["files"]["notes.txt"];
+    assert_eq!(notes["layer"], "global");
+    assert!(notes.get("keys").is_none());
+
+    // Without the flag (or apply.provenance) a stale map is removed
+    run(&["apply"]);
+    assert!(!path.exists());
+
+    run(&["config", "set", "apply.provenance", "true"]);
+    run(&["apply"]);
+    assert!(path.exists());
+
+    Ok(())
+}
+
+/// Test that --merge-opt adjusts the merge for one apply
+#[test]
+fn test_apply_merge_options() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success()
+    };
+
+    let mode_name = format!("test_mode_{}", unique_test_id());
+    run(&["mode", "create", &mode_name]);
+    run(&["mode", "use", &mode_name]);
+
+    let path = project_path.join("servers.json");
+    fs::write(
+        &path,
+        r#"{"servers": [{"name": "a", "port": 1}], "debug": true}"#,
+    )?;
+    run(&["add", "servers.json", "--global"]);
+    run(&["commit", "-m", "Global servers"]);
+    fs::write(
+        &path,
+        r#"{"servers": [{"name": "b", "port": 2}], "debug": null}"#,
+    )?;
+    run(&["add", "servers.json", "--mode"]);
+    run(&["commit", "-m", "Mode servers"]);
+    fs::remove_file(&path)?;
+
+    run(&["apply"]);
+    let merged: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    assert_eq!(merged["servers"].as_array().unwrap().len(), 2);
+    assert!(merged.get("debug").is_none());
+
+    run(&[
+        "apply",
+        "--merge-opt",
+        "keyed_arrays=false",
+        "--merge-opt",
+        "nulls=keep",
+    ]);
+    let merged: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    assert_eq!(
+        merged["servers"],
+        serde_json::json!([{"name": "b", "port": 2}])
+    );
+    assert!(merged["debug"].is_null());
+
+    jin()
+        .args(["apply", "--dry-run", "--merge-opt", "nulls=drop"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Use 'delete' or 'keep'"));
+
+    Ok(())
+}
+
+/// Test that staging an unmodified merged file warns about a merge artifact
+#[test]
+fn test_add_warns_about_merged_artifact() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success()
+    };
+
+    let mode_name = format!("test_mode_{}", unique_test_id());
+    run(&["mode", "create", &mode_name]);
+    run(&["mode", "use", &mode_name]);
+
+    fs::write(project_path.join("settings.json"), r#"{"theme": "dark"}"#)?;
+    run(&["add", "settings.json", "--global"]);
+    run(&["commit", "-m", "Global settings"]);
+    fs::write(project_path.join("settings.json"), r#"{"model": "large"}"#)?;
+    run(&["add", "settings.json", "--mode"]);
+    run(&["commit", "-m", "Mode settings"]);
+    fs::remove_file(project_path.join("settings.json"))?;
+    run(&["apply"]);
+
+    // The applied file combines both layers
+    run(&["add", "settings.json", "--mode"])
+        .stderr(predicate::str::contains(
+            "settings.json is the merged output of",
+        ))
+        .stderr(predicate::str::contains("jin sed --layer mode-base"));
+    run(&["reset"]);
+
+    // A file holding one layer's own version is not an artifact
+    fs::write(project_path.join("settings.json"), r#"{"model": "large"}"#)?;
+    run(&["add", "settings.json", "--mode"])
+        .stderr(predicate::str::contains("merged output").not());
+
+    Ok(())
+}
+
+/// Test jin assert exit codes for passing, failing and broken assertions
+#[test]
+fn test_assert_checks_merged_config() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+    };
+
+    let mode_name = format!("test_mode_{}", unique_test_id());
+    run(&["mode", "create", &mode_name]).success();
+
+    fs::write(
+        project_path.join("settings.json"),
+        r#"{"editor.formatOnSave": false, "theme": "dark"}"#,
+    )?;
+    run(&["add", "settings.json", "--global"]).success();
+    run(&["commit", "-m", "Global settings"]).success();
+    run(&["mode", "use", &mode_name]).success();
+    fs::write(
+        project_path.join("settings.json"),
+        r#"{"editor.formatOnSave": true}"#,
+    )?;
+    run(&["add", "settings.json", "--mode"]).success();
+    run(&["commit", "-m", "Mode settings"]).success();
+    run(&["mode", "unset"]).success();
+
+    let context = format!("mode={}", mode_name);
+    let key = ["settings.json", "editor.formatOnSave"];
+    run(&["assert", "key-equals", key[0], key[1], "false"])
+        .success()
+        .stdout(predicate::str::contains("ok: settings.json"));
+    run(&["assert", "key-equals", key[0], key[1], "true"])
+        .code(1)
+        .stdout(predicate::str::contains("is false, expected true"));
+    run(&[
+        "assert",
+        "key-equals",
+        key[0],
+        key[1],
+        "true",
+        "--context",
+        &context,
+    ])
+    .success();
+    run(&["assert", "key-exists", "settings.json", "theme"]).success();
+    run(&["assert", "key-absent", "settings.json", "model"]).success();
+    run(&["assert", "key-absent", "settings.json", "theme"]).code(1);
+    run(&["assert", "managed", "settings.json"]).success();
+    run(&["assert", "unmanaged", "settings.json"]).code(1);
+    run(&["assert", "unmanaged", "README.md"]).success();
+    run(&["assert", "no-conflicts", "--context", &context]).success();
+
+    // Assertions that can't be evaluated exit with 2
+    run(&["assert", "no-conflicts", "--context", "flavor=x"]).code(2);
+
+    Ok(())
+}
+
+/// Test exporting a mode and importing it under another name
+#[test]
+fn test_mode_export_import_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+    };
+
+    let mode_name = format!("test_mode_{}", unique_test_id());
+    let copy_name = format!("{}_copy", mode_name);
+    run(&["mode", "create", &mode_name]).success();
+    run(&["scope", "create", "lang:rust", "--mode", &mode_name]).success();
+    run(&["mode", "use", &mode_name]).success();
+    run(&["scope", "use", "lang:rust"]).success();
+
+    fs::write(project_path.join("CLAUDE.md"), "# Rules\n\nBe brief.\n")?;
+    run(&["add", "CLAUDE.md", "--mode"]).success();
+    fs::write(project_path.join("lint.toml"), "strict = true\n")?;
+    run(&["add", "lint.toml", "--mode", "--scope", "lang:rust"]).success();
+    run(&["commit", "-m", "Mode files"]).success();
+
+    let archive = project_path.join("mode.yaml");
+    run(&[
+        "mode",
+        "export",
+        &mode_name,
+        "-o",
+        archive.to_str().unwrap(),
+    ])
+    .success()
+    .stdout(predicate::str::contains("2 layer(s), 2 file(s)"));
+    let exported = fs::read_to_string(&archive)?;
+    assert!(exported.contains("Be brief."), "{}", exported);
+
+    // The same name can't be imported twice
+    run(&["mode", "import", archive.to_str().unwrap()])
+        .failure()
+        .stderr(predicate::str::contains("--as"));
+    run(&[
+        "mode",
+        "import",
+        archive.to_str().unwrap(),
+        "--as",
+        &copy_name,
+    ])
+    .success()
+    .stdout(predicate::str::contains("2 layer(s), 2 file(s)"));
+
+    run(&["mode", "use", &copy_name]).success();
+    run(&["scope", "use", "lang:rust"]).success();
+    fs::remove_file(project_path.join("CLAUDE.md"))?;
+    fs::remove_file(project_path.join("lint.toml"))?;
+    run(&["apply"]).success();
+    assert_eq!(
+        fs::read_to_string(project_path.join("CLAUDE.md"))?,
+        "# Rules\n\nBe brief.\n"
+    );
+    assert!(fs::read_to_string(project_path.join("lint.toml"))?.contains("strict = true"));
+
+    Ok(())
+}
+
+#[test]
+fn test_branch_scope_follows_host_branch() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    // Host repository on main, with a feature branch
+    let host = git2::Repository::init(project_path)?;
+    let signature = git2::Signature::now("Test", "test@example.com")?;
+    let tree = host.find_tree(host.index()?.write_tree()?)?;
+    host.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])?;
+    let main = host.head()?.name().unwrap().to_string();
+    let head = host.head()?.peel_to_commit()?;
+    host.branch("feature/x", &head, false)?;
+
+    jin_init(project_path, Some(jin_dir))?;
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success()
+    };
+
+    let path = project_path.join("app.json");
+    fs::write(&path, r#"{"api": "prod"}"#)?;
+    run(&["add", "app.json", "--global"]);
+    run(&["commit", "-m", "Global app"]);
+    run(&["config", "set", "apply.branch_scopes", "true"]);
+
+    // Scope layer commits on the branch go to its scope
+    run(&["scope", "create", "branch:feature:x"]);
+    host.set_head("refs/heads/feature/x")?;
+    fs::write(&path, r#"{"api": "staging"}"#)?;
+    run(&["add", "app.json", "--scope=branch:feature:x"]);
+    run(&["commit", "-m", "Branch override"]);
+    fs::remove_file(&path)?;
+
+    run(&["apply"]).stdout(predicate::str::contains(
+        "Using scope 'branch:feature:x' for branch 'feature/x'",
+    ));
+    assert!(fs::read_to_string(&path)?.contains("staging"));
+    run(&["status"]).stdout(predicate::str::contains(
+        "Scope: branch:feature:x (branch feature/x)",
+    ));
+
+    host.set_head(&main)?;
+    run(&["apply"]);
+    assert!(fs::read_to_string(&path)?.contains("prod"));
+
+    Ok(())
+}
+
+#[test]
+fn test_read_only_commands_fall_back_when_repo_is_offline() -> Result<(), Box<dyn std::error::Error>>
+{
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success()
+    };
+
+    let path = project_path.join("app.json");
+    fs::write(&path, r#"{"api": "prod"}"#)?;
+    run(&["add", "app.json", "--global"]);
+    run(&["commit", "-m", "Global app"]);
+    fs::remove_file(&path)?;
+    run(&["apply"]);
+
+    // Simulate an unmounted network share
+    let unmounted = project_path.join(".jin_global_offline");
+    fs::rename(jin_dir, &unmounted)?;
+
+    run(&["status"])
+        .stdout(predicate::str::contains("is unavailable"))
+        .stdout(predicate::str::contains(
+            "Layer summary (as of the last apply):",
+        ))
+        .stdout(predicate::str::contains("jin/global/: 1 file"));
+    run(&["layers"])
+        .stderr(predicate::str::contains("is unavailable"))
+        .stdout(predicate::str::contains("global-base"));
+    let output = run(&["layers", "--format", "json"])
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    assert!(json["cached_at"].is_string());
+    assert_eq!(json["layers"][0]["files"], 1);
+
+    fs::rename(&unmounted, jin_dir)?;
+    let output = run(&["layers", "--format", "json"])
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    assert!(json.get("cached_at").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_concurrent_applies_are_queued_and_coalesced() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success()
+    };
+
+    let path = project_path.join("app.json");
+    fs::write(&path, r#"{"api": "prod"}"#)?;
+    run(&["add", "app.json", "--global"]);
+    run(&["commit", "-m", "Global app"]);
+    fs::remove_file(&path)?;
+
+    let spawn = || {
+        std::process::Command::new(env!("CARGO_BIN_EXE_jin"))
+            .args(["apply", "--debounce", "1500"])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+    };
+    let first = spawn()?;
+    let running = project_path.join(".jin/queue/running.json");
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while !running.exists() && std::time::Instant::now() < deadline {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    run(&["status", "--sections", "queue"])
+        .stdout(predicate::str::contains("Apply queue:"))
+        .stdout(predicate::str::contains(format!(
+            "running: jin apply (pid {}",
+            first.id()
+        )));
+
+    // Requested while the first is still settling, so the first covers it
+    let second = spawn()?;
+    let first = first.wait_with_output()?;
+    let second = second.wait_with_output()?;
+    assert!(first.status.success() && second.status.success());
+    assert!(String::from_utf8(first.stdout)?.contains("Applied 1 files"));
+    let second_out = String::from_utf8(second.stdout)?;
+    assert!(
+        second_out.contains("Workspace already applied by a later jin apply"),
+        "{}",
+        second_out
+    );
+    assert!(String::from_utf8(second.stderr)?.contains("Waiting for another jin apply"));
+    assert!(path.exists());
+
+    run(&["status", "--sections", "queue"]).stdout(predicate::str::contains("Apply queue").not());
+
+    Ok(())
+}
+
+#[test]
+fn test_badge_reports_path_state() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    jin_init(project_path, Some(jin_dir))?;
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success()
+    };
+
+    let path = project_path.join("app.json");
+    fs::write(&path, r#"{"api": "prod"}"#)?;
+    run(&["add", "app.json", "--global"]);
+    run(&["commit", "-m", "Global app"]);
+    fs::remove_file(&path)?;
+    run(&["apply"]);
+
+    run(&["badge", "app.json", "notes.txt"])
+        .stdout(predicate::str::contains("app.json: managed (global-base)"))
+        .stdout(predicate::str::contains("notes.txt: not managed"));
+
+    fs::write(&path, r#"{"api": "dev"}"#)?;
+    run(&["add", "app.json", "--global"]);
+    let output = run(&["badge", path.to_str().unwrap(), "--format", "json"])
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(json["schema_version"], 1);
+    let badge = &json["badges"][0];
+    assert_eq!(badge["path"], "app.json");
+    assert_eq!(badge["managed"], true);
+    assert_eq!(badge["drifted"], true);
+    assert_eq!(badge["staged"], "global-base");
+    assert_eq!(badge["conflicted"], false);
+    assert_eq!(badge["layers"], serde_json::json!(["global-base"]));
+
+    run(&["schema", "badge"]).stdout(predicate::str::contains("jin badge --format json"));
+
+    Ok(())
+}
@@ -5,7 +5,7 @@
 //! data loss and provide early detection of detached workspace states.
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // Test fixture helpers from common module
 mod common;
@@ -41,7 +41,7 @@ fn create_layer_ref(
 /// Helper to initialize a Jin project with repository and context
 fn init_jin_project(jin_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
     jin::git::JinRepo::create_at(jin_dir)?;
-    jin::core::config::ProjectContext::default().save()?;
+    jin::core::config::ProjectContext::default().save(Path::new("."))?;
     Ok(())
 }
 
@@ -67,7 +67,7 @@ fn setup_tracked_file(
     // Create metadata with the file
     let mut metadata = WorkspaceMetadata::new();
     metadata.add_file(PathBuf::from(file_path), hash.clone());
-    metadata.save()?;
+    metadata.save(Path::new("."))?;
 
     Ok(())
 }
@@ -78,8 +78,8 @@ fn setup_tracked_file(
 
 #[test]
 #[serial]
-fn test_reset_hard_rejected_when_files_modified() {
-    // Test that reset --hard is rejected when workspace files are modified externally
+fn test_reset_hard_restores_modified_files() {
+    // Modified applied files are what reset --hard restores, not a detached state
     let fixture = TestFixture::new().unwrap();
     let jin_dir = fixture.jin_dir.as_ref().unwrap().clone();
 
@@ -94,37 +94,33 @@ fn test_reset_hard_rejected_when_files_modified() {
     let original_content = b"original content";
     setup_tracked_file(&fixture, file_path, original_content).unwrap();
 
-    // Modify file externally to create detached state
+    // Modify file externally
     fs::write(fixture.path().join(file_path), b"modified content").unwrap();
 
-    // Attempt reset --hard, should be rejected
-    let result = jin::commands::reset::execute(jin::cli::ResetArgs {
-        soft: false,
-        mixed: false,
-        hard: true,
-        mode: false,
-        scope: None,
-        project: false,
-        global: false,
-        force: true, // Skip confirmation for test
-    });
+    let result = jin::commands::reset::execute(
+        jin::cli::ResetArgs {
+            soft: false,
+            mixed: false,
+            hard: true,
+            mode: false,
+            scope: None,
+            project: false,
+            global: false,
+            staged: Vec::new(),
+            force: true, // Skip confirmation for test
+        },
+        fixture.path(),
+    );
 
     assert!(
-        result.is_err(),
-        "reset --hard should fail when files are modified"
+        result.is_ok(),
+        "reset --hard should restore modified files, got: {:?}",
+        result
+    );
+    assert_eq!(
+        fs::read(fixture.path().join(file_path)).unwrap(),
+        original_content
     );
-
-    // Verify it's a DetachedWorkspace error
-    match result {
-        Err(jin::core::error::JinError::DetachedWorkspace { details, .. }) => {
-            assert!(
-                details.contains("modified") || details.contains("Workspace files"),
-                "Error should mention file modification, got: {}",
-                details
-            );
-        }
-        _ => panic!("Expected DetachedWorkspace error, got: {:?}", result),
-    }
 }
 
 #[test]
@@ -147,7 +143,7 @@ fn test_reset_hard_rejected_when_layer_refs_missing() {
     use jin::staging::WorkspaceMetadata;
     let mut metadata = WorkspaceMetadata::new();
     metadata.applied_layers = vec!["mode/dev".to_string()];
-    metadata.save().unwrap();
+    metadata.save(fixture.path()).unwrap();
 
     // Delete the mode reference to create detached state
     let git_repo = git2::Repository::open_bare(&jin_dir).unwrap();
@@ -158,16 +154,20 @@ fn test_reset_hard_rejected_when_layer_refs_missing() {
         .unwrap();
 
     // Attempt reset --hard, should be rejected
-    let result = jin::commands::reset::execute(jin::cli::ResetArgs {
-        soft: false,
-        mixed: false,
-        hard: true,
-        mode: false,
-        scope: None,
-        project: false,
-        global: false,
-        force: true,
-    });
+    let result = jin::commands::reset::execute(
+        jin::cli::ResetArgs {
+            soft: false,
+            mixed: false,
+            hard: true,
+            mode: false,
+            scope: None,
+            project: false,
+            global: false,
+            staged: Vec::new(),
+            force: true,
+        },
+        fixture.path(),
+    );
 
     assert!(
         result.is_err(),
@@ -208,13 +208,13 @@ fn test_reset_hard_rejected_when_context_invalid() {
         mode: Some("production".to_string()),
         ..Default::default()
     };
-    context.save().unwrap();
+    context.save(fixture.path()).unwrap();
 
     // Create metadata so validation actually runs
     use jin::staging::WorkspaceMetadata;
     let mut metadata = WorkspaceMetadata::new();
     metadata.applied_layers = vec!["mode/production".to_string()];
-    metadata.save().unwrap();
+    metadata.save(fixture.path()).unwrap();
 
     // Delete the mode reference
     let git_repo = git2::Repository::open_bare(&jin_dir).unwrap();
@@ -225,16 +225,20 @@ fn test_reset_hard_rejected_when_context_invalid() {
         .unwrap();
 
     // Attempt reset --hard, should be rejected
-    let result = jin::commands::reset::execute(jin::cli::ResetArgs {
-        soft: false,
-        mixed: false,
-        hard: true,
-        mode: false,
-        scope: None,
-        project: false,
-        global: false,
-        force: true,
-    });
+    let result = jin::commands::reset::execute(
+        jin::cli::ResetArgs {
+            soft: false,
+            mixed: false,
+            hard: true,
+            mode: false,
+            scope: None,
+            project: false,
+            global: false,
+            staged: Vec::new(),
+            force: true,
+        },
+        fixture.path(),
+    );
 
     assert!(
         result.is_err(),
@@ -272,16 +276,20 @@ fn test_reset_soft_skips_validation() {
     fs::write(fixture.path().join(file_path), b"modified content").unwrap();
 
     // reset --soft should succeed (no validation for non-destructive operations)
-    let result = jin::commands::reset::execute(jin::cli::ResetArgs {
-        soft: true,
-        mixed: false,
-        hard: false,
-        mode: false,
-        scope: None,
-        project: false,
-        global: false,
-        force: false,
-    });
+    let result = jin::commands::reset::execute(
+        jin::cli::ResetArgs {
+            soft: true,
+            mixed: false,
+            hard: false,
+            mode: false,
+            scope: None,
+            project: false,
+            global: false,
+            staged: Vec::new(),
+            force: false,
+        },
+        fixture.path(),
+    );
 
     assert!(
         result.is_ok(),
@@ -311,16 +319,20 @@ fn test_reset_mixed_skips_validation() {
     fs::write(fixture.path().join(file_path), b"modified content").unwrap();
 
     // reset --mixed should succeed (no validation for non-destructive operations)
-    let result = jin::commands::reset::execute(jin::cli::ResetArgs {
-        soft: false,
-        mixed: true,
-        hard: false,
-        mode: false,
-        scope: None,
-        project: false,
-        global: false,
-        force: false,
-    });
+    let result = jin::commands::reset::execute(
+        jin::cli::ResetArgs {
+            soft: false,
+            mixed: true,
+            hard: false,
+            mode: false,
+            scope: None,
+            project: false,
+            global: false,
+            staged: Vec::new(),
+            force: false,
+        },
+        fixture.path(),
+    );
 
     assert!(
         result.is_ok(),
@@ -354,10 +366,21 @@ fn test_apply_force_rejected_when_files_modified() {
     fs::write(fixture.path().join(file_path), b"modified content").unwrap();
 
     // Attempt apply --force, should be rejected
-    let result = jin::commands::apply::execute(jin::cli::ApplyArgs {
-        force: true,
-        dry_run: false,
-    });
+    let result = jin::commands::apply::execute(
+        jin::cli::ApplyArgs {
+            force: true,
+            dry_run: false,
+            target: None,
+            ours: false,
+            theirs: false,
+            prefer: None,
+            provenance: false,
+            merge_opt: Vec::new(),
+            debounce: None,
+            no_reload: false,
+        },
+        fixture.path(),
+    );
 
     assert!(
         result.is_err(),
@@ -397,7 +420,7 @@ fn test_apply_force_rejected_when_layer_refs_missing() {
     use jin::staging::WorkspaceMetadata;
     let mut metadata = WorkspaceMetadata::new();
     metadata.applied_layers = vec!["mode/dev".to_string()];
-    metadata.save().unwrap();
+    metadata.save(fixture.path()).unwrap();
 
     // Delete the mode reference to create detached state
     let git_repo = git2::Repository::open_bare(&jin_dir).unwrap();
@@ -408,10 +431,21 @@ fn test_apply_force_rejected_when_layer_refs_missing() {
         .unwrap();
 
     // Attempt apply --force, should be rejected
-    let result = jin::commands::apply::execute(jin::cli::ApplyArgs {
-        force: true,
-        dry_run: false,
-    });
+    let result = jin::commands::apply::execute(
+        jin::cli::ApplyArgs {
+            force: true,
+            dry_run: false,
+            target: None,
+            ours: false,
+            theirs: false,
+            prefer: None,
+            provenance: false,
+            merge_opt: Vec::new(),
+            debounce: None,
+            no_reload: false,
+        },
+        fixture.path(),
+    );
 
     assert!(
         result.is_err(),
@@ -452,13 +486,13 @@ fn test_apply_force_rejected_when_context_invalid() {
         mode: Some("production".to_string()),
         ..Default::default()
     };
-    context.save().unwrap();
+    context.save(fixture.path()).unwrap();
 
     // Create metadata so validation actually runs
     use jin::staging::WorkspaceMetadata;
     let mut metadata = WorkspaceMetadata::new();
     metadata.applied_layers = vec!["mode/production".to_string()];
-    metadata.save().unwrap();
+    metadata.save(fixture.path()).unwrap();
 
     // Delete the mode reference
     let git_repo = git2::Repository::open_bare(&jin_dir).unwrap();
@@ -469,10 +503,21 @@ fn test_apply_force_rejected_when_context_invalid() {
         .unwrap();
 
     // Attempt apply --force, should be rejected
-    let result = jin::commands::apply::execute(jin::cli::ApplyArgs {
-        force: true,
-        dry_run: false,
-    });
+    let result = jin::commands::apply::execute(
+        jin::cli::ApplyArgs {
+            force: true,
+            dry_run: false,
+            target: None,
+            ours: false,
+            theirs: false,
+            prefer: None,
+            provenance: false,
+            merge_opt: Vec::new(),
+            debounce: None,
+            no_reload: false,
+        },
+        fixture.path(),
+    );
 
     assert!(
         result.is_err(),
@@ -510,10 +555,21 @@ fn test_apply_without_force_skips_validation() {
     fs::write(fixture.path().join(file_path), b"modified content").unwrap();
 
     // apply without --force should fail with dirty check, NOT DetachedWorkspace error
-    let result = jin::commands::apply::execute(jin::cli::ApplyArgs {
-        force: false,
-        dry_run: false,
-    });
+    let result = jin::commands::apply::execute(
+        jin::cli::ApplyArgs {
+            force: false,
+            dry_run: false,
+            target: None,
+            ours: false,
+            theirs: false,
+            prefer: None,
+            provenance: false,
+            merge_opt: Vec::new(),
+            debounce: None,
+            no_reload: false,
+        },
+        fixture.path(),
+    );
 
     // Should fail with "Workspace has uncommitted changes" error, not DetachedWorkspace
     match result {
@@ -559,16 +615,20 @@ fn test_reset_hard_error_includes_recovery_hint() {
     fs::write(fixture.path().join(file_path), b"modified content").unwrap();
 
     // Attempt reset --hard
-    let result = jin::commands::reset::execute(jin::cli::ResetArgs {
-        soft: false,
-        mixed: false,
-        hard: true,
-        mode: false,
-        scope: None,
-        project: false,
-        global: false,
-        force: true,
-    });
+    let result = jin::commands::reset::execute(
+        jin::cli::ResetArgs {
+            soft: false,
+            mixed: false,
+            hard: true,
+            mode: false,
+            scope: None,
+            project: false,
+            global: false,
+            staged: Vec::new(),
+            force: true,
+        },
+        fixture.path(),
+    );
 
     // Check error includes recovery hint
     match result {
@@ -609,10 +669,21 @@ fn test_apply_force_error_includes_recovery_hint() {
     fs::write(fixture.path().join(file_path), b"modified content").unwrap();
 
     // Attempt apply --force
-    let result = jin::commands::apply::execute(jin::cli::ApplyArgs {
-        force: true,
-        dry_run: false,
-    });
+    let result = jin::commands::apply::execute(
+        jin::cli::ApplyArgs {
+            force: true,
+            dry_run: false,
+            target: None,
+            ours: false,
+            theirs: false,
+            prefer: None,
+            provenance: false,
+            merge_opt: Vec::new(),
+            debounce: None,
+            no_reload: false,
+        },
+        fixture.path(),
+    );
 
     // Check error includes recovery hint
     match result {
@@ -651,16 +722,20 @@ fn test_reset_hard_allows_fresh_workspace() {
     // No metadata = fresh workspace, should pass validation
     // (The command will fail with "Nothing to reset", but that's expected behavior,
     // not a DetachedWorkspace error)
-    let result = jin::commands::reset::execute(jin::cli::ResetArgs {
-        soft: false,
-        mixed: false,
-        hard: true,
-        mode: false,
-        scope: None,
-        project: false,
-        global: false,
-        force: true,
-    });
+    let result = jin::commands::reset::execute(
+        jin::cli::ResetArgs {
+            soft: false,
+            mixed: false,
+            hard: true,
+            mode: false,
+            scope: None,
+            project: false,
+            global: false,
+            staged: Vec::new(),
+            force: true,
+        },
+        fixture.path(),
+    );
 
     // Should not be a DetachedWorkspace error
     match result {
@@ -687,10 +762,21 @@ fn test_apply_force_allows_fresh_workspace() {
     init_jin_project(&jin_dir).unwrap();
 
     // No metadata = fresh workspace, should pass validation
-    let result = jin::commands::apply::execute(jin::cli::ApplyArgs {
-        force: true,
-        dry_run: false,
-    });
+    let result = jin::commands::apply::execute(
+        jin::cli::ApplyArgs {
+            force: true,
+            dry_run: false,
+            target: None,
+            ours: false,
+            theirs: false,
+            prefer: None,
+            provenance: false,
+            merge_opt: Vec::new(),
+            debounce: None,
+            no_reload: false,
+        },
+        fixture.path(),
+    );
 
     // Should not be a DetachedWorkspace error
     match result {
@@ -76,7 +76,7 @@ fn test_repair_fixes_corrupted_index() -> Result<(), Box<dyn std::error::Error>>
 
     // Run repair
     jin()
-        .arg("repair")
+        .args(["repair", "--fix"])
         .current_dir(project_path)
         .env("JIN_DIR", jin_dir)
         .assert()
@@ -573,7 +573,7 @@ fn test_commit_recovery_after_error() -> Result<(), Box<dyn std::error::Error>>
 
     // Repair
     jin()
-        .arg("repair")
+        .args(["repair", "--fix"])
         .current_dir(project_path)
         .env("JIN_DIR", jin_dir)
         .assert()
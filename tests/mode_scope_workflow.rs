@@ -11,6 +11,7 @@
 //! 8. ModeScopeProject
 //! 9. ProjectBase
 
+use predicates::prelude::*;
 use std::fs;
 
 mod common;
@@ -910,3 +911,236 @@ fn test_scope_switch_clears_metadata() -> Result<(), Box<dyn std::error::Error>>
 
     Ok(())
 }
+
+/// Test that keys pinned with $final in global can't be overridden by a mode
+#[test]
+#[serial]
+fn test_final_keys_pinned_by_lower_layer() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    fixture.set_jin_dir();
+    jin_init(project_path, None)?;
+
+    let mode_name = format!("test_mode_{}", unique_test_id());
+    create_mode(&mode_name, Some(jin_dir))?;
+    jin()
+        .args(["mode", "use", &mode_name])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    for (content, flag, message) in [
+        (
+            r#"{"security": {"tls": true, "$final": ["tls"]}, "level": 1}"#,
+            "--global",
+            "Pin tls",
+        ),
+        (
+            r#"{"security": {"tls": false}, "level": 2}"#,
+            "--mode",
+            "Try to disable tls",
+        ),
+    ] {
+        fs::write(project_path.join("settings.json"), content)?;
+        jin()
+            .args(["add", "settings.json", flag])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+        jin()
+            .args(["commit", "-m", message])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+    }
+
+    jin()
+        .args(["apply", "--force"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "cannot override final key(s) security.tls",
+        ));
+
+    let content: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(project_path.join("settings.json"))?)?;
+    assert_eq!(
+        content,
+        serde_json::json!({"security": {"tls": true}, "level": 2})
+    );
+
+    jin()
+        .args(["config", "set", "final-keys.policy", "error"])
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["apply", "--dry-run"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "overrides final key(s) security.tls",
+        ));
+
+    Ok(())
+}
+
+/// Test `jin mode use --apply` switches the workspace, through the merge cache
+#[test]
+#[serial]
+fn test_mode_use_apply_switches_workspace() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    fixture.set_jin_dir();
+    jin_init(project_path, None)?;
+
+    let modes = [
+        format!("mode_a_{}", unique_test_id()),
+        format!("mode_b_{}", unique_test_id()),
+    ];
+    for (mode_name, value) in modes.iter().zip(["a", "b"]) {
+        create_mode(mode_name, Some(jin_dir))?;
+        jin()
+            .args(["mode", "use", mode_name])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+        fs::write(
+            project_path.join("config.json"),
+            format!(r#"{{"mode": "{}"}}"#, value),
+        )?;
+        jin()
+            .args(["add", "config.json", "--mode"])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+        jin()
+            .args(["commit", "-m", "Mode config"])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+    }
+
+    // Back and forth: the second visit of each mode is served from cache
+    for (mode_name, value) in modes.iter().zip(["a", "b"]).cycle().take(4) {
+        jin()
+            .args(["mode", "use", mode_name, "--apply"])
+            .current_dir(project_path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+        let content = fs::read_to_string(project_path.join("config.json"))?;
+        assert!(content.contains(value), "{}: {}", mode_name, content);
+    }
+    let cached = fs::read_dir(project_path.join(".jin/cache/merge"))?.count();
+    assert_eq!(cached, 2);
+
+    Ok(())
+}
+
+/// Test `jin archive mode` freezes a mode until `jin unarchive`
+#[test]
+#[serial]
+fn test_archived_mode_is_read_only() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = TestFixture::new()?;
+    let project_path = fixture.path();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap();
+
+    fixture.set_jin_dir();
+    jin_init(project_path, None)?;
+
+    let mode_name = format!("bob_setup_{}", unique_test_id());
+    create_mode(&mode_name, Some(jin_dir))?;
+    jin()
+        .args(["mode", "use", &mode_name])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    fs::write(project_path.join("config.json"), r#"{"editor": "vim"}"#)?;
+    jin()
+        .args(["add", "config.json", "--mode"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Bob's setup"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    jin()
+        .args(["archive", "mode", &mode_name, "-m", "Bob left the team"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "Archived mode '{}'",
+            mode_name
+        )));
+    jin()
+        .args(["mode", "list"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Archived modes (read-only):"));
+    jin()
+        .arg("archive")
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Bob left the team"));
+
+    // Commits to the archived mode are refused
+    fs::write(project_path.join("config.json"), r#"{"editor": "emacs"}"#)?;
+    jin()
+        .args(["add", "config.json", "--mode"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Edit Bob's setup"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "Run 'jin unarchive mode {}'",
+            mode_name
+        )));
+
+    jin()
+        .args(["unarchive", "mode", &mode_name])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Edit Bob's setup"])
+        .current_dir(project_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    Ok(())
+}
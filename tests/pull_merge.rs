@@ -18,10 +18,11 @@ fn test_pull_fast_forward_still_works() -> Result<(), Box<dyn std::error::Error>
     let remote_fixture = setup_jin_with_remote()?;
     let jin_dir = remote_fixture.jin_dir.as_ref().unwrap();
 
-    // Setup: Create initial commit in "remote" via temp workspace
-    // CRITICAL: Use the SAME JIN_DIR for both workspaces to share layer refs
+    // Setup: Create initial commit in "remote" via a teammate's workspace,
+    // with its own Jin repository
     let temp_workspace = TestFixture::new()?;
-    jin_init(temp_workspace.path(), Some(jin_dir))?;
+    let other_jin_dir = temp_workspace.jin_dir.as_ref().unwrap();
+    jin_init(temp_workspace.path(), Some(other_jin_dir))?;
 
     // Create initial file and commit
     fs::write(temp_workspace.path().join("config.txt"), "version=1")?;
@@ -29,14 +30,14 @@ fn test_pull_fast_forward_still_works() -> Result<(), Box<dyn std::error::Error>
     jin()
         .args(["add", "config.txt", "--global"])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
     jin()
         .args(["commit", "-m", "Initial commit"])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
@@ -48,14 +49,14 @@ fn test_pull_fast_forward_still_works() -> Result<(), Box<dyn std::error::Error>
             "--force",
         ])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
     jin()
         .arg("push")
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Successfully pushed"));
@@ -102,27 +103,28 @@ fn test_pull_divergent_clean_merge() -> Result<(), Box<dyn std::error::Error>> {
     let remote_fixture = setup_jin_with_remote()?;
     let jin_dir = remote_fixture.jin_dir.as_ref().unwrap();
 
-    // Step 1: Create base commit in remote via temp workspace
-    // CRITICAL: Use the SAME JIN_DIR for both workspaces to share layer refs
+    // Step 1: Create base commit in remote via a teammate's workspace,
+    // with its own Jin repository
     let temp_workspace = TestFixture::new()?;
-    jin_init(temp_workspace.path(), Some(jin_dir))?;
+    let other_jin_dir = temp_workspace.jin_dir.as_ref().unwrap();
+    jin_init(temp_workspace.path(), Some(other_jin_dir))?;
 
     fs::write(
         temp_workspace.path().join("config.txt"),
-        "setting1=value1\nsetting2=value2",
+        "setting1=value1\nsetting2=value2\nsetting3=value3\n",
     )?;
 
     jin()
         .args(["add", "config.txt", "--global"])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
     jin()
         .args(["commit", "-m", "Base commit"])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
@@ -134,14 +136,14 @@ fn test_pull_divergent_clean_merge() -> Result<(), Box<dyn std::error::Error>> {
             "--force",
         ])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
     jin()
         .arg("push")
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Successfully pushed"));
@@ -175,7 +177,7 @@ fn test_pull_divergent_clean_merge() -> Result<(), Box<dyn std::error::Error>> {
     // Step 2: Make local change (add setting3 at end)
     fs::write(
         remote_fixture.local_path.join("config.txt"),
-        "setting1=value1\nsetting2=value2\nsetting3=local",
+        "setting1=value1\nsetting2=value2\nsetting3=value3\nsetting4=local\n",
     )?;
 
     jin()
@@ -195,27 +197,27 @@ fn test_pull_divergent_clean_merge() -> Result<(), Box<dyn std::error::Error>> {
     // Step 3: Make remote change (modify setting1)
     fs::write(
         temp_workspace.path().join("config.txt"),
-        "setting1=remote\nsetting2=value2",
+        "setting1=remote\nsetting2=value2\nsetting3=value3\n",
     )?;
 
     jin()
         .args(["add", "config.txt", "--global"])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
     jin()
         .args(["commit", "-m", "Remote change"])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
     jin()
         .arg("push")
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Successfully pushed"));
@@ -246,7 +248,7 @@ fn test_pull_divergent_clean_merge() -> Result<(), Box<dyn std::error::Error>> {
 
     let merged_content = fs::read_to_string(remote_fixture.local_path.join("config.txt"))?;
     assert!(merged_content.contains("setting1=remote"));
-    assert!(merged_content.contains("setting3=local"));
+    assert!(merged_content.contains("setting4=local"));
 
     Ok(())
 }
@@ -260,24 +262,25 @@ fn test_pull_divergent_with_conflicts() -> Result<(), Box<dyn std::error::Error>
     let remote_fixture = setup_jin_with_remote()?;
     let jin_dir = remote_fixture.jin_dir.as_ref().unwrap();
 
-    // Step 1: Create base commit in remote via temp workspace
+    // Step 1: Create base commit in remote via a teammate's workspace,
+    // with its own Jin repository
     let temp_workspace = TestFixture::new()?;
-    let jin_dir = temp_workspace.jin_dir.as_ref().unwrap();
-    jin_init(temp_workspace.path(), Some(jin_dir))?;
+    let other_jin_dir = temp_workspace.jin_dir.as_ref().unwrap();
+    jin_init(temp_workspace.path(), Some(other_jin_dir))?;
 
     fs::write(temp_workspace.path().join("config.txt"), "version=1")?;
 
     jin()
         .args(["add", "config.txt", "--global"])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
     jin()
         .args(["commit", "-m", "Base commit"])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
@@ -289,14 +292,14 @@ fn test_pull_divergent_with_conflicts() -> Result<(), Box<dyn std::error::Error>
             "--force",
         ])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
     jin()
         .arg("push")
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Successfully pushed"));
@@ -350,21 +353,21 @@ fn test_pull_divergent_with_conflicts() -> Result<(), Box<dyn std::error::Error>
     jin()
         .args(["add", "config.txt", "--global"])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
     jin()
         .args(["commit", "-m", "Remote conflicting change"])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
     jin()
         .arg("push")
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Successfully pushed"));
@@ -384,7 +387,7 @@ fn test_pull_divergent_with_conflicts() -> Result<(), Box<dyn std::error::Error>
         .assert()
         .success()
         .stdout(predicates::str::contains(
-            "! global: Merged with 1 conflicts",
+            "! global: 1 conflict(s), not merged yet",
         ))
         .stdout(predicates::str::contains(
             "config.txt has conflicts (.jinmerge created)",
@@ -408,6 +411,50 @@ fn test_pull_divergent_with_conflicts() -> Result<(), Box<dyn std::error::Error>
     assert!(jinmerge_content.contains("version=2")); // Local
     assert!(jinmerge_content.contains("version=3")); // Remote
 
+    // The pull is paused: another pull is refused until resolved
+    jin()
+        .arg("pull")
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("paused on conflicts"));
+
+    // Step 5: Resolve into the layer, then apply
+    fs::write(
+        &jinmerge_path,
+        "# Jin merge conflict. Resolve and run 'jin resolve <file>'\nversion=4",
+    )?;
+
+    jin()
+        .args(["resolve", "config.txt"])
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("✓ global: Merged"))
+        .stdout(predicates::str::contains("Pull completed"));
+    assert!(!jinmerge_path.exists());
+
+    jin()
+        .args(["apply", "--force"])
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    let applied = fs::read_to_string(remote_fixture.local_path.join("config.txt"))?;
+    assert!(applied.contains("version=4"));
+    assert!(!applied.contains("version=3"));
+
+    // The merge contains the remote commit, so there is nothing to pull
+    jin()
+        .arg("pull")
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Already up to date"));
+
     Ok(())
 }
 
@@ -420,10 +467,11 @@ fn test_pull_divergent_clean_merge_different_files() -> Result<(), Box<dyn std::
     let remote_fixture = setup_jin_with_remote()?;
     let jin_dir = remote_fixture.jin_dir.as_ref().unwrap();
 
-    // Step 1: Create base commit (with initial file)
+    // Step 1: Create base commit (with initial file) in a teammate's
+    // workspace, with its own Jin repository
     let temp_workspace = TestFixture::new()?;
-    let jin_dir = temp_workspace.jin_dir.as_ref().unwrap();
-    jin_init(temp_workspace.path(), Some(jin_dir))?;
+    let other_jin_dir = temp_workspace.jin_dir.as_ref().unwrap();
+    jin_init(temp_workspace.path(), Some(other_jin_dir))?;
 
     // Create initial file
     fs::write(temp_workspace.path().join("base.txt"), "base content")?;
@@ -431,14 +479,14 @@ fn test_pull_divergent_clean_merge_different_files() -> Result<(), Box<dyn std::
     jin()
         .args(["add", "base.txt", "--global"])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
     jin()
         .args(["commit", "-m", "Base commit with initial file"])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
@@ -450,14 +498,14 @@ fn test_pull_divergent_clean_merge_different_files() -> Result<(), Box<dyn std::
             "--force",
         ])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
     jin()
         .arg("push")
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Successfully pushed"));
@@ -511,21 +559,21 @@ fn test_pull_divergent_clean_merge_different_files() -> Result<(), Box<dyn std::
     jin()
         .args(["add", "remote.txt", "--global"])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
     jin()
         .args(["commit", "-m", "Add remote file"])
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success();
 
     jin()
         .arg("push")
         .current_dir(temp_workspace.path())
-        .env("JIN_DIR", jin_dir)
+        .env("JIN_DIR", other_jin_dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Successfully pushed"));
@@ -6,6 +6,8 @@
 //! - Reports detachment details when workspace is detached
 //! - Does not run other repair checks when --check is used
 //! - Works with or without --dry-run flag
+//!
+//! They also cover report-only repair, `--fix` and the check targets.
 
 mod common;
 use common::fixtures::TestFixture;
@@ -247,3 +249,66 @@ fn test_repair_normal_mode_includes_workspace_check() {
     // The check may show "✓ (not initialized)" or similar
     result.success();
 }
+
+#[test]
+fn test_repair_reports_until_fix_and_audits_fixes() {
+    // Test that repair only reports by default and --fix repairs and audits
+    let fixture = TestFixture::new().unwrap();
+    let jin_dir = fixture.jin_dir.as_ref().unwrap().clone();
+
+    jin_cmd()
+        .args(["init"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    fs::write(fixture.path().join("config.txt"), "managed").unwrap();
+    jin_cmd()
+        .args(["add", "config.txt"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success();
+
+    // Drop the managed block that `jin add` wrote
+    fs::write(fixture.path().join(".gitignore"), "target/\n").unwrap();
+
+    jin_cmd()
+        .args(["repair", "--gitignore"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Checking .gitignore managed block",
+        ))
+        .stdout(predicate::str::contains("report only - no changes made"))
+        .stdout(predicate::str::contains("Checking staging index").not());
+    assert_eq!(
+        fs::read_to_string(fixture.path().join(".gitignore")).unwrap(),
+        "target/\n"
+    );
+
+    jin_cmd()
+        .args(["repair", "--gitignore", "--fix"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Fixed: Added 1 file to the .gitignore managed block",
+        ))
+        .stdout(predicate::str::contains("1 issue fixed"));
+
+    let gitignore = fs::read_to_string(fixture.path().join(".gitignore")).unwrap();
+    assert!(gitignore.starts_with("target/\n"));
+    assert!(gitignore.contains("config.txt"));
+
+    let audit_dir = fixture.path().join(".jin/audit");
+    let log: String = fs::read_dir(&audit_dir)
+        .unwrap()
+        .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect();
+    assert!(log.contains(r#""repair":"Added 1 file to the .gitignore managed block""#));
+}
@@ -728,3 +728,47 @@ fn test_resolve_preserves_resolved_content_formatting() {
         "Custom features array should be preserved"
     );
 }
+
+#[test]
+fn test_resolve_interactive_picks_side() {
+    let fixture = setup_test_repo().unwrap();
+    let jin_dir = fixture.jin_dir.clone().unwrap();
+    let mode_name = format!("test_mode_{}", unique_test_id());
+    let run = |args: &[&str]| {
+        jin_cmd()
+            .args(args)
+            .current_dir(fixture.path())
+            .env("JIN_DIR", &jin_dir)
+            .assert()
+            .success();
+    };
+
+    run(&["mode", "create", &mode_name]);
+    run(&["mode", "use", &mode_name]);
+
+    let config_path = fixture.path().join("config.txt");
+    fs::write(&config_path, "port = 8080\n").unwrap();
+    run(&["add", "config.txt", "--global"]);
+    run(&["commit", "-m", "Add to global"]);
+    fs::write(&config_path, "port = 9090\n").unwrap();
+    run(&["add", "config.txt", "--mode"]);
+    run(&["commit", "-m", "Add to mode"]);
+    fs::remove_file(&config_path).unwrap();
+    run(&["apply"]);
+
+    jin_cmd()
+        .args(["resolve", "--interactive"])
+        .current_dir(fixture.path())
+        .env("JIN_DIR", &jin_dir)
+        .write_stdin("x\nt\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Conflict 1/1"))
+        .stdout(predicate::str::contains("8080"))
+        .stdout(predicate::str::contains("Unknown choice 'x'"))
+        .stdout(predicate::str::contains("Apply operation completed"));
+
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), "port = 9090\n");
+    assert!(!fixture.path().join("config.txt.jinmerge").exists());
+    assert!(!fixture.path().join(".jin/.paused_apply.yaml").exists());
+}
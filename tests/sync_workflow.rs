@@ -630,6 +630,96 @@ fn test_push_succeeds_when_ahead() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Test push delivers new commits and can be limited to named layers
+///
+/// The remote must end up with exactly the local commit of each pushed layer,
+/// and layers left out of the selection must not be pushed.
+#[test]
+fn test_push_selected_layers_delivers_commits() -> Result<(), Box<dyn std::error::Error>> {
+    let remote_fixture = setup_jin_with_remote()?;
+    let mode_name = format!("select_test_{}", unique_test_id());
+    let jin_dir = remote_fixture.jin_dir.as_ref().unwrap();
+    let project = &remote_fixture.local_path;
+    let commit = |file: &str, content: &str, flag: &str| {
+        fs::write(project.join(file), content).unwrap();
+        jin()
+            .args(["add", file, flag])
+            .current_dir(project)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+        jin()
+            .args(["commit", "-m", file])
+            .current_dir(project)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+    };
+    let head = |repo: &git2::Repository, name: &str| {
+        repo.find_reference(name).ok().and_then(|r| r.target())
+    };
+
+    jin()
+        .args(["link", remote_fixture.remote_path.to_str().unwrap()])
+        .current_dir(project)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    create_mode(&mode_name, Some(jin_dir))?;
+    jin()
+        .args(["mode", "use", &mode_name])
+        .current_dir(project)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    commit("global.txt", "global 1", "--global");
+    commit("mode.txt", "mode 1", "--mode");
+
+    // Only the named layer is pushed
+    jin()
+        .args(["push", "global"])
+        .current_dir(project)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Successfully pushed 1 layer(s)"));
+    let local = git2::Repository::open(jin_dir)?;
+    let remote = git2::Repository::open_bare(&remote_fixture.remote_path)?;
+    let mode_ref = format!("refs/jin/layers/mode/{}/_", mode_name);
+    assert!(head(&local, &mode_ref).is_some());
+    assert_eq!(
+        head(&remote, "refs/jin/layers/global"),
+        head(&local, "refs/jin/layers/global")
+    );
+    assert_eq!(head(&remote, &mode_ref), None);
+
+    // A later commit on top is delivered along with the remaining layer
+    commit("global.txt", "global 2", "--global");
+    jin()
+        .arg("push")
+        .current_dir(project)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Successfully pushed 2 layer(s)"));
+    assert_eq!(
+        head(&remote, "refs/jin/layers/global"),
+        head(&local, "refs/jin/layers/global")
+    );
+    assert_eq!(head(&remote, &mode_ref), head(&local, &mode_ref));
+
+    // Unknown layers are an error
+    jin()
+        .args(["push", "mode/no-such-mode"])
+        .current_dir(project)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no-such-mode"));
+
+    Ok(())
+}
+
 /// Test push succeeds when local and remote are up-to-date (P1.M2.T3.S3)
 ///
 /// When local and remote point to the same commit, push should succeed
@@ -999,6 +1089,56 @@ fn test_link_force_replaces_existing() -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
+/// Test link --auth saves the method and token variable name, never the token
+#[test]
+fn test_link_auth_settings_saved_without_secret() -> Result<(), Box<dyn std::error::Error>> {
+    let remote_fixture = setup_jin_with_remote()?;
+    let jin_dir = remote_fixture.jin_dir.as_ref().unwrap();
+
+    jin()
+        .args([
+            "link",
+            remote_fixture.remote_path.to_str().unwrap(),
+            "--token-env",
+            "CI_JIN_TOKEN",
+        ])
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", jin_dir)
+        .env("CI_JIN_TOKEN", "s3cret-token")
+        .assert()
+        .success();
+
+    for (key, value) in [
+        ("remote.auth", "token"),
+        ("remote.token-env", "CI_JIN_TOKEN"),
+    ] {
+        jin()
+            .args(["config", "get", key])
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success()
+            .stdout(format!("{}\n", value));
+    }
+    let config = fs::read_to_string(jin_dir.join("config.toml"))?;
+    assert!(!config.contains("s3cret-token"));
+
+    jin()
+        .args([
+            "link",
+            remote_fixture.remote_path.to_str().unwrap(),
+            "--force",
+            "--auth",
+            "password",
+        ])
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid auth method"));
+
+    Ok(())
+}
+
 /// Test fetch loads context with graceful fallback (P2.M3.T1)
 ///
 /// Verifies that fetch command loads ProjectContext at startup:
@@ -1471,8 +1611,8 @@ fn test_fetch_updates_refs_without_merging() -> Result<(), Box<dyn std::error::E
         .assert()
         .success();
 
-    // Verify: Remote ref now exists locally (fetch updates refs)
-    let ref_path = format!("refs/jin/layers/mode/{}/_", mode_name);
+    // Verify: Remote ref now exists in the tracking namespace
+    let ref_path = format!("refs/jin/remote/origin/layers/mode/{}/_", mode_name);
     let jin_repo = git2::Repository::open(jin_dir)?;
     match jin_repo.find_reference(&ref_path) {
         Ok(reference) => {
@@ -1491,6 +1631,13 @@ fn test_fetch_updates_refs_without_merging() -> Result<(), Box<dyn std::error::E
         Err(e) => panic!("Fetch should have created remote ref locally: {}", e),
     }
 
+    // Verify: Local layer ref untouched until pull merges it
+    assert_eq!(
+        capture_ref_before_fetch(jin_dir, &mode_name)?,
+        pre_fetch_oid,
+        "Fetch should not update local layer refs"
+    );
+
     // Verify: Workspace NOT modified (fetch is read-only)
     let workspace_file = remote_fixture.local_path.join("remote.txt");
     assert!(
@@ -2215,3 +2362,767 @@ fn test_pull_rebase_rebases_local_changes() -> Result<(), Box<dyn std::error::Er
     // 5. Verify linear history: base -> B -> A'
     Ok(())
 }
+
+/// Test that status reports remote commits found by the freshness check
+#[test]
+fn test_status_reports_remote_freshness() -> Result<(), Box<dyn std::error::Error>> {
+    let remote_fixture = setup_jin_with_remote()?;
+    let local_jin_dir = remote_fixture.jin_dir.as_ref().unwrap();
+    let mode_name = format!("fresh_test_{}", unique_test_id());
+
+    // Local workspace links before anything is published and uses the same mode
+    jin()
+        .args(["link", remote_fixture.remote_path.to_str().unwrap()])
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", local_jin_dir)
+        .assert()
+        .success();
+    create_mode(&mode_name, Some(local_jin_dir))?;
+    jin()
+        .args(["mode", "use", &mode_name])
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", local_jin_dir)
+        .assert()
+        .success();
+
+    // Publish a mode commit to the remote from another workspace
+    let publisher = TestFixture::new()?;
+    let publisher_jin_dir = publisher.jin_dir.as_ref().unwrap();
+    jin_init(publisher.path(), Some(publisher_jin_dir))?;
+    create_mode(&mode_name, Some(publisher_jin_dir))?;
+    jin()
+        .args(["mode", "use", &mode_name])
+        .current_dir(publisher.path())
+        .env("JIN_DIR", publisher_jin_dir)
+        .assert()
+        .success();
+    fs::write(publisher.path().join("shared.txt"), "shared")?;
+    jin()
+        .args(["add", "shared.txt", "--mode"])
+        .current_dir(publisher.path())
+        .env("JIN_DIR", publisher_jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Shared change"])
+        .current_dir(publisher.path())
+        .env("JIN_DIR", publisher_jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["link", remote_fixture.remote_path.to_str().unwrap()])
+        .current_dir(publisher.path())
+        .env("JIN_DIR", publisher_jin_dir)
+        .assert()
+        .success();
+    jin()
+        .arg("push")
+        .current_dir(publisher.path())
+        .env("JIN_DIR", publisher_jin_dir)
+        .assert()
+        .success();
+
+    // Disabled by default
+    jin()
+        .arg("status")
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", local_jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Remote has").not());
+
+    jin()
+        .args(["config", "set", "remote.check-interval", "3600"])
+        .env("JIN_DIR", local_jin_dir)
+        .assert()
+        .success();
+
+    // Run the check synchronously so the result is deterministic
+    jin()
+        .arg("remote-check")
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", local_jin_dir)
+        .assert()
+        .success();
+
+    jin()
+        .arg("status")
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", local_jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "Remote has 1 newer commit on mode/{}",
+            mode_name
+        )));
+
+    // The check must not touch local layer refs
+    let repo = git2::Repository::open(local_jin_dir)?;
+    assert!(repo
+        .find_reference(&format!("refs/jin/layers/mode/{}/_", mode_name))
+        .is_err());
+
+    Ok(())
+}
+
+/// Test that remote layers can be listed and read without pulling
+#[test]
+fn test_remote_ls_and_show() -> Result<(), Box<dyn std::error::Error>> {
+    let remote_fixture = setup_jin_with_remote()?;
+    let local_jin_dir = remote_fixture.jin_dir.as_ref().unwrap();
+    let mode_name = format!("remote_ls_{}", unique_test_id());
+
+    // Publish a mode layer from a separate workspace
+    let publisher = TestFixture::new()?;
+    let jin_dir = publisher.jin_dir.as_ref().unwrap();
+    jin_init(publisher.path(), Some(jin_dir))?;
+    create_mode(&mode_name, Some(jin_dir))?;
+    jin()
+        .args(["mode", "use", &mode_name])
+        .current_dir(publisher.path())
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    fs::write(publisher.path().join("settings.json"), r#"{"model": "x"}"#)?;
+    jin()
+        .args(["add", "settings.json", "--mode"])
+        .current_dir(publisher.path())
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Add settings"])
+        .current_dir(publisher.path())
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args([
+            "link",
+            remote_fixture.remote_path.to_str().unwrap(),
+            "--force",
+        ])
+        .current_dir(publisher.path())
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .arg("push")
+        .current_dir(publisher.path())
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    // Inspect it from the local workspace
+    jin()
+        .args([
+            "link",
+            remote_fixture.remote_path.to_str().unwrap(),
+            "--force",
+        ])
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", local_jin_dir)
+        .assert()
+        .success();
+
+    let layer = format!("mode/{}", mode_name);
+    jin()
+        .args(["remote", "ls", "mode/"])
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", local_jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(&layer).and(predicate::str::contains("new")));
+
+    jin()
+        .args(["remote", "show", &layer])
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", local_jin_dir)
+        .assert()
+        .success()
+        .stdout("settings.json\n");
+
+    jin()
+        .args(["remote", "show", &layer, "settings.json"])
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", local_jin_dir)
+        .assert()
+        .success()
+        .stdout(r#"{"model": "x"}"#);
+
+    jin()
+        .args(["remote", "show", &layer, "missing.json"])
+        .current_dir(&remote_fixture.local_path)
+        .env("JIN_DIR", local_jin_dir)
+        .assert()
+        .failure();
+
+    // Nothing was pulled: the local layer ref still doesn't exist
+    let repo = git2::Repository::open_bare(local_jin_dir)?;
+    assert!(repo
+        .find_reference(&format!("refs/jin/layers/mode/{}/_", mode_name))
+        .is_err());
+
+    Ok(())
+}
+
+/// Test that the user-local layer syncs between machines via a private remote
+#[test]
+fn test_local_sync_between_machines() -> Result<(), Box<dyn std::error::Error>> {
+    let private = tempfile::TempDir::new()?;
+    git2::Repository::init_bare(private.path())?;
+    let private_url = private.path().to_str().unwrap();
+
+    let laptop = TestFixture::new()?;
+    let desktop = TestFixture::new()?;
+    for (machine, fixture, file) in [
+        ("laptop", &laptop, "laptop.json"),
+        ("desktop", &desktop, "desktop.json"),
+    ] {
+        let jin_dir = fixture.jin_dir.as_ref().unwrap();
+        jin_init(fixture.path(), Some(jin_dir))?;
+        fs::write(fixture.path().join(file), r#"{"theme": "dark"}"#)?;
+        jin()
+            .args(["add", file, "--local"])
+            .current_dir(fixture.path())
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+        jin()
+            .args(["commit", "-m", "Add local settings"])
+            .current_dir(fixture.path())
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+        jin()
+            .args(["local", "link", private_url, "--machine", machine])
+            .current_dir(fixture.path())
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+    }
+
+    let sync = |fixture: &TestFixture| {
+        jin()
+            .args(["local", "sync"])
+            .current_dir(fixture.path())
+            .env("JIN_DIR", fixture.jin_dir.as_ref().unwrap())
+            .assert()
+            .success();
+    };
+    sync(&laptop);
+    sync(&desktop);
+    sync(&laptop);
+
+    let local_files = |fixture: &TestFixture| -> Result<Vec<String>, git2::Error> {
+        let repo = git2::Repository::open_bare(fixture.jin_dir.as_ref().unwrap())?;
+        let tree = repo
+            .find_reference("refs/jin/layers/local")?
+            .peel_to_commit()?
+            .tree()?;
+        Ok(tree
+            .iter()
+            .filter_map(|e| e.name().map(str::to_string))
+            .collect())
+    };
+    assert_eq!(local_files(&laptop)?, vec!["desktop.json", "laptop.json"]);
+    assert_eq!(local_files(&desktop)?, vec!["desktop.json", "laptop.json"]);
+
+    // Only per-machine refs are published, never layer refs
+    let remote = git2::Repository::open_bare(private.path())?;
+    let mut refs: Vec<String> = remote
+        .references()?
+        .filter_map(|r| r.ok()?.name().map(str::to_string))
+        .collect();
+    refs.sort();
+    assert_eq!(
+        refs,
+        vec!["refs/jin/local/desktop", "refs/jin/local/laptop"]
+    );
+
+    Ok(())
+}
+
+/// Test that each machine's ID shows in doctor, the audit log and local sync
+#[test]
+fn test_machine_id_attributes_changes() -> Result<(), Box<dyn std::error::Error>> {
+    let private = tempfile::TempDir::new()?;
+    git2::Repository::init_bare(private.path())?;
+    let private_url = private.path().to_str().unwrap();
+
+    let laptop = TestFixture::new()?;
+    let desktop = TestFixture::new()?;
+    let run = |fixture: &TestFixture, args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(fixture.path())
+            .env("JIN_DIR", fixture.jin_dir.as_ref().unwrap())
+            .assert()
+            .success()
+    };
+    let machine_id = |fixture: &TestFixture| -> Result<String, std::io::Error> {
+        let id = fs::read_to_string(fixture.jin_dir.as_ref().unwrap().join("machine-id"))?;
+        Ok(id.trim().to_string())
+    };
+
+    for (machine, fixture) in [("laptop", &laptop), ("desktop", &desktop)] {
+        jin_init(fixture.path(), fixture.jin_dir.as_ref())?;
+        run(
+            fixture,
+            &["local", "link", private_url, "--machine", machine],
+        );
+    }
+    fs::write(laptop.path().join("laptop.json"), r#"{"theme": "dark"}"#)?;
+    run(&laptop, &["add", "laptop.json", "--local"]);
+    run(&laptop, &["commit", "-m", "Add local settings"]);
+    let laptop_id = machine_id(&laptop)?;
+    assert_eq!(laptop_id.len(), 32);
+
+    run(&laptop, &["doctor"])
+        .stdout(predicate::str::contains(format!(
+            "Machine ID:      {}",
+            laptop_id
+        )))
+        .stdout(predicate::str::contains("as 'laptop'"));
+
+    let audit_dir = laptop.path().join(".jin").join("audit");
+    let audit = fs::read_dir(&audit_dir)?
+        .map(|entry| fs::read_to_string(entry?.path()))
+        .collect::<Result<String, _>>()?;
+    assert!(audit.contains(&format!(r#""machine":"{}""#, laptop_id)));
+
+    run(&laptop, &["local", "sync"]);
+    run(&desktop, &["local", "sync"]).stdout(predicate::str::contains(format!(
+        "Merged user-local changes from 'laptop' (machine {})",
+        &laptop_id[..8]
+    )));
+    run(&desktop, &["doctor"]);
+    assert_ne!(machine_id(&desktop)?, laptop_id);
+
+    run(&laptop, &["doctor", "--regenerate-machine-id"]).stdout(predicate::str::contains(format!(
+        "changed from {}",
+        laptop_id
+    )));
+    assert_ne!(machine_id(&laptop)?, laptop_id);
+    Ok(())
+}
+
+#[test]
+fn test_lock_warns_and_blocks_teammate_commits() -> Result<(), Box<dyn std::error::Error>> {
+    let remote_fixture = setup_jin_with_remote()?;
+    let remote_url = remote_fixture.remote_path.to_str().unwrap();
+    let alice_dir = remote_fixture.jin_dir.as_ref().unwrap();
+    let alice = remote_fixture.local_path.as_path();
+
+    let bob_fixture = TestFixture::new()?;
+    let bob_dir = bob_fixture.jin_dir.as_ref().unwrap();
+    let bob = bob_fixture.path();
+    jin_init(bob, Some(bob_dir))?;
+
+    for (path, jin_dir, name) in [(alice, alice_dir, "alice"), (bob, bob_dir, "bob")] {
+        jin()
+            .args(["link", remote_url, "--force"])
+            .current_dir(path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+        jin()
+            .args(["config", "set", "user.name", name])
+            .current_dir(path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+    }
+
+    jin()
+        .args([
+            "lock",
+            "config.yaml",
+            "--layer",
+            "global",
+            "-m",
+            "migrating",
+        ])
+        .current_dir(alice)
+        .env("JIN_DIR", alice_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Locked 'config.yaml' in global"));
+
+    // Bob sees the lock and can't take it
+    jin()
+        .arg("lock")
+        .current_dir(bob)
+        .env("JIN_DIR", bob_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("config.yaml").and(predicate::str::contains("alice")));
+    jin()
+        .args(["lock", "config.yaml"])
+        .current_dir(bob)
+        .env("JIN_DIR", bob_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already locked by alice"));
+
+    // Committing to the locked path warns by default...
+    fs::write(bob.join("config.yaml"), "a: 1\n")?;
+    jin()
+        .args(["add", "config.yaml", "--global"])
+        .current_dir(bob)
+        .env("JIN_DIR", bob_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Edit locked file"])
+        .current_dir(bob)
+        .env("JIN_DIR", bob_dir)
+        .assert()
+        .success()
+        .stderr(
+            predicate::str::contains("locked by alice").and(predicate::str::contains("migrating")),
+        );
+
+    // ...and fails with the block policy
+    jin()
+        .args(["config", "set", "locks.policy", "block"])
+        .current_dir(bob)
+        .env("JIN_DIR", bob_dir)
+        .assert()
+        .success();
+    fs::write(bob.join("config.yaml"), "a: 2\n")?;
+    jin()
+        .args(["add", "config.yaml", "--global"])
+        .current_dir(bob)
+        .env("JIN_DIR", bob_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["commit", "-m", "Edit locked file again"])
+        .current_dir(bob)
+        .env("JIN_DIR", bob_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("locked path"));
+
+    // Only the owner releases the lock without --force
+    jin()
+        .args(["unlock", "config.yaml"])
+        .current_dir(bob)
+        .env("JIN_DIR", bob_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--force"));
+    jin()
+        .args(["unlock", "config.yaml", "--layer", "global"])
+        .current_dir(alice)
+        .env("JIN_DIR", alice_dir)
+        .assert()
+        .success();
+    jin()
+        .arg("lock")
+        .current_dir(bob)
+        .env("JIN_DIR", bob_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No locks"));
+    jin()
+        .args(["commit", "-m", "Edit after unlock"])
+        .current_dir(bob)
+        .env("JIN_DIR", bob_dir)
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+/// Test layer owners show in status and become reviewer notes on push
+#[test]
+fn test_owners_route_reviews_on_push() -> Result<(), Box<dyn std::error::Error>> {
+    let remote_fixture = setup_jin_with_remote()?;
+    let mode_name = format!("owners_test_{}", unique_test_id());
+    let jin_dir = remote_fixture.jin_dir.as_ref().unwrap();
+    let local = &remote_fixture.local_path;
+    let run = |args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(local)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success()
+    };
+
+    run(&["link", remote_fixture.remote_path.to_str().unwrap()]);
+    create_mode(&mode_name, Some(jin_dir))?;
+    run(&["mode", "use", &mode_name]);
+
+    fs::write(local.join(".jinowners"), "*  @platform\ndeploy/  @ops\n")?;
+    fs::create_dir(local.join("deploy"))?;
+    fs::write(local.join("deploy/prod.yaml"), "replicas: 1\n")?;
+    run(&["add", ".jinowners", "--mode"]);
+    run(&["commit", "-m", "Add owners"]);
+    run(&["push"]);
+
+    // Ownership of the layer shows for staged changes
+    fs::write(local.join("notes.txt"), "unowned? no, @platform\n")?;
+    run(&["add", "deploy/prod.yaml", "notes.txt", "--mode"]);
+    run(&["status"])
+        .stdout(predicate::str::contains(
+            "deploy/prod.yaml -> mode-base (owners: @ops)",
+        ))
+        .stdout(predicate::str::contains(
+            "notes.txt -> mode-base (owners: @platform)",
+        ));
+
+    run(&["commit", "-m", "Scale up"]);
+    run(&["push"])
+        .stdout(predicate::str::contains("Required reviewers:"))
+        .stdout(predicate::str::contains("Scale up: @ops @platform"));
+
+    // The notes reached the team remote
+    let remote = git2::Repository::open(&remote_fixture.remote_path)?;
+    let mut messages = Vec::new();
+    for note in remote.notes(Some("refs/notes/jin/reviewers"))? {
+        let (_, annotated) = note?;
+        let note = remote.find_note(Some("refs/notes/jin/reviewers"), annotated)?;
+        messages.push(note.message().unwrap_or_default().to_string());
+    }
+    messages.sort();
+    assert_eq!(
+        messages,
+        [
+            "Required-Reviewers: @ops @platform\n",
+            "Required-Reviewers: @platform\n"
+        ]
+    );
+
+    // The owners file never reaches the workspace through a merge
+    fs::remove_file(local.join(".jinowners"))?;
+    run(&["apply"]);
+    assert!(!local.join(".jinowners").exists());
+    Ok(())
+}
+
+/// Test link --ssh-key and --token-file save paths, never file contents
+#[test]
+fn test_link_key_and_token_file_paths_saved() -> Result<(), Box<dyn std::error::Error>> {
+    let remote_fixture = setup_jin_with_remote()?;
+    let jin_dir = remote_fixture.jin_dir.as_ref().unwrap();
+    let key_path = remote_fixture._tempdir.path().join("deploy_key");
+    let token_path = remote_fixture._tempdir.path().join("token");
+    fs::write(&key_path, "PRIVATE KEY")?;
+    fs::write(&token_path, "s3cret-token\n")?;
+
+    jin()
+        .args([
+            "link",
+            remote_fixture.remote_path.to_str().unwrap(),
+            "--ssh-key",
+            "/no/such/key",
+        ])
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--ssh-key file '/no/such/key'"));
+
+    jin()
+        .args([
+            "link",
+            remote_fixture.remote_path.to_str().unwrap(),
+            "--ssh-key",
+            key_path.to_str().unwrap(),
+            "--token-file",
+            token_path.to_str().unwrap(),
+        ])
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    for (key, value) in [
+        ("remote.ssh-key", key_path.to_str().unwrap()),
+        ("remote.token-file", token_path.to_str().unwrap()),
+    ] {
+        jin()
+            .args(["config", "get", key])
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success()
+            .stdout(format!("{}\n", value));
+    }
+    let config = fs::read_to_string(jin_dir.join("config.toml"))?;
+    assert!(!config.contains("s3cret-token"));
+    assert!(!config.contains("PRIVATE KEY"));
+
+    Ok(())
+}
+
+/// Test named remotes: add with default push layers, list, push, remove
+#[test]
+fn test_link_named_remotes() -> Result<(), Box<dyn std::error::Error>> {
+    let remote_fixture = setup_jin_with_remote()?;
+    let mode_name = format!("named_remote_{}", unique_test_id());
+    let jin_dir = remote_fixture.jin_dir.as_ref().unwrap();
+    let project = &remote_fixture.local_path;
+    let backup_path = remote_fixture._tempdir.path().join("backup");
+    fs::create_dir(&backup_path)?;
+    git2::Repository::init_bare(&backup_path)?;
+
+    jin()
+        .args(["link", remote_fixture.remote_path.to_str().unwrap()])
+        .current_dir(project)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args([
+            "link",
+            "add",
+            "backup",
+            backup_path.to_str().unwrap(),
+            "--push-layer",
+            "global",
+        ])
+        .current_dir(project)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Configured remote 'backup'"));
+    jin()
+        .args(["link", "list"])
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("origin"))
+        .stdout(predicate::str::contains("backup"))
+        .stdout(predicate::str::contains("push layers: global"));
+
+    create_mode(&mode_name, Some(jin_dir))?;
+    jin()
+        .args(["mode", "use", &mode_name])
+        .current_dir(project)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    for (file, flag) in [("global.txt", "--global"), ("mode.txt", "--mode")] {
+        fs::write(project.join(file), file)?;
+        jin()
+            .args(["add", file, flag])
+            .current_dir(project)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success();
+    }
+    jin()
+        .args(["commit", "-m", "layers"])
+        .current_dir(project)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+
+    // Only the backup's default push layers go to it; origin is untouched
+    jin()
+        .args(["push", "--remote", "backup"])
+        .current_dir(project)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pushing to backup"))
+        .stdout(predicate::str::contains("Successfully pushed 1 layer(s)"));
+    let backup = git2::Repository::open_bare(&backup_path)?;
+    let origin = git2::Repository::open_bare(&remote_fixture.remote_path)?;
+    assert!(backup.find_reference("refs/jin/layers/global").is_ok());
+    assert!(backup
+        .find_reference(&format!("refs/jin/layers/mode/{}/_", mode_name))
+        .is_err());
+    assert!(origin.find_reference("refs/jin/layers/global").is_err());
+
+    jin()
+        .args(["link", "remove", "backup"])
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success();
+    jin()
+        .args(["link", "list"])
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("backup").not());
+    jin()
+        .args(["push", "--remote", "backup"])
+        .current_dir(project)
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No remote named 'backup'"));
+    jin()
+        .args(["link", "remove", "backup"])
+        .env("JIN_DIR", jin_dir)
+        .assert()
+        .failure();
+
+    Ok(())
+}
+
+/// Test annotations on layer commits are shared through the remote
+#[test]
+fn test_annotations_shared_with_teammates() -> Result<(), Box<dyn std::error::Error>> {
+    let remote_fixture = setup_jin_with_remote()?;
+    let remote_url = remote_fixture.remote_path.to_str().unwrap();
+    let alice_dir = remote_fixture.jin_dir.as_ref().unwrap();
+    let alice = remote_fixture.local_path.as_path();
+
+    let bob_fixture = TestFixture::new()?;
+    let bob_dir = bob_fixture.jin_dir.as_ref().unwrap();
+    let bob = bob_fixture.path();
+    jin_init(bob, Some(bob_dir))?;
+
+    let run = |path: &std::path::Path, jin_dir: &std::path::Path, args: &[&str]| {
+        jin()
+            .args(args)
+            .current_dir(path)
+            .env("JIN_DIR", jin_dir)
+            .assert()
+            .success()
+    };
+    for (path, jin_dir, name) in [(alice, alice_dir, "alice"), (bob, bob_dir, "bob")] {
+        run(path, jin_dir, &["link", remote_url, "--force"]);
+        run(path, jin_dir, &["config", "set", "user.name", name]);
+    }
+
+    fs::write(alice.join("config.yaml"), "replicas: 3\n")?;
+    run(alice, alice_dir, &["add", "config.yaml", "--global"]);
+    run(alice, alice_dir, &["commit", "-m", "Scale up"]);
+    run(alice, alice_dir, &["push"]);
+    let log = run(alice, alice_dir, &["log"]).get_output().stdout.clone();
+    let log = String::from_utf8(log)?;
+    let commit = log
+        .split_whitespace()
+        .skip_while(|word| *word != "commit")
+        .nth(1)
+        .unwrap()
+        .to_string();
+
+    run(alice, alice_dir, &["annotate", &commit, "deployed to prod"]);
+
+    // Bob adds to the annotations after fetching
+    run(bob, bob_dir, &["fetch"]);
+    run(bob, bob_dir, &["annotate", &commit, "rolled back in prod"]).stdout(
+        predicate::str::contains(format!("Annotated commit {}", commit)),
+    );
+
+    run(alice, alice_dir, &["fetch"]);
+    run(alice, alice_dir, &["log"]).stdout(
+        predicate::str::contains("Notes:")
+            .and(predicate::str::contains("deployed to prod (alice"))
+            .and(predicate::str::contains("rolled back in prod (bob")),
+    );
+
+    jin()
+        .args(["annotate", "0000000", "nothing"])
+        .current_dir(alice)
+        .env("JIN_DIR", alice_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Commit '0000000'"));
+    Ok(())
+}
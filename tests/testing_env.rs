@@ -0,0 +1,48 @@
+//! Tests for the public `jin::testing` environments (test-utils feature)
+//!
+//! Run with `cargo test --features test-utils --test testing_env`.
+#![cfg(feature = "test-utils")]
+
+use jin::testing::JinEnv;
+
+fn env() -> JinEnv {
+    let env = JinEnv::with_binary(env!("CARGO_BIN_EXE_jin")).unwrap();
+    env.init().unwrap();
+    env
+}
+
+#[test]
+fn test_env_runs_isolated_workflow() {
+    let env = env();
+    let mode = JinEnv::unique_name("mode");
+    env.run_ok(&["mode", "create", &mode]).unwrap();
+    env.run_ok(&["mode", "use", &mode]).unwrap();
+
+    env.write("settings.json", r#"{"theme": "dark"}"#).unwrap();
+    env.run_ok(&["add", "settings.json", "--mode"]).unwrap();
+    env.run_ok(&["commit", "-m", "Add settings"]).unwrap();
+    std::fs::remove_file(env.workspace().join("settings.json")).unwrap();
+    env.run_ok(&["apply"]).unwrap();
+    assert!(env.read("settings.json").unwrap().contains("dark"));
+
+    // Another environment sees none of it
+    let other = self::env();
+    let modes = other.run_ok(&["modes"]).unwrap();
+    assert!(!modes.contains(&mode), "{}", modes);
+}
+
+#[test]
+fn test_env_reports_failed_commands() {
+    let env = env();
+    let error = env.run_ok(&["mode", "use", "missing"]).unwrap_err();
+    assert!(
+        error.to_string().contains("jin mode use missing"),
+        "{}",
+        error
+    );
+    assert!(!env
+        .run(&["mode", "use", "missing"])
+        .unwrap()
+        .status
+        .success());
+}
@@ -61,7 +61,7 @@ fn test_validation_passes_for_clean_workspace() {
     let context = jin::core::config::ProjectContext::default();
     let repo = jin::git::JinRepo::open_at(&jin_dir).unwrap();
 
-    let result = jin::staging::validate_workspace_attached(&context, &repo);
+    let result = jin::staging::validate_workspace_attached(fixture.path(), &context, &repo);
     assert!(result.is_ok(), "Clean workspace should pass validation");
 }
 
@@ -81,7 +81,7 @@ fn test_validation_allows_fresh_workspace() {
     let context = jin::core::config::ProjectContext::default();
     let repo = jin::git::JinRepo::open_at(&jin_dir).unwrap();
 
-    let result = jin::staging::validate_workspace_attached(&context, &repo);
+    let result = jin::staging::validate_workspace_attached(fixture.path(), &context, &repo);
     assert!(
         result.is_ok(),
         "Fresh workspace (no metadata) should pass validation"
@@ -114,11 +114,11 @@ fn test_validation_detects_deleted_mode() {
     use jin::staging::WorkspaceMetadata;
     let mut metadata = WorkspaceMetadata::new();
     metadata.applied_layers = vec!["mode/test_mode".to_string()];
-    metadata.save().unwrap();
+    metadata.save(fixture.path()).unwrap();
 
     // First verify validation passes with mode existing
     let repo = jin::git::JinRepo::open_at(&jin_dir).unwrap();
-    let result = jin::staging::validate_workspace_attached(&context, &repo);
+    let result = jin::staging::validate_workspace_attached(fixture.path(), &context, &repo);
     assert!(result.is_ok(), "Validation should pass when mode exists");
 
     // Delete the mode reference
@@ -130,7 +130,7 @@ fn test_validation_detects_deleted_mode() {
         .unwrap();
 
     // Now validation should fail
-    let result = jin::staging::validate_workspace_attached(&context, &repo);
+    let result = jin::staging::validate_workspace_attached(fixture.path(), &context, &repo);
     assert!(
         result.is_err(),
         "Validation should fail when mode is deleted"
@@ -176,11 +176,11 @@ fn test_validation_detects_deleted_scope() {
     use jin::staging::WorkspaceMetadata;
     let mut metadata = WorkspaceMetadata::new();
     metadata.applied_layers = vec!["mode/test_mode".to_string()];
-    metadata.save().unwrap();
+    metadata.save(fixture.path()).unwrap();
 
     // First verify validation passes with mode existing
     let repo = jin::git::JinRepo::open_at(&jin_dir).unwrap();
-    let result = jin::staging::validate_workspace_attached(&context, &repo);
+    let result = jin::staging::validate_workspace_attached(fixture.path(), &context, &repo);
     assert!(result.is_ok(), "Validation should pass when mode exists");
 
     // Delete the mode reference
@@ -192,7 +192,7 @@ fn test_validation_detects_deleted_scope() {
         .unwrap();
 
     // Now validation should fail
-    let result = jin::staging::validate_workspace_attached(&context, &repo);
+    let result = jin::staging::validate_workspace_attached(fixture.path(), &context, &repo);
     assert!(
         result.is_err(),
         "Validation should fail when mode is deleted"
@@ -238,11 +238,11 @@ fn test_validation_detects_modified_files() {
     use jin::staging::WorkspaceMetadata;
     let mut metadata = WorkspaceMetadata::new();
     metadata.add_file(PathBuf::from(file_path), hash.clone());
-    metadata.save().unwrap();
+    metadata.save(fixture.path()).unwrap();
 
     // Validation should pass with matching content
     let context = jin::core::config::ProjectContext::default();
-    let result = jin::staging::validate_workspace_attached(&context, &repo);
+    let result = jin::staging::validate_workspace_attached(fixture.path(), &context, &repo);
     assert!(
         result.is_ok(),
         "Validation should pass when files match metadata"
@@ -252,7 +252,7 @@ fn test_validation_detects_modified_files() {
     fs::write(fixture.path().join(file_path), b"modified content").unwrap();
 
     // Now validation should fail
-    let result = jin::staging::validate_workspace_attached(&context, &repo);
+    let result = jin::staging::validate_workspace_attached(fixture.path(), &context, &repo);
     assert!(
         result.is_err(),
         "Validation should fail when files are modified"
@@ -293,18 +293,18 @@ fn test_validation_detects_deleted_files() {
     use jin::staging::WorkspaceMetadata;
     let mut metadata = WorkspaceMetadata::new();
     metadata.add_file(PathBuf::from(file_path), hash);
-    metadata.save().unwrap();
+    metadata.save(fixture.path()).unwrap();
 
     // Validation should pass with file present
     let context = jin::core::config::ProjectContext::default();
-    let result = jin::staging::validate_workspace_attached(&context, &repo);
+    let result = jin::staging::validate_workspace_attached(fixture.path(), &context, &repo);
     assert!(result.is_ok(), "Validation should pass when files exist");
 
     // Delete the file externally
     fs::remove_file(fixture.path().join(file_path)).unwrap();
 
     // Now validation should fail
-    let result = jin::staging::validate_workspace_attached(&context, &repo);
+    let result = jin::staging::validate_workspace_attached(fixture.path(), &context, &repo);
     assert!(
         result.is_err(),
         "Validation should fail when tracked files are deleted"
@@ -336,12 +336,12 @@ fn test_validation_detects_missing_layer_refs() {
     use jin::staging::WorkspaceMetadata;
     let mut metadata = WorkspaceMetadata::new();
     metadata.applied_layers = vec!["mode/nonexistent".to_string()];
-    metadata.save().unwrap();
+    metadata.save(fixture.path()).unwrap();
 
     // Validation should fail
     let context = jin::core::config::ProjectContext::default();
     let repo = jin::git::JinRepo::open_at(&jin_dir).unwrap();
-    let result = jin::staging::validate_workspace_attached(&context, &repo);
+    let result = jin::staging::validate_workspace_attached(fixture.path(), &context, &repo);
 
     assert!(
         result.is_err(),
@@ -381,7 +381,7 @@ fn test_validation_with_multiple_layers_all_exist() {
     use jin::staging::WorkspaceMetadata;
     let mut metadata = WorkspaceMetadata::new();
     metadata.applied_layers = vec!["mode/dev".to_string()];
-    metadata.save().unwrap();
+    metadata.save(fixture.path()).unwrap();
 
     // Validation should pass
     let context = jin::core::config::ProjectContext {
@@ -389,7 +389,7 @@ fn test_validation_with_multiple_layers_all_exist() {
         ..Default::default()
     };
     let repo = jin::git::JinRepo::open_at(&jin_dir).unwrap();
-    let result = jin::staging::validate_workspace_attached(&context, &repo);
+    let result = jin::staging::validate_workspace_attached(fixture.path(), &context, &repo);
 
     assert!(
         result.is_ok(),
@@ -421,7 +421,7 @@ fn test_validation_with_some_layers_missing() {
         "mode/dev".to_string(),
         "mode/dev/scope/backend".to_string(), // This one doesn't exist
     ];
-    metadata.save().unwrap();
+    metadata.save(fixture.path()).unwrap();
 
     // Validation should fail
     let context = jin::core::config::ProjectContext {
@@ -429,7 +429,7 @@ fn test_validation_with_some_layers_missing() {
         ..Default::default()
     };
     let repo = jin::git::JinRepo::open_at(&jin_dir).unwrap();
-    let result = jin::staging::validate_workspace_attached(&context, &repo);
+    let result = jin::staging::validate_workspace_attached(fixture.path(), &context, &repo);
 
     assert!(
         result.is_err(),
@@ -464,7 +464,7 @@ fn test_validation_error_messages_include_recovery_hints() {
     use jin::staging::WorkspaceMetadata;
     let mut metadata = WorkspaceMetadata::new();
     metadata.applied_layers = vec!["mode/production".to_string()];
-    metadata.save().unwrap();
+    metadata.save(fixture.path()).unwrap();
 
     let context = jin::core::config::ProjectContext {
         mode: Some("production".to_string()),
@@ -482,7 +482,7 @@ fn test_validation_error_messages_include_recovery_hints() {
         .unwrap();
 
     // Check error includes recovery hint
-    let result = jin::staging::validate_workspace_attached(&context, &repo);
+    let result = jin::staging::validate_workspace_attached(fixture.path(), &context, &repo);
     match result {
         Err(jin::core::error::JinError::DetachedWorkspace { recovery_hint, .. }) => {
             assert!(
@@ -528,7 +528,7 @@ fn test_validation_order_checks_file_mismatch_first() {
     let mut metadata = WorkspaceMetadata::new();
     metadata.add_file(PathBuf::from(file_path), hash);
     metadata.applied_layers = vec!["mode/nonexistent".to_string()];
-    metadata.save().unwrap();
+    metadata.save(fixture.path()).unwrap();
 
     let context = jin::core::config::ProjectContext {
         mode: Some("nonexistent_mode".to_string()),
@@ -539,7 +539,7 @@ fn test_validation_order_checks_file_mismatch_first() {
     fs::write(fixture.path().join(file_path), b"modified").unwrap();
 
     // Validation should report file mismatch, not the other conditions
-    let result = jin::staging::validate_workspace_attached(&context, &repo);
+    let result = jin::staging::validate_workspace_attached(fixture.path(), &context, &repo);
     match result {
         Err(jin::core::error::JinError::DetachedWorkspace { details, .. }) => {
             // Should report file mismatch (Condition 1), not missing refs or invalid context